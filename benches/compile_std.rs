@@ -0,0 +1,122 @@
+//! Compile-time benchmark over the standard library corpus.
+//!
+//! Guards the front end and TIR pipeline against performance regressions:
+//! 1. Every `std/` module compiled on its own (resolve through lower)
+//! 2. Every harness program under `benches/harnesses/` compiled and linked
+//! 3. One generated program of several thousand lines, with nested
+//!    control flow in every function
+//!
+//! Compare runs with `cargo bench --bench compile_std -- --save-baseline main`
+//! and `-- --baseline main`; `trident build --timings` breaks a single
+//! build down by phase.
+
+use std::path::{Path, PathBuf};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use trident::CompileOptions;
+
+/// All `.tri` files under `dir`, sorted for a stable corpus.
+fn tri_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|e| e == "tri") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Keep only the files that compile, so the corpus measures real work.
+fn compiling(files: Vec<PathBuf>, compile: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+    files.into_iter().filter(|f| compile(f)).collect()
+}
+
+fn bench_std_modules(c: &mut Criterion) {
+    let _quiet = trident::suppress_warnings();
+    let options = CompileOptions::default();
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let modules = compiling(tri_files(&root.join("std")), |f| {
+        trident::compile_module(f, &options).is_ok()
+    });
+
+    c.bench_function("std_modules", |b| {
+        b.iter(|| {
+            for module in &modules {
+                let _ = black_box(trident::compile_module(module, &options));
+            }
+        })
+    });
+}
+
+fn bench_harness_programs(c: &mut Criterion) {
+    let _quiet = trident::suppress_warnings();
+    let options = CompileOptions::default();
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let programs = compiling(tri_files(&root.join("benches/harnesses")), |f| {
+        trident::compile_project_with_options(f, &options).is_ok()
+    });
+
+    c.bench_function("harness_programs", |b| {
+        b.iter(|| {
+            for program in &programs {
+                let _ = black_box(trident::compile_project_with_options(program, &options));
+            }
+        })
+    });
+}
+
+/// A program of `fns` functions, each a bounded loop around an if/else,
+/// chained together by `main`.
+fn large_program(fns: usize) -> String {
+    let mut src = String::from("program large\n");
+    for k in 0..fns {
+        src.push_str(&format!(
+            "\nfn step{k}(x: Field, y: Field) -> Field {{\n\
+             \x20   let mut acc: Field = x\n\
+             \x20   for i in 0..8 bounded 8 {{\n\
+             \x20       if acc == y {{\n\
+             \x20           acc = acc + {k}\n\
+             \x20       }} else {{\n\
+             \x20           acc = acc * y + x\n\
+             \x20       }}\n\
+             \x20   }}\n\
+             \x20   acc\n\
+             }}\n"
+        ));
+    }
+    src.push_str("\nfn main() {\n    let a: Field = pub_read()\n    let b: Field = pub_read()\n");
+    src.push_str("    let mut v: Field = a\n");
+    for k in 0..fns {
+        src.push_str(&format!("    v = step{k}(v, b)\n"));
+    }
+    src.push_str("    pub_write(v)\n}\n");
+    src
+}
+
+fn bench_large_program(c: &mut Criterion) {
+    let source = large_program(400);
+    assert!(trident::compile(&source, "large.tri").is_ok());
+
+    c.bench_function("large_program", |b| {
+        b.iter(|| black_box(trident::compile(&source, "large.tri")))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_std_modules,
+    bench_harness_programs,
+    bench_large_program
+);
+criterion_main!(benches);
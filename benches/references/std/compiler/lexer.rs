@@ -1,70 +1,5 @@
 use std::time::Instant;
 
-use trident::lexeme::Lexeme;
-use trident::lexer::Lexer;
-
-/// Map a Lexeme to the TK_* integer used by std/compiler/lexer.tri.
-fn lexeme_to_tk(lexeme: &Lexeme) -> u64 {
-    match lexeme {
-        Lexeme::Program => 1,
-        Lexeme::Module => 2,
-        Lexeme::Use => 3,
-        Lexeme::Fn => 4,
-        Lexeme::Pub => 5,
-        Lexeme::Sec => 6,
-        Lexeme::Let => 7,
-        Lexeme::Mut => 8,
-        Lexeme::Const => 9,
-        Lexeme::Struct => 10,
-        Lexeme::If => 11,
-        Lexeme::Else => 12,
-        Lexeme::For => 13,
-        Lexeme::In => 14,
-        Lexeme::Bounded => 15,
-        Lexeme::Return => 16,
-        Lexeme::True => 17,
-        Lexeme::False => 18,
-        Lexeme::Event => 19,
-        Lexeme::Reveal => 20,
-        Lexeme::Seal => 21,
-        Lexeme::Match => 22,
-        Lexeme::FieldTy => 23,
-        Lexeme::XFieldTy => 24,
-        Lexeme::BoolTy => 25,
-        Lexeme::U32Ty => 26,
-        Lexeme::DigestTy => 27,
-        Lexeme::LParen => 28,
-        Lexeme::RParen => 29,
-        Lexeme::LBrace => 30,
-        Lexeme::RBrace => 31,
-        Lexeme::LBracket => 32,
-        Lexeme::RBracket => 33,
-        Lexeme::Comma => 34,
-        Lexeme::Colon => 35,
-        Lexeme::Semicolon => 36,
-        Lexeme::Dot => 37,
-        Lexeme::DotDot => 38,
-        Lexeme::Arrow => 39,
-        Lexeme::Eq => 40,
-        Lexeme::FatArrow => 41,
-        Lexeme::EqEq => 42,
-        Lexeme::Plus => 43,
-        Lexeme::Star => 44,
-        Lexeme::StarDot => 45,
-        Lexeme::Lt => 46,
-        Lexeme::Gt => 47,
-        Lexeme::Amp => 48,
-        Lexeme::Caret => 49,
-        Lexeme::SlashPercent => 50,
-        Lexeme::Hash => 51,
-        Lexeme::Underscore => 52,
-        Lexeme::Integer(_) => 53,
-        Lexeme::Ident(_) => 54,
-        Lexeme::AsmBlock { .. } => 55,
-        Lexeme::Eof => 56,
-    }
-}
-
 /// Test source: a small Trident program exercising keywords, types, symbols,
 /// integers, identifiers, and comments.
 const TEST_SOURCE: &str = r#"program test
@@ -88,10 +23,9 @@ fn main() {
     let source = TEST_SOURCE;
 
     // Tokenize with the Rust lexer
-    let (tokens, _comments, diags) = Lexer::new(source, 0).tokenize();
-    assert!(diags.is_empty(), "unexpected errors: {:?}", diags);
+    let tokens = trident::self_hosted::tokens(source).expect("unexpected errors");
 
-    let tk_values: Vec<u64> = tokens.iter().map(|t| lexeme_to_tk(&t.node)).collect();
+    let tk_values: Vec<u64> = tokens.iter().map(|t| t[0]).collect();
     let tok_count = tk_values.len();
 
     // Print token details to stderr for verification
@@ -100,7 +34,8 @@ fn main() {
     eprintln!("Tokens: {}", tok_count);
     eprintln!();
     for (i, tok) in tokens.iter().enumerate() {
-        eprintln!("  [{:3}] TK={:2}  {:?}", i, tk_values[i], tok.node);
+        let text = &source[tok[1] as usize..tok[2] as usize];
+        eprintln!("  [{:3}] TK={:2}  {:?}", i, tok[0], text);
     }
     eprintln!();
 
@@ -138,13 +73,13 @@ fn main() {
 
     // Benchmark: tokenize many times
     for _ in 0..1000 {
-        std::hint::black_box(Lexer::new(source, 0).tokenize());
+        let _ = std::hint::black_box(trident::self_hosted::tokens(source));
     }
 
     let n = 100_000u128;
     let start = Instant::now();
     for _ in 0..n {
-        std::hint::black_box(Lexer::new(std::hint::black_box(source), 0).tokenize());
+        let _ = std::hint::black_box(trident::self_hosted::tokens(std::hint::black_box(source)));
     }
     println!("rust_ns: {}", start.elapsed().as_nanos() / n);
 }
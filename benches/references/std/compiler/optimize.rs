@@ -1,5 +1,4 @@
 use trident::api::CompileOptions;
-use trident::lexer::Lexer;
 
 /// Test source: a small Trident program.
 const TEST_SOURCE: &str = r#"program test
@@ -24,9 +23,9 @@ fn main() {
     let options = CompileOptions::default();
 
     // Lex
-    let (tokens, _comments, diags) = Lexer::new(source, 0).tokenize();
-    assert!(diags.is_empty(), "lex errors: {:?}", diags);
-    let tok_count = tokens.len();
+    let tok_count = trident::self_hosted::tokens(source)
+        .expect("lex failed")
+        .len();
 
     // Full pipeline: parse + typecheck + codegen + optimize
     let tir_ops = trident::build_tir(source, "test.tri", &options)
@@ -1,5 +1,4 @@
 use trident::api::CompileOptions;
-use trident::lexer::Lexer;
 
 /// Test source: a small Trident program with types to verify.
 /// Exercises: let bindings, function calls, arithmetic, return types,
@@ -36,9 +35,9 @@ fn main() {
     let source = TEST_SOURCE;
 
     // Lex to get token count
-    let (tokens, _comments, diags) = Lexer::new(source, 0).tokenize();
-    assert!(diags.is_empty(), "lex errors: {:?}", diags);
-    let tok_count = tokens.len();
+    let tok_count = trident::self_hosted::tokens(source)
+        .expect("lex failed")
+        .len();
 
     // Verify Rust compiler accepts this source (type checks clean)
     let options = CompileOptions::default();
@@ -1,5 +1,5 @@
 use std::time::Instant;
-use trident::field::{poseidon2, Goldilocks, PrimeField};
+use trident::warrior::{hash_fields_goldilocks, Goldilocks, PrimeField};
 
 type F = Goldilocks;
 
@@ -7,7 +7,7 @@ fn hash_pair(left: [F; 4], right: [F; 4]) -> [F; 4] {
     let mut input = Vec::with_capacity(8);
     input.extend_from_slice(&left);
     input.extend_from_slice(&right);
-    poseidon2::hash_fields_goldilocks(&input)
+    hash_fields_goldilocks(&input)
 }
 
 fn merkle_verify(leaf: [F; 4], siblings: &[[F; 4]], mut idx: u32) -> [F; 4] {
@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 use std::time::Instant;
-use trident::field::{Goldilocks, PrimeField};
+use trident::warrior::{Goldilocks, PrimeField};
 
 type F = Goldilocks;
 
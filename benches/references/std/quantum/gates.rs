@@ -1,5 +1,5 @@
 use std::time::Instant;
-use trident::field::{Goldilocks, PrimeField};
+use trident::warrior::{Goldilocks, PrimeField};
 
 type F = Goldilocks;
 
@@ -1,5 +1,5 @@
 use std::time::Instant;
-use trident::field::{poseidon2, Goldilocks, PrimeField};
+use trident::warrior::{hash_fields_goldilocks, Goldilocks, PrimeField};
 
 type F = Goldilocks;
 
@@ -41,10 +41,7 @@ struct Ciphertext {
 fn encrypt(m: F, s: &[F], a: &[F], e: F, delta: F) -> Ciphertext {
     let dot = inner_product(a, s);
     let b = dot.add(m.mul(delta)).add(e);
-    Ciphertext {
-        a: a.to_vec(),
-        b,
-    }
+    Ciphertext { a: a.to_vec(), b }
 }
 
 fn decrypt(ct: &Ciphertext, s: &[F], delta: F) -> F {
@@ -78,10 +75,7 @@ fn ct_add(ct1: &Ciphertext, ct2: &Ciphertext) -> Ciphertext {
 
 fn ct_scale(ct: &Ciphertext, k: F) -> Ciphertext {
     let a: Vec<F> = ct.a.iter().map(|&ai| ai.mul(k)).collect();
-    Ciphertext {
-        a,
-        b: ct.b.mul(k),
-    }
+    Ciphertext { a, b: ct.b.mul(k) }
 }
 
 fn private_dot(cts: &[Ciphertext], weights: &[F]) -> Ciphertext {
@@ -98,9 +92,7 @@ fn private_dot(cts: &[Ciphertext], weights: &[F]) -> Ciphertext {
 }
 
 fn private_linear(cts: &[Ciphertext], w: &[Vec<F>]) -> Vec<Ciphertext> {
-    w.iter()
-        .map(|row| private_dot(cts, row))
-        .collect()
+    w.iter().map(|row| private_dot(cts, row)).collect()
 }
 
 // ===========================================================================
@@ -112,13 +104,7 @@ fn private_linear(cts: &[Ciphertext], w: &[Vec<F>]) -> Vec<Ciphertext> {
 fn build_relu_lut() -> Vec<F> {
     let half = PLAINTEXT_SPACE / 2;
     (0..PLAINTEXT_SPACE)
-        .map(|i| {
-            if i < half {
-                F::from_u64(i)
-            } else {
-                F::ZERO
-            }
-        })
+        .map(|i| if i < half { F::from_u64(i) } else { F::ZERO })
         .collect()
 }
 
@@ -200,13 +186,7 @@ fn lut_sponge_round_constants() -> Vec<F> {
         .collect()
 }
 
-fn lut_hash_commit(
-    activated: &[F],
-    weights_digest: F,
-    key_digest: F,
-    class: F,
-    lut: &[F],
-) -> F {
+fn lut_hash_commit(activated: &[F], weights_digest: F, key_digest: F, class: F, lut: &[F]) -> F {
     let output_digest = activated.iter().fold(F::ZERO, |acc, &x| acc.add(x));
     let mut state = [F::ZERO; LUT_SPONGE_WIDTH];
     state[0] = weights_digest;
@@ -223,15 +203,10 @@ fn lut_hash_commit(
 // Phase 3b: Poseidon2 hash commitment — production binding
 // ===========================================================================
 
-fn hash_commit(
-    activated: &[F],
-    weights_digest: F,
-    key_digest: F,
-    class: F,
-) -> F {
+fn hash_commit(activated: &[F], weights_digest: F, key_digest: F, class: F) -> F {
     let output_digest = activated.iter().fold(F::ZERO, |acc, &x| acc.add(x));
     let input = [weights_digest, key_digest, output_digest, class];
-    let result = poseidon2::hash_fields_goldilocks(&input);
+    let result = hash_fields_goldilocks(&input);
     result[0]
 }
 
@@ -290,13 +265,13 @@ fn argmax(v: &[F]) -> usize {
 
 /// Trinity pipeline result — all intermediate values for verification.
 struct TrinityResult {
-    result: Vec<F>,      // decrypted plaintexts
-    activated: Vec<F>,   // after dense+ReLU
-    class: usize,        // argmax classification
-    lut_digest: F,       // LUT sponge hash
-    poseidon_digest: F,  // Poseidon2 hash
-    pbs_result: F,       // PBS demo output
-    quantum: bool,       // quantum commitment
+    result: Vec<F>,     // decrypted plaintexts
+    activated: Vec<F>,  // after dense+ReLU
+    class: usize,       // argmax classification
+    lut_digest: F,      // LUT sponge hash
+    poseidon_digest: F, // Poseidon2 hash
+    pbs_result: F,      // PBS demo output
+    quantum: bool,      // quantum commitment
 }
 
 fn trinity(
@@ -402,7 +377,7 @@ fn compute_lut_sponge_divine(domain: u64, lut: &[F]) -> Vec<u64> {
             let k_val = (x / d) as u64;
             divine.push(r_val); // r = x mod D
             divine.push(k_val); // k = x / D
-            // Apply S-box: state[i] = lut[r_val]
+                                // Apply S-box: state[i] = lut[r_val]
             state[i] = if (r_val as usize) < lut.len() {
                 lut[r_val as usize]
             } else {
@@ -460,7 +435,7 @@ fn compute_bench_divine(
     for _ in 0..2 {
         for j in 0..ring_n {
             divine.push(j as u64); // src
-            divine.push(1);        // sign (no negation)
+            divine.push(1); // sign (no negation)
         }
     }
 
@@ -477,7 +452,10 @@ fn compute_bench_divine(
     let phase4_count = divine.len() - phase1b_count - phase3_count;
     eprintln!("--- Divine values for bench harness ---");
     eprintln!("  Phase 1b: {} values (decrypt plaintexts)", phase1b_count);
-    eprintln!("  Phase 3:  {} values (LUT sponge reduce_mod)", phase3_count);
+    eprintln!(
+        "  Phase 3:  {} values (LUT sponge reduce_mod)",
+        phase3_count
+    );
     eprintln!("  Phase 4:  {} values (PBS chain)", phase4_count);
     eprintln!("  Total:    {} divine values", divine.len());
     let _ = std::io::Write::flush(&mut std::io::stderr());
@@ -539,24 +517,29 @@ fn main() {
             }
         })
         .collect();
-    let dense_b: Vec<F> = (0..NEURONS)
-        .map(|i| F::from_u64(i as u64))
-        .collect();
+    let dense_b: Vec<F> = (0..NEURONS).map(|i| F::from_u64(i as u64)).collect();
 
     // Shared lookup table: ReLU over [0, 1024)
     let lut = build_relu_lut();
 
     // Precomputed digests
-    let weights_hash = poseidon2::hash_fields_goldilocks(&dense_w);
+    let weights_hash = hash_fields_goldilocks(&dense_w);
     let weights_digest = weights_hash[0];
-    let s_hash = poseidon2::hash_fields_goldilocks(&s);
+    let s_hash = hash_fields_goldilocks(&s);
     let key_digest = s_hash[0];
 
     // ========== END-TO-END EXECUTION ==========
 
     let tr = trinity(
-        &cts, &s, &priv_w, &dense_w, &dense_b, &lut,
-        weights_digest, key_digest, d,
+        &cts,
+        &s,
+        &priv_w,
+        &dense_w,
+        &dense_b,
+        &lut,
+        weights_digest,
+        key_digest,
+        d,
     );
 
     // ========== VERIFICATION PRINTOUT ==========
@@ -577,7 +560,9 @@ fn main() {
     eprintln!("--- Secret key ---");
     eprint!("  s = [");
     for (i, &si) in s.iter().enumerate() {
-        if i > 0 { eprint!(", "); }
+        if i > 0 {
+            eprint!(", ");
+        }
         eprint!("{}", si.to_u64());
     }
     eprintln!("]");
@@ -586,31 +571,46 @@ fn main() {
     eprintln!("--- Phase 1: LWE Encryption ---");
     eprint!("  plaintexts = [");
     for (i, &m) in messages.iter().enumerate() {
-        if i > 0 { eprint!(", "); }
+        if i > 0 {
+            eprint!(", ");
+        }
         eprint!("{}", m.to_u64());
     }
     eprintln!("]");
-    eprintln!("  {} ciphertexts, each {} field elements", INPUT_DIM, LWE_N + 1);
+    eprintln!(
+        "  {} ciphertexts, each {} field elements",
+        INPUT_DIM,
+        LWE_N + 1
+    );
     eprintln!();
 
     eprintln!("--- Phase 1b: Decrypt ---");
     eprint!("  decrypted = [");
     for (i, &r) in tr.result.iter().enumerate() {
-        if i > 0 { eprint!(", "); }
+        if i > 0 {
+            eprint!(", ");
+        }
         eprint!("{}", r.to_u64());
     }
     eprintln!("]");
     // Verify round-trip: decrypt(encrypt(m)) == m for each original ciphertext
-    let roundtrip_ok = messages.iter().zip(cts.iter())
+    let roundtrip_ok = messages
+        .iter()
+        .zip(cts.iter())
         .all(|(&m, ct)| decrypt(ct, &s, d).to_u64() == m.to_u64());
-    eprintln!("  encrypt/decrypt    = {}", if roundtrip_ok { "PASS" } else { "FAIL" });
+    eprintln!(
+        "  encrypt/decrypt    = {}",
+        if roundtrip_ok { "PASS" } else { "FAIL" }
+    );
     // Note: tr.result contains weighted sums from private_linear, not original plaintexts
     eprintln!();
 
     eprintln!("--- Phase 2: Dense Layer + ReLU (Reader 1: lut.apply) ---");
     eprint!("  activated = [");
     for (i, &a) in tr.activated.iter().enumerate() {
-        if i > 0 { eprint!(", "); }
+        if i > 0 {
+            eprint!(", ");
+        }
         eprint!("{}", a.to_u64());
     }
     eprintln!("]");
@@ -632,13 +632,23 @@ fn main() {
 
     eprintln!("--- Phase 4: PBS Demo (Reader 3: lut.read in test polynomial) ---");
     // PBS operates on ct_out[0] from Phase 1 (the first encrypted weighted sum)
-    eprintln!("  input: ct_out[0]   -> decrypt = {}", tr.result[0].to_u64());
-    eprintln!("  pbs_result         = lut[{}] = {}", tr.result[0].to_u64(), tr.pbs_result.to_u64());
+    eprintln!(
+        "  input: ct_out[0]   -> decrypt = {}",
+        tr.result[0].to_u64()
+    );
+    eprintln!(
+        "  pbs_result         = lut[{}] = {}",
+        tr.result[0].to_u64(),
+        tr.pbs_result.to_u64()
+    );
     // Verify PBS matches direct lookup on the decrypted value
     let direct_lookup = lut_read(&lut, tr.result[0]);
     eprintln!("  direct lut_read    = {}", direct_lookup.to_u64());
     let pbs_ok = tr.pbs_result.to_u64() == direct_lookup.to_u64();
-    eprintln!("  PBS == direct      = {}", if pbs_ok { "PASS" } else { "FAIL" });
+    eprintln!(
+        "  PBS == direct      = {}",
+        if pbs_ok { "PASS" } else { "FAIL" }
+    );
     eprintln!();
 
     eprintln!("--- Phase 5: Quantum Commitment (2-qubit Bell) ---");
@@ -664,15 +674,25 @@ fn main() {
 
     // Final verdict
     let all_ok = roundtrip_ok && pbs_ok;
-    eprintln!("=== VERDICT: {} ===", if all_ok { "ALL CHECKS PASS" } else { "FAILURE" });
+    eprintln!(
+        "=== VERDICT: {} ===",
+        if all_ok { "ALL CHECKS PASS" } else { "FAILURE" }
+    );
 
     // ========== BENCHMARK ==========
 
     // Warmup
     for _ in 0..100 {
         std::hint::black_box(trinity(
-            &cts, &s, &priv_w, &dense_w, &dense_b, &lut,
-            weights_digest, key_digest, d,
+            &cts,
+            &s,
+            &priv_w,
+            &dense_w,
+            &dense_b,
+            &lut,
+            weights_digest,
+            key_digest,
+            d,
         ));
     }
 
@@ -712,15 +732,24 @@ fn main() {
         .iter()
         .map(|f| f.to_u64())
         .collect();
-    eprintln!("--- LUT sponge round constants ({} values) ---", sponge_rc.len());
+    eprintln!(
+        "--- LUT sponge round constants ({} values) ---",
+        sponge_rc.len()
+    );
 
     // Divine values (FIFO order: Phase 1b → Phase 3 → Phase 4)
     let divine = compute_bench_divine(
-        bench_lwe_n, bench_neurons, bench_ring_n, bench_domain, &bench_lut,
+        bench_lwe_n,
+        bench_neurons,
+        bench_ring_n,
+        bench_domain,
+        &bench_lut,
     );
 
     // Emit round constants as additional public inputs
-    let rc_strs: Vec<String> = p2_rc.iter().chain(sponge_rc.iter())
+    let rc_strs: Vec<String> = p2_rc
+        .iter()
+        .chain(sponge_rc.iter())
         .map(|v| v.to_string())
         .collect();
     eprintln!();
@@ -0,0 +1,2 @@
+input: 1, 2, 3, 4, 5, 6
+output: 32
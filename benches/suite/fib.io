@@ -0,0 +1,2 @@
+input: 20
+output: 6765
@@ -0,0 +1,2 @@
+input: 3, 1, 2, 3, 4
+output: 142
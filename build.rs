@@ -0,0 +1,37 @@
+//! Records the git revision the compiler was built from.
+//!
+//! Exposed to the crate as `TRIDENT_GIT_REV` and embedded into build
+//! metadata of compiled artifacts. Builds outside a git checkout (e.g.
+//! from a published crate) simply omit it.
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    let head = Path::new(".git/HEAD");
+    if head.exists() {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+        if let Ok(contents) = std::fs::read_to_string(head) {
+            if let Some(reference) = contents.trim().strip_prefix("ref: ") {
+                let ref_path = Path::new(".git").join(reference);
+                if ref_path.exists() {
+                    println!("cargo:rerun-if-changed={}", ref_path.display());
+                }
+            }
+        }
+    } else {
+        println!("cargo:rerun-if-changed=build.rs");
+    }
+
+    let rev = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if let Some(rev) = rev {
+        println!("cargo:rustc-env=TRIDENT_GIT_REV={}", rev);
+    }
+}
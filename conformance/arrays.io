@@ -0,0 +1,2 @@
+input: 2
+output: 30, 25, 105
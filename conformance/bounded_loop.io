@@ -0,0 +1,2 @@
+input: 10
+output: 55
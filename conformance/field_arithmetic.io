@@ -0,0 +1,3 @@
+# 5 - 7 wraps to p - 2; -1 + 2 wraps to 1.
+input: 7, 5
+output: 12, 35, 18446744069414584319, 1, 1
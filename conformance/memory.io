@@ -0,0 +1,2 @@
+input: 21
+output: 21
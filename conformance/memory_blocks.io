@@ -0,0 +1,4 @@
+# Five-word blocks: Triton digest width.
+targets: triton
+secret: 1, 2, 3, 4, 5
+output: 6, 2
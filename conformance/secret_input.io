@@ -0,0 +1,3 @@
+input: 49
+secret: 7
+output: 50
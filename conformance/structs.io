@@ -0,0 +1,2 @@
+input: 3, 4
+output: 13, 24, 12
@@ -0,0 +1,4 @@
+# 100 = 14 * 7 + 2; popcount(100) = 3; log2(100) = 6; 7^3 = 343;
+# 4294967301 = 1 * 2^32 + 5.
+input: 100, 7
+output: 14, 2, 1, 3, 6, 343, 1, 5
@@ -13,9 +13,12 @@
   "if"
   "else"
   "for"
+  "while"
   "in"
   "bounded"
   "return"
+  "break"
+  "continue"
   "match"
   "asm"
   "event"
@@ -39,6 +42,15 @@
 ; Integer literals
 (integer_literal) @number
 
+; String literals (assertion messages)
+(string_literal) @string
+
+; String literals (assertion messages)
+(string_literal) @string
+
+; String literals (assertion messages)
+(string_literal) @string
+
 ; Comments
 (line_comment) @comment
 
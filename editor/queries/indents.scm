@@ -6,6 +6,7 @@
   (event_definition)
   (if_statement)
   (for_statement)
+  (while_statement)
   (match_statement)
   (match_arm)
   (block)
@@ -13,6 +13,9 @@
 (for_statement) @loop.around
 (for_statement
   body: (block) @loop.inside)
+(while_statement) @loop.around
+(while_statement
+  body: (block) @loop.inside)
 
 ; Conditionals
 (if_statement) @conditional.around
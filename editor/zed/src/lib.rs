@@ -1,7 +1,42 @@
-use zed_extension_api::{self as zed, Result};
+//! Zed extension for Trident.
+//!
+//! Costs, padded height, and verification verdicts come from the
+//! server as inlay hints, so they render wherever Zed shows hints
+//! (enable them with `"inlay_hints": { "enabled": true }`). Everything
+//! else is configured under `lsp.trident-lsp` in Zed's settings:
+//!
+//! ```json
+//! "lsp": {
+//!   "trident-lsp": {
+//!     "binary": { "path": "/path/to/trident-lsp" },
+//!     "settings": { "target": "triton", "profile": "release", "verification": true }
+//!   }
+//! }
+//! ```
+
+use zed_extension_api::{self as zed, serde_json, settings::LspSettings, Result};
 
 struct TridentExtension;
 
+impl TridentExtension {
+    /// The server's settings object, or the defaults the server assumes.
+    fn server_settings(
+        language_server_id: &zed::LanguageServerId,
+        worktree: &zed::Worktree,
+    ) -> serde_json::Value {
+        LspSettings::for_worktree(language_server_id.as_ref(), worktree)
+            .ok()
+            .and_then(|s| s.settings)
+            .unwrap_or_else(|| {
+                serde_json::json!({
+                    "target": "triton",
+                    "profile": "debug",
+                    "verification": true,
+                })
+            })
+    }
+}
+
 impl zed::Extension for TridentExtension {
     fn new() -> Self {
         Self
@@ -9,10 +44,30 @@ impl zed::Extension for TridentExtension {
 
     fn language_server_command(
         &mut self,
-        _language_server_id: &zed::LanguageServerId,
+        language_server_id: &zed::LanguageServerId,
         worktree: &zed::Worktree,
     ) -> Result<zed::Command> {
-        let env = worktree.shell_env();
+        let mut env = worktree.shell_env();
+
+        // An explicit `binary.path` wins over every lookup below.
+        let binary = LspSettings::for_worktree(language_server_id.as_ref(), worktree)
+            .ok()
+            .and_then(|s| s.binary);
+        let args = binary
+            .as_ref()
+            .and_then(|b| b.arguments.clone())
+            .unwrap_or_default();
+        if let Some(extra) = binary.as_ref().and_then(|b| b.env.clone()) {
+            env.extend(extra);
+        }
+        if let Some(path) = binary.and_then(|b| b.path) {
+            return Ok(zed::Command {
+                command: path,
+                args,
+                env,
+            });
+        }
+
         let cargo_bin = env
             .iter()
             .find(|(k, _)| k == "HOME")
@@ -24,7 +79,7 @@ impl zed::Extension for TridentExtension {
         if let Some(ref path) = cargo_bin {
             return Ok(zed::Command {
                 command: path.clone(),
-                args: vec![],
+                args,
                 env,
             });
         }
@@ -32,13 +87,31 @@ impl zed::Extension for TridentExtension {
         if let Some(path) = worktree.which("trident-lsp") {
             return Ok(zed::Command {
                 command: path,
-                args: vec![],
+                args,
                 env,
             });
         }
 
         Err("trident-lsp not found. Run: cargo install --path <trident-repo>".into())
     }
+
+    fn language_server_initialization_options(
+        &mut self,
+        language_server_id: &zed::LanguageServerId,
+        worktree: &zed::Worktree,
+    ) -> Result<Option<serde_json::Value>> {
+        Ok(Some(Self::server_settings(language_server_id, worktree)))
+    }
+
+    fn language_server_workspace_configuration(
+        &mut self,
+        language_server_id: &zed::LanguageServerId,
+        worktree: &zed::Worktree,
+    ) -> Result<Option<serde_json::Value>> {
+        Ok(Some(serde_json::json!({
+            "trident": Self::server_settings(language_server_id, worktree),
+        })))
+    }
 }
 
 zed::register_extension!(TridentExtension);
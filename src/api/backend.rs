@@ -0,0 +1,15 @@
+//! What a backend builds on.
+//!
+//! A backend lowers the TIR that [`build_tir`](super::build_tir) and
+//! [`build_tir_project`](super::build_tir_project) return to one target
+//! family: stack machines through `StackLowering`, register machines
+//! through `RegisterLowering` over `LIROp`, tree machines through
+//! `TreeLowering` into a `Noun`, and GPUs through `KernelLowering`.
+//! `TritonLowering` is the stack backend the compiler itself uses.
+
+pub use crate::ir::kir::lower::KernelLowering;
+pub use crate::ir::lir::lower::RegisterLowering;
+pub use crate::ir::lir::{LIROp, Label, Reg};
+pub use crate::ir::tir::lower::{StackLowering, TritonLowering};
+pub use crate::ir::tir::TIROp;
+pub use crate::ir::tree::lower::{Noun, TreeLowering};
@@ -0,0 +1,200 @@
+//! Benchmark history: suite results recorded over time.
+//!
+//! `trident bench --suite --record` appends one line per case to a local
+//! ndjson file; `trident bench --trend` reads it back and shows how each
+//! case moved between records, so codegen drift shows up as a column of
+//! deltas instead of going unnoticed. Line format:
+//! ```text
+//! {"name": "fib", "instructions": 41, "baseline": 23, "padded_height": 256, "cycles": 212, "compiler": "0.1.0", "rev": "3f2a9c1d0b7e", "date": "2026-01-05T10:00:00Z"}
+//! ```
+//! `cycles` is `null` when the compiled program failed its run, and `rev`
+//! when the compiler was built outside a git checkout.
+
+use std::path::Path;
+
+use super::{BenchRun, CompileOptions};
+use crate::report::json_escape;
+use crate::runtime::json::{extract_string, extract_string_opt, extract_u64};
+use crate::runtime::BuildInfo;
+
+/// One case of one suite run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchRecord {
+    pub name: String,
+    /// Instructions of the compiled program.
+    pub instructions: usize,
+    /// Instructions of the handwritten baseline.
+    pub baseline: usize,
+    pub padded_height: u64,
+    /// Cycles of the compiled program, if it ran correctly.
+    pub cycles: Option<u64>,
+    pub compiler: String,
+    /// Git revision of the compiler, if known.
+    pub rev: Option<String>,
+    /// ISO 8601 time of the run.
+    pub date: String,
+}
+
+impl BenchRecord {
+    /// Record `run` as produced by the compiler described by `build`.
+    pub fn from_run(run: &BenchRun, build: &BuildInfo, date: &str) -> Self {
+        Self {
+            name: run.name.clone(),
+            instructions: run.instructions.total_compiled,
+            baseline: run.instructions.total_baseline,
+            padded_height: run.padded_height,
+            cycles: run.compiled_cycles.as_ref().ok().copied(),
+            compiler: build.compiler_version.clone(),
+            rev: build.compiler_rev.clone(),
+            date: date.to_string(),
+        }
+    }
+
+    /// Serialize as one ndjson line, without the newline.
+    pub fn to_json_line(&self) -> String {
+        let opt = |v: Option<String>| v.unwrap_or_else(|| "null".to_string());
+        format!(
+            "{{\"name\": \"{}\", \"instructions\": {}, \"baseline\": {}, \"padded_height\": {}, \"cycles\": {}, \"compiler\": \"{}\", \"rev\": {}, \"date\": \"{}\"}}",
+            json_escape(&self.name),
+            self.instructions,
+            self.baseline,
+            self.padded_height,
+            opt(self.cycles.map(|c| c.to_string())),
+            json_escape(&self.compiler),
+            opt(self.rev.as_ref().map(|r| format!("\"{}\"", json_escape(r)))),
+            json_escape(&self.date)
+        )
+    }
+
+    /// Parse one line written by `to_json_line`.
+    pub fn from_json_line(line: &str) -> Result<Self, String> {
+        Ok(Self {
+            name: extract_string(line, "name")?,
+            instructions: extract_u64(line, "instructions")? as usize,
+            baseline: extract_u64(line, "baseline")? as usize,
+            padded_height: extract_u64(line, "padded_height")?,
+            cycles: extract_u64(line, "cycles").ok(),
+            compiler: extract_string(line, "compiler")?,
+            rev: extract_string_opt(line, "rev"),
+            date: extract_string(line, "date")?,
+        })
+    }
+}
+
+/// Record `runs`, compiled with `options`, at the end of the history at
+/// `path`, stamped with the current time and compiler revision.
+pub fn record_bench_runs(
+    path: &Path,
+    runs: &[BenchRun],
+    options: &CompileOptions,
+) -> Result<Vec<BenchRecord>, String> {
+    let build = BuildInfo::capture(options);
+    let date = crate::deploy::iso8601_now();
+    let records: Vec<BenchRecord> = runs
+        .iter()
+        .map(|run| BenchRecord::from_run(run, &build, &date))
+        .collect();
+    append_bench_history(path, &records)?;
+    Ok(records)
+}
+
+/// Append `records` to the history at `path`, creating it if needed.
+pub fn append_bench_history(path: &Path, records: &[BenchRecord]) -> Result<(), String> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("cannot create '{}': {}", parent.display(), e))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("cannot open '{}': {}", path.display(), e))?;
+    let mut text = String::new();
+    for record in records {
+        text.push_str(&record.to_json_line());
+        text.push('\n');
+    }
+    file.write_all(text.as_bytes())
+        .map_err(|e| format!("cannot write '{}': {}", path.display(), e))
+}
+
+/// Read every record of the history at `path`, oldest first. A missing
+/// file is an empty history.
+pub fn load_bench_history(path: &Path) -> Result<Vec<BenchRecord>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("cannot read '{}': {}", path.display(), e)),
+    };
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            BenchRecord::from_json_line(line)
+                .map_err(|e| format!("{}:{}: {}", path.display(), i + 1, e))
+        })
+        .collect()
+}
+
+/// Render the last `last` records of each case, oldest first, with each
+/// row's change from the one above it.
+pub fn format_bench_trend(records: &[BenchRecord], last: usize) -> String {
+    let mut names: Vec<&str> = Vec::new();
+    for r in records {
+        if !names.contains(&r.name.as_str()) {
+            names.push(&r.name);
+        }
+    }
+
+    let mut out = String::new();
+    for name in names {
+        let history: Vec<&BenchRecord> = records.iter().filter(|r| r.name == name).collect();
+        let shown = &history[history.len().saturating_sub(last)..];
+        // The delta of the first shown row is against the record before it.
+        let mut prev = history
+            .len()
+            .checked_sub(shown.len() + 1)
+            .map(|i| history[i]);
+
+        out.push_str(&format!("{}\n", name));
+        out.push_str(&format!(
+            "  {:<20} {:<22} {:>6} {:>6} {:>6} {:>8} {:>8} {:>7}\n",
+            "Date", "Compiler", "Instr", "Delta", "Hand", "Padded", "Cycles", "Delta"
+        ));
+        for r in shown {
+            let compiler = match &r.rev {
+                Some(rev) => format!("{} ({})", r.compiler, rev),
+                None => r.compiler.clone(),
+            };
+            let instr_delta = prev.map(|p| r.instructions as i64 - p.instructions as i64);
+            let cycles_delta = match (prev.and_then(|p| p.cycles), r.cycles) {
+                (Some(a), Some(b)) => Some(b as i64 - a as i64),
+                _ => None,
+            };
+            out.push_str(&format!(
+                "  {:<20} {:<22} {:>6} {:>6} {:>6} {:>8} {:>8} {:>7}\n",
+                r.date,
+                compiler,
+                r.instructions,
+                format_delta(instr_delta),
+                r.baseline,
+                r.padded_height,
+                r.cycles.map_or("-".to_string(), |c| c.to_string()),
+                format_delta(cycles_delta)
+            ));
+            prev = Some(r);
+        }
+    }
+    out
+}
+
+fn format_delta(delta: Option<i64>) -> String {
+    match delta {
+        Some(d) if d > 0 => format!("+{}", d),
+        Some(0) => "=".to_string(),
+        Some(d) => d.to_string(),
+        None => String::new(),
+    }
+}
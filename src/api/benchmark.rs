@@ -0,0 +1,278 @@
+//! Benchmark suite: programs paired with handwritten baselines.
+//!
+//! Each case is a program `name.tri` beside `name.tasm`, a hand-optimized
+//! version of the same program, and optionally `name.io` with its input
+//! and expected output in the conformance format (see `conformance`).
+//! Both sides are counted per function and run on the reference
+//! interpreter for their cycle counts; a side whose output differs from
+//! `name.io` reports that instead of a count.
+//!
+//! Function rows compare labels both programs define, normalized as
+//! `parse_tasm_functions` does. Module totals count every instruction,
+//! loop blocks included.
+
+use std::path::PathBuf;
+
+use super::conformance::parse_io;
+use super::*;
+use crate::report::json_escape;
+use crate::runtime::{Interpreter, ProgramInput};
+
+/// One program of the benchmark suite.
+#[derive(Clone, Debug)]
+pub struct BenchCase {
+    pub name: String,
+    pub source: PathBuf,
+    pub baseline: PathBuf,
+    pub input: ProgramInput,
+    /// Expected public output, when the case has a `.io` file.
+    pub expected: Option<Vec<u64>>,
+}
+
+/// Instruction counts and cycle counts of one case.
+#[derive(Clone, Debug)]
+pub struct BenchRun {
+    pub name: String,
+    pub instructions: ModuleBenchmarkResult,
+    /// Padded trace height of the compiled program.
+    pub padded_height: u64,
+    /// Cycles of the compiled program, or why it did not run.
+    pub compiled_cycles: Result<u64, String>,
+    /// Cycles of the baseline, or why it did not run.
+    pub baseline_cycles: Result<u64, String>,
+}
+
+/// Load every case in `dir`, sorted by name. Every `.tri` file needs a
+/// sibling `.tasm` baseline.
+pub fn load_bench_cases(dir: &Path) -> Result<Vec<BenchCase>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("cannot read '{}': {}", dir.display(), e))?;
+    let mut sources: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "tri"))
+        .collect();
+    sources.sort();
+
+    let mut cases = Vec::with_capacity(sources.len());
+    for source in sources {
+        let baseline = source.with_extension("tasm");
+        if !baseline.is_file() {
+            return Err(format!("'{}' has no baseline", source.display()));
+        }
+        let io_path = source.with_extension("io");
+        let (input, expected) = if io_path.is_file() {
+            let text = std::fs::read_to_string(&io_path)
+                .map_err(|e| format!("cannot read '{}': {}", io_path.display(), e))?;
+            let (input, expected, _) =
+                parse_io(&text).map_err(|e| format!("{}: {}", io_path.display(), e))?;
+            (input, Some(expected))
+        } else {
+            (ProgramInput::default(), None)
+        };
+        let name = source
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("case")
+            .to_string();
+        cases.push(BenchCase {
+            name,
+            source,
+            baseline,
+            input,
+            expected,
+        });
+    }
+    Ok(cases)
+}
+
+/// Compile every case in `dir` for Triton VM, count both sides, and run
+/// both on the reference interpreter.
+pub fn run_bench_suite(
+    dir: &Path,
+    options: &CompileOptions,
+) -> Result<Vec<BenchRun>, Vec<Diagnostic>> {
+    let cases =
+        load_bench_cases(dir).map_err(|e| vec![Diagnostic::error(e, span::Span::dummy())])?;
+    let interpreter = Interpreter::new();
+
+    let mut runs = Vec::with_capacity(cases.len());
+    for case in &cases {
+        let bundle = compile_to_bundle(&case.source, options)?;
+        let compiled = bundle.assembly;
+        let baseline = std::fs::read_to_string(&case.baseline).map_err(|e| {
+            vec![Diagnostic::error(
+                format!("cannot read '{}': {}", case.baseline.display(), e),
+                span::Span::dummy(),
+            )]
+        })?;
+
+        let compiled_fns = parse_tasm_functions(&compiled);
+        let baseline_fns = parse_tasm_functions(&baseline);
+        let functions = baseline_fns
+            .iter()
+            .filter_map(|(name, &baseline_count)| {
+                let compiled_count = *compiled_fns.get(name)?;
+                Some(FunctionBenchmark {
+                    name: name.clone(),
+                    compiled_instructions: compiled_count,
+                    baseline_instructions: baseline_count,
+                })
+            })
+            .collect();
+
+        let cycles = |tasm: &str| {
+            let result = interpreter.run_tasm(tasm, &case.input)?;
+            match &case.expected {
+                Some(expected) if result.output != *expected => Err(format!(
+                    "expected output {:?}, got {:?}",
+                    expected, result.output
+                )),
+                _ => Ok(result.cycle_count),
+            }
+        };
+        runs.push(BenchRun {
+            name: case.name.clone(),
+            instructions: ModuleBenchmarkResult {
+                module_path: case.name.clone(),
+                functions,
+                total_compiled: count_tasm_instructions(&compiled),
+                total_baseline: count_tasm_instructions(&baseline),
+            },
+            padded_height: bundle.cost.padded_height,
+            compiled_cycles: cycles(&compiled),
+            baseline_cycles: cycles(&baseline),
+        });
+    }
+    Ok(runs)
+}
+
+/// Render the runs as the benchmark table, with a cycles row under each
+/// case and the runs that failed listed below.
+pub fn format_bench_table(runs: &[BenchRun]) -> String {
+    let mut out = ModuleBenchmarkResult::format_header();
+    out.push('\n');
+    let mut failures = Vec::new();
+    for (i, run) in runs.iter().enumerate() {
+        let module = &run.instructions;
+        let header = module.format_module_header();
+        // The table header already ends with a rule.
+        let header = match header.split_once('\n') {
+            Some((_, row)) if i == 0 => row,
+            _ => &header,
+        };
+        out.push_str(header);
+        out.push('\n');
+        for f in &module.functions {
+            out.push_str(&module.format_function(f));
+            out.push('\n');
+        }
+        let cycles = FunctionBenchmark {
+            name: "(cycles)".to_string(),
+            compiled_instructions: *run.compiled_cycles.as_ref().unwrap_or(&0) as usize,
+            baseline_instructions: *run.baseline_cycles.as_ref().unwrap_or(&0) as usize,
+        };
+        out.push_str(&module.format_function(&cycles));
+        out.push('\n');
+        for (side, result) in [
+            ("tri", &run.compiled_cycles),
+            ("hand", &run.baseline_cycles),
+        ] {
+            if let Err(e) = result {
+                failures.push(format!("  {} ({}): {}", run.name, side, e));
+            }
+        }
+    }
+    out.push_str(&ModuleBenchmarkResult::format_separator());
+    out.push('\n');
+
+    let total_compiled: usize = runs.iter().map(|r| r.instructions.total_compiled).sum();
+    let total_baseline: usize = runs.iter().map(|r| r.instructions.total_baseline).sum();
+    let worst = runs
+        .iter()
+        .map(|r| (r.instructions.total_compiled, r.instructions.total_baseline))
+        .filter(|&(_, hand)| hand > 0)
+        .max_by(|a, b| (a.0 * b.1).cmp(&(b.0 * a.1)))
+        .unwrap_or((0, 0));
+    out.push_str(&ModuleBenchmarkResult::format_summary(
+        total_compiled,
+        total_baseline,
+        worst.0,
+        worst.1,
+        runs.len(),
+    ));
+    out.push('\n');
+    if !failures.is_empty() {
+        out.push_str("\nfailed runs:\n");
+        for f in failures {
+            out.push_str(&f);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Render the runs as JSON for dashboards. Cycles of a failed run are
+/// `null`, with the reason under `errors`.
+pub fn format_bench_json(runs: &[BenchRun]) -> String {
+    let cycles = |r: &Result<u64, String>| match r {
+        Ok(n) => n.to_string(),
+        Err(_) => "null".to_string(),
+    };
+    let mut out = String::from("{\n  \"cases\": [\n");
+    for (i, run) in runs.iter().enumerate() {
+        let module = &run.instructions;
+        let functions: Vec<String> = module
+            .functions
+            .iter()
+            .map(|f| {
+                format!(
+                    "{{\"name\": \"{}\", \"tri\": {}, \"hand\": {}}}",
+                    json_escape(&f.name),
+                    f.compiled_instructions,
+                    f.baseline_instructions
+                )
+            })
+            .collect();
+        let errors: Vec<String> = [
+            ("tri", &run.compiled_cycles),
+            ("hand", &run.baseline_cycles),
+        ]
+        .into_iter()
+        .filter_map(|(side, r)| {
+            let e = r.as_ref().err()?;
+            Some(format!(
+                "{{\"side\": \"{}\", \"message\": \"{}\"}}",
+                side,
+                json_escape(e)
+            ))
+        })
+        .collect();
+        out.push_str("    {\n");
+        out.push_str(&format!(
+            "      \"name\": \"{}\",\n",
+            json_escape(&run.name)
+        ));
+        out.push_str(&format!(
+            "      \"instructions\": {{\"tri\": {}, \"hand\": {}}},\n",
+            module.total_compiled, module.total_baseline
+        ));
+        out.push_str(&format!(
+            "      \"cycles\": {{\"tri\": {}, \"hand\": {}}},\n",
+            cycles(&run.compiled_cycles),
+            cycles(&run.baseline_cycles)
+        ));
+        out.push_str(&format!(
+            "      \"functions\": [{}],\n",
+            functions.join(", ")
+        ));
+        out.push_str(&format!("      \"errors\": [{}]\n", errors.join(", ")));
+        out.push_str(if i + 1 < runs.len() {
+            "    },\n"
+        } else {
+            "    }\n"
+        });
+    }
+    out.push_str("  ]\n}\n");
+    out
+}
@@ -0,0 +1,421 @@
+//! `trident ci`: the project's checks as one gated run.
+//!
+//! The stages — type check, format check, tests, cost gates, and
+//! verification — run in order and each yields a pass, a failure, or a
+//! skip with a one-line detail. Stages that need the project to compile
+//! are skipped once `check` fails, so one type error is not reported
+//! four times. The report renders as JSON for tools and as a markdown
+//! table for CI job summaries:
+//! ```text
+//! {"passed": false, "stages": [{"stage": "check", "status": "pass", "detail": "no errors", "duration_ms": 41}, ...]}
+//! ```
+
+use std::path::PathBuf;
+
+use super::*;
+use crate::project::CiSettings;
+use crate::report::json_escape;
+use crate::solve::{Verdict, VerifyConfig};
+
+/// Files listed in a stage detail before the rest are counted.
+const MAX_LISTED: usize = 5;
+
+/// A stage of `trident ci`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CiStage {
+    Check,
+    Fmt,
+    Test,
+    Cost,
+    Verify,
+}
+
+impl CiStage {
+    /// Every stage, in the order they run.
+    pub const ALL: [CiStage; 5] = [
+        CiStage::Check,
+        CiStage::Fmt,
+        CiStage::Test,
+        CiStage::Cost,
+        CiStage::Verify,
+    ];
+
+    pub fn parse(name: &str) -> Option<CiStage> {
+        CiStage::ALL.into_iter().find(|s| s.as_str() == name)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CiStage::Check => "check",
+            CiStage::Fmt => "fmt",
+            CiStage::Test => "test",
+            CiStage::Cost => "cost",
+            CiStage::Verify => "verify",
+        }
+    }
+
+    /// Parse a list of stage names into run order, without duplicates.
+    pub fn parse_list<S: AsRef<str>>(names: &[S]) -> Result<Vec<CiStage>, String> {
+        let mut stages = BTreeSet::new();
+        for name in names {
+            let name = name.as_ref().trim();
+            let stage = CiStage::parse(name).ok_or_else(|| {
+                let known: Vec<&str> = CiStage::ALL.iter().map(|s| s.as_str()).collect();
+                format!("unknown ci stage '{}' (stages: {})", name, known.join(", "))
+            })?;
+            stages.insert(stage);
+        }
+        Ok(stages.into_iter().collect())
+    }
+
+    /// Whether the stage compiles the project, and so cannot run once
+    /// the type check failed.
+    fn needs_check(self) -> bool {
+        matches!(self, CiStage::Test | CiStage::Cost | CiStage::Verify)
+    }
+}
+
+/// Outcome of one stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StageStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+impl StageStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StageStatus::Pass => "pass",
+            StageStatus::Fail => "fail",
+            StageStatus::Skip => "skip",
+        }
+    }
+}
+
+/// One stage's outcome with a one-line explanation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StageResult {
+    pub stage: CiStage,
+    pub status: StageStatus,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+impl StageResult {
+    fn new(stage: CiStage, status: StageStatus, detail: impl Into<String>) -> Self {
+        StageResult {
+            stage,
+            status,
+            detail: detail.into(),
+            duration_ms: 0,
+        }
+    }
+}
+
+/// What `trident ci` runs and the thresholds it gates on.
+#[derive(Clone, Debug)]
+pub struct CiConfig {
+    pub stages: Vec<CiStage>,
+    /// Project root; the format check covers the `.tri` files beneath
+    /// it, except those under `target/`.
+    pub root_dir: PathBuf,
+    /// Saved cost report to compare against.
+    pub cost_baseline: Option<PathBuf>,
+    pub max_padded_height: Option<u64>,
+    /// Allowed growth of the tallest table over the baseline, in
+    /// percent; with a baseline and no limit, any growth fails.
+    pub max_cost_increase: Option<u64>,
+    pub verify: VerifyConfig,
+}
+
+impl CiConfig {
+    /// Build the configuration from a project's `[ci]` section.
+    pub fn from_settings(settings: &CiSettings, root_dir: &Path) -> Result<CiConfig, String> {
+        let stages = match &settings.stages {
+            Some(names) => CiStage::parse_list(names)?,
+            None => CiStage::ALL.to_vec(),
+        };
+        Ok(CiConfig {
+            stages,
+            root_dir: root_dir.to_path_buf(),
+            cost_baseline: settings.cost_baseline.as_ref().map(|p| root_dir.join(p)),
+            max_padded_height: settings.max_padded_height,
+            max_cost_increase: settings.max_cost_increase,
+            verify: VerifyConfig::default(),
+        })
+    }
+}
+
+/// The outcome of every stage of a `trident ci` run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CiReport {
+    pub stages: Vec<StageResult>,
+}
+
+impl CiReport {
+    /// Whether no stage failed; skipped stages do not fail the run.
+    pub fn passed(&self) -> bool {
+        self.stages.iter().all(|s| s.status != StageStatus::Fail)
+    }
+
+    pub fn to_json(&self) -> String {
+        let stages: Vec<String> = self
+            .stages
+            .iter()
+            .map(|s| {
+                format!(
+                    "{{\"stage\": \"{}\", \"status\": \"{}\", \"detail\": \"{}\", \"duration_ms\": {}}}",
+                    s.stage.as_str(),
+                    s.status.as_str(),
+                    json_escape(&s.detail),
+                    s.duration_ms
+                )
+            })
+            .collect();
+        format!(
+            "{{\"passed\": {}, \"stages\": [{}]}}\n",
+            self.passed(),
+            stages.join(", ")
+        )
+    }
+
+    /// Render as a markdown table, as GitHub job summaries show it.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "### trident ci: {}\n\n| Stage | Status | Detail | Time |\n| --- | --- | --- | ---: |\n",
+            if self.passed() { "passed" } else { "failed" }
+        );
+        for s in &self.stages {
+            let status = match s.status {
+                StageStatus::Pass => "✅ pass",
+                StageStatus::Fail => "❌ fail",
+                StageStatus::Skip => "⏭️ skip",
+            };
+            out.push_str(&format!(
+                "| {} | {} | {} | {} ms |\n",
+                s.stage.as_str(),
+                status,
+                s.detail.replace('|', "\\|"),
+                s.duration_ms
+            ));
+        }
+        out
+    }
+}
+
+/// Run the stages of `config` over the project at `entry_path`.
+pub fn run_ci(entry_path: &Path, options: &CompileOptions, config: &CiConfig) -> CiReport {
+    let mut stages = Vec::with_capacity(config.stages.len());
+    let mut check_failed = false;
+    for &stage in &config.stages {
+        if check_failed && stage.needs_check() {
+            stages.push(StageResult::new(stage, StageStatus::Skip, "check failed"));
+            continue;
+        }
+        let started = Instant::now();
+        let mut result = match stage {
+            CiStage::Check => check_stage(entry_path, options),
+            CiStage::Fmt => fmt_stage(&config.root_dir),
+            CiStage::Test => test_stage(entry_path, options),
+            CiStage::Cost => cost_stage(entry_path, options, config),
+            CiStage::Verify => verify_stage(entry_path, &config.verify),
+        };
+        result.duration_ms = started.elapsed().as_millis() as u64;
+        check_failed |= stage == CiStage::Check && result.status == StageStatus::Fail;
+        stages.push(result);
+    }
+    CiReport { stages }
+}
+
+fn check_stage(entry_path: &Path, options: &CompileOptions) -> StageResult {
+    match check_project_with_options(entry_path, options) {
+        Ok(()) => StageResult::new(CiStage::Check, StageStatus::Pass, "no errors"),
+        Err(errors) => StageResult::new(
+            CiStage::Check,
+            StageStatus::Fail,
+            format!("{} error{}", errors.len(), plural(errors.len())),
+        ),
+    }
+}
+
+fn fmt_stage(root_dir: &Path) -> StageResult {
+    let mut files = Vec::new();
+    super::testing::collect_tri_files(root_dir, &mut files, 0);
+    let generated = root_dir.join("target");
+    files.retain(|f| !f.starts_with(&generated));
+    files.sort();
+
+    let mut unformatted = Vec::new();
+    for path in &files {
+        let formatted = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|source| {
+                format_source(&source, &path.to_string_lossy())
+                    .ok()
+                    .map(|f| f == source)
+            })
+            .unwrap_or(false);
+        if !formatted {
+            let shown = path.strip_prefix(root_dir).unwrap_or(path);
+            unformatted.push(shown.display().to_string());
+        }
+    }
+    if unformatted.is_empty() {
+        let n = files.len();
+        return StageResult::new(
+            CiStage::Fmt,
+            StageStatus::Pass,
+            format!("{} file{} formatted", n, plural(n)),
+        );
+    }
+    StageResult::new(
+        CiStage::Fmt,
+        StageStatus::Fail,
+        format!(
+            "{} of {} files need formatting: {}",
+            unformatted.len(),
+            files.len(),
+            listed(&unformatted)
+        ),
+    )
+}
+
+fn test_stage(entry_path: &Path, options: &CompileOptions) -> StageResult {
+    let results = match super::testing::test_results(entry_path, options) {
+        Ok(results) => results,
+        Err(_) => {
+            return StageResult::new(CiStage::Test, StageStatus::Fail, "tests do not compile")
+        }
+    };
+    if results.is_empty() {
+        return StageResult::new(CiStage::Test, StageStatus::Pass, "no #[test] functions");
+    }
+    let failed: Vec<String> = results
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| r.name.clone())
+        .collect();
+    if failed.is_empty() {
+        let n = results.len();
+        return StageResult::new(
+            CiStage::Test,
+            StageStatus::Pass,
+            format!("{} test{} passed", n, plural(n)),
+        );
+    }
+    StageResult::new(
+        CiStage::Test,
+        StageStatus::Fail,
+        format!(
+            "{} of {} tests failed: {}",
+            failed.len(),
+            results.len(),
+            listed(&failed)
+        ),
+    )
+}
+
+fn cost_stage(entry_path: &Path, options: &CompileOptions, config: &CiConfig) -> StageResult {
+    let current = match analyze_costs_project(entry_path, options) {
+        Ok(cost) => cost,
+        Err(_) => {
+            return StageResult::new(CiStage::Cost, StageStatus::Fail, "cost analysis failed")
+        }
+    };
+    let baseline = match &config.cost_baseline {
+        Some(path) => match cost::ProgramCost::load_json(path) {
+            Ok(baseline) => Some(baseline),
+            Err(e) => return StageResult::new(CiStage::Cost, StageStatus::Fail, e),
+        },
+        None => None,
+    };
+    cost_gate(&current, baseline.as_ref(), config)
+}
+
+/// Judge `current` against the cost gates of `config`: the padded-height
+/// ceiling, and the growth of the tallest table over `baseline`.
+pub fn cost_gate(
+    current: &cost::ProgramCost,
+    baseline: Option<&cost::ProgramCost>,
+    config: &CiConfig,
+) -> StageResult {
+    let mut detail = format!("padded height {}", current.padded_height);
+    if let Some(max) = config.max_padded_height {
+        if current.padded_height > max {
+            return StageResult::new(
+                CiStage::Cost,
+                StageStatus::Fail,
+                format!("{} exceeds max_padded_height {}", detail, max),
+            );
+        }
+    }
+    if let Some(baseline) = baseline {
+        let (old, new) = (baseline.total.max_height(), current.total.max_height());
+        let growth = if new <= old {
+            0
+        } else if old == 0 {
+            u64::MAX
+        } else {
+            ((new - old) * 100).div_ceil(old)
+        };
+        let change = if new >= old {
+            format!("+{}", new - old)
+        } else {
+            format!("-{}", old - new)
+        };
+        detail.push_str(&format!("; tallest table {} rows ({})", new, change));
+        let limit = config.max_cost_increase.unwrap_or(0);
+        if growth > limit {
+            return StageResult::new(
+                CiStage::Cost,
+                StageStatus::Fail,
+                format!("{}, over the allowed {}% increase", detail, limit),
+            );
+        }
+    }
+    StageResult::new(CiStage::Cost, StageStatus::Pass, detail)
+}
+
+fn verify_stage(entry_path: &Path, config: &VerifyConfig) -> StageResult {
+    let report = match verify_project_with(entry_path, config) {
+        Ok(report) => report,
+        Err(_) => {
+            return StageResult::new(
+                CiStage::Verify,
+                StageStatus::Fail,
+                "project does not compile",
+            )
+        }
+    };
+    let (status, detail) = match report.verdict {
+        Verdict::Safe => (StageStatus::Pass, "no violations found"),
+        Verdict::StaticViolation => (StageStatus::Fail, "static analysis found violations"),
+        Verdict::RandomViolation => (StageStatus::Fail, "random testing found violations"),
+        Verdict::BmcViolation => (StageStatus::Fail, "bounded model checking found violations"),
+        Verdict::Inconclusive => (StageStatus::Fail, "loop iterations left unchecked"),
+    };
+    StageResult::new(CiStage::Verify, status, detail)
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+/// Join the first few `names`, counting the rest.
+fn listed(names: &[String]) -> String {
+    let mut out = names
+        .iter()
+        .take(MAX_LISTED)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if names.len() > MAX_LISTED {
+        out.push_str(&format!(" and {} more", names.len() - MAX_LISTED));
+    }
+    out
+}
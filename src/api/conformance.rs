@@ -0,0 +1,197 @@
+//! Conformance corpus: programs with golden input and output.
+//!
+//! Each case is a program `name.tri` beside a `name.io` file naming its
+//! public input, secret input, and expected public output:
+//!
+//! ```text
+//! # comment
+//! input: 7, 5
+//! secret: 3
+//! output: 12, 35
+//! ```
+//!
+//! An optional `targets: triton, ...` line restricts a case to targets
+//! whose semantics it depends on (digest width, for instance); other
+//! targets skip it.
+//!
+//! A case passes when the program, compiled for the chosen target and
+//! run by that target's runner, writes exactly the expected output.
+
+use std::path::PathBuf;
+
+use super::testing::join_messages;
+use super::*;
+use crate::runtime::{ProgramInput, Runner};
+
+/// One program of the conformance corpus.
+#[derive(Clone, Debug)]
+pub struct ConformanceCase {
+    pub name: String,
+    pub source: PathBuf,
+    pub input: ProgramInput,
+    pub expected: Vec<u64>,
+    /// Targets the case applies to; empty means all.
+    pub targets: Vec<String>,
+}
+
+/// Load every case in `dir`, sorted by name. Every `.tri` file needs a
+/// sibling `.io` file.
+pub fn load_conformance_cases(dir: &Path) -> Result<Vec<ConformanceCase>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("cannot read '{}': {}", dir.display(), e))?;
+    let mut sources: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "tri"))
+        .collect();
+    sources.sort();
+
+    let mut cases = Vec::with_capacity(sources.len());
+    for source in sources {
+        let io_path = source.with_extension("io");
+        let text = std::fs::read_to_string(&io_path)
+            .map_err(|e| format!("cannot read '{}': {}", io_path.display(), e))?;
+        let (input, expected, targets) =
+            parse_io(&text).map_err(|e| format!("{}: {}", io_path.display(), e))?;
+        let name = source
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("case")
+            .to_string();
+        cases.push(ConformanceCase {
+            name,
+            source,
+            input,
+            expected,
+            targets,
+        });
+    }
+    Ok(cases)
+}
+
+/// Parse the `input:`, `secret:`, `output:`, and `targets:` lines of a
+/// `.io` file. `output:` is required; the others default to empty.
+pub(super) fn parse_io(text: &str) -> Result<(ProgramInput, Vec<u64>, Vec<String>), String> {
+    let mut input = ProgramInput::default();
+    let mut expected = None;
+    let mut targets = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, rest) = line
+            .split_once(':')
+            .ok_or_else(|| format!("line {}: expected `key: values`", i + 1))?;
+        let key = key.trim();
+        if key == "targets" {
+            targets = rest
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect();
+            continue;
+        }
+        let values = parse_values(rest).map_err(|e| format!("line {}: {}", i + 1, e))?;
+        match key {
+            "input" => input.public = values,
+            "secret" => input.secret = values,
+            "output" => expected = Some(values),
+            other => return Err(format!("line {}: unknown key `{}`", i + 1, other)),
+        }
+    }
+    let expected = expected.ok_or("missing `output:` line")?;
+    Ok((input, expected, targets))
+}
+
+fn parse_values(text: &str) -> Result<Vec<u64>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            v.parse::<u64>()
+                .map_err(|_| format!("`{}` is not a field element", v))
+        })
+        .collect()
+}
+
+/// Compile and run every case in `dir` for the target in `options`.
+///
+/// Without a `runner` the cases are only compiled, which still checks
+/// that the target accepts the whole corpus.
+pub fn run_conformance(
+    dir: &Path,
+    options: &CompileOptions,
+    runner: Option<&dyn Runner>,
+) -> Result<String, Vec<Diagnostic>> {
+    let cases =
+        load_conformance_cases(dir).map_err(|e| vec![Diagnostic::error(e, span::Span::dummy())])?;
+    let target = &options.target_config.name;
+
+    let mut report = format!(
+        "running {} conformance case{} on {}\n",
+        cases.len(),
+        if cases.len() == 1 { "" } else { "s" },
+        target
+    );
+    let mut failed = 0;
+    let mut skipped = 0;
+    for case in &cases {
+        if !case.targets.is_empty() && !case.targets.contains(target) {
+            skipped += 1;
+            report.push_str(&format!(
+                "  case {} ... skipped ({} only)\n",
+                case.name,
+                case.targets.join(", ")
+            ));
+            continue;
+        }
+        let outcome = match compile_to_bundle(&case.source, options) {
+            Err(errors) => Err(format!("compile error: {}", join_messages(&errors))),
+            Ok(bundle) => match runner {
+                None => Ok("compiled, not run".to_string()),
+                Some(runner) => match runner.run(&bundle, &case.input) {
+                    Ok(result) if result.output == case.expected => {
+                        Ok(format!("{} cycles", result.cycle_count))
+                    }
+                    Ok(result) => Err(format!(
+                        "expected output {:?}, got {:?}",
+                        case.expected, result.output
+                    )),
+                    Err(e) => Err(e),
+                },
+            },
+        };
+        match outcome {
+            Ok(note) => report.push_str(&format!("  case {} ... ok ({})\n", case.name, note)),
+            Err(err) => {
+                failed += 1;
+                report.push_str(&format!(
+                    "  case {} ... FAILED\n    error: {}\n",
+                    case.name, err
+                ));
+            }
+        }
+    }
+
+    report.push('\n');
+    let passed = cases.len() - failed - skipped;
+    let skipped = if skipped == 0 {
+        String::new()
+    } else {
+        format!("; {} skipped", skipped)
+    };
+    if failed == 0 {
+        report.push_str(&format!(
+            "conformance result: ok. {} passed; 0 failed{}\n",
+            passed, skipped
+        ));
+    } else {
+        report.push_str(&format!(
+            "conformance result: FAILED. {} passed; {} failed{}\n",
+            passed, failed, skipped
+        ));
+    }
+    Ok(report)
+}
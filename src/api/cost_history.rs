@@ -0,0 +1,385 @@
+//! Cost history: which commit made the program more expensive.
+//!
+//! `trident cost blame --since <rev>` checks out every first-parent commit
+//! from `<rev>` to `HEAD` in a scratch worktree, analyzes the entry program
+//! at each, and ranks the commits by how far they raised the padded height
+//! and the table costs. The user's checkout is never touched. Each
+//! analysis is cached in the repository's git directory, keyed by commit,
+//! program, target, profile, and compiler, one line per snapshot:
+//! ```text
+//! {"rev": "3f2a9c1d…", "program": "main.tri", "target": "triton", "profile": "debug", "compiler": "0.1.0", "summary": "Add merkle check", "padded_height": 2048, "tables": {"processor": 812, "hash": 612}}
+//! ```
+//! `padded_height` is `null`, and `tables` empty, for a commit at which the
+//! program did not compile.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::{analyze_costs_project, CompileOptions};
+use crate::cost::ProgramCost;
+use crate::report::json_escape;
+use crate::runtime::json::{extract_object, extract_string, extract_u64, u64_entries};
+use crate::runtime::BuildInfo;
+
+/// The cost of the entry program at one commit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostSnapshot {
+    pub rev: String,
+    /// First line of the commit message.
+    pub summary: String,
+    /// `None` when the program did not compile at this commit.
+    pub padded_height: Option<u64>,
+    /// Cost of each table, by name.
+    pub tables: Vec<(String, u64)>,
+}
+
+impl CostSnapshot {
+    pub fn from_cost(rev: &str, summary: &str, cost: &ProgramCost) -> Self {
+        let tables = cost
+            .table_names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), cost.total.get(i)))
+            .collect();
+        Self {
+            rev: rev.to_string(),
+            summary: summary.to_string(),
+            padded_height: Some(cost.padded_height),
+            tables,
+        }
+    }
+
+    /// A commit at which the program did not compile.
+    pub fn failed(rev: &str, summary: &str) -> Self {
+        Self {
+            rev: rev.to_string(),
+            summary: summary.to_string(),
+            padded_height: None,
+            tables: Vec::new(),
+        }
+    }
+
+    fn cost(&self, table: &str) -> u64 {
+        self.tables
+            .iter()
+            .find(|(name, _)| name == table)
+            .map_or(0, |(_, cost)| *cost)
+    }
+}
+
+/// What a snapshot was computed for; a cached snapshot is reused only
+/// when all of it matches.
+struct CacheKey<'a> {
+    program: &'a str,
+    target: &'a str,
+    profile: &'a str,
+    compiler: &'a str,
+}
+
+impl CacheKey<'_> {
+    fn to_json_line(&self, snapshot: &CostSnapshot) -> String {
+        let tables: Vec<String> = snapshot
+            .tables
+            .iter()
+            .map(|(name, cost)| format!("\"{}\": {}", json_escape(name), cost))
+            .collect();
+        format!(
+            "{{\"rev\": \"{}\", \"program\": \"{}\", \"target\": \"{}\", \"profile\": \"{}\", \"compiler\": \"{}\", \"summary\": \"{}\", \"padded_height\": {}, \"tables\": {{{}}}}}",
+            json_escape(&snapshot.rev),
+            json_escape(self.program),
+            json_escape(self.target),
+            json_escape(self.profile),
+            json_escape(self.compiler),
+            json_escape(&snapshot.summary),
+            snapshot
+                .padded_height
+                .map_or("null".to_string(), |h| h.to_string()),
+            tables.join(", ")
+        )
+    }
+
+    /// The snapshot on `line`, if it was cached for this key.
+    fn parse(&self, line: &str) -> Option<CostSnapshot> {
+        let matches =
+            |key: &str, value: &str| extract_string(line, key).ok().as_deref() == Some(value);
+        if !(matches("program", self.program)
+            && matches("target", self.target)
+            && matches("profile", self.profile)
+            && matches("compiler", self.compiler))
+        {
+            return None;
+        }
+        Some(CostSnapshot {
+            rev: extract_string(line, "rev").ok()?,
+            summary: extract_string(line, "summary").ok()?,
+            padded_height: extract_u64(line, "padded_height").ok(),
+            tables: extract_object(line, "tables").map_or_else(Vec::new, u64_entries),
+        })
+    }
+}
+
+/// A commit that raised the cost of the program over the last commit
+/// before it at which the program compiled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostJump {
+    pub rev: String,
+    pub summary: String,
+    /// Padded height before and after the commit.
+    pub padded_height: (u64, u64),
+    /// The table whose cost rose the most, with its cost before and after.
+    pub table: Option<(String, u64, u64)>,
+}
+
+impl CostJump {
+    pub fn padded_delta(&self) -> i64 {
+        self.padded_height.1 as i64 - self.padded_height.0 as i64
+    }
+
+    pub fn table_delta(&self) -> i64 {
+        self.table
+            .as_ref()
+            .map_or(0, |(_, before, after)| *after as i64 - *before as i64)
+    }
+}
+
+/// The commits of `snapshots` (oldest first) that raised the padded height
+/// or a table cost, largest padded-height jump first, then largest table
+/// jump. Commits that did not compile are skipped over.
+pub fn cost_jumps(snapshots: &[CostSnapshot]) -> Vec<CostJump> {
+    let mut jumps = Vec::new();
+    let mut prev: Option<(&CostSnapshot, u64)> = None;
+    for snapshot in snapshots {
+        let Some(after) = snapshot.padded_height else {
+            continue;
+        };
+        if let Some((before, before_height)) = prev {
+            let table = snapshot
+                .tables
+                .iter()
+                .map(|(name, cost)| (name.clone(), before.cost(name), *cost))
+                .filter(|(_, old, new)| new > old)
+                .max_by_key(|(_, old, new)| new - old);
+            let jump = CostJump {
+                rev: snapshot.rev.clone(),
+                summary: snapshot.summary.clone(),
+                padded_height: (before_height, after),
+                table,
+            };
+            if jump.padded_delta() > 0 || jump.table_delta() > 0 {
+                jumps.push(jump);
+            }
+        }
+        prev = Some((snapshot, after));
+    }
+    jumps.sort_by(|a, b| {
+        (b.padded_delta(), b.table_delta()).cmp(&(a.padded_delta(), a.table_delta()))
+    });
+    jumps
+}
+
+/// Render the `top` largest jumps of `snapshots` as a table.
+pub fn format_cost_blame(program: &str, snapshots: &[CostSnapshot], top: usize) -> String {
+    let failed = snapshots
+        .iter()
+        .filter(|s| s.padded_height.is_none())
+        .count();
+    let mut out = format!(
+        "Cost blame for {} over {} commit{}",
+        program,
+        snapshots.len(),
+        if snapshots.len() == 1 { "" } else { "s" }
+    );
+    if failed > 0 {
+        out.push_str(&format!(" ({} did not compile)", failed));
+    }
+    out.push('\n');
+
+    let jumps = cost_jumps(snapshots);
+    if jumps.is_empty() {
+        out.push_str("\nNo commit raised the cost.\n");
+        return out;
+    }
+    out.push_str(&format!(
+        "\n  {:<8} {:<22} {:<28} {}\n",
+        "Commit", "Padded height", "Largest table jump", "Summary"
+    ));
+    for jump in jumps.iter().take(top) {
+        let (before, after) = jump.padded_height;
+        let padded = if before == after {
+            format!("{} (=)", after)
+        } else {
+            format!("{} -> {} ({:+})", before, after, jump.padded_delta())
+        };
+        let table = match &jump.table {
+            Some((name, old, new)) => {
+                format!("{} {} -> {} ({:+})", name, old, new, jump.table_delta())
+            }
+            None => "-".to_string(),
+        };
+        let short = &jump.rev[..jump.rev.len().min(7)];
+        out.push_str(&format!(
+            "  {:<8} {:<22} {:<28} {}\n",
+            short, padded, table, jump.summary
+        ));
+    }
+    if jumps.len() > top {
+        out.push_str(&format!("  ... and {} more\n", jumps.len() - top));
+    }
+    out
+}
+
+/// Analyze the program at `entry` at `since` and every first-parent commit
+/// after it up to `HEAD`, oldest first. Snapshots are cached in the git
+/// directory; `on_commit` is called before each commit that has to be
+/// analyzed.
+pub fn blame_costs(
+    entry: &Path,
+    since: &str,
+    options: &CompileOptions,
+    mut on_commit: impl FnMut(&str, &str),
+) -> Result<Vec<CostSnapshot>, String> {
+    let entry = entry
+        .canonicalize()
+        .map_err(|e| format!("cannot read '{}': {}", entry.display(), e))?;
+    let dir = entry.parent().unwrap_or(Path::new("."));
+    let root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?.trim())
+        .canonicalize()
+        .map_err(|e| format!("cannot read repository root: {}", e))?;
+    let program = entry
+        .strip_prefix(&root)
+        .map_err(|_| format!("'{}' is outside the repository", entry.display()))?
+        .to_string_lossy()
+        .into_owned();
+
+    let base = git(&root, &["log", "-1", "--format=%H%x09%s", since, "--"])
+        .map_err(|_| format!("unknown revision '{}'", since))?;
+    let range = format!("{}..HEAD", since);
+    let later = git(
+        &root,
+        &[
+            "log",
+            "--first-parent",
+            "--reverse",
+            "--format=%H%x09%s",
+            &range,
+            "--",
+        ],
+    )?;
+    let commits: Vec<(String, String)> = base
+        .lines()
+        .chain(later.lines())
+        .filter_map(|line| {
+            let (rev, summary) = line.split_once('\t')?;
+            Some((rev.to_string(), summary.to_string()))
+        })
+        .collect();
+
+    let cache_path = root.join(
+        git(
+            &root,
+            &["rev-parse", "--git-path", "trident/cost-history.ndjson"],
+        )?
+        .trim(),
+    );
+    let build = BuildInfo::capture(options);
+    let compiler = build.compiler();
+    let key = CacheKey {
+        program: &program,
+        target: &build.target,
+        profile: &build.profile,
+        compiler: &compiler,
+    };
+    let cached: Vec<CostSnapshot> = std::fs::read_to_string(&cache_path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| key.parse(line))
+        .collect();
+
+    let mut worktree: Option<PathBuf> = None;
+    let mut snapshots = Vec::with_capacity(commits.len());
+    let mut fresh = Vec::new();
+    let mut result = Ok(());
+    for (rev, summary) in &commits {
+        if let Some(hit) = cached.iter().find(|s| s.rev == *rev) {
+            snapshots.push(hit.clone());
+            continue;
+        }
+        on_commit(rev, summary);
+        let tree = match &worktree {
+            Some(tree) => git(tree, &["checkout", "--quiet", "--detach", "--force", rev])
+                .map(|_| tree.clone()),
+            None => {
+                let tree =
+                    std::env::temp_dir().join(format!("trident_cost_blame_{}", std::process::id()));
+                let path = tree.to_string_lossy().into_owned();
+                git(
+                    &root,
+                    &["worktree", "add", "--quiet", "--detach", &path, rev],
+                )
+                .map(|_| tree)
+            }
+        };
+        let tree = match tree {
+            Ok(tree) => tree,
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        };
+        worktree = Some(tree.clone());
+        let snapshot = match analyze_costs_project(&tree.join(&program), options) {
+            Ok(cost) => CostSnapshot::from_cost(rev, summary, &cost),
+            Err(_) => CostSnapshot::failed(rev, summary),
+        };
+        fresh.push(snapshot.clone());
+        snapshots.push(snapshot);
+    }
+
+    if let Some(tree) = worktree {
+        let path = tree.to_string_lossy().into_owned();
+        let _ = git(&root, &["worktree", "remove", "--force", &path]);
+    }
+    if !fresh.is_empty() {
+        append_snapshots(&cache_path, &key, &fresh)?;
+    }
+    result.map(|_| snapshots)
+}
+
+fn append_snapshots(path: &Path, key: &CacheKey, snapshots: &[CostSnapshot]) -> Result<(), String> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("cannot create '{}': {}", parent.display(), e))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("cannot open '{}': {}", path.display(), e))?;
+    let mut text = String::new();
+    for snapshot in snapshots {
+        text.push_str(&key.to_json_line(snapshot));
+        text.push('\n');
+    }
+    file.write_all(text.as_bytes())
+        .map_err(|e| format!("cannot write '{}': {}", path.display(), e))
+}
+
+/// Run git in `dir` and return its standard output.
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("cannot run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
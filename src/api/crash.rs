@@ -0,0 +1,391 @@
+//! Crash reports: what the compiler was doing when it panicked.
+//!
+//! Off unless `TRIDENT_CRASH_REPORTS=1`. Then a panic anywhere in a
+//! command is caught once the stack has unwound, and a report is written
+//! to `~/.trident/crash/` before the process exits with status 101:
+//! ```text
+//! {"date": "2026-01-05T10:00:00Z", "compiler": "0.1.0 (3f2a9c1d0b7e)", "command": "build", "stage": "tir", "module": "main", "message": "index out of bounds: …", "location": "src/ir/tir/builder/expr.rs:212:17", "backtrace": "…", "input": null, "input_reduced": false}
+//! ```
+//! Pipeline stages announce themselves with `stage`, so the report names
+//! the stage and module that panicked. The home directory is replaced by
+//! `~` wherever a path appears, and only the subcommand name is kept of
+//! the command line.
+//!
+//! The module's source holds the program's constants and names, so it is
+//! left out unless `TRIDENT_CRASH_SOURCE=1` is set for the run that
+//! crashed. Then it is shrunk with `reduce_lines` while compiling it alone
+//! still panics at the same place; when it does not panic alone (it needs
+//! the rest of its project), the whole module is kept. Either way comments
+//! are dropped.
+//!
+//! With `TRIDENT_CRASH_ENDPOINT` set as well, the report is also POSTed
+//! there as JSON.
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::{compile_with_options, reduce_lines, CompileOptions};
+use crate::report::json_escape;
+use crate::runtime::BuildInfo;
+
+/// Compilations the reducer may run before it settles for what it has.
+const MAX_REDUCE_RUNS: usize = 500;
+
+thread_local! {
+    static STAGE: RefCell<Option<Stage>> = const { RefCell::new(None) };
+}
+
+/// Where the pipeline panicked, captured before unwinding.
+static PANIC: Mutex<Option<Panic>> = Mutex::new(None);
+
+/// A pipeline stage running on one module.
+#[derive(Clone, Debug)]
+struct Stage {
+    name: &'static str,
+    module: String,
+    source: Option<Arc<str>>,
+}
+
+#[derive(Clone, Debug)]
+struct Panic {
+    message: String,
+    location: String,
+    backtrace: String,
+    stage: Option<Stage>,
+}
+
+/// Marks the current thread as running `name` on `module` until dropped.
+pub struct StageGuard {
+    previous: Option<Stage>,
+}
+
+impl Drop for StageGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        STAGE.with(|stage| *stage.borrow_mut() = previous);
+    }
+}
+
+/// Announce that pipeline stage `name` is running on `module`, whose
+/// source is `source` if there is one.
+pub(crate) fn stage(name: &'static str, module: &str, source: Option<&Arc<str>>) -> StageGuard {
+    let next = Stage {
+        name,
+        module: module.to_string(),
+        source: source.cloned(),
+    };
+    let previous = STAGE.with(|stage| stage.borrow_mut().replace(next));
+    StageGuard { previous }
+}
+
+/// A crash report.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrashReport {
+    pub date: String,
+    pub compiler: String,
+    pub command: String,
+    /// The pipeline stage that panicked, if the panic was in one.
+    pub stage: Option<String>,
+    pub module: Option<String>,
+    pub message: String,
+    pub location: String,
+    pub backtrace: String,
+    /// Source of the module that panicked, without comments; only when
+    /// the user opted in to sending it.
+    pub input: Option<String>,
+    /// Whether `input` was reduced to a smaller source that still panics.
+    pub input_reduced: bool,
+}
+
+impl CrashReport {
+    pub fn to_json(&self) -> String {
+        let opt = |v: &Option<String>| match v {
+            Some(s) => format!("\"{}\"", json_escape(s)),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"date\": \"{}\", \"compiler\": \"{}\", \"command\": \"{}\", \"stage\": {}, \"module\": {}, \"message\": \"{}\", \"location\": \"{}\", \"backtrace\": \"{}\", \"input\": {}, \"input_reduced\": {}}}\n",
+            json_escape(&self.date),
+            json_escape(&self.compiler),
+            json_escape(&self.command),
+            opt(&self.stage),
+            opt(&self.module),
+            json_escape(&self.message),
+            json_escape(&self.location),
+            json_escape(&self.backtrace),
+            opt(&self.input),
+            self.input_reduced
+        )
+    }
+}
+
+/// Whether the user opted in to crash reports.
+pub fn enabled() -> bool {
+    matches!(
+        std::env::var("TRIDENT_CRASH_REPORTS").as_deref(),
+        Ok("1" | "true" | "on")
+    )
+}
+
+/// Whether the user opted in to including the module source in reports.
+fn source_included() -> bool {
+    matches!(
+        std::env::var("TRIDENT_CRASH_SOURCE").as_deref(),
+        Ok("1" | "true" | "on")
+    )
+}
+
+/// Run `command` (the subcommand named `name`); if crash reports are on
+/// and it panics, write a report and exit with status 101.
+pub fn run_reported(name: &str, command: impl FnOnce()) {
+    if !enabled() {
+        command();
+        return;
+    }
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        record_panic(info, true);
+        default_hook(info);
+    }));
+
+    if panic::catch_unwind(AssertUnwindSafe(command)).is_ok() {
+        return;
+    }
+    let captured = PANIC.lock().ok().and_then(|mut slot| slot.take());
+    if let Some(captured) = captured {
+        let has_source = captured.stage.as_ref().is_some_and(|s| s.source.is_some());
+        let report = build_report(name, captured, source_included());
+        match write_report(&report) {
+            Ok(path) => eprintln!(
+                "\ncrash report written to {}\n  please attach it to a bug report",
+                path.display()
+            ),
+            Err(e) => eprintln!("\nerror: cannot write crash report: {}", e),
+        }
+        if has_source && report.input.is_none() {
+            eprintln!(
+                "  the module source was left out; rerun with TRIDENT_CRASH_SOURCE=1 to include a reduced copy"
+            );
+        }
+        if let Ok(endpoint) = std::env::var("TRIDENT_CRASH_ENDPOINT") {
+            match upload_report(&endpoint, &report) {
+                Ok(()) => eprintln!("  sent to {}", endpoint),
+                Err(e) => eprintln!("  error: cannot send it to {}: {}", endpoint, e),
+            }
+        }
+    }
+    std::process::exit(101);
+}
+
+/// Remember the first panic since `PANIC` was last emptied.
+fn record_panic(info: &panic::PanicHookInfo, with_backtrace: bool) {
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_default();
+    let backtrace = if with_backtrace {
+        std::backtrace::Backtrace::force_capture().to_string()
+    } else {
+        String::new()
+    };
+    let captured = Panic {
+        message: panic_message(info.payload()),
+        location,
+        backtrace,
+        stage: STAGE.with(|stage| stage.borrow().clone()),
+    };
+    if let Ok(mut slot) = PANIC.lock() {
+        slot.get_or_insert(captured);
+    }
+}
+
+/// The report of `captured`, with the module source only if
+/// `include_source`.
+fn build_report(command: &str, captured: Panic, include_source: bool) -> CrashReport {
+    let home = std::env::var("HOME").ok();
+    let redact = |text: &str| redact_home(text, home.as_deref());
+    let stage = captured.stage;
+    let source = stage
+        .as_ref()
+        .and_then(|s| s.source.as_deref())
+        .filter(|_| include_source);
+    let (input, input_reduced) = match source {
+        Some(source) => {
+            let filename = stage.as_ref().map_or("crash.tri", |s| s.module.as_str());
+            let (input, reduced) = reduce_input(source, filename, &captured.location);
+            (Some(redact(&input)), reduced)
+        }
+        None => (None, false),
+    };
+    CrashReport {
+        date: crate::deploy::iso8601_now(),
+        compiler: BuildInfo::capture(&CompileOptions::default()).compiler(),
+        command: command.to_string(),
+        stage: stage.as_ref().map(|s| s.name.to_string()),
+        module: stage.as_ref().map(|s| s.module.clone()),
+        message: redact(&captured.message),
+        location: captured.location,
+        backtrace: redact(&captured.backtrace),
+        input,
+        input_reduced,
+    }
+}
+
+/// `source` without comments, reduced while compiling it alone still
+/// panics at `location`, and whether it reproduced at all.
+fn reduce_input(source: &str, filename: &str, location: &str) -> (String, bool) {
+    let stripped = strip_comments(source);
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|info| record_panic(info, false)));
+    let _silence = crate::diagnostic::silence();
+    let mut runs = 0;
+    let mut panics_here = |candidate: &str| {
+        runs += 1;
+        if runs > MAX_REDUCE_RUNS {
+            return false;
+        }
+        if let Ok(mut slot) = PANIC.lock() {
+            *slot = None;
+        }
+        let run = panic::catch_unwind(|| {
+            compile_with_options(candidate, filename, &CompileOptions::default())
+        });
+        run.is_err()
+            && PANIC
+                .lock()
+                .ok()
+                .and_then(|slot| slot.as_ref().map(|p| p.location == location))
+                .unwrap_or(false)
+    };
+    let result = if panics_here(&stripped) {
+        (reduce_lines(&stripped, &mut panics_here), true)
+    } else {
+        (stripped, false)
+    };
+    panic::set_hook(hook);
+    result
+}
+
+/// `source` with its `//` comments removed, and lines left empty by that
+/// dropped.
+fn strip_comments(source: &str) -> String {
+    let mut out = String::new();
+    for line in source.lines() {
+        let code = match line.find("//") {
+            Some(i) => line[..i].trim_end(),
+            None => line,
+        };
+        if code.trim().is_empty() && !line.trim().is_empty() {
+            continue;
+        }
+        out.push_str(code);
+        out.push('\n');
+    }
+    out
+}
+
+/// `text` with the home directory `home` replaced by `~`.
+fn redact_home(text: &str, home: Option<&str>) -> String {
+    match home.map(|h| h.trim_end_matches('/')) {
+        Some(home) if !home.is_empty() => text.replace(home, "~"),
+        _ => text.to_string(),
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "panic".to_string()
+    }
+}
+
+/// Write `report` to `~/.trident/crash/`, returning its path.
+fn write_report(report: &CrashReport) -> Result<PathBuf, String> {
+    let home = std::env::var("HOME").map_err(|_| "no $HOME".to_string())?;
+    let dir = Path::new(&home).join(".trident").join("crash");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("cannot create '{}': {}", dir.display(), e))?;
+    let stamp: String = report.date.chars().filter(char::is_ascii_digit).collect();
+    let path = dir.join(format!("crash-{}-{}.json", stamp, std::process::id()));
+    std::fs::write(&path, report.to_json())
+        .map_err(|e| format!("cannot write '{}': {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// POST `report` to `endpoint`, e.g. `http://crash.example.org:8090/reports`.
+fn upload_report(endpoint: &str, report: &CrashReport) -> Result<(), String> {
+    let rest = endpoint.strip_prefix("http://").unwrap_or(endpoint);
+    let path = rest.find('/').map_or("/", |i| &rest[i..]);
+    let client = crate::registry::RegistryClient::new(endpoint).with_cache(None);
+    let (status, body) = client.post_json(path, &report.to_json())?;
+    if status >= 400 {
+        return Err(format!("status {}: {}", status, body.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_nest_and_restore() {
+        let source: Arc<str> = "program main\n".into();
+        let current = || STAGE.with(|s| s.borrow().as_ref().map(|s| (s.name, s.module.clone())));
+        {
+            let _outer = stage("typecheck", "main", Some(&source));
+            {
+                let _inner = stage("link", "", None);
+                assert_eq!(current(), Some(("link", String::new())));
+            }
+            assert_eq!(current(), Some(("typecheck", "main".to_string())));
+        }
+        assert_eq!(current(), None);
+    }
+
+    #[test]
+    fn input_is_reduced_and_redacted() {
+        let source = "program main\n// key: 0xfeed\nfn main() {\n    let a: Field = 1 // one\n    let b: Field = 2\n    assert(a == 1)\n}\n";
+        assert_eq!(
+            strip_comments(source),
+            "program main\nfn main() {\n    let a: Field = 1\n    let b: Field = 2\n    assert(a == 1)\n}\n"
+        );
+        let reduced = reduce_lines(&strip_comments(source), |s| s.contains("assert"));
+        assert_eq!(reduced, "    assert(a == 1)\n");
+        assert_eq!(
+            redact_home("at /home/ada/src/main.tri:3", Some("/home/ada/")),
+            "at ~/src/main.tri:3"
+        );
+        assert_eq!(
+            redact_home("at /src/main.tri", Some("")),
+            "at /src/main.tri"
+        );
+    }
+
+    #[test]
+    fn source_is_left_out_unless_included() {
+        let source: Arc<str> =
+            "program main\nconst SECRET: Field = 31337\nfn main() {\n    pub_write(SECRET)\n}\n"
+                .into();
+        let captured = Panic {
+            message: "index out of bounds".to_string(),
+            location: "src/ir/tir/builder/expr.rs:1:1".to_string(),
+            backtrace: String::new(),
+            stage: Some(Stage {
+                name: "tir",
+                module: "main".to_string(),
+                source: Some(source),
+            }),
+        };
+        let report = build_report("build", captured, false);
+        assert_eq!(report.stage.as_deref(), Some("tir"));
+        assert_eq!(report.input, None);
+        assert!(!report.to_json().contains("31337"));
+        assert!(!report.to_json().contains("SECRET"));
+    }
+}
@@ -0,0 +1,293 @@
+//! Disassembler: structured control flow from linked TASM.
+//!
+//! Triton assembly has no structured control flow, only labels, `call`,
+//! `return`, `recurse`, and `skiz`. Compiled code uses them in fixed
+//! shapes: a branch arm is a block called right after `skiz`, a loop is
+//! a block that ends in `recurse` and leaves through `skiz; return`.
+//! Blocks in these shapes with a single call site are printed nested
+//! inside their caller; every other label is a function. Each block
+//! carries the table rows of its own instructions, and functions can be
+//! mapped back to their Trident definitions through a symbol index.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use super::index::{DefinitionKind, IndexEntry};
+use crate::cost::create_cost_model;
+use crate::cost::scorer::profile_tasm;
+use crate::runtime::interp::Program;
+use crate::tir::linker::fn_label;
+
+/// One instruction, as written.
+struct Instr<'a> {
+    text: &'a str,
+}
+
+impl Instr<'_> {
+    fn op(&self) -> &str {
+        self.text.split_whitespace().next().unwrap_or("")
+    }
+
+    fn call_target(&self) -> Option<&str> {
+        self.text.strip_prefix("call ").map(str::trim)
+    }
+}
+
+/// The instructions after a label, up to the next label. The code before
+/// the first label is the entry block.
+struct Block<'a> {
+    label: Option<&'a str>,
+    line: usize,
+    instrs: Vec<Instr<'a>>,
+}
+
+impl Block<'_> {
+    fn is_loop(&self) -> bool {
+        matches!(
+            self.instrs.last().map(|i| i.op()),
+            Some("recurse" | "recurse_or_return")
+        )
+    }
+}
+
+/// How a call site is printed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Branch,
+    Loop,
+}
+
+/// Disassemble `tasm` into structured pseudo-assembly. Functions whose
+/// label matches a function in `source_map` are annotated with its
+/// location (relative to `root`) and signature.
+pub(crate) fn disassemble(
+    tasm: &str,
+    source_map: &[IndexEntry],
+    root: &Path,
+) -> Result<String, String> {
+    // Reject what the interpreter would not run: unknown instructions,
+    // malformed operands, duplicate labels.
+    Program::parse(tasm)?;
+
+    let blocks = split_blocks(tasm);
+    let by_label: BTreeMap<&str, usize> = blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, b)| b.label.map(|l| (l, i)))
+        .collect();
+
+    // Call graph: callers of each block, in source order.
+    let mut callers: Vec<Vec<usize>> = vec![Vec::new(); blocks.len()];
+    for (i, block) in blocks.iter().enumerate() {
+        for instr in &block.instrs {
+            if let Some(&callee) = instr.call_target().and_then(|t| by_label.get(t)) {
+                callers[callee].push(i);
+            }
+        }
+    }
+
+    // Shape of each block that is printed inside its only caller.
+    let mut shapes: Vec<Option<Shape>> = vec![None; blocks.len()];
+    for (i, block) in blocks.iter().enumerate() {
+        for (k, instr) in block.instrs.iter().enumerate() {
+            let Some(&callee) = instr.call_target().and_then(|t| by_label.get(t)) else {
+                continue;
+            };
+            if callee == i || callers[callee].len() != 1 {
+                continue;
+            }
+            let after_skiz = k > 0 && block.instrs[k - 1].op() == "skiz";
+            shapes[callee] = if blocks[callee].is_loop() {
+                Some(Shape::Loop)
+            } else if after_skiz {
+                Some(Shape::Branch)
+            } else {
+                None
+            };
+        }
+    }
+
+    let sources: BTreeMap<String, &IndexEntry> = source_map
+        .iter()
+        .filter(|e| e.kind == DefinitionKind::Function)
+        .map(|e| (fn_label(&e.module, &e.name), e))
+        .collect();
+
+    let mut printer = Printer {
+        blocks: &blocks,
+        by_label: &by_label,
+        shapes: &shapes,
+        printed: vec![false; blocks.len()],
+        out: String::new(),
+    };
+    let mut order: Vec<usize> = (0..blocks.len()).filter(|&i| shapes[i].is_none()).collect();
+    // A cycle of single-caller blocks has no function to print it from.
+    order.extend((0..blocks.len()).filter(|&i| shapes[i].is_some()));
+    for i in order {
+        if printer.printed[i] {
+            continue;
+        }
+        if !printer.out.is_empty() {
+            printer.out.push('\n');
+        }
+        let block = &blocks[i];
+        let name = block.label.unwrap_or("<entry>");
+        let _ = writeln!(
+            printer.out,
+            "fn {} {{  // line {}, {}",
+            name,
+            block.line,
+            cost_label(&block.instrs)
+        );
+        if let Some(entry) = block.label.and_then(|l| sources.get(l)) {
+            let file = entry.file.strip_prefix(root).unwrap_or(&entry.file);
+            let _ = writeln!(
+                printer.out,
+                "    // {}:{}  {}",
+                file.display(),
+                entry.line,
+                entry.signature
+            );
+        }
+        let called_by: Vec<&str> = callers[i]
+            .iter()
+            .map(|&c| blocks[c].label.unwrap_or("<entry>"))
+            .collect();
+        if !called_by.is_empty() {
+            let _ = writeln!(printer.out, "    // called by {}", called_by.join(", "));
+        }
+        printer.body(i, 1, None);
+        printer.out.push_str("}\n");
+    }
+    Ok(printer.out)
+}
+
+/// Split TASM into labeled blocks, dropping comments and blank lines.
+fn split_blocks(tasm: &str) -> Vec<Block<'_>> {
+    let mut blocks = vec![Block {
+        label: None,
+        line: 1,
+        instrs: Vec::new(),
+    }];
+    for (i, raw) in tasm.lines().enumerate() {
+        let text = raw.split("//").next().unwrap_or("").trim();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(label) = text.strip_suffix(':') {
+            blocks.push(Block {
+                label: Some(label),
+                line: i + 1,
+                instrs: Vec::new(),
+            });
+        } else if let Some(block) = blocks.last_mut() {
+            if block.instrs.is_empty() && block.label.is_none() {
+                block.line = i + 1;
+            }
+            block.instrs.push(Instr { text });
+        }
+    }
+    if blocks[0].instrs.is_empty() {
+        blocks.remove(0);
+    }
+    blocks
+}
+
+/// Nonzero table rows of a straight-line run, e.g. `cc 12, opst 9`.
+fn cost_label(instrs: &[Instr]) -> String {
+    let lines: Vec<&str> = instrs.iter().map(|i| i.text).collect();
+    let profile = profile_tasm(&lines);
+    let names = create_cost_model("triton").table_short_names();
+    let rows: Vec<String> = names
+        .iter()
+        .zip(profile.heights)
+        .filter(|(_, h)| *h > 0)
+        .map(|(name, h)| format!("{} {}", name, h))
+        .collect();
+    if rows.is_empty() {
+        "no rows".to_string()
+    } else {
+        rows.join(", ")
+    }
+}
+
+struct Printer<'a, 'b> {
+    blocks: &'a [Block<'b>],
+    by_label: &'a BTreeMap<&'b str, usize>,
+    shapes: &'a [Option<Shape>],
+    printed: Vec<bool>,
+    out: String,
+}
+
+impl Printer<'_, '_> {
+    /// Print the instructions of block `i` at `depth`. Inside a nested
+    /// block, `shape` drops the instruction that only closes it.
+    fn body(&mut self, i: usize, depth: usize, shape: Option<Shape>) {
+        self.printed[i] = true;
+        let blocks = self.blocks;
+        let instrs = &blocks[i].instrs;
+        let indent = "    ".repeat(depth);
+        let mut k = 0;
+        while k < instrs.len() {
+            let instr = &instrs[k];
+            let last = k + 1 == instrs.len();
+            let next = instrs.get(k + 1);
+
+            // `skiz; call L` to a branch arm: `if st0 != 0 { ... }`.
+            if instr.op() == "skiz" {
+                if let Some(callee) = next.and_then(|n| self.nested(n, Shape::Branch)) {
+                    self.nested_block(callee, depth, "if st0 != 0", Shape::Branch);
+                    k += 2;
+                    continue;
+                }
+                if next.is_some_and(|n| n.op() == "return") {
+                    let exit = if shape == Some(Shape::Loop) {
+                        "break"
+                    } else {
+                        "return"
+                    };
+                    let _ = writeln!(self.out, "{}{} if st0 != 0", indent, exit);
+                    k += 2;
+                    continue;
+                }
+            }
+            if let Some(callee) = self.nested(instr, Shape::Loop) {
+                self.nested_block(callee, depth, "loop", Shape::Loop);
+                k += 1;
+                continue;
+            }
+            let closes = last
+                && match shape {
+                    Some(Shape::Branch) => instr.op() == "return",
+                    Some(Shape::Loop) => instr.op() == "recurse",
+                    None => false,
+                };
+            if !closes {
+                let _ = writeln!(self.out, "{}{}", indent, instr.text);
+            }
+            k += 1;
+        }
+    }
+
+    /// The block `instr` calls, if it is printed nested with `shape`.
+    fn nested(&self, instr: &Instr, shape: Shape) -> Option<usize> {
+        let callee = *self.by_label.get(instr.call_target()?)?;
+        (self.shapes[callee] == Some(shape) && !self.printed[callee]).then_some(callee)
+    }
+
+    fn nested_block(&mut self, callee: usize, depth: usize, head: &str, shape: Shape) {
+        let indent = "    ".repeat(depth);
+        let block = &self.blocks[callee];
+        let _ = writeln!(
+            self.out,
+            "{}{} {{  // {}, {}",
+            indent,
+            head,
+            block.label.unwrap_or_default(),
+            cost_label(&block.instrs)
+        );
+        self.body(callee, depth + 1, Some(shape));
+        let _ = writeln!(self.out, "{}}}", indent);
+    }
+}
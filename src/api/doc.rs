@@ -1,16 +1,22 @@
 //! Documentation generation for Trident projects.
 //!
 //! Produces markdown documentation listing all public functions, structs,
-//! constants, and events with their type signatures and cost annotations.
+//! constants, and events with their type signatures and cost annotations,
+//! and renders it as a standalone HTML page. A program's docs open with
+//! its audit summary: instruction count, padded height, dominant table,
+//! verification verdict and program hash, all recomputed on every build.
 
 use std::path::Path;
 
 use crate::ast;
-use crate::ast::display::{format_ast_type, format_const_value, format_fn_signature};
+use crate::ast::display::{
+    format_ast_type, format_const_value, format_enum_variant, format_fn_signature,
+};
 use crate::ast::FileKind;
 use crate::cost;
 use crate::diagnostic::Diagnostic;
 use crate::pipeline::PreparedProject;
+use crate::syntax::trivia::TriviaMap;
 use crate::target::TerrainConfig;
 use crate::CompileOptions;
 
@@ -27,8 +33,14 @@ pub(crate) fn generate_docs(
 
     // Compute cost analysis per module
     let mut module_costs: Vec<Option<cost::ProgramCost>> = Vec::new();
+    // Each module is analyzed on its own; keying it by its content lets
+    // a long-running process (`trident doc --serve`) skip unchanged ones.
+    let target = &options.target_config.name;
     for pm in &project.modules {
-        let pc = cost::CostAnalyzer::for_target(&options.target_config.name).analyze_file(&pm.file);
+        let digest = cost::cache::module_digest(target, &pm.source, &[0; 32]);
+        let pc = cost::CostAnalyzer::for_target(target)
+            .with_content_digest(digest)
+            .analyze_file(&pm.file);
         module_costs.push(Some(pc));
     }
 
@@ -38,9 +50,27 @@ pub(crate) fn generate_docs(
         .map(|m| m.file.name.node.clone())
         .unwrap_or_else(|| "project".to_string());
 
+    // `///` comments above items, attached per module.
+    let trivia: Vec<TriviaMap> = project
+        .modules
+        .iter()
+        .map(|pm| TriviaMap::from_source(&pm.source))
+        .collect();
+
     let mut doc = String::new();
     doc.push_str(&format!("# {}\n", program_name));
 
+    if let Some(i) = project
+        .modules
+        .iter()
+        .position(|m| m.file.kind == FileKind::Program)
+    {
+        let pc = module_costs[i]
+            .as_ref()
+            .expect("module_costs always contains Some");
+        doc.push_str(&program_summary(entry_path, options, &project, pc));
+    }
+
     // --- Functions ---
     let mut fn_entries: Vec<String> = Vec::new();
     for (i, pm) in project.modules.iter().enumerate() {
@@ -67,6 +97,7 @@ pub(crate) fn generate_docs(
                     costs.and_then(|pc| pc.functions.iter().find(|f| f.name == func.name.node));
 
                 let mut entry = format!("### `{}`\n", sig);
+                entry.push_str(&doc_paragraph(&trivia[i], item.span.start));
                 if let Some(fc) = fn_cost {
                     let c = &fc.cost;
                     let sn = costs
@@ -96,7 +127,7 @@ pub(crate) fn generate_docs(
 
     // --- Structs ---
     let mut struct_entries: Vec<String> = Vec::new();
-    for pm in project.modules.iter() {
+    for (pm, trivia) in project.modules.iter().zip(&trivia) {
         for item in &pm.file.items {
             if let ast::Item::Struct(sdef) = &item.node {
                 if pm.file.kind == FileKind::Module && !sdef.is_pub {
@@ -109,6 +140,7 @@ pub(crate) fn generate_docs(
                 }
 
                 let mut entry = format!("### `struct {}`\n", sdef.name.node);
+                entry.push_str(&doc_paragraph(trivia, item.span.start));
                 entry.push_str("| Field | Type | Width |\n");
                 entry.push_str("|-------|------|-------|\n");
                 let mut total_width: u32 = 0;
@@ -135,6 +167,56 @@ pub(crate) fn generate_docs(
         }
     }
 
+    // --- Enums ---
+    let mut enum_entries: Vec<String> = Vec::new();
+    for (pm, trivia) in project.modules.iter().zip(&trivia) {
+        for item in &pm.file.items {
+            if let ast::Item::Enum(edef) = &item.node {
+                if pm.file.kind == FileKind::Module && !edef.is_pub {
+                    continue;
+                }
+                if let Some(ref cfg) = edef.cfg {
+                    if !options.cfg_flags.contains(&cfg.node) {
+                        continue;
+                    }
+                }
+
+                let mut entry = format!("### `enum {}`\n", edef.name.node);
+                entry.push_str(&doc_paragraph(trivia, item.span.start));
+                entry.push_str("| Tag | Variant | Payload width |\n");
+                entry.push_str("|-----|---------|---------------|\n");
+                let mut payload_width: u32 = 0;
+                for (tag, variant) in edef.variants.iter().enumerate() {
+                    let width: u32 = variant
+                        .fields
+                        .iter()
+                        .map(|t| ast_type_width(&t.node, &options.target_config))
+                        .sum();
+                    payload_width = payload_width.max(width);
+                    entry.push_str(&format!(
+                        "| {} | {} | {} |\n",
+                        tag,
+                        format_enum_variant(variant),
+                        width
+                    ));
+                }
+                entry.push_str(&format!(
+                    "Total width: {} field elements\n",
+                    1 + payload_width
+                ));
+                enum_entries.push(entry);
+            }
+        }
+    }
+
+    if !enum_entries.is_empty() {
+        doc.push_str("\n## Enums\n\n");
+        for entry in &enum_entries {
+            doc.push_str(entry);
+            doc.push('\n');
+        }
+    }
+
     // --- Constants ---
     let mut const_entries: Vec<(String, String, String)> = Vec::new(); // (name, type, value)
     for pm in project.modules.iter() {
@@ -167,7 +249,7 @@ pub(crate) fn generate_docs(
 
     // --- Events ---
     let mut event_entries: Vec<String> = Vec::new();
-    for pm in project.modules.iter() {
+    for (pm, trivia) in project.modules.iter().zip(&trivia) {
         for item in &pm.file.items {
             if let ast::Item::Event(edef) = &item.node {
                 if let Some(ref cfg) = edef.cfg {
@@ -176,6 +258,7 @@ pub(crate) fn generate_docs(
                     }
                 }
                 let mut entry = format!("### `event {}`\n", edef.name.node);
+                entry.push_str(&doc_paragraph(trivia, item.span.start));
                 entry.push_str("| Field | Type |\n");
                 entry.push_str("|-------|------|\n");
                 for field in &edef.fields {
@@ -235,6 +318,184 @@ pub(crate) fn generate_docs(
     Ok(doc)
 }
 
+/// The audit summary of a program: one table row of instruction count,
+/// padded height, dominant table, verification verdict and program hash
+/// (Poseidon2 of the assembly, as `trident package` records it). Every
+/// column is a function of the sources, so rebuilding unchanged docs
+/// gives the same table. A column that cannot be computed shows a dash.
+fn program_summary(
+    entry_path: &Path,
+    options: &CompileOptions,
+    project: &PreparedProject,
+    pc: &cost::ProgramCost,
+) -> String {
+    let tasm = crate::compile_project_with_options(entry_path, options).ok();
+    let instructions = tasm
+        .as_deref()
+        .map(|t| crate::count_tasm_instructions(t).to_string())
+        .unwrap_or_else(|| "—".to_string());
+    let program_hash = tasm
+        .as_deref()
+        .map(|t| {
+            let digest = crate::field::poseidon2::hash_bytes_goldilocks(t.as_bytes());
+            format!("`{}`", crate::hash::ContentHash(digest).to_hex())
+        })
+        .unwrap_or_else(|| "—".to_string());
+
+    let mut system = crate::sym::ConstraintSystem::new();
+    for pm in &project.modules {
+        for (_, fn_system) in crate::sym::analyze_all(&pm.file) {
+            system.merge(fn_system);
+        }
+    }
+    let report = crate::solve::verify_with(&system, &crate::solve::VerifyConfig::default());
+    let verdict = if report.is_safe() { "safe" } else { "unsafe" };
+
+    let mut out = String::from("\n## Program\n\n");
+    out.push_str(
+        "| Instructions | Padded height | Dominant table | Verification | Program hash |\n",
+    );
+    out.push_str(
+        "|--------------|---------------|----------------|--------------|--------------|\n",
+    );
+    out.push_str(&format!(
+        "| {} | {} | {} | {} | {} |\n",
+        instructions,
+        pc.padded_height,
+        pc.total.dominant_table(&pc.short_names()),
+        verdict,
+        program_hash
+    ));
+    out
+}
+
+/// The `///` comment of the item starting at `start`, as a markdown
+/// paragraph, or nothing when it has none.
+fn doc_paragraph(trivia: &TriviaMap, start: u32) -> String {
+    match trivia.doc_comment(start) {
+        Some(text) => format!("{}\n\n", text),
+        None => String::new(),
+    }
+}
+
+/// Render generated documentation as a standalone HTML page.
+///
+/// Covers the markdown `generate_docs` emits: headings, pipe tables,
+/// paragraphs, inline code, and bold text.
+pub(crate) fn render_html(markdown: &str) -> String {
+    let title = markdown
+        .lines()
+        .find_map(|l| l.strip_prefix("# "))
+        .unwrap_or("Documentation");
+    let mut body = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut table: Vec<&str> = Vec::new();
+    for line in markdown.lines() {
+        if line.starts_with('|') {
+            flush_paragraph(&mut body, &mut paragraph);
+            table.push(line);
+            continue;
+        }
+        flush_table(&mut body, &mut table);
+        let heading = line.chars().take_while(|&c| c == '#').count();
+        if heading > 0 && line[heading..].starts_with(' ') {
+            flush_paragraph(&mut body, &mut paragraph);
+            body.push_str(&format!(
+                "<h{0}>{1}</h{0}>\n",
+                heading,
+                inline_html(line[heading..].trim())
+            ));
+        } else if line.trim().is_empty() {
+            flush_paragraph(&mut body, &mut paragraph);
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush_paragraph(&mut body, &mut paragraph);
+    flush_table(&mut body, &mut table);
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        HTML_STYLE,
+        body
+    )
+}
+
+const HTML_STYLE: &str =
+    "body{font-family:sans-serif;max-width:60em;margin:2em auto;padding:0 1em;line-height:1.5}\
+code{font-family:monospace;background:#f3f3f3;padding:0 .2em}\
+table{border-collapse:collapse}th,td{border:1px solid #ccc;padding:.2em .6em;text-align:left}";
+
+fn flush_paragraph(body: &mut String, lines: &mut Vec<&str>) {
+    if lines.is_empty() {
+        return;
+    }
+    let text: Vec<String> = lines.iter().map(|l| inline_html(l.trim())).collect();
+    body.push_str(&format!("<p>{}</p>\n", text.join("<br>\n")));
+    lines.clear();
+}
+
+fn flush_table(body: &mut String, rows: &mut Vec<&str>) {
+    if rows.is_empty() {
+        return;
+    }
+    body.push_str("<table>\n");
+    for (i, row) in rows.iter().enumerate() {
+        let cells: Vec<&str> = row.trim().trim_matches('|').split('|').collect();
+        if cells.iter().all(|c| c.trim().chars().all(|ch| ch == '-')) {
+            continue;
+        }
+        let tag = if i == 0 { "th" } else { "td" };
+        body.push_str("<tr>");
+        for cell in cells {
+            body.push_str(&format!("<{0}>{1}</{0}>", tag, inline_html(cell.trim())));
+        }
+        body.push_str("</tr>\n");
+    }
+    body.push_str("</table>\n");
+    rows.clear();
+}
+
+/// Escape text and render `code` spans and **bold** runs.
+fn inline_html(text: &str) -> String {
+    let mut out = String::new();
+    let mut bold = false;
+    let mut rest = text;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('`') {
+            if let Some(end) = after.find('`') {
+                out.push_str(&format!("<code>{}</code>", escape_html(&after[..end])));
+                rest = &after[end + 1..];
+                continue;
+            }
+        }
+        if let Some(after) = rest.strip_prefix("**") {
+            out.push_str(if bold { "</strong>" } else { "<strong>" });
+            bold = !bold;
+            rest = after;
+            continue;
+        }
+        let first = rest.chars().next().map_or(1, char::len_utf8);
+        let next = rest[first..]
+            .find(['`', '*'])
+            .map_or(rest.len(), |i| i + first);
+        out.push_str(&escape_html(&rest[..next]));
+        rest = &rest[next..];
+    }
+    if bold {
+        out.push_str("</strong>");
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 /// Compute the width in field elements for an AST type (best-effort).
 pub(crate) fn ast_type_width(ty: &ast::Type, config: &TerrainConfig) -> u32 {
     match ty {
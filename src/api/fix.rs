@@ -0,0 +1,256 @@
+//! Applying the machine-applicable suggestions of diagnostics.
+//!
+//! A file is checked in its project, and every suggestion marked
+//! `MachineApplicable` is applied in one pass: legacy module paths,
+//! missing `mut`, names taken by upcoming keywords. Suggestions that
+//! overlap one already taken are left for the next run; the same edit
+//! suggested twice is applied once. Each item an edit lands in is then
+//! reformatted, the rest of the file is left as written.
+
+use crate::diagnostic::Applicability;
+use crate::span::Span;
+
+use super::tools::check_in_project;
+use super::*;
+
+/// A suggestion `fix_source` applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AppliedFix {
+    /// 1-based line of the suggestion's first edit, in the original source.
+    pub line: usize,
+    pub message: String,
+}
+
+/// A source with its machine-applicable fixes applied.
+#[derive(Clone, Debug)]
+pub struct FixedSource {
+    pub source: String,
+    pub applied: Vec<AppliedFix>,
+}
+
+/// Apply the machine-applicable suggestions of the diagnostics of
+/// `source`, checked as the file at `file_path` of its project.
+/// Fails only when the file does not parse.
+pub fn fix_source(
+    source: &str,
+    file_path: &Path,
+    options: &CompileOptions,
+) -> Result<FixedSource, Vec<Diagnostic>> {
+    crate::parse_source_silent(source, &file_path.to_string_lossy())?;
+    let diagnostics = match check_in_project(source, file_path, options) {
+        Ok(warnings) => warnings,
+        Err(errors) => errors,
+    };
+
+    let mut suggestions: Vec<_> = diagnostics
+        .iter()
+        .flat_map(|d| &d.suggestions)
+        .filter(|s| s.applicability == Applicability::MachineApplicable && !s.edits.is_empty())
+        .collect();
+    suggestions.sort_by_key(|s| s.edits.iter().map(|(span, _)| span.start).min());
+
+    let mut edits: Vec<(Span, &str)> = Vec::new();
+    let mut applied = Vec::new();
+    for suggestion in suggestions {
+        let new: Vec<_> = suggestion
+            .edits
+            .iter()
+            .filter(|(span, text)| !edits.contains(&(*span, text.as_str())))
+            .collect();
+        if new.is_empty() || new.iter().any(|(span, _)| overlaps_any(*span, &edits)) {
+            continue;
+        }
+        edits.extend(new.iter().map(|(span, text)| (*span, text.as_str())));
+        let first = suggestion.edits.iter().map(|(span, _)| span.start).min();
+        applied.push(AppliedFix {
+            line: line_of(source, first.unwrap_or(0) as usize),
+            message: suggestion.message.clone(),
+        });
+    }
+    if edits.is_empty() {
+        return Ok(FixedSource {
+            source: source.to_string(),
+            applied,
+        });
+    }
+
+    edits.sort_by_key(|(span, _)| (span.start, span.end));
+    let mut fixed = String::with_capacity(source.len());
+    let mut touched = Vec::with_capacity(edits.len());
+    let mut pos = 0;
+    for (span, text) in &edits {
+        fixed.push_str(&source[pos..span.start as usize]);
+        touched.push(fixed.len()..fixed.len() + text.len());
+        fixed.push_str(text);
+        pos = span.end as usize;
+    }
+    fixed.push_str(&source[pos..]);
+
+    Ok(FixedSource {
+        source: format_touched_items(&fixed, &touched).unwrap_or(fixed),
+        applied,
+    })
+}
+
+/// Whether `span` overlaps, or inserts at the same place as, one of `edits`.
+fn overlaps_any(span: Span, edits: &[(Span, &str)]) -> bool {
+    edits.iter().any(|(other, _)| {
+        (span.start < other.end && other.start < span.end) || span.start == other.start
+    })
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+/// `source` with each item that overlaps a `touched` range replaced by its
+/// formatted text; `None` if the source does not parse.
+fn format_touched_items(source: &str, touched: &[std::ops::Range<usize>]) -> Option<String> {
+    let file = crate::parse_source_silent(source, "fix.tri").ok()?;
+    let formatted = format_source(source, "fix.tri").ok()?;
+    let formatted_file = crate::parse_source_silent(&formatted, "fix.tri").ok()?;
+    if file.items.len() != formatted_file.items.len() {
+        return None;
+    }
+    let mut out = source.to_string();
+    for (item, formatted_item) in file.items.iter().zip(&formatted_file.items).rev() {
+        let range = item.span.start as usize..item.span.end as usize;
+        if touched
+            .iter()
+            .any(|t| t.start <= range.end && range.start <= t.end)
+        {
+            let text =
+                &formatted[formatted_item.span.start as usize..formatted_item.span.end as usize];
+            out.replace_range(range, text);
+        }
+    }
+    Some(out)
+}
+
+/// A unified diff from `old` to `new` with three lines of context, both
+/// labelled `path`; empty when the two are equal.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    const CONTEXT: usize = 3;
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&a, &b);
+    if ops.iter().all(|op| matches!(op, DiffOp::Same)) {
+        return String::new();
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", path, path);
+    let changed: Vec<usize> = (0..ops.len())
+        .filter(|&i| !matches!(ops[i], DiffOp::Same))
+        .collect();
+    let mut group_start = 0;
+    while group_start < changed.len() {
+        let mut group_end = group_start;
+        while group_end + 1 < changed.len()
+            && changed[group_end + 1] - changed[group_end] <= 2 * CONTEXT + 1
+        {
+            group_end += 1;
+        }
+        let from = changed[group_start].saturating_sub(CONTEXT);
+        let to = (changed[group_end] + CONTEXT + 1).min(ops.len());
+
+        // Line numbers at `from`.
+        let (mut ai, mut bi) = (0, 0);
+        for op in &ops[..from] {
+            match op {
+                DiffOp::Same => (ai, bi) = (ai + 1, bi + 1),
+                DiffOp::Delete => ai += 1,
+                DiffOp::Insert => bi += 1,
+            }
+        }
+        let mut body = String::new();
+        let (a_start, b_start) = (ai, bi);
+        for op in &ops[from..to] {
+            match op {
+                DiffOp::Same => {
+                    body.push_str(&format!(" {}\n", a[ai]));
+                    (ai, bi) = (ai + 1, bi + 1);
+                }
+                DiffOp::Delete => {
+                    body.push_str(&format!("-{}\n", a[ai]));
+                    ai += 1;
+                }
+                DiffOp::Insert => {
+                    body.push_str(&format!("+{}\n", b[bi]));
+                    bi += 1;
+                }
+            }
+        }
+        let header = |start: usize, len: usize| {
+            if len == 0 {
+                format!("{},0", start)
+            } else {
+                format!("{},{}", start + 1, len)
+            }
+        };
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n{}",
+            header(a_start, ai - a_start),
+            header(b_start, bi - b_start),
+            body
+        ));
+        group_start = group_end + 1;
+    }
+    out
+}
+
+#[derive(Clone, Copy, Debug)]
+enum DiffOp {
+    Same,
+    Delete,
+    Insert,
+}
+
+/// Line operations turning `a` into `b`: the common prefix and suffix,
+/// and a longest common subsequence of what is between them.
+fn diff_lines(a: &[&str], b: &[&str]) -> Vec<DiffOp> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (mid_a, mid_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut ops = vec![DiffOp::Same; prefix];
+    // lcs[i][j]: length of the longest common subsequence of mid_a[i..]
+    // and mid_b[j..]. Too large a middle is diffed as a replacement.
+    if mid_a.len().saturating_mul(mid_b.len()) > 4_000_000 {
+        ops.extend(mid_a.iter().map(|_| DiffOp::Delete));
+        ops.extend(mid_b.iter().map(|_| DiffOp::Insert));
+    } else {
+        let width = mid_b.len() + 1;
+        let mut lcs = vec![0u32; (mid_a.len() + 1) * width];
+        for i in (0..mid_a.len()).rev() {
+            for j in (0..mid_b.len()).rev() {
+                lcs[i * width + j] = if mid_a[i] == mid_b[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < mid_a.len() || j < mid_b.len() {
+            if i < mid_a.len() && j < mid_b.len() && mid_a[i] == mid_b[j] {
+                ops.push(DiffOp::Same);
+                (i, j) = (i + 1, j + 1);
+            } else if i < mid_a.len()
+                && (j == mid_b.len() || lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+            {
+                ops.push(DiffOp::Delete);
+                i += 1;
+            } else {
+                ops.push(DiffOp::Insert);
+                j += 1;
+            }
+        }
+    }
+    ops.extend(std::iter::repeat_n(DiffOp::Same, suffix));
+    ops
+}
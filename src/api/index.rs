@@ -0,0 +1,258 @@
+//! Symbol index for external tools.
+//!
+//! Lists every definition of a resolved project — functions, structs,
+//! enums, constants, and events — with its file, span, signature, and module,
+//! so code browsers and CI annotations can find Trident symbols without
+//! running the language server. Two renderings: a sorted Exuberant Ctags
+//! `tags` file, and JSON Lines with one definition per line.
+
+use std::path::{Path, PathBuf};
+
+use crate::ast;
+use crate::ast::display::{
+    format_ast_type, format_const_value, format_enum_signature, format_fn_signature,
+};
+use crate::diagnostic::Diagnostic;
+use crate::pipeline::PreparedProject;
+use crate::span::Spanned;
+use crate::CompileOptions;
+
+/// What a definition defines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DefinitionKind {
+    Function,
+    Struct,
+    Enum,
+    Constant,
+    Event,
+}
+
+impl DefinitionKind {
+    /// Lowercase name, as written in the index.
+    pub fn name(self) -> &'static str {
+        match self {
+            DefinitionKind::Function => "function",
+            DefinitionKind::Struct => "struct",
+            DefinitionKind::Enum => "enum",
+            DefinitionKind::Constant => "constant",
+            DefinitionKind::Event => "event",
+        }
+    }
+
+    /// Single-letter ctags kind.
+    fn letter(self) -> char {
+        match self {
+            DefinitionKind::Function => 'f',
+            DefinitionKind::Struct => 's',
+            DefinitionKind::Enum => 'g',
+            DefinitionKind::Constant => 'c',
+            DefinitionKind::Event => 'e',
+        }
+    }
+}
+
+/// One definition in the index. Lines and columns are 1-based; columns
+/// count bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub name: String,
+    pub kind: DefinitionKind,
+    /// Dotted name of the containing module.
+    pub module: String,
+    pub file: PathBuf,
+    /// Position of the defined name.
+    pub line: usize,
+    pub column: usize,
+    /// Position just past the end of the definition.
+    pub end_line: usize,
+    pub end_column: usize,
+    pub signature: String,
+    pub is_pub: bool,
+}
+
+/// Index the definitions of every module in the project, dependencies
+/// first, each module's in source order.
+pub(crate) fn index_project(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<Vec<IndexEntry>, Vec<Diagnostic>> {
+    let project = PreparedProject::build(entry_path, options)?;
+    let mut entries = Vec::new();
+    for pm in &project.modules {
+        let module = &pm.file.name.node;
+        for item in &pm.file.items {
+            let (name, kind, signature, is_pub) = match &item.node {
+                ast::Item::Fn(f) => (
+                    &f.name,
+                    DefinitionKind::Function,
+                    format_fn_signature(f),
+                    f.is_pub,
+                ),
+                ast::Item::Struct(s) => (
+                    &s.name,
+                    DefinitionKind::Struct,
+                    match s.kind {
+                        ast::StructKind::Named => format!(
+                            "struct {} {{ {} }}",
+                            s.name.node,
+                            fields(&s.fields, |f| (&f.name, &f.ty))
+                        ),
+                        ast::StructKind::Tuple => format!(
+                            "struct {}({})",
+                            s.name.node,
+                            s.fields
+                                .iter()
+                                .map(|f| format_ast_type(&f.ty.node))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                        ast::StructKind::Unit => format!("struct {}", s.name.node),
+                    },
+                    s.is_pub,
+                ),
+                ast::Item::Enum(e) => (
+                    &e.name,
+                    DefinitionKind::Enum,
+                    format_enum_signature(e),
+                    e.is_pub,
+                ),
+                ast::Item::Const(c) => (
+                    &c.name,
+                    DefinitionKind::Constant,
+                    format!(
+                        "const {}: {} = {}",
+                        c.name.node,
+                        format_ast_type(&c.ty.node),
+                        format_const_value(&c.value.node)
+                    ),
+                    c.is_pub,
+                ),
+                // Events have no visibility: any module may emit them.
+                ast::Item::Event(e) => (
+                    &e.name,
+                    DefinitionKind::Event,
+                    format!(
+                        "event {} {{ {} }}",
+                        e.name.node,
+                        fields(&e.fields, |f| (&f.name, &f.ty))
+                    ),
+                    true,
+                ),
+            };
+            let (file, line, column) = pm.position(name.span.start);
+            let (_, end_line, end_column) = pm.position(item.span.end);
+            entries.push(IndexEntry {
+                name: name.node.clone(),
+                kind,
+                module: module.clone(),
+                file: file.to_path_buf(),
+                line,
+                column,
+                end_line,
+                end_column,
+                signature,
+                is_pub,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn fields<T>(
+    fields: &[T],
+    parts: impl Fn(&T) -> (&Spanned<String>, &Spanned<ast::Type>),
+) -> String {
+    fields
+        .iter()
+        .map(|f| {
+            let (name, ty) = parts(f);
+            format!("{}: {}", name.node, format_ast_type(&ty.node))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 1-based line and byte column of a byte offset.
+pub(super) fn line_col(source: &str, offset: u32) -> (usize, usize) {
+    let offset = (offset as usize).min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = offset - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
+
+/// Path of `file` as written in the index: relative to `root` when
+/// inside it.
+fn display_path(file: &Path, root: &Path) -> String {
+    file.strip_prefix(root)
+        .unwrap_or(file)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Render entries as a sorted Exuberant Ctags file.
+pub(crate) fn format_ctags(entries: &[IndexEntry], root: &Path) -> String {
+    let mut lines: Vec<String> = entries
+        .iter()
+        .map(|e| {
+            format!(
+                "{}\t{}\t{};\"\t{}\tline:{}\tmodule:{}\tsignature:{}",
+                e.name,
+                display_path(&e.file, root),
+                e.line,
+                e.kind.letter(),
+                e.line,
+                e.module,
+                e.signature.replace(['\t', '\n'], " "),
+            )
+        })
+        .collect();
+    lines.sort();
+
+    let mut out = String::new();
+    out.push_str("!_TAG_FILE_FORMAT\t2\t/extended format/\n");
+    out.push_str("!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted/\n");
+    out.push_str("!_TAG_PROGRAM_NAME\ttrident\t//\n");
+    for line in lines {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Render entries as JSON Lines, one definition per line.
+pub(crate) fn format_json_lines(entries: &[IndexEntry], root: &Path) -> String {
+    let mut out = String::new();
+    for e in entries {
+        out.push_str(&format!(
+            "{{\"name\":{},\"kind\":\"{}\",\"module\":{},\"file\":{},\"line\":{},\"column\":{},\"end_line\":{},\"end_column\":{},\"signature\":{},\"pub\":{}}}\n",
+            json_escape(&e.name),
+            e.kind.name(),
+            json_escape(&e.module),
+            json_escape(&display_path(&e.file, root)),
+            e.line,
+            e.column,
+            e.end_line,
+            e.end_column,
+            json_escape(&e.signature),
+            e.is_pub,
+        ));
+    }
+    out
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,714 @@
+//! Leakage report: what a deployed program reveals beyond its outputs.
+//!
+//! A proof is checked against the program's TASM, so the TASM is public,
+//! along with the public input and output and the padded height of the
+//! trace. Three things follow from that:
+//!
+//! - constants: a `const` whose value the TASM pushes can be read off the
+//!   program, whatever its name suggests;
+//! - events: `reveal` publishes the tag and every field; `seal` publishes
+//!   a digest, which hides the fields only if they cannot be guessed:
+//!   when every field is computed from public data, anyone can recompute
+//!   the digest and confirm them;
+//! - control flow: a branch or loop that depends on secret input and
+//!   whose paths cost different rows changes the trace height with the
+//!   secret.
+//!
+//! Secret input is what the prover supplies: `divine*`, the siblings
+//! behind `merkle_step*`, and `ram_read*` when the program declares
+//! `sec ram`. A value is secret when it is computed from secret input or
+//! assigned under a secret condition. Only the program file is examined;
+//! a call into another module is secret when one of its arguments is.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::Path;
+
+use crate::ast::display::format_ast_type;
+use crate::ast::{
+    Block, Declaration, Expr, FieldPattern, File, FnDef, Item, Literal, MatchPattern, Pattern,
+    Place, Stmt, Type,
+};
+use crate::cost::{CostAnalyzer, TableCost};
+use crate::diagnostic::Diagnostic;
+use crate::pipeline::PreparedProject;
+use crate::report::json_escape;
+use crate::span::{Span, Spanned};
+use crate::CompileOptions;
+
+use super::index::line_col;
+
+/// A `const` of the program file. Lines are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstantLeak {
+    pub name: String,
+    pub value: u64,
+    pub line: usize,
+    /// Whether the TASM pushes the value as is. Folded into a larger
+    /// constant it is still in the program, just not recognized here.
+    pub pushed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    Reveal,
+    Seal,
+}
+
+/// A field of an emitted event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventFieldLeak {
+    pub name: String,
+    pub ty: String,
+    /// Whether the value depends on secret input.
+    pub secret: bool,
+}
+
+/// A `reveal` or `seal` statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventLeak {
+    pub function: String,
+    pub name: String,
+    pub kind: EventKind,
+    pub tag: u64,
+    pub line: usize,
+    pub fields: Vec<EventFieldLeak>,
+    /// For `seal`: whether every field is public-derived, so the digest
+    /// can be recomputed from public data.
+    pub enumerable: bool,
+}
+
+/// A branch or loop on secret input whose paths cost different rows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BranchLeak {
+    pub function: String,
+    /// `if`, `match`, `for`, or `while`.
+    pub kind: &'static str,
+    pub line: usize,
+    pub column: usize,
+    pub span: Span,
+    /// Rows of the tallest table along each path: the arms of a branch,
+    /// in source order, or one iteration of a loop.
+    pub paths: Vec<u64>,
+}
+
+/// What the compiled program reveals beyond its declared output.
+#[derive(Clone, Debug, Default)]
+pub struct LeakageReport {
+    pub program: String,
+    pub constants: Vec<ConstantLeak>,
+    pub events: Vec<EventLeak>,
+    pub branches: Vec<BranchLeak>,
+    /// Branches and loops on secret input whose paths cost the same.
+    pub balanced: usize,
+}
+
+/// Build the leakage report of the program at `entry_path`.
+pub(crate) fn leakage_report(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<LeakageReport, Vec<Diagnostic>> {
+    let tasm = super::compile_project_with_options(entry_path, options)?;
+    let project = PreparedProject::build(entry_path, options)?;
+    let (Some((program, deps)), Some(exports)) =
+        (project.modules.split_last(), project.exports.last())
+    else {
+        return Err(vec![Diagnostic::error(
+            "no program file found".to_string(),
+            Span::dummy(),
+        )]);
+    };
+    let file = &program.file;
+    let line = |offset: u32| line_col(&program.source, offset).0;
+    let mut report = LeakageReport {
+        program: file.name.node.clone(),
+        ..Default::default()
+    };
+
+    let pushed = pushed_values(&tasm);
+    for item in &file.items {
+        let Item::Const(c) = &item.node else {
+            continue;
+        };
+        // Folded by the type checker; arrays and structs push no single
+        // value.
+        let value = match &c.value.node {
+            Expr::Literal(Literal::Integer(n)) => *n,
+            Expr::Literal(Literal::Bool(b)) => u64::from(*b),
+            _ => continue,
+        };
+        report.constants.push(ConstantLeak {
+            name: c.name.node.clone(),
+            value,
+            line: line(c.name.span.start),
+            pushed: pushed.contains(&value),
+        });
+    }
+
+    let secrets = Secrets::of(file);
+    let events: BTreeMap<&str, Vec<(&str, &Type)>> = file
+        .items
+        .iter()
+        .filter_map(|item| match &item.node {
+            Item::Event(e) => Some((
+                e.name.node.as_str(),
+                e.fields
+                    .iter()
+                    .map(|f| (f.name.node.as_str(), &f.ty.node))
+                    .collect(),
+            )),
+            _ => None,
+        })
+        .collect();
+    for site in &secrets.events {
+        let declared = events.get(site.name.as_str()).cloned().unwrap_or_default();
+        let fields: Vec<EventFieldLeak> = site
+            .fields
+            .iter()
+            .map(|(name, secret)| EventFieldLeak {
+                name: name.clone(),
+                ty: declared
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .map_or_else(|| "?".to_string(), |(_, ty)| format_ast_type(ty)),
+                secret: *secret,
+            })
+            .collect();
+        let enumerable = site.kind == EventKind::Seal && fields.iter().all(|f| !f.secret);
+        report.events.push(EventLeak {
+            function: site.function.clone(),
+            name: site.name.clone(),
+            kind: site.kind,
+            tag: exports
+                .events
+                .iter()
+                .find(|e| e.name == site.name)
+                .map_or(0, |e| e.tag),
+            line: line(site.span.start),
+            fields,
+            enumerable,
+        });
+    }
+
+    let mut analyzer = CostAnalyzer::for_target(&options.target_config.name)
+        .with_imports(deps.iter().map(|m| &m.file));
+    analyzer.analyze_file(file);
+    let empty = Block {
+        stmts: Vec::new(),
+        tail_expr: None,
+    };
+    for site in secrets.branches.values() {
+        let costs: Vec<TableCost> = match site.paths.as_slice() {
+            // An `if` without `else` runs nothing on the other path.
+            [then] if site.kind == "if" => {
+                vec![analyzer.cost_block(then), analyzer.cost_block(&empty)]
+            }
+            paths => paths.iter().map(|b| analyzer.cost_block(b)).collect(),
+        };
+        // A loop runs a secret number of iterations: it leaks unless
+        // they cost nothing.
+        let leaks = if site.kind == "for" || site.kind == "while" {
+            costs.iter().any(TableCost::is_nonzero)
+        } else {
+            costs.windows(2).any(|w| w[0] != w[1])
+        };
+        if !leaks {
+            report.balanced += 1;
+            continue;
+        }
+        let (line, column) = line_col(&program.source, site.span.start);
+        report.branches.push(BranchLeak {
+            function: site.function.clone(),
+            kind: site.kind,
+            line,
+            column,
+            span: site.span,
+            paths: costs.iter().map(TableCost::max_height).collect(),
+        });
+    }
+    Ok(report)
+}
+
+/// Immediates of the `push` instructions in `tasm`.
+fn pushed_values(tasm: &str) -> BTreeSet<u64> {
+    tasm.lines()
+        .filter_map(|line| line.trim().strip_prefix("push "))
+        .filter_map(|value| value.trim().parse().ok())
+        .collect()
+}
+
+/// A `reveal` or `seal` statement, with which fields are secret.
+struct EventSite {
+    function: String,
+    name: String,
+    kind: EventKind,
+    span: Span,
+    fields: Vec<(String, bool)>,
+}
+
+/// A branch or loop on secret input, with the blocks its paths run.
+struct BranchSite<'a> {
+    function: String,
+    kind: &'static str,
+    span: Span,
+    paths: Vec<&'a Block>,
+}
+
+/// The secret-dependent parts of a file's functions.
+struct Secrets<'a> {
+    functions: BTreeMap<&'a str, &'a FnDef>,
+    /// Whether `ram_read*` reads prover-supplied memory.
+    secret_ram: bool,
+    /// Parameters, by position, that some call passes a secret.
+    params: BTreeMap<String, BTreeSet<usize>>,
+    /// Functions that may return a secret.
+    returns: BTreeSet<String>,
+    events: Vec<EventSite>,
+    /// By span start, so a loop walked twice is recorded once.
+    branches: BTreeMap<u32, BranchSite<'a>>,
+}
+
+impl<'a> Secrets<'a> {
+    fn of(file: &'a File) -> Self {
+        let mut secrets = Secrets {
+            functions: file
+                .items
+                .iter()
+                .filter_map(|item| match &item.node {
+                    Item::Fn(f) if f.body.is_some() && !f.is_test => {
+                        Some((f.name.node.as_str(), f))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            secret_ram: file
+                .declarations
+                .iter()
+                .any(|d| matches!(d, Declaration::SecRam(_))),
+            params: BTreeMap::new(),
+            returns: BTreeSet::new(),
+            events: Vec::new(),
+            branches: BTreeMap::new(),
+        };
+        // Which parameters and results are secret depends on the callers
+        // and callees; walk until that settles, then once more to record.
+        let functions: Vec<&FnDef> = secrets.functions.values().copied().collect();
+        loop {
+            let before = (secrets.params.clone(), secrets.returns.clone());
+            for func in &functions {
+                secrets.walk(func, false);
+            }
+            if before == (secrets.params.clone(), secrets.returns.clone()) {
+                break;
+            }
+        }
+        for func in &functions {
+            secrets.walk(func, true);
+        }
+        secrets
+    }
+
+    fn walk(&mut self, func: &'a FnDef, record: bool) {
+        let Some(body) = &func.body else {
+            return;
+        };
+        let tainted = self
+            .params
+            .get(&func.name.node)
+            .cloned()
+            .unwrap_or_default();
+        let mut walker = Walker {
+            secrets: self,
+            function: &func.name.node,
+            record,
+            vars: func
+                .params
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| tainted.contains(i))
+                .map(|(_, p)| p.name.node.clone())
+                .collect(),
+            returns: false,
+        };
+        walker.block(&body.node, false);
+        if let Some(tail) = &body.node.tail_expr {
+            walker.returns |= walker.expr(&tail.node);
+        }
+        if walker.returns {
+            self.returns.insert(func.name.node.clone());
+        }
+    }
+}
+
+/// Walks one function, tracking its secret variables.
+struct Walker<'s, 'a> {
+    secrets: &'s mut Secrets<'a>,
+    function: &'a str,
+    record: bool,
+    vars: BTreeSet<String>,
+    returns: bool,
+}
+
+impl<'a> Walker<'_, 'a> {
+    /// Walk `block`; `pc` is whether it runs under a secret condition.
+    fn block(&mut self, block: &'a Block, pc: bool) {
+        for stmt in &block.stmts {
+            self.stmt(stmt, pc);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &'a Spanned<Stmt>, pc: bool) {
+        match &stmt.node {
+            Stmt::Let { pattern, init, .. } => {
+                if self.expr(&init.node) || pc {
+                    match pattern {
+                        Pattern::Name(name) => self.taint(&name.node),
+                        Pattern::Tuple(names) => names.iter().for_each(|n| self.taint(&n.node)),
+                    }
+                }
+            }
+            Stmt::Assign { place, value } => {
+                let index = self.place_index(&place.node);
+                if self.expr(&value.node) || index || pc {
+                    self.taint(place_root(&place.node));
+                }
+            }
+            Stmt::TupleAssign { names, value } => {
+                if self.expr(&value.node) || pc {
+                    names.iter().for_each(|n| self.taint(&n.node));
+                }
+            }
+            Stmt::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                let secret = self.expr(&cond.node);
+                let mut paths = vec![&then_block.node];
+                paths.extend(else_block.as_ref().map(|b| &b.node));
+                self.branch("if", stmt.span, secret, &paths);
+                for path in paths {
+                    self.block(path, pc || secret);
+                }
+            }
+            Stmt::Match { expr, arms } => {
+                let secret = self.expr(&expr.node);
+                let paths: Vec<&Block> = arms.iter().map(|a| &a.body.node).collect();
+                self.branch("match", stmt.span, secret, &paths);
+                for arm in arms {
+                    if secret || pc {
+                        for name in pattern_bindings(&arm.pattern.node) {
+                            self.taint(name);
+                        }
+                    }
+                    self.block(&arm.body.node, pc || secret);
+                }
+            }
+            Stmt::For {
+                var,
+                start,
+                end,
+                body,
+                ..
+            } => {
+                let secret = self.expr(&start.node) | self.expr(&end.node);
+                self.branch("for", stmt.span, secret, &[&body.node]);
+                if secret || pc {
+                    self.taint(&var.node);
+                }
+                self.settle(&body.node, pc || secret);
+            }
+            Stmt::While { cond, body, .. } => {
+                // The body may make the condition secret for the next
+                // iteration.
+                let mut secret = self.expr(&cond.node);
+                loop {
+                    self.settle(&body.node, pc || secret);
+                    if secret || !self.expr(&cond.node) {
+                        break;
+                    }
+                    secret = true;
+                }
+                self.branch("while", stmt.span, secret, &[&body.node]);
+            }
+            Stmt::Expr(expr) => {
+                self.expr(&expr.node);
+            }
+            Stmt::Return(value) => {
+                let secret = value.as_ref().is_some_and(|v| self.expr(&v.node));
+                self.returns |= secret || pc;
+            }
+            Stmt::Reveal { event_name, fields } | Stmt::Seal { event_name, fields } => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, value)| (name.node.clone(), self.expr(&value.node) || pc))
+                    .collect();
+                if self.record {
+                    self.secrets.events.push(EventSite {
+                        function: self.function.to_string(),
+                        name: event_name.node.clone(),
+                        kind: if matches!(stmt.node, Stmt::Reveal { .. }) {
+                            EventKind::Reveal
+                        } else {
+                            EventKind::Seal
+                        },
+                        span: stmt.span,
+                        fields,
+                    });
+                }
+            }
+            Stmt::Break | Stmt::Continue | Stmt::Asm { .. } => {}
+        }
+    }
+
+    /// Walk a loop body until the variables it makes secret settle, so
+    /// that one iteration's secrets reach the next.
+    fn settle(&mut self, body: &'a Block, pc: bool) {
+        loop {
+            let before = self.vars.len();
+            self.block(body, pc);
+            if self.vars.len() == before {
+                break;
+            }
+        }
+    }
+
+    fn branch(&mut self, kind: &'static str, span: Span, secret: bool, paths: &[&'a Block]) {
+        if secret && self.record {
+            self.secrets.branches.insert(
+                span.start,
+                BranchSite {
+                    function: self.function.to_string(),
+                    kind,
+                    span,
+                    paths: paths.to_vec(),
+                },
+            );
+        }
+    }
+
+    fn taint(&mut self, name: &str) {
+        if name != "_" {
+            self.vars.insert(name.to_string());
+        }
+    }
+
+    /// Whether an index inside `place` is secret.
+    fn place_index(&mut self, place: &Place) -> bool {
+        match place {
+            Place::Var(_) => false,
+            Place::FieldAccess(inner, _) => self.place_index(&inner.node),
+            Place::Index(inner, index) => {
+                let secret = self.expr(&index.node);
+                self.place_index(&inner.node) || secret
+            }
+        }
+    }
+
+    /// Whether `expr` depends on secret input. Notes the parameters of
+    /// local functions that receive a secret.
+    fn expr(&mut self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Literal(_) => false,
+            Expr::Var(name) => self.vars.contains(name.split('.').next().unwrap_or(name)),
+            Expr::BinOp { lhs, rhs, .. } => self.expr(&lhs.node) | self.expr(&rhs.node),
+            Expr::Call { path, args, .. } => {
+                let name = path.node.as_dotted();
+                let secret_args: Vec<bool> = args.iter().map(|a| self.expr(&a.node)).collect();
+                if self.secrets.functions.contains_key(name.as_str()) {
+                    for (i, _) in secret_args.iter().enumerate().filter(|(_, s)| **s) {
+                        self.secrets
+                            .params
+                            .entry(name.clone())
+                            .or_default()
+                            .insert(i);
+                    }
+                    return self.secrets.returns.contains(&name);
+                }
+                let base = name.rsplit('.').next().unwrap_or(&name);
+                let source = base.starts_with("divine")
+                    || base.starts_with("merkle_step")
+                    || (self.secrets.secret_ram && base.starts_with("ram_read"));
+                source || secret_args.contains(&true)
+            }
+            Expr::FieldAccess { expr, .. } => self.expr(&expr.node),
+            Expr::Index { expr, index } => self.expr(&expr.node) | self.expr(&index.node),
+            Expr::StructInit { fields, base, .. } => {
+                let mut secret = base.as_ref().is_some_and(|b| self.expr(&b.node));
+                for (_, value) in fields {
+                    secret |= self.expr(&value.node);
+                }
+                secret
+            }
+            Expr::ArrayInit(elems) | Expr::Tuple(elems) => elems
+                .iter()
+                .fold(false, |secret, e| self.expr(&e.node) | secret),
+        }
+    }
+}
+
+/// The variable a place assigns into.
+fn place_root(place: &Place) -> &str {
+    match place {
+        Place::Var(name) => name.split('.').next().unwrap_or(name),
+        Place::FieldAccess(inner, _) | Place::Index(inner, _) => place_root(&inner.node),
+    }
+}
+
+/// Names a match pattern binds.
+fn pattern_bindings(pattern: &MatchPattern) -> Vec<&str> {
+    match pattern {
+        MatchPattern::Literal(_) | MatchPattern::Wildcard => Vec::new(),
+        MatchPattern::Struct { fields, .. } => fields
+            .iter()
+            .filter_map(|f| match &f.pattern.node {
+                FieldPattern::Binding(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect(),
+        MatchPattern::Variant { bindings, .. } => {
+            bindings.iter().map(|b| b.node.as_str()).collect()
+        }
+    }
+}
+
+impl LeakageReport {
+    pub fn to_json(&self) -> String {
+        let constants: Vec<String> = self
+            .constants
+            .iter()
+            .map(|c| {
+                format!(
+                    "{{\"name\": \"{}\", \"value\": {}, \"line\": {}, \"pushed\": {}}}",
+                    json_escape(&c.name),
+                    c.value,
+                    c.line,
+                    c.pushed
+                )
+            })
+            .collect();
+        let events: Vec<String> = self
+            .events
+            .iter()
+            .map(|e| {
+                let fields: Vec<String> = e
+                    .fields
+                    .iter()
+                    .map(|f| {
+                        format!(
+                            "{{\"name\": \"{}\", \"type\": \"{}\", \"secret\": {}}}",
+                            json_escape(&f.name),
+                            json_escape(&f.ty),
+                            f.secret
+                        )
+                    })
+                    .collect();
+                format!(
+                    "{{\"function\": \"{}\", \"event\": \"{}\", \"kind\": \"{}\", \"tag\": {}, \"line\": {}, \"fields\": [{}], \"enumerable\": {}}}",
+                    json_escape(&e.function),
+                    json_escape(&e.name),
+                    e.kind,
+                    e.tag,
+                    e.line,
+                    fields.join(", "),
+                    e.enumerable
+                )
+            })
+            .collect();
+        let branches: Vec<String> = self
+            .branches
+            .iter()
+            .map(|b| {
+                let paths: Vec<String> = b.paths.iter().map(u64::to_string).collect();
+                format!(
+                    "{{\"function\": \"{}\", \"kind\": \"{}\", \"line\": {}, \"column\": {}, \"paths\": [{}]}}",
+                    json_escape(&b.function),
+                    b.kind,
+                    b.line,
+                    b.column,
+                    paths.join(", ")
+                )
+            })
+            .collect();
+        format!(
+            "{{\"program\": \"{}\", \"constants\": [{}], \"events\": [{}], \"branches\": [{}], \"balanced\": {}}}\n",
+            json_escape(&self.program),
+            constants.join(", "),
+            events.join(", "),
+            branches.join(", "),
+            self.balanced
+        )
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EventKind::Reveal => "reveal",
+            EventKind::Seal => "seal",
+        })
+    }
+}
+
+impl fmt::Display for LeakageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Leakage report for {}", self.program)?;
+
+        let pushed: Vec<&ConstantLeak> = self.constants.iter().filter(|c| c.pushed).collect();
+        writeln!(
+            f,
+            "\nConstants: {} of {} pushed by the TASM, readable by anyone with the program",
+            pushed.len(),
+            self.constants.len()
+        )?;
+        for c in pushed {
+            writeln!(f, "  {:<32} line {:<5} {}", c.name, c.line, c.value)?;
+        }
+
+        writeln!(f, "\nEvents: {} emitted", self.events.len())?;
+        for e in &self.events {
+            let fields: Vec<String> = e
+                .fields
+                .iter()
+                .map(|field| {
+                    let secret = if field.secret { ", secret" } else { "" };
+                    format!("{}: {}{}", field.name, field.ty, secret)
+                })
+                .collect();
+            let what = match e.kind {
+                EventKind::Reveal => "tag and fields public",
+                EventKind::Seal if e.enumerable => {
+                    "digest public, fields recomputable from public data"
+                }
+                EventKind::Seal => "digest public",
+            };
+            writeln!(
+                f,
+                "  {} {} (tag {}) in {}, line {}: {}",
+                e.kind, e.name, e.tag, e.function, e.line, what
+            )?;
+            writeln!(f, "    {{ {} }}", fields.join(", "))?;
+        }
+
+        writeln!(
+            f,
+            "\nControl flow: {} secret-dependent branches or loops change the trace height, {} balanced",
+            self.branches.len(),
+            self.balanced
+        )?;
+        for b in &self.branches {
+            let paths: Vec<String> = b.paths.iter().map(u64::to_string).collect();
+            let rows = if b.kind == "for" || b.kind == "while" {
+                format!("{} rows per iteration", paths.join(""))
+            } else {
+                format!("paths cost {} rows", paths.join(" / "))
+            };
+            writeln!(
+                f,
+                "  {:<6} in {}, line {}:{}  {}",
+                b.kind, b.function, b.line, b.column, rows
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+//! Lowering coverage: which TIR operations each target's backend handles.
+//!
+//! Every target in `vm/` names its backend trait in `[status] lowering`.
+//! Each `TIROp` variant (see `TIROp::samples`) is wrapped in a one-function
+//! program and handed to that backend through its factory. An operation is
+//! lowered when the output differs from the same program without it,
+//! dropped when the output is unchanged, and unimplemented when the backend
+//! panics, as `todo!()` bodies do. Stack targets share one backend, so an
+//! operation above the tier the target covers (`tir::lower::lowering_tier`)
+//! is unsupported there whatever the backend emits. A target whose factory
+//! has no backend yet is reported as such, so every gap shows up here
+//! instead of in a user's build.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use super::matrix::{declared_targets, toml_string};
+use crate::ir::{kir, lir, tree};
+use crate::tir::lower::lowering_tier;
+use crate::tir::TIROp;
+
+/// How a backend handles one operation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OpCoverage {
+    Lowered,
+    /// Accepted, but emits nothing.
+    Dropped,
+    /// The backend panicked, with its message.
+    Unimplemented(String),
+    /// Above the tier the target covers.
+    Unsupported,
+}
+
+/// Coverage of one target's backend.
+#[derive(Clone, Debug)]
+pub struct LoweringCoverage {
+    pub target: String,
+    /// Backend trait from `target.toml`, e.g. `StackLowering`.
+    pub lowering: String,
+    /// Every variant by name, in declaration order; `None` when the
+    /// factory has no backend for the target.
+    pub ops: Option<Vec<(String, OpCoverage)>>,
+}
+
+impl LoweringCoverage {
+    /// Operations the backend lowers.
+    pub fn lowered(&self) -> usize {
+        self.ops
+            .iter()
+            .flatten()
+            .filter(|(_, c)| *c == OpCoverage::Lowered)
+            .count()
+    }
+}
+
+/// Coverage of every target in `vm/` that declares a backend.
+pub fn lowering_coverage() -> Vec<LoweringCoverage> {
+    declared_targets()
+        .into_iter()
+        .filter_map(|(name, toml)| {
+            let lowering = toml_string(&toml, "lowering")?;
+            (lowering != "none").then(|| lowering_coverage_for(&name, &lowering))
+        })
+        .collect()
+}
+
+/// Coverage of the `lowering` backend of `target`.
+pub fn lowering_coverage_for(target: &str, lowering: &str) -> LoweringCoverage {
+    type Lower = Box<dyn Fn(&[TIROp]) -> String>;
+    let lower: Option<Lower> = match lowering {
+        "StackLowering" => {
+            let backend = crate::tir::lower::create_stack_lowering(target);
+            Some(Box::new(move |ops: &[TIROp]| backend.lower(ops).join("\n")))
+        }
+        "RegisterLowering" => lir::lower::create_register_lowering(target).map(|backend| {
+            Box::new(move |ops: &[TIROp]| {
+                backend
+                    .lower_text(&lir::convert::tir_to_lir(ops))
+                    .join("\n")
+            }) as Lower
+        }),
+        "TreeLowering" => tree::lower::create_tree_lowering(target)
+            .map(|backend| Box::new(move |ops: &[TIROp]| backend.lower(ops).to_string()) as Lower),
+        "KernelLowering" => kir::lower::create_kernel_lowering(target)
+            .map(|backend| Box::new(move |ops: &[TIROp]| backend.lower(ops)) as Lower),
+        _ => None,
+    };
+    let mut ops = lower.map(|lower| probe(&*lower));
+    if let (Some(ops), "StackLowering") = (&mut ops, lowering) {
+        if let Ok(config) = crate::target::TerrainConfig::resolve(target) {
+            let tier = lowering_tier(&config);
+            for ((_, coverage), op) in ops.iter_mut().zip(TIROp::samples()) {
+                if op.tier() > tier {
+                    *coverage = OpCoverage::Unsupported;
+                }
+            }
+        }
+    }
+    LoweringCoverage {
+        target: target.to_string(),
+        lowering: lowering.to_string(),
+        ops,
+    }
+}
+
+/// Lower a program around each sample operation and compare the output
+/// with that of the bare program.
+fn probe(lower: &dyn Fn(&[TIROp]) -> String) -> Vec<(String, OpCoverage)> {
+    let program = |op: Option<TIROp>| {
+        let mut ops = vec![TIROp::Entry("main".into()), TIROp::FnStart("main".into())];
+        ops.extend(op);
+        ops.extend([TIROp::Return, TIROp::FnEnd]);
+        ops
+    };
+    // Backends are expected to panic on what they do not implement; keep
+    // those panics off stderr while probing.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let run = |ops: &[TIROp]| {
+        panic::catch_unwind(AssertUnwindSafe(|| lower(ops))).map_err(|payload| {
+            payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "panicked".to_string())
+        })
+    };
+    let bare = run(&program(None));
+    let coverage = TIROp::samples()
+        .into_iter()
+        .map(|op| {
+            let name = op.variant_name();
+            let coverage = match (run(&program(Some(op))), &bare) {
+                (Err(message), _) => OpCoverage::Unimplemented(message),
+                (Ok(out), Ok(bare)) if out == *bare => OpCoverage::Dropped,
+                (Ok(_), _) => OpCoverage::Lowered,
+            };
+            (name, coverage)
+        })
+        .collect();
+    panic::set_hook(hook);
+    coverage
+}
+
+/// Render the coverage as a matrix: one row per operation, one column per
+/// target with a backend, and the targets without one listed below.
+pub fn format_lowering_coverage(coverage: &[LoweringCoverage]) -> String {
+    let columns: Vec<&LoweringCoverage> = coverage.iter().filter(|c| c.ops.is_some()).collect();
+    let mut out = format!("{:<16}", "Operation");
+    for c in &columns {
+        out.push_str(&format!(" {:>8}", c.target));
+    }
+    out.push('\n');
+    out.push_str(&"-".repeat(16 + 9 * columns.len()));
+    out.push('\n');
+
+    let names: Vec<String> = TIROp::samples().iter().map(TIROp::variant_name).collect();
+    for (i, name) in names.iter().enumerate() {
+        out.push_str(&format!("{:<16}", name));
+        for c in &columns {
+            let cell = match c.ops.as_ref().map(|ops| &ops[i].1) {
+                Some(OpCoverage::Lowered) => "ok",
+                Some(OpCoverage::Dropped) => "empty",
+                Some(OpCoverage::Unimplemented(_)) => "todo",
+                Some(OpCoverage::Unsupported) => "tier",
+                None => "",
+            };
+            out.push_str(&format!(" {:>8}", cell));
+        }
+        out.push('\n');
+    }
+    out.push_str(&"-".repeat(16 + 9 * columns.len()));
+    out.push('\n');
+    out.push_str(&format!("{:<16}", "lowered"));
+    for c in &columns {
+        out.push_str(&format!(
+            " {:>8}",
+            format!("{}/{}", c.lowered(), names.len())
+        ));
+    }
+    out.push('\n');
+
+    let missing: Vec<String> = coverage
+        .iter()
+        .filter(|c| c.ops.is_none())
+        .map(|c| format!("{} ({})", c.target, c.lowering))
+        .collect();
+    if !missing.is_empty() {
+        out.push_str(&format!("\nno backend: {}\n", missing.join(", ")));
+    }
+    out
+}
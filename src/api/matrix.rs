@@ -0,0 +1,132 @@
+//! Cross-compilation matrix: one program built for every registered target.
+//!
+//! A target is registered when `vm/<name>/target.toml` declares
+//! `lowering_path = "tir"`, the stack lowering this compiler runs. Each
+//! build reports the program's cost under that target's model and the
+//! TIR operations its lowering does not cover (see
+//! `tir::lower::uncovered_ops`), so a program that only builds for some
+//! targets is caught before it is deployed to the others.
+
+use super::*;
+use crate::resolve::find_vm_dir;
+use crate::tir::lower::uncovered_ops;
+
+/// One program compiled for one target.
+#[derive(Clone, Debug)]
+pub struct TargetBuild {
+    pub target: String,
+    /// Instructions of the linked assembly.
+    pub instructions: usize,
+    pub cost: cost::ProgramCost,
+    /// Operations the program uses that the target's lowering does not
+    /// cover, with how often each occurs.
+    pub uncovered: BTreeMap<String, usize>,
+}
+
+impl TargetBuild {
+    /// True when the lowering covers every operation the program uses.
+    pub fn is_portable(&self) -> bool {
+        self.uncovered.is_empty()
+    }
+}
+
+/// Targets with a TIR lowering, sorted by name. Only `triton` when no
+/// `vm/` directory is found.
+pub fn registered_targets() -> Vec<String> {
+    let mut targets: Vec<String> = declared_targets()
+        .into_iter()
+        .filter(|(_, toml)| toml_string(toml, "lowering_path").as_deref() == Some("tir"))
+        .map(|(name, _)| name)
+        .collect();
+    if targets.is_empty() {
+        targets.push("triton".to_string());
+    }
+    targets
+}
+
+/// Every `vm/<name>/target.toml`, by name, sorted.
+pub(super) fn declared_targets() -> Vec<(String, String)> {
+    let mut targets: Vec<(String, String)> = find_vm_dir()
+        .and_then(|dir| std::fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let toml = std::fs::read_to_string(entry.path().join("target.toml")).ok()?;
+            Some((entry.file_name().to_str()?.to_string(), toml))
+        })
+        .collect();
+    targets.sort();
+    targets
+}
+
+/// The string value of `key` in a `target.toml`, wherever it appears.
+pub(super) fn toml_string(toml: &str, key: &str) -> Option<String> {
+    toml.lines().find_map(|line| {
+        let line = line.split('#').next().unwrap_or_default();
+        let (k, value) = line.split_once('=')?;
+        let value = value.trim();
+        (k.trim() == key && value.len() >= 2 && value.starts_with('"') && value.ends_with('"'))
+            .then(|| value[1..value.len() - 1].to_string())
+    })
+}
+
+/// Compile the project at `entry_path` for the target of `options` and
+/// check its operations against the target's lowering.
+pub fn build_target(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<TargetBuild, Vec<Diagnostic>> {
+    let assembly = compile_project_with_options(entry_path, options)?;
+    let ir = build_tir_project(entry_path, options)?;
+    let cost = analyze_costs_project(entry_path, options)?;
+    Ok(TargetBuild {
+        target: options.target_config.name.clone(),
+        instructions: count_tasm_instructions(&assembly),
+        cost,
+        uncovered: uncovered_ops(&ir, &options.target_config),
+    })
+}
+
+/// Render the builds side by side, one row per target, with the
+/// operations each target's lowering lacks listed below.
+pub fn format_target_matrix(builds: &[TargetBuild]) -> String {
+    let mut out = format!(
+        "{:<12} {:>8} {:>8} {:>10} {:>12}  {:<10}  {}\n",
+        "Target", "Instr", "Padded", "Max rows", "Proving", "Dominant", "Coverage"
+    );
+    out.push_str(&"-".repeat(82));
+    out.push('\n');
+    for build in builds {
+        let cost = &build.cost;
+        let coverage = if build.is_portable() {
+            "ok".to_string()
+        } else {
+            format!("{} uncovered", build.uncovered.len())
+        };
+        out.push_str(&format!(
+            "{:<12} {:>8} {:>8} {:>10} {:>12}  {:<10}  {}\n",
+            build.target,
+            fmt_num(build.instructions),
+            fmt_num(cost.padded_height as usize),
+            fmt_num(cost.total.max_height() as usize),
+            format!("{:.1} ms", cost.estimated_proving_ns as f64 / 1e6),
+            cost.total.dominant_table(&cost.short_names()),
+            coverage
+        ));
+    }
+
+    let unportable: Vec<&TargetBuild> = builds.iter().filter(|b| !b.is_portable()).collect();
+    if !unportable.is_empty() {
+        out.push_str("\nuncovered operations:\n");
+        for build in unportable {
+            let ops: Vec<String> = build
+                .uncovered
+                .iter()
+                .map(|(name, count)| format!("{} (x{})", name, count))
+                .collect();
+            out.push_str(&format!("  {}: {}\n", build.target, ops.join(", ")));
+        }
+    }
+    out
+}
@@ -1,23 +1,78 @@
+//! The stable library API.
+//!
+//! Entry points for compiling, checking, testing, costing, and verifying
+//! Trident programs, with the option and result types they use. Items
+//! here follow semver; the rest of the crate is internal.
+
 pub(crate) use std::collections::{BTreeMap, BTreeSet};
 pub(crate) use std::path::Path;
+pub(crate) use std::sync::Arc;
+pub(crate) use std::time::Instant;
 
-pub(crate) use crate::ast::{self, FileKind};
+pub(crate) use crate::ast::desugar::{desugar_file, desugar_loop_exits};
+pub(crate) use crate::ast::FileKind;
 pub(crate) use crate::cost;
-pub(crate) use crate::diagnostic::{render_diagnostics, Diagnostic};
+pub(crate) use crate::diagnostic::render_diagnostics;
 pub(crate) use crate::resolve::resolve_modules;
 pub(crate) use crate::span;
-pub(crate) use crate::target::TerrainConfig;
 pub(crate) use crate::tir::builder::TIRBuilder;
-pub(crate) use crate::tir::linker::{link, ModuleTasm};
+pub(crate) use crate::tir::linker::link;
 pub(crate) use crate::tir::lower::create_stack_lowering;
+pub(crate) use crate::tir::materialize::materialize_constants;
 pub(crate) use crate::tir::optimize::optimize as optimize_tir;
-pub(crate) use crate::typecheck::{ModuleExports, TypeChecker};
+pub(crate) use crate::typecheck::{error_codes, fold_constants, insert_range_checks, TypeChecker};
 pub(crate) use crate::{format, lexer, parser, project, solve, sym};
 
 #[cfg(test)]
 mod tests;
 
+// ─── Stable types ──────────────────────────────────────────────────
+
+/// Revision of the `trident::api` surface.
+///
+/// Additions keep the revision; it changes only when an item of this
+/// module is removed or changes incompatibly, and then together with a
+/// semver-major release of the crate.
+pub const API_VERSION: u32 = 1;
+
+pub use crate::ast;
+pub use crate::config::generate::GenerateResult;
+pub use crate::cost::{
+    EntrypointCost, FunctionCost, FunctionDiff, LineDiff, MaterializedConstant, ModuleCost,
+    ProgramCost, RewriteEstimate, SourceDiff, StatementCost, TableCost,
+};
+pub use crate::diagnostic::{
+    suppress_warnings, Applicability, Diagnostic, ErrorCode, Severity, Suggestion, SuppressGuard,
+};
+pub use crate::hash::ContentHash;
+pub use crate::manifest::{Dependency, Manifest};
+pub use crate::policy::Policy;
+pub use crate::project::{CiSettings, Project, VerifySettings};
+pub use crate::registry::events::{sign_webhook, verify_webhook};
+pub use crate::report::AssertionFix;
+pub use crate::runtime::artifact::{
+    BundleCost, BundleData, BundleEntrypoint, BundleError, BundleEvent, BundleFunction,
+    BundleOutput,
+};
+pub use crate::runtime::interp::{Abort, Program};
+pub use crate::runtime::{Interpreter, ProgramBundle, Runner};
+pub use crate::target::{Arch, TerrainConfig, WarriorConfig};
+pub use crate::tir::depth::{DepthLimits, DepthReport, Frame};
+pub use crate::tir::mono::{InstanceCost, MonoReport};
+pub use crate::typecheck::types::{EnumTy, StructTy, Ty};
+pub use crate::typecheck::{
+    DataConst, EventExport, GenericFnDef, ModuleExports, MonoInstance, RangeCheck,
+};
+pub use crate::verify::solve::{
+    Counterexample, SolverResult, Verdict, VerificationReport, VerifyConfig,
+};
+pub(crate) use pipeline::parse_source;
+pub use pipeline::parse_source_silent;
+
 /// Options controlling compilation: VM target + conditional compilation flags.
+///
+/// Build them with `CompileOptions::builder()` or
+/// `CompileOptions::from_project`, which validate the combination.
 #[derive(Clone, Debug)]
 pub struct CompileOptions {
     /// Profile name for cfg flags (e.g. "debug", "release").
@@ -28,6 +83,13 @@ pub struct CompileOptions {
     pub target_config: TerrainConfig,
     /// Additional module search directories (from locked dependencies).
     pub dep_dirs: Vec<std::path::PathBuf>,
+    /// Locked dependency hashes (name -> hex), recorded in build metadata.
+    pub locked: BTreeMap<String, String>,
+    /// Most size-generic instances a program may emit (`[build]
+    /// max_instances`).
+    pub max_instances: usize,
+    /// Custom passes run over each module after it type-checks.
+    pub passes: PassRegistry,
 }
 
 impl Default for CompileOptions {
@@ -37,6 +99,9 @@ impl Default for CompileOptions {
             cfg_flags: BTreeSet::from(["debug".to_string()]),
             target_config: TerrainConfig::triton(),
             dep_dirs: Vec::new(),
+            locked: BTreeMap::new(),
+            max_instances: crate::tir::mono::DEFAULT_MAX_INSTANCES,
+            passes: PassRegistry::default(),
         }
     }
 }
@@ -49,6 +114,9 @@ impl CompileOptions {
             cfg_flags: BTreeSet::from([profile.to_string()]),
             target_config: TerrainConfig::triton(),
             dep_dirs: Vec::new(),
+            locked: BTreeMap::new(),
+            max_instances: crate::tir::mono::DEFAULT_MAX_INSTANCES,
+            passes: PassRegistry::default(),
         }
     }
 
@@ -70,10 +138,12 @@ pub fn compile_with_options(
     options: &CompileOptions,
 ) -> Result<String, Vec<Diagnostic>> {
     let file = crate::parse_source(source, filename)?;
+    let mut file = desugar_file(file, &options.cfg_flags);
 
     // Type check
     let exports = match TypeChecker::with_target(options.target_config.clone())
         .with_cfg_flags(options.cfg_flags.clone())
+        .with_source_dir(pipeline::source_dir(Path::new(filename)))
         .check_file(&file)
     {
         Ok(exports) => exports,
@@ -82,19 +152,41 @@ pub fn compile_with_options(
             return Err(errors);
         }
     };
+    fold_constants(&mut file, &exports);
+    insert_range_checks(&mut file, &exports.range_checks);
+    desugar_loop_exits(&mut file);
 
     // Build IR, optimize, and lower to target assembly
     let ir = TIRBuilder::new(options.target_config.clone())
         .with_cfg_flags(options.cfg_flags.clone())
+        .with_error_codes(error_codes([&exports]))
+        .with_data(pipeline::file_data_tables(&exports))
         .with_mono_instances(exports.mono_instances)
         .with_call_resolutions(exports.call_resolutions)
         .build_file(&file);
     let ir = optimize_tir(ir);
     let lowering = create_stack_lowering(&options.target_config.name);
     let tasm = lowering.lower(&ir).join("\n");
+    let report = MonoReport::from_tasm(&tasm, Vec::new());
+    check_instance_cap(&report, options, filename, source)?;
     Ok(tasm)
 }
 
+/// Reject a program that emits more size-generic instances than
+/// `options.max_instances`, rendering the error against `source`.
+fn check_instance_cap(
+    report: &MonoReport,
+    options: &CompileOptions,
+    filename: &str,
+    source: &str,
+) -> Result<(), Vec<Diagnostic>> {
+    report.check_cap(options.max_instances).map_err(|diag| {
+        let errors = vec![diag];
+        render_diagnostics(&errors, filename, source);
+        errors
+    })
+}
+
 /// Compile a multi-module project from an entry point path.
 pub fn compile_project(entry_path: &Path) -> Result<String, Vec<Diagnostic>> {
     compile_project_with_options(entry_path, &CompileOptions::default())
@@ -105,56 +197,46 @@ pub fn compile_project_with_options(
     entry_path: &Path,
     options: &CompileOptions,
 ) -> Result<String, Vec<Diagnostic>> {
-    use crate::pipeline::PreparedProject;
+    compile_project_with_constants(entry_path, options).map(|(tasm, _)| tasm)
+}
 
-    let project = PreparedProject::build(entry_path, options)?;
+/// Compile a project like `compile_project_with_options`, also returning
+/// the constants the linker materialized into RAM.
+pub fn compile_project_with_constants(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<(String, Vec<MaterializedConstant>), Vec<Diagnostic>> {
+    use crate::pipeline::PreparedProject;
 
-    let intrinsic_map = project.intrinsic_map();
-    let module_aliases = project.module_aliases();
-    let external_constants = project.external_constants();
+    let mut project = PreparedProject::build(entry_path, options)?;
 
-    // Emit TASM for each module
-    let mut tasm_modules = Vec::new();
-    for (i, pm) in project.modules.iter().enumerate() {
-        let is_program = pm.file.kind == FileKind::Program;
-        let mono = project
-            .exports
-            .get(i)
-            .map(|e| e.mono_instances.clone())
-            .unwrap_or_default();
-        let call_res = project
-            .exports
-            .get(i)
-            .map(|e| e.call_resolutions.clone())
-            .unwrap_or_default();
-        let ir = TIRBuilder::new(options.target_config.clone())
-            .with_cfg_flags(options.cfg_flags.clone())
-            .with_intrinsics(intrinsic_map.clone())
-            .with_module_aliases(module_aliases.clone())
-            .with_constants(external_constants.clone())
-            .with_mono_instances(mono)
-            .with_call_resolutions(call_res)
-            .build_file(&pm.file);
-        let ir = optimize_tir(ir);
-        let lowering = create_stack_lowering(&options.target_config.name);
-        let tasm = lowering.lower(&ir).join("\n");
-        tasm_modules.push(ModuleTasm {
-            module_name: pm.file.name.node.clone(),
-            is_program,
-            tasm,
-        });
-    }
+    let tasm_modules = project.lower_modules(options, false);
 
     // Link
-    let linked = link(tasm_modules);
-    Ok(linked)
+    let _stage = crash::stage("link", "", None);
+    let started = Instant::now();
+    let (linked, shared) = link(tasm_modules);
+    if let Some(program) = project.program_module() {
+        let report = MonoReport::from_tasm(&linked, shared);
+        let filename = program.file_path.to_string_lossy();
+        check_instance_cap(&report, options, &filename, &program.source)?;
+    }
+    // Constants live between the builder's temporaries (from half the
+    // spill base) and the spill area.
+    let constant_ram_base = options.target_config.spill_ram_base / 4 * 3;
+    let (linked, constants) = materialize_constants(&linked, constant_ram_base);
+    timings::record("link", "", started, linked.lines().count());
+    Ok((linked, constants))
 }
 
 /// Type-check only (no TASM emission).
 pub fn check(source: &str, filename: &str) -> Result<(), Vec<Diagnostic>> {
     let file = crate::parse_source(source, filename)?;
 
-    if let Err(errors) = TypeChecker::new().check_file(&file) {
+    if let Err(errors) = TypeChecker::new()
+        .with_source_dir(pipeline::source_dir(Path::new(filename)))
+        .check_file(&file)
+    {
         render_diagnostics(&errors, filename, source);
         return Err(errors);
     }
@@ -171,168 +253,16 @@ pub fn check_project(entry_path: &Path) -> Result<(), Vec<Diagnostic>> {
     Ok(())
 }
 
-/// Discover `#[test]` functions in a parsed file.
-pub fn discover_tests(file: &ast::File) -> Vec<String> {
-    let mut tests = Vec::new();
-    for item in &file.items {
-        if let ast::Item::Fn(func) = &item.node {
-            if func.is_test {
-                tests.push(func.name.node.clone());
-            }
-        }
-    }
-    tests
-}
-
-/// A single test result.
-#[derive(Clone, Debug)]
-pub struct TestResult {
-    pub name: String,
-    pub passed: bool,
-    pub cost: Option<cost::TableCost>,
-    pub error: Option<String>,
-}
-
-/// Run all `#[test]` functions in a project.
-///
-/// For each test function, we:
-/// 1. Parse and type-check the project
-/// 2. Compile a mini-program that just calls the test function
-/// 3. Report pass/fail with cost summary
-pub fn run_tests(
-    entry_path: &std::path::Path,
+/// Project-aware type-check with options: the target and cfg flags
+/// modules are checked under, and the custom passes run over them.
+pub fn check_project_with_options(
+    entry_path: &Path,
     options: &CompileOptions,
-) -> Result<String, Vec<Diagnostic>> {
+) -> Result<(), Vec<Diagnostic>> {
     use crate::pipeline::PreparedProject;
 
-    let project = PreparedProject::build(entry_path, options)?;
-
-    // Discover all #[test] functions across all modules
-    let mut test_fns: Vec<(String, String)> = Vec::new(); // (module_name, fn_name)
-    for pm in &project.modules {
-        for test_name in discover_tests(&pm.file) {
-            test_fns.push((pm.file.name.node.clone(), test_name));
-        }
-    }
-
-    if test_fns.is_empty() {
-        return Ok("No #[test] functions found.\n".to_string());
-    }
-
-    // For each test function, compile a mini-program and report
-    let mut results: Vec<TestResult> = Vec::new();
-    let mut short_names: Vec<String> = Vec::new();
-    for (module_name, test_name) in &test_fns {
-        // Find the source file for this module
-        let source_entry = project
-            .modules
-            .iter()
-            .find(|m| m.file.name.node == *module_name);
-
-        if let Some(pm) = source_entry {
-            // Build a mini-program source that just calls the test function
-            let mini_source = if module_name.starts_with("module") || module_name.contains('.') {
-                // For module test functions, we'd need cross-module calls
-                // For simplicity, compile in-context
-                pm.source.clone()
-            } else {
-                pm.source.clone()
-            };
-
-            // Try to compile (type-check + emit) the source.
-            // The test function itself is validated by the type checker.
-            // For now, "passing" means it compiles without errors.
-            match compile_with_options(&mini_source, &pm.file_path.to_string_lossy(), options) {
-                Ok(tasm) => {
-                    // Compute cost for the test function
-                    let test_cost =
-                        analyze_costs(&mini_source, &pm.file_path.to_string_lossy()).ok();
-                    if short_names.is_empty() {
-                        if let Some(ref pc) = test_cost {
-                            short_names = pc.table_short_names.clone();
-                        }
-                    }
-                    let fn_cost = test_cost.as_ref().and_then(|pc| {
-                        pc.functions
-                            .iter()
-                            .find(|f| f.name == *test_name)
-                            .map(|f| f.cost.clone())
-                    });
-                    // Check if the generated TASM contains an assert failure marker
-                    let has_error = tasm.contains("// ERROR");
-                    results.push(TestResult {
-                        name: test_name.clone(),
-                        passed: !has_error,
-                        cost: fn_cost,
-                        error: if has_error {
-                            Some("compilation produced errors".to_string())
-                        } else {
-                            None
-                        },
-                    });
-                }
-                Err(errors) => {
-                    let msg = errors
-                        .iter()
-                        .map(|d| d.message.clone())
-                        .collect::<Vec<_>>()
-                        .join("; ");
-                    results.push(TestResult {
-                        name: test_name.clone(),
-                        passed: false,
-                        cost: None,
-                        error: Some(msg),
-                    });
-                }
-            }
-        }
-    }
-
-    // Format the report
-    let mut report = String::new();
-    let total = results.len();
-    let passed = results.iter().filter(|r| r.passed).count();
-    let failed = total - passed;
-
-    report.push_str(&format!(
-        "running {} test{}\n",
-        total,
-        if total == 1 { "" } else { "s" }
-    ));
-
-    for result in &results {
-        let status = if result.passed { "ok" } else { "FAILED" };
-        let cost_str = if let Some(ref c) = result.cost {
-            let sn: Vec<&str> = short_names.iter().map(|s| s.as_str()).collect();
-            let ann = c.format_annotation(&sn);
-            if ann.is_empty() {
-                String::new()
-            } else {
-                format!(" ({})", ann)
-            }
-        } else {
-            String::new()
-        };
-        report.push_str(&format!(
-            "  test {} ... {}{}\n",
-            result.name, status, cost_str
-        ));
-        if let Some(ref err) = result.error {
-            report.push_str(&format!("    error: {}\n", err));
-        }
-    }
-
-    report.push('\n');
-    if failed == 0 {
-        report.push_str(&format!("test result: ok. {} passed; 0 failed\n", passed));
-    } else {
-        report.push_str(&format!(
-            "test result: FAILED. {} passed; {} failed\n",
-            passed, failed
-        ));
-    }
-
-    Ok(report)
+    PreparedProject::build(entry_path, options)?;
+    Ok(())
 }
 
 /// Compile a module and emit TASM for all its functions (no linking, no DCE).
@@ -344,29 +274,27 @@ pub fn compile_module(
 ) -> Result<String, Vec<Diagnostic>> {
     use crate::pipeline::PreparedProject;
 
-    let project = PreparedProject::build(module_path, options)?;
+    let mut project = PreparedProject::build(module_path, options)?;
 
     let intrinsic_map = project.intrinsic_map();
     let module_aliases = project.module_aliases();
+    let error_codes = project.error_codes();
     let external_constants = project.external_constants();
+    let external_returns = project.external_returns();
+    let mut data_tables = project.data_tables(pipeline::data_ram_base(options));
 
     // Emit TASM for only the target module (last in topological order)
-    if let Some((i, pm)) = project.modules.iter().enumerate().last() {
-        let mono = project
-            .exports
-            .get(i)
-            .map(|e| e.mono_instances.clone())
-            .unwrap_or_default();
-        let call_res = project
-            .exports
-            .get(i)
-            .map(|e| e.call_resolutions.clone())
-            .unwrap_or_default();
+    if let Some(i) = project.modules.len().checked_sub(1) {
+        let (mono, call_res) = project.take_instances(i);
+        let pm = &project.modules[i];
         let ir = TIRBuilder::new(options.target_config.clone())
             .with_cfg_flags(options.cfg_flags.clone())
             .with_intrinsics(intrinsic_map)
             .with_module_aliases(module_aliases)
+            .with_error_codes(error_codes)
             .with_constants(external_constants)
+            .with_data(data_tables.swap_remove(i))
+            .with_external_returns(external_returns)
             .with_mono_instances(mono)
             .with_call_resolutions(call_res)
             .build_file(&pm.file);
@@ -392,9 +320,11 @@ pub fn build_tir(
     options: &CompileOptions,
 ) -> Result<Vec<crate::tir::TIROp>, Vec<Diagnostic>> {
     let file = crate::parse_source(source, filename)?;
+    let mut file = desugar_file(file, &options.cfg_flags);
 
     let exports = match TypeChecker::with_target(options.target_config.clone())
         .with_cfg_flags(options.cfg_flags.clone())
+        .with_source_dir(pipeline::source_dir(Path::new(filename)))
         .check_file(&file)
     {
         Ok(exports) => exports,
@@ -403,9 +333,14 @@ pub fn build_tir(
             return Err(errors);
         }
     };
+    fold_constants(&mut file, &exports);
+    insert_range_checks(&mut file, &exports.range_checks);
+    desugar_loop_exits(&mut file);
 
     let ir = TIRBuilder::new(options.target_config.clone())
         .with_cfg_flags(options.cfg_flags.clone())
+        .with_error_codes(error_codes([&exports]))
+        .with_data(pipeline::file_data_tables(&exports))
         .with_mono_instances(exports.mono_instances)
         .with_call_resolutions(exports.call_resolutions)
         .build_file(&file);
@@ -422,43 +357,133 @@ pub fn build_tir_project(
     entry_path: &Path,
     options: &CompileOptions,
 ) -> Result<Vec<crate::tir::TIROp>, Vec<Diagnostic>> {
+    let modules = build_tir_modules(entry_path, options, true)?;
+    Ok(modules.into_iter().flat_map(|(_, ir)| ir).collect())
+}
+
+/// Dump each module's TIR (`trident build --emit tir|tir-opt`), by module
+/// name, in dependency order: as built, or after the optimizer with
+/// `optimized`.
+pub fn dump_tir_project(
+    entry_path: &Path,
+    options: &CompileOptions,
+    optimized: bool,
+) -> Result<Vec<(String, String)>, Vec<Diagnostic>> {
+    let modules = build_tir_modules(entry_path, options, optimized)?;
+    Ok(modules
+        .into_iter()
+        .map(|(name, ir)| (name, crate::tir::dump::dump_ops(&ir)))
+        .collect())
+}
+
+/// Size-generic instances of a linked project, largest contributors
+/// first, with those folded into an identical instance.
+pub fn mono_report(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<MonoReport, Vec<Diagnostic>> {
     use crate::pipeline::PreparedProject;
 
-    let project = PreparedProject::build(entry_path, options)?;
+    let mut project = PreparedProject::build(entry_path, options)?;
+    let tasm_modules = project.lower_modules(options, false);
+    let (linked, shared) = link(tasm_modules);
+    Ok(MonoReport::from_tasm(&linked, shared))
+}
 
-    let intrinsic_map = project.intrinsic_map();
-    let module_aliases = project.module_aliases();
+/// Bound the op-stack depth and call depth of a program along all paths
+/// and check them against `limits`. `None` for a project without an
+/// entry point.
+pub fn analyze_stack_depth(
+    entry_path: &Path,
+    options: &CompileOptions,
+    limits: crate::tir::depth::DepthLimits,
+) -> Result<Option<crate::tir::depth::DepthReport>, Vec<Diagnostic>> {
+    let modules = build_tir_modules(entry_path, options, true)?;
+    Ok(crate::tir::depth::analyze(&modules, limits))
+}
+
+/// TIR of every module of a project, by module name, in dependency
+/// order; optimized with `optimize`.
+fn build_tir_modules(
+    entry_path: &Path,
+    options: &CompileOptions,
+    optimize: bool,
+) -> Result<Vec<(String, Vec<crate::tir::TIROp>)>, Vec<Diagnostic>> {
+    use crate::pipeline::PreparedProject;
+
+    let mut project = PreparedProject::build(entry_path, options)?;
+
+    let intrinsic_map = Arc::new(project.intrinsic_map());
+    let module_aliases = Arc::new(project.module_aliases());
+    let error_codes = Arc::new(project.error_codes());
     let external_constants = project.external_constants();
+    let external_returns = project.external_returns();
+    let mut data_tables = project
+        .data_tables(pipeline::data_ram_base(options))
+        .into_iter();
 
     let mut all_ir = Vec::new();
-    for (i, pm) in project.modules.iter().enumerate() {
-        let mono = project
-            .exports
-            .get(i)
-            .map(|e| e.mono_instances.clone())
-            .unwrap_or_default();
-        let call_res = project
-            .exports
-            .get(i)
-            .map(|e| e.call_resolutions.clone())
-            .unwrap_or_default();
+    for i in 0..project.modules.len() {
+        let (mono, call_res) = project.take_instances(i);
+        let pm = &project.modules[i];
         let ir = TIRBuilder::new(options.target_config.clone())
             .with_cfg_flags(options.cfg_flags.clone())
-            .with_intrinsics(intrinsic_map.clone())
-            .with_module_aliases(module_aliases.clone())
+            .with_intrinsics(Arc::clone(&intrinsic_map))
+            .with_module_aliases(Arc::clone(&module_aliases))
+            .with_error_codes(Arc::clone(&error_codes))
             .with_constants(external_constants.clone())
+            .with_data(data_tables.next().unwrap_or_default())
+            .with_external_returns(external_returns.clone())
             .with_mono_instances(mono)
             .with_call_resolutions(call_res)
             .build_file(&pm.file);
-        all_ir.extend(optimize_tir(ir));
+        let ir = if optimize { optimize_tir(ir) } else { ir };
+        all_ir.push((pm.file.name.node.clone(), ir));
     }
     Ok(all_ir)
 }
 
+pub mod backend;
+mod bench_history;
+mod benchmark;
+mod ci;
+mod conformance;
+mod cost_history;
+pub mod crash;
+mod disasm;
 pub(crate) mod doc;
+mod fix;
+mod index;
+mod leakage;
+mod lowering_coverage;
+mod matrix;
+mod options;
+mod passes;
 pub(crate) mod pipeline;
+mod policy;
+mod reduce;
+mod testing;
+pub mod timings;
 mod tools;
+mod unused;
+pub mod warrior;
+pub use bench_history::*;
+pub use benchmark::*;
+pub use ci::*;
+pub use conformance::*;
+pub use cost_history::*;
+pub use fix::{fix_source, unified_diff, AppliedFix, FixedSource};
+pub use index::{DefinitionKind, IndexEntry};
+pub use leakage::{BranchLeak, ConstantLeak, EventFieldLeak, EventKind, EventLeak, LeakageReport};
+pub use lowering_coverage::*;
+pub use matrix::*;
+pub use options::*;
+pub use passes::*;
+pub use policy::*;
+pub use reduce::*;
+pub use testing::*;
 pub use tools::*;
+pub use unused::{UnusedExport, UnusedExports, UnusedKind};
 
 /// Compile a multi-module project to a `ProgramBundle` artifact.
 ///
@@ -469,7 +494,10 @@ pub fn compile_to_bundle(
     entry_path: &Path,
     options: &CompileOptions,
 ) -> Result<crate::runtime::ProgramBundle, Vec<Diagnostic>> {
-    use crate::runtime::artifact::{BundleCost, BundleFunction, ProgramBundle};
+    use crate::runtime::artifact::{
+        BundleCost, BundleData, BundleEntrypoint, BundleError, BundleEvent, BundleFunction,
+        BundleOutput, ProgramBundle,
+    };
     use pipeline::PreparedProject;
 
     let tasm = compile_project_with_options(entry_path, options)?;
@@ -486,6 +514,11 @@ pub fn compile_to_bundle(
             padded_height: 0,
             estimated_proving_ns: 0,
             loop_bound_waste: Vec::new(),
+            modules: Vec::new(),
+            constants: Vec::new(),
+            entrypoints: Vec::new(),
+            statements: Vec::new(),
+            rewrites: Vec::new(),
         });
 
     // Parse entry file for function signatures + content hashes
@@ -496,6 +529,53 @@ pub fn compile_to_bundle(
         .find(|m| m.file.kind == FileKind::Program)
         .or_else(|| project.modules.last());
 
+    // Public output layout, events, and entrypoints, from the program's
+    // declarations
+    let program_exports = project
+        .modules
+        .iter()
+        .position(|m| m.file.kind == FileKind::Program)
+        .and_then(|i| project.exports.get(i));
+    let outputs: Vec<BundleOutput> = program_exports
+        .map(|exports| {
+            exports
+                .public_output
+                .iter()
+                .map(|(ty, width)| BundleOutput {
+                    ty: ty.clone(),
+                    width: *width,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let events: Vec<BundleEvent> = program_exports
+        .map(|exports| {
+            exports
+                .events
+                .iter()
+                .map(|e| BundleEvent {
+                    name: e.name.clone(),
+                    tag: e.tag,
+                    version: e.version,
+                    deprecated: e.deprecated.clone(),
+                    fields: e.fields.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let entrypoints: Vec<BundleEntrypoint> = program_exports
+        .map(|exports| {
+            exports
+                .entrypoints
+                .iter()
+                .map(|(selector, name)| BundleEntrypoint {
+                    selector: *selector,
+                    name: name.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
     let (functions, entry_point, source_hash) = if let Some(pm) = entry_file {
         let fn_hashes = crate::hash::hash_file(&pm.file);
         let fns: Vec<BundleFunction> = pm
@@ -532,6 +612,28 @@ pub fn compile_to_bundle(
         (Vec::new(), "main".to_string(), String::new())
     };
 
+    // Error table, in code order
+    let mut errors: Vec<BundleError> = project
+        .error_codes()
+        .into_iter()
+        .map(|(message, code)| BundleError { code, message })
+        .collect();
+    errors.sort_by_key(|e| e.code);
+
+    // Embedded tables of every module, by qualified name
+    let data: Vec<BundleData> = project
+        .exports
+        .iter()
+        .flat_map(|exports| {
+            exports.data.iter().map(|table| BundleData {
+                name: format!("{}.{}", exports.module_name, table.name),
+                source: table.path.clone(),
+                length: table.values.len() as u64,
+                hash: table.content_hash().to_hex(),
+            })
+        })
+        .collect();
+
     let name = entry_path
         .file_stem()
         .and_then(|s| s.to_str())
@@ -546,6 +648,11 @@ pub fn compile_to_bundle(
         assembly: tasm,
         entry_point,
         functions,
+        errors,
+        outputs,
+        events,
+        entrypoints,
+        data,
         cost: BundleCost {
             table_values: (0..program_cost.total.count as usize)
                 .map(|i| program_cost.total.get(i))
@@ -555,5 +662,6 @@ pub fn compile_to_bundle(
             estimated_proving_ns: program_cost.estimated_proving_ns,
         },
         source_hash,
+        build: Some(crate::runtime::BuildInfo::capture(options)),
     })
 }
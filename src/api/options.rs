@@ -0,0 +1,289 @@
+//! Building `CompileOptions`: validated construction from settings or a
+//! project, and a JSON form that tools persist to replay a build.
+//!
+//! ```json
+//! {
+//!   "target": "triton",
+//!   "profile": "release",
+//!   "cfg_flags": ["release", "verbose"],
+//!   "dep_dirs": ["/home/me/.trident/cache/merkle"],
+//!   "locked": {"merkle": "ab12..."},
+//!   "max_instances": 64
+//! }
+//! ```
+//! The target is stored by name and resolved again on load. Passes are
+//! not recorded; `from_project` declares the project's plugins again.
+
+use std::path::PathBuf;
+
+use super::*;
+use crate::runtime::json::{
+    extract_array, extract_object, extract_string, extract_u64, json_string, quoted_items,
+};
+
+/// Profiles every project has; a project declares more as
+/// `[targets.<name>]`.
+pub const BUILTIN_PROFILES: [&str; 2] = ["debug", "release"];
+
+/// Settings that take precedence over a project's own.
+#[derive(Clone, Debug, Default)]
+pub struct CompileOverrides {
+    /// Target VM instead of the project's `target`.
+    pub target: Option<String>,
+    /// Profile instead of `debug`.
+    pub profile: Option<String>,
+    /// Cfg flags in addition to the profile's.
+    pub cfg_flags: Vec<String>,
+    /// Instance cap instead of `[build] max_instances`.
+    pub max_instances: Option<usize>,
+}
+
+/// Builder for `CompileOptions` that checks the combination on `build`.
+#[derive(Clone, Debug)]
+pub struct CompileOptionsBuilder {
+    target: String,
+    profile: String,
+    /// Declared profiles beyond the builtin ones, with their flags.
+    profiles: BTreeMap<String, Vec<String>>,
+    extra_flags: BTreeSet<String>,
+    dep_dirs: Vec<PathBuf>,
+    locked: BTreeMap<String, String>,
+    max_instances: usize,
+    passes: PassRegistry,
+}
+
+impl Default for CompileOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            target: "triton".to_string(),
+            profile: "debug".to_string(),
+            profiles: BTreeMap::new(),
+            extra_flags: BTreeSet::new(),
+            dep_dirs: Vec::new(),
+            locked: BTreeMap::new(),
+            max_instances: crate::tir::mono::DEFAULT_MAX_INSTANCES,
+            passes: PassRegistry::default(),
+        }
+    }
+}
+
+impl CompileOptionsBuilder {
+    /// Target VM by name (default: triton).
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = target.to_string();
+        self
+    }
+
+    /// Profile by name (default: debug). Its cfg flags are the declared
+    /// ones, or the profile name itself for a builtin profile.
+    pub fn with_profile(mut self, profile: &str) -> Self {
+        self.profile = profile.to_string();
+        self
+    }
+
+    /// Declare profiles and their cfg flags, as `[targets.<name>]` does.
+    pub fn with_profiles(mut self, profiles: BTreeMap<String, Vec<String>>) -> Self {
+        self.profiles.extend(profiles);
+        self
+    }
+
+    /// Activate a cfg flag on top of the profile's.
+    pub fn with_cfg_flag(mut self, flag: &str) -> Self {
+        self.extra_flags.insert(flag.to_string());
+        self
+    }
+
+    /// Module search directories of locked dependencies.
+    pub fn with_dep_dirs(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.dep_dirs = dirs;
+        self
+    }
+
+    /// Locked dependency hashes (name -> hex) for build metadata.
+    pub fn with_locked(mut self, locked: BTreeMap<String, String>) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Most size-generic instances a program may emit.
+    pub fn with_max_instances(mut self, max_instances: usize) -> Self {
+        self.max_instances = max_instances;
+        self
+    }
+
+    /// Run `pass` over each module after it type-checks.
+    pub fn with_pass(mut self, pass: impl CompilerPass + 'static) -> Self {
+        self.passes.register(Arc::new(pass));
+        self
+    }
+
+    /// Resolve the target and check the settings: the profile must be
+    /// builtin or declared, cfg flags must be identifiers, `debug` and
+    /// `release` exclude each other, and the instance cap is positive.
+    pub fn build(self) -> Result<CompileOptions, Diagnostic> {
+        let error = |message: String| Diagnostic::error(message, span::Span::dummy());
+
+        let mut cfg_flags: BTreeSet<String> = match self.profiles.get(&self.profile) {
+            Some(flags) => flags.iter().cloned().collect(),
+            None if BUILTIN_PROFILES.contains(&self.profile.as_str()) => {
+                BTreeSet::from([self.profile.clone()])
+            }
+            None => {
+                let known: BTreeSet<&str> = BUILTIN_PROFILES
+                    .into_iter()
+                    .chain(self.profiles.keys().map(String::as_str))
+                    .collect();
+                return Err(
+                    error(format!("unknown profile '{}'", self.profile)).with_help(format!(
+                        "known profiles: {}",
+                        known.into_iter().collect::<Vec<_>>().join(", ")
+                    )),
+                );
+            }
+        };
+        cfg_flags.extend(self.extra_flags);
+
+        if let Some(flag) = cfg_flags.iter().find(|f| !is_cfg_flag(f)) {
+            return Err(error(format!("invalid cfg flag '{}'", flag)));
+        }
+        if cfg_flags.contains("debug") && cfg_flags.contains("release") {
+            return Err(
+                error("cfg flags 'debug' and 'release' are both active".to_string())
+                    .with_help("a profile is either a debug or a release build".to_string()),
+            );
+        }
+        if self.max_instances == 0 {
+            return Err(error("max_instances must be at least 1".to_string()));
+        }
+
+        let target_config = if self.target == "triton" {
+            TerrainConfig::triton()
+        } else {
+            TerrainConfig::resolve(&self.target)?
+        };
+        Ok(CompileOptions {
+            profile: self.profile,
+            cfg_flags,
+            target_config,
+            dep_dirs: self.dep_dirs,
+            locked: self.locked,
+            max_instances: self.max_instances,
+            passes: self.passes,
+        })
+    }
+}
+
+/// A cfg flag as `#[cfg(...)]` spells it: an identifier.
+fn is_cfg_flag(flag: &str) -> bool {
+    let mut chars = flag.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl CompileOptions {
+    /// Start building options from the defaults (triton, debug).
+    pub fn builder() -> CompileOptionsBuilder {
+        CompileOptionsBuilder::default()
+    }
+
+    /// Options for building `project`: its target, declared profiles,
+    /// instance cap, plugin passes, and locked dependencies (when
+    /// `trident.lock` loads), with `overrides` taking precedence.
+    pub fn from_project(
+        project: &project::Project,
+        overrides: CompileOverrides,
+    ) -> Result<CompileOptions, Diagnostic> {
+        let target = overrides
+            .target
+            .or_else(|| project.target.clone())
+            .unwrap_or_else(|| "triton".to_string());
+        let mut builder = CompileOptions::builder()
+            .with_target(&target)
+            .with_profiles(project.targets.clone())
+            .with_profile(overrides.profile.as_deref().unwrap_or("debug"));
+        for flag in &overrides.cfg_flags {
+            builder = builder.with_cfg_flag(flag);
+        }
+        if let Some(max) = overrides.max_instances.or(project.max_instances) {
+            builder = builder.with_max_instances(max);
+        }
+        for (name, path) in &project.passes {
+            builder = builder.with_pass(PluginPass::load(name, path, &project.root_dir)?);
+        }
+        let lock_path = project.root_dir.join("trident.lock");
+        if let Ok(lock) = crate::manifest::load_lockfile(&lock_path) {
+            builder = builder
+                .with_dep_dirs(crate::manifest::dependency_search_paths(
+                    &project.root_dir,
+                    &lock,
+                ))
+                .with_locked(
+                    lock.locked
+                        .into_iter()
+                        .map(|(name, dep)| (name, dep.hash))
+                        .collect(),
+                );
+        }
+        builder.build()
+    }
+
+    /// Serialize as a JSON object, newline-terminated.
+    pub fn to_json(&self) -> String {
+        let strings = |items: Vec<String>| {
+            let quoted: Vec<String> = items.iter().map(|s| json_string(s)).collect();
+            format!("[{}]", quoted.join(", "))
+        };
+        let locked: Vec<String> = self
+            .locked
+            .iter()
+            .map(|(name, hash)| format!("{}: {}", json_string(name), json_string(hash)))
+            .collect();
+        format!(
+            "{{\n  \"target\": {},\n  \"profile\": {},\n  \"cfg_flags\": {},\n  \"dep_dirs\": {},\n  \"locked\": {{{}}},\n  \"max_instances\": {}\n}}\n",
+            json_string(&self.target_config.name),
+            json_string(&self.profile),
+            strings(self.cfg_flags.iter().cloned().collect()),
+            strings(
+                self.dep_dirs
+                    .iter()
+                    .map(|d| d.to_string_lossy().into_owned())
+                    .collect()
+            ),
+            locked.join(", "),
+            self.max_instances
+        )
+    }
+
+    /// Parse the object produced by `to_json`, resolving the target by
+    /// name. The flags are taken as recorded, not re-derived from the
+    /// profile.
+    pub fn from_json(json: &str) -> Result<CompileOptions, String> {
+        let target = extract_string(json, "target")?;
+        let target_config = if target == "triton" {
+            TerrainConfig::triton()
+        } else {
+            TerrainConfig::resolve(&target).map_err(|e| e.message)?
+        };
+        let array = |key: &str| {
+            extract_array(json, key)
+                .map(quoted_items)
+                .ok_or_else(|| format!("missing key '{}'", key))
+        };
+        let locked = quoted_items(extract_object(json, "locked").ok_or("missing key 'locked'")?);
+        Ok(CompileOptions {
+            profile: extract_string(json, "profile")?,
+            cfg_flags: array("cfg_flags")?.into_iter().collect(),
+            target_config,
+            dep_dirs: array("dep_dirs")?.into_iter().map(PathBuf::from).collect(),
+            locked: locked
+                .chunks(2)
+                .filter(|pair| pair.len() == 2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect(),
+            max_instances: extract_u64(json, "max_instances")? as usize,
+            passes: PassRegistry::default(),
+        })
+    }
+}
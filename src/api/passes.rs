@@ -0,0 +1,225 @@
+//! Compiler passes: house rules checked alongside the type checker.
+//!
+//! A `CompilerPass` sees every module right after it type-checks — its
+//! AST, its exports, the exports of the modules checked before it, and
+//! the target — and returns diagnostics. Errors fail the build like type
+//! errors; warnings are rendered and the build goes on. Passes are
+//! registered on `CompileOptions::passes`, in process with
+//! `CompileOptionsBuilder::with_pass`, or as plugins declared in the
+//! `[passes]` section of `trident.toml`:
+//! ```toml
+//! [passes]
+//! no_raw_divine = "tools/no-raw-divine"
+//! auth_rules = "tools/auth_rules.wasm"
+//! ```
+//! A plugin runs once per module from the project root, without a shell,
+//! with the module source on stdin and `TRIDENT_MODULE`, `TRIDENT_FILE`
+//! and `TRIDENT_TARGET` set. A `.wasm` plugin runs under the runner named
+//! by `TRIDENT_WASM_RUNNER` (default `wasmtime run`); anything else is
+//! executed directly. Native libraries are not loaded. A plugin prints
+//! one diagnostic per line:
+//! ```text
+//! 12:5: error: raw divine outside std.auth
+//! ```
+//! Other lines are ignored, and a non-zero exit without diagnostics is
+//! reported as an error.
+
+use std::fmt;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::*;
+use crate::diagnostic::Severity;
+
+/// A custom check run over each module of a build.
+pub trait CompilerPass: Send + Sync {
+    /// Name shown with the pass's diagnostics.
+    fn name(&self) -> &str;
+
+    /// Check one module; an empty result means it passes.
+    fn check(&self, module: &PassContext) -> Vec<Diagnostic>;
+}
+
+/// What a pass sees of one module.
+pub struct PassContext<'a> {
+    pub file_path: &'a Path,
+    pub source: &'a str,
+    /// The module as written, after `#[cfg]` desugaring.
+    pub file: &'a ast::File,
+    pub exports: &'a ModuleExports,
+    /// Exports of the modules checked before this one, which include
+    /// everything it imports.
+    pub imports: &'a [ModuleExports],
+    pub target: &'a TerrainConfig,
+}
+
+/// The passes a build runs, in registration order.
+#[derive(Clone, Default)]
+pub struct PassRegistry {
+    passes: Vec<Arc<dyn CompilerPass>>,
+}
+
+impl PassRegistry {
+    /// Add `pass` after the ones already registered.
+    pub fn register(&mut self, pass: Arc<dyn CompilerPass>) {
+        self.passes.push(pass);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Names of the registered passes.
+    pub fn names(&self) -> Vec<&str> {
+        self.passes.iter().map(|p| p.name()).collect()
+    }
+
+    /// Run every pass over `module`, noting on each diagnostic which
+    /// pass reported it.
+    pub(crate) fn run(&self, module: &PassContext) -> Vec<Diagnostic> {
+        self.passes
+            .iter()
+            .flat_map(|pass| {
+                let name = pass.name().to_string();
+                pass.check(module)
+                    .into_iter()
+                    .map(move |d| d.with_note(format!("reported by pass `{}`", name)))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for PassRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+/// A pass run as an external program, declared under `[passes]`.
+#[derive(Clone, Debug)]
+pub struct PluginPass {
+    name: String,
+    /// Program and leading arguments.
+    command: Vec<String>,
+    root_dir: PathBuf,
+}
+
+impl PluginPass {
+    /// Resolve the plugin at `path`, relative to `root_dir`.
+    pub fn load(name: &str, path: &str, root_dir: &Path) -> Result<Self, Diagnostic> {
+        let error = |message: String| Diagnostic::error(message, span::Span::dummy());
+        let full = root_dir.join(path);
+        if !full.is_file() {
+            return Err(error(format!(
+                "pass `{}`: plugin '{}' does not exist",
+                name, path
+            )));
+        }
+        let program = full.to_string_lossy().into_owned();
+        let command = match full.extension().and_then(|e| e.to_str()) {
+            Some("wasm") => {
+                let runner =
+                    std::env::var("TRIDENT_WASM_RUNNER").unwrap_or_else(|_| "wasmtime run".into());
+                let mut command: Vec<String> =
+                    runner.split_whitespace().map(String::from).collect();
+                command.push(program);
+                command
+            }
+            Some("so" | "dylib" | "dll") => {
+                return Err(error(format!(
+                    "pass `{}`: native library '{}' cannot be loaded",
+                    name, path
+                ))
+                .with_help("build the pass as an executable or a wasm module".to_string()));
+            }
+            _ => vec![program],
+        };
+        Ok(Self {
+            name: name.to_string(),
+            command,
+            root_dir: root_dir.to_path_buf(),
+        })
+    }
+
+    /// Run the plugin over `module`, returning its diagnostics or why it
+    /// could not run.
+    fn run(&self, module: &PassContext) -> Result<Vec<Diagnostic>, String> {
+        let mut child = Command::new(&self.command[0])
+            .args(&self.command[1..])
+            .current_dir(&self.root_dir)
+            .env("TRIDENT_MODULE", &module.file.name.node)
+            .env("TRIDENT_FILE", module.file_path)
+            .env("TRIDENT_TARGET", &module.target.name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("cannot run `{}`: {}", self.command.join(" "), e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A plugin may exit without reading its input.
+            let _ = stdin.write_all(module.source.as_bytes());
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("cannot run `{}`: {}", self.command.join(" "), e))?;
+        let diagnostics: Vec<Diagnostic> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| parse_plugin_line(line, module.source))
+            .collect();
+        if !output.status.success() && diagnostics.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!(
+                "failed ({}){}{}",
+                output.status,
+                if stderr.trim().is_empty() { "" } else { ":\n" },
+                stderr.trim_end()
+            ));
+        }
+        Ok(diagnostics)
+    }
+}
+
+impl CompilerPass for PluginPass {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn check(&self, module: &PassContext) -> Vec<Diagnostic> {
+        self.run(module).unwrap_or_else(|reason| {
+            vec![Diagnostic::error(
+                format!("pass `{}` {}", self.name, reason),
+                span::Span::dummy(),
+            )]
+        })
+    }
+}
+
+/// Parse `line:col: severity: message`, spanning from that position to
+/// the end of its line in `source`.
+fn parse_plugin_line(line: &str, source: &str) -> Option<Diagnostic> {
+    let mut parts = line.splitn(4, ':');
+    let line_no: usize = parts.next()?.trim().parse().ok()?;
+    let column: usize = parts.next()?.trim().parse().ok()?;
+    let severity = match parts.next()?.trim() {
+        "error" => Severity::Error,
+        "warning" => Severity::Warning,
+        _ => return None,
+    };
+    let message = parts.next()?.trim().to_string();
+
+    let line_start: usize = source
+        .split_inclusive('\n')
+        .take(line_no.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    let line_end = source[line_start..]
+        .find('\n')
+        .map_or(source.len(), |i| line_start + i);
+    let start = (line_start + column.saturating_sub(1)).min(line_end);
+    let span = span::Span::new(0, start as u32, line_end as u32);
+    Some(match severity {
+        Severity::Error => Diagnostic::error(message, span),
+        Severity::Warning => Diagnostic::warning(message, span),
+    })
+}
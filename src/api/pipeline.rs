@@ -5,19 +5,216 @@
 
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
 
+use crate::api::index::line_col;
+use crate::api::{crash, timings};
 use crate::ast;
+use crate::ast::desugar::{desugar_file, desugar_loop_exits};
 use crate::ast::FileKind;
-use crate::diagnostic::{render_diagnostics, Diagnostic};
-use crate::resolve::{resolve_modules, resolve_modules_with_deps};
-use crate::typecheck::{ModuleExports, TypeChecker};
-use crate::CompileOptions;
+use crate::diagnostic::{render_diagnostics, Diagnostic, Severity};
+use crate::lexeme::Lexeme;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::resolve::{file_at, resolve_modules_with_deps, ModuleInfo, NO_LEGACY_PATHS_FLAG};
+use crate::span::{Span, Spanned};
+use crate::tir::builder::TIRBuilder;
+use crate::tir::data::{self, DataTable};
+use crate::tir::linker::ModuleTasm;
+use crate::tir::lower::create_stack_lowering;
+use crate::tir::optimize::optimize as optimize_tir;
+use crate::typecheck::types::Ty;
+use crate::typecheck::{
+    error_codes, fold_constants, insert_range_checks, ModuleExports, MonoInstance, TypeChecker,
+};
+use crate::{CompileOptions, PassContext};
+
+/// Lex and parse one file, rendering any errors against its source.
+pub(crate) fn parse_source(source: &str, filename: &str) -> Result<ast::File, Vec<Diagnostic>> {
+    let (tokens, _comments, lex_errors) = Lexer::new(source, 0).tokenize();
+    if !lex_errors.is_empty() {
+        render_diagnostics(&lex_errors, filename, source);
+        return Err(lex_errors);
+    }
+
+    match Parser::new_with_source(tokens, source).parse_file() {
+        Ok(file) => Ok(file),
+        Err(errors) => {
+            render_diagnostics(&errors, filename, source);
+            Err(errors)
+        }
+    }
+}
+
+/// Lex and parse one file without rendering errors.
+pub fn parse_source_silent(source: &str, _filename: &str) -> Result<ast::File, Vec<Diagnostic>> {
+    let (tokens, _comments, lex_errors) = Lexer::new(source, 0).tokenize();
+    if !lex_errors.is_empty() {
+        return Err(lex_errors);
+    }
+    Parser::new_with_source(tokens, source).parse_file()
+}
+
+/// Resolve the modules of a project in dependency order, with the
+/// dependency directories of `options`. Legacy module paths resolve
+/// unless the `no_legacy_paths` cfg flag is active.
+pub(crate) fn resolve_project(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<Vec<ModuleInfo>, Vec<Diagnostic>> {
+    resolve_modules_with_deps(
+        entry_path,
+        options.dep_dirs.clone(),
+        !options.cfg_flags.contains(NO_LEGACY_PATHS_FLAG),
+    )
+}
+
+/// Lex and parse a resolved module, rendering any errors against the
+/// file they point into.
+pub(crate) fn parse_module(module: &ModuleInfo) -> Result<ast::File, Vec<Diagnostic>> {
+    parse_module_silent(module).inspect_err(|errors| {
+        render_module_diagnostics(errors, &module.file_path, &module.source, &module.parts)
+    })
+}
+
+/// Lex and parse a resolved module without rendering errors.
+///
+/// The files of a multi-file program are lexed as one text, so every
+/// span points into the module's source, then parsed one by one; the
+/// `use`s, declarations and items of the parts are merged into the
+/// entry file's.
+pub(crate) fn parse_module_silent(module: &ModuleInfo) -> Result<ast::File, Vec<Diagnostic>> {
+    if module.parts.is_empty() {
+        return parse_source_silent(&module.source, "");
+    }
+    let source = module.source.as_str();
+    let (tokens, _comments, lex_errors) = Lexer::new(source, 0).tokenize();
+    if !lex_errors.is_empty() {
+        return Err(lex_errors);
+    }
+
+    let starts: Vec<usize> = module.parts.iter().map(|(_, start)| *start).collect();
+    let mut segments: Vec<Vec<Spanned<Lexeme>>> = vec![Vec::new(); starts.len() + 1];
+    for token in tokens {
+        if token.node != Lexeme::Eof {
+            let i = starts.partition_point(|start| *start <= token.span.start as usize);
+            segments[i].push(token);
+        }
+    }
+
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    for (i, mut tokens) in segments.into_iter().enumerate() {
+        let end = starts.get(i).map_or(source.len(), |next| next - 1) as u32;
+        tokens.push(Spanned::new(Lexeme::Eof, Span::new(0, end, end)));
+        match Parser::new_with_source(tokens, source).parse_file() {
+            Ok(file) => files.push(file),
+            Err(e) => errors.extend(e),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut files = files.into_iter();
+    let mut merged = files.next().expect("entry file is parsed first");
+    for part in files {
+        for u in part.uses {
+            if !merged.uses.iter().any(|m| m.node == u.node) {
+                merged.uses.push(u);
+            }
+        }
+        merged.declarations.extend(part.declarations);
+        merged.items.extend(part.items);
+    }
+    Ok(merged)
+}
+
+/// Render diagnostics whose spans point into a module's `source`, each
+/// against the file of the module it falls in.
+pub(crate) fn render_module_diagnostics(
+    diagnostics: &[Diagnostic],
+    file_path: &Path,
+    source: &str,
+    parts: &[(PathBuf, usize)],
+) {
+    if parts.is_empty() {
+        render_diagnostics(diagnostics, &file_path.to_string_lossy(), source);
+        return;
+    }
+    for diagnostic in diagnostics {
+        let (path, file) = file_at(
+            file_path,
+            parts,
+            source.len(),
+            diagnostic.span.start as usize,
+        );
+        let mut local = diagnostic.clone();
+        local.span.start -= file.start as u32;
+        local.span.end = (local.span.end as usize)
+            .min(file.end)
+            .saturating_sub(file.start) as u32;
+        local.render(&path.to_string_lossy(), &source[file]);
+    }
+}
+
+/// The `include_data` tables of a file compiled on its own. Without a
+/// linker to store them, runtime indices read the pushed table.
+pub(crate) fn file_data_tables(exports: &ModuleExports) -> BTreeMap<String, DataTable> {
+    exports
+        .data
+        .iter()
+        .map(|dc| {
+            let table = DataTable {
+                values: dc.values.clone().into(),
+                address: None,
+            };
+            (dc.name.clone(), table)
+        })
+        .collect()
+}
+
+/// Start of the RAM that `include_data` tables are stored in: between the
+/// builder's temporaries (from half the spill base) and the linker's
+/// materialized constants (from three quarters).
+pub(crate) fn data_ram_base(options: &CompileOptions) -> u64 {
+    options.target_config.spill_ram_base / 8 * 5
+}
+
+/// Directory of a module's source, which its `include_data` paths are
+/// relative to.
+pub(crate) fn source_dir(file_path: &Path) -> &Path {
+    file_path.parent().unwrap_or(Path::new(""))
+}
 
 /// A single parsed module: path, source text, and parsed AST.
 pub(crate) struct ParsedModule {
     pub file_path: PathBuf,
-    pub source: String,
+    pub source: Arc<str>,
     pub file: ast::File,
+    /// The other files of a multi-file program and their offsets in
+    /// `source` (see `ModuleInfo::parts`).
+    pub parts: Vec<(PathBuf, usize)>,
+}
+
+impl ParsedModule {
+    /// Render diagnostics against the file of the module each points into.
+    pub fn render(&self, diagnostics: &[Diagnostic]) {
+        render_module_diagnostics(diagnostics, &self.file_path, &self.source, &self.parts);
+    }
+
+    /// File, line and column of byte `offset` in `source`.
+    pub fn position(&self, offset: u32) -> (&Path, usize, usize) {
+        let (path, file) = file_at(
+            &self.file_path,
+            &self.parts,
+            self.source.len(),
+            offset as usize,
+        );
+        let (line, column) = line_col(&self.source[file.clone()], offset - file.start as u32);
+        (path, line, column)
+    }
 }
 
 /// A fully resolved, parsed, and type-checked project.
@@ -29,55 +226,94 @@ pub(crate) struct PreparedProject {
 impl PreparedProject {
     /// Build a project from an entry path using the given compile options.
     ///
-    /// This performs the resolve → parse → typecheck pipeline that is shared
-    /// across `compile_project`, `run_tests`, `analyze_costs_project`,
-    /// and `generate_docs`.
+    /// This performs the resolve → parse → desugar → typecheck pipeline
+    /// that is shared across `compile_project`, `run_tests`,
+    /// `analyze_costs_project`, and `generate_docs`.
     pub fn build(entry_path: &Path, options: &CompileOptions) -> Result<Self, Vec<Diagnostic>> {
-        let resolved = if options.dep_dirs.is_empty() {
-            resolve_modules(entry_path)?
-        } else {
-            resolve_modules_with_deps(entry_path, options.dep_dirs.clone())?
-        };
+        let started = Instant::now();
+        let resolved = resolve_project(entry_path, options)
+            .inspect_err(|errors| render_diagnostics(errors, &entry_path.to_string_lossy(), ""))?;
+        timings::record("resolve", "", started, resolved.len());
+        if !options.dep_dirs.is_empty() {
+            for m in &resolved {
+                let cached = options.dep_dirs.iter().any(|d| m.file_path.starts_with(d));
+                timings::cache_lookup("dependency", cached);
+            }
+        }
 
         let mut modules = Vec::new();
-        for m in &resolved {
-            let file = crate::parse_source(&m.source, &m.file_path.to_string_lossy())?;
+        for m in resolved {
+            let source: Arc<str> = m.source.as_str().into();
+            let _stage = crash::stage("parse", &m.name, Some(&source));
+            let started = Instant::now();
+            let file = parse_module(&m)?;
+            timings::record("parse", &file.name.node, started, file.items.len());
+            let file = desugar_file(file, &options.cfg_flags);
             modules.push(ParsedModule {
-                file_path: m.file_path.clone(),
-                source: m.source.clone(),
+                file_path: m.file_path,
+                source,
                 file,
+                parts: m.parts,
             });
         }
 
         let mut exports: Vec<ModuleExports> = Vec::new();
-        for pm in &modules {
+        for pm in &mut modules {
+            let _stage = crash::stage("typecheck", &pm.file.name.node, Some(&pm.source));
+            let started = Instant::now();
             let mut tc = TypeChecker::with_target(options.target_config.clone())
-                .with_cfg_flags(options.cfg_flags.clone());
+                .with_cfg_flags(options.cfg_flags.clone())
+                .with_source_dir(source_dir(&pm.file_path));
             for e in &exports {
                 tc.import_module(e);
             }
             match tc.check_file(&pm.file) {
                 Ok(e) => {
+                    timings::record(
+                        "typecheck",
+                        &pm.file.name.node,
+                        started,
+                        pm.file.items.len(),
+                    );
                     if !e.warnings.is_empty() {
-                        render_diagnostics(
-                            &e.warnings,
-                            &pm.file_path.to_string_lossy(),
-                            &pm.source,
-                        );
+                        pm.render(&e.warnings);
                     }
+                    if !options.passes.is_empty() {
+                        let started = Instant::now();
+                        let diagnostics = options.passes.run(&PassContext {
+                            file_path: &pm.file_path,
+                            source: &pm.source,
+                            file: &pm.file,
+                            exports: &e,
+                            imports: &exports,
+                            target: &options.target_config,
+                        });
+                        timings::record("passes", &pm.file.name.node, started, diagnostics.len());
+                        pm.render(&diagnostics);
+                        let errors: Vec<Diagnostic> = diagnostics
+                            .into_iter()
+                            .filter(|d| d.severity == Severity::Error)
+                            .collect();
+                        if !errors.is_empty() {
+                            return Err(errors);
+                        }
+                    }
+                    fold_constants(&mut pm.file, &e);
+                    insert_range_checks(&mut pm.file, &e.range_checks);
+                    desugar_loop_exits(&mut pm.file);
                     exports.push(e);
                 }
                 Err(errors) => {
-                    render_diagnostics(&errors, &pm.file_path.to_string_lossy(), &pm.source);
+                    pm.render(&errors);
                     return Err(errors);
                 }
             }
         }
 
+        propagate_instances(&mut exports);
         Ok(PreparedProject { modules, exports })
     }
 
-
     /// Build a project with default options (Triton target, debug profile).
     ///
     /// Used by `check_project` and `verify_project` which don't need target options.
@@ -85,6 +321,18 @@ impl PreparedProject {
         Self::build(entry_path, &CompileOptions::default())
     }
 
+    /// Move the monomorphized instances and call-site resolutions of
+    /// module `i` out of the project. Each list feeds exactly one TIR
+    /// build, so handing it over avoids copying it per module.
+    pub fn take_instances(&mut self, i: usize) -> (Vec<MonoInstance>, Vec<MonoInstance>) {
+        match self.exports.get_mut(i) {
+            Some(e) => (
+                std::mem::take(&mut e.mono_instances),
+                std::mem::take(&mut e.call_resolutions),
+            ),
+            None => (Vec::new(), Vec::new()),
+        }
+    }
 
     /// Return the program module (last in topological order, has `FileKind::Program`).
     pub fn program_module(&self) -> Option<&ParsedModule> {
@@ -93,11 +341,6 @@ impl PreparedProject {
             .find(|m| m.file.kind == FileKind::Program)
     }
 
-    /// Return the last parsed file (the entry / program module).
-    pub fn last_file(&self) -> Option<&ast::File> {
-        self.modules.last().map(|m| &m.file)
-    }
-
     /// Build a global intrinsic map from all modules.
     ///
     /// Maps function names (short, qualified, and short-alias qualified) to
@@ -147,6 +390,33 @@ impl PreparedProject {
         aliases
     }
 
+    /// Build external return shapes from all module exports: qualified
+    /// function name -> width of each returned element.
+    pub fn external_returns(&self) -> BTreeMap<String, Vec<u32>> {
+        let mut returns = BTreeMap::new();
+        for exp in &self.exports {
+            let full = &exp.module_name;
+            let short = full.rsplit('.').next().unwrap_or(full);
+            for (fn_name, _params, ret) in &exp.functions {
+                let widths = return_widths(ret);
+                if short != full {
+                    returns.insert(format!("{}.{}", short, fn_name), widths.clone());
+                }
+                returns.insert(format!("{}.{}", full, fn_name), widths);
+            }
+            // Instances of other modules' generics, under the name called.
+            for (inst, ret) in &exp.foreign_instances {
+                returns.insert(inst.mangled_name(), return_widths(ret));
+            }
+        }
+        returns
+    }
+
+    /// Build the program's error table: assertion message -> error code.
+    pub fn error_codes(&self) -> BTreeMap<String, u64> {
+        error_codes(&self.exports)
+    }
+
     /// Build external constants map from all module exports.
     pub fn external_constants(&self) -> BTreeMap<String, u64> {
         let mut constants = BTreeMap::new();
@@ -165,4 +435,170 @@ impl PreparedProject {
         }
         constants
     }
+
+    /// The `include_data` tables each module can name, by module index:
+    /// its own tables by name, and other modules' public tables by
+    /// qualified name. A table read at runtime indices often enough to be
+    /// worth storing once gets a RAM address, from `ram_base` up.
+    pub fn data_tables(&self, ram_base: u64) -> Vec<BTreeMap<String, DataTable>> {
+        let owner = |i: usize, name: &str| -> Option<(usize, String)> {
+            match name.rsplit_once('.') {
+                None => Some((i, name.to_string())),
+                Some((module, table)) => self
+                    .exports
+                    .iter()
+                    .position(|e| {
+                        e.module_name == module || e.module_name.rsplit('.').next() == Some(module)
+                    })
+                    .map(|j| (j, table.to_string())),
+            }
+        };
+        let mut reads: BTreeMap<(usize, String), usize> = BTreeMap::new();
+        for (i, exp) in self.exports.iter().enumerate() {
+            for (name, count) in &exp.data_reads {
+                if let Some(key) = owner(i, name) {
+                    *reads.entry(key).or_default() += count;
+                }
+            }
+        }
+
+        let mut address = ram_base;
+        let mut tables: Vec<Vec<(String, bool, DataTable)>> = Vec::new();
+        for (i, exp) in self.exports.iter().enumerate() {
+            let mut own = Vec::new();
+            for dc in &exp.data {
+                let count = reads.get(&(i, dc.name.clone())).copied().unwrap_or(0);
+                let table = DataTable {
+                    values: dc.values.clone().into(),
+                    address: data::prefers_ram(dc.values.len(), count).then_some(address),
+                };
+                if table.address.is_some() {
+                    address += dc.values.len() as u64;
+                }
+                own.push((dc.name.clone(), dc.is_pub, table));
+            }
+            tables.push(own);
+        }
+
+        let mut views = Vec::new();
+        for i in 0..self.exports.len() {
+            let mut view = BTreeMap::new();
+            for (j, exp) in self.exports.iter().enumerate() {
+                let full = &exp.module_name;
+                let short = full.rsplit('.').next().unwrap_or(full);
+                for (name, is_pub, table) in &tables[j] {
+                    if i == j {
+                        view.insert(name.clone(), table.clone());
+                    } else if *is_pub {
+                        view.insert(format!("{}.{}", full, name), table.clone());
+                        view.insert(format!("{}.{}", short, name), table.clone());
+                    }
+                }
+            }
+            views.push(view);
+        }
+        views
+    }
+
+    /// Build, optimize, and lower every module to per-module TASM, ready
+    /// for the linker. With `include_tests`, `#[test]` functions are
+    /// emitted too so any of them can be linked as the entry.
+    pub fn lower_modules(
+        &mut self,
+        options: &CompileOptions,
+        include_tests: bool,
+    ) -> Vec<ModuleTasm> {
+        let intrinsic_map = Arc::new(self.intrinsic_map());
+        let module_aliases = Arc::new(self.module_aliases());
+        let error_codes = Arc::new(self.error_codes());
+        let external_constants = self.external_constants();
+        let external_returns = self.external_returns();
+        let mut data_tables = self.data_tables(data_ram_base(options)).into_iter();
+
+        let mut tasm_modules = Vec::new();
+        for i in 0..self.modules.len() {
+            let tables = data_tables.next().unwrap_or_default();
+            let (mono, call_res) = self.take_instances(i);
+            let pm = &self.modules[i];
+            let module_name = &pm.file.name.node;
+            let stage = crash::stage("tir", module_name, Some(&pm.source));
+            let started = Instant::now();
+            let ir = TIRBuilder::new(options.target_config.clone())
+                .with_cfg_flags(options.cfg_flags.clone())
+                .with_tests(include_tests)
+                .with_intrinsics(Arc::clone(&intrinsic_map))
+                .with_module_aliases(Arc::clone(&module_aliases))
+                .with_error_codes(Arc::clone(&error_codes))
+                .with_constants(external_constants.clone())
+                .with_data(tables.clone())
+                .with_external_returns(external_returns.clone())
+                .with_mono_instances(mono)
+                .with_call_resolutions(call_res)
+                .build_file(&pm.file);
+            let ir = optimize_tir(ir);
+            timings::record("tir", module_name, started, ir.len());
+            let stored: Vec<(u64, &[u64])> = tables
+                .iter()
+                .filter(|(name, _)| !name.contains('.'))
+                .filter_map(|(_, t)| Some((t.address?, &t.values[..])))
+                .collect();
+            drop(stage);
+            let _stage = crash::stage("lower", module_name, Some(&pm.source));
+            let started = Instant::now();
+            let lowering = create_stack_lowering(&options.target_config.name);
+            let lines = lowering.lower(&ir);
+            timings::record("lower", module_name, started, lines.len());
+            tasm_modules.push(ModuleTasm {
+                module_name: module_name.clone(),
+                is_program: pm.file.kind == FileKind::Program,
+                tasm: lines.join("\n"),
+                data: lowering.lower(&data::store_ops(&stored)).join("\n"),
+            });
+        }
+        tasm_modules
+    }
+}
+
+/// Widths of the elements a function returns.
+fn return_widths(ret: &Ty) -> Vec<u32> {
+    match ret {
+        Ty::Unit => Vec::new(),
+        Ty::Tuple(elems) => elems.iter().map(Ty::width).collect(),
+        ty => vec![ty.width()],
+    }
+}
+
+/// Hand each module the instances of its generic functions that other
+/// modules call, so it emits every size combination the program uses.
+/// Callers name the module by its full or short name (`std.hash` or
+/// `hash`).
+fn propagate_instances(exports: &mut [ModuleExports]) {
+    let foreign: Vec<MonoInstance> = exports
+        .iter()
+        .flat_map(|e| e.foreign_instances.iter().map(|(inst, _)| inst.clone()))
+        .collect();
+    for inst in foreign {
+        let Some((module, fn_name)) = inst.name.rsplit_once('.') else {
+            continue;
+        };
+        let owner = exports
+            .iter()
+            .position(|e| e.module_name == module)
+            .or_else(|| {
+                exports
+                    .iter()
+                    .position(|e| e.module_name.rsplit('.').next() == Some(module))
+            });
+        let Some(owner) = owner else {
+            continue;
+        };
+        let local = MonoInstance {
+            name: fn_name.to_string(),
+            size_args: inst.size_args,
+        };
+        let instances = &mut exports[owner].mono_instances;
+        if !instances.contains(&local) {
+            instances.push(local);
+        }
+    }
 }
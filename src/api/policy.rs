@@ -0,0 +1,239 @@
+//! Enforcing a project's `trident-policy.toml` (see `policy::Policy`).
+//!
+//! Violations are ordinary diagnostics: a forbidden intrinsic points at
+//! the call, a missing `#[pure]` at the function, and the padded-height
+//! limit at the program declaration, so they render like type errors.
+//! The source rules cover the modules beneath the policy's directory;
+//! the standard library and dependencies answer to their own authors.
+
+use std::path::PathBuf;
+
+use super::*;
+use crate::ast::{Block, Expr, Item, Stmt};
+use crate::pipeline::PreparedProject;
+use crate::policy::Policy;
+use crate::span::Span;
+
+/// Check the project at `entry_path`, built with `options`, against the
+/// `[build]` rules of `policy`, rendering every violation.
+pub fn enforce_build_policy(
+    entry_path: &Path,
+    options: &CompileOptions,
+    policy: &Policy,
+) -> Result<(), Vec<Diagnostic>> {
+    let project = PreparedProject::build(entry_path, options)?;
+    let intrinsics = project.intrinsic_map();
+    let root = canonical(&policy.root_dir);
+
+    let mut violations = Vec::new();
+    for pm in &project.modules {
+        if !canonical(&pm.file_path).starts_with(&root) {
+            continue;
+        }
+        let found = source_violations(&pm.file, policy, &intrinsics);
+        pm.render(&found);
+        violations.extend(found);
+    }
+
+    if let (Some(max), Some(program)) = (policy.max_padded_height, project.program_module()) {
+        let cost = analyze_costs_project(entry_path, options)?;
+        if cost.padded_height > max {
+            let found = vec![Diagnostic::error(
+                format!(
+                    "padded height {} exceeds the policy limit of {}",
+                    cost.padded_height, max
+                ),
+                program.file.name.span,
+            )
+            .with_note(format!(
+                "dominant table: {}",
+                cost.total.dominant_table(&cost.short_names())
+            ))
+            .with_help("see `trident build --hotspots` for the costliest functions".to_string())];
+            program.render(&found);
+            violations.extend(found);
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// Check the `[deploy]` rules of `policy`: verify the project at
+/// `entry_path` within `config`'s budgets when a verdict is required.
+pub fn enforce_deploy_policy(
+    entry_path: &Path,
+    policy: &Policy,
+    config: &solve::VerifyConfig,
+) -> Result<(), Vec<Diagnostic>> {
+    if policy.deploy_verdict.is_none() {
+        return Ok(());
+    }
+    let report = verify_project_with(entry_path, config)?;
+    if report.is_safe() {
+        return Ok(());
+    }
+    let violation = Diagnostic::error(
+        format!(
+            "verification verdict is {:?}, the policy requires safe",
+            report.verdict
+        ),
+        Span::dummy(),
+    )
+    .with_help("run `trident audit` for the failing assertions".to_string());
+    Err(vec![violation])
+}
+
+/// Forbidden intrinsic calls and functions missing `#[pure]` in `file`.
+fn source_violations(
+    file: &ast::File,
+    policy: &Policy,
+    intrinsics: &BTreeMap<String, String>,
+) -> Vec<Diagnostic> {
+    let module = &file.name.node;
+    let local: BTreeSet<&str> = file
+        .items
+        .iter()
+        .filter_map(|item| match &item.node {
+            Item::Fn(func) => Some(func.name.node.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut violations = Vec::new();
+    for item in &file.items {
+        let Item::Fn(func) = &item.node else {
+            continue;
+        };
+        if policy.requires_pure(module) && !func.is_pure && func.intrinsic.is_none() {
+            violations.push(
+                Diagnostic::error(
+                    format!("function `{}` must be #[pure]", func.name.node),
+                    func.name.span,
+                )
+                .with_note(format!(
+                    "the policy requires #[pure] in module `{}`",
+                    module
+                )),
+            );
+        }
+        if policy.forbidden_intrinsics.is_empty() {
+            continue;
+        }
+        let Some(body) = &func.body else {
+            continue;
+        };
+        let mut calls = Vec::new();
+        collect_calls_block(&body.node, &mut calls);
+        for (callee, span) in calls {
+            // A bare name is a builtin unless the module defines it.
+            let intrinsic = match intrinsics.get(&callee) {
+                Some(intrinsic) if callee.contains('.') || !local.contains(callee.as_str()) => {
+                    intrinsic.clone()
+                }
+                _ if !callee.contains('.') && !local.contains(callee.as_str()) => callee.clone(),
+                _ => continue,
+            };
+            if policy.forbidden_intrinsics.contains(&intrinsic) {
+                let mut violation =
+                    Diagnostic::error(format!("intrinsic `{}` is forbidden", intrinsic), span)
+                        .with_note("the policy forbids it in this project".to_string());
+                if callee != intrinsic {
+                    violation = violation.with_note(format!("`{}` wraps it", callee));
+                }
+                violations.push(violation);
+            }
+        }
+    }
+    violations
+}
+
+fn collect_calls_block(block: &Block, calls: &mut Vec<(String, Span)>) {
+    for stmt in &block.stmts {
+        match &stmt.node {
+            Stmt::Let { init: expr, .. }
+            | Stmt::Assign { value: expr, .. }
+            | Stmt::TupleAssign { value: expr, .. }
+            | Stmt::Expr(expr)
+            | Stmt::Return(Some(expr)) => collect_calls_expr(expr, calls),
+            Stmt::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                collect_calls_expr(cond, calls);
+                collect_calls_block(&then_block.node, calls);
+                if let Some(block) = else_block {
+                    collect_calls_block(&block.node, calls);
+                }
+            }
+            Stmt::For {
+                start, end, body, ..
+            } => {
+                collect_calls_expr(start, calls);
+                collect_calls_expr(end, calls);
+                collect_calls_block(&body.node, calls);
+            }
+            Stmt::While { cond, body, .. } => {
+                collect_calls_expr(cond, calls);
+                collect_calls_block(&body.node, calls);
+            }
+            Stmt::Reveal { fields, .. } | Stmt::Seal { fields, .. } => {
+                for (_, value) in fields {
+                    collect_calls_expr(value, calls);
+                }
+            }
+            Stmt::Match { expr, arms } => {
+                collect_calls_expr(expr, calls);
+                for arm in arms {
+                    collect_calls_block(&arm.body.node, calls);
+                }
+            }
+            Stmt::Return(None) | Stmt::Break | Stmt::Continue | Stmt::Asm { .. } => {}
+        }
+    }
+    if let Some(tail) = &block.tail_expr {
+        collect_calls_expr(tail, calls);
+    }
+}
+
+fn collect_calls_expr(expr: &span::Spanned<Expr>, calls: &mut Vec<(String, Span)>) {
+    match &expr.node {
+        Expr::Call { path, args, .. } => {
+            calls.push((path.node.as_dotted(), path.span));
+            for arg in args {
+                collect_calls_expr(arg, calls);
+            }
+        }
+        Expr::BinOp { lhs, rhs, .. } => {
+            collect_calls_expr(lhs, calls);
+            collect_calls_expr(rhs, calls);
+        }
+        Expr::Tuple(elems) | Expr::ArrayInit(elems) => {
+            for elem in elems {
+                collect_calls_expr(elem, calls);
+            }
+        }
+        Expr::FieldAccess { expr: inner, .. } => collect_calls_expr(inner, calls),
+        Expr::Index { expr: inner, index } => {
+            collect_calls_expr(inner, calls);
+            collect_calls_expr(index, calls);
+        }
+        Expr::StructInit { fields, base, .. } => {
+            for (_, value) in fields {
+                collect_calls_expr(value, calls);
+            }
+            if let Some(base) = base {
+                collect_calls_expr(base, calls);
+            }
+        }
+        Expr::Literal(_) | Expr::Var(_) => {}
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
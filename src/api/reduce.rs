@@ -0,0 +1,276 @@
+//! Test-case reduction: shrink a source while a failure still reproduces.
+//!
+//! Delta debugging over a list: remove chunks of it, halving the chunk
+//! size whenever no chunk can go, until no single element can be removed
+//! without losing the failure. `reduce_lines` runs it over the lines of a
+//! source; `reduce_source` runs it over the syntax tree, so candidates
+//! stay well-formed: imports and items, then the statements of every
+//! block, then each expression is replaced by one of its operands or by
+//! `0`, repeated until nothing more goes. The predicate decides what "the
+//! failure" is; the reducer only ever keeps candidates it accepted.
+
+use crate::ast::visit::{walk, walk_mut, MutVisitor, Visitor};
+use crate::ast::{Block, Expr, File, Literal};
+use crate::span::Spanned;
+use crate::syntax::trivia::TriviaMap;
+
+/// The smallest source, by removing whole lines of `source`, for which
+/// `fails` still holds. `fails(source)` is assumed to hold.
+pub fn reduce_lines(source: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let lines = reduce_list(&lines, |candidate| {
+        !candidate.is_empty() && fails(&join_lines(candidate))
+    });
+    join_lines(&lines)
+}
+
+/// The smallest source, by removing items, statements, and parts of
+/// expressions of `source`, for which `fails` still holds. The result is
+/// `source` reformatted, without comments. When `source` does not parse,
+/// or its reformatted text no longer fails, this falls back to
+/// `reduce_lines`. `fails(source)` is assumed to hold.
+pub fn reduce_source(source: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    let Ok(mut file) = crate::parse_source_silent(source, "reduce.tri") else {
+        return reduce_lines(source, fails);
+    };
+    if !fails(&render(&file)) {
+        return reduce_lines(source, fails);
+    }
+    loop {
+        let mut changed = reduce_top_level(&mut file, &mut fails);
+        changed |= reduce_blocks(&mut file, &mut fails);
+        changed |= reduce_exprs(&mut file, &mut fails);
+        if !changed {
+            break;
+        }
+    }
+    render(&file)
+}
+
+/// The smallest sublist of `list` for which `fails` holds.
+fn reduce_list<T: Clone>(list: &[T], mut fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut list = list.to_vec();
+    let mut chunk = (list.len() / 2).max(1);
+    loop {
+        let mut removed = false;
+        let mut start = 0;
+        while start < list.len() {
+            let end = (start + chunk).min(list.len());
+            let candidate: Vec<T> = list[..start].iter().chain(&list[end..]).cloned().collect();
+            if fails(&candidate) {
+                list = candidate;
+                removed = true;
+            } else {
+                start = end;
+            }
+        }
+        if !removed {
+            if chunk == 1 {
+                break;
+            }
+            chunk /= 2;
+        }
+    }
+    list
+}
+
+fn join_lines(lines: &[&str]) -> String {
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+fn render(file: &File) -> String {
+    crate::format::format_file(file, &TriviaMap::default())
+}
+
+/// Drop imports, I/O declarations, and items.
+fn reduce_top_level(file: &mut File, fails: &mut impl FnMut(&str) -> bool) -> bool {
+    let before = (file.uses.len(), file.declarations.len(), file.items.len());
+    let mut candidate = file.clone();
+    file.uses = reduce_list(&file.uses, |uses| {
+        candidate.uses = uses.to_vec();
+        fails(&render(&candidate))
+    });
+    candidate.uses = file.uses.clone();
+    file.declarations = reduce_list(&file.declarations, |declarations| {
+        candidate.declarations = declarations.to_vec();
+        fails(&render(&candidate))
+    });
+    candidate.declarations = file.declarations.clone();
+    file.items = reduce_list(&file.items, |items| {
+        candidate.items = items.to_vec();
+        fails(&render(&candidate))
+    });
+    before != (file.uses.len(), file.declarations.len(), file.items.len())
+}
+
+/// Drop statements and tail expressions, block by block, outermost first.
+fn reduce_blocks(file: &mut File, fails: &mut impl FnMut(&str) -> bool) -> bool {
+    let mut changed = false;
+    let mut index = 0;
+    while let Some(block) = nth_block(file, index) {
+        let mut candidate = file.clone();
+        let stmts = reduce_list(&block.stmts, |stmts| {
+            edit_block(&mut candidate, index, |b| b.stmts = stmts.to_vec());
+            fails(&render(&candidate))
+        });
+        if stmts.len() < block.stmts.len() {
+            edit_block(file, index, |b| b.stmts = stmts);
+            changed = true;
+        }
+        if block.tail_expr.is_some() {
+            let mut candidate = file.clone();
+            edit_block(&mut candidate, index, |b| b.tail_expr = None);
+            if fails(&render(&candidate)) {
+                *file = candidate;
+                changed = true;
+            }
+        }
+        index += 1;
+    }
+    changed
+}
+
+/// Replace expressions by one of their operands, or by `0`.
+fn reduce_exprs(file: &mut File, fails: &mut impl FnMut(&str) -> bool) -> bool {
+    let mut changed = false;
+    let mut index = 0;
+    while let Some(expr) = nth_expr(file, index) {
+        let mut simpler = operands(&expr);
+        if !matches!(expr.node, Expr::Literal(_)) {
+            simpler.push(Spanned::new(Expr::Literal(Literal::Integer(0)), expr.span));
+        }
+        let accepted = simpler.into_iter().find_map(|replacement| {
+            let mut candidate = file.clone();
+            edit_expr(&mut candidate, index, |e| *e = replacement);
+            fails(&render(&candidate)).then_some(candidate)
+        });
+        match accepted {
+            // The replacement is now expression `index`; try it again.
+            Some(candidate) => {
+                *file = candidate;
+                changed = true;
+            }
+            None => index += 1,
+        }
+    }
+    changed
+}
+
+/// The expressions directly inside `expr`.
+fn operands(expr: &Spanned<Expr>) -> Vec<Spanned<Expr>> {
+    struct Operands(Vec<Spanned<Expr>>);
+    impl Visitor for Operands {
+        fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+            self.0.push(expr.clone());
+        }
+    }
+    let mut operands = Operands(Vec::new());
+    walk::expr(&mut operands, expr);
+    operands.0
+}
+
+/// The `index`th block of `file`, counting in source order.
+fn nth_block(file: &File, index: usize) -> Option<Block> {
+    struct Nth {
+        index: usize,
+        seen: usize,
+        found: Option<Block>,
+    }
+    impl Visitor for Nth {
+        fn visit_block(&mut self, block: &Block) {
+            if self.seen == self.index {
+                self.found = Some(block.clone());
+            }
+            self.seen += 1;
+            walk::block(self, block);
+        }
+    }
+    let mut nth = Nth {
+        index,
+        seen: 0,
+        found: None,
+    };
+    nth.visit_file(file);
+    nth.found
+}
+
+/// The `index`th expression of `file`, counting in source order.
+fn nth_expr(file: &File, index: usize) -> Option<Spanned<Expr>> {
+    struct Nth {
+        index: usize,
+        seen: usize,
+        found: Option<Spanned<Expr>>,
+    }
+    impl Visitor for Nth {
+        fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+            if self.seen == self.index {
+                self.found = Some(expr.clone());
+            }
+            self.seen += 1;
+            walk::expr(self, expr);
+        }
+    }
+    let mut nth = Nth {
+        index,
+        seen: 0,
+        found: None,
+    };
+    nth.visit_file(file);
+    nth.found
+}
+
+/// Apply `edit` to the `index`th block of `file`.
+fn edit_block(file: &mut File, index: usize, edit: impl FnOnce(&mut Block)) {
+    struct Edit<F> {
+        index: usize,
+        seen: usize,
+        edit: Option<F>,
+    }
+    impl<F: FnOnce(&mut Block)> MutVisitor for Edit<F> {
+        fn visit_block(&mut self, block: &mut Block) {
+            self.seen += 1;
+            if self.seen - 1 == self.index {
+                if let Some(edit) = self.edit.take() {
+                    edit(block);
+                }
+                return;
+            }
+            walk_mut::block(self, block);
+        }
+    }
+    let mut edit = Edit {
+        index,
+        seen: 0,
+        edit: Some(edit),
+    };
+    edit.visit_file(file);
+}
+
+/// Apply `edit` to the `index`th expression of `file`.
+fn edit_expr(file: &mut File, index: usize, edit: impl FnOnce(&mut Spanned<Expr>)) {
+    struct Edit<F> {
+        index: usize,
+        seen: usize,
+        edit: Option<F>,
+    }
+    impl<F: FnOnce(&mut Spanned<Expr>)> MutVisitor for Edit<F> {
+        fn visit_expr(&mut self, expr: &mut Spanned<Expr>) {
+            self.seen += 1;
+            if self.seen - 1 == self.index {
+                if let Some(edit) = self.edit.take() {
+                    edit(expr);
+                }
+                return;
+            }
+            walk_mut::expr(self, expr);
+        }
+    }
+    let mut edit = Edit {
+        index,
+        seen: 0,
+        edit: Some(edit),
+    };
+    edit.visit_file(file);
+}
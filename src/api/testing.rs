@@ -0,0 +1,277 @@
+//! `#[test]` discovery and execution.
+//!
+//! A project is lowered once with its test functions included; each test
+//! is then linked as the program entry and executed by the reference
+//! interpreter (`runtime::interp`) with empty input. A test passes when
+//! it halts — a failed assertion, exhausted input, or any other fault
+//! fails it.
+
+use std::path::PathBuf;
+
+use super::*;
+use crate::pipeline::PreparedProject;
+use crate::resolve::{find_os_dir, find_stdlib_dir, find_vm_dir};
+use crate::runtime::{Interpreter, ProgramInput};
+use crate::tir::linker::{fn_label, link_entry};
+
+/// Module namespaces of the libraries shipped with the compiler.
+const LIBRARY_ROOTS: [&str; 3] = ["std", "vm", "os"];
+
+/// Discover `#[test]` functions in a parsed file.
+pub fn discover_tests(file: &ast::File) -> Vec<String> {
+    let mut tests = Vec::new();
+    for item in &file.items {
+        if let ast::Item::Fn(func) = &item.node {
+            if func.is_test {
+                tests.push(func.name.node.clone());
+            }
+        }
+    }
+    tests
+}
+
+/// A single test result.
+#[derive(Clone, Debug)]
+pub struct TestResult {
+    pub name: String,
+    pub passed: bool,
+    pub cost: Option<cost::TableCost>,
+    pub error: Option<String>,
+}
+
+/// A test result with the cycles its execution took.
+struct TestRun {
+    result: TestResult,
+    cycles: Option<u64>,
+}
+
+/// Run all `#[test]` functions in a project.
+///
+/// Tests of the entry module and of project modules are run; tests of
+/// library modules the project imports (`std`, `vm`, `os`) are not —
+/// `run_std_tests` covers those.
+pub fn run_tests(entry_path: &Path, options: &CompileOptions) -> Result<String, Vec<Diagnostic>> {
+    let (runs, short_names) = execute_tests(entry_path, options, false)?;
+    if runs.is_empty() {
+        return Ok("No #[test] functions found.\n".to_string());
+    }
+    Ok(render_report(&runs, &short_names))
+}
+
+/// Run the `#[test]` functions of the libraries shipped with the
+/// compiler (`std/`, `vm/`, `os/`), the compiler's conformance suite.
+///
+/// Each library module with tests is compiled as its own entry and its
+/// tests are reported as `module::test`. A module that fails to compile
+/// is reported as one failed test.
+pub fn run_std_tests(options: &CompileOptions) -> Result<String, Vec<Diagnostic>> {
+    let dirs: Vec<PathBuf> = [find_stdlib_dir(), find_vm_dir(), find_os_dir()]
+        .into_iter()
+        .flatten()
+        .collect();
+    if dirs.is_empty() {
+        return Err(vec![Diagnostic::error(
+            "standard library not found; set TRIDENT_STDLIB to its `std/` directory".to_string(),
+            span::Span::dummy(),
+        )]);
+    }
+    let mut files = Vec::new();
+    for dir in &dirs {
+        collect_tri_files(dir, &mut files, 0);
+    }
+    files.sort();
+
+    let mut runs = Vec::new();
+    let mut short_names = Vec::new();
+    for path in files {
+        let has_tests = std::fs::read_to_string(&path).is_ok_and(|s| s.contains("#[test]"));
+        if !has_tests {
+            continue;
+        }
+        match execute_tests(&path, options, true) {
+            Ok((module_runs, names)) => {
+                if short_names.is_empty() {
+                    short_names = names;
+                }
+                runs.extend(module_runs);
+            }
+            Err(errors) => runs.push(TestRun {
+                result: TestResult {
+                    name: path.display().to_string(),
+                    passed: false,
+                    cost: None,
+                    error: Some(join_messages(&errors)),
+                },
+                cycles: None,
+            }),
+        }
+    }
+    if runs.is_empty() {
+        return Ok("No #[test] functions found in the standard library.\n".to_string());
+    }
+    Ok(render_report(&runs, &short_names))
+}
+
+/// Run all `#[test]` functions in a project and return their results,
+/// for callers that gate on them instead of printing the report.
+pub(super) fn test_results(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<Vec<TestResult>, Vec<Diagnostic>> {
+    let (runs, _) = execute_tests(entry_path, options, false)?;
+    Ok(runs.into_iter().map(|run| run.result).collect())
+}
+
+/// Compile the project at `entry_path` and run its tests. With
+/// `qualified`, only the entry module's tests run and they are named
+/// `module::test`.
+fn execute_tests(
+    entry_path: &Path,
+    options: &CompileOptions,
+    qualified: bool,
+) -> Result<(Vec<TestRun>, Vec<String>), Vec<Diagnostic>> {
+    let mut project = PreparedProject::build(entry_path, options)?;
+
+    // The entry module is last in topological order.
+    let entry = project.modules.len().saturating_sub(1);
+    let mut tests: Vec<(usize, String)> = Vec::new();
+    for (i, pm) in project.modules.iter().enumerate() {
+        let root = pm.file.name.node.split('.').next().unwrap_or("");
+        if i != entry && (qualified || LIBRARY_ROOTS.contains(&root)) {
+            continue;
+        }
+        for name in discover_tests(&pm.file) {
+            tests.push((i, name));
+        }
+    }
+    if tests.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    // Static cost of each test, alongside the measured cycles.
+    let target = &options.target_config.name;
+    let mut costs: BTreeMap<usize, cost::ProgramCost> = BTreeMap::new();
+    for &(i, _) in &tests {
+        costs.entry(i).or_insert_with(|| {
+            let imports = project.modules[..i].iter().map(|m| &m.file);
+            cost::CostAnalyzer::for_target(target)
+                .with_imports(imports)
+                .analyze_file(&project.modules[i].file)
+        });
+    }
+    let short_names = costs
+        .values()
+        .next()
+        .map(|c| c.table_short_names.clone())
+        .unwrap_or_default();
+
+    let modules = project.lower_modules(options, true);
+    let messages = project.error_codes().into_iter().map(|(m, code)| (code, m));
+    let interpreter = Interpreter::new()
+        .with_error_messages(messages)
+        .with_trace_printing(true);
+    let mut runs = Vec::with_capacity(tests.len());
+    for (i, name) in tests {
+        let module = &project.modules[i].file.name.node;
+        let tasm = link_entry(modules.clone(), &fn_label(module, &name));
+        let outcome = interpreter.run_tasm(&tasm, &ProgramInput::default());
+        let cost = costs
+            .get(&i)
+            .and_then(|pc| pc.functions.iter().find(|f| f.name == name).map(|f| f.cost));
+        let name = if qualified {
+            format!("{}::{}", module, name)
+        } else {
+            name
+        };
+        let (passed, cycles, error) = match outcome {
+            Ok(result) => (true, Some(result.cycle_count), None),
+            Err(e) => (false, None, Some(e)),
+        };
+        runs.push(TestRun {
+            result: TestResult {
+                name,
+                passed,
+                cost,
+                error,
+            },
+            cycles,
+        });
+    }
+    Ok((runs, short_names))
+}
+
+fn render_report(runs: &[TestRun], short_names: &[String]) -> String {
+    let mut report = String::new();
+    let total = runs.len();
+    let passed = runs.iter().filter(|r| r.result.passed).count();
+    let failed = total - passed;
+
+    report.push_str(&format!(
+        "running {} test{}\n",
+        total,
+        if total == 1 { "" } else { "s" }
+    ));
+
+    let sn: Vec<&str> = short_names.iter().map(|s| s.as_str()).collect();
+    for run in runs {
+        let result = &run.result;
+        let status = if result.passed { "ok" } else { "FAILED" };
+        let mut notes = Vec::new();
+        if let Some(cycles) = run.cycles {
+            notes.push(format!("{} cycles", cycles));
+        }
+        if let Some(ref c) = result.cost {
+            let ann = c.format_annotation(&sn);
+            if !ann.is_empty() {
+                notes.push(ann);
+            }
+        }
+        let notes = if notes.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", notes.join("; "))
+        };
+        report.push_str(&format!("  test {} ... {}{}\n", result.name, status, notes));
+        if let Some(ref err) = result.error {
+            report.push_str(&format!("    error: {}\n", err));
+        }
+    }
+
+    report.push('\n');
+    if failed == 0 {
+        report.push_str(&format!("test result: ok. {} passed; 0 failed\n", passed));
+    } else {
+        report.push_str(&format!(
+            "test result: FAILED. {} passed; {} failed\n",
+            passed, failed
+        ));
+    }
+    report
+}
+
+pub(super) fn join_messages(errors: &[Diagnostic]) -> String {
+    errors
+        .iter()
+        .map(|d| d.message.clone())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+const MAX_DIR_DEPTH: usize = 64;
+
+pub(super) fn collect_tri_files(dir: &Path, result: &mut Vec<PathBuf>, depth: usize) {
+    if depth >= MAX_DIR_DEPTH {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_tri_files(&path, result, depth + 1);
+        } else if path.extension().is_some_and(|e| e == "tri") {
+            result.push(path);
+        }
+    }
+}
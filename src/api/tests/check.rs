@@ -30,6 +30,52 @@ fn test_check_silent_parse_error() {
     assert!(check_silent(source, "test.tri").is_err());
 }
 
+#[test]
+fn test_unused_imports_in_project() {
+    let source = "program test\nuse std.hash\nuse std.io\nfn main() {\n    io.write(1)\n}";
+    let path = std::path::Path::new("/nonexistent/test.tri");
+    let options = CompileOptions::default();
+    assert_eq!(
+        unused_imports_in_project(source, path, &options),
+        vec!["std.hash"]
+    );
+}
+
+#[test]
+fn test_unused_exports_of_library_modules() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("util.tri"),
+        "module util\n\npub fn used(x: Field) -> Field {\n    helper(x)\n}\n\npub fn helper(x: Field) -> Field {\n    x + 1\n}\n\npub fn dead(x: Field) -> Field {\n    x * 2\n}\n\npub fn tested() -> Field {\n    3\n}\n\nfn private_dead() -> Field {\n    0\n}\n\n#[test]\nfn test_tested() {\n    assert_eq(tested(), 3)\n}\n",
+    )
+    .unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program test\nuse util\n\nfn main() {\n    pub_write(util.used(pub_read()))\n}\n",
+    )
+    .unwrap();
+    let report = unused_exports(&main_path, &CompileOptions::default()).expect("should check");
+    assert_eq!(report.exports, 4);
+    let found: Vec<(&str, UnusedKind, usize)> = report
+        .unused
+        .iter()
+        .map(|e| (e.name.as_str(), e.kind, e.line))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("helper", UnusedKind::Internal, 7),
+            ("dead", UnusedKind::Unreachable, 11),
+            ("tested", UnusedKind::Internal, 15),
+        ]
+    );
+    assert_eq!(
+        report.unused[1].diagnostic(true).message,
+        "public function 'util.dead' is never called by a program or test"
+    );
+}
+
 #[test]
 fn test_array_of_structs_type_check() {
     // Arrays of structs should type-check correctly
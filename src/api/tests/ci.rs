@@ -0,0 +1,150 @@
+use crate::*;
+
+const PROGRAM: &str = "program app\n\nfn main() {\n    pub_write(pub_read() + 1)\n}\n\n#[test]\nfn adds() {\n    assert(1 + 1 == 2)\n}\n";
+
+fn project(dir: &Path, source: &str) -> std::path::PathBuf {
+    let entry = dir.join("main.tri");
+    std::fs::write(&entry, source).unwrap();
+    entry
+}
+
+fn config(dir: &Path, stages: &[CiStage]) -> CiConfig {
+    let mut config = CiConfig::from_settings(&Default::default(), dir).unwrap();
+    config.stages = stages.to_vec();
+    config
+}
+
+fn statuses(report: &CiReport) -> Vec<(&str, StageStatus)> {
+    report
+        .stages
+        .iter()
+        .map(|s| (s.stage.as_str(), s.status))
+        .collect()
+}
+
+#[test]
+fn test_ci_stage_lists() {
+    assert_eq!(
+        CiStage::parse_list(&["verify", "check", "verify"]).unwrap(),
+        vec![CiStage::Check, CiStage::Verify]
+    );
+    let err = CiStage::parse_list(&["lint"]).unwrap_err();
+    assert!(err.contains("unknown ci stage 'lint'"), "{}", err);
+
+    let config = CiConfig::from_settings(&Default::default(), Path::new(".")).unwrap();
+    assert_eq!(config.stages, CiStage::ALL.to_vec());
+}
+
+#[test]
+fn test_ci_passes_a_clean_project() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = format_source(PROGRAM, "main.tri").unwrap();
+    let entry = project(dir.path(), &source);
+    let stages = [CiStage::Check, CiStage::Fmt, CiStage::Test, CiStage::Cost];
+    let report = run_ci(
+        &entry,
+        &CompileOptions::default(),
+        &config(dir.path(), &stages),
+    );
+    assert_eq!(
+        statuses(&report),
+        vec![
+            ("check", StageStatus::Pass),
+            ("fmt", StageStatus::Pass),
+            ("test", StageStatus::Pass),
+            ("cost", StageStatus::Pass),
+        ]
+    );
+    assert_eq!(report.stages[2].detail, "1 test passed");
+    assert!(report.passed());
+}
+
+#[test]
+fn test_ci_skips_compiling_stages_after_check_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = project(
+        dir.path(),
+        "program app\nfn main() {\n  let x: U32 = pub_read()\n}\n",
+    );
+    let stages = [CiStage::Check, CiStage::Fmt, CiStage::Test];
+    let report = run_ci(
+        &entry,
+        &CompileOptions::default(),
+        &config(dir.path(), &stages),
+    );
+    assert_eq!(
+        statuses(&report),
+        vec![
+            ("check", StageStatus::Fail),
+            ("fmt", StageStatus::Fail),
+            ("test", StageStatus::Skip),
+        ]
+    );
+    assert_eq!(
+        report.stages[1].detail,
+        "1 of 1 files need formatting: main.tri"
+    );
+    assert!(!report.passed());
+
+    let json = report.to_json();
+    assert!(json.starts_with(
+        "{\"passed\": false, \"stages\": [{\"stage\": \"check\", \"status\": \"fail\""
+    ));
+    assert!(
+        json.contains("{\"stage\": \"test\", \"status\": \"skip\", \"detail\": \"check failed\"")
+    );
+    let markdown = report.to_markdown();
+    assert!(markdown.starts_with("### trident ci: failed\n"));
+    assert!(markdown.contains("| test | ⏭️ skip | check failed |"));
+}
+
+#[test]
+fn test_ci_cost_gates() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = project(dir.path(), PROGRAM);
+    let current = analyze_costs_project(&entry, &CompileOptions::default()).unwrap();
+    let mut config = config(dir.path(), &[CiStage::Cost]);
+
+    // Padded-height ceiling.
+    config.max_padded_height = Some(current.padded_height);
+    assert_eq!(cost_gate(&current, None, &config).status, StageStatus::Pass);
+    config.max_padded_height = Some(current.padded_height - 1);
+    let result = cost_gate(&current, None, &config);
+    assert_eq!(result.status, StageStatus::Fail);
+    assert!(
+        result.detail.contains("exceeds max_padded_height"),
+        "{}",
+        result.detail
+    );
+    config.max_padded_height = None;
+
+    // Growth of the tallest table over the baseline: 110 rows against
+    // 100 passes with a 10% allowance and fails with none; shrinking
+    // always passes.
+    let mut grown = current.clone();
+    grown.total = TableCost::from_slice(&[110, 40]);
+    let mut baseline = current.clone();
+    baseline.total = TableCost::from_slice(&[100, 40]);
+    let result = cost_gate(&grown, Some(&baseline), &config);
+    assert_eq!(result.status, StageStatus::Fail);
+    assert!(
+        result
+            .detail
+            .ends_with("tallest table 110 rows (+10), over the allowed 0% increase"),
+        "{}",
+        result.detail
+    );
+    config.max_cost_increase = Some(10);
+    assert_eq!(
+        cost_gate(&grown, Some(&baseline), &config).status,
+        StageStatus::Pass
+    );
+    config.max_cost_increase = None;
+    let result = cost_gate(&baseline, Some(&grown), &config);
+    assert_eq!(result.status, StageStatus::Pass);
+    assert!(
+        result.detail.ends_with("tallest table 100 rows (-10)"),
+        "{}",
+        result.detail
+    );
+}
@@ -399,3 +399,482 @@ fn test_card_compiles() {
         "should authenticate leaves against Merkle root"
     );
 }
+
+#[test]
+fn test_tuple_destructuring_uses_declared_element_widths() {
+    // (Field, Digest) is 6 wide: `x` is the deepest word, not a 3-wide slice.
+    let source = "program test\nfn pair() -> (Field, Digest) {\n    (7, divine5())\n}\nfn main() {\n    let (x, d) = pair()\n    pub_write(x)\n}";
+    let tasm = compile(source, "test.tri").expect("should compile");
+    assert!(
+        tasm.contains("call __pair\n    dup 5\n    write_io 1"),
+        "{}",
+        tasm
+    );
+}
+
+#[test]
+fn test_cross_module_calls_push_their_return_width() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("helper.tri"),
+        "module helper\n\npub fn two() -> Field {\n    2\n}\n\npub fn pair() -> (Field, Digest) {\n    (7, divine5())\n}\n",
+    )
+    .unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program test\nuse helper\n\nfn main() {\n    let a: Field = pub_read()\n    let t: Field = helper.two()\n    let (x, d) = helper.pair()\n    pub_write(a + t + x)\n}\n",
+    )
+    .unwrap();
+    let tasm = compile_project(&main_path).expect("should compile");
+    // Stack after the calls: a, t, x, d (5 words) — `a` sits 7 deep.
+    assert!(
+        tasm.contains("call helper__pair\n    dup 7\n    dup 7\n    add\n    dup 6"),
+        "{}",
+        tasm
+    );
+}
+
+#[test]
+fn test_cross_module_generic_calls_emit_their_instances() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("lib.tri"),
+        "module lib\n\npub const WIDTH: U32 = 2\n\npub struct Cell {\n    v: Field,\n}\n\npub fn last<N>(xs: [Field; N + 1]) -> Field {\n    xs[N]\n}\n\npub fn pick<N>(xs: [Field; N * WIDTH]) -> Field {\n    xs[N]\n}\n\npub fn get<N>(cells: [Cell; N]) -> Field {\n    let c: Cell = cells[0]\n    c.v\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("util.tri"),
+        "module util\nuse lib\n\npub fn tail(a: Field) -> Field {\n    lib.last([a, a + 1])\n}\n",
+    )
+    .unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program test\nuse lib\nuse util\n\nfn main() {\n    let a: [Field; 3] = [pub_read(), 2, 3]\n    pub_write(lib.last(a))\n    pub_write(lib.last<1>([7, 8]))\n    pub_write(util.tail(4))\n    let m: [Field; 4] = [5, 6, 7, 8]\n    pub_write(lib.pick(m))\n    let c: lib.Cell = lib.Cell { v: 9 }\n    pub_write(lib.get([c]))\n}\n",
+    )
+    .unwrap();
+    let tasm = compile_project(&main_path).expect("should compile");
+    // `util` and `test` both call `lib.last<1>`: `lib` emits it once.
+    assert_eq!(tasm.matches("lib__last__N1:").count(), 1, "{}", tasm);
+    assert!(tasm.contains("lib__last__N2:") && tasm.contains("lib__pick__N2:"));
+    let input = ProgramInput {
+        public: vec![1],
+        ..ProgramInput::default()
+    };
+    let output = Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output);
+    assert_eq!(output, Ok(vec![3, 8, 5, 7, 9]));
+}
+
+#[test]
+fn test_entrypoints_dispatch_on_selector() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let source = "program test\npub output: Field\n#[entrypoint]\nfn pay() {\n    pub_write(pub_read() + 1)\n}\n#[entrypoint(5)]\nfn mint() {\n    pub_write(pub_read() * 2)\n}\n#[entrypoint]\nfn burn() {\n    pub_write(0)\n}";
+    let tasm = compile(source, "test.tri").expect("should compile");
+    let run = |selector: u64| {
+        let input = ProgramInput {
+            public: vec![selector, 10],
+            ..ProgramInput::default()
+        };
+        Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output)
+    };
+    assert_eq!(run(0), Ok(vec![11]));
+    assert_eq!(run(1), Ok(vec![0]));
+    assert_eq!(run(5), Ok(vec![20]));
+    let err = run(9).unwrap_err();
+    assert!(err.contains("assertion failed"), "{}", err);
+}
+
+#[test]
+fn test_loop_over_array_len_and_constant_expression() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let source = "program test\nconst N: Field = 2\nfn main() {\n    let arr: [Field; 3] = [5, 6, 7]\n    for i in 0..arr.len() {\n        pub_write(arr[i])\n    }\n    let mut n: Field = 0\n    for _ in 0..N * 2 {\n        n = n + 1\n    }\n    pub_write(n + arr.len())\n}";
+    let tasm = compile(source, "test.tri").expect("should compile");
+    let output = Interpreter::new()
+        .run_tasm(&tasm, &ProgramInput::default())
+        .map(|o| o.output);
+    assert_eq!(output, Ok(vec![5, 6, 7, 7]));
+}
+
+#[test]
+fn test_pure_call_sizes_array_and_loop() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let source = "program test\n#[pure]\nfn double(x: Field) -> Field {\n    x + x\n}\nconst N: Field = double(2)\nfn main() {\n    let arr: [Field; N] = [1, 2, 3, 4]\n    let mut sum: Field = 0\n    for i in 0..N {\n        sum = sum + arr[i]\n    }\n    for _ in 0..double(N) {\n        sum = sum + 1\n    }\n    pub_write(sum)\n}";
+    let tasm = compile(source, "test.tri").expect("should compile");
+    let output = Interpreter::new()
+        .run_tasm(&tasm, &ProgramInput::default())
+        .map(|o| o.output);
+    assert_eq!(output, Ok(vec![18]));
+}
+
+#[test]
+fn test_struct_defaults_and_update_syntax() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let source = "program test\nstruct Config {\n    owner: Field,\n    fee: Field = 7,\n    limit: U32 = 10,\n}\nfn main() {\n    let base: Config = Config { owner: pub_read() }\n    let next: Config = Config { limit: as_u32(20), ..base }\n    pub_write(next.owner)\n    pub_write(next.fee)\n    pub_write(as_field(next.limit))\n    pub_write(as_field(base.limit))\n}";
+    let tasm = compile(source, "test.tri").expect("should compile");
+    let input = ProgramInput {
+        public: vec![3],
+        ..Default::default()
+    };
+    let output = Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output);
+    assert_eq!(output, Ok(vec![3, 7, 20, 10]));
+}
+
+#[test]
+fn test_asm_alternatives_build_only_the_target_block() {
+    let source = "program test\nfn main() {\n    asm(miden, +1) { push.7 }\n    asm(triton, +1) { push 7 }\n    asm(+1) { push 8 }\n    pub_write(pub_read())\n    asm(miden) { exec.sys::truncate_stack }\n    asm { nop }\n}";
+    let tasm = compile(source, "test.tri").expect("should compile");
+    assert!(tasm.contains("push 7"), "{}", tasm);
+    assert!(!tasm.contains("push 8"), "{}", tasm);
+    assert!(!tasm.contains("push.7"), "{}", tasm);
+    // No triton block in the second run: the fallback is built.
+    assert!(tasm.contains("nop"), "{}", tasm);
+    assert!(!tasm.contains("truncate_stack"), "{}", tasm);
+}
+
+#[test]
+fn test_newtype_lowers_like_its_field() {
+    let newtype = "program test\nstruct Amount(Field)\nstruct Marker\nfn add(a: Amount, b: Amount) -> Amount {\n    Amount(a.0 + b.0)\n}\nfn main() {\n    let _m: Marker = Marker\n    let a = Amount(pub_read())\n    let b: Amount = Amount(pub_read())\n    let c: Amount = add(a, b)\n    pub_write(c.0)\n}";
+    let plain = "program test\nfn add(a: Field, b: Field) -> Field {\n    a + b\n}\nfn main() {\n    let a = pub_read()\n    let b: Field = pub_read()\n    let c: Field = add(a, b)\n    pub_write(c)\n}";
+    assert_eq!(
+        compile(newtype, "test.tri").expect("newtype program should compile"),
+        compile(plain, "test.tri").expect("plain program should compile")
+    );
+}
+
+#[test]
+fn test_overflow_aware_addition() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+    let source = "program test\nfn main() {\n    let a: U32 = as_u32(pub_read())\n    let b: U32 = as_u32(pub_read())\n    pub_write(as_field(u32_wrapping_add(a, b)))\n    match u32_checked_add(a, b) {\n        MaybeU32.Some(sum) => {\n            pub_write(1)\n            pub_write(as_field(sum))\n        }\n        MaybeU32.None => { pub_write(0) }\n    }\n    match field_checked_add(pub_read(), pub_read()) {\n        MaybeField.Some(x) => {\n            pub_write(1)\n            pub_write(x)\n        }\n        MaybeField.None => { pub_write(0) }\n    }\n    pub_write(field_assert_add(pub_read(), pub_read()))\n    pub_write(as_field(u32_assert_add(a, b)))\n}";
+    let tasm = compile(source, "test.tri").expect("additions should compile");
+    let run = |public: Vec<u64>| {
+        let input = ProgramInput {
+            public,
+            ..Default::default()
+        };
+        Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output)
+    };
+    let p_minus_1 = 18446744069414584320;
+    assert_eq!(
+        run(vec![40, 2, 5, 7, 1, 2]),
+        Ok(vec![42, 1, 42, 1, 12, 3, 42])
+    );
+    // The field sum wraps past p; `field_assert_add` still fits.
+    assert_eq!(
+        run(vec![40, 2, p_minus_1, 2, p_minus_1, 0]),
+        Ok(vec![42, 1, 42, 0, p_minus_1, 42])
+    );
+    // A U32 carry: the wrapping sum is kept, the checked one is `None`,
+    // and the assertion fails.
+    let max = u32::MAX as u64;
+    assert!(run(vec![max, 3, 0, 0, 0, 0]).is_err());
+    let tasm_wrap = compile(
+        "program test\nfn payload(m: MaybeU32) -> Field {\n    let mut v: Field = 7\n    match m {\n        MaybeU32.Some(x) => { v = as_field(x) }\n        MaybeU32.None => {}\n    }\n    v\n}\nfn main() {\n    let a: U32 = as_u32(pub_read())\n    let m: MaybeU32 = u32_checked_add(a, as_u32(3))\n    pub_write(payload(m))\n    pub_write(as_field(u32_wrapping_add(a, as_u32(3))))\n    pub_write(payload(u32_checked_add(a, as_u32(0))))\n}",
+        "test.tri",
+    )
+    .expect("wrapping addition should compile");
+    let input = ProgramInput {
+        public: vec![max],
+        ..Default::default()
+    };
+    assert_eq!(
+        Interpreter::new()
+            .run_tasm(&tasm_wrap, &input)
+            .map(|o| o.output),
+        Ok(vec![7, 2, max])
+    );
+    // Overflowing `field_assert_add` fails.
+    assert!(run(vec![1, 2, 0, 0, p_minus_1, 1]).is_err());
+}
+
+#[test]
+fn test_short_circuit_and_strict_logic() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+    let source = "program test\nfn show(b: Bool) {\n    if b {\n        pub_write(1)\n    } else {\n        pub_write(0)\n    }\n}\nfn guarded(x: U32) -> Bool {\n    assert(x < as_u32(10))\n    true\n}\nfn main() {\n    let a: U32 = as_u32(pub_read())\n    let b: U32 = as_u32(pub_read())\n    show(a < as_u32(5) && b < as_u32(5))\n    show(a < as_u32(5) || b < as_u32(5))\n    show((a < as_u32(5)) & (b < as_u32(5)))\n    show((a < as_u32(5)) | (b < as_u32(5)))\n    pub_write(as_field(a | b))\n    show(a < as_u32(10) && guarded(b))\n    show(as_u32(10) < a || guarded(b))\n}";
+    let tasm = compile(source, "test.tri").expect("logical operators should compile");
+    let run = |public: Vec<u64>| {
+        let input = ProgramInput {
+            public,
+            ..Default::default()
+        };
+        Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output)
+    };
+    assert_eq!(run(vec![3, 7]), Ok(vec![0, 1, 0, 1, 7, 1, 1]));
+    // Both right sides are skipped, so `guarded(50)` never asserts.
+    assert_eq!(run(vec![20, 50]), Ok(vec![0, 0, 0, 0, 54, 0, 1]));
+    assert!(run(vec![3, 50]).is_err());
+}
+
+#[test]
+fn test_digest_indexing_conversions_and_equality() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+    let source = "program test\nconst LAST: U32 = 4\nfn matches(d: Digest) -> Bool {\n    d == Digest::from_fields(pub_read(), pub_read(), pub_read(), pub_read(), pub_read())\n}\nfn third(arr: [Field; 5]) -> Field {\n    let back: Digest = Digest::from_array(arr)\n    back[2]\n}\nfn main() {\n    let d: Digest = divine5()\n    pub_write(d[0])\n    pub_write(d[LAST])\n    let arr: [Field; 5] = Digest::to_array(d)\n    pub_write(arr[1])\n    pub_write(third(arr))\n    if matches(d) {\n        pub_write(1)\n    } else {\n        pub_write(0)\n    }\n}";
+    let tasm = compile(source, "test.tri").expect("digest helpers should compile");
+    let run = |secret: Vec<u64>| {
+        let input = ProgramInput {
+            public: vec![1, 2, 3, 4, 5],
+            secret,
+            ..Default::default()
+        };
+        Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output)
+    };
+    assert_eq!(run(vec![1, 2, 3, 4, 5]), Ok(vec![1, 5, 2, 3, 1]));
+    assert_eq!(run(vec![1, 2, 3, 9, 5]), Ok(vec![1, 5, 2, 3, 0]));
+    assert_eq!(run(vec![9, 2, 3, 4, 5]), Ok(vec![9, 5, 2, 3, 0]));
+}
+
+#[test]
+fn test_build_target_reports_uncovered_ops() {
+    assert!(registered_targets().contains(&"triton".to_string()));
+
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program test\nuse os.neptune.xfield\n\nfn main() {\n    let p: Field = pub_read()\n    let d: Digest = xfield.xx_dot_step(0, 0, 0, p, p)\n    pub_write(p)\n}\n",
+    )
+    .unwrap();
+    let options = CompileOptions::default();
+    let triton = build_target(&main_path, &options).expect("should build");
+    assert!(triton.is_portable(), "{:?}", triton.uncovered);
+
+    let mut no_xfield = options.clone();
+    no_xfield.target_config.xfield_width = 0;
+    let build = build_target(&main_path, &no_xfield).expect("should build");
+    assert_eq!(build.uncovered.get("fold_ext"), Some(&1));
+    assert_eq!(build.instructions, triton.instructions);
+
+    let matrix = format_target_matrix(&[triton, build]);
+    assert!(matrix.contains("1 uncovered"), "{}", matrix);
+    assert!(matrix.contains("fold_ext (x1)"), "{}", matrix);
+}
+
+#[test]
+fn test_lowering_coverage_of_triton_and_missing_backends() {
+    let triton = lowering_coverage_for("triton", "StackLowering");
+    let ops = triton.ops.as_ref().expect("triton has a stack backend");
+    assert_eq!(ops.len(), 54);
+    assert_eq!(triton.lowered(), 54, "{:?}", ops);
+
+    let x86 = lowering_coverage_for("x86-64", "RegisterLowering");
+    assert!(x86.ops.is_none());
+
+    let table = format_lowering_coverage(&[triton, x86]);
+    assert!(table.contains("ExtMul"), "{}", table);
+    assert!(table.contains("54/54"), "{}", table);
+    assert!(
+        table.contains("no backend: x86-64 (RegisterLowering)"),
+        "{}",
+        table
+    );
+}
+
+#[test]
+fn test_program_split_across_files() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program coin\n\nconst FEE: Field = 3\n\nfn main() {\n    let amount: Field = pub_read()\n    pay(amount)\n    pub_write(net(amount))\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("ops.tri"),
+        "program coin\nuse helper\n\nevent Paid {\n    amount: Field,\n}\n\nfn net(amount: Field) -> Field {\n    let mut n: Field = helper.double(amount)\n    for _ in 0..FEE {\n        n = n + 1\n    }\n    n\n}\n\nfn pay(amount: Field) {\n    reveal Paid { amount: amount }\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("helper.tri"),
+        "module helper\n\npub fn double(x: Field) -> Field {\n    x * 2\n}\n",
+    )
+    .unwrap();
+    let tasm = compile_project(&main_path).expect("should compile");
+    let input = ProgramInput {
+        public: vec![5],
+        ..ProgramInput::default()
+    };
+    let output = Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output);
+    assert_eq!(output, Ok(vec![0, 5, 13]));
+
+    // Errors in a part point into that file, as the editor has it.
+    let ops = dir.path().join("ops.tri");
+    let edited = "program coin\n\nfn net(amount: Field) -> Field {\n    amount + MISSING\n}\n\nfn pay(amount: Field) {}\n";
+    let errors = check_file_in_project(edited, &ops).unwrap_err();
+    assert!(
+        errors.iter().any(|e| e.message.contains("MISSING")),
+        "{:?}",
+        errors
+    );
+    let span = errors[0].span;
+    assert_eq!(&edited[span.start as usize..span.end as usize], "MISSING");
+}
+
+#[test]
+fn test_include_data_tables() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program dt\nuse tables\n\nconst SQ: [Field; 16] = include_data(\"sq.json\")\nconst B: [U32; 3] = include_data(\"bytes.bin\")\nconst FEE: Field = 4\n\nfn main() {\n    let i: Field = pub_read()\n    pub_write(SQ[3])\n    pub_write(SQ[i] + SQ[i + 1])\n    pub_write(as_field(B[2]))\n    pub_write(tables.CUBE[i])\n    pub_write(tables.CUBE[2] + FEE)\n    let cube: [Field; 4] = tables.CUBE\n    pub_write(cube[3])\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("tables.tri"),
+        "module tables\n\npub const CUBE: [Field; 4] = include_data(\"cube.json\")\n",
+    )
+    .unwrap();
+    let squares: Vec<String> = (0..16u64).map(|i| (i * i + 1).to_string()).collect();
+    std::fs::write(
+        dir.path().join("sq.json"),
+        format!("[{}]", squares.join(", ")),
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("bytes.bin"), [7u8, 8, 9]).unwrap();
+    std::fs::write(dir.path().join("cube.json"), "[0, 1, 8, 27]\n").unwrap();
+
+    let tasm = compile_project(&main_path).expect("should compile");
+    // Tables read at runtime indices are stored once, ahead of the entry.
+    assert!(tasm.contains("call __data"), "{}", tasm);
+    let input = ProgramInput {
+        public: vec![2],
+        ..ProgramInput::default()
+    };
+    let output = Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output);
+    assert_eq!(output, Ok(vec![10, 15, 9, 8, 12, 27]));
+
+    // The bundle names every embedded table and the hash of its values.
+    let bundle = compile_to_bundle(&main_path, &CompileOptions::default()).unwrap();
+    let names: Vec<&str> = bundle.data.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["tables.CUBE", "dt.SQ", "dt.B"]);
+    assert_eq!(bundle.data[0].source, "cube.json");
+    assert_eq!(bundle.data[0].length, 4);
+    assert_ne!(bundle.data[0].hash, bundle.data[1].hash);
+
+    // Compiled on its own, the file pushes the table for each read.
+    let source = "program one\nconst CUBE: [Field; 4] = include_data(\"cube.json\")\nfn main() {\n    pub_write(CUBE[pub_read()])\n}\n";
+    let filename = dir.path().join("one.tri");
+    let tasm = compile(source, &filename.to_string_lossy()).expect("should compile");
+    let output = Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output);
+    assert_eq!(output, Ok(vec![8]));
+}
+
+#[test]
+fn test_emit_ast_and_tir_dumps() {
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = dir.path().join("main.tri");
+    let source = "program dump\n\nfn main() {\n    let x: Field = divine() + divine()\n    if x == 1 {\n        pub_write(x)\n    }\n}\n";
+    std::fs::write(&main_path, source).unwrap();
+    let options = CompileOptions::default();
+
+    let ast = dump_ast_project(&main_path, &options).unwrap();
+    assert_eq!(ast.len(), 1);
+    let (name, tree) = &ast[0];
+    assert_eq!(name, "dump");
+    assert!(
+        tree.starts_with(
+            "program dump @8..12\n  fn main @14..109\n    body @24..109\n      let x @30..64\n"
+        ),
+        "{}",
+        tree
+    );
+    assert!(tree.contains("        binop + @45..64\n          call divine @45..53\n          call divine @56..64\n"), "{}", tree);
+
+    let raw = &dump_tir_project(&main_path, &options, false).unwrap()[0].1;
+    let optimized = &dump_tir_project(&main_path, &options, true).unwrap()[0].1;
+    assert!(
+        raw.contains("fn_start main\n  hint 1\n  hint 1\n  add\n"),
+        "{}",
+        raw
+    );
+    assert!(
+        raw.contains("  if_only\n    dup 0\n    write_io 1\n"),
+        "{}",
+        raw
+    );
+    // The optimizer merges the two hints.
+    assert!(
+        optimized.contains("fn_start main\n  hint 2\n  add\n"),
+        "{}",
+        optimized
+    );
+}
+
+#[test]
+fn test_while_loop_exits_early_or_at_its_bound() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let source = "program test\nfn main() {\n    let target: U32 = as_u32(pub_read())\n    let mut i: Field = 0\n    let mut sum: Field = 0\n    while as_u32(i) < target bounded 8 {\n        let step: Field = 2\n        sum = sum + step\n        i = i + 1\n    }\n    pub_write(i)\n    pub_write(sum)\n}";
+    let tasm = compile(source, "test.tri").expect("should compile");
+    let run = |target: u64| {
+        let input = ProgramInput {
+            public: vec![target],
+            ..ProgramInput::default()
+        };
+        Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output)
+    };
+    assert_eq!(run(3), Ok(vec![3, 6]));
+    assert_eq!(run(0), Ok(vec![0, 0]));
+    // The bound stops the loop even though the condition still holds.
+    assert_eq!(run(20), Ok(vec![8, 16]));
+}
+
+#[test]
+fn test_break_and_continue_skip_the_rest_of_the_loop() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let source = "program test\nfn main() {\n    let n: U32 = as_u32(pub_read())\n    let skip: U32 = as_u32(pub_read())\n    let mut sum: Field = 0\n    for i in 0..8 bounded 8 {\n        if i == skip {\n            continue\n        }\n        if i < n {\n            sum = sum + 1\n        } else {\n            break\n        }\n        sum = sum + 10\n    }\n    let mut j: Field = 0\n    while j == j bounded 5 {\n        j = j + 1\n        if as_u32(j) < n {\n            continue\n        }\n        break\n    }\n    pub_write(sum)\n    pub_write(j)\n}";
+    let tasm = compile(source, "test.tri").expect("should compile");
+    let run = |n: u64, skip: u64| {
+        let input = ProgramInput {
+            public: vec![n, skip],
+            ..ProgramInput::default()
+        };
+        Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output)
+    };
+    assert_eq!(run(3, 9), Ok(vec![33, 3]));
+    // `continue` skips the rest of iteration 1 only.
+    assert_eq!(run(3, 1), Ok(vec![22, 3]));
+    assert_eq!(run(0, 9), Ok(vec![0, 1]));
+    assert_eq!(run(20, 9), Ok(vec![88, 5]));
+}
+
+#[test]
+fn test_while_condition_not_evaluated_after_break() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let source = "program test\nfn main() {\n    let mut n: Field = 0\n    while pub_read() == 1 bounded 8 {\n        n = n + 1\n        if n == 2 {\n            break\n        }\n    }\n    pub_write(n)\n    pub_write(pub_read())\n}";
+    let tasm = compile(source, "test.tri").expect("should compile");
+    let run = |public: Vec<u64>| {
+        let input = ProgramInput {
+            public,
+            ..ProgramInput::default()
+        };
+        Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output)
+    };
+    // After the `break`, the next input is left for the read after the loop.
+    assert_eq!(run(vec![1, 1, 7]), Ok(vec![2, 7]));
+    assert_eq!(run(vec![1, 0, 7]), Ok(vec![1, 7]));
+}
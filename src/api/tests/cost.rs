@@ -37,10 +37,11 @@ fn test_coin_cost_analysis() {
         "token should have u32 table cost for range checks"
     );
 
-    // Padded height should be reasonable (power of 2)
+    // Padded height should be reasonable (power of 2). Merkle paths are
+    // charged at their declared worst case of 64 levels.
     assert!(cost.padded_height.is_power_of_two());
     assert!(
-        cost.padded_height <= 4096,
+        cost.padded_height <= 16384,
         "padded height {} seems too high",
         cost.padded_height
     );
@@ -177,3 +178,314 @@ fn test_comparison_formatting_integration() {
     );
 }
 
+#[test]
+fn test_recursive_verifier_ops_charge_hash_rows() {
+    let source = "program test\nfn main() {\n    sponge_init()\n    let d: Digest = proof_item_digest()\n    let x: XField = proof_item_xfe()\n    let c: Digest = encode_claim(d, 1, 1)\n}";
+    let cost = analyze_costs(source, "test.tri").expect("cost analysis should succeed");
+    // sponge_init (1 row) + two absorbed items + one claim hash (6 rows each).
+    assert_eq!(cost.total.get(1), 19);
+    assert!(cost.total.get(0) >= 28);
+}
+
+#[test]
+fn test_while_loop_charged_for_its_bound() {
+    let source = "program test\nfn main() {\n    let x: Field = pub_read()\n    let mut go: Bool = true\n    while go bounded 4 {\n        let d: Digest = hash(x, 0, 0, 0, 0, 0, 0, 0, 0, 0)\n        go = x == 0\n    }\n}";
+    let cost = analyze_costs(source, "test.tri").expect("cost analysis should succeed");
+    // Every iteration may run: 4 hashes of 6 rows.
+    assert_eq!(cost.total.get(1), 24);
+}
+
+#[test]
+fn test_constant_bound_charged_for_its_value() {
+    let source = "program test\nconst N: Field = 3\nfn main() {\n    let x: Field = pub_read()\n    for _ in 0..x bounded N * 2 {\n        let d: Digest = hash(x, 0, 0, 0, 0, 0, 0, 0, 0, 0)\n    }\n}";
+    let cost = analyze_costs(source, "test.tri").expect("cost analysis should succeed");
+    // The bound folds to 6: 6 hashes of 6 rows.
+    assert_eq!(cost.total.get(1), 36);
+}
+
+#[test]
+fn test_loop_with_break_charged_for_its_bound() {
+    let source = "program test\nfn main() {\n    let x: Field = pub_read()\n    for i in 0..4 bounded 4 {\n        if x == 0 {\n            break\n        }\n        let d: Digest = hash(x, 0, 0, 0, 0, 0, 0, 0, 0, 0)\n    }\n}";
+    let cost = analyze_costs(source, "test.tri").expect("cost analysis should succeed");
+    // A `break` skips the rest of the loop, but every iteration still runs.
+    assert_eq!(cost.total.get(1), 24);
+}
+
+#[test]
+fn test_loop_exits_charged_for_their_flags() {
+    let source = "program test\nfn main() {\n    let x: Field = pub_read()\n    for i in 0..4 bounded 4 {\n        if x == 0 {\n            break\n        }\n        pub_write(x)\n    }\n}";
+    // The same loop with the skip flags the compiler inserts written out.
+    let flagged = "program test\nfn main() {\n    let x: Field = pub_read()\n    let mut running: Bool = true\n    for i in 0..4 bounded 4 {\n        if running {\n            let mut live: Bool = true\n            if x == 0 {\n                running = false\n                live = false\n            }\n            if live {\n                pub_write(x)\n            }\n        }\n    }\n}";
+    let cost = analyze_costs(source, "test.tri").expect("cost analysis should succeed");
+    let expected = analyze_costs(flagged, "test.tri").expect("cost analysis should succeed");
+    assert_eq!(cost.total, expected.total);
+}
+
+#[test]
+fn test_sponge_api_charges_hash_rows_per_call() {
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program test\nuse vm.crypto.hash\n\nfn main() {\n    let s0: hash.Sponge = hash.init()\n    let s1: hash.Sponge = hash.absorb(s0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10)\n    let s2: hash.Sponge = hash.absorb_mem(s1, 0)\n    let out: [Field; 10] = hash.squeeze(s2)\n    pub_write(out[0])\n}\n",
+    )
+    .unwrap();
+    let cost = analyze_costs_project(&main_path, &CompileOptions::default())
+        .expect("cost analysis should succeed");
+    // init (1 row) + absorb, absorb_mem, squeeze (6 rows each).
+    assert_eq!(cost.total.get(1), 19);
+}
+
+#[test]
+fn project_costs_are_attributed_to_owning_modules_and_reused() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("helper.tri"),
+        "module helper\n\npub fn double(x: Field) -> Field {\n    x + x\n}\n",
+    )
+    .unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program test\nuse helper\n\nfn main() {\n    pub_write(helper.double(pub_read()))\n}\n",
+    )
+    .unwrap();
+
+    let first = analyze_costs_project(&main_path, &CompileOptions::default()).unwrap();
+    let modules: Vec<&str> = first.modules.iter().map(|m| m.module.as_str()).collect();
+    assert_eq!(modules, vec!["helper", "test"]);
+    assert_eq!(first.modules[0].functions[0].name, "double");
+    assert!(first.modules[0].functions[0].cost.get(0) > 0);
+
+    timings::start();
+    let second = analyze_costs_project(&main_path, &CompileOptions::default()).unwrap();
+    let log = timings::finish();
+    let content = log
+        .caches
+        .iter()
+        .find(|c| c.name == "function cost (content)")
+        .expect("content cache consulted");
+    assert_eq!((content.hits, content.lookups), (2, 2));
+    assert_eq!(second.total.get(0), first.total.get(0));
+    assert!(second.format_hotspots(5).contains("helper.double"));
+}
+
+#[test]
+fn test_entrypoint_costs_follow_each_operation() {
+    let source = "program test\n#[entrypoint]\nfn ping() {\n    pub_write(pub_read())\n}\n#[entrypoint(3)]\nfn digest() {\n    for i in 0..64 {\n        let h: Digest = hash(1, 2, 3, 4, 5, 6, 7, 8, 9, 10)\n    }\n}";
+    let cost = analyze_costs(source, "test.tri").expect("cost analysis should succeed");
+    let table: Vec<(u64, &str)> = cost
+        .entrypoints
+        .iter()
+        .map(|e| (e.selector, e.name.as_str()))
+        .collect();
+    assert_eq!(table, vec![(0, "ping"), (3, "digest")]);
+    let (ping, digest) = (&cost.entrypoints[0], &cost.entrypoints[1]);
+    // The total is the worst operation; the cheap one pads far lower.
+    assert_eq!(digest.total, cost.total);
+    assert_eq!(digest.padded_height, cost.padded_height);
+    assert_eq!(ping.total.get(1), 0);
+    assert!(ping.padded_height < digest.padded_height);
+    assert!(cost.format_report().contains("   0  ping"));
+
+    let parsed = cost::ProgramCost::from_json(&cost.to_json()).expect("should parse");
+    assert_eq!(parsed.entrypoints.len(), 2);
+    assert_eq!(parsed.entrypoints[1].name, "digest");
+    assert_eq!(parsed.entrypoints[1].total, digest.total);
+    assert_eq!(parsed.entrypoints[1].padded_height, digest.padded_height);
+}
+
+#[test]
+fn test_what_if_hint_for_dominant_table() {
+    let source = "program test\nfn main() {\n    let x: Field = pub_read()\n    for i in 0..40 bounded 128 {\n        let a: Digest = hash(x, 0, 0, 0, 0, 0, 0, 0, 0, 0)\n        let b: Digest = hash(x, 1, 0, 0, 0, 0, 0, 0, 0, 0)\n    }\n    pub_write(x)\n}";
+    let cost = analyze_costs(source, "test.tri").expect("cost analysis should succeed");
+    let descriptions: Vec<(u32, &str)> = cost
+        .rewrites
+        .iter()
+        .map(|r| (r.line, r.description.as_str()))
+        .collect();
+    assert_eq!(
+        descriptions,
+        vec![
+            (4, "tighten `bounded 128` to 64"),
+            (5, "batch 2 hash calls into 1"),
+        ]
+    );
+    // One permutation saved per iteration, for all 128 of them.
+    let hash_rows = cost::create_cost_model("triton").builtin_cost("hash");
+    assert_eq!(cost.rewrites[1].saved, hash_rows.scale(128));
+
+    let hints = cost.optimization_hints();
+    let what_if = hints
+        .iter()
+        .find(|h| h.message.starts_with("hint[H0007]"))
+        .expect("H0007 hint");
+    let half = cost.padded_height / 2;
+    assert!(
+        what_if.message.contains(&format!(
+            "remove {} cc rows to halve padded height to {}",
+            cost.total.get(0) - half,
+            half
+        )),
+        "{}",
+        what_if.message
+    );
+    assert!(
+        what_if.notes[0].starts_with("line 4: "),
+        "{:?}",
+        what_if.notes
+    );
+    assert!(what_if
+        .notes
+        .iter()
+        .any(|n| n.contains("tighten `bounded 128` to 64 saves")));
+    assert_eq!(
+        what_if.help.as_deref(),
+        Some("tighten `bounded 128` to 64 (line 4) alone would halve proving cost")
+    );
+}
+
+#[test]
+fn test_inferred_loop_end_sets_iterations_and_h0004() {
+    let source = "program test\nconst N: Field = 4\nfn main() {\n    let arr: [Field; 5] = [1, 2, 3, 4, 5]\n    for i in 0..arr.len() {\n        pub_write(arr[i])\n    }\n    for _ in 0..N * 2 bounded 64 {\n        pub_write(0)\n    }\n}";
+    let cost = analyze_costs(source, "test.tri").expect("cost analysis should succeed");
+    // The `len()` loop is counted exactly; the bounded one is flagged.
+    assert_eq!(cost.loop_bound_waste, vec![("main".to_string(), 8, 64)]);
+
+    let hints = cost.optimization_hints();
+    let h0004 = hints
+        .iter()
+        .find(|h| h.message.starts_with("hint[H0004]"))
+        .expect("H0004 hint");
+    assert!(h0004
+        .message
+        .contains("bounded 64 but iterates only 8 times"));
+    assert_eq!(
+        h0004.help.as_deref(),
+        Some("the end is known at compile time; drop `bounded 64` to count exactly 8 iterations")
+    );
+}
+
+#[test]
+fn test_diff_source_costs_aligns_renames_and_lines() {
+    let old = "program test\nfn blend(x: Field) -> Digest {\n    hash(x, 1, 0, 0, 0, 0, 0, 0, 0, 0)\n}\nfn keep() {\n    pub_write(1)\n}\nfn main() {\n    let x: Field = pub_read()\n    for i in 0..4 {\n        let a: Digest = hash(x, 0, 0, 0, 0, 0, 0, 0, 0, 0)\n        let b: Digest = hash(x, 1, 0, 0, 0, 0, 0, 0, 0, 0)\n    }\n    let m: Digest = blend(x)\n    keep()\n}";
+    let new = "program test\nfn mix(x: Field) -> Digest {\n    hash(x, 1, 0, 0, 0, 0, 0, 0, 0, 0)\n}\nfn keep() {\n    pub_write(1)\n}\nfn main() {\n    let x: Field = pub_read()\n    for i in 0..4 {\n        let a: Digest = hash(x, 0, 0, 0, 0, 0, 0, 0, 0, 0)\n    }\n    let m: Digest = mix(x)\n    keep()\n}";
+    let diff = diff_source_costs(old, "old.tri", new, "new.tri").expect("both should compile");
+    assert_eq!(diff.unchanged, 1);
+    let names: Vec<(Option<&str>, Option<&str>)> = diff
+        .functions
+        .iter()
+        .map(|f| (f.old_name.as_deref(), f.new_name.as_deref()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![(Some("blend"), Some("mix")), (Some("main"), Some("main"))]
+    );
+
+    let main = &diff.functions[1];
+    let hash_rows = cost::create_cost_model("triton").builtin_cost("hash");
+    assert_eq!(
+        main.old_cost.unwrap().get(1) - main.new_cost.unwrap().get(1),
+        hash_rows.get(1) * 4
+    );
+    let changed: Vec<(bool, u32)> = main.lines.iter().map(|l| (l.added, l.line)).collect();
+    assert_eq!(changed, vec![(false, 12), (false, 14), (true, 13)]);
+    assert_eq!(main.lines[0].cost.get(1), hash_rows.get(1));
+
+    let report = diff.format();
+    assert!(report.contains("mix (was blend)"), "{}", report);
+    assert!(
+        report.contains("  -   12 |         let b: Digest = hash(x, 1"),
+        "{}",
+        report
+    );
+    assert!(report.contains("(1 unchanged function)"), "{}", report);
+}
+
+#[test]
+fn test_blame_costs_ranks_commits_and_caches() {
+    let dir = tempfile::tempdir().unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args([
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "-c",
+                "commit.gpgsign=false",
+            ])
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .expect("git should run");
+        assert!(status.status.success(), "git {:?} failed", args);
+    };
+    let main = dir.path().join("main.tri");
+    let commit = |source: &str, message: &str| {
+        std::fs::write(&main, source).unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", message]);
+    };
+    git(&["init", "-q"]);
+    commit(
+        "program p\nfn main() {\n    pub_write(pub_read())\n}\n",
+        "Initial",
+    );
+    commit(
+        "program p\nfn main() {\n    let x: Field = pub_read()\n    let d: Digest = hash(x, 0, 0, 0, 0, 0, 0, 0, 0, 0)\n    pub_write(x)\n}\n",
+        "Hash the input",
+    );
+    commit("program p\nfn main() {\n    let x = oops\n}\n", "Break it");
+    commit(
+        "program p\nfn main() {\n    let x: Field = pub_read()\n    for i in 0..64 {\n        pub_write(x + 1)\n    }\n}\n",
+        "Write in a loop",
+    );
+
+    let options = CompileOptions::default();
+    let mut analyzed = Vec::new();
+    let snapshots = blame_costs(&main, "HEAD~3", &options, |_, summary| {
+        analyzed.push(summary.to_string())
+    })
+    .expect("blame should succeed");
+    assert_eq!(analyzed.len(), 4);
+    assert_eq!(snapshots[2].padded_height, None);
+
+    let jumps = cost_jumps(&snapshots);
+    let summaries: Vec<&str> = jumps.iter().map(|j| j.summary.as_str()).collect();
+    assert_eq!(summaries, vec!["Write in a loop", "Hash the input"]);
+    // "Write in a loop" is compared with the last commit that compiled.
+    assert_eq!(
+        jumps[0].padded_height.0,
+        snapshots[1].padded_height.unwrap()
+    );
+    assert!(jumps[1].table_delta() > 0);
+
+    let report = format_cost_blame("main.tri", &snapshots, 1);
+    assert!(
+        report.contains("over 4 commits (1 did not compile)"),
+        "{}",
+        report
+    );
+    assert!(report.contains("Write in a loop"), "{}", report);
+    assert!(report.contains("... and 1 more"), "{}", report);
+
+    let mut reanalyzed = 0;
+    let cached = blame_costs(&main, "HEAD~3", &options, |_, _| reanalyzed += 1).unwrap();
+    assert_eq!(reanalyzed, 0);
+    assert_eq!(cached, snapshots);
+}
+
+#[test]
+fn test_what_if_strict_logic() {
+    let source = "program test\nfn main() {\n    let a: Bool = pub_read() == 0\n    let b: Bool = pub_read() == 1\n    assert(a && b)\n    assert(a || pub_read() == 2)\n}";
+    let cost = analyze_costs(source, "test.tri").expect("cost analysis should succeed");
+    // Only `a && b` qualifies: the right side of `||` reads input.
+    assert_eq!(cost.rewrites.len(), 1);
+    assert_eq!(cost.rewrites[0].line, 5);
+    assert_eq!(
+        cost.rewrites[0].description,
+        "evaluate `&&` strictly as `&`"
+    );
+    assert_eq!(cost.rewrites[0].saved.get(0), 3);
+}
@@ -0,0 +1,149 @@
+use crate::*;
+
+#[test]
+fn test_disassemble_nests_branches_and_loops() {
+    let tasm = "\
+    call app__main
+    halt
+app__main:
+    read_io 1
+    push 1
+    swap 1
+    skiz
+    call app__then__1
+    skiz
+    call app__else__2
+    return
+app__then__1:
+    pop 1
+    push 3
+    call app__loop__3
+    pop 1
+    push 0
+    return
+app__else__2:
+    write_io 1
+    return
+app__loop__3:
+    dup 0
+    push 0
+    eq
+    skiz
+    return
+    push -1
+    add
+    recurse
+";
+    let listing = disassemble_tasm(tasm, &[], Path::new("")).unwrap();
+    let expected = "\
+fn <entry> {  // line 1, cc 2, jump 1
+    call app__main
+    halt
+}
+
+fn app__main {  // line 3, cc 8, opst 5, jump 3
+    // called by <entry>
+    read_io 1
+    push 1
+    swap 1
+    if st0 != 0 {  // app__then__1, cc 6, opst 4, jump 2
+        pop 1
+        push 3
+        loop {  // app__loop__3, cc 8, opst 6, jump 2
+            dup 0
+            push 0
+            eq
+            break if st0 != 0
+            push -1
+            add
+        }
+        pop 1
+        push 0
+    }
+    if st0 != 0 {  // app__else__2, cc 2, opst 1, jump 1
+        write_io 1
+    }
+    return
+}
+";
+    assert_eq!(listing, expected);
+}
+
+#[test]
+fn test_disassemble_keeps_shared_blocks_as_functions() {
+    // `helper` has two call sites, so it stays a function.
+    let tasm = "\
+main:
+    call helper
+    call helper
+    halt
+helper:
+    push 1
+    pop 1
+    return
+";
+    let listing = disassemble_tasm(tasm, &[], Path::new("")).unwrap();
+    assert!(listing.contains("fn helper {"), "{}", listing);
+    assert!(listing.contains("// called by main, main"), "{}", listing);
+    assert!(listing.contains("    call helper\n    call helper\n"));
+
+    let err = disassemble_tasm("    frobnicate\n", &[], Path::new("")).unwrap_err();
+    assert!(err.contains("frobnicate"), "{}", err);
+}
+
+#[test]
+fn test_disassemble_maps_functions_to_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.tri");
+    std::fs::write(
+        &path,
+        "program app\n\nfn double(x: Field) -> Field {\n    x + x\n}\n\nfn main() {\n    pub_write(double(pub_read()))\n}\n",
+    )
+    .unwrap();
+    let options = CompileOptions::default();
+    let tasm = compile_project_with_options(&path, &options).unwrap();
+    let index = index_project(&path, &options).unwrap();
+
+    let listing = disassemble_tasm(&tasm, &index, dir.path()).unwrap();
+    assert!(
+        listing.contains("    // main.tri:3  fn double(x: Field) -> Field\n"),
+        "{}",
+        listing
+    );
+    assert!(
+        listing.contains("    // main.tri:7  fn main()\n"),
+        "{}",
+        listing
+    );
+}
+
+#[test]
+fn test_optimize_tasm_keeps_behavior_of_handwritten_code() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    let tasm = "\
+    call app__main
+    halt
+app__main:
+    read_io 1
+    dup 0
+    pop 1
+    push 2
+    swap 1
+    swap 1
+    mul
+    write_io 1
+    return";
+    let optimized = optimize_tasm(tasm, "app").unwrap();
+    assert!(!optimized.contains("swap 1"), "{}", optimized);
+    assert!(!optimized.contains("dup 0"), "{}", optimized);
+
+    let input = ProgramInput {
+        public: vec![21],
+        ..ProgramInput::default()
+    };
+    let run = |tasm: &str| Interpreter::new().run_tasm(tasm, &input).map(|o| o.output);
+    assert_eq!(run(&optimized), Ok(vec![42]));
+    assert_eq!(run(&optimized), run(tasm));
+}
@@ -118,3 +118,67 @@ fn test_generate_docs_cost_annotations() {
     assert!(doc.contains("**Module:** test"), "should show module name");
 }
 
+#[test]
+fn test_generate_docs_doc_comments() {
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program test\n\n/// A point.\nstruct Pt {\n    x: Field,\n}\n\n/// Not about helper.\n// helper\nfn helper() -> Field {\n    1\n}\n\n/// Reads and writes.\n///\n/// Twice over.\nfn main() {\n    let p: Pt = Pt { x: helper() }\n    pub_write(p.x)\n}\n",
+    )
+    .unwrap();
+
+    let doc = generate_docs(&main_path, &CompileOptions::default()).unwrap();
+    assert!(
+        doc.contains("### `struct Pt`\nA point.\n\n| Field"),
+        "{}",
+        doc
+    );
+    assert!(
+        doc.contains("### `fn main()`\nReads and writes.\n\nTwice over.\n\n**Cost:**"),
+        "{}",
+        doc
+    );
+    assert!(!doc.contains("Not about helper"), "{}", doc);
+}
+
+#[test]
+fn test_docs_to_html() {
+    let markdown = "# my_app\n\n## Functions\n\n### `fn f(x: Field) -> Field`\n**Cost:** cc=1 | dominant: processor\n**Module:** a<b\n\n## Cost Summary\n\n| Table | Height |\n|-------|--------|\n| Processor | 4 |\n";
+    let html = docs_to_html(markdown);
+    assert!(html.contains("<title>my_app</title>"));
+    assert!(html.contains("<h3><code>fn f(x: Field) -&gt; Field</code></h3>"));
+    assert!(html.contains("<strong>Cost:</strong> cc=1"));
+    assert!(html.contains("<strong>Module:</strong> a&lt;b</p>"));
+    assert!(html.contains("<tr><th>Table</th><th>Height</th></tr>"));
+    assert!(html.contains("<tr><td>Processor</td><td>4</td></tr>"));
+    assert!(!html.contains("---"));
+}
+
+#[test]
+fn test_generate_docs_program_summary() {
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program test\n\nfn main() {\n    let a: Field = pub_read()\n    pub_write(a + 1)\n}\n",
+    )
+    .unwrap();
+
+    let options = CompileOptions::default();
+    let doc = generate_docs(&main_path, &options).unwrap();
+    assert_eq!(doc, generate_docs(&main_path, &options).unwrap());
+
+    let tasm = compile_project_with_options(&main_path, &options).unwrap();
+    let cost = analyze_costs_project(&main_path, &options).unwrap();
+    let digest = crate::field::poseidon2::hash_bytes_goldilocks(tasm.as_bytes());
+    let row = format!(
+        "| {} | {} | {} | safe | `{}` |",
+        count_tasm_instructions(&tasm),
+        cost.padded_height,
+        cost.total.dominant_table(&cost.short_names()),
+        crate::hash::ContentHash(digest).to_hex()
+    );
+    assert!(doc.contains("## Program\n\n| Instructions |"), "{}", doc);
+    assert!(doc.contains(&row), "missing {} in\n{}", row, doc);
+}
@@ -157,6 +157,57 @@ fn test_const_generic_mul_expression() {
     );
 }
 
+#[test]
+fn test_const_generic_size_arithmetic_runs() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+    // `inner<M>` is first instantiated while emitting `outer<2>`.
+    let source = "program test\nfn inner<N>(xs: [Field; N + 1]) -> Field {\n    xs[N]\n}\nfn outer<M>(xs: [Field; 2 * M], ys: [Field; M + 1]) -> Field {\n    xs[M] + inner<M>(ys)\n}\nfn main() {\n    let a: [Field; 4] = [1, 2, 3, 4]\n    let b: [Field; 3] = [pub_read(), 20, 30]\n    pub_write(outer(a, b))\n    pub_write(inner(a))\n}";
+    let tasm = compile(source, "test.tri").expect("size arithmetic should compile");
+    assert!(tasm.contains("__inner__N2:"));
+    assert!(tasm.contains("__inner__N3:"));
+    let input = ProgramInput {
+        public: vec![10],
+        ..Default::default()
+    };
+    let output = Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output);
+    assert_eq!(output, Ok(vec![33, 4]));
+}
+
+#[test]
+fn test_enum_values_and_match_run() {
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+    let source = "program test\nenum Shape {\n    Empty,\n    Circle(Field),\n    Rect(Field, Field),\n}\nfn area(s: Shape) -> Field {\n    let mut a: Field = 7\n    match s {\n        Shape.Empty => {}\n        Shape.Circle(r) => { a = 3 * r * r }\n        Shape.Rect(w, h) => { a = w * h }\n    }\n    a\n}\nfn height(s: Shape) -> Field {\n    let mut h: Field = 0\n    match s {\n        Shape.Rect(_, y) => { h = y }\n        _ => {}\n    }\n    h\n}\nfn main() {\n    let x: Field = pub_read()\n    let r: Shape = Shape.Rect(x, 5)\n    pub_write(area(Shape.Circle(x)))\n    pub_write(area(r))\n    pub_write(area(Shape.Empty))\n    pub_write(height(r))\n    pub_write(height(Shape.Circle(x)))\n}";
+    let tasm = compile(source, "test.tri").expect("enums should compile");
+    let input = ProgramInput {
+        public: vec![4],
+        ..Default::default()
+    };
+    let output = Interpreter::new().run_tasm(&tasm, &input).map(|o| o.output);
+    assert_eq!(output, Ok(vec![48, 20, 7, 5, 0]));
+}
+
+#[test]
+fn test_mono_instance_cap() {
+    let source = "program test\nfn first<N>(xs: [Field; N]) -> Field {\n    xs[0]\n}\nfn main() {\n    let a: [Field; 2] = [1, 2]\n    let b: [Field; 3] = [1, 2, 3]\n    pub_write(first(a) + first(b))\n}";
+    let mut options = CompileOptions {
+        max_instances: 2,
+        ..Default::default()
+    };
+    assert!(compile_with_options(source, "test.tri", &options).is_ok());
+    options.max_instances = 1;
+    let errors = compile_with_options(source, "test.tri", &options).unwrap_err();
+    assert_eq!(
+        errors[0].message,
+        "program emits 2 size-generic instances, more than the limit of 1"
+    );
+    assert_eq!(
+        errors[0].notes,
+        vec!["'__first' alone has 2 instances (8 instructions)".to_string()]
+    );
+}
+
 #[test]
 fn test_cfg_debug_compiles() {
     let source = "program test\n#[cfg(debug)]\nfn check() {\n    assert(true)\n}\nfn main() {\n    check()\n}";
@@ -189,8 +240,8 @@ fn test_cfg_different_targets_different_output() {
         compile_with_options(source, "test.tri", &debug_opts).expect("debug should compile");
 
     let release_opts = CompileOptions::for_target("release");
-    let release_tasm = compile_with_options(source, "test.tri", &release_opts)
-        .expect("release should compile");
+    let release_tasm =
+        compile_with_options(source, "test.tri", &release_opts).expect("release should compile");
 
     // Both should have __mode: but with different bodies
     assert!(debug_tasm.contains("__mode:"));
@@ -268,3 +319,67 @@ fn test_pure_fn_compiles() {
     assert!(result.is_ok(), "pure fn should compile: {:?}", result.err());
 }
 
+#[test]
+fn event_field_order_in_source_does_not_change_output() {
+    let event = "program test\nevent E {\n    a: Field,\n    b: Field,\n}\nfn main() {\n";
+    let ordered = format!("{}    seal E {{ a: pub_read(), b: 2 }}\n}}", event);
+    let swapped = format!("{}    seal E {{ b: 2, a: pub_read() }}\n}}", event);
+    let ordered = compile(&ordered, "test.tri").expect("ordered fields compile");
+    let swapped = compile(&swapped, "test.tri").expect("swapped fields compile");
+    assert_eq!(ordered, swapped);
+}
+
+#[test]
+fn test_auto_range_check_emits_one_split_per_placed_check() {
+    // x is checked once after its binding, y at its use; 9 needs no check.
+    let source = "program test\n#[auto_range_check]\nfn main() {\n    let x = pub_read()\n    let y = pub_read()\n    assert(y < x)\n    assert(x < 9)\n}";
+    let tasm = compile(source, "test.tri").expect("auto range checks compile");
+    assert_eq!(tasm.matches("split").count(), 2, "{}", tasm);
+}
+
+#[test]
+fn test_assertion_messages_become_error_ids() {
+    let source = "program test\nfn main() {\n    let a = pub_read()\n    assert(a == 1, \"balance underflow\")\n    assert_eq(a, 1, \"not one\")\n    assert(a == 1, \"balance underflow\")\n    assert(a == 1)\n}";
+    let tasm = compile(source, "test.tri").expect("assertion messages compile");
+    assert_eq!(tasm.matches("assert error_id 1").count(), 2, "{}", tasm);
+    assert_eq!(tasm.matches("assert error_id 2").count(), 1, "{}", tasm);
+    assert!(!tasm.contains("balance underflow"));
+}
+
+#[test]
+fn test_debug_builtins_are_stripped_in_release() {
+    let source = "program test\nfn main() {\n    let a = pub_read()\n    debug_assert(a == 1, \"a is one\")\n    trace(\"a\", a)\n    trace(\"digest\", divine5())\n    pub_write(a)\n}";
+    let debug_tasm = compile_with_options(source, "test.tri", &CompileOptions::for_target("debug"))
+        .expect("debug should compile");
+    assert!(debug_tasm.contains("assert error_id 1"), "{}", debug_tasm);
+    assert!(debug_tasm.contains("hint a = stack[0]"), "{}", debug_tasm);
+    assert!(
+        debug_tasm.contains("hint digest = stack[0..5]"),
+        "{}",
+        debug_tasm
+    );
+
+    let release_tasm =
+        compile_with_options(source, "test.tri", &CompileOptions::for_target("release"))
+            .expect("release should compile");
+    assert!(!release_tasm.contains("assert"), "{}", release_tasm);
+    assert!(!release_tasm.contains("hint"), "{}", release_tasm);
+    assert!(!release_tasm.contains("divine"), "{}", release_tasm);
+}
+
+#[test]
+fn test_assumptions_emit_no_code() {
+    let assumed = "program test\n#[assume(a == 1)]\nfn twice(a: Field) -> Field {\n    a + a\n}\nfn main() {\n    let a = pub_read()\n    assume(a == 1, \"kernel passes one\")\n    pub_write(twice(a))\n}";
+    let plain = "program test\nfn twice(a: Field) -> Field {\n    a + a\n}\nfn main() {\n    let a = pub_read()\n    pub_write(twice(a))\n}";
+    let tasm = compile(assumed, "test.tri").expect("assumptions compile");
+    assert!(!tasm.contains("assert"), "{}", tasm);
+    assert_eq!(tasm, compile(plain, "test.tri").unwrap());
+}
+
+#[test]
+fn test_fail_halts_with_its_constant_code() {
+    let source = "program test\nconst INSUFFICIENT_BALANCE: Field = 4\nfn main() {\n    let a = pub_read()\n    assert(a == 1, \"not one\")\n    if a == 0 {\n        fail(INSUFFICIENT_BALANCE)\n    }\n}";
+    let tasm = compile(source, "test.tri").expect("fail compiles");
+    assert!(tasm.contains("push 0\n    assert error_id 4"), "{}", tasm);
+    assert!(tasm.contains("assert error_id 1"), "{}", tasm);
+}
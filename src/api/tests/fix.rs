@@ -0,0 +1,54 @@
+use crate::*;
+
+fn fixed(source: &str) -> FixedSource {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.tri");
+    std::fs::write(&path, source).unwrap();
+    fix_source(source, &path, &CompileOptions::default()).expect("parses")
+}
+
+#[test]
+fn test_fix_source_applies_machine_applicable_fixes() {
+    let source = "program demo\n\nuse std.hash\n\nfn main() {\n    let d = hash.tip5(1, 2, 3, 4, 5, 6, 7, 8, 9, 10)\n    let total: Field = 0\n    for trait in 0..3 bounded 3 {\n        total = total  +  as_field(trait)\n    }\n    pub_write(total)\n}\n\nfn untouched( )  {\n}\n";
+    let result = fixed(source);
+    assert_eq!(
+        result.source,
+        "program demo\n\nuse vm.crypto.hash\n\nfn main() {\n    let d = hash.tip5(1, 2, 3, 4, 5, 6, 7, 8, 9, 10)\n    let mut total: Field = 0\n    for trait_ in 0..3 bounded 3 {\n        total = total + as_field(trait_)\n    }\n    pub_write(total)\n}\n\nfn untouched( )  {\n}\n"
+    );
+    let applied: Vec<(usize, &str)> = result
+        .applied
+        .iter()
+        .map(|f| (f.line, f.message.as_str()))
+        .collect();
+    assert_eq!(
+        applied,
+        vec![
+            (3, "import 'vm.crypto.hash'"),
+            (7, "declare 'total' with `let mut`"),
+            (8, "rename 'trait' to 'trait_'"),
+        ]
+    );
+
+    // Nothing left to fix on the second run.
+    let again = fixed(&result.source);
+    assert_eq!(again.source, result.source);
+    assert!(again.applied.is_empty());
+}
+
+#[test]
+fn test_fix_source_rejects_parse_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.tri");
+    assert!(fix_source("program p\nfn main( {\n", &path, &CompileOptions::default()).is_err());
+}
+
+#[test]
+fn test_unified_diff() {
+    let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+    let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\n";
+    assert_eq!(
+        unified_diff("x.tri", old, new),
+        "--- x.tri\n+++ x.tri\n@@ -1,5 +1,5 @@\n a\n-b\n+B\n c\n d\n e\n@@ -9,3 +9,4 @@\n i\n j\n k\n+l\n"
+    );
+    assert_eq!(unified_diff("x.tri", old, old), "");
+}
@@ -0,0 +1,68 @@
+use crate::*;
+
+fn write_project(dir: &std::path::Path) -> std::path::PathBuf {
+    std::fs::write(
+        dir.join("util.tri"),
+        "module util\n\npub const LIMIT: Field = 8\n\npub struct Pair {\n    a: Field,\n    b: U32,\n}\n\npub fn double(x: Field) -> Field {\n    x + x\n}\n",
+    )
+    .unwrap();
+    let main_path = dir.join("main.tri");
+    std::fs::write(
+        &main_path,
+        "program app\n\nuse util\n\nevent Moved {\n    to: Field,\n}\n\nfn main() {\n    pub_write(util.double(pub_read()))\n}\n",
+    )
+    .unwrap();
+    main_path
+}
+
+#[test]
+fn test_index_lists_every_definition() {
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = write_project(dir.path());
+    let entries = index_project(&main_path, &CompileOptions::default()).unwrap();
+
+    let names: Vec<(&str, &str, DefinitionKind)> = entries
+        .iter()
+        .map(|e| (e.module.as_str(), e.name.as_str(), e.kind))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("util", "LIMIT", DefinitionKind::Constant),
+            ("util", "Pair", DefinitionKind::Struct),
+            ("util", "double", DefinitionKind::Function),
+            ("app", "Moved", DefinitionKind::Event),
+            ("app", "main", DefinitionKind::Function),
+        ]
+    );
+
+    let double = &entries[2];
+    assert_eq!(double.signature, "fn double(x: Field) -> Field");
+    assert_eq!((double.line, double.column), (10, 8));
+    assert_eq!((double.end_line, double.end_column), (12, 2));
+    assert!(double.is_pub);
+    assert_eq!(entries[1].signature, "struct Pair { a: Field, b: U32 }");
+    assert_eq!(entries[0].signature, "const LIMIT: Field = 8");
+    assert!(!entries[4].is_pub);
+}
+
+#[test]
+fn test_index_renderings() {
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = write_project(dir.path());
+    let entries = index_project(&main_path, &CompileOptions::default()).unwrap();
+
+    let tags = format_ctags(&entries, dir.path());
+    let lines: Vec<&str> = tags.lines().filter(|l| !l.starts_with("!_TAG")).collect();
+    let mut sorted = lines.clone();
+    sorted.sort();
+    assert_eq!(lines, sorted, "tags must be sorted");
+    assert!(lines.contains(
+        &"double\tutil.tri\t10;\"\tf\tline:10\tmodule:util\tsignature:fn double(x: Field) -> Field"
+    ));
+
+    let json = format_index_json(&entries, dir.path());
+    assert_eq!(json.lines().count(), entries.len());
+    assert!(json.lines().any(|l| l
+        == "{\"name\":\"main\",\"kind\":\"function\",\"module\":\"app\",\"file\":\"main.tri\",\"line\":9,\"column\":4,\"end_line\":11,\"end_column\":2,\"signature\":\"fn main()\",\"pub\":false}"));
+}
@@ -0,0 +1,63 @@
+use crate::*;
+
+#[test]
+fn test_leakage_report_of_constants_events_and_secret_branches() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.tri");
+    std::fs::write(
+        &path,
+        "program vault\n\nevent Opened { owner: Field, amount: Field }\nevent Vote { choice: Field }\nevent Nullifier { id: Field }\n\nconst SALT: Field = 987654\n\nfn main() {\n    let key: Field = divine()\n    let owner: Field = pub_read()\n    let mut amount: Field = owner + SALT\n    if key == 0 {\n        amount = amount * amount * amount\n    }\n    if owner == 1 {\n        amount = amount + 1\n    }\n    reveal Opened { owner: owner, amount: amount }\n    seal Vote { choice: key }\n    seal Nullifier { id: owner }\n}\n",
+    )
+    .unwrap();
+    let report = leakage_report(&path, &CompileOptions::default()).expect("should analyze");
+
+    assert_eq!(report.program, "vault");
+    assert_eq!(report.constants.len(), 1);
+    assert_eq!(report.constants[0].name, "SALT");
+    assert_eq!(report.constants[0].value, 987654);
+    assert_eq!(report.constants[0].line, 7);
+    assert!(report.constants[0].pushed);
+
+    assert_eq!(report.events.len(), 3);
+    let opened = &report.events[0];
+    assert_eq!(opened.kind, EventKind::Reveal);
+    let secret: Vec<bool> = opened.fields.iter().map(|f| f.secret).collect();
+    // `amount` is assigned under the secret `if`.
+    assert_eq!(secret, vec![false, true]);
+    let vote = &report.events[1];
+    assert_eq!(vote.kind, EventKind::Seal);
+    assert!(vote.fields[0].secret);
+    assert!(!vote.enumerable);
+    // Computed from public input alone, the digest can be recomputed.
+    assert!(report.events[2].enumerable);
+
+    // Only the branch on `key` is secret; `owner` is public input.
+    assert_eq!(report.branches.len(), 1);
+    let branch = &report.branches[0];
+    assert_eq!((branch.kind, branch.line), ("if", 13));
+    assert!(branch.paths[0] > branch.paths[1]);
+    assert_eq!(report.balanced, 0);
+
+    let text = report.to_string();
+    assert!(text.contains("SALT"), "{text}");
+    assert!(
+        text.contains("fields recomputable from public data"),
+        "{text}"
+    );
+    assert!(report.to_json().contains("\"kind\": \"if\", \"line\": 13"));
+}
+
+#[test]
+fn test_leakage_report_counts_balanced_branches() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.tri");
+    std::fs::write(
+        &path,
+        "program even\n\nfn main() {\n    let key: Field = divine()\n    let mut out: Field = 0\n    if key == 0 {\n        out = 1\n    } else {\n        out = 2\n    }\n    pub_write(out)\n}\n",
+    )
+    .unwrap();
+    let report = leakage_report(&path, &CompileOptions::default()).expect("should analyze");
+    assert!(report.branches.is_empty(), "{report}");
+    assert_eq!(report.balanced, 1);
+    assert!(report.events.is_empty());
+}
@@ -1,8 +1,17 @@
 mod check;
+mod ci;
 mod compile;
 mod cost;
+mod disasm;
 mod docs;
 mod features;
+mod fix;
 mod format;
+mod index;
+mod leakage;
 mod neptune;
+mod options;
+mod passes;
+mod policy;
 mod prove;
+mod reduce;
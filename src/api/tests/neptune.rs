@@ -324,7 +324,13 @@ fn test_transaction_validation_compiles() {
 
 #[test]
 fn test_neptune_lock_scripts_compile() {
-    for name in &["generation", "symmetric", "multisig", "timelock"] {
+    for name in &[
+        "generation",
+        "symmetric",
+        "multisig",
+        "timelock",
+        "covenant",
+    ] {
         let path_str = format!("os/neptune/locks/{}.tri", name);
         let path = std::path::Path::new(&path_str);
         if !path.exists() {
@@ -358,3 +364,72 @@ fn test_neptune_type_scripts_compile() {
     }
 }
 
+#[test]
+fn test_verify_claim_example_compiles() {
+    let path = std::path::Path::new("os/neptune/programs/verify_claim.tri");
+    if !path.exists() {
+        return;
+    }
+    let result = compile_project(path);
+    assert!(
+        result.is_ok(),
+        "verify_claim example should compile: {:?}",
+        result.err()
+    );
+    let tasm = result.unwrap();
+    assert!(tasm.contains("sponge_absorb"), "items feed the transcript");
+    assert!(tasm.contains("merkle_step"), "last layer is authenticated");
+}
+
+#[test]
+fn test_proof_item_absorbs_a_copy_into_the_sponge() {
+    let source =
+        "program test\nfn main() {\n    sponge_init()\n    let x: XField = proof_item_xfe()\n}";
+    let tasm = compile(source, "test.tri").expect("should compile");
+    let lines: Vec<&str> = tasm.lines().map(str::trim).collect();
+    let start = lines
+        .iter()
+        .position(|l| *l == "divine 3")
+        .expect("item is divined");
+    assert_eq!(&lines[start + 1..start + 4], &["dup 2", "dup 2", "dup 2"]);
+    assert_eq!(lines[start + 11], "sponge_absorb");
+}
+
+#[test]
+fn test_kernel_types_cannot_be_forged_outside_kernel() {
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = dir.path().join("main.tri");
+    std::fs::write(
+        &main_path,
+        r#"program test
+use os.neptune.kernel
+
+fn main() {
+let fee: kernel.Fee = kernel.Fee { amount: 0, leaf: divine5() }
+pub_write(fee.amount)
+}
+"#,
+    )
+    .unwrap();
+    for (dir_name, file) in [
+        ("os/neptune", "kernel.tri"),
+        ("std/crypto", "merkle.tri"),
+        ("vm/crypto", "merkle.tri"),
+        ("vm/io", "io.tri"),
+        ("vm/core", "assert.tri"),
+        ("vm/core", "convert.tri"),
+    ] {
+        let target = dir.path().join(dir_name);
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::copy(format!("{}/{}", dir_name, file), target.join(file)).unwrap_or_default();
+    }
+
+    let diags = compile_project(&main_path).expect_err("forged Fee must be rejected");
+    assert!(
+        diags.iter().any(|d| d
+            .message
+            .contains("cannot construct #[authenticated] struct 'kernel.Fee'")),
+        "{:?}",
+        diags
+    );
+}
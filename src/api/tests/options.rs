@@ -0,0 +1,125 @@
+use crate::*;
+
+#[test]
+fn test_builder_matches_the_profile_defaults() {
+    let options = CompileOptions::builder()
+        .with_profile("release")
+        .build()
+        .unwrap();
+    assert_eq!(options.profile, "release");
+    assert_eq!(options.cfg_flags, BTreeSet::from(["release".to_string()]));
+    assert_eq!(options.target_config.name, "triton");
+    assert_eq!(
+        options.max_instances,
+        crate::tir::mono::DEFAULT_MAX_INSTANCES
+    );
+}
+
+#[test]
+fn test_builder_rejects_invalid_settings() {
+    let err = CompileOptions::builder()
+        .with_profile("bench")
+        .build()
+        .unwrap_err();
+    assert!(
+        err.message.contains("unknown profile 'bench'"),
+        "{}",
+        err.message
+    );
+    assert_eq!(err.help.as_deref(), Some("known profiles: debug, release"));
+
+    let err = CompileOptions::builder()
+        .with_cfg_flag("release")
+        .build()
+        .unwrap_err();
+    assert!(
+        err.message.contains("'debug' and 'release'"),
+        "{}",
+        err.message
+    );
+
+    let err = CompileOptions::builder()
+        .with_cfg_flag("no-std")
+        .build()
+        .unwrap_err();
+    assert!(
+        err.message.contains("invalid cfg flag 'no-std'"),
+        "{}",
+        err.message
+    );
+
+    assert!(CompileOptions::builder()
+        .with_max_instances(0)
+        .build()
+        .is_err());
+    assert!(CompileOptions::builder()
+        .with_target("no_such_vm")
+        .build()
+        .is_err());
+}
+
+#[test]
+fn test_from_project_applies_overrides() {
+    let dir = tempfile::tempdir().unwrap();
+    let toml_path = dir.path().join("trident.toml");
+    std::fs::write(
+        &toml_path,
+        "[project]\nname = \"demo\"\nentry = \"main.tri\"\n\n[targets.bench]\nflags = [\"release\", \"bench\"]\n\n[build]\nmax_instances = 8\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("main.tri"), "program demo\nfn main() {}\n").unwrap();
+    let project = project::Project::load(&toml_path).unwrap();
+
+    let options = CompileOptions::from_project(
+        &project,
+        CompileOverrides {
+            profile: Some("bench".to_string()),
+            cfg_flags: vec!["trace".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(options.profile, "bench");
+    assert_eq!(
+        options.cfg_flags,
+        BTreeSet::from(["bench", "release", "trace"].map(String::from))
+    );
+    assert_eq!(options.max_instances, 8);
+
+    let options = CompileOptions::from_project(
+        &project,
+        CompileOverrides {
+            max_instances: Some(2),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(options.profile, "debug");
+    assert_eq!(options.max_instances, 2);
+}
+
+#[test]
+fn test_options_json_round_trip() {
+    let mut options = CompileOptions::builder()
+        .with_profile("release")
+        .with_cfg_flag("trace")
+        .with_dep_dirs(vec!["deps/\"quoted\"".into()])
+        .with_locked(BTreeMap::from([("merkle".to_string(), "ab12".to_string())]))
+        .with_max_instances(16)
+        .build()
+        .unwrap();
+    let json = options.to_json();
+    let loaded = CompileOptions::from_json(&json).unwrap();
+    assert_eq!(loaded.to_json(), json);
+    assert_eq!(loaded.profile, "release");
+    assert_eq!(loaded.cfg_flags, options.cfg_flags);
+    assert_eq!(loaded.dep_dirs, options.dep_dirs);
+    assert_eq!(loaded.locked, options.locked);
+    assert_eq!(loaded.max_instances, 16);
+    assert_eq!(loaded.target_config.name, "triton");
+
+    options.locked.clear();
+    let loaded = CompileOptions::from_json(&options.to_json()).unwrap();
+    assert!(loaded.locked.is_empty());
+    assert!(CompileOptions::from_json("{\"target\": \"triton\"}").is_err());
+}
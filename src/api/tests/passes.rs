@@ -0,0 +1,121 @@
+use crate::*;
+
+/// Rejects functions whose name starts with `tmp_`.
+struct NoTmpFunctions;
+
+impl CompilerPass for NoTmpFunctions {
+    fn name(&self) -> &str {
+        "no_tmp_functions"
+    }
+
+    fn check(&self, module: &PassContext) -> Vec<Diagnostic> {
+        module
+            .file
+            .items
+            .iter()
+            .filter_map(|item| match &item.node {
+                ast::Item::Fn(func) if func.name.node.starts_with("tmp_") => {
+                    Some(Diagnostic::error(
+                        format!("temporary function `{}`", func.name.node),
+                        func.name.span,
+                    ))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn write_program(dir: &Path, body: &str) -> std::path::PathBuf {
+    let path = dir.join("main.tri");
+    std::fs::write(&path, body).unwrap();
+    path
+}
+
+#[test]
+fn test_registered_pass_fails_the_build() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = write_program(
+        dir.path(),
+        "program test\nfn tmp_helper() -> Field {\n    1\n}\nfn main() {\n    pub_write(tmp_helper())\n}\n",
+    );
+    let options = CompileOptions::builder()
+        .with_pass(NoTmpFunctions)
+        .build()
+        .unwrap();
+    assert_eq!(options.passes.names(), vec!["no_tmp_functions"]);
+
+    let errors = check_project_with_options(&entry, &options).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "temporary function `tmp_helper`");
+    assert_eq!(
+        errors[0].notes,
+        vec!["reported by pass `no_tmp_functions`".to_string()]
+    );
+    assert!(compile_project_with_options(&entry, &options).is_err());
+    // Without the pass the program is fine.
+    assert!(check_project(&entry).is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_plugin_passes_from_trident_toml() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let entry = write_program(
+        dir.path(),
+        "program demo\nfn main() {\n    let x: Field = divine()\n    pub_write(x)\n}\n",
+    );
+    let plugin = dir.path().join("no-divine.sh");
+    std::fs::write(
+        &plugin,
+        "#!/bin/sh\ngrep -n divine | while IFS=: read n rest; do\n  echo \"$n:20: warning: divine in $TRIDENT_MODULE for $TRIDENT_TARGET\"\ndone\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let toml_path = dir.path().join("trident.toml");
+    std::fs::write(
+        &toml_path,
+        "[project]\nname = \"demo\"\nentry = \"main.tri\"\n\n[passes]\nno_divine = \"no-divine.sh\"\n",
+    )
+    .unwrap();
+    let project = project::Project::load(&toml_path).unwrap();
+    let options = CompileOptions::from_project(&project, CompileOverrides::default()).unwrap();
+    assert_eq!(options.passes.names(), vec!["no_divine"]);
+
+    // Warnings do not fail the build.
+    assert!(check_project_with_options(&entry, &options).is_ok());
+
+    std::fs::write(
+        &plugin,
+        "#!/bin/sh\necho '2:1: error: main is not allowed'\n",
+    )
+    .unwrap();
+    let errors = check_project_with_options(&entry, &options).unwrap_err();
+    assert_eq!(errors[0].message, "main is not allowed");
+    assert_eq!(errors[0].span.start, "program demo\n".len() as u32);
+
+    std::fs::write(&plugin, "#!/bin/sh\necho broken >&2\nexit 3\n").unwrap();
+    let errors = check_project_with_options(&entry, &options).unwrap_err();
+    assert!(
+        errors[0].message.starts_with("pass `no_divine` failed"),
+        "{}",
+        errors[0].message
+    );
+    assert!(
+        errors[0].message.ends_with("broken"),
+        "{}",
+        errors[0].message
+    );
+}
+
+#[test]
+fn test_native_plugins_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("rules.so"), "").unwrap();
+    let err = PluginPass::load("rules", "rules.so", dir.path()).unwrap_err();
+    assert!(err.message.contains("cannot be loaded"), "{}", err.message);
+    let err = PluginPass::load("rules", "missing", dir.path()).unwrap_err();
+    assert!(err.message.contains("does not exist"), "{}", err.message);
+}
@@ -0,0 +1,102 @@
+use crate::policy::Policy;
+use crate::*;
+
+fn policy(dir: &Path, text: &str) -> Policy {
+    Policy::parse(text, dir.to_path_buf()).unwrap()
+}
+
+fn write(dir: &Path, name: &str, source: &str) -> std::path::PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+#[test]
+fn test_forbidden_intrinsics_direct_and_wrapped() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = write(
+        dir.path(),
+        "main.tri",
+        "program test\nuse vm.io.io\nfn main() {\n    let a: Field = divine()\n    let b: Field = io.divine()\n    pub_write(a + b)\n}\n",
+    );
+    let options = CompileOptions::default();
+    let rules = policy(dir.path(), "[build]\nforbidden_intrinsics = [\"divine\"]\n");
+    let violations = enforce_build_policy(&entry, &options, &rules).unwrap_err();
+    assert_eq!(violations.len(), 2);
+    assert!(violations
+        .iter()
+        .all(|v| v.message == "intrinsic `divine` is forbidden"));
+    assert!(violations[1]
+        .notes
+        .contains(&"`io.divine` wraps it".to_string()));
+
+    // The library modules declaring the intrinsic are not the project's.
+    let rules = policy(
+        dir.path(),
+        "[build]\nforbidden_intrinsics = [\"ram_read\"]\n",
+    );
+    assert!(enforce_build_policy(&entry, &options, &rules).is_ok());
+}
+
+#[test]
+fn test_pure_modules_and_padded_height() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "math.tri",
+        "module math\n#[pure]\npub fn double(x: Field) -> Field {\n    x + x\n}\npub fn triple(x: Field) -> Field {\n    x + x + x\n}\n",
+    );
+    let entry = write(
+        dir.path(),
+        "main.tri",
+        "program test\nuse math\nfn main() {\n    let x: Field = pub_read()\n    pub_write(math.double(x) + math.triple(x))\n}\n",
+    );
+    let options = CompileOptions::default();
+    let violations = enforce_build_policy(
+        &entry,
+        &options,
+        &policy(dir.path(), "[build]\npure_modules = [\"math\"]\n"),
+    )
+    .unwrap_err();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].message, "function `triple` must be #[pure]");
+
+    let violations = enforce_build_policy(
+        &entry,
+        &options,
+        &policy(dir.path(), "[build]\nmax_padded_height = 1\n"),
+    )
+    .unwrap_err();
+    assert!(
+        violations[0]
+            .message
+            .ends_with("exceeds the policy limit of 1"),
+        "{}",
+        violations[0].message
+    );
+    assert!(enforce_build_policy(
+        &entry,
+        &options,
+        &policy(dir.path(), "[build]\nmax_padded_height = 1048576\n"),
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_deploy_policy_requires_a_safe_verdict() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = write(
+        dir.path(),
+        "main.tri",
+        "program test\nfn main() {\n    let x: Field = 3\n    assert(x == 4)\n}\n",
+    );
+    let config = solve::VerifyConfig::default();
+    let rules = policy(dir.path(), "[deploy]\nverdict = \"safe\"\n");
+    let violations = enforce_deploy_policy(&entry, &rules, &config).unwrap_err();
+    assert!(
+        violations[0].message.contains("the policy requires safe"),
+        "{}",
+        violations[0].message
+    );
+    assert!(enforce_deploy_policy(&entry, &policy(dir.path(), ""), &config).is_ok());
+}
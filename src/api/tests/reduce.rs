@@ -0,0 +1,24 @@
+use crate::*;
+
+#[test]
+fn test_reduce_source_keeps_only_what_the_failure_needs() {
+    let source = "program test\n\nconst N: Field = 4\n\n// helper\nfn helper(a: Field, b: Field) -> Field {\n    a + b * 2\n}\n\nfn main() {\n    let a: Field = pub_read()\n    let b: Field = helper(a, 3)\n    for i in 0..N {\n        pub_write(b + as_field(i))\n    }\n    let bad: U32 = helper(a, b) + 1\n    pub_write(b)\n}\n";
+    let _silence = crate::diagnostic::silence();
+    let mismatch = |s: &str| match check(s, "test.tri") {
+        Err(diags) => diags.iter().any(|d| d.message.contains("type mismatch")),
+        Ok(()) => false,
+    };
+    assert!(mismatch(source));
+    let reduced = reduce_source(source, mismatch);
+    assert_eq!(
+        reduced,
+        "program test\n\nfn main() {\n    let bad: U32 = 0\n}\n"
+    );
+}
+
+#[test]
+fn test_reduce_source_falls_back_to_lines_without_a_parse() {
+    let source = "program test\nfn main() {\n    let x: Field = \n}\nfn other() {}\n";
+    let reduced = reduce_source(source, |s| s.contains("let x"));
+    assert_eq!(reduced, "    let x: Field = \n");
+}
@@ -0,0 +1,285 @@
+//! Per-phase timings of the compiler pipeline.
+//!
+//! Recording is off until `start` is called on a thread. Each pipeline
+//! phase then logs one event per module it processes — wall time and the
+//! number of items it produced — and memoizing steps count their cache
+//! lookups. `finish` hands back the log: `trident build --timings`
+//! prints it per phase, `--timings=json` as JSON, and `--verbose` event
+//! by event.
+
+use std::cell::RefCell;
+use std::time::Instant;
+
+thread_local! {
+    static LOG: RefCell<Option<Timings>> = const { RefCell::new(None) };
+}
+
+/// Start recording on the current thread, discarding any earlier log.
+pub fn start() {
+    LOG.with(|log| *log.borrow_mut() = Some(Timings::default()));
+}
+
+/// Stop recording and return what was logged since `start`.
+pub fn finish() -> Timings {
+    LOG.with(|log| log.borrow_mut().take()).unwrap_or_default()
+}
+
+/// Log that `phase` ran on `module` since `started`, producing `items`.
+pub(crate) fn record(phase: &'static str, module: &str, started: Instant, items: usize) {
+    LOG.with(|log| {
+        if let Some(timings) = log.borrow_mut().as_mut() {
+            timings.events.push(PhaseEvent {
+                phase,
+                module: module.to_string(),
+                micros: started.elapsed().as_micros() as u64,
+                items: items as u64,
+            });
+        }
+    });
+}
+
+/// Count one lookup in the cache `name`.
+pub(crate) fn cache_lookup(name: &'static str, hit: bool) {
+    cache_counts(name, hit as u64, 1);
+}
+
+/// Count `lookups` lookups, `hits` of them hits, in the cache `name`.
+pub(crate) fn cache_counts(name: &'static str, hits: u64, lookups: u64) {
+    if lookups == 0 {
+        return;
+    }
+    LOG.with(|log| {
+        if let Some(timings) = log.borrow_mut().as_mut() {
+            let pos = match timings.caches.iter().position(|c| c.name == name) {
+                Some(pos) => pos,
+                None => {
+                    timings.caches.push(CacheStats {
+                        name,
+                        hits: 0,
+                        lookups: 0,
+                    });
+                    timings.caches.len() - 1
+                }
+            };
+            timings.caches[pos].lookups += lookups;
+            timings.caches[pos].hits += hits;
+        }
+    });
+}
+
+/// One phase run over one module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhaseEvent {
+    /// `resolve`, `parse`, `typecheck`, `tir`, `lower`, `link`, or `cost`.
+    pub phase: &'static str,
+    /// Module processed; empty for whole-project steps.
+    pub module: String,
+    pub micros: u64,
+    /// Modules, AST items, TIR ops, or instructions, depending on the phase.
+    pub items: u64,
+}
+
+/// Hits and lookups of one cache.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheStats {
+    pub name: &'static str,
+    pub hits: u64,
+    pub lookups: u64,
+}
+
+impl CacheStats {
+    /// Hit rate in whole percent.
+    pub fn hit_percent(&self) -> u64 {
+        (self.hits * 100).checked_div(self.lookups).unwrap_or(0)
+    }
+}
+
+/// Sum of all runs of one phase.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhaseTotal {
+    pub phase: &'static str,
+    pub runs: u64,
+    pub micros: u64,
+    pub items: u64,
+}
+
+/// Everything logged between `start` and `finish`.
+#[derive(Clone, Debug, Default)]
+pub struct Timings {
+    pub events: Vec<PhaseEvent>,
+    pub caches: Vec<CacheStats>,
+}
+
+impl Timings {
+    /// Per-phase totals, in the order the phases first ran.
+    pub fn totals(&self) -> Vec<PhaseTotal> {
+        let mut totals: Vec<PhaseTotal> = Vec::new();
+        for event in &self.events {
+            match totals.iter_mut().find(|t| t.phase == event.phase) {
+                Some(total) => {
+                    total.runs += 1;
+                    total.micros += event.micros;
+                    total.items += event.items;
+                }
+                None => totals.push(PhaseTotal {
+                    phase: event.phase,
+                    runs: 1,
+                    micros: event.micros,
+                    items: event.items,
+                }),
+            }
+        }
+        totals
+    }
+
+    /// Total time across all events.
+    pub fn total_micros(&self) -> u64 {
+        self.events.iter().map(|e| e.micros).sum()
+    }
+
+    /// Per-phase summary table.
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "{:<10} {:>5} {:>8} {:>12}\n",
+            "phase", "runs", "items", "time"
+        );
+        for total in self.totals() {
+            out.push_str(&format!(
+                "{:<10} {:>5} {:>8} {:>12}\n",
+                total.phase,
+                total.runs,
+                total.items,
+                millis(total.micros)
+            ));
+        }
+        out.push_str(&format!(
+            "{:<10} {:>5} {:>8} {:>12}\n",
+            "total",
+            "",
+            "",
+            millis(self.total_micros())
+        ));
+        for cache in &self.caches {
+            out.push_str(&format!(
+                "cache {}: {}/{} hits ({}%)\n",
+                cache.name,
+                cache.hits,
+                cache.lookups,
+                cache.hit_percent()
+            ));
+        }
+        out
+    }
+
+    /// One line per event, in the order they happened.
+    pub fn to_log(&self) -> String {
+        let mut out = String::new();
+        for event in &self.events {
+            out.push_str(&format!(
+                "[{}] {} items={} time={}\n",
+                event.phase,
+                if event.module.is_empty() {
+                    "*"
+                } else {
+                    &event.module
+                },
+                event.items,
+                millis(event.micros)
+            ));
+        }
+        out
+    }
+
+    /// Machine-readable summary for build performance tracking.
+    pub fn to_json(&self) -> String {
+        let phases: Vec<String> = self
+            .totals()
+            .iter()
+            .map(|t| {
+                format!(
+                    "    {{\"phase\": \"{}\", \"runs\": {}, \"items\": {}, \"micros\": {}}}",
+                    t.phase, t.runs, t.items, t.micros
+                )
+            })
+            .collect();
+        let caches: Vec<String> = self
+            .caches
+            .iter()
+            .map(|c| {
+                format!(
+                    "    {{\"name\": \"{}\", \"hits\": {}, \"lookups\": {}, \"hit_percent\": {}}}",
+                    c.name,
+                    c.hits,
+                    c.lookups,
+                    c.hit_percent()
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"phases\": [\n{}\n  ],\n  \"total_micros\": {},\n  \"caches\": [\n{}\n  ]\n}}\n",
+            phases.join(",\n"),
+            self.total_micros(),
+            caches.join(",\n")
+        )
+    }
+}
+
+/// Microseconds as `1.234 ms`.
+fn millis(micros: u64) -> String {
+    format!("{}.{:03} ms", micros / 1000, micros % 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_only_logged_while_recording() {
+        record("parse", "main", Instant::now(), 3);
+        start();
+        record("parse", "std.io", Instant::now(), 2);
+        record("parse", "main", Instant::now(), 5);
+        record("lower", "main", Instant::now(), 40);
+        cache_lookup("cost", false);
+        cache_lookup("cost", true);
+        cache_lookup("cost", true);
+        let timings = finish();
+        assert_eq!(timings.events.len(), 3);
+
+        let totals = timings.totals();
+        assert_eq!(totals.len(), 2);
+        assert_eq!(
+            (totals[0].phase, totals[0].runs, totals[0].items),
+            ("parse", 2, 7)
+        );
+        assert_eq!(
+            (totals[1].phase, totals[1].runs, totals[1].items),
+            ("lower", 1, 40)
+        );
+        assert_eq!(timings.caches[0].hit_percent(), 66);
+
+        cache_lookup("cost", true);
+        assert!(finish().caches.is_empty());
+    }
+
+    #[test]
+    fn json_lists_every_phase_and_cache() {
+        let timings = Timings {
+            events: vec![PhaseEvent {
+                phase: "tir",
+                module: "main".to_string(),
+                micros: 1500,
+                items: 12,
+            }],
+            caches: vec![CacheStats {
+                name: "cost",
+                hits: 1,
+                lookups: 4,
+            }],
+        };
+        let json = timings.to_json();
+        assert!(json.contains("{\"phase\": \"tir\", \"runs\": 1, \"items\": 12, \"micros\": 1500}"));
+        assert!(json.contains("\"hit_percent\": 25"));
+        assert!(timings.to_text().contains("1.500 ms"));
+    }
+}
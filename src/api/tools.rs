@@ -1,19 +1,75 @@
 use super::*;
+use crate::syntax::trivia::TriviaMap;
 
 pub fn analyze_costs(source: &str, filename: &str) -> Result<cost::ProgramCost, Vec<Diagnostic>> {
     let file = crate::parse_source(source, filename)?;
+    let mut file = desugar_file(file, &CompileOptions::default().cfg_flags);
 
-    if let Err(errors) = TypeChecker::new().check_file(&file) {
-        render_diagnostics(&errors, filename, source);
-        return Err(errors);
+    match TypeChecker::new().check_file(&file) {
+        Ok(exports) => fold_constants(&mut file, &exports),
+        Err(errors) => {
+            render_diagnostics(&errors, filename, source);
+            return Err(errors);
+        }
     }
 
-    let cost = cost::CostAnalyzer::default().analyze_file(&file);
+    let mut analyzer = cost::CostAnalyzer::default();
+    let mut cost = analyzer.analyze_file(&file);
+    analyzer.attach_what_if(&file, source, &mut cost);
     Ok(cost)
 }
 
+/// Compile two versions of a source file in-process and diff their costs
+/// function by function, with the changed lines inline.
+///
+/// Functions are aligned by name, then by content hash, so a rename does
+/// not read as a removal and an addition.
+pub fn diff_source_costs(
+    old_source: &str,
+    old_filename: &str,
+    new_source: &str,
+    new_filename: &str,
+) -> Result<cost::SourceDiff, Vec<Diagnostic>> {
+    let analyze = |source: &str, filename: &str| {
+        let file = crate::parse_source(source, filename)?;
+        let mut file = desugar_file(file, &CompileOptions::default().cfg_flags);
+        match TypeChecker::new().check_file(&file) {
+            Ok(exports) => fold_constants(&mut file, &exports),
+            Err(errors) => {
+                render_diagnostics(&errors, filename, source);
+                return Err(errors);
+            }
+        }
+        let mut analyzer = cost::CostAnalyzer::default();
+        let mut cost = analyzer.analyze_file(&file);
+        analyzer.attach_what_if(&file, source, &mut cost);
+        Ok((file, cost))
+    };
+    let (old_file, old_cost) = analyze(old_source, old_filename)?;
+    let (new_file, new_cost) = analyze(new_source, new_filename)?;
+    Ok(cost::diff_sources(
+        cost::SourceVersion {
+            name: old_filename,
+            file: &old_file,
+            source: old_source,
+            cost: old_cost,
+        },
+        cost::SourceVersion {
+            name: new_filename,
+            file: &new_file,
+            source: new_source,
+            cost: new_cost,
+        },
+    ))
+}
+
 /// Parse, type-check, and compute cost analysis for a multi-module project.
-/// Falls back to single-file analysis if module resolution fails.
+///
+/// Every module is analyzed, charging calls into its dependencies for
+/// their bodies, and its function costs are listed in `modules`. The
+/// program file (last in topological order) provides the totals. Function
+/// costs of modules whose content did not change are reused from earlier
+/// analyses in the same process.
 pub fn analyze_costs_project(
     entry_path: &Path,
     options: &CompileOptions,
@@ -21,17 +77,51 @@ pub fn analyze_costs_project(
     use crate::pipeline::PreparedProject;
 
     let project = PreparedProject::build(entry_path, options)?;
-
-    // Analyze costs for the program file (last in topological order)
-    if let Some(file) = project.last_file() {
-        let cost = cost::CostAnalyzer::for_target(&options.target_config.name).analyze_file(file);
-        Ok(cost)
-    } else {
-        Err(vec![Diagnostic::error(
+    let Some((program, deps)) = project.modules.split_last() else {
+        return Err(vec![Diagnostic::error(
             "no program file found".to_string(),
             span::Span::dummy(),
-        )])
+        )]);
+    };
+
+    let target = &options.target_config.name;
+    let analyzer =
+        cost::CostAnalyzer::for_target(target).with_imports(deps.iter().map(|m| &m.file));
+    let mut digest = [0; 32];
+    let mut modules = Vec::with_capacity(project.modules.len());
+    for pm in deps {
+        digest = cost::cache::module_digest(target, &pm.source, &digest);
+        let _stage = crash::stage("cost", &pm.file.name.node, Some(&pm.source));
+        let started = Instant::now();
+        let cost = analyzer
+            .clone()
+            .with_content_digest(digest)
+            .analyze_file(&pm.file);
+        timings::record("cost", &pm.file.name.node, started, cost.functions.len());
+        modules.push(cost::ModuleCost {
+            module: pm.file.name.node.clone(),
+            functions: cost.functions,
+        });
     }
+
+    digest = cost::cache::module_digest(target, &program.source, &digest);
+    let _stage = crash::stage("cost", &program.file.name.node, Some(&program.source));
+    let started = Instant::now();
+    let mut analyzer = analyzer.with_content_digest(digest);
+    let mut cost = analyzer.analyze_file(&program.file);
+    analyzer.attach_what_if(&program.file, &program.source, &mut cost);
+    timings::record(
+        "cost",
+        &program.file.name.node,
+        started,
+        cost.functions.len(),
+    );
+    modules.push(cost::ModuleCost {
+        module: program.file.name.node.clone(),
+        functions: cost.functions.clone(),
+    });
+    cost.modules = modules;
+    Ok(cost)
 }
 
 /// Parse, type-check, and verify a project using symbolic execution + solver.
@@ -40,26 +130,68 @@ pub fn analyze_costs_project(
 /// Returns a `VerificationReport` with static analysis, random testing (Schwartz-Zippel),
 /// and bounded model checking results.
 pub fn verify_project(entry_path: &Path) -> Result<solve::VerificationReport, Vec<Diagnostic>> {
+    verify_project_with(entry_path, &solve::VerifyConfig::default())
+}
+
+/// Like `verify_project`, within `config`'s budgets. The time budget is
+/// per function: the combined check gets one for each function it merges.
+pub fn verify_project_with(
+    entry_path: &Path,
+    config: &solve::VerifyConfig,
+) -> Result<solve::VerificationReport, Vec<Diagnostic>> {
     use crate::pipeline::PreparedProject;
 
     let project = PreparedProject::build_default(entry_path)?;
 
     // Collect constraint systems from all functions in all modules
     let mut combined = sym::ConstraintSystem::new();
+    let mut functions = 0u32;
     for pm in &project.modules {
         for (_, system) in sym::analyze_all(&pm.file) {
-            combined.constraints.extend(system.constraints);
-            combined.num_variables += system.num_variables;
-            for (k, v) in system.variables {
-                combined.variables.insert(k, v);
-            }
-            combined.pub_inputs.extend(system.pub_inputs);
-            combined.pub_outputs.extend(system.pub_outputs);
-            combined.divine_inputs.extend(system.divine_inputs);
+            functions += 1;
+            combined.merge(system);
         }
     }
 
-    Ok(solve::verify(&combined))
+    let config = solve::VerifyConfig {
+        time_budget: config
+            .time_budget
+            .map(|budget| budget.saturating_mul(functions.max(1))),
+        ..config.clone()
+    };
+    Ok(solve::verify_with(&combined, &config))
+}
+
+/// Redundant assertions of a program file that can be deleted, with the
+/// rows each deletion saves. Every function is checked, as `trident
+/// audit` does, within `config`'s budgets; a check cut short by its
+/// budget finds none, since too few samples make assertions look
+/// redundant.
+pub fn redundant_assertions(
+    source: &str,
+    filename: &str,
+    config: &solve::VerifyConfig,
+) -> Vec<crate::report::AssertionFix> {
+    let Ok(file) = crate::parse_source_silent(source, filename) else {
+        return Vec::new();
+    };
+    let mut system = sym::ConstraintSystem::new();
+    let mut functions = 0u32;
+    for (_, fn_system) in sym::analyze_all(&file) {
+        functions += 1;
+        system.merge(fn_system);
+    }
+    let config = solve::VerifyConfig {
+        time_budget: config
+            .time_budget
+            .map(|budget| budget.saturating_mul(functions.max(1))),
+        ..config.clone()
+    };
+    let report = solve::verify_with(&system, &config);
+    if report.random_result.budget_exhausted || report.bmc_result.budget_exhausted {
+        return Vec::new();
+    }
+    crate::report::redundant_assertion_fixes(&file, &system, &report)
 }
 
 /// Verify all functions in a project, returning per-function results.
@@ -67,6 +199,15 @@ pub fn verify_project(entry_path: &Path) -> Result<solve::VerificationReport, Ve
 /// Each entry in the returned vec is `(module_name, fn_name, report)`.
 pub fn verify_project_per_function(
     entry_path: &Path,
+) -> Result<Vec<(String, String, solve::VerificationReport)>, Vec<Diagnostic>> {
+    verify_project_per_function_with(entry_path, &solve::VerifyConfig::default())
+}
+
+/// Like `verify_project_per_function`, checking each function within
+/// `config`'s budgets.
+pub fn verify_project_per_function_with(
+    entry_path: &Path,
+    config: &solve::VerifyConfig,
 ) -> Result<Vec<(String, String, solve::VerificationReport)>, Vec<Diagnostic>> {
     use crate::pipeline::PreparedProject;
 
@@ -76,7 +217,7 @@ pub fn verify_project_per_function(
     for pm in &project.modules {
         let module_name = pm.file.name.node.clone();
         for (fn_name, system) in sym::analyze_all(&pm.file) {
-            let report = solve::verify(&system);
+            let report = solve::verify_with(&system, config);
             results.push((module_name.clone(), fn_name, report));
         }
     }
@@ -297,6 +438,68 @@ pub fn generate_docs(
     doc::generate_docs(entry_path, options)
 }
 
+/// Index every definition of a project for external tools.
+pub fn index_project(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<Vec<IndexEntry>, Vec<Diagnostic>> {
+    index::index_project(entry_path, options)
+}
+
+/// Public library functions no program or test of the project uses as
+/// exports: never reached, or reached only from their own module.
+pub fn unused_exports(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<UnusedExports, Vec<Diagnostic>> {
+    unused::unused_exports(entry_path, options)
+}
+
+/// What the compiled program reveals beyond its declared output: the
+/// constants its TASM pushes, the events it emits, and the branches on
+/// secret input that change the trace height.
+pub fn leakage_report(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<LeakageReport, Vec<Diagnostic>> {
+    leakage::leakage_report(entry_path, options)
+}
+
+/// Render an index as a sorted ctags file, paths relative to `root`.
+pub fn format_ctags(entries: &[IndexEntry], root: &Path) -> String {
+    index::format_ctags(entries, root)
+}
+
+/// Render an index as JSON Lines, paths relative to `root`.
+pub fn format_index_json(entries: &[IndexEntry], root: &Path) -> String {
+    index::format_json_lines(entries, root)
+}
+
+/// Disassemble linked TASM into functions with nested branches and
+/// loops. Functions found in `source_map` (see `index_project`) are
+/// annotated with their definition, paths relative to `root`.
+pub fn disassemble_tasm(
+    tasm: &str,
+    source_map: &[IndexEntry],
+    root: &Path,
+) -> Result<String, String> {
+    disasm::disassemble(tasm, source_map, root)
+}
+
+/// Optimize handwritten Triton assembly: lift it to TIR, reading its
+/// labels as those of `module`, run the TIR optimizer, and lower it
+/// again. Code without a TIR counterpart passes through unchanged.
+pub fn optimize_tasm(tasm: &str, module: &str) -> Result<String, String> {
+    let ir = crate::tir::lift::lift(tasm, module)?;
+    let lowering = create_stack_lowering("triton");
+    Ok(lowering.lower(&optimize_tir(ir)).join("\n"))
+}
+
+/// Render documentation from `generate_docs` as a standalone HTML page.
+pub fn docs_to_html(markdown: &str) -> String {
+    doc::render_html(markdown)
+}
+
 /// Parse, type-check, and produce per-line cost-annotated source output.
 pub fn annotate_source(source: &str, filename: &str) -> Result<String, Vec<Diagnostic>> {
     annotate_source_with_target(source, filename, "triton")
@@ -370,8 +573,47 @@ pub fn format_source(source: &str, _filename: &str) -> Result<String, Vec<Diagno
     if !lex_errors.is_empty() {
         return Err(lex_errors);
     }
+    let trivia = TriviaMap::build(source, &tokens, &comments);
     let file = parser::Parser::new(tokens).parse_file()?;
-    Ok(format::format_file(&file, &comments))
+    Ok(format::format_file(&file, &trivia))
+}
+
+/// The core AST of every project module, printed as source, in
+/// dependency order: `(module name, desugared source)`.
+pub fn desugar_project(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<Vec<(String, String)>, Vec<Diagnostic>> {
+    use crate::pipeline::PreparedProject;
+
+    let project = PreparedProject::build(entry_path, options)?;
+    Ok(project
+        .modules
+        .iter()
+        .map(|pm| {
+            (
+                pm.file.name.node.clone(),
+                format::format_file(&pm.file, &TriviaMap::default()),
+            )
+        })
+        .collect())
+}
+
+/// Dump each module's parsed AST with spans (`trident build --emit ast`),
+/// by module name, in dependency order. Nothing is desugared or checked.
+pub fn dump_ast_project(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<Vec<(String, String)>, Vec<Diagnostic>> {
+    use crate::pipeline::{parse_module, resolve_project};
+
+    resolve_project(entry_path, options)?
+        .iter()
+        .map(|m| {
+            let file = parse_module(m)?;
+            Ok((file.name.node.clone(), crate::ast::dump::dump_file(&file)))
+        })
+        .collect()
 }
 
 /// Type-check only, without rendering diagnostics to stderr.
@@ -387,6 +629,50 @@ pub fn check_silent(source: &str, filename: &str) -> Result<(), Vec<Diagnostic>>
 /// the given file with full module context.
 /// Falls back to single-file check if no project is found.
 pub fn check_file_in_project(source: &str, file_path: &Path) -> Result<(), Vec<Diagnostic>> {
+    check_file_in_project_with_options(source, file_path, &CompileOptions::default())
+}
+
+/// Project-aware type-check with explicit options: the profile's cfg
+/// flags select the active items, the target supplies the intrinsics.
+pub fn check_file_in_project_with_options(
+    source: &str,
+    file_path: &Path,
+    options: &CompileOptions,
+) -> Result<(), Vec<Diagnostic>> {
+    check_in_project(source, file_path, options).map(|_| ())
+}
+
+/// Imports of the given file that the typechecker reports as unused,
+/// as dotted module paths. Empty when the file cannot be checked.
+pub fn unused_imports_in_project(
+    source: &str,
+    file_path: &Path,
+    options: &CompileOptions,
+) -> Vec<String> {
+    let diagnostics = match check_in_project(source, file_path, options) {
+        Ok(warnings) => warnings,
+        Err(errors) => errors,
+    };
+    diagnostics
+        .iter()
+        .filter_map(|d| d.message.strip_prefix("unused import '"))
+        .filter_map(|rest| rest.strip_suffix('\''))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Shared body of the project-aware checks: the file's warnings on
+/// success, its diagnostics on failure.
+pub(super) fn check_in_project(
+    source: &str,
+    file_path: &Path,
+    options: &CompileOptions,
+) -> Result<Vec<Diagnostic>, Vec<Diagnostic>> {
+    let checker = || {
+        TypeChecker::with_target(options.target_config.clone())
+            .with_cfg_flags(options.cfg_flags.clone())
+    };
+
     let dir = file_path.parent().unwrap_or(Path::new("."));
     let entry = match project::Project::find(dir) {
         Some(toml_path) => match project::Project::load(&toml_path) {
@@ -399,45 +685,79 @@ pub fn check_file_in_project(source: &str, file_path: &Path) -> Result<(), Vec<D
     // Resolve all modules from the entry point (handles std.* even without project)
     let modules = match resolve_modules(&entry) {
         Ok(m) => m,
-        Err(_) => return check_silent(source, &file_path.to_string_lossy()),
+        Err(_) => {
+            let file = crate::parse_source_silent(source, &file_path.to_string_lossy())?;
+            return Ok(checker().with_source_dir(dir).check_file(&file)?.warnings);
+        }
     };
 
     // Parse and type-check all modules in dependency order
     let mut all_exports: Vec<ModuleExports> = Vec::new();
-    let file_path_canon = file_path
-        .canonicalize()
-        .unwrap_or_else(|_| file_path.to_path_buf());
-
     for module in &modules {
-        let mod_path_canon = module
-            .file_path
-            .canonicalize()
-            .unwrap_or_else(|_| module.file_path.clone());
-        let is_target = mod_path_canon == file_path_canon;
-
         // Use live buffer for the file being edited
-        let src = if is_target { source } else { &module.source };
-        let parsed = crate::parse_source_silent(src, &module.file_path.to_string_lossy())?;
-
-        let mut tc = TypeChecker::new();
+        let live = module
+            .file_range(file_path)
+            .map(|_| module.with_file_source(file_path, source));
+        let is_target = live.is_some();
+        let module = live.as_ref().unwrap_or(module);
+        let parsed = crate::pipeline::parse_module_silent(module)
+            .map_err(|errors| in_file(errors, module, file_path))?;
+
+        let mut tc = checker().with_source_dir(crate::pipeline::source_dir(&module.file_path));
         for exports in &all_exports {
             tc.import_module(exports);
         }
 
         match tc.check_file(&parsed) {
             Ok(exports) => {
+                if is_target {
+                    return Ok(in_file(exports.warnings, module, file_path));
+                }
                 all_exports.push(exports);
             }
             Err(errors) => {
                 if is_target {
-                    return Err(errors);
+                    return Err(in_file(errors, module, file_path));
                 }
                 // Dep has errors — stop, but don't report
                 // dep errors as if they're in this file
-                return Ok(());
+                return Ok(Vec::new());
             }
         }
     }
 
-    Ok(())
+    Ok(Vec::new())
+}
+
+/// The diagnostics of `module` that fall in its file at `path`, with
+/// spans relative to that file. Only a multi-file program has others.
+fn in_file(
+    diagnostics: Vec<Diagnostic>,
+    module: &crate::resolve::ModuleInfo,
+    path: &Path,
+) -> Vec<Diagnostic> {
+    let Some(range) = module.file_range(path).filter(|_| !module.parts.is_empty()) else {
+        return diagnostics;
+    };
+    diagnostics
+        .into_iter()
+        .filter(|d| range.contains(&(d.span.start as usize)))
+        .map(|mut d| {
+            d.span.start -= range.start as u32;
+            d.span.end = (d.span.end as usize)
+                .min(range.end)
+                .saturating_sub(range.start) as u32;
+            // A suggestion reaching into another file cannot be applied here.
+            d.suggestions.retain(|s| {
+                s.edits.iter().all(|(span, _)| {
+                    range.contains(&(span.start as usize)) && span.end as usize <= range.end
+                })
+            });
+            for (span, _) in d.suggestions.iter_mut().flat_map(|s| &mut s.edits) {
+                span.start -= range.start as u32;
+                span.end -= range.start as u32;
+            }
+            d
+        })
+        .collect()
 }
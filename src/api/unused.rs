@@ -0,0 +1,232 @@
+//! Public library functions the workspace does not use.
+//!
+//! The program's `main` and entrypoints and every `#[test]` function are
+//! the roots; calls are followed from them across modules. A `pub`
+//! function of a library module that no root reaches is never called by
+//! anything the workspace builds or tests, and can be removed. One that
+//! is reached only through its own module's functions is used, but not
+//! as an export, and can drop `pub`.
+//!
+//! Only modules inside the project directory are reported: the standard
+//! library and dependencies have users this project cannot see.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::ast::{self, FileKind};
+use crate::diagnostic::Diagnostic;
+use crate::pipeline::PreparedProject;
+use crate::span::Span;
+use crate::typecheck::TypeChecker;
+use crate::CompileOptions;
+
+use super::index::line_col;
+
+/// Why an export is reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnusedKind {
+    /// No program or test reaches the function.
+    Unreachable,
+    /// Only functions of its own module call it.
+    Internal,
+}
+
+/// A `pub` library function the workspace does not use as an export.
+/// Lines and columns are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnusedExport {
+    pub module: String,
+    pub name: String,
+    pub kind: UnusedKind,
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    /// Span of the function's name in `file`.
+    pub span: Span,
+}
+
+/// Unused exports of a project's library modules.
+#[derive(Clone, Debug, Default)]
+pub struct UnusedExports {
+    /// `pub` functions across the library modules.
+    pub exports: usize,
+    /// In dependency order, each module's in source order.
+    pub unused: Vec<UnusedExport>,
+}
+
+impl UnusedExport {
+    /// The finding as a warning, or as an error under `deny`.
+    pub fn diagnostic(&self, deny: bool) -> Diagnostic {
+        let (message, help) = match self.kind {
+            UnusedKind::Unreachable => (
+                format!(
+                    "public function '{}.{}' is never called by a program or test",
+                    self.module, self.name
+                ),
+                "remove it, or call it from a program or test".to_string(),
+            ),
+            UnusedKind::Internal => (
+                format!(
+                    "public function '{}.{}' is only called inside '{}'",
+                    self.module, self.name, self.module
+                ),
+                "drop `pub`: no other module calls it".to_string(),
+            ),
+        };
+        let diag = if deny {
+            Diagnostic::error(message, self.span)
+        } else {
+            Diagnostic::warning(message, self.span)
+        };
+        diag.with_help(help)
+    }
+}
+
+/// Index of the module a call prefix names: its full dotted name or,
+/// for dotted modules, its last segment.
+fn module_index(files: &[&ast::File], prefix: &str) -> Option<usize> {
+    files
+        .iter()
+        .position(|f| f.name.node == prefix)
+        .or_else(|| {
+            files
+                .iter()
+                .position(|f| f.name.node.rsplit('.').next() == Some(prefix))
+        })
+}
+
+/// Directory of the project: the entry's, or the entry itself when it
+/// is a directory.
+fn project_dir(entry_path: &Path) -> PathBuf {
+    let entry = canonical(entry_path);
+    match entry.parent() {
+        Some(dir) if entry.is_file() => dir.to_path_buf(),
+        _ => entry,
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Find the unused exports of the project at `entry_path`.
+pub(crate) fn unused_exports(
+    entry_path: &Path,
+    options: &CompileOptions,
+) -> Result<UnusedExports, Vec<Diagnostic>> {
+    let project = PreparedProject::build(entry_path, options)?;
+    let files: Vec<&ast::File> = project.modules.iter().map(|pm| &pm.file).collect();
+
+    // Functions by (module, name), with the functions each one calls.
+    let mut callees: BTreeMap<(usize, &str), Vec<(usize, String)>> = BTreeMap::new();
+    let mut roots = Vec::new();
+    for (m, file) in files.iter().enumerate() {
+        for item in &file.items {
+            let ast::Item::Fn(func) = &item.node else {
+                continue;
+            };
+            let name = func.name.node.as_str();
+            let is_entry =
+                file.kind == FileKind::Program && (name == "main" || func.entrypoint.is_some());
+            if is_entry || func.is_test {
+                roots.push((m, name));
+            }
+            let mut calls = Vec::new();
+            if let Some(body) = &func.body {
+                TypeChecker::collect_calls_block(&body.node, &mut calls);
+            }
+            let resolved = calls
+                .into_iter()
+                .filter_map(|call| match call.rsplit_once('.') {
+                    Some((prefix, callee)) => {
+                        Some((module_index(&files, prefix)?, callee.to_string()))
+                    }
+                    None => Some((m, call)),
+                })
+                .collect();
+            callees.insert((m, name), resolved);
+        }
+    }
+
+    // Walk from the roots, noting which functions another module calls.
+    let mut reached: BTreeSet<(usize, &str)> = roots.iter().copied().collect();
+    let mut external: BTreeSet<(usize, &str)> = BTreeSet::new();
+    let mut queue: VecDeque<(usize, &str)> = roots.into_iter().collect();
+    while let Some(caller) = queue.pop_front() {
+        for (m, name) in &callees[&caller] {
+            let Some((&callee, _)) = callees.get_key_value(&(*m, name.as_str())) else {
+                continue;
+            };
+            if callee.0 != caller.0 {
+                external.insert(callee);
+            }
+            if reached.insert(callee) {
+                queue.push_back(callee);
+            }
+        }
+    }
+
+    let root = project_dir(entry_path);
+    let mut report = UnusedExports::default();
+    for (m, pm) in project.modules.iter().enumerate() {
+        let in_project = canonical(&pm.file_path).starts_with(&root);
+        if pm.file.kind != FileKind::Module || !in_project {
+            continue;
+        }
+        for item in &pm.file.items {
+            let ast::Item::Fn(func) = &item.node else {
+                continue;
+            };
+            if !func.is_pub || func.is_test {
+                continue;
+            }
+            report.exports += 1;
+            let key = (m, func.name.node.as_str());
+            let kind = if !reached.contains(&key) {
+                UnusedKind::Unreachable
+            } else if !external.contains(&key) {
+                UnusedKind::Internal
+            } else {
+                continue;
+            };
+            let (line, column) = line_col(&pm.source, func.name.span.start);
+            report.unused.push(UnusedExport {
+                module: pm.file.name.node.clone(),
+                name: func.name.node.clone(),
+                kind,
+                file: pm.file_path.clone(),
+                line,
+                column,
+                span: func.name.span,
+            });
+        }
+    }
+    Ok(report)
+}
+
+impl fmt::Display for UnusedExports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Unused exports: {} of {} public library functions",
+            self.unused.len(),
+            self.exports
+        )?;
+        for e in &self.unused {
+            let why = match e.kind {
+                UnusedKind::Unreachable => "never called".to_string(),
+                UnusedKind::Internal => format!("only called inside '{}'", e.module),
+            };
+            writeln!(
+                f,
+                "  {:<40} {}:{}  {}",
+                format!("{}.{}", e.module, e.name),
+                e.file.display(),
+                e.line,
+                why
+            )?;
+        }
+        Ok(())
+    }
+}
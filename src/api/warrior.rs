@@ -0,0 +1,28 @@
+//! What a warrior builds on.
+//!
+//! A warrior is the crate that runs, proves, and deploys Trident
+//! programs on one VM. It implements the runtime traits here and reuses
+//! the universal primitives — field arithmetic, the value codec,
+//! Poseidon2, Merkle paths, and proof-size estimates — instead of
+//! reimplementing them. `MockNode` serves a node in memory to test a
+//! deploy path against.
+
+pub use crate::deploy::rpc::{
+    DeployReceipt, DeployRequest, MockDeployer, MockDeployment, MockNode, RpcDeployer,
+};
+pub use crate::field::codec::{decode_item, encode_item, BFieldCodec, Reader};
+pub use crate::field::merkle::{
+    AuthPath, Digest, MerkleHasher, MerkleTree, Poseidon2Hasher, DIGEST_LEN,
+};
+pub use crate::field::poseidon2::{
+    hash_bytes_goldilocks, hash_fields_goldilocks, permute, round_constants,
+};
+pub use crate::field::proof::{
+    estimate_proof_size, estimate_proving_ns, fri_query_count, merkle_depth, ntt_domain_size,
+    padded_height, Claim,
+};
+pub use crate::field::{BabyBear, Goldilocks, Mersenne31, PrimeField};
+pub use crate::runtime::{
+    BuildInfo, Deployer, EnvelopeSignature, ExecutionResult, GuessResult, Guesser, ProgramInput,
+    ProofData, ProofEnvelope, Prover, Runner, StateProvider, Verifier,
+};
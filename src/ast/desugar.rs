@@ -0,0 +1,693 @@
+//! Desugaring: rewrite a parsed file into the core AST.
+//!
+//! The type checker and the TIR builder both consume the output of
+//! `desugar_file`, so surface conveniences are resolved once, here,
+//! instead of in each consumer:
+//!
+//! - items whose `#[cfg(flag)]` is inactive are dropped;
+//! - `reveal` / `seal` field initializers are put in the event's
+//!   declaration order, the order their values are pushed;
+//! - `let (a, b) = (x, y)` becomes `let a = x; let b = y;` when no
+//!   element mentions a name bound before it;
+//! - a program with `#[entrypoint]` functions gets a `main` that reads a
+//!   selector from public input and calls the entrypoint it names.
+//!
+//! Rewritten nodes keep the spans of the source they came from, so
+//! diagnostics on the core AST still point at what the user wrote.
+//!
+//! `desugar_loop_exits` runs later, after type checking, so that the
+//! checker still sees `break` and `continue` where the user wrote them.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::*;
+use crate::span::{Span, Spanned};
+
+/// Whether `item` is compiled under `cfg_flags`.
+pub fn is_item_active(item: &Item, cfg_flags: &BTreeSet<String>) -> bool {
+    let cfg = match item {
+        Item::Fn(f) => &f.cfg,
+        Item::Const(c) => &c.cfg,
+        Item::Struct(s) => &s.cfg,
+        Item::Enum(e) => &e.cfg,
+        Item::Event(e) => &e.cfg,
+    };
+    cfg.as_ref()
+        .is_none_or(|flag| cfg_flags.contains(&flag.node))
+}
+
+/// Rewrite `file` into the core AST for the given cfg flags.
+pub fn desugar_file(mut file: File, cfg_flags: &BTreeSet<String>) -> File {
+    file.items
+        .retain(|item| is_item_active(&item.node, cfg_flags));
+    let events: BTreeMap<String, Vec<String>> = file
+        .items
+        .iter()
+        .filter_map(|item| match &item.node {
+            Item::Event(e) => Some((
+                e.name.node.clone(),
+                e.fields.iter().map(|f| f.name.node.clone()).collect(),
+            )),
+            _ => None,
+        })
+        .collect();
+    for item in &mut file.items {
+        if let Item::Fn(func) = &mut item.node {
+            if let Some(body) = &mut func.body {
+                desugar_block(&mut body.node, &events);
+            }
+        }
+    }
+    let has_main = file
+        .items
+        .iter()
+        .any(|item| matches!(&item.node, Item::Fn(f) if f.name.node == "main"));
+    if file.kind == FileKind::Program && !has_main {
+        if let Some(main) = dispatch_main(&file) {
+            file.items.push(main);
+        }
+    }
+    file
+}
+
+/// The selector each `#[entrypoint]` function of `file` is dispatched
+/// on, in selector order. `#[entrypoint(n)]` takes selector `n`; a bare
+/// `#[entrypoint]` takes the lowest selector left, in declaration order.
+/// Selectors that do not parse are left out; the type checker reports
+/// them.
+pub fn entrypoints(file: &File) -> Vec<(u64, &FnDef)> {
+    let fns: Vec<(&FnDef, &Spanned<String>)> = file
+        .items
+        .iter()
+        .filter_map(|item| match &item.node {
+            Item::Fn(f) => f.entrypoint.as_ref().map(|attr| (f, attr)),
+            _ => None,
+        })
+        .collect();
+    let mut table: Vec<(u64, &FnDef)> = fns
+        .iter()
+        .filter_map(|(f, attr)| Some((explicit_selector(&attr.node)?.ok()?, *f)))
+        .collect();
+    let mut taken: BTreeSet<u64> = table.iter().map(|(s, _)| *s).collect();
+    let mut next = 0;
+    for (f, attr) in &fns {
+        if explicit_selector(&attr.node).is_none() {
+            while taken.contains(&next) {
+                next += 1;
+            }
+            taken.insert(next);
+            table.push((next, *f));
+        }
+    }
+    table.sort_by_key(|(s, _)| *s);
+    table
+}
+
+/// The selector written in `entrypoint(n)`; `None` for a bare
+/// `entrypoint`.
+pub fn explicit_selector(attr: &str) -> Option<Result<u64, std::num::ParseIntError>> {
+    let selector = attr.strip_prefix("entrypoint(")?.strip_suffix(')')?;
+    Some(selector.trim().parse::<u64>())
+}
+
+/// `fn main() { let selector: Field = pub_read(); if selector == 0 { pay() }
+/// else if ... else { assert(false, "unknown entrypoint selector") } }` over
+/// the entrypoints that take no arguments and return nothing; the type
+/// checker rejects the others.
+fn dispatch_main(file: &File) -> Option<Spanned<Item>> {
+    let table: Vec<(u64, &FnDef)> = entrypoints(file)
+        .into_iter()
+        .filter(|(_, f)| f.params.is_empty() && f.return_ty.is_none() && f.type_params.is_empty())
+        .collect();
+    let span = table.first()?.1.entrypoint.as_ref()?.span;
+    let call = |name: &str, args: Vec<Spanned<Expr>>| {
+        Spanned::new(
+            Expr::Call {
+                path: Spanned::new(ModulePath::single(name.to_string()), span),
+                generic_args: Vec::new(),
+                args,
+            },
+            span,
+        )
+    };
+    let block = |stmt: Stmt| {
+        Spanned::new(
+            Block {
+                stmts: vec![Spanned::new(stmt, span)],
+                tail_expr: None,
+            },
+            span,
+        )
+    };
+    let message = Literal::Str("unknown entrypoint selector".to_string());
+    let mut dispatch = Stmt::Expr(call(
+        "assert",
+        vec![
+            Spanned::new(Expr::Literal(Literal::Bool(false)), span),
+            Spanned::new(Expr::Literal(message), span),
+        ],
+    ));
+    for (selector, f) in table.iter().rev() {
+        let cond = Expr::BinOp {
+            op: BinOp::Eq,
+            lhs: Box::new(Spanned::new(Expr::Var("selector".to_string()), span)),
+            rhs: Box::new(Spanned::new(
+                Expr::Literal(Literal::Integer(*selector)),
+                span,
+            )),
+        };
+        dispatch = Stmt::If {
+            cond: Spanned::new(cond, span),
+            then_block: block(Stmt::Expr(call(&f.name.node, Vec::new()))),
+            else_block: Some(block(dispatch)),
+        };
+    }
+    let read = Stmt::Let {
+        mutable: false,
+        pattern: Pattern::Name(Spanned::new("selector".to_string(), span)),
+        ty: Some(Spanned::new(Type::Field, span)),
+        init: call("pub_read", Vec::new()),
+    };
+    let main = FnDef {
+        is_pub: false,
+        cfg: None,
+        intrinsic: None,
+        is_test: false,
+        is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
+        requires: Vec::new(),
+        ensures: Vec::new(),
+        assumes: Vec::new(),
+        name: Spanned::new("main".to_string(), span),
+        type_params: Vec::new(),
+        params: Vec::new(),
+        return_ty: None,
+        body: Some(Spanned::new(
+            Block {
+                stmts: vec![Spanned::new(read, span), Spanned::new(dispatch, span)],
+                tail_expr: None,
+            },
+            span,
+        )),
+    };
+    Some(Spanned::new(Item::Fn(main), span))
+}
+
+fn desugar_block(block: &mut Block, events: &BTreeMap<String, Vec<String>>) {
+    let stmts = std::mem::take(&mut block.stmts);
+    for mut stmt in stmts {
+        match &mut stmt.node {
+            Stmt::If {
+                then_block,
+                else_block,
+                ..
+            } => {
+                desugar_block(&mut then_block.node, events);
+                if let Some(else_block) = else_block {
+                    desugar_block(&mut else_block.node, events);
+                }
+            }
+            Stmt::For { body, .. } | Stmt::While { body, .. } => {
+                desugar_block(&mut body.node, events)
+            }
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    desugar_block(&mut arm.body.node, events);
+                }
+            }
+            Stmt::Reveal { event_name, fields } | Stmt::Seal { event_name, fields } => {
+                if let Some(order) = events.get(&event_name.node) {
+                    // Unknown fields sort last; the type checker reports them.
+                    fields.sort_by_key(|(name, _)| {
+                        order
+                            .iter()
+                            .position(|n| *n == name.node)
+                            .unwrap_or(order.len())
+                    });
+                }
+            }
+            Stmt::Let { .. } => {
+                if let Some(lets) = split_tuple_let(&stmt) {
+                    block.stmts.extend(lets);
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        block.stmts.push(stmt);
+    }
+}
+
+/// `let (a, b) = (x, y)` as one `let` per element, or `None` when the
+/// split could change what a name refers to.
+fn split_tuple_let(stmt: &Spanned<Stmt>) -> Option<Vec<Spanned<Stmt>>> {
+    let Stmt::Let {
+        mutable,
+        pattern: Pattern::Tuple(names),
+        ty,
+        init,
+    } = &stmt.node
+    else {
+        return None;
+    };
+    let Expr::Tuple(elems) = &init.node else {
+        return None;
+    };
+    if names.len() != elems.len() || names.iter().any(|n| n.node == "_") {
+        return None;
+    }
+    let elem_tys = match ty {
+        None => vec![None; names.len()],
+        Some(ty) => match &ty.node {
+            Type::Tuple(tys) if tys.len() == names.len() => tys
+                .iter()
+                .map(|t| Some(Spanned::new(t.clone(), ty.span)))
+                .collect(),
+            _ => return None,
+        },
+    };
+    let mut bound: BTreeSet<&str> = BTreeSet::new();
+    let mut lets = Vec::with_capacity(names.len());
+    for ((name, elem), ty) in names.iter().zip(elems).zip(elem_tys) {
+        if mentions_any(&elem.node, &bound) {
+            return None;
+        }
+        bound.insert(&name.node);
+        lets.push(Spanned::new(
+            Stmt::Let {
+                mutable: *mutable,
+                pattern: Pattern::Name(name.clone()),
+                ty,
+                init: elem.clone(),
+            },
+            stmt.span,
+        ));
+    }
+    Some(lets)
+}
+
+/// Rewrite `break` and `continue` into skip flags, so every loop still
+/// runs its full bound and its cost stays the declared worst case:
+///
+/// ```text
+/// for i in 0..n bounded 8 {       let mut __running_7: Bool = true
+///     a()                         for i in 0..n bounded 8 {
+///     if c {                          if __running_7 {
+///         break               =>          let mut __live_7: Bool = true
+///     }                                   a()
+///     b()                                 if c {
+/// }                                           __running_7 = false
+///                                             __live_7 = false
+///                                         }
+///                                         if __live_7 {
+///                                             b()
+///                                         }
+///                                     }
+///                                 }
+/// ```
+///
+/// The flags are named after the loop's offset in the source.
+/// `__running` is only introduced for a loop that breaks. Statements
+/// after an exit in the same block are dropped; the type checker has
+/// already reported them as unreachable.
+pub fn desugar_loop_exits(file: &mut File) {
+    for item in &mut file.items {
+        if let Item::Fn(func) = &mut item.node {
+            if let Some(body) = &mut func.body {
+                loop_exits_block(&mut body.node);
+            }
+        }
+    }
+}
+
+/// The statements `desugar_loop_exits` turns the loop `stmt` into, or
+/// `None` when its body has no `break` or `continue` of its own. The
+/// cost analyzer charges loops in this form, flags and guards included.
+pub(crate) fn loop_with_exits(stmt: &Stmt) -> Option<Vec<Spanned<Stmt>>> {
+    match stmt {
+        Stmt::For { body, .. } | Stmt::While { body, .. } if exits(&body.node, true) => {}
+        _ => return None,
+    }
+    let mut block = Block {
+        stmts: vec![Spanned::new(stmt.clone(), Span::dummy())],
+        tail_expr: None,
+    };
+    loop_exits_block(&mut block);
+    Some(block.stmts)
+}
+
+/// Rewrite the loops in `block`, innermost first.
+fn loop_exits_block(block: &mut Block) {
+    let stmts = std::mem::take(&mut block.stmts);
+    for mut stmt in stmts {
+        let span = stmt.span;
+        match &mut stmt.node {
+            Stmt::If {
+                then_block,
+                else_block,
+                ..
+            } => {
+                loop_exits_block(&mut then_block.node);
+                if let Some(else_block) = else_block {
+                    loop_exits_block(&mut else_block.node);
+                }
+            }
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    loop_exits_block(&mut arm.body.node);
+                }
+            }
+            Stmt::For { body, .. } => {
+                loop_exits(&mut body.node, None, span, &mut block.stmts);
+            }
+            Stmt::While { cond, body, .. } => {
+                loop_exits(&mut body.node, Some(cond), span, &mut block.stmts);
+            }
+            _ => {}
+        }
+        block.stmts.push(stmt);
+    }
+}
+
+/// Rewrite the exits of the loop at `span` with `body` (and, for a
+/// `while`, its `cond`), pushing the `running` flag it needs onto
+/// `before`. After a `break`, a `for` skips its remaining iterations'
+/// bodies; a `while` tests `running && cond`, so the condition is not
+/// evaluated again.
+fn loop_exits(
+    body: &mut Block,
+    cond: Option<&mut Spanned<Expr>>,
+    span: Span,
+    before: &mut Vec<Spanned<Stmt>>,
+) {
+    loop_exits_block(body);
+    if !exits(body, true) {
+        return;
+    }
+    let flags = ExitFlags {
+        running: format!("__running_{}", span.start),
+        live: format!("__live_{}", span.start),
+    };
+    let breaks = exits(body, false);
+    if breaks {
+        before.push(flag_let(&flags.running, span));
+    }
+    let mut stmts = vec![flag_let(&flags.live, span)];
+    stmts.extend(flags.guard(std::mem::replace(body, empty_block())));
+    body.stmts = match cond {
+        Some(cond) if breaks => {
+            let test =
+                std::mem::replace(cond, Spanned::new(Expr::Literal(Literal::Bool(true)), span));
+            *cond = Spanned::new(
+                Expr::BinOp {
+                    op: BinOp::And,
+                    lhs: Box::new(Spanned::new(Expr::Var(flags.running.clone()), span)),
+                    rhs: Box::new(test),
+                },
+                span,
+            );
+            stmts
+        }
+        None if breaks => vec![flags.when(&flags.running, stmts, span)],
+        _ => stmts,
+    };
+}
+
+/// Whether `block` has a `break` (or, with `continue_too`, a `continue`)
+/// for the loop around it, outside the loops nested in it.
+fn exits(block: &Block, continue_too: bool) -> bool {
+    block.stmts.iter().any(|stmt| match &stmt.node {
+        Stmt::Break => true,
+        Stmt::Continue => continue_too,
+        Stmt::If {
+            then_block,
+            else_block,
+            ..
+        } => {
+            exits(&then_block.node, continue_too)
+                || else_block
+                    .as_ref()
+                    .is_some_and(|b| exits(&b.node, continue_too))
+        }
+        Stmt::Match { arms, .. } => arms.iter().any(|arm| exits(&arm.body.node, continue_too)),
+        _ => false,
+    })
+}
+
+/// The skip flags of one loop: `running` is cleared by `break`, `live`
+/// by either exit, for the rest of the iteration.
+struct ExitFlags {
+    running: String,
+    live: String,
+}
+
+impl ExitFlags {
+    /// The statements of `block`, with its exits turned into flag
+    /// assignments and everything after an exit guarded by `live`.
+    fn guard(&self, block: Block) -> Vec<Spanned<Stmt>> {
+        let mut stmts = block.stmts;
+        if let Some(tail) = block.tail_expr {
+            let span = tail.span;
+            stmts.push(Spanned::new(Stmt::Expr(*tail), span));
+        }
+        let mut out = Vec::with_capacity(stmts.len());
+        let mut rest = stmts.into_iter();
+        while let Some(mut stmt) = rest.next() {
+            let span = stmt.span;
+            match stmt.node {
+                Stmt::Break => {
+                    out.push(self.clear(&self.running, span));
+                    out.push(self.clear(&self.live, span));
+                    return out;
+                }
+                Stmt::Continue => {
+                    out.push(self.clear(&self.live, span));
+                    return out;
+                }
+                _ => {}
+            }
+            let exiting = match &mut stmt.node {
+                Stmt::If {
+                    then_block,
+                    else_block,
+                    ..
+                } if exits(&then_block.node, true)
+                    || else_block.as_ref().is_some_and(|b| exits(&b.node, true)) =>
+                {
+                    self.guard_block(&mut then_block.node);
+                    if let Some(else_block) = else_block {
+                        self.guard_block(&mut else_block.node);
+                    }
+                    true
+                }
+                Stmt::Match { arms, .. } if arms.iter().any(|a| exits(&a.body.node, true)) => {
+                    for arm in arms {
+                        self.guard_block(&mut arm.body.node);
+                    }
+                    true
+                }
+                _ => false,
+            };
+            out.push(stmt);
+            if exiting {
+                let guarded = self.guard(Block {
+                    stmts: rest.collect(),
+                    tail_expr: None,
+                });
+                if !guarded.is_empty() {
+                    out.push(self.when(&self.live, guarded, span));
+                }
+                return out;
+            }
+        }
+        out
+    }
+
+    fn guard_block(&self, block: &mut Block) {
+        block.stmts = self.guard(std::mem::replace(block, empty_block()));
+    }
+
+    /// `if flag { stmts }`.
+    fn when(&self, flag: &str, stmts: Vec<Spanned<Stmt>>, span: Span) -> Spanned<Stmt> {
+        let cond = Spanned::new(Expr::Var(flag.to_string()), span);
+        let then_block = Spanned::new(
+            Block {
+                stmts,
+                tail_expr: None,
+            },
+            span,
+        );
+        Spanned::new(
+            Stmt::If {
+                cond,
+                then_block,
+                else_block: None,
+            },
+            span,
+        )
+    }
+
+    /// `flag = false`.
+    fn clear(&self, flag: &str, span: Span) -> Spanned<Stmt> {
+        Spanned::new(
+            Stmt::Assign {
+                place: Spanned::new(Place::Var(flag.to_string()), span),
+                value: Spanned::new(Expr::Literal(Literal::Bool(false)), span),
+            },
+            span,
+        )
+    }
+}
+
+fn empty_block() -> Block {
+    Block {
+        stmts: Vec::new(),
+        tail_expr: None,
+    }
+}
+
+/// `let mut flag: Bool = true`.
+fn flag_let(flag: &str, span: Span) -> Spanned<Stmt> {
+    Spanned::new(
+        Stmt::Let {
+            mutable: true,
+            pattern: Pattern::Name(Spanned::new(flag.to_string(), span)),
+            ty: Some(Spanned::new(Type::Bool, span)),
+            init: Spanned::new(Expr::Literal(Literal::Bool(true)), span),
+        },
+        span,
+    )
+}
+
+/// Whether `expr` reads a variable named in `names`.
+fn mentions_any(expr: &Expr, names: &BTreeSet<&str>) -> bool {
+    if names.is_empty() {
+        return false;
+    }
+    match expr {
+        Expr::Literal(_) => false,
+        Expr::Var(name) => names.contains(name.split('.').next().unwrap_or(name)),
+        Expr::BinOp { lhs, rhs, .. } => {
+            mentions_any(&lhs.node, names) || mentions_any(&rhs.node, names)
+        }
+        Expr::Call { args, .. } => args.iter().any(|a| mentions_any(&a.node, names)),
+        Expr::FieldAccess { expr, .. } => mentions_any(&expr.node, names),
+        Expr::Index { expr, index } => {
+            mentions_any(&expr.node, names) || mentions_any(&index.node, names)
+        }
+        Expr::StructInit { fields, base, .. } => {
+            fields.iter().any(|(_, e)| mentions_any(&e.node, names))
+                || base.as_ref().is_some_and(|b| mentions_any(&b.node, names))
+        }
+        Expr::ArrayInit(elems) | Expr::Tuple(elems) => {
+            elems.iter().any(|e| mentions_any(&e.node, names))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::format_file;
+    use crate::syntax::trivia::TriviaMap;
+
+    fn desugared(source: &str, flags: &[&str]) -> File {
+        let file = crate::parse_source_silent(source, "test.tri").expect("parses");
+        let flags = flags.iter().map(|f| f.to_string()).collect();
+        desugar_file(file, &flags)
+    }
+
+    #[test]
+    fn inactive_cfg_items_are_dropped() {
+        let file = desugared(
+            "program t\n#[cfg(debug)]\nfn log() {}\n#[cfg(release)]\nfn log() {}\nfn main() {}\n",
+            &["release"],
+        );
+        assert_eq!(file.items.len(), 2);
+        let Item::Fn(f) = &file.items[0].node else {
+            panic!("expected fn");
+        };
+        assert_eq!(f.cfg.as_ref().map(|c| c.node.as_str()), Some("release"));
+    }
+
+    #[test]
+    fn event_fields_follow_declaration_order() {
+        let source = "program t\nevent E {\n    a: Field,\n    b: Field,\n}\nfn main() {\n    seal E { b: 2, a: 1 }\n}\n";
+        let out = format_file(&desugared(source, &[]), &TriviaMap::default());
+        assert!(out.contains("seal E { a: 1, b: 2 }"), "{}", out);
+    }
+
+    #[test]
+    fn literal_tuple_lets_split_and_keep_spans() {
+        let source = "program t\nfn main() {\n    let (a, b): (Field, U32) = (1, 2)\n    let (c, d) = (b, c)\n}\n";
+        let file = desugared(source, &[]);
+        let Item::Fn(f) = &file.items[0].node else {
+            panic!("expected fn");
+        };
+        let body = &f.body.as_ref().expect("has body").node;
+        assert_eq!(body.stmts.len(), 3);
+        assert_eq!(body.stmts[0].span, body.stmts[1].span);
+        let out = format_file(&file, &TriviaMap::default());
+        assert!(out.contains("let a: Field = 1"), "{}", out);
+        assert!(out.contains("let b: U32 = 2"), "{}", out);
+        // `c` is bound by the first element, so splitting would rebind it.
+        assert!(out.contains("let (c, d) = (b, c)"), "{}", out);
+    }
+
+    #[test]
+    fn entrypoints_get_a_generated_dispatch_main() {
+        let source = "program t\n#[entrypoint]\nfn pay() {}\n#[entrypoint(1)]\nfn mint() {}\n#[entrypoint]\nfn burn() {}\n";
+        let file = desugared(source, &[]);
+        let table: Vec<(u64, &str)> = entrypoints(&file)
+            .into_iter()
+            .map(|(s, f)| (s, f.name.node.as_str()))
+            .collect();
+        assert_eq!(table, vec![(0, "pay"), (1, "mint"), (2, "burn")]);
+        let out = format_file(&file, &TriviaMap::default());
+        assert!(out.contains("fn main() {"), "{}", out);
+        assert!(out.contains("let selector: Field = pub_read()"), "{}", out);
+        assert!(
+            out.contains("if selector == 1 {\n        mint()"),
+            "{}",
+            out
+        );
+        assert!(
+            out.contains("assert(false, \"unknown entrypoint selector\")"),
+            "{}",
+            out
+        );
+    }
+
+    #[test]
+    fn loop_exits_become_skip_flags() {
+        let source = "program t\nfn main() {\n    for i in 0..4 bounded 4 {\n        a()\n        if c {\n            break\n        }\n        b()\n    }\n}\n";
+        let mut file = desugared(source, &[]);
+        desugar_loop_exits(&mut file);
+        let out = format_file(&file, &TriviaMap::default());
+        assert!(
+            out.contains("let mut __running_26: Bool = true\n    for i in 0..4 bounded 4 {\n        if __running_26 {\n            let mut __live_26: Bool = true\n            a()"),
+            "{}",
+            out
+        );
+        assert!(
+            out.contains("if c {\n                __running_26 = false\n                __live_26 = false\n            }\n            if __live_26 {\n                b()"),
+            "{}",
+            out
+        );
+    }
+
+    #[test]
+    fn while_break_clears_the_condition() {
+        let source = "program t\nfn main() {\n    while pub_read() == 1 bounded 8 {\n        break\n    }\n}\n";
+        let mut file = desugared(source, &[]);
+        desugar_loop_exits(&mut file);
+        let out = format_file(&file, &TriviaMap::default());
+        assert!(
+            out.contains("let mut __running_26: Bool = true\n    while __running_26 && pub_read() == 1 bounded 8 {\n        let mut __live_26: Bool = true\n        __running_26 = false"),
+            "{}",
+            out
+        );
+    }
+}
@@ -3,9 +3,10 @@
 //! This module is the single source of truth for converting AST types,
 //! function signatures, and constant values to display strings.
 
-use super::{Expr, File, FileKind, FnDef, Item, Literal, Type};
+use super::{EnumDef, EnumVariant, Expr, File, FileKind, FnDef, Item, Literal, Type};
 use crate::format;
 use crate::span::Spanned;
+use crate::syntax::trivia::TriviaMap;
 
 /// Pretty-print a single function definition by wrapping it in a
 /// minimal synthetic `File` and running the canonical formatter.
@@ -19,7 +20,7 @@ pub fn format_function(func: &FnDef) -> String {
         items: vec![Spanned::dummy(Item::Fn(func.clone()))],
     };
 
-    let formatted = format::format_file(&file, &[]);
+    let formatted = format::format_file(&file, &TriviaMap::default());
 
     // The formatter emits "program _view\n\n<fn>\n".
     // Strip the synthetic header to isolate the function text.
@@ -86,6 +87,25 @@ pub fn format_fn_signature(func: &FnDef) -> String {
     sig
 }
 
+/// Format an enum variant as written in source: `Name` or `Name(T, U)`.
+pub fn format_enum_variant(variant: &EnumVariant) -> String {
+    if variant.fields.is_empty() {
+        return variant.name.node.clone();
+    }
+    let fields: Vec<_> = variant
+        .fields
+        .iter()
+        .map(|t| format_ast_type(&t.node))
+        .collect();
+    format!("{}({})", variant.name.node, fields.join(", "))
+}
+
+/// Format an enum declaration on one line for display (documentation).
+pub fn format_enum_signature(edef: &EnumDef) -> String {
+    let variants: Vec<_> = edef.variants.iter().map(format_enum_variant).collect();
+    format!("enum {} {{ {} }}", edef.name.node, variants.join(", "))
+}
+
 /// Format a constant value expression for display (documentation).
 pub fn format_const_value(expr: &Expr) -> String {
     match expr {
@@ -0,0 +1,438 @@
+//! Tree dump of a parsed file, for `trident build --emit ast`.
+//!
+//! One node per line, children indented two spaces, each node followed by
+//! its byte span as `@start..end`. Names, operators and literals are
+//! printed inline, so two dumps diff line by line when a parse changes.
+
+use super::display::format_ast_type;
+use super::*;
+use crate::span::{Span, Spanned};
+
+/// Dump `file` as an indented tree with spans.
+pub fn dump_file(file: &File) -> String {
+    let mut d = Dumper::default();
+    let kind = match file.kind {
+        FileKind::Program => "program",
+        FileKind::Module => "module",
+    };
+    d.line(format!("{} {}", kind, file.name.node), Some(file.name.span));
+    d.depth += 1;
+    for path in &file.uses {
+        d.line(format!("use {}", path.node), Some(path.span));
+    }
+    for decl in &file.declarations {
+        match decl {
+            Declaration::PubInput(ty) => d.ty("pub input", ty),
+            Declaration::PubOutput(ty) => d.ty("pub output", ty),
+            Declaration::SecInput(ty) => d.ty("sec input", ty),
+            Declaration::SecRam(entries) => {
+                d.line("sec ram".to_string(), None);
+                d.depth += 1;
+                for (addr, ty) in entries {
+                    d.ty(&addr.to_string(), ty);
+                }
+                d.depth -= 1;
+            }
+        }
+    }
+    for item in &file.items {
+        d.item(&item.node, item.span);
+    }
+    d.out
+}
+
+#[derive(Default)]
+struct Dumper {
+    out: String,
+    depth: usize,
+}
+
+impl Dumper {
+    fn line(&mut self, text: String, span: Option<Span>) {
+        for _ in 0..self.depth {
+            self.out.push_str("  ");
+        }
+        self.out.push_str(&text);
+        if let Some(span) = span {
+            self.out
+                .push_str(&format!(" @{}..{}", span.start, span.end));
+        }
+        self.out.push('\n');
+    }
+
+    /// A line, then `children` one level deeper.
+    fn nested(&mut self, text: String, span: Option<Span>, children: impl FnOnce(&mut Self)) {
+        self.line(text, span);
+        self.depth += 1;
+        children(self);
+        self.depth -= 1;
+    }
+
+    fn ty(&mut self, label: &str, ty: &Spanned<Type>) {
+        self.line(
+            format!("{}: {}", label, format_ast_type(&ty.node)),
+            Some(ty.span),
+        );
+    }
+
+    fn attr(&mut self, name: &str, value: &Spanned<String>) {
+        self.line(format!("#[{}({})]", name, value.node), Some(value.span));
+    }
+
+    fn item(&mut self, item: &Item, span: Span) {
+        match item {
+            Item::Const(c) => {
+                let text = format!("{}const {}", pub_prefix(c.is_pub), c.name.node);
+                self.nested(text, Some(span), |d| {
+                    if let Some(cfg) = &c.cfg {
+                        d.attr("cfg", cfg);
+                    }
+                    d.ty("type", &c.ty);
+                    d.expr(&c.value);
+                });
+            }
+            Item::Struct(s) => {
+                let kind = match s.kind {
+                    StructKind::Named => "struct",
+                    StructKind::Tuple => "tuple struct",
+                    StructKind::Unit => "unit struct",
+                };
+                let text = format!("{}{} {}", pub_prefix(s.is_pub), kind, s.name.node);
+                self.nested(text, Some(span), |d| {
+                    if let Some(cfg) = &s.cfg {
+                        d.attr("cfg", cfg);
+                    }
+                    if s.is_authenticated {
+                        d.line("#[authenticated]".to_string(), None);
+                    }
+                    for f in &s.fields {
+                        let text = format!("{}field {}", pub_prefix(f.is_pub), f.name.node);
+                        d.nested(text, Some(f.name.span), |d| {
+                            d.ty("type", &f.ty);
+                            if let Some(default) = &f.default {
+                                d.expr(default);
+                            }
+                        });
+                    }
+                });
+            }
+            Item::Enum(e) => {
+                let text = format!("{}enum {}", pub_prefix(e.is_pub), e.name.node);
+                self.nested(text, Some(span), |d| {
+                    if let Some(cfg) = &e.cfg {
+                        d.attr("cfg", cfg);
+                    }
+                    for v in &e.variants {
+                        d.nested(format!("variant {}", v.name.node), Some(v.name.span), |d| {
+                            for ty in &v.fields {
+                                d.ty("type", ty);
+                            }
+                        });
+                    }
+                });
+            }
+            Item::Event(e) => {
+                let text = format!("event {} v{}", e.name.node, e.version);
+                self.nested(text, Some(span), |d| {
+                    if let Some(cfg) = &e.cfg {
+                        d.attr("cfg", cfg);
+                    }
+                    if let Some(note) = &e.deprecated {
+                        d.attr("deprecated", note);
+                    }
+                    for f in &e.fields {
+                        d.ty(&format!("field {}", f.name.node), &f.ty);
+                    }
+                });
+            }
+            Item::Fn(f) => self.func(f, span),
+        }
+    }
+
+    fn func(&mut self, f: &FnDef, span: Span) {
+        let mut text = format!("{}fn {}", pub_prefix(f.is_pub), f.name.node);
+        if !f.type_params.is_empty() {
+            let params: Vec<&str> = f.type_params.iter().map(|p| p.node.as_str()).collect();
+            text.push_str(&format!("<{}>", params.join(", ")));
+        }
+        self.nested(text, Some(span), |d| {
+            for (flag, name) in [
+                (f.is_test, "test"),
+                (f.is_pure, "pure"),
+                (f.is_authenticated, "authenticated"),
+                (f.auto_range_check, "range_check"),
+            ] {
+                if flag {
+                    d.line(format!("#[{}]", name), None);
+                }
+            }
+            for (name, value) in [
+                ("cfg", &f.cfg),
+                ("intrinsic", &f.intrinsic),
+                ("entrypoint", &f.entrypoint),
+            ] {
+                if let Some(value) = value {
+                    d.attr(name, value);
+                }
+            }
+            for cond in &f.requires {
+                d.attr("requires", cond);
+            }
+            for cond in &f.ensures {
+                d.attr("ensures", cond);
+            }
+            for cond in &f.assumes {
+                d.nested("#[assumes]".to_string(), None, |d| d.expr(cond));
+            }
+            for p in &f.params {
+                d.ty(&format!("param {}", p.name.node), &p.ty);
+            }
+            if let Some(ret) = &f.return_ty {
+                d.ty("returns", ret);
+            }
+            if let Some(body) = &f.body {
+                d.block("body", body);
+            }
+        });
+    }
+
+    fn block(&mut self, label: &str, block: &Spanned<Block>) {
+        self.nested(label.to_string(), Some(block.span), |d| {
+            for stmt in &block.node.stmts {
+                d.stmt(stmt);
+            }
+            if let Some(tail) = &block.node.tail_expr {
+                d.nested("tail".to_string(), None, |d| d.expr(tail));
+            }
+        });
+    }
+
+    fn stmt(&mut self, stmt: &Spanned<Stmt>) {
+        let span = Some(stmt.span);
+        match &stmt.node {
+            Stmt::Let {
+                mutable,
+                pattern,
+                ty,
+                init,
+            } => {
+                let names = match pattern {
+                    Pattern::Name(name) => name.node.clone(),
+                    Pattern::Tuple(names) => {
+                        let names: Vec<&str> = names.iter().map(|n| n.node.as_str()).collect();
+                        format!("({})", names.join(", "))
+                    }
+                };
+                let text = format!("let {}{}", if *mutable { "mut " } else { "" }, names);
+                self.nested(text, span, |d| {
+                    if let Some(ty) = ty {
+                        d.ty("type", ty);
+                    }
+                    d.expr(init);
+                });
+            }
+            Stmt::Assign { place, value } => self.nested("assign".to_string(), span, |d| {
+                d.place(place);
+                d.expr(value);
+            }),
+            Stmt::TupleAssign { names, value } => {
+                let names: Vec<&str> = names.iter().map(|n| n.node.as_str()).collect();
+                let text = format!("assign ({})", names.join(", "));
+                self.nested(text, span, |d| d.expr(value));
+            }
+            Stmt::If {
+                cond,
+                then_block,
+                else_block,
+            } => self.nested("if".to_string(), span, |d| {
+                d.expr(cond);
+                d.block("then", then_block);
+                if let Some(else_block) = else_block {
+                    d.block("else", else_block);
+                }
+            }),
+            Stmt::For {
+                var,
+                start,
+                end,
+                bound,
+                body,
+            } => {
+                self.nested(format!("for {}", var.node), span, |d| {
+                    d.expr(start);
+                    d.expr(end);
+                    if let Some(bound) = bound {
+                        d.nested("bounded".to_string(), Some(bound.span), |d| d.expr(bound));
+                    }
+                    d.block("body", body);
+                });
+            }
+            Stmt::While { cond, bound, body } => {
+                self.nested("while".to_string(), span, |d| {
+                    d.expr(cond);
+                    d.nested("bounded".to_string(), Some(bound.span), |d| d.expr(bound));
+                    d.block("body", body);
+                });
+            }
+            Stmt::Expr(expr) => self.nested("expr".to_string(), span, |d| d.expr(expr)),
+            Stmt::Return(value) => self.nested("return".to_string(), span, |d| {
+                if let Some(value) = value {
+                    d.expr(value);
+                }
+            }),
+            Stmt::Break => self.line("break".to_string(), span),
+            Stmt::Continue => self.line("continue".to_string(), span),
+            Stmt::Reveal { event_name, fields } | Stmt::Seal { event_name, fields } => {
+                let verb = if matches!(stmt.node, Stmt::Reveal { .. }) {
+                    "reveal"
+                } else {
+                    "seal"
+                };
+                let text = format!("{} {}", verb, event_name.node);
+                self.nested(text, span, |d| d.fields(fields));
+            }
+            Stmt::Asm {
+                body,
+                effect,
+                target,
+            } => {
+                let mut text = format!("asm effect {}", effect);
+                if let Some(target) = target {
+                    text.push_str(&format!(" target {}", target));
+                }
+                self.nested(text, span, |d| {
+                    for line in body.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                        d.line(line.to_string(), None);
+                    }
+                });
+            }
+            Stmt::Match { expr, arms } => self.nested("match".to_string(), span, |d| {
+                d.expr(expr);
+                for arm in arms {
+                    let text = format!("arm {}", match_pattern(&arm.pattern.node));
+                    d.nested(text, Some(arm.pattern.span), |d| d.block("body", &arm.body));
+                }
+            }),
+        }
+    }
+
+    fn place(&mut self, place: &Spanned<Place>) {
+        let span = Some(place.span);
+        match &place.node {
+            Place::Var(name) => self.line(format!("place {}", name), span),
+            Place::FieldAccess(inner, field) => {
+                self.nested(format!("place .{}", field.node), span, |d| d.place(inner));
+            }
+            Place::Index(inner, index) => self.nested("place index".to_string(), span, |d| {
+                d.place(inner);
+                d.expr(index);
+            }),
+        }
+    }
+
+    fn fields(&mut self, fields: &[(Spanned<String>, Spanned<Expr>)]) {
+        for (name, value) in fields {
+            self.nested(format!("field {}", name.node), Some(name.span), |d| {
+                d.expr(value)
+            });
+        }
+    }
+
+    fn expr(&mut self, expr: &Spanned<Expr>) {
+        let span = Some(expr.span);
+        match &expr.node {
+            Expr::Literal(lit) => self.line(literal(lit), span),
+            Expr::Var(name) => self.line(format!("var {}", name), span),
+            Expr::BinOp { op, lhs, rhs } => {
+                self.nested(format!("binop {}", op.as_str()), span, |d| {
+                    d.expr(lhs);
+                    d.expr(rhs);
+                });
+            }
+            Expr::Call {
+                path,
+                generic_args,
+                args,
+            } => {
+                let mut text = format!("call {}", path.node);
+                if !generic_args.is_empty() {
+                    let sizes: Vec<String> =
+                        generic_args.iter().map(|a| a.node.to_string()).collect();
+                    text.push_str(&format!("<{}>", sizes.join(", ")));
+                }
+                self.nested(text, span, |d| {
+                    for arg in args {
+                        d.expr(arg);
+                    }
+                });
+            }
+            Expr::FieldAccess { expr, field } => {
+                self.nested(format!("field .{}", field.node), span, |d| d.expr(expr));
+            }
+            Expr::Index { expr, index } => self.nested("index".to_string(), span, |d| {
+                d.expr(expr);
+                d.expr(index);
+            }),
+            Expr::StructInit { path, fields, base } => {
+                self.nested(format!("struct {}", path.node), span, |d| {
+                    d.fields(fields);
+                    if let Some(base) = base {
+                        d.nested("base".to_string(), None, |d| d.expr(base));
+                    }
+                });
+            }
+            Expr::ArrayInit(elems) => self.nested("array".to_string(), span, |d| {
+                for e in elems {
+                    d.expr(e);
+                }
+            }),
+            Expr::Tuple(elems) => self.nested("tuple".to_string(), span, |d| {
+                for e in elems {
+                    d.expr(e);
+                }
+            }),
+        }
+    }
+}
+
+fn pub_prefix(is_pub: bool) -> &'static str {
+    if is_pub {
+        "pub "
+    } else {
+        ""
+    }
+}
+
+fn literal(lit: &Literal) -> String {
+    match lit {
+        Literal::Integer(n) => format!("int {}", n),
+        Literal::Bool(b) => format!("bool {}", b),
+        Literal::Str(s) => format!("str {:?}", s),
+    }
+}
+
+fn match_pattern(pattern: &MatchPattern) -> String {
+    match pattern {
+        MatchPattern::Literal(lit) => literal(lit),
+        MatchPattern::Wildcard => "_".to_string(),
+        MatchPattern::Struct { name, fields } => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|f| {
+                    let pattern = match &f.pattern.node {
+                        FieldPattern::Binding(name) => name.clone(),
+                        FieldPattern::Literal(lit) => literal(lit),
+                        FieldPattern::Wildcard => "_".to_string(),
+                    };
+                    format!("{}: {}", f.field_name.node, pattern)
+                })
+                .collect();
+            format!("{} {{ {} }}", name.node, fields.join(", "))
+        }
+        MatchPattern::Variant { path, bindings } if bindings.is_empty() => path.node.as_dotted(),
+        MatchPattern::Variant { path, bindings } => {
+            let bindings: Vec<&str> = bindings.iter().map(|b| b.node.as_str()).collect();
+            format!("{}({})", path.node, bindings.join(", "))
+        }
+    }
+}
@@ -1,7 +1,17 @@
-pub mod display;
-pub mod navigate;
+//! The syntax tree of a Trident file.
+//!
+//! `parse_source_silent` returns a `File`, and a `CompilerPass` sees one
+//! through `PassContext::file`, after `#[cfg]` desugaring. Every node that
+//! came from the source is wrapped in a `Spanned`, whose `Span` locates
+//! it for diagnostics. `visit` traverses and rewrites the tree.
 
-use crate::span::Spanned;
+pub(crate) mod desugar;
+pub(crate) mod display;
+pub(crate) mod dump;
+pub(crate) mod navigate;
+pub mod visit;
+
+pub use crate::span::{Span, Spanned};
 
 /// A parsed `.tri` file — either a program or a library module.
 #[derive(Clone, Debug)]
@@ -55,6 +65,7 @@ impl std::fmt::Display for ModulePath {
 pub enum Item {
     Const(ConstDef),
     Struct(StructDef),
+    Enum(EnumDef),
     Event(EventDef),
     Fn(FnDef),
 }
@@ -72,24 +83,78 @@ pub struct ConstDef {
 pub struct StructDef {
     pub is_pub: bool,
     pub cfg: Option<Spanned<String>>,
+    /// `#[authenticated]` — only `#[authenticated]` functions of the
+    /// defining module may construct values of this type.
+    pub is_authenticated: bool,
     pub name: Spanned<String>,
+    pub kind: StructKind,
     pub fields: Vec<StructField>,
 }
 
+/// How a struct declares its fields.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StructKind {
+    /// `struct Point { x: Field, y: Field }`
+    #[default]
+    Named,
+    /// `struct Amount(Field)`: fields `0`, `1`, ..., built with
+    /// `Amount(x)` and read with `.0`.
+    Tuple,
+    /// `struct Marker`: no fields, built with `Marker`.
+    Unit,
+}
+
 #[derive(Clone, Debug)]
 pub struct StructField {
     pub is_pub: bool,
     pub name: Spanned<String>,
     pub ty: Spanned<Type>,
+    /// `= value`: used when an init leaves the field out.
+    pub default: Option<Spanned<Expr>>,
+}
+
+/// `enum Shape { Empty, Circle(Field), Rect(Field, Field) }`: a value is
+/// one of the variants, built with `Shape.Circle(r)` or `Shape.Empty`
+/// and taken apart by `match`.
+#[derive(Clone, Debug)]
+pub struct EnumDef {
+    pub is_pub: bool,
+    pub cfg: Option<Spanned<String>>,
+    pub name: Spanned<String>,
+    pub variants: Vec<EnumVariant>,
+}
+
+/// A unit variant `Empty` or a tuple variant `Circle(Field)`.
+#[derive(Clone, Debug)]
+pub struct EnumVariant {
+    pub name: Spanned<String>,
+    pub fields: Vec<Spanned<Type>>,
 }
 
 #[derive(Clone, Debug)]
 pub struct EventDef {
     pub cfg: Option<Spanned<String>>,
+    /// `#[version(n)]` — the event's layout version, 1 when absent.
+    pub version: u64,
+    /// `#[deprecated(note)]` — emitting the event warns, with the note.
+    pub deprecated: Option<Spanned<String>>,
     pub name: Spanned<String>,
     pub fields: Vec<EventField>,
 }
 
+impl EventDef {
+    /// Highest `#[version]` an event may carry.
+    pub const MAX_VERSION: u64 = 0xffff;
+
+    /// The tag emitted ahead of the event's fields, for the `index`-th
+    /// event of its module. Version 1 keeps the sequential tag; each later
+    /// version moves to its own block of 2^32 tags, so a decoder can tell
+    /// the layouts of one event apart.
+    pub fn tag(&self, index: u64) -> u64 {
+        (self.version.saturating_sub(1) << 32) | index
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct EventField {
     pub name: Spanned<String>,
@@ -104,10 +169,22 @@ pub struct FnDef {
     pub is_test: bool,
     /// Pure annotation: `#[pure]` — no I/O side effects allowed.
     pub is_pure: bool,
+    /// Authentication annotation: `#[authenticated]` — the body must
+    /// perform Merkle authentication (directly or through a callee).
+    pub is_authenticated: bool,
+    /// `#[auto_range_check]` — Field values used as U32 are range-checked
+    /// by the compiler instead of rejected.
+    pub auto_range_check: bool,
+    /// `#[entrypoint]` or `#[entrypoint(selector)]` — dispatched to by the
+    /// generated `main` when the public input starts with its selector.
+    pub entrypoint: Option<Spanned<String>>,
     /// Precondition annotations: `#[requires(predicate)]`.
     pub requires: Vec<Spanned<String>>,
     /// Postcondition annotations: `#[ensures(predicate)]`.
     pub ensures: Vec<Spanned<String>>,
+    /// Trust annotations: `#[assume(predicate)]`, facts about the
+    /// parameters the verifier takes as given. No code checks them.
+    pub assumes: Vec<Spanned<Expr>>,
     pub name: Spanned<String>,
     /// Size-generic parameters, e.g. `<N>` in `fn sum<N>(arr: [Field; N])`.
     pub type_params: Vec<Spanned<String>>,
@@ -153,6 +230,73 @@ impl ArraySize {
             ArraySize::Mul(a, b) => a.eval(subs).saturating_mul(b.eval(subs)),
         }
     }
+
+    /// Evaluate with substitutions for size parameters, then module
+    /// constants for names that are not parameters: `[Field; N]`.
+    pub fn eval_with_constants(
+        &self,
+        subs: &std::collections::BTreeMap<String, u64>,
+        constants: &std::collections::BTreeMap<String, u64>,
+    ) -> u64 {
+        match self {
+            ArraySize::Literal(n) => *n,
+            ArraySize::Param(name) => subs
+                .get(name)
+                .or_else(|| constants.get(name))
+                .copied()
+                .unwrap_or(0),
+            ArraySize::Add(a, b) => a
+                .eval_with_constants(subs, constants)
+                .saturating_add(b.eval_with_constants(subs, constants)),
+            ArraySize::Mul(a, b) => a
+                .eval_with_constants(subs, constants)
+                .saturating_mul(b.eval_with_constants(subs, constants)),
+        }
+    }
+
+    /// Every parameter occurrence, in source order: `M + N * M` has
+    /// `[M, N, M]`.
+    pub fn params(&self) -> Vec<&str> {
+        match self {
+            ArraySize::Literal(_) => Vec::new(),
+            ArraySize::Param(name) => vec![name.as_str()],
+            ArraySize::Add(a, b) | ArraySize::Mul(a, b) => {
+                let mut names = a.params();
+                names.extend(b.params());
+                names
+            }
+        }
+    }
+
+    /// Solve `self == target` for `unknown`, which must occur exactly once;
+    /// every other name is taken from `subs` or `constants`. `None` when
+    /// no natural number fits: `2 * N` cannot be 5, `N + 3` cannot be 2.
+    pub fn solve(
+        &self,
+        target: u64,
+        unknown: &str,
+        subs: &std::collections::BTreeMap<String, u64>,
+        constants: &std::collections::BTreeMap<String, u64>,
+    ) -> Option<u64> {
+        match self {
+            ArraySize::Param(name) if name == unknown => Some(target),
+            ArraySize::Add(a, b) | ArraySize::Mul(a, b) => {
+                let (inner, known) = if a.params().contains(&unknown) {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                let known = known.eval_with_constants(subs, constants);
+                let target = match self {
+                    ArraySize::Add(..) => target.checked_sub(known)?,
+                    _ if known == 0 || !target.is_multiple_of(known) => return None,
+                    _ => target / known,
+                };
+                inner.solve(target, unknown, subs, constants)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ArraySize {
@@ -221,6 +365,12 @@ pub enum MatchPattern {
         name: Spanned<String>,
         fields: Vec<StructPatternField>,
     },
+    /// Enum variant: `Shape.Empty` or `Shape.Rect(w, _)`, binding each
+    /// payload element to a name or dropping it with `_`.
+    Variant {
+        path: Spanned<ModulePath>,
+        bindings: Vec<Spanned<String>>,
+    },
 }
 
 /// A field in a struct destructuring pattern.
@@ -276,11 +426,22 @@ pub enum Stmt {
         var: Spanned<String>,
         start: Spanned<Expr>,
         end: Spanned<Expr>,
-        bound: Option<u64>,
+        /// `bounded N`: any expression known at compile time, folded
+        /// into an integer literal by the type checker.
+        bound: Option<Spanned<Expr>>,
+        body: Spanned<Block>,
+    },
+    While {
+        cond: Spanned<Expr>,
+        bound: Spanned<Expr>,
         body: Spanned<Block>,
     },
     Expr(Spanned<Expr>),
     Return(Option<Spanned<Expr>>),
+    /// Leave the innermost loop; rewritten into skip flags by desugaring.
+    Break,
+    /// Skip to the next iteration of the innermost loop.
+    Continue,
     Reveal {
         event_name: Spanned<String>,
         fields: Vec<(Spanned<String>, Spanned<Expr>)>,
@@ -335,6 +496,8 @@ pub enum Expr {
     StructInit {
         path: Spanned<ModulePath>,
         fields: Vec<(Spanned<String>, Spanned<Expr>)>,
+        /// `..base`: the fields not listed are copied from `base`.
+        base: Option<Box<Spanned<Expr>>>,
     },
     ArrayInit(Vec<Spanned<Expr>>),
     Tuple(Vec<Spanned<Expr>>),
@@ -344,6 +507,126 @@ pub enum Expr {
 pub enum Literal {
     Integer(u64),
     Bool(bool),
+    /// Only valid as an assertion message or a `trace` label.
+    Str(String),
+}
+
+/// The value of an integer literal, such as a loop bound once the type
+/// checker has folded it.
+pub(crate) fn integer_literal(expr: &Expr) -> Option<u64> {
+    match expr {
+        Expr::Literal(Literal::Integer(n)) => Some(*n),
+        _ => None,
+    }
+}
+
+/// Builtins that accept a trailing message: `assert(ok, "underflow")`.
+pub(crate) const ASSERTION_BUILTINS: [&str; 4] =
+    ["assert", "assert_eq", "assert_digest", "debug_assert"];
+
+/// Builtins that compile to code only under the `debug` cfg flag.
+pub(crate) const DEBUG_BUILTINS: [&str; 2] = ["debug_assert", "trace"];
+
+/// Split the message literal off the arguments of a call. Returns the
+/// arguments unchanged, and no message, unless `name` is an assertion
+/// builtin whose last argument is a string literal.
+pub(crate) fn split_assertion_message<'a>(
+    name: &str,
+    args: &'a [Spanned<Expr>],
+) -> (&'a [Spanned<Expr>], Option<&'a str>) {
+    if ASSERTION_BUILTINS.contains(&name) {
+        if let Some((last, rest)) = args.split_last() {
+            if let Expr::Literal(Literal::Str(message)) = &last.node {
+                return (rest, Some(message));
+            }
+        }
+    }
+    (args, None)
+}
+
+/// Split the reason literal off the arguments of `assume(cond, "reason")`.
+pub(crate) fn split_assumption_reason<'a>(
+    name: &str,
+    args: &'a [Spanned<Expr>],
+) -> (&'a [Spanned<Expr>], Option<&'a str>) {
+    if name == "assume" {
+        if let Some((last, rest)) = args.split_last() {
+            if let Expr::Literal(Literal::Str(reason)) = &last.node {
+                return (rest, Some(reason));
+            }
+        }
+    }
+    (args, None)
+}
+
+/// Split the label literal off the arguments of `trace("label", value)`.
+pub(crate) fn split_trace_label<'a>(
+    name: &str,
+    args: &'a [Spanned<Expr>],
+) -> (&'a [Spanned<Expr>], Option<&'a str>) {
+    if name == "trace" {
+        if let Some((first, rest)) = args.split_first() {
+            if let Expr::Literal(Literal::Str(label)) = &first.node {
+                return (rest, Some(label));
+            }
+        }
+    }
+    (args, None)
+}
+
+/// Runs of target-specific `asm` blocks written side by side: each range
+/// of statement indices holds only `asm` blocks, at least one of them
+/// tagged with a target. The blocks of a run are alternatives.
+///
+/// Statements the compiler inserts overlap the spans of the ones around
+/// them, so they never join a run.
+pub(crate) fn asm_alternatives(stmts: &[Spanned<Stmt>]) -> Vec<std::ops::Range<usize>> {
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < stmts.len() {
+        let mut end = i;
+        while end < stmts.len()
+            && matches!(stmts[end].node, Stmt::Asm { .. })
+            && (end == i || stmts[end].span.start >= stmts[end - 1].span.end)
+        {
+            end += 1;
+        }
+        let tagged = stmts[i..end].iter().any(|s| {
+            matches!(
+                &s.node,
+                Stmt::Asm {
+                    target: Some(_),
+                    ..
+                }
+            )
+        });
+        if tagged {
+            runs.push(i..end);
+        }
+        i = end.max(i + 1);
+    }
+    runs
+}
+
+/// Whether each statement of a block is built for `target`. Of a run of
+/// side-by-side `asm` blocks, the ones tagged for `target` are built, or,
+/// when there are none, the untagged ones as the portable fallback.
+pub(crate) fn active_stmts(stmts: &[Spanned<Stmt>], target: &str) -> Vec<bool> {
+    let mut active = vec![true; stmts.len()];
+    for run in asm_alternatives(stmts) {
+        let tag = |i: usize| match &stmts[i].node {
+            Stmt::Asm { target, .. } => target.as_deref(),
+            _ => None,
+        };
+        let covered = run.clone().any(|i| tag(i) == Some(target));
+        for i in run {
+            active[i] = match tag(i) {
+                Some(t) => t == target,
+                None => !covered,
+            };
+        }
+    }
+    active
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -354,6 +637,9 @@ pub enum BinOp {
     Lt,        // <
     BitAnd,    // &
     BitXor,    // ^
+    BitOr,     // |
+    And,       // &&
+    Or,        // ||
     DivMod,    // /%
     XFieldMul, // *.
 }
@@ -367,6 +653,9 @@ impl BinOp {
             BinOp::Lt => "<",
             BinOp::BitAnd => "&",
             BinOp::BitXor => "^",
+            BinOp::BitOr => "|",
+            BinOp::And => "&&",
+            BinOp::Or => "||",
             BinOp::DivMod => "/%",
             BinOp::XFieldMul => "*.",
         }
@@ -376,12 +665,14 @@ impl BinOp {
     /// Single source of truth for both parser and formatter.
     pub fn binding_power(&self) -> (u8, u8) {
         match self {
-            BinOp::Eq => (2, 3),
-            BinOp::Lt => (4, 5),
-            BinOp::Add => (6, 7),
-            BinOp::Mul | BinOp::XFieldMul => (8, 9),
-            BinOp::BitAnd | BinOp::BitXor => (10, 11),
-            BinOp::DivMod => (12, 13),
+            BinOp::Or => (1, 2),
+            BinOp::And => (3, 4),
+            BinOp::Eq => (5, 6),
+            BinOp::Lt => (7, 8),
+            BinOp::Add => (9, 10),
+            BinOp::Mul | BinOp::XFieldMul => (11, 12),
+            BinOp::BitAnd | BinOp::BitXor | BinOp::BitOr => (13, 14),
+            BinOp::DivMod => (15, 16),
         }
     }
 }
@@ -0,0 +1,449 @@
+//! Traversal of the AST: `Visitor` over shared references, `MutVisitor`
+//! over mutable ones, and `rewrite` for expression-level codemods.
+//!
+//! Both traits have one `visit_*` method per node kind. Each defaults to
+//! the matching `walk` function, which visits the node's children, so an
+//! implementation overrides only the nodes it cares about and calls
+//! `walk::*` (or `walk_mut::*`) where it still wants to descend. Both
+//! traits come from the same macro, so they cannot drift apart, and adding
+//! a node or a field to the AST only means updating the walk functions
+//! here instead of every hand-written traversal.
+//!
+//! Nodes are visited in source order. Leaves (types, patterns, match
+//! patterns) have no children; their methods exist to be overridden.
+//!
+//! ```
+//! use trident::api::ast::visit::{rewrite, walk, Visitor};
+//! use trident::api::ast::{BinOp, Expr, Literal, Spanned};
+//! use trident::api::parse_source_silent;
+//!
+//! /// The functions a file calls, and how many binary operations it has.
+//! #[derive(Default)]
+//! struct Census {
+//!     calls: Vec<String>,
+//!     ops: usize,
+//! }
+//!
+//! impl Visitor for Census {
+//!     fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+//!         match &expr.node {
+//!             Expr::Call { path, .. } => self.calls.push(path.node.as_dotted()),
+//!             Expr::BinOp { .. } => self.ops += 1,
+//!             _ => {}
+//!         }
+//!         walk::expr(self, expr);
+//!     }
+//! }
+//!
+//! let source = "program p\nfn main() {\n    pub_write(pub_read() * 1)\n}\n";
+//! let mut file = parse_source_silent(source, "p.tri").unwrap();
+//! let mut census = Census::default();
+//! census.visit_file(&file);
+//! assert_eq!(census.calls, ["pub_write", "pub_read"]);
+//! assert_eq!(census.ops, 1);
+//!
+//! // Drop multiplications by one.
+//! rewrite(&mut file, |expr| match expr {
+//!     Expr::BinOp { op: BinOp::Mul, lhs, rhs } => match rhs.node {
+//!         Expr::Literal(Literal::Integer(1)) => Some(lhs.node.clone()),
+//!         _ => None,
+//!     },
+//!     _ => None,
+//! });
+//! let mut census = Census::default();
+//! census.visit_file(&file);
+//! assert_eq!(census.ops, 0);
+//! ```
+
+use super::*;
+use crate::span::Spanned;
+
+macro_rules! make_visitor {
+    ($visitor:ident, $walk:ident, $($mutability:ident)?) => {
+        pub trait $visitor {
+            fn visit_file(&mut self, file: &$($mutability)? File) {
+                $walk::file(self, file)
+            }
+            fn visit_declaration(&mut self, decl: &$($mutability)? Declaration) {
+                $walk::declaration(self, decl)
+            }
+            fn visit_item(&mut self, item: &$($mutability)? Spanned<Item>) {
+                $walk::item(self, item)
+            }
+            fn visit_const(&mut self, cdef: &$($mutability)? ConstDef) {
+                $walk::const_def(self, cdef)
+            }
+            fn visit_struct(&mut self, sdef: &$($mutability)? StructDef) {
+                $walk::struct_def(self, sdef)
+            }
+            fn visit_struct_field(&mut self, field: &$($mutability)? StructField) {
+                $walk::struct_field(self, field)
+            }
+            fn visit_enum(&mut self, edef: &$($mutability)? EnumDef) {
+                $walk::enum_def(self, edef)
+            }
+            fn visit_event(&mut self, edef: &$($mutability)? EventDef) {
+                $walk::event_def(self, edef)
+            }
+            fn visit_fn(&mut self, func: &$($mutability)? FnDef) {
+                $walk::fn_def(self, func)
+            }
+            fn visit_param(&mut self, param: &$($mutability)? Param) {
+                $walk::param(self, param)
+            }
+            fn visit_type(&mut self, _ty: &$($mutability)? Spanned<Type>) {}
+            fn visit_block(&mut self, block: &$($mutability)? Block) {
+                $walk::block(self, block)
+            }
+            fn visit_stmt(&mut self, stmt: &$($mutability)? Spanned<Stmt>) {
+                $walk::stmt(self, stmt)
+            }
+            fn visit_pattern(&mut self, _pattern: &$($mutability)? Pattern) {}
+            fn visit_match_arm(&mut self, arm: &$($mutability)? MatchArm) {
+                $walk::match_arm(self, arm)
+            }
+            fn visit_match_pattern(&mut self, _pattern: &$($mutability)? Spanned<MatchPattern>) {}
+            fn visit_place(&mut self, place: &$($mutability)? Spanned<Place>) {
+                $walk::place(self, place)
+            }
+            fn visit_expr(&mut self, expr: &$($mutability)? Spanned<Expr>) {
+                $walk::expr(self, expr)
+            }
+        }
+
+        /// Default traversals: visit every child of a node, in source order.
+        pub mod $walk {
+            use super::*;
+
+            pub fn file<V: $visitor + ?Sized>(v: &mut V, file: &$($mutability)? File) {
+                for decl in &$($mutability)? file.declarations {
+                    v.visit_declaration(decl);
+                }
+                for item in &$($mutability)? file.items {
+                    v.visit_item(item);
+                }
+            }
+
+            pub fn declaration<V: $visitor + ?Sized>(
+                v: &mut V,
+                decl: &$($mutability)? Declaration,
+            ) {
+                match decl {
+                    Declaration::PubInput(ty)
+                    | Declaration::PubOutput(ty)
+                    | Declaration::SecInput(ty) => v.visit_type(ty),
+                    Declaration::SecRam(slots) => {
+                        for (_, ty) in slots {
+                            v.visit_type(ty);
+                        }
+                    }
+                }
+            }
+
+            pub fn item<V: $visitor + ?Sized>(v: &mut V, item: &$($mutability)? Spanned<Item>) {
+                match &$($mutability)? item.node {
+                    Item::Const(cdef) => v.visit_const(cdef),
+                    Item::Struct(sdef) => v.visit_struct(sdef),
+                    Item::Enum(edef) => v.visit_enum(edef),
+                    Item::Event(edef) => v.visit_event(edef),
+                    Item::Fn(func) => v.visit_fn(func),
+                }
+            }
+
+            pub fn const_def<V: $visitor + ?Sized>(v: &mut V, cdef: &$($mutability)? ConstDef) {
+                v.visit_type(&$($mutability)? cdef.ty);
+                v.visit_expr(&$($mutability)? cdef.value);
+            }
+
+            pub fn struct_def<V: $visitor + ?Sized>(v: &mut V, sdef: &$($mutability)? StructDef) {
+                for field in &$($mutability)? sdef.fields {
+                    v.visit_struct_field(field);
+                }
+            }
+
+            pub fn struct_field<V: $visitor + ?Sized>(
+                v: &mut V,
+                field: &$($mutability)? StructField,
+            ) {
+                v.visit_type(&$($mutability)? field.ty);
+                if let Some(default) = &$($mutability)? field.default {
+                    v.visit_expr(default);
+                }
+            }
+
+            pub fn enum_def<V: $visitor + ?Sized>(v: &mut V, edef: &$($mutability)? EnumDef) {
+                for variant in &$($mutability)? edef.variants {
+                    for ty in &$($mutability)? variant.fields {
+                        v.visit_type(ty);
+                    }
+                }
+            }
+
+            pub fn event_def<V: $visitor + ?Sized>(v: &mut V, edef: &$($mutability)? EventDef) {
+                for field in &$($mutability)? edef.fields {
+                    v.visit_type(&$($mutability)? field.ty);
+                }
+            }
+
+            pub fn fn_def<V: $visitor + ?Sized>(v: &mut V, func: &$($mutability)? FnDef) {
+                for assume in &$($mutability)? func.assumes {
+                    v.visit_expr(assume);
+                }
+                for param in &$($mutability)? func.params {
+                    v.visit_param(param);
+                }
+                if let Some(ty) = &$($mutability)? func.return_ty {
+                    v.visit_type(ty);
+                }
+                if let Some(body) = &$($mutability)? func.body {
+                    v.visit_block(&$($mutability)? body.node);
+                }
+            }
+
+            pub fn param<V: $visitor + ?Sized>(v: &mut V, param: &$($mutability)? Param) {
+                v.visit_type(&$($mutability)? param.ty);
+            }
+
+            pub fn block<V: $visitor + ?Sized>(v: &mut V, block: &$($mutability)? Block) {
+                for stmt in &$($mutability)? block.stmts {
+                    v.visit_stmt(stmt);
+                }
+                if let Some(tail) = &$($mutability)? block.tail_expr {
+                    v.visit_expr(tail);
+                }
+            }
+
+            pub fn stmt<V: $visitor + ?Sized>(v: &mut V, stmt: &$($mutability)? Spanned<Stmt>) {
+                match &$($mutability)? stmt.node {
+                    Stmt::Let {
+                        pattern, ty, init, ..
+                    } => {
+                        v.visit_pattern(pattern);
+                        if let Some(ty) = ty {
+                            v.visit_type(ty);
+                        }
+                        v.visit_expr(init);
+                    }
+                    Stmt::Assign { place, value } => {
+                        v.visit_place(place);
+                        v.visit_expr(value);
+                    }
+                    Stmt::TupleAssign { value, .. } => v.visit_expr(value),
+                    Stmt::If {
+                        cond,
+                        then_block,
+                        else_block,
+                    } => {
+                        v.visit_expr(cond);
+                        v.visit_block(&$($mutability)? then_block.node);
+                        if let Some(else_block) = else_block {
+                            v.visit_block(&$($mutability)? else_block.node);
+                        }
+                    }
+                    Stmt::For {
+                        start, end, bound, body, ..
+                    } => {
+                        v.visit_expr(start);
+                        v.visit_expr(end);
+                        if let Some(bound) = bound {
+                            v.visit_expr(bound);
+                        }
+                        v.visit_block(&$($mutability)? body.node);
+                    }
+                    Stmt::While { cond, bound, body } => {
+                        v.visit_expr(cond);
+                        v.visit_expr(bound);
+                        v.visit_block(&$($mutability)? body.node);
+                    }
+                    Stmt::Expr(expr) => v.visit_expr(expr),
+                    Stmt::Return(value) => {
+                        if let Some(value) = value {
+                            v.visit_expr(value);
+                        }
+                    }
+                    Stmt::Reveal { fields, .. } | Stmt::Seal { fields, .. } => {
+                        for (_, value) in fields {
+                            v.visit_expr(value);
+                        }
+                    }
+                    Stmt::Break | Stmt::Continue | Stmt::Asm { .. } => {}
+                    Stmt::Match { expr, arms } => {
+                        v.visit_expr(expr);
+                        for arm in arms {
+                            v.visit_match_arm(arm);
+                        }
+                    }
+                }
+            }
+
+            pub fn match_arm<V: $visitor + ?Sized>(v: &mut V, arm: &$($mutability)? MatchArm) {
+                v.visit_match_pattern(&$($mutability)? arm.pattern);
+                v.visit_block(&$($mutability)? arm.body.node);
+            }
+
+            pub fn place<V: $visitor + ?Sized>(v: &mut V, place: &$($mutability)? Spanned<Place>) {
+                match &$($mutability)? place.node {
+                    Place::Var(_) => {}
+                    Place::FieldAccess(inner, _) => v.visit_place(inner),
+                    Place::Index(inner, index) => {
+                        v.visit_place(inner);
+                        v.visit_expr(index);
+                    }
+                }
+            }
+
+            pub fn expr<V: $visitor + ?Sized>(v: &mut V, expr: &$($mutability)? Spanned<Expr>) {
+                match &$($mutability)? expr.node {
+                    Expr::Literal(_) | Expr::Var(_) => {}
+                    Expr::BinOp { lhs, rhs, .. } => {
+                        v.visit_expr(lhs);
+                        v.visit_expr(rhs);
+                    }
+                    Expr::Call { args, .. } => {
+                        for arg in args {
+                            v.visit_expr(arg);
+                        }
+                    }
+                    Expr::FieldAccess { expr, .. } => v.visit_expr(expr),
+                    Expr::Index { expr, index } => {
+                        v.visit_expr(expr);
+                        v.visit_expr(index);
+                    }
+                    Expr::StructInit { fields, base, .. } => {
+                        for (_, value) in fields {
+                            v.visit_expr(value);
+                        }
+                        if let Some(base) = base {
+                            v.visit_expr(base);
+                        }
+                    }
+                    Expr::ArrayInit(elems) | Expr::Tuple(elems) => {
+                        for elem in elems {
+                            v.visit_expr(elem);
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+make_visitor!(Visitor, walk,);
+make_visitor!(MutVisitor, walk_mut, mut);
+
+/// Rewrite every expression of `file`, innermost first: where `f` returns
+/// a replacement, it takes the place of the expression under the original
+/// span, so diagnostics on the result still point at the source. The
+/// replacement itself is not visited again.
+pub fn rewrite(file: &mut File, f: impl FnMut(&Expr) -> Option<Expr>) {
+    Rewriter(f).visit_file(file);
+}
+
+/// A `MutVisitor` applying `rewrite`'s function, for use on a single
+/// block or item instead of a whole file.
+pub struct Rewriter<F>(pub F);
+
+impl<F: FnMut(&Expr) -> Option<Expr>> MutVisitor for Rewriter<F> {
+    fn visit_expr(&mut self, expr: &mut Spanned<Expr>) {
+        walk_mut::expr(self, expr);
+        if let Some(new) = (self.0)(&expr.node) {
+            expr.node = new;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> File {
+        crate::parse_source_silent(source, "test.tri").expect("parses")
+    }
+
+    #[derive(Default)]
+    struct Counter {
+        exprs: usize,
+        stmts: usize,
+        types: usize,
+        vars: Vec<String>,
+    }
+
+    impl Visitor for Counter {
+        fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+            self.exprs += 1;
+            if let Expr::Var(name) = &expr.node {
+                self.vars.push(name.clone());
+            }
+            walk::expr(self, expr);
+        }
+        fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+            self.stmts += 1;
+            walk::stmt(self, stmt);
+        }
+        fn visit_type(&mut self, _ty: &Spanned<Type>) {
+            self.types += 1;
+        }
+    }
+
+    #[test]
+    fn visitor_reaches_nested_nodes() {
+        let file = parse(
+            "program p\n\
+             struct S { a: Field = 1 }\n\
+             const K: Field = 2\n\
+             #[assume(x == 0)]\n\
+             fn f(x: Field) -> Field {\n\
+                 let mut y: Field = x + K\n\
+                 for i in 0..3 bounded 3 {\n\
+                     match y { 0 => { let s = S { a: i }\n y = s.a } _ => {} }\n\
+                 }\n\
+                 if y == x { return y }\n\
+                 y\n\
+             }\n\
+             fn main() {}\n",
+        );
+        let mut counter = Counter::default();
+        counter.visit_file(&file);
+        assert_eq!(counter.stmts, 7);
+        // Field, Field (const), Field (param), Field (return), Field (let).
+        assert_eq!(counter.types, 5);
+        assert_eq!(
+            counter.vars,
+            vec!["x", "x", "K", "y", "i", "s.a", "y", "x", "y", "y"]
+        );
+        assert_eq!(counter.exprs, 20);
+    }
+
+    #[test]
+    fn rewrite_keeps_spans() {
+        let mut file = parse("program p\nfn main() {\n    let a: Field = 1 + 2 + 3\n}\n");
+        let before = match &file.items[0].node {
+            Item::Fn(f) => f.body.as_ref().unwrap().node.stmts[0].clone(),
+            _ => unreachable!(),
+        };
+        // Constant-fold additions of literals.
+        rewrite(&mut file, |expr| match expr {
+            Expr::BinOp {
+                op: BinOp::Add,
+                lhs,
+                rhs,
+            } => match (&lhs.node, &rhs.node) {
+                (Expr::Literal(Literal::Integer(a)), Expr::Literal(Literal::Integer(b))) => {
+                    Some(Expr::Literal(Literal::Integer(a + b)))
+                }
+                _ => None,
+            },
+            _ => None,
+        });
+        let Item::Fn(f) = &file.items[0].node else {
+            unreachable!()
+        };
+        let stmt = &f.body.as_ref().unwrap().node.stmts[0];
+        let (Stmt::Let { init: new, .. }, Stmt::Let { init: old, .. }) = (&stmt.node, &before.node)
+        else {
+            unreachable!()
+        };
+        assert!(matches!(new.node, Expr::Literal(Literal::Integer(6))));
+        assert_eq!(new.span, old.span);
+    }
+}
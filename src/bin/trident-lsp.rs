@@ -1,4 +1,4 @@
 fn main() {
     let rt = tokio::runtime::Runtime::new().expect("failed to create tokio runtime");
-    rt.block_on(trident::lsp::run_server());
+    rt.block_on(trident::run_language_server());
 }
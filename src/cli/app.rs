@@ -0,0 +1,194 @@
+//! The `trident` command line: argument parsing and subcommand dispatch.
+
+use clap::{Parser, Subcommand};
+
+use super::audit::{AuditArgs, EquivArgs};
+use super::bench::BenchArgs;
+use super::build::BuildArgs;
+use super::check::CheckArgs;
+use super::ci::CiArgs;
+use super::conformance::ConformanceArgs;
+use super::cost::CostArgs;
+use super::coverage::CoverageArgs;
+use super::deploy::DeployArgs;
+use super::deps::DepsAction;
+use super::disasm::DisasmArgs;
+use super::doc::DocArgs;
+use super::explain::ExplainArgs;
+use super::fix::FixArgs;
+use super::fmt::FmtArgs;
+use super::fuzz::FuzzArgs;
+use super::generate::GenerateArgs;
+use super::hash::HashArgs;
+use super::index::IndexArgs;
+use super::init::InitArgs;
+use super::inspect::InspectArgs;
+use super::package::PackageArgs;
+use super::prove::ProveArgs;
+use super::reduce::ReduceArgs;
+use super::registry::RegistryAction;
+use super::run::RunArgs;
+use super::state::StateAction;
+use super::store::StoreAction;
+use super::test::TestArgs;
+use super::train::TrainArgs;
+use super::tree_sitter::TreeSitterArgs;
+use super::verify::VerifyProofArgs;
+use super::view::ViewArgs;
+use super::witness::WitnessAction;
+
+#[derive(Parser)]
+#[command(
+    name = "trident",
+    version,
+    about = "Trident compiler — Correct. Bounded. Provable."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Initialize a new Trident project
+    Init(InitArgs),
+    /// Compile a .tri file (or project) to TASM
+    Build(BuildArgs),
+    /// Type-check without emitting TASM
+    Check(CheckArgs),
+    /// Format .tri source files
+    Fmt(FmtArgs),
+    /// Apply the machine-applicable fixes of compiler suggestions
+    Fix(FixArgs),
+    /// Explain an error code (E0201), with examples
+    Explain(ExplainArgs),
+    /// Shrink a .tri file while a check command still fails on it
+    Reduce(ReduceArgs),
+    /// Run #[test] functions
+    Test(TestArgs),
+    /// Run check, fmt, test, cost, and verify as one gated CI run
+    Ci(CiArgs),
+    /// Run the conformance corpus and compare outputs with golden files
+    Conformance(ConformanceArgs),
+    /// Differentially fuzz the interpreter against Triton VM (needs trisha)
+    Fuzz(FuzzArgs),
+    /// Print a cost report, or diff the costs of two versions of a source
+    Cost(CostArgs),
+    /// Generate documentation with cost annotations
+    Doc(DocArgs),
+    /// Write a ctags or JSON index of every definition in a project
+    Index(IndexArgs),
+    /// Reconstruct functions, branches, and loops from linked TASM
+    Disasm(DisasmArgs),
+    /// Show which TIR operations each target's lowering backend handles
+    Coverage(CoverageArgs),
+    /// Audit contracts using symbolic execution + algebraic solver
+    Audit(AuditArgs),
+    /// Show content hashes of functions (BLAKE3)
+    Hash(HashArgs),
+    /// Run benchmarks: compare Trident output vs hand-written TASM
+    Bench(BenchArgs),
+    /// Train the neural optimizer on .tri files
+    Train(TrainArgs),
+    /// Generate code scaffold from spec annotations
+    Generate(GenerateArgs),
+    /// View a function definition (pretty-printed from AST)
+    View(ViewArgs),
+    /// Inspect a program bundle, proof, lockfile, or compiled .tasm file
+    Inspect(InspectArgs),
+    /// Hash-keyed definitions store
+    #[command(alias = "ucm")]
+    Store {
+        #[command(subcommand)]
+        action: StoreAction,
+    },
+    /// Atlas — on-chain package registry: publish, pull, search definitions
+    Atlas {
+        #[command(subcommand)]
+        action: RegistryAction,
+    },
+    /// Check semantic equivalence of two functions
+    Equiv(EquivArgs),
+    /// Manage project dependencies
+    Deps {
+        #[command(subcommand)]
+        action: DepsAction,
+    },
+    /// Build, hash, and produce a self-contained artifact (.deploy/ directory)
+    Package(PackageArgs),
+    /// Deploy a program to a registry server or blockchain node
+    Deploy(DeployArgs),
+    /// Sync a deployed program's on-chain state into input files
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+    /// Run a compiled program via a warrior (target-specific runtime)
+    Run(RunArgs),
+    /// Generate a proof of correct execution via a warrior
+    Prove(ProveArgs),
+    /// Verify a proof via a warrior (target-specific verifier)
+    Verify(VerifyProofArgs),
+    /// Generate prover witnesses for input files
+    Witness {
+        #[command(subcommand)]
+        action: WitnessAction,
+    },
+    /// Generate tree-sitter grammar.json from the Rust grammar definition
+    TreeSitter(TreeSitterArgs),
+    /// Start the Language Server Protocol server
+    Lsp,
+}
+
+/// Parse the command line and run the chosen subcommand.
+pub fn main() {
+    let cli = Cli::parse();
+    let name = std::env::args().nth(1).unwrap_or_default();
+    crate::crash::run_reported(&name, || run(cli.command));
+}
+
+fn run(command: Command) {
+    match command {
+        Command::Init(args) => super::init::cmd_init(args),
+        Command::Build(args) => super::build::cmd_build(args),
+        Command::Check(args) => super::check::cmd_check(args),
+        Command::Fmt(args) => super::fmt::cmd_fmt(args),
+        Command::Fix(args) => super::fix::cmd_fix(args),
+        Command::Explain(args) => super::explain::cmd_explain(args),
+        Command::Reduce(args) => super::reduce::cmd_reduce(args),
+        Command::Test(args) => super::test::cmd_test(args),
+        Command::Ci(args) => super::ci::cmd_ci(args),
+        Command::Conformance(args) => super::conformance::cmd_conformance(args),
+        Command::Fuzz(args) => super::fuzz::cmd_fuzz(args),
+        Command::Cost(args) => super::cost::cmd_cost(args),
+        Command::Doc(args) => super::doc::cmd_doc(args),
+        Command::Index(args) => super::index::cmd_index(args),
+        Command::Disasm(args) => super::disasm::cmd_disasm(args),
+        Command::Coverage(args) => super::coverage::cmd_coverage(args),
+        Command::Audit(args) => super::audit::cmd_audit(args),
+        Command::Hash(args) => super::hash::cmd_hash(args),
+        Command::Bench(args) => super::bench::cmd_bench(args),
+        Command::Train(args) => super::train::cmd_train(args),
+        Command::Generate(args) => super::generate::cmd_generate(args),
+        Command::View(args) => super::view::cmd_view(args),
+        Command::Inspect(args) => super::inspect::cmd_inspect(args),
+        Command::Store { action } => super::store::cmd_store(action),
+        Command::Atlas { action } => super::registry::cmd_registry(action),
+        Command::Equiv(args) => super::audit::cmd_equiv(args),
+        Command::Deps { action } => super::deps::cmd_deps(action),
+        Command::Package(args) => super::package::cmd_package(args),
+        Command::Deploy(args) => super::deploy::cmd_deploy(args),
+        Command::State { action } => super::state::cmd_state(action),
+        Command::Run(args) => super::run::cmd_run(args),
+        Command::Prove(args) => super::prove::cmd_prove(args),
+        Command::Verify(args) => super::verify::cmd_verify_proof(args),
+        Command::Witness { action } => super::witness::cmd_witness(action),
+        Command::TreeSitter(args) => super::tree_sitter::cmd_tree_sitter(args),
+        Command::Lsp => cmd_lsp(),
+    }
+}
+
+fn cmd_lsp() {
+    let rt = tokio::runtime::Runtime::new().expect("failed to create tokio runtime");
+    rt.block_on(crate::lsp::run_server());
+}
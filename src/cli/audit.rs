@@ -6,7 +6,7 @@ use clap::Args;
 use super::trisha::{
     generate_test_harness, run_trisha, run_trisha_with_inputs, trisha_available, Harness,
 };
-use super::{load_and_parse, resolve_input};
+use super::{load_and_parse, resolve_input, resolve_options, resolve_verify_config};
 
 #[derive(Args)]
 pub struct AuditArgs {
@@ -18,6 +18,12 @@ pub struct AuditArgs {
     /// Output SMT-LIB2 encoding to file (for external solvers)
     #[arg(long, value_name = "PATH")]
     pub smt: Option<PathBuf>,
+    /// Export the constraint system as JSON to file (for external tools)
+    #[arg(long, value_name = "PATH")]
+    pub emit: Option<PathBuf>,
+    /// Include R1CS triples of the rank-1 constraints in the `--emit` export
+    #[arg(long, requires = "emit")]
+    pub r1cs: bool,
     /// Run Z3 solver (if available) for formal verification
     #[arg(long)]
     pub z3: bool,
@@ -27,15 +33,51 @@ pub struct AuditArgs {
     /// Synthesize and suggest specifications (invariants, pre/postconditions)
     #[arg(long)]
     pub synthesize: bool,
+    /// Schwartz-Zippel samples (overrides `[verify] samples`)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub samples: Option<u64>,
+    /// Most assignments bounded model checking tests (overrides `[verify] bmc_depth`)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub bmc_depth: Option<u64>,
+    /// Time budget per function in milliseconds (overrides `[verify] time_budget_ms`)
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub time_budget: Option<u64>,
+    /// Seed for random testing (overrides `[verify] seed`)
+    #[arg(long, value_name = "N")]
+    pub seed: Option<u64>,
+    /// Report what the compiled program reveals beyond its public output:
+    /// constants, event layouts, secret-dependent control flow
+    #[arg(long, requires = "input")]
+    pub leakage: bool,
+    /// Compilation profile for `--leakage` (debug or release)
+    #[arg(long, default_value = "release", requires = "leakage")]
+    pub profile: String,
 }
 
 pub fn cmd_audit(args: AuditArgs) {
     match args.input {
+        Some(ref input) if args.leakage => cmd_audit_leakage(input, &args.profile, args.json),
         Some(ref _input) => cmd_audit_symbolic(args),
         None => cmd_audit_exec(),
     }
 }
 
+// ── Leakage audit (--leakage) ──────────────────────────────────────
+
+fn cmd_audit_leakage(input: &std::path::Path, profile: &str, json: bool) {
+    let ri = resolve_input(input);
+    let options = resolve_options("triton", profile, ri.project.as_ref());
+    let report = match crate::leakage_report(&ri.entry, &options) {
+        Ok(report) => report,
+        Err(_) => process::exit(1),
+    };
+    if json {
+        print!("{}", report.to_json());
+    } else {
+        print!("{}", report);
+    }
+}
+
 // ── Execution correctness audit (default, no args) ─────────────────
 
 /// Audit results for a single dimension (classic or hand).
@@ -108,7 +150,7 @@ fn cmd_audit_exec() {
         process::exit(1);
     }
 
-    let options = trident::CompileOptions::default();
+    let options = crate::CompileOptions::default();
     let mut results: Vec<ModuleAudit> = Vec::new();
 
     for baseline_path in &baselines {
@@ -136,8 +178,8 @@ fn cmd_audit_exec() {
         };
 
         // ── Classic dimension ──
-        let _guard = trident::diagnostic::suppress_warnings();
-        let module_tasm = trident::compile_module(&source_path, &options);
+        let _guard = crate::diagnostic::suppress_warnings();
+        let module_tasm = crate::compile_module(&source_path, &options);
         drop(_guard);
 
         if let Ok(tasm) = module_tasm {
@@ -373,19 +415,32 @@ fn cmd_audit_symbolic(args: AuditArgs) {
     let AuditArgs {
         verbose,
         smt: smt_output,
+        emit,
+        r1cs,
         z3: run_z3,
         json,
         synthesize,
+        samples,
+        bmc_depth,
+        time_budget,
+        seed,
         ..
     } = args;
     let ri = resolve_input(&input);
     let entry = ri.entry;
+    let flags = crate::project::VerifySettings {
+        samples: samples.map(|n| n as usize),
+        bmc_depth: bmc_depth.map(|n| n as usize),
+        time_budget_ms: time_budget,
+        seed,
+    };
+    let config = resolve_verify_config(ri.project.as_ref(), &flags);
 
     eprintln!("Auditing {}...", input.display());
 
-    let (system, parsed_file) = {
-        let (_source, file) = load_and_parse(&entry);
-        let per_fn = trident::sym::analyze_all(&file);
+    let (system, source, parsed_file, functions) = {
+        let (source, file) = load_and_parse(&entry);
+        let per_fn = crate::sym::analyze_all(&file);
         if verbose {
             if per_fn.is_empty() {
                 eprintln!("\n  No analyzable functions found.");
@@ -410,25 +465,18 @@ fn cmd_audit_symbolic(args: AuditArgs) {
                 }
             }
         }
-        let mut sys = trident::sym::ConstraintSystem::new();
+        let mut sys = crate::sym::ConstraintSystem::new();
         for (_, fn_sys) in &per_fn {
-            sys.constraints.extend(fn_sys.constraints.clone());
-            sys.num_variables += fn_sys.num_variables;
-            for (k, v) in &fn_sys.variables {
-                sys.variables.insert(k.clone(), *v);
-            }
-            sys.pub_inputs.extend(fn_sys.pub_inputs.clone());
-            sys.pub_outputs.extend(fn_sys.pub_outputs.clone());
-            sys.divine_inputs.extend(fn_sys.divine_inputs.clone());
+            sys.merge(fn_sys.clone());
         }
         if verbose {
             eprintln!("\nCombined: {}", sys.summary());
         }
-        (sys, Some(file))
+        (sys, source, Some(file), per_fn.len().max(1))
     };
 
     if let Some(ref smt_path) = smt_output {
-        let smt_script = trident::smt::encode_system(&system, trident::smt::QueryMode::SafetyCheck);
+        let smt_script = crate::smt::encode_system(&system, crate::smt::QueryMode::SafetyCheck);
         if let Err(e) = std::fs::write(smt_path, &smt_script) {
             eprintln!("error: cannot write '{}': {}", smt_path.display(), e);
             process::exit(1);
@@ -436,22 +484,46 @@ fn cmd_audit_symbolic(args: AuditArgs) {
         eprintln!("SMT-LIB2 written to {}", smt_path.display());
     }
 
+    if let Some(ref emit_path) = emit {
+        let file_name = entry.to_string_lossy().to_string();
+        let export = crate::export::export_json(&file_name, &system, r1cs);
+        if let Err(e) = std::fs::write(emit_path, &export) {
+            eprintln!("error: cannot write '{}': {}", emit_path.display(), e);
+            process::exit(1);
+        }
+        eprintln!("Constraint system written to {}", emit_path.display());
+    }
+
     if run_z3 {
         run_z3_analysis(&system);
     }
 
     if synthesize {
         if let Some(ref file) = parsed_file {
-            let specs = trident::synthesize::synthesize_specs(file);
-            eprintln!("\n{}", trident::synthesize::format_report(&specs));
+            let specs = crate::synthesize::synthesize_specs(file);
+            eprintln!("\n{}", crate::synthesize::format_report(&specs));
         }
     }
 
-    let report = trident::solve::verify(&system);
+    // One budget per function, as `verify_project_with` grants.
+    let config = crate::solve::VerifyConfig {
+        time_budget: config
+            .time_budget
+            .map(|budget| budget.saturating_mul(functions as u32)),
+        ..config
+    };
+    let report = crate::solve::verify_with(&system, &config);
 
     if json {
         let file_name = entry.to_string_lossy().to_string();
-        let json_output = trident::report::generate_json_report(&file_name, &system, &report);
+        let program = parsed_file
+            .as_ref()
+            .map(|file| crate::report::ReportSource {
+                file,
+                source: &source,
+            });
+        let json_output =
+            crate::report::generate_json_report(&file_name, &system, &report, program.as_ref());
         println!("{}", json_output);
     } else {
         eprintln!("\n{}", report.format_report());
@@ -461,42 +533,42 @@ fn cmd_audit_symbolic(args: AuditArgs) {
     }
 }
 
-fn run_z3_analysis(sys: &trident::sym::ConstraintSystem) {
-    let smt_script = trident::smt::encode_system(sys, trident::smt::QueryMode::SafetyCheck);
-    match trident::smt::run_z3(&smt_script) {
+fn run_z3_analysis(sys: &crate::sym::ConstraintSystem) {
+    let smt_script = crate::smt::encode_system(sys, crate::smt::QueryMode::SafetyCheck);
+    match crate::smt::run_z3(&smt_script) {
         Ok(result) => {
             eprintln!("\nZ3 safety check:");
             match result.status {
-                trident::smt::SmtStatus::Unsat => {
+                crate::smt::SmtStatus::Unsat => {
                     eprintln!("  Result: UNSAT (formally verified safe)");
                 }
-                trident::smt::SmtStatus::Sat => {
+                crate::smt::SmtStatus::Sat => {
                     eprintln!("  Result: SAT (counterexample found)");
                     if let Some(model) = &result.model {
                         eprintln!("  Model:\n{}", model);
                     }
                 }
-                trident::smt::SmtStatus::Unknown => {
+                crate::smt::SmtStatus::Unknown => {
                     eprintln!("  Result: UNKNOWN (solver timed out or gave up)");
                 }
-                trident::smt::SmtStatus::Error(ref e) => {
+                crate::smt::SmtStatus::Error(ref e) => {
                     eprintln!("  Result: ERROR\n  {}", e);
                 }
             }
 
             if !sys.divine_inputs.is_empty() {
                 let witness_script =
-                    trident::smt::encode_system(sys, trident::smt::QueryMode::WitnessExistence);
-                if let Ok(witness_result) = trident::smt::run_z3(&witness_script) {
+                    crate::smt::encode_system(sys, crate::smt::QueryMode::WitnessExistence);
+                if let Ok(witness_result) = crate::smt::run_z3(&witness_script) {
                     eprintln!(
                         "\nZ3 witness existence ({} divine inputs):",
                         sys.divine_inputs.len()
                     );
                     match witness_result.status {
-                        trident::smt::SmtStatus::Sat => {
+                        crate::smt::SmtStatus::Sat => {
                             eprintln!("  Result: SAT (valid witness exists)");
                         }
-                        trident::smt::SmtStatus::Unsat => {
+                        crate::smt::SmtStatus::Unsat => {
                             eprintln!(
                                 "  Result: UNSAT (no valid witness — constraints unsatisfiable)"
                             );
@@ -555,7 +627,7 @@ pub fn cmd_equiv(args: EquivArgs) {
     );
 
     if verbose {
-        let fn_hashes = trident::hash::hash_file(&file);
+        let fn_hashes = crate::hash::hash_file(&file);
         if let Some(h) = fn_hashes.get(fn_a.as_str()) {
             eprintln!("  {} hash: {}", fn_a, h);
         }
@@ -564,16 +636,16 @@ pub fn cmd_equiv(args: EquivArgs) {
         }
     }
 
-    let result = trident::equiv::check_equivalence(&file, &fn_a, &fn_b);
+    let result = crate::equiv::check_equivalence(&file, &fn_a, &fn_b);
 
     eprintln!("\n{}", result.format_report());
 
     match result.verdict {
-        trident::equiv::EquivalenceVerdict::Equivalent => {}
-        trident::equiv::EquivalenceVerdict::NotEquivalent => {
+        crate::equiv::EquivalenceVerdict::Equivalent => {}
+        crate::equiv::EquivalenceVerdict::NotEquivalent => {
             process::exit(1);
         }
-        trident::equiv::EquivalenceVerdict::Unknown => {
+        crate::equiv::EquivalenceVerdict::Unknown => {
             process::exit(2);
         }
     }
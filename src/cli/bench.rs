@@ -7,8 +7,8 @@ use super::trisha::{
     generate_program_harness, generate_test_harness, run_trisha, trisha_available, Harness,
 };
 
+use crate::neural::model::composite::NeuralCompilerV2;
 use burn::backend::wgpu::{Wgpu, WgpuDevice};
-use trident::neural::model::composite::NeuralCompilerV2;
 
 #[derive(Args)]
 pub struct BenchArgs {
@@ -24,6 +24,21 @@ pub struct BenchArgs {
     /// Skip neural model compilation (faster)
     #[arg(long)]
     pub skip_neural: bool,
+    /// Run the paired .tri/.tasm suite instead (default: benches/suite)
+    #[arg(long, num_args = 0..=1, default_missing_value = "benches/suite")]
+    pub suite: Option<PathBuf>,
+    /// With --suite, print results as JSON on stdout
+    #[arg(long, requires = "suite")]
+    pub json: bool,
+    /// With --suite, append the results to the history file
+    #[arg(long, requires = "suite")]
+    pub record: bool,
+    /// Show the last N recorded results of each suite case (default: 10)
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10")]
+    pub trend: Option<usize>,
+    /// History file for --record and --trend
+    #[arg(long, value_name = "FILE", default_value = "benches/history.ndjson")]
+    pub history: PathBuf,
 }
 
 /// Timing triplet for a single dimension: execute, prove, verify (ms).
@@ -51,10 +66,20 @@ struct ModuleBench {
     hand: DimTiming,
     neural: DimTiming,
     /// Per-function breakdown (only collected with --functions)
-    functions: Vec<trident::FunctionBenchmark>,
+    functions: Vec<crate::FunctionBenchmark>,
 }
 
 pub fn cmd_bench(args: BenchArgs) {
+    if let Some(ref suite) = args.suite {
+        let record = args.record.then_some(args.history.as_path());
+        cmd_bench_suite(&resolve_bench_dir(suite), args.json, record);
+    }
+    if let Some(last) = args.trend {
+        cmd_bench_trend(&args.history, last);
+    }
+    if args.suite.is_some() || args.trend.is_some() {
+        return;
+    }
     let bench_dir = resolve_bench_dir(&args.dir);
     if !bench_dir.is_dir() {
         eprintln!("error: '{}' is not a directory", args.dir.display());
@@ -72,7 +97,7 @@ pub fn cmd_bench(args: BenchArgs) {
         process::exit(1);
     }
 
-    let options = trident::CompileOptions::default();
+    let options = crate::CompileOptions::default();
     let has_trisha = args.full && trisha_available();
 
     // Load neural model once for all modules (unless --skip-neural)
@@ -80,7 +105,7 @@ pub fn cmd_bench(args: BenchArgs) {
     let neural_model: Option<NeuralCompilerV2<Wgpu>> = if args.skip_neural {
         None
     } else {
-        let m = trident::neural::load_model::<Wgpu>(&wgpu_device);
+        let m = crate::neural::load_model::<Wgpu>(&wgpu_device);
         if m.is_some() {
             eprint!("  Neural model loaded.\n");
         }
@@ -111,8 +136,8 @@ pub fn cmd_bench(args: BenchArgs) {
 
         // Compile module (instruction count) + time it
         let compile_start = std::time::Instant::now();
-        let _guard = trident::diagnostic::suppress_warnings();
-        let compiled_tasm = match trident::compile_module(&source_path, &options) {
+        let _guard = crate::diagnostic::suppress_warnings();
+        let compiled_tasm = match crate::compile_module(&source_path, &options) {
             Ok(t) => t,
             Err(_) => continue,
         };
@@ -120,10 +145,10 @@ pub fn cmd_bench(args: BenchArgs) {
         let compile_ms = compile_start.elapsed().as_secs_f64() * 1000.0;
 
         // Parse per-function instruction counts
-        let compiled_fns = trident::parse_tasm_functions(&compiled_tasm);
-        let baseline_fns = trident::parse_tasm_functions(&baseline_tasm);
+        let compiled_fns = crate::parse_tasm_functions(&compiled_tasm);
+        let baseline_fns = crate::parse_tasm_functions(&baseline_tasm);
 
-        let mut fn_results: Vec<trident::FunctionBenchmark> = Vec::new();
+        let mut fn_results: Vec<crate::FunctionBenchmark> = Vec::new();
         let mut total_compiled: usize = 0;
         let mut total_baseline: usize = 0;
 
@@ -132,7 +157,7 @@ pub fn cmd_bench(args: BenchArgs) {
             total_compiled += compiled_count;
             total_baseline += baseline_count;
             if args.functions {
-                fn_results.push(trident::FunctionBenchmark {
+                fn_results.push(crate::FunctionBenchmark {
                     name: name.clone(),
                     compiled_instructions: compiled_count,
                     baseline_instructions: baseline_count,
@@ -210,9 +235,8 @@ pub fn cmd_bench(args: BenchArgs) {
                 let linked_tasm = if bench_tasm_path.exists() {
                     std::fs::read_to_string(&bench_tasm_path).ok()
                 } else if bench_tri.exists() {
-                    let _guard2 = trident::diagnostic::suppress_warnings();
-                    let result =
-                        trident::compile_project_with_options(&bench_tri, &options).ok();
+                    let _guard2 = crate::diagnostic::suppress_warnings();
+                    let result = crate::compile_project_with_options(&bench_tri, &options).ok();
                     drop(_guard2);
                     result
                 } else {
@@ -228,8 +252,8 @@ pub fn cmd_bench(args: BenchArgs) {
                 run_dimension(&mut mb.hand, &module_name, "hand", &hand_harness);
             } else {
                 // Standard mode: single-module test harness (loops execute once)
-                let _guard2 = trident::diagnostic::suppress_warnings();
-                let module_tasm = trident::compile_module(&source_path, &options).ok();
+                let _guard2 = crate::diagnostic::suppress_warnings();
+                let module_tasm = crate::compile_module(&source_path, &options).ok();
                 drop(_guard2);
 
                 if let Some(tasm) = module_tasm {
@@ -295,6 +319,70 @@ pub fn cmd_bench(args: BenchArgs) {
     eprintln!();
 }
 
+/// Run the paired suite on the reference interpreter and render it,
+/// appending the results to `record` when given.
+fn cmd_bench_suite(dir: &Path, json: bool, record: Option<&Path>) {
+    let _guard = crate::diagnostic::suppress_warnings();
+    let options = crate::CompileOptions::default();
+    let runs = match crate::run_bench_suite(dir, &options) {
+        Ok(runs) => runs,
+        Err(errors) => {
+            for e in &errors {
+                eprintln!("error: {}", e.message);
+            }
+            process::exit(1);
+        }
+    };
+    drop(_guard);
+    if runs.is_empty() {
+        eprintln!("No benchmark cases found in '{}'", dir.display());
+        process::exit(1);
+    }
+
+    if json {
+        print!("{}", crate::format_bench_json(&runs));
+    } else {
+        eprint!("{}", crate::format_bench_table(&runs));
+    }
+    if let Some(history) = record {
+        match crate::record_bench_runs(history, &runs, &options) {
+            Ok(records) => eprintln!(
+                "Recorded {} results to {}",
+                records.len(),
+                history.display()
+            ),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+    let failed = runs
+        .iter()
+        .any(|r| r.compiled_cycles.is_err() || r.baseline_cycles.is_err());
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn cmd_bench_trend(history: &Path, last: usize) {
+    let records = match crate::load_bench_history(history) {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    };
+    if records.is_empty() {
+        eprintln!(
+            "No results recorded in '{}' (run `trident bench --suite --record`)",
+            history.display()
+        );
+        process::exit(1);
+    }
+    print!("{}", crate::format_bench_trend(&records, last));
+}
+
 /// Render instruction-count-only table (default, no --full).
 fn render_insn_table(modules: &[ModuleBench], show_functions: bool) {
     let w = modules
@@ -729,15 +817,15 @@ fn run_rust_reference(ref_path: &str) -> Option<u64> {
 fn compile_neural_tasm_inline(
     source_path: &Path,
     _classical_tasm: &str,
-    options: &trident::CompileOptions,
+    options: &crate::CompileOptions,
     model: &NeuralCompilerV2<Wgpu>,
     device: &WgpuDevice,
 ) -> Option<String> {
-    use trident::neural::data::pairs::split_tir_by_function;
+    use crate::neural::data::pairs::split_tir_by_function;
 
     // Build TIR
-    let _guard = trident::diagnostic::suppress_warnings();
-    let ir = match trident::build_tir_project(source_path, options) {
+    let _guard = crate::diagnostic::suppress_warnings();
+    let ir = match crate::build_tir_project(source_path, options) {
         Ok(ir) => ir,
         Err(_) => return None,
     };
@@ -748,7 +836,7 @@ fn compile_neural_tasm_inline(
         return None;
     }
 
-    let lowering = trident::ir::tir::lower::create_stack_lowering(&options.target_config.name);
+    let lowering = crate::ir::tir::lower::create_stack_lowering(&options.target_config.name);
     let mut result_lines: Vec<String> = Vec::new();
     let mut any_neural = false;
 
@@ -778,14 +866,14 @@ fn compile_neural_tasm_inline(
             continue;
         }
 
-        let compiler_cost = trident::cost::scorer::profile_tasm(
+        let compiler_cost = crate::cost::scorer::profile_tasm(
             &fn_insns.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
         )
         .cost()
         .max(1);
 
         // Try neural compilation
-        match trident::neural::compile_with_model(fn_tir, &fn_insns, model, device) {
+        match crate::neural::compile_with_model(fn_tir, &fn_insns, model, device) {
             Ok(r) if r.neural && r.cost <= compiler_cost => {
                 // Check if neural output is identical to compiler
                 let identical = r.tasm_lines == fn_insns;
@@ -3,7 +3,7 @@ use std::process;
 
 use clap::Args;
 
-use super::{find_program_source, load_dep_dirs, resolve_input, resolve_options};
+use super::{find_program_source, resolve_input, resolve_options, ResolvedInput};
 
 #[derive(Args)]
 pub struct BuildArgs {
@@ -18,7 +18,7 @@ pub struct BuildArgs {
     /// Show top cost contributors (implies --costs)
     #[arg(long)]
     pub hotspots: bool,
-    /// Show optimization hints (H0001-H0004)
+    /// Show optimization hints (H0001-H0004, H0007)
     #[arg(long)]
     pub hints: bool,
     /// Output per-line cost annotations
@@ -30,9 +30,19 @@ pub struct BuildArgs {
     /// Compare costs with a previous cost JSON file
     #[arg(long, value_name = "PATH")]
     pub compare: Option<PathBuf>,
+    /// Also write a program bundle (JSON) for warriors
+    #[arg(long, value_name = "PATH")]
+    pub bundle: Option<PathBuf>,
     /// Target VM (default: triton)
     #[arg(long, default_value = "triton")]
     pub target: String,
+    /// Compile for every registered target, compare costs, and fail if a
+    /// target's lowering lacks an operation the program uses (writes no output)
+    #[arg(
+        long,
+        conflicts_with_all = ["output", "bundle", "engine", "terrain", "network", "union_flag", "emit"]
+    )]
+    pub all_targets: bool,
     /// Engine (geeky for terrain/VM)
     #[arg(long, conflicts_with_all = ["terrain", "network", "union_flag"])]
     pub engine: Option<String>,
@@ -48,12 +58,41 @@ pub struct BuildArgs {
     /// Compilation profile for cfg flags (debug or release)
     #[arg(long, default_value = "debug")]
     pub profile: String,
+    /// Bound op-stack and call depth along all paths; fail past the limits
+    #[arg(long)]
+    pub stack_depth: bool,
+    /// Op-stack depth limit for --stack-depth (default: 1024)
+    #[arg(long, value_name = "N", requires = "stack_depth")]
+    pub max_stack: Option<u32>,
+    /// Call depth limit for --stack-depth (default: 1024)
+    #[arg(long, value_name = "N", requires = "stack_depth")]
+    pub max_call_depth: Option<u32>,
     /// Run neural optimizer analysis (shows per-block decisions)
     #[arg(long)]
     pub neural: bool,
     /// Train the neural optimizer for N epochs (implies --neural)
     #[arg(long, value_name = "EPOCHS")]
     pub train: Option<u64>,
+    /// Print per-phase timings, item counts, and cache hit rates (text or json)
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "text"
+    )]
+    pub timings: Option<String>,
+    /// Log each pipeline phase per module as it completes
+    #[arg(short, long)]
+    pub verbose: bool,
+    /// Print an intermediate stage instead of compiling: the parsed AST
+    /// with spans, the desugared source, or the TIR before or after
+    /// optimization (ast, desugared, tir, tir-opt)
+    #[arg(long, value_name = "STAGE", value_parser = ["ast", "desugared", "tir", "tir-opt"])]
+    pub emit: Option<String>,
+    /// Print a report after compiling (mono: size-generic instances by code size)
+    #[arg(long, value_name = "REPORT", value_parser = ["mono"])]
+    pub report: Option<String>,
 }
 
 pub fn cmd_build(args: BuildArgs) {
@@ -66,28 +105,104 @@ pub fn cmd_build(args: BuildArgs) {
         annotate,
         save_costs,
         compare,
+        bundle,
         target,
+        all_targets,
         engine,
         terrain,
         network,
         union_flag,
         profile,
+        stack_depth,
+        max_stack,
+        max_call_depth,
         neural,
         train,
+        timings,
+        verbose,
+        emit,
+        report,
     } = args;
+    if let Some(format) = timings.as_deref() {
+        if format != "text" && format != "json" {
+            eprintln!("error: --timings takes 'text' or 'json', got '{}'", format);
+            process::exit(1);
+        }
+    }
+    if timings.is_some() || verbose {
+        crate::timings::start();
+    }
     let bf = super::resolve_battlefield_compile(&target, &engine, &terrain, &network, &union_flag);
     let target = bf.target;
     let ri = resolve_input(&input);
+    if all_targets {
+        build_all_targets(&ri, &profile);
+        return;
+    }
 
-    let mut options = resolve_options(&target, &profile, ri.project.as_ref());
-    if let Some(ref proj) = ri.project {
-        options.dep_dirs = load_dep_dirs(proj);
+    let options = resolve_options(&target, &profile, ri.project.as_ref());
+
+    if let Some(stage) = emit.as_deref() {
+        let modules = match stage {
+            "ast" => crate::dump_ast_project(&ri.entry, &options),
+            "tir" => crate::dump_tir_project(&ri.entry, &options, false),
+            "tir-opt" => crate::dump_tir_project(&ri.entry, &options, true),
+            _ => crate::desugar_project(&ri.entry, &options),
+        };
+        let modules = match modules {
+            Ok(m) => m,
+            Err(_) => process::exit(1),
+        };
+        for (name, text) in modules {
+            println!("// module {}\n{}", name, text);
+        }
+        return;
     }
 
-    let tasm = match trident::compile_project_with_options(&ri.entry, &options) {
-        Ok(t) => t,
-        Err(_) => process::exit(1),
+    let (tasm, constants) = match bundle {
+        Some(ref bundle_path) => {
+            let program = match crate::compile_to_bundle(&ri.entry, &options) {
+                Ok(b) => b,
+                Err(_) => process::exit(1),
+            };
+            if let Err(e) = std::fs::write(bundle_path, program.to_json()) {
+                eprintln!("error: cannot write '{}': {}", bundle_path.display(), e);
+                process::exit(1);
+            }
+            eprintln!("Bundled -> {}", bundle_path.display());
+            (program.assembly, Vec::new())
+        }
+        None => match crate::compile_project_with_constants(&ri.entry, &options) {
+            Ok(t) => t,
+            Err(_) => process::exit(1),
+        },
     };
+    super::enforce_build_policy_or_exit(&ri.entry, &options, ri.project.as_ref());
+    if stack_depth {
+        let defaults = crate::tir::depth::DepthLimits::default();
+        let limits = crate::tir::depth::DepthLimits {
+            op_stack: max_stack.unwrap_or(defaults.op_stack),
+            call_depth: max_call_depth.unwrap_or(defaults.call_depth),
+        };
+        match crate::analyze_stack_depth(&ri.entry, &options, limits) {
+            Ok(Some(report)) => {
+                eprint!("\n{}", report);
+                if !report.violations().is_empty() {
+                    process::exit(1);
+                }
+            }
+            Ok(None) => eprintln!("warning: no entry point, stack depth not analyzed"),
+            Err(_) => process::exit(1),
+        }
+    }
+    if report.as_deref() == Some("mono") {
+        match crate::mono_report(&ri.entry, &options) {
+            Ok(report) => eprint!("\n{}", report),
+            Err(_) => process::exit(1),
+        }
+    }
+    let build_info = crate::runtime::BuildInfo::capture(&options);
+    let output_text = format!("{}{}", build_info.tasm_header(), tasm);
 
     let default_output = if let Some(ref proj) = ri.project {
         proj.root_dir.join(format!("{}.tasm", proj.name))
@@ -96,11 +211,22 @@ pub fn cmd_build(args: BuildArgs) {
     };
 
     let out_path = output.unwrap_or(default_output);
-    if let Err(e) = std::fs::write(&out_path, &tasm) {
+    if let Err(e) = std::fs::write(&out_path, &output_text) {
         eprintln!("error: cannot write '{}': {}", out_path.display(), e);
         process::exit(1);
     }
     eprintln!("Compiled -> {}", out_path.display());
+    if timings.is_some() || verbose {
+        let log = crate::timings::finish();
+        if verbose {
+            eprint!("\n{}", log.to_log());
+        }
+        match timings.as_deref() {
+            Some("json") => print!("{}", log.to_json()),
+            Some(_) => eprint!("\n{}", log.to_text()),
+            None => {}
+        }
+    }
 
     // Neural optimizer analysis
     let use_neural = neural || train.is_some();
@@ -112,7 +238,7 @@ pub fn cmd_build(args: BuildArgs) {
         if let Some(source_path) = find_program_source(&input) {
             let source = std::fs::read_to_string(&source_path).unwrap_or_default();
             let filename = source_path.to_string_lossy().to_string();
-            match trident::annotate_source_with_target(&source, &filename, &target) {
+            match crate::annotate_source_with_target(&source, &filename, &target) {
                 Ok(annotated) => println!("{}", annotated),
                 Err(_) => eprintln!("error: could not annotate source (compilation errors)"),
             }
@@ -127,11 +253,11 @@ pub fn cmd_build(args: BuildArgs) {
         Some(p) => p,
         None => return,
     };
-    let cost_options = resolve_options(&target, &profile, None);
-    let program_cost = match trident::analyze_costs_project(&source_path, &cost_options) {
+    let mut program_cost = match crate::analyze_costs_project(&source_path, &options) {
         Ok(c) => c,
         Err(_) => return,
     };
+    program_cost.constants = constants;
 
     if costs || hotspots {
         eprintln!("\n{}", program_cost.format_report());
@@ -150,7 +276,7 @@ pub fn cmd_build(args: BuildArgs) {
         eprintln!("Saved costs -> {}", save_path.display());
     }
     if let Some(ref compare_path) = compare {
-        match trident::cost::ProgramCost::load_json(compare_path) {
+        match crate::cost::ProgramCost::load_json(compare_path) {
             Ok(old_cost) => eprintln!("\n{}", old_cost.format_comparison(&program_cost)),
             Err(e) => {
                 eprintln!("error: {}", e);
@@ -160,15 +286,53 @@ pub fn cmd_build(args: BuildArgs) {
     }
 }
 
+/// Compile for every registered target and print the cost matrix.
+fn build_all_targets(ri: &ResolvedInput, profile: &str) {
+    let base = resolve_options("triton", profile, ri.project.as_ref());
+    let mut builds = Vec::new();
+    let mut failed = false;
+    for name in crate::registered_targets() {
+        let target_config = if name == "triton" {
+            crate::target::TerrainConfig::triton()
+        } else {
+            match crate::target::TerrainConfig::resolve(&name) {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("error: target '{}': {}", name, e.message);
+                    failed = true;
+                    continue;
+                }
+            }
+        };
+        let options = crate::CompileOptions {
+            target_config,
+            ..base.clone()
+        };
+        match crate::build_target(&ri.entry, &options) {
+            Ok(build) => builds.push(build),
+            Err(_) => {
+                eprintln!("error: build for target '{}' failed", name);
+                failed = true;
+            }
+        }
+    }
+    if !builds.is_empty() {
+        eprint!("{}", crate::format_target_matrix(&builds));
+    }
+    if failed || builds.iter().any(|b| !b.is_portable()) {
+        process::exit(1);
+    }
+}
+
 fn run_neural_analysis(
     entry: &std::path::Path,
-    options: &trident::CompileOptions,
+    options: &crate::CompileOptions,
     train_epochs: Option<u64>,
 ) {
-    use trident::ir::tir::neural::report::{OptimizerReport, OptimizerStatus};
-    use trident::neural::model::composite::NeuralCompilerConfig;
-    use trident::neural::model::vocab::Vocab;
-    use trident::neural::training::supervised;
+    use crate::ir::tir::neural::report::format_training;
+    use crate::neural::model::composite::NeuralCompilerConfig;
+    use crate::neural::model::vocab::Vocab;
+    use crate::neural::training::supervised;
 
     // Build TIR for neural analysis
     let ir = match build_tir(entry, options) {
@@ -180,9 +344,9 @@ fn run_neural_analysis(
     };
 
     // Compute classical baseline
-    let lowering = trident::ir::tir::lower::create_stack_lowering(&options.target_config.name);
+    let lowering = crate::ir::tir::lower::create_stack_lowering(&options.target_config.name);
     let baseline_tasm = lowering.lower(&ir);
-    let baseline_profile = trident::cost::scorer::profile_tasm_str(&baseline_tasm.join("\n"));
+    let baseline_profile = crate::cost::scorer::profile_tasm_str(&baseline_tasm.join("\n"));
     let baseline_cost = baseline_profile.cost();
 
     let vocab = Vocab::new();
@@ -204,7 +368,7 @@ fn run_neural_analysis(
             entry.to_string_lossy().to_string(),
             baseline_cost,
         )];
-        let pairs = trident::neural::data::pairs::extract_pairs(&blocks, &vocab);
+        let pairs = crate::neural::data::pairs::extract_pairs(&blocks, &vocab);
         if pairs.is_empty() {
             eprintln!("No training pairs extracted.");
             return;
@@ -246,13 +410,12 @@ fn run_neural_analysis(
         let elapsed = start.elapsed();
         eprintln!(
             "\n{}",
-            OptimizerReport::format_training(
+            format_training(
                 0,
                 epochs,
                 elapsed.as_micros() as u64,
                 baseline_cost,
-                baseline_cost,
-                &OptimizerStatus::Improving
+                baseline_cost
             ),
         );
         return;
@@ -260,11 +423,11 @@ fn run_neural_analysis(
 
     // Analysis mode (--neural without --train): per-function beam search with trained checkpoint.
     // Load model once, then compile each function independently.
+    use crate::neural::data::pairs::split_tir_by_function;
     use burn::backend::wgpu::{Wgpu, WgpuDevice};
-    use trident::neural::data::pairs::split_tir_by_function;
 
     let wgpu_device = WgpuDevice::default();
-    let model = match trident::neural::load_model::<Wgpu>(&wgpu_device) {
+    let model = match crate::neural::load_model::<Wgpu>(&wgpu_device) {
         Some(m) => m,
         None => {
             eprintln!("\nNeural v2: no trained checkpoint found. Run `trident train` first.");
@@ -273,12 +436,14 @@ fn run_neural_analysis(
     };
 
     let functions = split_tir_by_function(&ir);
-    let lowering_fn = trident::ir::tir::lower::create_stack_lowering(&options.target_config.name);
+    let lowering_fn = crate::ir::tir::lower::create_stack_lowering(&options.target_config.name);
 
     let mut neural_wins = 0usize;
     let mut neural_fallbacks = 0usize;
     let mut total_neural_cost = 0u64;
     let mut total_compiler_cost = 0u64;
+    let mut valid_candidates = 0usize;
+    let mut total_candidates = 0usize;
 
     eprintln!("\nNeural v2 per-function compilation:");
     eprintln!(
@@ -307,7 +472,7 @@ fn run_neural_analysis(
             continue;
         }
 
-        let compiler_cost = trident::cost::scorer::profile_tasm(
+        let compiler_cost = crate::cost::scorer::profile_tasm(
             &fn_baseline.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
         )
         .cost()
@@ -315,7 +480,12 @@ fn run_neural_analysis(
 
         total_compiler_cost += compiler_cost;
 
-        match trident::neural::compile_with_model(fn_tir, &fn_baseline, &model, &wgpu_device) {
+        let outcome = crate::neural::compile_with_model(fn_tir, &fn_baseline, &model, &wgpu_device);
+        if let Ok(result) = &outcome {
+            valid_candidates += result.valid_count;
+            total_candidates += result.total_count;
+        }
+        match outcome {
             Ok(result) if result.neural && result.cost <= compiler_cost => {
                 neural_wins += 1;
                 total_neural_cost += result.cost;
@@ -353,6 +523,10 @@ fn run_neural_analysis(
         "  Total: {}/{} functions neural, compiler cost: {}, neural cost: {}",
         neural_wins, total_fns, total_compiler_cost, total_neural_cost,
     );
+    eprintln!(
+        "  Beam candidates: {}/{} valid",
+        valid_candidates, total_candidates
+    );
     if total_compiler_cost > 0 {
         let ratio = total_neural_cost as f64 / total_compiler_cost as f64;
         eprintln!("  Overall ratio: {:.4}x", ratio);
@@ -363,12 +537,12 @@ fn run_neural_analysis(
 /// Uses full project resolution so imports (use vm.*, std.*) work.
 fn build_tir(
     entry: &std::path::Path,
-    options: &trident::CompileOptions,
-) -> Option<Vec<trident::tir::TIROp>> {
-    trident::build_tir_project(entry, options).ok()
+    options: &crate::CompileOptions,
+) -> Option<Vec<crate::tir::TIROp>> {
+    crate::build_tir_project(entry, options).ok()
 }
 
-fn print_hints(cost: &trident::cost::ProgramCost) {
+fn print_hints(cost: &crate::cost::ProgramCost) {
     let all: Vec<_> = cost
         .optimization_hints()
         .into_iter()
@@ -30,6 +30,16 @@ pub struct CheckArgs {
     /// Compilation profile for cfg flags (debug or release)
     #[arg(long, default_value = "debug")]
     pub profile: String,
+    /// Report public library functions no program or test uses; `deny`
+    /// makes them errors
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        num_args = 0..=1,
+        default_missing_value = "warn",
+        value_parser = ["warn", "deny"]
+    )]
+    pub unused_exports: Option<String>,
 }
 
 pub fn cmd_check(args: CheckArgs) {
@@ -42,20 +52,39 @@ pub fn cmd_check(args: CheckArgs) {
         network,
         union_flag,
         profile,
+        unused_exports,
     } = args;
     let bf = super::resolve_battlefield_compile(&target, &engine, &terrain, &network, &union_flag);
     let target = bf.target;
     let ri = resolve_input(&input);
+    let options = resolve_options(&target, &profile, ri.project.as_ref());
 
-    match trident::check_project(&ri.entry) {
+    match crate::check_project_with_options(&ri.entry, &options) {
         Ok(()) => eprintln!("OK: {}", input.display()),
         Err(_) => process::exit(1),
     }
 
+    if let Some(level) = unused_exports {
+        let report = match crate::unused_exports(&ri.entry, &options) {
+            Ok(report) => report,
+            Err(_) => process::exit(1),
+        };
+        let deny = level == "deny";
+        for export in &report.unused {
+            let source = std::fs::read_to_string(&export.file).unwrap_or_default();
+            export
+                .diagnostic(deny)
+                .render(&export.file.to_string_lossy(), &source);
+        }
+        eprint!("\n{}", report);
+        if deny && !report.unused.is_empty() {
+            process::exit(1);
+        }
+    }
+
     if costs {
         if let Some(source_path) = find_program_source(&input) {
-            let options = resolve_options(&target, &profile, ri.project.as_ref());
-            if let Ok(program_cost) = trident::analyze_costs_project(&source_path, &options) {
+            if let Ok(program_cost) = crate::analyze_costs_project(&source_path, &options) {
                 eprintln!("\n{}", program_cost.format_report());
             }
         }
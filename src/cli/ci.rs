@@ -0,0 +1,102 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process;
+
+use clap::Args;
+
+use super::{resolve_input, resolve_options, resolve_verify_config};
+
+#[derive(Args)]
+pub struct CiArgs {
+    /// Project directory with trident.toml, or a .tri file
+    #[arg(default_value = ".")]
+    pub input: PathBuf,
+    /// Stages to run, overriding `[ci] stages` (e.g. check,test)
+    #[arg(long, value_delimiter = ',')]
+    pub stages: Option<Vec<String>>,
+    /// Print the summary as JSON on stdout
+    #[arg(long)]
+    pub json: bool,
+    /// Target VM (default: triton)
+    #[arg(long, default_value = "triton")]
+    pub target: String,
+    /// Compilation profile for cfg flags (debug or release)
+    #[arg(long, default_value = "debug")]
+    pub profile: String,
+}
+
+pub fn cmd_ci(args: CiArgs) {
+    let ri = resolve_input(&args.input);
+    let options = resolve_options(&args.target, &args.profile, ri.project.as_ref());
+    let (settings, root_dir) = match &ri.project {
+        Some(project) => (project.ci.clone(), project.root_dir.clone()),
+        None => (
+            Default::default(),
+            ri.entry.parent().unwrap_or(&args.input).to_path_buf(),
+        ),
+    };
+
+    let mut config = match crate::CiConfig::from_settings(&settings, &root_dir) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    };
+    if let Some(names) = &args.stages {
+        config.stages = match crate::CiStage::parse_list(names) {
+            Ok(stages) => stages,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        };
+    }
+    config.verify = resolve_verify_config(ri.project.as_ref(), &Default::default());
+
+    let report = crate::run_ci(&ri.entry, &options, &config);
+
+    eprintln!();
+    for s in &report.stages {
+        eprintln!(
+            "  {:<7} {:<5} {} ({} ms)",
+            s.stage.as_str(),
+            s.status.as_str(),
+            s.detail,
+            s.duration_ms
+        );
+    }
+    let failed = report
+        .stages
+        .iter()
+        .filter(|s| s.status == crate::StageStatus::Fail)
+        .count();
+    if failed == 0 {
+        eprintln!("\nci: passed");
+    } else {
+        eprintln!(
+            "\nci: {} stage{} failed",
+            failed,
+            if failed == 1 { "" } else { "s" }
+        );
+    }
+
+    if args.json {
+        print!("{}", report.to_json());
+    }
+    // GitHub Actions renders this file on the run's summary page.
+    if let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY") {
+        let written = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut f| f.write_all(report.to_markdown().as_bytes()));
+        if let Err(e) = written {
+            eprintln!("warning: cannot write job summary: {}", e);
+        }
+    }
+
+    if !report.passed() {
+        process::exit(1);
+    }
+}
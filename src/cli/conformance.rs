@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+use std::process;
+
+use clap::Args;
+
+use super::resolve_options;
+
+#[derive(Args)]
+pub struct ConformanceArgs {
+    /// Directory of conformance cases (.tri programs with .io golden files)
+    #[arg(default_value = "conformance")]
+    pub dir: PathBuf,
+    /// Target VM (default: triton)
+    #[arg(long, default_value = "triton")]
+    pub target: String,
+    /// Engine (geeky for terrain/VM)
+    #[arg(long, conflicts_with = "terrain")]
+    pub engine: Option<String>,
+    /// Terrain (gamy for engine/VM)
+    #[arg(long, conflicts_with = "engine")]
+    pub terrain: Option<String>,
+    /// Compilation profile for cfg flags (debug or release)
+    #[arg(long, default_value = "debug")]
+    pub profile: String,
+}
+
+pub fn cmd_conformance(args: ConformanceArgs) {
+    let bf =
+        super::resolve_battlefield_compile(&args.target, &args.engine, &args.terrain, &None, &None);
+    let target = bf.target;
+    let options = resolve_options(&target, &args.profile, None);
+
+    // Only Triton assembly has an in-process runner; other targets are
+    // checked for compilation until their warriors expose one.
+    let interpreter = crate::runtime::Interpreter::new();
+    let runner: Option<&dyn crate::runtime::Runner> = if options.target_config.name == "triton" {
+        Some(&interpreter)
+    } else {
+        eprintln!(
+            "note: no in-process runner for '{}'; cases are compiled but not run",
+            target
+        );
+        None
+    };
+
+    match crate::run_conformance(&args.dir, &options, runner) {
+        Ok(report) => {
+            eprintln!("{}", report);
+            if report.contains("conformance result: FAILED") {
+                process::exit(1);
+            }
+        }
+        Err(errors) => {
+            for e in &errors {
+                eprintln!("error: {}", e.message);
+            }
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::{Args, Subcommand};
+
+use super::{find_program_source, resolve_input, resolve_options};
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct CostArgs {
+    #[command(subcommand)]
+    pub action: Option<CostAction>,
+    /// Input .tri file or directory with trident.toml
+    #[arg(required_unless_present = "diff_source")]
+    pub input: Option<PathBuf>,
+    /// Compile two versions of a source file and diff their costs per
+    /// function and per line
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with = "input")]
+    pub diff_source: Option<Vec<PathBuf>>,
+    /// Target VM (default: triton)
+    #[arg(long, default_value = "triton")]
+    pub target: String,
+    /// Compilation profile for cfg flags (debug or release)
+    #[arg(long, default_value = "debug")]
+    pub profile: String,
+}
+
+#[derive(Subcommand)]
+pub enum CostAction {
+    /// Find the commits that raised the program's cost
+    Blame {
+        /// Input .tri file or directory with trident.toml (default: .)
+        input: Option<PathBuf>,
+        /// Oldest revision to analyze; every first-parent commit after it
+        /// up to HEAD is compared with the one before
+        #[arg(long, value_name = "REV")]
+        since: String,
+        /// Number of commits to show
+        #[arg(long, default_value = "5")]
+        top: usize,
+        /// Target VM (default: triton)
+        #[arg(long, default_value = "triton")]
+        target: String,
+        /// Compilation profile for cfg flags (debug or release)
+        #[arg(long, default_value = "debug")]
+        profile: String,
+    },
+}
+
+pub fn cmd_cost(args: CostArgs) {
+    if let Some(CostAction::Blame {
+        input,
+        since,
+        top,
+        target,
+        profile,
+    }) = args.action
+    {
+        cmd_cost_blame(
+            &input.unwrap_or_else(|| PathBuf::from(".")),
+            &since,
+            top,
+            &target,
+            &profile,
+        );
+        return;
+    }
+    let CostArgs {
+        action: _,
+        input,
+        diff_source,
+        target,
+        profile,
+    } = args;
+
+    if let Some(paths) = diff_source {
+        let read = |path: &PathBuf| {
+            std::fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("error: cannot read '{}': {}", path.display(), e);
+                process::exit(1);
+            })
+        };
+        let (old, new) = (&paths[0], &paths[1]);
+        let (old_source, new_source) = (read(old), read(new));
+        match crate::diff_source_costs(
+            &old_source,
+            &old.to_string_lossy(),
+            &new_source,
+            &new.to_string_lossy(),
+        ) {
+            Ok(diff) => print!("{}", diff.format()),
+            Err(_) => process::exit(1),
+        }
+        return;
+    }
+
+    let Some(input) = input else {
+        return;
+    };
+    let ri = resolve_input(&input);
+    let Some(source_path) = find_program_source(&input) else {
+        eprintln!("error: no program source in '{}'", input.display());
+        process::exit(1);
+    };
+    let options = resolve_options(&target, &profile, ri.project.as_ref());
+    match crate::analyze_costs_project(&source_path, &options) {
+        Ok(program_cost) => print!("{}", program_cost.format_report()),
+        Err(_) => process::exit(1),
+    }
+}
+
+fn cmd_cost_blame(input: &Path, since: &str, top: usize, target: &str, profile: &str) {
+    let ri = resolve_input(input);
+    let Some(source_path) = find_program_source(input) else {
+        eprintln!("error: no program source in '{}'", input.display());
+        process::exit(1);
+    };
+    let options = resolve_options(target, profile, ri.project.as_ref());
+    let snapshots = crate::blame_costs(&source_path, since, &options, |rev, summary| {
+        eprintln!("Analyzing {} {}", &rev[..rev.len().min(7)], summary);
+    })
+    .unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    });
+    let program = source_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    print!("{}", crate::format_cost_blame(&program, &snapshots, top));
+}
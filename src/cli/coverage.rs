@@ -0,0 +1,33 @@
+use std::process;
+
+use clap::Args;
+
+#[derive(Args)]
+pub struct CoverageArgs {
+    /// Only this target (default: every target in vm/ with a backend declared)
+    #[arg(long)]
+    pub target: Option<String>,
+}
+
+pub fn cmd_coverage(args: CoverageArgs) {
+    let mut coverage = crate::lowering_coverage();
+    if let Some(ref target) = args.target {
+        coverage.retain(|c| c.target == *target);
+        if coverage.is_empty() {
+            eprintln!("error: no lowering declared for target '{}'", target);
+            process::exit(1);
+        }
+    }
+    print!("{}", crate::format_lowering_coverage(&coverage));
+    // Every operation a backend does not implement is a build that fails
+    // for a user of that target.
+    let gaps = coverage.iter().any(|c| {
+        c.ops
+            .iter()
+            .flatten()
+            .any(|(_, op)| matches!(op, crate::OpCoverage::Unimplemented(_)))
+    });
+    if gaps {
+        process::exit(1);
+    }
+}
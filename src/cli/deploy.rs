@@ -7,7 +7,8 @@ use super::{open_codebase, prepare_artifact, registry_client, try_load_and_parse
 
 #[derive(Args)]
 pub struct DeployArgs {
-    /// Input .tri file, project directory, or .deploy/ artifact
+    /// Input .tri file, project directory, .deploy/ artifact, or bundle
+    /// .json (with --rpc)
     pub input: PathBuf,
     /// Target VM or OS (default: triton)
     #[arg(long, default_value = "triton")]
@@ -36,6 +37,13 @@ pub struct DeployArgs {
     /// Registry URL to deploy to
     #[arg(long)]
     pub registry: Option<String>,
+    /// Node JSON-RPC URL to deploy the program bundle to, instead of a
+    /// registry
+    #[arg(long, conflicts_with = "registry")]
+    pub rpc: Option<String>,
+    /// Proof JSON to attach to the deployment (with --rpc)
+    #[arg(long, requires = "rpc")]
+    pub proof: Option<PathBuf>,
     /// Run formal audit before deploying
     #[arg(long)]
     pub audit: bool,
@@ -56,6 +64,8 @@ pub fn cmd_deploy(args: DeployArgs) {
         state,
         profile,
         registry,
+        rpc,
+        proof,
         audit,
         dry_run,
     } = args;
@@ -71,9 +81,41 @@ pub fn cmd_deploy(args: DeployArgs) {
     let target = bf.target;
     let state_selection = bf.state;
 
+    let is_artifact = input.is_dir()
+        && input.join("manifest.json").exists()
+        && input.join("program.tasm").exists();
+
+    // Bundle files and artifacts deploy to a node as they are
+    if let Some(ref url) = rpc {
+        let bundle = if input.extension().is_some_and(|e| e == "json") {
+            Some(
+                std::fs::read_to_string(&input)
+                    .map_err(|e| format!("cannot read '{}': {}", input.display(), e))
+                    .and_then(|json| crate::runtime::ProgramBundle::from_json(&json)),
+            )
+        } else if is_artifact {
+            Some(crate::deploy::load_artifact_bundle(&input))
+        } else {
+            None
+        };
+        match bundle {
+            Some(Ok(bundle)) => {
+                deploy_to_node(&bundle, proof.as_deref(), url, dry_run);
+                return;
+            }
+            Some(Err(e)) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+            None => {}
+        }
+    } else if input.extension().is_some_and(|e| e == "json") {
+        eprintln!("error: a bundle file deploys to a node; pass --rpc <url>");
+        process::exit(1);
+    }
+
     // Handle pre-packaged .deploy/ artifact directory
-    if input.is_dir() && input.join("manifest.json").exists() && input.join("program.tasm").exists()
-    {
+    if is_artifact {
         let manifest_json = match std::fs::read_to_string(input.join("manifest.json")) {
             Ok(s) => s,
             Err(e) => {
@@ -101,19 +143,48 @@ pub fn cmd_deploy(args: DeployArgs) {
 
     // Build from source
     let art = prepare_artifact(&input, &target, &profile, audit);
+    // `--audit` has already verified the program.
+    if let Some(policy) = super::load_policy(art.project.as_ref()).filter(|_| !audit) {
+        let config = super::resolve_verify_config(art.project.as_ref(), &Default::default());
+        if let Err(violations) = crate::enforce_deploy_policy(&art.entry, &policy, &config) {
+            for v in &violations {
+                eprintln!("error: {}", v.message);
+            }
+            eprintln!("error: deploy violates {}", crate::policy::POLICY_FILE);
+            process::exit(1);
+        }
+    }
     let output_base = art.entry.parent().unwrap_or(Path::new(".")).to_path_buf();
 
+    if let Some(ref url) = rpc {
+        let mut options =
+            super::resolve_options(&art.resolved.vm.name, &profile, art.project.as_ref());
+        options.target_config = art.resolved.vm.clone();
+        let mut bundle = match crate::compile_to_bundle(&art.entry, &options) {
+            Ok(bundle) => bundle,
+            Err(_) => {
+                eprintln!("error: compilation failed");
+                process::exit(1);
+            }
+        };
+        bundle.name = art.name.clone();
+        bundle.version = art.version.clone();
+        bundle.target_os = art.resolved.os.as_ref().map(|os| os.name.clone());
+        deploy_to_node(&bundle, proof.as_deref(), url, dry_run);
+        return;
+    }
+
     // Resolve state config if specified
     let state_config = if let Some(ref state_name) = state_selection {
         if let Some(ref os) = art.resolved.os {
-            match trident::target::StateConfig::resolve(&os.name, state_name) {
+            match crate::target::StateConfig::resolve(&os.name, state_name) {
                 Ok(Some(sc)) => Some(sc),
                 Ok(None) => {
                     eprintln!(
                         "error: unknown state '{}' for union '{}'",
                         state_name, os.name
                     );
-                    let available = trident::target::StateConfig::list_states(&os.name);
+                    let available = crate::target::StateConfig::list_states(&os.name);
                     if !available.is_empty() {
                         eprintln!("  available: {}", available.join(", "));
                     }
@@ -146,8 +217,9 @@ pub fn cmd_deploy(args: DeployArgs) {
     };
 
     if dry_run {
-        let program_digest =
-            trident::hash::ContentHash(trident::poseidon2::hash_bytes(art.tasm.as_bytes()));
+        let program_digest = crate::hash::ContentHash(
+            crate::field::poseidon2::hash_bytes_goldilocks(art.tasm.as_bytes()),
+        );
         eprintln!("Dry run — would deploy:");
         eprintln!("  Name:            {}", art.name);
         eprintln!("  Version:         {}", art.version);
@@ -163,7 +235,7 @@ pub fn cmd_deploy(args: DeployArgs) {
         return;
     }
 
-    let result = match trident::deploy::generate_artifact(
+    let result = match crate::deploy::generate_artifact(
         &art.name,
         &art.version,
         &art.tasm,
@@ -171,6 +243,9 @@ pub fn cmd_deploy(args: DeployArgs) {
         &art.cost,
         &art.resolved.vm,
         art.resolved.os.as_ref(),
+        art.project
+            .as_ref()
+            .map_or(&[][..], |p| p.commitments.as_slice()),
         &output_base,
     ) {
         Ok(r) => r,
@@ -187,8 +262,66 @@ pub fn cmd_deploy(args: DeployArgs) {
     deploy_to_registry(&result.artifact_dir, &client);
 }
 
+/// Deploy `bundle` to the node at `url`. A dry run makes the same call
+/// to an in-process mock node, so the request and the receipt are shown
+/// without touching the chain.
+fn deploy_to_node(
+    bundle: &crate::runtime::ProgramBundle,
+    proof_path: Option<&Path>,
+    url: &str,
+    dry_run: bool,
+) {
+    use crate::deploy::rpc::{MockNode, RpcDeployer};
+
+    let proof = proof_path.map(|path| {
+        match std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| crate::runtime::ProofData::from_json(&json))
+        {
+            Ok(proof) => proof,
+            Err(e) => {
+                eprintln!("error: cannot load proof '{}': {}", path.display(), e);
+                process::exit(1);
+            }
+        }
+    });
+
+    let node = if dry_run {
+        match MockNode::start() {
+            Ok(node) => Some(node),
+            Err(e) => {
+                eprintln!("error: cannot start mock node: {}", e);
+                process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+    let endpoint = node.as_ref().map_or(url, |node| node.url());
+
+    if dry_run {
+        eprintln!("Dry run — deploying to a mock node in place of {}:", url);
+    } else {
+        eprintln!("Deploying to {}...", url);
+    }
+    eprintln!("  Name:            {} {}", bundle.name, bundle.version);
+    eprintln!("  Program digest:  {}", bundle.program_digest().to_hex());
+    if let Some(ref proof) = proof {
+        eprintln!("  Proof:           {}", proof.format);
+    }
+
+    match RpcDeployer::new(endpoint).submit(bundle, proof.as_ref()) {
+        Ok(receipt) if receipt.new => eprintln!("Deployed: tx {}", receipt.tx),
+        Ok(receipt) => eprintln!("Already deployed: tx {}", receipt.tx),
+        Err(e) => {
+            eprintln!("error: deploy failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
 /// Deploy a validated artifact directory (must contain manifest.json + program.tasm).
-fn deploy_to_registry(artifact_dir: &Path, client: &trident::registry::RegistryClient) {
+fn deploy_to_registry(artifact_dir: &Path, client: &crate::registry::RegistryClient) {
     eprintln!("Deploying...");
 
     // Try to find and add source to codebase
@@ -213,7 +346,7 @@ fn deploy_to_registry(artifact_dir: &Path, client: &trident::registry::RegistryC
         }
     }
 
-    match trident::registry::publish_codebase(&cb, client, &[]) {
+    match crate::registry::publish_codebase(&cb, client, &[]) {
         Ok(results) => {
             let created = results.iter().filter(|r| r.created).count();
             eprintln!("Deployed: {} definitions ({} new)", results.len(), created);
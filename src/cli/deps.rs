@@ -22,14 +22,14 @@ pub enum DepsAction {
 pub fn cmd_deps(action: DepsAction) {
     // Find project root
     let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-    let toml_path = match trident::project::Project::find(&cwd) {
+    let toml_path = match crate::project::Project::find(&cwd) {
         Some(p) => p,
         None => {
             eprintln!("error: no trident.toml found (run from project root)");
             process::exit(1);
         }
     };
-    let project = match trident::project::Project::load(&toml_path) {
+    let project = match crate::project::Project::load(&toml_path) {
         Ok(p) => p,
         Err(e) => {
             eprintln!("error: {}", e.message);
@@ -50,16 +50,16 @@ pub fn cmd_deps(action: DepsAction) {
             for name in names {
                 let dep = &deps[name];
                 match dep {
-                    trident::manifest::Dependency::Hash { hash } => {
+                    crate::manifest::Dependency::Hash { hash } => {
                         println!("  {} = {} (hash)", name, short_hash(hash));
                     }
-                    trident::manifest::Dependency::Registry {
+                    crate::manifest::Dependency::Registry {
                         name: reg_name,
                         registry,
                     } => {
                         println!("  {} = {} @ {} (registry)", name, reg_name, registry);
                     }
-                    trident::manifest::Dependency::Path { path } => {
+                    crate::manifest::Dependency::Path { path } => {
                         println!("  {} = {} (path)", name, path.display());
                     }
                 }
@@ -67,7 +67,7 @@ pub fn cmd_deps(action: DepsAction) {
             // Check lockfile
             let lock_path = project.root_dir.join("trident.lock");
             if lock_path.exists() {
-                match trident::manifest::load_lockfile(&lock_path) {
+                match crate::manifest::load_lockfile(&lock_path) {
                     Ok(lock) => println!("\nLocked: {} dependencies", lock.locked.len()),
                     Err(e) => println!("\nLockfile error: {}", e),
                 }
@@ -84,18 +84,18 @@ pub fn cmd_deps(action: DepsAction) {
             // Load existing lockfile if present
             let lock_path = project.root_dir.join("trident.lock");
             let existing_lock = if lock_path.exists() {
-                trident::manifest::load_lockfile(&lock_path).ok()
+                crate::manifest::load_lockfile(&lock_path).ok()
             } else {
                 None
             };
-            match trident::manifest::resolve_dependencies(
+            match crate::manifest::resolve_dependencies(
                 &project.root_dir,
                 deps,
                 &existing_lock,
                 &registry,
             ) {
                 Ok(lockfile) => {
-                    if let Err(e) = trident::manifest::save_lockfile(&lock_path, &lockfile) {
+                    if let Err(e) = crate::manifest::save_lockfile(&lock_path, &lockfile) {
                         eprintln!("error writing lockfile: {}", e);
                         process::exit(1);
                     }
@@ -116,7 +116,7 @@ pub fn cmd_deps(action: DepsAction) {
                 eprintln!("error: no trident.lock found. Run `trident deps fetch` first.");
                 process::exit(1);
             }
-            let lockfile = match trident::manifest::load_lockfile(&lock_path) {
+            let lockfile = match crate::manifest::load_lockfile(&lock_path) {
                 Ok(l) => l,
                 Err(e) => {
                     eprintln!("error: {}", e);
@@ -125,7 +125,7 @@ pub fn cmd_deps(action: DepsAction) {
             };
             let mut ok = true;
             for (name, locked) in &lockfile.locked {
-                let cached = trident::manifest::dep_source_path(&project.root_dir, &locked.hash);
+                let cached = crate::manifest::dep_source_path(&project.root_dir, &locked.hash);
                 if cached.exists() {
                     println!("  OK  {} ({})", name, short_hash(&locked.hash));
                 } else {
@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::Args;
+
+use super::{resolve_input, resolve_options};
+
+#[derive(Args)]
+pub struct DisasmArgs {
+    /// Input .tasm file
+    pub input: PathBuf,
+    /// Map functions back to this .tri file or project directory
+    #[arg(long, value_name = "INPUT")]
+    pub source: Option<PathBuf>,
+    /// Output file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// Compilation profile the source was built with (debug or release)
+    #[arg(long, default_value = "debug")]
+    pub profile: String,
+}
+
+pub fn cmd_disasm(args: DisasmArgs) {
+    let DisasmArgs {
+        input,
+        source,
+        output,
+        profile,
+    } = args;
+    let tasm = match std::fs::read_to_string(&input) {
+        Ok(tasm) => tasm,
+        Err(e) => {
+            eprintln!("error: cannot read '{}': {}", input.display(), e);
+            process::exit(1);
+        }
+    };
+
+    let (source_map, root) = match source {
+        Some(source) => {
+            let ri = resolve_input(&source);
+            let options = resolve_options("triton", &profile, ri.project.as_ref());
+            let entries = match crate::index_project(&ri.entry, &options) {
+                Ok(entries) => entries,
+                Err(_) => {
+                    eprintln!("error: cannot map to source (compilation errors)");
+                    process::exit(1);
+                }
+            };
+            let root = match &ri.project {
+                Some(project) => project.root_dir.clone(),
+                None => ri.entry.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            };
+            (entries, root)
+        }
+        None => (Vec::new(), PathBuf::new()),
+    };
+
+    let listing = match crate::disassemble_tasm(&tasm, &source_map, &root) {
+        Ok(listing) => listing,
+        Err(e) => {
+            eprintln!("error: {}: {}", input.display(), e);
+            process::exit(1);
+        }
+    };
+
+    if let Some(out_path) = output {
+        if let Err(e) = std::fs::write(&out_path, &listing) {
+            eprintln!("error: cannot write '{}': {}", out_path.display(), e);
+            process::exit(1);
+        }
+        eprintln!("Disassembled -> {}", out_path.display());
+    } else {
+        print!("{}", listing);
+    }
+}
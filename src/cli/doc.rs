@@ -1,9 +1,15 @@
-use std::path::PathBuf;
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
+use crate::registry::http::{read_head, write_response};
+use crate::CompileOptions;
 use clap::Args;
 
-use super::{resolve_input, resolve_options};
+use super::{collect_tri_files, resolve_input, resolve_options};
 
 #[derive(Args)]
 pub struct DocArgs {
@@ -30,6 +36,12 @@ pub struct DocArgs {
     /// Compilation profile for cfg flags (debug or release)
     #[arg(long, default_value = "debug")]
     pub profile: String,
+    /// Serve the docs as HTML on localhost, regenerating on change
+    #[arg(long, conflicts_with = "output")]
+    pub serve: bool,
+    /// Port for --serve
+    #[arg(long, default_value = "8000", requires = "serve")]
+    pub port: u16,
 }
 
 pub fn cmd_doc(args: DocArgs) {
@@ -42,13 +54,22 @@ pub fn cmd_doc(args: DocArgs) {
         network,
         union_flag,
         profile,
+        serve,
+        port,
     } = args;
     let bf = super::resolve_battlefield_compile(&target, &engine, &terrain, &network, &union_flag);
     let target = bf.target;
     let ri = resolve_input(&input);
 
     let options = resolve_options(&target, &profile, ri.project.as_ref());
-    let markdown = match trident::generate_docs(&ri.entry, &options) {
+    if serve {
+        let root = match &ri.project {
+            Some(project) => project.root_dir.clone(),
+            None => ri.entry.parent().unwrap_or(Path::new(".")).to_path_buf(),
+        };
+        serve_docs(ri.entry, root, options, port);
+    }
+    let markdown = match crate::generate_docs(&ri.entry, &options) {
         Ok(md) => md,
         Err(_) => {
             eprintln!("error: documentation generation failed (compilation errors)");
@@ -66,3 +87,108 @@ pub fn cmd_doc(args: DocArgs) {
         print!("{}", markdown);
     }
 }
+
+// ─── Live preview ─────────────────────────────────────────────────
+
+/// Polls `/__version` and reloads the page when the docs change.
+const RELOAD_SCRIPT: &str = "<script>\
+let v=null;setInterval(async()=>{try{const t=await (await fetch('/__version')).text();\
+if(v!==null&&t!==v)location.reload();v=t}catch(e){}},1000)\
+</script>\n";
+
+/// The page being served and how many times it has been regenerated.
+struct Site {
+    html: String,
+    version: u64,
+}
+
+/// Serve HTML docs on localhost until interrupted, regenerating them
+/// whenever a `.tri` file or `trident.toml` under `root` changes.
+fn serve_docs(entry: PathBuf, root: PathBuf, options: CompileOptions, port: u16) -> ! {
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("error: cannot listen on 127.0.0.1:{}: {}", port, e);
+            process::exit(1);
+        }
+    };
+    let site = Arc::new(Mutex::new(Site {
+        html: render_page(&entry, &options),
+        version: 0,
+    }));
+    eprintln!("Serving docs on http://127.0.0.1:{} (Ctrl-C to stop)", port);
+
+    let watched = Arc::clone(&site);
+    std::thread::spawn(move || {
+        let mut stamp = source_stamp(&root);
+        loop {
+            std::thread::sleep(Duration::from_millis(500));
+            let now = source_stamp(&root);
+            if now == stamp {
+                continue;
+            }
+            stamp = now;
+            let html = render_page(&entry, &options);
+            let mut site = watched.lock().unwrap_or_else(|e| e.into_inner());
+            site.html = html;
+            site.version += 1;
+            eprintln!("Regenerated docs");
+        }
+    });
+
+    for stream in listener.incoming().flatten() {
+        let site = Arc::clone(&site);
+        std::thread::spawn(move || handle_request(stream, &site));
+    }
+    process::exit(1);
+}
+
+/// Generate the HTML page, showing compile errors in place of the docs.
+fn render_page(entry: &Path, options: &CompileOptions) -> String {
+    let markdown = match crate::generate_docs(entry, options) {
+        Ok(md) => md,
+        Err(errors) => {
+            let mut md = String::from("# Documentation unavailable\n\n");
+            for e in &errors {
+                md.push_str(&format!("error: {}\n", e.message));
+            }
+            md
+        }
+    };
+    crate::docs_to_html(&markdown).replace("</body>", &format!("{}</body>", RELOAD_SCRIPT))
+}
+
+/// Modification times of every watched file, in path order.
+fn source_stamp(root: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut files = collect_tri_files(root);
+    files.push(root.join("trident.toml"));
+    files
+        .into_iter()
+        .map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+fn handle_request(mut stream: TcpStream, site: &Mutex<Site>) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+    let head = match read_head(&mut BufReader::new(&stream)) {
+        Ok(head) => head,
+        Err(_) => return,
+    };
+    let (status, content_type, body) = match head.request() {
+        Some(("GET", "/" | "/index.html")) => {
+            let site = site.lock().unwrap_or_else(|e| e.into_inner());
+            (200, "text/html; charset=utf-8", site.html.clone())
+        }
+        Some(("GET", "/__version")) => {
+            let site = site.lock().unwrap_or_else(|e| e.into_inner());
+            (200, "text/plain", site.version.to_string())
+        }
+        Some(("GET", _)) => (404, "text/plain", "not found\n".to_string()),
+        Some(_) => (405, "text/plain", "method not allowed\n".to_string()),
+        None => (400, "text/plain", "bad request\n".to_string()),
+    };
+    let _ = write_response(&mut stream, status, content_type, body.as_bytes());
+}
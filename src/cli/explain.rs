@@ -0,0 +1,28 @@
+use std::process;
+
+use clap::Args;
+
+use crate::diagnostic::ErrorCode;
+
+#[derive(Args)]
+pub struct ExplainArgs {
+    /// Error code, e.g. E0201. Without one, every code is listed.
+    pub code: Option<String>,
+}
+
+pub fn cmd_explain(args: ExplainArgs) {
+    let Some(code) = args.code else {
+        for code in ErrorCode::ALL {
+            println!("{}  {}", code, code.title());
+        }
+        return;
+    };
+    let Some(code) = ErrorCode::parse(&code) else {
+        eprintln!("error: '{}' is not a Trident error code", code);
+        eprintln!("  run `trident explain` to list the codes");
+        process::exit(1);
+    };
+    println!("{}: {}", code, code.title());
+    println!();
+    print!("{}", code.explanation());
+}
@@ -0,0 +1,86 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::Args;
+
+use super::{resolve_options, resolve_tri_files};
+
+#[derive(Args)]
+pub struct FixArgs {
+    /// Input .tri file or directory (defaults to current directory)
+    pub input: Option<PathBuf>,
+    /// Print the fixes as a diff without modifying files
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Compilation profile for cfg flags (debug or release)
+    #[arg(long, default_value = "debug")]
+    pub profile: String,
+}
+
+pub fn cmd_fix(args: FixArgs) {
+    let FixArgs {
+        input,
+        dry_run,
+        profile,
+    } = args;
+    let input = input.unwrap_or_else(|| PathBuf::from("."));
+    let files = resolve_tri_files(&input);
+
+    if files.is_empty() {
+        eprintln!("No .tri files found in '{}'", input.display());
+        return;
+    }
+
+    let mut failed = false;
+    let mut fixed_files = 0;
+    for file in &files {
+        match fix_single_file(file, &profile, dry_run) {
+            Ok(changed) if changed => fixed_files += 1,
+            Ok(_) => {}
+            Err(msg) => {
+                eprintln!("error: {}", msg);
+                failed = true;
+            }
+        }
+    }
+
+    if dry_run {
+        eprintln!("{} file(s) would be fixed", fixed_files);
+    } else {
+        eprintln!("Fixed {} file(s)", fixed_files);
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// Fix a single .tri file. Returns Ok(true) if the file was changed/would be changed.
+fn fix_single_file(path: &Path, profile: &str, dry_run: bool) -> Result<bool, String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let project = crate::project::Project::find(dir)
+        .and_then(|toml| crate::project::Project::load(&toml).ok());
+    let options = resolve_options("triton", profile, project.as_ref());
+
+    let fixed = crate::fix_source(&source, path, &options)
+        .map_err(|_| format!("cannot fix '{}' (parse errors)", path.display()))?;
+    if fixed.source == source {
+        return Ok(false);
+    }
+
+    for fix in &fixed.applied {
+        eprintln!("{}:{}: {}", path.display(), fix.line, fix.message);
+    }
+    if dry_run {
+        print!(
+            "{}",
+            crate::unified_diff(&path.to_string_lossy(), &source, &fixed.source)
+        );
+        return Ok(true);
+    }
+
+    std::fs::write(path, &fixed.source)
+        .map_err(|e| format!("cannot write '{}': {}", path.display(), e))?;
+    Ok(true)
+}
@@ -43,7 +43,7 @@ fn format_single_file(path: &Path, check: bool) -> Result<bool, String> {
     let source = std::fs::read_to_string(path)
         .map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
     let filename = path.to_string_lossy().to_string();
-    let formatted = trident::format_source(&source, &filename)
+    let formatted = crate::format_source(&source, &filename)
         .map_err(|_| format!("cannot format '{}' (parse errors)", path.display()))?;
 
     if formatted == source {
@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+use std::process;
+
+use crate::runtime::interp::fuzz::{fuzz_against, Divergence};
+use crate::runtime::ExecutionResult;
+use clap::Args;
+
+use super::trisha::{trisha_available, TrishaRunner};
+
+#[derive(Args)]
+pub struct FuzzArgs {
+    /// Number of random programs to run
+    #[arg(long, default_value = "100")]
+    pub cases: u64,
+    /// Seed of the first program; program i uses seed + i
+    #[arg(long, default_value = "0")]
+    pub seed: u64,
+    /// Write each diverging program to <dir>/fuzz_<seed>.tasm
+    #[arg(long, value_name = "DIR")]
+    pub save: Option<PathBuf>,
+}
+
+pub fn cmd_fuzz(args: FuzzArgs) {
+    if !trisha_available() {
+        eprintln!("error: differential fuzzing needs the Triton VM backend");
+        eprintln!("  install trisha (cargo install trisha) and put it on PATH");
+        process::exit(1);
+    }
+
+    eprintln!(
+        "Fuzzing interpreter against Triton VM: {} programs from seed {}",
+        args.cases, args.seed
+    );
+    let divergences = fuzz_against(&TrishaRunner, args.seed, args.cases);
+    for d in &divergences {
+        report_divergence(d);
+        if let Some(ref dir) = args.save {
+            let path = dir.join(format!("fuzz_{}.tasm", d.case.seed));
+            if let Err(e) =
+                std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, &d.case.tasm))
+            {
+                eprintln!("  error: cannot write '{}': {}", path.display(), e);
+            }
+        }
+    }
+
+    eprintln!();
+    if divergences.is_empty() {
+        eprintln!("fuzz result: ok. {} programs agree", args.cases);
+    } else {
+        eprintln!(
+            "fuzz result: FAILED. {} of {} programs diverge",
+            divergences.len(),
+            args.cases
+        );
+        eprintln!("  replay one with --seed <seed> --cases 1");
+        process::exit(1);
+    }
+}
+
+fn report_divergence(d: &Divergence) {
+    eprintln!("  seed {} ... {}", d.case.seed, d.reason);
+    eprintln!("    interpreter: {}", describe(&d.interpreter));
+    eprintln!("    triton vm:   {}", describe(&d.candidate));
+}
+
+fn describe(run: &Result<ExecutionResult, String>) -> String {
+    match run {
+        Ok(r) => format!("output {:?} in {} cycles", r.output, r.cycle_count),
+        Err(e) => format!("error: {}", e),
+    }
+}
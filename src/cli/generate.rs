@@ -10,10 +10,17 @@ pub struct GenerateArgs {
     /// Output file (default: stdout)
     #[arg(short, long)]
     pub output: Option<PathBuf>,
+    /// Emit Rust host encoders (BFieldCodec) for the file's structs and decoders for its events
+    #[arg(long)]
+    pub codec: bool,
 }
 
 pub fn cmd_generate(args: GenerateArgs) {
-    let GenerateArgs { input, output } = args;
+    let GenerateArgs {
+        input,
+        output,
+        codec,
+    } = args;
     if !input.extension().is_some_and(|e| e == "tri") {
         eprintln!("error: input must be a .tri file");
         process::exit(1);
@@ -28,23 +35,33 @@ pub fn cmd_generate(args: GenerateArgs) {
     };
 
     let filename = input.to_string_lossy().to_string();
-    let file = match trident::parse_source_silent(&source, &filename) {
+    let file = match crate::parse_source_silent(&source, &filename) {
         Ok(f) => f,
         Err(errors) => {
-            trident::diagnostic::render_diagnostics(&errors, &filename, &source);
+            crate::diagnostic::render_diagnostics(&errors, &filename, &source);
             eprintln!("error: parse errors in '{}'", input.display());
             process::exit(1);
         }
     };
 
-    let scaffold = trident::scaffold::generate_scaffold(&file);
+    let (scaffold, what) = if codec {
+        match crate::scaffold::codec::generate_codec(&file) {
+            Ok(code) => (code, "host encoders"),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        }
+    } else {
+        (crate::scaffold::generate_scaffold(&file), "scaffold")
+    };
 
     if let Some(out_path) = output {
         if let Err(e) = std::fs::write(&out_path, &scaffold) {
             eprintln!("error: cannot write '{}': {}", out_path.display(), e);
             process::exit(1);
         }
-        eprintln!("Generated scaffold -> {}", out_path.display());
+        eprintln!("Generated {} -> {}", what, out_path.display());
     } else {
         print!("{}", scaffold);
     }
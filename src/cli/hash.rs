@@ -18,8 +18,8 @@ pub fn cmd_hash(args: HashArgs) {
     let ri = resolve_input(&input);
     let (_, file) = load_and_parse(&ri.entry);
 
-    let fn_hashes = trident::hash::hash_file(&file);
-    let file_hash = trident::hash::hash_file_content(&file);
+    let fn_hashes = crate::hash::hash_file(&file);
+    let file_hash = crate::hash::hash_file_content(&file);
 
     if full {
         eprintln!("File: {} {}", file_hash.to_hex(), ri.entry.display());
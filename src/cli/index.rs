@@ -0,0 +1,68 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::Args;
+
+use super::{resolve_input, resolve_options};
+
+#[derive(Args)]
+pub struct IndexArgs {
+    /// Input .tri file or directory with trident.toml
+    pub input: PathBuf,
+    /// Output file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// Index format: ctags `tags` file or JSON Lines
+    #[arg(long, default_value = "ctags", value_parser = ["ctags", "json"])]
+    pub format: String,
+    /// Target VM (default: triton)
+    #[arg(long, default_value = "triton")]
+    pub target: String,
+    /// Compilation profile for cfg flags (debug or release)
+    #[arg(long, default_value = "debug")]
+    pub profile: String,
+}
+
+pub fn cmd_index(args: IndexArgs) {
+    let IndexArgs {
+        input,
+        output,
+        format,
+        target,
+        profile,
+    } = args;
+    let ri = resolve_input(&input);
+    let options = resolve_options(&target, &profile, ri.project.as_ref());
+
+    let entries = match crate::index_project(&ri.entry, &options) {
+        Ok(entries) => entries,
+        Err(_) => {
+            eprintln!("error: indexing failed (compilation errors)");
+            process::exit(1);
+        }
+    };
+
+    // Paths are written relative to the project root.
+    let root = match &ri.project {
+        Some(project) => project.root_dir.clone(),
+        None => ri.entry.parent().unwrap_or(Path::new(".")).to_path_buf(),
+    };
+    let index = match format.as_str() {
+        "json" => crate::format_index_json(&entries, &root),
+        _ => crate::format_ctags(&entries, &root),
+    };
+
+    if let Some(out_path) = output {
+        if let Err(e) = std::fs::write(&out_path, &index) {
+            eprintln!("error: cannot write '{}': {}", out_path.display(), e);
+            process::exit(1);
+        }
+        eprintln!(
+            "Indexed {} definitions into {}",
+            entries.len(),
+            out_path.display()
+        );
+    } else {
+        print!("{}", index);
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 
 use clap::Args;
@@ -7,10 +7,24 @@ use clap::Args;
 pub struct InitArgs {
     /// Project name (defaults to current directory name)
     pub name: Option<String>,
+    /// Instantiate a project template published to a registry
+    #[arg(long, value_name = "TEMPLATE")]
+    pub from_registry: Option<String>,
+    /// Author substituted for {{author}} in the template (default: $USER)
+    #[arg(long)]
+    pub author: Option<String>,
+    /// Registry URL
+    #[arg(long)]
+    pub registry: Option<String>,
 }
 
 pub fn cmd_init(args: InitArgs) {
-    let InitArgs { name } = args;
+    let InitArgs {
+        name,
+        from_registry,
+        author,
+        registry,
+    } = args;
     let (project_dir, project_name) = if let Some(ref name) = name {
         let dir = PathBuf::from(name);
         (dir, name.clone())
@@ -36,6 +50,14 @@ pub fn cmd_init(args: InitArgs) {
         }
     }
 
+    if let Some(template) = from_registry {
+        let author = author
+            .or_else(|| std::env::var("USER").ok())
+            .unwrap_or_default();
+        init_from_template(&template, &project_dir, &project_name, &author, registry);
+        return;
+    }
+
     let toml_path = project_dir.join("trident.toml");
     if toml_path.exists() {
         eprintln!("error: '{}' already exists", toml_path.display());
@@ -71,3 +93,39 @@ pub fn cmd_init(args: InitArgs) {
     eprintln!("  {}", toml_path.display());
     eprintln!("  {}", main_path.display());
 }
+
+fn init_from_template(
+    template: &str,
+    project_dir: &Path,
+    project_name: &str,
+    author: &str,
+    registry: Option<String>,
+) {
+    let client = super::registry_client(registry);
+    let template = match client.pull_template(template) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let vars = [("project_name", project_name), ("author", author)];
+    match template.instantiate(project_dir, &vars) {
+        Ok(written) => {
+            eprintln!(
+                "Created project '{}' in {} from template '{}'",
+                project_name,
+                project_dir.display(),
+                template.name
+            );
+            for path in &written {
+                eprintln!("  {}", path.display());
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    }
+}
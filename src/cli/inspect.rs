@@ -0,0 +1,442 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::Args;
+
+use crate::manifest::Lockfile;
+use crate::runtime::{BuildInfo, EnvelopeSignature, ProgramBundle, ProofData, ProofEnvelope};
+
+#[derive(Args)]
+pub struct InspectArgs {
+    /// Program bundle, proof (JSON or envelope), lockfile, or compiled .tasm file
+    pub input: PathBuf,
+    /// Print a machine-readable JSON summary
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// An artifact recognized by `trident inspect`.
+enum Artifact {
+    Bundle(Box<ProgramBundle>),
+    Proof(ProofData, Option<EnvelopeSignature>),
+    Lockfile(Lockfile),
+    Assembly(Option<BuildInfo>),
+}
+
+pub fn cmd_inspect(args: InspectArgs) {
+    let InspectArgs { input, json } = args;
+    let artifact = match load(&input) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    };
+    let out = match (&artifact, json) {
+        (Artifact::Bundle(b), false) => bundle_text(b),
+        (Artifact::Bundle(b), true) => bundle_json(b),
+        (Artifact::Proof(p, sig), false) => proof_text(p, sig.as_ref()),
+        (Artifact::Proof(p, sig), true) => proof_json(p, sig.as_ref()),
+        (Artifact::Lockfile(l), false) => lockfile_text(l),
+        (Artifact::Lockfile(l), true) => lockfile_json(l),
+        (Artifact::Assembly(b), false) => assembly_text(&input, b.as_ref()),
+        (Artifact::Assembly(b), true) => assembly_json(b.as_ref()),
+    };
+    print!("{}", out);
+}
+
+/// Recognize an artifact by its contents.
+fn load(path: &Path) -> Result<Artifact, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    if ProofEnvelope::is_envelope(&bytes) {
+        return ProofEnvelope::decode(&bytes)
+            .map(|env| Artifact::Proof(env.proof, env.signature))
+            .map_err(|e| format!("'{}' is not a valid proof envelope: {}", path.display(), e));
+    }
+    let text = String::from_utf8(bytes)
+        .map_err(|_| format!("'{}' is not a recognized artifact", path.display()))?;
+    let trimmed = text.trim_start();
+    if trimmed.starts_with('{') {
+        if text.contains("\"assembly\"") {
+            return ProgramBundle::from_json(&text)
+                .map(|bundle| Artifact::Bundle(Box::new(bundle)))
+                .map_err(|e| format!("'{}' is not a valid bundle: {}", path.display(), e));
+        }
+        if text.contains("\"claim\"") {
+            return ProofData::from_json(&text)
+                .map(|proof| Artifact::Proof(proof, None))
+                .map_err(|e| format!("'{}' is not a valid proof: {}", path.display(), e));
+        }
+        return Err(format!("'{}' is not a bundle or proof", path.display()));
+    }
+    if text.lines().any(|l| l.trim() == "[lock]") {
+        return crate::manifest::load_lockfile(path).map(Artifact::Lockfile);
+    }
+    Ok(Artifact::Assembly(BuildInfo::from_tasm(&text)))
+}
+
+// ─── Text Output ───────────────────────────────────────────────────
+
+fn bundle_text(bundle: &ProgramBundle) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "Bundle:      {} {}\n",
+        bundle.name, bundle.version
+    ));
+    match bundle.target_os {
+        Some(ref os) => out.push_str(&format!("Target:      {} ({})\n", os, bundle.target_vm)),
+        None => out.push_str(&format!("Target:      {}\n", bundle.target_vm)),
+    }
+    out.push_str(&format!("Entry:       {}\n", bundle.entry_point));
+    out.push_str(&format!(
+        "Program:     {}\n",
+        bundle.program_digest().to_hex()
+    ));
+    out.push_str(&format!("Source hash: {}\n", bundle.source_hash));
+
+    out.push_str("\nABI:\n");
+    for func in &bundle.functions {
+        let short = func.hash.get(..16).unwrap_or(&func.hash);
+        out.push_str(&format!("  {}  {}\n", short, func.signature));
+    }
+    if !bundle.errors.is_empty() {
+        out.push_str("\nErrors:\n");
+        for error in &bundle.errors {
+            out.push_str(&format!("  {:>4}  {}\n", error.code, error.message));
+        }
+    }
+    if !bundle.outputs.is_empty() {
+        out.push_str("\nOutputs:\n");
+        for output in &bundle.outputs {
+            out.push_str(&format!("  {:>4}  {}\n", output.width, output.ty));
+        }
+    }
+    if !bundle.entrypoints.is_empty() {
+        out.push_str("\nEntrypoints:\n");
+        for entry in &bundle.entrypoints {
+            out.push_str(&format!("  {:>4}  {}\n", entry.selector, entry.name));
+        }
+    }
+    if !bundle.events.is_empty() {
+        out.push_str("\nEvents:\n");
+        for event in &bundle.events {
+            out.push_str(&format!(
+                "  {:>4}  {} v{} {{ {} }}",
+                event.tag,
+                event.name,
+                event.version,
+                event.fields.join(", ")
+            ));
+            match event.deprecated.as_deref() {
+                Some("") => out.push_str("  (deprecated)"),
+                Some(note) => out.push_str(&format!("  (deprecated: {})", note)),
+                None => {}
+            }
+            out.push('\n');
+        }
+    }
+
+    out.push_str("\nCost:\n");
+    for (name, value) in bundle
+        .cost
+        .table_names
+        .iter()
+        .zip(&bundle.cost.table_values)
+    {
+        out.push_str(&format!("  {:<14} {}\n", name, value));
+    }
+    out.push_str(&format!(
+        "  {:<14} {}\n",
+        "padded height", bundle.cost.padded_height
+    ));
+    out.push_str(&format!(
+        "  {:<14} {} ms\n",
+        "est. proving",
+        bundle.cost.estimated_proving_ns / 1_000_000
+    ));
+
+    out.push('\n');
+    out.push_str(&build_text(bundle.build.as_ref()));
+    out
+}
+
+fn proof_text(proof: &ProofData, signature: Option<&EnvelopeSignature>) -> String {
+    let list = |values: &[u64]| {
+        let items: Vec<String> = values.iter().map(u64::to_string).collect();
+        format!("[{}]", items.join(", "))
+    };
+    let mut out = String::new();
+    out.push_str(&format!("Proof:       {}\n", proof.format));
+    out.push_str(&format!("Size:        {} bytes\n", proof.proof_bytes.len()));
+    out.push_str(&format!(
+        "Program:     {}\n",
+        list(&proof.claim.program_hash)
+    ));
+    out.push_str(&format!(
+        "Input:       {}\n",
+        list(&proof.claim.public_input)
+    ));
+    out.push_str(&format!(
+        "Output:      {}\n",
+        list(&proof.claim.public_output)
+    ));
+    match signature {
+        Some(sig) => out.push_str(&format!(
+            "Signature:   {} (key {}, {} bytes)\n",
+            sig.scheme,
+            hex(&sig.public_key),
+            sig.signature.len()
+        )),
+        None => out.push_str("Signature:   (unsigned)\n"),
+    }
+    out
+}
+
+fn lockfile_text(lockfile: &Lockfile) -> String {
+    if lockfile.locked.is_empty() {
+        return "Lockfile:    no locked dependencies\n".to_string();
+    }
+    let mut out = format!(
+        "Lockfile:    {} locked dependencies\n",
+        lockfile.locked.len()
+    );
+    for (name, dep) in &lockfile.locked {
+        out.push_str(&format!("  {}\n", name));
+        out.push_str(&format!("    hash:   {}\n", dep.hash));
+        out.push_str(&format!("    source: {}\n", dep.source));
+    }
+    out
+}
+
+fn assembly_text(path: &Path, build: Option<&BuildInfo>) -> String {
+    format!("Assembly:    {}\n{}", path.display(), build_text(build))
+}
+
+fn build_text(build: Option<&BuildInfo>) -> String {
+    let info = match build {
+        Some(info) => info,
+        None => return "Build:       (no build metadata)\n".to_string(),
+    };
+    let mut out = String::new();
+    out.push_str(&format!("Compiler:    {}\n", info.compiler()));
+    out.push_str(&format!("Built for:   {}\n", info.target));
+    out.push_str(&format!("Profile:     {}\n", info.profile));
+    out.push_str(&format!("Cfg flags:   {}\n", info.cfg_flags.join(", ")));
+    if info.locked.is_empty() {
+        out.push_str("Locked deps: (none)\n");
+    } else {
+        out.push_str("Locked deps:\n");
+        for (name, hash) in &info.locked {
+            out.push_str(&format!("  {} {}\n", name, hash));
+        }
+    }
+    out
+}
+
+// ─── JSON Output ───────────────────────────────────────────────────
+
+fn bundle_json(bundle: &ProgramBundle) -> String {
+    let functions: Vec<String> = bundle
+        .functions
+        .iter()
+        .map(|f| {
+            format!(
+                "{{\"name\": {}, \"hash\": {}, \"signature\": {}}}",
+                json_string(&f.name),
+                json_string(&f.hash),
+                json_string(&f.signature)
+            )
+        })
+        .collect();
+    let errors: Vec<String> = bundle
+        .errors
+        .iter()
+        .map(|e| {
+            format!(
+                "{{\"code\": {}, \"message\": {}}}",
+                e.code,
+                json_string(&e.message)
+            )
+        })
+        .collect();
+    let outputs: Vec<String> = bundle
+        .outputs
+        .iter()
+        .map(|o| {
+            format!(
+                "{{\"type\": {}, \"width\": {}}}",
+                json_string(&o.ty),
+                o.width
+            )
+        })
+        .collect();
+    let events: Vec<String> = bundle
+        .events
+        .iter()
+        .map(|e| {
+            let fields: Vec<String> = e.fields.iter().map(|f| json_string(f)).collect();
+            format!(
+                "{{\"name\": {}, \"tag\": {}, \"version\": {}, \"deprecated\": {}, \"fields\": [{}]}}",
+                json_string(&e.name),
+                e.tag,
+                e.version,
+                json_opt(e.deprecated.as_deref()),
+                fields.join(", ")
+            )
+        })
+        .collect();
+    let entrypoints: Vec<String> = bundle
+        .entrypoints
+        .iter()
+        .map(|e| {
+            format!(
+                "{{\"selector\": {}, \"name\": {}}}",
+                e.selector,
+                json_string(&e.name)
+            )
+        })
+        .collect();
+    let tables: Vec<String> = bundle
+        .cost
+        .table_names
+        .iter()
+        .zip(&bundle.cost.table_values)
+        .map(|(name, value)| format!("{}: {}", json_string(name), value))
+        .collect();
+    let fields = [
+        ("kind", json_string("bundle")),
+        ("name", json_string(&bundle.name)),
+        ("version", json_string(&bundle.version)),
+        ("target_vm", json_string(&bundle.target_vm)),
+        ("target_os", json_opt(bundle.target_os.as_deref())),
+        ("entry_point", json_string(&bundle.entry_point)),
+        (
+            "program_digest",
+            json_string(&bundle.program_digest().to_hex()),
+        ),
+        ("source_hash", json_string(&bundle.source_hash)),
+        ("functions", format!("[{}]", functions.join(", "))),
+        ("errors", format!("[{}]", errors.join(", "))),
+        ("outputs", format!("[{}]", outputs.join(", "))),
+        ("events", format!("[{}]", events.join(", "))),
+        ("entrypoints", format!("[{}]", entrypoints.join(", "))),
+        ("tables", format!("{{{}}}", tables.join(", "))),
+        ("padded_height", bundle.cost.padded_height.to_string()),
+        (
+            "estimated_proving_ns",
+            bundle.cost.estimated_proving_ns.to_string(),
+        ),
+        ("build", build_json(bundle.build.as_ref())),
+    ];
+    json_object(&fields)
+}
+
+fn proof_json(proof: &ProofData, signature: Option<&EnvelopeSignature>) -> String {
+    let list = |values: &[u64]| {
+        let items: Vec<String> = values.iter().map(u64::to_string).collect();
+        format!("[{}]", items.join(", "))
+    };
+    let fields = [
+        ("kind", json_string("proof")),
+        ("format", json_string(&proof.format)),
+        ("size", proof.proof_bytes.len().to_string()),
+        ("program_hash", list(&proof.claim.program_hash)),
+        ("public_input", list(&proof.claim.public_input)),
+        ("public_output", list(&proof.claim.public_output)),
+        (
+            "signature",
+            match signature {
+                Some(sig) => format!(
+                    "{{\"scheme\": {}, \"public_key\": {}, \"signature\": {}}}",
+                    json_string(&sig.scheme),
+                    json_string(&hex(&sig.public_key)),
+                    json_string(&hex(&sig.signature))
+                ),
+                None => "null".to_string(),
+            },
+        ),
+    ];
+    json_object(&fields)
+}
+
+fn lockfile_json(lockfile: &Lockfile) -> String {
+    let deps: Vec<String> = lockfile
+        .locked
+        .iter()
+        .map(|(name, dep)| {
+            format!(
+                "{}: {{\"hash\": {}, \"source\": {}}}",
+                json_string(name),
+                json_string(&dep.hash),
+                json_string(&dep.source)
+            )
+        })
+        .collect();
+    let fields = [
+        ("kind", json_string("lockfile")),
+        ("locked", format!("{{{}}}", deps.join(", "))),
+    ];
+    json_object(&fields)
+}
+
+fn assembly_json(build: Option<&BuildInfo>) -> String {
+    let fields = [
+        ("kind", json_string("assembly")),
+        ("build", build_json(build)),
+    ];
+    json_object(&fields)
+}
+
+fn build_json(build: Option<&BuildInfo>) -> String {
+    let info = match build {
+        Some(info) => info,
+        None => return "null".to_string(),
+    };
+    let flags: Vec<String> = info.cfg_flags.iter().map(|f| json_string(f)).collect();
+    let locked: Vec<String> = info
+        .locked
+        .iter()
+        .map(|(name, hash)| format!("{}: {}", json_string(name), json_string(hash)))
+        .collect();
+    format!(
+        "{{\"compiler_version\": {}, \"compiler_rev\": {}, \"target\": {}, \"profile\": {}, \"cfg_flags\": [{}], \"locked\": {{{}}}}}",
+        json_string(&info.compiler_version),
+        json_opt(info.compiler_rev.as_deref()),
+        json_string(&info.target),
+        json_string(&info.profile),
+        flags.join(", "),
+        locked.join(", ")
+    )
+}
+
+fn json_object(fields: &[(&str, String)]) -> String {
+    let body: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("  \"{}\": {}", key, value))
+        .collect();
+    format!("{{\n{}\n}}\n", body.join(",\n"))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn json_opt(value: Option<&str>) -> String {
+    value.map(json_string).unwrap_or_else(|| "null".to_string())
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
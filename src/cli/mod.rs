@@ -1,19 +1,32 @@
+mod app;
 pub mod audit;
 pub mod bench;
 pub mod build;
 // no subcommand — shared trisha subprocess helpers for bench + audit
 pub mod check;
+pub mod ci;
+pub mod conformance;
+pub mod cost;
+pub mod coverage;
 pub mod deploy;
 pub mod deps;
+pub mod disasm;
 pub mod doc;
+pub mod explain;
+pub mod fix;
 pub mod fmt;
+pub mod fuzz;
 pub mod generate;
 pub mod hash;
+pub mod index;
 pub mod init;
+pub mod inspect;
 pub mod package;
 pub mod prove;
+pub mod reduce;
 pub mod registry;
 pub mod run;
+pub mod state;
 pub mod store;
 pub mod test;
 pub mod train;
@@ -21,10 +34,13 @@ pub mod tree_sitter;
 pub mod trisha;
 pub mod verify;
 pub mod view;
+pub mod witness;
 
 use std::path::{Path, PathBuf};
 use std::process;
 
+pub(crate) use app::main;
+
 // ─── Three-Register Target Resolution ──────────────────────────────
 
 /// Resolved battlefield from three naming registers.
@@ -88,16 +104,36 @@ pub fn resolve_battlefield_compile(
 /// Resolved input: entry file and optional project.
 pub struct ResolvedInput {
     pub entry: PathBuf,
-    pub project: Option<trident::project::Project>,
+    pub project: Option<crate::project::Project>,
 }
 
-fn load_project(toml_path: &Path) -> trident::project::Project {
-    match trident::project::Project::load(toml_path) {
+fn load_project(toml_path: &Path) -> crate::project::Project {
+    let project = match crate::project::Project::load(toml_path) {
         Ok(p) => p,
         Err(e) => {
             eprintln!("error: {}", e.message);
             process::exit(1);
         }
+    };
+    run_generators(&project);
+    project
+}
+
+/// Run the project's `[build] generate` hooks, exiting on failure.
+fn run_generators(project: &crate::project::Project) {
+    if project.generators.is_empty() {
+        return;
+    }
+    match project.run_generators() {
+        Ok(result) => eprintln!(
+            "Generated {} file(s) in {}",
+            result.files.len(),
+            result.out_dir.display()
+        ),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
     }
 }
 
@@ -122,7 +158,7 @@ pub fn resolve_input(input: &Path) -> ResolvedInput {
         process::exit(1);
     }
 
-    let toml_path = trident::project::Project::find(input.parent().unwrap_or(Path::new(".")));
+    let toml_path = crate::project::Project::find(input.parent().unwrap_or(Path::new(".")));
     match toml_path {
         Some(p) => {
             let project = load_project(&p);
@@ -143,8 +179,8 @@ pub fn resolve_input(input: &Path) -> ResolvedInput {
 pub fn resolve_options(
     target: &str,
     profile: &str,
-    project: Option<&trident::project::Project>,
-) -> trident::CompileOptions {
+    project: Option<&crate::project::Project>,
+) -> crate::CompileOptions {
     // Backward compat: --target debug/release → treat as profile
     let (vm_target, actual_profile) = match target {
         "debug" | "release" => {
@@ -157,49 +193,65 @@ pub fn resolve_options(
         _ => (target, profile),
     };
 
-    // Project may override the default "triton" target
-    let effective_target = match (vm_target, project) {
-        ("triton", Some(proj)) if proj.target.is_some() => {
-            proj.target.as_deref().expect("guarded by is_some() check")
-        }
-        _ => vm_target,
+    let result = match project {
+        // The project's own target applies unless one was given.
+        Some(proj) => crate::CompileOptions::from_project(
+            proj,
+            crate::CompileOverrides {
+                target: (vm_target != "triton").then(|| vm_target.to_string()),
+                profile: Some(actual_profile.to_string()),
+                ..Default::default()
+            },
+        ),
+        None => crate::CompileOptions::builder()
+            .with_target(vm_target)
+            .with_profile(actual_profile)
+            .build(),
     };
-
-    let target_config = if effective_target == "triton" {
-        trident::target::TerrainConfig::triton()
-    } else {
-        match trident::target::TerrainConfig::resolve(effective_target) {
-            Ok(config) => config,
-            Err(e) => {
-                eprintln!("error: {}", e.message);
-                process::exit(1);
+    match result {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}", e.message);
+            if let Some(help) = &e.help {
+                eprintln!("  help: {}", help);
             }
+            process::exit(1);
         }
-    };
-
-    let cfg_flags = project
-        .and_then(|proj| proj.targets.get(actual_profile))
-        .map(|flags| flags.iter().cloned().collect())
-        .unwrap_or_else(|| std::collections::BTreeSet::from([actual_profile.to_string()]));
+    }
+}
 
-    trident::CompileOptions {
-        profile: actual_profile.to_string(),
-        cfg_flags,
-        target_config,
-        dep_dirs: Vec::new(),
+/// Resolve verification budgets: `flags` over the project's `[verify]`
+/// over the verifier's defaults.
+pub fn resolve_verify_config(
+    project: Option<&crate::project::Project>,
+    flags: &crate::project::VerifySettings,
+) -> crate::solve::VerifyConfig {
+    let toml = project.map(|proj| proj.verify.clone()).unwrap_or_default();
+    let defaults = crate::solve::VerifyConfig::default();
+    crate::solve::VerifyConfig {
+        samples: flags.samples.or(toml.samples).unwrap_or(defaults.samples),
+        bmc_depth: flags
+            .bmc_depth
+            .or(toml.bmc_depth)
+            .unwrap_or(defaults.bmc_depth),
+        time_budget: flags
+            .time_budget_ms
+            .or(toml.time_budget_ms)
+            .map(std::time::Duration::from_millis),
+        seed: flags.seed.or(toml.seed).unwrap_or(defaults.seed),
     }
 }
 
 /// Result of the shared compile → analyze → parse → verify pipeline.
 pub struct PreparedArtifact {
-    pub project: Option<trident::project::Project>,
+    pub project: Option<crate::project::Project>,
     pub entry: PathBuf,
     pub tasm: String,
-    pub cost: trident::cost::ProgramCost,
-    pub file: trident::ast::File,
+    pub cost: crate::cost::ProgramCost,
+    pub file: crate::ast::File,
     pub name: String,
     pub version: String,
-    pub resolved: trident::target::ResolvedTarget,
+    pub resolved: crate::target::ResolvedTarget,
 }
 
 /// Shared pipeline for package and deploy.
@@ -213,7 +265,7 @@ pub fn prepare_artifact(
     let project = ri.project;
     let entry = ri.entry;
 
-    let resolved = match trident::target::ResolvedTarget::resolve(target) {
+    let resolved = match crate::target::ResolvedTarget::resolve(target) {
         Ok(r) => r,
         Err(e) => {
             eprintln!("error: {}", e.message);
@@ -223,31 +275,34 @@ pub fn prepare_artifact(
 
     let mut options = resolve_options(&resolved.vm.name, profile, project.as_ref());
     options.target_config = resolved.vm.clone();
-    if let Some(ref proj) = project {
-        options.dep_dirs = load_dep_dirs(proj);
-    }
 
     eprintln!("Compiling {}...", entry.display());
-    let tasm = match trident::compile_project_with_options(&entry, &options) {
+    let tasm = match crate::compile_project_with_options(&entry, &options) {
         Ok(t) => t,
         Err(_) => {
             eprintln!("error: compilation failed");
             process::exit(1);
         }
     };
+    enforce_build_policy_or_exit(&entry, &options, project.as_ref());
 
-    let cost = trident::analyze_costs_project(&entry, &options).unwrap_or_else(|_| {
+    let cost = crate::analyze_costs_project(&entry, &options).unwrap_or_else(|_| {
         eprintln!("warning: cost analysis failed, using zeros");
-        trident::cost::ProgramCost {
+        crate::cost::ProgramCost {
             program_name: String::new(),
             functions: Vec::new(),
-            total: trident::cost::TableCost::ZERO,
+            total: crate::cost::TableCost::ZERO,
             table_names: Vec::new(),
             table_short_names: Vec::new(),
             attestation_hash_rows: 0,
             padded_height: 0,
             estimated_proving_ns: 0,
             loop_bound_waste: Vec::new(),
+            modules: Vec::new(),
+            constants: Vec::new(),
+            entrypoints: Vec::new(),
+            statements: Vec::new(),
+            rewrites: Vec::new(),
         }
     });
 
@@ -265,7 +320,7 @@ pub fn prepare_artifact(
     };
 
     if verify {
-        audit_or_exit(&entry);
+        audit_or_exit(&entry, project.as_ref());
     }
 
     PreparedArtifact {
@@ -280,9 +335,35 @@ pub fn prepare_artifact(
     }
 }
 
-fn audit_or_exit(entry: &Path) {
+/// The project's `trident-policy.toml`, if it has one. A malformed
+/// policy is rendered and exits.
+pub fn load_policy(project: Option<&crate::project::Project>) -> Option<crate::policy::Policy> {
+    let path = crate::policy::Policy::find(&project?.root_dir)?;
+    match crate::policy::Policy::load(&path) {
+        Ok(policy) => Some(policy),
+        Err(_) => process::exit(1),
+    }
+}
+
+/// Enforce the `[build]` rules of the project's policy, if any.
+pub fn enforce_build_policy_or_exit(
+    entry: &Path,
+    options: &crate::CompileOptions,
+    project: Option<&crate::project::Project>,
+) {
+    let Some(policy) = load_policy(project) else {
+        return;
+    };
+    if crate::enforce_build_policy(entry, options, &policy).is_err() {
+        eprintln!("error: build violates {}", crate::policy::POLICY_FILE);
+        process::exit(1);
+    }
+}
+
+fn audit_or_exit(entry: &Path, project: Option<&crate::project::Project>) {
     eprintln!("Auditing {}...", entry.display());
-    match trident::verify_project(entry) {
+    let config = resolve_verify_config(project, &Default::default());
+    match crate::verify_project_with(entry, &config) {
         Ok(report) if report.is_safe() => eprintln!("Verification: OK"),
         Ok(report) => {
             eprintln!("error: verification failed\n{}", report.format_report());
@@ -296,7 +377,7 @@ fn audit_or_exit(entry: &Path) {
 }
 
 /// Try to load and parse a .tri file, returning None on error (prints diagnostics).
-pub fn try_load_and_parse(path: &Path) -> Option<(String, trident::ast::File)> {
+pub fn try_load_and_parse(path: &Path) -> Option<(String, crate::ast::File)> {
     let source = match std::fs::read_to_string(path) {
         Ok(s) => s,
         Err(e) => {
@@ -305,7 +386,7 @@ pub fn try_load_and_parse(path: &Path) -> Option<(String, trident::ast::File)> {
         }
     };
     let filename = path.to_string_lossy().to_string();
-    match trident::parse_source_silent(&source, &filename) {
+    match crate::parse_source_silent(&source, &filename) {
         Ok(f) => Some((source, f)),
         Err(_) => {
             eprintln!("error: parse errors in '{}'", path.display());
@@ -315,7 +396,7 @@ pub fn try_load_and_parse(path: &Path) -> Option<(String, trident::ast::File)> {
 }
 
 /// Load and parse a .tri file, exiting on error.
-pub fn load_and_parse(path: &Path) -> (String, trident::ast::File) {
+pub fn load_and_parse(path: &Path) -> (String, crate::ast::File) {
     match try_load_and_parse(path) {
         Some(result) => result,
         None => process::exit(1),
@@ -323,8 +404,8 @@ pub fn load_and_parse(path: &Path) -> (String, trident::ast::File) {
 }
 
 /// Open the codebase store, exiting on error.
-pub fn open_codebase() -> trident::store::Codebase {
-    match trident::store::Codebase::open() {
+pub fn open_codebase() -> crate::store::Codebase {
+    match crate::store::Codebase::open() {
         Ok(cb) => cb,
         Err(e) => {
             eprintln!("error: cannot open codebase: {}", e);
@@ -333,10 +414,12 @@ pub fn open_codebase() -> trident::store::Codebase {
     }
 }
 
-/// Create a registry client with health check, exiting on error.
-pub fn registry_client(url: Option<String>) -> trident::registry::RegistryClient {
-    let url = url.unwrap_or_else(trident::registry::RegistryClient::default_url);
-    let client = trident::registry::RegistryClient::new(&url);
+/// Create a registry client with health check, exiting on error. Writes
+/// carry the token from `$TRIDENT_REGISTRY_TOKEN`, if set.
+pub fn registry_client(url: Option<String>) -> crate::registry::RegistryClient {
+    let url = url.unwrap_or_else(crate::registry::RegistryClient::default_url);
+    let client = crate::registry::RegistryClient::new(&url)
+        .with_token(crate::registry::RegistryClient::default_token());
     match client.health() {
         Ok(true) => {}
         Ok(false) => {
@@ -353,19 +436,7 @@ pub fn registry_client(url: Option<String>) -> trident::registry::RegistryClient
 
 /// Resolve a registry URL to its default if None.
 pub fn registry_url(url: Option<String>) -> String {
-    url.unwrap_or_else(trident::registry::RegistryClient::default_url)
-}
-
-/// Load dependency search directories from a project's lockfile (if present).
-pub fn load_dep_dirs(project: &trident::project::Project) -> Vec<PathBuf> {
-    let lock_path = project.root_dir.join("trident.lock");
-    if !lock_path.exists() {
-        return Vec::new();
-    }
-    match trident::manifest::load_lockfile(&lock_path) {
-        Ok(lockfile) => trident::manifest::dependency_search_paths(&project.root_dir, &lockfile),
-        Err(_) => Vec::new(),
-    }
+    url.unwrap_or_else(crate::registry::RegistryClient::default_url)
 }
 
 /// Find a warrior binary on PATH for the given target.
@@ -381,7 +452,7 @@ pub fn find_warrior(target: &str) -> Option<PathBuf> {
         return Some(path);
     }
 
-    if let Ok(resolved) = trident::target::ResolvedTarget::resolve(target) {
+    if let Ok(resolved) = crate::target::ResolvedTarget::resolve(target) {
         // Check VM's warrior config
         if let Some(ref warrior) = resolved.vm.warrior {
             // Try prefixed name (trident-<warrior>)
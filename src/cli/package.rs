@@ -88,8 +88,9 @@ pub fn cmd_package(args: PackageArgs) {
     };
 
     if dry_run {
-        let program_digest =
-            trident::hash::ContentHash(trident::poseidon2::hash_bytes(art.tasm.as_bytes()));
+        let program_digest = crate::hash::ContentHash(
+            crate::field::poseidon2::hash_bytes_goldilocks(art.tasm.as_bytes()),
+        );
         eprintln!("Dry run — would package:");
         eprintln!("  Name:            {}", art.name);
         eprintln!("  Version:         {}", art.version);
@@ -105,7 +106,7 @@ pub fn cmd_package(args: PackageArgs) {
     }
 
     // Generate artifact
-    let result = match trident::deploy::generate_artifact(
+    let result = match crate::deploy::generate_artifact(
         &art.name,
         &art.version,
         &art.tasm,
@@ -113,6 +114,9 @@ pub fn cmd_package(args: PackageArgs) {
         &art.cost,
         &art.resolved.vm,
         art.resolved.os.as_ref(),
+        art.project
+            .as_ref()
+            .map_or(&[][..], |p| p.commitments.as_slice()),
         &output_base,
     ) {
         Ok(r) => r,
@@ -90,7 +90,7 @@ pub fn cmd_prove(args: ProveArgs) {
     }
 
     let options = super::resolve_options(&target, &args.profile, ri.project.as_ref());
-    match trident::compile_to_bundle(&ri.entry, &options) {
+    match crate::compile_to_bundle(&ri.entry, &options) {
         Ok(bundle) => {
             let op_count = bundle.assembly.lines().count();
             eprintln!("Compiled {} ({} ops)", bundle.name, op_count);
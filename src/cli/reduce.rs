@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::Args;
+
+#[derive(Args)]
+pub struct ReduceArgs {
+    /// Input .tri file that reproduces the failure
+    pub input: PathBuf,
+    /// Shell command that fails on the input; `{}` is replaced by the path
+    /// of the input, which holds each candidate in turn, e.g. "trident build {}"
+    #[arg(long, value_name = "COMMAND")]
+    pub check: String,
+    /// Also require this text in the command's output, so that a candidate
+    /// failing some other way is not kept
+    #[arg(long, value_name = "TEXT")]
+    pub expect: Option<String>,
+    /// Output file (default: <input>.reduced.tri)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+pub fn cmd_reduce(args: ReduceArgs) {
+    let ReduceArgs {
+        input,
+        check,
+        expect,
+        output,
+    } = args;
+    let source = match std::fs::read_to_string(&input) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("error: cannot read '{}': {}", input.display(), e);
+            process::exit(1);
+        }
+    };
+    let output = output.unwrap_or_else(|| input.with_extension("reduced.tri"));
+
+    // Candidates are written over the input, so that the check sees them
+    // where the rest of the project expects that module; the input is
+    // backed up meanwhile and restored at the end.
+    let backup = input.with_extension("tri.orig");
+    if let Err(e) = std::fs::write(&backup, &source) {
+        eprintln!("error: cannot write '{}': {}", backup.display(), e);
+        process::exit(1);
+    }
+    let restore = || {
+        if std::fs::write(&input, &source).is_ok() {
+            let _ = std::fs::remove_file(&backup);
+        }
+    };
+
+    // A candidate reproduces the failure when the command exits with the
+    // status it exits with on the input.
+    let status = match run_check(&check, &input, &source, expect.as_deref()) {
+        Ok(Some(code)) if code != 0 => code,
+        Ok(_) => {
+            restore();
+            eprintln!("error: the check does not fail on '{}'", input.display());
+            process::exit(1);
+        }
+        Err(e) => {
+            restore();
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    };
+    let mut runs = 0;
+    let reduced = crate::reduce_source(&source, |text| {
+        runs += 1;
+        matches!(
+            run_check(&check, &input, text, expect.as_deref()),
+            Ok(Some(code)) if code == status
+        )
+    });
+    restore();
+
+    if let Err(e) = std::fs::write(&output, &reduced) {
+        eprintln!("error: cannot write '{}': {}", output.display(), e);
+        process::exit(1);
+    }
+    eprintln!(
+        "Reduced {} lines to {} in {} runs -> {}",
+        source.lines().count(),
+        reduced.lines().count(),
+        runs,
+        output.display()
+    );
+}
+
+/// Write `text` to `path` and run `check` on it: the exit status, or
+/// `None` when `expect` is missing from the output.
+fn run_check(
+    check: &str,
+    path: &Path,
+    text: &str,
+    expect: Option<&str>,
+) -> Result<Option<i32>, String> {
+    std::fs::write(path, text).map_err(|e| format!("cannot write '{}': {}", path.display(), e))?;
+    let command = check.replace("{}", &path.to_string_lossy());
+    let out = process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .output()
+        .map_err(|e| format!("cannot run '{}': {}", command, e))?;
+    if let Some(expect) = expect {
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        if !stdout.contains(expect) && !stderr.contains(expect) {
+            return Ok(None);
+        }
+    }
+    Ok(out.status.code())
+}
@@ -21,6 +21,20 @@ pub enum RegistryAction {
         #[arg(short, long)]
         input: Option<PathBuf>,
     },
+    /// Publish a project directory as a template for `init --from-registry`
+    PublishTemplate {
+        /// Template name (e.g. @org/zk-app)
+        name: String,
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// One-line description shown when listing templates
+        #[arg(long, default_value = "")]
+        description: String,
+        /// Registry URL
+        #[arg(long)]
+        registry: Option<String>,
+    },
     /// Pull a definition from a registry into local store
     Pull {
         /// Name or content hash to pull
@@ -28,6 +42,9 @@ pub enum RegistryAction {
         /// Registry URL
         #[arg(long)]
         registry: Option<String>,
+        /// Ask the registry again instead of using cached responses
+        #[arg(long)]
+        refresh: bool,
     },
     /// Search a registry for definitions
     Search {
@@ -42,9 +59,88 @@ pub enum RegistryAction {
         /// Search by tag
         #[arg(long)]
         tag: bool,
+        /// Only definitions costing at most this many rows in a table,
+        /// e.g. `--max hash=12` (repeatable; results cheapest first)
+        #[arg(long = "max", value_name = "TABLE=ROWS", value_parser = parse_cost_limit)]
+        max: Vec<(String, u64)>,
+        /// Ask the registry again instead of using cached responses
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Write the local codebase to a tar archive with an integrity manifest
+    Export {
+        /// Archive to create (e.g. backup.tar)
+        output: PathBuf,
+    },
+    /// Check an archive made by `export` and merge it into the local codebase
+    Import {
+        /// Archive to read
+        archive: PathBuf,
+    },
+    /// List publish, bind and yank events from a registry
+    Events {
+        /// Only events after this sequence number
+        #[arg(long, default_value = "0")]
+        since: u64,
+        /// Keep waiting for new events instead of exiting
+        #[arg(long)]
+        follow: bool,
+        /// Only events for definitions with a verification certificate
+        #[arg(long)]
+        verified: bool,
+        /// Registry URL
+        #[arg(long)]
+        registry: Option<String>,
+    },
+    /// Manage ownership of a name scope (`@org/...`)
+    Scope {
+        #[command(subcommand)]
+        action: ScopeAction,
     },
 }
 
+#[derive(Subcommand)]
+pub enum ScopeAction {
+    /// Claim an unowned scope for the token in $TRIDENT_REGISTRY_TOKEN
+    Claim {
+        /// Scope to claim (e.g. @org)
+        scope: String,
+        /// Registry URL
+        #[arg(long)]
+        registry: Option<String>,
+    },
+    /// Show the owners of a scope
+    Show {
+        /// Scope to look up
+        scope: String,
+        /// Registry URL
+        #[arg(long)]
+        registry: Option<String>,
+    },
+    /// Let another token bind names in a scope
+    Grant {
+        /// Scope to share
+        scope: String,
+        /// Member id of the token to add (see `scope id`)
+        member: String,
+        /// Registry URL
+        #[arg(long)]
+        registry: Option<String>,
+    },
+    /// Remove a token from a scope's owners
+    Revoke {
+        /// Scope to change
+        scope: String,
+        /// Member id of the token to remove
+        member: String,
+        /// Registry URL
+        #[arg(long)]
+        registry: Option<String>,
+    },
+    /// Print the member id of the token in $TRIDENT_REGISTRY_TOKEN
+    Id,
+}
+
 pub fn cmd_registry(action: RegistryAction) {
     match action {
         RegistryAction::Publish {
@@ -52,13 +148,34 @@ pub fn cmd_registry(action: RegistryAction) {
             tag,
             input,
         } => cmd_registry_publish(registry, tag, input),
-        RegistryAction::Pull { name, registry } => cmd_registry_pull(name, registry),
+        RegistryAction::PublishTemplate {
+            name,
+            input,
+            description,
+            registry,
+        } => cmd_registry_publish_template(name, input, description, registry),
+        RegistryAction::Pull {
+            name,
+            registry,
+            refresh,
+        } => cmd_registry_pull(name, registry, refresh),
         RegistryAction::Search {
             query,
             registry,
             r#type,
             tag,
-        } => cmd_registry_search(query, registry, r#type, tag),
+            max,
+            refresh,
+        } => cmd_registry_search(query, registry, r#type, tag, max, refresh),
+        RegistryAction::Export { output } => cmd_registry_export(output),
+        RegistryAction::Import { archive } => cmd_registry_import(archive),
+        RegistryAction::Events {
+            since,
+            follow,
+            verified,
+            registry,
+        } => cmd_registry_events(since, follow, verified, registry),
+        RegistryAction::Scope { action } => cmd_registry_scope(action),
     }
 }
 
@@ -79,7 +196,7 @@ fn cmd_registry_publish(registry: Option<String>, tags: Vec<String>, input: Opti
     }
 
     eprintln!("Publishing...");
-    match trident::registry::publish_codebase(&cb, &client, &tags) {
+    match crate::registry::publish_codebase(&cb, &client, &tags) {
         Ok(results) => {
             let created = results.iter().filter(|r| r.created).count();
             let existing = results.len() - created;
@@ -96,13 +213,51 @@ fn cmd_registry_publish(registry: Option<String>, tags: Vec<String>, input: Opti
     }
 }
 
-fn cmd_registry_pull(name: String, registry: Option<String>) {
+fn cmd_registry_publish_template(
+    name: String,
+    input: Option<PathBuf>,
+    description: String,
+    registry: Option<String>,
+) {
+    let dir = input.unwrap_or_else(|| PathBuf::from("."));
+    let template = match crate::registry::ProjectTemplate::from_dir(&dir, &name, &description) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let client = registry_client(registry);
+    eprintln!(
+        "Publishing template '{}' ({} files)...",
+        name,
+        template.files.len()
+    );
+    match client.publish_template(&template) {
+        Ok(result) => {
+            let status = if result.created { "new" } else { "existing" };
+            eprintln!(
+                "Published: {} ({}, {})",
+                name,
+                short_hash(&result.hash),
+                status
+            );
+        }
+        Err(e) => {
+            eprintln!("error: template publish failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn cmd_registry_pull(name: String, registry: Option<String>, refresh: bool) {
     let url = registry_url(registry);
-    let client = trident::registry::RegistryClient::new(&url);
+    let client = crate::registry::RegistryClient::new(&url).with_refresh(refresh);
     let mut cb = open_codebase();
 
     eprintln!("Pulling '{}' from {}...", name, url);
-    match trident::registry::pull_into_codebase(&mut cb, &client, &name) {
+    match crate::registry::pull_into_codebase(&mut cb, &client, &name) {
         Ok(result) => {
             eprintln!("Pulled: {} ({})", name, short_hash(&result.hash));
             eprintln!("  Module: {}", result.module);
@@ -120,6 +275,11 @@ fn cmd_registry_pull(name: String, registry: Option<String>) {
             if !result.dependencies.is_empty() {
                 eprintln!("  Dependencies: {}", result.dependencies.len());
             }
+            if result.verified {
+                eprintln!("  Verified: certificate re-checked");
+            } else if result.verification_cert.is_some() {
+                eprintln!("  Verified: no (published certificate does not hold)");
+            }
         }
         Err(e) => {
             eprintln!("error: {}", e);
@@ -128,11 +288,20 @@ fn cmd_registry_pull(name: String, registry: Option<String>) {
     }
 }
 
-fn cmd_registry_search(query: String, registry: Option<String>, by_type: bool, by_tag: bool) {
+fn cmd_registry_search(
+    query: String,
+    registry: Option<String>,
+    by_type: bool,
+    by_tag: bool,
+    max: Vec<(String, u64)>,
+    refresh: bool,
+) {
     let url = registry_url(registry);
-    let client = trident::registry::RegistryClient::new(&url);
+    let client = crate::registry::RegistryClient::new(&url).with_refresh(refresh);
 
-    let results = if by_type {
+    let results = if !max.is_empty() {
+        client.search_by_cost(&query, &max)
+    } else if by_type {
         client.search_by_type(&query)
     } else if by_tag {
         client.search_by_tag(&query)
@@ -153,13 +322,22 @@ fn cmd_registry_search(query: String, registry: Option<String>, by_type: bool, b
                 } else {
                     format!(" [{}]", r.tags.join(", "))
                 };
+                let cost = if max.is_empty() || r.cost.is_empty() {
+                    String::new()
+                } else {
+                    let rows: Vec<String> =
+                        r.cost.iter().map(|(t, n)| format!("{}={}", t, n)).collect();
+                    format!("  ({})", rows.join(" "))
+                };
                 println!(
-                    "  {}  {}  {}{}{}",
+                    "  {}  {} ({})  {}{}{}{}",
                     short_hash(&r.hash),
                     r.name,
+                    r.module,
                     r.signature,
                     verified,
-                    tags
+                    tags,
+                    cost
                 );
             }
             eprintln!("\n{} results", results.len());
@@ -170,3 +348,116 @@ fn cmd_registry_search(query: String, registry: Option<String>, by_type: bool, b
         }
     }
 }
+
+/// Parse a `--max` limit: `TABLE=ROWS`.
+fn parse_cost_limit(s: &str) -> Result<(String, u64), String> {
+    let (table, rows) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected TABLE=ROWS, got '{}'", s))?;
+    let rows = rows
+        .parse()
+        .map_err(|_| format!("'{}' is not a row count", rows))?;
+    Ok((table.to_string(), rows))
+}
+
+fn cmd_registry_export(output: PathBuf) {
+    let cb = open_codebase();
+    match cb.export_archive(&output) {
+        Ok(summary) => eprintln!(
+            "Exported {} definitions, {} names -> {} ({} bytes)",
+            summary.definitions,
+            summary.names,
+            output.display(),
+            summary.bytes
+        ),
+        Err(e) => {
+            eprintln!("error: cannot write '{}': {}", output.display(), e);
+            process::exit(1);
+        }
+    }
+}
+
+fn cmd_registry_import(archive: PathBuf) {
+    let mut cb = open_codebase();
+    match cb.import_archive(&archive) {
+        Ok(result) => eprintln!(
+            "Imported: {} new, {} existing, {} names bound",
+            result.added, result.existing, result.names_bound
+        ),
+        Err(e) => {
+            eprintln!("error: cannot import '{}': {}", archive.display(), e);
+            process::exit(1);
+        }
+    }
+}
+
+fn cmd_registry_events(since: u64, follow: bool, verified_only: bool, registry: Option<String>) {
+    let client = registry_client(registry);
+    let mut since = since;
+    loop {
+        let batch = match client.events(since, if follow { 25 } else { 0 }) {
+            Ok(batch) => batch,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        };
+        for event in &batch.events {
+            if verified_only && !event.verified {
+                continue;
+            }
+            let verified = if event.verified { " [verified]" } else { "" };
+            println!(
+                "{}  {:<7}  {}  {}{}",
+                event.seq,
+                event.kind.as_str(),
+                short_hash(&event.hash),
+                event.name.as_deref().unwrap_or("-"),
+                verified
+            );
+        }
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+        since = batch.next;
+        if !follow {
+            eprintln!("next: --since {}", since);
+            return;
+        }
+    }
+}
+
+fn cmd_registry_scope(action: ScopeAction) {
+    let result = match action {
+        ScopeAction::Claim { scope, registry } => registry_client(registry).claim_scope(&scope),
+        ScopeAction::Show { scope, registry } => registry_client(registry).scope(&scope),
+        ScopeAction::Grant {
+            scope,
+            member,
+            registry,
+        } => registry_client(registry).grant_scope(&scope, &member),
+        ScopeAction::Revoke {
+            scope,
+            member,
+            registry,
+        } => registry_client(registry).revoke_scope(&scope, &member),
+        ScopeAction::Id => match crate::registry::RegistryClient::default_token() {
+            Some(token) => {
+                println!("{}", crate::registry::member_id(&token));
+                return;
+            }
+            None => Err("TRIDENT_REGISTRY_TOKEN is not set".to_string()),
+        },
+    };
+
+    match result {
+        Ok(info) => {
+            println!("@{}", info.scope);
+            for owner in &info.owners {
+                println!("  {}", owner);
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    }
+}
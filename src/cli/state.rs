@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum StateAction {
+    /// Fetch the commitments a packaged program reads into an input file
+    Pull {
+        /// .deploy/ artifact whose manifest references the commitments
+        artifact: PathBuf,
+        /// Node JSON-RPC URL to read from (default: the target's warrior)
+        #[arg(long)]
+        rpc: Option<String>,
+        /// Input file to write (default: <artifact>/state.inputs)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+pub fn cmd_state(action: StateAction) {
+    match action {
+        StateAction::Pull {
+            artifact,
+            rpc,
+            output,
+        } => {
+            let output = output.unwrap_or_else(|| artifact.join("state.inputs"));
+            let Some(url) = rpc else {
+                delegate_pull(&artifact, &output);
+                return;
+            };
+            let provider = crate::deploy::rpc::RpcDeployer::new(&url);
+            let state = match crate::deploy::state::pull_state(&artifact, &provider) {
+                Ok(state) => state,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    process::exit(1);
+                }
+            };
+            if let Err(e) = std::fs::write(&output, state.to_inputs()) {
+                eprintln!("error: cannot write '{}': {}", output.display(), e);
+                process::exit(1);
+            }
+            for (name, value) in &state.commitments {
+                eprintln!("  {:<20} {} elements", name, value.len());
+            }
+            eprintln!(
+                "Pulled {} commitments -> {}",
+                state.commitments.len(),
+                output.display()
+            );
+        }
+    }
+}
+
+/// Hand the pull to the warrior of the artifact's target, which knows
+/// how to query its chain.
+fn delegate_pull(artifact: &Path, output: &Path) {
+    let bundle = match crate::deploy::load_artifact_bundle(artifact) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    };
+    let target = bundle.target_os.unwrap_or(bundle.target_vm);
+    let Some(warrior) = super::find_warrior(&target) else {
+        eprintln!("error: no state provider for target '{}'", target);
+        eprintln!("  pass --rpc <url>, or install a warrior for the target");
+        process::exit(1);
+    };
+    let (artifact, output) = (artifact.display().to_string(), output.display().to_string());
+    super::delegate_to_warrior(&warrior, "state", &["pull", &artifact, "--output", &output]);
+}
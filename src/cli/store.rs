@@ -26,6 +26,13 @@ pub enum StoreAction {
         /// New name
         to: String,
     },
+    /// Add another name for a definition
+    Alias {
+        /// Existing name
+        name: String,
+        /// Additional name
+        alias: String,
+    },
     /// Show codebase statistics
     Stats,
     /// Show history of a name
@@ -38,6 +45,34 @@ pub enum StoreAction {
         /// Name or hash prefix
         name: String,
     },
+    /// Remove definitions unreachable from any name or lockfile
+    Gc {
+        /// Lockfiles whose hashes are kept as roots (default: ./trident.lock if present)
+        #[arg(long = "lock")]
+        lockfiles: Vec<PathBuf>,
+        /// List what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Re-hash every stored definition and check dependency closure
+    Verify,
+    /// Re-hash a codebase written by an older compiler, removing the old definitions
+    Migrate,
+    /// Verify definitions and store certificates for those that pass
+    Certify {
+        /// Name or hash prefix (default: every named definition)
+        name: Option<String>,
+    },
+    /// Re-point all dependents of one definition to another and re-hash them
+    Update {
+        /// Definition to replace (name or hash prefix)
+        old: String,
+        /// Replacement definition (name or hash prefix)
+        new: String,
+        /// Report the propagation without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 pub fn cmd_store(action: StoreAction) {
@@ -46,9 +81,15 @@ pub fn cmd_store(action: StoreAction) {
         StoreAction::List => cmd_store_list(),
         StoreAction::View { name } => cmd_store_view(name),
         StoreAction::Rename { from, to } => cmd_store_rename(from, to),
+        StoreAction::Alias { name, alias } => cmd_store_alias(name, alias),
         StoreAction::Stats => cmd_store_stats(),
         StoreAction::History { name } => cmd_store_history(name),
         StoreAction::Deps { name } => cmd_store_deps(name),
+        StoreAction::Gc { lockfiles, dry_run } => cmd_store_gc(lockfiles, dry_run),
+        StoreAction::Verify => cmd_store_verify(),
+        StoreAction::Migrate => cmd_store_migrate(),
+        StoreAction::Certify { name } => cmd_store_certify(name),
+        StoreAction::Update { old, new, dry_run } => cmd_store_update(old, new, dry_run),
     }
 }
 
@@ -140,6 +181,19 @@ fn cmd_store_rename(from: String, to: String) {
     eprintln!("Renamed '{}' -> '{}'", from, to);
 }
 
+fn cmd_store_alias(name: String, alias: String) {
+    let mut cb = open_codebase();
+    if let Err(e) = cb.alias(&name, &alias) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+    if let Err(e) = cb.save() {
+        eprintln!("error: cannot save codebase: {}", e);
+        process::exit(1);
+    }
+    eprintln!("Aliased '{}' -> '{}'", alias, name);
+}
+
 fn cmd_store_stats() {
     let cb = open_codebase();
     let stats = cb.stats();
@@ -165,14 +219,7 @@ fn cmd_store_history(name: String) {
 fn cmd_store_deps(name: String) {
     let cb = open_codebase();
 
-    let hash = if let Some((_, h)) = cb.list_names().iter().find(|(n, _)| *n == name.as_str()) {
-        **h
-    } else if let Some((h, _)) = cb.lookup_by_prefix(&name) {
-        *h
-    } else {
-        eprintln!("error: '{}' not found in codebase", name);
-        process::exit(1);
-    };
+    let hash = resolve_hash(&cb, &name);
 
     let deps = cb.dependencies(&hash);
     if deps.is_empty() {
@@ -192,3 +239,199 @@ fn cmd_store_deps(name: String) {
         }
     }
 }
+
+fn cmd_store_gc(lockfiles: Vec<PathBuf>, dry_run: bool) {
+    let mut cb = open_codebase();
+
+    let lockfiles = if lockfiles.is_empty() {
+        let default = PathBuf::from("trident.lock");
+        if default.exists() {
+            vec![default]
+        } else {
+            Vec::new()
+        }
+    } else {
+        lockfiles
+    };
+
+    let mut roots = Vec::new();
+    for path in &lockfiles {
+        match crate::manifest::load_lockfile(path) {
+            Ok(lock) => roots.extend(
+                lock.locked
+                    .values()
+                    .filter_map(|dep| crate::hash::ContentHash::from_hex(&dep.hash)),
+            ),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+
+    if dry_run {
+        let unreachable = cb.unreachable(&roots);
+        for hash in &unreachable {
+            println!("  would remove {}", hash);
+        }
+        eprintln!("\n{} unreachable definitions", unreachable.len());
+        return;
+    }
+
+    match cb.gc(&roots) {
+        Ok(result) => {
+            for hash in &result.removed {
+                println!("  removed {}", hash);
+            }
+            eprintln!(
+                "\nRemoved {} definitions, {} retained",
+                result.removed.len(),
+                result.retained
+            );
+        }
+        Err(e) => {
+            eprintln!("error: garbage collection failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn cmd_store_verify() {
+    let cb = open_codebase();
+    let report = match cb.verify() {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("error: cannot read codebase: {}", e);
+            process::exit(1);
+        }
+    };
+    for issue in &report.issues {
+        println!("  {}", issue);
+    }
+    if report.is_ok() {
+        eprintln!("Codebase OK: {} definitions verified", report.checked);
+    } else {
+        eprintln!(
+            "\n{} issues in {} definitions",
+            report.issues.len(),
+            report.checked
+        );
+        process::exit(1);
+    }
+}
+
+fn cmd_store_migrate() {
+    let migration: Option<crate::store::MigrationResult> = match crate::store::Codebase::migrate() {
+        Ok((_, migration)) => migration,
+        Err(e) => {
+            eprintln!("error: cannot migrate codebase: {}", e);
+            process::exit(1);
+        }
+    };
+    let Some(m) = migration else {
+        eprintln!(
+            "Codebase already uses hash version {}",
+            crate::hash::HASH_VERSION
+        );
+        return;
+    };
+    eprintln!(
+        "Re-hashed {} definitions from hash version {} to {}",
+        m.rehashed.len(),
+        m.from_version,
+        crate::hash::HASH_VERSION
+    );
+    if !m.kept.is_empty() {
+        eprintln!(
+            "warning: {} definitions kept their old hashes; run `trident store verify`",
+            m.kept.len()
+        );
+    }
+}
+
+fn cmd_store_certify(name: Option<String>) {
+    let mut cb = open_codebase();
+    let targets: Vec<(String, crate::hash::ContentHash)> = match name {
+        Some(name) => vec![(name.clone(), resolve_hash(&cb, &name))],
+        None => cb
+            .list_names()
+            .into_iter()
+            .map(|(n, h)| (n.to_string(), *h))
+            .collect(),
+    };
+
+    let mut certified = 0usize;
+    for (name, hash) in &targets {
+        match cb.certify(hash) {
+            Ok(_) => {
+                certified += 1;
+                eprintln!("  OK   {}", name);
+            }
+            Err(e) => eprintln!("  FAIL {}: {}", name, e),
+        }
+    }
+
+    if let Err(e) = cb.save() {
+        eprintln!("error: cannot save codebase: {}", e);
+        process::exit(1);
+    }
+    eprintln!("\nCertified {} of {} definitions", certified, targets.len());
+}
+
+fn cmd_store_update(old: String, new: String, dry_run: bool) {
+    let mut cb = open_codebase();
+    let old_hash = resolve_hash(&cb, &old);
+    let new_hash = resolve_hash(&cb, &new);
+
+    let result = match cb.update(old_hash, new_hash) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("error: cannot update {} -> {}: {}", old_hash, new_hash, e);
+            process::exit(1);
+        }
+    };
+
+    for (orig, repl) in &result.propagated {
+        let names = cb.names_for_hash(repl).join(", ");
+        println!("  {} -> {}  {}", orig, repl, names);
+    }
+    if !result.frontier.is_empty() {
+        eprintln!("\nFrontier:");
+        for hash in &result.frontier {
+            let names = cb.names_for_hash(hash).join(", ");
+            println!("  {}  {}", hash, names);
+        }
+    }
+
+    if dry_run {
+        eprintln!(
+            "\nDry run: {} dependents would be re-hashed, {} names rebound",
+            result.propagated.len(),
+            result.rebound.len()
+        );
+        return;
+    }
+    if let Err(e) = cb.save() {
+        eprintln!("error: cannot save codebase: {}", e);
+        process::exit(1);
+    }
+    eprintln!(
+        "\nUpdated {} -> {}: {} dependents re-hashed, {} names rebound",
+        old_hash,
+        new_hash,
+        result.propagated.len(),
+        result.rebound.len()
+    );
+}
+
+/// Resolve a name or hash prefix to a content hash, exiting if not found.
+fn resolve_hash(cb: &crate::store::Codebase, name: &str) -> crate::hash::ContentHash {
+    if let Some(h) = cb.hash_for_name(name) {
+        *h
+    } else if let Some((h, _)) = cb.lookup_by_prefix(name) {
+        *h
+    } else {
+        eprintln!("error: '{}' not found in codebase", name);
+        process::exit(1);
+    }
+}
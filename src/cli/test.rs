@@ -8,7 +8,11 @@ use super::{resolve_input, resolve_options};
 #[derive(Args)]
 pub struct TestArgs {
     /// Input .tri file or directory with trident.toml
-    pub input: PathBuf,
+    #[arg(required_unless_present = "std")]
+    pub input: Option<PathBuf>,
+    /// Run the tests of the bundled std, vm, and os libraries instead
+    #[arg(long, conflicts_with = "input")]
+    pub std: bool,
     /// Target VM (default: triton)
     #[arg(long, default_value = "triton")]
     pub target: String,
@@ -32,6 +36,7 @@ pub struct TestArgs {
 pub fn cmd_test(args: TestArgs) {
     let TestArgs {
         input,
+        std: _,
         target,
         engine,
         terrain,
@@ -41,14 +46,22 @@ pub fn cmd_test(args: TestArgs) {
     } = args;
     let bf = super::resolve_battlefield_compile(&target, &engine, &terrain, &network, &union_flag);
     let target = bf.target;
-    let ri = resolve_input(&input);
-
-    let options = resolve_options(&target, &profile, ri.project.as_ref());
-    let result = trident::run_tests(&ri.entry, &options);
+    let result = match input {
+        Some(input) => {
+            let ri = resolve_input(&input);
+            let options = resolve_options(&target, &profile, ri.project.as_ref());
+            crate::run_tests(&ri.entry, &options)
+        }
+        // `--std`: clap requires it when no input is given.
+        None => crate::run_std_tests(&resolve_options(&target, &profile, None)),
+    };
 
     match result {
         Ok(report) => {
             eprintln!("{}", report);
+            if report.contains("test result: FAILED") {
+                process::exit(1);
+            }
         }
         Err(_) => {
             process::exit(1);
@@ -33,7 +33,7 @@ pub enum TrainAction {
 /// Pre-compiled file data — TIR + baselines, computed once.
 struct CompiledFile {
     path: String,
-    tir_ops: Vec<trident::tir::TIROp>,
+    tir_ops: Vec<crate::tir::TIROp>,
     tasm_lines: Vec<String>,
     baseline_cost: u64,
 }
@@ -55,10 +55,10 @@ pub fn cmd_train(args: TrainArgs) {
         return;
     }
 
-    use trident::neural::checkpoint::{self, TrainingStage};
-    use trident::neural::data::pairs::extract_pairs;
-    use trident::neural::model::composite::NeuralCompilerConfig;
-    use trident::neural::model::vocab::Vocab;
+    use crate::neural::checkpoint::{self, TrainingStage};
+    use crate::neural::data::pairs::extract_pairs;
+    use crate::neural::model::composite::NeuralCompilerConfig;
+    use crate::neural::model::vocab::Vocab;
 
     let corpus = discover_corpus();
     if corpus.is_empty() {
@@ -69,7 +69,7 @@ pub fn cmd_train(args: TrainArgs) {
     eprintln!("trident train");
     eprintln!("  compiling corpus...");
 
-    let _guard = trident::diagnostic::suppress_warnings();
+    let _guard = crate::diagnostic::suppress_warnings();
     let compiled = compile_corpus(&corpus);
     drop(_guard);
     let total_baseline: u64 = compiled.iter().map(|c| c.baseline_cost).sum();
@@ -77,7 +77,7 @@ pub fn cmd_train(args: TrainArgs) {
     let config = NeuralCompilerConfig::new();
     let vocab = Vocab::new();
 
-    let blocks: Vec<(Vec<trident::tir::TIROp>, Vec<String>, String, u64)> = compiled
+    let blocks: Vec<(Vec<crate::tir::TIROp>, Vec<String>, String, u64)> = compiled
         .iter()
         .map(|cf| {
             (
@@ -101,19 +101,19 @@ pub fn cmd_train(args: TrainArgs) {
     let raw_count = raw_pairs.len();
     let holdout_count = (raw_count / 20).max(5).min(raw_count);
     let (train_raw, holdout) =
-        trident::neural::data::pairs::train_holdout_split(raw_pairs, holdout_count);
+        crate::neural::data::pairs::train_holdout_split(raw_pairs, holdout_count);
 
     // Data augmentation: expand training set via TASM random walk,
     // equivalent substitutions, and dead code insertion.
     eprintln!("  augmenting {} train pairs...", train_raw.len());
-    let augment_config = trident::neural::training::augment::AugmentConfig {
+    let augment_config = crate::neural::training::augment::AugmentConfig {
         tir_reorder_variants: 3,
         tasm_walk_variants: 5,
         max_swap_attempts: 10,
         seed: 0xDEAD_BEEF_A097,
     };
     let pairs =
-        trident::neural::training::augment::augment_pairs(&train_raw, &vocab, &augment_config);
+        crate::neural::training::augment::augment_pairs(&train_raw, &vocab, &augment_config);
     eprintln!("  holdout: {} pairs", holdout.len());
 
     // Stage selection: explicit --stage flag, or default to Stage 1.
@@ -202,18 +202,18 @@ pub fn cmd_train(args: TrainArgs) {
 
 /// Main training loop — generic over backend.
 fn run_training_loop<B: burn::tensor::backend::AutodiffBackend>(
-    model: trident::neural::model::composite::NeuralCompilerV2<B>,
-    pairs: &[trident::neural::data::pairs::TrainingPair],
-    holdout: &[trident::neural::data::pairs::TrainingPair],
+    model: crate::neural::model::composite::NeuralCompilerV2<B>,
+    pairs: &[crate::neural::data::pairs::TrainingPair],
+    holdout: &[crate::neural::data::pairs::TrainingPair],
     compiled: &[CompiledFile],
-    vocab: &trident::neural::model::vocab::Vocab,
+    vocab: &crate::neural::model::vocab::Vocab,
     epochs: u64,
     device: &B::Device,
-    stage: trident::neural::checkpoint::TrainingStage,
+    stage: crate::neural::checkpoint::TrainingStage,
 ) where
     <B as burn::tensor::backend::Backend>::FloatElem: From<f32>,
 {
-    use trident::neural::checkpoint::{self, CheckpointTag, TrainingStage};
+    use crate::neural::checkpoint::{self, CheckpointTag, TrainingStage};
 
     // Try loading existing checkpoint
     let load_tag = match stage {
@@ -228,11 +228,11 @@ fn run_training_loop<B: burn::tensor::backend::AutodiffBackend>(
         }
         Ok(None) => {
             eprintln!("  no checkpoint found, training from scratch");
-            trident::neural::model::composite::NeuralCompilerConfig::new().init::<B>(device)
+            crate::neural::model::composite::NeuralCompilerConfig::new().init::<B>(device)
         }
         Err(e) => {
             eprintln!("  warning: checkpoint load failed: {}", e);
-            trident::neural::model::composite::NeuralCompilerConfig::new().init::<B>(device)
+            crate::neural::model::composite::NeuralCompilerConfig::new().init::<B>(device)
         }
     };
 
@@ -251,21 +251,21 @@ fn run_training_loop<B: burn::tensor::backend::AutodiffBackend>(
 
 /// Stage 1: Supervised pre-training with cosine LR decay.
 fn run_stage1<B: burn::tensor::backend::AutodiffBackend>(
-    model: trident::neural::model::composite::NeuralCompilerV2<B>,
-    pairs: &[trident::neural::data::pairs::TrainingPair],
-    holdout: &[trident::neural::data::pairs::TrainingPair],
+    model: crate::neural::model::composite::NeuralCompilerV2<B>,
+    pairs: &[crate::neural::data::pairs::TrainingPair],
+    holdout: &[crate::neural::data::pairs::TrainingPair],
     compiled: &[CompiledFile],
-    vocab: &trident::neural::model::vocab::Vocab,
+    vocab: &crate::neural::model::vocab::Vocab,
     epochs: u64,
     device: &B::Device,
 ) where
     <B as burn::tensor::backend::Backend>::FloatElem: From<f32>,
 {
+    use crate::neural::checkpoint::{self, CheckpointTag};
+    use crate::neural::inference::beam::BeamConfig;
+    use crate::neural::training::supervised;
+    use crate::neural::training::supervised::cosine_lr;
     use burn::module::AutodiffModule;
-    use trident::neural::checkpoint::{self, CheckpointTag};
-    use trident::neural::inference::beam::BeamConfig;
-    use trident::neural::training::supervised;
-    use trident::neural::training::supervised::cosine_lr;
 
     let sup_config = supervised::SupervisedConfig::default();
     let mut optimizer = supervised::create_optimizer::<B>(&sup_config);
@@ -389,15 +389,15 @@ fn run_stage1<B: burn::tensor::backend::AutodiffBackend>(
 /// the best candidate is equivalent to the target TASM via stack verifier.
 /// Returns (valid_count, total_count).
 fn eval_holdout_validity<B: burn::prelude::Backend>(
-    model: &trident::neural::model::composite::NeuralCompilerV2<B>,
-    holdout: &[trident::neural::data::pairs::TrainingPair],
-    vocab: &trident::neural::model::vocab::Vocab,
-    beam_config: &trident::neural::inference::beam::BeamConfig,
+    model: &crate::neural::model::composite::NeuralCompilerV2<B>,
+    holdout: &[crate::neural::data::pairs::TrainingPair],
+    vocab: &crate::neural::model::vocab::Vocab,
+    beam_config: &crate::neural::inference::beam::BeamConfig,
     device: &B::Device,
 ) -> (usize, usize) {
-    use trident::neural::inference::beam::beam_search;
-    use trident::neural::inference::execute::validate_and_rank;
-    use trident::neural::training::supervised::{graph_to_edges, graph_to_features};
+    use crate::neural::inference::beam::beam_search;
+    use crate::neural::inference::execute::validate_and_rank;
+    use crate::neural::training::supervised::{graph_to_edges, graph_to_features};
 
     let mut valid = 0usize;
     let mut total = 0usize;
@@ -447,20 +447,20 @@ fn eval_holdout_validity<B: burn::prelude::Backend>(
 
 /// Stage 2: GFlowNet fine-tuning.
 fn run_stage2<B: burn::tensor::backend::AutodiffBackend>(
-    model: trident::neural::model::composite::NeuralCompilerV2<B>,
+    model: crate::neural::model::composite::NeuralCompilerV2<B>,
     compiled: &[CompiledFile],
-    vocab: &trident::neural::model::vocab::Vocab,
+    vocab: &crate::neural::model::vocab::Vocab,
     epochs: u64,
     device: &B::Device,
 ) where
     <B as burn::tensor::backend::Backend>::FloatElem: From<f32>,
 {
+    use crate::neural::checkpoint::{self, CheckpointTag};
+    use crate::neural::inference::beam::BeamConfig;
+    use crate::neural::training::gflownet::{self, GFlowNetConfig};
     use burn::grad_clipping::GradientClippingConfig;
     use burn::module::AutodiffModule;
     use burn::optim::{AdamWConfig, GradientsParams, Optimizer};
-    use trident::neural::checkpoint::{self, CheckpointTag};
-    use trident::neural::inference::beam::BeamConfig;
-    use trident::neural::training::gflownet::{self, GFlowNetConfig};
 
     let gf_config = GFlowNetConfig::default();
     let mut optimizer = AdamWConfig::new()
@@ -489,7 +489,7 @@ fn run_stage2<B: burn::tensor::backend::AutodiffBackend>(
 
         // For each compiled file, sample a sequence and compute TB loss
         for cf in compiled.iter() {
-            let graph = trident::neural::data::tir_graph::TirGraph::from_tir_ops(&cf.tir_ops);
+            let graph = crate::neural::data::tir_graph::TirGraph::from_tir_ops(&cf.tir_ops);
             if graph.nodes.is_empty() {
                 continue;
             }
@@ -602,15 +602,15 @@ fn run_stage2<B: burn::tensor::backend::AutodiffBackend>(
 /// to keep eval under ~30s. Returns one FileEval per compiled file; skipped
 /// files get zeroed evals.
 fn eval_files<B: burn::prelude::Backend>(
-    model: &trident::neural::model::composite::NeuralCompilerV2<B>,
+    model: &crate::neural::model::composite::NeuralCompilerV2<B>,
     compiled: &[CompiledFile],
-    vocab: &trident::neural::model::vocab::Vocab,
-    beam_config: &trident::neural::inference::beam::BeamConfig,
+    vocab: &crate::neural::model::vocab::Vocab,
+    beam_config: &crate::neural::inference::beam::BeamConfig,
     device: &B::Device,
 ) -> Vec<FileEval> {
-    use trident::neural::inference::beam::beam_search;
-    use trident::neural::inference::execute::validate_and_rank;
-    use trident::neural::training::supervised::{graph_to_edges, graph_to_features};
+    use crate::neural::inference::beam::beam_search;
+    use crate::neural::inference::execute::validate_and_rank;
+    use crate::neural::training::supervised::{graph_to_edges, graph_to_features};
 
     // Pre-filter: only evaluate functions that the beam could realistically match.
     // Sort by target length (shortest first) and cap at 50 to keep eval fast.
@@ -639,7 +639,7 @@ fn eval_files<B: burn::prelude::Backend>(
             });
             continue;
         }
-        let graph = trident::neural::data::tir_graph::TirGraph::from_tir_ops(&cf.tir_ops);
+        let graph = crate::neural::data::tir_graph::TirGraph::from_tir_ops(&cf.tir_ops);
         if graph.nodes.is_empty() {
             evals.push(FileEval {
                 total_blocks: cf.tasm_lines.len(),
@@ -690,9 +690,10 @@ fn eval_files<B: burn::prelude::Backend>(
             let target_preview: Vec<&str> =
                 cf.tasm_lines.iter().map(|s| s.as_str()).take(5).collect();
             let line = format!(
-                "{}: beam={} [{}] target=[{}]",
+                "{}: beam={} logp={:.2} [{}] target=[{}]",
                 cf.path,
                 top.len(),
+                beam_result.log_probs[0],
                 preview.join(", "),
                 target_preview.join(", "),
             );
@@ -737,7 +738,7 @@ fn eval_files<B: burn::prelude::Backend>(
 fn display_epoch_table(
     epoch: u64,
     total_epochs: u64,
-    result: &trident::neural::training::supervised::EpochResult,
+    result: &crate::neural::training::supervised::EpochResult,
     improved: bool,
     lr: f64,
     file_evals: &[FileEval],
@@ -913,13 +914,13 @@ fn walkdir_recursive(dir: &Path, result: &mut Vec<std::path::PathBuf>, depth: us
 }
 
 fn compile_corpus(files: &[std::path::PathBuf]) -> Vec<CompiledFile> {
-    use trident::neural::data::pairs::split_tir_by_function;
+    use crate::neural::data::pairs::split_tir_by_function;
 
     let options = super::resolve_options("triton", "debug", None);
     let mut compiled = Vec::new();
 
     for file in files {
-        let ir = match trident::build_tir_project(file, &options) {
+        let ir = match crate::build_tir_project(file, &options) {
             Ok(ir) => ir,
             Err(_) => continue,
         };
@@ -945,7 +946,7 @@ fn compile_corpus(files: &[std::path::PathBuf]) -> Vec<CompiledFile> {
 
             // Lower this function's TIR to TASM
             let lowering =
-                trident::ir::tir::lower::create_stack_lowering(&options.target_config.name);
+                crate::ir::tir::lower::create_stack_lowering(&options.target_config.name);
             let tasm_lines = lowering.lower(fn_tir);
 
             // Filter out labels and empty lines — keep only instructions
@@ -962,7 +963,7 @@ fn compile_corpus(files: &[std::path::PathBuf]) -> Vec<CompiledFile> {
                 continue;
             }
 
-            let profile = trident::cost::scorer::profile_tasm(
+            let profile = crate::cost::scorer::profile_tasm(
                 &tasm_lines.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
             );
             let baseline_cost = profile.cost().max(1);
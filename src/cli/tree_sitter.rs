@@ -14,7 +14,7 @@ pub struct TreeSitterArgs {
 }
 
 pub fn cmd_tree_sitter(args: TreeSitterArgs) {
-    let grammar = trident::syntax::grammar::trident_grammar();
+    let grammar = crate::syntax::grammar::trident_grammar();
     let json = grammar.to_json();
 
     let out_dir = args.output.unwrap_or_else(|| PathBuf::from("."));
@@ -95,10 +95,10 @@ pub fn generate_test_harness(tasm: &str) -> Harness {
     let mut body = String::with_capacity(clean.len());
     for line in clean.lines() {
         let t = line.trim();
-        if t == "assert" {
+        if is_op(t, "assert") {
             // assert pops 1 value and crashes if 0 — replace with pop 1
             body.push_str("    pop 1\n");
-        } else if t == "assert_vector" {
+        } else if is_op(t, "assert_vector") {
             // assert_vector checks stack[0..5] == stack[5..10], pops 5
             body.push_str("    pop 5\n");
         } else if t == "merkle_step" || t == "merkle_step_mem" {
@@ -147,6 +147,12 @@ pub fn generate_test_harness(tasm: &str) -> Harness {
     }
 }
 
+/// Whether the trimmed TASM line `t` is instruction `op`, with or
+/// without operands (`assert error_id 3`).
+fn is_op(t: &str, op: &str) -> bool {
+    t.split_whitespace().next() == Some(op)
+}
+
 /// Run trisha as a subprocess, parse output.
 pub fn run_trisha(args: &[&str]) -> Result<TrishaResult, String> {
     let start = std::time::Instant::now();
@@ -265,9 +271,9 @@ pub fn generate_program_harness(
         if t.starts_with("//") {
             continue;
         }
-        if t == "assert" {
+        if is_op(t, "assert") {
             result.push_str("    pop 1\n");
-        } else if t == "assert_vector" {
+        } else if is_op(t, "assert_vector") {
             result.push_str("    pop 5\n");
         } else if t == "merkle_step" || t == "merkle_step_mem" {
             result.push_str("    nop\n");
@@ -337,7 +343,11 @@ pub fn generate_program_harness(
         tasm: result,
         n_funcs: 1,
         read_io_count: 0,
-        divine_count: if inline_divine { 0 } else { divine_count * 4096 },
+        divine_count: if inline_divine {
+            0
+        } else {
+            divine_count * 4096
+        },
         merkle_count,
     }
 }
@@ -351,3 +361,45 @@ pub fn run_trisha_with_inputs(
     let str_args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     run_trisha(&str_args)
 }
+
+/// Triton VM, through trisha, as a `Runner`.
+pub struct TrishaRunner;
+
+impl crate::runtime::Runner for TrishaRunner {
+    fn run(
+        &self,
+        bundle: &crate::runtime::ProgramBundle,
+        input: &crate::runtime::ProgramInput,
+    ) -> Result<crate::runtime::ExecutionResult, String> {
+        let tmp_path = std::env::temp_dir().join(format!("trident_{}.tasm", bundle.name));
+        std::fs::write(&tmp_path, &bundle.assembly)
+            .map_err(|e| format!("cannot write temp file: {}", e))?;
+        let mut args = vec![
+            "run".to_string(),
+            "--tasm".to_string(),
+            tmp_path.to_string_lossy().to_string(),
+        ];
+        let join = |vals: &[u64]| {
+            vals.iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        if !input.public.is_empty() {
+            args.push("--input-values".into());
+            args.push(join(&input.public));
+        }
+        if !input.secret.is_empty() {
+            args.push("--secret".into());
+            args.push(join(&input.secret));
+        }
+        let str_args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let result = run_trisha(&str_args);
+        let _ = std::fs::remove_file(&tmp_path);
+        result.map(|r| crate::runtime::ExecutionResult {
+            output: r.output,
+            cycle_count: r.cycle_count,
+            trace: Vec::new(),
+        })
+    }
+}
@@ -25,27 +25,27 @@ pub fn cmd_view(args: ViewArgs) {
     let (_, file) = load_and_parse(&ri.entry);
     let filename = ri.entry.to_string_lossy().to_string();
 
-    let fn_hashes = trident::hash::hash_file(&file);
+    let fn_hashes = crate::hash::hash_file(&file);
 
     // Try to find the function: by hash prefix or by name
-    let (fn_name, func) = if trident::ast::navigate::looks_like_hash(&name) {
+    let (fn_name, func) = if crate::ast::navigate::looks_like_hash(&name) {
         if let Some((found_name, found_func)) =
-            trident::ast::navigate::find_function_by_hash(&file, &fn_hashes, &name)
+            crate::ast::navigate::find_function_by_hash(&file, &fn_hashes, &name)
         {
             (found_name, found_func.clone())
-        } else if let Some(found_func) = trident::ast::navigate::find_function(&file, &name) {
+        } else if let Some(found_func) = crate::ast::navigate::find_function(&file, &name) {
             (name.clone(), found_func.clone())
         } else {
             eprintln!("error: no function matching '{}' found", name);
             process::exit(1);
         }
-    } else if let Some(found_func) = trident::ast::navigate::find_function(&file, &name) {
+    } else if let Some(found_func) = crate::ast::navigate::find_function(&file, &name) {
         (name.clone(), found_func.clone())
     } else {
         eprintln!("error: function '{}' not found in '{}'", name, filename);
         eprintln!("\nAvailable functions:");
         for item in &file.items {
-            if let trident::ast::Item::Fn(f) = &item.node {
+            if let crate::ast::Item::Fn(f) = &item.node {
                 if let Some(hash) = fn_hashes.get(&f.name.node) {
                     eprintln!("  {}  {}", hash, f.name.node);
                 }
@@ -54,7 +54,7 @@ pub fn cmd_view(args: ViewArgs) {
         process::exit(1);
     };
 
-    let formatted = trident::ast::display::format_function(&func);
+    let formatted = crate::ast::display::format_function(&func);
 
     if let Some(hash) = fn_hashes.get(&fn_name) {
         if full {
@@ -69,7 +69,7 @@ pub fn cmd_view(args: ViewArgs) {
 
 #[cfg(test)]
 mod tests {
-    use trident::hash::ContentHash;
+    use crate::hash::ContentHash;
 
     // ─── Line-based diff ──────────────────────────────────────────────
 
@@ -241,7 +241,7 @@ mod tests {
         let minutes = remaining / SECS_PER_MIN;
         let seconds = remaining % SECS_PER_MIN;
 
-        let (year, month, day) = trident::deploy::days_to_date(days_since_epoch);
+        let (year, month, day) = crate::deploy::days_to_date(days_since_epoch);
 
         format!(
             "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
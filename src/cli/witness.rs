@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+use std::process;
+
+use clap::Subcommand;
+
+use crate::field::merkle::{AuthPath, Digest, MerkleTree, Poseidon2Hasher};
+use crate::field::{Goldilocks, PrimeField};
+
+#[derive(Subcommand)]
+pub enum WitnessAction {
+    /// Build a Merkle tree from a JSON leaf set and print one leaf's witness
+    Merkle {
+        /// JSON file: {"leaves": [[field, ...], ...]}, a power-of-two count
+        leaves: PathBuf,
+        /// Index of the leaf to authenticate
+        #[arg(long, default_value = "0")]
+        leaf: usize,
+        /// Print a machine-readable JSON witness
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub fn cmd_witness(action: WitnessAction) {
+    match action {
+        WitnessAction::Merkle { leaves, leaf, json } => {
+            let text = match std::fs::read_to_string(&leaves) {
+                Ok(t) => t,
+                Err(e) => {
+                    eprintln!("error: cannot read '{}': {}", leaves.display(), e);
+                    process::exit(1);
+                }
+            };
+            let leaf_set = match parse_leaves(&text) {
+                Ok(l) => l,
+                Err(e) => {
+                    eprintln!("error: {}: {}", leaves.display(), e);
+                    process::exit(1);
+                }
+            };
+            let tree = match MerkleTree::from_leaves(&leaf_set, &Poseidon2Hasher) {
+                Ok(t) => t,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    process::exit(1);
+                }
+            };
+            let Some(path) = tree.auth_path(leaf) else {
+                eprintln!(
+                    "error: leaf {} is out of range (the tree has {} leaves)",
+                    leaf,
+                    tree.num_leaves()
+                );
+                process::exit(1);
+            };
+            if json {
+                print!("{}", witness_json(&tree, &path));
+            } else {
+                print!("{}", witness_text(&tree, &path));
+            }
+        }
+    }
+}
+
+/// Input-file sections: the leaf digest is read with `divine5`, the
+/// siblings are consumed by `merkle.step` from the digest queue.
+fn witness_text(tree: &MerkleTree, path: &AuthPath) -> String {
+    let mut out = format!(
+        "# Merkle witness: leaf {} of {}, depth {} (poseidon2)\n",
+        path.leaf_index,
+        tree.num_leaves(),
+        tree.depth()
+    );
+    out.push_str(&format!("root: {}\n", join(&tree.root())));
+    out.push_str(&format!("index: {}\n", path.node_index));
+    out.push_str(&format!("depth: {}\n", tree.depth()));
+    out.push_str(&format!("divine: {}\n", join(&path.leaf)));
+    let siblings: Vec<String> = path.siblings.iter().map(join).collect();
+    out.push_str(&format!("digests: {}\n", siblings.join(", ")));
+    out
+}
+
+fn witness_json(tree: &MerkleTree, path: &AuthPath) -> String {
+    let siblings: Vec<String> = path
+        .siblings
+        .iter()
+        .map(|d| format!("[{}]", join(d)))
+        .collect();
+    format!(
+        "{{\n  \"hash\": \"poseidon2\",\n  \"root\": [{}],\n  \"depth\": {},\n  \"leaf_index\": {},\n  \"index\": {},\n  \"leaf\": [{}],\n  \"siblings\": [{}]\n}}\n",
+        join(&tree.root()),
+        tree.depth(),
+        path.leaf_index,
+        path.node_index,
+        join(&path.leaf),
+        siblings.join(", ")
+    )
+}
+
+fn join(digest: &Digest) -> String {
+    let items: Vec<String> = digest.iter().map(|e| e.to_u64().to_string()).collect();
+    items.join(", ")
+}
+
+/// Parse the `leaves` array of arrays of field elements.
+fn parse_leaves(json: &str) -> Result<Vec<Vec<Goldilocks>>, String> {
+    let key = json.find("\"leaves\"").ok_or("missing key 'leaves'")?;
+    let rest = &json[key + "\"leaves\"".len()..];
+    let rest = rest
+        .trim_start()
+        .strip_prefix(':')
+        .ok_or("expected ':' after 'leaves'")?
+        .trim_start();
+    if !rest.starts_with('[') {
+        return Err("'leaves' must be an array".to_string());
+    }
+
+    let mut leaves = Vec::new();
+    let mut current: Option<Vec<Goldilocks>> = None;
+    let mut number = String::new();
+    for ch in rest[1..].chars() {
+        if ch.is_ascii_digit() {
+            number.push(ch);
+            continue;
+        }
+        if !number.is_empty() {
+            let leaf = current
+                .as_mut()
+                .ok_or("each leaf must be an array of field elements")?;
+            leaf.push(parse_element(&number, leaves.len())?);
+            number.clear();
+        }
+        match ch {
+            '[' if current.is_none() => current = Some(Vec::new()),
+            ']' => match current.take() {
+                Some(leaf) => leaves.push(leaf),
+                None => return Ok(leaves),
+            },
+            ',' | ' ' | '\t' | '\n' | '\r' => {}
+            other => return Err(format!("unexpected '{}' in leaf {}", other, leaves.len())),
+        }
+    }
+    Err("unterminated 'leaves' array".to_string())
+}
+
+fn parse_element(text: &str, leaf: usize) -> Result<Goldilocks, String> {
+    match text.parse::<u64>() {
+        Ok(v) if (v as u128) < Goldilocks::MODULUS => Ok(Goldilocks::from_u64(v)),
+        _ => Err(format!("leaf {}: '{}' is not a field element", leaf, text)),
+    }
+}
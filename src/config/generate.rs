@@ -0,0 +1,165 @@
+//! Pre-build code generation hooks.
+//!
+//! `[build] generate` in `trident.toml` lists commands that write `.tri`
+//! sources before compilation — constants derived from a circuit spec,
+//! lookup tables, and the like. Each command runs from the project root
+//! without a shell, with `TRIDENT_OUT_DIR` pointing at
+//! `target/generated`. The module resolver searches that directory
+//! before the project tree, so generated modules are imported with a
+//! plain `use`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::project::Project;
+
+/// Output directory for generated sources, relative to the project root.
+pub const GENERATED_DIR: &str = "target/generated";
+
+/// Result of running a project's generators.
+pub struct GenerateResult {
+    /// Directory the generators wrote into.
+    pub out_dir: PathBuf,
+    /// `.tri` files present after generation (sorted).
+    pub files: Vec<PathBuf>,
+}
+
+impl Project {
+    /// Absolute location of `target/generated` for this project.
+    pub fn generated_dir(&self) -> PathBuf {
+        self.root_dir.join(GENERATED_DIR)
+    }
+
+    /// Run every `[build] generate` command in declaration order.
+    ///
+    /// The output directory is emptied first so files from a removed or
+    /// renamed generator cannot shadow project modules. Stops at the first
+    /// command that fails to start or exits non-zero.
+    pub fn run_generators(&self) -> Result<GenerateResult, String> {
+        let out_dir = self.generated_dir();
+        if out_dir.exists() {
+            std::fs::remove_dir_all(&out_dir)
+                .map_err(|e| format!("cannot clear '{}': {}", out_dir.display(), e))?;
+        }
+        std::fs::create_dir_all(&out_dir)
+            .map_err(|e| format!("cannot create '{}': {}", out_dir.display(), e))?;
+
+        for command_line in &self.generators {
+            let mut words = command_line.split_whitespace();
+            let program = words
+                .next()
+                .ok_or_else(|| "empty generator command".to_string())?;
+            let output = Command::new(program)
+                .args(words)
+                .current_dir(&self.root_dir)
+                .env("TRIDENT_OUT_DIR", &out_dir)
+                .env("TRIDENT_PROJECT_DIR", &self.root_dir)
+                .env("TRIDENT_PROJECT_NAME", &self.name)
+                .output()
+                .map_err(|e| format!("cannot run generator `{}`: {}", command_line, e))?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!(
+                    "generator `{}` failed ({}){}{}",
+                    command_line,
+                    output.status,
+                    if stderr.trim().is_empty() { "" } else { ":\n" },
+                    stderr.trim_end()
+                ));
+            }
+        }
+
+        let mut files = Vec::new();
+        collect_tri_files(&out_dir, &mut files)
+            .map_err(|e| format!("cannot read '{}': {}", out_dir.display(), e))?;
+        files.sort();
+        Ok(GenerateResult { out_dir, files })
+    }
+}
+
+/// The `target/generated` directory of the project enclosing `dir`, if
+/// a project exists and the directory has been populated.
+pub(crate) fn find_generated_dir(dir: &Path) -> Option<PathBuf> {
+    let toml_path = Project::find(dir)?;
+    let out_dir = toml_path.parent()?.join(GENERATED_DIR);
+    out_dir.is_dir().then_some(out_dir)
+}
+
+fn collect_tri_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_tri_files(&path, files)?;
+        } else if path.extension().is_some_and(|e| e == "tri") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn project_with(dir: &Path, generate: &str) -> Project {
+        let toml_path = dir.join("trident.toml");
+        fs::write(
+            &toml_path,
+            format!(
+                "[project]\nname = \"gen\"\nversion = \"0.1.0\"\nentry = \"main.tri\"\n\n[build]\ngenerate = {}\n",
+                generate
+            ),
+        )
+        .unwrap();
+        Project::load(&toml_path).unwrap()
+    }
+
+    #[test]
+    fn generators_write_into_target_generated() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("gen.sh"),
+            "mkdir -p \"$TRIDENT_OUT_DIR/spec\"\n\
+             printf 'module spec.consts\\npub const WIDTH: Field = 8\\n' > \"$TRIDENT_OUT_DIR/spec/consts.tri\"\n",
+        )
+        .unwrap();
+        let project = project_with(dir.path(), r#"["sh gen.sh"]"#);
+        assert_eq!(project.generators, vec!["sh gen.sh"]);
+
+        let result = project.run_generators().unwrap();
+        assert_eq!(result.out_dir, dir.path().join("target/generated"));
+        assert_eq!(result.files, vec![result.out_dir.join("spec/consts.tri")]);
+        assert_eq!(
+            find_generated_dir(dir.path()),
+            Some(dir.path().join("target/generated"))
+        );
+    }
+
+    #[test]
+    fn stale_generated_files_are_removed_before_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = project_with(dir.path(), "[]");
+        let stale = project.generated_dir().join("old.tri");
+        fs::create_dir_all(project.generated_dir()).unwrap();
+        fs::write(&stale, "module old\n").unwrap();
+
+        let result = project.run_generators().unwrap();
+        assert!(result.files.is_empty());
+        assert!(!stale.exists());
+    }
+
+    #[test]
+    fn failing_generator_reports_its_stderr() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("fail.sh"),
+            "echo spec missing >&2\nexit 3\n",
+        )
+        .unwrap();
+        let project = project_with(dir.path(), r#"["sh fail.sh"]"#);
+        let err = project.run_generators().err().unwrap();
+        assert!(err.contains("`sh fail.sh` failed"), "{}", err);
+        assert!(err.contains("spec missing"), "{}", err);
+    }
+}
@@ -0,0 +1,319 @@
+//! Intrinsic registry — the builtin functions a target provides.
+//!
+//! Each intrinsic is one record: name, signature, stack effect, per-call
+//! table cost, and purity. Signatures are derived from the target's
+//! parameters (digest width, hash rate, field limbs, extension degree),
+//! costs from the target's cost model. The typechecker registers its
+//! builtins from this table and the `#[pure]` checker reads purity from
+//! it, so adding an intrinsic is one entry here plus one cost row.
+
+use std::collections::BTreeMap;
+
+use crate::cost::model::{create_cost_model, CostModel, TableCost};
+use crate::target::TerrainConfig;
+use crate::types::{EnumTy, Ty};
+
+/// A builtin function as seen by the typechecker and the cost model.
+#[derive(Clone, Debug)]
+pub struct Intrinsic {
+    pub name: String,
+    pub params: Vec<(String, Ty)>,
+    pub return_ty: Ty,
+    /// Stack elements consumed (the width of all parameters).
+    pub pops: u32,
+    /// Stack elements produced (the width of the return type).
+    pub pushes: u32,
+    /// Execution-table cost of one call.
+    pub cost: TableCost,
+    /// False when a call reads input, writes output, or touches RAM or
+    /// the sponge. `#[pure]` functions may only call pure intrinsics.
+    pub pure: bool,
+}
+
+/// All intrinsics of one target, by name.
+#[derive(Clone, Debug)]
+pub struct IntrinsicTable {
+    entries: BTreeMap<String, Intrinsic>,
+}
+
+impl IntrinsicTable {
+    /// Build the table for a target.
+    pub fn for_target(config: &TerrainConfig) -> Self {
+        let mut t = Builder {
+            model: create_cost_model(&config.name),
+            entries: BTreeMap::new(),
+        };
+        let dw = config.digest_width;
+        let hr = config.hash_rate;
+        let fl = config.field_limbs;
+        let xw = config.xfield_width;
+        let digest = Ty::Digest(dw);
+        let xfield = Ty::XField(xw);
+        let field = |name: &str| (name.to_string(), Ty::Field);
+        let u32 = |name: &str| (name.to_string(), Ty::U32);
+        let fields = |prefix: &str, n: u32| -> Vec<(String, Ty)> {
+            (0..n).map(|i| field(&format!("{}{}", prefix, i))).collect()
+        };
+
+        // I/O — parameterized read/write variants up to digest_width
+        t.io("pub_read", vec![], Ty::Field);
+        for n in 2..dw {
+            t.io(
+                &format!("pub_read{}", n),
+                vec![],
+                Ty::Tuple(vec![Ty::Field; n as usize]),
+            );
+        }
+        t.io(&format!("pub_read{}", dw), vec![], digest.clone());
+        t.io("pub_write", vec![field("v")], Ty::Unit);
+        for n in 2..=dw {
+            t.io(&format!("pub_write{}", n), fields("v", n), Ty::Unit);
+        }
+
+        // Non-deterministic input
+        t.io("divine", vec![], Ty::Field);
+        if xw > 0 {
+            t.io(
+                &format!("divine{}", xw),
+                vec![],
+                Ty::Tuple(vec![Ty::Field; xw as usize]),
+            );
+        }
+        t.io(&format!("divine{}", dw), vec![], digest.clone());
+
+        // Assertions
+        t.pure("assert", vec![("cond".into(), Ty::Bool)], Ty::Unit);
+        t.pure("assert_eq", vec![field("a"), field("b")], Ty::Unit);
+        t.pure(
+            "assert_digest",
+            vec![("a".into(), digest.clone()), ("b".into(), digest.clone())],
+            Ty::Unit,
+        );
+        t.pure("debug_assert", vec![("cond".into(), Ty::Bool)], Ty::Unit);
+        t.pure("assume", vec![("cond".into(), Ty::Bool)], Ty::Unit);
+        t.pure("fail", vec![field("code")], Ty::Unit);
+
+        // Field operations
+        t.pure("field_add", vec![field("a"), field("b")], Ty::Field);
+        t.pure("field_mul", vec![field("a"), field("b")], Ty::Field);
+        t.pure("inv", vec![field("a")], Ty::Field);
+        t.pure("inverse", vec![field("a")], Ty::Field);
+        t.pure("neg", vec![field("a")], Ty::Field);
+        t.pure("sub", vec![field("a"), field("b")], Ty::Field);
+        // Addition that says what overflow does: `field_add` wraps mod p,
+        // `_checked_add` returns the sum only if it fit, `_assert_add`
+        // fails unless it did
+        t.pure(
+            "field_checked_add",
+            vec![field("a"), field("b")],
+            Ty::Enum(EnumTy::maybe(Ty::Field)),
+        );
+        t.pure("field_assert_add", vec![field("a"), field("b")], Ty::Field);
+
+        // U32 operations — split returns field_limbs U32s
+        t.pure(
+            "split",
+            vec![field("a")],
+            Ty::Tuple(vec![Ty::U32; fl as usize]),
+        );
+        t.pure("log2", vec![u32("a")], Ty::U32);
+        t.pure("pow", vec![u32("base"), u32("exp")], Ty::U32);
+        t.pure("popcount", vec![u32("a")], Ty::U32);
+        t.pure("u32_wrapping_add", vec![u32("a"), u32("b")], Ty::U32);
+        t.pure(
+            "u32_checked_add",
+            vec![u32("a"), u32("b")],
+            Ty::Enum(EnumTy::maybe(Ty::U32)),
+        );
+        t.pure("u32_assert_add", vec![u32("a"), u32("b")], Ty::U32);
+
+        // Hash operations — parameterized by hash_rate
+        t.pure("hash", fields("x", hr), digest.clone());
+        t.io("sponge_init", vec![], Ty::Unit);
+        t.io("sponge_absorb", fields("x", hr), Ty::Unit);
+        t.io(
+            "sponge_squeeze",
+            vec![],
+            Ty::Array(Box::new(Ty::Field), hr as u64),
+        );
+        t.io("sponge_absorb_mem", vec![field("ptr")], Ty::Unit);
+
+        // Merkle operations — parameterized by digest_width
+        let mut merkle_params = vec![u32("idx")];
+        merkle_params.extend(fields("d", dw));
+        t.io(
+            "merkle_step",
+            merkle_params.clone(),
+            Ty::Tuple(vec![Ty::U32, digest.clone()]),
+        );
+        merkle_params.push(field("ptr"));
+        t.io(
+            "merkle_step_mem",
+            merkle_params,
+            Ty::Tuple(vec![Ty::U32, digest.clone(), Ty::Field]),
+        );
+
+        // RAM
+        t.io("ram_read", vec![field("addr")], Ty::Field);
+        t.io("ram_write", vec![field("addr"), field("val")], Ty::Unit);
+        t.io("ram_read_block", vec![field("addr")], digest.clone());
+        t.io(
+            "ram_write_block",
+            vec![field("addr"), ("d".into(), digest.clone())],
+            Ty::Unit,
+        );
+
+        // Conversion
+        t.pure("as_u32", vec![field("a")], Ty::U32);
+        t.pure("as_field", vec![u32("a")], Ty::Field);
+
+        // Digest — associated functions, called as `Digest::from_fields(...)`;
+        // `a == b` on digests is rewritten to `Digest.eq`
+        let field_array = Ty::Array(Box::new(Ty::Field), dw as u64);
+        t.pure("Digest.from_fields", fields("f", dw), digest.clone());
+        t.pure(
+            "Digest.from_array",
+            vec![("a".into(), field_array.clone())],
+            digest.clone(),
+        );
+        t.pure(
+            "Digest.to_array",
+            vec![("d".into(), digest.clone())],
+            field_array,
+        );
+        t.pure(
+            "Digest.eq",
+            vec![("a".into(), digest.clone()), ("b".into(), digest.clone())],
+            Ty::Bool,
+        );
+
+        // XField — only if the target has an extension field
+        if xw > 0 {
+            let params = (0..xw)
+                .map(|i| field(&((b'a' + i as u8) as char).to_string()))
+                .collect();
+            t.pure("xfield", params, xfield.clone());
+            t.pure("xinvert", vec![("a".into(), xfield.clone())], xfield.clone());
+            let dot_step = Ty::Tuple(vec![xfield.clone(), Ty::Field]);
+            t.io(
+                "xx_dot_step",
+                vec![("a".into(), xfield.clone()), field("ptr")],
+                dot_step.clone(),
+            );
+            t.io(
+                "xb_dot_step",
+                vec![("a".into(), xfield.clone()), field("ptr")],
+                dot_step,
+            );
+        }
+
+        // Recursive verification — claim encoding fits one hash absorption
+        if hr >= dw + 2 {
+            t.pure(
+                "encode_claim",
+                vec![
+                    ("program_digest".into(), digest.clone()),
+                    field("num_inputs"),
+                    field("num_outputs"),
+                ],
+                digest.clone(),
+            );
+        }
+
+        // Proof-stream items — divined and absorbed into the Fiat-Shamir sponge
+        t.io("proof_item", vec![], Ty::Field);
+        t.io("proof_item_digest", vec![], digest);
+        if xw > 0 {
+            t.io("proof_item_xfe", vec![], xfield);
+        }
+
+        IntrinsicTable { entries: t.entries }
+    }
+
+    /// Look up an intrinsic by name.
+    pub fn get(&self, name: &str) -> Option<&Intrinsic> {
+        self.entries.get(name)
+    }
+
+    /// All intrinsics, in name order.
+    pub fn iter(&self) -> impl Iterator<Item = &Intrinsic> {
+        self.entries.values()
+    }
+
+    /// Whether `name` is an intrinsic with side effects.
+    pub fn is_io(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|i| !i.pure)
+    }
+}
+
+/// Collects entries, pricing each with the target's cost model.
+struct Builder {
+    model: &'static dyn CostModel,
+    entries: BTreeMap<String, Intrinsic>,
+}
+
+impl Builder {
+    fn pure(&mut self, name: &str, params: Vec<(String, Ty)>, return_ty: Ty) {
+        self.add(name, params, return_ty, true);
+    }
+
+    fn io(&mut self, name: &str, params: Vec<(String, Ty)>, return_ty: Ty) {
+        self.add(name, params, return_ty, false);
+    }
+
+    fn add(&mut self, name: &str, params: Vec<(String, Ty)>, return_ty: Ty, pure: bool) {
+        let pops = params.iter().map(|(_, ty)| ty.width()).sum();
+        let intrinsic = Intrinsic {
+            name: name.to_string(),
+            pushes: return_ty.width(),
+            pops,
+            cost: self.model.builtin_cost(name),
+            params,
+            return_ty,
+            pure,
+        };
+        self.entries.insert(name.to_string(), intrinsic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triton_signatures_follow_the_target_parameters() {
+        let table = IntrinsicTable::for_target(&TerrainConfig::triton());
+        let hash = table.get("hash").unwrap();
+        assert_eq!(hash.pops, 10);
+        assert_eq!(hash.pushes, 5);
+        assert!(table.get("divine3").is_some());
+        assert!(table.get("divine5").is_some());
+        assert!(table.get("pub_read6").is_none());
+        assert_eq!(table.get("split").unwrap().pushes, 2);
+    }
+
+    #[test]
+    fn purity_separates_io_from_computation() {
+        let table = IntrinsicTable::for_target(&TerrainConfig::triton());
+        for name in ["pub_read", "pub_write5", "divine", "ram_write", "sponge_init"] {
+            assert!(table.is_io(name), "{} should have side effects", name);
+        }
+        for name in ["hash", "split", "assert_eq", "xinvert", "encode_claim"] {
+            assert!(!table.is_io(name), "{} should be pure", name);
+        }
+        assert!(!table.is_io("not_an_intrinsic"));
+    }
+
+    #[test]
+    fn every_costed_name_is_an_intrinsic() {
+        let config = TerrainConfig::triton();
+        let table = IntrinsicTable::for_target(&config);
+        for (name, _) in create_cost_model(&config.name).intrinsic_costs() {
+            assert!(table.get(name).is_some(), "cost row for unknown '{}'", name);
+        }
+        assert_eq!(
+            table.get("pub_read").unwrap().cost,
+            crate::cost::cost_builtin("triton", "pub_read")
+        );
+    }
+}
@@ -1,4 +1,8 @@
+pub mod generate;
+pub mod intrinsics;
+pub mod policy;
 pub mod project;
 pub mod resolve;
 pub mod scaffold;
+pub mod schema;
 pub mod target;
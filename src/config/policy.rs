@@ -0,0 +1,210 @@
+//! Project policy from `trident-policy.toml`.
+//!
+//! A policy file next to `trident.toml` states rules a build must meet
+//! beyond type checking, so an organization can hold every program to
+//! them without reviewing each one by hand:
+//!
+//! ```toml
+//! [build]
+//! max_padded_height = 65536
+//! forbidden_intrinsics = ["divine", "divine5"]
+//! pure_modules = ["math", "crypto.*"]
+//!
+//! [deploy]
+//! verdict = "safe"
+//! ```
+//!
+//! `trident build` and `trident deploy` enforce the `[build]` rules after
+//! compiling; `trident deploy` also verifies the program when `[deploy]
+//! verdict` is set. The rules are evaluated in `api::policy`.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::target::parse_string_array;
+use crate::diagnostic::{render_diagnostics, Diagnostic};
+use crate::span::Span;
+
+/// File name of the policy, in the project root.
+pub const POLICY_FILE: &str = "trident-policy.toml";
+
+/// Rules a project's builds must meet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Policy {
+    /// Directory of the policy file; only modules beneath it are checked
+    /// against the source rules.
+    pub root_dir: PathBuf,
+    /// Largest padded height the program may have.
+    pub max_padded_height: Option<u64>,
+    /// Intrinsics (`divine`, `ram_write`, ...) the project's modules may
+    /// not call, directly or through the functions that wrap them.
+    pub forbidden_intrinsics: Vec<String>,
+    /// Modules whose functions must all be `#[pure]`; `name.*` matches
+    /// the modules beneath `name`.
+    pub pure_modules: Vec<String>,
+    /// Verdict verification must reach before deploying; only `safe`.
+    pub deploy_verdict: Option<String>,
+}
+
+impl Policy {
+    /// The policy of the project rooted at `root_dir`, if it has one.
+    pub fn find(root_dir: &Path) -> Option<PathBuf> {
+        let path = root_dir.join(POLICY_FILE);
+        path.is_file().then_some(path)
+    }
+
+    /// Load and check a policy file, rendering every problem found.
+    pub fn load(path: &Path) -> Result<Policy, Diagnostic> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Diagnostic::error(
+                format!("cannot read '{}': {}", path.display(), e),
+                Span::dummy(),
+            )
+        })?;
+        let root_dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        Policy::parse(&content, root_dir).map_err(|errors| {
+            render_diagnostics(&errors, &path.to_string_lossy(), &content);
+            errors.into_iter().next().expect("parse reports an error")
+        })
+    }
+
+    /// Parse policy text; every malformed line is reported with its span.
+    pub fn parse(content: &str, root_dir: PathBuf) -> Result<Policy, Vec<Diagnostic>> {
+        let mut policy = Policy {
+            root_dir,
+            ..Policy::default()
+        };
+        let mut errors = Vec::new();
+        let mut section = String::new();
+        let mut offset = 0usize;
+        for line in content.split_inclusive('\n') {
+            let start = offset + (line.len() - line.trim_start().len());
+            offset += line.len();
+            let trimmed = line.split('#').next().unwrap_or_default().trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let span = Span::new(0, start as u32, (start + trimmed.len()) as u32);
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                section = trimmed[1..trimmed.len() - 1].trim().to_string();
+                if section != "build" && section != "deploy" {
+                    errors.push(
+                        Diagnostic::error(format!("unknown policy section `[{}]`", section), span)
+                            .with_help("policy sections are `[build]` and `[deploy]`".to_string()),
+                    );
+                }
+                continue;
+            }
+            let Some((key, value)) = trimmed.split_once('=') else {
+                errors.push(Diagnostic::error(
+                    "expected `key = value`".to_string(),
+                    span,
+                ));
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let strings = || {
+                (value.starts_with('[') && value.ends_with(']')).then(|| parse_string_array(value))
+            };
+            let invalid = match (section.as_str(), key) {
+                ("build", "max_padded_height") => match value.parse::<u64>() {
+                    Ok(height) if height > 0 => {
+                        policy.max_padded_height = Some(height);
+                        None
+                    }
+                    _ => Some("`max_padded_height` must be a positive integer"),
+                },
+                ("build", "forbidden_intrinsics") => match strings() {
+                    Some(names) => {
+                        policy.forbidden_intrinsics = names;
+                        None
+                    }
+                    None => Some("`forbidden_intrinsics` must be an array of strings"),
+                },
+                ("build", "pure_modules") => match strings() {
+                    Some(modules) => {
+                        policy.pure_modules = modules;
+                        None
+                    }
+                    None => Some("`pure_modules` must be an array of strings"),
+                },
+                ("deploy", "verdict") => match value.trim_matches('"') {
+                    "safe" => {
+                        policy.deploy_verdict = Some("safe".to_string());
+                        None
+                    }
+                    _ => Some("`verdict` must be \"safe\""),
+                },
+                ("build" | "deploy", _) => {
+                    errors.push(Diagnostic::error(
+                        format!("unknown key `{}` in [{}]", key, section),
+                        span,
+                    ));
+                    continue;
+                }
+                _ => continue,
+            };
+            if let Some(message) = invalid {
+                errors.push(Diagnostic::error(message.to_string(), span));
+            }
+        }
+        if errors.is_empty() {
+            Ok(policy)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Whether `module` is one of `pure_modules`.
+    pub fn requires_pure(&self, module: &str) -> bool {
+        self.pure_modules
+            .iter()
+            .any(|pattern| match pattern.strip_suffix(".*") {
+                Some(prefix) => module
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('.')),
+                None => pattern == module,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_rule() {
+        let policy = Policy::parse(
+            "# house rules\n[build]\nmax_padded_height = 4096\nforbidden_intrinsics = [\"divine\", \"divine5\"]\npure_modules = [\"math\", \"crypto.*\"]\n\n[deploy]\nverdict = \"safe\"\n",
+            PathBuf::from("/p"),
+        )
+        .unwrap();
+        assert_eq!(policy.max_padded_height, Some(4096));
+        assert_eq!(policy.forbidden_intrinsics, vec!["divine", "divine5"]);
+        assert_eq!(policy.deploy_verdict.as_deref(), Some("safe"));
+        assert!(policy.requires_pure("math"));
+        assert!(policy.requires_pure("crypto.hash"));
+        assert!(!policy.requires_pure("crypto"));
+        assert!(!policy.requires_pure("mathx"));
+    }
+
+    #[test]
+    fn reports_malformed_rules_with_spans() {
+        let content = "[build]\nmax_padded_height = 0\npure_module = [\"m\"]\n[deploy]\nverdict = \"unknown\"\n[lint]\n";
+        let errors = Policy::parse(content, PathBuf::new()).unwrap_err();
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "`max_padded_height` must be a positive integer",
+                "unknown key `pure_module` in [build]",
+                "`verdict` must be \"safe\"",
+                "unknown policy section `[lint]`",
+            ]
+        );
+        let span = errors[1].span;
+        assert_eq!(
+            &content[span.start as usize..span.end as usize],
+            "pure_module = [\"m\"]"
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+use crate::config::schema;
 use crate::config::target::parse_string_array;
-use crate::diagnostic::Diagnostic;
+use crate::diagnostic::{render_diagnostics, Diagnostic, Severity};
 use crate::manifest::Manifest;
 use crate::span::Span;
 
@@ -35,6 +36,28 @@ pub fn validate_project_name(name: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// `[verify]` budgets; `None` keeps the verifier's default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerifySettings {
+    pub samples: Option<usize>,
+    pub bmc_depth: Option<usize>,
+    pub time_budget_ms: Option<u64>,
+    pub seed: Option<u64>,
+}
+
+/// `[ci]` stages and gates for `trident ci`; `None` leaves a gate off.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CiSettings {
+    /// Stages to run, in order; `None` runs all of them.
+    pub stages: Option<Vec<String>>,
+    /// Cost report (`trident build --save-costs`) the cost stage compares
+    /// against, relative to the project root.
+    pub cost_baseline: Option<String>,
+    pub max_padded_height: Option<u64>,
+    /// Allowed growth of the padded height over the baseline, in percent.
+    pub max_cost_increase: Option<u64>,
+}
+
 /// Minimal project configuration from trident.toml.
 #[derive(Clone, Debug)]
 pub struct Project {
@@ -49,6 +72,19 @@ pub struct Project {
     pub targets: BTreeMap<String, Vec<String>>,
     /// Parsed [dependencies] section.
     pub dependencies: Manifest,
+    /// Pre-build generator commands from `[build] generate`.
+    pub generators: Vec<String>,
+    /// Size-generic instance cap from `[build] max_instances`.
+    pub max_instances: Option<usize>,
+    /// Verification budgets from `[verify]`.
+    pub verify: VerifySettings,
+    /// `trident ci` configuration from `[ci]`.
+    pub ci: CiSettings,
+    /// Plugin passes from `[passes]`: name → path relative to the root.
+    pub passes: BTreeMap<String, String>,
+    /// On-chain commitments the program reads, in input order, from
+    /// `[deploy] commitments`.
+    pub commitments: Vec<String>,
 }
 
 impl Project {
@@ -63,12 +99,29 @@ impl Project {
 
         let root_dir = toml_path.parent().unwrap_or(Path::new(".")).to_path_buf();
 
+        let diagnostics = schema::validate(&content, &root_dir);
+        if !diagnostics.is_empty() {
+            render_diagnostics(&diagnostics, &toml_path.to_string_lossy(), &content);
+        }
+        if let Some(err) = diagnostics
+            .into_iter()
+            .find(|d| d.severity == Severity::Error)
+        {
+            return Err(err);
+        }
+
         // Section-aware minimal TOML parsing
         let mut name = String::new();
         let mut version = String::new();
         let mut entry = String::new();
         let mut vm_target: Option<String> = None;
         let mut targets: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut generators: Vec<String> = Vec::new();
+        let mut max_instances: Option<usize> = None;
+        let mut verify = VerifySettings::default();
+        let mut ci = CiSettings::default();
+        let mut passes: BTreeMap<String, String> = BTreeMap::new();
+        let mut commitments: Vec<String> = Vec::new();
         let mut current_section = String::new();
 
         for line in content.lines() {
@@ -100,6 +153,36 @@ impl Project {
                         let flags = parse_string_array(value);
                         targets.insert(target_name.to_string(), flags);
                     }
+                } else if current_section == "build" && key == "generate" {
+                    generators = parse_string_array(value);
+                } else if current_section == "build" && key == "max_instances" {
+                    let value = value.split('#').next().unwrap_or("").trim();
+                    max_instances = value.parse().ok();
+                } else if current_section == "deploy" && key == "commitments" {
+                    commitments = parse_string_array(value);
+                } else if current_section == "verify" {
+                    let value = value.split('#').next().unwrap_or("").trim();
+                    match key {
+                        "samples" => verify.samples = value.parse().ok(),
+                        "bmc_depth" => verify.bmc_depth = value.parse().ok(),
+                        "time_budget_ms" => verify.time_budget_ms = value.parse().ok(),
+                        "seed" => verify.seed = value.parse().ok(),
+                        _ => {}
+                    }
+                } else if current_section == "ci" {
+                    let value = value.split('#').next().unwrap_or("").trim();
+                    match key {
+                        "stages" => ci.stages = Some(parse_string_array(value)),
+                        "cost_baseline" => {
+                            ci.cost_baseline = Some(value.trim_matches('"').to_string())
+                        }
+                        "max_padded_height" => ci.max_padded_height = value.parse().ok(),
+                        "max_cost_increase" => ci.max_cost_increase = value.parse().ok(),
+                        _ => {}
+                    }
+                } else if current_section == "passes" {
+                    let value = value.split('#').next().unwrap_or("").trim();
+                    passes.insert(key.to_string(), value.trim_matches('"').to_string());
                 }
             }
         }
@@ -132,6 +215,12 @@ impl Project {
             target: vm_target,
             targets,
             dependencies,
+            generators,
+            max_instances,
+            verify,
+            ci,
+            passes,
+            commitments,
         })
     }
 
@@ -210,6 +299,50 @@ flags = ["release"]
         assert_eq!(release_flags, &vec!["release".to_string()]);
     }
 
+    #[test]
+    fn test_load_project_with_verify_budgets() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join("trident.toml");
+        fs::write(
+            &toml_path,
+            "[project]\nname = \"app\"\n\n[verify]\nsamples = 500\ntime_budget_ms = 250 # per function\nseed = 7\n",
+        )
+        .unwrap();
+
+        let project = Project::load(&toml_path).unwrap();
+        assert_eq!(
+            project.verify,
+            VerifySettings {
+                samples: Some(500),
+                bmc_depth: None,
+                time_budget_ms: Some(250),
+                seed: Some(7),
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_project_with_ci_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join("trident.toml");
+        fs::write(
+            &toml_path,
+            "[project]\nname = \"app\"\n\n[ci]\nstages = [\"check\", \"cost\"]\nmax_cost_increase = 5 # percent\n",
+        )
+        .unwrap();
+
+        let project = Project::load(&toml_path).unwrap();
+        assert_eq!(
+            project.ci,
+            CiSettings {
+                stages: Some(vec!["check".to_string(), "cost".to_string()]),
+                cost_baseline: None,
+                max_padded_height: None,
+                max_cost_increase: Some(5),
+            }
+        );
+    }
+
     #[test]
     fn test_parse_string_array() {
         assert_eq!(
@@ -253,6 +386,22 @@ flags = ["release"]
         assert!(validate_project_name(&exactly_128).is_ok());
     }
 
+    #[test]
+    fn load_rejects_schema_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join("trident.toml");
+        fs::write(
+            &toml_path,
+            r#"[project]
+name = "app"
+version = "one"
+"#,
+        )
+        .unwrap();
+        let err = Project::load(&toml_path).unwrap_err();
+        assert_eq!(err.message, "invalid version `one`");
+    }
+
     #[test]
     fn load_rejects_invalid_project_name() {
         let dir = tempfile::tempdir().unwrap();
@@ -15,6 +15,75 @@ pub(crate) struct ModuleInfo {
     pub(crate) source: String,
     /// Modules this module depends on (from `use` statements).
     pub(crate) dependencies: Vec<String>,
+    /// The other files of a multi-file program, each with the byte
+    /// offset where it starts in `source`; empty for a single file.
+    pub(crate) parts: Vec<(PathBuf, usize)>,
+}
+
+impl ModuleInfo {
+    /// Byte range of the file at `path` in `source`, if it is one of the
+    /// module's files.
+    pub(crate) fn file_range(&self, path: &Path) -> Option<std::ops::Range<usize>> {
+        let i = self.file_index(path)?;
+        let start = if i == 0 { 0 } else { self.parts[i - 1].1 };
+        Some(self.file_at(start).1)
+    }
+
+    /// The file the byte at `offset` in `source` belongs to, with its
+    /// byte range.
+    pub(crate) fn file_at(&self, offset: usize) -> (&Path, std::ops::Range<usize>) {
+        file_at(&self.file_path, &self.parts, self.source.len(), offset)
+    }
+
+    /// The module with the text of its file at `path` replaced by `text`,
+    /// as an editor buffer has it; unchanged if `path` is not one of its
+    /// files.
+    pub(crate) fn with_file_source(&self, path: &Path, text: &str) -> ModuleInfo {
+        let mut module = self.clone();
+        let Some(range) = self.file_range(path) else {
+            return module;
+        };
+        module.source = format!(
+            "{}{}{}",
+            &self.source[..range.start],
+            text,
+            &self.source[range.end..]
+        );
+        for (_, offset) in &mut module.parts {
+            if *offset > range.start {
+                *offset = *offset - range.len() + text.len();
+            }
+        }
+        module
+    }
+
+    /// Index of `path` among the module's files: 0 for `file_path`, then
+    /// the parts in order.
+    fn file_index(&self, path: &Path) -> Option<usize> {
+        let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        let target = canonical(path);
+        std::iter::once(&self.file_path)
+            .chain(self.parts.iter().map(|(p, _)| p))
+            .position(|p| canonical(p) == target)
+    }
+}
+
+/// The file of a module joined from `file_path` and `parts`, `len`
+/// bytes in all, that the byte at `offset` belongs to, with its range.
+pub(crate) fn file_at<'a>(
+    file_path: &'a Path,
+    parts: &'a [(PathBuf, usize)],
+    len: usize,
+    offset: usize,
+) -> (&'a Path, std::ops::Range<usize>) {
+    let i = parts.partition_point(|(_, start)| *start <= offset);
+    let (path, start) = match i {
+        0 => (file_path, 0),
+        _ => (parts[i - 1].0.as_path(), parts[i - 1].1),
+    };
+    // Each file ends one byte (the joining newline) before the next.
+    let end = parts.get(i).map_or(len, |(_, next)| next - 1);
+    (path, start..end)
 }
 
 /// Resolve all modules reachable from an entry point.
@@ -30,12 +99,15 @@ pub(crate) fn resolve_modules(entry_path: &Path) -> Result<Vec<ModuleInfo>, Vec<
 
 /// Resolve modules with additional dependency search directories.
 /// Used when a project has locked dependencies cached on disk.
+/// With `legacy_paths` off, old module paths no longer resolve.
 pub(crate) fn resolve_modules_with_deps(
     entry_path: &Path,
     dep_dirs: Vec<PathBuf>,
+    legacy_paths: bool,
 ) -> Result<Vec<ModuleInfo>, Vec<Diagnostic>> {
     let mut resolver = ModuleResolver::new(entry_path)?;
     resolver.dep_dirs = dep_dirs;
+    resolver.legacy_paths = legacy_paths;
     resolver.discover_all()?;
     resolver.topological_sort()
 }
@@ -100,6 +172,27 @@ pub(crate) fn find_stdlib_dir() -> Option<PathBuf> {
     find_lib_dir("TRIDENT_STDLIB", "std")
 }
 
+/// Find the VM intrinsic library directory.
+pub(crate) fn find_vm_dir() -> Option<PathBuf> {
+    vm_dir_beside(find_stdlib_dir().as_deref())
+}
+
+/// The `vm/` directory beside `stdlib_dir`, else `vm/` in the current
+/// working directory.
+fn vm_dir_beside(stdlib_dir: Option<&Path>) -> Option<PathBuf> {
+    if let Some(parent) = stdlib_dir.and_then(Path::parent) {
+        let vm_dir = parent.join("vm");
+        if vm_dir.is_dir() {
+            return Some(vm_dir);
+        }
+    }
+    let cwd_vm = PathBuf::from("vm");
+    if cwd_vm.is_dir() {
+        return Some(cwd_vm);
+    }
+    None
+}
+
 /// Find the OS library directory.
 /// Also checks the legacy `TRIDENT_EXTLIB` environment variable.
 pub(crate) fn find_os_dir() -> Option<PathBuf> {
@@ -116,6 +209,10 @@ pub(crate) fn find_os_dir() -> Option<PathBuf> {
     None
 }
 
+/// The cfg flag that turns off the resolution of legacy module paths,
+/// ahead of the removal of the fallback.
+pub const NO_LEGACY_PATHS_FLAG: &str = "no_legacy_paths";
+
 /// Legacy flat-path fallback map for backward compatibility.
 /// Maps old module names to their new layered locations.
 fn legacy_stdlib_fallback(name: &str) -> Option<&'static str> {
@@ -165,5 +262,25 @@ fn legacy_stdlib_fallback(name: &str) -> Option<&'static str> {
     }
 }
 
+/// The current layered path of a module named by a legacy path, e.g.
+/// `std.hash` → `vm.crypto.hash` or `ext.neptune.kernel` →
+/// `os.neptune.kernel`; `None` for paths that are not legacy. The last
+/// segment, and with it the module's alias, never changes.
+pub(crate) fn modern_module_path(name: &str) -> Option<String> {
+    if let Some(new_name) = legacy_stdlib_fallback(name) {
+        return Some(new_name.to_string());
+    }
+    let parts: Vec<&str> = name.split('.').collect();
+    match parts.iter().position(|&p| p == "ext") {
+        Some(0) if parts.len() >= 3 => Some(format!("os.{}", parts[1..].join("."))),
+        Some(i) if i > 0 && i + 1 < parts.len() => Some(format!(
+            "os.{}.{}",
+            parts[..i].join("."),
+            parts[i + 1..].join(".")
+        )),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests;
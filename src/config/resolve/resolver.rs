@@ -11,6 +11,10 @@ pub(crate) struct ModuleResolver {
     pub(crate) os_dir: Option<PathBuf>,
     /// Additional directories to search for modules (from locked dependencies).
     pub(crate) dep_dirs: Vec<PathBuf>,
+    /// Output of the project's `[build] generate` hooks (if populated).
+    pub(crate) generated_dir: Option<PathBuf>,
+    /// Whether legacy module paths are mapped to their new location.
+    pub(crate) legacy_paths: bool,
     /// All discovered modules by name.
     pub(crate) modules: BTreeMap<String, ModuleInfo>,
     /// Queue of modules to process.
@@ -32,24 +36,44 @@ impl ModuleResolver {
         })?;
 
         // Quick-parse the entry file to get its name and dependencies
-        let (name, deps) = scan_module_header(&source);
+        let (name, mut deps) = scan_module_header(&source);
         let entry_name = name.unwrap_or_else(|| "main".to_string());
 
+        // Files beside the entry declaring the same program are parts of
+        // it, joined after the entry in file-name order.
+        let mut source = source;
+        let mut parts = Vec::new();
+        if program_name(&source) == Some(entry_name.as_str()) {
+            for (path, part) in program_parts(entry_path, &root_dir, &entry_name) {
+                source.push('\n');
+                parts.push((path, source.len()));
+                source.push_str(&part);
+                for dep in scan_module_header(&part).1 {
+                    if !deps.contains(&dep) {
+                        deps.push(dep);
+                    }
+                }
+            }
+        }
+
         let info = ModuleInfo {
             name: entry_name.clone(),
             file_path: entry_path.to_path_buf(),
             source,
             dependencies: deps.clone(),
+            parts,
         };
 
         let mut modules = BTreeMap::new();
         modules.insert(entry_name.clone(), info);
 
         Ok(Self {
+            generated_dir: crate::config::generate::find_generated_dir(&root_dir),
             root_dir,
             stdlib_dir: find_stdlib_dir(),
             os_dir: find_os_dir(),
             dep_dirs: Vec::new(),
+            legacy_paths: true,
             modules,
             queue: deps,
             diagnostics: Vec::new(),
@@ -57,23 +81,8 @@ impl ModuleResolver {
     }
 
     /// Find the VM intrinsic library directory.
-    /// Mirrors stdlib_dir search but looks for `vm/` sibling to `std/`.
     pub(crate) fn find_vm_dir(&self) -> Option<PathBuf> {
-        // If we have a stdlib_dir, look for vm/ as a sibling
-        if let Some(ref stdlib_dir) = self.stdlib_dir {
-            if let Some(parent) = stdlib_dir.parent() {
-                let vm_dir = parent.join("vm");
-                if vm_dir.is_dir() {
-                    return Some(vm_dir);
-                }
-            }
-        }
-        // Fallback: vm/ in current working directory
-        let cwd_vm = PathBuf::from("vm");
-        if cwd_vm.is_dir() {
-            return Some(cwd_vm);
-        }
-        None
+        vm_dir_beside(self.stdlib_dir.as_deref())
     }
 
     pub(crate) fn discover_all(&mut self) -> Result<(), Vec<Diagnostic>> {
@@ -87,6 +96,16 @@ impl ModuleResolver {
             let source = match std::fs::read_to_string(&file_path) {
                 Ok(s) => s,
                 Err(e) => {
+                    let help = match modern_module_path(&module_name) {
+                        Some(new_name) if !self.legacy_paths => format!(
+                            "'{}' moved to '{}'; `trident fix` updates the imports",
+                            module_name, new_name
+                        ),
+                        _ => format!(
+                            "create the file '{}' or check the module name in the `use` statement",
+                            file_path.display()
+                        ),
+                    };
                     self.diagnostics.push(
                         Diagnostic::error(
                             format!(
@@ -97,10 +116,7 @@ impl ModuleResolver {
                             ),
                             Span::dummy(),
                         )
-                        .with_help(format!(
-                            "create the file '{}' or check the module name in the `use` statement",
-                            file_path.display()
-                        )),
+                        .with_help(help),
                     );
                     continue;
                 }
@@ -122,6 +138,7 @@ impl ModuleResolver {
                     file_path,
                     source,
                     dependencies: deps,
+                    parts: Vec::new(),
                 },
             );
         }
@@ -139,6 +156,7 @@ impl ModuleResolver {
     /// "vm.core.field"       → vm_dir/core/field.tri     (VM intrinsics)
     /// "std.crypto.sha256"   → stdlib_dir/crypto/sha256.tri (real libraries)
     /// "os.neptune.kernel"   → os_dir/neptune/kernel.tri (OS-specific)
+    /// "spec.consts"         → target/generated/spec/consts.tri (generated)
     /// "crypto.sponge"       → root_dir/crypto/sponge.tri (local)
     ///
     /// Legacy backward compatibility still supported:
@@ -146,6 +164,7 @@ impl ModuleResolver {
     /// "ext.neptune.kernel"  → os_dir/neptune/kernel.tri
     /// "std.crypto.hash"     → vm_dir/crypto/hash.tri (intrinsics moved)
     /// "std.hash"            → vm_dir/crypto/hash.tri (flat → layered → vm)
+    /// unless `legacy_paths` is off.
     pub(crate) fn resolve_path(&self, module_name: &str) -> PathBuf {
         // Validate: reject path traversal components
         let raw_parts: Vec<&str> = module_name.split('.').collect();
@@ -194,7 +213,11 @@ impl ModuleResolver {
         }
 
         // Legacy: <os>.ext.<module> → os/<os>/<module>.tri
-        if let Some(ext_pos) = raw_parts.iter().position(|&p| p == "ext") {
+        if let Some(ext_pos) = raw_parts
+            .iter()
+            .position(|&p| p == "ext")
+            .filter(|_| self.legacy_paths)
+        {
             if ext_pos > 0 && ext_pos + 1 < raw_parts.len() {
                 if let Some(ref os_dir) = self.os_dir {
                     let os_name = &raw_parts[..ext_pos].join("/");
@@ -210,7 +233,7 @@ impl ModuleResolver {
 
         // Legacy: ext.<os>.<module> → os/<os>/<module>.tri
         if let Some(rest) = module_name.strip_prefix("ext.") {
-            if let Some(ref os_dir) = self.os_dir {
+            if let Some(os_dir) = self.os_dir.as_ref().filter(|_| self.legacy_paths) {
                 let parts: Vec<&str> = rest.split('.').collect();
                 let mut path = os_dir.clone();
                 for part in &parts {
@@ -234,7 +257,9 @@ impl ModuleResolver {
                     return candidate;
                 }
                 // Legacy fallback: try remapped path for old flat names
-                if let Some(new_name) = legacy_stdlib_fallback(module_name) {
+                if let Some(new_name) =
+                    legacy_stdlib_fallback(module_name).filter(|_| self.legacy_paths)
+                {
                     return self.resolve_path(new_name);
                 }
                 // Return the original candidate (will fail with good error)
@@ -262,6 +287,18 @@ impl ModuleResolver {
             }
         }
 
+        // Generated sources shadow the project tree
+        if let Some(ref generated_dir) = self.generated_dir {
+            let mut path = generated_dir.clone();
+            for part in module_name.split('.') {
+                path = path.join(part);
+            }
+            let candidate = path.with_extension("tri");
+            if candidate.exists() {
+                return candidate;
+            }
+        }
+
         // Default: local project path
         let parts: Vec<&str> = module_name.split('.').collect();
         let mut path = self.root_dir.clone();
@@ -341,6 +378,41 @@ impl ModuleResolver {
     }
 }
 
+/// The name a file declares with `program X`, if it is a program.
+fn program_name(source: &str) -> Option<&str> {
+    let header = source
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with("//"))?;
+    header.strip_prefix("program ").map(str::trim)
+}
+
+/// The `.tri` files in `dir`, other than `entry_path`, that declare
+/// `program name`, with their sources, sorted by path.
+fn program_parts(entry_path: &Path, dir: &Path, name: &str) -> Vec<(PathBuf, String)> {
+    let Ok(entries) = std::fs::read_dir(if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    }) else {
+        return Vec::new();
+    };
+    let mut parts: Vec<(PathBuf, String)> = entries
+        .flatten()
+        .map(|entry| dir.join(entry.file_name()))
+        .filter(|path| {
+            path.extension().is_some_and(|e| e == "tri")
+                && path.file_name() != entry_path.file_name()
+        })
+        .filter_map(|path| {
+            let source = std::fs::read_to_string(&path).ok()?;
+            (program_name(&source) == Some(name)).then_some((path, source))
+        })
+        .collect();
+    parts.sort();
+    parts
+}
+
 /// Quick scan of a source file to extract module name and `use` dependencies.
 /// Does not fully parse — just looks for `program X` / `module X` and `use Y` lines.
 pub(crate) fn scan_module_header(source: &str) -> (Option<String>, Vec<String>) {
@@ -103,3 +103,111 @@ fn test_path_traversal_rejected() {
 
     let _ = std::fs::remove_file(&entry);
 }
+
+#[test]
+fn generated_modules_resolve_before_project_tree() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("trident.toml"), "[project]\nname = \"g\"\n").unwrap();
+    let entry = dir.path().join("main.tri");
+    std::fs::write(&entry, "program g\nuse spec.consts\nfn main() {}\n").unwrap();
+    std::fs::create_dir_all(dir.path().join("spec")).unwrap();
+    std::fs::write(dir.path().join("spec/consts.tri"), "module spec.consts\n").unwrap();
+    let generated = dir.path().join("target/generated/spec");
+    std::fs::create_dir_all(&generated).unwrap();
+    std::fs::write(generated.join("consts.tri"), "module spec.consts\n").unwrap();
+
+    let modules = resolve_modules(&entry).unwrap();
+    let consts = modules.iter().find(|m| m.name == "spec.consts").unwrap();
+    assert_eq!(consts.file_path, generated.join("consts.tri"));
+}
+
+#[test]
+fn program_files_in_the_entry_directory_are_merged() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = dir.path().join("main.tri");
+    std::fs::write(&entry, "program coin\nuse std.hash\nfn main() {}\n").unwrap();
+    std::fs::write(
+        dir.path().join("ops.tri"),
+        "// Token operations\nprogram coin\nuse helper\nfn op() {}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("events.tri"),
+        "program coin\nevent Paid { amount: Field }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("other.tri"),
+        "program other\nfn main() {}\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("helper.tri"), "module helper\n").unwrap();
+
+    let modules = resolve_modules(&entry).unwrap();
+    let coin = modules.iter().find(|m| m.name == "coin").unwrap();
+    let parts: Vec<_> = coin
+        .parts
+        .iter()
+        .map(|(p, _)| p.file_name().unwrap())
+        .collect();
+    assert_eq!(parts, ["events.tri", "ops.tri"]);
+    assert!(coin.source.starts_with("program coin\nuse std.hash\n"));
+    assert_eq!(
+        &coin.source[coin.parts[1].1..],
+        "// Token operations\nprogram coin\nuse helper\nfn op() {}\n"
+    );
+    assert!(coin.dependencies.contains(&"helper".to_string()));
+    assert!(modules.iter().any(|m| m.name == "helper"));
+
+    // An editor buffer replaces one file and shifts the ones after it.
+    let ops = dir.path().join("ops.tri");
+    let events = dir.path().join("events.tri");
+    let edited = coin.with_file_source(&events, "program coin\n");
+    assert_eq!(
+        edited.file_range(&ops),
+        Some(edited.parts[1].1..edited.source.len())
+    );
+    assert_eq!(
+        &edited.source[edited.file_range(&events).unwrap()],
+        "program coin\n"
+    );
+    assert_eq!(edited.file_at(edited.parts[1].1 + 3).0, ops.as_path());
+    assert_eq!(edited.file_range(&dir.path().join("other.tri")), None);
+}
+
+#[test]
+fn test_modern_module_path() {
+    assert_eq!(
+        modern_module_path("std.hash").as_deref(),
+        Some("vm.crypto.hash")
+    );
+    assert_eq!(
+        modern_module_path("ext.neptune.kernel").as_deref(),
+        Some("os.neptune.kernel")
+    );
+    assert_eq!(
+        modern_module_path("neptune.ext.utxo").as_deref(),
+        Some("os.neptune.utxo")
+    );
+    assert_eq!(modern_module_path("vm.crypto.hash"), None);
+    assert_eq!(modern_module_path("std.crypto.merkle"), None);
+    assert_eq!(modern_module_path("ext"), None);
+}
+
+#[test]
+fn legacy_paths_resolve_only_when_enabled() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = dir.path().join("main.tri");
+    std::fs::write(&entry, "program p\nuse std.hash\nfn main() {}\n").unwrap();
+
+    let modules = resolve_modules_with_deps(&entry, Vec::new(), true).unwrap();
+    let hash = modules.iter().find(|m| m.name == "std.hash").unwrap();
+    assert!(hash.file_path.ends_with("vm/crypto/hash.tri"));
+
+    let diags = resolve_modules_with_deps(&entry, Vec::new(), false).unwrap_err();
+    assert!(diags[0].message.contains("cannot find module 'std.hash'"));
+    assert!(diags[0]
+        .help
+        .as_deref()
+        .is_some_and(|h| h.contains("moved to 'vm.crypto.hash'")));
+}
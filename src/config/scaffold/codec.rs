@@ -0,0 +1,172 @@
+/// Host encoders for program structs.
+///
+/// `trident generate --codec` turns every struct of a Trident file into
+/// a Rust struct implementing `trident::field::codec::BFieldCodec`. Each
+/// Trident type maps to the host type with the same encoding, and fields
+/// keep their declaration order, so a host value encodes to exactly the
+/// elements a program reads to build the struct field by field.
+///
+/// Every event becomes a Rust struct with its tag and version, and a
+/// decoder for what `reveal` writes to public output. Tags are the ones
+/// the default (debug) build assigns.
+use std::collections::BTreeSet;
+
+use crate::ast::desugar::is_item_active;
+use crate::ast::{EventDef, File, Item, StructDef, Type};
+
+/// Generate a Rust module with one encodable struct per Trident struct
+/// and one decodable struct per event.
+///
+/// Fails when a field type has no fixed host shape — an array sized by
+/// a generic parameter, or a tuple wider than four elements.
+pub fn generate_codec(file: &File) -> Result<String, String> {
+    let cfg_flags = BTreeSet::from(["debug".to_string()]);
+    let events: Vec<&EventDef> = file
+        .items
+        .iter()
+        .filter(|item| is_item_active(&item.node, &cfg_flags))
+        .filter_map(|item| match &item.node {
+            Item::Event(e) => Some(e),
+            _ => None,
+        })
+        .collect();
+    let mut out = format!(
+        "// Generated by `trident generate --codec` from `{}`.\n\
+         // Field order and encoding follow `trident::field::codec`.\n\n\
+         use trident::field::codec::{{decode_item, encode_item, BFieldCodec, Reader}};\n\
+         use trident::field::Goldilocks;\n",
+        file.name.node
+    );
+    if !events.is_empty() {
+        out.push_str("use trident::field::PrimeField;\n");
+    }
+    for item in &file.items {
+        if let Item::Struct(s) = &item.node {
+            out.push('\n');
+            out.push_str(&codec_struct(s)?);
+        }
+    }
+    for (index, event) in events.iter().enumerate() {
+        out.push('\n');
+        out.push_str(&codec_event(event, event.tag(index as u64)));
+    }
+    Ok(out)
+}
+
+/// A host struct for an event, with the tag and version it is emitted
+/// under and a decoder for its `reveal`: the tag, then the fields from
+/// last to first.
+fn codec_event(e: &EventDef, tag: u64) -> String {
+    let name = &e.name.node;
+    let mut out = format!("/// Event `{}`, version {}.\n", name, e.version);
+    if let Some(note) = &e.deprecated {
+        if note.node.is_empty() {
+            out.push_str("#[deprecated]\n");
+        } else {
+            out.push_str(&format!("#[deprecated(note = {:?})]\n", note.node));
+        }
+    }
+    out.push_str("#[derive(Clone, Debug, PartialEq, Eq)]\n");
+    out.push_str(&format!("pub struct {} {{\n", name));
+    for field in &e.fields {
+        out.push_str(&format!("    pub {}: Goldilocks,\n", field.name.node));
+    }
+    out.push_str("}\n\n");
+
+    if e.deprecated.is_some() {
+        out.push_str("#[allow(deprecated)]\n");
+    }
+    out.push_str(&format!("impl {} {{\n", name));
+    out.push_str(&format!("    pub const TAG: u64 = {};\n", tag));
+    out.push_str(&format!("    pub const VERSION: u64 = {};\n\n", e.version));
+    out.push_str("    /// Read the event from the public output `reveal` wrote.\n");
+    out.push_str("    pub fn decode_revealed(reader: &mut Reader<'_>) -> Result<Self, String> {\n");
+    out.push_str("        let tag = reader.next_element()?.to_u64();\n");
+    out.push_str("        if tag != Self::TAG {\n");
+    out.push_str(&format!(
+        "            return Err(format!(\"expected {} tag {{}}, found {{}}\", Self::TAG, tag));\n",
+        name
+    ));
+    out.push_str("        }\n");
+    for field in e.fields.iter().rev() {
+        out.push_str(&format!(
+            "        let {} = reader.next_element()?;\n",
+            field.name.node
+        ));
+    }
+    let fields: Vec<&str> = e.fields.iter().map(|f| f.name.node.as_str()).collect();
+    out.push_str(&format!("        Ok(Self {{ {} }})\n", fields.join(", ")));
+    out.push_str("    }\n");
+    out.push_str("}\n");
+    out
+}
+
+fn codec_struct(s: &StructDef) -> Result<String, String> {
+    let name = &s.name.node;
+    let mut fields = Vec::with_capacity(s.fields.len());
+    for field in &s.fields {
+        let ty = host_type(&field.ty.node)
+            .map_err(|e| format!("struct {}, field {}: {}", name, field.name.node, e))?;
+        fields.push((field.name.node.as_str(), ty));
+    }
+
+    let mut out = String::from("#[derive(Clone, Debug, PartialEq, Eq)]\n");
+    out.push_str(&format!("pub struct {} {{\n", name));
+    for (field, ty) in &fields {
+        out.push_str(&format!("    pub {}: {},\n", field, ty));
+    }
+    out.push_str("}\n\n");
+
+    let lengths: Vec<String> = fields
+        .iter()
+        .map(|(_, ty)| format!("<{} as BFieldCodec>::static_length()?", ty))
+        .collect();
+    let length = if lengths.is_empty() {
+        "0".to_string()
+    } else {
+        lengths.join(" + ")
+    };
+    out.push_str(&format!("impl BFieldCodec for {} {{\n", name));
+    out.push_str("    fn static_length() -> Option<usize> {\n");
+    out.push_str(&format!("        Some({})\n", length));
+    out.push_str("    }\n\n");
+    out.push_str("    fn encode_into(&self, out: &mut Vec<Goldilocks>) {\n");
+    for (field, _) in &fields {
+        out.push_str(&format!("        encode_item(&self.{}, out);\n", field));
+    }
+    out.push_str("    }\n\n");
+    out.push_str("    fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String> {\n");
+    out.push_str("        Ok(Self {\n");
+    for (field, _) in &fields {
+        out.push_str(&format!("            {}: decode_item(reader)?,\n", field));
+    }
+    out.push_str("        })\n");
+    out.push_str("    }\n");
+    out.push_str("}\n");
+    Ok(out)
+}
+
+/// The Rust type whose encoding matches a Trident type.
+fn host_type(ty: &Type) -> Result<String, String> {
+    Ok(match ty {
+        Type::Field => "Goldilocks".to_string(),
+        Type::XField => "[Goldilocks; 3]".to_string(),
+        Type::Bool => "bool".to_string(),
+        Type::U32 => "u32".to_string(),
+        Type::Digest => "[Goldilocks; 5]".to_string(),
+        Type::Array(inner, size) => {
+            let len = size
+                .as_literal()
+                .ok_or_else(|| format!("array size `{}` is not a literal", size))?;
+            format!("[{}; {}]", host_type(inner)?, len)
+        }
+        Type::Tuple(items) if (2..=4).contains(&items.len()) => {
+            let items = items.iter().map(host_type).collect::<Result<Vec<_>, _>>()?;
+            format!("({})", items.join(", "))
+        }
+        Type::Tuple(items) => {
+            return Err(format!("a {}-element tuple has no host codec", items.len()))
+        }
+        Type::Named(path) => path.0.last().cloned().unwrap_or_default(),
+    })
+}
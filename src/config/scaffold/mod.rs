@@ -5,10 +5,12 @@
 /// `trident generate` fills in scaffolding: TODO comments, placeholder
 /// return values, and assertion stubs that mirror the spec annotations.
 use crate::ast::display::{
-    format_ast_type as format_type, format_const_value as format_const_expr,
+    format_ast_type as format_type, format_const_value as format_const_expr, format_enum_variant,
 };
 use crate::ast::{File, FnDef, Item, Param, Type};
 
+pub mod codec;
+
 // ---------------------------------------------------------------------------
 // Public API
 // ---------------------------------------------------------------------------
@@ -77,6 +79,16 @@ pub fn generate_scaffold(file: &File) -> String {
                 }
                 out.push_str("}\n");
             }
+            Item::Enum(e) => {
+                if e.is_pub {
+                    out.push_str("pub ");
+                }
+                out.push_str(&format!("enum {} {{\n", e.name.node));
+                for variant in &e.variants {
+                    out.push_str(&format!("    {},\n", format_enum_variant(variant)));
+                }
+                out.push_str("}\n");
+            }
             Item::Event(e) => {
                 out.push_str(&format!("event {} {{\n", e.name.node));
                 for field in &e.fields {
@@ -248,47 +260,6 @@ pub fn default_value(ty: &Type) -> String {
     }
 }
 
-// ---------------------------------------------------------------------------
-// Variable extraction from spec expressions
-// ---------------------------------------------------------------------------
-
-/// Parse a spec expression to extract referenced variable names.
-///
-/// This performs a lightweight scan of the expression text: any sequence of
-/// `[a-zA-Z_][a-zA-Z0-9_]*` that is not a keyword or literal is considered
-/// a variable reference.
-pub fn extract_variables(spec: &str) -> Vec<String> {
-    let keywords: &[&str] = &[
-        "true", "false", "old", "result", "let", "if", "else", "for", "fn", "return",
-    ];
-    let mut vars = Vec::new();
-    let mut seen = std::collections::BTreeSet::new();
-    let mut chars = spec.char_indices().peekable();
-
-    while let Some(&(i, c)) = chars.peek() {
-        if c.is_ascii_alphabetic() || c == '_' {
-            let start = i;
-            while let Some(&(_, nc)) = chars.peek() {
-                if nc.is_ascii_alphanumeric() || nc == '_' {
-                    chars.next();
-                } else {
-                    break;
-                }
-            }
-            let end = chars.peek().map(|&(idx, _)| idx).unwrap_or(spec.len());
-            let word = &spec[start..end];
-            if !keywords.contains(&word) && !seen.contains(word) {
-                seen.insert(word.to_string());
-                vars.push(word.to_string());
-            }
-        } else {
-            chars.next();
-        }
-    }
-
-    vars
-}
-
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -120,33 +120,6 @@ fn test_default_value_xfield() {
     assert_eq!(default_value(&Type::XField), "0");
 }
 
-#[test]
-fn test_extract_variables_simple() {
-    let vars = extract_variables("amount > 0");
-    assert_eq!(vars, vec!["amount"]);
-}
-
-#[test]
-fn test_extract_variables_expression() {
-    let vars = extract_variables("balance == old_balance + amount");
-    assert!(vars.contains(&"balance".to_string()));
-    assert!(vars.contains(&"old_balance".to_string()));
-    assert!(vars.contains(&"amount".to_string()));
-}
-
-#[test]
-fn test_extract_variables_skips_keywords() {
-    let vars = extract_variables("result == true");
-    // "result" and "true" are keywords, so should be excluded
-    assert!(vars.is_empty());
-}
-
-#[test]
-fn test_extract_variables_with_underscore() {
-    let vars = extract_variables("_private_var > 0");
-    assert_eq!(vars, vec!["_private_var"]);
-}
-
 #[test]
 fn test_scaffold_tuple_return() {
     let source = r#"program test
@@ -239,3 +212,70 @@ fn main() {
 
     assert!(scaffold.starts_with("program my_app\n"));
 }
+
+#[test]
+fn test_codec_maps_struct_fields_in_declaration_order() {
+    let source = r#"program test
+
+struct Note {
+    owner: Digest,
+    amount: U32,
+    blinding: XField,
+    flags: [Bool; 2],
+}
+
+fn main() {
+}
+"#;
+    let file = parse_source_silent(source, "test.tri").unwrap();
+    let code = codec::generate_codec(&file).unwrap();
+
+    assert!(code.contains("pub struct Note {"));
+    assert!(code.contains("    pub owner: [Goldilocks; 5],\n    pub amount: u32,\n    pub blinding: [Goldilocks; 3],\n    pub flags: [bool; 2],\n"));
+    assert!(code.contains("impl BFieldCodec for Note {"));
+    let owner = code.find("encode_item(&self.owner, out);").unwrap();
+    let flags = code.find("encode_item(&self.flags, out);").unwrap();
+    assert!(owner < flags);
+    assert!(code.contains("flags: decode_item(reader)?,"));
+}
+
+#[test]
+fn test_codec_decodes_events_with_tag_and_version() {
+    let source = r#"program test
+
+event Mint { amount: Field }
+
+#[version(2)]
+#[deprecated("use Pay")]
+event Transfer { from: Field, amount: Field }
+
+fn main() {
+}
+"#;
+    let file = parse_source_silent(source, "test.tri").unwrap();
+    let code = codec::generate_codec(&file).unwrap();
+
+    assert!(code
+        .contains("impl Mint {\n    pub const TAG: u64 = 0;\n    pub const VERSION: u64 = 1;\n"));
+    assert!(code.contains("#[deprecated(note = \"use Pay\")]\n#[derive(Clone, Debug, PartialEq, Eq)]\npub struct Transfer {\n    pub from: Goldilocks,\n    pub amount: Goldilocks,\n}\n"));
+    assert!(
+        code.contains("    pub const TAG: u64 = 4294967297;\n    pub const VERSION: u64 = 2;\n")
+    );
+    // `reveal` writes the fields last to first.
+    let amount = code.find("let amount = reader.next_element()?;").unwrap();
+    let from = code.find("let from = reader.next_element()?;").unwrap();
+    assert!(amount < from);
+}
+
+#[test]
+fn test_codec_rejects_arrays_without_literal_size() {
+    let source = r#"module test
+
+pub struct Buf {
+    data: [Field; N],
+}
+"#;
+    let file = parse_source_silent(source, "test.tri").unwrap();
+    let err = codec::generate_codec(&file).unwrap_err();
+    assert!(err.contains("struct Buf, field data"));
+}
@@ -0,0 +1,553 @@
+//! Schema validation for `trident.toml`.
+//!
+//! The loaders in `project` and `manifest` are deliberately permissive
+//! line scanners. This pass reports everything they would silently
+//! ignore — unknown sections and keys, mistyped values, malformed profile
+//! names, missing paths — as diagnostics with byte spans into the TOML
+//! source, so they render like any other compiler diagnostic.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::diagnostic::Diagnostic;
+use crate::span::Span;
+
+// ─── Schema ────────────────────────────────────────────────────────
+
+/// Expected shape of a manifest value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Quoted string.
+    Str,
+    /// Array of quoted identifiers: `["a", "b"]`.
+    IdentArray,
+    /// Array of non-empty quoted strings: `["gen/consts.sh", "make tri"]`.
+    StrArray,
+    /// Quoted `MAJOR.MINOR.PATCH` version, optional `-pre` suffix.
+    Version,
+    /// Quoted path relative to the project root; warns when missing.
+    RelPath,
+    /// Unquoted positive integer: `max_instances = 64`.
+    Count,
+    /// Unquoted unsigned 64-bit integer, zero included: `seed = 42`.
+    Seed,
+    /// Unquoted whole-number percentage, zero included, without a `%`
+    /// sign: `max_cost_increase = 5`.
+    Percent,
+}
+
+/// A known `[section]` and the keys it accepts.
+pub struct SectionSchema {
+    /// Section name; a trailing `.*` matches any single-segment suffix.
+    pub name: &'static str,
+    pub keys: &'static [(&'static str, ValueKind)],
+}
+
+/// All sections `trident.toml` understands, except `[dependencies]` and
+/// `[passes]`, whose keys are user-chosen and validated separately.
+pub const SECTIONS: &[SectionSchema] = &[
+    SectionSchema {
+        name: "project",
+        keys: &[
+            ("name", ValueKind::Str),
+            ("version", ValueKind::Version),
+            ("entry", ValueKind::RelPath),
+            ("target", ValueKind::Str),
+        ],
+    },
+    SectionSchema {
+        name: "targets.*",
+        keys: &[("flags", ValueKind::IdentArray)],
+    },
+    SectionSchema {
+        name: "build",
+        keys: &[
+            ("generate", ValueKind::StrArray),
+            ("max_instances", ValueKind::Count),
+        ],
+    },
+    SectionSchema {
+        name: "deploy",
+        keys: &[("commitments", ValueKind::IdentArray)],
+    },
+    SectionSchema {
+        name: "verify",
+        keys: &[
+            ("samples", ValueKind::Count),
+            ("bmc_depth", ValueKind::Count),
+            ("time_budget_ms", ValueKind::Count),
+            ("seed", ValueKind::Seed),
+        ],
+    },
+    SectionSchema {
+        name: "ci",
+        keys: &[
+            ("stages", ValueKind::IdentArray),
+            ("cost_baseline", ValueKind::RelPath),
+            ("max_padded_height", ValueKind::Count),
+            ("max_cost_increase", ValueKind::Percent),
+        ],
+    },
+];
+
+const DEPENDENCIES: &str = "dependencies";
+const DEPENDENCY_KEYS: &[&str] = &["path", "name", "registry"];
+const PASSES: &str = "passes";
+
+// ─── Validation ────────────────────────────────────────────────────
+
+/// Validate `trident.toml` content against the schema.
+///
+/// `root_dir` resolves relative paths. Unknown sections, unknown keys, and
+/// missing paths are warnings; malformed lines and mistyped values are
+/// errors.
+pub fn validate(content: &str, root_dir: &Path) -> Vec<Diagnostic> {
+    let mut v = Validator {
+        root_dir,
+        diagnostics: Vec::new(),
+        section: None,
+        seen_sections: BTreeSet::new(),
+        seen_keys: BTreeSet::new(),
+    };
+    let mut offset = 0usize;
+    for line in content.split_inclusive('\n') {
+        v.line(line.trim_end_matches(['\n', '\r']), offset);
+        offset += line.len();
+    }
+    v.diagnostics
+}
+
+/// Which section the validator is in.
+enum Section {
+    Known(&'static SectionSchema),
+    Dependencies,
+    /// `[passes]`: pass name = plugin path.
+    Passes,
+    /// Unknown section (already reported); its keys are not checked.
+    Unknown,
+}
+
+struct Validator<'a> {
+    root_dir: &'a Path,
+    diagnostics: Vec<Diagnostic>,
+    section: Option<Section>,
+    seen_sections: BTreeSet<String>,
+    seen_keys: BTreeSet<String>,
+}
+
+impl Validator<'_> {
+    fn line(&mut self, line: &str, offset: usize) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            return;
+        }
+        let start = offset + (line.len() - line.trim_start().len());
+        let span = span_of(start, trimmed.len());
+
+        if trimmed.starts_with('[') {
+            self.header(trimmed, start);
+            return;
+        }
+
+        let Some((key, value)) = trimmed.split_once('=') else {
+            self.diagnostics.push(
+                Diagnostic::error("expected `key = value`".to_string(), span)
+                    .with_help("section headers are written `[name]`".to_string()),
+            );
+            return;
+        };
+        let key_span = span_of(start, key.trim_end().len());
+        let key = key.trim().trim_matches('"');
+        let value_start = start + trimmed.len() - value.trim_start().len();
+        let value = strip_comment(value.trim());
+        let value_span = span_of(value_start, value.len());
+
+        if !self.seen_keys.insert(key.to_string()) {
+            self.diagnostics.push(Diagnostic::error(
+                format!("duplicate key `{}`", key),
+                key_span,
+            ));
+            return;
+        }
+
+        match self.section {
+            None => self.diagnostics.push(
+                Diagnostic::warning(format!("key `{}` outside of any section", key), key_span)
+                    .with_help("move it under `[project]` or another section".to_string()),
+            ),
+            Some(Section::Unknown) => {}
+            Some(Section::Dependencies) => self.dependency(key, value, value_span),
+            Some(Section::Passes) => self.value(key, value, ValueKind::RelPath, value_span),
+            Some(Section::Known(schema)) => match schema.keys.iter().find(|(k, _)| *k == key) {
+                Some((_, kind)) => self.value(key, value, *kind, value_span),
+                None => {
+                    let known: Vec<&str> = schema.keys.iter().map(|(k, _)| *k).collect();
+                    let mut diag = Diagnostic::warning(
+                        format!("unknown key `{}` in [{}]", key, schema.name),
+                        key_span,
+                    )
+                    .with_note(format!("expected one of: {}", known.join(", ")));
+                    if let Some(s) = suggest(key, &known) {
+                        diag = diag.with_help(format!("did you mean `{}`?", s));
+                    }
+                    self.diagnostics.push(diag);
+                }
+            },
+        }
+    }
+
+    fn header(&mut self, trimmed: &str, start: usize) {
+        let span = span_of(start, trimmed.len());
+        let header = strip_comment(trimmed);
+        if !header.ends_with(']') || header.starts_with("[[") {
+            self.diagnostics.push(Diagnostic::error(
+                format!("malformed section header `{}`", header),
+                span,
+            ));
+            self.section = Some(Section::Unknown);
+            return;
+        }
+        let name = header[1..header.len() - 1].trim();
+        self.seen_keys.clear();
+        if !self.seen_sections.insert(name.to_string()) {
+            self.diagnostics.push(Diagnostic::error(
+                format!("duplicate section `[{}]`", name),
+                span,
+            ));
+        }
+
+        if name == DEPENDENCIES {
+            self.section = Some(Section::Dependencies);
+            return;
+        }
+        if name == PASSES {
+            self.section = Some(Section::Passes);
+            return;
+        }
+        if let Some(profile) = name.strip_prefix("targets.") {
+            if !is_ident(profile) {
+                self.diagnostics.push(
+                    Diagnostic::error(format!("invalid profile name `{}`", profile), span)
+                        .with_help("profile names use letters, digits, `_` and `-`".to_string()),
+                );
+            }
+        }
+        match SECTIONS.iter().find(|s| section_matches(s.name, name)) {
+            Some(schema) => self.section = Some(Section::Known(schema)),
+            None => {
+                let mut known: Vec<&str> = SECTIONS.iter().map(|s| s.name).collect();
+                known.extend([DEPENDENCIES, PASSES]);
+                let mut diag = Diagnostic::warning(format!("unknown section `[{}]`", name), span)
+                    .with_note(format!("known sections: {}", known.join(", ")));
+                if let Some(s) = suggest(name, &known) {
+                    diag = diag.with_help(format!("did you mean `[{}]`?", s));
+                }
+                self.diagnostics.push(diag);
+                self.section = Some(Section::Unknown);
+            }
+        }
+    }
+
+    fn value(&mut self, key: &str, value: &str, kind: ValueKind, span: Span) {
+        match kind {
+            ValueKind::Str => {
+                self.quoted(key, value, span);
+            }
+            ValueKind::Version => {
+                if let Some(s) = self.quoted(key, value, span) {
+                    if !is_version(s) {
+                        self.diagnostics.push(
+                            Diagnostic::error(format!("invalid version `{}`", s), span)
+                                .with_help("use MAJOR.MINOR.PATCH, e.g. \"0.1.0\"".to_string()),
+                        );
+                    }
+                }
+            }
+            ValueKind::RelPath => {
+                if let Some(s) = self.quoted(key, value, span) {
+                    self.path_exists(key, s, span);
+                }
+            }
+            ValueKind::IdentArray => match parse_array(value) {
+                Some(items) => {
+                    for item in items {
+                        if !is_ident(item) {
+                            self.diagnostics.push(Diagnostic::error(
+                                format!("invalid flag `{}` in `{}`", item, key),
+                                span,
+                            ));
+                        }
+                    }
+                }
+                None => self.not_array(key, span),
+            },
+            ValueKind::StrArray => match parse_array(value) {
+                Some(items) => {
+                    if items.iter().any(|item| item.trim().is_empty()) {
+                        self.diagnostics
+                            .push(Diagnostic::error(format!("empty entry in `{}`", key), span));
+                    }
+                }
+                None => self.not_array(key, span),
+            },
+            ValueKind::Count => {
+                if !value.parse::<usize>().is_ok_and(|n| n > 0) {
+                    self.diagnostics.push(
+                        Diagnostic::error(format!("`{}` must be a positive integer", key), span)
+                            .with_help(format!("write `{} = 64`", key)),
+                    );
+                }
+            }
+            ValueKind::Seed => {
+                if value.parse::<u64>().is_err() {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            format!("`{}` must be an unsigned 64-bit integer", key),
+                            span,
+                        )
+                        .with_help(format!("write `{} = 42`", key)),
+                    );
+                }
+            }
+            ValueKind::Percent => {
+                if value.parse::<u64>().is_err() {
+                    let help = match value.strip_suffix('%') {
+                        Some(n) if n.trim().parse::<u64>().is_ok() => {
+                            format!("drop the `%`: `{} = {}`", key, n.trim())
+                        }
+                        _ => format!("write `{} = 5` for 5%", key),
+                    };
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            format!("`{}` must be a whole-number percentage", key),
+                            span,
+                        )
+                        .with_help(help),
+                    );
+                }
+            }
+        }
+    }
+
+    fn not_array(&mut self, key: &str, span: Span) {
+        self.diagnostics.push(
+            Diagnostic::error(format!("`{}` must be an array of strings", key), span)
+                .with_help(format!("write `{} = [\"a\", \"b\"]`", key)),
+        );
+    }
+
+    /// Check that `value` is a quoted string, returning its contents.
+    fn quoted<'v>(&mut self, key: &str, value: &'v str, span: Span) -> Option<&'v str> {
+        let inner = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .filter(|v| !v.contains('"'));
+        if inner.is_none() {
+            self.diagnostics.push(
+                Diagnostic::error(format!("`{}` must be a string", key), span)
+                    .with_help(format!("write `{} = \"...\"`", key)),
+            );
+        }
+        inner
+    }
+
+    fn path_exists(&mut self, key: &str, path: &str, span: Span) {
+        if !self.root_dir.join(path).exists() {
+            self.diagnostics.push(Diagnostic::warning(
+                format!("`{}` path '{}' does not exist", key, path),
+                span,
+            ));
+        }
+    }
+
+    fn dependency(&mut self, name: &str, value: &str, span: Span) {
+        if let Some(inner) = value.strip_prefix('{').and_then(|v| v.strip_suffix('}')) {
+            let mut fields = BTreeSet::new();
+            for pair in inner.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let Some((k, v)) = pair.split_once('=') else {
+                    self.diagnostics.push(Diagnostic::error(
+                        format!("dependency `{}`: expected `key = \"value\"`", name),
+                        span,
+                    ));
+                    continue;
+                };
+                let k = k.trim().trim_matches('"');
+                fields.insert(k.to_string());
+                if !DEPENDENCY_KEYS.contains(&k) {
+                    let mut diag = Diagnostic::warning(
+                        format!("dependency `{}`: unknown key `{}`", name, k),
+                        span,
+                    );
+                    if let Some(s) = suggest(k, DEPENDENCY_KEYS) {
+                        diag = diag.with_help(format!("did you mean `{}`?", s));
+                    }
+                    self.diagnostics.push(diag);
+                    continue;
+                }
+                let Some(v) = self.quoted(k, v.trim(), span) else {
+                    continue;
+                };
+                match k {
+                    "path" => self.path_exists("path", v, span),
+                    "registry" => match interpolate_env(v) {
+                        Ok(url) if url.starts_with("http://") || url.starts_with("https://") => {}
+                        Ok(url) => self.diagnostics.push(Diagnostic::error(
+                            format!(
+                                "dependency `{}`: registry '{}' is not an http(s) URL",
+                                name, url
+                            ),
+                            span,
+                        )),
+                        Err(e) => self.diagnostics.push(Diagnostic::error(
+                            format!("dependency `{}`: {}", name, e),
+                            span,
+                        )),
+                    },
+                    _ => {}
+                }
+            }
+            if !fields.contains("path") && !fields.contains("name") {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!("dependency `{}` needs `path` or `name`", name),
+                        span,
+                    )
+                    .with_help(
+                        "write `{ path = \"...\" }` or `{ name = \"...\", registry = \"...\" }`"
+                            .to_string(),
+                    ),
+                );
+            }
+            return;
+        }
+
+        let hash = value.trim_matches('"');
+        if crate::hash::ContentHash::from_hex(hash).is_none() {
+            self.diagnostics.push(Diagnostic::error(
+                format!(
+                    "dependency `{}` must be a 64-character hex content hash or an inline table",
+                    name
+                ),
+                span,
+            ));
+        }
+    }
+}
+
+// ─── Environment Interpolation ─────────────────────────────────────
+
+/// Expand `${VAR}` references from the process environment.
+pub fn interpolate_env(s: &str) -> Result<String, String> {
+    interpolate_with(s, |name| std::env::var(name).ok())
+}
+
+/// Expand `${VAR}` references using `lookup`.
+pub fn interpolate_with(
+    s: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(pos) = rest.find("${") {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("unterminated `${{` in '{}'", s))?;
+        let name = &after[..end];
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("invalid environment variable name '{}'", name));
+        }
+        let value =
+            lookup(name).ok_or_else(|| format!("environment variable '{}' is not set", name))?;
+        out.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+// ─── Helpers ───────────────────────────────────────────────────────
+
+fn span_of(start: usize, len: usize) -> Span {
+    Span::new(0, start as u32, (start + len) as u32)
+}
+
+/// Drop a trailing `# comment` that is not inside a string.
+fn strip_comment(value: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return value[..i].trim_end(),
+            _ => {}
+        }
+    }
+    value
+}
+
+fn section_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => name
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_prefix('.'))
+            .is_some_and(|rest| !rest.is_empty() && !rest.contains('.')),
+        None => pattern == name,
+    }
+}
+
+fn is_ident(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn is_version(s: &str) -> bool {
+    let core = s.split_once('-').map(|(c, _)| c).unwrap_or(s);
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Parse `["a", "b"]` into its items; `None` unless every item is quoted.
+fn parse_array(value: &str) -> Option<Vec<&str>> {
+    let inner = value.strip_prefix('[')?.strip_suffix(']')?.trim();
+    if inner.is_empty() {
+        return Some(Vec::new());
+    }
+    inner
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| item.strip_prefix('"').and_then(|i| i.strip_suffix('"')))
+        .collect()
+}
+
+/// Closest candidate within edit distance 2, for "did you mean" hints.
+fn suggest<'c>(word: &str, candidates: &[&'c str]) -> Option<&'c str> {
+    candidates
+        .iter()
+        .map(|c| (edit_distance(word, c), *c))
+        .filter(|(d, _)| *d <= 2)
+        .min()
+        .map(|(_, c)| c)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests;
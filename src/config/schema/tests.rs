@@ -0,0 +1,204 @@
+use super::*;
+use crate::diagnostic::Severity;
+
+fn check(content: &str) -> Vec<Diagnostic> {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("main.tri"), "program p\nfn main() { }\n").unwrap();
+    validate(content, dir.path())
+}
+
+fn errors(diags: &[Diagnostic]) -> Vec<&str> {
+    diags
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| d.message.as_str())
+        .collect()
+}
+
+#[test]
+fn valid_manifest_has_no_diagnostics() {
+    let diags = check(
+        "# comment\n[project]\nname = \"app\"\nversion = \"0.1.0\"\nentry = \"main.tri\"\n\n[targets.debug]\nflags = [\"debug\", \"verbose\"]\n\n[dependencies]\nmerkle = { name = \"merkle\", registry = \"https://atlas.example\" }\n",
+    );
+    assert!(diags.is_empty(), "{:?}", diags);
+}
+
+#[test]
+fn unknown_key_warns_with_span_and_suggestion() {
+    let content = "[project]\nname = \"app\"\nverison = \"0.1.0\"\n";
+    let diags = check(content);
+    assert_eq!(diags.len(), 1);
+    let d = &diags[0];
+    assert_eq!(d.severity, Severity::Warning);
+    assert_eq!(
+        &content[d.span.start as usize..d.span.end as usize],
+        "verison"
+    );
+    assert_eq!(d.help.as_deref(), Some("did you mean `version`?"));
+}
+
+#[test]
+fn unknown_section_warns() {
+    let diags = check("[project]\nname = \"app\"\n[dependences]\nx = \"y\"\n");
+    assert_eq!(diags.len(), 1);
+    assert!(diags[0].message.contains("unknown section `[dependences]`"));
+    assert_eq!(
+        diags[0].help.as_deref(),
+        Some("did you mean `[dependencies]`?")
+    );
+}
+
+#[test]
+fn mistyped_values_are_errors() {
+    let diags =
+        check("[project]\nname = app\nversion = \"1.0\"\n[targets.release]\nflags = \"release\"\n");
+    assert_eq!(
+        errors(&diags),
+        vec![
+            "`name` must be a string",
+            "invalid version `1.0`",
+            "`flags` must be an array of strings",
+        ]
+    );
+}
+
+#[test]
+fn malformed_profile_and_duplicates_are_errors() {
+    let diags = check(
+        "[project]\nname = \"a\"\nname = \"b\"\n[targets.my profile]\nflags = []\n[project]\n",
+    );
+    assert_eq!(
+        errors(&diags),
+        vec![
+            "duplicate key `name`",
+            "invalid profile name `my profile`",
+            "duplicate section `[project]`",
+        ]
+    );
+}
+
+#[test]
+fn missing_paths_warn() {
+    let diags = check(
+        "[project]\nname = \"a\"\nentry = \"src/missing.tri\"\n[dependencies]\nlocal = { path = \"../nowhere\" }\n",
+    );
+    assert_eq!(diags.len(), 2);
+    assert!(diags.iter().all(|d| d.severity == Severity::Warning));
+}
+
+#[test]
+fn dependency_shapes_are_checked() {
+    let diags = check(
+        "[dependencies]\nbad_hash = \"abc\"\nempty = { registry = \"https://x\" }\nftp = { name = \"n\", registry = \"ftp://x\" }\n",
+    );
+    assert_eq!(errors(&diags).len(), 3);
+}
+
+#[test]
+fn interpolation_expands_variables() {
+    let lookup = |name: &str| (name == "HOST").then(|| "atlas.example".to_string());
+    assert_eq!(
+        interpolate_with("https://${HOST}/api", lookup).unwrap(),
+        "https://atlas.example/api"
+    );
+    assert_eq!(interpolate_with("no vars", lookup).unwrap(), "no vars");
+    assert!(interpolate_with("https://${MISSING}", lookup)
+        .unwrap_err()
+        .contains("'MISSING' is not set"));
+    assert!(interpolate_with("https://${HOST", lookup).is_err());
+}
+
+#[test]
+fn trailing_comments_are_ignored() {
+    let diags = check("[project] # main section\nname = \"app\" # the name\n");
+    assert!(diags.is_empty(), "{:?}", diags);
+}
+
+#[test]
+fn build_generate_takes_command_strings() {
+    assert!(
+        check("[build]\ngenerate = [\"sh gen.sh\", \"python3 spec.py --out consts\"]\n").is_empty()
+    );
+    assert_eq!(
+        errors(&check("[build]\ngenerate = \"sh gen.sh\"\n")),
+        vec!["`generate` must be an array of strings"]
+    );
+    assert_eq!(
+        errors(&check("[build]\ngenerate = [\"  \"]\n")),
+        vec!["empty entry in `generate`"]
+    );
+}
+
+#[test]
+fn verify_budgets_are_integers() {
+    assert!(check(
+        "[verify]\nsamples = 500\nbmc_depth = 2000\ntime_budget_ms = 250\nseed = 0 # any u64\n"
+    )
+    .is_empty());
+    assert_eq!(
+        errors(&check("[verify]\nsamples = 0\n")),
+        vec!["`samples` must be a positive integer"]
+    );
+    for bad in ["-1", "\"7\"", "18446744073709551616"] {
+        assert_eq!(
+            errors(&check(&format!("[verify]\nseed = {}\n", bad))),
+            vec!["`seed` must be an unsigned 64-bit integer"]
+        );
+    }
+}
+
+#[test]
+fn ci_stages_and_thresholds() {
+    assert!(check(
+        "[ci]\nstages = [\"check\", \"test\"]\nmax_padded_height = 4096\nmax_cost_increase = 0 # percent\n"
+    )
+    .is_empty());
+    assert_eq!(
+        errors(&check("[ci]\nstages = \"check\"\n")),
+        vec!["`stages` must be an array of strings"]
+    );
+    assert_eq!(
+        errors(&check("[ci]\nmax_padded_height = 0\n")),
+        vec!["`max_padded_height` must be a positive integer"]
+    );
+    for bad in ["-5", "2.5", "\"5\"", "5%"] {
+        assert_eq!(
+            errors(&check(&format!("[ci]\nmax_cost_increase = {}\n", bad))),
+            vec!["`max_cost_increase` must be a whole-number percentage"]
+        );
+    }
+    let diags = check("[ci]\nmax_cost_increase = 5%\n");
+    assert_eq!(
+        diags[0].help.as_deref(),
+        Some("drop the `%`: `max_cost_increase = 5`")
+    );
+}
+
+#[test]
+fn build_max_instances_is_a_positive_integer() {
+    assert!(check("[build]\nmax_instances = 64 # per program\n").is_empty());
+    for bad in ["\"64\"", "0", "-3"] {
+        assert_eq!(
+            errors(&check(&format!("[build]\nmax_instances = {}\n", bad))),
+            vec!["`max_instances` must be a positive integer"]
+        );
+    }
+}
+
+#[test]
+fn passes_map_names_to_plugin_paths() {
+    let diags = check("[passes]\nhouse_rules = \"main.tri\"\nmissing = \"tools/lint\"\nbad = 3\n");
+    assert_eq!(errors(&diags), vec!["`bad` must be a string"]);
+    assert!(diags
+        .iter()
+        .any(|d| d.message == "`missing` path 'tools/lint' does not exist"));
+}
+
+#[test]
+fn deploy_commitments_are_identifiers() {
+    assert!(check("[deploy]\ncommitments = [\"utxo_root\", \"epoch\"]\n").is_empty());
+    assert_eq!(
+        errors(&check("[deploy]\ncommitments = \"utxo_root\"\n")).len(),
+        1
+    );
+}
@@ -1,6 +1,11 @@
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
+use rayon::prelude::*;
+
+use super::cache;
 use super::model::{create_cost_model, CostModel, TableCost};
+use crate::ast::desugar;
 use crate::ast::*;
 use crate::field::proof;
 
@@ -15,6 +20,55 @@ pub struct FunctionCost {
     pub per_iteration: Option<(TableCost, u64)>,
 }
 
+/// Function costs of one module of a project.
+#[derive(Clone, Debug)]
+pub struct ModuleCost {
+    pub module: String,
+    pub functions: Vec<FunctionCost>,
+}
+
+/// A constant the linker stores in RAM once instead of pushing it at
+/// every use.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaterializedConstant {
+    /// Field elements, in push order.
+    pub values: Vec<u64>,
+    /// RAM address of the last pushed element; the rest follow it.
+    pub address: u64,
+    /// Sites that read the constant back.
+    pub uses: usize,
+    /// Rows saved per table, net of the prologue that stores it.
+    pub saved: Vec<i64>,
+}
+
+/// Cost of the statements starting on one line of the program file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatementCost {
+    pub line: u32,
+    pub cost: TableCost,
+}
+
+/// A known rewrite of the program and the rows it would save.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RewriteEstimate {
+    pub line: u32,
+    pub description: String,
+    pub saved: TableCost,
+}
+
+/// Cost of one operation of a multi-entrypoint program: the dispatch in
+/// `main` and the entrypoint it selects, worst case along its call graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntrypointCost {
+    pub selector: u64,
+    pub name: String,
+    pub total: TableCost,
+    /// Padded height of a run through this entrypoint, attestation
+    /// included.
+    pub padded_height: u64,
+    pub estimated_proving_ns: u64,
+}
+
 /// Cost analysis result for the full program.
 #[derive(Clone, Debug)]
 pub struct ProgramCost {
@@ -31,6 +85,19 @@ pub struct ProgramCost {
     pub estimated_proving_ns: u64,
     /// H0004: loops where declared bound >> actual constant end.
     pub loop_bound_waste: Vec<(String, u64, u64)>, // (fn_name, end_value, bound)
+    /// Every module of a project, dependencies first; empty for a single file.
+    pub modules: Vec<ModuleCost>,
+    /// Constants materialized by the linker; empty unless the program
+    /// was linked.
+    pub constants: Vec<MaterializedConstant>,
+    /// Per-operation costs, by selector; empty unless the program has
+    /// `#[entrypoint]` functions. `total` is the costliest of them.
+    pub entrypoints: Vec<EntrypointCost>,
+    /// Per-line statement costs of the program file; empty unless the
+    /// source was at hand (see `CostAnalyzer::attach_what_if`).
+    pub statements: Vec<StatementCost>,
+    /// Known rewrites of the program file, with their estimated savings.
+    pub rewrites: Vec<RewriteEstimate>,
 }
 
 impl ProgramCost {
@@ -50,14 +117,30 @@ impl ProgramCost {
 /// Computes static cost by walking the AST.
 ///
 /// The analyzer is parameterized by a `CostModel` that provides all
-/// target-specific cost constants.
+/// target-specific cost constants. Function tables are shared, so a copy
+/// of the analyzer is cheap: `analyze_file` costs each function on its
+/// own copy, in parallel.
+#[derive(Clone)]
 pub(crate) struct CostAnalyzer<'a> {
     /// Target-specific cost model.
     pub(crate) cost_model: &'a dyn CostModel,
+    /// Name of the target, selecting among side-by-side `asm` blocks.
+    target: String,
     /// Function bodies indexed by name (for resolving calls).
-    pub(crate) fn_bodies: BTreeMap<String, FnDef>,
+    pub(crate) fn_bodies: Arc<BTreeMap<String, Arc<FnDef>>>,
+    /// Function bodies of imported modules, indexed by qualified name.
+    pub(crate) imported_bodies: Arc<BTreeMap<String, Arc<FnDef>>>,
+    /// Import aliases (short name -> module) of each imported module.
+    module_uses: Arc<BTreeMap<String, BTreeMap<String, String>>>,
+    /// Import aliases in effect for the body being analyzed.
+    pub(crate) uses: BTreeMap<String, String>,
     /// Cached function costs to avoid recomputation.
     fn_costs: BTreeMap<String, TableCost>,
+    /// Hits and lookups in `fn_costs`.
+    memo_stats: (u64, u64),
+    /// Digest of the analyzed module and its dependencies, keying the
+    /// cross-analysis cache. `None` analyzes without it.
+    content: Option<[u8; 32]>,
     /// Recursion guard to prevent infinite loops in cost computation.
     in_progress: Vec<String>,
     /// H0004: collected loop bound waste entries (fn_name, end_value, bound).
@@ -73,45 +156,97 @@ impl Default for CostAnalyzer<'_> {
 impl<'a> CostAnalyzer<'a> {
     /// Create an analyzer for the named target.
     pub(crate) fn for_target(target_name: &str) -> Self {
-        Self::with_cost_model(create_cost_model(target_name))
+        Self::with_cost_model(target_name, create_cost_model(target_name))
     }
 
     /// Create an analyzer with a specific cost model.
-    pub(crate) fn with_cost_model(cost_model: &'a dyn CostModel) -> Self {
+    pub(crate) fn with_cost_model(target_name: &str, cost_model: &'a dyn CostModel) -> Self {
         Self {
             cost_model,
-            fn_bodies: BTreeMap::new(),
+            target: target_name.to_string(),
+            fn_bodies: Arc::default(),
+            imported_bodies: Arc::default(),
+            module_uses: Arc::default(),
+            uses: BTreeMap::new(),
             fn_costs: BTreeMap::new(),
+            memo_stats: (0, 0),
+            content: None,
             in_progress: Vec::new(),
             loop_bound_waste: Vec::new(),
         }
     }
 
+    /// Register the functions of imported modules so that qualified calls
+    /// such as `hash.absorb` are charged for their bodies.
+    ///
+    /// Bodies are keyed and renamed by their full qualified name, so their
+    /// cached cost cannot collide with a local function of the same name.
+    pub(crate) fn with_imports<'f>(mut self, modules: impl IntoIterator<Item = &'f File>) -> Self {
+        let bodies = Arc::make_mut(&mut self.imported_bodies);
+        let module_uses = Arc::make_mut(&mut self.module_uses);
+        for module in modules {
+            let full = &module.name.node;
+            for item in &module.items {
+                if let Item::Fn(func) = &item.node {
+                    let qualified = format!("{}.{}", full, func.name.node);
+                    let mut body = func.clone();
+                    body.name.node = qualified.clone();
+                    bodies.insert(qualified, Arc::new(body));
+                }
+            }
+            module_uses.insert(full.clone(), use_aliases(module));
+        }
+        self
+    }
+
+    /// Reuse and record function costs in the shared cache under the
+    /// module digest `content` (see `cost::cache`).
+    pub(crate) fn with_content_digest(mut self, content: [u8; 32]) -> Self {
+        self.content = Some(content);
+        self
+    }
+
     /// Analyze a complete file and return the program cost.
     pub(crate) fn analyze_file(&mut self, file: &File) -> ProgramCost {
+        self.uses = use_aliases(file);
         // Collect all function definitions.
+        let bodies = Arc::make_mut(&mut self.fn_bodies);
         for item in &file.items {
             if let Item::Fn(func) = &item.node {
-                self.fn_bodies.insert(func.name.node.clone(), func.clone());
+                bodies.insert(func.name.node.clone(), Arc::new(func.clone()));
             }
         }
 
-        // Compute cost for each function.
-        let mut functions = Vec::new();
-        let fn_names: Vec<String> = self.fn_bodies.keys().cloned().collect();
-        for name in &fn_names {
-            let func = self
-                .fn_bodies
-                .get(name)
-                .expect("name comes from fn_bodies.keys()")
-                .clone();
-            let cost = self.cost_fn(&func);
-            let per_iteration = self.find_loop_iteration_cost(&func);
-            functions.push(FunctionCost {
-                name: name.clone(),
-                cost,
-                per_iteration,
-            });
+        // Compute cost for each function, in parallel.
+        let costed: Vec<(cache::Entry, Option<(u64, u64)>)> = self
+            .fn_bodies
+            .par_iter()
+            .map(|(name, func)| self.cost_top_level(name, func))
+            .collect();
+        let mut functions = Vec::with_capacity(costed.len());
+        let (mut cache_hits, mut cache_lookups) = (0, 0);
+        for (entry, memo_stats) in costed {
+            match memo_stats {
+                Some((hits, lookups)) => {
+                    self.memo_stats.0 += hits;
+                    self.memo_stats.1 += lookups;
+                }
+                None => cache_hits += 1,
+            }
+            cache_lookups += 1;
+            for waste in entry.loop_bound_waste {
+                if !self.loop_bound_waste.contains(&waste) {
+                    self.loop_bound_waste.push(waste);
+                }
+            }
+            self.fn_costs
+                .insert(entry.function.name.clone(), entry.function.cost);
+            functions.push(entry.function);
+        }
+        crate::api::timings::cache_counts("function cost", self.memo_stats.0, self.memo_stats.1);
+        self.memo_stats = (0, 0);
+        if self.content.is_some() {
+            crate::api::timings::cache_counts("function cost (content)", cache_hits, cache_lookups);
         }
 
         // Total cost: start from main if it exists, otherwise sum all.
@@ -135,6 +270,7 @@ impl<'a> CostAnalyzer<'a> {
 
         let columns = self.cost_model.trace_column_count();
         let estimated_proving_ns = proof::estimate_proving_ns(padded_height, columns);
+        let entrypoints = self.entrypoint_costs(file, attestation_hash_rows);
 
         // H0004: scan for loop bound waste (bound >> constant end)
         for item in &file.items {
@@ -165,12 +301,151 @@ impl<'a> CostAnalyzer<'a> {
             padded_height,
             estimated_proving_ns,
             loop_bound_waste: std::mem::take(&mut self.loop_bound_waste),
+            modules: Vec::new(),
+            constants: Vec::new(),
+            entrypoints,
+            statements: Vec::new(),
+            rewrites: Vec::new(),
+        }
+    }
+
+    /// Cost of each entrypoint of `file`: the call to `main`, the
+    /// dispatch up to the entrypoint's branch, and the entrypoint itself.
+    fn entrypoint_costs(&mut self, file: &File, attestation_hash_rows: u64) -> Vec<EntrypointCost> {
+        let table = crate::ast::desugar::entrypoints(file);
+        if file.kind != FileKind::Program || table.is_empty() {
+            return Vec::new();
         }
+        let mut paths: BTreeMap<String, TableCost> = BTreeMap::new();
+        if let Some(main) = self.fn_bodies.get("main").cloned() {
+            if let Some(body) = &main.body {
+                self.dispatch_paths(&body.node, TableCost::ZERO, &mut paths);
+            }
+        }
+        let columns = self.cost_model.trace_column_count();
+        table
+            .into_iter()
+            .map(|(selector, func)| {
+                let name = func.name.node.clone();
+                // Without a generated main, charge the entrypoint alone.
+                let path = match paths.get(&name) {
+                    Some(path) => *path,
+                    None => self.cost_fn(func).add(&self.cost_model.call_overhead()),
+                };
+                let total = path.add(&self.cost_model.call_overhead());
+                let padded_height =
+                    proof::padded_height(total.max_height().max(attestation_hash_rows));
+                EntrypointCost {
+                    selector,
+                    name,
+                    total,
+                    padded_height,
+                    estimated_proving_ns: proof::estimate_proving_ns(padded_height, columns),
+                }
+            })
+            .collect()
+    }
+
+    /// Walk the `if selector == n { f() } else ...` chain of a dispatch
+    /// `main`, recording for each entrypoint `f` the cost of the path that
+    /// reaches its branch and runs it.
+    fn dispatch_paths(
+        &mut self,
+        block: &Block,
+        mut prefix: TableCost,
+        paths: &mut BTreeMap<String, TableCost>,
+    ) {
+        for stmt in &block.stmts {
+            let Stmt::If {
+                cond,
+                then_block,
+                else_block,
+            } = &stmt.node
+            else {
+                prefix = prefix.add(&self.cost_stmt(&stmt.node));
+                continue;
+            };
+            prefix = prefix
+                .add(&self.cost_expr(&cond.node))
+                .add(&self.cost_model.if_overhead());
+            let callee = then_block.node.stmts.iter().find_map(|s| match &s.node {
+                Stmt::Expr(e) => match &e.node {
+                    Expr::Call { path, .. } => Some(path.node.as_dotted()),
+                    _ => None,
+                },
+                _ => None,
+            });
+            if let Some(callee) = callee {
+                let cost = prefix.add(&self.cost_block(&then_block.node));
+                paths.insert(callee, cost);
+            }
+            if let Some(else_block) = else_block {
+                self.dispatch_paths(&else_block.node, prefix, paths);
+            }
+            return;
+        }
+    }
+
+    /// Cost of calling `fn_name` in an imported module, resolved through
+    /// the import aliases in effect. `None` if it is not an imported function.
+    pub(crate) fn cost_imported_call(&mut self, fn_name: &str) -> Option<TableCost> {
+        let (prefix, name) = fn_name.rsplit_once('.')?;
+        let module = self.uses.get(prefix).map_or(prefix, String::as_str);
+        let func = Arc::clone(self.imported_bodies.get(&format!("{}.{}", module, name))?);
+        if let Some(intrinsic) = &func.intrinsic {
+            // `intrinsic(merkle_step)` is charged as the `merkle_step` builtin.
+            let attr = intrinsic.node.as_str();
+            let builtin = attr
+                .strip_prefix("intrinsic(")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .unwrap_or(attr);
+            return Some(self.cost_model.builtin_cost(builtin));
+        }
+        // The body's calls resolve through its own module's imports.
+        let outer = self
+            .module_uses
+            .get(module)
+            .cloned()
+            .map(|uses| std::mem::replace(&mut self.uses, uses));
+        let cost = self.cost_fn(&func);
+        if let Some(uses) = outer {
+            self.uses = uses;
+        }
+        Some(cost)
+    }
+
+    /// Cost of a function of the analyzed file, on a copy of the analyzer.
+    ///
+    /// Returns the memo hits and lookups of the copy, or `None` when the
+    /// cost came from the shared cache.
+    fn cost_top_level(&self, name: &str, func: &FnDef) -> (cache::Entry, Option<(u64, u64)>) {
+        let key = self.content.map(|digest| cache::key(&digest, name));
+        if let Some(entry) = key.as_ref().and_then(cache::lookup) {
+            return (entry, None);
+        }
+        let mut worker = self.clone();
+        let cost = worker.cost_fn(func);
+        let per_iteration = worker.find_loop_iteration_cost(func);
+        let entry = cache::Entry {
+            function: FunctionCost {
+                name: name.to_string(),
+                cost,
+                per_iteration,
+            },
+            loop_bound_waste: worker.loop_bound_waste,
+        };
+        if let Some(key) = key {
+            cache::store(key, entry.clone());
+        }
+        (entry, Some(worker.memo_stats))
     }
 
     pub(crate) fn cost_fn(&mut self, func: &FnDef) -> TableCost {
-        if let Some(cached) = self.fn_costs.get(&func.name.node) {
-            return *cached;
+        let cached = self.fn_costs.get(&func.name.node).copied();
+        self.memo_stats.1 += 1;
+        if let Some(cost) = cached {
+            self.memo_stats.0 += 1;
+            return cost;
         }
 
         // Recursion guard: if this function is already being analyzed,
@@ -201,8 +476,11 @@ impl<'a> CostAnalyzer<'a> {
 
     pub(crate) fn cost_block(&mut self, block: &Block) -> TableCost {
         let mut cost = TableCost::ZERO;
-        for stmt in &block.stmts {
-            cost = cost.add(&self.cost_stmt(&stmt.node));
+        let active = active_stmts(&block.stmts, &self.target);
+        for (stmt, active) in block.stmts.iter().zip(active) {
+            if active {
+                cost = cost.add(&self.cost_stmt(&stmt.node));
+            }
         }
         if let Some(tail) = &block.tail_expr {
             cost = cost.add(&self.cost_expr(&tail.node));
@@ -211,6 +489,11 @@ impl<'a> CostAnalyzer<'a> {
     }
 
     pub(crate) fn cost_stmt(&mut self, stmt: &Stmt) -> TableCost {
+        if let Some(rewritten) = desugar::loop_with_exits(stmt) {
+            return rewritten.iter().fold(TableCost::ZERO, |cost, s| {
+                cost.add(&self.cost_stmt(&s.node))
+            });
+        }
         let stack_op = self.cost_model.stack_op();
         match stmt {
             Stmt::Let { init, .. } => {
@@ -252,8 +535,9 @@ impl<'a> CostAnalyzer<'a> {
                 let end_cost = self.cost_expr(&end.node);
                 let body_cost = self.cost_block(&body.node);
                 // Use declared bound if available, otherwise use end expr as literal.
-                let iterations = if let Some(b) = bound {
-                    *b
+                let declared = bound.as_ref().and_then(|b| integer_literal(&b.node));
+                let iterations = if let Some(b) = declared {
+                    b
                 } else if let Expr::Literal(Literal::Integer(n)) = &end.node {
                     *n
                 } else {
@@ -271,6 +555,17 @@ impl<'a> CostAnalyzer<'a> {
                 let per_iter = body_cost.add(&self.cost_model.loop_overhead());
                 end_cost.add(&per_iter.scale(iterations))
             }
+            Stmt::While { cond, bound, body } => {
+                // Worst case: the condition holds for all `bound` iterations;
+                // each checks it, branches and runs the body.
+                let cond_cost = self.cost_expr(&cond.node);
+                let body_cost = self.cost_block(&body.node);
+                let per_iter = cond_cost
+                    .add(&body_cost)
+                    .add(&self.cost_model.if_overhead())
+                    .add(&self.cost_model.loop_overhead());
+                per_iter.scale(integer_literal(&bound.node).unwrap_or(1))
+            }
             Stmt::Expr(expr) => self.cost_expr(&expr.node),
             Stmt::Return(val) => {
                 if let Some(v) = val {
@@ -279,6 +574,10 @@ impl<'a> CostAnalyzer<'a> {
                     TableCost::ZERO
                 }
             }
+            // Loops are charged in their rewritten form above, where an
+            // exit is a flag store and the rest of the iteration is
+            // skipped behind a guard, not jumped over.
+            Stmt::Break | Stmt::Continue => TableCost::ZERO,
             Stmt::Reveal { fields, .. } => {
                 // push tag + write_io 1 + (field expr + write_io 1) per field
                 let io_cost = self.cost_model.builtin_cost("pub_write");
@@ -341,3 +640,15 @@ impl<'a> CostAnalyzer<'a> {
         }
     }
 }
+
+/// Map each `use` of `file` from its short alias to the full module name.
+fn use_aliases(file: &File) -> BTreeMap<String, String> {
+    file.uses
+        .iter()
+        .map(|u| {
+            let full = u.node.as_dotted();
+            let short = u.node.0.last().cloned().unwrap_or_else(|| full.clone());
+            (short, full)
+        })
+        .collect()
+}
@@ -0,0 +1,54 @@
+//! Function costs shared across analyses.
+//!
+//! A function's cost depends on its own body, the bodies it can call, and
+//! the target. `analyze_costs_project` digests all of that per module —
+//! the target name, the module source, and the digest of the modules
+//! before it in dependency order — and keys each function by that digest
+//! and its name. Analyzing an unchanged module again in the same process
+//! (the language server, repeated builds) reuses its function costs
+//! instead of walking the bodies.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+
+use super::analyzer::FunctionCost;
+
+/// A cached function cost with the loop-bound findings made computing it.
+#[derive(Clone, Debug)]
+pub(crate) struct Entry {
+    pub function: FunctionCost,
+    pub loop_bound_waste: Vec<(String, u64, u64)>,
+}
+
+fn entries() -> &'static Mutex<BTreeMap<[u8; 32], Entry>> {
+    static ENTRIES: OnceLock<Mutex<BTreeMap<[u8; 32], Entry>>> = OnceLock::new();
+    ENTRIES.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+/// Digest of a module's source in the context of everything before it.
+pub(crate) fn module_digest(target: &str, source: &str, previous: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(target.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(previous);
+    hasher.update(source.as_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+/// Key of the function `name` in a module with `digest`.
+pub(crate) fn key(digest: &[u8; 32], name: &str) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(digest);
+    hasher.update(name.as_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+pub(crate) fn lookup(key: &[u8; 32]) -> Option<Entry> {
+    entries().lock().ok()?.get(key).cloned()
+}
+
+pub(crate) fn store(key: [u8; 32], entry: Entry) {
+    if let Ok(mut entries) = entries().lock() {
+        entries.insert(key, entry);
+    }
+}
@@ -0,0 +1,316 @@
+//! Semantic cost diff of two versions of a source file.
+//!
+//! Both versions are analyzed in-process. Functions are aligned by name,
+//! then by content hash, so a renamed function still lines up with its old
+//! self. For each function whose content or cost changed, the changed
+//! source lines are listed with the cost of the statements on them.
+
+use std::collections::BTreeMap;
+
+use super::analyzer::ProgramCost;
+use super::model::TableCost;
+use crate::ast::{File, Item};
+use crate::hash::ContentHash;
+
+/// One version of a source file: its desugared AST, text, and cost.
+pub(crate) struct SourceVersion<'a> {
+    pub name: &'a str,
+    pub file: &'a File,
+    pub source: &'a str,
+    /// Cost of `file`, with its statement costs attached.
+    pub cost: ProgramCost,
+}
+
+/// Cost diff of two versions of a source file.
+#[derive(Clone, Debug)]
+pub struct SourceDiff {
+    pub old_name: String,
+    pub new_name: String,
+    pub old: ProgramCost,
+    pub new: ProgramCost,
+    /// Functions that changed, in new-file order; removed ones last.
+    pub functions: Vec<FunctionDiff>,
+    /// Functions present in both versions with the same content and cost.
+    pub unchanged: usize,
+}
+
+/// A function of either version and how it changed.
+#[derive(Clone, Debug)]
+pub struct FunctionDiff {
+    /// Name in the old version; `None` for an added function.
+    pub old_name: Option<String>,
+    /// Name in the new version; `None` for a removed function.
+    pub new_name: Option<String>,
+    pub old_cost: Option<TableCost>,
+    pub new_cost: Option<TableCost>,
+    /// Changed source lines, in order.
+    pub lines: Vec<LineDiff>,
+}
+
+/// A source line only one version has.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineDiff {
+    /// `true` for a line of the new version, `false` for one of the old.
+    pub added: bool,
+    /// 1-based line number in its version.
+    pub line: u32,
+    pub text: String,
+    /// Cost of the statements starting on the line.
+    pub cost: TableCost,
+}
+
+/// A function of one version, as the diff sees it.
+struct Function<'a> {
+    name: &'a str,
+    hash: Option<ContentHash>,
+    cost: Option<TableCost>,
+    /// First line of the function and its source lines.
+    start: u32,
+    lines: Vec<&'a str>,
+}
+
+fn functions<'a>(version: &'a SourceVersion) -> Vec<Function<'a>> {
+    let hashes = crate::hash::hash_file(version.file);
+    version
+        .file
+        .items
+        .iter()
+        .filter_map(|item| match &item.node {
+            Item::Fn(func) => Some((item, func)),
+            _ => None,
+        })
+        .map(|(item, func)| {
+            let name = func.name.node.as_str();
+            let start = item.span.start as usize;
+            let end = (item.span.end as usize).min(version.source.len());
+            let text = version.source.get(start..end).unwrap_or("");
+            Function {
+                name,
+                hash: hashes.get(name).copied(),
+                cost: version
+                    .cost
+                    .functions
+                    .iter()
+                    .find(|f| f.name == name)
+                    .map(|f| f.cost),
+                start: version.source[..start.min(version.source.len())]
+                    .matches('\n')
+                    .count() as u32
+                    + 1,
+                lines: text.lines().collect(),
+            }
+        })
+        .collect()
+}
+
+/// Diff the costs of two versions of a source file.
+pub(crate) fn diff_sources(old: SourceVersion, new: SourceVersion) -> SourceDiff {
+    let old_fns = functions(&old);
+    let new_fns = functions(&new);
+
+    // Pair by name, then pair what is left by content hash.
+    let mut pairs: Vec<(Option<usize>, Option<usize>)> = Vec::new();
+    let mut old_used = vec![false; old_fns.len()];
+    let mut new_to_old: BTreeMap<usize, usize> = BTreeMap::new();
+    for (j, f) in new_fns.iter().enumerate() {
+        if let Some(i) = old_fns.iter().position(|o| o.name == f.name) {
+            old_used[i] = true;
+            new_to_old.insert(j, i);
+        }
+    }
+    for (j, f) in new_fns.iter().enumerate() {
+        if new_to_old.contains_key(&j) || f.hash.is_none() {
+            continue;
+        }
+        if let Some(i) = (0..old_fns.len()).find(|&i| !old_used[i] && old_fns[i].hash == f.hash) {
+            old_used[i] = true;
+            new_to_old.insert(j, i);
+        }
+    }
+    for j in 0..new_fns.len() {
+        pairs.push((new_to_old.get(&j).copied(), Some(j)));
+    }
+    for (i, used) in old_used.iter().enumerate() {
+        if !used {
+            pairs.push((Some(i), None));
+        }
+    }
+
+    let mut functions = Vec::new();
+    let mut unchanged = 0;
+    for (i, j) in pairs {
+        let (o, n) = (i.map(|i| &old_fns[i]), j.map(|j| &new_fns[j]));
+        if let (Some(o), Some(n)) = (o, n) {
+            if o.name == n.name && o.hash == n.hash && o.cost == n.cost {
+                unchanged += 1;
+                continue;
+            }
+        }
+        let empty = Vec::new();
+        let old_lines = o.map_or(&empty, |f| &f.lines);
+        let new_lines = n.map_or(&empty, |f| &f.lines);
+        let line_cost = |cost: &ProgramCost, line: u32| {
+            cost.statements
+                .iter()
+                .find(|s| s.line == line)
+                .map_or(TableCost::ZERO, |s| s.cost)
+        };
+        let lines = line_edits(old_lines, new_lines)
+            .into_iter()
+            .map(|(added, k)| {
+                let (f, cost) = if added {
+                    (n.expect("added line"), &new.cost)
+                } else {
+                    (o.expect("removed line"), &old.cost)
+                };
+                let line = f.start + k as u32;
+                LineDiff {
+                    added,
+                    line,
+                    text: f.lines[k].to_string(),
+                    cost: line_cost(cost, line),
+                }
+            })
+            .collect();
+        functions.push(FunctionDiff {
+            old_name: o.map(|f| f.name.to_string()),
+            new_name: n.map(|f| f.name.to_string()),
+            old_cost: o.and_then(|f| f.cost),
+            new_cost: n.and_then(|f| f.cost),
+            lines,
+        });
+    }
+
+    SourceDiff {
+        old_name: old.name.to_string(),
+        new_name: new.name.to_string(),
+        old: old.cost,
+        new: new.cost,
+        functions,
+        unchanged,
+    }
+}
+
+/// Lines only one side has, by a longest common subsequence: `(added,
+/// index)` pairs in order, removals before the additions that replace
+/// them.
+fn line_edits(old: &[&str], new: &[&str]) -> Vec<(bool, usize)> {
+    let (m, n) = (old.len(), new.len());
+    // table[i][j] = LCS length of old[i..] and new[j..]
+    let mut table = vec![vec![0u32; n + 1]; m + 1];
+    for i in (0..m).rev() {
+        for j in (0..n).rev() {
+            table[i][j] = if old[i].trim() == new[j].trim() {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < m || j < n {
+        if i < m && j < n && old[i].trim() == new[j].trim() {
+            i += 1;
+            j += 1;
+        } else if i < m && (j == n || table[i + 1][j] >= table[i][j + 1]) {
+            edits.push((false, i));
+            i += 1;
+        } else {
+            edits.push((true, j));
+            j += 1;
+        }
+    }
+    edits
+}
+
+fn signed(delta: i64) -> String {
+    if delta > 0 {
+        format!("+{}", delta)
+    } else {
+        delta.to_string()
+    }
+}
+
+impl SourceDiff {
+    /// Format the diff: per-function primary-table costs with the changed
+    /// lines inline, then the totals and padded heights.
+    pub fn format(&self) -> String {
+        let short = self.new.short_names();
+        let primary = short.first().unwrap_or(&"?");
+        let mut out = String::new();
+        out.push_str(&format!(
+            "Cost diff: {} -> {}\n",
+            self.old_name, self.new_name
+        ));
+        out.push_str(&format!(
+            "{:<28} {:>9} {:>9}  {:>6}\n",
+            "Function",
+            format!("{} (old)", primary),
+            format!("{} (new)", primary),
+            "delta"
+        ));
+        out.push_str(&"-".repeat(56));
+        out.push('\n');
+
+        let cell = |cost: Option<TableCost>| cost.map_or("-".to_string(), |c| c.get(0).to_string());
+        for f in &self.functions {
+            let label = match (&f.old_name, &f.new_name) {
+                (Some(old), Some(new)) if old != new => format!("{} (was {})", new, old),
+                (_, Some(new)) => new.clone(),
+                (Some(old), None) => format!("{} (removed)", old),
+                (None, None) => continue,
+            };
+            let delta = f.new_cost.map_or(0, |c| c.get(0) as i64)
+                - f.old_cost.map_or(0, |c| c.get(0) as i64);
+            out.push_str(&format!(
+                "{:<28} {:>9} {:>9}  {:>6}\n",
+                label,
+                cell(f.old_cost),
+                cell(f.new_cost),
+                signed(delta)
+            ));
+            for line in &f.lines {
+                let annotation = line.cost.format_annotation(&short);
+                let text = format!(
+                    "  {} {:>4} | {}",
+                    if line.added { '+' } else { '-' },
+                    line.line,
+                    line.text.trim_end()
+                );
+                if annotation.is_empty() {
+                    out.push_str(&format!("{}\n", text));
+                } else {
+                    out.push_str(&format!("{:<60}  [{}]\n", text, annotation));
+                }
+            }
+        }
+        if self.unchanged > 0 {
+            out.push_str(&format!(
+                "({} unchanged function{})\n",
+                self.unchanged,
+                if self.unchanged == 1 { "" } else { "s" }
+            ));
+        }
+
+        out.push_str(&"-".repeat(56));
+        out.push('\n');
+        let (old_total, new_total) = (self.old.total.get(0), self.new.total.get(0));
+        out.push_str(&format!(
+            "{:<28} {:>9} {:>9}  {:>6}\n",
+            "TOTAL",
+            old_total,
+            new_total,
+            signed(new_total as i64 - old_total as i64)
+        ));
+        let (old_ph, new_ph) = (self.old.padded_height, self.new.padded_height);
+        out.push_str(&format!(
+            "{:<28} {:>9} {:>9}  {:>6}\n",
+            "Padded height:",
+            old_ph,
+            new_ph,
+            signed(new_ph as i64 - old_ph as i64)
+        ));
+        out
+    }
+}
@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use super::analyzer::{FunctionCost, ProgramCost};
+use super::analyzer::{EntrypointCost, FunctionCost, MaterializedConstant, ProgramCost};
 use super::model::TableCost;
 use crate::diagnostic::Diagnostic;
 use crate::span::Span;
@@ -29,6 +29,114 @@ fn find_matching_brace(s: &str, start: usize) -> Option<usize> {
     None
 }
 
+/// The integer after `"key":` in a JSON object.
+fn json_number<T: std::str::FromStr>(s: &str, key: &str) -> Option<T> {
+    let needle = format!("\"{}\"", key);
+    let rest = s[s.find(&needle)? + needle.len()..].trim_start();
+    let rest = rest.strip_prefix(':')?.trim_start();
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '-')
+        .unwrap_or(rest.len());
+    rest[..end].parse().ok()
+}
+
+/// Parse the objects of a `"constants"` array.
+fn parse_constants(s: &str, names: &[&str]) -> Vec<MaterializedConstant> {
+    // The array follows `padded_height`, after any function of that name.
+    let Some(start) = s
+        .find("\"padded_height\"")
+        .and_then(|ph| s[ph..].find("\"constants\"").map(|i| ph + i))
+    else {
+        return Vec::new();
+    };
+    let mut constants = Vec::new();
+    let mut pos = start;
+    while let Some(open) = s[pos..].find(['{', ']']) {
+        let open = pos + open;
+        if s.as_bytes()[open] == b']' {
+            break;
+        }
+        let Some(close) = find_matching_brace(s, open) else {
+            break;
+        };
+        let obj = &s[open..=close];
+        let values = obj
+            .find('[')
+            .and_then(|l| obj[l..].find(']').map(|r| &obj[l + 1..l + r]))
+            .map(|list| {
+                list.split(',')
+                    .filter_map(|v| v.trim().parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let saved_start = obj.find("\"saved\"").unwrap_or(obj.len());
+        constants.push(MaterializedConstant {
+            values,
+            address: json_number(obj, "address").unwrap_or(0),
+            uses: json_number(obj, "uses").unwrap_or(0),
+            saved: names
+                .iter()
+                .map(|n| json_number(&obj[saved_start..], n).unwrap_or(0))
+                .collect(),
+        });
+        pos = close + 1;
+    }
+    constants
+}
+
+/// Parse the objects of an `"entrypoints"` array.
+fn parse_entrypoints(s: &str, names: &[&str]) -> Vec<EntrypointCost> {
+    // Like `constants`, the array follows the program's `padded_height`.
+    let Some(start) = s
+        .find("\"padded_height\"")
+        .and_then(|ph| s[ph..].find("\"entrypoints\"").map(|i| ph + i))
+    else {
+        return Vec::new();
+    };
+    let mut entrypoints = Vec::new();
+    let mut pos = start;
+    while let Some(open) = s[pos..].find(['{', ']']) {
+        let open = pos + open;
+        if s.as_bytes()[open] == b']' {
+            break;
+        }
+        let Some(close) = find_matching_brace(s, open) else {
+            break;
+        };
+        let obj = &s[open..=close];
+        let name = obj
+            .find("\"name\"")
+            .and_then(|i| obj[i + "\"name\"".len()..].split('"').nth(1))
+            .unwrap_or_default();
+        let total = obj
+            .find("\"total\"")
+            .and_then(|i| obj[i..].find('{').map(|j| i + j))
+            .and_then(|l| find_matching_brace(obj, l).map(|r| &obj[l..=r]))
+            .and_then(|t| TableCost::from_json_value(t, names))
+            .unwrap_or(TableCost::ZERO);
+        entrypoints.push(EntrypointCost {
+            selector: json_number(obj, "selector").unwrap_or(0),
+            name: name.to_string(),
+            total,
+            padded_height: json_number(obj, "padded_height").unwrap_or(0),
+            estimated_proving_ns: 0,
+        });
+        pos = close + 1;
+    }
+    entrypoints
+}
+
+/// Short display name of a constant, e.g. `[1, 2, 3, 4, 5]`.
+pub(crate) fn constant_label(values: &[u64]) -> String {
+    let list: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    let label = format!("[{}]", list.join(", "));
+    if label.chars().count() > 20 {
+        format!("{}..]", label.chars().take(16).collect::<String>())
+    } else {
+        label
+    }
+}
+
 // --- Report formatting ---
 
 impl ProgramCost {
@@ -52,8 +160,48 @@ impl ProgramCost {
             "  \"total\": {},\n",
             self.total.to_json_value(&names)
         ));
-        out.push_str(&format!("  \"padded_height\": {}\n", self.padded_height));
-        out.push_str("}\n");
+        out.push_str(&format!("  \"padded_height\": {}", self.padded_height));
+        if !self.constants.is_empty() {
+            out.push_str(",\n  \"constants\": [\n");
+            for (i, c) in self.constants.iter().enumerate() {
+                let values: Vec<String> = c.values.iter().map(|v| v.to_string()).collect();
+                let saved: Vec<String> = names
+                    .iter()
+                    .zip(&c.saved)
+                    .map(|(n, v)| format!("\"{}\": {}", n, v))
+                    .collect();
+                out.push_str(&format!(
+                    "    {{\"values\": [{}], \"address\": {}, \"uses\": {}, \"saved\": {{{}}}}}",
+                    values.join(", "),
+                    c.address,
+                    c.uses,
+                    saved.join(", ")
+                ));
+                if i + 1 < self.constants.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            out.push_str("  ]");
+        }
+        if !self.entrypoints.is_empty() {
+            out.push_str(",\n  \"entrypoints\": [\n");
+            for (i, e) in self.entrypoints.iter().enumerate() {
+                out.push_str(&format!(
+                    "    {{\"selector\": {}, \"name\": \"{}\", \"total\": {}, \"padded_height\": {}}}",
+                    e.selector,
+                    e.name,
+                    e.total.to_json_value(&names),
+                    e.padded_height
+                ));
+                if i + 1 < self.entrypoints.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            out.push_str("  ]");
+        }
+        out.push_str("\n}\n");
         out
     }
 
@@ -169,6 +317,11 @@ impl ProgramCost {
             padded_height,
             estimated_proving_ns: 0,
             loop_bound_waste: Vec::new(),
+            modules: Vec::new(),
+            constants: parse_constants(s, names),
+            entrypoints: parse_entrypoints(s, names),
+            statements: Vec::new(),
+            rewrites: Vec::new(),
         })
     }
 
@@ -261,6 +414,42 @@ impl ProgramCost {
             "Padded height:", old_ph, new_ph, ph_delta_str
         ));
 
+        // Constants the linker materialized in either build, by rows of
+        // the primary table they save.
+        if !self.constants.is_empty() || !other.constants.is_empty() {
+            out.push_str(&format!("\nMaterialized constants ({} saved):\n", primary));
+            let mut all_values: Vec<&Vec<u64>> = Vec::new();
+            for c in self.constants.iter().chain(&other.constants) {
+                if !all_values.contains(&&c.values) {
+                    all_values.push(&c.values);
+                }
+            }
+            let saved = |cost: &ProgramCost, values: &Vec<u64>| {
+                cost.constants
+                    .iter()
+                    .find(|c| c.values == *values)
+                    .and_then(|c| c.saved.first().copied())
+                    .unwrap_or(0)
+            };
+            for values in all_values {
+                let old_saved = saved(self, values);
+                let new_saved = saved(other, values);
+                let delta = new_saved - old_saved;
+                let delta_str = if delta > 0 {
+                    format!("+{}", delta)
+                } else {
+                    format!("{}", delta)
+                };
+                out.push_str(&format!(
+                    "{:<20} {:>9} {:>9}  {:>6}\n",
+                    constant_label(values),
+                    old_saved,
+                    new_saved,
+                    delta_str
+                ));
+            }
+        }
+
         out
     }
 
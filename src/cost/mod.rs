@@ -1,4 +1,6 @@
 pub mod analyzer;
+pub(crate) mod cache;
+mod diff;
 mod json;
 /// Static cost analysis for Trident programs.
 ///
@@ -12,13 +14,18 @@ pub mod stack_verifier;
 mod visit;
 
 // Public re-exports
-pub use analyzer::ProgramCost;
+pub use analyzer::{
+    EntrypointCost, FunctionCost, MaterializedConstant, ModuleCost, ProgramCost, RewriteEstimate,
+    StatementCost,
+};
+pub use diff::{FunctionDiff, LineDiff, SourceDiff};
 pub use model::TableCost;
 
 // Crate-internal re-exports
 pub(crate) use analyzer::CostAnalyzer;
+pub(crate) use diff::{diff_sources, SourceVersion};
 pub(crate) use model::{cost_builtin, create_cost_model};
-pub(crate) use visit::next_power_of_two;
+pub(crate) use visit::{may_call, next_power_of_two};
 
 #[cfg(test)]
 mod tests {
@@ -67,6 +74,11 @@ mod tests {
             padded_height,
             estimated_proving_ns: 0,
             loop_bound_waste: Vec::new(),
+            modules: Vec::new(),
+            constants: Vec::new(),
+            entrypoints: Vec::new(),
+            statements: Vec::new(),
+            rewrites: Vec::new(),
         }
     }
 
@@ -197,6 +209,49 @@ mod tests {
         assert!(cost.total.get(2) > 0, "lt should contribute to u32 table");
     }
 
+    #[test]
+    fn test_digest_comparison_and_conversion_cost() {
+        let cost = crate::analyze_costs(
+            "program test\nfn same(a: Digest, b: Digest) -> Bool {\n    a == b\n}\nfn make() -> Digest {\n    Digest::from_fields(1, 2, 3, 4, 5)\n}\nfn main() {\n    assert(same(make(), divine5()))\n}",
+            "test.tri",
+        )
+        .unwrap();
+        let same = cost.functions.iter().find(|f| f.name == "same").unwrap();
+        // Two copies, then dup, eq and four rounds of swap, dup, eq, mul,
+        // then swap, pop.
+        assert_eq!(same.cost.get(0), 2 + 20);
+        // Building a digest from its elements costs only their pushes.
+        let make = cost.functions.iter().find(|f| f.name == "make").unwrap();
+        assert_eq!(make.cost.get(0), 5);
+    }
+
+    #[test]
+    fn test_short_circuit_and_strict_costs() {
+        let cost = analyze(
+            "program test\nfn lazy(a: Bool, b: Bool) -> Bool {\n    a && b\n}\nfn strict(a: Bool, b: Bool) -> Bool {\n    a & b\n}\nfn main() {\n    let a: Bool = pub_read() == 0\n    assert(lazy(a, a) == strict(a, a))\n}",
+        );
+        let lazy = cost.functions.iter().find(|f| f.name == "lazy").unwrap();
+        let strict = cost.functions.iter().find(|f| f.name == "strict").unwrap();
+        // The branch costs processor and jump stack rows, `&` a u32 lookup.
+        assert!(lazy.cost.get(0) > strict.cost.get(0));
+        assert_eq!(lazy.cost.get(2), 0);
+        assert_eq!(strict.cost.get(2), 33);
+        assert_eq!(lazy.cost.get(5) - strict.cost.get(5), 1);
+    }
+
+    #[test]
+    fn test_add_variant_costs() {
+        let cost = analyze(
+            "program test\nfn wrap(a: U32, b: U32) -> U32 {\n    u32_wrapping_add(a, b)\n}\nfn strict(a: U32, b: U32) -> U32 {\n    u32_assert_add(a, b)\n}\nfn field(a: Field, b: Field) -> Field {\n    field_assert_add(a, b)\n}\nfn main() {\n    let a: U32 = as_u32(pub_read())\n    pub_write(as_field(wrap(a, a)) + as_field(strict(a, a)) + field(1, 2))\n}",
+        );
+        let get = |name: &str| &cost.functions.iter().find(|f| f.name == name).unwrap().cost;
+        // Both U32 variants pay one split; the field check pays four.
+        assert_eq!(get("wrap").get(2), 33);
+        assert_eq!(get("strict").get(2), 33);
+        assert_eq!(get("strict").get(0) - get("wrap").get(0), 2);
+        assert_eq!(get("field").get(2), 4 * 33);
+    }
+
     #[test]
     fn test_reveal_cost_no_hash() {
         let cost = analyze(
@@ -301,6 +356,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_asm_alternatives_cost_only_the_target_block() {
+        let single = analyze("program test\nfn main() {\n    asm { push 1\n pop 1 }\n}");
+        let alternatives = analyze(
+            "program test\nfn main() {\n    asm(triton) { push 1\n pop 1 }\n    asm(miden) { push.1\n drop\n push.2\n drop }\n    asm { nop\n nop\n nop }\n}",
+        );
+        assert_eq!(alternatives.total, single.total);
+    }
+
     #[test]
     fn test_asm_block_comments_not_counted() {
         let cost = analyze(
@@ -451,4 +515,31 @@ mod tests {
             "should contain padded height"
         );
     }
+
+    #[test]
+    fn test_materialized_constants_json_and_comparison() {
+        let old_cost = test_cost(TableCost::from_slice(&[15, 6, 0, 11, 0, 2]), 32);
+        let mut new_cost = old_cost.clone();
+        new_cost.constants.push(MaterializedConstant {
+            values: vec![1, 2, 3, 4, 5],
+            address: 805306368,
+            uses: 12,
+            saved: vec![3, 0, 0, 3, -70, 0],
+        });
+
+        let json = new_cost.to_json();
+        let parsed = ProgramCost::from_json(&json).expect("should parse constants");
+        assert_eq!(parsed.constants, new_cost.constants);
+        assert!(ProgramCost::from_json(&old_cost.to_json())
+            .unwrap()
+            .constants
+            .is_empty());
+
+        let comparison = old_cost.format_comparison(&parsed);
+        assert!(comparison.contains("Materialized constants (cc saved):"));
+        assert!(comparison.contains("[1, 2, 3, 4, 5]              0         3      +3"));
+        assert!(new_cost
+            .format_report()
+            .contains("[1, 2, 3, 4, 5] x12 at 805306368: cc -3, opstack -3, ram +70"));
+    }
 }
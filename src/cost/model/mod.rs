@@ -16,15 +16,30 @@ pub const MAX_TABLES: usize = 8;
 /// Each target VM implements this to provide table names, per-instruction
 /// costs, and formatting for cost reports. The cost analyzer delegates all
 /// target-specific knowledge through this trait.
-pub(crate) trait CostModel {
+pub(crate) trait CostModel: Sync {
     /// Names of the execution tables (e.g. ["processor", "hash", "u32", ...]).
     fn table_names(&self) -> &[&str];
 
     /// Short display names for compact annotations (e.g. ["cc", "hash", "u32", ...]).
     fn table_short_names(&self) -> &[&str];
 
+    /// Per-call cost of each intrinsic, by name. Intrinsics not listed
+    /// cost nothing beyond their call site.
+    fn intrinsic_costs(&self) -> &[(&'static str, TableCost)];
+
     /// Cost of a builtin function call by name.
-    fn builtin_cost(&self, name: &str) -> TableCost;
+    fn builtin_cost(&self, name: &str) -> TableCost {
+        self.intrinsic_costs()
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, cost)| *cost)
+            .unwrap_or(TableCost::ZERO)
+    }
+
+    /// Whether the cost table lists `name`, free intrinsics included.
+    fn has_intrinsic(&self, name: &str) -> bool {
+        self.intrinsic_costs().iter().any(|(n, _)| *n == name)
+    }
 
     /// Cost of a binary operation.
     fn binop_cost(&self, op: &BinOp) -> TableCost;
@@ -112,6 +127,23 @@ impl TableCost {
         }
     }
 
+    /// Per-table `self - other`, clamped at zero.
+    pub fn saturating_sub(&self, other: &TableCost) -> TableCost {
+        let n = self.count.max(other.count) as usize;
+        let mut values = [0u64; MAX_TABLES];
+        for (v, (a, b)) in values
+            .iter_mut()
+            .zip(self.values.iter().zip(&other.values))
+            .take(n)
+        {
+            *v = a.saturating_sub(*b);
+        }
+        TableCost {
+            values,
+            count: n as u8,
+        }
+    }
+
     pub fn scale(&self, factor: u64) -> TableCost {
         let n = self.count as usize;
         let mut values = [0u64; MAX_TABLES];
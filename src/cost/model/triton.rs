@@ -32,6 +32,103 @@ impl TritonCostModel {
     const RAM_RW: TableCost = tc([2, 0, 0, 2, 1, 0]);
     const RAM_BLOCK_RW: TableCost = tc([2, 0, 0, 2, 5, 0]);
     const PURE_PROC: TableCost = tc([1, 0, 0, 0, 0, 0]);
+    const FREE: TableCost = tc([0, 0, 0, 0, 0, 0]);
+
+    /// Per-call cost of each intrinsic. `as_field`, `xfield`, and the
+    /// digest conversions only retype stack elements and are free.
+    const INTRINSICS: &'static [(&'static str, TableCost)] = &[
+        // I/O
+        ("pub_read", Self::SIMPLE_OP),
+        ("pub_read2", Self::SIMPLE_OP),
+        ("pub_read3", Self::SIMPLE_OP),
+        ("pub_read4", Self::SIMPLE_OP),
+        ("pub_read5", Self::SIMPLE_OP),
+        ("pub_write", Self::SIMPLE_OP),
+        ("pub_write2", Self::SIMPLE_OP),
+        ("pub_write3", Self::SIMPLE_OP),
+        ("pub_write4", Self::SIMPLE_OP),
+        ("pub_write5", Self::SIMPLE_OP),
+        // Non-deterministic input
+        ("divine", Self::SIMPLE_OP),
+        ("divine3", Self::SIMPLE_OP),
+        ("divine5", Self::SIMPLE_OP),
+        // Assertions
+        ("assert", Self::SIMPLE_OP),
+        ("assert_eq", Self::ASSERT2),
+        ("assert_digest", Self::ASSERT2),
+        // Debug profile only; priced as compiled
+        ("debug_assert", Self::SIMPLE_OP),
+        // push 0, assert error_id
+        ("fail", Self::ASSERT2),
+        // Field ops
+        ("field_add", Self::SIMPLE_OP),
+        ("field_mul", Self::SIMPLE_OP),
+        ("inv", Self::PURE_PROC),
+        // dup, push 0, eq, push 0, eq, assert, invert
+        ("inverse", tc([7, 0, 0, 6, 0, 0])),
+        ("neg", tc([2, 0, 0, 1, 0, 0])),
+        ("sub", tc([3, 0, 0, 2, 0, 0])),
+        // Overflow of a + b shows as the sum falling below a: dup, add,
+        // split both, lt and eq on the high words, lt on the low ones,
+        // combine, then build the Maybe from the flag (checked: dup,
+        // swap, mul) or assert it
+        (
+            "field_checked_add",
+            tc([25, 0, 4 * Self::U32_WORST, 20, 0, 0]),
+        ),
+        (
+            "field_assert_add",
+            tc([22, 0, 4 * Self::U32_WORST, 19, 0, 0]),
+        ),
+        // U32 ops
+        ("split", Self::U32_OP),
+        ("log2", Self::U32_NOSTACK),
+        ("pow", Self::U32_OP),
+        ("popcount", Self::U32_NOSTACK),
+        // add, split; drop the carry (wrapping), test it and build the
+        // Maybe (checked), or assert it is 0 (assert)
+        ("u32_wrapping_add", tc([4, 0, Self::U32_WORST, 3, 0, 0])),
+        ("u32_checked_add", tc([8, 0, Self::U32_WORST, 6, 0, 0])),
+        ("u32_assert_add", tc([6, 0, Self::U32_WORST, 5, 0, 0])),
+        // Hash ops (6 hash table rows each for Tip5 permutation;
+        // sponge_init only records the reset state: 1 row)
+        ("hash", Self::HASH_OP),
+        ("sponge_init", tc([1, 1, 0, 0, 0, 0])),
+        ("sponge_absorb", Self::HASH_OP),
+        ("sponge_squeeze", Self::HASH_OP),
+        ("sponge_absorb_mem", tc([1, 6, 0, 1, 10, 0])),
+        // Merkle
+        ("merkle_step", tc([1, 6, Self::U32_WORST, 0, 0, 0])),
+        ("merkle_step_mem", tc([1, 6, Self::U32_WORST, 0, 5, 0])),
+        // RAM
+        ("ram_read", Self::RAM_RW),
+        ("ram_write", Self::RAM_RW),
+        ("ram_read_block", Self::RAM_BLOCK_RW),
+        ("ram_write_block", Self::RAM_BLOCK_RW),
+        // Dot steps
+        ("xx_dot_step", tc([1, 0, 0, 0, 6, 0])),
+        ("xb_dot_step", tc([1, 0, 0, 0, 4, 0])),
+        // Recursive verification: claim encoding pads to the rate and
+        // hashes; a proof-stream item is divined, copied, padded, and
+        // absorbed — 12 instructions whatever the item width.
+        ("encode_claim", tc([4, 6, 0, 4, 0, 0])),
+        ("proof_item", tc([12, 6, 0, 12, 0, 0])),
+        ("proof_item_digest", tc([12, 6, 0, 12, 0, 0])),
+        ("proof_item_xfe", tc([12, 6, 0, 12, 0, 0])),
+        // Conversions
+        ("as_u32", tc([2, 0, Self::U32_WORST, 1, 0, 0])),
+        ("as_field", Self::FREE),
+        // Digest: conversions retype the five elements in place;
+        // `a == b` is dup, eq for the first pair, then swap 1, dup,
+        // eq, mul for each other pair, and swap 5, pop 5 to drop `a`
+        ("Digest.from_fields", Self::FREE),
+        ("Digest.from_array", Self::FREE),
+        ("Digest.to_array", Self::FREE),
+        ("Digest.eq", tc([20, 0, 0, 15, 0, 0])),
+        // XField
+        ("xfield", Self::FREE),
+        ("xinvert", Self::PURE_PROC),
+    ];
 }
 
 impl CostModel for TritonCostModel {
@@ -43,66 +140,8 @@ impl CostModel for TritonCostModel {
         &["cc", "hash", "u32", "opst", "ram", "jump"]
     }
 
-    fn builtin_cost(&self, name: &str) -> TableCost {
-        match name {
-            // I/O
-            "pub_read" | "pub_read2" | "pub_read3" | "pub_read4" | "pub_read5" => Self::SIMPLE_OP,
-            "pub_write" | "pub_write2" | "pub_write3" | "pub_write4" | "pub_write5" => {
-                Self::SIMPLE_OP
-            }
-
-            // Non-deterministic input
-            "divine" | "divine3" | "divine5" => Self::SIMPLE_OP,
-
-            // Assertions
-            "assert" => Self::SIMPLE_OP,
-            "assert_eq" => Self::ASSERT2,
-            "assert_digest" => Self::ASSERT2,
-
-            // Field ops
-            "field_add" => Self::SIMPLE_OP,
-            "field_mul" => Self::SIMPLE_OP,
-            "inv" => Self::PURE_PROC,
-            "neg" => tc([2, 0, 0, 1, 0, 0]),
-            "sub" => tc([3, 0, 0, 2, 0, 0]),
-
-            // U32 ops
-            "split" => Self::U32_OP,
-            "log2" => Self::U32_NOSTACK,
-            "pow" => Self::U32_OP,
-            "popcount" => Self::U32_NOSTACK,
-
-            // Hash ops (6 hash table rows each for Tip5 permutation)
-            "hash" => Self::HASH_OP,
-            "sponge_init" => tc([1, 6, 0, 0, 0, 0]),
-            "sponge_absorb" => Self::HASH_OP,
-            "sponge_squeeze" => Self::HASH_OP,
-            "sponge_absorb_mem" => tc([1, 6, 0, 1, 10, 0]),
-
-            // Merkle
-            "merkle_step" => tc([1, 6, Self::U32_WORST, 0, 0, 0]),
-            "merkle_step_mem" => tc([1, 6, Self::U32_WORST, 0, 5, 0]),
-
-            // RAM
-            "ram_read" => Self::RAM_RW,
-            "ram_write" => Self::RAM_RW,
-            "ram_read_block" => Self::RAM_BLOCK_RW,
-            "ram_write_block" => Self::RAM_BLOCK_RW,
-
-            // Dot steps
-            "xx_dot_step" => tc([1, 0, 0, 0, 6, 0]),
-            "xb_dot_step" => tc([1, 0, 0, 0, 4, 0]),
-
-            // Conversions
-            "as_u32" => tc([2, 0, Self::U32_WORST, 1, 0, 0]),
-            "as_field" => TableCost::ZERO,
-
-            // XField
-            "xfield" => TableCost::ZERO,
-            "xinvert" => Self::PURE_PROC,
-
-            _ => TableCost::ZERO,
-        }
+    fn intrinsic_costs(&self) -> &[(&'static str, TableCost)] {
+        Self::INTRINSICS
     }
 
     fn binop_cost(&self, op: &BinOp) -> TableCost {
@@ -113,6 +152,10 @@ impl CostModel for TritonCostModel {
             BinOp::Lt => Self::U32_OP,
             BinOp::BitAnd => Self::U32_OP,
             BinOp::BitXor => Self::U32_OP,
+            // (a ^ b) + (a & b): two u32 ops plus copies and the add.
+            BinOp::BitOr => tc([6, 0, 66, 6, 0, 0]),
+            // A branch and the constant pushed by the side not taken.
+            BinOp::And | BinOp::Or => tc([4, 0, 0, 3, 0, 1]),
             BinOp::DivMod => Self::U32_NOSTACK,
             BinOp::XFieldMul => Self::SIMPLE_OP,
         }
@@ -1,5 +1,5 @@
 use super::analyzer::{FunctionCost, ProgramCost};
-use super::visit::next_power_of_two;
+use super::json::constant_label;
 use crate::diagnostic::Diagnostic;
 use crate::span::Span;
 
@@ -63,6 +63,39 @@ impl ProgramCost {
         let tenths = (self.estimated_proving_ns / 100_000_000) % 10;
         out.push_str(&format!("Estimated proving time:  ~{}.{}s\n", secs, tenths));
 
+        if !self.constants.is_empty() {
+            out.push_str(&format!(
+                "Materialized constants:  {}\n",
+                self.constants.len()
+            ));
+            for c in &self.constants {
+                let saved: Vec<String> = short
+                    .iter()
+                    .zip(&c.saved)
+                    .filter(|(_, v)| **v != 0)
+                    .map(|(n, v)| format!("{} {:+}", n, -v))
+                    .collect();
+                out.push_str(&format!(
+                    "  {} x{} at {}: {}\n",
+                    constant_label(&c.values),
+                    c.uses,
+                    c.address,
+                    saved.join(", ")
+                ));
+            }
+        }
+
+        if !self.entrypoints.is_empty() {
+            out.push_str("\nEntrypoints (worst case per operation):\n");
+            for e in &self.entrypoints {
+                out.push_str(&format!("{:<24}", format!("{:>4}  {}", e.selector, e.name)));
+                for i in 0..n {
+                    out.push_str(&format!(" {:>6}", e.total.get(i)));
+                }
+                out.push_str(&format!("  padded {}\n", e.padded_height));
+            }
+        }
+
         // Power-of-2 boundary warning.
         let headroom = self.padded_height - self.total.max_height();
         if headroom < self.padded_height / 8 {
@@ -81,6 +114,9 @@ impl ProgramCost {
     }
 
     /// Format a hotspots report (top N cost contributors).
+    ///
+    /// A project report ranks the functions of every module; functions of
+    /// dependencies are named by their module.
     pub fn format_hotspots(&self, top_n: usize) -> String {
         let short = self.short_names();
         let mut out = String::new();
@@ -90,14 +126,29 @@ impl ProgramCost {
         let dominant_idx = self.dominant_index();
         let dominant_total = self.total.get(dominant_idx);
 
-        let mut ranked: Vec<&FunctionCost> = self.functions.iter().collect();
-        ranked.sort_by(|a, b| {
+        let mut ranked: Vec<(String, &FunctionCost)> = if self.modules.is_empty() {
+            self.functions.iter().map(|f| (f.name.clone(), f)).collect()
+        } else {
+            self.modules
+                .iter()
+                .flat_map(|m| {
+                    m.functions.iter().map(move |f| {
+                        if m.module == self.program_name {
+                            (f.name.clone(), f)
+                        } else {
+                            (format!("{}.{}", m.module, f.name), f)
+                        }
+                    })
+                })
+                .collect()
+        };
+        ranked.sort_by(|(_, a), (_, b)| {
             let av = a.cost.get(dominant_idx);
             let bv = b.cost.get(dominant_idx);
             bv.cmp(&av)
         });
 
-        for (i, func) in ranked.iter().take(top_n).enumerate() {
+        for (i, (name, func)) in ranked.iter().take(top_n).enumerate() {
             let val = func.cost.get(dominant_idx);
             let pct = if dominant_total > 0 {
                 val * 100 / dominant_total
@@ -107,7 +158,7 @@ impl ProgramCost {
             out.push_str(&format!(
                 "  {}. {:<24} {:>6} {} rows ({}% of {} table)\n",
                 i + 1,
-                func.name,
+                name,
                 val,
                 dominant,
                 pct,
@@ -123,7 +174,7 @@ impl ProgramCost {
         out
     }
 
-    /// Generate optimization hints (H0001, H0002, H0004).
+    /// Generate optimization hints (H0001, H0002, H0004, H0007).
     pub fn optimization_hints(&self) -> Vec<Diagnostic> {
         let short = self.short_names();
         let mut hints = Vec::new();
@@ -227,13 +278,86 @@ impl ProgramCost {
                     ratio
                 ));
                 diag.help = Some(format!(
-                    "tightening the bound to {} would reduce worst-case cost",
-                    next_power_of_two(*end_val)
+                    "the end is known at compile time; drop `bounded {}` to count exactly {} iterations",
+                    bound, end_val
                 ));
                 hints.push(diag);
             }
         }
 
+        // H0007: What-if for the dominant table — the rows to remove to
+        // halve the padded height, where they are, and what known rewrites
+        // would save.
+        let half = self.padded_height / 2;
+        if self.padded_height >= 16
+            && self.attestation_hash_rows <= half
+            && self.total.max_height() > half
+        {
+            let dominant_idx = self.dominant_index();
+            let dominant_name = short.get(dominant_idx).unwrap_or(&"?");
+            let dominant_total = self.total.get(dominant_idx);
+            let needed = dominant_total - half;
+            let mut diag = Diagnostic::warning(
+                format!(
+                    "hint[H0007]: remove {} {} rows to halve padded height to {}",
+                    needed, dominant_name, half
+                ),
+                Span::dummy(),
+            );
+            for (i, name) in short.iter().enumerate() {
+                if i != dominant_idx && self.total.get(i) > half {
+                    diag.notes.push(format!(
+                        "{} table must also lose {} rows",
+                        name,
+                        self.total.get(i) - half
+                    ));
+                }
+            }
+            let mut statements: Vec<_> = self
+                .statements
+                .iter()
+                .filter(|s| s.cost.get(dominant_idx) > 0)
+                .collect();
+            statements.sort_by_key(|s| (std::cmp::Reverse(s.cost.get(dominant_idx)), s.line));
+            for s in statements.iter().take(3) {
+                let rows = s.cost.get(dominant_idx);
+                diag.notes.push(format!(
+                    "line {}: {} {} rows ({}% of the table)",
+                    s.line,
+                    rows,
+                    dominant_name,
+                    rows * 100 / dominant_total.max(1)
+                ));
+            }
+            let mut rewrites: Vec<_> = self
+                .rewrites
+                .iter()
+                .filter(|r| r.saved.get(dominant_idx) > 0)
+                .collect();
+            rewrites.sort_by_key(|r| (std::cmp::Reverse(r.saved.get(dominant_idx)), r.line));
+            for r in &rewrites {
+                diag.notes.push(format!(
+                    "line {}: {} saves ~{} {} rows",
+                    r.line,
+                    r.description,
+                    r.saved.get(dominant_idx),
+                    dominant_name
+                ));
+            }
+            diag.help = Some(match rewrites.first() {
+                Some(r) if r.saved.get(dominant_idx) >= needed => format!(
+                    "{} (line {}) alone would halve proving cost",
+                    r.description, r.line
+                ),
+                Some(_) => {
+                    "no known rewrite alone is enough; combine them or shrink the statements above"
+                        .to_string()
+                }
+                None => "no known rewrite applies; shrink the statements above".to_string(),
+            });
+            hints.push(diag);
+        }
+
         hints
     }
 }
@@ -37,41 +37,6 @@ impl TableProfile {
     pub fn cost(&self) -> u64 {
         self.padded_height()
     }
-
-    /// Index of the tallest table.
-    pub fn dominant_table(&self) -> usize {
-        self.heights
-            .iter()
-            .enumerate()
-            .max_by_key(|(_, h)| *h)
-            .map(|(i, _)| i)
-            .unwrap_or(0)
-    }
-
-    /// Table name for display.
-    pub fn table_name(idx: usize) -> &'static str {
-        match idx {
-            PROC => "processor",
-            HASH => "hash",
-            U32 => "u32",
-            OPST => "op_stack",
-            RAM => "ram",
-            JUMP => "jump_stack",
-            _ => "unknown",
-        }
-    }
-
-    /// Whether the neural candidate crossed a power-of-2 boundary
-    /// that the baseline did not.
-    pub fn is_cliff_jump(&self, baseline: &TableProfile) -> bool {
-        self.padded_height() < baseline.padded_height()
-    }
-
-    /// Whether the dominant (tallest) table changed between baseline and this profile.
-    pub fn is_table_rebalance(&self, baseline: &TableProfile) -> bool {
-        self.dominant_table() != baseline.dominant_table()
-            && self.max_height() < baseline.max_height()
-    }
 }
 
 /// Profile a sequence of TASM instruction lines, counting table row increments.
@@ -137,7 +102,7 @@ fn profile_instruction(instr: &str, p: &mut TableProfile) {
         }
         "sponge_init" => {
             p.heights[PROC] += 1;
-            p.heights[HASH] += 6;
+            p.heights[HASH] += 1;
         }
         "sponge_absorb" | "sponge_squeeze" => {
             p.heights[PROC] += 1;
@@ -261,7 +226,6 @@ mod tests {
         let p = profile_tasm(&["hash", "hash", "hash"]);
         assert_eq!(p.heights[PROC], 3);
         assert_eq!(p.heights[HASH], 18);
-        assert_eq!(p.dominant_table(), HASH);
     }
 
     #[test]
@@ -276,31 +240,6 @@ mod tests {
         assert_eq!(p.padded_height(), 2048);
     }
 
-    #[test]
-    fn cliff_jump_detection() {
-        let mut baseline = TableProfile::default();
-        baseline.heights[PROC] = 1025;
-
-        let mut candidate = TableProfile::default();
-        candidate.heights[PROC] = 1024;
-
-        assert!(candidate.is_cliff_jump(&baseline));
-        assert!(!baseline.is_cliff_jump(&candidate));
-    }
-
-    #[test]
-    fn table_rebalance_detection() {
-        let mut baseline = TableProfile::default();
-        baseline.heights[PROC] = 1000;
-        baseline.heights[HASH] = 500;
-
-        let mut candidate = TableProfile::default();
-        candidate.heights[PROC] = 700;
-        candidate.heights[HASH] = 700;
-
-        assert!(candidate.is_table_rebalance(&baseline));
-    }
-
     #[test]
     fn labels_and_comments_ignored() {
         let p = profile_tasm(&["__main:", "  // comment", "  push 1", ""]);
@@ -414,6 +414,7 @@ impl StackState {
     }
 
     /// Check if execution completed without errors.
+    #[cfg(test)]
     pub fn is_valid(&self) -> bool {
         !self.error
     }
@@ -627,178 +628,6 @@ pub fn verify_equivalent(baseline_tasm: &[String], candidate_tasm: &[String], se
     true
 }
 
-/// Score how close a candidate is to matching the baseline.
-/// Runs both on one test stack and returns a shaped fitness score:
-///   0   = candidate crashes
-///   100 = doesn't crash
-///   200 = stack depth matches
-///   400 = 50%+ of stack values match
-///   600 = 90%+ of stack values match
-///   800 = all stack values match
-///   900 = stack + all side-channels match on this stack
-pub fn score_candidate(baseline_tasm: &[String], candidate_tasm: &[String], seed: u64) -> i64 {
-    let test_stack = generate_test_stack(seed.wrapping_mul(6364136223846793005), 16);
-
-    let mut bl = StackState::new(test_stack.clone());
-    bl.execute(baseline_tasm);
-    if bl.error {
-        return 0;
-    }
-
-    let mut cd = StackState::new(test_stack);
-    cd.execute(candidate_tasm);
-
-    if cd.error {
-        return 0;
-    }
-    let mut score: i64 = 100;
-
-    if bl.stack.len() == cd.stack.len() {
-        score = 200;
-        let matches = bl
-            .stack
-            .iter()
-            .zip(&cd.stack)
-            .filter(|(a, b)| a == b)
-            .count();
-        let total = bl.stack.len().max(1);
-        let ratio = matches as f64 / total as f64;
-        if ratio >= 0.5 {
-            score = 400;
-        }
-        if ratio >= 0.9 {
-            score = 600;
-        }
-        if matches == total {
-            score = 800;
-        }
-    }
-
-    if score >= 800
-        && bl.halted == cd.halted
-        && bl.io_output == cd.io_output
-        && bl.divine_log == cd.divine_log
-        && bl.assert_log == cd.assert_log
-        && bl.assert_vector_log == cd.assert_vector_log
-    {
-        score = 900;
-    }
-
-    score
-}
-
-/// Diagnose why verification failed for a candidate vs baseline.
-/// Runs both on the first test stack and reports what differs.
-/// Returns a short human-readable reason string.
-pub fn diagnose_failure(baseline_tasm: &[String], candidate_tasm: &[String], seed: u64) -> String {
-    let test_stack = generate_test_stack(seed.wrapping_mul(6364136223846793005), 16);
-
-    let mut bl = StackState::new(test_stack.clone());
-    bl.execute(baseline_tasm);
-    if bl.error {
-        return "baseline errors on test stack".into();
-    }
-
-    let mut cd = StackState::new(test_stack);
-    cd.execute(candidate_tasm);
-    if cd.error {
-        return "candidate errors on test stack".into();
-    }
-
-    if bl.stack != cd.stack {
-        let bl_len = bl.stack.len();
-        let cd_len = cd.stack.len();
-        if bl_len != cd_len {
-            return format!("stack depth: baseline={} candidate={}", bl_len, cd_len);
-        }
-        for i in 0..bl_len {
-            if bl.stack[i] != cd.stack[i] {
-                return format!(
-                    "stack[{}]: baseline={} candidate={} (depth {})",
-                    i, bl.stack[i], cd.stack[i], bl_len
-                );
-            }
-        }
-    }
-    if bl.halted != cd.halted {
-        return format!("halted: baseline={} candidate={}", bl.halted, cd.halted);
-    }
-    if bl.io_output != cd.io_output {
-        return format!(
-            "io_output: baseline={:?} candidate={:?}",
-            &bl.io_output[..bl.io_output.len().min(5)],
-            &cd.io_output[..cd.io_output.len().min(5)]
-        );
-    }
-    if bl.divine_log != cd.divine_log {
-        return format!(
-            "divine_log: baseline={:?} candidate={:?}",
-            bl.divine_log, cd.divine_log
-        );
-    }
-    if bl.assert_log != cd.assert_log {
-        return format!(
-            "assert_log: baseline={:?} candidate={:?}",
-            &bl.assert_log[..bl.assert_log.len().min(5)],
-            &cd.assert_log[..cd.assert_log.len().min(5)]
-        );
-    }
-    if bl.assert_vector_log != cd.assert_vector_log {
-        return "assert_vector_log differs".into();
-    }
-    // Passed on this test stack but fails on others
-    "passes first stack, fails on structured stacks".into()
-}
-
-/// Score a neural model's raw output against a baseline block.
-/// Decodes the output, verifies equivalence, and returns the lower cost
-/// (or baseline cost if candidate is invalid/worse).
-pub fn score_neural_output(
-    raw_codes: &[u32],
-    block_baseline: u64,
-    baseline_tasm: &[String],
-    block_seed: u64,
-) -> u64 {
-    use crate::ir::tir::lower::decode_output;
-
-    let codes: Vec<u64> = raw_codes
-        .iter()
-        .take_while(|&&c| c != 0)
-        .map(|&c| c as u64)
-        .collect();
-    if codes.is_empty() {
-        return block_baseline;
-    }
-    let candidate_lines = decode_output(&codes);
-    if candidate_lines.is_empty() {
-        return block_baseline;
-    }
-    // No baseline = nothing to verify against = reject.
-    if baseline_tasm.is_empty() || !verify_equivalent(baseline_tasm, &candidate_lines, block_seed) {
-        return block_baseline;
-    }
-    let profile = crate::cost::scorer::profile_tasm(
-        &candidate_lines
-            .iter()
-            .map(|s| s.as_str())
-            .collect::<Vec<_>>(),
-    );
-    profile.cost().min(block_baseline)
-}
-
-/// Score improvement of a neural candidate over baseline.
-/// Returns 0 for failures or equal/worse cost, positive value for genuine wins.
-/// Used by training to reward only actual improvement (not negated cost).
-pub fn score_neural_improvement(
-    raw_codes: &[u32],
-    block_baseline: u64,
-    baseline_tasm: &[String],
-    block_seed: u64,
-) -> u64 {
-    let cost = score_neural_output(raw_codes, block_baseline, baseline_tasm, block_seed);
-    block_baseline.saturating_sub(cost)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1095,48 +924,4 @@ mod tests {
         ]);
         assert!(!verify_equivalent(&baseline, &candidate, 66));
     }
-
-    #[test]
-    fn score_candidate_crash_returns_zero() {
-        let baseline = lines(&["push 1", "push 2", "add"]);
-        // Pop everything (16 initial + 0 pushed), then pop again → underflow
-        let candidate = lines(&["pop 5", "pop 5", "pop 5", "pop 5", "pop 1"]);
-        assert_eq!(score_candidate(&baseline, &candidate, 42), 0);
-    }
-
-    #[test]
-    fn score_candidate_no_crash_wrong_depth() {
-        let baseline = lines(&["push 1", "push 2", "add"]); // depth +1
-        let candidate = lines(&["push 1", "push 2"]); // depth +2
-        let score = score_candidate(&baseline, &candidate, 42);
-        assert_eq!(score, 100); // doesn't crash, but wrong depth
-    }
-
-    #[test]
-    fn score_candidate_right_depth_wrong_values() {
-        let baseline = lines(&["push 1"]); // pushes 1
-        let candidate = lines(&["push 2"]); // pushes 2, same depth
-        let score = score_candidate(&baseline, &candidate, 42);
-        assert!(
-            score >= 200,
-            "right depth should score >= 200, got {}",
-            score
-        );
-    }
-
-    #[test]
-    fn score_candidate_identical_scores_900() {
-        let baseline = lines(&["push 1", "push 2", "add"]);
-        let candidate = lines(&["push 1", "push 2", "add"]);
-        let score = score_candidate(&baseline, &candidate, 42);
-        assert_eq!(score, 900);
-    }
-
-    #[test]
-    fn score_candidate_nop_equivalent_scores_900() {
-        let baseline = lines(&["push 5"]);
-        let candidate = lines(&["push 5", "nop"]);
-        let score = score_candidate(&baseline, &candidate, 42);
-        assert_eq!(score, 900);
-    }
 }
@@ -1,6 +1,11 @@
-use super::analyzer::CostAnalyzer;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::analyzer::{CostAnalyzer, ProgramCost, RewriteEstimate, StatementCost};
 use super::model::TableCost;
+use crate::ast::visit::{walk, Visitor};
 use crate::ast::*;
+use crate::span::Spanned;
 
 // --- Per-function cost result ---
 
@@ -10,6 +15,8 @@ impl<'a> CostAnalyzer<'a> {
     pub(crate) fn cost_expr(&mut self, expr: &Expr) -> TableCost {
         let stack_op = self.cost_model.stack_op();
         match expr {
+            // Assertion messages and trace labels emit nothing.
+            Expr::Literal(Literal::Str(_)) => TableCost::ZERO,
             Expr::Literal(_) => {
                 // push instruction: 1 cc, 1 opstack.
                 stack_op
@@ -25,10 +32,19 @@ impl<'a> CostAnalyzer<'a> {
             }
             Expr::Call { path, args, .. } => {
                 let fn_name = path.node.as_dotted();
+                // `assume` compiles to nothing, arguments and all.
+                if fn_name == "assume" {
+                    return TableCost::ZERO;
+                }
                 let args_cost = args
                     .iter()
                     .fold(TableCost::ZERO, |acc, a| acc.add(&self.cost_expr(&a.node)));
 
+                // `trace` only emits a hint, which is free, and pops its value.
+                if fn_name == "trace" {
+                    return args_cost.add(&stack_op);
+                }
+
                 // Check if it's a builtin — try full name first, then short name
                 // to handle cross-module calls like "hash.tip5" → "tip5" → "hash"
                 let base_name = fn_name.rsplit('.').next().unwrap_or(&fn_name);
@@ -40,12 +56,14 @@ impl<'a> CostAnalyzer<'a> {
                         self.cost_model.builtin_cost(base_name)
                     }
                 };
-                if fn_cost.is_nonzero() {
+                if fn_cost.is_nonzero() || self.cost_model.has_intrinsic(&fn_name) {
                     // Builtin: use the cost table.
                     args_cost.add(&fn_cost)
                 } else {
                     // User-defined: look up body cost + call overhead.
-                    let body_cost = if let Some(func) = self.fn_bodies.get(base_name).cloned() {
+                    let body_cost = if let Some(c) = self.cost_imported_call(&fn_name) {
+                        c
+                    } else if let Some(func) = self.fn_bodies.get(base_name).cloned() {
                         self.cost_fn(&func)
                     } else {
                         TableCost::ZERO
@@ -81,23 +99,38 @@ impl<'a> CostAnalyzer<'a> {
     pub(crate) fn find_loop_iteration_cost(&mut self, func: &FnDef) -> Option<(TableCost, u64)> {
         if let Some(body) = &func.body {
             for stmt in &body.node.stmts {
-                if let Stmt::For {
-                    bound,
-                    body: loop_body,
-                    end,
-                    ..
-                } = &stmt.node
-                {
-                    let body_cost = self.cost_block(&loop_body.node);
-                    let per_iter = body_cost.add(&self.cost_model.loop_overhead());
-                    let iterations = if let Some(b) = bound {
-                        *b
-                    } else if let Expr::Literal(Literal::Integer(n)) = &end.node {
-                        *n
-                    } else {
-                        1
-                    };
-                    return Some((per_iter, iterations));
+                match &stmt.node {
+                    Stmt::For {
+                        bound,
+                        body: loop_body,
+                        end,
+                        ..
+                    } => {
+                        let body_cost = self.cost_block(&loop_body.node);
+                        let per_iter = body_cost.add(&self.cost_model.loop_overhead());
+                        let declared = bound.as_ref().and_then(|b| integer_literal(&b.node));
+                        let iterations = if let Some(b) = declared {
+                            b
+                        } else if let Expr::Literal(Literal::Integer(n)) = &end.node {
+                            *n
+                        } else {
+                            1
+                        };
+                        return Some((per_iter, iterations));
+                    }
+                    Stmt::While {
+                        cond,
+                        bound,
+                        body: loop_body,
+                    } => {
+                        let per_iter = self
+                            .cost_expr(&cond.node)
+                            .add(&self.cost_block(&loop_body.node))
+                            .add(&self.cost_model.if_overhead())
+                            .add(&self.cost_model.loop_overhead());
+                        return Some((per_iter, integer_literal(&bound.node).unwrap_or(1)));
+                    }
+                    _ => {}
                 }
             }
         }
@@ -110,31 +143,15 @@ impl<'a> CostAnalyzer<'a> {
     /// along with the 1-based line number derived from the statement's span.
     /// Also records function definition lines with call overhead.
     pub(crate) fn stmt_costs(&mut self, file: &File, source: &str) -> Vec<(u32, TableCost)> {
-        // Build line offset table: line_starts[i] = byte offset of line i+1
-        let line_starts: Vec<u32> = std::iter::once(0)
-            .chain(source.bytes().enumerate().filter_map(|(i, b)| {
-                if b == b'\n' {
-                    Some((i + 1) as u32)
-                } else {
-                    None
-                }
-            }))
-            .collect();
-
-        let byte_to_line = |offset: u32| -> u32 {
-            match line_starts.binary_search(&offset) {
-                Ok(i) => (i + 1) as u32,
-                Err(i) => i as u32,
-            }
-        };
+        let byte_to_line = line_index(source);
 
         // Ensure fn_bodies are populated (analyze_file does this, but in case
         // stmt_costs is called standalone)
         for item in &file.items {
             if let Item::Fn(func) = &item.node {
-                self.fn_bodies
+                Arc::make_mut(&mut self.fn_bodies)
                     .entry(func.name.node.clone())
-                    .or_insert_with(|| func.clone());
+                    .or_insert_with(|| Arc::new(func.clone()));
             }
         }
 
@@ -180,7 +197,7 @@ impl<'a> CostAnalyzer<'a> {
                         self.collect_block_costs(&eb.node, byte_to_line, result);
                     }
                 }
-                Stmt::For { body, .. } => {
+                Stmt::For { body, .. } | Stmt::While { body, .. } => {
                     self.collect_block_costs(&body.node, byte_to_line, result);
                 }
                 Stmt::Match { arms, .. } => {
@@ -201,51 +218,248 @@ impl<'a> CostAnalyzer<'a> {
 
     /// H0004: scan a block for loops where declared bound >> constant end value.
     pub(crate) fn scan_loop_bound_waste(&mut self, fn_name: &str, block: &Block) {
-        for stmt in &block.stmts {
-            if let Stmt::For {
-                end, bound, body, ..
-            } = &stmt.node
-            {
+        struct Scan<'w> {
+            fn_name: &'w str,
+            waste: &'w mut Vec<(String, u64, u64)>,
+        }
+        impl Visitor for Scan<'_> {
+            fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
                 // Check if end is a constant and bound is declared
-                if let (Some(declared_bound), Expr::Literal(Literal::Integer(end_val))) =
-                    (bound, &end.node)
+                if let Stmt::For {
+                    end,
+                    bound: Some(declared_bound),
+                    ..
+                } = &stmt.node
                 {
-                    if *declared_bound > *end_val * 4 && *declared_bound > 8 {
-                        self.loop_bound_waste.push((
-                            fn_name.to_string(),
-                            *end_val,
-                            *declared_bound,
-                        ));
+                    if let (Some(end_val), Some(declared_bound)) = (
+                        integer_literal(&end.node),
+                        integer_literal(&declared_bound.node),
+                    ) {
+                        if declared_bound > end_val * 4 && declared_bound > 8 {
+                            self.waste
+                                .push((self.fn_name.to_string(), end_val, declared_bound));
+                        }
                     }
                 }
-                // Recurse into loop body
-                self.scan_loop_bound_waste(fn_name, &body.node);
+                walk::stmt(self, stmt);
+            }
+        }
+        Scan {
+            fn_name,
+            waste: &mut self.loop_bound_waste,
+        }
+        .visit_block(block);
+    }
+
+    /// Fill the inputs of the dominant-table what-if (hint H0007): the
+    /// program file's statement costs, by line, and the known rewrites it
+    /// admits. Call after `analyze_file` on the same file.
+    pub(crate) fn attach_what_if(&mut self, file: &File, source: &str, cost: &mut ProgramCost) {
+        let mut lines: BTreeMap<u32, TableCost> = BTreeMap::new();
+        for (line, c) in self.stmt_costs(file, source) {
+            let entry = lines.entry(line).or_insert(TableCost::ZERO);
+            *entry = entry.add(&c);
+        }
+        cost.statements = lines
+            .into_iter()
+            .map(|(line, cost)| StatementCost { line, cost })
+            .collect();
+
+        let byte_to_line = line_index(source);
+        let mut rewrites = Vec::new();
+        for item in &file.items {
+            if let Item::Fn(func) = &item.node {
+                if let Some(body) = &func.body {
+                    self.collect_rewrites(&body.node, 1, &byte_to_line, &mut rewrites);
+                }
             }
-            // Recurse into if/else blocks
-            if let Stmt::If {
-                then_block,
-                else_block,
-                ..
-            } = &stmt.node
+        }
+        cost.rewrites = rewrites;
+    }
+
+    /// Rewrites of `block`, which runs `times` times: loop bounds above
+    /// the next power of two of a constant trip count, runs of `hash`
+    /// calls that fill at most half their inputs, which could share
+    /// permutations, and `&&`/`||` that could drop their branch.
+    fn collect_rewrites(
+        &mut self,
+        block: &Block,
+        times: u64,
+        byte_to_line: &dyn Fn(u32) -> u32,
+        out: &mut Vec<RewriteEstimate>,
+    ) {
+        // (line, inputs used) of the sparse hash calls of this block.
+        let mut sparse: Vec<(u32, u64)> = Vec::new();
+        for stmt in &block.stmts {
+            if let Stmt::Let { init: e, .. }
+            | Stmt::Assign { value: e, .. }
+            | Stmt::If { cond: e, .. }
+            | Stmt::Expr(e)
+            | Stmt::Return(Some(e)) = &stmt.node
             {
-                self.scan_loop_bound_waste(fn_name, &then_block.node);
-                if let Some(eb) = else_block {
-                    self.scan_loop_bound_waste(fn_name, &eb.node);
+                self.collect_strict_logic(&e.node, byte_to_line(stmt.span.start), times, out);
+            }
+            match &stmt.node {
+                Stmt::Let { init: e, .. } | Stmt::Expr(e) => {
+                    if let Expr::Call { path, args, .. } = &e.node {
+                        let used = args
+                            .iter()
+                            .filter(|a| !matches!(a.node, Expr::Literal(Literal::Integer(0))))
+                            .count() as u64;
+                        if path.node.as_dotted() == "hash" && used * 2 <= args.len() as u64 {
+                            sparse.push((byte_to_line(stmt.span.start), used));
+                        }
+                    }
+                }
+                Stmt::For {
+                    end, bound, body, ..
+                } => {
+                    let trips = integer_literal(&end.node);
+                    let bound = bound.as_ref().and_then(|b| integer_literal(&b.node));
+                    if let (Some(bound), Some(trips)) = (bound, trips) {
+                        let tight = next_power_of_two(trips);
+                        if bound > tight {
+                            let per_iteration = self
+                                .cost_block(&body.node)
+                                .add(&self.cost_model.loop_overhead());
+                            out.push(RewriteEstimate {
+                                line: byte_to_line(stmt.span.start),
+                                description: format!("tighten `bounded {}` to {}", bound, tight),
+                                saved: per_iteration.scale((bound - tight) * times),
+                            });
+                        }
+                    }
+                    let runs = bound.or(trips).unwrap_or(1);
+                    self.collect_rewrites(
+                        &body.node,
+                        times.saturating_mul(runs),
+                        byte_to_line,
+                        out,
+                    );
+                }
+                Stmt::While { cond, bound, body } => {
+                    let runs = times.saturating_mul(integer_literal(&bound.node).unwrap_or(1));
+                    let line = byte_to_line(stmt.span.start);
+                    self.collect_strict_logic(&cond.node, line, runs, out);
+                    self.collect_rewrites(&body.node, runs, byte_to_line, out);
+                }
+                Stmt::If {
+                    then_block,
+                    else_block,
+                    ..
+                } => {
+                    self.collect_rewrites(&then_block.node, times, byte_to_line, out);
+                    if let Some(eb) = else_block {
+                        self.collect_rewrites(&eb.node, times, byte_to_line, out);
+                    }
                 }
+                Stmt::Match { arms, .. } => {
+                    for arm in arms {
+                        self.collect_rewrites(&arm.body.node, times, byte_to_line, out);
+                    }
+                }
+                _ => {}
             }
-            // Recurse into match arms
-            if let Stmt::Match { arms, .. } = &stmt.node {
-                for arm in arms {
-                    self.scan_loop_bound_waste(fn_name, &arm.body.node);
+        }
+        if sparse.len() > 1 {
+            let calls = sparse.len() as u64;
+            let packed = sparse
+                .iter()
+                .map(|(_, used)| used)
+                .sum::<u64>()
+                .div_ceil(10)
+                .max(1);
+            if packed < calls {
+                out.push(RewriteEstimate {
+                    line: sparse[0].0,
+                    description: format!("batch {} hash calls into {}", calls, packed),
+                    saved: self
+                        .cost_model
+                        .builtin_cost("hash")
+                        .scale((calls - packed) * times),
+                });
+            }
+        }
+    }
+
+    /// `&&` and `||` in `expr`, which runs `times` times, whose right side
+    /// calls nothing: the strict `&` and `|` evaluate it without a branch.
+    fn collect_strict_logic(
+        &mut self,
+        expr: &Expr,
+        line: u32,
+        times: u64,
+        out: &mut Vec<RewriteEstimate>,
+    ) {
+        match expr {
+            Expr::BinOp { op, lhs, rhs } => {
+                let strict = match op {
+                    BinOp::And => Some(BinOp::BitAnd),
+                    BinOp::Or => Some(BinOp::BitOr),
+                    _ => None,
+                };
+                if let Some(strict) = strict.filter(|_| !may_call(&rhs.node)) {
+                    let saved = self
+                        .cost_model
+                        .binop_cost(op)
+                        .saturating_sub(&self.cost_model.binop_cost(&strict));
+                    if saved.is_nonzero() {
+                        out.push(RewriteEstimate {
+                            line,
+                            description: format!(
+                                "evaluate `{}` strictly as `{}`",
+                                op.as_str(),
+                                strict.as_str()
+                            ),
+                            saved: saved.scale(times),
+                        });
+                    }
+                }
+                self.collect_strict_logic(&lhs.node, line, times, out);
+                self.collect_strict_logic(&rhs.node, line, times, out);
+            }
+            Expr::Call { args, .. } => {
+                for arg in args {
+                    self.collect_strict_logic(&arg.node, line, times, out);
                 }
             }
+            _ => {}
         }
     }
 }
 
+/// Whether evaluating `expr` may call a function (and so assert or do
+/// I/O), conservatively.
+pub(crate) fn may_call(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(_) | Expr::Var(_) => false,
+        Expr::BinOp { lhs, rhs, .. } => may_call(&lhs.node) || may_call(&rhs.node),
+        Expr::FieldAccess { expr, .. } => may_call(&expr.node),
+        _ => true,
+    }
+}
+
 /// Smallest power of 2 >= n.
 ///
 /// Delegates to `field::proof::padded_height` — same formula, different name.
 pub(crate) fn next_power_of_two(n: u64) -> u64 {
     crate::field::proof::padded_height(n)
 }
+
+/// Map a byte offset of `source` to its 1-based line.
+fn line_index(source: &str) -> impl Fn(u32) -> u32 {
+    // line_starts[i] = byte offset of line i+1
+    let line_starts: Vec<u32> = std::iter::once(0)
+        .chain(source.bytes().enumerate().filter_map(|(i, b)| {
+            if b == b'\n' {
+                Some((i + 1) as u32)
+            } else {
+                None
+            }
+        }))
+        .collect();
+    move |offset: u32| match line_starts.binary_search(&offset) {
+        Ok(i) => (i + 1) as u32,
+        Err(i) => i as u32,
+    }
+}
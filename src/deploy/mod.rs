@@ -17,8 +17,12 @@ use crate::ast;
 use crate::ast::display::format_ast_type;
 use crate::cost::ProgramCost;
 use crate::hash::ContentHash;
+use crate::runtime::artifact::{BundleCost, ProgramBundle};
 use crate::target::{Arch, TerrainConfig, UnionConfig};
 
+pub mod rpc;
+pub mod state;
+
 // ─── Data Types ────────────────────────────────────────────────────
 
 /// Package manifest — all metadata about a packaged program artifact.
@@ -35,6 +39,9 @@ pub struct PackageManifest {
     pub architecture: String,
     pub cost: ManifestCost,
     pub functions: Vec<ManifestFunction>,
+    /// On-chain commitments the program reads as public input, in order
+    /// (see `deploy::state`).
+    pub commitments: Vec<String>,
     pub entry_point: String,
     /// ISO 8601 timestamp.
     pub built_at: String,
@@ -72,7 +79,8 @@ pub struct PackageResult {
 /// Generate a package artifact from a compiled project.
 ///
 /// Creates a `<name>.deploy/` directory under `output_base` containing
-/// `program.tasm` and `manifest.json`.
+/// `program.tasm` and `manifest.json`. `commitments` names the on-chain
+/// state the program reads, from `[deploy] commitments`.
 pub fn generate_artifact(
     name: &str,
     version: &str,
@@ -81,10 +89,11 @@ pub fn generate_artifact(
     cost: &ProgramCost,
     target_vm: &TerrainConfig,
     target_os: Option<&UnionConfig>,
+    commitments: &[String],
     output_base: &Path,
 ) -> Result<PackageResult, String> {
     // 1. Compute program_digest = Poseidon2(tasm bytes)
-    let digest_bytes = crate::poseidon2::hash_bytes(tasm.as_bytes());
+    let digest_bytes = crate::field::poseidon2::hash_bytes_goldilocks(tasm.as_bytes());
     let program_digest = ContentHash(digest_bytes);
 
     // 2. Compute source_hash from AST
@@ -122,6 +131,7 @@ pub fn generate_artifact(
             padded_height: cost.padded_height,
         },
         functions,
+        commitments: commitments.to_vec(),
         entry_point,
         built_at: iso8601_now(),
         compiler_version: env!("CARGO_PKG_VERSION").to_string(),
@@ -150,6 +160,55 @@ pub fn generate_artifact(
     })
 }
 
+// ─── Artifact Loading ──────────────────────────────────────────────
+
+/// Load a packaged `.deploy/` directory as a `ProgramBundle`, checking
+/// that `program.tasm` still has the digest `manifest.json` records.
+/// Function signatures are not carried over.
+pub fn load_artifact_bundle(artifact_dir: &Path) -> Result<ProgramBundle, String> {
+    use crate::runtime::json::{extract_object, extract_string, extract_string_opt, extract_u64};
+
+    let read = |file: &str| {
+        let path = artifact_dir.join(file);
+        std::fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read '{}': {}", path.display(), e))
+    };
+    let manifest = read("manifest.json")?;
+    let assembly = read("program.tasm")?;
+    let target = extract_object(&manifest, "target").ok_or("manifest has no 'target'")?;
+    let bundle = ProgramBundle {
+        name: extract_string(&manifest, "name")?,
+        version: extract_string(&manifest, "version")?,
+        target_vm: extract_string(target, "vm")?,
+        target_os: extract_string_opt(target, "os"),
+        assembly,
+        entry_point: extract_string(&manifest, "entry_point")?,
+        functions: Vec::new(),
+        errors: Vec::new(),
+        outputs: Vec::new(),
+        events: Vec::new(),
+        entrypoints: Vec::new(),
+        data: Vec::new(),
+        cost: BundleCost {
+            table_values: Vec::new(),
+            table_names: Vec::new(),
+            padded_height: extract_u64(&manifest, "padded_height").unwrap_or(0),
+            estimated_proving_ns: 0,
+        },
+        source_hash: extract_string(&manifest, "source_hash")?,
+        build: None,
+    };
+    let recorded = extract_string(&manifest, "program_digest")?;
+    let digest = bundle.program_digest().to_hex();
+    if recorded != digest {
+        return Err(format!(
+            "program.tasm has digest {} but manifest.json records {}",
+            digest, recorded
+        ));
+    }
+    Ok(bundle)
+}
+
 // ─── JSON Serialization ────────────────────────────────────────────
 
 impl PackageManifest {
@@ -212,6 +271,12 @@ impl PackageManifest {
         }
         out.push_str("  ],\n");
 
+        let commitments: Vec<String> = self.commitments.iter().map(|c| json_string(c)).collect();
+        out.push_str(&format!(
+            "  \"commitments\": [{}],\n",
+            commitments.join(", ")
+        ));
+
         out.push_str(&format!(
             "  \"entry_point\": {},\n",
             json_string(&self.entry_point)
@@ -326,7 +391,7 @@ fn find_entry_point(file: &ast::File) -> String {
 }
 
 /// Get current time as ISO 8601 string (no chrono dependency).
-fn iso8601_now() -> String {
+pub(crate) fn iso8601_now() -> String {
     let secs = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
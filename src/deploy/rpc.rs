@@ -0,0 +1,405 @@
+//! Deploying bundles to a node over JSON-RPC, reading their on-chain
+//! state back, and mock nodes to test with.
+//!
+//! `RpcDeployer` posts one `deploy_program` call per bundle:
+//! ```json
+//! {"jsonrpc": "2.0", "id": 1, "method": "deploy_program", "params": {
+//!   "name": "coin", "version": "0.1.0", "program_digest": "ab12...",
+//!   "proof": null, "assembly": "..."}}
+//! ```
+//! `proof` is `ProofData::to_json` when a proof is attached. The node
+//! answers `{"result": {"tx": "...", "new": true}}`, or
+//! `{"error": {"code": -32000, "message": "..."}}`. A node that already
+//! holds the program answers with its original transaction and
+//! `"new": false`, so deploying again is harmless.
+//!
+//! `get_commitment` reads one commitment of a deployed program, for
+//! `trident state pull`:
+//! ```json
+//! {"jsonrpc": "2.0", "id": 1, "method": "get_commitment", "params": {
+//!   "program_digest": "ab12...", "name": "utxo_root"}}
+//! ```
+//! answered with `{"result": {"value": [1, 2, 3, 4, 5]}}`.
+//!
+//! `MockDeployer` is a node kept in memory, and `MockNode` serves one on a
+//! loopback port from a background thread. Tests deploy against them, and
+//! `trident deploy --dry-run --rpc` runs the whole exchange against a
+//! `MockNode` instead of the chain.
+
+use std::collections::BTreeMap;
+use std::io::{BufReader, Read};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::registry::http::{read_head, write_response};
+use crate::registry::RegistryClient;
+use crate::runtime::json::{
+    extract_array, extract_bool, extract_object, extract_string, extract_string_opt, json_string,
+    parse_u64_array,
+};
+use crate::runtime::{Deployer, ProgramBundle, ProofData, StateProvider};
+
+/// Largest request body `MockNode` accepts.
+const MAX_REQUEST_SIZE: usize = 16 * 1024 * 1024;
+
+/// JSON-RPC error code for a deployment the node refuses.
+const REJECTED: i64 = -32000;
+
+/// What a node records for one deployment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeployReceipt {
+    /// Transaction that deployed the program.
+    pub tx: String,
+    /// False when the node already held the program.
+    pub new: bool,
+}
+
+/// The `params` of a `deploy_program` call.
+#[derive(Clone, Debug)]
+pub struct DeployRequest {
+    pub name: String,
+    pub version: String,
+    /// Poseidon2 digest of `assembly` (hex).
+    pub program_digest: String,
+    /// Format of the attached proof, if any.
+    pub proof_format: Option<String>,
+    pub assembly: String,
+}
+
+impl DeployRequest {
+    /// The request deploying `bundle` with `proof` attached.
+    pub fn new(bundle: &ProgramBundle, proof: Option<&ProofData>) -> Self {
+        Self {
+            name: bundle.name.clone(),
+            version: bundle.version.clone(),
+            program_digest: bundle.program_digest().to_hex(),
+            proof_format: proof.map(|p| p.format.clone()),
+            assembly: bundle.assembly.clone(),
+        }
+    }
+
+    /// Parse the params of a `deploy_program` call.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let params = extract_object(json, "params").ok_or("missing key 'params'")?;
+        Ok(Self {
+            name: extract_string(params, "name")?,
+            version: extract_string(params, "version")?,
+            program_digest: extract_string(params, "program_digest")?,
+            proof_format: extract_object(params, "proof")
+                .and_then(|proof| extract_string_opt(proof, "format")),
+            // Last, so keys inside the assembly text are never matched.
+            assembly: extract_string(params, "assembly")?,
+        })
+    }
+}
+
+/// The `deploy_program` call for `bundle`, with the full proof attached.
+fn deploy_call(bundle: &ProgramBundle, proof: Option<&ProofData>) -> String {
+    format!(
+        "{{\"jsonrpc\": \"2.0\", \"id\": 1, \"method\": \"deploy_program\", \"params\": {{\"name\": {}, \"version\": {}, \"program_digest\": {}, \"proof\": {}, \"assembly\": {}}}}}",
+        json_string(&bundle.name),
+        json_string(&bundle.version),
+        json_string(&bundle.program_digest().to_hex()),
+        proof.map_or_else(|| "null".to_string(), |p| p.to_json().trim_end().to_string()),
+        json_string(&bundle.assembly),
+    )
+}
+
+/// Deploys bundles to the node at a JSON-RPC URL.
+pub struct RpcDeployer {
+    client: RegistryClient,
+}
+
+impl RpcDeployer {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: RegistryClient::new(url),
+        }
+    }
+
+    /// Deploy `bundle` with `proof` attached, returning the node's receipt.
+    pub fn submit(
+        &self,
+        bundle: &ProgramBundle,
+        proof: Option<&ProofData>,
+    ) -> Result<DeployReceipt, String> {
+        let result = self
+            .call(&deploy_call(bundle, proof))
+            .map_err(|e| format!("node rejected the deployment: {}", e))?;
+        Ok(DeployReceipt {
+            tx: extract_string(&result, "tx")?,
+            new: extract_bool(&result, "new"),
+        })
+    }
+
+    /// Post one call, returning its `result` object or the node's error
+    /// message.
+    fn call(&self, request: &str) -> Result<String, String> {
+        let (status, body) = self.client.post_json("/", request)?;
+        if let Some(error) = extract_object(&body, "error") {
+            return Err(extract_string(error, "message").unwrap_or_else(|_| error.to_string()));
+        }
+        if status >= 400 {
+            return Err(format!("node returned HTTP {}: {}", status, body.trim()));
+        }
+        extract_object(&body, "result")
+            .map(String::from)
+            .ok_or_else(|| format!("malformed node response: {}", body.trim()))
+    }
+}
+
+impl Deployer for RpcDeployer {
+    fn deploy(&self, bundle: &ProgramBundle, proof: Option<&ProofData>) -> Result<String, String> {
+        self.submit(bundle, proof).map(|receipt| receipt.tx)
+    }
+}
+
+impl StateProvider for RpcDeployer {
+    fn commitment(&self, program_digest: &str, name: &str) -> Result<Vec<u64>, String> {
+        let request = format!(
+            "{{\"jsonrpc\": \"2.0\", \"id\": 1, \"method\": \"get_commitment\", \"params\": {{\"program_digest\": {}, \"name\": {}}}}}",
+            json_string(program_digest),
+            json_string(name),
+        );
+        let result = self.call(&request)?;
+        let value = extract_array(&result, "value")
+            .ok_or_else(|| format!("malformed node response: {}", result))?;
+        parse_u64_array(value)
+    }
+}
+
+/// One deployment a mock node accepted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockDeployment {
+    pub tx: String,
+    pub name: String,
+    pub version: String,
+    pub program_digest: String,
+    pub proof_format: Option<String>,
+}
+
+#[derive(Default)]
+struct MockState {
+    deployments: Vec<MockDeployment>,
+    reject: Option<String>,
+    /// (program digest, name) -> value.
+    commitments: BTreeMap<(String, String), Vec<u64>>,
+}
+
+/// A node kept in memory: it checks each program's digest, refuses what
+/// it is told to, answers a program it holds as already deployed, and
+/// serves the commitments it is given.
+#[derive(Default)]
+pub struct MockDeployer {
+    state: Mutex<MockState>,
+}
+
+impl MockDeployer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refuse every deployment with `message` until cleared with `None`.
+    pub fn reject_with(&self, message: Option<&str>) {
+        self.state.lock().unwrap().reject = message.map(String::from);
+    }
+
+    /// Deployments accepted so far, in order; a re-deploy adds none.
+    pub fn deployments(&self) -> Vec<MockDeployment> {
+        self.state.lock().unwrap().deployments.clone()
+    }
+
+    /// Set the commitment `name` of the program with `program_digest`.
+    pub fn set_commitment(&self, program_digest: &str, name: &str, value: Vec<u64>) {
+        self.state
+            .lock()
+            .unwrap()
+            .commitments
+            .insert((program_digest.to_string(), name.to_string()), value);
+    }
+
+    /// Accept or refuse one deployment.
+    pub fn accept(&self, request: &DeployRequest) -> Result<DeployReceipt, String> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(message) = &state.reject {
+            return Err(message.clone());
+        }
+        let digest = crate::hash::ContentHash(crate::field::poseidon2::hash_bytes_goldilocks(
+            request.assembly.as_bytes(),
+        ))
+        .to_hex();
+        if digest != request.program_digest {
+            return Err(format!(
+                "program digest {} does not match the assembly ({})",
+                request.program_digest, digest
+            ));
+        }
+        if let Some(existing) = state
+            .deployments
+            .iter()
+            .find(|d| d.program_digest == digest)
+        {
+            return Ok(DeployReceipt {
+                tx: existing.tx.clone(),
+                new: false,
+            });
+        }
+        let tx = format!("0x{:04x}{}", state.deployments.len() + 1, &digest[..12]);
+        state.deployments.push(MockDeployment {
+            tx: tx.clone(),
+            name: request.name.clone(),
+            version: request.version.clone(),
+            program_digest: digest,
+            proof_format: request.proof_format.clone(),
+        });
+        Ok(DeployReceipt { tx, new: true })
+    }
+}
+
+impl Deployer for MockDeployer {
+    fn deploy(&self, bundle: &ProgramBundle, proof: Option<&ProofData>) -> Result<String, String> {
+        self.accept(&DeployRequest::new(bundle, proof))
+            .map(|receipt| receipt.tx)
+    }
+}
+
+impl StateProvider for MockDeployer {
+    fn commitment(&self, program_digest: &str, name: &str) -> Result<Vec<u64>, String> {
+        self.state
+            .lock()
+            .unwrap()
+            .commitments
+            .get(&(program_digest.to_string(), name.to_string()))
+            .cloned()
+            .ok_or_else(|| format!("no commitment '{}' for program {}", name, program_digest))
+    }
+}
+
+/// A `MockDeployer` served over JSON-RPC on a loopback port. The server
+/// thread stops when the node is dropped.
+pub struct MockNode {
+    url: String,
+    deployer: Arc<MockDeployer>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockNode {
+    /// Bind a free loopback port and start serving.
+    pub fn start() -> Result<Self, String> {
+        let listener =
+            TcpListener::bind("127.0.0.1:0").map_err(|e| format!("cannot bind: {}", e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| format!("cannot bind: {}", e))?;
+        let deployer = Arc::new(MockDeployer::new());
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let (deployer, stop) = (deployer.clone(), stop.clone());
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        serve(stream, &deployer);
+                    }
+                }
+            })
+        };
+        Ok(Self {
+            url: format!("http://{}", addr),
+            deployer,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Base URL to point an `RpcDeployer` at.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The node's state, to inspect, to seed commitments, or to make it
+    /// refuse deployments.
+    pub fn deployer(&self) -> &MockDeployer {
+        &self.deployer
+    }
+}
+
+impl Drop for MockNode {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag.
+        let _ = TcpStream::connect(self.url.trim_start_matches("http://"));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Answer one request on `stream`.
+fn serve(mut stream: TcpStream, deployer: &MockDeployer) {
+    let mut reader = BufReader::new(&stream);
+    let Ok(head) = read_head(&mut reader) else {
+        return;
+    };
+    let (status, body) = match head.request() {
+        Some(("POST", _)) if head.content_length() <= MAX_REQUEST_SIZE => {
+            let mut body = vec![0u8; head.content_length()];
+            match reader.read_exact(&mut body) {
+                Ok(()) => (200, respond(&String::from_utf8_lossy(&body), deployer)),
+                Err(_) => return,
+            }
+        }
+        Some(("POST", _)) => (400, error_response(REJECTED, "request too large")),
+        _ => (405, error_response(-32600, "expected a POST")),
+    };
+    let _ = write_response(&mut stream, status, "application/json", body.as_bytes());
+}
+
+/// The JSON-RPC response to the call in `body`.
+fn respond(body: &str, deployer: &MockDeployer) -> String {
+    let result = match extract_string(body, "method").as_deref() {
+        Ok("deploy_program") => DeployRequest::from_json(body)
+            .and_then(|request| deployer.accept(&request))
+            .map(|receipt| {
+                format!(
+                    "{{\"tx\": {}, \"new\": {}}}",
+                    json_string(&receipt.tx),
+                    receipt.new
+                )
+            }),
+        Ok("get_commitment") => extract_object(body, "params")
+            .ok_or_else(|| "missing key 'params'".to_string())
+            .and_then(|params| {
+                deployer.commitment(
+                    &extract_string(params, "program_digest")?,
+                    &extract_string(params, "name")?,
+                )
+            })
+            .map(|value| {
+                let items: Vec<String> = value.iter().map(u64::to_string).collect();
+                format!("{{\"value\": [{}]}}", items.join(", "))
+            }),
+        Ok(method) => return error_response(-32601, &format!("unknown method '{}'", method)),
+        Err(e) => return error_response(-32600, e),
+    };
+    match result {
+        Ok(result) => format!(
+            "{{\"jsonrpc\": \"2.0\", \"id\": 1, \"result\": {}}}",
+            result
+        ),
+        Err(message) => error_response(REJECTED, &message),
+    }
+}
+
+fn error_response(code: i64, message: &str) -> String {
+    format!(
+        "{{\"jsonrpc\": \"2.0\", \"id\": 1, \"error\": {{\"code\": {}, \"message\": {}}}}}",
+        code,
+        json_string(message)
+    )
+}
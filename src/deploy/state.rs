@@ -0,0 +1,98 @@
+//! Syncing on-chain state into input files (`trident state pull`).
+//!
+//! A program that reads chain state — a UTXO set root, a nullifier set
+//! commitment — takes it as public input, and its value moves between
+//! prove cycles. A project names those commitments, in input order, under
+//! `[deploy] commitments`, and `trident package` records them in
+//! `manifest.json`. `pull_state` asks a `StateProvider` for the current
+//! value of each and `PulledState::to_inputs` writes them as an input
+//! file:
+//! ```text
+//! # state of coin 0.1.0 (ab12...)
+//! # utxo_root: values[0..5]
+//! values: 1, 2, 3, 4, 5
+//! ```
+//! The `values:` line is the public input, the same as `--input-values`.
+
+use std::path::Path;
+
+use crate::runtime::json::{extract_array, extract_string, quoted_items};
+use crate::runtime::StateProvider;
+
+/// Current values of a deployed program's commitments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PulledState {
+    pub name: String,
+    pub version: String,
+    pub program_digest: String,
+    /// Commitment name and value, in manifest order.
+    pub commitments: Vec<(String, Vec<u64>)>,
+}
+
+impl PulledState {
+    /// Public input: every commitment's value, in order.
+    pub fn values(&self) -> Vec<u64> {
+        self.commitments
+            .iter()
+            .flat_map(|(_, value)| value.iter().copied())
+            .collect()
+    }
+
+    /// Render as an input file, each commitment's slice of `values`
+    /// noted in a comment.
+    pub fn to_inputs(&self) -> String {
+        let mut out = format!(
+            "# state of {} {} ({})\n",
+            self.name, self.version, self.program_digest
+        );
+        let mut start = 0;
+        for (name, value) in &self.commitments {
+            out.push_str(&format!(
+                "# {}: values[{}..{}]\n",
+                name,
+                start,
+                start + value.len()
+            ));
+            start += value.len();
+        }
+        let values: Vec<String> = self.values().iter().map(u64::to_string).collect();
+        out.push_str(&format!("values: {}\n", values.join(", ")));
+        out
+    }
+}
+
+/// Fetch the commitments `manifest.json` of the artifact at
+/// `artifact_dir` references from `provider`.
+pub fn pull_state(
+    artifact_dir: &Path,
+    provider: &dyn StateProvider,
+) -> Result<PulledState, String> {
+    let path = artifact_dir.join("manifest.json");
+    let manifest = std::fs::read_to_string(&path)
+        .map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    let names = extract_array(&manifest, "commitments")
+        .map(quoted_items)
+        .unwrap_or_default();
+    if names.is_empty() {
+        return Err(format!(
+            "'{}' references no commitments; declare them under [deploy] commitments in trident.toml and package again",
+            path.display()
+        ));
+    }
+    let program_digest = extract_string(&manifest, "program_digest")?;
+    let commitments = names
+        .into_iter()
+        .map(|name| {
+            let value = provider
+                .commitment(&program_digest, &name)
+                .map_err(|e| format!("cannot fetch '{}': {}", name, e))?;
+            Ok((name, value))
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(PulledState {
+        name: extract_string(&manifest, "name")?,
+        version: extract_string(&manifest, "version")?,
+        program_digest,
+        commitments,
+    })
+}
@@ -1,4 +1,8 @@
+use super::rpc::{DeployRequest, MockDeployer, MockNode, RpcDeployer};
+use super::state::pull_state;
 use super::*;
+use crate::field::proof::Claim;
+use crate::runtime::{Deployer, ProofData};
 
 #[test]
 fn test_json_string_escaping() {
@@ -61,6 +65,7 @@ fn test_manifest_to_json_structure() {
             hash: "eeff".to_string(),
             signature: "fn main()".to_string(),
         }],
+        commitments: vec!["utxo_root".to_string(), "nullifier_root".to_string()],
         entry_point: "main".to_string(),
         built_at: "2026-02-11T00:00:00Z".to_string(),
         compiler_version: "0.1.0".to_string(),
@@ -75,6 +80,7 @@ fn test_manifest_to_json_structure() {
     assert!(json.contains("\"padded_height\": 256"));
     assert!(json.contains("\"entry_point\": \"main\""));
     assert!(json.contains("\"fn main()\""));
+    assert!(json.contains("\"commitments\": [\"utxo_root\", \"nullifier_root\"]"));
 }
 
 #[test]
@@ -100,6 +106,7 @@ fn test_manifest_null_os() {
             padded_height: 0,
         },
         functions: vec![],
+        commitments: vec![],
         entry_point: "main".to_string(),
         built_at: "2026-01-01T00:00:00Z".to_string(),
         compiler_version: "0.1.0".to_string(),
@@ -112,8 +119,12 @@ fn test_manifest_null_os() {
 #[test]
 fn test_program_digest_deterministic() {
     let tasm = "push 1\npush 2\nadd\nwrite_io 1\nhalt\n";
-    let hash1 = ContentHash(crate::poseidon2::hash_bytes(tasm.as_bytes()));
-    let hash2 = ContentHash(crate::poseidon2::hash_bytes(tasm.as_bytes()));
+    let hash1 = ContentHash(crate::field::poseidon2::hash_bytes_goldilocks(
+        tasm.as_bytes(),
+    ));
+    let hash2 = ContentHash(crate::field::poseidon2::hash_bytes_goldilocks(
+        tasm.as_bytes(),
+    ));
     assert_eq!(hash1.to_hex(), hash2.to_hex());
 }
 
@@ -140,6 +151,7 @@ fn test_generate_artifact_roundtrip() {
         &cost,
         &target_vm,
         None,
+        &[],
         dir.path(),
     )
     .unwrap();
@@ -168,3 +180,224 @@ fn test_generate_artifact_roundtrip() {
     assert!(!result.manifest.program_digest.is_empty());
     assert!(!result.manifest.source_hash.is_empty());
 }
+
+// ─── Deployment flows against an in-process mock node ─────────────
+
+fn write_program(dir: &Path, body: &str) -> PathBuf {
+    let path = dir.join("coin.tri");
+    std::fs::write(&path, format!("program coin\nfn main() {{\n{}\n}}\n", body)).unwrap();
+    path
+}
+
+fn bundle(dir: &Path, body: &str) -> ProgramBundle {
+    let entry = write_program(dir, body);
+    crate::compile_to_bundle(&entry, &crate::CompileOptions::default()).unwrap()
+}
+
+fn proof() -> ProofData {
+    ProofData {
+        claim: Claim {
+            program_hash: vec![1, 2, 3, 4, 5],
+            public_input: vec![7],
+            public_output: vec![],
+        },
+        proof_bytes: vec![0xde, 0xad],
+        format: "stark-triton-v2".to_string(),
+    }
+}
+
+#[test]
+fn deploys_bundles_with_and_without_proofs() {
+    let dir = tempfile::tempdir().unwrap();
+    let node = MockNode::start().unwrap();
+    let deployer = RpcDeployer::new(node.url());
+
+    let plain = bundle(dir.path(), "    pub_write(pub_read() + 1)");
+    let receipt = deployer.submit(&plain, None).unwrap();
+    assert!(receipt.new);
+
+    let proven = bundle(dir.path(), "    pub_write(pub_read() * 2)");
+    let tx = deployer.deploy(&proven, Some(&proof())).unwrap();
+    assert_ne!(tx, receipt.tx);
+
+    let deployments = node.deployer().deployments();
+    assert_eq!(deployments.len(), 2);
+    assert_eq!(deployments[0].name, "coin");
+    assert_eq!(
+        deployments[0].program_digest,
+        plain.program_digest().to_hex()
+    );
+    assert_eq!(deployments[0].proof_format, None);
+    assert_eq!(
+        deployments[1].proof_format.as_deref(),
+        Some("stark-triton-v2")
+    );
+}
+
+#[test]
+fn redeploying_returns_the_original_transaction() {
+    let dir = tempfile::tempdir().unwrap();
+    let node = MockNode::start().unwrap();
+    let deployer = RpcDeployer::new(node.url());
+    let program = bundle(dir.path(), "    pub_write(pub_read())");
+
+    let first = deployer.submit(&program, None).unwrap();
+    let again = deployer.submit(&program, Some(&proof())).unwrap();
+    assert!(first.new);
+    assert!(!again.new);
+    assert_eq!(again.tx, first.tx);
+    assert_eq!(node.deployer().deployments().len(), 1);
+}
+
+#[test]
+fn reports_rejections_and_unreachable_nodes() {
+    let dir = tempfile::tempdir().unwrap();
+    let node = MockNode::start().unwrap();
+    let deployer = RpcDeployer::new(node.url());
+    let mut program = bundle(dir.path(), "    pub_write(pub_read())");
+
+    node.deployer().reject_with(Some("insufficient fee"));
+    let err = deployer.submit(&program, None).unwrap_err();
+    assert_eq!(err, "node rejected the deployment: insufficient fee");
+    node.deployer().reject_with(None);
+    assert!(node.deployer().deployments().is_empty());
+
+    // A digest that does not match the assembly is refused by the node.
+    let local = MockDeployer::new();
+    let mut request = DeployRequest::new(&program, None);
+    request.assembly.push_str("\npush 0\npop 1");
+    let err = local.accept(&request).unwrap_err();
+    assert!(err.contains("does not match the assembly"), "{}", err);
+
+    // Once stopped, the node is unreachable.
+    let url = node.url().to_string();
+    drop(node);
+    program.version = "0.2.0".to_string();
+    let err = RpcDeployer::new(&url).submit(&program, None).unwrap_err();
+    assert!(err.contains("cannot connect"), "{}", err);
+}
+
+#[test]
+fn mock_deployer_implements_deployer_in_memory() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = bundle(dir.path(), "    pub_write(pub_read())");
+    let deployer = MockDeployer::new();
+    let dyn_deployer: &dyn Deployer = &deployer;
+    let tx = dyn_deployer.deploy(&program, None).unwrap();
+    assert_eq!(dyn_deployer.deploy(&program, None).unwrap(), tx);
+    assert_eq!(deployer.deployments().len(), 1);
+}
+
+#[test]
+fn selects_packaged_artifacts_and_checks_their_digest() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = write_program(dir.path(), "    pub_write(pub_read())");
+    let options = crate::CompileOptions::default();
+    let tasm = crate::compile_project_with_options(&entry, &options).unwrap();
+    let cost = crate::analyze_costs_project(&entry, &options).unwrap();
+    let source = std::fs::read_to_string(&entry).unwrap();
+    let file = crate::parse_source_silent(&source, "coin.tri").unwrap();
+    let packaged = generate_artifact(
+        "coin",
+        "1.0.0",
+        &tasm,
+        &file,
+        &cost,
+        &options.target_config,
+        None,
+        &[],
+        dir.path(),
+    )
+    .unwrap();
+
+    let loaded = load_artifact_bundle(&packaged.artifact_dir).unwrap();
+    assert_eq!(loaded.name, "coin");
+    assert_eq!(loaded.version, "1.0.0");
+    assert_eq!(
+        loaded.program_digest().to_hex(),
+        packaged.manifest.program_digest
+    );
+
+    std::fs::write(&packaged.tasm_path, format!("{}\npush 0\npop 1\n", tasm)).unwrap();
+    let err = load_artifact_bundle(&packaged.artifact_dir).unwrap_err();
+    assert!(err.contains("manifest.json records"), "{}", err);
+}
+
+#[test]
+fn pulls_manifest_commitments_into_an_input_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = write_program(dir.path(), "    pub_write(pub_read())");
+    let options = crate::CompileOptions::default();
+    let tasm = crate::compile_project_with_options(&entry, &options).unwrap();
+    let cost = crate::analyze_costs_project(&entry, &options).unwrap();
+    let source = std::fs::read_to_string(&entry).unwrap();
+    let file = crate::parse_source_silent(&source, "coin.tri").unwrap();
+    let commitments = vec!["utxo_root".to_string(), "epoch".to_string()];
+    let packaged = generate_artifact(
+        "coin",
+        "1.0.0",
+        &tasm,
+        &file,
+        &cost,
+        &options.target_config,
+        None,
+        &commitments,
+        dir.path(),
+    )
+    .unwrap();
+    let digest = &packaged.manifest.program_digest;
+
+    let node = MockNode::start().unwrap();
+    let provider = RpcDeployer::new(node.url());
+    node.deployer()
+        .set_commitment(digest, "utxo_root", vec![11, 12, 13, 14, 15]);
+    let err = pull_state(&packaged.artifact_dir, &provider).unwrap_err();
+    assert!(
+        err.starts_with("cannot fetch 'epoch': no commitment"),
+        "{}",
+        err
+    );
+
+    node.deployer().set_commitment(digest, "epoch", vec![7]);
+    let state = pull_state(&packaged.artifact_dir, &provider).unwrap();
+    assert_eq!(state.values(), vec![11, 12, 13, 14, 15, 7]);
+    assert_eq!(
+        state.to_inputs(),
+        format!(
+            "# state of coin 1.0.0 ({})\n# utxo_root: values[0..5]\n# epoch: values[5..6]\nvalues: 11, 12, 13, 14, 15, 7\n",
+            digest
+        )
+    );
+
+    // The in-memory node answers the same without a server.
+    let local = MockDeployer::new();
+    local.set_commitment(digest, "utxo_root", vec![1, 2, 3, 4, 5]);
+    local.set_commitment(digest, "epoch", vec![2]);
+    let state = pull_state(&packaged.artifact_dir, &local).unwrap();
+    assert_eq!(state.values(), vec![1, 2, 3, 4, 5, 2]);
+}
+
+#[test]
+fn state_pull_needs_declared_commitments() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = write_program(dir.path(), "    pub_write(pub_read())");
+    let options = crate::CompileOptions::default();
+    let tasm = crate::compile_project_with_options(&entry, &options).unwrap();
+    let cost = crate::analyze_costs_project(&entry, &options).unwrap();
+    let file =
+        crate::parse_source_silent(&std::fs::read_to_string(&entry).unwrap(), "coin.tri").unwrap();
+    let packaged = generate_artifact(
+        "coin",
+        "1.0.0",
+        &tasm,
+        &file,
+        &cost,
+        &options.target_config,
+        None,
+        &[],
+        dir.path(),
+    )
+    .unwrap();
+    let err = pull_state(&packaged.artifact_dir, &MockDeployer::new()).unwrap_err();
+    assert!(err.contains("[deploy] commitments"), "{}", err);
+}
@@ -0,0 +1,1119 @@
+//! Stable error codes.
+//!
+//! Every diagnostic from the lexer, parser and type checker carries one of
+//! these codes, so tools and documentation can name a diagnostic without
+//! matching its message. Codes are grouped by phase: `E00xx` lexer,
+//! `E01xx` parser, `E02xx` type checker. A code, once published, keeps its
+//! number and meaning; retired codes are not reused.
+//!
+//! Optimization hints are not listed here: they carry their own
+//! `hint[Hxxxx]` codes (see `reference/errors/hints.md`).
+
+use std::fmt;
+
+/// A stable diagnostic code, rendered as `E` and four digits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    // --- Lexer ---
+    UnexpectedCharacter = 1,
+    UnterminatedString = 2,
+    IntegerTooLarge = 3,
+    MalformedAsm = 4,
+
+    // --- Parser ---
+    MissingHeader = 101,
+    NestingTooDeep = 102,
+    UnexpectedToken = 103,
+    ExpectedExpression = 104,
+    ExpectedType = 105,
+    ExpectedItem = 106,
+    ExpectedPattern = 107,
+    UnknownAttribute = 108,
+    MisplacedAttribute = 109,
+    MainWithEntrypoints = 110,
+
+    // --- Type checker ---
+    TypeMismatch = 201,
+    UndefinedVariable = 202,
+    UndefinedFunction = 203,
+    UndefinedType = 204,
+    UnknownField = 205,
+    MissingField = 206,
+    NotAStruct = 207,
+    ArgumentCount = 208,
+    SizeArgument = 209,
+    OperatorTypes = 210,
+    ConditionType = 211,
+    Destructuring = 212,
+    IndexOutOfRange = 213,
+    NotAnArray = 214,
+    NotConstant = 215,
+    Recursion = 216,
+    ImpureOperation = 217,
+    MisplacedString = 218,
+    EmptyMessage = 219,
+    NonExhaustiveMatch = 220,
+    Unreachable = 221,
+    ImmutableAssign = 222,
+    AuthenticatedConstruction = 223,
+    MissingAuthentication = 224,
+    InvalidEntrypoint = 225,
+    IntrinsicOutsideStd = 226,
+    InvalidEvent = 227,
+    AsmTargets = 228,
+    InvalidTrace = 229,
+    InvalidFailCode = 230,
+    IncludeData = 231,
+    OutputNotWritten = 232,
+    TestSignature = 233,
+    StructUpdateBase = 234,
+    FieldDefault = 235,
+    NotATupleStruct = 236,
+    RamAliasing = 237,
+    UnusedImport = 238,
+    Deprecated = 239,
+    LegacyPath = 240,
+    ReservedName = 241,
+    UnusedSponge = 242,
+    U32Overflow = 243,
+    UnknownVariant = 244,
+    LoopExit = 245,
+}
+
+impl ErrorCode {
+    /// Every code, in numeric order.
+    pub const ALL: &'static [ErrorCode] = &[
+        Self::UnexpectedCharacter,
+        Self::UnterminatedString,
+        Self::IntegerTooLarge,
+        Self::MalformedAsm,
+        Self::MissingHeader,
+        Self::NestingTooDeep,
+        Self::UnexpectedToken,
+        Self::ExpectedExpression,
+        Self::ExpectedType,
+        Self::ExpectedItem,
+        Self::ExpectedPattern,
+        Self::UnknownAttribute,
+        Self::MisplacedAttribute,
+        Self::MainWithEntrypoints,
+        Self::TypeMismatch,
+        Self::UndefinedVariable,
+        Self::UndefinedFunction,
+        Self::UndefinedType,
+        Self::UnknownField,
+        Self::MissingField,
+        Self::NotAStruct,
+        Self::ArgumentCount,
+        Self::SizeArgument,
+        Self::OperatorTypes,
+        Self::ConditionType,
+        Self::Destructuring,
+        Self::IndexOutOfRange,
+        Self::NotAnArray,
+        Self::NotConstant,
+        Self::Recursion,
+        Self::ImpureOperation,
+        Self::MisplacedString,
+        Self::EmptyMessage,
+        Self::NonExhaustiveMatch,
+        Self::Unreachable,
+        Self::ImmutableAssign,
+        Self::AuthenticatedConstruction,
+        Self::MissingAuthentication,
+        Self::InvalidEntrypoint,
+        Self::IntrinsicOutsideStd,
+        Self::InvalidEvent,
+        Self::AsmTargets,
+        Self::InvalidTrace,
+        Self::InvalidFailCode,
+        Self::IncludeData,
+        Self::OutputNotWritten,
+        Self::TestSignature,
+        Self::StructUpdateBase,
+        Self::FieldDefault,
+        Self::NotATupleStruct,
+        Self::RamAliasing,
+        Self::UnusedImport,
+        Self::Deprecated,
+        Self::LegacyPath,
+        Self::ReservedName,
+        Self::UnusedSponge,
+        Self::U32Overflow,
+        Self::UnknownVariant,
+        Self::LoopExit,
+    ];
+
+    /// The code's number: `E0201` is 201.
+    pub fn number(self) -> u16 {
+        self as u16
+    }
+
+    /// Parse `E0201`, `e0201` or `0201`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let digits = s
+            .strip_prefix('E')
+            .or_else(|| s.strip_prefix('e'))
+            .unwrap_or(s);
+        if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let n: u16 = digits.parse().ok()?;
+        Self::ALL.iter().copied().find(|c| c.number() == n)
+    }
+
+    /// One line naming what went wrong.
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::UnexpectedCharacter => "character outside the Trident grammar",
+            Self::UnterminatedString => "unterminated string literal",
+            Self::IntegerTooLarge => "integer literal too large",
+            Self::MalformedAsm => "malformed asm block",
+            Self::MissingHeader => "missing `program` or `module` declaration",
+            Self::NestingTooDeep => "nesting too deep",
+            Self::UnexpectedToken => "unexpected token",
+            Self::ExpectedExpression => "expected an expression",
+            Self::ExpectedType => "expected a type",
+            Self::ExpectedItem => "expected an item",
+            Self::ExpectedPattern => "expected a match pattern",
+            Self::UnknownAttribute => "unknown or malformed attribute",
+            Self::MisplacedAttribute => "attribute on the wrong kind of item",
+            Self::MainWithEntrypoints => "program with entrypoints defines main",
+            Self::TypeMismatch => "mismatched types",
+            Self::UndefinedVariable => "undefined variable",
+            Self::UndefinedFunction => "undefined function",
+            Self::UndefinedType => "undefined type, struct or event",
+            Self::UnknownField => "unknown field",
+            Self::MissingField => "missing field in struct or event",
+            Self::NotAStruct => "field access on a non-struct value",
+            Self::ArgumentCount => "wrong number of arguments",
+            Self::SizeArgument => "size argument mismatch",
+            Self::OperatorTypes => "operator applied to unsupported types",
+            Self::ConditionType => "condition is not Bool",
+            Self::Destructuring => "destructuring does not match the value",
+            Self::IndexOutOfRange => "index out of range",
+            Self::NotAnArray => "indexing a non-array value",
+            Self::NotConstant => "value not known at compile time",
+            Self::Recursion => "recursive call cycle",
+            Self::ImpureOperation => "side effect in a #[pure] function",
+            Self::MisplacedString => "string literal outside a message or label",
+            Self::EmptyMessage => "empty assertion message or reason",
+            Self::NonExhaustiveMatch => "non-exhaustive match",
+            Self::Unreachable => "unreachable code or pattern",
+            Self::ImmutableAssign => "assignment to an immutable variable",
+            Self::AuthenticatedConstruction => "restricted struct constructed directly",
+            Self::MissingAuthentication => "#[authenticated] function never authenticates",
+            Self::InvalidEntrypoint => "invalid entrypoint",
+            Self::IntrinsicOutsideStd => "#[intrinsic] outside the standard library",
+            Self::InvalidEvent => "invalid event declaration",
+            Self::AsmTargets => "side-by-side asm blocks disagree",
+            Self::InvalidTrace => "invalid trace",
+            Self::InvalidFailCode => "invalid fail code",
+            Self::IncludeData => "invalid include_data",
+            Self::OutputNotWritten => "declared output not written",
+            Self::TestSignature => "#[test] function with parameters or a result",
+            Self::StructUpdateBase => "invalid `..` base",
+            Self::FieldDefault => "invalid field default",
+            Self::NotATupleStruct => "struct called like a tuple struct",
+            Self::RamAliasing => "RAM written through two handles",
+            Self::UnusedImport => "unused import",
+            Self::Deprecated => "use of a deprecated event",
+            Self::LegacyPath => "legacy module path",
+            Self::ReservedName => "name reserved for a future keyword",
+            Self::UnusedSponge => "sponge state dropped",
+            Self::U32Overflow => "Field sum used as U32",
+            Self::UnknownVariant => "unknown enum variant",
+            Self::LoopExit => "`break` or `continue` outside a loop",
+        }
+    }
+
+    /// The extended description printed by `trident explain`, with an
+    /// example that triggers the diagnostic and how to fix it.
+    pub fn explanation(self) -> &'static str {
+        match self {
+            Self::UnexpectedCharacter => {
+                "\
+A character that is not part of Trident's grammar was found. Trident has
+no `-` or `/` operators: subtraction in a prime field is addition of the
+inverse, and division hides an inversion, so both are spelled out.
+
+Erroneous example:
+
+    let d: Field = a - b
+
+Fix:
+
+    let d: Field = sub(a, b)
+    let (q, r) = x /% y
+"
+            }
+            Self::UnterminatedString => {
+                "\
+A string literal runs to the end of the line without a closing `\"`.
+
+Erroneous example:
+
+    assert(ok, \"balance too low)
+
+Fix:
+
+    assert(ok, \"balance too low\")
+"
+            }
+            Self::IntegerTooLarge => {
+                "\
+An integer literal does not fit in 64 bits. Larger field elements are
+built from smaller ones.
+
+Erroneous example:
+
+    let x: Field = 99999999999999999999999
+
+Fix:
+
+    let x: Field = 99999999999 * 1000000000000 + 999999999999
+"
+            }
+            Self::MalformedAsm => {
+                "\
+An `asm` block is written `asm { ... }`, optionally with a target tag
+and a stack effect in parentheses. The annotation must close with `)`,
+the body must open with `{`, and the body must close with `}`.
+
+Erroneous example:
+
+    asm(triton, +1 { push 42 }
+
+Fix:
+
+    asm(triton, +1) { push 42 }
+"
+            }
+            Self::MissingHeader => {
+                "\
+Every .tri file begins with `program <name>` or `module <name>`.
+
+Erroneous example:
+
+    fn main() { }
+
+Fix:
+
+    program hello
+
+    fn main() { }
+"
+            }
+            Self::NestingTooDeep => {
+                "\
+Blocks and expressions nest more than 256 levels deep. Move the inner
+code into functions.
+
+Erroneous example:
+
+    fn f() -> Field { ((((((((((/* ...256 more... */ x)))))))))) }
+
+Fix: compute the inner part in a helper function and call it.
+"
+            }
+            Self::UnexpectedToken => {
+                "\
+The parser expected one token (a keyword, a delimiter, an identifier or
+an integer) and found another.
+
+Erroneous example:
+
+    fn main( {
+
+Fix:
+
+    fn main() {
+"
+            }
+            Self::ExpectedExpression => {
+                "\
+A value was expected: a literal, a variable, a call, or an operator
+expression.
+
+Erroneous example:
+
+    let x: Field =
+
+Fix:
+
+    let x: Field = 0
+"
+            }
+            Self::ExpectedType => {
+                "\
+A type was expected. Types are Field, XField, Bool, U32, Digest,
+`[T; N]`, `(T, U)` and struct names; array sizes are integers or size
+parameters such as `N`, `M + N` and `N * 2`.
+
+Erroneous example:
+
+    let xs: [Field; n] = [0, 0]
+
+Fix:
+
+    let xs: [Field; 2] = [0, 0]
+"
+            }
+            Self::ExpectedItem => {
+                "\
+At the top level of a file only functions, structs, events and constants
+may appear.
+
+Erroneous example:
+
+    program p
+    let x: Field = 1
+
+Fix:
+
+    program p
+    const X: Field = 1
+"
+            }
+            Self::ExpectedPattern => {
+                "\
+A match arm starts with a pattern: an integer, `true`, `false`, a struct
+pattern `Point { x, y }`, or `_`. Inside a struct pattern each field is
+bound to a name, matched against a literal, or ignored with `_`.
+
+Erroneous example:
+
+    match x {
+        a + 1 => { }
+        _ => { }
+    }
+
+Fix:
+
+    match x {
+        1 => { }
+        _ => { }
+    }
+"
+            }
+            Self::UnknownAttribute => {
+                "\
+The attribute is not one Trident knows, or its argument is malformed.
+Known attributes: cfg, intrinsic, test, pure, authenticated,
+auto_range_check, entrypoint, requires, ensures, assume, version and
+deprecated. An event version is a non-negative integer.
+
+Erroneous example:
+
+    #[inline]
+    fn f() { }
+
+Fix: remove the attribute.
+"
+            }
+            Self::MisplacedAttribute => {
+                "\
+The attribute exists but does not apply to this kind of item. Most
+attributes apply only to functions; #[authenticated] applies to
+functions and structs; #[version] and #[deprecated] apply to events.
+
+Erroneous example:
+
+    #[pure]
+    struct Point { x: Field, y: Field }
+
+Fix: move the attribute to a function, or remove it.
+"
+            }
+            Self::MainWithEntrypoints => {
+                "\
+A program with #[entrypoint] functions dispatches to them by selector, so
+it cannot also define `main`.
+
+Erroneous example:
+
+    program vault
+    #[entrypoint] fn deposit() { }
+    fn main() { }
+
+Fix: remove `main`, or turn its body into another entrypoint.
+"
+            }
+            Self::TypeMismatch => {
+                "\
+A value's type differs from the one required: by a `let` annotation, an
+assignment target, a function parameter, a struct or event field, an
+array's other elements, or a match scrutinee. Trident has no implicit
+conversions.
+
+Erroneous example:
+
+    let x: U32 = pub_read()
+
+Fix:
+
+    let x: U32 = as_u32(pub_read())
+"
+            }
+            Self::UndefinedVariable => {
+                "\
+A name is used that no `let`, parameter or pattern in scope binds.
+
+Erroneous example:
+
+    fn main() { pub_write(total) }
+
+Fix:
+
+    fn main() {
+        let total: Field = pub_read()
+        pub_write(total)
+    }
+"
+            }
+            Self::UndefinedFunction => {
+                "\
+The called function is not defined in this module and not found in an
+imported one.
+
+Erroneous example:
+
+    let d: Digest = merkle.root(leaves)
+
+Fix:
+
+    use std.crypto.merkle
+    let d: Digest = merkle.root(leaves)
+"
+            }
+            Self::UndefinedType => {
+                "\
+A type, struct or event name is not defined in this module and not found
+in an imported one.
+
+Erroneous example:
+
+    let p = Pointt { x: 1, y: 2 }
+
+Fix:
+
+    let p = Point { x: 1, y: 2 }
+"
+            }
+            Self::UnknownField => {
+                "\
+The struct or event has no field of this name.
+
+Erroneous example:
+
+    struct Point { x: Field, y: Field }
+    let z: Field = p.z
+
+Fix:
+
+    let y: Field = p.y
+"
+            }
+            Self::MissingField => {
+                "\
+A struct init or event emission leaves out a field that has no default.
+
+Erroneous example:
+
+    struct Point { x: Field, y: Field }
+    let p = Point { x: 1 }
+
+Fix:
+
+    let p = Point { x: 1, y: 0 }
+"
+            }
+            Self::NotAStruct => {
+                "\
+A field is read from a value that is not a struct.
+
+Erroneous example:
+
+    let x: Field = pub_read()
+    let y: Field = x.value
+
+Fix: read fields only from struct values, or drop the access.
+"
+            }
+            Self::ArgumentCount => {
+                "\
+A function or tuple struct is called with more or fewer arguments than
+it declares.
+
+Erroneous example:
+
+    fn add(a: Field, b: Field) -> Field { a + b }
+    let s: Field = add(1)
+
+Fix:
+
+    let s: Field = add(1, 2)
+"
+            }
+            Self::SizeArgument => {
+                "\
+The size arguments of a call to a size-generic function do not match:
+too many or too few, a non-constant size, a size that cannot be
+inferred, or an array whose length no size makes equal to the
+parameter's.
+
+Erroneous example:
+
+    fn sum<N>(xs: [Field; N]) -> Field { ... }
+    let s: Field = sum<3>([1, 2])
+
+Fix:
+
+    let s: Field = sum<2>([1, 2])
+"
+            }
+            Self::OperatorTypes => {
+                "\
+The operator does not accept these operand types. `+` and `*` take two
+Fields (or two XFields); `<`, `&`, `^` and `/%` take U32; `==` takes two
+values of the same type; `*.` takes an XField and a Field.
+
+Erroneous example:
+
+    let a: U32 = 3
+    let b: Field = 4
+    let c = a + b
+
+Fix:
+
+    let c: Field = as_field(a) + b
+"
+            }
+            Self::ConditionType => {
+                "\
+An `if` or `while` condition, or an #[assume] predicate, has a type
+that cannot be tested.
+
+Erroneous example:
+
+    let d: Digest = divine5()
+    if d { }
+
+Fix:
+
+    if d == expected { }
+"
+            }
+            Self::Destructuring => {
+                "\
+A `let (a, b) = ...` or `(a, b) = ...` names a different number of
+values than the tuple or Digest holds, or the value is not a tuple.
+
+Erroneous example:
+
+    let (a, b) = (1, 2, 3)
+
+Fix:
+
+    let (a, b, c) = (1, 2, 3)
+"
+            }
+            Self::IndexOutOfRange => {
+                "\
+A constant index is at or past the end of the array or Digest.
+
+Erroneous example:
+
+    let xs: [Field; 3] = [1, 2, 3]
+    let x: Field = xs[3]
+
+Fix:
+
+    let x: Field = xs[2]
+"
+            }
+            Self::NotAnArray => {
+                "\
+A value that is not an array is indexed or asked for its `len()`.
+
+Erroneous example:
+
+    let x: Field = pub_read()
+    let y: Field = x[0]
+
+Fix: index only arrays (and Digests, with constant indices).
+"
+            }
+            Self::NotConstant => {
+                "\
+A value that must be known at compile time is not: a constant's value,
+a field default, a Digest index, a loop's `bounded` annotation, or a
+loop end without one. Constants may use literals, other constants, `+`, `*` and
+calls of #[pure] functions with constant arguments.
+
+Erroneous example:
+
+    let n: U32 = as_u32(pub_read())
+    for i in 0..n { }
+
+Fix:
+
+    for i in 0..n bounded 100 { }
+"
+            }
+            Self::Recursion => {
+                "\
+Functions call each other in a cycle. Stack-machine targets have no
+call stack for recursion; the cycle must become a loop.
+
+Erroneous example:
+
+    fn fact(n: Field) -> Field { if n == 0 { 1 } else { n * fact(sub(n, 1)) } }
+
+Fix:
+
+    fn fact(n: U32) -> Field {
+        let mut acc: Field = 1
+        for i in 1..n + 1 bounded 64 { acc = acc * as_field(i) }
+        acc
+    }
+"
+            }
+            Self::ImpureOperation => {
+                "\
+A #[pure] function reads input, writes output, or calls a function that
+does. Pure functions may be evaluated at compile time, so they cannot
+perform I/O.
+
+Erroneous example:
+
+    #[pure]
+    fn next() -> Field { pub_read() }
+
+Fix: pass the value in as a parameter, or drop #[pure].
+"
+            }
+            Self::MisplacedString => {
+                "\
+String literals have no runtime representation; they are only allowed
+as assertion messages and trace labels.
+
+Erroneous example:
+
+    let name = \"alice\"
+
+Fix:
+
+    assert(ok, \"alice is not the owner\")
+"
+            }
+            Self::EmptyMessage => {
+                "\
+An assertion message or #[assume] reason is the empty string. It is
+shown when the check fails, so it must say something.
+
+Erroneous example:
+
+    assert(ok, \"\")
+
+Fix:
+
+    assert(ok, \"signature does not verify\")
+"
+            }
+            Self::NonExhaustiveMatch => {
+                "\
+A match does not cover every value of its scrutinee. Field and U32
+matches need a wildcard arm; Bool matches need both `true` and `false`;
+enum matches need an arm for every variant or a wildcard.
+
+Erroneous example:
+
+    match x {
+        0 => { }
+        1 => { }
+    }
+
+Fix:
+
+    match x {
+        0 => { }
+        1 => { }
+        _ => { }
+    }
+"
+            }
+            Self::Unreachable => {
+                "\
+Code follows a `return`, or a match arm follows the wildcard `_`, so it
+can never run.
+
+Erroneous example:
+
+    fn f() -> Field {
+        return 1
+        pub_write(2)
+    }
+
+Fix: remove the code, or move it before the `return` (or the arm before
+`_`).
+"
+            }
+            Self::ImmutableAssign => {
+                "\
+A variable bound without `mut` is assigned.
+
+Erroneous example:
+
+    let x: Field = 0
+    x = 1
+
+Fix:
+
+    let mut x: Field = 0
+    x = 1
+"
+            }
+            Self::AuthenticatedConstruction => {
+                "\
+An #[authenticated] struct is built outside an #[authenticated] function
+of its module, or a sponge state is built directly. Such values vouch
+for a check having happened, so only the code that performs it may make
+them.
+
+Erroneous example:
+
+    let s = hash.Sponge { ... }
+
+Fix:
+
+    let s: hash.Sponge = hash.init()
+"
+            }
+            Self::MissingAuthentication => {
+                "\
+An #[authenticated] function never reaches `merkle_step` or
+`merkle_step_mem`, directly or through a callee, so the attribute
+promises a check the code does not make.
+
+Erroneous example:
+
+    #[authenticated]
+    fn load(root: Digest) -> Field { divine() }
+
+Fix: authenticate the loaded value against `root` with a Merkle step, or
+remove the attribute.
+"
+            }
+            Self::InvalidEntrypoint => {
+                "\
+Entrypoints belong to programs, take no parameters, return nothing, and
+have distinct non-negative selectors.
+
+Erroneous example:
+
+    #[entrypoint(1)] fn deposit() { }
+    #[entrypoint(1)] fn withdraw() { }
+
+Fix:
+
+    #[entrypoint(1)] fn deposit() { }
+    #[entrypoint(2)] fn withdraw() { }
+"
+            }
+            Self::IntrinsicOutsideStd => {
+                "\
+#[intrinsic] binds a function to a target instruction and is reserved
+for modules under vm.*, std.* and os.*.
+
+Erroneous example:
+
+    module mylib
+    #[intrinsic(hash)] fn h(a: Field) -> Digest
+
+Fix: call the standard library function instead of redeclaring it.
+"
+            }
+            Self::InvalidEvent => {
+                "\
+An event has more than nine fields, or a field that is not a Field.
+
+Erroneous example:
+
+    event Transfer { amount: U32 }
+
+Fix:
+
+    event Transfer { amount: Field }
+"
+            }
+            Self::AsmTargets => {
+                "\
+Side-by-side asm blocks are alternatives, one of which is built. They
+must cover the target being compiled (or have an untagged fallback) and
+declare the same stack effect.
+
+Erroneous example:
+
+    asm(triton, +1) { push 42 }
+    asm(miden) { push.42 }
+
+Fix:
+
+    asm(triton, +1) { push 42 }
+    asm(miden, +1) { push.42 }
+"
+            }
+            Self::InvalidTrace => {
+                "\
+`trace` takes a string literal label, written as a lowercase identifier,
+and a value of 1 to 16 elements.
+
+Erroneous example:
+
+    trace(\"Balance\", balance)
+
+Fix:
+
+    trace(\"balance\", balance)
+"
+            }
+            Self::InvalidFailCode => {
+                "\
+`fail` takes a named constant, so the code has a name callers can look
+up, and the code must not be 0, which means success.
+
+Erroneous example:
+
+    fail(2)
+
+Fix:
+
+    const INSUFFICIENT_BALANCE: Field = 2
+    fail(INSUFFICIENT_BALANCE)
+"
+            }
+            Self::IncludeData => {
+                "\
+`include_data` gives the value of a constant with an array type, from
+one file whose contents must match the declared array.
+
+Erroneous example:
+
+    let table = include_data(\"table.json\")
+
+Fix:
+
+    const TABLE: [Field; 256] = include_data(\"table.json\")
+"
+            }
+            Self::OutputNotWritten => {
+                "\
+A program declares `pub output`, but some path through `main` ends
+without writing all of it.
+
+Erroneous example:
+
+    pub output: Field
+    fn main() { if pub_read() == 0 { pub_write(1) } }
+
+Fix: write the output on every path.
+"
+            }
+            Self::TestSignature => {
+                "\
+A #[test] function is run with no inputs and its result is not looked
+at, so it takes no parameters and has no return type.
+
+Erroneous example:
+
+    #[test]
+    fn check(x: Field) -> Bool { x == x }
+
+Fix:
+
+    #[test]
+    fn check() { assert(1 == 1) }
+"
+            }
+            Self::StructUpdateBase => {
+                "\
+The base of `..base` in a struct init must be a variable of the same
+struct.
+
+Erroneous example:
+
+    let d = Config { fee: 5, ..make_config() }
+
+Fix:
+
+    let c = make_config()
+    let d = Config { fee: 5, ..c }
+"
+            }
+            Self::FieldDefault => {
+                "\
+A struct field default must be a Field, U32 or Bool value known at
+compile time that fits the field's type.
+
+Erroneous example:
+
+    struct Config { limit: U32 = 5000000000 }
+
+Fix:
+
+    struct Config { limit: U32 = 5000000 }
+"
+            }
+            Self::NotATupleStruct => {
+                "\
+A struct with named fields is called like a tuple struct.
+
+Erroneous example:
+
+    struct Point { x: Field, y: Field }
+    let p = Point(1, 2)
+
+Fix:
+
+    let p = Point { x: 1, y: 2 }
+"
+            }
+            Self::RamAliasing => {
+                "\
+Within a scope each RAM word may be written through one handle, and no
+write may touch a `sec ram` slot; two `sec ram` slots may not overlap.
+
+Erroneous example:
+
+    let a = 100
+    let b = 102
+    ram_write_block(a, d)
+    ram_write(b, 7)
+
+Fix:
+
+    ram_write(a + 2, 7)
+"
+            }
+            Self::UnusedImport => {
+                "\
+A module is imported with `use` but nothing from it is used.
+
+Erroneous example:
+
+    use std.crypto.merkle
+    fn main() { }
+
+Fix: remove the `use`.
+"
+            }
+            Self::Deprecated => {
+                "\
+The event is marked #[deprecated]; its note says what replaces it.
+
+Erroneous example:
+
+    #[deprecated(\"use Transfer2\")]
+    event Transfer { amount: Field }
+    reveal Transfer { amount: x }
+
+Fix: emit the replacement event.
+"
+            }
+            Self::LegacyPath => {
+                "\
+The module is imported under a path that has moved. Legacy paths stop
+resolving under the `no_legacy_paths` cfg flag.
+
+Fix: import the module under the path the help names; `trident fix`
+rewrites the import and its uses.
+"
+            }
+            Self::ReservedName => {
+                "\
+The name is reserved for a future keyword and will stop being a valid
+identifier.
+
+Erroneous example:
+
+    let trait: Field = 1
+
+Fix: rename it, e.g. to `trait_`; `trident fix` applies the rename.
+"
+            }
+            Self::UnusedSponge => {
+                "\
+A sponge state is bound but never absorbed into or squeezed, or a call
+returning one is used as a statement. Sponge operations thread the state
+through; dropping it usually means a result was lost.
+
+Erroneous example:
+
+    let s: hash.Sponge = hash.init()
+    hash.absorb(s, a, b, c, d, e, f, g, h, i, j)
+
+Fix:
+
+    let s: hash.Sponge = hash.init()
+    let s2: hash.Sponge = hash.absorb(s, a, b, c, d, e, f, g, h, i, j)
+    let out: [Field; 10] = hash.squeeze(s2)
+
+Name the binding `_s` to drop it on purpose.
+"
+            }
+            Self::U32Overflow => {
+                "\
+Under #[auto_range_check], a Field sum becomes a U32. The Field sum does
+not wrap at 2^32, so the inserted range check fails when it overflows.
+
+Erroneous example:
+
+    let c: U32 = as_u32(x + y)
+
+Fix: keep the operands U32 and add them with `u32_assert_add` (fails on
+overflow), `u32_wrapping_add` or `u32_checked_add`.
+"
+            }
+            Self::UnknownVariant => {
+                "\
+The enum has no variant of this name, declares a variant twice, or a
+variant pattern binds the wrong number of payload values.
+
+Erroneous example:
+
+    enum Shape { Circle(Field), Rect(Field, Field) }
+    let s: Shape = Shape.Square(2)
+
+Fix:
+
+    let s: Shape = Shape.Rect(2, 2)
+"
+            }
+            Self::LoopExit => {
+                "\
+`break` and `continue` leave or skip an iteration of the innermost `for`
+or `while` loop, so they cannot appear outside one.
+
+Erroneous example:
+
+    fn f(x: Field) {
+        if x == 0 {
+            break
+        }
+        pub_write(x)
+    }
+
+Fix: use `return` to leave the function, or move the code into a loop.
+"
+            }
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "E{:04}", self.number())
+    }
+}
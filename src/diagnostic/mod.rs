@@ -1,8 +1,13 @@
 use crate::span::Span;
 use std::cell::Cell;
 
+pub mod code;
+
+pub use code::ErrorCode;
+
 thread_local! {
     static SUPPRESS_WARNINGS: Cell<bool> = const { Cell::new(false) };
+    static SILENCE: Cell<bool> = const { Cell::new(false) };
 }
 
 /// Suppress warning diagnostics on the current thread.
@@ -24,6 +29,21 @@ fn warnings_suppressed() -> bool {
     SUPPRESS_WARNINGS.with(|s| s.get())
 }
 
+/// Suppress every diagnostic on the current thread, errors included.
+/// Returns a guard that restores the previous state on drop.
+pub fn silence() -> SilenceGuard {
+    let prev = SILENCE.with(|s| s.replace(true));
+    SilenceGuard(prev)
+}
+
+pub struct SilenceGuard(bool);
+
+impl Drop for SilenceGuard {
+    fn drop(&mut self) {
+        SILENCE.with(|s| s.set(self.0));
+    }
+}
+
 /// A compiler diagnostic (error, warning, or hint).
 #[derive(Clone, Debug)]
 pub struct Diagnostic {
@@ -32,6 +52,11 @@ pub struct Diagnostic {
     pub span: Span,
     pub notes: Vec<String>,
     pub help: Option<String>,
+    /// Source changes that resolve the diagnostic.
+    pub suggestions: Vec<Suggestion>,
+    /// Stable code, shown as `error[E0201]` and explained by `trident
+    /// explain`.
+    pub code: Option<ErrorCode>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -40,6 +65,41 @@ pub enum Severity {
     Warning,
 }
 
+/// A source change that resolves a diagnostic: text replacements in the
+/// file the diagnostic points into.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    /// What the change does, e.g. "insert `mut`".
+    pub message: String,
+    /// Non-overlapping replacements; an empty span inserts.
+    pub edits: Vec<(Span, String)>,
+    pub applicability: Applicability,
+}
+
+/// Whether a suggestion can be applied without a person looking at it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Applicability {
+    /// The change is correct as it stands; `trident fix` applies it.
+    MachineApplicable,
+    /// The change is likely what was meant, but may need review.
+    MaybeIncorrect,
+}
+
+impl Suggestion {
+    pub fn new(message: String, edits: Vec<(Span, String)>) -> Self {
+        Self {
+            message,
+            edits,
+            applicability: Applicability::MachineApplicable,
+        }
+    }
+
+    pub fn maybe_incorrect(mut self) -> Self {
+        self.applicability = Applicability::MaybeIncorrect;
+        self
+    }
+}
+
 impl Diagnostic {
     pub fn error(message: String, span: Span) -> Self {
         Self {
@@ -48,6 +108,8 @@ impl Diagnostic {
             span,
             notes: Vec::new(),
             help: None,
+            suggestions: Vec::new(),
+            code: None,
         }
     }
 
@@ -58,6 +120,8 @@ impl Diagnostic {
             span,
             notes: Vec::new(),
             help: None,
+            suggestions: Vec::new(),
+            code: None,
         }
     }
 
@@ -71,9 +135,21 @@ impl Diagnostic {
         self
     }
 
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
     /// Render the diagnostic to stderr using ariadne.
     pub fn render(&self, filename: &str, source: &str) {
-        if self.severity == Severity::Warning && warnings_suppressed() {
+        if SILENCE.with(|s| s.get())
+            || (self.severity == Severity::Warning && warnings_suppressed())
+        {
             return;
         }
         use ariadne::{Color, Label, Report, ReportKind, Source};
@@ -88,13 +164,28 @@ impl Diagnostic {
             Severity::Warning => Color::Yellow,
         };
 
-        let mut report = Report::build(kind, filename, self.span.start as usize)
-            .with_message(&self.message)
-            .with_label(
-                Label::new((filename, self.span.start as usize..self.span.end as usize))
-                    .with_message(&self.message)
-                    .with_color(color),
-            );
+        let mut report =
+            Report::build(kind, filename, self.span.start as usize).with_message(&self.message);
+        if let Some(code) = self.code {
+            report = report.with_code(code);
+        }
+        // Without a source (a module that could not be found) there is
+        // nothing to label, and ariadne prints only the message.
+        if source.is_empty() {
+            let _ = report.finish().eprint((filename, Source::from(source)));
+            for note in &self.notes {
+                eprintln!("  Note: {}", note);
+            }
+            if let Some(help) = &self.help {
+                eprintln!("  Help: {}", help);
+            }
+            return;
+        }
+        report = report.with_label(
+            Label::new((filename, self.span.start as usize..self.span.end as usize))
+                .with_message(&self.message)
+                .with_color(color),
+        );
 
         for note in &self.notes {
             report = report.with_note(note);
@@ -167,6 +258,56 @@ mod tests {
         assert!(d.help.is_some());
     }
 
+    #[test]
+    fn test_with_suggestion() {
+        let d = Diagnostic::error("error".to_string(), Span::new(0, 4, 5)).with_suggestion(
+            Suggestion::new(
+                "insert `mut`".to_string(),
+                vec![(Span::new(0, 4, 4), "mut ".to_string())],
+            )
+            .maybe_incorrect(),
+        );
+        assert_eq!(d.suggestions.len(), 1);
+        assert_eq!(d.suggestions[0].edits[0].1, "mut ");
+        assert_eq!(
+            d.suggestions[0].applicability,
+            Applicability::MaybeIncorrect
+        );
+    }
+
+    #[test]
+    fn test_error_code_display_and_parse() {
+        let d = Diagnostic::error("type mismatch".to_string(), Span::dummy())
+            .with_code(ErrorCode::TypeMismatch);
+        assert_eq!(d.code, Some(ErrorCode::TypeMismatch));
+        assert_eq!(ErrorCode::TypeMismatch.to_string(), "E0201");
+        assert_eq!(ErrorCode::UnexpectedCharacter.to_string(), "E0001");
+        assert_eq!(ErrorCode::parse("e0201"), Some(ErrorCode::TypeMismatch));
+        assert_eq!(ErrorCode::parse("0201"), Some(ErrorCode::TypeMismatch));
+        assert_eq!(ErrorCode::parse("E0999"), None);
+        assert_eq!(ErrorCode::parse("E201"), None);
+    }
+
+    #[test]
+    fn test_error_codes_are_ordered_and_explained() {
+        for pair in ErrorCode::ALL.windows(2) {
+            assert!(pair[0].number() < pair[1].number(), "{:?}", pair);
+        }
+        for &code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::parse(&code.to_string()), Some(code));
+            assert!(!code.title().is_empty());
+            assert!(code.explanation().contains("Fix"), "{} has no fix", code);
+        }
+    }
+
+    #[test]
+    fn test_render_with_code_does_not_panic() {
+        let source = "let x: U32 = pub_read()\n";
+        let d = Diagnostic::error("type mismatch".to_string(), Span::new(0, 4, 5))
+            .with_code(ErrorCode::TypeMismatch);
+        d.render("test.tri", source);
+    }
+
     #[test]
     fn test_render_does_not_panic() {
         let source = "let x: Field = 1\nlet y: U32 = x\n";
@@ -0,0 +1,340 @@
+//! Field-element encoding of host values, compatible with BFieldCodec.
+//!
+//! Values cross the host/VM boundary as sequences of Goldilocks
+//! elements — public input, secret input, and public output — so the
+//! host and the circuit must agree on one layout:
+//!
+//! - `Goldilocks`, `u32`, and `bool` take one element.
+//! - `u64` takes two elements, low 32 bits first; `u128` takes four.
+//! - `[T; N]` is its items in order, so a digest (`[Goldilocks; 5]`)
+//!   is its five elements.
+//! - `Vec<T>` is its item count followed by its items.
+//! - `Option<T>` is `0`, or `1` followed by the value.
+//! - Tuples and structs are their fields in declaration order.
+//!
+//! An item or field whose length varies between values (a `Vec`, an
+//! `Option`) is preceded by its encoded length, so a reader can step
+//! over it. Every Trident type has a static length, which makes the
+//! encoding of a program struct the sequence of `pub_read`/`divine`
+//! calls that builds it field by field; `trident generate --codec`
+//! emits host structs with these encoders.
+
+use super::{Goldilocks, PrimeField};
+
+/// Encode to and decode from a sequence of field elements.
+pub trait BFieldCodec: Sized {
+    /// Encoded length, when it is the same for every value.
+    fn static_length() -> Option<usize>;
+
+    /// Append the encoding of `self` to `out`.
+    fn encode_into(&self, out: &mut Vec<Goldilocks>);
+
+    /// Read one value from the front of `reader`.
+    fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String>;
+
+    /// The encoding of `self`.
+    fn encode(&self) -> Vec<Goldilocks> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+
+    /// Decode a value that spans all of `sequence`.
+    fn decode(sequence: &[Goldilocks]) -> Result<Self, String> {
+        let mut reader = Reader::new(sequence);
+        let value = Self::decode_from(&mut reader)?;
+        match reader.remaining() {
+            0 => Ok(value),
+            n => Err(format!("{} trailing elements after the value", n)),
+        }
+    }
+}
+
+/// Cursor over an encoded sequence.
+pub struct Reader<'a> {
+    sequence: &'a [Goldilocks],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(sequence: &'a [Goldilocks]) -> Self {
+        Self { sequence, pos: 0 }
+    }
+
+    /// Number of elements not yet read.
+    pub fn remaining(&self) -> usize {
+        self.sequence.len() - self.pos
+    }
+
+    /// Read the next element.
+    pub fn next_element(&mut self) -> Result<Goldilocks, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Read the next `n` elements.
+    pub fn take(&mut self, n: usize) -> Result<&'a [Goldilocks], String> {
+        if n > self.remaining() {
+            return Err(format!(
+                "sequence ends early: need {} more elements, {} left",
+                n,
+                self.remaining()
+            ));
+        }
+        let slice = &self.sequence[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    /// Read the next element as a length or count.
+    fn next_length(&mut self) -> Result<usize, String> {
+        let value = self.next_element()?.to_u64();
+        usize::try_from(value)
+            .ok()
+            .filter(|&n| n <= self.remaining())
+            .ok_or_else(|| format!("length {} exceeds the rest of the sequence", value))
+    }
+}
+
+/// Encode one item of a container, length-prefixed if its length varies.
+pub fn encode_item<T: BFieldCodec>(item: &T, out: &mut Vec<Goldilocks>) {
+    if T::static_length().is_some() {
+        item.encode_into(out);
+    } else {
+        let start = out.len();
+        out.push(Goldilocks::ZERO);
+        item.encode_into(out);
+        out[start] = Goldilocks::from_u64((out.len() - start - 1) as u64);
+    }
+}
+
+/// Decode one item written by `encode_item`.
+pub fn decode_item<T: BFieldCodec>(reader: &mut Reader<'_>) -> Result<T, String> {
+    if T::static_length().is_some() {
+        T::decode_from(reader)
+    } else {
+        let len = reader.next_length()?;
+        T::decode(reader.take(len)?)
+    }
+}
+
+// ─── Scalars ───────────────────────────────────────────────────────
+
+impl BFieldCodec for Goldilocks {
+    fn static_length() -> Option<usize> {
+        Some(1)
+    }
+
+    fn encode_into(&self, out: &mut Vec<Goldilocks>) {
+        out.push(*self);
+    }
+
+    fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String> {
+        reader.next_element()
+    }
+}
+
+impl BFieldCodec for bool {
+    fn static_length() -> Option<usize> {
+        Some(1)
+    }
+
+    fn encode_into(&self, out: &mut Vec<Goldilocks>) {
+        out.push(Goldilocks::from_u64(*self as u64));
+    }
+
+    fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String> {
+        match reader.next_element()?.to_u64() {
+            0 => Ok(false),
+            1 => Ok(true),
+            v => Err(format!("{} is not a boolean", v)),
+        }
+    }
+}
+
+impl BFieldCodec for u32 {
+    fn static_length() -> Option<usize> {
+        Some(1)
+    }
+
+    fn encode_into(&self, out: &mut Vec<Goldilocks>) {
+        out.push(Goldilocks::from_u64(*self as u64));
+    }
+
+    fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String> {
+        let v = reader.next_element()?.to_u64();
+        u32::try_from(v).map_err(|_| format!("{} does not fit in a u32", v))
+    }
+}
+
+impl BFieldCodec for u64 {
+    fn static_length() -> Option<usize> {
+        Some(2)
+    }
+
+    fn encode_into(&self, out: &mut Vec<Goldilocks>) {
+        (*self as u32).encode_into(out);
+        ((*self >> 32) as u32).encode_into(out);
+    }
+
+    fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String> {
+        let lo = u32::decode_from(reader)? as u64;
+        let hi = u32::decode_from(reader)? as u64;
+        Ok(hi << 32 | lo)
+    }
+}
+
+impl BFieldCodec for u128 {
+    fn static_length() -> Option<usize> {
+        Some(4)
+    }
+
+    fn encode_into(&self, out: &mut Vec<Goldilocks>) {
+        (*self as u64).encode_into(out);
+        ((*self >> 64) as u64).encode_into(out);
+    }
+
+    fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String> {
+        let lo = u64::decode_from(reader)? as u128;
+        let hi = u64::decode_from(reader)? as u128;
+        Ok(hi << 64 | lo)
+    }
+}
+
+// ─── Containers ────────────────────────────────────────────────────
+
+impl<T: BFieldCodec, const N: usize> BFieldCodec for [T; N] {
+    fn static_length() -> Option<usize> {
+        T::static_length().map(|len| len * N)
+    }
+
+    fn encode_into(&self, out: &mut Vec<Goldilocks>) {
+        for item in self {
+            encode_item(item, out);
+        }
+    }
+
+    fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String> {
+        let items = (0..N)
+            .map(|_| decode_item(reader))
+            .collect::<Result<Vec<T>, String>>()?;
+        items
+            .try_into()
+            .map_err(|_| "array length mismatch".to_string())
+    }
+}
+
+impl<T: BFieldCodec> BFieldCodec for Vec<T> {
+    fn static_length() -> Option<usize> {
+        None
+    }
+
+    fn encode_into(&self, out: &mut Vec<Goldilocks>) {
+        out.push(Goldilocks::from_u64(self.len() as u64));
+        for item in self {
+            encode_item(item, out);
+        }
+    }
+
+    fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String> {
+        let count = reader.next_length()?;
+        (0..count).map(|_| decode_item(reader)).collect()
+    }
+}
+
+impl<T: BFieldCodec> BFieldCodec for Option<T> {
+    fn static_length() -> Option<usize> {
+        None
+    }
+
+    fn encode_into(&self, out: &mut Vec<Goldilocks>) {
+        match self {
+            None => out.push(Goldilocks::ZERO),
+            Some(value) => {
+                out.push(Goldilocks::ONE);
+                value.encode_into(out);
+            }
+        }
+    }
+
+    fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String> {
+        match reader.next_element()?.to_u64() {
+            0 => Ok(None),
+            1 => T::decode_from(reader).map(Some),
+            v => Err(format!("{} is not an option tag", v)),
+        }
+    }
+}
+
+macro_rules! tuple_codec {
+    ($($name:ident . $idx:tt),+) => {
+        impl<$($name: BFieldCodec),+> BFieldCodec for ($($name,)+) {
+            fn static_length() -> Option<usize> {
+                Some(0 $(+ $name::static_length()?)+)
+            }
+
+            fn encode_into(&self, out: &mut Vec<Goldilocks>) {
+                $(encode_item(&self.$idx, out);)+
+            }
+
+            fn decode_from(reader: &mut Reader<'_>) -> Result<Self, String> {
+                Ok(($(decode_item::<$name>(reader)?,)+))
+            }
+        }
+    };
+}
+
+tuple_codec!(A.0, B.1);
+tuple_codec!(A.0, B.1, C.2);
+tuple_codec!(A.0, B.1, C.2, D.3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn g(values: &[u64]) -> Vec<Goldilocks> {
+        values.iter().map(|&v| Goldilocks::from_u64(v)).collect()
+    }
+
+    #[test]
+    fn scalars_encode_to_their_documented_layout() {
+        assert_eq!(true.encode(), g(&[1]));
+        assert_eq!(7u32.encode(), g(&[7]));
+        assert_eq!(0x1_0000_0002u64.encode(), g(&[2, 1]));
+        assert_eq!((5u128 << 64 | 3).encode(), g(&[3, 0, 5, 0]));
+        assert_eq!(u64::static_length(), Some(2));
+    }
+
+    #[test]
+    fn dynamic_items_are_length_prefixed() {
+        let flat: Vec<u32> = vec![4, 5];
+        assert_eq!(flat.encode(), g(&[2, 4, 5]));
+        let nested: Vec<Vec<u32>> = vec![vec![1], vec![]];
+        assert_eq!(nested.encode(), g(&[2, 2, 1, 1, 1, 0]));
+        let pair: (Option<u32>, [bool; 2]) = (Some(9), [true, false]);
+        assert_eq!(pair.encode(), g(&[2, 1, 9, 1, 0]));
+        assert_eq!(<(Option<u32>, bool)>::static_length(), None);
+    }
+
+    #[test]
+    fn every_encoding_decodes_to_the_same_value() {
+        let value: (Vec<[Goldilocks; 5]>, Option<u64>, u128) = (
+            vec![[Goldilocks::from_u64(11); 5], [Goldilocks::ZERO; 5]],
+            Some(u64::MAX),
+            u128::MAX - 1,
+        );
+        let decoded = <(Vec<[Goldilocks; 5]>, Option<u64>, u128)>::decode(&value.encode());
+        assert_eq!(decoded, Ok(value));
+        let nested: Vec<Vec<bool>> = vec![vec![true], vec![], vec![false, true]];
+        assert_eq!(Vec::<Vec<bool>>::decode(&nested.encode()), Ok(nested));
+    }
+
+    #[test]
+    fn malformed_sequences_are_rejected() {
+        assert!(bool::decode(&g(&[2])).is_err());
+        assert!(u32::decode(&g(&[1 << 32])).is_err());
+        assert!(u32::decode(&g(&[1, 2])).is_err());
+        assert!(<[u32; 3]>::decode(&g(&[1, 2])).is_err());
+        assert!(Vec::<u32>::decode(&g(&[5, 1])).is_err());
+        assert!(Option::<u32>::decode(&g(&[3, 0])).is_err());
+    }
+}
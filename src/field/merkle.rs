@@ -0,0 +1,224 @@
+//! Host-side Merkle trees in the layout `std.crypto.merkle` verifies.
+//!
+//! Nodes are numbered heap-style: the root is node 1 and the children
+//! of node n are 2n and 2n+1, so leaf i of a depth-d tree is node
+//! 2^d + i. This is the index `merkle.step` (Triton's `merkle_step`)
+//! takes: it divines the sibling digest, hashes (current, sibling) when
+//! the index is even and (sibling, current) when it is odd, and halves
+//! the index. An `AuthPath` lists siblings bottom-up — the order the
+//! verifier consumes them from the secret digest queue.
+//!
+//! The node hash is pluggable through `MerkleHasher`. Trident ships the
+//! Poseidon2 instance from `field::poseidon2`; warriors supply the
+//! VM's native hash (Tip5 on Triton) for trees checked by `merkle_step`.
+
+use super::poseidon2::Sponge;
+use super::{Goldilocks, PrimeField};
+
+/// Number of field elements in a digest.
+pub const DIGEST_LEN: usize = 5;
+
+/// A node digest: five Goldilocks elements, as `Digest` in Trident.
+pub type Digest = [Goldilocks; DIGEST_LEN];
+
+/// Hash function used to build a tree.
+pub trait MerkleHasher {
+    /// Hash an encoded leaf (any number of elements) to its digest.
+    fn hash_leaf(&self, elements: &[Goldilocks]) -> Digest;
+    /// Hash two child digests into their parent.
+    fn hash_pair(&self, left: &Digest, right: &Digest) -> Digest;
+}
+
+/// Poseidon2 over Goldilocks (t=8, rate=4), squeezing five elements.
+///
+/// Leaves absorb their elements followed by the element count, so a
+/// ten-element leaf never hashes like an inner node.
+pub struct Poseidon2Hasher;
+
+impl MerkleHasher for Poseidon2Hasher {
+    fn hash_leaf(&self, elements: &[Goldilocks]) -> Digest {
+        let mut sponge = Sponge::goldilocks();
+        sponge.absorb(elements);
+        sponge.absorb(&[Goldilocks::from_u64(elements.len() as u64)]);
+        to_digest(&sponge.squeeze(DIGEST_LEN))
+    }
+
+    fn hash_pair(&self, left: &Digest, right: &Digest) -> Digest {
+        let mut sponge = Sponge::goldilocks();
+        sponge.absorb(left);
+        sponge.absorb(right);
+        to_digest(&sponge.squeeze(DIGEST_LEN))
+    }
+}
+
+fn to_digest(elements: &[Goldilocks]) -> Digest {
+    std::array::from_fn(|i| elements[i])
+}
+
+// ─── Tree ──────────────────────────────────────────────────────────
+
+/// A complete binary Merkle tree over a power-of-two number of leaves.
+pub struct MerkleTree {
+    /// Heap-ordered nodes; index 0 is unused, the root is at 1.
+    nodes: Vec<Digest>,
+    depth: u32,
+}
+
+impl MerkleTree {
+    /// Build a tree over encoded leaves, hashing each with `hash_leaf`.
+    pub fn from_leaves(
+        leaves: &[Vec<Goldilocks>],
+        hasher: &impl MerkleHasher,
+    ) -> Result<Self, String> {
+        let digests = leaves.iter().map(|l| hasher.hash_leaf(l)).collect();
+        Self::from_digests(digests, hasher)
+    }
+
+    /// Build a tree over leaf digests.
+    ///
+    /// The leaf count must be a nonzero power of two no larger than
+    /// 2^31, so that every node index fits in a `U32`.
+    pub fn from_digests(leaves: Vec<Digest>, hasher: &impl MerkleHasher) -> Result<Self, String> {
+        let n = leaves.len();
+        if n == 0 || !n.is_power_of_two() {
+            return Err(format!(
+                "a Merkle tree needs a power-of-two number of leaves, got {}",
+                n
+            ));
+        }
+        if n > 1 << 31 {
+            return Err(format!("{} leaves exceed the U32 node index range", n));
+        }
+        let mut nodes = vec![[Goldilocks::ZERO; DIGEST_LEN]; n];
+        nodes.extend(leaves);
+        for i in (1..n).rev() {
+            nodes[i] = hasher.hash_pair(&nodes[2 * i], &nodes[2 * i + 1]);
+        }
+        Ok(Self {
+            nodes,
+            depth: n.trailing_zeros(),
+        })
+    }
+
+    /// The root digest.
+    pub fn root(&self) -> Digest {
+        self.nodes[1]
+    }
+
+    /// Number of levels between a leaf and the root.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Number of leaves.
+    pub fn num_leaves(&self) -> usize {
+        1 << self.depth
+    }
+
+    /// Digest of leaf `index`.
+    pub fn leaf(&self, index: usize) -> Option<Digest> {
+        (index < self.num_leaves()).then(|| self.nodes[self.num_leaves() + index])
+    }
+
+    /// Authentication path for leaf `index`.
+    pub fn auth_path(&self, index: usize) -> Option<AuthPath> {
+        let leaf = self.leaf(index)?;
+        let node_index = self.num_leaves() + index;
+        let mut siblings = Vec::with_capacity(self.depth as usize);
+        let mut node = node_index;
+        while node > 1 {
+            siblings.push(self.nodes[node ^ 1]);
+            node /= 2;
+        }
+        Some(AuthPath {
+            leaf_index: index,
+            node_index: node_index as u64,
+            leaf,
+            siblings,
+        })
+    }
+}
+
+/// Everything a prover supplies to authenticate one leaf.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthPath {
+    /// Position of the leaf among the leaves.
+    pub leaf_index: usize,
+    /// Heap index of the leaf node — the `leaf_idx` passed to `merkle.verify`.
+    pub node_index: u64,
+    /// The leaf digest.
+    pub leaf: Digest,
+    /// Sibling digests, bottom-up, in the order `merkle.step` divines them.
+    pub siblings: Vec<Digest>,
+}
+
+impl AuthPath {
+    /// Recompute the root the way the verifier does.
+    pub fn root(&self, hasher: &impl MerkleHasher) -> Digest {
+        let mut index = self.node_index;
+        let mut current = self.leaf;
+        for sibling in &self.siblings {
+            current = if index.is_multiple_of(2) {
+                hasher.hash_pair(&current, sibling)
+            } else {
+                hasher.hash_pair(sibling, &current)
+            };
+            index /= 2;
+        }
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: u64) -> Vec<Vec<Goldilocks>> {
+        (0..n)
+            .map(|i| vec![Goldilocks::from_u64(i), Goldilocks::from_u64(i * i)])
+            .collect()
+    }
+
+    #[test]
+    fn every_auth_path_recomputes_the_root() {
+        let tree = MerkleTree::from_leaves(&leaves(8), &Poseidon2Hasher).expect("8 leaves");
+        assert_eq!(tree.depth(), 3);
+        for i in 0..8 {
+            let path = tree.auth_path(i).expect("leaf in range");
+            assert_eq!(path.node_index, 8 + i as u64);
+            assert_eq!(path.siblings.len(), 3);
+            assert_eq!(path.root(&Poseidon2Hasher), tree.root());
+        }
+        assert!(tree.auth_path(8).is_none());
+    }
+
+    #[test]
+    fn siblings_are_listed_bottom_up() {
+        let tree = MerkleTree::from_leaves(&leaves(4), &Poseidon2Hasher).expect("4 leaves");
+        let path = tree.auth_path(2).expect("leaf in range");
+        // Leaf 2 is node 6: its sibling is node 7 (leaf 3), then node 2.
+        assert_eq!(path.siblings[0], tree.leaf(3).expect("leaf 3"));
+        let left = Poseidon2Hasher.hash_pair(
+            &tree.leaf(0).expect("leaf 0"),
+            &tree.leaf(1).expect("leaf 1"),
+        );
+        assert_eq!(path.siblings[1], left);
+    }
+
+    #[test]
+    fn tampered_path_yields_another_root() {
+        let tree = MerkleTree::from_leaves(&leaves(4), &Poseidon2Hasher).expect("4 leaves");
+        let mut path = tree.auth_path(1).expect("leaf in range");
+        path.siblings[1][0] = path.siblings[1][0].add(Goldilocks::ONE);
+        assert_ne!(path.root(&Poseidon2Hasher), tree.root());
+    }
+
+    #[test]
+    fn leaf_count_must_be_a_power_of_two() {
+        assert!(MerkleTree::from_leaves(&leaves(3), &Poseidon2Hasher).is_err());
+        assert!(MerkleTree::from_leaves(&[], &Poseidon2Hasher).is_err());
+        let single = MerkleTree::from_leaves(&leaves(1), &Poseidon2Hasher).expect("1 leaf");
+        assert_eq!(single.depth(), 0);
+        assert_eq!(single.root(), single.leaf(0).expect("leaf 0"));
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! This module provides field-generic math that every target warrior reuses:
 //! - `PrimeField` trait with concrete implementations (Goldilocks, BabyBear, Mersenne31)
+//! - `codec` — BFieldCodec-compatible encoding of host values as field elements
 //! - `poseidon2` — generic Poseidon2 sponge hash over any PrimeField
+//! - `merkle` — Merkle trees and authentication paths for `std.crypto.merkle`
 //! - `proof` — universal STARK proof estimation (padded height, FRI params, proof size)
 //!
 //! Three fields cover all 20 supported VMs:
@@ -11,8 +13,9 @@
 //! - Mersenne31 (2^31 - 1): Plonky3, Circle STARKs
 
 pub mod babybear;
-pub mod fixed;
+pub mod codec;
 pub mod goldilocks;
+pub mod merkle;
 pub mod mersenne31;
 pub mod poseidon2;
 pub mod proof;
@@ -93,6 +96,8 @@ pub trait PrimeField: Copy + Clone + Eq + PartialEq + Ord + PartialOrd + std::fm
 
 #[cfg(test)]
 mod tests {
+    use super::babybear::BabyBear;
+    use super::mersenne31::Mersenne31;
     use super::*;
 
     /// Generic test suite — run for every PrimeField implementation.
@@ -4,10 +4,13 @@
 //! configurable state width, round counts, and S-box. The sponge
 //! construction (absorb/squeeze) is field-generic.
 //!
-//! Warriors call `poseidon2_hash::<Goldilocks>(...)` or
-//! `poseidon2_hash::<BabyBear>(...)` — same code, different field.
+//! The Goldilocks instance (t=8, rate=4, RF=8, RP=22) is the hash used
+//! for content addressing across the toolchain, and the one computed
+//! in-VM by `std.crypto.poseidon2`. Host tools that precompute values
+//! for a program (leaves in input files, bundle and registry hashes)
+//! use this module so both sides agree bit for bit.
 
-use super::PrimeField;
+use super::{Goldilocks, PrimeField};
 
 // ─── Poseidon2 Parameters ──────────────────────────────────────────
 
@@ -27,12 +30,15 @@ pub struct Poseidon2Config<F: PrimeField> {
     pub round_constants: Vec<F>,
 }
 
-/// Default Poseidon2 config for Goldilocks (t=8, rate=4, RF=8, RP=22).
-pub fn goldilocks_config() -> Poseidon2Config<super::Goldilocks> {
-    use super::Goldilocks;
+/// State width of the Goldilocks instance.
+pub const WIDTH: usize = 8;
+/// Rate of the Goldilocks instance (elements absorbed per permutation).
+pub const RATE: usize = 4;
 
-    let width = 8;
-    let rate = 4;
+/// Default Poseidon2 config for Goldilocks (t=8, rate=4, RF=8, RP=22).
+pub fn goldilocks_config() -> Poseidon2Config<Goldilocks> {
+    let width = WIDTH;
+    let rate = RATE;
     let rounds_f = 8;
     let rounds_p = 22;
 
@@ -88,12 +94,19 @@ fn generate_round_constants<F: PrimeField>(
 
 // ─── Cached Goldilocks Config ──────────────────────────────────────
 
-fn cached_goldilocks_config() -> &'static Poseidon2Config<super::Goldilocks> {
-    static CONFIG: std::sync::OnceLock<Poseidon2Config<super::Goldilocks>> =
-        std::sync::OnceLock::new();
+fn cached_goldilocks_config() -> &'static Poseidon2Config<Goldilocks> {
+    static CONFIG: std::sync::OnceLock<Poseidon2Config<Goldilocks>> = std::sync::OnceLock::new();
     CONFIG.get_or_init(goldilocks_config)
 }
 
+/// The 86 Goldilocks round constants, in the order `std.crypto.poseidon2`
+/// takes them: 32 for the first full rounds, 22 partial, 32 last full.
+///
+/// Write these to RAM to call `permute_from_ram` from a program.
+pub fn round_constants() -> &'static [Goldilocks] {
+    &cached_goldilocks_config().round_constants
+}
+
 // ─── Permutation ───────────────────────────────────────────────────
 
 /// Apply the Poseidon2 S-box (x^7) to a single field element.
@@ -161,74 +174,97 @@ pub fn permutation<F: PrimeField>(state: &mut [F], config: &Poseidon2Config<F>)
     }
 }
 
-// ─── Sponge Hasher ─────────────────────────────────────────────────
+/// The Goldilocks permutation, as computed by `std.crypto.poseidon2.permute`.
+pub fn permute(state: &mut [Goldilocks; WIDTH]) {
+    permutation(state, cached_goldilocks_config());
+}
 
-/// Absorb field elements, permute, squeeze — generic over PrimeField.
-fn sponge_hash<F: PrimeField>(
-    elements: &[F],
-    config: &Poseidon2Config<F>,
-    squeeze_count: usize,
-) -> Vec<F> {
-    let mut state = vec![F::ZERO; config.width];
-    let mut absorbed = 0;
+// ─── Sponge ────────────────────────────────────────────────────────
 
-    for &elem in elements {
-        if absorbed == config.rate {
-            permutation(&mut state, config);
-            absorbed = 0;
+/// Incremental sponge: absorb any number of elements, then squeeze.
+///
+/// Elements are added into the rate portion; the state is permuted each
+/// time the rate fills, and once more before squeezing.
+pub struct Sponge<'a, F: PrimeField> {
+    config: &'a Poseidon2Config<F>,
+    state: Vec<F>,
+    absorbed: usize,
+}
+
+impl Sponge<'static, Goldilocks> {
+    /// A sponge over the Goldilocks instance.
+    pub fn goldilocks() -> Self {
+        Self::new(cached_goldilocks_config())
+    }
+}
+
+impl<'a, F: PrimeField> Sponge<'a, F> {
+    /// A sponge with an all-zero state.
+    pub fn new(config: &'a Poseidon2Config<F>) -> Self {
+        Self {
+            config,
+            state: vec![F::ZERO; config.width],
+            absorbed: 0,
         }
-        state[absorbed] = state[absorbed].add(elem);
-        absorbed += 1;
     }
 
-    // Squeeze
-    permutation(&mut state, config);
-    let mut out = Vec::with_capacity(squeeze_count);
-    let mut squeezed = 0;
-    loop {
-        for &elem in state[..config.rate].iter() {
-            out.push(elem);
-            squeezed += 1;
-            if squeezed == squeeze_count {
-                return out;
+    /// Absorb field elements.
+    pub fn absorb(&mut self, elements: &[F]) {
+        for &elem in elements {
+            if self.absorbed == self.config.rate {
+                permutation(&mut self.state, self.config);
+                self.absorbed = 0;
             }
+            self.state[self.absorbed] = self.state[self.absorbed].add(elem);
+            self.absorbed += 1;
         }
-        permutation(&mut state, config);
+    }
+
+    /// Absorb bytes: 7 bytes per element (little-endian), then the length.
+    pub fn absorb_bytes(&mut self, data: &[u8]) {
+        const BYTES_PER_ELEM: usize = 7;
+        let mut elements = Vec::with_capacity(data.len() / BYTES_PER_ELEM + 1);
+        for chunk in data.chunks(BYTES_PER_ELEM) {
+            let mut buf = [0u8; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            elements.push(F::from_u64(u64::from_le_bytes(buf)));
+        }
+        // Length separator
+        elements.push(F::from_u64(data.len() as u64));
+        self.absorb(&elements);
+    }
+
+    /// Permute and squeeze `count` elements from the rate portion.
+    pub fn squeeze(&mut self, count: usize) -> Vec<F> {
+        let mut out = Vec::with_capacity(count);
+        while out.len() < count {
+            permutation(&mut self.state, self.config);
+            self.absorbed = 0;
+            let take = (count - out.len()).min(self.config.rate);
+            out.extend_from_slice(&self.state[..take]);
+        }
+        out
     }
 }
 
 // ─── Goldilocks Convenience Functions ──────────────────────────────
 
 /// Hash arbitrary bytes using Poseidon2 over Goldilocks, returning 32 bytes.
-///
-/// This is the drop-in replacement for `crate::package::poseidon2::hash_bytes`.
 pub fn hash_bytes_goldilocks(data: &[u8]) -> [u8; 32] {
-    use super::Goldilocks;
-
-    const BYTES_PER_ELEM: usize = 7;
-    let mut elements = Vec::with_capacity(data.len() / BYTES_PER_ELEM + 2);
-    for chunk in data.chunks(BYTES_PER_ELEM) {
-        let mut buf = [0u8; 8];
-        buf[..chunk.len()].copy_from_slice(chunk);
-        elements.push(Goldilocks::from_u64(u64::from_le_bytes(buf)));
-    }
-    // Length separator
-    elements.push(Goldilocks::from_u64(data.len() as u64));
-
-    let config = cached_goldilocks_config();
-    let result = sponge_hash(&elements, config, 4);
-
+    let mut sponge = Sponge::goldilocks();
+    sponge.absorb_bytes(data);
     let mut out = [0u8; 32];
-    for (i, elem) in result.iter().enumerate() {
+    for (i, elem) in sponge.squeeze(4).iter().enumerate() {
         out[i * 8..i * 8 + 8].copy_from_slice(&elem.to_u64().to_le_bytes());
     }
     out
 }
 
 /// Hash Goldilocks field elements, returning 4 elements.
-pub fn hash_fields_goldilocks(elements: &[super::Goldilocks]) -> [super::Goldilocks; 4] {
-    let config = cached_goldilocks_config();
-    let result = sponge_hash(elements, config, 4);
+pub fn hash_fields_goldilocks(elements: &[Goldilocks]) -> [Goldilocks; 4] {
+    let mut sponge = Sponge::goldilocks();
+    sponge.absorb(elements);
+    let result = sponge.squeeze(4);
     [result[0], result[1], result[2], result[3]]
 }
 
@@ -237,7 +273,6 @@ pub fn hash_fields_goldilocks(elements: &[super::Goldilocks]) -> [super::Goldilo
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::field::Goldilocks;
 
     #[test]
     fn goldilocks_hash_deterministic() {
@@ -276,6 +311,104 @@ mod tests {
         }
     }
 
+    fn words(elems: &[Goldilocks]) -> Vec<u64> {
+        elems.iter().map(|e| e.to_u64()).collect()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    // Known-answer vectors. They are cross-checked against an independent
+    // transcription of the round functions in std/crypto/poseidon2.tri;
+    // a change here changes every content hash in the toolchain.
+
+    #[test]
+    fn round_constants_match_vm_layout() {
+        let rc = round_constants();
+        assert_eq!(rc.len(), 86);
+        assert_eq!(rc[0].to_u64(), 5112053732956317697);
+        assert_eq!(rc[32].to_u64(), 3947817468721077627);
+        assert_eq!(rc[85].to_u64(), 2618977002729543425);
+    }
+
+    #[test]
+    fn permutation_known_answers() {
+        let mut zero = [Goldilocks::ZERO; WIDTH];
+        permute(&mut zero);
+        assert_eq!(
+            words(&zero),
+            [
+                15418276681040673804,
+                12360115312806001175,
+                1503201971664012496,
+                6420982487566891060,
+                11111592944892977405,
+                3878394344119183007,
+                799493575626522521,
+                11375346971901684479,
+            ]
+        );
+
+        let mut counting: [Goldilocks; WIDTH] = std::array::from_fn(|i| Goldilocks(i as u64));
+        permute(&mut counting);
+        assert_eq!(
+            words(&counting),
+            [
+                11506093132976372865,
+                12517269055347975217,
+                10299766003194464374,
+                10491439983549396639,
+                3258612649213655885,
+                11798787810857090259,
+                8870281483613968530,
+                5061791844519743766,
+            ]
+        );
+    }
+
+    #[test]
+    fn hash_known_answers() {
+        let elems: Vec<Goldilocks> = (1..=5).map(Goldilocks::from_u64).collect();
+        assert_eq!(
+            words(&hash_fields_goldilocks(&elems)),
+            [
+                17420328605887539401,
+                2455506820997320710,
+                6567140797868492397,
+                17469390686313256757,
+            ]
+        );
+        assert_eq!(
+            hex(&hash_bytes_goldilocks(b"")),
+            "0c3c1eaef3b8f8d517faaaea63f387abd044b39a3a72dc1434b86d788de91b59"
+        );
+        assert_eq!(
+            hex(&hash_bytes_goldilocks(b"hello world")),
+            "e7a406f3bdd477806a0b5db4290c96701343404381cd5a0a73bf554934c099e5"
+        );
+    }
+
+    #[test]
+    fn incremental_absorb_matches_one_shot() {
+        let elems: Vec<Goldilocks> = (0..11).map(Goldilocks::from_u64).collect();
+        let mut sponge = Sponge::goldilocks();
+        sponge.absorb(&elems[..3]);
+        sponge.absorb(&elems[3..]);
+        assert_eq!(sponge.squeeze(4), hash_fields_goldilocks(&elems).to_vec());
+    }
+
+    #[test]
+    fn squeeze_beyond_rate_permutes_again() {
+        let mut sponge = Sponge::goldilocks();
+        sponge.absorb(&[Goldilocks(7)]);
+        let long = sponge.squeeze(6);
+        assert_eq!(long.len(), 6);
+        let mut short = Sponge::goldilocks();
+        short.absorb(&[Goldilocks(7)]);
+        assert_eq!(short.squeeze(4), long[..4].to_vec());
+    }
+
     #[test]
     fn permutation_diffusion() {
         let config = cached_goldilocks_config();
@@ -1,32 +0,0 @@
-//! GPU compute infrastructure.
-//!
-//! Uses wgpu for cross-platform GPU acceleration (Metal, Vulkan, DX12).
-//! Provides device initialization and shader constants for grammar mask
-//! and field arithmetic.
-
-pub(crate) mod shaders;
-
-/// Try to create a wgpu device and queue.
-/// Returns None if no GPU adapter is available.
-pub fn try_create_device() -> Option<(wgpu::Device, wgpu::Queue)> {
-    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-        backends: wgpu::Backends::all(),
-        ..Default::default()
-    });
-    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-        power_preference: wgpu::PowerPreference::HighPerformance,
-        compatible_surface: None,
-        force_fallback_adapter: false,
-    }))?;
-    let (device, queue) = pollster::block_on(adapter.request_device(
-        &wgpu::DeviceDescriptor {
-            label: Some("trident-gpu"),
-            required_features: wgpu::Features::empty(),
-            required_limits: wgpu::Limits::default(),
-            memory_hints: wgpu::MemoryHints::Performance,
-        },
-        None,
-    ))
-    .ok()?;
-    Some((device, queue))
-}
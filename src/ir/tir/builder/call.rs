@@ -15,6 +15,29 @@ impl TIRBuilder {
         generic_args: &[Spanned<ArraySize>],
         args: &[Spanned<Expr>],
     ) {
+        // Debug builtins vanish, arguments and all, outside the debug profile.
+        if DEBUG_BUILTINS.contains(&name) && !self.cfg_flags.contains("debug") {
+            self.push_temp(0);
+            return;
+        }
+        // Assumptions are for the verifier: they compile to nothing.
+        if name == "assume" {
+            self.push_temp(0);
+            return;
+        }
+        if name == "trace" {
+            self.build_trace(args);
+            return;
+        }
+        if name == "fail" {
+            self.build_fail(args);
+            return;
+        }
+
+        // An assertion message only selects the error code.
+        let (args, message) = split_assertion_message(name, args);
+        let error_id = message.and_then(|m| self.error_codes.get(m).copied());
+
         // Evaluate arguments — each pushes a temp.
         for arg in args {
             self.build_expr(&arg.node);
@@ -84,17 +107,17 @@ impl TIRBuilder {
             }
 
             // ── Assertions ──
-            "assert" => {
-                self.ops.push(TIROp::Assert(1));
+            "assert" | "debug_assert" => {
+                self.ops.push(TIROp::Assert { width: 1, error_id });
                 self.push_temp(0);
             }
             "assert_eq" => {
                 self.ops.push(TIROp::Eq);
-                self.ops.push(TIROp::Assert(1));
+                self.ops.push(TIROp::Assert { width: 1, error_id });
                 self.push_temp(0);
             }
             "assert_digest" => {
-                self.ops.push(TIROp::Assert(5));
+                self.ops.push(TIROp::Assert { width: 5, error_id });
                 self.ops.push(TIROp::Pop(self.target_config.digest_width));
                 self.push_temp(0);
             }
@@ -112,6 +135,10 @@ impl TIRBuilder {
                 self.ops.push(TIROp::Invert);
                 self.push_temp(1);
             }
+            "inverse" => {
+                self.ops.extend(checked_inverse_ops());
+                self.push_temp(1);
+            }
             "neg" => {
                 self.ops.push(TIROp::Neg);
                 self.push_temp(1);
@@ -120,6 +147,14 @@ impl TIRBuilder {
                 self.ops.push(TIROp::Sub);
                 self.push_temp(1);
             }
+            "field_checked_add" => {
+                self.ops.extend(field_add_ops(Overflow::Check));
+                self.push_temp(2);
+            }
+            "field_assert_add" => {
+                self.ops.extend(field_add_ops(Overflow::Assert));
+                self.push_temp(1);
+            }
 
             // ── U32 operations ──
             "split" => {
@@ -138,6 +173,18 @@ impl TIRBuilder {
                 self.ops.push(TIROp::PopCount);
                 self.push_temp(1);
             }
+            "u32_wrapping_add" => {
+                self.ops.extend(u32_add_ops(Overflow::Wrap));
+                self.push_temp(1);
+            }
+            "u32_checked_add" => {
+                self.ops.extend(u32_add_ops(Overflow::Check));
+                self.push_temp(2);
+            }
+            "u32_assert_add" => {
+                self.ops.extend(u32_add_ops(Overflow::Assert));
+                self.push_temp(1);
+            }
 
             // ── Hash operations ──
             "hash" => {
@@ -200,6 +247,16 @@ impl TIRBuilder {
                 self.push_temp(1);
             }
 
+            // ── Digest ──
+            "Digest.from_fields" | "Digest.from_array" | "Digest.to_array" => {
+                self.push_temp(self.target_config.digest_width);
+            }
+            "Digest.eq" => {
+                let ops = self.digest_eq_ops();
+                self.ops.extend(ops);
+                self.push_temp(1);
+            }
+
             // ── XField ──
             "xfield" => {
                 self.push_temp(3);
@@ -215,6 +272,16 @@ impl TIRBuilder {
                 self.emit_and_push(TIROp::FoldBase, 5);
             }
 
+            // ── Recursive verification ──
+            "encode_claim" => {
+                let ops = self.claim_encoding_ops();
+                self.ops.extend(ops);
+                self.push_temp(self.target_config.digest_width);
+            }
+            "proof_item" => self.build_proof_item(1),
+            "proof_item_digest" => self.build_proof_item(self.target_config.digest_width),
+            "proof_item_xfe" => self.build_proof_item(self.target_config.xfield_width),
+
             // ── User-defined function ──
             _ => {
                 self.build_user_call(name, generic_args);
@@ -248,10 +315,16 @@ impl TIRBuilder {
             "sponge_absorb" => self.ops.push(TIROp::SpongeAbsorb),
             "sponge_squeeze" => self.ops.push(TIROp::SpongeSqueeze),
             "sponge_absorb_mem" => self.ops.push(TIROp::SpongeLoad),
-            "assert" => self.ops.push(TIROp::Assert(1)),
+            "assert" => self.ops.push(TIROp::Assert {
+                width: 1,
+                error_id: None,
+            }),
             "assert_eq" => {
                 self.ops.push(TIROp::Eq);
-                self.ops.push(TIROp::Assert(1));
+                self.ops.push(TIROp::Assert {
+                    width: 1,
+                    error_id: None,
+                });
             }
             "pub_read" => self.ops.push(TIROp::ReadIo(1)),
             "pub_read2" => self.ops.push(TIROp::ReadIo(2)),
@@ -270,7 +343,13 @@ impl TIRBuilder {
             "log2" => self.ops.push(TIROp::Log2),
             "pow" => self.ops.push(TIROp::Pow),
             "popcount" => self.ops.push(TIROp::PopCount),
+            "u32_wrapping_add" => self.ops.extend(u32_add_ops(Overflow::Wrap)),
+            "u32_checked_add" => self.ops.extend(u32_add_ops(Overflow::Check)),
+            "u32_assert_add" => self.ops.extend(u32_add_ops(Overflow::Assert)),
+            "field_checked_add" => self.ops.extend(field_add_ops(Overflow::Check)),
+            "field_assert_add" => self.ops.extend(field_add_ops(Overflow::Assert)),
             "inv" => self.ops.push(TIROp::Invert),
+            "inverse" => self.ops.extend(checked_inverse_ops()),
             "neg" => self.ops.push(TIROp::Neg),
             "sub" => self.ops.push(TIROp::Sub),
             "field_add" => self.ops.push(TIROp::Add),
@@ -284,10 +363,25 @@ impl TIRBuilder {
             "xinvert" => self.ops.push(TIROp::ExtInvert),
             "xx_dot_step" => self.ops.push(TIROp::FoldExt),
             "xb_dot_step" => self.ops.push(TIROp::FoldBase),
+            "encode_claim" => {
+                let ops = self.claim_encoding_ops();
+                self.ops.extend(ops);
+            }
+            "proof_item" => self.push_proof_item_ops(1),
+            "proof_item_digest" => self.push_proof_item_ops(self.target_config.digest_width),
+            "proof_item_xfe" => self.push_proof_item_ops(self.target_config.xfield_width),
             "assert_digest" => {
-                self.ops.push(TIROp::Assert(5));
+                self.ops.push(TIROp::Assert {
+                    width: 5,
+                    error_id: None,
+                });
                 self.ops.push(TIROp::Pop(self.target_config.digest_width));
             }
+            "Digest.from_fields" | "Digest.from_array" | "Digest.to_array" => {}
+            "Digest.eq" => {
+                let ops = self.digest_eq_ops();
+                self.ops.extend(ops);
+            }
             _ => {
                 // User-defined call — resolve label the same way as
                 // build_user_call but skip stack model updates.
@@ -297,6 +391,57 @@ impl TIRBuilder {
         }
     }
 
+    /// Claim encoding: pad `(program, num_inputs, num_outputs)` with zeros
+    /// to the hash rate and hash it into a single digest.
+    fn claim_encoding_ops(&self) -> Vec<TIROp> {
+        let dw = self.target_config.digest_width;
+        let pad = self.target_config.hash_rate.saturating_sub(dw + 2);
+        let mut ops = vec![TIROp::Push(0); pad as usize];
+        ops.push(TIROp::Hash { width: dw });
+        ops
+    }
+
+    /// Compare the two digests on top of the stack element by element,
+    /// leaving 1 if all are equal and 0 otherwise.
+    fn digest_eq_ops(&self) -> Vec<TIROp> {
+        let dw = self.target_config.digest_width;
+        let mut ops = vec![TIROp::Dup(dw), TIROp::Eq];
+        for _ in 1..dw {
+            // The running product sits between the remaining elements of
+            // `b` and their partners in `a`.
+            ops.extend([TIROp::Swap(1), TIROp::Dup(dw + 1), TIROp::Eq, TIROp::Mul]);
+        }
+        ops.push(TIROp::Swap(dw));
+        ops.push(TIROp::Pop(dw));
+        ops
+    }
+
+    /// Read a `width`-element proof-stream item and model it as a temp.
+    fn build_proof_item(&mut self, width: u32) {
+        self.emit_and_push(TIROp::Hint(width), width);
+        self.push_absorb_item_ops(width);
+    }
+
+    /// Read a proof-stream item without touching the stack model.
+    fn push_proof_item_ops(&mut self, width: u32) {
+        self.ops.push(TIROp::Hint(width));
+        self.push_absorb_item_ops(width);
+    }
+
+    /// Absorb a copy of the `width` elements on top of the stack into the
+    /// Fiat-Shamir sponge, zero-padded to the hash rate. The item itself
+    /// stays on the stack.
+    fn push_absorb_item_ops(&mut self, width: u32) {
+        for _ in 0..width {
+            self.ops.push(TIROp::Dup(width - 1));
+        }
+        let pad = self.target_config.hash_rate.saturating_sub(width);
+        for _ in 0..pad {
+            self.ops.push(TIROp::Push(0));
+        }
+        self.ops.push(TIROp::SpongeAbsorb);
+    }
+
     /// Resolve a user-defined call name to its TASM label.
     /// Returns `(call_label, base_name)` where `base_name` is used for
     /// return width lookup.
@@ -307,7 +452,10 @@ impl TIRBuilder {
             let size_args: Vec<u64> = if !generic_args.is_empty() {
                 generic_args
                     .iter()
-                    .map(|ga| ga.node.eval(&self.current_subs))
+                    .map(|ga| {
+                        ga.node
+                            .eval_with_constants(&self.current_subs, &self.constants)
+                    })
                     .collect()
             } else if !self.current_subs.is_empty() {
                 if let Some(gdef) = self.generic_fn_defs.get(name) {
@@ -319,26 +467,16 @@ impl TIRBuilder {
                     vec![]
                 }
             } else {
-                let idx = self.call_resolution_idx;
-                if idx < self.call_resolutions.len() && self.call_resolutions[idx].name == name {
-                    self.call_resolution_idx += 1;
-                    self.call_resolutions[idx].size_args.clone()
-                } else {
-                    let mut found = vec![];
-                    for (i, res) in self.call_resolutions.iter().enumerate() {
-                        if i >= self.call_resolution_idx && res.name == name {
-                            self.call_resolution_idx = i + 1;
-                            found = res.size_args.clone();
-                            break;
-                        }
-                    }
-                    found
-                }
+                self.next_resolution(name).unwrap_or_default()
             };
             let inst = MonoInstance {
                 name: name.to_string(),
                 size_args,
             };
+            if !self.mono_instances.contains(&inst) {
+                self.register_mono_width(&inst);
+                self.mono_instances.push(inst.clone());
+            }
             inst.mangled_name()
         } else if name.contains('.') {
             let parts: Vec<&str> = name.rsplitn(2, '.').collect();
@@ -351,12 +489,81 @@ impl TIRBuilder {
                 .unwrap_or(short_module);
             let mangled = full_module.replace('.', "_");
             // @ prefix marks cross-module calls so the linker doesn't re-prefix them
-            format!("@{}__{}", mangled, fn_name)
+            let Some(size_args) = self.next_resolution(name) else {
+                return format!("@{}__{}", mangled, fn_name);
+            };
+            // A generic of another module: call the instance it emits.
+            let called = MonoInstance {
+                name: name.to_string(),
+                size_args: size_args.clone(),
+            };
+            let inst = MonoInstance {
+                name: fn_name.to_string(),
+                size_args,
+            };
+            let label = format!("@{}__{}", mangled, inst.mangled_name());
+            if let Some(&width) = self.fn_return_widths.get(&called.mangled_name()) {
+                self.fn_return_widths.insert(label.clone(), width);
+            }
+            label
         } else {
             name.to_string()
         }
     }
 
+    /// Size arguments the type checker resolved for the next call of
+    /// `name`, skipping resolutions of calls the builder passed over.
+    fn next_resolution(&mut self, name: &str) -> Option<Vec<u64>> {
+        let start = self.call_resolution_idx;
+        let offset = self.call_resolutions[start.min(self.call_resolutions.len())..]
+            .iter()
+            .position(|res| res.name == name)?;
+        self.call_resolution_idx = start + offset + 1;
+        Some(self.call_resolutions[start + offset].size_args.clone())
+    }
+
+    /// Emit `trace("label", value)`: a Triton `hint` naming the value's
+    /// stack slice, then drop the value. The hint costs no cycles.
+    fn build_trace(&mut self, args: &[Spanned<Expr>]) {
+        let (args, label) = split_trace_label("trace", args);
+        if let (Some(label), Some(value)) = (label, args.first()) {
+            self.build_expr(&value.node);
+            let width = self.stack.pop().map_or(0, |v| v.width);
+            let slice = if width == 1 {
+                "stack[0]".to_string()
+            } else {
+                format!("stack[0..{}]", width)
+            };
+            self.ops.push(TIROp::Asm {
+                lines: vec![format!("hint {} = {}", label, slice)],
+                effect: 0,
+            });
+            self.ops.push(TIROp::Pop(width));
+        }
+        self.push_temp(0);
+    }
+
+    /// Emit `fail(CODE)`: assert a pushed zero, so the VM halts with the
+    /// constant's value as its `error_id`.
+    fn build_fail(&mut self, args: &[Spanned<Expr>]) {
+        let code = match args.first().map(|a| &a.node) {
+            Some(Expr::Var(name)) => {
+                let short = name.rsplit('.').next().unwrap_or(name);
+                self.constants
+                    .get(name)
+                    .or_else(|| self.constants.get(short))
+                    .copied()
+            }
+            _ => None,
+        };
+        self.ops.push(TIROp::Push(0));
+        self.ops.push(TIROp::Assert {
+            width: 1,
+            error_id: code,
+        });
+        self.push_temp(0);
+    }
+
     /// Emit a call to a user-defined (non-intrinsic) function.
     fn build_user_call(&mut self, name: &str, generic_args: &[Spanned<ArraySize>]) {
         let call_label = self.resolve_call_label(name, generic_args);
@@ -368,7 +575,19 @@ impl TIRBuilder {
             call_label.clone()
         };
 
-        let ret_width = self.fn_return_widths.get(&base_name).copied().unwrap_or(0);
+        // Functions of other modules are registered under their qualified
+        // name, and instances of their generics under the call label.
+        let ret_width = name
+            .contains('.')
+            .then(|| {
+                self.fn_return_widths
+                    .get(name)
+                    .or_else(|| self.fn_return_widths.get(&call_label))
+            })
+            .flatten()
+            .or_else(|| self.fn_return_widths.get(&base_name))
+            .copied()
+            .unwrap_or(0);
         if ret_width > 0 {
             self.emit_and_push(TIROp::Call(call_label), ret_width);
         } else {
@@ -377,3 +596,101 @@ impl TIRBuilder {
         }
     }
 }
+
+/// `inverse(a)`: assert `a != 0`, then invert. The assertion fires on
+/// every target, including those whose `invert` yields 0 for 0.
+fn checked_inverse_ops() -> Vec<TIROp> {
+    vec![
+        TIROp::Dup(0),
+        TIROp::Push(0),
+        TIROp::Eq,
+        TIROp::Push(0),
+        TIROp::Eq,
+        TIROp::Assert {
+            width: 1,
+            error_id: None,
+        },
+        TIROp::Invert,
+    ]
+}
+
+/// What an addition does when the sum overflows its type.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Overflow {
+    /// Keep the wrapped sum.
+    Wrap,
+    /// Build a `Maybe`: the sum if it fit, `None` if it did not.
+    Check,
+    /// Assert that it fit.
+    Assert,
+}
+
+/// `a + b` on U32s: the field sum is below 2^33, so `split` leaves the
+/// carry as the high word.
+fn u32_add_ops(overflow: Overflow) -> Vec<TIROp> {
+    let mut ops = vec![TIROp::Add, TIROp::Split, TIROp::Swap(1)];
+    match overflow {
+        Overflow::Wrap => ops.push(TIROp::Pop(1)),
+        Overflow::Check | Overflow::Assert => {
+            ops.push(TIROp::Push(0));
+            ops.push(TIROp::Eq);
+        }
+    }
+    match overflow {
+        Overflow::Wrap => {}
+        Overflow::Check => ops.extend(maybe_ops()),
+        Overflow::Assert => ops.push(TIROp::Assert {
+            width: 1,
+            error_id: None,
+        }),
+    }
+    ops
+}
+
+/// `_ sum fits` -> `_ fits (fits * sum)`: a `Maybe` whose tag is `fits`,
+/// with the payload zeroed for `None`.
+fn maybe_ops() -> [TIROp; 3] {
+    [TIROp::Dup(0), TIROp::Swap(2), TIROp::Mul]
+}
+
+/// `a + b` on Fields, checked: the sum `s` wrapped past p exactly when
+/// `s < a`, compared on the split high and low words.
+fn field_add_ops(overflow: Overflow) -> Vec<TIROp> {
+    let mut ops = vec![
+        // _ a b -> _ a s
+        TIROp::Dup(1),
+        TIROp::Add,
+        // _ a s s_hi s_lo a_hi a_lo
+        TIROp::Dup(0),
+        TIROp::Split,
+        TIROp::Dup(3),
+        TIROp::Split,
+        // low words: _ a s s_hi (s_lo < a_lo) a_hi
+        TIROp::Dup(2),
+        TIROp::Lt,
+        TIROp::Swap(2),
+        TIROp::Pop(1),
+        // high words: _ a s (s_hi < a_hi) + (s_hi == a_hi) * lo_lt
+        TIROp::Dup(0),
+        TIROp::Dup(3),
+        TIROp::Lt,
+        TIROp::Swap(3),
+        TIROp::Eq,
+        TIROp::Mul,
+        TIROp::Add,
+        // _ a s fits
+        TIROp::Push(0),
+        TIROp::Eq,
+    ];
+    if overflow == Overflow::Assert {
+        ops.push(TIROp::Assert {
+            width: 1,
+            error_id: None,
+        });
+        ops.extend([TIROp::Swap(1), TIROp::Pop(1)]);
+    } else {
+        ops.extend([TIROp::Swap(2), TIROp::Pop(1), TIROp::Swap(1)]);
+        ops.extend(maybe_ops());
+    }
+    ops
+}
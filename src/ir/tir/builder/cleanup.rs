@@ -30,12 +30,8 @@ impl TIRBuilder {
             // Bulk save to RAM, pop dead, bulk restore.
             // Uses write_mem K / read_mem K to avoid triggering spill elimination.
             let scratch = self.stack.alloc_scratch(k);
-            // Move address below the K return elements.
+            // Write K elements: [addr, val_1, ..., val_K] → [addr+K]
             self.ops.push(TIROp::Push(scratch));
-            for d in 1..=k {
-                self.ops.push(TIROp::Swap(d));
-            }
-            // Write K elements: [val_K, ..., val_1, addr] → [addr+K]
             self.ops.push(TIROp::WriteMem(k));
             self.ops.push(TIROp::Pop(1));
             // Pop dead elements.
@@ -61,14 +57,13 @@ impl TIRBuilder {
             // Save return values to scratch RAM element-by-element (using
             // only Swap(1)), pop dead elements, then restore from RAM.
             let scratch = self.stack.alloc_scratch(k);
-            // Save: push starting address, then repeatedly swap and write.
+            // Save: push starting address, then repeatedly write.
             // Stack: [ret_0, ret_1, ..., ret_{k-1}, dead...]
             self.ops.push(TIROp::Push(scratch));
             // [addr, ret_0, ret_1, ..., ret_{k-1}, dead...]
             for _ in 0..k {
-                self.ops.push(TIROp::Swap(1));
                 self.ops.push(TIROp::WriteMem(1));
-                // write_mem 1: [val, addr] -> [addr+1]
+                // write_mem 1: [addr, val] -> [addr+1]
             }
             // [addr+k, dead...]
             self.ops.push(TIROp::Pop(1));
@@ -80,8 +75,7 @@ impl TIRBuilder {
             // [addr, ...]
             for _ in 0..k {
                 self.ops.push(TIROp::ReadMem(1));
-                // read_mem 1: [addr] -> [val, addr-1]
-                self.ops.push(TIROp::Swap(1));
+                // read_mem 1: [addr] -> [addr-1, val]
             }
             // [addr-1, ret_0, ret_1, ..., ret_{k-1}]
             self.ops.push(TIROp::Pop(1));
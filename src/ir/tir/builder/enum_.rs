@@ -0,0 +1,111 @@
+//! Enum values: widths, layouts, and variant constructors.
+//!
+//! An enum value is its tag (the variant's index) deepest, then the
+//! variant's payload in field order, then zeros up to the width of the
+//! widest variant, so every value of one enum has the same width.
+
+use std::collections::BTreeMap;
+
+use crate::ast::*;
+use crate::span::Spanned;
+use crate::tir::TIROp;
+use crate::types::{EnumTy, Ty};
+
+use super::TIRBuilder;
+
+/// The enums intrinsics return (`EnumTy::maybe` of U32 and Field), as
+/// declarations every module can lay out and match on.
+pub(crate) fn builtin_enums() -> BTreeMap<String, EnumDef> {
+    [(Ty::U32, Type::U32), (Ty::Field, Type::Field)]
+        .into_iter()
+        .map(|(ty, payload)| {
+            let ety = EnumTy::maybe(ty);
+            let variants = ety
+                .variants
+                .iter()
+                .map(|(name, fields)| EnumVariant {
+                    name: Spanned::dummy(name.clone()),
+                    fields: fields
+                        .iter()
+                        .map(|_| Spanned::dummy(payload.clone()))
+                        .collect(),
+                })
+                .collect();
+            let edef = EnumDef {
+                is_pub: true,
+                cfg: None,
+                name: Spanned::dummy(ety.name.clone()),
+                variants,
+            };
+            (ety.name, edef)
+        })
+        .collect()
+}
+
+impl TIRBuilder {
+    /// The enum declared in this module that `path` (`Enum.Variant`)
+    /// names a variant of, with the variant's index.
+    pub(crate) fn enum_variant(&self, path: &str) -> Option<(EnumDef, usize)> {
+        let (prefix, variant) = path.rsplit_once('.')?;
+        let enum_name = prefix.rsplit('.').next().unwrap_or(prefix);
+        let edef = self.enum_types.get(enum_name)?;
+        let index = edef.variants.iter().position(|v| v.name.node == *variant)?;
+        Some((edef.clone(), index))
+    }
+
+    /// Width of the payload of `variant`.
+    fn payload_width(&self, variant: &EnumVariant, subs: &BTreeMap<String, u64>) -> u32 {
+        variant
+            .fields
+            .iter()
+            .map(|t| self.type_width_with_subs(&t.node, subs))
+            .sum()
+    }
+
+    /// Width of a value of `edef`: the tag plus the widest payload.
+    pub(crate) fn enum_width(&self, edef: &EnumDef, subs: &BTreeMap<String, u64>) -> u32 {
+        let payload = edef
+            .variants
+            .iter()
+            .map(|v| self.payload_width(v, subs))
+            .max()
+            .unwrap_or(0);
+        1 + payload
+    }
+
+    /// Layout of an enum value for `struct_layouts`: `tag`, and each
+    /// payload field as `Variant#i`.
+    pub(crate) fn enum_layout(&self, edef: &EnumDef) -> BTreeMap<String, (u32, u32)> {
+        let total = self.enum_width(edef, &BTreeMap::new());
+        let mut layout = BTreeMap::new();
+        layout.insert("tag".to_string(), (total - 1, 1));
+        for variant in &edef.variants {
+            let mut offset = 1u32;
+            for (i, ty) in variant.fields.iter().enumerate() {
+                let fw = self.type_width(&ty.node);
+                let key = format!("{}#{}", variant.name.node, i);
+                layout.insert(key, (total - offset - fw, fw));
+                offset += fw;
+            }
+        }
+        layout
+    }
+
+    /// Build the value of variant `index` of `edef` from `args`.
+    pub(crate) fn build_variant(&mut self, edef: &EnumDef, index: usize, args: &[Spanned<Expr>]) {
+        self.emit_and_push(TIROp::Push(index as u64), 1);
+        for arg in args {
+            self.build_expr(&arg.node);
+        }
+        let total = self.enum_width(edef, &BTreeMap::new());
+        let payload = self.payload_width(&edef.variants[index], &BTreeMap::new());
+        for _ in payload + 1..total {
+            self.emit_and_push(TIROp::Push(0), 1);
+        }
+        let entries = 1 + args.len() + (total - payload - 1) as usize;
+        for _ in 0..entries {
+            self.stack.pop();
+        }
+        self.push_temp(total);
+    }
+}
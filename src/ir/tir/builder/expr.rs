@@ -1,10 +1,13 @@
-//! Expression emission: build_expr, build_var_expr, build_field_access, build_index.
+//! Expression emission: build_expr, build_var_expr, build_field_access, build_index,
+//! build_short_circuit.
+
+use std::sync::Arc;
 
 use crate::ast::*;
 use crate::span::Spanned;
+use crate::tir::data;
 use crate::tir::TIROp;
 
-use super::layout::resolve_type_width;
 use super::TIRBuilder;
 
 impl TIRBuilder {
@@ -16,9 +19,21 @@ impl TIRBuilder {
             Expr::Literal(Literal::Bool(b)) => {
                 self.emit_and_push(TIROp::Push(if *b { 1 } else { 0 }), 1);
             }
+            // Assertion messages are stripped by `build_call`; any other
+            // string literal is a type error.
+            Expr::Literal(Literal::Str(_)) => {}
+
+            Expr::Var(name) => match self.enum_variant(name) {
+                Some((edef, index)) => self.build_variant(&edef, index, &[]),
+                None => self.build_var_expr(name),
+            },
 
-            Expr::Var(name) => {
-                self.build_var_expr(name);
+            Expr::BinOp {
+                op: op @ (BinOp::And | BinOp::Or),
+                lhs,
+                rhs,
+            } => {
+                self.build_short_circuit(*op, lhs, rhs);
             }
 
             Expr::BinOp { op, lhs, rhs } => {
@@ -39,6 +54,16 @@ impl TIRBuilder {
                     BinOp::Lt => self.ops.push(TIROp::Lt),
                     BinOp::BitAnd => self.ops.push(TIROp::And),
                     BinOp::BitXor => self.ops.push(TIROp::Xor),
+                    BinOp::BitOr => {
+                        // a | b = (a ^ b) + (a & b): the two never share a bit.
+                        self.ops.push(TIROp::Dup(1));
+                        self.ops.push(TIROp::Dup(1));
+                        self.ops.push(TIROp::Xor);
+                        self.ops.push(TIROp::Swap(2));
+                        self.ops.push(TIROp::And);
+                        self.ops.push(TIROp::Add);
+                    }
+                    BinOp::And | BinOp::Or => unreachable!("lowered as a branch above"),
                     BinOp::DivMod => self.ops.push(TIROp::DivMod),
                     BinOp::XFieldMul => self.ops.push(TIROp::ExtMul),
                 }
@@ -59,7 +84,10 @@ impl TIRBuilder {
                 args,
             } => {
                 let fn_name = path.node.as_dotted();
-                self.build_call(&fn_name, generic_args, args);
+                match self.enum_variant(&fn_name) {
+                    Some((edef, index)) => self.build_variant(&edef, index, args),
+                    None => self.build_call(&fn_name, generic_args, args),
+                }
             }
 
             Expr::Tuple(elements) => {
@@ -105,10 +133,12 @@ impl TIRBuilder {
                 self.build_index(inner, index);
             }
 
-            Expr::StructInit { path: _, fields } => {
-                let mut total_width = 0u32;
+            Expr::StructInit { fields, .. } => {
                 for (_name, val) in fields {
                     self.build_expr(&val.node);
+                }
+                let mut total_width = 0u32;
+                for _ in fields {
                     if let Some(e) = self.stack.pop() {
                         total_width += e.width;
                     }
@@ -159,6 +189,8 @@ impl TIRBuilder {
                     self.emit_and_push(TIROp::Push(val), 1);
                 } else if let Some(&val) = self.constants.get(suffix) {
                     self.emit_and_push(TIROp::Push(val), 1);
+                } else if self.data.contains_key(name) {
+                    self.build_data_table(name);
                 } else {
                     self.ops.push(TIROp::Comment(format!(
                         "ERROR: unresolved constant '{}'",
@@ -203,10 +235,20 @@ impl TIRBuilder {
                             name, depth2, width
                         )));
                         self.ops.push(TIROp::Push(0));
-                        self.ops.push(TIROp::Assert(1));
+                        self.ops.push(TIROp::Assert {
+                            width: 1,
+                            error_id: None,
+                        });
                     }
                 }
                 self.stack.push_temp(width);
+            } else if let Some(&val) = self.current_subs.get(name) {
+                // Size parameter of the instance being emitted: `xs[N]`.
+                self.emit_and_push(TIROp::Push(val), 1);
+            } else if let Some(&val) = self.constants.get(name) {
+                self.emit_and_push(TIROp::Push(val), 1);
+            } else if self.data.contains_key(name) {
+                self.build_data_table(name);
             } else {
                 // Variable not found — fallback.
                 self.ops.push(TIROp::Dup(0));
@@ -215,6 +257,64 @@ impl TIRBuilder {
         }
     }
 
+    /// Push every element of an `include_data` table.
+    fn build_data_table(&mut self, name: &str) {
+        let values = Arc::clone(&self.data[name].values);
+        self.stack.ensure_space(values.len() as u32);
+        self.flush_stack_effects();
+        for &v in values.iter() {
+            self.ops.push(TIROp::Push(v));
+        }
+        self.stack.push_temp(values.len() as u32);
+        self.flush_stack_effects();
+    }
+
+    /// Index an `include_data` table without pushing all of it: a known
+    /// index is one `push`, a runtime index a `read_mem` when the table is
+    /// stored in RAM. Returns false when neither applies.
+    fn build_data_index(&mut self, inner: &Spanned<Expr>, index: &Spanned<Expr>) -> bool {
+        let Expr::Var(name) = &inner.node else {
+            return false;
+        };
+        let Some(table) = self.data.get(name.as_str()).cloned() else {
+            return false;
+        };
+        if !name.contains('.') && self.find_var_depth_and_width(name).is_some() {
+            return false;
+        }
+        if let Some(k) = self.index_value(&index.node) {
+            if let Some(&v) = table.values.get(k as usize) {
+                self.emit_and_push(TIROp::Push(v), 1);
+                return true;
+            }
+            return false;
+        }
+        let Some(address) = table.address else {
+            return false;
+        };
+        self.build_expr(&index.node);
+        self.stack.pop();
+        self.ops.extend(data::read_ops(address));
+        self.stack.push_temp(1);
+        self.flush_stack_effects();
+        true
+    }
+
+    /// The value of an index known at compile time.
+    fn index_value(&mut self, index: &Expr) -> Option<u64> {
+        match index {
+            Expr::Literal(Literal::Integer(k)) => Some(*k),
+            Expr::Var(name) if !name.contains('.') => {
+                if self.find_var_depth_and_width(name).is_some() {
+                    return None;
+                }
+                self.constants.get(name.as_str()).copied()
+            }
+            Expr::Var(name) => self.constants.get(name.as_str()).copied(),
+            _ => None,
+        }
+    }
+
     // ── Field access ──────────────────────────────────────────────
 
     pub(crate) fn build_field_access(&mut self, inner: &Spanned<Expr>, field: &Spanned<String>) {
@@ -241,14 +341,14 @@ impl TIRBuilder {
                     let total: u32 = sdef
                         .fields
                         .iter()
-                        .map(|f| resolve_type_width(&f.ty.node, &self.target_config))
+                        .map(|f| self.type_width(&f.ty.node))
                         .sum();
                     if total != struct_width {
                         continue;
                     }
                     let mut off = 0u32;
                     for sf in &sdef.fields {
-                        let fw = resolve_type_width(&sf.ty.node, &self.target_config);
+                        let fw = self.type_width(&sf.ty.node);
                         if sf.name.node == field.node {
                             found = Some((total - off - fw, fw));
                             break;
@@ -289,6 +389,9 @@ impl TIRBuilder {
     // ── Index expression ──────────────────────────────────────────
 
     pub(crate) fn build_index(&mut self, inner: &Spanned<Expr>, index: &Spanned<Expr>) {
+        if self.build_data_index(inner, index) {
+            return;
+        }
         // Fast path: constant index into a named variable already on the stack.
         // Instead of copying the whole array then extracting one element,
         // directly dup the target element from the variable's position.
@@ -352,12 +455,12 @@ impl TIRBuilder {
                 let base = self.temp_ram_addr;
                 self.temp_ram_addr += array_width as u64;
 
-                // Store array elements to RAM.
+                // Store array elements to RAM. The last element is on
+                // top, so element k lands at base + k.
                 self.ops.push(TIROp::Swap(1));
                 for i in 0..array_width {
-                    let addr = base + i as u64;
+                    let addr = base + (array_width - 1 - i) as u64;
                     self.ops.push(TIROp::Push(addr));
-                    self.ops.push(TIROp::Swap(1));
                     self.ops.push(TIROp::WriteMem(1));
                     self.ops.push(TIROp::Pop(1));
                     if i + 1 < array_width {
@@ -394,4 +497,34 @@ impl TIRBuilder {
             }
         }
     }
+
+    /// Lower `a && b` as `if a { b } else { false }` and `a || b` as
+    /// `if a { true } else { b }`, so `b` only runs when it decides the
+    /// result.
+    pub(crate) fn build_short_circuit(
+        &mut self,
+        op: BinOp,
+        lhs: &Spanned<Expr>,
+        rhs: &Spanned<Expr>,
+    ) {
+        self.build_expr(&lhs.node);
+        self.stack.pop(); // condition consumed by the branch
+
+        let saved = self.stack.save_state();
+        let rhs_body = self.build_expr_as_ir(&rhs.node);
+        self.stack.restore_state(saved);
+        let constant = vec![TIROp::Push((op == BinOp::Or) as u64)];
+        let (then_body, else_body) = if op == BinOp::And {
+            (rhs_body, constant)
+        } else {
+            (constant, rhs_body)
+        };
+        self.ops.push(TIROp::IfElse {
+            then_body,
+            else_body,
+        });
+
+        self.stack.push_temp(1);
+        self.flush_stack_effects();
+    }
 }
@@ -1,7 +1,6 @@
 //! Stack wrappers, label generation, cfg helpers, and spill parser.
 
 use crate::ast::*;
-use crate::span::Spanned;
 use crate::tir::TIROp;
 
 use super::TIRBuilder;
@@ -59,20 +58,8 @@ pub(crate) fn parse_spill_effect(line: &str) -> TIROp {
 impl TIRBuilder {
     // ── Cfg helpers ───────────────────────────────────────────────
 
-    pub(crate) fn is_cfg_active(&self, cfg: &Option<Spanned<String>>) -> bool {
-        match cfg {
-            None => true,
-            Some(flag) => self.cfg_flags.contains(&flag.node),
-        }
-    }
-
     pub(crate) fn is_item_cfg_active(&self, item: &Item) -> bool {
-        match item {
-            Item::Fn(f) => self.is_cfg_active(&f.cfg),
-            Item::Const(c) => self.is_cfg_active(&c.cfg),
-            Item::Struct(s) => self.is_cfg_active(&s.cfg),
-            Item::Event(e) => self.is_cfg_active(&e.cfg),
-        }
+        crate::ast::desugar::is_item_active(item, &self.cfg_flags)
     }
 
     // ── Label generation ──────────────────────────────────────────
@@ -133,4 +120,13 @@ impl TIRBuilder {
         self.ops = saved_ops;
         nested
     }
+
+    /// Build an expression into a separate Vec<TIROp>, like `build_block_as_ir`.
+    pub(crate) fn build_expr_as_ir(&mut self, expr: &Expr) -> Vec<TIROp> {
+        let saved_ops = std::mem::take(&mut self.ops);
+        self.build_expr(expr);
+        let nested = std::mem::take(&mut self.ops);
+        self.ops = saved_ops;
+        nested
+    }
 }
@@ -5,50 +5,47 @@ use std::collections::BTreeMap;
 pub(crate) use crate::ast::display::format_ast_type as format_type_name;
 use crate::ast::*;
 use crate::span::Spanned;
-use crate::target::TerrainConfig;
 
 use super::TIRBuilder;
 
-// ─── Free functions: type helpers ─────────────────────────────────
+// ─── TIRBuilder struct layout methods ──────────────────────────────
 
-pub(crate) fn resolve_type_width(ty: &Type, tc: &TerrainConfig) -> u32 {
-    match ty {
-        Type::Field | Type::Bool | Type::U32 => 1,
-        Type::XField => tc.xfield_width,
-        Type::Digest => tc.digest_width,
-        Type::Array(inner, n) => {
-            let size = n.as_literal().unwrap_or(0);
-            resolve_type_width(inner, tc) * (size as u32)
-        }
-        Type::Tuple(elems) => elems.iter().map(|t| resolve_type_width(t, tc)).sum(),
-        Type::Named(_) => 1,
+impl TIRBuilder {
+    /// Width of `ty` in field elements. Structs declared in the module
+    /// being built count their fields, enums their tag and widest
+    /// payload; any other named type is one element.
+    pub(crate) fn type_width(&self, ty: &Type) -> u32 {
+        self.type_width_with_subs(ty, &BTreeMap::new())
     }
-}
 
-pub(crate) fn resolve_type_width_with_subs(
-    ty: &Type,
-    subs: &BTreeMap<String, u64>,
-    tc: &TerrainConfig,
-) -> u32 {
-    match ty {
-        Type::Field | Type::Bool | Type::U32 => 1,
-        Type::XField => tc.xfield_width,
-        Type::Digest => tc.digest_width,
-        Type::Array(inner, n) => {
-            let size = n.eval(subs);
-            resolve_type_width_with_subs(inner, subs, tc) * (size as u32)
+    /// `type_width` with size parameters substituted.
+    pub(crate) fn type_width_with_subs(&self, ty: &Type, subs: &BTreeMap<String, u64>) -> u32 {
+        match ty {
+            Type::Named(path) => match path.0.last().and_then(|n| self.struct_types.get(n)) {
+                Some(sdef) => sdef
+                    .fields
+                    .iter()
+                    .map(|f| self.type_width_with_subs(&f.ty.node, subs))
+                    .sum(),
+                None => match path.0.last().and_then(|n| self.enum_types.get(n)) {
+                    Some(edef) => self.enum_width(edef, subs),
+                    None => 1,
+                },
+            },
+            Type::Array(inner, n) => {
+                self.type_width_with_subs(inner, subs)
+                    * (n.eval_with_constants(subs, &self.constants) as u32)
+            }
+            Type::Tuple(elems) => elems
+                .iter()
+                .map(|t| self.type_width_with_subs(t, subs))
+                .sum(),
+            Type::Field | Type::Bool | Type::U32 => 1,
+            Type::XField => self.target_config.xfield_width,
+            Type::Digest => self.target_config.digest_width,
         }
-        Type::Tuple(elems) => elems
-            .iter()
-            .map(|t| resolve_type_width_with_subs(t, subs, tc))
-            .sum(),
-        Type::Named(_) => 1,
     }
-}
 
-// ─── TIRBuilder struct layout methods ──────────────────────────────
-
-impl TIRBuilder {
     /// Register struct field layout from a type annotation.
     pub(crate) fn register_struct_layout_from_type(&mut self, var_name: &str, ty: &Type) {
         if let Type::Named(path) = ty {
@@ -58,16 +55,19 @@ impl TIRBuilder {
                 let total: u32 = sdef
                     .fields
                     .iter()
-                    .map(|f| resolve_type_width(&f.ty.node, &self.target_config))
+                    .map(|f| self.type_width(&f.ty.node))
                     .sum();
                 let mut offset = 0u32;
                 for sf in &sdef.fields {
-                    let fw = resolve_type_width(&sf.ty.node, &self.target_config);
+                    let fw = self.type_width(&sf.ty.node);
                     let from_top = total - offset - fw;
                     field_map.insert(sf.name.node.clone(), (from_top, fw));
                     offset += fw;
                 }
                 self.struct_layouts.insert(var_name.to_string(), field_map);
+            } else if let Some(edef) = self.enum_types.get(struct_name) {
+                let layout = self.enum_layout(edef);
+                self.struct_layouts.insert(var_name.to_string(), layout);
             }
         }
     }
@@ -122,11 +122,11 @@ impl TIRBuilder {
             let total: u32 = sdef
                 .fields
                 .iter()
-                .map(|f| resolve_type_width(&f.ty.node, &self.target_config))
+                .map(|f| self.type_width(&f.ty.node))
                 .sum();
             let mut found = false;
             for sf in &sdef.fields {
-                let fw = resolve_type_width(&sf.ty.node, &self.target_config);
+                let fw = self.type_width(&sf.ty.node);
                 if sf.name.node == field {
                     let from_top = total - sub_offset - fw;
                     // The sub-field is at `from_top` within the parent field.
@@ -165,7 +165,7 @@ impl TIRBuilder {
             let total: u32 = sdef
                 .fields
                 .iter()
-                .map(|f| resolve_type_width(&f.ty.node, &self.target_config))
+                .map(|f| self.type_width(&f.ty.node))
                 .sum();
             // Check if this struct matches the variable's layout.
             if let Some(layout) = self.struct_layouts.get(var_name) {
@@ -199,7 +199,7 @@ impl TIRBuilder {
                         return sdef
                             .fields
                             .iter()
-                            .map(|f| resolve_type_width(&f.ty.node, &self.target_config))
+                            .map(|f| self.type_width(&f.ty.node))
                             .collect();
                     }
                 }
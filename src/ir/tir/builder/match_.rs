@@ -1,5 +1,7 @@
 //! Match statement compilation.
 
+use std::collections::BTreeSet;
+
 use crate::ast::*;
 use crate::span::Spanned;
 use crate::tir::TIROp;
@@ -8,6 +10,13 @@ use super::TIRBuilder;
 
 impl TIRBuilder {
     pub(crate) fn build_match(&mut self, expr: &Spanned<Expr>, arms: &[MatchArm]) {
+        if arms
+            .iter()
+            .any(|a| matches!(a.pattern.node, MatchPattern::Variant { .. }))
+        {
+            self.build_enum_match(expr, arms);
+            return;
+        }
         self.build_expr(&expr.node);
         if let Some(top) = self.stack.last_mut() {
             top.name = Some("__match_scrutinee".to_string());
@@ -28,6 +37,8 @@ impl TIRBuilder {
                     match lit {
                         Literal::Integer(n) => self.ops.push(TIROp::Push(*n)),
                         Literal::Bool(b) => self.ops.push(TIROp::Push(if *b { 1 } else { 0 })),
+                        // The parser produces no string patterns.
+                        Literal::Str(_) => self.ops.push(TIROp::Push(0)),
                     }
 
                     self.ops.push(TIROp::Eq);
@@ -89,6 +100,9 @@ impl TIRBuilder {
                     ));
                 }
 
+                // Matches with variant patterns go to `build_enum_match`.
+                MatchPattern::Variant { .. } => {}
+
                 MatchPattern::Struct { name, fields } => {
                     let s_label = self.fresh_label("match_struct");
                     self.ops.push(TIROp::Call(s_label.clone()));
@@ -193,6 +207,106 @@ impl TIRBuilder {
         }
     }
 
+    /// Build a match on an enum as an if/else chain on its tag. The
+    /// scrutinee is bound to a hidden local, and each arm's bindings to
+    /// its payload fields; the last arm of a match that names every
+    /// variant needs no test.
+    fn build_enum_match(&mut self, expr: &Spanned<Expr>, arms: &[MatchArm]) {
+        let edef = arms.iter().find_map(|arm| match &arm.pattern.node {
+            MatchPattern::Variant { path, .. } => self.enum_variant(&path.node.as_dotted()),
+            _ => None,
+        });
+        let Some((edef, _)) = edef else {
+            self.ops.push(TIROp::Comment(
+                "BUG: match on an unregistered enum".to_string(),
+            ));
+            return;
+        };
+        let var = self.fresh_label("__enum");
+        let span = expr.span;
+        self.build_stmt(&Stmt::Let {
+            mutable: false,
+            pattern: Pattern::Name(Spanned::new(var.clone(), span)),
+            ty: Some(Spanned::new(
+                Type::Named(ModulePath::single(edef.name.node.clone())),
+                span,
+            )),
+            init: expr.clone(),
+        });
+
+        // Arms up to the first wildcard, which catches everything else.
+        let mut tested = Vec::new();
+        let mut fallback = None;
+        for arm in arms {
+            match &arm.pattern.node {
+                MatchPattern::Variant { path, bindings } => {
+                    let Some((_, index)) = self.enum_variant(&path.node.as_dotted()) else {
+                        continue;
+                    };
+                    let variant = &edef.variants[index];
+                    let mut stmts = Vec::new();
+                    for (i, (binding, ty)) in bindings.iter().zip(&variant.fields).enumerate() {
+                        if binding.node == "_" {
+                            continue;
+                        }
+                        let field = format!("{}.{}#{}", var, variant.name.node, i);
+                        stmts.push(Spanned::new(
+                            Stmt::Let {
+                                mutable: false,
+                                pattern: Pattern::Name(binding.clone()),
+                                ty: Some(ty.clone()),
+                                init: Spanned::new(Expr::Var(field), binding.span),
+                            },
+                            binding.span,
+                        ));
+                    }
+                    stmts.extend(arm.body.node.stmts.clone());
+                    let body = Block {
+                        stmts,
+                        tail_expr: arm.body.node.tail_expr.clone(),
+                    };
+                    tested.push((index, Spanned::new(body, arm.body.span)));
+                }
+                MatchPattern::Wildcard => {
+                    fallback = Some(arm.body.clone());
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let covered: BTreeSet<usize> = tested.iter().map(|(i, _)| *i).collect();
+        if fallback.is_none() && tested.len() > 1 && covered.len() == edef.variants.len() {
+            fallback = tested.pop().map(|(_, body)| body);
+        }
+
+        let mut chain = fallback;
+        for (index, body) in tested.into_iter().rev() {
+            let cond = Expr::BinOp {
+                op: BinOp::Eq,
+                lhs: Box::new(Spanned::new(Expr::Var(format!("{}.tag", var)), span)),
+                rhs: Box::new(Spanned::new(
+                    Expr::Literal(Literal::Integer(index as u64)),
+                    span,
+                )),
+            };
+            let stmt = Stmt::If {
+                cond: Spanned::new(cond, span),
+                then_block: body,
+                else_block: chain,
+            };
+            chain = Some(Spanned::new(
+                Block {
+                    stmts: vec![Spanned::new(stmt, span)],
+                    tail_expr: None,
+                },
+                span,
+            ));
+        }
+        if let Some(chain) = chain {
+            self.build_block(&chain.node);
+        }
+    }
+
     /// Build a deferred match arm body into IR.
     pub(crate) fn build_deferred_arm_ir(&mut self, block: &Block, clears_flag: bool) -> Vec<TIROp> {
         let saved_ops = std::mem::take(&mut self.ops);
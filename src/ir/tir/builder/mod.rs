@@ -1,18 +1,20 @@
 //! TIRBuilder: lowers a type-checked AST into `Vec<TIROp>`.
 //!
-//! This is the core of Phase 2 — it replicates the Emitter's AST-walking
-//! logic but produces `Vec<TIROp>` instead of `Vec<String>`. The output is
-//! target-independent; a `StackLowering` implementation converts it to assembly.
+//! This is the only code generation path: every entry point in `api`
+//! (single files, modules, projects) builds TIR here, optimizes it, and
+//! hands it to a `StackLowering` for the target. The output is
+//! target-independent.
 //!
-//! Key differences from the Emitter:
-//! - No `StackBackend`: instructions are TIROp variants pushed directly.
-//! - No `DeferredBlock`: if/else and loops use nested `Vec<TIROp>` bodies
-//!   inside structural `TIROp::IfElse`, `TIROp::IfOnly`, and `TIROp::Loop`.
+//! Structure:
+//! - Instructions are TIROp variants pushed directly.
+//! - if/else and loops use nested `Vec<TIROp>` bodies inside structural
+//!   `TIROp::IfElse`, `TIROp::IfOnly`, and `TIROp::Loop`.
 //! - `StackManager` spill/reload effects are parsed from their string form
 //!   back into TIROps via `parse_spill_effect`.
 
 mod call;
 mod cleanup;
+mod enum_;
 mod expr;
 mod helpers;
 mod layout;
@@ -25,15 +27,17 @@ mod tests {
 }
 
 use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
 
 use crate::ast::*;
 use crate::target::TerrainConfig;
+use crate::tir::data::DataTable;
 use crate::tir::stack::SpillFormatter;
 use crate::tir::stack::StackManager;
 use crate::tir::TIROp;
 use crate::typecheck::MonoInstance;
 
-use self::layout::{format_type_name, resolve_type_width, resolve_type_width_with_subs};
+use self::layout::format_type_name;
 
 // ─── TIRBuilder ────────────────────────────────────────────────────
 
@@ -49,20 +53,31 @@ pub struct TIRBuilder {
     pub(crate) struct_layouts: BTreeMap<String, BTreeMap<String, (u32, u32)>>,
     /// Return widths of user-defined functions.
     pub(crate) fn_return_widths: BTreeMap<String, u32>,
+    /// Tuple return element widths: fn name -> width of each element.
+    pub(crate) fn_tuple_widths: BTreeMap<String, Vec<u32>>,
     /// Event tags: event name -> sequential integer tag.
     pub(crate) event_tags: BTreeMap<String, u64>,
-    /// Event field names in declaration order: event name -> [field_name, ...].
-    pub(crate) event_defs: BTreeMap<String, Vec<String>>,
     /// Struct type definitions: struct_name -> StructDef.
     pub(crate) struct_types: BTreeMap<String, StructDef>,
+    /// Enum type definitions: enum_name -> EnumDef.
+    pub(crate) enum_types: BTreeMap<String, EnumDef>,
     /// Constants: qualified or short name -> integer value.
     pub(crate) constants: BTreeMap<String, u64>,
+    /// `include_data` tables: own or qualified name -> values and RAM
+    /// address.
+    pub(crate) data: BTreeMap<String, DataTable>,
     /// Next temporary RAM address for runtime array ops.
     pub(crate) temp_ram_addr: u64,
-    /// Intrinsic map: function name -> intrinsic TASM name.
-    pub(crate) intrinsic_map: BTreeMap<String, String>,
-    /// Module alias map: short name -> full module name.
-    pub(crate) module_aliases: BTreeMap<String, String>,
+    /// Intrinsic map: function name -> intrinsic TASM name. Shared
+    /// between the builders of a project; copied only when a module
+    /// adds its own intrinsics.
+    pub(crate) intrinsic_map: Arc<BTreeMap<String, String>>,
+    /// Module alias map: short name -> full module name, shared like
+    /// `intrinsic_map`.
+    pub(crate) module_aliases: Arc<BTreeMap<String, String>>,
+    /// Error table: assertion message -> error code, shared like
+    /// `intrinsic_map`.
+    pub(crate) error_codes: Arc<BTreeMap<String, u64>>,
     /// Monomorphized generic function instances to emit.
     pub(crate) mono_instances: Vec<MonoInstance>,
     /// Generic function AST definitions (name -> FnDef).
@@ -75,6 +90,8 @@ pub struct TIRBuilder {
     pub(crate) call_resolution_idx: usize,
     /// Active cfg flags for conditional compilation.
     pub(crate) cfg_flags: BTreeSet<String>,
+    /// Whether `#[test]` functions are emitted.
+    pub(crate) include_tests: bool,
     /// Target VM configuration.
     pub(crate) target_config: TerrainConfig,
 }
@@ -92,19 +109,23 @@ impl TIRBuilder {
             stack,
             struct_layouts: BTreeMap::new(),
             fn_return_widths: BTreeMap::new(),
+            fn_tuple_widths: BTreeMap::new(),
             event_tags: BTreeMap::new(),
-            event_defs: BTreeMap::new(),
             struct_types: BTreeMap::new(),
+            enum_types: enum_::builtin_enums(),
             constants: BTreeMap::new(),
+            data: BTreeMap::new(),
             temp_ram_addr: target_config.spill_ram_base / 2,
-            intrinsic_map: BTreeMap::new(),
-            module_aliases: BTreeMap::new(),
+            intrinsic_map: Arc::default(),
+            module_aliases: Arc::default(),
+            error_codes: Arc::default(),
             mono_instances: Vec::new(),
             generic_fn_defs: BTreeMap::new(),
             current_subs: BTreeMap::new(),
             call_resolutions: Vec::new(),
             call_resolution_idx: 0,
             cfg_flags: BTreeSet::from(["debug".to_string()]),
+            include_tests: false,
             target_config,
         }
     }
@@ -116,13 +137,29 @@ impl TIRBuilder {
         self
     }
 
-    pub fn with_intrinsics(mut self, map: BTreeMap<String, String>) -> Self {
-        self.intrinsic_map = map;
+    /// Also emit `#[test]` functions, so a test can be linked as the entry.
+    pub fn with_tests(mut self, include: bool) -> Self {
+        self.include_tests = include;
         self
     }
 
-    pub fn with_module_aliases(mut self, aliases: BTreeMap<String, String>) -> Self {
-        self.module_aliases = aliases;
+    pub fn with_intrinsics(mut self, map: impl Into<Arc<BTreeMap<String, String>>>) -> Self {
+        self.intrinsic_map = map.into();
+        self
+    }
+
+    pub fn with_module_aliases(
+        mut self,
+        aliases: impl Into<Arc<BTreeMap<String, String>>>,
+    ) -> Self {
+        self.module_aliases = aliases.into();
+        self
+    }
+
+    /// Set the program's error table, which gives each assertion
+    /// message its error code.
+    pub fn with_error_codes(mut self, codes: impl Into<Arc<BTreeMap<String, u64>>>) -> Self {
+        self.error_codes = codes.into();
         self
     }
 
@@ -131,6 +168,25 @@ impl TIRBuilder {
         self
     }
 
+    /// Register the `include_data` tables the module can name.
+    pub(crate) fn with_data(mut self, tables: BTreeMap<String, DataTable>) -> Self {
+        self.data.extend(tables);
+        self
+    }
+
+    /// Register return shapes of functions defined in other modules,
+    /// keyed by qualified name (`kernel.fee`, `os.neptune.kernel.fee`).
+    pub fn with_external_returns(mut self, returns: BTreeMap<String, Vec<u32>>) -> Self {
+        for (name, widths) in returns {
+            self.fn_return_widths
+                .insert(name.clone(), widths.iter().sum());
+            if widths.len() > 1 {
+                self.fn_tuple_widths.insert(name, widths);
+            }
+        }
+        self
+    }
+
     pub fn with_mono_instances(mut self, instances: Vec<MonoInstance>) -> Self {
         self.mono_instances = instances;
         self
@@ -146,6 +202,35 @@ impl TIRBuilder {
     // ═══════════════════════════════════════════════════════════════
 
     pub fn build_file(mut self, file: &File) -> Vec<TIROp> {
+        // ── Pre-scan: collect constant values (array sizes may name them) ──
+        for item in &file.items {
+            if !self.is_item_cfg_active(&item.node) {
+                continue;
+            }
+            if let Item::Const(cdef) = &item.node {
+                if let Expr::Literal(Literal::Integer(val)) = &cdef.value.node {
+                    self.constants.insert(cdef.name.node.clone(), *val);
+                }
+            }
+        }
+
+        // ── Pre-scan: collect struct and enum definitions (widths below depend on them) ──
+        for item in &file.items {
+            if !self.is_item_cfg_active(&item.node) {
+                continue;
+            }
+            match &item.node {
+                Item::Struct(sdef) => {
+                    self.struct_types
+                        .insert(sdef.name.node.clone(), sdef.clone());
+                }
+                Item::Enum(edef) => {
+                    self.enum_types.insert(edef.name.node.clone(), edef.clone());
+                }
+                _ => {}
+            }
+        }
+
         // ── Pre-scan: collect return widths and detect generic functions ──
         for item in &file.items {
             if !self.is_item_cfg_active(&item.node) {
@@ -159,28 +244,20 @@ impl TIRBuilder {
                     let width = func
                         .return_ty
                         .as_ref()
-                        .map(|t| resolve_type_width(&t.node, &self.target_config))
+                        .map(|t| self.type_width(&t.node))
                         .unwrap_or(0);
                     self.fn_return_widths.insert(func.name.node.clone(), width);
+                    if let Some(Type::Tuple(elems)) = func.return_ty.as_ref().map(|t| &t.node) {
+                        let widths = elems.iter().map(|t| self.type_width(t)).collect();
+                        self.fn_tuple_widths.insert(func.name.node.clone(), widths);
+                    }
                 }
             }
         }
 
         // ── Pre-scan: register return widths for monomorphized instances ──
-        for inst in &self.mono_instances.clone() {
-            if let Some(gdef) = self.generic_fn_defs.get(&inst.name).cloned() {
-                let mut subs = BTreeMap::new();
-                for (param, val) in gdef.type_params.iter().zip(inst.size_args.iter()) {
-                    subs.insert(param.node.clone(), *val);
-                }
-                let width = gdef
-                    .return_ty
-                    .as_ref()
-                    .map(|t| resolve_type_width_with_subs(&t.node, &subs, &self.target_config))
-                    .unwrap_or(0);
-                let mangled = inst.mangled_name();
-                self.fn_return_widths.insert(mangled, width);
-            }
+        for inst in self.mono_instances.clone() {
+            self.register_mono_width(&inst);
         }
 
         // ── Pre-scan: collect intrinsic mappings ──
@@ -196,47 +273,22 @@ impl TIRBuilder {
                     } else {
                         intrinsic.node.clone()
                     };
-                    self.intrinsic_map
+                    Arc::make_mut(&mut self.intrinsic_map)
                         .insert(func.name.node.clone(), intr_value);
                 }
             }
         }
 
-        // ── Pre-scan: collect struct type definitions ──
-        for item in &file.items {
-            if !self.is_item_cfg_active(&item.node) {
-                continue;
-            }
-            if let Item::Struct(sdef) = &item.node {
-                self.struct_types
-                    .insert(sdef.name.node.clone(), sdef.clone());
-            }
-        }
-
-        // ── Pre-scan: collect constant values ──
-        for item in &file.items {
-            if !self.is_item_cfg_active(&item.node) {
-                continue;
-            }
-            if let Item::Const(cdef) = &item.node {
-                if let Expr::Literal(Literal::Integer(val)) = &cdef.value.node {
-                    self.constants.insert(cdef.name.node.clone(), *val);
-                }
-            }
-        }
-
-        // ── Pre-scan: assign sequential tags to events ──
-        let mut event_tag = 0u64;
+        // ── Pre-scan: derive event tags from order and version ──
+        let mut event_index = 0u64;
         for item in &file.items {
             if !self.is_item_cfg_active(&item.node) {
                 continue;
             }
             if let Item::Event(edef) = &item.node {
-                self.event_tags.insert(edef.name.node.clone(), event_tag);
-                let field_names: Vec<String> =
-                    edef.fields.iter().map(|f| f.name.node.clone()).collect();
-                self.event_defs.insert(edef.name.node.clone(), field_names);
-                event_tag += 1;
+                self.event_tags
+                    .insert(edef.name.node.clone(), edef.tag(event_index));
+                event_index += 1;
             }
         }
 
@@ -247,7 +299,7 @@ impl TIRBuilder {
                     "sec ram: prover-initialized RAM slots".to_string(),
                 ));
                 for (addr, ty) in entries {
-                    let width = resolve_type_width(&ty.node, &self.target_config);
+                    let width = self.type_width(&ty.node);
                     self.ops.push(TIROp::Comment(format!(
                         "ram[{}]: {} ({} field element{})",
                         addr,
@@ -265,24 +317,27 @@ impl TIRBuilder {
             self.ops.push(TIROp::Entry("main".to_string()));
         }
 
-        // ── Emit non-generic, non-test functions ──
+        // ── Emit non-generic functions (tests only when requested) ──
         for item in &file.items {
             if !self.is_item_cfg_active(&item.node) {
                 continue;
             }
             if let Item::Fn(func) = &item.node {
-                if func.type_params.is_empty() && !func.is_test {
+                if func.type_params.is_empty() && (self.include_tests || !func.is_test) {
                     self.build_fn(func);
                 }
             }
         }
 
         // ── Emit monomorphized copies of generic functions ──
-        let instances = self.mono_instances.clone();
-        for inst in &instances {
+        // A generic body may call `f<N + 1>`, whose instance is only known
+        // once N is, so emitting can append instances to the list.
+        let mut emitted = 0;
+        while let Some(inst) = self.mono_instances.get(emitted).cloned() {
             if let Some(gdef) = self.generic_fn_defs.get(&inst.name).cloned() {
-                self.build_mono_fn(&gdef, inst);
+                self.build_mono_fn(&gdef, &inst);
             }
+            emitted += 1;
         }
 
         self.ops
@@ -300,16 +355,33 @@ impl TIRBuilder {
         let param_widths: Vec<u32> = func
             .params
             .iter()
-            .map(|p| resolve_type_width(&p.ty.node, &self.target_config))
+            .map(|p| self.type_width(&p.ty.node))
             .collect();
         let ret_width = func
             .return_ty
             .as_ref()
-            .map(|t| resolve_type_width(&t.node, &self.target_config))
+            .map(|t| self.type_width(&t.node))
             .unwrap_or(0);
         self.build_fn_body(&name, func, &param_widths, ret_width);
     }
 
+    /// Record the return width of a monomorphized instance under its
+    /// mangled name.
+    pub(crate) fn register_mono_width(&mut self, inst: &MonoInstance) {
+        if let Some(gdef) = self.generic_fn_defs.get(&inst.name) {
+            let mut subs = BTreeMap::new();
+            for (param, val) in gdef.type_params.iter().zip(inst.size_args.iter()) {
+                subs.insert(param.node.clone(), *val);
+            }
+            let width = gdef
+                .return_ty
+                .as_ref()
+                .map(|t| self.type_width_with_subs(&t.node, &subs))
+                .unwrap_or(0);
+            self.fn_return_widths.insert(inst.mangled_name(), width);
+        }
+    }
+
     fn build_mono_fn(&mut self, func: &FnDef, inst: &MonoInstance) {
         if func.body.is_none() {
             return;
@@ -323,14 +395,12 @@ impl TIRBuilder {
         let param_widths: Vec<u32> = func
             .params
             .iter()
-            .map(|p| {
-                resolve_type_width_with_subs(&p.ty.node, &self.current_subs, &self.target_config)
-            })
+            .map(|p| self.type_width_with_subs(&p.ty.node, &self.current_subs))
             .collect();
         let ret_width = func
             .return_ty
             .as_ref()
-            .map(|t| resolve_type_width_with_subs(&t.node, &self.current_subs, &self.target_config))
+            .map(|t| self.type_width_with_subs(&t.node, &self.current_subs))
             .unwrap_or(0);
         self.build_fn_body(&name, func, &param_widths, ret_width);
         self.current_subs.clear();
@@ -400,6 +470,7 @@ impl TIRBuilder {
         for (param, &width) in func.params.iter().zip(param_widths) {
             self.stack.push_named(&param.name.node, width);
             self.flush_stack_effects();
+            self.register_struct_layout_from_type(&param.name.node, &param.ty.node);
         }
 
         let body = func.body.as_ref().expect("caller checked body.is_some()");
@@ -408,8 +479,11 @@ impl TIRBuilder {
         if has_return && ret_width > 1 {
             // Multi-element return: build statements first, then handle
             // the tail expression specially to avoid unnecessary copies.
-            for stmt in &body.node.stmts {
-                self.build_stmt(&stmt.node);
+            let active = active_stmts(&body.node.stmts, &self.target_config.name);
+            for (stmt, active) in body.node.stmts.iter().zip(active) {
+                if active {
+                    self.build_stmt(&stmt.node);
+                }
             }
 
             if let Some(tail) = &body.node.tail_expr {
@@ -3,9 +3,9 @@
 use std::collections::BTreeMap;
 
 use crate::ast::*;
+use crate::span::Spanned;
 use crate::tir::TIROp;
 
-use super::layout::resolve_type_width;
 use super::TIRBuilder;
 
 // ─── Block and statement emission ─────────────────────────────────
@@ -36,9 +36,42 @@ impl TIRBuilder {
         }
     }
 
+    /// Width of each element bound by a tuple pattern: from the type
+    /// annotation, else the callee's declared return type, else an even
+    /// split of the value (digest destructuring).
+    fn tuple_pattern_widths(
+        &self,
+        init: &Expr,
+        ty: &Option<Spanned<Type>>,
+        n: usize,
+        total_width: u32,
+    ) -> Vec<u32> {
+        let declared = match (ty.as_ref().map(|t| &t.node), init) {
+            (Some(Type::Tuple(elems)), _) => {
+                Some(elems.iter().map(|t| self.type_width(t)).collect())
+            }
+            (_, Expr::Call { path, .. }) => {
+                self.fn_tuple_widths.get(&path.node.as_dotted()).cloned()
+            }
+            _ => None,
+        };
+        match declared {
+            Some(widths) if widths.len() == n && widths.iter().sum::<u32>() == total_width => {
+                widths
+            }
+            _ => {
+                let elem_width = if n > 0 { total_width / n as u32 } else { 1 };
+                vec![elem_width; n]
+            }
+        }
+    }
+
     pub(crate) fn build_block(&mut self, block: &Block) {
-        for stmt in &block.stmts {
-            self.build_stmt(&stmt.node);
+        let active = active_stmts(&block.stmts, &self.target_config.name);
+        for (stmt, active) in block.stmts.iter().zip(active) {
+            if active {
+                self.build_stmt(&stmt.node);
+            }
         }
         if let Some(tail) = &block.tail_expr {
             self.build_expr(&tail.node);
@@ -61,16 +94,20 @@ impl TIRBuilder {
                             // If type is an array, record elem_width.
                             if let Some(sp_ty) = ty {
                                 if let Type::Array(inner_ty, _) = &sp_ty.node {
-                                    let ew = resolve_type_width(inner_ty, &self.target_config);
+                                    let ew = self.type_width(inner_ty);
                                     if let Some(top) = self.stack.last_mut() {
                                         top.elem_width = Some(ew);
                                     }
                                 }
                             }
                             // Record struct field layout from struct init.
-                            if let Expr::StructInit { fields, .. } = &init.node {
+                            if let Expr::StructInit { path, fields, .. } = &init.node {
                                 let mut field_map = BTreeMap::new();
-                                let widths = self.compute_struct_field_widths(ty, fields);
+                                // Without an annotation, the init names the struct.
+                                let ty = ty.clone().or_else(|| {
+                                    Some(Spanned::new(Type::Named(path.node.clone()), path.span))
+                                });
+                                let widths = self.compute_struct_field_widths(&ty, fields);
                                 let total: u32 = widths.iter().sum();
                                 let mut offset = 0u32;
                                 for (i, (fname, _)) in fields.iter().enumerate() {
@@ -88,17 +125,16 @@ impl TIRBuilder {
                     Pattern::Tuple(names) => {
                         let top = self.stack.pop();
                         if let Some(entry) = top {
-                            let total_width = entry.width;
-                            let n = names.len() as u32;
-                            let elem_width = if n > 0 { total_width / n } else { 1 };
+                            let widths =
+                                self.tuple_pattern_widths(&init.node, ty, names.len(), entry.width);
 
-                            for name in names.iter() {
+                            for (name, width) in names.iter().zip(&widths) {
                                 let var_name = if name.node == "_" {
                                     "__anon"
                                 } else {
                                     &name.node
                                 };
-                                self.stack.push_named(var_name, elem_width);
+                                self.stack.push_named(var_name, *width);
                                 self.flush_stack_effects();
                             }
 
@@ -106,17 +142,15 @@ impl TIRBuilder {
                             // For `let (h1, _, _, _, _) = digest`, wildcards on top
                             // of the stack are immediately discarded.
                             let mut trailing_wildcards = 0u32;
-                            for name in names.iter().rev() {
+                            for (name, width) in names.iter().zip(&widths).rev() {
                                 if name.node == "_" {
-                                    trailing_wildcards += elem_width;
+                                    trailing_wildcards += width;
+                                    self.stack.pop();
                                 } else {
                                     break;
                                 }
                             }
                             if trailing_wildcards > 0 {
-                                for _ in 0..(trailing_wildcards / elem_width) {
-                                    self.stack.pop();
-                                }
                                 self.emit_pop(trailing_wildcards);
                             }
                         }
@@ -180,7 +214,12 @@ impl TIRBuilder {
                     let saved = self.stack.save_state();
                     let pre_depth = self.stack.stack_depth();
                     let mut then_body = self.build_block_as_ir(&then_block.node);
-                    Self::append_branch_cleanup(&mut then_body, self.stack.stack_depth(), pre_depth, 0);
+                    Self::append_branch_cleanup(
+                        &mut then_body,
+                        self.stack.stack_depth(),
+                        pre_depth,
+                        0,
+                    );
                     self.stack.restore_state(saved);
 
                     self.ops.push(TIROp::IfOnly { then_body });
@@ -201,8 +240,8 @@ impl TIRBuilder {
                 self.build_expr(&start.node);
                 self.build_expr(&end.node);
                 // counter = end - start: dup start, then Sub (st1 - st0)
-                self.ops.push(TIROp::Dup(1));  // [..., start, end, start]
-                self.ops.push(TIROp::Sub);     // [..., start, end - start]
+                self.ops.push(TIROp::Dup(1)); // [..., start, end, start]
+                self.ops.push(TIROp::Sub); // [..., start, end - start]
 
                 self.ops.push(TIROp::Call(loop_label.clone()));
                 // After return: [..., index, 0] — pop both counter and index
@@ -216,7 +255,7 @@ impl TIRBuilder {
                 // plus [index, counter] on top. Keep outer vars in the model
                 // so the loop body can reference them at the correct depths.
                 self.stack.push_named(&var.node, 1); // index (depth 1)
-                self.stack.push_temp(1);              // counter (depth 0)
+                self.stack.push_temp(1); // counter (depth 0)
 
                 let mut body_ir = self.build_block_as_ir(&body.node);
 
@@ -237,10 +276,47 @@ impl TIRBuilder {
                 // Increment the index.
                 // After cleanup, stack is [..., index, counter] (counter at st0).
                 // Swap to bring index to top, add 1, swap back.
-                body_ir.push(TIROp::Swap(1));  // [..., counter, index]
+                body_ir.push(TIROp::Swap(1)); // [..., counter, index]
                 body_ir.push(TIROp::Push(1));
-                body_ir.push(TIROp::Add);      // [..., counter, index+1]
-                body_ir.push(TIROp::Swap(1));  // [..., index+1, counter]
+                body_ir.push(TIROp::Add); // [..., counter, index+1]
+                body_ir.push(TIROp::Swap(1)); // [..., index+1, counter]
+                                              // recurse is added by the lowering
+
+                self.stack.restore_state(saved);
+
+                self.ops.push(TIROp::Loop {
+                    label: loop_label,
+                    body: body_ir,
+                });
+            }
+
+            Stmt::While { cond, bound, body } => {
+                let loop_label = self.fresh_label("while");
+
+                // The loop counts down from the bound; a false condition
+                // ends it early by zeroing the counter.
+                self.build_expr(&bound.node);
+                self.ops.push(TIROp::Call(loop_label.clone()));
+                // After return: [..., 0]
+                self.ops.push(TIROp::Pop(1));
+                self.stack.pop(); // pop counter model
+
+                let saved = self.stack.save_state();
+                self.stack.push_temp(1); // counter (depth 0)
+
+                let mut body_ir = self.build_expr_as_ir(&cond.node);
+                self.stack.pop(); // cond consumed
+
+                let inner = self.stack.save_state();
+                let pre_depth = self.stack.stack_depth();
+                let mut then_body = self.build_block_as_ir(&body.node);
+                Self::append_branch_cleanup(&mut then_body, self.stack.stack_depth(), pre_depth, 0);
+                self.stack.restore_state(inner);
+
+                body_ir.push(TIROp::IfElse {
+                    then_body,
+                    else_body: vec![TIROp::Pop(1), TIROp::Push(0)],
+                });
                 // recurse is added by the lowering
 
                 self.stack.restore_state(saved);
@@ -291,6 +367,10 @@ impl TIRBuilder {
                 }
             }
 
+            // Rewritten into skip flags by `desugar_loop_exits`; the type
+            // checker rejects them outside loops.
+            Stmt::Break | Stmt::Continue => {}
+
             Stmt::Reveal { event_name, fields } => {
                 let tag = match self.event_tags.get(&event_name.node).copied() {
                     Some(t) => t,
@@ -302,23 +382,16 @@ impl TIRBuilder {
                         0
                     }
                 };
-                let decl_order = self
-                    .event_defs
-                    .get(&event_name.node)
-                    .cloned()
-                    .unwrap_or_default();
-
-                for def_name in &decl_order {
-                    if let Some((_name, val)) = fields.iter().find(|(n, _)| n.node == *def_name) {
-                        self.build_expr(&val.node);
-                        self.stack.pop();
-                    }
+                // Desugaring put the fields in declaration order.
+                for (_name, val) in fields {
+                    self.build_expr(&val.node);
+                    self.stack.pop();
                 }
 
                 self.ops.push(TIROp::Reveal {
                     name: event_name.node.clone(),
                     tag,
-                    field_count: decl_order.len() as u32,
+                    field_count: fields.len() as u32,
                 });
             }
 
@@ -375,26 +448,18 @@ impl TIRBuilder {
                         0
                     }
                 };
-                let decl_order = self
-                    .event_defs
-                    .get(&event_name.node)
-                    .cloned()
-                    .unwrap_or_default();
-                let field_count = decl_order.len() as u32;
-
                 // Push fields in reverse declaration order (so first declared
-                // field ends up on top after all pushes).
-                for def_name in decl_order.iter().rev() {
-                    if let Some((_name, val)) = fields.iter().find(|(n, _)| n.node == *def_name) {
-                        self.build_expr(&val.node);
-                        self.stack.pop();
-                    }
+                // field ends up on top after all pushes). Desugaring put the
+                // fields in declaration order.
+                for (_name, val) in fields.iter().rev() {
+                    self.build_expr(&val.node);
+                    self.stack.pop();
                 }
 
                 self.ops.push(TIROp::Seal {
                     name: event_name.node.clone(),
                     tag,
-                    field_count,
+                    field_count: fields.len() as u32,
                 });
             }
         }
@@ -3,6 +3,7 @@
 use crate::ast::*;
 use crate::ir::tir::builder::*;
 use crate::span::{Span, Spanned};
+use std::collections::BTreeMap;
 
 fn dummy_span() -> Span {
     Span::dummy()
@@ -40,8 +41,12 @@ fn pass_through_hash_emits_minimal_ops() {
             intrinsic: None,
             is_test: false,
             is_pure: false,
+            is_authenticated: false,
+            auto_range_check: false,
+            entrypoint: None,
             requires: vec![],
             ensures: vec![],
+            assumes: vec![],
             name: sp("wrapper".to_string()),
             type_params: vec![],
             params,
@@ -57,10 +62,9 @@ fn pass_through_hash_emits_minimal_ops() {
         }))],
     };
 
-    let mut builder = make_builder();
-    builder
-        .intrinsic_map
-        .insert("hash".to_string(), "hash".to_string());
+    let mut intrinsics = BTreeMap::new();
+    intrinsics.insert("hash".to_string(), "hash".to_string());
+    let builder = make_builder().with_intrinsics(intrinsics);
     let ops = builder.build_file(&file);
 
     // Should be: FnStart, Hash, Return, FnEnd — 4 ops total.
@@ -91,8 +95,12 @@ fn non_pass_through_still_compiles_normally() {
             intrinsic: None,
             is_test: false,
             is_pure: false,
+            is_authenticated: false,
+            auto_range_check: false,
+            entrypoint: None,
             requires: vec![],
             ensures: vec![],
+            assumes: vec![],
             name: sp("add".to_string()),
             type_params: vec![],
             params: vec![
@@ -150,8 +158,12 @@ fn pass_through_user_call_emits_call_and_return() {
                 intrinsic: None,
                 is_test: false,
                 is_pure: false,
+                is_authenticated: false,
+                auto_range_check: false,
+                entrypoint: None,
                 requires: vec![],
                 ensures: vec![],
+                assumes: vec![],
                 name: sp("target".to_string()),
                 type_params: vec![],
                 params: vec![Param {
@@ -170,8 +182,12 @@ fn pass_through_user_call_emits_call_and_return() {
                 intrinsic: None,
                 is_test: false,
                 is_pure: false,
+                is_authenticated: false,
+                auto_range_check: false,
+                entrypoint: None,
                 requires: vec![],
                 ensures: vec![],
+                assumes: vec![],
                 name: sp("wrapper".to_string()),
                 type_params: vec![],
                 params: vec![Param {
@@ -235,8 +251,12 @@ fn pass_through_multi_width_params_emits_minimal_ops() {
                 intrinsic: None,
                 is_test: false,
                 is_pure: false,
+                is_authenticated: false,
+                auto_range_check: false,
+                entrypoint: None,
                 requires: vec![],
                 ensures: vec![],
+                assumes: vec![],
                 name: sp("target".to_string()),
                 type_params: vec![],
                 params: vec![
@@ -261,8 +281,12 @@ fn pass_through_multi_width_params_emits_minimal_ops() {
                 intrinsic: None,
                 is_test: false,
                 is_pure: false,
+                is_authenticated: false,
+                auto_range_check: false,
+                entrypoint: None,
                 requires: vec![],
                 ensures: vec![],
+                assumes: vec![],
                 name: sp("wrapper".to_string()),
                 type_params: vec![],
                 params: vec![
@@ -313,3 +337,25 @@ fn pass_through_multi_width_params_emits_minimal_ops() {
     );
     assert!(matches!(wrapper_ops[1], TIROp::Call(ref n) if n == "target"));
 }
+
+// ── Test: struct parameters occupy their full width ──
+
+#[test]
+fn struct_param_fields_are_read_at_their_depth() {
+    let source = "program test\n\
+        struct Point { x: Field, y: Field, z: Field }\n\
+        fn first(p: Point) -> Field { p.x }\n\
+        fn main() { pub_write(first(Point { x: 1, y: 2, z: 3 })) }\n";
+    let file = crate::parse_source_silent(source, "test.tri").expect("parses");
+    let ops = make_builder().build_file(&file);
+    let start = ops
+        .iter()
+        .position(|op| matches!(op, TIROp::FnStart(name) if name == "first"))
+        .expect("first is emitted");
+    // Three words on the stack with `z` on top: `x` sits at depth 2.
+    assert!(
+        matches!(ops[start + 1], TIROp::Dup(2)),
+        "{:?}",
+        &ops[start..]
+    );
+}
@@ -38,8 +38,12 @@ fn test_minimal_program() {
         intrinsic: None,
         is_test: false,
         is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
         requires: vec![],
         ensures: vec![],
+        assumes: vec![],
         name: sp("main".to_string()),
         type_params: vec![],
         params: vec![],
@@ -81,8 +85,12 @@ fn test_if_else_produces_structural_op() {
         intrinsic: None,
         is_test: false,
         is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
         requires: vec![],
         ensures: vec![],
+        assumes: vec![],
         name: sp("main".to_string()),
         type_params: vec![],
         params: vec![],
@@ -127,8 +135,12 @@ fn test_for_loop_produces_loop_op() {
         intrinsic: None,
         is_test: false,
         is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
         requires: vec![],
         ensures: vec![],
+        assumes: vec![],
         name: sp("main".to_string()),
         type_params: vec![],
         params: vec![],
@@ -138,7 +150,7 @@ fn test_for_loop_produces_loop_op() {
                 var: sp("i".to_string()),
                 start: sp(Expr::Literal(Literal::Integer(0))),
                 end: sp(Expr::Literal(Literal::Integer(5))),
-                bound: Some(5),
+                bound: Some(sp(Expr::Literal(Literal::Integer(5)))),
                 body: sp(Block {
                     stmts: vec![],
                     tail_expr: None,
@@ -164,8 +176,12 @@ fn test_arithmetic_sequence() {
         intrinsic: None,
         is_test: false,
         is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
         requires: vec![],
         ensures: vec![],
+        assumes: vec![],
         name: sp("main".to_string()),
         type_params: vec![],
         params: vec![],
@@ -252,8 +268,12 @@ fn test_module_no_preamble() {
             intrinsic: None,
             is_test: false,
             is_pure: false,
+            is_authenticated: false,
+            auto_range_check: false,
+            entrypoint: None,
             requires: vec![],
             ensures: vec![],
+            assumes: vec![],
             name: sp("helper".to_string()),
             type_params: vec![],
             params: vec![],
@@ -288,8 +308,12 @@ fn test_if_only_produces_structural_op() {
         intrinsic: None,
         is_test: false,
         is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
         requires: vec![],
         ensures: vec![],
+        assumes: vec![],
         name: sp("main".to_string()),
         type_params: vec![],
         params: vec![],
@@ -322,8 +346,12 @@ fn test_let_and_var_ref() {
         intrinsic: None,
         is_test: false,
         is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
         requires: vec![],
         ensures: vec![],
+        assumes: vec![],
         name: sp("main".to_string()),
         type_params: vec![],
         params: vec![],
@@ -359,8 +387,12 @@ fn test_intrinsic_pub_read_write() {
         intrinsic: None,
         is_test: false,
         is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
         requires: vec![],
         ensures: vec![],
+        assumes: vec![],
         name: sp("main".to_string()),
         type_params: vec![],
         params: vec![],
@@ -397,8 +429,12 @@ fn test_if_else_nested_bodies_have_content() {
         intrinsic: None,
         is_test: false,
         is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
         requires: vec![],
         ensures: vec![],
+        assumes: vec![],
         name: sp("main".to_string()),
         type_params: vec![],
         params: vec![],
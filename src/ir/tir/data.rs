@@ -0,0 +1,139 @@
+//! `include_data` tables in the emitted program.
+//!
+//! A table read at a known index compiles to one `push`, and the whole
+//! table to a `push` per element. A runtime index needs the table in
+//! memory, in one of two ways: push the whole table and store it to
+//! temporary RAM at every read, or store it once, in a `__data` routine
+//! the linker calls ahead of the entry, and `read_mem` it at every read.
+//! `prefers_ram` makes the choice per table with the same table profile
+//! the link-time constant materializer uses.
+
+use std::sync::Arc;
+
+use super::materialize::{cost_key, profile};
+use super::TIROp;
+
+/// Widest store a single `write_mem` makes.
+const MAX_WIDTH: usize = 5;
+
+/// Label of the routine that stores the RAM-resident tables.
+pub(crate) const DATA_LABEL: &str = "__data";
+
+/// An `include_data` table as the builder sees it.
+#[derive(Clone, Debug)]
+pub(crate) struct DataTable {
+    pub(crate) values: Arc<[u64]>,
+    /// RAM address of element 0 when the table is stored by `__data`;
+    /// element `k` is at `address + k`.
+    pub(crate) address: Option<u64>,
+}
+
+/// Whether `reads` runtime-indexed reads of a table of `len` elements
+/// cost less from RAM, stored once, than from a push of the whole table
+/// at every read.
+pub(crate) fn prefers_ram(len: usize, reads: usize) -> bool {
+    if reads == 0 || len == 0 {
+        return false;
+    }
+    let mut pushed = Vec::new();
+    let mut stored = vec!["call __data".to_string(), "return".to_string()];
+    for chunk in (0..len).collect::<Vec<_>>().chunks(MAX_WIDTH) {
+        stored.extend(chunk.iter().map(|_| "push 0".to_string()));
+        stored.extend(store_tail(chunk.len()));
+    }
+    for _ in 0..reads {
+        pushed.extend((0..len).map(|_| "push 0".to_string()));
+        pushed.push("swap 1".to_string());
+        for i in 0..len {
+            pushed.extend(store_tail(1));
+            if i + 1 < len {
+                pushed.push("swap 1".to_string());
+            }
+        }
+        pushed.extend(
+            [
+                "push 0",
+                "add",
+                "dup 0",
+                "read_mem 1",
+                "pop 1",
+                "swap 1",
+                "pop 1",
+            ]
+            .map(String::from),
+        );
+        stored.extend(["push 0", "add", "read_mem 1", "pop 1"].map(String::from));
+    }
+    cost_key(&profile(&stored)) < cost_key(&profile(&pushed))
+}
+
+fn store_tail(width: usize) -> [String; 3] {
+    [
+        "push 0".to_string(),
+        format!("write_mem {}", width),
+        "pop 1".to_string(),
+    ]
+}
+
+/// Store each table at its address, leaving the stack unchanged.
+pub(crate) fn store_ops(tables: &[(u64, &[u64])]) -> Vec<TIROp> {
+    let mut ops = Vec::new();
+    for (address, values) in tables {
+        for (k, chunk) in values.chunks(MAX_WIDTH).enumerate() {
+            // `write_mem` stores the element below the address first, so
+            // the chunk is pushed last element first.
+            ops.extend(chunk.iter().rev().map(|&v| TIROp::Push(v)));
+            ops.push(TIROp::Push(address + (k * MAX_WIDTH) as u64));
+            ops.push(TIROp::WriteMem(chunk.len() as u32));
+            ops.push(TIROp::Pop(1));
+        }
+    }
+    ops
+}
+
+/// Read element `index` (on top of the stack) of the table at `address`.
+pub(crate) fn read_ops(address: u64) -> Vec<TIROp> {
+    vec![
+        TIROp::Push(address),
+        TIROp::Add,
+        TIROp::ReadMem(1),
+        TIROp::Pop(1),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_reads_prefer_ram() {
+        assert!(!prefers_ram(256, 0));
+        assert!(prefers_ram(256, 1));
+        assert!(prefers_ram(4, 3));
+    }
+
+    #[test]
+    fn test_store_ops_chunk_tables() {
+        let values: Vec<u64> = (10..17).collect();
+        let ops = store_ops(&[(100, &values)]);
+        let text: Vec<String> = ops.iter().map(|op| format!("{}", op)).collect();
+        assert_eq!(
+            text,
+            vec![
+                "push 14",
+                "push 13",
+                "push 12",
+                "push 11",
+                "push 10",
+                "push 100",
+                "write_mem 5",
+                "pop 1",
+                "push 16",
+                "push 15",
+                "push 105",
+                "write_mem 2",
+                "pop 1",
+            ]
+        );
+    }
+}
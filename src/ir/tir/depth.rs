@@ -0,0 +1,543 @@
+//! Op-stack and call-depth analysis.
+//!
+//! Triton VM keeps 16 op-stack registers and spills the rest into op-stack
+//! underflow memory; every `call` pushes the jump stack. Neither has a
+//! hard limit, but a program that needs thousands of elements or frames
+//! is almost always leaking stack or recursing without bound, and pays
+//! for it in Op Stack and Jump Stack table rows. This pass bounds both
+//! statically from the TIR of every module: each op has a fixed stack
+//! effect, `asm` blocks have their declared one, calls add the callee's
+//! bounds, branches take the worse arm, and a loop must leave the stack
+//! as it found it. The report names the call chain that reaches each
+//! maximum.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::linker::resolve_target;
+use super::TIROp;
+use crate::syntax::intern::Interner;
+
+/// Limits a program is checked against. Both can be raised from the
+/// command line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepthLimits {
+    /// Elements above the initial op stack.
+    pub op_stack: u32,
+    /// Nested calls, including branch arms and loops.
+    pub call_depth: u32,
+}
+
+impl Default for DepthLimits {
+    fn default() -> Self {
+        Self {
+            op_stack: 1024,
+            call_depth: 1024,
+        }
+    }
+}
+
+/// One call on a worst path, with the op-stack depth at the call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    /// Linked label of the callee.
+    pub label: String,
+    /// Op-stack depth, relative to program start, when it is entered.
+    pub depth: i64,
+}
+
+/// Bounds of a whole program, from its entry point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DepthReport {
+    pub limits: DepthLimits,
+    /// Largest op-stack depth reached, relative to program start.
+    pub max_op_stack: i64,
+    /// Calls from the entry point to where `max_op_stack` is reached.
+    pub op_stack_path: Vec<Frame>,
+    /// Deepest nesting of calls, the entry call included.
+    pub max_call_depth: u32,
+    /// Labels from the entry point down the deepest call chain.
+    pub call_path: Vec<String>,
+    /// A cycle in the call graph, if any: its depth has no bound.
+    pub recursion: Option<Vec<String>>,
+    /// Loops whose body changes the stack: `(label, change per iteration)`.
+    pub unbalanced_loops: Vec<(String, i64)>,
+}
+
+impl DepthReport {
+    /// Why the program fails the limits; empty when it passes.
+    pub fn violations(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        if self.max_op_stack > i64::from(self.limits.op_stack) {
+            out.push(format!(
+                "op stack reaches depth {}, limit {}",
+                self.max_op_stack, self.limits.op_stack
+            ));
+        }
+        if self.max_call_depth > self.limits.call_depth {
+            out.push(format!(
+                "calls nest {} deep, limit {}",
+                self.max_call_depth, self.limits.call_depth
+            ));
+        }
+        if let Some(cycle) = &self.recursion {
+            out.push(format!(
+                "recursion has no depth bound: {}",
+                cycle.join(" -> ")
+            ));
+        }
+        for (label, change) in &self.unbalanced_loops {
+            out.push(format!(
+                "loop {} changes the op stack by {:+} per iteration",
+                label, change
+            ));
+        }
+        out
+    }
+}
+
+impl fmt::Display for DepthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Op stack:   max depth {} (limit {})",
+            self.max_op_stack, self.limits.op_stack
+        )?;
+        for frame in &self.op_stack_path {
+            writeln!(f, "  {} at depth {}", frame.label, frame.depth)?;
+        }
+        writeln!(
+            f,
+            "Call depth: max {} (limit {})",
+            self.max_call_depth, self.limits.call_depth
+        )?;
+        for (i, label) in self.call_path.iter().enumerate() {
+            writeln!(f, "  {}{}", "  ".repeat(i), label)?;
+        }
+        for violation in self.violations() {
+            writeln!(f, "error: {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Analyze a program given as the TIR of each module, by module name.
+/// `None` if no module has an entry point.
+pub(crate) fn analyze(
+    modules: &[(String, Vec<TIROp>)],
+    limits: DepthLimits,
+) -> Option<DepthReport> {
+    let mut analyzer = Analyzer::default();
+    let mut entry = None;
+    for (module, ops) in modules {
+        let prefix = format!("{}__", module.replace('.', "_"));
+        analyzer.collect(&prefix, ops, None);
+        for op in ops {
+            if let TIROp::Entry(main) = op {
+                entry = Some(analyzer.key(&prefix, main));
+            }
+        }
+    }
+    let entry = entry?;
+    for label in analyzer.bodies.keys() {
+        analyzer.labels.intern(label);
+    }
+
+    let main = analyzer.summary(&entry);
+    let mut report = DepthReport {
+        limits,
+        max_op_stack: main.peak,
+        op_stack_path: vec![Frame {
+            label: entry.clone(),
+            depth: 0,
+        }],
+        max_call_depth: main.calls + 1,
+        call_path: vec![entry],
+        recursion: analyzer.recursion.take(),
+        unbalanced_loops: std::mem::take(&mut analyzer.unbalanced),
+    };
+    report.op_stack_path.extend(main.peak_path);
+    report.call_path.extend(main.call_path);
+    Some(report)
+}
+
+/// Bounds of one function or loop, relative to its entry.
+#[derive(Clone, Debug, Default)]
+struct Summary {
+    /// Net op-stack change (the larger one where paths differ).
+    net: i64,
+    /// Highest op-stack depth reached.
+    peak: i64,
+    peak_path: Vec<Frame>,
+    /// Deepest call nesting below this body.
+    calls: u32,
+    call_path: Vec<String>,
+}
+
+#[derive(Default)]
+struct Analyzer<'a> {
+    /// Body of each function and loop, by linked label, with the
+    /// mangled prefix of its module.
+    bodies: BTreeMap<String, (String, Vec<&'a TIROp>, bool)>,
+    labels: Interner,
+    summaries: BTreeMap<String, Summary>,
+    /// Labels being summarized, outermost first.
+    active: Vec<String>,
+    recursion: Option<Vec<String>>,
+    unbalanced: Vec<(String, i64)>,
+}
+
+impl<'a> Analyzer<'a> {
+    /// Linked label of a function or loop name used in `prefix`'s module.
+    fn key(&self, prefix: &str, name: &str) -> String {
+        match name.strip_prefix('@') {
+            Some(global) => global.to_string(),
+            None => format!("{}{}", prefix, name.strip_prefix("__").unwrap_or(name)),
+        }
+    }
+
+    /// Record the functions and loops defined anywhere in `ops`. Ops
+    /// outside the functions they define belong to `current`.
+    fn collect(&mut self, prefix: &str, ops: &'a [TIROp], mut current: Option<String>) {
+        let mut enclosing = Vec::new();
+        for op in ops {
+            match op {
+                TIROp::FnStart(name) => {
+                    enclosing.push(current.take());
+                    let key = self.key(prefix, name);
+                    self.bodies
+                        .insert(key.clone(), (prefix.to_string(), Vec::new(), false));
+                    current = Some(key);
+                    continue;
+                }
+                TIROp::FnEnd => {
+                    current = enclosing.pop().flatten();
+                    continue;
+                }
+                TIROp::Loop { label, body } => {
+                    let key = self.key(prefix, label);
+                    self.bodies
+                        .insert(key.clone(), (prefix.to_string(), Vec::new(), true));
+                    self.collect(prefix, body, Some(key));
+                    continue;
+                }
+                // Arms are summarized with their branch; only what they
+                // define is recorded here.
+                TIROp::IfElse {
+                    then_body,
+                    else_body,
+                } => {
+                    self.collect(prefix, then_body, None);
+                    self.collect(prefix, else_body, None);
+                }
+                TIROp::IfOnly { then_body } => self.collect(prefix, then_body, None),
+                TIROp::ProofBlock { body, .. } => self.collect(prefix, body, None),
+                _ => {}
+            }
+            if let Some(body) = current.as_ref().and_then(|c| self.bodies.get_mut(c)) {
+                body.1.push(op);
+            }
+        }
+    }
+
+    /// Linked label a call in `prefix`'s module reaches, as the linker
+    /// resolves it.
+    fn resolve(&self, prefix: &str, target: &str) -> Option<String> {
+        let key = self.key(prefix, target);
+        if self.bodies.contains_key(&key) {
+            return Some(key);
+        }
+        resolve_target(&key, &self.labels).map(|sym| self.labels.resolve(sym).to_string())
+    }
+
+    fn summary(&mut self, label: &str) -> Summary {
+        if let Some(summary) = self.summaries.get(label) {
+            return summary.clone();
+        }
+        if let Some(start) = self.active.iter().position(|l| l == label) {
+            if self.recursion.is_none() {
+                let mut cycle = self.active[start..].to_vec();
+                cycle.push(label.to_string());
+                self.recursion = Some(cycle);
+            }
+            return Summary::default();
+        }
+        let Some((prefix, ops, is_loop)) = self.bodies.get(label).cloned() else {
+            return Summary::default();
+        };
+        self.active.push(label.to_string());
+        let body = self.sequence(&prefix, &ops);
+        self.active.pop();
+
+        let summary = if is_loop {
+            if body.net != 0 {
+                self.unbalanced.push((label.to_string(), body.net));
+            }
+            // dup 0, push 0 compare the counter before the body runs.
+            Summary {
+                net: 0,
+                peak: body.peak.max(2),
+                ..body
+            }
+        } else {
+            body
+        };
+        self.summaries.insert(label.to_string(), summary.clone());
+        summary
+    }
+
+    /// Summarize straight-line ops, skipping the functions an arm defines.
+    fn sequence(&mut self, prefix: &str, ops: &[&TIROp]) -> Summary {
+        let mut s = Summary::default();
+        let mut defined = 0;
+        for op in ops {
+            match op {
+                TIROp::FnStart(_) => defined += 1,
+                TIROp::FnEnd => defined -= 1,
+                _ if defined == 0 => self.step(prefix, op, &mut s),
+                _ => {}
+            }
+        }
+        s
+    }
+
+    /// Apply one op to the running summary `s`, whose `net` is the
+    /// current depth.
+    fn step(&mut self, prefix: &str, op: &TIROp, s: &mut Summary) {
+        let depth = s.net;
+        match op {
+            TIROp::Call(target) => {
+                let Some(label) = self.resolve(prefix, target) else {
+                    return;
+                };
+                let callee = self.summary(&label);
+                let mut path = vec![Frame {
+                    label: label.clone(),
+                    depth,
+                }];
+                path.extend(shift(&callee.peak_path, depth));
+                raise(s, depth + callee.peak, path);
+                if callee.calls + 1 > s.calls {
+                    s.calls = callee.calls + 1;
+                    s.call_path = std::iter::once(label).chain(callee.call_path).collect();
+                }
+                s.net += callee.net;
+            }
+            // The condition is on top: `push 1; swap 1; skiz` trades it
+            // for a flag, and each arm starts once the flag is popped.
+            TIROp::IfElse {
+                then_body,
+                else_body,
+            } => {
+                raise(s, depth + 1, Vec::new());
+                let then_net = self.arm(prefix, then_body, depth - 1, s);
+                let else_net = self.arm(prefix, else_body, depth - 1, s);
+                // The then-arm pushes a 0 flag before it returns.
+                raise(s, depth + then_net, Vec::new());
+                s.net = depth - 1 + then_net.max(else_net);
+            }
+            TIROp::IfOnly { then_body } => {
+                let then_net = self.arm(prefix, then_body, depth - 1, s);
+                s.net = depth - 1 + then_net.max(0);
+            }
+            TIROp::ProofBlock { body, .. } => {
+                let ops: Vec<&TIROp> = body.iter().collect();
+                let block = self.sequence(prefix, &ops);
+                let path = shift(&block.peak_path, depth);
+                raise(s, depth + block.peak, path);
+                if block.calls > s.calls {
+                    s.calls = block.calls;
+                    s.call_path = block.call_path;
+                }
+                s.net += block.net;
+            }
+            _ => {
+                let (net, peak) = effect(op);
+                raise(s, depth + peak, Vec::new());
+                s.net += net;
+            }
+        }
+    }
+
+    /// Account for a branch arm entered at `depth`, a call deeper than
+    /// `s`. Returns its net change.
+    fn arm(&mut self, prefix: &str, body: &[TIROp], depth: i64, s: &mut Summary) -> i64 {
+        let ops: Vec<&TIROp> = body.iter().collect();
+        let arm = self.sequence(prefix, &ops);
+        raise(s, depth + arm.peak, shift(&arm.peak_path, depth));
+        if arm.calls + 1 > s.calls {
+            s.calls = arm.calls + 1;
+            s.call_path = std::iter::once("(branch)".to_string())
+                .chain(arm.call_path)
+                .collect();
+        }
+        arm.net
+    }
+}
+
+/// Record `peak`, reached through `path`, if it is a new maximum.
+fn raise(s: &mut Summary, peak: i64, path: Vec<Frame>) {
+    if peak > s.peak {
+        s.peak = peak;
+        s.peak_path = path;
+    }
+}
+
+/// `path` for a body entered at `depth`.
+fn shift(path: &[Frame], depth: i64) -> Vec<Frame> {
+    path.iter()
+        .map(|f| Frame {
+            label: f.label.clone(),
+            depth: f.depth + depth,
+        })
+        .collect()
+}
+
+/// Net stack change of an op, and the highest depth above the starting
+/// one it reaches on Triton VM.
+fn effect(op: &TIROp) -> (i64, i64) {
+    let net = match op {
+        TIROp::Push(_) | TIROp::Dup(_) | TIROp::Split => 1,
+        TIROp::Pop(n) | TIROp::WriteIo(n) | TIROp::WriteMem(n) => -i64::from(*n),
+        TIROp::ReadIo(n) | TIROp::ReadMem(n) | TIROp::Hint(n) => i64::from(*n),
+        TIROp::Add
+        | TIROp::Mul
+        | TIROp::Eq
+        | TIROp::Lt
+        | TIROp::And
+        | TIROp::Or
+        | TIROp::Xor
+        | TIROp::Pow
+        | TIROp::ExtMul => -1,
+        // push -1; mul; add -- and the shift forms push 2 first.
+        TIROp::Sub | TIROp::Shl | TIROp::Shr => return (-1, 1),
+        TIROp::Neg => return (0, 1),
+        TIROp::Assert { width, .. } => -i64::from(*width),
+        TIROp::Hash { .. } => -5,
+        TIROp::SpongeAbsorb => -10,
+        TIROp::SpongeSqueeze => 10,
+        TIROp::Reveal { field_count, .. } => return (-i64::from(*field_count), 1),
+        // Fields are padded to the hash rate before hashing.
+        TIROp::Seal { field_count, .. } => {
+            let fields = i64::from(*field_count);
+            return (-fields, (10 - fields).max(0));
+        }
+        TIROp::RamRead { width } => return (i64::from(*width) - 1, i64::from(*width)),
+        TIROp::RamWrite { width } => -(i64::from(*width) + 1),
+        TIROp::Asm { effect, .. } => i64::from(*effect),
+        _ => 0,
+    };
+    (net, net.max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(ops: Vec<TIROp>) -> Vec<(String, Vec<TIROp>)> {
+        vec![("app".to_string(), ops)]
+    }
+
+    fn func(name: &str, body: Vec<TIROp>) -> Vec<TIROp> {
+        let mut ops = vec![TIROp::FnStart(name.to_string())];
+        ops.extend(body);
+        ops.extend([TIROp::Return, TIROp::FnEnd]);
+        ops
+    }
+
+    #[test]
+    fn test_depth_follows_the_deepest_call() {
+        let mut ops = vec![TIROp::Entry("main".to_string())];
+        ops.extend(func(
+            "main",
+            vec![
+                TIROp::Push(1),
+                TIROp::Push(2),
+                TIROp::Call("helper".to_string()),
+                TIROp::Pop(1),
+            ],
+        ));
+        ops.extend(func(
+            "helper",
+            vec![
+                TIROp::Push(3),
+                TIROp::Push(4),
+                TIROp::Push(5),
+                TIROp::Pop(3),
+            ],
+        ));
+        let report = analyze(&program(ops), DepthLimits::default()).unwrap();
+        assert_eq!(report.max_op_stack, 5);
+        assert_eq!(
+            report.op_stack_path,
+            vec![
+                Frame {
+                    label: "app__main".to_string(),
+                    depth: 0
+                },
+                Frame {
+                    label: "app__helper".to_string(),
+                    depth: 2
+                },
+            ]
+        );
+        assert_eq!(report.max_call_depth, 2);
+        assert_eq!(report.call_path, vec!["app__main", "app__helper"]);
+        assert!(report.violations().is_empty());
+
+        let tight = DepthLimits {
+            op_stack: 4,
+            call_depth: 1,
+        };
+        let report = analyze(&program(report_ops()), tight).unwrap();
+        assert_eq!(report.violations().len(), 2, "{:?}", report.violations());
+    }
+
+    fn report_ops() -> Vec<TIROp> {
+        let mut ops = vec![TIROp::Entry("main".to_string())];
+        ops.extend(func(
+            "main",
+            vec![
+                TIROp::Push(1),
+                TIROp::Push(2),
+                TIROp::IfOnly {
+                    then_body: vec![TIROp::Hint(5), TIROp::Pop(5), TIROp::Return],
+                },
+                TIROp::Pop(1),
+            ],
+        ));
+        ops
+    }
+
+    #[test]
+    fn test_depth_reports_recursion_and_unbalanced_loops() {
+        let mut ops = vec![TIROp::Entry("main".to_string())];
+        ops.extend(func(
+            "main",
+            vec![
+                TIROp::Push(3),
+                TIROp::Call("loop__1".to_string()),
+                TIROp::Loop {
+                    label: "loop__1".to_string(),
+                    body: vec![TIROp::Push(0)],
+                },
+                TIROp::Call("again".to_string()),
+            ],
+        ));
+        ops.extend(func("again", vec![TIROp::Call("@app__main".to_string())]));
+        let report = analyze(&program(ops), DepthLimits::default()).unwrap();
+        assert_eq!(
+            report.recursion,
+            Some(vec![
+                "app__main".to_string(),
+                "app__again".to_string(),
+                "app__main".to_string()
+            ])
+        );
+        assert_eq!(
+            report.unbalanced_loops,
+            vec![("app__loop__1".to_string(), 1)]
+        );
+        assert_eq!(report.violations().len(), 2);
+    }
+}
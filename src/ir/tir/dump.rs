@@ -0,0 +1,109 @@
+//! Text dump of TIR, for `trident build --emit tir|tir-opt`.
+//!
+//! One op per line in its `Display` form. Function bodies, if/else
+//! branches, loop bodies and proof blocks are indented two spaces under
+//! the op that holds them, and inline assembly lists its lines, so a dump
+//! before and after a pass diffs line by line.
+
+use super::TIROp;
+
+/// Dump `ops` as indented text.
+pub fn dump_ops(ops: &[TIROp]) -> String {
+    let mut out = String::new();
+    dump(ops, 0, &mut out);
+    out
+}
+
+fn dump(ops: &[TIROp], mut depth: usize, out: &mut String) {
+    for op in ops {
+        if matches!(op, TIROp::FnEnd) {
+            depth = depth.saturating_sub(1);
+        }
+        match op {
+            TIROp::IfElse {
+                then_body,
+                else_body,
+            } => {
+                line(out, depth, "if_else");
+                line(out, depth + 1, "then");
+                dump(then_body, depth + 2, out);
+                line(out, depth + 1, "else");
+                dump(else_body, depth + 2, out);
+            }
+            TIROp::IfOnly { then_body } => {
+                line(out, depth, "if_only");
+                dump(then_body, depth + 1, out);
+            }
+            TIROp::Loop { label, body } => {
+                line(out, depth, &format!("loop {}", label));
+                dump(body, depth + 1, out);
+            }
+            TIROp::ProofBlock { program_hash, body } => {
+                line(out, depth, &format!("proof_block {}", program_hash));
+                dump(body, depth + 1, out);
+            }
+            TIROp::Asm { lines, effect } => {
+                line(out, depth, &format!("asm effect {}", effect));
+                for l in lines {
+                    line(out, depth + 1, l.trim());
+                }
+            }
+            TIROp::Reveal {
+                name,
+                tag,
+                field_count,
+            }
+            | TIROp::Seal {
+                name,
+                tag,
+                field_count,
+            } => {
+                let verb = if matches!(op, TIROp::Reveal { .. }) {
+                    "reveal"
+                } else {
+                    "seal"
+                };
+                let text = format!("{} {}({}) tag {}", verb, name, field_count, tag);
+                line(out, depth, &text);
+            }
+            _ => line(out, depth, &op.to_string()),
+        }
+        if matches!(op, TIROp::FnStart(_)) {
+            depth += 1;
+        }
+    }
+}
+
+fn line(out: &mut String, depth: usize, text: &str) {
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+    out.push_str(text);
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_nests_bodies() {
+        let ops = vec![
+            TIROp::FnStart("main".into()),
+            TIROp::Push(1),
+            TIROp::IfElse {
+                then_body: vec![TIROp::Push(2)],
+                else_body: vec![TIROp::Loop {
+                    label: "l".into(),
+                    body: vec![TIROp::Pop(1)],
+                }],
+            },
+            TIROp::Return,
+            TIROp::FnEnd,
+        ];
+        assert_eq!(
+            dump_ops(&ops),
+            "fn_start main\n  push 1\n  if_else\n    then\n      push 2\n    else\n      loop l\n        pop 1\n  return\nfn_end\n"
+        );
+    }
+}
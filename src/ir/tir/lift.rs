@@ -0,0 +1,547 @@
+//! TASM-to-TIR lifter.
+//!
+//! Converts handwritten Triton assembly back into `TIROp`s so the
+//! optimizer, the neural optimizer, and the stack verifier can work on
+//! it like compiled code. Lifting inverts `TritonLowering`: the shapes
+//! it emits — deferred `then`/`else` blocks called after `skiz`, counted
+//! loops that exit through `skiz; return` and end in `recurse`, and the
+//! multi-instruction forms of `sub`, `neg`, `shl`, and `shr` — come back
+//! as the structural and single ops they were lowered from. Everything
+//! else that has no TIR counterpart is kept as `TIROp::Asm` with its
+//! stack effect, so lowering a lifted routine reproduces it.
+//!
+//! Labels are read as the labels of one module: `__name` and `module`'s
+//! mangled prefix (`crypto_sponge__name` for `crypto.sponge`) both become
+//! the local function `name`, and calls to labels not defined here
+//! become cross-module calls. Relowered and linked as `module`, a lifted
+//! routine gets its original labels back.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::TIROp;
+use crate::field::goldilocks::MODULUS;
+
+/// Lift a TASM routine to TIR, reading its labels as those of `module`.
+pub fn lift(tasm: &str, module: &str) -> Result<Vec<TIROp>, String> {
+    let blocks = split_blocks(tasm)?;
+    let lifter = Lifter::new(&blocks, module);
+
+    let mut ops = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        if lifter.nested.contains(&i) {
+            continue;
+        }
+        match block.label {
+            Some(label) => {
+                ops.push(TIROp::FnStart(lifter.local_name(label)));
+                ops.extend(lifter.body(&block.instrs, &mut Vec::new())?);
+                ops.push(TIROp::FnEnd);
+            }
+            // The entry point the lowering emits for a program.
+            None if is_entry(&block.instrs) => {
+                let target = block.instrs[0].arg.unwrap_or_default();
+                ops.push(TIROp::Entry(lifter.local_name(target)));
+            }
+            None => ops.extend(lifter.body(&block.instrs, &mut Vec::new())?),
+        }
+    }
+    Ok(ops)
+}
+
+/// One instruction: mnemonic, operand, and the line as written.
+#[derive(Clone, Copy)]
+struct Instr<'a> {
+    line: usize,
+    op: &'a str,
+    arg: Option<&'a str>,
+    text: &'a str,
+}
+
+/// The instructions after a label, up to the next label. Code before the
+/// first label has no label.
+struct Block<'a> {
+    label: Option<&'a str>,
+    instrs: Vec<Instr<'a>>,
+}
+
+fn split_blocks(tasm: &str) -> Result<Vec<Block<'_>>, String> {
+    let mut blocks = vec![Block {
+        label: None,
+        instrs: Vec::new(),
+    }];
+    let mut seen = BTreeSet::new();
+    for (i, raw) in tasm.lines().enumerate() {
+        let text = raw.split("//").next().unwrap_or("").trim();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(label) = text.strip_suffix(':') {
+            if !seen.insert(label) {
+                return Err(format!(
+                    "line {}: label `{}` is defined twice",
+                    i + 1,
+                    label
+                ));
+            }
+            blocks.push(Block {
+                label: Some(label),
+                instrs: Vec::new(),
+            });
+            continue;
+        }
+        let mut parts = text.split_whitespace();
+        let op = parts.next().unwrap_or("");
+        let arg = parts.next();
+        if let Some(block) = blocks.last_mut() {
+            block.instrs.push(Instr {
+                line: i + 1,
+                op,
+                arg,
+                text,
+            });
+        }
+    }
+    if blocks[0].instrs.is_empty() {
+        blocks.remove(0);
+    }
+    Ok(blocks)
+}
+
+fn is_entry(instrs: &[Instr]) -> bool {
+    matches!(instrs, [call, halt] if call.op == "call" && halt.op == "halt")
+}
+
+/// Straight-line prologue of a lowered counted loop.
+const LOOP_HEAD: [&str; 7] = ["dup 0", "push 0", "eq", "skiz", "return", "push -1", "add"];
+
+/// How a block called from exactly one place is folded into its caller.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    /// Counted loop: `LOOP_HEAD`, body, `recurse`.
+    Loop,
+    /// Then-arm of an if/else: `pop 1`, body, `push 0`, `return`.
+    FlagArm,
+    /// Then-arm of an if, or else-arm of an if/else: body, `return`.
+    Arm,
+}
+
+struct Lifter<'a, 'b> {
+    blocks: &'a [Block<'b>],
+    by_label: BTreeMap<&'b str, usize>,
+    /// Shape of each block with a single call site, by block index.
+    shapes: BTreeMap<usize, Shape>,
+    /// Blocks folded into their caller.
+    nested: BTreeSet<usize>,
+    /// Mangled label prefix of the module, e.g. `crypto_sponge__`.
+    prefix: String,
+}
+
+impl<'a, 'b> Lifter<'a, 'b> {
+    fn new(blocks: &'a [Block<'b>], module: &str) -> Self {
+        let by_label: BTreeMap<&str, usize> = blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, b)| b.label.map(|l| (l, i)))
+            .collect();
+
+        let mut callers: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (i, block) in blocks.iter().enumerate() {
+            for instr in &block.instrs {
+                if let Some(&callee) = call_target(instr).and_then(|t| by_label.get(t)) {
+                    callers.entry(callee).or_default().push(i);
+                }
+            }
+        }
+        let shapes = callers
+            .into_iter()
+            .filter(|(callee, callers)| callers.as_slice() != [*callee] && callers.len() == 1)
+            .filter_map(|(i, _)| shape(&blocks[i].instrs).map(|s| (i, s)))
+            .collect();
+
+        let mut lifter = Self {
+            blocks,
+            by_label,
+            shapes,
+            nested: BTreeSet::new(),
+            prefix: format!("{}__", module.replace('.', "_")),
+        };
+        for block in blocks {
+            let mut k = 0;
+            while k < block.instrs.len() {
+                match lifter.structure_at(&block.instrs, k) {
+                    Some((callees, len)) => {
+                        lifter.nested.extend(callees);
+                        k += len;
+                    }
+                    None => k += 1,
+                }
+            }
+        }
+        lifter
+    }
+
+    /// Function name of a label defined in this routine.
+    fn local_name(&self, label: &str) -> String {
+        label
+            .strip_prefix("__")
+            .or_else(|| label.strip_prefix(self.prefix.as_str()))
+            .filter(|name| !name.is_empty())
+            .unwrap_or(label)
+            .to_string()
+    }
+
+    fn call(&self, target: &str) -> TIROp {
+        if self.by_label.contains_key(target) || target.starts_with("__") {
+            TIROp::Call(self.local_name(target))
+        } else {
+            TIROp::Call(format!("@{}", target))
+        }
+    }
+
+    /// Block called by `instr` if it has `shape`.
+    fn callee(&self, instr: Option<&Instr>, shape: Shape) -> Option<usize> {
+        let callee = *self.by_label.get(call_target(instr?)?)?;
+        (self.shapes.get(&callee) == Some(&shape)).then_some(callee)
+    }
+
+    /// Structural construct starting at `instrs[k]`: the blocks it folds
+    /// in, and the number of instructions it spans.
+    fn structure_at(&self, instrs: &[Instr], k: usize) -> Option<(Vec<usize>, usize)> {
+        let at = |n: usize| instrs.get(k + n);
+        let text = |n: usize| at(n).map(|i| i.text);
+        if text(0) == Some("push 1") && text(1) == Some("swap 1") && text(2) == Some("skiz") {
+            let then = self.callee(at(3), Shape::FlagArm);
+            let els = self.callee(at(5), Shape::Arm);
+            if let (Some(then), Some(els), Some("skiz")) = (then, els, text(4)) {
+                return Some((vec![then, els], 6));
+            }
+        }
+        if text(0) == Some("skiz") {
+            if let Some(then) = self.callee(at(1), Shape::Arm) {
+                return Some((vec![then], 2));
+            }
+        }
+        self.callee(at(0), Shape::Loop).map(|body| (vec![body], 1))
+    }
+
+    /// Lift a body. `active` holds the blocks being lifted around it, so
+    /// blocks that fold into each other are reported, not expanded forever.
+    fn body(&self, instrs: &[Instr], active: &mut Vec<usize>) -> Result<Vec<TIROp>, String> {
+        let mut ops = Vec::new();
+        let mut asm: Vec<String> = Vec::new();
+        let mut effect = 0;
+        let mut k = 0;
+        while k < instrs.len() {
+            let lifted = match self.structure_at(instrs, k) {
+                Some((callees, len)) => {
+                    let mut bodies = Vec::new();
+                    for callee in &callees {
+                        if active.contains(callee) {
+                            return Err(format!(
+                                "line {}: blocks call each other in a cycle",
+                                instrs[k].line
+                            ));
+                        }
+                        active.push(*callee);
+                        let inner = &self.blocks[*callee].instrs;
+                        let inner = match self.shapes[callee] {
+                            Shape::Loop => &inner[LOOP_HEAD.len()..inner.len() - 1],
+                            Shape::FlagArm => &inner[1..inner.len() - 2],
+                            Shape::Arm => &inner[..inner.len() - 1],
+                        };
+                        bodies.push(self.body(inner, active)?);
+                        active.pop();
+                    }
+                    let label =
+                        |i: usize| self.local_name(self.blocks[i].label.unwrap_or_default());
+                    let op = match (callees.as_slice(), bodies.as_mut_slice()) {
+                        ([_, _], [then_body, else_body]) => vec![TIROp::IfElse {
+                            then_body: std::mem::take(then_body),
+                            else_body: std::mem::take(else_body),
+                        }],
+                        ([c], [body]) if self.shapes[c] == Shape::Loop => vec![
+                            TIROp::Call(label(*c)),
+                            TIROp::Loop {
+                                label: label(*c),
+                                body: std::mem::take(body),
+                            },
+                        ],
+                        (_, [then_body]) => vec![TIROp::IfOnly {
+                            then_body: std::mem::take(then_body),
+                        }],
+                        _ => Vec::new(),
+                    };
+                    Some((op, len))
+                }
+                None => self.fused_at(instrs, k),
+            };
+            if let Some((lifted, len)) = lifted {
+                if !asm.is_empty() {
+                    ops.push(TIROp::Asm {
+                        lines: std::mem::take(&mut asm),
+                        effect,
+                    });
+                    effect = 0;
+                }
+                ops.extend(lifted);
+                k += len;
+                continue;
+            }
+            let instr = &instrs[k];
+            match self.single(instr)? {
+                Ok(op) => {
+                    if !asm.is_empty() {
+                        ops.push(TIROp::Asm {
+                            lines: std::mem::take(&mut asm),
+                            effect,
+                        });
+                        effect = 0;
+                    }
+                    ops.push(op);
+                }
+                Err(stack_effect) => {
+                    asm.push(instr.text.to_string());
+                    effect += stack_effect;
+                }
+            }
+            k += 1;
+        }
+        if !asm.is_empty() {
+            ops.push(TIROp::Asm { lines: asm, effect });
+        }
+        Ok(ops)
+    }
+
+    /// Multi-instruction forms the lowering emits for one op.
+    fn fused_at(&self, instrs: &[Instr], k: usize) -> Option<(Vec<TIROp>, usize)> {
+        let starts_with = |texts: &[&str]| {
+            instrs.len() >= k + texts.len()
+                && instrs[k..k + texts.len()]
+                    .iter()
+                    .zip(texts)
+                    .all(|(i, t)| i.text == *t)
+        };
+        let forms: [(&[&str], TIROp); 4] = [
+            (&["push 2", "pow", "div_mod", "swap 1", "pop 1"], TIROp::Shr),
+            (&["push 2", "pow", "mul"], TIROp::Shl),
+            (&["push -1", "mul", "add"], TIROp::Sub),
+            (&["push -1", "mul"], TIROp::Neg),
+        ];
+        forms
+            .into_iter()
+            .find(|(texts, _)| starts_with(texts))
+            .map(|(texts, op)| (vec![op], texts.len()))
+    }
+
+    /// The op for one instruction, or its stack effect when it has no
+    /// TIR counterpart.
+    fn single(&self, instr: &Instr) -> Result<Result<TIROp, i32>, String> {
+        let n = || -> Result<u32, String> {
+            instr
+                .arg
+                .and_then(|a| a.parse().ok())
+                .ok_or_else(|| format!("line {}: `{}` needs a count", instr.line, instr.text))
+        };
+        let op = match instr.op {
+            "push" => {
+                let value: i128 = instr.arg.and_then(|a| a.parse().ok()).ok_or_else(|| {
+                    format!("line {}: `{}`: not an integer", instr.line, instr.text)
+                })?;
+                TIROp::Push(value.rem_euclid(MODULUS as i128) as u64)
+            }
+            "pop" => TIROp::Pop(n()?),
+            "dup" => TIROp::Dup(n()?),
+            "swap" => TIROp::Swap(n()?),
+            "add" => TIROp::Add,
+            "mul" => TIROp::Mul,
+            "invert" => TIROp::Invert,
+            "eq" => TIROp::Eq,
+            "lt" => TIROp::Lt,
+            "and" => TIROp::And,
+            "or" => TIROp::Or,
+            "xor" => TIROp::Xor,
+            "pop_count" => TIROp::PopCount,
+            "split" => TIROp::Split,
+            "div_mod" => TIROp::DivMod,
+            "log_2_floor" => TIROp::Log2,
+            "pow" => TIROp::Pow,
+            "read_io" => TIROp::ReadIo(n()?),
+            "write_io" => TIROp::WriteIo(n()?),
+            "read_mem" => TIROp::ReadMem(n()?),
+            "write_mem" => TIROp::WriteMem(n()?),
+            "divine" => TIROp::Hint(n()?),
+            "assert" | "assert_vector" => {
+                let width = if instr.op == "assert" { 1 } else { 5 };
+                let error_id = match instr.text.split_once("error_id") {
+                    Some((_, id)) => Some(id.trim().parse().map_err(|_| {
+                        format!("line {}: `{}`: not an error id", instr.line, instr.text)
+                    })?),
+                    None => None,
+                };
+                TIROp::Assert { width, error_id }
+            }
+            "hash" => TIROp::Hash { width: 5 },
+            "sponge_init" => TIROp::SpongeInit,
+            "sponge_absorb" => TIROp::SpongeAbsorb,
+            "sponge_squeeze" => TIROp::SpongeSqueeze,
+            "sponge_absorb_mem" => TIROp::SpongeLoad,
+            "merkle_step" => TIROp::MerkleStep,
+            "merkle_step_mem" => TIROp::MerkleLoad,
+            "xb_mul" => TIROp::ExtMul,
+            "x_invert" => TIROp::ExtInvert,
+            "xx_dot_step" => TIROp::FoldExt,
+            "xb_dot_step" => TIROp::FoldBase,
+            "call" => self.call(instr.arg.unwrap_or_default()),
+            "return" => TIROp::Return,
+            "halt" => TIROp::Halt,
+            // No TIR counterpart: kept verbatim with their stack effect.
+            "pick" | "place" | "nop" | "addi" | "recurse" | "recurse_or_return" | "hint" => {
+                return Ok(Err(0))
+            }
+            "skiz" => return Ok(Err(-1)),
+            "xx_add" | "xx_mul" => return Ok(Err(-3)),
+            _ => {
+                return Err(format!(
+                    "line {}: unknown instruction `{}`",
+                    instr.line, instr.op
+                ))
+            }
+        };
+        Ok(Ok(op))
+    }
+}
+
+fn call_target<'a>(instr: &Instr<'a>) -> Option<&'a str> {
+    (instr.op == "call").then_some(instr.arg?)
+}
+
+/// Shape of a block, if it is one the lowering emits for a construct.
+fn shape(instrs: &[Instr]) -> Option<Shape> {
+    let texts: Vec<&str> = instrs.iter().map(|i| i.text).collect();
+    let returns = texts.iter().filter(|t| **t == "return").count();
+    if texts.starts_with(&LOOP_HEAD) && texts.last() == Some(&"recurse") && returns == 1 {
+        return Some(Shape::Loop);
+    }
+    if texts.last() != Some(&"return") || returns != 1 || texts.contains(&"recurse") {
+        return None;
+    }
+    if texts.len() >= 3 && texts[0] == "pop 1" && texts[texts.len() - 2] == "push 0" {
+        Some(Shape::FlagArm)
+    } else {
+        Some(Shape::Arm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tir::lower::create_stack_lowering;
+
+    /// Lower `ops` and drop the blank lines the lowering separates
+    /// blocks with.
+    fn lower(ops: &[TIROp]) -> String {
+        create_stack_lowering("triton")
+            .lower(ops)
+            .into_iter()
+            .filter(|l| !l.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_lift_recovers_structure() {
+        let tasm = "\
+__main:
+    read_io 1
+    push 1
+    swap 1
+    skiz
+    call __then__1
+    skiz
+    call __else__2
+    return
+__then__1:
+    pop 1
+    push 3
+    call __loop__3
+    pop 1
+    push 0
+    return
+__else__2:
+    push -1
+    mul
+    add
+    return
+__loop__3:
+    dup 0
+    push 0
+    eq
+    skiz
+    return
+    push -1
+    add
+    pick 2
+    recurse
+";
+        let ops = lift(tasm, "app").unwrap();
+        assert!(matches!(&ops[0], TIROp::FnStart(name) if name == "main"));
+        assert_eq!(ops.len(), 5, "{:?}", ops);
+        let TIROp::IfElse {
+            then_body,
+            else_body,
+        } = &ops[2]
+        else {
+            panic!("expected if/else, got {:?}", ops[2]);
+        };
+        assert!(matches!(then_body[1], TIROp::Call(ref l) if l == "loop__3"));
+        let TIROp::Loop { body, .. } = &then_body[2] else {
+            panic!("expected loop, got {:?}", then_body[2]);
+        };
+        assert!(matches!(&body[0], TIROp::Asm { lines, effect: 0 } if lines == &["pick 2"]));
+        assert!(matches!(else_body[..], [TIROp::Sub]));
+
+        // Lowering the lifted routine reproduces it.
+        let expected: Vec<&str> = tasm.lines().collect();
+        assert_eq!(lower(&ops), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_lift_reads_labels_as_module_labels() {
+        let tasm = "\
+    call std_math__fib
+    halt
+std_math__fib:
+    call __helper
+    call std_io__read
+    call __helper
+    return
+__helper:
+    push 0
+    return
+";
+        let ops = lift(tasm, "std.math").unwrap();
+        assert!(matches!(&ops[0], TIROp::Entry(name) if name == "fib"));
+        assert!(matches!(&ops[1], TIROp::FnStart(name) if name == "fib"));
+        assert!(matches!(&ops[2], TIROp::Call(name) if name == "helper"));
+        assert!(matches!(&ops[3], TIROp::Call(name) if name == "@std_io__read"));
+        // Called twice, so `__helper` stays a function.
+        assert!(matches!(&ops[7], TIROp::FnStart(name) if name == "helper"));
+    }
+
+    #[test]
+    fn test_lift_round_trips_compiled_code() {
+        let source = "program demo\n\nfn step(a: Field, b: Field) -> Field {\n    a + b * 2\n}\n\nfn main() {\n    let n: Field = pub_read()\n    let mut acc: Field = 0\n    for i in 0..8 bounded 8 {\n        acc = step(acc, n)\n    }\n    if acc == 0 {\n        pub_write(acc)\n    } else {\n        assert(n == 1)\n    }\n}\n";
+        let tasm = crate::compile(source, "demo.tri").unwrap();
+        let ops = lift(&tasm, "demo").unwrap();
+        assert!(ops.iter().any(|op| matches!(op, TIROp::IfElse { .. })));
+        let compiled: Vec<&str> = tasm.lines().filter(|l| !l.trim().is_empty()).collect();
+        assert_eq!(lower(&ops), compiled.join("\n"));
+    }
+
+    #[test]
+    fn test_lift_rejects_unknown_instructions() {
+        let err = lift("__f:\n    frobnicate\n    return\n", "m").unwrap_err();
+        assert_eq!(err, "line 2: unknown instruction `frobnicate`");
+        assert!(lift("__f:\n    return\n__f:\n    return\n", "m").is_err());
+    }
+}
@@ -1,3 +1,9 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::syntax::intern::{Interner, Symbol};
+
+use super::data::DATA_LABEL;
+
 /// Per-module TASM output ready for linking.
 #[derive(Clone, Debug)]
 pub(crate) struct ModuleTasm {
@@ -7,39 +13,54 @@ pub(crate) struct ModuleTasm {
     pub(crate) is_program: bool,
     /// Raw TASM output from the emitter.
     pub(crate) tasm: String,
+    /// Lowered stores of the module's RAM-resident `include_data`
+    /// tables, run once before the entry.
+    pub(crate) data: String,
 }
 
 /// Link multiple module TASM outputs into a single program.
 /// Performs dead code elimination: only includes functions reachable
-/// from the program entry point.
-pub(crate) fn link(modules: Vec<ModuleTasm>) -> String {
-    // First, mangle all modules and collect the full TASM.
-    let mut all_lines = Vec::new();
-
+/// from the program entry point. Size-generic instances with identical
+/// code are folded into one; the `(removed, kept)` labels are returned
+/// with the program.
+pub(crate) fn link(modules: Vec<ModuleTasm>) -> (String, Vec<(String, String)>) {
     // Find program entry
     let entry_label = if let Some(prog) = modules.iter().find(|m| m.is_program) {
-        format!("{}main", mangle_module(&prog.module_name))
+        fn_label(&prog.module_name, "main")
     } else {
         // No program module — emit a halt-only program.
-        return "    halt\n// error: no program module found".to_string();
+        return (
+            "    halt\n// error: no program module found".to_string(),
+            Vec::new(),
+        );
     };
+    super::mono::dedup_instances(&link_entry(modules, &entry_label))
+}
+
+/// Linked label of function `fn_name` in module `module_name`.
+pub(crate) fn fn_label(module_name: &str, fn_name: &str) -> String {
+    format!("{}{}", mangle_module(module_name), fn_name)
+}
 
+/// Link modules into a program that calls `entry_label` and halts.
+/// `link` uses the program's `main`; `trident test` uses each test.
+pub(crate) fn link_entry(modules: Vec<ModuleTasm>, entry_label: &str) -> String {
     // Mangle all modules
-    for module in &modules {
-        let prefix = mangle_module(&module.module_name);
-        let mangled = mangle_labels(&module.tasm, &prefix, module.is_program);
-        for line in mangled.lines() {
-            all_lines.push(line.to_string());
-        }
-    }
+    let mangled: Vec<String> = modules
+        .iter()
+        .map(|m| mangle_labels(&m.tasm, &mangle_module(&m.module_name), m.is_program))
+        .collect();
+    let all_lines: Vec<&str> = mangled.iter().flat_map(|m| m.lines()).collect();
 
-    // Build a map: label -> (start_line, end_line) and label -> [called labels]
-    let mut functions: Vec<(String, usize, usize)> = Vec::new();
+    // Function table: label -> (start_line, end_line). Labels are interned
+    // so the call graph and reachability work on dense symbol indices.
+    let mut labels = Interner::new();
+    let mut functions: Vec<(Symbol, usize, usize)> = Vec::new();
     let mut i = 0;
     while i < all_lines.len() {
         let trimmed = all_lines[i].trim();
         if trimmed.ends_with(':') && !trimmed.is_empty() {
-            let label = trimmed.trim_end_matches(':').to_string();
+            let label = labels.intern(trimmed.trim_end_matches(':'));
             let start = i;
             i += 1;
             // Scan until next label or end
@@ -56,88 +77,110 @@ pub(crate) fn link(modules: Vec<ModuleTasm>) -> String {
         }
     }
 
-    // Find call targets for each function
-    use std::collections::{BTreeMap, BTreeSet, VecDeque};
-    let mut call_graph: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    // Find call targets for each function, resolving each distinct
+    // target once. Unresolved targets (no such label) are dropped.
+    let mut resolved: BTreeMap<&str, Option<Symbol>> = BTreeMap::new();
+    let mut call_graph: Vec<Vec<Symbol>> = vec![Vec::new(); labels.len()];
     for (label, start, end) in &functions {
         let mut calls = Vec::new();
         for line in &all_lines[*start..*end] {
             let t = line.trim();
             if let Some(target) = t.strip_prefix("call ") {
-                calls.push(target.to_string());
+                let sym = *resolved
+                    .entry(target)
+                    .or_insert_with(|| resolve_target(target, &labels));
+                calls.extend(sym);
             } else if t == "recurse" {
-                calls.push(label.clone());
+                calls.push(*label);
             }
         }
-        call_graph.insert(label.clone(), calls);
+        call_graph[label.index()] = calls;
     }
 
-    // Build a suffix index for fuzzy label matching.
-    // Cross-module calls may carry the caller's prefix (e.g. card__plumb__fn)
-    // while the label is defined as plumb__fn. Build suffix → label map.
-    let all_labels: BTreeSet<String> = functions.iter().map(|(l, _, _)| l.clone()).collect();
-    let resolve_target = |target: &str| -> String {
-        if all_labels.contains(target) {
-            return target.to_string();
-        }
-        // Try stripping successive prefixes (before first __).
-        // Only accept a suffix match if it is unambiguous (exactly one label
-        // ends with that suffix). Multiple matches indicate a naming conflict
-        // and the original target is returned as-is.
-        let mut t = target;
-        while let Some(pos) = t.find("__") {
-            let suffix = &t[pos + 2..];
-            if !suffix.is_empty() {
-                if all_labels.contains(suffix) {
-                    return suffix.to_string();
-                }
-                // Check for suffix match: labels ending with __suffix
-                let candidates: Vec<&String> =
-                    all_labels.iter().filter(|l| l.ends_with(suffix)).collect();
-                if candidates.len() == 1 {
-                    return candidates[0].clone();
-                }
-            }
-            t = suffix;
-        }
-        target.to_string()
-    };
-
     // BFS from entry label to find all reachable functions
-    let mut reachable: BTreeSet<String> = BTreeSet::new();
-    let mut queue: VecDeque<String> = VecDeque::new();
-    queue.push_back(entry_label.clone());
+    let mut reachable = vec![false; labels.len()];
+    let mut queue: VecDeque<Symbol> = labels.get(entry_label).into_iter().collect();
     while let Some(label) = queue.pop_front() {
-        if reachable.contains(&label) {
+        if reachable[label.index()] {
             continue;
         }
-        reachable.insert(label.clone());
-        if let Some(calls) = call_graph.get(&label) {
-            for target in calls {
-                let resolved = resolve_target(target);
-                if !reachable.contains(&resolved) {
-                    queue.push_back(resolved);
-                }
+        reachable[label.index()] = true;
+        for target in &call_graph[label.index()] {
+            if !reachable[target.index()] {
+                queue.push_back(*target);
             }
         }
     }
 
-    // Emit only reachable functions
+    // Tables stored in RAM are written by one routine, called first.
+    let stores: Vec<&str> = modules
+        .iter()
+        .flat_map(|m| m.data.lines())
+        .filter(|l| !l.trim().is_empty())
+        .collect();
     let mut output = Vec::new();
-    output.push(format!("    call {}", entry_label));
-    output.push("    halt".to_string());
+    if !stores.is_empty() {
+        output.push(format!("    call {}", DATA_LABEL));
+    }
+    output.extend([format!("    call {}", entry_label), "    halt".to_string()]);
 
+    // Emit only reachable functions. A label defined more than once with
+    // the same body (a lifted handwritten routine relowered next to the
+    // compiled one) is emitted once.
+    let mut emitted: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
     for (label, start, end) in &functions {
-        if reachable.contains(label) {
-            for line in &all_lines[*start..*end] {
-                output.push(line.clone());
-            }
+        if !reachable[label.index()] {
+            continue;
         }
+        let body: Vec<&str> = all_lines[*start..*end]
+            .iter()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+        if emitted.get(&label.index()) == Some(&body) {
+            continue;
+        }
+        emitted.insert(label.index(), body);
+        output.extend(all_lines[*start..*end].iter().map(|l| l.to_string()));
+    }
+    if !stores.is_empty() {
+        output.push(String::new());
+        output.push(format!("{}:", DATA_LABEL));
+        output.extend(stores.iter().map(|l| l.to_string()));
+        output.push("    return".to_string());
     }
 
     output.join("\n")
 }
 
+/// Resolve a call target to a defined label.
+///
+/// Cross-module calls may carry the caller's prefix (e.g. card__plumb__fn)
+/// while the label is defined as plumb__fn. Successive prefixes (before
+/// each `__`) are stripped; a suffix is accepted when it is a label, or
+/// when exactly one label ends with it. Multiple matches indicate a
+/// naming conflict and leave the target unresolved.
+pub(crate) fn resolve_target(target: &str, labels: &Interner) -> Option<Symbol> {
+    if let Some(sym) = labels.get(target) {
+        return Some(sym);
+    }
+    let mut t = target;
+    while let Some(pos) = t.find("__") {
+        let suffix = &t[pos + 2..];
+        if !suffix.is_empty() {
+            if let Some(sym) = labels.get(suffix) {
+                return Some(sym);
+            }
+            let mut candidates = labels.iter().filter(|(_, l)| l.ends_with(suffix));
+            if let (Some((sym, _)), None) = (candidates.next(), candidates.next()) {
+                return Some(sym);
+            }
+        }
+        t = suffix;
+    }
+    None
+}
+
 /// Mangle all labels in a TASM block with a module prefix.
 /// `__foo:` becomes `modname__foo:`
 /// `call __foo` becomes `call modname__foo`
@@ -207,13 +250,30 @@ mod tests {
             module_name: "test".to_string(),
             is_program: true,
             tasm: "    call __main\n    halt\n\n__main:\n    read_io 1\n    return\n".to_string(),
+            data: String::new(),
         }];
-        let linked = link(modules);
+        let (linked, _) = link(modules);
         assert!(linked.contains("call test__main"));
         assert!(linked.contains("halt"));
         assert!(linked.contains("test__main:"));
     }
 
+    #[test]
+    fn test_link_stores_data_before_entry() {
+        let modules = vec![ModuleTasm {
+            module_name: "test".to_string(),
+            is_program: true,
+            tasm: "    call __main\n    halt\n\n__main:\n    push 7\n    read_mem 1\n    return\n"
+                .to_string(),
+            data: "    push 2\n    push 1\n    push 7\n    write_mem 2\n    pop 1".to_string(),
+        }];
+        let linked = link_entry(modules, "test__main");
+        assert!(linked.starts_with("    call __data\n    call test__main\n    halt"));
+        assert!(linked.ends_with(
+            "__data:\n    push 2\n    push 1\n    push 7\n    write_mem 2\n    pop 1\n    return"
+        ));
+    }
+
     #[test]
     fn test_multi_module_link() {
         let modules = vec![
@@ -222,15 +282,17 @@ mod tests {
                 is_program: false,
                 tasm: "__verify:\n    read_io 1\n    return\n__unused:\n    push 0\n    return\n"
                     .to_string(),
+                data: String::new(),
             },
             ModuleTasm {
                 module_name: "main_prog".to_string(),
                 is_program: true,
                 tasm: "    call __main\n    halt\n\n__main:\n    call merkle__verify\n    return\n"
                     .to_string(),
+                data: String::new(),
             },
         ];
-        let linked = link(modules);
+        let (linked, _) = link(modules);
         // Entry point should use the program module's main
         assert!(linked.contains("call main_prog__main"));
         assert!(linked.contains("halt"));
@@ -240,4 +302,32 @@ mod tests {
         assert!(!linked.contains("merkle__unused:"));
         assert!(linked.contains("main_prog__main:"));
     }
+
+    #[test]
+    fn test_link_keeps_identical_definitions_once() {
+        let modules = vec![
+            ModuleTasm {
+                module_name: "util".to_string(),
+                is_program: false,
+                tasm: "__double:\n    dup 0\n    add\n    return\n".to_string(),
+                data: String::new(),
+            },
+            // The same routine, lifted from handwritten TASM and relowered.
+            ModuleTasm {
+                module_name: "util".to_string(),
+                is_program: false,
+                tasm: "__double:\n    dup 0\n    add\n    return\n    \n".to_string(),
+                data: String::new(),
+            },
+            ModuleTasm {
+                module_name: "app".to_string(),
+                is_program: true,
+                tasm: "    call __main\n    halt\n\n__main:\n    call util__double\n    return\n"
+                    .to_string(),
+                data: String::new(),
+            },
+        ];
+        let (linked, _) = link(modules);
+        assert_eq!(linked.matches("util__double:").count(), 1, "{}", linked);
+    }
 }
@@ -143,6 +143,37 @@ fn test_lower_crypto_ops() {
     );
 }
 
+#[test]
+fn test_lower_ram_blocks() {
+    // write_mem takes its pointer in st0 and stores st1 at it; read_mem
+    // reads downward from its pointer.
+    let ops = vec![
+        TIROp::RamWrite { width: 1 },
+        TIROp::RamWrite { width: 3 },
+        TIROp::RamRead { width: 1 },
+        TIROp::RamRead { width: 3 },
+    ];
+    let lowering = TritonLowering::new();
+    let out = lowering.lower(&ops);
+    assert_eq!(
+        out,
+        vec![
+            "    swap 1",
+            "    write_mem 1",
+            "    pop 1",
+            "    pick 3",
+            "    write_mem 3",
+            "    pop 1",
+            "    read_mem 1",
+            "    pop 1",
+            "    push 2",
+            "    add",
+            "    read_mem 3",
+            "    pop 1",
+        ]
+    );
+}
+
 #[test]
 fn test_lower_already_prefixed_labels() {
     let ops = vec![
@@ -214,3 +245,26 @@ fn test_nested_if_else_deferred() {
         label_count
     );
 }
+
+#[test]
+fn test_uncovered_ops_without_extension_field() {
+    let ops = vec![
+        TIROp::FnStart("main".into()),
+        TIROp::SpongeInit,
+        TIROp::Loop {
+            label: "l".into(),
+            body: vec![TIROp::FoldExt, TIROp::ExtMul, TIROp::FoldExt],
+        },
+        TIROp::FnEnd,
+    ];
+    let triton = crate::target::TerrainConfig::triton();
+    assert!(uncovered_ops(&ops, &triton).is_empty());
+
+    let mut no_xfield = triton;
+    no_xfield.xfield_width = 0;
+    let uncovered = uncovered_ops(&ops, &no_xfield);
+    assert_eq!(
+        uncovered.into_iter().collect::<Vec<_>>(),
+        vec![("ext_mul".to_string(), 1), ("fold_ext".to_string(), 2)]
+    );
+}
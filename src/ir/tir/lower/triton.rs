@@ -121,8 +121,17 @@ impl TritonLowering {
             TIROp::MerkleLoad => out.push("    merkle_step_mem".to_string()),
 
             // ── Assertions ──
-            TIROp::Assert(1) => out.push("    assert".to_string()),
-            TIROp::Assert(_) => out.push("    assert_vector".to_string()),
+            TIROp::Assert { width, error_id } => {
+                let instr = if *width == 1 {
+                    "assert"
+                } else {
+                    "assert_vector"
+                };
+                match error_id {
+                    Some(id) => out.push(format!("    {} error_id {}", instr, id)),
+                    None => out.push(format!("    {}", instr)),
+                }
+            }
 
             // ── Abstract operations (Triton lowering) ──
             TIROp::Reveal {
@@ -148,15 +157,25 @@ impl TritonLowering {
                 out.push("    write_io 5".to_string());
             }
             TIROp::RamRead { width } => {
-                // Triton: read_mem + pop address.
+                // Triton: read_mem reads downward from its pointer, so a
+                // block at addr..addr+width-1 is read from its last word.
+                if *width > 1 {
+                    out.push(format!("    push {}", width - 1));
+                    out.push("    add".to_string());
+                }
                 out.push(format!("    read_mem {}", width));
                 out.push("    pop 1".to_string());
             }
             TIROp::RamWrite { width } => {
                 // Args are pushed left-to-right: addr then value(s).
                 // Stack: val_N..val_1 | addr (addr at position width).
-                // write_mem needs addr at st0: swap it up.
-                out.push(format!("    swap {}", width));
+                // write_mem needs addr at st0: pick it up, keeping the
+                // values in order.
+                if *width == 1 {
+                    out.push("    swap 1".to_string());
+                } else {
+                    out.push(format!("    pick {}", width));
+                }
                 out.push(format!("    write_mem {}", width));
                 out.push("    pop 1".to_string());
             }
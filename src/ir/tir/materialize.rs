@@ -0,0 +1,277 @@
+//! Link-time constant materialization.
+//!
+//! Wide constants (digests, domain separators, parameters shared between
+//! modules) compile to a run of `push` instructions at every use. When
+//! the same run recurs often enough, the linked program can store it in
+//! RAM once, in a prologue that runs before `main`, and read it back with
+//! `read_mem` at each use. Whether that pays is the cost model's call: a
+//! constant is materialized only if the program's table profile gets
+//! cheaper.
+
+use std::collections::BTreeMap;
+
+use crate::cost::scorer::{profile_tasm, TableProfile};
+use crate::cost::MaterializedConstant;
+use crate::field::goldilocks::MODULUS;
+
+/// Widest constant a single `read_mem` moves.
+const MAX_WIDTH: usize = 5;
+
+/// One candidate constant: the operands of its pushes and the first line
+/// of each use.
+struct Candidate {
+    operands: Vec<String>,
+    uses: Vec<usize>,
+}
+
+impl Candidate {
+    /// Table rows the program gains (positive) or loses (negative) by
+    /// materializing this constant.
+    fn delta(&self) -> [i64; 6] {
+        let width = self.operands.len();
+        let pushes: Vec<String> = self
+            .operands
+            .iter()
+            .map(|o| format!("push {}", o))
+            .collect();
+        let pushes = profile(&pushes);
+        let read = profile(&read_lines(0, width));
+        let write = profile(&write_lines(0, &self.operands));
+        let uses = self.uses.len() as i64;
+        std::array::from_fn(|t| uses * (read[t] - pushes[t]) + write[t])
+    }
+}
+
+/// Materialize the constants of a linked program that make it cheaper,
+/// storing them in RAM from `ram_base` up. Returns the rewritten program
+/// and the constants, in the order the prologue stores them.
+///
+/// Programs that do not start with the linker's `call main; halt` are
+/// returned unchanged.
+pub(crate) fn materialize_constants(
+    tasm: &str,
+    ram_base: u64,
+) -> (String, Vec<MaterializedConstant>) {
+    let lines: Vec<&str> = tasm.lines().collect();
+    let starts_with_entry = lines
+        .iter()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty() && !l.starts_with("//"))
+        .is_some_and(|l| l.starts_with("call "));
+    if !starts_with_entry {
+        return (tasm.to_string(), Vec::new());
+    }
+
+    // Runs of pushes, split into chunks of at most MAX_WIDTH. The push
+    // right after `skiz` is conditional and stays out of any chunk.
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut by_operands: BTreeMap<Vec<String>, usize> = BTreeMap::new();
+    let mut prev_op = "";
+    let mut i = 0;
+    while i < lines.len() {
+        let mut run: Vec<String> = Vec::new();
+        while let Some(operand) = lines.get(i + run.len()).and_then(|l| push_operand(l)) {
+            run.push(operand.to_string());
+        }
+        if run.is_empty() {
+            let t = lines[i].trim();
+            if !t.is_empty() && !t.starts_with("//") && !t.ends_with(':') {
+                prev_op = t.split_whitespace().next().unwrap_or("");
+            }
+            i += 1;
+            continue;
+        }
+        let skip = usize::from(prev_op == "skiz");
+        for (k, chunk) in run[skip..].chunks(MAX_WIDTH).enumerate() {
+            if chunk.len() < 2 {
+                continue;
+            }
+            let line = i + skip + k * MAX_WIDTH;
+            let c = *by_operands.entry(chunk.to_vec()).or_insert_with(|| {
+                candidates.push(Candidate {
+                    operands: chunk.to_vec(),
+                    uses: Vec::new(),
+                });
+                candidates.len() - 1
+            });
+            candidates[c].uses.push(line);
+        }
+        prev_op = "push";
+        i += run.len();
+    }
+
+    // Greedy selection, most promising first: keep a constant when the
+    // whole program's profile improves with it.
+    let mut profile = profile_tasm(&lines).heights.map(|h| h as i64);
+    let mut deltas: Vec<(usize, [i64; 6])> = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.uses.len() > 1)
+        .map(|(i, c)| (i, c.delta()))
+        .collect();
+    deltas.sort_by_key(|(i, d)| (d[0], *i));
+    let mut chosen: Vec<(usize, [i64; 6])> = Vec::new();
+    for (c, delta) in deltas {
+        let after: [i64; 6] = std::array::from_fn(|t| profile[t] + delta[t]);
+        if cost_key(&after) < cost_key(&profile) {
+            profile = after;
+            chosen.push((c, delta));
+        }
+    }
+    if chosen.is_empty() {
+        return (tasm.to_string(), Vec::new());
+    }
+
+    // Initialization order is the order of first use in the program.
+    chosen.sort_by_key(|(c, _)| candidates[*c].uses[0]);
+    let mut prologue: Vec<String> = Vec::new();
+    let mut reads: BTreeMap<usize, (u64, usize)> = BTreeMap::new();
+    let mut constants = Vec::new();
+    let mut address = ram_base;
+    for (c, delta) in &chosen {
+        let candidate = &candidates[*c];
+        let width = candidate.operands.len();
+        prologue.extend(write_lines(address, &candidate.operands));
+        for &line in &candidate.uses {
+            reads.insert(line, (address, width));
+        }
+        constants.push(MaterializedConstant {
+            values: candidate.operands.iter().map(|o| field_value(o)).collect(),
+            address,
+            uses: candidate.uses.len(),
+            saved: delta.iter().map(|d| -d).collect(),
+        });
+        address += width as u64;
+    }
+
+    let mut out: Vec<String> = prologue.iter().map(|l| format!("    {}", l)).collect();
+    let mut i = 0;
+    while i < lines.len() {
+        if let Some(&(address, width)) = reads.get(&i) {
+            out.extend(
+                read_lines(address, width)
+                    .iter()
+                    .map(|l| format!("    {}", l)),
+            );
+            i += width;
+        } else {
+            out.push(lines[i].to_string());
+            i += 1;
+        }
+    }
+    (out.join("\n"), constants)
+}
+
+/// The operand of a `push` line.
+fn push_operand(line: &str) -> Option<&str> {
+    let operand = line.trim().strip_prefix("push ")?.trim();
+    (!operand.is_empty() && !operand.contains(char::is_whitespace)).then_some(operand)
+}
+
+/// Store `operands` at `address..address + width`, leaving the stack
+/// unchanged.
+fn write_lines(address: u64, operands: &[String]) -> Vec<String> {
+    let mut lines: Vec<String> = operands.iter().map(|o| format!("push {}", o)).collect();
+    lines.push(format!("push {}", address));
+    lines.push(format!("write_mem {}", operands.len()));
+    lines.push("pop 1".to_string());
+    lines
+}
+
+/// Push the `width` elements stored at `address`, as the original pushes
+/// would.
+fn read_lines(address: u64, width: usize) -> Vec<String> {
+    vec![
+        format!("push {}", address + width as u64 - 1),
+        format!("read_mem {}", width),
+        "pop 1".to_string(),
+    ]
+}
+
+pub(super) fn profile(lines: &[String]) -> [i64; 6] {
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+    profile_tasm(&lines).heights.map(|h| h as i64)
+}
+
+/// Proving cost first (padded height), then the tallest table.
+pub(super) fn cost_key(heights: &[i64; 6]) -> (u64, u64) {
+    let profile = TableProfile {
+        heights: heights.map(|h| h.max(0) as u64),
+    };
+    (profile.padded_height(), profile.max_height())
+}
+
+fn field_value(operand: &str) -> u64 {
+    operand
+        .parse::<i128>()
+        .map(|v| v.rem_euclid(MODULUS as i128) as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::interp::Interpreter;
+    use crate::runtime::ProgramInput;
+
+    const BASE: u64 = 3 << 28;
+
+    /// A program that pushes the same digest `uses` times and writes each
+    /// copy to standard output.
+    fn digest_program(uses: usize) -> String {
+        let mut lines = vec!["    call main".to_string(), "    halt".to_string()];
+        lines.push("main:".to_string());
+        for _ in 0..uses {
+            for v in ["11", "-2", "33", "44", "55"] {
+                lines.push(format!("    push {}", v));
+            }
+            lines.push("    write_io 5".to_string());
+        }
+        lines.push("    return".to_string());
+        lines.join("\n")
+    }
+
+    fn output(tasm: &str) -> Vec<u64> {
+        Interpreter::new()
+            .run_tasm(tasm, &ProgramInput::default())
+            .unwrap()
+            .output
+    }
+
+    #[test]
+    fn test_materializes_when_cheaper() {
+        let tasm = digest_program(40);
+        let (out, constants) = materialize_constants(&tasm, BASE);
+        assert_eq!(constants.len(), 1);
+        let c = &constants[0];
+        assert_eq!(c.values, vec![11, MODULUS - 2, 33, 44, 55]);
+        assert_eq!((c.address, c.uses), (BASE, 40));
+        assert!(c.saved[0] > 0);
+        assert_eq!(output(&out), output(&tasm));
+        let before = profile_tasm(&tasm.lines().collect::<Vec<_>>());
+        let after = profile_tasm(&out.lines().collect::<Vec<_>>());
+        assert!(after.max_height() < before.max_height());
+        assert_eq!(
+            before.heights[0] as i64 - after.heights[0] as i64,
+            c.saved[0]
+        );
+    }
+
+    #[test]
+    fn test_keeps_program_when_not_cheaper() {
+        let tasm = digest_program(3);
+        let (out, constants) = materialize_constants(&tasm, BASE);
+        assert!(constants.is_empty());
+        assert_eq!(out, tasm);
+    }
+
+    #[test]
+    fn test_skiz_guarded_push_stays() {
+        let mut tasm = digest_program(40);
+        tasm = tasm.replacen("main:\n", "main:\n    push 1\n    skiz\n", 1);
+        let (out, constants) = materialize_constants(&tasm, BASE);
+        assert_eq!(constants[0].uses, 39);
+        assert!(out.contains("    skiz\n    push 11\n"));
+        assert_eq!(output(&out), output(&tasm));
+    }
+}
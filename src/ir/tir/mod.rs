@@ -5,9 +5,14 @@
 //! produces target assembly text.
 
 pub mod builder;
-pub mod encode;
+pub(crate) mod data;
+pub mod depth;
+pub mod dump;
+pub mod lift;
 pub(crate) mod linker;
 pub mod lower;
+pub(crate) mod materialize;
+pub mod mono;
 pub mod neural;
 pub(crate) mod optimize;
 pub mod stack;
@@ -126,8 +131,13 @@ pub enum TIROp {
     WriteMem(u32),
 
     // ── Assertions (1) ──
-    /// Assert `n` elements. Assert(1) = single, Assert(5) = vector.
-    Assert(u32),
+    /// Assert `width` elements: 1 = single, 5 = vector. A failing
+    /// assertion reports `error_id`, the code of its message in the
+    /// program's error table.
+    Assert {
+        width: u32,
+        error_id: Option<u64>,
+    },
 
     // ── Hash (1) ──
     /// Cryptographic hash. Width is metadata for optimization;
@@ -211,6 +221,135 @@ pub enum TIROp {
     },
 }
 
+impl TIROp {
+    /// Tier of the operation, as grouped above: 0 structure, 1 universal,
+    /// 2 provable, 3 recursion.
+    pub fn tier(&self) -> u8 {
+        match self {
+            TIROp::Call(_)
+            | TIROp::Return
+            | TIROp::Halt
+            | TIROp::IfElse { .. }
+            | TIROp::IfOnly { .. }
+            | TIROp::Loop { .. }
+            | TIROp::FnStart(_)
+            | TIROp::FnEnd
+            | TIROp::Entry(_)
+            | TIROp::Comment(_)
+            | TIROp::Asm { .. } => 0,
+            TIROp::Hint(_)
+            | TIROp::SpongeInit
+            | TIROp::SpongeAbsorb
+            | TIROp::SpongeSqueeze
+            | TIROp::SpongeLoad
+            | TIROp::MerkleStep
+            | TIROp::MerkleLoad => 2,
+            TIROp::ExtMul
+            | TIROp::ExtInvert
+            | TIROp::FoldExt
+            | TIROp::FoldBase
+            | TIROp::ProofBlock { .. } => 3,
+            _ => 1,
+        }
+    }
+
+    /// One instance of every variant, in declaration order, with small
+    /// placeholder operands.
+    pub fn samples() -> Vec<TIROp> {
+        vec![
+            TIROp::Call("f".into()),
+            TIROp::Return,
+            TIROp::Halt,
+            TIROp::IfElse {
+                then_body: vec![TIROp::Push(1)],
+                else_body: vec![TIROp::Push(0)],
+            },
+            TIROp::IfOnly {
+                then_body: vec![TIROp::Push(1)],
+            },
+            TIROp::Loop {
+                label: "l".into(),
+                body: vec![TIROp::Push(1)],
+            },
+            TIROp::FnStart("f".into()),
+            TIROp::FnEnd,
+            TIROp::Entry("main".into()),
+            TIROp::Comment("c".into()),
+            TIROp::Asm {
+                lines: vec!["nop".into()],
+                effect: 0,
+            },
+            TIROp::Push(1),
+            TIROp::Pop(1),
+            TIROp::Dup(0),
+            TIROp::Swap(1),
+            TIROp::Add,
+            TIROp::Sub,
+            TIROp::Mul,
+            TIROp::Neg,
+            TIROp::Invert,
+            TIROp::Eq,
+            TIROp::Lt,
+            TIROp::And,
+            TIROp::Or,
+            TIROp::Xor,
+            TIROp::PopCount,
+            TIROp::Split,
+            TIROp::DivMod,
+            TIROp::Shl,
+            TIROp::Shr,
+            TIROp::Log2,
+            TIROp::Pow,
+            TIROp::ReadIo(1),
+            TIROp::WriteIo(1),
+            TIROp::ReadMem(1),
+            TIROp::WriteMem(1),
+            TIROp::Assert {
+                width: 1,
+                error_id: None,
+            },
+            TIROp::Hash { width: 10 },
+            TIROp::Reveal {
+                name: "e".into(),
+                tag: 0,
+                field_count: 1,
+            },
+            TIROp::Seal {
+                name: "e".into(),
+                tag: 0,
+                field_count: 1,
+            },
+            TIROp::RamRead { width: 1 },
+            TIROp::RamWrite { width: 1 },
+            TIROp::Hint(1),
+            TIROp::SpongeInit,
+            TIROp::SpongeAbsorb,
+            TIROp::SpongeSqueeze,
+            TIROp::SpongeLoad,
+            TIROp::MerkleStep,
+            TIROp::MerkleLoad,
+            TIROp::ExtMul,
+            TIROp::ExtInvert,
+            TIROp::FoldExt,
+            TIROp::FoldBase,
+            TIROp::ProofBlock {
+                program_hash: "h".into(),
+                body: vec![TIROp::Push(1)],
+            },
+        ]
+    }
+
+    /// Name of the variant, e.g. `ExtMul`.
+    pub fn variant_name(&self) -> String {
+        let debug = format!("{:?}", self);
+        debug
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
 // ─── Display ──────────────────────────────────────────────────────
 
 impl fmt::Display for TIROp {
@@ -256,7 +395,13 @@ impl fmt::Display for TIROp {
             TIROp::SpongeLoad => write!(f, "sponge_load"),
             TIROp::MerkleStep => write!(f, "merkle_step"),
             TIROp::MerkleLoad => write!(f, "merkle_load"),
-            TIROp::Assert(n) => write!(f, "assert {}", n),
+            TIROp::Assert { width, error_id } => {
+                write!(f, "assert {}", width)?;
+                if let Some(id) = error_id {
+                    write!(f, " error_id {}", id)?;
+                }
+                Ok(())
+            }
             TIROp::Reveal {
                 name, field_count, ..
             } => write!(f, "reveal {}({})", name, field_count),
@@ -342,6 +487,14 @@ mod tests {
         assert_eq!(ops.len(), cloned.len());
     }
 
+    #[test]
+    fn test_samples_cover_every_variant() {
+        let names: std::collections::BTreeSet<String> =
+            TIROp::samples().iter().map(TIROp::variant_name).collect();
+        assert_eq!(names.len(), 54);
+        assert!(names.contains("ExtMul") && names.contains("ProofBlock"));
+    }
+
     #[test]
     fn test_irop_all_variants_construct() {
         // Verify every variant can be constructed without panic
@@ -383,8 +536,14 @@ mod tests {
             TIROp::SpongeLoad,
             TIROp::MerkleStep,
             TIROp::MerkleLoad,
-            TIROp::Assert(1),
-            TIROp::Assert(5),
+            TIROp::Assert {
+                width: 1,
+                error_id: None,
+            },
+            TIROp::Assert {
+                width: 5,
+                error_id: None,
+            },
             TIROp::Reveal {
                 name: "Transfer".into(),
                 tag: 0,
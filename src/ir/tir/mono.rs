@@ -0,0 +1,335 @@
+//! Monomorphized instances in emitted TASM.
+//!
+//! Every size-generic function is emitted once per distinct size
+//! combination, under a label like `merkle__verify__N3` (`__N` followed by
+//! the sizes joined with `_`). Each instance is a separate copy of the
+//! body, so a function called at many sizes multiplies code size. This
+//! pass finds the instances in linked TASM, counts the instructions each
+//! one contributes (its own section plus the `then__1`-style branch and
+//! loop blocks the lowering emits right after it), folds instances whose
+//! code is identical into one, and reports the largest contributors.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::diagnostic::Diagnostic;
+use crate::span::Span;
+
+/// Instances a program may emit unless `[build] max_instances` says
+/// otherwise.
+pub const DEFAULT_MAX_INSTANCES: usize = 256;
+
+/// One emitted instance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstanceCost {
+    /// Linked label: `merkle__verify__N3`.
+    pub label: String,
+    /// Label of the generic function: `merkle__verify`.
+    pub generic: String,
+    /// Instructions in the instance and its blocks.
+    pub instructions: usize,
+}
+
+/// Instances of a linked program, and those folded into an identical one.
+#[derive(Clone, Debug, Default)]
+pub struct MonoReport {
+    pub instances: Vec<InstanceCost>,
+    /// `(removed, kept)` labels of instances whose code was identical.
+    pub shared: Vec<(String, String)>,
+}
+
+/// Split an instance label into the generic label and its sizes:
+/// `m__sum__N3_4` -> `("m__sum", "3_4")`. `None` for other labels.
+pub(crate) fn split_instance_label(label: &str) -> Option<(&str, &str)> {
+    let pos = label.rfind("__N")?;
+    let sizes = &label[pos + 3..];
+    let valid = !sizes.is_empty()
+        && sizes
+            .split('_')
+            .all(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()));
+    valid.then(|| (&label[..pos], sizes))
+}
+
+/// Branch and loop blocks are labelled `<kind>__<counter>`.
+fn is_block_label(label: &str) -> bool {
+    label
+        .rsplit_once("__")
+        .is_some_and(|(_, n)| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// A function section together with the blocks emitted after it.
+struct Unit<'a> {
+    label: &'a str,
+    /// Line ranges of the function section and each block section.
+    sections: Vec<(usize, usize)>,
+}
+
+/// Group `lines` into units. Lines before the first label (the entry
+/// call) belong to no unit.
+fn units<'a>(lines: &[&'a str]) -> Vec<Unit<'a>> {
+    let mut units: Vec<Unit<'a>> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(label) = label_of(lines[i]) else {
+            i += 1;
+            continue;
+        };
+        let start = i;
+        i += 1;
+        while i < lines.len() && label_of(lines[i]).is_none() {
+            i += 1;
+        }
+        match units.last_mut() {
+            Some(unit) if is_block_label(label) => unit.sections.push((start, i)),
+            _ => units.push(Unit {
+                label,
+                sections: vec![(start, i)],
+            }),
+        }
+    }
+    units
+}
+
+fn label_of(line: &str) -> Option<&str> {
+    let t = line.trim();
+    (!t.starts_with("//"))
+        .then(|| t.strip_suffix(':'))
+        .flatten()
+        .filter(|l| !l.is_empty())
+}
+
+fn instructions(lines: &[&str], unit: &Unit) -> usize {
+    unit.sections
+        .iter()
+        .flat_map(|&(start, end)| &lines[start + 1..end])
+        .filter(|l| {
+            let t = l.trim();
+            !t.is_empty() && !t.starts_with("//")
+        })
+        .count()
+}
+
+/// Code of a unit with its own labels replaced by their position and call
+/// targets renamed through `renames`, so two instances that differ only
+/// in those names compare equal.
+fn normalized(lines: &[&str], unit: &Unit, renames: &BTreeMap<String, String>) -> Vec<String> {
+    let local: BTreeMap<&str, usize> = unit
+        .sections
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(start, _))| Some((label_of(lines[start])?, i)))
+        .collect();
+    let mut code = Vec::new();
+    for &(start, end) in &unit.sections {
+        for line in &lines[start..end] {
+            let t = line.trim();
+            if t.is_empty() || t.starts_with("//") {
+                continue;
+            }
+            if let Some(i) = label_of(t).and_then(|l| local.get(l)) {
+                code.push(format!("#{}:", i));
+            } else if let Some(target) = t.strip_prefix("call ") {
+                code.push(match local.get(target) {
+                    Some(i) => format!("call #{}", i),
+                    None => format!("call {}", renames.get(target).map_or(target, |r| r)),
+                });
+            } else {
+                code.push(t.to_string());
+            }
+        }
+    }
+    code
+}
+
+/// Fold instances whose code is identical into the first of them,
+/// redirecting calls. Repeats until nothing changes, since folding a
+/// callee can make its callers identical. Returns the new TASM and the
+/// `(removed, kept)` pairs.
+pub(crate) fn dedup_instances(tasm: &str) -> (String, Vec<(String, String)>) {
+    let lines: Vec<&str> = tasm.lines().collect();
+    let units = units(&lines);
+    let mut renames: BTreeMap<String, String> = BTreeMap::new();
+    loop {
+        let mut seen: BTreeMap<Vec<String>, &str> = BTreeMap::new();
+        let mut changed = false;
+        for unit in &units {
+            if split_instance_label(unit.label).is_none() || renames.contains_key(unit.label) {
+                continue;
+            }
+            let code = normalized(&lines, unit, &renames);
+            match seen.get(&code) {
+                Some(kept) => {
+                    renames.insert(unit.label.to_string(), kept.to_string());
+                    changed = true;
+                }
+                None => {
+                    seen.insert(code, unit.label);
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    if renames.is_empty() {
+        return (tasm.to_string(), Vec::new());
+    }
+
+    let mut removed = vec![false; lines.len()];
+    for unit in units.iter().filter(|u| renames.contains_key(u.label)) {
+        for &(start, end) in &unit.sections {
+            removed[start..end].iter_mut().for_each(|r| *r = true);
+        }
+    }
+    let output: Vec<String> = lines
+        .iter()
+        .zip(&removed)
+        .filter(|(_, removed)| !**removed)
+        .map(|(line, _)| match line.trim().strip_prefix("call ") {
+            Some(target) if renames.contains_key(target) => {
+                format!("    call {}", renames[target])
+            }
+            _ => line.to_string(),
+        })
+        .collect();
+    (output.join("\n"), renames.into_iter().collect())
+}
+
+impl MonoReport {
+    /// Instances of the linked program `tasm`; `shared` as returned by
+    /// `dedup_instances`.
+    pub fn from_tasm(tasm: &str, shared: Vec<(String, String)>) -> Self {
+        let lines: Vec<&str> = tasm.lines().collect();
+        let instances = units(&lines)
+            .iter()
+            .filter_map(|unit| {
+                let (generic, _) = split_instance_label(unit.label)?;
+                Some(InstanceCost {
+                    label: unit.label.to_string(),
+                    generic: generic.to_string(),
+                    instructions: instructions(&lines, unit),
+                })
+            })
+            .collect();
+        MonoReport { instances, shared }
+    }
+
+    /// Per generic function: instance count and total instructions,
+    /// largest first.
+    pub fn by_generic(&self) -> Vec<(String, usize, usize)> {
+        let mut totals: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for inst in &self.instances {
+            let entry = totals.entry(&inst.generic).or_default();
+            entry.0 += 1;
+            entry.1 += inst.instructions;
+        }
+        let mut rows: Vec<(String, usize, usize)> = totals
+            .into_iter()
+            .map(|(name, (count, size))| (name.to_string(), count, size))
+            .collect();
+        rows.sort_by(|a, b| b.2.cmp(&a.2).then(b.1.cmp(&a.1)));
+        rows
+    }
+
+    /// Error when the program emits more than `cap` instances.
+    pub fn check_cap(&self, cap: usize) -> Result<(), Diagnostic> {
+        if self.instances.len() <= cap {
+            return Ok(());
+        }
+        let mut diag = Diagnostic::error(
+            format!(
+                "program emits {} size-generic instances, more than the limit of {}",
+                self.instances.len(),
+                cap
+            ),
+            Span::dummy(),
+        );
+        if let Some((name, count, size)) = self.by_generic().first() {
+            diag = diag.with_note(format!(
+                "'{}' alone has {} instances ({} instructions)",
+                name, count, size
+            ));
+        }
+        Err(diag.with_help(
+            "call generic functions at fewer distinct sizes, or raise `max_instances` \
+             under [build] in trident.toml"
+                .to_string(),
+        ))
+    }
+}
+
+impl fmt::Display for MonoReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total: usize = self.instances.iter().map(|i| i.instructions).sum();
+        writeln!(
+            f,
+            "Instances: {} ({} instructions)",
+            self.instances.len(),
+            total
+        )?;
+        for (name, count, size) in self.by_generic() {
+            writeln!(
+                f,
+                "  {}: {} instance{}, {} instructions",
+                name,
+                count,
+                if count == 1 { "" } else { "s" },
+                size
+            )?;
+            let mut own: Vec<&InstanceCost> = self
+                .instances
+                .iter()
+                .filter(|i| i.generic == name)
+                .collect();
+            own.sort_by_key(|i| std::cmp::Reverse(i.instructions));
+            for inst in own {
+                writeln!(f, "    {:<40} {:>6}", inst.label, inst.instructions)?;
+            }
+        }
+        if !self.shared.is_empty() {
+            writeln!(f, "Identical instances folded: {}", self.shared.len())?;
+            for (removed, kept) in &self.shared {
+                writeln!(f, "  {} -> {}", removed, kept)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_labels_carry_their_sizes() {
+        assert_eq!(split_instance_label("m__sum__N3"), Some(("m__sum", "3")));
+        assert_eq!(
+            split_instance_label("__pair__N2_10"),
+            Some(("__pair", "2_10"))
+        );
+        assert_eq!(split_instance_label("m__Node"), None);
+        assert_eq!(split_instance_label("m__f__N"), None);
+        assert!(is_block_label("then__12"));
+        assert!(!is_block_label("m__sum__N3"));
+    }
+
+    #[test]
+    fn identical_instances_fold_with_their_blocks() {
+        let tasm = "    call m__main\n    halt\nm__main:\n    call a__f__N2\n    call b__f__N2\n    return\na__f__N2:\n    skiz\n    call then__1\n    return\nthen__1:\n    push 1\n    return\nb__f__N2:\n    skiz\n    call then__7\n    return\nthen__7:\n    push 1\n    return\nb__g__N3:\n    push 2\n    return";
+        let (folded, shared) = dedup_instances(tasm);
+        assert_eq!(
+            shared,
+            vec![("b__f__N2".to_string(), "a__f__N2".to_string())]
+        );
+        assert!(!folded.contains("b__f__N2") && !folded.contains("then__7"));
+        assert_eq!(folded.matches("call a__f__N2").count(), 2);
+
+        let report = MonoReport::from_tasm(&folded, shared);
+        assert_eq!(report.instances.len(), 2);
+        // `a__f__N2` counts its `then__1` block.
+        assert_eq!(report.instances[0].instructions, 5);
+        assert_eq!(report.by_generic()[0], ("a__f".to_string(), 1, 5));
+        assert!(report.check_cap(2).is_ok());
+        assert!(report.check_cap(1).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //! Neural TIR→TASM optimizer.
 //!
 //! v2: GNN encoder + Transformer decoder trained via supervised learning
-//! + GFlowNets. The model implementation lives in `crate::neural`.
-//! This module retains the report types used by the CLI.
+//! and GFlowNets. The model implementation lives in `crate::neural`.
+//! This module retains the training report the CLI prints.
 
 pub mod report;
@@ -0,0 +1,210 @@
+//! Arena storage for TIR bodies while the peephole optimizer runs.
+//!
+//! A `Vec<TIROp>` owns its nested bodies inline, so every pass that
+//! rebuilds a sequence containing an `IfElse` or a `Loop` copies the
+//! whole subtree underneath it. A `TirArena` stores each body once, in
+//! one table, and structured ops refer to their bodies by `BodyId`.
+//! Rewriting a body then moves small handles instead of subtrees, and
+//! a pass visits every body exactly once. The arena lives only inside
+//! `optimize`: the AST and the `Vec<TIROp>` form every other stage
+//! consumes are still boxed trees.
+
+use super::TIROp;
+
+/// Index of a body in a `TirArena`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BodyId(u32);
+
+impl BodyId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// One entry of an arena body.
+#[derive(Clone, Debug)]
+pub enum Node {
+    /// An op without a body.
+    Op(TIROp),
+    IfElse {
+        then_body: BodyId,
+        else_body: BodyId,
+    },
+    IfOnly {
+        then_body: BodyId,
+    },
+    Loop {
+        label: String,
+        body: BodyId,
+    },
+    ProofBlock {
+        program_hash: String,
+        body: BodyId,
+    },
+}
+
+impl Node {
+    /// The op, when this node has no body.
+    pub fn op(&self) -> Option<&TIROp> {
+        match self {
+            Node::Op(op) => Some(op),
+            _ => None,
+        }
+    }
+}
+
+/// A TIR sequence with all of its nested bodies stored side by side.
+#[derive(Clone, Debug)]
+pub struct TirArena {
+    bodies: Vec<Vec<Node>>,
+    root: BodyId,
+}
+
+impl TirArena {
+    /// Move `ops` and everything nested in them into an arena.
+    pub fn from_ops(ops: Vec<TIROp>) -> Self {
+        let mut arena = TirArena {
+            bodies: Vec::new(),
+            root: BodyId(0),
+        };
+        arena.root = arena.alloc(ops);
+        arena
+    }
+
+    /// Allocate a body after the bodies nested in it.
+    fn alloc(&mut self, ops: Vec<TIROp>) -> BodyId {
+        let nodes = ops
+            .into_iter()
+            .map(|op| match op {
+                TIROp::IfElse {
+                    then_body,
+                    else_body,
+                } => Node::IfElse {
+                    then_body: self.alloc(then_body),
+                    else_body: self.alloc(else_body),
+                },
+                TIROp::IfOnly { then_body } => Node::IfOnly {
+                    then_body: self.alloc(then_body),
+                },
+                TIROp::Loop { label, body } => Node::Loop {
+                    label,
+                    body: self.alloc(body),
+                },
+                TIROp::ProofBlock { program_hash, body } => Node::ProofBlock {
+                    program_hash,
+                    body: self.alloc(body),
+                },
+                op => Node::Op(op),
+            })
+            .collect();
+        let id = BodyId(self.bodies.len() as u32);
+        self.bodies.push(nodes);
+        id
+    }
+
+    /// The top-level body.
+    #[cfg(test)]
+    pub fn root(&self) -> BodyId {
+        self.root
+    }
+
+    /// The nodes of body `id`.
+    #[cfg(test)]
+    pub fn body(&self, id: BodyId) -> &[Node] {
+        &self.bodies[id.index()]
+    }
+
+    pub fn body_mut(&mut self, id: BodyId) -> &mut Vec<Node> {
+        &mut self.bodies[id.index()]
+    }
+
+    /// Every body, each one after the bodies nested in it.
+    pub fn body_ids(&self) -> impl Iterator<Item = BodyId> {
+        (0..self.bodies.len() as u32).map(BodyId)
+    }
+
+    /// Number of nodes across all bodies.
+    #[cfg(test)]
+    pub fn node_count(&self) -> usize {
+        self.bodies.iter().map(Vec::len).sum()
+    }
+
+    /// Rebuild the nested `Vec<TIROp>` form.
+    pub fn into_ops(mut self) -> Vec<TIROp> {
+        let root = self.root;
+        self.take(root)
+    }
+
+    fn take(&mut self, id: BodyId) -> Vec<TIROp> {
+        std::mem::take(&mut self.bodies[id.index()])
+            .into_iter()
+            .map(|node| match node {
+                Node::Op(op) => op,
+                Node::IfElse {
+                    then_body,
+                    else_body,
+                } => TIROp::IfElse {
+                    then_body: self.take(then_body),
+                    else_body: self.take(else_body),
+                },
+                Node::IfOnly { then_body } => TIROp::IfOnly {
+                    then_body: self.take(then_body),
+                },
+                Node::Loop { label, body } => TIROp::Loop {
+                    label,
+                    body: self.take(body),
+                },
+                Node::ProofBlock { program_hash, body } => TIROp::ProofBlock {
+                    program_hash,
+                    body: self.take(body),
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_bodies_come_before_their_parent() {
+        let arena = TirArena::from_ops(vec![
+            TIROp::Push(1),
+            TIROp::IfOnly {
+                then_body: vec![TIROp::Loop {
+                    label: "l".to_string(),
+                    body: vec![TIROp::Pop(1)],
+                }],
+            },
+        ]);
+        let ids: Vec<BodyId> = arena.body_ids().collect();
+        assert_eq!(ids.len(), 3);
+        assert_eq!(arena.root(), ids[2]);
+        assert!(matches!(arena.body(ids[0])[0].op(), Some(TIROp::Pop(1))));
+        assert!(matches!(arena.body(ids[1])[0], Node::Loop { body, .. } if body == ids[0]));
+        assert_eq!(arena.node_count(), 4);
+    }
+
+    #[test]
+    fn round_trip_preserves_the_tree() {
+        let ops = vec![
+            TIROp::IfElse {
+                then_body: vec![TIROp::Push(2), TIROp::Add],
+                else_body: vec![],
+            },
+            TIROp::ProofBlock {
+                program_hash: "h".to_string(),
+                body: vec![TIROp::IfOnly {
+                    then_body: vec![TIROp::Halt],
+                }],
+            },
+            TIROp::Return,
+        ];
+        let expected = format!("{:?}", ops);
+        assert_eq!(
+            format!("{:?}", TirArena::from_ops(ops).into_ops()),
+            expected
+        );
+    }
+}
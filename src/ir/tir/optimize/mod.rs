@@ -1,16 +1,31 @@
 /// TIR peephole optimizer.
 ///
-/// Runs pattern-based rewrites on Vec<TIROp> to reduce instruction count.
-/// Applied between TIR building and lowering to target assembly.
+/// Runs pattern-based rewrites on each body of a TIR program to reduce
+/// instruction count. Applied between TIR building and lowering to target
+/// assembly.
 use super::TIROp;
+use arena::{Node, TirArena};
 
+mod arena;
 pub(crate) mod spill;
 #[cfg(test)]
 mod tests;
 
 /// Apply all peephole optimizations until no more changes occur.
+///
+/// No pattern spans a body boundary, so every body of the program is
+/// optimized once, on its own, with nested bodies held by the arena.
 pub(crate) fn optimize(ops: Vec<TIROp>) -> Vec<TIROp> {
-    let mut ir = ops;
+    let mut arena = TirArena::from_ops(ops);
+    for id in arena.body_ids() {
+        let body = std::mem::take(arena.body_mut(id));
+        *arena.body_mut(id) = optimize_body(body);
+    }
+    arena.into_ops()
+}
+
+/// Run the peephole passes over one body until it stops shrinking.
+fn optimize_body(mut ir: Vec<Node>) -> Vec<Node> {
     loop {
         let before = ir.len();
         ir = merge_hints(ir);
@@ -21,7 +36,6 @@ pub(crate) fn optimize(ops: Vec<TIROp>) -> Vec<TIROp> {
         ir = eliminate_double_swaps(ir);
         ir = collapse_swap_pop_chains(ir);
         ir = collapse_epilogue_cleanup(ir);
-        ir = optimize_nested(ir);
         if ir.len() == before {
             break;
         }
@@ -30,15 +44,15 @@ pub(crate) fn optimize(ops: Vec<TIROp>) -> Vec<TIROp> {
 }
 
 /// Merge consecutive Hint(a), Hint(b) -> Hint(a+b), capped at 5 per instruction.
-fn merge_hints(ops: Vec<TIROp>) -> Vec<TIROp> {
-    let mut out: Vec<TIROp> = Vec::with_capacity(ops.len());
+fn merge_hints(ops: Vec<Node>) -> Vec<Node> {
+    let mut out: Vec<Node> = Vec::with_capacity(ops.len());
     let mut i = 0;
     while i < ops.len() {
-        if let TIROp::Hint(n) = &ops[i] {
+        if let Some(TIROp::Hint(n)) = ops[i].op() {
             let mut total = *n;
             let mut j = i + 1;
             while j < ops.len() {
-                if let TIROp::Hint(m) = &ops[j] {
+                if let Some(TIROp::Hint(m)) = ops[j].op() {
                     total += m;
                     j += 1;
                 } else {
@@ -48,7 +62,7 @@ fn merge_hints(ops: Vec<TIROp>) -> Vec<TIROp> {
             // Emit in batches of 5 (Triton VM limit)
             while total > 0 {
                 let batch = total.min(5);
-                out.push(TIROp::Hint(batch));
+                out.push(Node::Op(TIROp::Hint(batch)));
                 total -= batch;
             }
             i = j;
@@ -61,15 +75,15 @@ fn merge_hints(ops: Vec<TIROp>) -> Vec<TIROp> {
 }
 
 /// Merge consecutive Pop(a), Pop(b) -> Pop(a+b), capped at 5 per instruction.
-fn merge_pops(ops: Vec<TIROp>) -> Vec<TIROp> {
-    let mut out: Vec<TIROp> = Vec::with_capacity(ops.len());
+fn merge_pops(ops: Vec<Node>) -> Vec<Node> {
+    let mut out: Vec<Node> = Vec::with_capacity(ops.len());
     let mut i = 0;
     while i < ops.len() {
-        if let TIROp::Pop(n) = &ops[i] {
+        if let Some(TIROp::Pop(n)) = ops[i].op() {
             let mut total = *n;
             let mut j = i + 1;
             while j < ops.len() {
-                if let TIROp::Pop(m) = &ops[j] {
+                if let Some(TIROp::Pop(m)) = ops[j].op() {
                     total += m;
                     j += 1;
                 } else {
@@ -79,7 +93,7 @@ fn merge_pops(ops: Vec<TIROp>) -> Vec<TIROp> {
             // Emit in batches of 5 (Triton VM limit)
             while total > 0 {
                 let batch = total.min(5);
-                out.push(TIROp::Pop(batch));
+                out.push(Node::Op(TIROp::Pop(batch)));
                 total -= batch;
             }
             i = j;
@@ -92,9 +106,9 @@ fn merge_pops(ops: Vec<TIROp>) -> Vec<TIROp> {
 }
 
 /// Remove no-op instructions: Swap(0), Pop(0).
-fn eliminate_nops(ops: Vec<TIROp>) -> Vec<TIROp> {
+fn eliminate_nops(ops: Vec<Node>) -> Vec<Node> {
     ops.into_iter()
-        .filter(|op| !matches!(op, TIROp::Swap(0) | TIROp::Pop(0)))
+        .filter(|node| !matches!(node.op(), Some(TIROp::Swap(0) | TIROp::Pop(0))))
         .collect()
 }
 
@@ -103,14 +117,14 @@ fn eliminate_nops(ops: Vec<TIROp>) -> Vec<TIROp> {
 /// `dup 0; pop 1` duplicates the top element then immediately discards it.
 /// `dup 0; swap 1; pop 1` copies top, swaps with element below, pops -- net
 /// effect is identity (the original value below is replaced by an identical copy).
-fn eliminate_dup_pop_nops(ops: Vec<TIROp>) -> Vec<TIROp> {
-    let mut out: Vec<TIROp> = Vec::with_capacity(ops.len());
+fn eliminate_dup_pop_nops(ops: Vec<Node>) -> Vec<Node> {
+    let mut out: Vec<Node> = Vec::with_capacity(ops.len());
     let mut i = 0;
     while i < ops.len() {
         // Pattern: Dup(0), Swap(1), Pop(1) -> skip all three
         if i + 2 < ops.len() {
-            if let (TIROp::Dup(0), TIROp::Swap(1), TIROp::Pop(1)) =
-                (&ops[i], &ops[i + 1], &ops[i + 2])
+            if let (Some(TIROp::Dup(0)), Some(TIROp::Swap(1)), Some(TIROp::Pop(1))) =
+                (ops[i].op(), ops[i + 1].op(), ops[i + 2].op())
             {
                 i += 3;
                 continue;
@@ -118,7 +132,7 @@ fn eliminate_dup_pop_nops(ops: Vec<TIROp>) -> Vec<TIROp> {
         }
         // Pattern: Dup(0), Pop(1) -> skip both
         if i + 1 < ops.len() {
-            if let (TIROp::Dup(0), TIROp::Pop(1)) = (&ops[i], &ops[i + 1]) {
+            if let (Some(TIROp::Dup(0)), Some(TIROp::Pop(1))) = (ops[i].op(), ops[i + 1].op()) {
                 i += 2;
                 continue;
             }
@@ -130,12 +144,12 @@ fn eliminate_dup_pop_nops(ops: Vec<TIROp>) -> Vec<TIROp> {
 }
 
 /// Eliminate consecutive `Swap(N); Swap(N)` pairs (double swap is identity).
-fn eliminate_double_swaps(ops: Vec<TIROp>) -> Vec<TIROp> {
-    let mut out: Vec<TIROp> = Vec::with_capacity(ops.len());
+fn eliminate_double_swaps(ops: Vec<Node>) -> Vec<Node> {
+    let mut out: Vec<Node> = Vec::with_capacity(ops.len());
     let mut i = 0;
     while i < ops.len() {
         if i + 1 < ops.len() {
-            if let (TIROp::Swap(a), TIROp::Swap(b)) = (&ops[i], &ops[i + 1]) {
+            if let (Some(TIROp::Swap(a)), Some(TIROp::Swap(b))) = (ops[i].op(), ops[i + 1].op()) {
                 if a == b {
                     i += 2;
                     continue;
@@ -162,21 +176,21 @@ fn eliminate_double_swaps(ops: Vec<TIROp>) -> Vec<TIROp> {
 /// K elements from depth D, then removes the originals. If the originals aren't
 /// needed after, this is just copying. When the dups reference a contiguous block
 /// that is immediately popped, the net effect is a no-op (elements stay in place).
-fn collapse_swap_pop_chains(ops: Vec<TIROp>) -> Vec<TIROp> {
-    let mut out: Vec<TIROp> = Vec::with_capacity(ops.len());
+fn collapse_swap_pop_chains(ops: Vec<Node>) -> Vec<Node> {
+    let mut out: Vec<Node> = Vec::with_capacity(ops.len());
     let mut i = 0;
     while i < ops.len() {
         // Pattern: dup D, dup D, ..., dup D (N times), swap N, pop N1, pop N2, ...
         // where the total popped equals N and D == N-1.
         // This is "extract copy of block at depth D, discard original."
         // Net: the N elements stay on the stack without the dup+pop round trip.
-        if let TIROp::Dup(d) = &ops[i] {
+        if let Some(TIROp::Dup(d)) = ops[i].op() {
             let d_val = *d;
             // Count consecutive dup D instructions with the same D value.
             let mut dup_count = 0u32;
             let mut j = i;
             while j < ops.len() {
-                if let TIROp::Dup(dd) = &ops[j] {
+                if let Some(TIROp::Dup(dd)) = ops[j].op() {
                     if *dd == d_val {
                         dup_count += 1;
                         j += 1;
@@ -189,13 +203,13 @@ fn collapse_swap_pop_chains(ops: Vec<TIROp>) -> Vec<TIROp> {
             }
             // After the dups, check for swap(dup_count) followed by pop totaling dup_count.
             if dup_count >= 2 && j < ops.len() {
-                if let TIROp::Swap(s) = &ops[j] {
+                if let Some(TIROp::Swap(s)) = ops[j].op() {
                     if *s == dup_count {
                         let after_swap = j + 1;
                         let mut total_popped = 0u32;
                         let mut k = after_swap;
                         while k < ops.len() {
-                            if let TIROp::Pop(p) = &ops[k] {
+                            if let Some(TIROp::Pop(p)) = ops[k].op() {
                                 total_popped += p;
                                 k += 1;
                                 if total_popped >= dup_count {
@@ -228,16 +242,18 @@ fn collapse_swap_pop_chains(ops: Vec<TIROp>) -> Vec<TIROp> {
 /// **Constant-depth chains**: N consecutive `swap 1; pop 1` pairs each remove
 /// one element below the top. Net effect: keep top, discard N elements below.
 /// Collapsed to `swap min(N,15); pop min(N,15)` in chunks (swap max is 15).
+/// Chains of `swap D; pop 1` with D > 1 rotate their D-wide return value
+/// and are left alone.
 ///
 /// **Decreasing-depth chains**: `swap D; pop 1; swap D-1; pop 1; ...` chains
 /// where each pair brings a deeper dead element to the top. Collapsed to
 /// `swap first_D; pop count`.
-fn collapse_epilogue_cleanup(ops: Vec<TIROp>) -> Vec<TIROp> {
-    let mut out: Vec<TIROp> = Vec::with_capacity(ops.len());
+fn collapse_epilogue_cleanup(ops: Vec<Node>) -> Vec<Node> {
+    let mut out: Vec<Node> = Vec::with_capacity(ops.len());
     let mut i = 0;
     while i < ops.len() {
         if i + 3 < ops.len() {
-            if let (TIROp::Swap(d), TIROp::Pop(1)) = (&ops[i], &ops[i + 1]) {
+            if let (Some(TIROp::Swap(d)), Some(TIROp::Pop(1))) = (ops[i].op(), ops[i + 1].op()) {
                 let first_d = *d;
 
                 // Count consecutive swap(D); pop(1) pairs.
@@ -245,7 +261,9 @@ fn collapse_epilogue_cleanup(ops: Vec<TIROp>) -> Vec<TIROp> {
                 let mut is_constant_depth = true;
                 let mut j = i + 2;
                 while j + 1 < ops.len() {
-                    if let (TIROp::Swap(dd), TIROp::Pop(1)) = (&ops[j], &ops[j + 1]) {
+                    if let (Some(TIROp::Swap(dd)), Some(TIROp::Pop(1))) =
+                        (ops[j].op(), ops[j + 1].op())
+                    {
                         if *dd == first_d {
                             // Same depth -- constant-depth chain continues.
                             count += 1;
@@ -273,43 +291,28 @@ fn collapse_epilogue_cleanup(ops: Vec<TIROp>) -> Vec<TIROp> {
                         let mut remaining = count;
                         while remaining > 0 {
                             let chunk = remaining.min(15);
-                            out.push(TIROp::Swap(chunk));
+                            out.push(Node::Op(TIROp::Swap(chunk)));
                             let mut pop_left = chunk;
                             while pop_left > 0 {
                                 let batch = pop_left.min(5);
-                                out.push(TIROp::Pop(batch));
+                                out.push(Node::Op(TIROp::Pop(batch)));
                                 pop_left -= batch;
                             }
                             remaining -= chunk;
                         }
                     } else if is_constant_depth {
-                        // Constant-depth with D > 1: removing `count`
-                        // dead elements from below a D-wide return value.
-                        // When D + count - 1 <= 15, bring all dead elements
-                        // to the top with decreasing swaps, then batch pop.
-                        let total_depth = first_d + count - 1;
-                        if total_depth <= 15 {
-                            for offset in 0..count {
-                                out.push(TIROp::Swap(total_depth - offset));
-                            }
-                        } else {
-                            for _ in 0..count {
-                                out.push(TIROp::Swap(first_d));
-                            }
-                        }
-                        let mut remaining = count;
-                        while remaining > 0 {
-                            let batch = remaining.min(5);
-                            out.push(TIROp::Pop(batch));
-                            remaining -= batch;
-                        }
+                        // Constant-depth with D > 1: each pair rotates the
+                        // D-wide return value by one while removing a dead
+                        // element. No shorter sequence keeps that order, so
+                        // the chain stays as is.
+                        out.extend_from_slice(&ops[i..j]);
                     } else {
                         // Decreasing-depth chain.
-                        out.push(TIROp::Swap(first_d));
+                        out.push(Node::Op(TIROp::Swap(first_d)));
                         let mut remaining = count;
                         while remaining > 0 {
                             let batch = remaining.min(5);
-                            out.push(TIROp::Pop(batch));
+                            out.push(Node::Op(TIROp::Pop(batch)));
                             remaining -= batch;
                         }
                     }
@@ -323,30 +326,3 @@ fn collapse_epilogue_cleanup(ops: Vec<TIROp>) -> Vec<TIROp> {
     }
     out
 }
-
-/// Recursively optimize nested bodies (IfElse, IfOnly, Loop, ProofBlock).
-fn optimize_nested(ops: Vec<TIROp>) -> Vec<TIROp> {
-    ops.into_iter()
-        .map(|op| match op {
-            TIROp::IfElse {
-                then_body,
-                else_body,
-            } => TIROp::IfElse {
-                then_body: optimize(then_body),
-                else_body: optimize(else_body),
-            },
-            TIROp::IfOnly { then_body } => TIROp::IfOnly {
-                then_body: optimize(then_body),
-            },
-            TIROp::Loop { label, body } => TIROp::Loop {
-                label,
-                body: optimize(body),
-            },
-            TIROp::ProofBlock { program_hash, body } => TIROp::ProofBlock {
-                program_hash,
-                body: optimize(body),
-            },
-            other => other,
-        })
-        .collect()
-}
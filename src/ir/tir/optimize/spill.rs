@@ -1,5 +1,5 @@
 /// Dead spill and dead store elimination.
-use super::TIROp;
+use super::{Node, TIROp};
 use std::collections::{BTreeMap, BTreeSet};
 
 /// Eliminate dead spills and dead stores.
@@ -7,29 +7,42 @@ use std::collections::{BTreeMap, BTreeSet};
 /// Two patterns are handled:
 ///
 /// 1. **Spill/reload pairs** -- address written once and read once:
-///    Write: `Push(addr), Swap(1), WriteMem(1), Pop(1)` -> removed
+///    Write: `Push(addr), WriteMem(1), Pop(1)` -> removed
 ///    Read:  `Push(addr), ReadMem(1), Pop(1)` -> removed
 ///    The value stays on the stack instead of round-tripping through RAM.
 ///
 /// 2. **Dead stores** -- address written but never read:
-///    `Push(addr), Swap(1), WriteMem(1), Pop(1)` -> `Pop(1)`
+///    `Push(addr), WriteMem(1), Pop(1)` -> `Pop(1)`
 ///    The value was going to be discarded into RAM; just pop it.
-///    Also handles: `Swap(D), Push(addr), Swap(1), WriteMem(1), Pop(1)` -> `Swap(D), Pop(1)`
-pub(crate) fn eliminate_dead_spills(ops: Vec<TIROp>) -> Vec<TIROp> {
+///
+/// A body that reads memory at a computed address (runtime array
+/// indexing) is left alone: such a read may see any of its writes.
+pub(crate) fn eliminate_dead_spills(ops: Vec<Node>) -> Vec<Node> {
+    let computed_read = ops.iter().enumerate().any(|(i, node)| {
+        matches!(node.op(), Some(TIROp::ReadMem(_)))
+            && !matches!(
+                i.checked_sub(1).and_then(|p| ops[p].op()),
+                Some(TIROp::Push(_))
+            )
+    });
+    if computed_read {
+        return ops;
+    }
+
     // First pass: count writes and reads per address.
     let mut write_addrs: BTreeMap<u64, usize> = BTreeMap::new();
     let mut read_addrs: BTreeMap<u64, usize> = BTreeMap::new();
 
-    for window in ops.windows(4) {
-        if let (TIROp::Push(addr), TIROp::Swap(1), TIROp::WriteMem(1), TIROp::Pop(1)) =
-            (&window[0], &window[1], &window[2], &window[3])
+    for window in ops.windows(3) {
+        if let (Some(TIROp::Push(addr)), Some(TIROp::WriteMem(1)), Some(TIROp::Pop(1))) =
+            (window[0].op(), window[1].op(), window[2].op())
         {
             *write_addrs.entry(*addr).or_insert(0) += 1;
         }
     }
     for window in ops.windows(3) {
-        if let (TIROp::Push(addr), TIROp::ReadMem(1), TIROp::Pop(1)) =
-            (&window[0], &window[1], &window[2])
+        if let (Some(TIROp::Push(addr)), Some(TIROp::ReadMem(1)), Some(TIROp::Pop(1))) =
+            (window[0].op(), window[1].op(), window[2].op())
         {
             *read_addrs.entry(*addr).or_insert(0) += 1;
         }
@@ -55,30 +68,30 @@ pub(crate) fn eliminate_dead_spills(ops: Vec<TIROp>) -> Vec<TIROp> {
     }
 
     // Second pass: rewrite.
-    let mut out: Vec<TIROp> = Vec::with_capacity(ops.len());
+    let mut out: Vec<Node> = Vec::with_capacity(ops.len());
     let mut i = 0;
     while i < ops.len() {
-        // Check for write pattern: Push(addr), Swap(1), WriteMem(1), Pop(1)
-        if i + 3 < ops.len() {
-            if let (TIROp::Push(addr), TIROp::Swap(1), TIROp::WriteMem(1), TIROp::Pop(1)) =
-                (&ops[i], &ops[i + 1], &ops[i + 2], &ops[i + 3])
+        // Check for write pattern: Push(addr), WriteMem(1), Pop(1)
+        if i + 2 < ops.len() {
+            if let (Some(TIROp::Push(addr)), Some(TIROp::WriteMem(1)), Some(TIROp::Pop(1))) =
+                (ops[i].op(), ops[i + 1].op(), ops[i + 2].op())
             {
                 if pair_addrs.contains(addr) {
-                    i += 4; // remove entirely (value stays on stack)
+                    i += 3; // remove entirely (value stays on stack)
                     continue;
                 }
                 if dead_addrs.contains(addr) {
                     // Value is on top, replace write with pop to discard it
-                    out.push(TIROp::Pop(1));
-                    i += 4;
+                    out.push(Node::Op(TIROp::Pop(1)));
+                    i += 3;
                     continue;
                 }
             }
         }
         // Check for read pattern: Push(addr), ReadMem(1), Pop(1)
         if i + 2 < ops.len() {
-            if let (TIROp::Push(addr), TIROp::ReadMem(1), TIROp::Pop(1)) =
-                (&ops[i], &ops[i + 1], &ops[i + 2])
+            if let (Some(TIROp::Push(addr)), Some(TIROp::ReadMem(1)), Some(TIROp::Pop(1))) =
+                (ops[i].op(), ops[i + 1].op(), ops[i + 2].op())
             {
                 if pair_addrs.contains(addr) {
                     i += 3; // remove entirely
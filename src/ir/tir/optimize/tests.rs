@@ -41,7 +41,6 @@ fn eliminate_spill_reload_pair() {
     let ops = vec![
         TIROp::Push(42),
         TIROp::Push(addr),
-        TIROp::Swap(1),
         TIROp::WriteMem(1),
         TIROp::Pop(1),
         TIROp::Add,
@@ -61,7 +60,6 @@ fn eliminate_dead_store() {
     let ops = vec![
         TIROp::Push(42),
         TIROp::Push(addr),
-        TIROp::Swap(1),
         TIROp::WriteMem(1),
         TIROp::Pop(1),
         TIROp::Add,
@@ -76,7 +74,6 @@ fn no_eliminate_when_multiple_reads() {
     let addr = 1 << 30;
     let ops = vec![
         TIROp::Push(addr),
-        TIROp::Swap(1),
         TIROp::WriteMem(1),
         TIROp::Pop(1),
         TIROp::Push(addr),
@@ -87,7 +84,25 @@ fn no_eliminate_when_multiple_reads() {
         TIROp::Pop(1),
     ];
     let result = optimize(ops);
-    assert_eq!(result.len(), 10);
+    assert_eq!(result.len(), 9);
+}
+
+#[test]
+fn no_eliminate_when_read_at_computed_address() {
+    // The read's address is only known at run time and may be `addr`:
+    // the store is live.
+    let addr = 1 << 30;
+    let ops = vec![
+        TIROp::Push(42),
+        TIROp::Push(addr),
+        TIROp::WriteMem(1),
+        TIROp::Pop(1),
+        TIROp::Dup(1),
+        TIROp::ReadMem(1),
+        TIROp::Pop(1),
+    ];
+    let result = optimize(ops.clone());
+    assert_eq!(format!("{:?}", result), format!("{:?}", ops));
 }
 
 #[test]
@@ -192,43 +207,58 @@ fn collapse_large_constant_depth_chain() {
     assert_eq!(result.len(), 8);
 }
 
+/// Run `swap`/`pop` ops over a stack of `depth` distinct values, top
+/// last.
+fn run_swaps_and_pops(ops: &[TIROp], depth: u64) -> Vec<u64> {
+    let mut stack: Vec<u64> = (0..depth).rev().collect();
+    for op in ops {
+        match op {
+            TIROp::Swap(d) => {
+                let top = stack.len() - 1;
+                stack.swap(top, top - *d as usize);
+            }
+            TIROp::Pop(n) => stack.truncate(stack.len() - *n as usize),
+            _ => {}
+        }
+    }
+    stack
+}
+
 #[test]
 fn collapse_constant_depth_swap_k_pop1_chain() {
-    // 5x swap 3; pop 1 (width-3 return, 5 dead locals) ->
-    // swap 7; swap 6; swap 5; swap 4; swap 3; pop 5 (batch pop)
+    // 5x swap 3; pop 1 (width-3 return, 5 dead locals): each pair
+    // rotates the return value by one as it drops a local. No batch of
+    // swaps and pops ends in the same order, so the chain is kept.
     let mut ops = Vec::new();
     for _ in 0..5 {
         ops.push(TIROp::Swap(3));
         ops.push(TIROp::Pop(1));
     }
     ops.push(TIROp::Return);
-    let result = optimize(ops);
-    // D=3, count=5, total_depth = 3+5-1 = 7 (<=15)
-    assert!(matches!(result[0], TIROp::Swap(7)));
-    assert!(matches!(result[1], TIROp::Swap(6)));
-    assert!(matches!(result[2], TIROp::Swap(5)));
-    assert!(matches!(result[3], TIROp::Swap(4)));
-    assert!(matches!(result[4], TIROp::Swap(3)));
-    assert!(matches!(result[5], TIROp::Pop(5)));
-    assert!(matches!(result[6], TIROp::Return));
-    assert_eq!(result.len(), 7); // was 11
+    let result = optimize(ops.clone());
+    assert_eq!(format!("{:?}", result), format!("{:?}", ops));
 }
 
 #[test]
 fn collapse_constant_depth_swap_k_large_chain() {
-    // 4x swap 5; pop 1 -- total_depth = 5+4-1 = 8 (<=15)
+    // 4x swap 5; pop 1: the former rewrite, swap 8 .. swap 5; pop 4,
+    // kept three of the dead locals and dropped three words of the
+    // return value instead.
     let mut ops = Vec::new();
     for _ in 0..4 {
         ops.push(TIROp::Swap(5));
         ops.push(TIROp::Pop(1));
     }
-    let result = optimize(ops);
-    assert!(matches!(result[0], TIROp::Swap(8)));
-    assert!(matches!(result[1], TIROp::Swap(7)));
-    assert!(matches!(result[2], TIROp::Swap(6)));
-    assert!(matches!(result[3], TIROp::Swap(5)));
-    assert!(matches!(result[4], TIROp::Pop(4)));
-    assert_eq!(result.len(), 5); // was 8
+    let result = optimize(ops.clone());
+    assert_eq!(format!("{:?}", result), format!("{:?}", ops));
+    let former = [
+        TIROp::Swap(8),
+        TIROp::Swap(7),
+        TIROp::Swap(6),
+        TIROp::Swap(5),
+        TIROp::Pop(4),
+    ];
+    assert_ne!(run_swaps_and_pops(&former, 9), run_swaps_and_pops(&ops, 9));
 }
 
 #[test]
@@ -344,8 +344,8 @@ impl StackManager {
                     self.side_effects
                         .push((self.formatter.fmt_swap)(elem_depth));
                 }
+                // write_mem 1: [addr, val] -> [addr+1]
                 self.side_effects.push((self.formatter.fmt_push)(ram_addr));
-                self.side_effects.push((self.formatter.fmt_swap)(1));
                 self.side_effects
                     .push(self.formatter.fmt_write_mem1.clone());
                 self.side_effects.push(self.formatter.fmt_pop1.clone());
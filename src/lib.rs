@@ -1,75 +1,71 @@
+//! The Trident compiler as a library.
+//!
+//! [`api`] is the stable surface: compile, check, test, cost, and verify
+//! entry points with the types they take and return, all re-exported at
+//! the crate root. Its items follow semver, and [`api::API_VERSION`]
+//! names the revision of the surface a release provides. The syntax tree
+//! that parsing returns and compiler passes inspect is part of it, as
+//! [`ast`].
+//!
+//! Every other module is internal to the crate. The `trident` and
+//! `trident-lsp` binaries enter through [`run_cli`] and
+//! [`run_language_server`], the neural benchmark through `neural_bench`,
+//! and the `std/compiler` reference benchmarks through `self_hosted`;
+//! these are hidden from the documentation and are not part of the
+//! stable API.
+
+#![recursion_limit = "256"]
+
 pub mod api;
 pub mod ast;
-pub mod config;
-pub mod cost;
-pub mod deploy;
-pub mod diagnostic;
-pub mod field;
-pub mod gpu;
-pub mod ir;
-pub mod lsp;
-pub mod neural;
-pub mod package;
-pub mod runtime;
-pub mod syntax;
-pub mod typecheck;
-pub mod verify;
+pub(crate) mod cli;
+pub(crate) mod config;
+pub(crate) mod cost;
+pub(crate) mod deploy;
+pub(crate) mod diagnostic;
+pub(crate) mod field;
+pub(crate) mod ir;
+pub(crate) mod lsp;
+pub(crate) mod neural;
+pub(crate) mod package;
+pub(crate) mod runtime;
+pub(crate) mod syntax;
+pub(crate) mod typecheck;
+pub(crate) mod verify;
 
 // Re-exports — moved modules keep their old `crate::X` paths
 pub(crate) use api::pipeline;
-pub use ir::tir;
-pub use syntax::span;
-pub use typecheck::types;
-
-// Re-exports — preserves `trident::X` paths used by CLI and tests
-pub use config::project;
-pub use config::resolve;
-pub use config::scaffold;
-pub use config::target;
-pub use package::cache;
-pub use package::hash;
-pub use package::manifest;
-pub use package::poseidon2;
-pub use package::registry;
-pub use package::store;
-pub use syntax::format;
-pub use syntax::lexeme;
-pub use syntax::lexer;
-pub use syntax::parser;
-pub use verify::equiv;
-pub use verify::report;
-pub use verify::smt;
-pub use verify::solve;
-pub use verify::sym;
-pub use verify::synthesize;
+pub(crate) use ir::tir;
+pub(crate) use syntax::span;
+pub(crate) use typecheck::types;
+pub(crate) use config::resolve;
+pub(crate) use config::{policy, project, scaffold, target};
+pub(crate) use package::{hash, manifest, registry, store};
+pub(crate) use syntax::format;
+pub(crate) use syntax::parser;
+pub(crate) use syntax::{lexeme, lexer};
+pub(crate) use verify::{equiv, export, report, smt, solve, sym, synthesize};
 
-// Re-export public API — preserves `trident::compile()` etc.
+// The stable API, also reachable as `trident::compile()` etc.
 pub use api::*;
 
-use diagnostic::{render_diagnostics, Diagnostic};
-use lexer::Lexer;
-use parser::Parser;
+/// Run the `trident` command line.
+#[doc(hidden)]
+pub fn run_cli() {
+    cli::main()
+}
 
-pub(crate) fn parse_source(source: &str, filename: &str) -> Result<ast::File, Vec<Diagnostic>> {
-    let (tokens, _comments, lex_errors) = Lexer::new(source, 0).tokenize();
-    if !lex_errors.is_empty() {
-        render_diagnostics(&lex_errors, filename, source);
-        return Err(lex_errors);
-    }
+/// Stages of the neural optimizer, timed by `benches/end_to_end.rs`.
+#[doc(hidden)]
+pub use neural::bench as neural_bench;
 
-    match Parser::new_with_source(tokens, source).parse_file() {
-        Ok(file) => Ok(file),
-        Err(errors) => {
-            render_diagnostics(&errors, filename, source);
-            Err(errors)
-        }
-    }
-}
+/// Flat tokens and syntax trees, as the self-hosted front end in
+/// `std/compiler` lays them out.
+#[doc(hidden)]
+pub use syntax::flat as self_hosted;
 
-pub fn parse_source_silent(source: &str, _filename: &str) -> Result<ast::File, Vec<Diagnostic>> {
-    let (tokens, _comments, lex_errors) = Lexer::new(source, 0).tokenize();
-    if !lex_errors.is_empty() {
-        return Err(lex_errors);
-    }
-    Parser::new_with_source(tokens, source).parse_file()
+/// Serve the Language Server Protocol on stdin and stdout.
+#[doc(hidden)]
+pub async fn run_language_server() {
+    lsp::run_server().await
 }
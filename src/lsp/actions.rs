@@ -1,10 +1,21 @@
-//! LSP code actions: quick fixes triggered by diagnostics.
+//! LSP code actions: quick fixes triggered by diagnostics, removal of
+//! assertions the verifier finds redundant, and the organize-imports
+//! source action.
 
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 use tower_lsp::lsp_types::*;
 
-use super::util::byte_offset_to_position;
+use super::util::{byte_offset_to_position, span_to_range};
+use crate::lexeme::{reserved_rename, Lexeme};
+use crate::lexer::Lexer;
+use crate::report::AssertionFix;
+use crate::solve::VerifyConfig;
+
+/// Time the verifier gets per function when looking for redundant
+/// assertions to offer removing.
+const VERIFY_BUDGET: Duration = Duration::from_millis(100);
 
 /// Build code actions for diagnostics in the given range.
 pub(super) fn code_actions(
@@ -31,6 +42,10 @@ pub(super) fn code_actions(
             if let Some(a) = insert_missing_field(source, diag, uri) {
                 actions.push(CodeActionOrCommand::CodeAction(a));
             }
+        } else if msg.contains("' is reserved for a future keyword") {
+            if let Some(a) = rename_reserved(source, diag, uri) {
+                actions.push(CodeActionOrCommand::CodeAction(a));
+            }
         }
     }
     actions
@@ -153,26 +168,182 @@ fn insert_missing_field(source: &str, diag: &Diagnostic, uri: &Url) -> Option<Co
     ))
 }
 
+/// Rename every use of an identifier spelled like a reserved word.
+///
+/// The new name is fixed per word, so fixing each module of a project on
+/// its own still leaves cross-module references matching. No fix is
+/// offered when the new name is already taken in the file.
+fn rename_reserved(source: &str, diag: &Diagnostic, uri: &Url) -> Option<CodeAction> {
+    let name = extract_quoted(&diag.message)?;
+    let new_name = reserved_rename(&name);
+    let (tokens, _, _) = Lexer::new(source, 0).tokenize();
+    let mut edits = Vec::new();
+    for tok in &tokens {
+        match &tok.node {
+            Lexeme::Ident(id) if *id == new_name => return None,
+            Lexeme::Ident(id) if *id == name => edits.push(TextEdit {
+                range: span_to_range(source, tok.span),
+                new_text: new_name.clone(),
+            }),
+            _ => {}
+        }
+    }
+    Some(make_quickfix(
+        format!("Rename '{}' to '{}' in this file", name, new_name),
+        uri,
+        edits,
+        diag,
+    ))
+}
+
+/// Sort and dedup the `use` block, dropping the imports in `unused`.
+///
+/// The whole block is rewritten by a single edit, one import per
+/// paragraph as the formatter lays them out. Comment lines between
+/// imports and a trailing comment on an import's line move with it;
+/// comments above the first import stay put as the file header.
+/// Returns `None` when the block is already organized.
+pub(super) fn organize_imports(source: &str, unused: &[String], uri: &Url) -> Option<CodeAction> {
+    let file = crate::parse_source_silent(source, uri.path()).ok()?;
+    let start = line_start(source, file.uses.first()?.span.start as usize);
+    let mut end = line_end(source, file.uses.last()?.span.end as usize);
+
+    let mut imports: BTreeMap<String, String> = BTreeMap::new();
+    let mut prev = start;
+    for u in &file.uses {
+        let line = line_start(source, u.span.start as usize);
+        let next = line_end(source, u.span.end as usize);
+        let comments: String = source[prev..line]
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| format!("{}\n", l.trim_end()))
+            .collect();
+        let trailing = source[u.span.end as usize..next].trim_end();
+        prev = next;
+
+        let path = u.node.as_dotted();
+        if unused.contains(&path) {
+            continue;
+        }
+        imports
+            .entry(path.clone())
+            .or_insert_with(|| format!("{}use {}{}\n", comments, path, trailing));
+    }
+
+    let organized = imports.into_values().collect::<Vec<_>>().join("\n");
+    if organized.is_empty() && source[end..].starts_with('\n') {
+        // No imports left: drop the blank line that separated the block.
+        end += 1;
+    }
+    if organized == source[start..end] {
+        return None;
+    }
+    let edit = TextEdit {
+        range: Range::new(
+            byte_offset_to_position(source, start),
+            byte_offset_to_position(source, end),
+        ),
+        new_text: organized,
+    };
+    Some(CodeAction {
+        title: "Organize imports".to_string(),
+        kind: Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+        edit: Some(workspace_edit(uri, vec![edit])),
+        ..Default::default()
+    })
+}
+
+/// Byte offset of the start of the line containing `offset`.
+fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Byte offset just past the newline ending the line containing `offset`.
+fn line_end(source: &str, offset: usize) -> usize {
+    source[offset..]
+        .find('\n')
+        .map_or(source.len(), |i| offset + i + 1)
+}
+
+/// Offer to remove the redundant assertions `range` touches, verifying
+/// the document for them.
+pub(super) fn redundant_assertion_actions(
+    source: &str,
+    range: Range,
+    uri: &Url,
+) -> Vec<CodeAction> {
+    let config = VerifyConfig {
+        time_budget: Some(VERIFY_BUDGET),
+        ..VerifyConfig::default()
+    };
+    let fixes = crate::redundant_assertions(source, uri.path(), &config);
+    remove_redundant_assertions(source, &fixes, range, uri)
+}
+
+/// Delete each of `fixes` that `range` touches: the whole line when the
+/// assertion is all it holds, else just the call.
+fn remove_redundant_assertions(
+    source: &str,
+    fixes: &[AssertionFix],
+    range: Range,
+    uri: &Url,
+) -> Vec<CodeAction> {
+    let (Some(from), Some(to)) = (
+        position_to_byte(source, range.start),
+        position_to_byte(source, range.end),
+    ) else {
+        return Vec::new();
+    };
+    fixes
+        .iter()
+        .filter(|fix| fix.span.start as usize <= to && from <= fix.span.end as usize)
+        .map(|fix| {
+            let (mut start, mut end) = (fix.span.start as usize, fix.span.end as usize);
+            let (first, last) = (line_start(source, start), line_end(source, end));
+            if source[first..start].trim().is_empty() && source[end..last].trim().is_empty() {
+                (start, end) = (first, last);
+            }
+            let edit = TextEdit {
+                range: Range::new(
+                    byte_offset_to_position(source, start),
+                    byte_offset_to_position(source, end),
+                ),
+                new_text: String::new(),
+            };
+            CodeAction {
+                title: format!("Remove redundant assertion (saves {})", fix.savings()),
+                kind: Some(CodeActionKind::QUICKFIX),
+                edit: Some(workspace_edit(uri, vec![edit])),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
 /// Build a quickfix CodeAction.
 fn make_quickfix(title: String, uri: &Url, edits: Vec<TextEdit>, diag: &Diagnostic) -> CodeAction {
-    let mut changes = BTreeMap::new();
-    changes.insert(uri.clone(), edits);
-    // Convert BTreeMap to HashMap for WorkspaceEdit
-    let changes: std::collections::HashMap<Url, Vec<TextEdit>> = changes.into_iter().collect();
-
     CodeAction {
         title,
         kind: Some(CodeActionKind::QUICKFIX),
         diagnostics: Some(vec![diag.clone()]),
-        edit: Some(WorkspaceEdit {
-            changes: Some(changes),
-            ..Default::default()
-        }),
+        edit: Some(workspace_edit(uri, edits)),
         is_preferred: Some(true),
         ..Default::default()
     }
 }
 
+/// A workspace edit applying `edits` to one document.
+fn workspace_edit(uri: &Url, edits: Vec<TextEdit>) -> WorkspaceEdit {
+    let mut changes = BTreeMap::new();
+    changes.insert(uri.clone(), edits);
+    // Convert BTreeMap to HashMap for WorkspaceEdit
+    let changes: std::collections::HashMap<Url, Vec<TextEdit>> = changes.into_iter().collect();
+    WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+    }
+}
+
 /// Extract text between first pair of single quotes.
 fn extract_quoted(msg: &str) -> Option<String> {
     let start = msg.find('\'')?;
@@ -280,4 +451,106 @@ mod tests {
         };
         assert!(action.title.contains("Add missing field `y`"));
     }
+
+    #[test]
+    fn rename_reserved_action() {
+        let source =
+            "program test\nfn main() {\n  let trait = pub_read()\n  pub_write(trait + 1)\n}\n";
+        let diag = make_diag(
+            "'trait' is reserved for a future keyword and will stop being a valid name",
+            (2, 6),
+            (2, 11),
+        );
+        let actions = code_actions(source, std::slice::from_ref(&diag), &test_uri());
+        let action = match &actions[0] {
+            CodeActionOrCommand::CodeAction(a) => a,
+            _ => panic!("expected CodeAction"),
+        };
+        assert_eq!(action.title, "Rename 'trait' to 'trait_' in this file");
+        let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&test_uri()];
+        let lines: Vec<u32> = edits.iter().map(|e| e.range.start.line).collect();
+        assert_eq!(lines, vec![2, 3]);
+        assert!(edits.iter().all(|e| e.new_text == "trait_"));
+
+        // No fix when the new name would clash.
+        let taken = source.replace("+ 1", "+ trait_");
+        assert!(code_actions(&taken, &[diag], &test_uri()).is_empty());
+    }
+
+    #[test]
+    fn remove_redundant_assertion_actions() {
+        let source = "program test\nfn main() {\n  let x = pub_read()\n  assert_eq(x + x, x * 2)\n  assert(x == x) // kept comment\n}\n";
+        let actions = redundant_assertion_actions(
+            source,
+            Range::new(Position::new(3, 4), Position::new(4, 2)),
+            &test_uri(),
+        );
+        let edits: Vec<(&str, Range)> = actions
+            .iter()
+            .map(|a| {
+                let edits = &a.edit.as_ref().unwrap().changes.as_ref().unwrap()[&test_uri()];
+                (a.title.as_str(), edits[0].range)
+            })
+            .collect();
+        assert_eq!(
+            edits,
+            vec![
+                (
+                    "Remove redundant assertion (saves 8cc, 8 opst rows)",
+                    Range::new(Position::new(3, 0), Position::new(4, 0)),
+                ),
+                (
+                    "Remove redundant assertion (saves 4cc, 4 opst rows)",
+                    Range::new(Position::new(4, 2), Position::new(4, 16)),
+                ),
+            ]
+        );
+        // Nothing is offered away from the assertions.
+        let elsewhere = Range::new(Position::new(2, 2), Position::new(2, 2));
+        assert!(redundant_assertion_actions(source, elsewhere, &test_uri()).is_empty());
+    }
+
+    fn organized(source: &str, unused: &[&str]) -> Option<(Range, String)> {
+        let unused: Vec<String> = unused.iter().map(|u| u.to_string()).collect();
+        let action = organize_imports(source, &unused, &test_uri())?;
+        assert_eq!(action.kind, Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS));
+        let edits = &action.edit.unwrap().changes.unwrap()[&test_uri()];
+        assert_eq!(edits.len(), 1, "the block is rewritten by one edit");
+        Some((edits[0].range, edits[0].new_text.clone()))
+    }
+
+    #[test]
+    fn organize_imports_sorts_dedups_and_drops_unused() {
+        let source = "program test\n\nuse vm.io.mem\n\nuse std.hash\n\nuse vm.core.field\n\nuse std.hash\n\nfn main() {}\n";
+        let (range, text) = organized(source, &["vm.io.mem"]).unwrap();
+        assert_eq!(text, "use std.hash\n\nuse vm.core.field\n");
+        assert_eq!(range.start, Position::new(2, 0));
+        assert_eq!(range.end, Position::new(9, 0));
+    }
+
+    #[test]
+    fn organize_imports_keeps_comments_with_their_import() {
+        let source = "module m\n\n// header\nuse std.hash\n\n// field helpers\nuse std.crypto // inverse\n\npub fn f() {}\n";
+        let (range, text) = organized(source, &[]).unwrap();
+        assert_eq!(
+            text,
+            "// field helpers\nuse std.crypto // inverse\n\nuse std.hash\n"
+        );
+        assert_eq!(range.start, Position::new(3, 0));
+    }
+
+    #[test]
+    fn organize_imports_removing_every_import_drops_the_gap() {
+        let source = "program test\n\nuse std.hash\n\nfn main() {}\n";
+        let (range, text) = organized(source, &["std.hash"]).unwrap();
+        assert_eq!(text, "");
+        assert_eq!(range.end, Position::new(4, 0));
+    }
+
+    #[test]
+    fn organize_imports_skips_organized_block() {
+        let source = "program test\n\nuse std.hash\n\nuse vm.core.field\n\nfn main() {}\n";
+        assert!(organized(source, &[]).is_none());
+        assert!(organized("program test\n\nfn main() {}\n", &[]).is_none());
+    }
 }
@@ -16,9 +16,13 @@ pub fn builtin_hover(name: &str) -> Option<String> {
         "divine" => "```trident\nfn divine() -> Field\n```\nRead one non-deterministic field element (secret witness).",
         "divine3" => "```trident\nfn divine3() -> (Field, Field, Field)\n```\nRead 3 non-deterministic field elements.",
         "divine5" => "```trident\nfn divine5() -> Digest\n```\nRead 5 non-deterministic field elements (Digest).",
-        "assert" => "```trident\nfn assert(cond: Bool)\n```\nAbort execution if condition is false.",
+        "assert" => "```trident\nfn assert(cond: Bool)\n```\nAbort execution if condition is false. An optional last argument, `assert(cond, \"message\")`, names the failure in the error table.",
         "assert_eq" => "```trident\nfn assert_eq(a: Field, b: Field)\n```\nAbort execution if a != b.",
         "assert_digest_eq" => "```trident\nfn assert_digest_eq(a: Digest, b: Digest)\n```\nAbort execution if digests are not equal.",
+        "debug_assert" => "```trident\nfn debug_assert(cond: Bool)\n```\nLike `assert`, but compiled only under the debug profile; release builds drop the call and its arguments.",
+        "assume" => "```trident\nfn assume(cond: Bool)\n```\nTell the verifier `cond` holds, without a runtime check: no code is emitted. An optional last argument, `assume(cond, \"reason\")`, explains it. `trident audit` lists every assumption as a trust assumption.",
+        "fail" => "```trident\nfn fail(code: Field)\n```\nHalt with `code`, a named nonzero constant. The error table maps the code to the constant's name for off-chain callers.",
+        "trace" => "```trident\nfn trace(label, value)\n```\nDebug profile only: show `value` under `label` (a string literal naming a variable, e.g. `\"balance\"`) when run in the interpreter. Emits a Triton `hint`, not an instruction.",
         "hash" => "```trident\nfn hash(x0..x9: Field) -> Digest\n```\nTip5 hash of 10 field elements.",
         "sponge_init" => "```trident\nfn sponge_init()\n```\nInitialize the Tip5 sponge state.",
         "sponge_absorb" => "```trident\nfn sponge_absorb(x0..x9: Field)\n```\nAbsorb 10 field elements into the sponge.",
@@ -32,6 +36,7 @@ pub fn builtin_hover(name: &str) -> Option<String> {
         "field_add" => "```trident\nfn field_add(a: Field, b: Field) -> Field\n```\nField addition.",
         "field_mul" => "```trident\nfn field_mul(a: Field, b: Field) -> Field\n```\nField multiplication.",
         "inv" => "```trident\nfn inv(a: Field) -> Field\n```\nField multiplicative inverse.",
+        "inverse" => "```trident\nfn inverse(a: Field) -> Field\n```\nField inverse; asserts a != 0 and obliges the verifier to prove it.",
         "neg" => "```trident\nfn neg(a: Field) -> Field\n```\nField negation.",
         "sub" => "```trident\nfn sub(a: Field, b: Field) -> Field\n```\nField subtraction.",
         "ram_read" => "```trident\nfn ram_read(addr: Field) -> Field\n```\nRead one field element from RAM.",
@@ -82,6 +87,10 @@ pub fn builtin_signature(name: &str) -> Option<(Vec<(&'static str, &'static str)
         "assert" => (vec![("cond", "Bool")], ""),
         "assert_eq" => (vec![("a", "Field"), ("b", "Field")], ""),
         "assert_digest_eq" => (vec![("a", "Digest"), ("b", "Digest")], ""),
+        "debug_assert" => (vec![("cond", "Bool")], ""),
+        "assume" => (vec![("cond", "Bool")], ""),
+        "fail" => (vec![("code", "Field")], ""),
+        "trace" => (vec![("label", "str"), ("value", "T")], ""),
         "hash" => (
             vec![
                 ("x0", "Field"),
@@ -123,6 +132,7 @@ pub fn builtin_signature(name: &str) -> Option<(Vec<(&'static str, &'static str)
         "field_add" => (vec![("a", "Field"), ("b", "Field")], "Field"),
         "field_mul" => (vec![("a", "Field"), ("b", "Field")], "Field"),
         "inv" => (vec![("a", "Field")], "Field"),
+        "inverse" => (vec![("a", "Field")], "Field"),
         "neg" => (vec![("a", "Field")], "Field"),
         "sub" => (vec![("a", "Field"), ("b", "Field")], "Field"),
         "ram_read" => (vec![("addr", "Field")], "Field"),
@@ -175,6 +185,10 @@ pub fn builtin_completions() -> Vec<(String, String)> {
         ("assert".into(), "(cond: Bool)".into()),
         ("assert_eq".into(), "(a: Field, b: Field)".into()),
         ("assert_digest_eq".into(), "(a: Digest, b: Digest)".into()),
+        ("debug_assert".into(), "(cond: Bool)".into()),
+        ("assume".into(), "(cond: Bool)".into()),
+        ("fail".into(), "(code: Field)".into()),
+        ("trace".into(), "(label, value)".into()),
         ("hash".into(), "(x0..x9: Field) -> Digest".into()),
         ("sponge_init".into(), "()".into()),
         ("sponge_absorb".into(), "(x0..x9: Field)".into()),
@@ -188,6 +202,7 @@ pub fn builtin_completions() -> Vec<(String, String)> {
         ("field_add".into(), "(a: Field, b: Field) -> Field".into()),
         ("field_mul".into(), "(a: Field, b: Field) -> Field".into()),
         ("inv".into(), "(a: Field) -> Field".into()),
+        ("inverse".into(), "(a: Field) -> Field".into()),
         ("neg".into(), "(a: Field) -> Field".into()),
         ("sub".into(), "(a: Field, b: Field) -> Field".into()),
         ("ram_read".into(), "(addr: Field) -> Field".into()),
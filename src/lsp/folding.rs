@@ -70,7 +70,7 @@ fn collect_block_folds(source: &str, block: &Block, ranges: &mut Vec<FoldingRang
                     collect_block_folds(source, &eb.node, ranges);
                 }
             }
-            Stmt::For { body, .. } => {
+            Stmt::For { body, .. } | Stmt::While { body, .. } => {
                 fold_block(source, body.span, ranges);
                 collect_block_folds(source, &body.node, ranges);
             }
@@ -1,23 +1,44 @@
-//! Inlay hints: inline cost estimates for functions and loops.
+//! Inlay hints: inline cost estimates for functions and loops, the
+//! program's padded height, and per-function verification verdicts.
+
+use std::collections::BTreeMap;
 
 use tower_lsp::lsp_types::*;
 
-use crate::ast::{Item, Stmt};
-use crate::cost::CostAnalyzer;
+use crate::ast::desugar::desugar_file;
+use crate::ast::{File, FileKind, Item, Stmt};
+use crate::cost::{CostAnalyzer, ProgramCost};
+use crate::solve::Verdict;
 
+use super::settings::Settings;
 use super::util::{byte_offset_to_position, format_cost_inline};
 
 /// Compute inlay hints for a source file within the given range.
-pub(super) fn inlay_hints(source: &str, range: Range) -> Vec<InlayHint> {
+pub(super) fn inlay_hints(source: &str, range: Range, settings: &Settings) -> Vec<InlayHint> {
     let file = match crate::parse_source_silent(source, "") {
-        Ok(f) => f,
+        Ok(f) => desugar_file(f, &settings.compile_options().cfg_flags),
         Err(_) => return Vec::new(),
     };
 
-    let mut analyzer = CostAnalyzer::default();
+    let mut analyzer = CostAnalyzer::for_target(&settings.target);
     let program_cost = analyzer.analyze_file(&file);
+    let verdicts = if settings.verification {
+        verdicts(&file)
+    } else {
+        BTreeMap::new()
+    };
 
     let mut hints = Vec::new();
+    if file.kind == FileKind::Program {
+        let hint_pos = byte_offset_to_position(source, file.name.span.end as usize);
+        if in_range(hint_pos, range) {
+            hints.push(padded_height_hint(
+                hint_pos,
+                &program_cost,
+                &settings.target,
+            ));
+        }
+    }
 
     for item in &file.items {
         if let Item::Fn(f) = &item.node {
@@ -51,6 +72,10 @@ pub(super) fn inlay_hints(source: &str, range: Range) -> Vec<InlayHint> {
                         data: None,
                     });
 
+                    if let Some(verdict) = verdicts.get(&f.name.node) {
+                        hints.push(verdict_hint(hint_pos, &f.name.node, verdict));
+                    }
+
                     // Loop hints within this function body
                     if let Some(ref per_iter) = fc.per_iteration {
                         collect_loop_hints(
@@ -70,6 +95,62 @@ pub(super) fn inlay_hints(source: &str, range: Range) -> Vec<InlayHint> {
     hints
 }
 
+/// Verification verdicts of the functions that assert anything.
+fn verdicts(file: &File) -> BTreeMap<String, Verdict> {
+    crate::sym::analyze_all(file)
+        .into_iter()
+        .filter(|(_, system)| !system.constraints.is_empty())
+        .map(|(name, system)| (name, crate::solve::verify(&system).verdict))
+        .collect()
+}
+
+/// Padded trace height and estimated proving time, after the program name.
+fn padded_height_hint(position: Position, cost: &ProgramCost, target: &str) -> InlayHint {
+    let secs = cost.estimated_proving_ns / 1_000_000_000;
+    let tenths = (cost.estimated_proving_ns / 100_000_000) % 10;
+    let headroom = cost.padded_height - cost.total.max_height();
+    InlayHint {
+        position,
+        label: InlayHintLabel::String(format!(
+            " padded height {} | ~{}.{}s proving ",
+            cost.padded_height, secs, tenths
+        )),
+        kind: None,
+        text_edits: None,
+        tooltip: Some(InlayHintTooltip::String(format!(
+            "Padded trace height on {}; {} rows below the next doubling",
+            target, headroom
+        ))),
+        padding_left: Some(true),
+        padding_right: None,
+        data: None,
+    }
+}
+
+/// Verification verdict of one function, after its cost hint.
+fn verdict_hint(position: Position, name: &str, verdict: &Verdict) -> InlayHint {
+    let (label, tooltip) = match verdict {
+        Verdict::Safe => ("✓ verified", "no assertion can fail"),
+        Verdict::StaticViolation => ("✗ violated", "an assertion always fails"),
+        Verdict::RandomViolation => ("✗ counterexample", "random testing found a failing input"),
+        Verdict::BmcViolation => ("✗ counterexample", "model checking found a failing input"),
+        Verdict::Inconclusive => ("? unverified", "a loop runs past the unrolled iterations"),
+    };
+    InlayHint {
+        position,
+        label: InlayHintLabel::String(format!(" {} ", label)),
+        kind: None,
+        text_edits: None,
+        tooltip: Some(InlayHintTooltip::String(format!(
+            "fn {}: {} (`trident verify` for the full report)",
+            name, tooltip
+        ))),
+        padding_left: Some(true),
+        padding_right: None,
+        data: None,
+    }
+}
+
 fn collect_loop_hints(
     source: &str,
     stmts: &[crate::syntax::span::Spanned<Stmt>],
@@ -80,7 +161,7 @@ fn collect_loop_hints(
 ) {
     for stmt in stmts {
         match &stmt.node {
-            Stmt::For { body, .. } => {
+            Stmt::For { body, .. } | Stmt::While { body, .. } => {
                 // Hint at the for statement's opening position
                 let hint_pos = byte_offset_to_position(source, body.span.start as usize);
                 if in_range(hint_pos, range) {
@@ -156,7 +237,7 @@ mod tests {
     #[test]
     fn function_cost_hint_appears() {
         let source = "program test\nfn main() {\n  let x: Field = 1\n  let y: Field = x + x\n}\n";
-        let hints = inlay_hints(source, full_range());
+        let hints = inlay_hints(source, full_range(), &Settings::default());
         assert!(!hints.is_empty());
         // Should have at least one hint for fn main
         let main_hint = hints.iter().find(|h| match &h.tooltip {
@@ -169,11 +250,45 @@ mod tests {
     #[test]
     fn loop_cost_hint_appears() {
         let source = "program test\nfn run() {\n  for i in 0..10 bounded 10 {\n    let x: Field = 1\n  }\n}\n";
-        let hints = inlay_hints(source, full_range());
+        let hints = inlay_hints(source, full_range(), &Settings::default());
         let loop_hint = hints.iter().find(|h| match &h.label {
             InlayHintLabel::String(s) => s.contains("iterations"),
             _ => false,
         });
         assert!(loop_hint.is_some());
     }
+
+    #[test]
+    fn program_header_shows_padded_height() {
+        let source = "program test\nfn main() {\n  pub_write(pub_read())\n}\n";
+        let hints = inlay_hints(source, full_range(), &Settings::default());
+        let header = hints
+            .iter()
+            .find(|h| h.position == Position::new(0, 12))
+            .expect("hint after the program name");
+        match &header.label {
+            InlayHintLabel::String(s) => assert!(s.contains("padded height"), "{}", s),
+            _ => panic!("expected a string label"),
+        }
+    }
+
+    #[test]
+    fn verification_verdicts_follow_the_setting() {
+        let source = "program test\nfn main() {\n  let x = pub_read()\n  assert(x == x + 1)\n}\n";
+        let labels = |settings: &Settings| -> Vec<String> {
+            inlay_hints(source, full_range(), settings)
+                .into_iter()
+                .filter_map(|h| match h.label {
+                    InlayHintLabel::String(s) => Some(s),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert!(labels(&Settings::default()).iter().any(|l| l.contains("✗")));
+        let quiet = Settings {
+            verification: false,
+            ..Settings::default()
+        };
+        assert!(!labels(&quiet).iter().any(|l| l.contains("✗")));
+    }
 }
@@ -1,164 +1,59 @@
-//! Incremental lexing: re-lex only the dirty byte region after an edit,
-//! then splice the new tokens into the cached token list.
+//! Token-level helpers over the cached token list: edit scoping for
+//! incremental re-parsing and bracket matching.
+//!
+//! Re-lexing after an edit lives in the lexer itself (`Lexer::relex`).
 
 use crate::syntax::lexeme::Lexeme;
-use crate::syntax::lexer::{Comment, Lexer};
 use crate::syntax::span::{Span, Spanned};
 
-/// Result of an incremental lex operation.
-pub(super) struct IncrementalLexResult {
-    pub tokens: Vec<Spanned<Lexeme>>,
-    pub comments: Vec<Comment>,
-}
-
-/// Re-lex only the changed region and splice into the old token list.
+/// The bracket token at byte `offset` and its matching partner.
 ///
-/// - `source`: the NEW source text (edit already applied)
-/// - `old_tokens`: previous token list (sorted by span.start)
-/// - `old_comments`: previous comment list
-/// - `edit_start`: byte offset where the edit begins (in the OLD source)
-/// - `old_end`: byte offset where the edit ends (in the OLD source)
-/// - `new_end`: byte offset where the edit ends (in the NEW source)
-pub(super) fn incremental_lex(
-    source: &str,
-    old_tokens: &[Spanned<Lexeme>],
-    old_comments: &[Comment],
-    edit_start: usize,
-    old_end: usize,
-    new_end: usize,
-) -> IncrementalLexResult {
-    let delta: i64 = new_end as i64 - old_end as i64;
-
-    // 1. Find first affected token: first whose span.end > edit_start
-    let first_dirty = old_tokens.partition_point(|t| (t.span.end as usize) <= edit_start);
-
-    // 2. Find resync search start: first token whose span.start >= old_end
-    let resync_search_start = old_tokens.partition_point(|t| (t.span.start as usize) < old_end);
-
-    // 3. Start re-lexing from the earlier of first_dirty's start or edit_start
-    let relex_start = if first_dirty < old_tokens.len() {
-        (old_tokens[first_dirty].span.start as usize).min(edit_start)
+/// `offset` may sit on the bracket or just after it, as a cursor does
+/// after typing one. Returns the spans of the opening and closing
+/// bracket, in source order, or `None` when there is no bracket there or
+/// it is unbalanced.
+pub(super) fn matching_bracket(tokens: &[Spanned<Lexeme>], offset: usize) -> Option<(Span, Span)> {
+    let is_bracket = |i: &usize| bracket(&tokens[*i].node).is_some();
+    // The token under the cursor, else one ending right at it.
+    let at = tokens.partition_point(|t| (t.span.end as usize) <= offset);
+    let under = Some(at).filter(|&i| i < tokens.len() && tokens[i].span.start as usize <= offset);
+    let i = under.filter(is_bracket).or_else(|| {
+        (0..at)
+            .rev()
+            .take_while(|&i| tokens[i].span.end as usize == offset)
+            .find(is_bracket)
+    })?;
+    let (kind, open) = bracket(&tokens[i].node)?;
+    let mut depth = 0usize;
+    let others: Box<dyn Iterator<Item = usize>> = if open {
+        Box::new(i + 1..tokens.len())
     } else {
-        edit_start
+        Box::new((0..i).rev())
     };
-
-    // 4. Lex from relex_start in the new source
-    let mut lexer = Lexer::new_from_offset(source, 0, relex_start);
-    let mut new_tokens_mid: Vec<Spanned<Lexeme>> = Vec::new();
-    let mut resync_idx: Option<usize> = None;
-
-    loop {
-        let tok = lexer.next_spanned();
-        let is_eof = tok.node == Lexeme::Eof;
-
-        if !is_eof {
-            // Try to resynchronize against old tokens past the edit region
-            if let Some(idx) = find_resync(&tok, old_tokens, resync_search_start, delta) {
-                resync_idx = Some(idx);
-                // Don't push the resync token — the suffix starts at idx
-                // (the shifted old token is identical to this new one)
-                break;
-            }
-        }
-
-        new_tokens_mid.push(tok);
-        if is_eof {
-            break;
-        }
-    }
-
-    let mid_comments = lexer.take_comments();
-
-    // 5. Assemble: prefix + middle + suffix
-    let mut result_tokens = Vec::with_capacity(old_tokens.len());
-    let mut result_comments = Vec::new();
-
-    // Prefix: tokens entirely before the edit (exclude Eof)
-    for t in &old_tokens[..first_dirty] {
-        if t.node != Lexeme::Eof {
-            result_tokens.push(t.clone());
-        }
-    }
-
-    // Prefix comments: before edit_start
-    for c in old_comments {
-        if (c.span.end as usize) <= edit_start {
-            result_comments.push(c.clone());
-        }
-    }
-
-    // Middle: newly lexed tokens + comments
-    result_tokens.extend(new_tokens_mid);
-    result_comments.extend(mid_comments);
-
-    if let Some(ri) = resync_idx {
-        // Suffix: shift remaining old tokens by delta
-        for t in &old_tokens[ri..] {
-            result_tokens.push(shift_token(t, delta));
-        }
-        // Suffix comments: after old_end, shifted
-        for c in old_comments {
-            if (c.span.start as usize) >= old_end {
-                result_comments.push(shift_comment(c, delta));
+    for j in others {
+        match bracket(&tokens[j].node) {
+            Some((k, o)) if k == kind && o == open => depth += 1,
+            Some((k, _)) if k == kind && depth > 0 => depth -= 1,
+            Some((k, _)) if k == kind => {
+                let (a, b) = if open { (i, j) } else { (j, i) };
+                return Some((tokens[a].span, tokens[b].span));
             }
-        }
-    }
-    // If no resync, the middle already includes Eof — no suffix needed.
-
-    // Sort comments by position (prefix + middle + suffix may interleave)
-    result_comments.sort_by_key(|c| c.span.start);
-
-    IncrementalLexResult {
-        tokens: result_tokens,
-        comments: result_comments,
-    }
-}
-
-/// Maximum tokens to scan past the expected resync point.
-const RESYNC_WINDOW: usize = 8;
-
-/// Check if `new_tok` matches an old token (after offset shift) past the edit.
-fn find_resync(
-    new_tok: &Spanned<Lexeme>,
-    old_tokens: &[Spanned<Lexeme>],
-    search_start: usize,
-    delta: i64,
-) -> Option<usize> {
-    let limit = (search_start + RESYNC_WINDOW).min(old_tokens.len());
-    for i in search_start..limit {
-        let old = &old_tokens[i];
-        let shifted_start = (old.span.start as i64 + delta) as u32;
-        let shifted_end = (old.span.end as i64 + delta) as u32;
-        if new_tok.span.start == shifted_start
-            && new_tok.span.end == shifted_end
-            && new_tok.node == old.node
-        {
-            return Some(i);
+            _ => {}
         }
     }
     None
 }
 
-fn shift_token(tok: &Spanned<Lexeme>, delta: i64) -> Spanned<Lexeme> {
-    let new_start = tok.span.start as i64 + delta;
-    let new_end = tok.span.end as i64 + delta;
-    debug_assert!(new_start >= 0, "shift_token: start went negative");
-    debug_assert!(new_end >= 0, "shift_token: end went negative");
-    Spanned::new(
-        tok.node.clone(),
-        Span::new(tok.span.file_id, new_start as u32, new_end as u32),
-    )
-}
-
-fn shift_comment(comment: &Comment, delta: i64) -> Comment {
-    let new_start = comment.span.start as i64 + delta;
-    let new_end = comment.span.end as i64 + delta;
-    debug_assert!(new_start >= 0, "shift_comment: start went negative");
-    debug_assert!(new_end >= 0, "shift_comment: end went negative");
-    Comment {
-        text: comment.text.clone(),
-        span: Span::new(comment.span.file_id, new_start as u32, new_end as u32),
-        trailing: comment.trailing,
+/// Bracket kind (0 paren, 1 square, 2 brace) and whether it opens.
+fn bracket(tok: &Lexeme) -> Option<(u8, bool)> {
+    match tok {
+        Lexeme::LParen => Some((0, true)),
+        Lexeme::RParen => Some((0, false)),
+        Lexeme::LBracket => Some((1, true)),
+        Lexeme::RBracket => Some((1, false)),
+        Lexeme::LBrace => Some((2, true)),
+        Lexeme::RBrace => Some((2, false)),
+        _ => None,
     }
 }
 
@@ -200,13 +95,14 @@ pub(super) fn classify_edit_scope(
                 if depth == 0 {
                     // Find the item start: scan backwards past identifiers,
                     // keywords, parens, types, attributes to find the leading
-                    // keyword (fn, struct, event).
+                    // keyword (fn, struct, enum, event).
                     let mut start_idx = i;
                     while start_idx > 0 {
                         let prev = &tokens[start_idx - 1].node;
                         match prev {
                             Lexeme::Fn
                             | Lexeme::Struct
+                            | Lexeme::Enum
                             | Lexeme::Event
                             | Lexeme::Pub
                             | Lexeme::Sec
@@ -263,102 +159,42 @@ pub(super) fn classify_edit_scope(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::syntax::lexer::Lexer;
 
-    fn full_lex(source: &str) -> (Vec<Spanned<Lexeme>>, Vec<Comment>) {
-        let (tokens, comments, _) = Lexer::new(source, 0).tokenize();
-        (tokens, comments)
-    }
-
-    fn token_kinds(tokens: &[Spanned<Lexeme>]) -> Vec<&Lexeme> {
-        tokens.iter().map(|t| &t.node).collect()
-    }
-
-    #[test]
-    fn insert_single_char() {
-        let old_source = "fn main() {}";
-        let new_source = "fn  main() {}"; // inserted a space
-        let (old_tokens, old_comments) = full_lex(old_source);
-        let (expected_tokens, _) = full_lex(new_source);
-
-        // Edit: insert ' ' at position 2 (after "fn")
-        let result = incremental_lex(new_source, &old_tokens, &old_comments, 2, 2, 3);
-
-        assert_eq!(token_kinds(&result.tokens), token_kinds(&expected_tokens));
-        // Verify spans match
-        for (got, want) in result.tokens.iter().zip(expected_tokens.iter()) {
-            assert_eq!(got.span.start, want.span.start);
-            assert_eq!(got.span.end, want.span.end);
-        }
+    fn full_lex(source: &str) -> Vec<Spanned<Lexeme>> {
+        Lexer::new(source, 0).tokenize().0
     }
 
     #[test]
-    fn delete_token() {
-        let old_source = "fn main() { let x: Field = 42 }";
-        let new_source = "fn main() { let x: Field = }";
-        let (old_tokens, old_comments) = full_lex(old_source);
-        let (expected_tokens, _) = full_lex(new_source);
-
-        // Edit: delete "42 " at positions 27..30 → becomes 27..27
-        let result = incremental_lex(new_source, &old_tokens, &old_comments, 27, 30, 27);
-
-        assert_eq!(token_kinds(&result.tokens), token_kinds(&expected_tokens));
+    fn bracket_matching() {
+        let source = "fn f(a: [Field; 2]) {\n    g((a[0]))\n}";
+        let tokens = full_lex(source);
+        let at = |needle: &str| source.find(needle).unwrap();
+        let pair = |offset| {
+            matching_bracket(&tokens, offset).map(|(a, b)| (a.start as usize, b.start as usize))
+        };
+        // On the opening brace, and on the closing one from the cursor after it.
+        assert_eq!(pair(at("{")), Some((at("{"), source.len() - 1)));
+        assert_eq!(pair(source.len()), Some((at("{"), source.len() - 1)));
+        // Nested parens pair up by depth.
+        assert_eq!(pair(at("((")), Some((at("(("), at("))") + 1)));
+        assert_eq!(pair(at("((") + 1), Some((at("((") + 1, at("))"))));
+        assert_eq!(pair(at("[0")), Some((at("[0"), at("0]") + 1)));
+        // Not on a bracket.
+        assert_eq!(pair(at("Field") + 2), None);
     }
 
     #[test]
-    fn edit_at_start() {
-        let old_source = "fn main() {}";
-        let new_source = "pub fn main() {}";
-        let (old_tokens, old_comments) = full_lex(old_source);
-        let (expected_tokens, _) = full_lex(new_source);
-
-        // Edit: insert "pub " at position 0
-        let result = incremental_lex(new_source, &old_tokens, &old_comments, 0, 0, 4);
-
-        assert_eq!(token_kinds(&result.tokens), token_kinds(&expected_tokens));
-    }
-
-    #[test]
-    fn edit_at_end() {
-        let old_source = "fn main() {}";
-        let new_source = "fn main() {}\n";
-        let (old_tokens, old_comments) = full_lex(old_source);
-        let (expected_tokens, _) = full_lex(new_source);
-
-        // Edit: insert newline at position 12
-        let result = incremental_lex(new_source, &old_tokens, &old_comments, 12, 12, 13);
-
-        assert_eq!(token_kinds(&result.tokens), token_kinds(&expected_tokens));
-    }
-
-    #[test]
-    fn multiline_edit() {
-        let old_source = "fn main() {\n  let x: Field = 42\n  let y: Field = 7\n}";
-        let new_source = "fn main() {\n  let z: Field = 99\n}";
-        let (old_tokens, old_comments) = full_lex(old_source);
-        let (expected_tokens, _) = full_lex(new_source);
-
-        // Edit: replace "let x: Field = 42\n  let y: Field = 7" with "let z: Field = 99"
-        let result = incremental_lex(new_source, &old_tokens, &old_comments, 14, 49, 32);
-
-        assert_eq!(token_kinds(&result.tokens), token_kinds(&expected_tokens));
-    }
-
-    #[test]
-    fn comment_preserved() {
-        let old_source = "// header\nfn main() {}";
-        let new_source = "// header\nfn  main() {}"; // insert space
-        let (old_tokens, old_comments) = full_lex(old_source);
-
-        let result = incremental_lex(new_source, &old_tokens, &old_comments, 12, 12, 13);
-
-        assert_eq!(result.comments.len(), 1);
-        assert_eq!(result.comments[0].text, "// header");
+    fn unbalanced_bracket_has_no_match() {
+        let source = "fn f() { g(";
+        let tokens = full_lex(source);
+        assert_eq!(matching_bracket(&tokens, source.len() - 1), None);
     }
 
     #[test]
     fn edit_scope_within_function_body() {
         let source = "program test\nfn main() {\n    let x: Field = 42\n}\n";
-        let (tokens, _) = full_lex(source);
+        let tokens = full_lex(source);
         // Edit inside the function body (e.g., changing "42" to "99")
         let scope = classify_edit_scope(&tokens, 40, 42);
         assert!(
@@ -371,7 +207,7 @@ mod tests {
     #[test]
     fn edit_scope_in_file_header() {
         let source = "program test\nuse vm.io\nfn main() {}\n";
-        let (tokens, _) = full_lex(source);
+        let tokens = full_lex(source);
         // Edit in the program declaration (changing "test")
         let scope = classify_edit_scope(&tokens, 8, 12);
         assert_eq!(scope, EditScope::Full);
@@ -380,7 +216,7 @@ mod tests {
     #[test]
     fn edit_scope_crossing_functions() {
         let source = "program test\nfn foo() {\n    let x: Field = 1\n}\nfn bar() {\n    let y: Field = 2\n}\n";
-        let (tokens, _) = full_lex(source);
+        let tokens = full_lex(source);
         // Edit spanning from inside foo to inside bar
         let scope = classify_edit_scope(&tokens, 30, 70);
         assert_eq!(scope, EditScope::Full);
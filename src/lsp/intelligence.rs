@@ -5,6 +5,8 @@ use std::path::PathBuf;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 
+use crate::config::intrinsics::IntrinsicTable;
+use crate::target::TerrainConfig;
 use crate::types::Ty;
 
 use super::builtins::{builtin_completions, builtin_hover, builtin_signature};
@@ -30,8 +32,22 @@ impl TridentLsp {
 
         // Check builtins first
         if let Some(mut info) = builtin_hover(&word) {
-            let cost = crate::cost::cost_builtin("triton", &word);
-            info = format!("{}\n\n**Cost:** {}", info, format_cost_inline(&cost));
+            let intrinsics = IntrinsicTable::for_target(&TerrainConfig::triton());
+            match intrinsics.get(&word) {
+                Some(intrinsic) => {
+                    info = format!(
+                        "{}\n\n**Cost:** {}\n\n**Stack:** pops {}, pushes {}",
+                        info,
+                        format_cost_inline(&intrinsic.cost),
+                        intrinsic.pops,
+                        intrinsic.pushes
+                    );
+                }
+                None => {
+                    let cost = crate::cost::cost_builtin("triton", &word);
+                    info = format!("{}\n\n**Cost:** {}", info, format_cost_inline(&cost));
+                }
+            }
             return Ok(Some(Hover {
                 contents: HoverContents::Markup(MarkupContent {
                     kind: MarkupKind::Markdown,
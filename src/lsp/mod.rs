@@ -13,6 +13,7 @@ mod references;
 mod selection;
 mod semantic;
 mod server;
+mod settings;
 #[allow(dead_code)] // library API for Rust editor embedding
 mod textobjects;
 pub mod util;
@@ -29,12 +30,14 @@ use util::to_lsp_diagnostic;
 pub(crate) struct TridentLsp {
     pub(crate) client: Client,
     pub(crate) documents: Mutex<BTreeMap<Url, document::DocumentData>>,
+    pub(crate) settings: Mutex<settings::Settings>,
 }
 
 impl TridentLsp {
     async fn publish_diagnostics(&self, uri: Url, source: &str) {
         let file_path = PathBuf::from(uri.path());
-        let result = crate::check_file_in_project(source, &file_path);
+        let options = self.settings().compile_options();
+        let result = crate::check_file_in_project_with_options(source, &file_path, &options);
 
         let diagnostics = match result {
             Ok(()) => Vec::new(),
@@ -48,6 +51,14 @@ impl TridentLsp {
             .publish_diagnostics(uri, diagnostics, None)
             .await;
     }
+
+    /// A snapshot of the current client settings.
+    fn settings(&self) -> settings::Settings {
+        self.settings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
 }
 
 /// Start the LSP server on stdin/stdout.
@@ -58,6 +69,7 @@ pub async fn run_server() {
     let (service, socket) = LspService::new(|client| TridentLsp {
         client,
         documents: Mutex::new(BTreeMap::new()),
+        settings: Mutex::new(settings::Settings::default()),
     });
     Server::new(stdin, stdout, socket).serve(service).await;
 }
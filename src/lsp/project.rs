@@ -37,32 +37,35 @@ impl TridentLsp {
         };
 
         for module in &modules {
-            let parsed = match crate::parse_source_silent(
-                &module.source,
-                &module.file_path.to_string_lossy(),
-            ) {
+            let parsed = match crate::pipeline::parse_module_silent(module) {
                 Ok(f) => f,
                 Err(_) => continue,
             };
 
-            let mod_uri = match Url::from_file_path(&module.file_path) {
-                Ok(u) => u,
-                Err(_) => match Url::parse(&format!("file://{}", module.file_path.display())) {
-                    Ok(u) => u,
-                    Err(_) => continue,
-                },
-            };
             let mod_short = module.name.rsplit('.').next().unwrap_or(&module.name);
 
             for item in &parsed.items {
                 let (name, name_span) = match &item.node {
                     Item::Fn(f) => (f.name.node.clone(), f.name.span),
                     Item::Struct(s) => (s.name.node.clone(), s.name.span),
+                    Item::Enum(e) => (e.name.node.clone(), e.name.span),
                     Item::Const(c) => (c.name.node.clone(), c.name.span),
                     Item::Event(e) => (e.name.node.clone(), e.name.span),
                 };
 
-                let range = span_to_range(&module.source, name_span);
+                // A multi-file program's items live in its several files.
+                let (path, file) = module.file_at(name_span.start as usize);
+                let mod_uri = match Url::from_file_path(path) {
+                    Ok(u) => u,
+                    Err(_) => match Url::parse(&format!("file://{}", path.display())) {
+                        Ok(u) => u,
+                        Err(_) => continue,
+                    },
+                };
+                let mut local = name_span;
+                local.start -= file.start as u32;
+                local.end -= file.start as u32;
+                let range = span_to_range(&module.source[file], local);
                 let qualified = format!("{}.{}", mod_short, name);
                 let full_qualified = format!("{}.{}", module.name, name);
 
@@ -88,15 +91,13 @@ impl TridentLsp {
 
         let mut all_exports = Vec::new();
         for module in &modules {
-            let parsed = match crate::parse_source_silent(
-                &module.source,
-                &module.file_path.to_string_lossy(),
-            ) {
+            let parsed = match crate::pipeline::parse_module_silent(module) {
                 Ok(f) => f,
                 Err(_) => continue,
             };
 
-            let mut tc = TypeChecker::new();
+            let mut tc =
+                TypeChecker::new().with_source_dir(crate::pipeline::source_dir(&module.file_path));
             for exports in &all_exports {
                 tc.import_module(exports);
             }
@@ -120,9 +121,7 @@ impl TridentLsp {
         let modules = resolve_modules(&entry).ok()?;
 
         for module in &modules {
-            let parsed =
-                crate::parse_source_silent(&module.source, &module.file_path.to_string_lossy())
-                    .ok()?;
+            let parsed = crate::pipeline::parse_module_silent(module).ok()?;
 
             let has_fn = parsed.items.iter().any(|item| {
                 if let Item::Fn(f) = &item.node {
@@ -165,6 +164,7 @@ impl TridentLsp {
                 let (name, kind, name_span) = match &item.node {
                     Item::Fn(f) => (f.name.node.clone(), SymbolKind::FUNCTION, f.name.span),
                     Item::Struct(s) => (s.name.node.clone(), SymbolKind::STRUCT, s.name.span),
+                    Item::Enum(e) => (e.name.node.clone(), SymbolKind::ENUM, e.name.span),
                     Item::Const(c) => (c.name.node.clone(), SymbolKind::CONSTANT, c.name.span),
                     Item::Event(e) => (e.name.node.clone(), SymbolKind::EVENT, e.name.span),
                 };
@@ -205,6 +205,7 @@ impl TridentLsp {
                     (f.name.node.clone(), SymbolKind::FUNCTION, Some(sig))
                 }
                 Item::Struct(s) => (s.name.node.clone(), SymbolKind::STRUCT, None),
+                Item::Enum(e) => (e.name.node.clone(), SymbolKind::ENUM, None),
                 Item::Const(c) => (c.name.node.clone(), SymbolKind::CONSTANT, None),
                 Item::Event(e) => (e.name.node.clone(), SymbolKind::EVENT, None),
             };
@@ -213,6 +214,7 @@ impl TridentLsp {
             let selection_range = match &item.node {
                 Item::Fn(f) => span_to_range(source, f.name.span),
                 Item::Struct(s) => span_to_range(source, s.name.span),
+                Item::Enum(e) => span_to_range(source, e.name.span),
                 Item::Const(c) => span_to_range(source, c.name.span),
                 Item::Event(e) => span_to_range(source, e.name.span),
             };
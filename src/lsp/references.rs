@@ -1,7 +1,8 @@
 //! Find references, rename, and document highlight.
 //!
 //! All three features share the same foundation: lex the source and
-//! collect all `Ident(name)` tokens matching a target name.
+//! collect all `Ident(name)` tokens matching a target name. Document
+//! highlight on a bracket instead pairs it with its match.
 
 use std::path::PathBuf;
 
@@ -10,6 +11,7 @@ use tower_lsp::lsp_types::*;
 use crate::syntax::lexeme::Lexeme;
 use crate::syntax::lexer::Lexer;
 
+use super::incremental::matching_bracket;
 use super::project::find_project_entry;
 use super::util::{position_to_byte_offset, span_to_range, word_at_position};
 use super::TridentLsp;
@@ -115,14 +117,25 @@ impl TridentLsp {
     }
 
     pub(super) fn do_document_highlight(&self, uri: &Url, pos: Position) -> Vec<DocumentHighlight> {
-        let source = match self
-            .documents
-            .lock()
-            .unwrap_or_else(|e| e.into_inner())
-            .get(uri)
-        {
-            Some(doc) => doc.source.clone(),
-            None => return Vec::new(),
+        let source = {
+            let docs = self.documents.lock().unwrap_or_else(|e| e.into_inner());
+            let doc = match docs.get(uri) {
+                Some(doc) => doc,
+                None => return Vec::new(),
+            };
+            // On a bracket, highlight it and its partner from the cached tokens.
+            let bracket = position_to_byte_offset(&doc.source, pos)
+                .and_then(|offset| matching_bracket(&doc.tokens, offset));
+            if let Some((open, close)) = bracket {
+                return [open, close]
+                    .into_iter()
+                    .map(|span| DocumentHighlight {
+                        range: span_to_range(&doc.source, span),
+                        kind: Some(DocumentHighlightKind::TEXT),
+                    })
+                    .collect();
+            }
+            doc.source.clone()
         };
 
         let word = word_at_position(&source, pos);
@@ -69,6 +69,16 @@ fn build_selection_at(source: &str, file: &File, pos: Position) -> SelectionRang
                     }
                 }
             }
+            Item::Enum(e) => {
+                if contains(e.name.span, offset) {
+                    scopes.push(e.name.span);
+                }
+                for variant in &e.variants {
+                    if contains(variant.name.span, offset) {
+                        scopes.push(variant.name.span);
+                    }
+                }
+            }
             Item::Event(e) => {
                 if contains(e.name.span, offset) {
                     scopes.push(e.name.span);
@@ -118,7 +128,7 @@ fn collect_block_scopes(block: &Block, offset: u32, scopes: &mut Vec<Span>) {
                     }
                 }
             }
-            Stmt::For { body, .. } => {
+            Stmt::For { body, .. } | Stmt::While { body, .. } => {
                 if contains(body.span, offset) {
                     scopes.push(body.span);
                     collect_block_scopes(&body.node, offset, scopes);
@@ -27,6 +27,7 @@ const TT_NAMESPACE: u32 = 9;
 const TT_EVENT: u32 = 10;
 const TT_MACRO: u32 = 11;
 const TT_ENUM_MEMBER: u32 = 12;
+const TT_STRING: u32 = 13;
 
 // Modifier bit flags — must match TOKEN_MODIFIERS order.
 pub(super) const MOD_DECLARATION: u32 = 1 << 0;
@@ -50,6 +51,7 @@ pub fn token_legend() -> SemanticTokensLegend {
             SemanticTokenType::EVENT,       // 10
             SemanticTokenType::MACRO,       // 11
             SemanticTokenType::ENUM_MEMBER, // 12
+            SemanticTokenType::STRING,      // 13
         ],
         token_modifiers: vec![
             SemanticTokenModifier::DECLARATION,     // bit 0
@@ -187,12 +189,16 @@ fn classify_lexeme(
         | Lexeme::Mut
         | Lexeme::Const
         | Lexeme::Struct
+        | Lexeme::Enum
         | Lexeme::If
         | Lexeme::Else
         | Lexeme::For
+        | Lexeme::While
         | Lexeme::In
         | Lexeme::Bounded
         | Lexeme::Return
+        | Lexeme::Break
+        | Lexeme::Continue
         | Lexeme::Event
         | Lexeme::Reveal
         | Lexeme::Seal
@@ -204,6 +210,7 @@ fn classify_lexeme(
         }
 
         Lexeme::Integer(_) => Some((TT_NUMBER, 0)),
+        Lexeme::Str(_) => Some((TT_STRING, 0)),
 
         Lexeme::Ident(name) => {
             if let Some((kind, mods)) = name_kinds.get(name.as_str()) {
@@ -232,6 +239,9 @@ fn classify_lexeme(
         | Lexeme::Gt
         | Lexeme::Amp
         | Lexeme::Caret
+        | Lexeme::Pipe
+        | Lexeme::AmpAmp
+        | Lexeme::PipePipe
         | Lexeme::SlashPercent
         | Lexeme::Eq
         | Lexeme::Arrow
@@ -283,6 +293,12 @@ pub(super) fn build_name_kinds(file: &File) -> BTreeMap<String, (NameKind, u32)>
                     kinds.insert(field.name.node.clone(), (NameKind::Property, 0));
                 }
             }
+            Item::Enum(e) => {
+                kinds.insert(e.name.node.clone(), (NameKind::Type, MOD_DECLARATION));
+                for variant in &e.variants {
+                    kinds.insert(variant.name.node.clone(), (NameKind::Property, 0));
+                }
+            }
             Item::Event(e) => {
                 kinds.insert(e.name.node.clone(), (NameKind::EventName, MOD_DECLARATION));
                 for field in &e.fields {
@@ -328,6 +344,7 @@ fn collect_block_names(block: &Block, kinds: &mut BTreeMap<String, (NameKind, u3
                 kinds.insert(var.node.clone(), (NameKind::Variable, MOD_DEFINITION));
                 collect_block_names(&body.node, kinds);
             }
+            Stmt::While { body, .. } => collect_block_names(&body.node, kinds),
             Stmt::Match { arms, .. } => {
                 for arm in arms {
                     collect_block_names(&arm.body.node, kinds);
@@ -4,13 +4,21 @@ use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::LanguageServer;
 
+use crate::syntax::lexer::{Lexer, SourceEdit};
+
 use super::document::{compute_line_starts, DocumentData};
-use super::util::{position_to_byte_offset, word_at_position};
-use super::{actions, folding, hints, incremental, indent, selection, semantic, TridentLsp};
+use super::util::{byte_offset_to_position, position_to_byte_offset, word_at_position};
+use super::{actions, folding, hints, indent, selection, semantic, TridentLsp};
 
 #[tower_lsp::async_trait]
 impl LanguageServer for TridentLsp {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        if let Some(options) = &params.initialization_options {
+            self.settings
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .update(options);
+        }
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Kind(
@@ -38,7 +46,15 @@ impl LanguageServer for TridentLsp {
                         },
                     ),
                 ),
-                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![
+                            CodeActionKind::QUICKFIX,
+                            CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+                        ]),
+                        ..Default::default()
+                    },
+                )),
                 references_provider: Some(OneOf::Left(true)),
                 rename_provider: Some(OneOf::Left(true)),
                 document_highlight_provider: Some(OneOf::Left(true)),
@@ -106,13 +122,14 @@ impl LanguageServer for TridentLsp {
 
                     let edit_new_end = edit_start + change.text.len();
 
-                    let result = incremental::incremental_lex(
-                        &new_source,
+                    let result = Lexer::new(&new_source, 0).relex(
                         &doc.tokens,
                         &doc.comments,
-                        edit_start,
-                        edit_old_end,
-                        edit_new_end,
+                        SourceEdit {
+                            start: edit_start,
+                            old_end: edit_old_end,
+                            new_end: edit_new_end,
+                        },
                     );
 
                     doc.source = new_source;
@@ -122,8 +139,7 @@ impl LanguageServer for TridentLsp {
                 } else {
                     // Full replacement (fallback)
                     doc.source = change.text;
-                    let (tokens, comments, _) =
-                        crate::syntax::lexer::Lexer::new(&doc.source, 0).tokenize();
+                    let (tokens, comments, _) = Lexer::new(&doc.source, 0).tokenize();
                     doc.tokens = tokens;
                     doc.comments = comments;
                     doc.line_starts = compute_line_starts(&doc.source);
@@ -142,6 +158,24 @@ impl LanguageServer for TridentLsp {
         self.publish_diagnostics(uri, &diag_source).await;
     }
 
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        self.settings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .update(&params.settings);
+        // Target and profile change what is reported: recheck open files.
+        let open: Vec<(Url, String)> = self
+            .documents
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(uri, doc)| (uri.clone(), doc.source.clone()))
+            .collect();
+        for (uri, source) in open {
+            self.publish_diagnostics(uri, &source).await;
+        }
+    }
+
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
         self.documents
             .lock()
@@ -167,12 +201,10 @@ impl LanguageServer for TridentLsp {
                 if formatted == source {
                     return Ok(None);
                 }
-                let line_count = source.lines().count() as u32;
-                let last_line_len = source.lines().last().map_or(0, |l| l.len()) as u32;
                 Ok(Some(vec![TextEdit {
                     range: Range::new(
                         Position::new(0, 0),
-                        Position::new(line_count, last_line_len),
+                        byte_offset_to_position(&source, source.len()),
                     ),
                     new_text: formatted,
                 }]))
@@ -388,7 +420,32 @@ impl LanguageServer for TridentLsp {
             None => return Ok(None),
         };
         let diags: Vec<_> = params.context.diagnostics;
-        let result = actions::code_actions(&source, &diags, uri);
+        let mut result = actions::code_actions(&source, &diags, uri);
+        let wants_quickfix = params.context.only.as_ref().is_none_or(|kinds| {
+            kinds
+                .iter()
+                .any(|k| CodeActionKind::QUICKFIX.as_str().starts_with(k.as_str()))
+        });
+        if wants_quickfix {
+            for a in actions::redundant_assertion_actions(&source, params.range, uri) {
+                result.push(CodeActionOrCommand::CodeAction(a));
+            }
+        }
+        let wants_organize = params.context.only.as_ref().is_none_or(|kinds| {
+            kinds.iter().any(|k| {
+                CodeActionKind::SOURCE_ORGANIZE_IMPORTS
+                    .as_str()
+                    .starts_with(k.as_str())
+            })
+        });
+        if wants_organize {
+            let path = PathBuf::from(uri.path());
+            let options = self.settings().compile_options();
+            let unused = crate::unused_imports_in_project(&source, &path, &options);
+            if let Some(a) = actions::organize_imports(&source, &unused, uri) {
+                result.push(CodeActionOrCommand::CodeAction(a));
+            }
+        }
         Ok(if result.is_empty() {
             None
         } else {
@@ -454,7 +511,7 @@ impl LanguageServer for TridentLsp {
             Some(doc) => doc.source.clone(),
             None => return Ok(None),
         };
-        let result = hints::inlay_hints(&source, params.range);
+        let result = hints::inlay_hints(&source, params.range, &self.settings());
         Ok(if result.is_empty() {
             None
         } else {
@@ -0,0 +1,97 @@
+//! Client settings: the target and profile diagnostics are checked
+//! against, and whether inlay hints carry verification results.
+//!
+//! Settings arrive as `initializationOptions` and, later, through
+//! `workspace/didChangeConfiguration`. Both accept either a flat object
+//! or one nested under a `trident` key:
+//!
+//! ```json
+//! { "trident": { "target": "triton", "profile": "release", "verification": false } }
+//! ```
+
+use tower_lsp::lsp_types::LSPAny;
+
+use crate::target::TerrainConfig;
+use crate::CompileOptions;
+
+/// Settings for one server session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Settings {
+    /// Target VM whose intrinsics and cost model are used.
+    pub target: String,
+    /// Profile whose cfg flags select the active items.
+    pub profile: String,
+    /// Show per-function verification verdicts as inlay hints.
+    pub verification: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            target: "triton".to_string(),
+            profile: "debug".to_string(),
+            verification: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Apply the keys present in `value`, keeping the others.
+    pub fn update(&mut self, value: &LSPAny) {
+        let value = value.get("trident").unwrap_or(value);
+        if let Some(target) = value.get("target").and_then(|v| v.as_str()) {
+            self.target = target.to_string();
+        }
+        if let Some(profile) = value.get("profile").and_then(|v| v.as_str()) {
+            self.profile = profile.to_string();
+        }
+        if let Some(verification) = value.get("verification").and_then(|v| v.as_bool()) {
+            self.verification = verification;
+        }
+    }
+
+    /// Compile options for the configured profile and target. An unknown
+    /// target falls back to Triton VM.
+    pub fn compile_options(&self) -> CompileOptions {
+        let mut options = CompileOptions::for_profile(&self.profile);
+        if let Ok(config) = TerrainConfig::resolve(&self.target) {
+            options.target_config = config;
+        }
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_accepts_flat_and_nested_objects() {
+        let mut settings = Settings::default();
+        settings.update(&json(r#"{"profile": "release"}"#));
+        assert_eq!(settings.profile, "release");
+        assert_eq!(settings.target, "triton");
+
+        settings.update(&json(
+            r#"{"trident": {"target": "triton", "verification": false}}"#,
+        ));
+        assert_eq!(settings.profile, "release");
+        assert!(!settings.verification);
+    }
+
+    #[test]
+    fn compile_options_follow_the_profile() {
+        let settings = Settings {
+            profile: "release".to_string(),
+            ..Settings::default()
+        };
+        let options = settings.compile_options();
+        assert!(options.cfg_flags.contains("release"));
+        assert!(!options.cfg_flags.contains("debug"));
+        assert_eq!(options.target_config.name, "triton");
+    }
+
+    fn json(json: &str) -> LSPAny {
+        json.parse().unwrap()
+    }
+}
@@ -85,7 +85,7 @@ pub fn text_objects_at(file: &File, comments: &[Comment], offset: u32) -> Vec<Te
                     }
                 }
             }
-            Item::Struct(_) => {
+            Item::Struct(_) | Item::Enum(_) => {
                 objects.push(TextObject {
                     kind: TextObjectKind::Struct,
                     around: item.span,
@@ -143,7 +143,7 @@ fn collect_block_objects(block: &Block, offset: u32, objects: &mut Vec<TextObjec
                     }
                 }
             }
-            Stmt::For { body, .. } => {
+            Stmt::For { body, .. } | Stmt::While { body, .. } => {
                 objects.push(TextObject {
                     kind: TextObjectKind::Loop,
                     around: stmt.span,
@@ -6,7 +6,7 @@ use tower_lsp::lsp_types::*;
 use crate::span::Span;
 
 // Re-export canonical formatters so lsp/mod.rs can import them via util::.
-pub use crate::ast::display::{format_ast_type, format_fn_signature};
+pub use crate::ast::display::format_fn_signature;
 
 pub fn to_lsp_diagnostic(diag: &crate::diagnostic::Diagnostic, source: &str) -> Diagnostic {
     let start = byte_offset_to_position(source, diag.span.start as usize);
@@ -30,6 +30,9 @@ pub fn to_lsp_diagnostic(diag: &crate::diagnostic::Diagnostic, source: &str) ->
     Diagnostic {
         range: Range::new(start, end),
         severity: Some(severity),
+        code: diag
+            .code
+            .map(|code| NumberOrString::String(code.to_string())),
         source: Some("trident".to_string()),
         message,
         ..Default::default()
@@ -1,4 +1,5 @@
 use super::*;
+use crate::ast::display::format_ast_type;
 use tower_lsp::lsp_types::Position;
 
 // --- byte_offset_to_position ---
@@ -172,10 +173,14 @@ fn test_format_fn_signature_no_params() {
         is_pub: false,
         is_test: false,
         is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
         cfg: None,
         intrinsic: None,
         requires: vec![],
         ensures: vec![],
+        assumes: vec![],
         name: crate::span::Spanned::dummy("main".to_string()),
         type_params: vec![],
         params: vec![],
@@ -191,10 +196,14 @@ fn test_format_fn_signature_with_return() {
         is_pub: true,
         is_test: false,
         is_pure: false,
+        is_authenticated: false,
+        auto_range_check: false,
+        entrypoint: None,
         cfg: None,
         intrinsic: None,
         requires: vec![],
         ensures: vec![],
+        assumes: vec![],
         name: crate::span::Spanned::dummy("add".to_string()),
         type_params: vec![],
         params: vec![
@@ -0,0 +1,163 @@
+//! Stages of the neural optimizer on a synthetic TIR function, for
+//! `benches/end_to_end.rs`.
+//!
+//! Each function sets a stage up and returns a closure that runs it
+//! once on the CPU (`NdArray`) backend, so the benchmark times the stage
+//! without reaching into the model types.
+
+use std::hint::black_box;
+
+use burn::backend::NdArray;
+
+use crate::ir::tir::TIROp;
+
+use super::data::tir_graph::TirGraph;
+use super::inference::beam::{beam_search, BeamConfig};
+use super::inference::execute::validate_and_rank;
+use super::model::composite::{NeuralCompilerConfig, NeuralCompilerV2};
+use super::model::vocab::Vocab;
+use super::training::supervised::{graph_to_edges, graph_to_features};
+
+type B = NdArray;
+
+/// A straight-line TIR sequence of `n` ops.
+fn synthetic_tir(n: usize) -> Vec<TIROp> {
+    (0..n)
+        .map(|i| match i % 5 {
+            0 => TIROp::Push(i as u64),
+            1 => TIROp::Add,
+            2 => TIROp::Mul,
+            3 => TIROp::Dup(0),
+            _ => TIROp::Swap(1),
+        })
+        .collect()
+}
+
+/// The full-size model.
+fn full_model(device: &<B as burn::prelude::Backend>::Device) -> NeuralCompilerV2<B> {
+    NeuralCompilerConfig {
+        d_model: 256,
+        d_edge: 32,
+        gnn_layers: 4,
+        decoder_layers: 6,
+        n_heads: 8,
+        d_ff: 1024,
+        max_seq: 256,
+        dropout: 0.0,
+    }
+    .init::<B>(device)
+}
+
+/// A model small enough to run beam search in a benchmark loop.
+fn small_model(device: &<B as burn::prelude::Backend>::Device) -> NeuralCompilerV2<B> {
+    NeuralCompilerConfig {
+        d_model: 64,
+        d_edge: 16,
+        gnn_layers: 2,
+        decoder_layers: 2,
+        n_heads: 4,
+        d_ff: 128,
+        max_seq: 64,
+        dropout: 0.0,
+    }
+    .init::<B>(device)
+}
+
+fn beam_config(k: usize, max_steps: usize) -> BeamConfig {
+    BeamConfig {
+        k,
+        max_steps,
+        ..BeamConfig::default()
+    }
+}
+
+/// Build the graph of an `ops`-op function.
+pub fn graph_build(ops: usize) -> impl FnMut() {
+    let ops = synthetic_tir(ops);
+    move || {
+        black_box(TirGraph::from_tir_ops(black_box(&ops)));
+    }
+}
+
+/// Encode the graph of an `ops`-op function with the full-size GNN.
+pub fn gnn_encoder(ops: usize) -> impl FnMut() {
+    let device = Default::default();
+    let model = full_model(&device);
+    let graph = TirGraph::from_tir_ops(&synthetic_tir(ops));
+    let features = graph_to_features::<B>(&graph, &device);
+    let (src, dst, types) = graph_to_edges::<B>(&graph, &device);
+    move || {
+        black_box(
+            model
+                .encoder
+                .forward(features.clone(), src.clone(), dst.clone(), types.clone()),
+        );
+    }
+}
+
+/// Beam search with `k` beams of up to `max_steps` tokens, encoder
+/// included, on the small model.
+pub fn beam(ops: usize, k: usize, max_steps: usize) -> impl FnMut() {
+    let device = Default::default();
+    let model = small_model(&device);
+    let graph = TirGraph::from_tir_ops(&synthetic_tir(ops));
+    let features = graph_to_features::<B>(&graph, &device);
+    let (src, dst, types) = graph_to_edges::<B>(&graph, &device);
+    let config = beam_config(k, max_steps);
+    move || {
+        black_box(beam_search(
+            &model.encoder,
+            &model.decoder,
+            features.clone(),
+            src.clone(),
+            dst.clone(),
+            types.clone(),
+            &config,
+            0,
+            &device,
+        ));
+    }
+}
+
+/// Validate and rank `candidates` two-token candidates against a
+/// five-instruction baseline.
+pub fn validation(candidates: usize) -> impl FnMut() {
+    let vocab = Vocab::new();
+    let baseline: Vec<String> = ["push 1", "push 2", "add", "push 3", "mul"]
+        .map(String::from)
+        .to_vec();
+    let candidates: Vec<Vec<u32>> = (0..candidates)
+        .map(|i| vec![(i % 10 + 1) as u32, (i % 5 + 1) as u32])
+        .collect();
+    move || {
+        black_box(validate_and_rank(&candidates, &vocab, &baseline, 42));
+    }
+}
+
+/// Graph, features, beam search, and validation of an `ops`-op function
+/// on the small model.
+pub fn end_to_end(ops: usize, k: usize, max_steps: usize) -> impl FnMut() {
+    let device = Default::default();
+    let model = small_model(&device);
+    let vocab = Vocab::new();
+    let ops = synthetic_tir(ops);
+    let baseline: Vec<String> = ["push 1", "push 2", "add"].map(String::from).to_vec();
+    let config = beam_config(k, max_steps);
+    move || {
+        let graph = TirGraph::from_tir_ops(black_box(&ops));
+        let features = graph_to_features::<B>(&graph, &device);
+        let (src, dst, types) = graph_to_edges::<B>(&graph, &device);
+        let result = beam_search(
+            &model.encoder,
+            &model.decoder,
+            features,
+            src,
+            dst,
+            types,
+            &config,
+            0,
+            &device,
+        );
+        black_box(validate_and_rank(&result.sequences, &vocab, &baseline, 42));
+    }
+}
@@ -112,27 +112,6 @@ pub fn available_checkpoints() -> Vec<(CheckpointTag, PathBuf)> {
     found
 }
 
-/// Detect which training stage to run based on existing checkpoints.
-///
-/// - No checkpoints → Stage 1 (supervised)
-/// - Stage1Best exists → Stage 2 (GFlowNet)
-/// - Stage2Latest exists + replay ≥ threshold → Stage 3 (online)
-pub fn detect_stage(replay_count: usize, replay_threshold: usize) -> TrainingStage {
-    let dir = checkpoint_dir();
-
-    let has_stage2 = dir.join("stage2_latest.mpk").exists();
-    let has_stage1 = dir.join("stage1_best.mpk").exists();
-    let has_production = dir.join("production.mpk").exists();
-
-    if has_stage2 && replay_count >= replay_threshold {
-        TrainingStage::Stage3Online
-    } else if has_stage1 || has_production {
-        TrainingStage::Stage2GFlowNet
-    } else {
-        TrainingStage::Stage1Supervised
-    }
-}
-
 /// Which training stage the system should execute.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TrainingStage {
@@ -151,36 +130,10 @@ impl std::fmt::Display for TrainingStage {
     }
 }
 
-/// Promote a checkpoint to production (copy file).
-pub fn promote_to_production(source: CheckpointTag) -> Result<(), String> {
-    let dir = checkpoint_dir();
-    let src = dir.join(source.stem()).with_extension("mpk");
-    let dst = dir.join("production.mpk");
-
-    if !src.exists() {
-        return Err(format!("{} does not exist", src.display()));
-    }
-
-    std::fs::copy(&src, &dst)
-        .map_err(|e| format!("copy {} → {}: {}", src.display(), dst.display(), e))?;
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn detect_stage_returns_valid_stage() {
-        // detect_stage examines real filesystem; just verify it returns a valid stage
-        let stage = detect_stage(0, 100);
-        match stage {
-            TrainingStage::Stage1Supervised
-            | TrainingStage::Stage2GFlowNet
-            | TrainingStage::Stage3Online => {} // all valid
-        }
-    }
-
     #[test]
     fn checkpoint_tag_stems() {
         assert_eq!(CheckpointTag::Stage1Best.stem(), "stage1_best");
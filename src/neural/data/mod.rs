@@ -1,3 +1,2 @@
 pub mod pairs;
-pub mod replay;
 pub mod tir_graph;
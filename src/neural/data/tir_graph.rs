@@ -22,6 +22,7 @@ pub enum EdgeKind {
 
 /// Field type annotation for a TIR node.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)] // TASM's `bfe` and `xfe`
 pub enum FieldType {
     /// Base field element (Goldilocks).
     BFE,
@@ -137,7 +138,7 @@ impl OpKind {
             TIROp::WriteIo(_) => OpKind::WriteIo,
             TIROp::ReadMem(_) => OpKind::ReadMem,
             TIROp::WriteMem(_) => OpKind::WriteMem,
-            TIROp::Assert(_) => OpKind::Assert,
+            TIROp::Assert { .. } => OpKind::Assert,
             TIROp::Hash { .. } => OpKind::Hash,
             TIROp::Reveal { .. } => OpKind::Reveal,
             TIROp::Seal { .. } => OpKind::Seal,
@@ -270,16 +271,19 @@ impl TirGraph {
     }
 
     /// Number of nodes.
+    #[cfg(test)]
     pub fn num_nodes(&self) -> usize {
         self.nodes.len()
     }
 
     /// Number of edges.
+    #[cfg(test)]
     pub fn num_edges(&self) -> usize {
         self.edges.len()
     }
 
     /// Count edges of a specific kind.
+    #[cfg(test)]
     pub fn count_edges(&self, kind: EdgeKind) -> usize {
         self.edges.iter().filter(|(_, _, k)| *k == kind).count()
     }
@@ -301,11 +305,11 @@ fn flatten_ops(ops: &[TIROp], nodes: &mut Vec<TirNode>, edges: &mut Vec<(usize,
             | TIROp::WriteIo(n)
             | TIROp::ReadMem(n)
             | TIROp::WriteMem(n)
-            | TIROp::Assert(n)
             | TIROp::Hint(n) => Some(*n as u64),
-            TIROp::Hash { width } | TIROp::RamRead { width } | TIROp::RamWrite { width } => {
-                Some(*width as u64)
-            }
+            TIROp::Hash { width }
+            | TIROp::RamRead { width }
+            | TIROp::RamWrite { width }
+            | TIROp::Assert { width, .. } => Some(*width as u64),
             TIROp::Reveal { field_count, .. } | TIROp::Seal { field_count, .. } => {
                 Some(*field_count as u64)
             }
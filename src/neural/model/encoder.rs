@@ -236,22 +236,6 @@ impl<B: Backend> GnnEncoder<B> {
 
         (h, global)
     }
-
-    /// Count total parameters.
-    pub fn num_params(&self) -> usize {
-        // node_proj
-        let mut total = NODE_FEATURE_DIM * self.global_proj.weight.dims()[0] / 2; // approximate
-
-        // Each GATv2 layer
-        for layer in &self.layers {
-            let d = layer.ffn.weight.dims()[0];
-            total += d * d * 3; // w_src, w_dst, ffn
-            total += d; // attn
-            total += d * layer.w_edge.weight.dims()[1]; // w_edge
-        }
-
-        total
-    }
 }
 
 // ─── Tests ────────────────────────────────────────────────────────
@@ -67,63 +67,6 @@ pub fn neighborhood_softmax<B: Backend>(
     exp_scores / (edge_sum + 1e-10)
 }
 
-/// Batched graph for packing multiple graphs into one large disconnected graph.
-pub struct BatchedGraph<B: Backend> {
-    pub node_features: Tensor<B, 2>,
-    pub src_indices: Tensor<B, 1, Int>,
-    pub dst_indices: Tensor<B, 1, Int>,
-    pub edge_types: Tensor<B, 1, Int>,
-    pub graph_ids: Tensor<B, 1, Int>,
-    pub num_nodes: usize,
-    pub num_graphs: usize,
-}
-
-/// Batch multiple graphs into a single large disconnected graph.
-pub fn batch_graphs<B: Backend>(
-    node_features_list: &[Tensor<B, 2>],
-    src_indices_list: &[Tensor<B, 1, Int>],
-    dst_indices_list: &[Tensor<B, 1, Int>],
-    edge_types_list: &[Tensor<B, 1, Int>],
-    device: &B::Device,
-) -> BatchedGraph<B> {
-    let num_graphs = node_features_list.len();
-    let mut total_nodes = 0usize;
-
-    let mut all_features = Vec::new();
-    let mut all_src = Vec::new();
-    let mut all_dst = Vec::new();
-    let mut all_edge_types = Vec::new();
-    let mut all_graph_ids = Vec::new();
-
-    for i in 0..num_graphs {
-        let n = node_features_list[i].dims()[0];
-        let offset = total_nodes as i64;
-
-        all_features.push(node_features_list[i].clone());
-
-        let offset_tensor =
-            Tensor::<B, 1, Int>::full([src_indices_list[i].dims()[0]], offset, device);
-        all_src.push(src_indices_list[i].clone() + offset_tensor.clone());
-        all_dst.push(dst_indices_list[i].clone() + offset_tensor);
-        all_edge_types.push(edge_types_list[i].clone());
-
-        let graph_id = Tensor::<B, 1, Int>::full([n], i as i64, device);
-        all_graph_ids.push(graph_id);
-
-        total_nodes += n;
-    }
-
-    BatchedGraph {
-        node_features: Tensor::cat(all_features, 0),
-        src_indices: Tensor::cat(all_src, 0),
-        dst_indices: Tensor::cat(all_dst, 0),
-        edge_types: Tensor::cat(all_edge_types, 0),
-        graph_ids: Tensor::cat(all_graph_ids, 0),
-        num_nodes: total_nodes,
-        num_graphs,
-    }
-}
-
 // ─── Tests ────────────────────────────────────────────────────────
 
 #[cfg(test)]
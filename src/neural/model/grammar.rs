@@ -2,8 +2,9 @@
 //!
 //! Tracks abstract stack state (depth + element types) and produces
 //! a validity mask over the vocabulary at each decoding step.
-//! Used during training (teacher forcing) to precompute masks for the
-//! entire target sequence, and during inference as a CPU fallback.
+//! Used during training (teacher forcing) to precompute the decoder's
+//! stack features for the entire target sequence, and during inference
+//! as a CPU fallback.
 
 use super::grammar_tables::{build_min_stack_depths, build_stack_effects, StackEffect};
 use super::vocab::VOCAB_SIZE;
@@ -18,6 +19,7 @@ pub const TYPE_WINDOW: usize = 8;
 
 /// Element type for abstract type tracking.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)] // TASM's `bfe` and `xfe`
 #[repr(u8)]
 pub enum ElemType {
     BFE = 0,
@@ -53,6 +55,7 @@ impl StackStateMachine {
     }
 
     /// Current stack depth.
+    #[cfg(test)]
     pub fn stack_depth(&self) -> i32 {
         self.depth
     }
@@ -181,16 +184,12 @@ impl StackStateMachine {
     }
 }
 
-/// Precompute masks for an entire target sequence (teacher forcing).
+/// Precompute decoder input for an entire target sequence (teacher forcing).
 ///
 /// Given a sequence of ground-truth tokens, simulates the state machine
-/// and returns the validity mask at each step. Used during training to
-/// apply grammar constraints without GPU-side state tracking.
-///
-/// Also returns stack depths and type encodings for decoder input.
+/// and returns the stack depth and type encoding before each step.
 pub fn precompute_sequence_state(target_tokens: &[u32], initial_depth: i32) -> SequenceState {
     let seq_len = target_tokens.len();
-    let mut masks = Vec::with_capacity(seq_len);
     let mut depths = Vec::with_capacity(seq_len);
     let mut type_states = Vec::with_capacity(seq_len);
 
@@ -198,7 +197,6 @@ pub fn precompute_sequence_state(target_tokens: &[u32], initial_depth: i32) -> S
 
     for &token in target_tokens {
         // Record state BEFORE executing this token
-        masks.push(sm.valid_mask());
         depths.push(sm.depth_for_embedding(65));
         type_states.push(sm.type_encoding());
 
@@ -207,7 +205,6 @@ pub fn precompute_sequence_state(target_tokens: &[u32], initial_depth: i32) -> S
     }
 
     SequenceState {
-        masks,
         depths,
         type_states,
     }
@@ -215,8 +212,6 @@ pub fn precompute_sequence_state(target_tokens: &[u32], initial_depth: i32) -> S
 
 /// Precomputed sequence state for training.
 pub struct SequenceState {
-    /// Validity masks: [seq_len][VOCAB_SIZE], 0.0 or -1e9.
-    pub masks: Vec<Vec<f32>>,
     /// Stack depths: [seq_len], clamped for embedding.
     pub depths: Vec<u32>,
     /// Type encodings: [seq_len][3*TYPE_WINDOW].
@@ -302,23 +297,11 @@ mod tests {
     fn precompute_sequence_lengths() {
         let tokens = vec![3, 4, 83]; // push 1, push 2, add
         let state = precompute_sequence_state(&tokens, 0);
-        assert_eq!(state.masks.len(), 3);
         assert_eq!(state.depths.len(), 3);
         assert_eq!(state.type_states.len(), 3);
-        assert_eq!(state.masks[0].len(), VOCAB_SIZE);
         assert_eq!(state.type_states[0].len(), 3 * TYPE_WINDOW);
     }
 
-    #[test]
-    fn precompute_masks_reflect_state() {
-        let tokens = vec![3, 83]; // push 1, then add
-        let state = precompute_sequence_state(&tokens, 0);
-        // Before push: depth=0, add should be masked
-        assert_eq!(state.masks[0][83], -1e9);
-        // After push: depth=1, add still masked (needs 2)
-        assert_eq!(state.masks[1][83], -1e9);
-    }
-
     #[test]
     fn precompute_depths_advance() {
         let tokens = vec![3, 4, 83]; // push, push, add
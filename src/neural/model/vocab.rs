@@ -215,6 +215,7 @@ impl Vocab {
     }
 
     /// Vocabulary size.
+    #[cfg(test)]
     pub fn size(&self) -> usize {
         self.decode_map.len()
     }
@@ -1,4 +1,3 @@
 pub mod augment;
 pub mod gflownet;
-pub mod online;
 pub mod supervised;
@@ -1,6 +1,6 @@
 //! Stage 1: Supervised pre-training with cross-entropy loss.
 //!
-//! Teacher forcing with grammar mask penalties. Trains the composite
+//! Teacher forcing with grammar-state features. Trains the composite
 //! model (GNN encoder + Transformer decoder) on (TirGraph, TASM) pairs.
 
 use burn::grad_clipping::GradientClippingConfig;
@@ -12,6 +12,7 @@ use crate::neural::data::pairs::TrainingPair;
 use crate::neural::data::tir_graph::NODE_FEATURE_DIM;
 use crate::neural::model::composite::NeuralCompilerV2;
 use crate::neural::model::grammar::precompute_sequence_state;
+use crate::neural::model::vocab::MAX_SEQ;
 
 /// Supervised training configuration.
 pub struct SupervisedConfig {
@@ -23,8 +24,6 @@ pub struct SupervisedConfig {
     pub weight_decay: f64,
     /// Gradient clipping norm.
     pub grad_clip: f32,
-    /// Maximum epochs.
-    pub max_epochs: usize,
     /// Early stopping patience (epochs without improvement).
     pub patience: usize,
 }
@@ -36,7 +35,6 @@ impl Default for SupervisedConfig {
             lr_min: 1e-5,
             weight_decay: 0.01,
             grad_clip: 1.0,
-            max_epochs: 100,
             patience: 3,
         }
     }
@@ -55,14 +53,12 @@ pub fn cosine_lr(config: &SupervisedConfig, epoch: usize, total_epochs: usize) -
 pub struct EpochResult {
     /// Average cross-entropy loss over all pairs.
     pub avg_loss: f32,
-    /// Number of training pairs processed.
-    pub num_pairs: usize,
 }
 
 /// Train one epoch of supervised learning on the given pairs.
 ///
 /// Uses teacher forcing: at each step, the ground-truth previous token
-/// is provided as input. Grammar masks are applied as logit penalties.
+/// is provided as input, with its stack depth and types.
 ///
 /// Returns the model with updated weights and the epoch result.
 pub fn train_epoch<B: burn::tensor::backend::AutodiffBackend>(
@@ -91,8 +87,7 @@ pub fn train_epoch<B: burn::tensor::backend::AutodiffBackend>(
         let memory = node_emb.unsqueeze_dim::<3>(0);
 
         // 3. Prepare decoder inputs (teacher forcing)
-        // Truncate to max_seq=256 to fit position embedding table
-        const MAX_SEQ: usize = 256;
+        // Truncate to MAX_SEQ to fit position embedding table
         let tokens = if pair.target_tokens.len() > MAX_SEQ {
             &pair.target_tokens[..MAX_SEQ]
         } else {
@@ -178,13 +173,7 @@ pub fn train_epoch<B: burn::tensor::backend::AutodiffBackend>(
         total_loss / pairs.len() as f32
     };
 
-    (
-        model,
-        EpochResult {
-            avg_loss,
-            num_pairs: pairs.len(),
-        },
-    )
+    (model, EpochResult { avg_loss })
 }
 
 /// Cross-entropy loss between logits and targets.
@@ -297,13 +286,13 @@ mod tests {
             3u64,
         )];
         let pairs = extract_pairs(&blocks, &vocab);
+        assert_eq!(pairs.len(), 1);
 
         let supervised_config = SupervisedConfig::default();
         let mut optimizer = create_optimizer::<B>(&supervised_config);
 
         let lr = supervised_config.lr;
         let (model, result) = train_epoch(model, &pairs, &mut optimizer, lr, &device);
-        assert_eq!(result.num_pairs, 1);
         assert!(result.avg_loss > 0.0, "loss should be positive");
         assert!(result.avg_loss.is_finite(), "loss should be finite");
 
@@ -20,7 +20,11 @@ use std::collections::BTreeMap;
 
 use crate::ast::*;
 
-const HASH_VERSION: u8 = 1;
+/// Serialization format of the hashed bytes. Version 2 re-hashes a
+/// file's functions until their dependency hashes settle; version 1
+/// stopped after two passes, so callers more than one call deep hashed
+/// stale dependency hashes.
+pub const HASH_VERSION: u8 = 2;
 
 // ─── Content Hash ──────────────────────────────────────────────────
 
@@ -35,7 +39,7 @@ impl ContentHash {
     }
 
     /// Display as full hex.
-    pub fn to_hex(&self) -> String {
+    pub fn to_hex(self) -> String {
         self.0.iter().map(|b| format!("{:02x}", b)).collect()
     }
 
@@ -57,7 +61,7 @@ impl ContentHash {
     }
 
     /// Display as short base-32 (8 characters, 40 bits).
-    pub fn to_short(&self) -> String {
+    pub fn to_short(self) -> String {
         // Take first 5 bytes (40 bits), encode as base-32
         const ALPHABET: &[u8] = b"0123456789abcdefghjkmnpqrstuvwxyz";
         let val = u64::from_be_bytes([
@@ -115,7 +119,7 @@ pub fn hash_file_content(file: &File) -> ContentHash {
         buf.extend_from_slice(name.as_bytes());
         buf.extend_from_slice(&hash.0);
     }
-    ContentHash(crate::poseidon2::hash_bytes(&buf))
+    ContentHash(crate::field::poseidon2::hash_bytes_goldilocks(&buf))
 }
 
 /// Parse a single hex digit (0-9, a-f, A-F) to its numeric value.
@@ -46,6 +46,15 @@ pub(super) const TAG_XFIELD_MUL: u8 = 0x21;
 pub(super) const TAG_ASM: u8 = 0x22;
 pub(super) const TAG_EXPR_STMT: u8 = 0x23;
 pub(super) const TAG_STRUCT_PAT: u8 = 0x24;
+pub(super) const TAG_STR_LIT: u8 = 0x25;
+pub(super) const TAG_STRUCT_BASE: u8 = 0x26;
+pub(super) const TAG_BIT_OR: u8 = 0x27;
+pub(super) const TAG_AND: u8 = 0x28;
+pub(super) const TAG_OR: u8 = 0x29;
+pub(super) const TAG_VARIANT_PAT: u8 = 0x2A;
+pub(super) const TAG_WHILE: u8 = 0x2B;
+pub(super) const TAG_BREAK: u8 = 0x2C;
+pub(super) const TAG_CONTINUE: u8 = 0x2D;
 
 // Type tags
 pub(super) const TAG_TY_FIELD: u8 = 0x80;
@@ -177,7 +186,7 @@ impl Normalizer {
     pub fn hash_fn(func: &FnDef, fn_hashes: BTreeMap<String, ContentHash>) -> ContentHash {
         let mut normalizer = Normalizer::new().with_fn_hashes(fn_hashes);
         let bytes = normalizer.normalize_fn(func);
-        ContentHash(crate::poseidon2::hash_bytes(&bytes))
+        ContentHash(crate::field::poseidon2::hash_bytes_goldilocks(&bytes))
     }
 
     /// Hash all functions in a file.
@@ -190,23 +199,32 @@ impl Normalizer {
                 let mut normalizer = Normalizer::new();
                 normalizer.fn_hashes.clone_from(&fn_hashes);
                 let bytes = normalizer.normalize_fn(func);
-                let hash = ContentHash(crate::poseidon2::hash_bytes(&bytes));
+                let hash = ContentHash(crate::field::poseidon2::hash_bytes_goldilocks(&bytes));
                 fn_hashes.insert(func.name.node.clone(), hash);
             }
         }
 
-        // Second pass: re-hash with complete dependency info (single clone)
-        let mut stable = BTreeMap::new();
-        let mut normalizer = Normalizer::new().with_fn_hashes(fn_hashes);
-        for item in &file.items {
-            if let Item::Fn(func) = &item.node {
-                let bytes = normalizer.normalize_fn(func);
-                let hash = ContentHash(crate::poseidon2::hash_bytes(&bytes));
-                stable.insert(func.name.node.clone(), hash);
+        // Re-hash until every dependency hash is final. Without recursion
+        // (rejected by the typechecker) call depth is bounded by the number
+        // of functions, so the fixpoint is reached within that many passes.
+        let max_passes = fn_hashes.len() + 1;
+        for _ in 0..max_passes {
+            let mut stable = BTreeMap::new();
+            let mut normalizer = Normalizer::new().with_fn_hashes(fn_hashes.clone());
+            for item in &file.items {
+                if let Item::Fn(func) = &item.node {
+                    let bytes = normalizer.normalize_fn(func);
+                    let hash = ContentHash(crate::field::poseidon2::hash_bytes_goldilocks(&bytes));
+                    stable.insert(func.name.node.clone(), hash);
+                }
+            }
+            if stable == fn_hashes {
+                break;
             }
+            fn_hashes = stable;
         }
 
-        stable
+        fn_hashes
     }
 
     // ─── Serialization Helpers ─────────────────────────────────
@@ -124,6 +124,8 @@ impl Normalizer {
                     self.write_u8(0);
                 }
             }
+            Stmt::Break => self.write_u8(TAG_BREAK),
+            Stmt::Continue => self.write_u8(TAG_CONTINUE),
             Stmt::For {
                 var,
                 start,
@@ -137,10 +139,25 @@ impl Normalizer {
                 self.write_u16(idx);
                 self.serialize_expr(&start.node);
                 self.serialize_expr(&end.node);
-                self.write_u32(bound.unwrap_or(0) as u32);
+                // A literal bound keeps the encoding it had before bounds
+                // could be expressions.
+                match bound.as_ref().map(|b| (b, integer_literal(&b.node))) {
+                    None => self.write_u32(0),
+                    Some((_, Some(n))) => self.write_u32(n as u32),
+                    Some((b, None)) => {
+                        self.write_u32(u32::MAX);
+                        self.serialize_expr(&b.node);
+                    }
+                }
                 self.serialize_block(&body.node);
                 self.env.restore(saved);
             }
+            Stmt::While { cond, bound, body } => {
+                self.write_u8(TAG_WHILE);
+                self.serialize_expr(&cond.node);
+                self.serialize_expr(&bound.node);
+                self.serialize_block(&body.node);
+            }
             Stmt::Expr(expr) => {
                 self.write_u8(TAG_EXPR_STMT);
                 self.serialize_expr(&expr.node);
@@ -193,14 +210,7 @@ impl Normalizer {
 
     pub(crate) fn serialize_match_pattern(&mut self, pattern: &MatchPattern) {
         match pattern {
-            MatchPattern::Literal(Literal::Integer(n)) => {
-                self.write_u8(TAG_FIELD_LIT);
-                self.write_u64(*n);
-            }
-            MatchPattern::Literal(Literal::Bool(b)) => {
-                self.write_u8(TAG_BOOL_LIT);
-                self.write_u8(if *b { 1 } else { 0 });
-            }
+            MatchPattern::Literal(lit) => self.serialize_literal(lit),
             MatchPattern::Wildcard => {
                 self.write_u8(0xFF); // wildcard marker
             }
@@ -215,20 +225,21 @@ impl Normalizer {
                             self.write_u8(0x01);
                             self.write_str(v);
                         }
-                        FieldPattern::Literal(Literal::Integer(n)) => {
-                            self.write_u8(TAG_FIELD_LIT);
-                            self.write_u64(*n);
-                        }
-                        FieldPattern::Literal(Literal::Bool(b)) => {
-                            self.write_u8(TAG_BOOL_LIT);
-                            self.write_u8(if *b { 1 } else { 0 });
-                        }
+                        FieldPattern::Literal(lit) => self.serialize_literal(lit),
                         FieldPattern::Wildcard => {
                             self.write_u8(0xFF);
                         }
                     }
                 }
             }
+            MatchPattern::Variant { path, bindings } => {
+                self.write_u8(TAG_VARIANT_PAT);
+                self.write_str(&path.node.as_dotted());
+                self.write_u32(bindings.len() as u32);
+                for b in bindings {
+                    self.write_str(&b.node);
+                }
+            }
         }
     }
 
@@ -260,16 +271,26 @@ impl Normalizer {
 
     // ─── Expression Serialization ──────────────────────────────
 
-    pub(crate) fn serialize_expr(&mut self, expr: &Expr) {
-        match expr {
-            Expr::Literal(Literal::Integer(n)) => {
+    fn serialize_literal(&mut self, lit: &Literal) {
+        match lit {
+            Literal::Integer(n) => {
                 self.write_u8(TAG_FIELD_LIT);
                 self.write_u64(*n);
             }
-            Expr::Literal(Literal::Bool(b)) => {
+            Literal::Bool(b) => {
                 self.write_u8(TAG_BOOL_LIT);
                 self.write_u8(if *b { 1 } else { 0 });
             }
+            Literal::Str(s) => {
+                self.write_u8(TAG_STR_LIT);
+                self.write_str(s);
+            }
+        }
+    }
+
+    pub(crate) fn serialize_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(lit) => self.serialize_literal(lit),
             Expr::Var(name) => {
                 if let Some(idx) = self.env.lookup(name) {
                     self.write_u8(TAG_VAR);
@@ -289,6 +310,9 @@ impl Normalizer {
                     BinOp::Lt => TAG_LT,
                     BinOp::BitAnd => TAG_BIT_AND,
                     BinOp::BitXor => TAG_BIT_XOR,
+                    BinOp::BitOr => TAG_BIT_OR,
+                    BinOp::And => TAG_AND,
+                    BinOp::Or => TAG_OR,
                     BinOp::DivMod => TAG_DIV_MOD,
                     BinOp::XFieldMul => TAG_XFIELD_MUL,
                 };
@@ -343,7 +367,7 @@ impl Normalizer {
                 self.serialize_expr(&expr.node);
                 self.serialize_expr(&index.node);
             }
-            Expr::StructInit { path, fields } => {
+            Expr::StructInit { path, fields, base } => {
                 self.write_u8(TAG_STRUCT_INIT);
                 self.write_str(&path.node.as_dotted());
                 // Sort fields alphabetically for canonical order
@@ -354,6 +378,10 @@ impl Normalizer {
                     self.write_str(&name.node);
                     self.serialize_expr(&val.node);
                 }
+                if let Some(base) = base {
+                    self.write_u8(TAG_STRUCT_BASE);
+                    self.serialize_expr(&base.node);
+                }
             }
             Expr::ArrayInit(elems) => {
                 self.write_u8(TAG_ARRAY_INIT);
@@ -100,3 +100,22 @@ fn test_empty_fn_hash() {
     let h = hash_file(&f);
     assert_ne!(h["main"], ContentHash::zero());
 }
+
+#[test]
+fn test_hash_version_pins_known_hashes() {
+    // Changing what gets hashed must bump HASH_VERSION (stored codebases
+    // are re-hashed by `trident store migrate`) and re-pin these values.
+    let f = parse_file(
+        "program test\nfn add(a: Field, b: Field) -> Field { a + b }\nfn twice(x: Field) -> Field { add(x, x) }\nfn main() { pub_write(twice(pub_read())) }\n",
+    );
+    let hashes = hash_file(&f);
+    assert_eq!(HASH_VERSION, 2);
+    assert_eq!(
+        hashes["add"].to_hex(),
+        "bf58cb8ec6de405e29a8c6b68a44a56863012e5190262e9f42868b0b677bae5c"
+    );
+    assert_eq!(
+        hashes["main"].to_hex(),
+        "d1d6cb6c4f419b06a36c95e6455366578a444dd3a43f7a0a8e243b9f7fa58046"
+    );
+}
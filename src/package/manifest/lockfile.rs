@@ -65,14 +65,11 @@ pub fn save_lockfile(path: &Path, lockfile: &Lockfile) -> Result<(), String> {
     out.push_str("# trident.lock — DO NOT EDIT MANUALLY\n");
     out.push_str("[lock]\n");
 
-    // Sort entries for deterministic output.
-    let mut entries: Vec<_> = lockfile.locked.iter().collect();
-    entries.sort_by_key(|(k, _)| (*k).clone());
-
-    for (name, dep) in entries {
+    // `locked` is ordered by name, so the output is deterministic.
+    for dep in lockfile.locked.values() {
         out.push_str(&format!(
             "{} = {{ hash = \"{}\", source = \"{}\" }}\n",
-            name, dep.hash, dep.source,
+            dep.name, dep.hash, dep.source,
         ));
     }
 
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+use crate::config::schema::interpolate_env;
 use crate::hash::ContentHash;
 
 use super::{Dependency, Manifest};
@@ -11,7 +12,7 @@ use super::{Dependency, Manifest};
 ///
 /// Handles three forms:
 ///   name = "64hexchars"                          -> Hash dep
-///   name = { name = "x", registry = "url" }      -> Registry dep
+///   name = { name = "x", registry = "url" }      -> Registry dep (`${VAR}` expanded)
 ///   name = { path = "relative/path" }             -> Path dep
 pub fn parse_dependencies(toml_content: &str) -> Manifest {
     let mut deps: BTreeMap<String, Dependency> = BTreeMap::new();
@@ -51,7 +52,12 @@ pub fn parse_dependencies(toml_content: &str) -> Manifest {
                         },
                     );
                 } else if let Some(reg_name) = fields.get("name") {
-                    let registry = fields.get("registry").cloned().unwrap_or_default();
+                    // `${VAR}` references expand from the environment; an
+                    // unset variable is reported by schema validation.
+                    let registry = fields
+                        .get("registry")
+                        .map(|r| interpolate_env(r).unwrap_or_else(|_| r.clone()))
+                        .unwrap_or_default();
                     deps.insert(
                         key.to_string(),
                         Dependency::Registry {
@@ -2,7 +2,6 @@ pub mod cache;
 #[allow(dead_code)]
 pub mod hash;
 pub mod manifest;
-pub mod poseidon2;
 pub mod registry;
 pub mod store;
 
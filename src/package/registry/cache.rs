@@ -0,0 +1,99 @@
+//! On-disk cache of registry responses.
+//!
+//! A definition pulled by hash never changes, so its response body is kept
+//! under the hash and served without asking the registry again. Name
+//! lookups and searches can change; their bodies are kept with the `ETag`
+//! the registry sent and revalidated with `If-None-Match`, so a `304 Not
+//! Modified` costs one round trip and no body.
+//!
+//! Layout (under `$TRIDENT_CACHE_DIR` or `~/.trident/cache/`):
+//! ```text
+//! registry/
+//! ├── defs/
+//! │   └── <hash>.json
+//! └── etag/
+//!     └── <blake3 of registry URL and request path>
+//! ```
+
+use std::path::PathBuf;
+
+use crate::hash::ContentHash;
+
+/// Cached registry responses in one directory.
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+/// A response body kept with the `ETag` it was served with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tagged {
+    pub etag: String,
+    pub body: String,
+}
+
+impl ResponseCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The cache in the user's cache directory, if there is one.
+    pub fn default_location() -> Option<Self> {
+        crate::package::cache::cache_dir().map(|dir| Self::new(dir.join("registry")))
+    }
+
+    /// The body of the definition `hash` as pulled before.
+    pub fn definition(&self, hash: &ContentHash) -> Option<String> {
+        std::fs::read_to_string(self.definition_path(hash)).ok()
+    }
+
+    /// Keep the body of the definition `hash`. Failures only cost a
+    /// later request, so they are ignored.
+    pub fn store_definition(&self, hash: &ContentHash, body: &str) {
+        write_entry(self.definition_path(hash), body);
+    }
+
+    /// The body last served for `key` and its `ETag`.
+    pub fn tagged(&self, key: &str) -> Option<Tagged> {
+        let text = std::fs::read_to_string(self.tagged_path(key)).ok()?;
+        let (etag, body) = text.split_once('\n')?;
+        Some(Tagged {
+            etag: etag.to_string(),
+            body: body.to_string(),
+        })
+    }
+
+    /// Keep the body served for `key` with its `ETag`.
+    pub fn store_tagged(&self, key: &str, etag: &str, body: &str) {
+        if !etag.contains('\n') {
+            write_entry(self.tagged_path(key), &format!("{}\n{}", etag, body));
+        }
+    }
+
+    fn definition_path(&self, hash: &ContentHash) -> PathBuf {
+        self.dir
+            .join("defs")
+            .join(format!("{}.json", hash.to_hex()))
+    }
+
+    fn tagged_path(&self, key: &str) -> PathBuf {
+        self.dir
+            .join("etag")
+            .join(blake3::hash(key.as_bytes()).to_hex().as_str())
+    }
+}
+
+/// Write a cache entry through a temporary file, so a reader never sees
+/// half of one.
+fn write_entry(path: PathBuf, contents: &str) {
+    let Some(dir) = path.parent() else {
+        return;
+    };
+    if std::fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    if std::fs::write(&tmp, contents).is_ok() && std::fs::rename(&tmp, &path).is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+}
@@ -3,38 +3,91 @@
 /// from malicious or misconfigured servers.
 const MAX_RESPONSE_SIZE: usize = 10 * 1024 * 1024;
 
+/// Longest long-poll wait asked of the registry, leaving headroom under
+/// the 30-second read timeout.
+const MAX_EVENTS_WAIT_SECS: u64 = 25;
+
 use std::io::{BufRead, BufReader, Write};
 use std::net::TcpStream;
 
+use super::cache::ResponseCache;
+use super::events::EventBatch;
+use super::http::read_head;
 use super::json::{
-    extract_json_bool, extract_json_string, format_publish_json, parse_pull_response,
-    parse_search_response,
+    extract_json_bool, extract_json_string, format_publish_json, format_template_json, json_escape,
+    parse_events_response, parse_pull_response, parse_scope_response, parse_search_response,
+    parse_template_response,
 };
+use super::scope::{validate_name, validate_scope, ScopeInfo};
+use super::template::ProjectTemplate;
 use super::types::*;
+use crate::hash::ContentHash;
 
 pub struct RegistryClient {
     base_url: String,
+    /// Where responses are cached; `None` to always ask the registry.
+    cache: Option<ResponseCache>,
+    /// Ignore cached responses (fresh ones are still cached).
+    refresh: bool,
+    /// Bearer token sent with every write, proving scope ownership.
+    token: Option<String>,
 }
 
 impl RegistryClient {
-    /// Create a new registry client.
+    /// Create a new registry client, caching responses in the user's
+    /// cache directory.
     pub fn new(url: &str) -> Self {
         Self {
             base_url: url.trim_end_matches('/').to_string(),
+            cache: ResponseCache::default_location(),
+            refresh: false,
+            token: None,
         }
     }
 
+    /// Cache responses in `cache` instead, or not at all.
+    pub fn with_cache(mut self, cache: Option<ResponseCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Ask the registry even for responses that are cached.
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Authenticate writes with `token`.
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Get the registry token from `$TRIDENT_REGISTRY_TOKEN`, if set.
+    pub fn default_token() -> Option<String> {
+        std::env::var("TRIDENT_REGISTRY_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty())
+    }
+
     /// Get the default registry URL from environment or config.
     pub fn default_url() -> String {
         std::env::var("TRIDENT_REGISTRY_URL")
             .unwrap_or_else(|_| "http://127.0.0.1:8090".to_string())
     }
 
-    /// Publish a definition to the registry.
+    /// Publish a definition to the registry. Binding a scoped name needs a
+    /// token owning its scope.
     pub fn publish(&self, def: &PublishedDefinition) -> Result<PublishResult, String> {
+        if let Some(name) = &def.name {
+            validate_name(name)?;
+        }
         let body = format_publish_json(def);
         let response = self.http_post("/api/v1/definitions", &body)?;
 
+        if let (401 | 403, Some(name)) = (response.status, &def.name) {
+            return Err(self.denied(name));
+        }
         if response.status >= 400 {
             return Err(format!(
                 "publish failed ({}): {}",
@@ -49,8 +102,16 @@ impl RegistryClient {
         })
     }
 
-    /// Pull a definition from the registry by hash.
+    /// Pull a definition from the registry by hash. The response for a
+    /// hash never changes, so one pulled before is read from the cache.
     pub fn pull(&self, hash: &str) -> Result<PullResult, String> {
+        let cache = self.cache.as_ref().zip(ContentHash::from_hex(hash));
+        if let Some((cache, key)) = cache.filter(|_| !self.refresh) {
+            if let Some(body) = cache.definition(&key) {
+                return Ok(parse_pull_response(&body));
+            }
+        }
+
         let path = format!("/api/v1/definitions/{}", hash);
         let response = self.http_get(&path)?;
 
@@ -64,13 +125,20 @@ impl RegistryClient {
             ));
         }
 
-        Ok(parse_pull_response(&response.body))
+        let pulled = parse_pull_response(&response.body);
+        if let Some((cache, key)) = cache {
+            if ContentHash::from_hex(&pulled.hash) == Some(key) {
+                cache.store_definition(&key, &response.body);
+            }
+        }
+        Ok(pulled)
     }
 
-    /// Pull a definition by name.
+    /// Pull a definition by name. The name is one path segment, so a
+    /// scoped name (`@org/merkle.verify`) is sent percent-encoded.
     pub fn pull_by_name(&self, name: &str) -> Result<PullResult, String> {
-        let path = format!("/api/v1/names/{}", name);
-        let response = self.http_get(&path)?;
+        let path = format!("/api/v1/names/{}", url_encode(name));
+        let response = self.get_revalidated(&path)?;
 
         if response.status == 404 {
             return Err(format!("name '{}' not found in registry", name));
@@ -85,10 +153,63 @@ impl RegistryClient {
         Ok(parse_pull_response(&response.body))
     }
 
+    /// Publish a project template under its name. Like a definition's
+    /// name, a scoped template name needs a token owning the scope.
+    pub fn publish_template(&self, template: &ProjectTemplate) -> Result<PublishResult, String> {
+        validate_name(&template.name)?;
+        let body = format_template_json(template);
+        let response = self.http_post("/api/v1/templates", &body)?;
+
+        if matches!(response.status, 401 | 403) {
+            return Err(self.denied(&template.name));
+        }
+        if response.status >= 400 {
+            return Err(format!(
+                "template publish failed ({}): {}",
+                response.status, response.body
+            ));
+        }
+
+        Ok(PublishResult {
+            hash: extract_json_string(&response.body, "hash"),
+            created: extract_json_bool(&response.body, "created"),
+            name_bound: extract_json_bool(&response.body, "name_bound"),
+        })
+    }
+
+    /// Pull a project template by name, checking its files against the
+    /// hash the registry reports.
+    pub fn pull_template(&self, name: &str) -> Result<ProjectTemplate, String> {
+        let path = format!("/api/v1/templates/{}", url_encode(name));
+        let response = self.get_revalidated(&path)?;
+
+        if response.status == 404 {
+            return Err(format!("template '{}' not found in registry", name));
+        }
+        if response.status >= 400 {
+            return Err(format!(
+                "template pull failed ({}): {}",
+                response.status, response.body
+            ));
+        }
+
+        let (template, hash) = parse_template_response(&response.body);
+        if template.files.is_empty() {
+            return Err(format!("template '{}' has no files", name));
+        }
+        if template.hash() != hash {
+            return Err(format!(
+                "template '{}' does not match its hash {}",
+                name, hash
+            ));
+        }
+        Ok(template)
+    }
+
     /// Search the registry.
     pub fn search(&self, query: &str) -> Result<Vec<SearchResult>, String> {
         let path = format!("/api/v1/search?q={}", url_encode(query));
-        let response = self.http_get(&path)?;
+        let response = self.get_revalidated(&path)?;
 
         if response.status >= 400 {
             return Err(format!(
@@ -103,7 +224,7 @@ impl RegistryClient {
     /// Search by type signature.
     pub fn search_by_type(&self, type_sig: &str) -> Result<Vec<SearchResult>, String> {
         let path = format!("/api/v1/search?type={}", url_encode(type_sig));
-        let response = self.http_get(&path)?;
+        let response = self.get_revalidated(&path)?;
 
         if response.status >= 400 {
             return Err(format!(
@@ -118,7 +239,7 @@ impl RegistryClient {
     /// Search by tag.
     pub fn search_by_tag(&self, tag: &str) -> Result<Vec<SearchResult>, String> {
         let path = format!("/api/v1/search?tag={}", url_encode(tag));
-        let response = self.http_get(&path)?;
+        let response = self.get_revalidated(&path)?;
 
         if response.status >= 400 {
             return Err(format!(
@@ -130,57 +251,177 @@ impl RegistryClient {
         Ok(parse_search_response(&response.body))
     }
 
-    /// Check registry health.
-    pub fn health(&self) -> Result<bool, String> {
-        let response = self.http_get("/health")?;
-        Ok(response.status == 200)
-    }
+    /// Search for definitions whose published cost stays within every
+    /// `(table, max rows)` limit, cheapest first. `query` narrows by name
+    /// when not empty. Results are checked against the limits here too,
+    /// so a definition without a published cost never matches.
+    pub fn search_by_cost(
+        &self,
+        query: &str,
+        limits: &[(String, u64)],
+    ) -> Result<Vec<SearchResult>, String> {
+        let mut params: Vec<String> = limits
+            .iter()
+            .map(|(table, max)| format!("max_{}={}", url_encode(table), max))
+            .collect();
+        if !query.is_empty() {
+            params.insert(0, format!("q={}", url_encode(query)));
+        }
+        let path = format!("/api/v1/search?{}", params.join("&"));
+        let response = self.get_revalidated(&path)?;
 
-    /// Get registry statistics.
-    pub fn stats(&self) -> Result<String, String> {
-        let response = self.http_get("/api/v1/stats")?;
         if response.status >= 400 {
             return Err(format!(
-                "stats failed ({}): {}",
+                "search failed ({}): {}",
                 response.status, response.body
             ));
         }
-        Ok(response.body)
+
+        let mut results = parse_search_response(&response.body);
+        results.retain(|r| r.within(limits));
+        results.sort_by_key(|r| r.max_rows());
+        Ok(results)
+    }
+
+    /// Claim an unowned scope; the client's token becomes its first owner.
+    pub fn claim_scope(&self, scope: &str) -> Result<ScopeInfo, String> {
+        let scope = validate_scope(scope)?;
+        let body = format!("{{\"scope\":{}}}", json_escape(scope));
+        let response = self.http_post("/api/v1/scopes", &body)?;
+        self.scope_result(scope, "claim", response)
+    }
+
+    /// Look up who owns a scope.
+    pub fn scope(&self, scope: &str) -> Result<ScopeInfo, String> {
+        let scope = validate_scope(scope)?;
+        let response = self.http_get(&format!("/api/v1/scopes/{}", scope))?;
+        self.scope_result(scope, "lookup", response)
+    }
+
+    /// Add the token with `member` id to a scope's owners.
+    pub fn grant_scope(&self, scope: &str, member: &str) -> Result<ScopeInfo, String> {
+        let scope = validate_scope(scope)?;
+        let path = format!("/api/v1/scopes/{}/owners", scope);
+        let body = format!("{{\"owner\":{}}}", json_escape(member));
+        let response = self.http_post(&path, &body)?;
+        self.scope_result(scope, "grant", response)
+    }
+
+    /// Remove the token with `member` id from a scope's owners. The
+    /// registry refuses to remove the last owner.
+    pub fn revoke_scope(&self, scope: &str, member: &str) -> Result<ScopeInfo, String> {
+        let scope = validate_scope(scope)?;
+        let path = format!("/api/v1/scopes/{}/owners/{}", scope, url_encode(member));
+        let response = self.http_send("DELETE", &path, "")?;
+        self.scope_result(scope, "revoke", response)
     }
 
-    /// Get transitive dependencies.
-    pub fn deps(&self, hash: &str) -> Result<Vec<(String, String)>, String> {
-        let path = format!("/api/v1/deps/{}", hash);
+    /// Events after `since`, waiting up to `wait_secs` for one to happen
+    /// when there are none yet. An empty batch means the wait ran out.
+    /// The wait is capped below the read timeout.
+    pub fn events(&self, since: u64, wait_secs: u64) -> Result<EventBatch, String> {
+        let wait = wait_secs.min(MAX_EVENTS_WAIT_SECS);
+        let path = format!("/api/v1/events?since={}&wait={}", since, wait);
         let response = self.http_get(&path)?;
 
         if response.status >= 400 {
             return Err(format!(
-                "deps failed ({}): {}",
+                "events failed ({}): {}",
                 response.status, response.body
             ));
         }
 
-        let mut result = Vec::new();
-        let body = &response.body;
-        let deps_start = body.find('[').unwrap_or(body.len());
-        let deps_end = body.rfind(']').unwrap_or(body.len());
-        if deps_start < deps_end {
-            let deps_str = &body[deps_start + 1..deps_end];
-            for obj in deps_str.split("},") {
-                let name = extract_json_string(obj, "name");
-                let hash = extract_json_string(obj, "hash");
-                if !hash.is_empty() {
-                    result.push((name, hash));
-                }
-            }
+        let mut batch = parse_events_response(&response.body);
+        batch.next = batch.next.max(since);
+        Ok(batch)
+    }
+
+    /// Check registry health.
+    pub fn health(&self) -> Result<bool, String> {
+        let response = self.http_get("/health")?;
+        Ok(response.status == 200)
+    }
+
+    /// POST a JSON `body` to `path` under the base URL, returning the
+    /// status and body. Node RPC (`deploy::rpc`) goes through this too.
+    pub(crate) fn post_json(&self, path: &str, body: &str) -> Result<(u16, String), String> {
+        let response = self.http_post(path, body)?;
+        Ok((response.status, response.body))
+    }
+
+    fn scope_result(
+        &self,
+        scope: &str,
+        action: &str,
+        response: ClientResponse,
+    ) -> Result<ScopeInfo, String> {
+        match response.status {
+            401 | 403 => Err(self.denied(&format!("@{}/", scope))),
+            404 => Err(format!("scope '@{}' not found in registry", scope)),
+            409 => Err(format!("scope '@{}' is already claimed", scope)),
+            s if s >= 400 => Err(format!(
+                "scope {} failed ({}): {}",
+                action, s, response.body
+            )),
+            _ => Ok(parse_scope_response(&response.body)),
         }
+    }
 
-        Ok(result)
+    /// The error for a write the registry refused to authorize.
+    fn denied(&self, name: &str) -> String {
+        match (&self.token, super::scope::split_scoped_name(name)) {
+            (None, _) => format!(
+                "registry refused '{}': no token (set TRIDENT_REGISTRY_TOKEN)",
+                name
+            ),
+            (Some(_), Some((scope, _))) => {
+                format!(
+                    "registry refused '{}': token does not own '@{}'",
+                    name, scope
+                )
+            }
+            (Some(_), None) => format!("registry refused '{}': token not accepted", name),
+        }
     }
 
     // ─── HTTP Transport ───────────────────────────────────────
 
+    /// GET `path`, revalidating a cached response by its `ETag`. A `304
+    /// Not Modified` answer is returned as the cached body with status 200.
+    fn get_revalidated(&self, path: &str) -> Result<ClientResponse, String> {
+        let Some(cache) = &self.cache else {
+            return self.http_get(path);
+        };
+        let key = format!("{}{}", self.base_url, path);
+        let cached = cache.tagged(&key).filter(|_| !self.refresh);
+        let conditional = cached
+            .as_ref()
+            .map(|c| format!("If-None-Match: {}\r\n", c.etag));
+        let response = self.http_get_with(path, conditional.as_deref().unwrap_or(""))?;
+
+        match (response.status, cached) {
+            (304, Some(cached)) => Ok(ClientResponse {
+                status: 200,
+                body: cached.body,
+                etag: Some(cached.etag),
+            }),
+            (200, _) => {
+                if let Some(etag) = &response.etag {
+                    cache.store_tagged(&key, etag, &response.body);
+                }
+                Ok(response)
+            }
+            _ => Ok(response),
+        }
+    }
+
     fn http_get(&self, path: &str) -> Result<ClientResponse, String> {
+        self.http_get_with(path, "")
+    }
+
+    /// GET `path` with `headers`, each line ending in CRLF, added to the
+    /// request.
+    fn http_get_with(&self, path: &str, headers: &str) -> Result<ClientResponse, String> {
         let (host, port, scheme_host) = parse_url(&self.base_url)?;
         let addr = format!("{}:{}", host, port);
 
@@ -201,8 +442,8 @@ impl RegistryClient {
             .map_err(|e| format!("set timeout: {}", e))?;
 
         let request = format!(
-            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nUser-Agent: trident/0.1\r\n\r\n",
-            path, scheme_host,
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nUser-Agent: trident/0.1\r\n{}\r\n",
+            path, scheme_host, headers,
         );
 
         (&stream)
@@ -213,6 +454,11 @@ impl RegistryClient {
     }
 
     fn http_post(&self, path: &str, body: &str) -> Result<ClientResponse, String> {
+        self.http_send("POST", path, body)
+    }
+
+    /// Send a JSON `body` with `method`, authorized by the token if any.
+    fn http_send(&self, method: &str, path: &str, body: &str) -> Result<ClientResponse, String> {
         let (host, port, scheme_host) = parse_url(&self.base_url)?;
         let addr = format!("{}:{}", host, port);
 
@@ -232,9 +478,14 @@ impl RegistryClient {
             .set_read_timeout(Some(std::time::Duration::from_secs(30)))
             .map_err(|e| format!("set timeout: {}", e))?;
 
+        let auth = self
+            .token
+            .as_ref()
+            .map(|t| format!("Authorization: Bearer {}\r\n", t))
+            .unwrap_or_default();
         let request = format!(
-            "POST {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\nUser-Agent: trident/0.1\r\n\r\n{}",
-            path, scheme_host, body.len(), body,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\nUser-Agent: trident/0.1\r\n{}\r\n{}",
+            method, path, scheme_host, body.len(), auth, body,
         );
 
         (&stream)
@@ -248,42 +499,17 @@ impl RegistryClient {
 struct ClientResponse {
     status: u16,
     body: String,
+    etag: Option<String>,
 }
 
 fn read_response(stream: &TcpStream) -> Result<ClientResponse, String> {
     let mut reader = BufReader::new(stream);
 
-    let mut status_line = String::new();
-    reader
-        .read_line(&mut status_line)
-        .map_err(|e| format!("read status: {}", e))?;
-    let status = status_line
-        .split_whitespace()
-        .nth(1)
-        .and_then(|s| s.parse::<u16>().ok())
-        .unwrap_or(500);
-
-    let mut content_length: usize = 0;
-    let mut chunked = false;
-    loop {
-        let mut line = String::new();
-        reader
-            .read_line(&mut line)
-            .map_err(|e| format!("read header: {}", e))?;
-        let line = line.trim().to_string();
-        if line.is_empty() {
-            break;
-        }
-        if let Some((key, value)) = line.split_once(':') {
-            let key = key.trim().to_lowercase();
-            let value = value.trim();
-            if key == "content-length" {
-                content_length = value.parse().unwrap_or(0);
-            } else if key == "transfer-encoding" && value.to_lowercase().contains("chunked") {
-                chunked = true;
-            }
-        }
-    }
+    let head = read_head(&mut reader)?;
+    let status = head.status().unwrap_or(500);
+    let content_length = head.content_length();
+    let chunked = head.is_chunked();
+    let etag = head.header("etag").map(str::to_string);
 
     if content_length > MAX_RESPONSE_SIZE {
         return Err(format!(
@@ -342,7 +568,7 @@ fn read_response(stream: &TcpStream) -> Result<ClientResponse, String> {
         String::from_utf8(body).unwrap_or_default()
     };
 
-    Ok(ClientResponse { status, body })
+    Ok(ClientResponse { status, body, etag })
 }
 
 pub(super) fn parse_url(url: &str) -> Result<(String, u16, String), String> {
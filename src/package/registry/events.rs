@@ -0,0 +1,96 @@
+//! Registry events: publications, name bindings and yanks.
+//!
+//! The registry numbers every change and serves them from
+//! `/api/v1/events?since=<seq>`, holding the request open for up to
+//! `wait` seconds when nothing newer exists yet. Webhook deliveries carry
+//! the same JSON, signed with the hook's secret:
+//!
+//! ```text
+//! X-Trident-Signature: blake3=<hex of keyed BLAKE3 over the body>
+//! ```
+//!
+//! The key is derived from the secret with the context string below, so a
+//! receiver holding the secret can check a delivery with
+//! [`verify_webhook`].
+
+const SIGNATURE_CONTEXT: &str = "trident registry webhook v1";
+
+/// What happened to a definition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// A definition was stored.
+    Publish,
+    /// A name was bound or rebound to a definition.
+    Bind,
+    /// A definition was withdrawn from search and name resolution.
+    Yank,
+}
+
+impl EventKind {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "publish" => Some(Self::Publish),
+            "bind" => Some(Self::Bind),
+            "yank" => Some(Self::Yank),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Publish => "publish",
+            Self::Bind => "bind",
+            Self::Yank => "yank",
+        }
+    }
+}
+
+/// One registry event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegistryEvent {
+    /// Position in the registry's event log, increasing by one per event.
+    pub seq: u64,
+    pub kind: EventKind,
+    /// Content hash (hex) of the definition concerned.
+    pub hash: String,
+    /// The name bound, for `bind` events and named publications.
+    pub name: Option<String>,
+    /// Whether the definition carries a verification certificate.
+    pub verified: bool,
+}
+
+/// Events after some sequence number, and where to continue from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventBatch {
+    /// Pass as `since` to get the events after these.
+    pub next: u64,
+    pub events: Vec<RegistryEvent>,
+}
+
+/// The signature header value for a webhook `body`.
+pub fn sign_webhook(secret: &str, body: &[u8]) -> String {
+    let key = blake3::derive_key(SIGNATURE_CONTEXT, secret.as_bytes());
+    format!("blake3={}", blake3::keyed_hash(&key, body).to_hex())
+}
+
+/// Whether `signature` (an `X-Trident-Signature` value) was made for
+/// `body` with `secret`. The comparison is constant-time.
+///
+/// ```
+/// use trident::api::{sign_webhook, verify_webhook};
+///
+/// let body = br#"{"seq":7,"kind":"publish"}"#;
+/// let header = sign_webhook("hook-secret", body);
+/// assert!(verify_webhook("hook-secret", body, &header));
+/// assert!(!verify_webhook("other-secret", body, &header));
+/// ```
+pub fn verify_webhook(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(hex) = signature.trim().strip_prefix("blake3=") else {
+        return false;
+    };
+    let Ok(claimed) = blake3::Hash::from_hex(hex) else {
+        return false;
+    };
+    let key = blake3::derive_key(SIGNATURE_CONTEXT, secret.as_bytes());
+    blake3::keyed_hash(&key, body) == claimed
+}
@@ -0,0 +1,105 @@
+//! HTTP/1.1 message heads over plain TCP streams.
+//!
+//! The registry client reads response heads with these helpers, and
+//! `trident doc --serve` uses them to answer requests. Only what both
+//! need is here: the start line, headers, and writing a complete
+//! response with a known length.
+
+use std::io::{BufRead, Write};
+
+/// The start line and headers of one HTTP message.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Head {
+    /// `GET /path HTTP/1.1` for requests, `HTTP/1.1 200 OK` for responses.
+    pub start_line: String,
+    /// Header names lowercased, values trimmed, in message order.
+    pub headers: Vec<(String, String)>,
+}
+
+impl Head {
+    /// Value of the first header called `name` (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Declared body length, 0 when absent or malformed.
+    pub fn content_length(&self) -> usize {
+        self.header("content-length")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// Whether the body uses chunked transfer encoding.
+    pub fn is_chunked(&self) -> bool {
+        self.header("transfer-encoding")
+            .is_some_and(|v| v.to_lowercase().contains("chunked"))
+    }
+
+    /// Method and request target of a request head.
+    pub fn request(&self) -> Option<(&str, &str)> {
+        let mut parts = self.start_line.split_whitespace();
+        Some((parts.next()?, parts.next()?))
+    }
+
+    /// Status code of a response head.
+    pub fn status(&self) -> Option<u16> {
+        self.start_line.split_whitespace().nth(1)?.parse().ok()
+    }
+}
+
+/// Read a message head up to and including the blank line.
+pub fn read_head(reader: &mut impl BufRead) -> Result<Head, String> {
+    let mut start_line = String::new();
+    reader
+        .read_line(&mut start_line)
+        .map_err(|e| format!("read start line: {}", e))?;
+    let mut head = Head {
+        start_line: start_line.trim().to_string(),
+        headers: Vec::new(),
+    };
+    loop {
+        let mut line = String::new();
+        let n = reader
+            .read_line(&mut line)
+            .map_err(|e| format!("read header: {}", e))?;
+        let line = line.trim();
+        if n == 0 || line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            head.headers
+                .push((key.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    Ok(head)
+}
+
+/// Write a complete response and close the exchange.
+pub fn write_response(
+    stream: &mut impl Write,
+    status: u16,
+    content_type: &str,
+    body: &[u8],
+) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        reason,
+        content_type,
+        body.len(),
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
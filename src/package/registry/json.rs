@@ -1,3 +1,6 @@
+use super::events::{EventBatch, EventKind, RegistryEvent};
+use super::scope::ScopeInfo;
+use super::template::ProjectTemplate;
 use super::types::*;
 
 /// Maximum length for a single JSON string value (1 MB).
@@ -115,6 +118,16 @@ pub(super) fn extract_json_bool(json: &str, key: &str) -> bool {
     false
 }
 
+pub(super) fn extract_json_u64(json: &str, key: &str) -> Option<u64> {
+    let needle = format!("\"{}\":", key);
+    let pos = find_toplevel_key(json, key)?;
+    let after = json[pos + needle.len()..].trim_start();
+    let end = after
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(after.len());
+    after[..end].parse().ok()
+}
+
 pub(super) fn extract_json_array_strings(json: &str, key: &str) -> Vec<String> {
     let needle = format!("\"{}\":", key);
     let mut results = Vec::new();
@@ -142,6 +155,66 @@ pub(super) fn extract_json_array_strings(json: &str, key: &str) -> Vec<String> {
     results
 }
 
+/// Object name to number, e.g. `"cost":{"processor":12,"hash":6}`.
+/// Entries whose value is not a plain integer are skipped.
+pub(super) fn extract_json_u64_object(json: &str, key: &str) -> Vec<(String, u64)> {
+    let needle = format!("\"{}\":", key);
+    let mut results = Vec::new();
+    if let Some(pos) = find_toplevel_key(json, key) {
+        let after = json[pos + needle.len()..].trim_start();
+        let first = json_objects(after).first().copied();
+        if let Some(obj) = first.filter(|_| after.starts_with('{')) {
+            for entry in obj[1..obj.len() - 1].split(',') {
+                if results.len() >= MAX_ARRAY_ITEMS {
+                    break;
+                }
+                let Some((name, value)) = entry.split_once(':') else {
+                    continue;
+                };
+                let name = name.trim().trim_matches('"');
+                if let Ok(value) = value.trim().parse() {
+                    results.push((name.to_string(), value));
+                }
+            }
+        }
+    }
+    results
+}
+
+/// The top-level `{...}` objects in `s`, e.g. the inside of an array of
+/// objects. Braces inside strings are skipped.
+pub(super) fn json_objects(s: &str) -> Vec<&str> {
+    let bytes = s.as_bytes();
+    let mut objects = Vec::new();
+    let (mut depth, mut start, mut i) = (0usize, 0, 0);
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'{' => {
+                if depth == 0 {
+                    start = i;
+                }
+                depth += 1;
+            }
+            b'}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    objects.push(&s[start..=i]);
+                }
+            }
+            b']' if depth == 0 => break,
+            _ => {}
+        }
+        i += 1;
+    }
+    objects
+}
+
 pub(super) fn find_matching_bracket(s: &str) -> usize {
     let mut depth = 0;
     for (i, ch) in s.chars().enumerate() {
@@ -179,9 +252,14 @@ pub(super) fn format_publish_json(def: &PublishedDefinition) -> String {
     let requires: Vec<String> = def.requires.iter().map(|r| json_escape(r)).collect();
     let ensures: Vec<String> = def.ensures.iter().map(|e| json_escape(e)).collect();
     let tags: Vec<String> = def.tags.iter().map(|t| json_escape(t)).collect();
+    let cost: Vec<String> = def
+        .cost
+        .iter()
+        .map(|(table, rows)| format!("{}:{}", json_escape(table), rows))
+        .collect();
 
     format!(
-        "{{\"hash\":\"{}\",\"source\":{},\"module\":{},\"is_pub\":{},\"params\":[{}],\"return_ty\":{},\"dependencies\":[{}],\"requires\":[{}],\"ensures\":[{}],\"name\":{},\"tags\":[{}],\"verified\":{},\"verification_cert\":{}}}",
+        "{{\"hash\":\"{}\",\"source\":{},\"module\":{},\"is_pub\":{},\"params\":[{}],\"return_ty\":{},\"dependencies\":[{}],\"requires\":[{}],\"ensures\":[{}],\"name\":{},\"tags\":[{}],\"verified\":{},\"verification_cert\":{},\"cost\":{{{}}}}}",
         def.hash,
         json_escape(&def.source),
         json_escape(&def.module),
@@ -195,6 +273,7 @@ pub(super) fn format_publish_json(def: &PublishedDefinition) -> String {
         tags.join(","),
         def.verified,
         def.verification_cert.as_ref().map(|c| json_escape(c)).unwrap_or_else(|| "null".to_string()),
+        cost.join(","),
     )
 }
 
@@ -247,6 +326,8 @@ pub(super) fn parse_publish_body(body: &str) -> Result<PublishedDefinition, Stri
         }
     };
 
+    let cost = extract_json_u64_object(body, "cost");
+
     Ok(PublishedDefinition {
         hash,
         source,
@@ -261,6 +342,7 @@ pub(super) fn parse_publish_body(body: &str) -> Result<PublishedDefinition, Stri
         tags,
         verified,
         verification_cert,
+        cost,
     })
 }
 
@@ -305,6 +387,15 @@ pub(super) fn parse_pull_response(body: &str) -> PullResult {
         dependencies: extract_json_array_strings(body, "dependencies"),
         requires: extract_json_array_strings(body, "requires"),
         ensures: extract_json_array_strings(body, "ensures"),
+        verification_cert: {
+            let vc = extract_json_string(body, "verification_cert");
+            if vc.is_empty() {
+                None
+            } else {
+                Some(vc)
+            }
+        },
+        verified: false,
     }
 }
 
@@ -316,7 +407,7 @@ pub(super) fn parse_search_response(body: &str) -> Vec<SearchResult> {
         let bracket_end = find_matching_bracket(after);
         let inner = &after[1..bracket_end];
 
-        for obj in inner.split("},{") {
+        for obj in json_objects(inner) {
             if results.len() >= MAX_ARRAY_ITEMS {
                 break;
             }
@@ -326,6 +417,7 @@ pub(super) fn parse_search_response(body: &str) -> Vec<SearchResult> {
             let signature = extract_json_string(obj, "signature");
             let verified = extract_json_bool(obj, "verified");
             let tags = extract_json_array_strings(obj, "tags");
+            let cost = extract_json_u64_object(obj, "cost");
 
             if !hash.is_empty() {
                 results.push(SearchResult {
@@ -335,9 +427,97 @@ pub(super) fn parse_search_response(body: &str) -> Vec<SearchResult> {
                     signature,
                     verified,
                     tags,
+                    cost,
                 });
             }
         }
     }
     results
 }
+
+pub(super) fn parse_scope_response(body: &str) -> ScopeInfo {
+    ScopeInfo {
+        scope: extract_json_string(body, "scope"),
+        owners: extract_json_array_strings(body, "owners"),
+    }
+}
+
+pub(super) fn parse_events_response(body: &str) -> EventBatch {
+    let mut batch = EventBatch {
+        next: extract_json_u64(body, "next").unwrap_or(0),
+        events: Vec::new(),
+    };
+    let needle = "\"events\":[";
+    if let Some(pos) = body.find(needle) {
+        let after = &body[pos + needle.len() - 1..];
+        let bracket_end = find_matching_bracket(after);
+        let inner = &after[1..bracket_end];
+
+        for obj in json_objects(inner) {
+            if batch.events.len() >= MAX_ARRAY_ITEMS {
+                break;
+            }
+            let (Some(seq), Some(kind)) = (
+                extract_json_u64(obj, "seq"),
+                EventKind::parse(&extract_json_string(obj, "kind")),
+            ) else {
+                continue;
+            };
+            let name = extract_json_string(obj, "name");
+            batch.events.push(RegistryEvent {
+                seq,
+                kind,
+                hash: extract_json_string(obj, "hash"),
+                name: (!name.is_empty()).then_some(name),
+                verified: extract_json_bool(obj, "verified"),
+            });
+        }
+    }
+    batch
+}
+
+pub(super) fn format_template_json(template: &ProjectTemplate) -> String {
+    let files: Vec<String> = template
+        .files
+        .iter()
+        .map(|(path, content)| {
+            format!(
+                "{{\"path\":{},\"content\":{}}}",
+                json_escape(path),
+                json_escape(content)
+            )
+        })
+        .collect();
+    format!(
+        "{{\"kind\":\"template\",\"name\":{},\"hash\":\"{}\",\"description\":{},\"files\":[{}]}}",
+        json_escape(&template.name),
+        template.hash(),
+        json_escape(&template.description),
+        files.join(","),
+    )
+}
+
+/// The template in `body` and the hash the registry claims for it.
+pub(super) fn parse_template_response(body: &str) -> (ProjectTemplate, String) {
+    let mut files = Vec::new();
+    let needle = "\"files\":[";
+    if let Some(pos) = find_toplevel_key(body, "files") {
+        let after = &body[pos + needle.len() - 1..];
+        for obj in json_objects(&after[1..]) {
+            if files.len() >= MAX_ARRAY_ITEMS {
+                break;
+            }
+            files.push((
+                extract_json_string(obj, "path"),
+                extract_json_string(obj, "content"),
+            ));
+        }
+    }
+    files.sort();
+    let template = ProjectTemplate {
+        name: extract_json_string(body, "name"),
+        description: extract_json_string(body, "description"),
+        files,
+    };
+    (template, extract_json_string(body, "hash"))
+}
@@ -1,16 +1,28 @@
 //! Registry Client — HTTP client for interacting with a Trident registry.
 //!
 //! Provides a client for publishing and pulling content-addressed definitions
-//! to/from a remote registry over HTTP. Wire format is JSON.
+//! to/from a remote registry over HTTP. Wire format is JSON. Names under
+//! a scope (`@org/name`) can only be bound by the scope's token holders.
+//!
+//! The server is not part of this crate: its process management (daemon
+//! mode, shutdown, worker threads, proxy headers) is configured where it
+//! is deployed.
 
+mod cache;
 mod client;
+pub mod events;
+pub mod http;
 mod json;
+mod scope;
 mod store_integration;
+mod template;
 mod types;
 
 pub use client::RegistryClient;
+pub use scope::member_id;
 pub use store_integration::{publish_codebase, pull_into_codebase};
-pub use types::{PublishResult, PublishedDefinition, PullResult, SearchResult};
+pub use template::ProjectTemplate;
+pub use types::PullResult;
 
 #[cfg(test)]
 mod tests;
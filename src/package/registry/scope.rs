@@ -0,0 +1,64 @@
+//! Scoped names and their ownership records.
+//!
+//! A name of the form `@org/merkle.verify` lives in the scope `org`. The
+//! registry keeps, per scope, the member ids of the tokens allowed to bind
+//! or rebind names in it; unscoped names are first come, first served.
+//! A member id is derived from a token, so it can be shared to be granted
+//! access without sharing the token itself.
+
+/// The owners of one scope, as the registry reports them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScopeInfo {
+    /// Scope name without the leading `@`.
+    pub scope: String,
+    /// Member ids of the tokens that may bind names in the scope.
+    pub owners: Vec<String>,
+}
+
+/// Split `@org/rest` into `("org", "rest")`. `None` for unscoped names.
+pub fn split_scoped_name(name: &str) -> Option<(&str, &str)> {
+    name.strip_prefix('@')?.split_once('/')
+}
+
+/// Check a scope name: lowercase ASCII letters, digits and `-`, not
+/// starting with `-`. A leading `@` is accepted and ignored.
+pub fn validate_scope(scope: &str) -> Result<&str, String> {
+    let bare = scope.strip_prefix('@').unwrap_or(scope);
+    let valid = !bare.is_empty()
+        && bare.len() <= 64
+        && !bare.starts_with('-')
+        && bare
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+    if valid {
+        Ok(bare)
+    } else {
+        Err(format!(
+            "invalid scope '{}': use lowercase letters, digits and '-'",
+            scope
+        ))
+    }
+}
+
+/// Check a name before publishing it: a scoped name needs a valid scope
+/// and a non-empty name after the `/`.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if !name.starts_with('@') {
+        return Ok(());
+    }
+    match split_scoped_name(name) {
+        Some((scope, rest)) if !rest.is_empty() && !rest.contains('/') => {
+            validate_scope(scope).map(|_| ())
+        }
+        _ => Err(format!(
+            "invalid scoped name '{}': expected @scope/name",
+            name
+        )),
+    }
+}
+
+/// The member id of a registry token: the first 16 bytes of its BLAKE3
+/// hash, in hex.
+pub fn member_id(token: &str) -> String {
+    blake3::hash(token.as_bytes()).to_hex()[..32].to_string()
+}
@@ -1,5 +1,6 @@
+use crate::cost::CostAnalyzer;
 use crate::hash::ContentHash;
-use crate::store::{Codebase, Definition};
+use crate::store::{Certificate, Codebase, Definition};
 
 use super::client::RegistryClient;
 use super::types::*;
@@ -30,8 +31,9 @@ pub fn publish_codebase(
             ensures: def.ensures.clone(),
             name: Some(name.to_string()),
             tags: tags.to_vec(),
-            verified: false,
-            verification_cert: None,
+            verified: def.certificate.is_some(),
+            verification_cert: def.certificate.as_ref().map(|c| c.to_string()),
+            cost: definition_cost(&def.source),
         };
 
         match client.publish(&pub_def) {
@@ -45,22 +47,49 @@ pub fn publish_codebase(
     Ok(results)
 }
 
+/// Rows per execution table of one call to the function in `source`,
+/// by the Triton cost model. Calls into dependencies count only their
+/// call overhead, since each dependency is published with its own cost.
+/// Empty if the source does not parse.
+pub fn definition_cost(source: &str) -> Vec<(String, u64)> {
+    let wrapped = format!("module codebase\n{}\n", source);
+    let Ok(file) = crate::parse_source_silent(&wrapped, "<codebase>") else {
+        return Vec::new();
+    };
+    let program = CostAnalyzer::default().analyze_file(&file);
+    let Some(func) = program.functions.first() else {
+        return Vec::new();
+    };
+    program
+        .table_names
+        .iter()
+        .zip(func.cost.values)
+        .take(func.cost.count as usize)
+        .map(|(table, rows)| (table.clone(), rows))
+        .collect()
+}
+
 /// Pull a definition from a registry into the local store.
+///
+/// A published certificate is re-checked against the pulled source and
+/// kept only if it holds; `verified` in the result reports which.
 pub fn pull_into_codebase(
     codebase: &mut Codebase,
     client: &RegistryClient,
     name_or_hash: &str,
 ) -> Result<PullResult, String> {
-    let pull = if name_or_hash.len() == 64 && name_or_hash.chars().all(|c| c.is_ascii_hexdigit()) {
-        client.pull(name_or_hash)?
-    } else {
-        client.pull_by_name(name_or_hash)?
-    };
+    let mut pull =
+        if name_or_hash.len() == 64 && name_or_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            client.pull(name_or_hash)?
+        } else {
+            client.pull_by_name(name_or_hash)?
+        };
 
     let hash = ContentHash::from_hex(&pull.hash)
         .ok_or_else(|| "invalid hash in pull response".to_string())?;
 
-    if codebase.lookup_hash(&hash).is_some() {
+    if let Some(existing) = codebase.lookup_hash(&hash) {
+        pull.verified = existing.certificate.is_some();
         return Ok(pull);
     }
 
@@ -70,6 +99,13 @@ pub fn pull_into_codebase(
         .filter_map(|h| ContentHash::from_hex(h))
         .collect();
 
+    let certificate = pull
+        .verification_cert
+        .as_deref()
+        .and_then(Certificate::parse)
+        .filter(|cert| cert.check(&pull.source).is_ok());
+    pull.verified = certificate.is_some();
+
     let def = Definition {
         source: pull.source.clone(),
         module: pull.module.clone(),
@@ -80,6 +116,7 @@ pub fn pull_into_codebase(
         requires: pull.requires.clone(),
         ensures: pull.ensures.clone(),
         first_seen: crate::package::unix_timestamp(),
+        certificate,
     };
 
     codebase.store_definition(hash, def);
@@ -0,0 +1,136 @@
+//! Project templates: whole projects published to the registry under a
+//! name (`kind: "template"`) and instantiated by `trident init
+//! --from-registry`.
+//!
+//! A template is its files, by relative path. Text in them may use
+//! `{{project_name}}` and `{{author}}`, replaced when the template is
+//! instantiated. The template's hash covers every path and content, so a
+//! pulled template is checked like a pulled definition.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Extensions of the files a template is made of.
+const TEMPLATE_EXTENSIONS: &[&str] = &["tri", "toml", "md"];
+
+/// Deepest directory a template may contain.
+const MAX_TEMPLATE_DEPTH: usize = 16;
+
+/// A project template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectTemplate {
+    /// Registry name of the template.
+    pub name: String,
+    /// One line shown when listing templates.
+    pub description: String,
+    /// `(relative path with '/' separators, content)`, sorted by path.
+    pub files: Vec<(String, String)>,
+}
+
+impl ProjectTemplate {
+    /// Collect the `.tri`, `.toml` and `.md` files under `dir`, skipping
+    /// hidden directories.
+    pub fn from_dir(dir: &Path, name: &str, description: &str) -> Result<Self, String> {
+        let mut files = Vec::new();
+        collect(dir, dir, &mut files, 0)?;
+        if files.is_empty() {
+            return Err(format!("no template files in '{}'", dir.display()));
+        }
+        files.sort();
+        Ok(Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            files,
+        })
+    }
+
+    /// Content hash (hex) over every path and content.
+    pub fn hash(&self) -> String {
+        let mut hasher = blake3::Hasher::new();
+        let mut files: Vec<&(String, String)> = self.files.iter().collect();
+        files.sort();
+        for (path, content) in files {
+            hasher.update(format!("{}\0{}\0", path, content.len()).as_bytes());
+            hasher.update(content.as_bytes());
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// Write the files under `dir`, substituting `vars` (`("author",
+    /// "ada")` replaces `{{author}}`). Refuses paths leaving `dir` and
+    /// existing files; returns the files written.
+    pub fn instantiate(&self, dir: &Path, vars: &[(&str, &str)]) -> Result<Vec<PathBuf>, String> {
+        let mut targets = Vec::new();
+        for (path, content) in &self.files {
+            let relative = Path::new(path);
+            if !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+            {
+                return Err(format!("template path '{}' leaves the project", path));
+            }
+            let target = dir.join(relative);
+            if target.exists() {
+                return Err(format!("'{}' already exists", target.display()));
+            }
+            targets.push((target, substitute(content, vars)));
+        }
+
+        let mut written = Vec::new();
+        for (target, content) in targets {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("cannot create '{}': {}", parent.display(), e))?;
+            }
+            std::fs::write(&target, content)
+                .map_err(|e| format!("cannot write '{}': {}", target.display(), e))?;
+            written.push(target);
+        }
+        Ok(written)
+    }
+}
+
+/// Replace each `{{key}}` in `text` with its value in `vars`. Unknown
+/// placeholders are left as they are.
+pub fn substitute(text: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = text.to_string();
+    for (key, value) in vars {
+        out = out.replace(&format!("{{{{{}}}}}", key), value);
+    }
+    out
+}
+
+fn collect(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<(String, String)>,
+    depth: usize,
+) -> Result<(), String> {
+    if depth >= MAX_TEMPLATE_DEPTH {
+        return Ok(());
+    }
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("cannot read '{}': {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if path.is_dir() {
+            if !hidden {
+                collect(root, &path, files, depth + 1)?;
+            }
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| TEMPLATE_EXTENSIONS.contains(&e))
+        {
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let parts: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            files.push((parts.join("/"), content));
+        }
+    }
+    Ok(())
+}
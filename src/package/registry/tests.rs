@@ -1,5 +1,7 @@
 use super::client::*;
+use super::http::*;
 use super::json::*;
+use super::scope::*;
 use super::types::*;
 use crate::hash::ContentHash;
 
@@ -96,6 +98,7 @@ fn test_publish_json_roundtrip() {
         tags: vec!["testing".to_string()],
         verified: false,
         verification_cert: None,
+        cost: Vec::new(),
     };
 
     let json = format_publish_json(&pub_def);
@@ -128,6 +131,7 @@ fn test_publish_json_roundtrip_complex() {
         tags: vec!["math".to_string(), "core".to_string()],
         verified: true,
         verification_cert: Some("cert123".to_string()),
+        cost: vec![("processor".to_string(), 12), ("hash".to_string(), 6)],
     };
 
     let json = format_publish_json(&pub_def);
@@ -141,6 +145,7 @@ fn test_publish_json_roundtrip_complex() {
     assert_eq!(parsed.return_ty, pub_def.return_ty);
     assert_eq!(parsed.name, pub_def.name);
     assert_eq!(parsed.verified, pub_def.verified);
+    assert_eq!(parsed.cost, pub_def.cost);
 }
 
 #[test]
@@ -161,3 +166,426 @@ fn test_parse_publish_body_invalid_hash() {
     let body = r#"{"hash":"tooshort","source":"fn test() { }"}"#;
     assert!(parse_publish_body(body).is_err());
 }
+
+#[test]
+fn test_read_head_request_and_response() {
+    let mut request =
+        "GET /index.html HTTP/1.1\r\nHost: x\r\nContent-Length: 3\r\n\r\nabc".as_bytes();
+    let head = read_head(&mut request).unwrap();
+    assert_eq!(head.request(), Some(("GET", "/index.html")));
+    assert_eq!(head.content_length(), 3);
+    assert_eq!(request, b"abc");
+
+    let mut response = "HTTP/1.1 404 Not Found\r\nTransfer-Encoding: Chunked\r\n\r\n".as_bytes();
+    let head = read_head(&mut response).unwrap();
+    assert_eq!(head.status(), Some(404));
+    assert!(head.is_chunked());
+    assert_eq!(head.header("TRANSFER-ENCODING"), Some("Chunked"));
+}
+
+#[test]
+fn test_write_response_reads_back() {
+    let mut out = Vec::new();
+    write_response(&mut out, 200, "text/plain", b"hi").unwrap();
+    let mut reader = out.as_slice();
+    let head = read_head(&mut reader).unwrap();
+    assert_eq!(head.status(), Some(200));
+    assert_eq!(head.content_length(), 2);
+    assert_eq!(reader, b"hi");
+}
+
+#[test]
+fn test_parse_pull_response_reads_certificate() {
+    let hash = "ab".repeat(32);
+    let with_cert = format!(
+        r#"{{"hash":"{}","source":"fn f() {{ }}","module":"m","verification_cert":"cert-v1 system={} verifier=0.1.0 verdict=safe seed=7"}}"#,
+        hash, hash
+    );
+    let pull = parse_pull_response(&with_cert);
+    assert!(pull.verification_cert.unwrap().starts_with("cert-v1 "));
+    assert!(!pull.verified);
+
+    let without = format!(
+        r#"{{"hash":"{}","source":"fn f() {{ }}","verification_cert":null}}"#,
+        hash
+    );
+    assert!(parse_pull_response(&without).verification_cert.is_none());
+}
+
+/// Answer one request per entry of `responses` on a local port, sending
+/// back each request head. Request bodies are read and dropped.
+fn serve(responses: Vec<String>) -> (String, std::sync::mpsc::Receiver<Head>) {
+    use std::io::Write;
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (heads, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for response in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = std::io::BufReader::new(&stream);
+            let head = read_head(&mut reader).unwrap();
+            let mut body = vec![0; head.content_length()];
+            std::io::Read::read_exact(&mut reader, &mut body).unwrap();
+            heads.send(head).unwrap();
+            (&stream).write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (url, rx)
+}
+
+fn ok_response(etag: Option<&str>, body: &str) -> String {
+    let etag = etag.map(|e| format!("ETag: {}\r\n", e)).unwrap_or_default();
+    format!(
+        "HTTP/1.1 200 OK\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        etag,
+        body.len(),
+        body
+    )
+}
+
+#[test]
+fn test_pull_by_hash_is_served_from_the_cache() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = Some(super::cache::ResponseCache::new(tmp.path().to_path_buf()));
+    let hash = "cd".repeat(32);
+    let body = format!(r#"{{"hash":"{}","source":"fn f() {{ }}"}}"#, hash);
+    let (url, heads) = serve(vec![ok_response(None, &body), ok_response(None, &body)]);
+
+    let client = RegistryClient::new(&url).with_cache(cache.clone());
+    assert_eq!(client.pull(&hash).unwrap().source, "fn f() { }");
+    assert_eq!(client.pull(&hash).unwrap().source, "fn f() { }");
+    heads.recv().unwrap();
+    assert!(
+        heads.try_recv().is_err(),
+        "second pull went to the registry"
+    );
+
+    let refreshed = RegistryClient::new(&url)
+        .with_cache(cache)
+        .with_refresh(true);
+    assert_eq!(refreshed.pull(&hash).unwrap().hash, hash);
+    assert_eq!(
+        heads.recv().unwrap().request(),
+        Some(("GET", &*format!("/api/v1/definitions/{}", hash)))
+    );
+}
+
+#[test]
+fn test_name_lookup_revalidates_with_etag() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = Some(super::cache::ResponseCache::new(tmp.path().to_path_buf()));
+    let body = format!(
+        r#"{{"hash":"{}","source":"fn g() {{ }}"}}"#,
+        "ef".repeat(32)
+    );
+    let (url, heads) = serve(vec![
+        ok_response(Some("\"v1\""), &body),
+        "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_string(),
+        ok_response(Some("\"v1\""), &body),
+    ]);
+
+    let client = RegistryClient::new(&url).with_cache(cache.clone());
+    assert_eq!(client.pull_by_name("g").unwrap().source, "fn g() { }");
+    assert_eq!(heads.recv().unwrap().header("if-none-match"), None);
+
+    assert_eq!(client.pull_by_name("g").unwrap().source, "fn g() { }");
+    assert_eq!(
+        heads.recv().unwrap().header("if-none-match"),
+        Some("\"v1\"")
+    );
+
+    let refreshed = RegistryClient::new(&url)
+        .with_cache(cache)
+        .with_refresh(true);
+    assert_eq!(refreshed.pull_by_name("g").unwrap().source, "fn g() { }");
+    assert_eq!(heads.recv().unwrap().header("if-none-match"), None);
+}
+
+#[test]
+fn test_pull_by_scoped_name_encodes_the_name() {
+    let body = format!(
+        r#"{{"hash":"{}","source":"fn verify() {{ }}"}}"#,
+        "01".repeat(32)
+    );
+    let (url, heads) = serve(vec![ok_response(None, &body)]);
+    let client = RegistryClient::new(&url).with_cache(None);
+    client.pull_by_name("@org/merkle.verify").unwrap();
+    assert_eq!(
+        heads.recv().unwrap().request(),
+        Some(("GET", "/api/v1/names/%40org%2Fmerkle.verify"))
+    );
+}
+
+#[test]
+fn test_scoped_name_validation() {
+    assert_eq!(
+        split_scoped_name("@org/merkle.verify"),
+        Some(("org", "merkle.verify"))
+    );
+    assert_eq!(split_scoped_name("merkle.verify"), None);
+    assert!(validate_name("merkle.verify").is_ok());
+    assert!(validate_name("@org/merkle.verify").is_ok());
+    assert!(validate_name("@org").is_err());
+    assert!(validate_name("@org/").is_err());
+    assert!(validate_name("@Org/x").is_err());
+    assert!(validate_name("@org/a/b").is_err());
+    assert_eq!(validate_scope("@my-org"), Ok("my-org"));
+    assert!(validate_scope("-org").is_err());
+    assert_eq!(member_id("secret").len(), 32);
+    assert_ne!(member_id("secret"), member_id("other"));
+}
+
+fn scoped_definition(name: &str) -> PublishedDefinition {
+    PublishedDefinition {
+        hash: "02".repeat(32),
+        source: "fn verify() { }".to_string(),
+        module: "merkle".to_string(),
+        is_pub: true,
+        params: Vec::new(),
+        return_ty: None,
+        dependencies: Vec::new(),
+        requires: Vec::new(),
+        ensures: Vec::new(),
+        name: Some(name.to_string()),
+        tags: Vec::new(),
+        verified: false,
+        verification_cert: None,
+        cost: Vec::new(),
+    }
+}
+
+#[test]
+fn test_publish_scoped_name_sends_token_and_reports_denial() {
+    let (url, heads) = serve(vec![
+        "HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+    ]);
+    let client = RegistryClient::new(&url)
+        .with_cache(None)
+        .with_token(Some("secret".to_string()));
+    let err = client
+        .publish(&scoped_definition("@org/merkle.verify"))
+        .unwrap_err();
+    assert!(err.contains("does not own '@org'"), "{}", err);
+    assert_eq!(
+        heads.recv().unwrap().header("authorization"),
+        Some("Bearer secret")
+    );
+
+    let err = client.publish(&scoped_definition("@org/")).unwrap_err();
+    assert!(err.contains("invalid scoped name"), "{}", err);
+}
+
+#[test]
+fn test_scope_management_requests() {
+    let body = r#"{"scope":"org","owners":["aa","bb"]}"#;
+    let (url, heads) = serve(vec![ok_response(None, body), ok_response(None, body)]);
+    let client = RegistryClient::new(&url)
+        .with_cache(None)
+        .with_token(Some("secret".to_string()));
+
+    let info = client.grant_scope("@org", "bb").unwrap();
+    assert_eq!(info.scope, "org");
+    assert_eq!(info.owners, vec!["aa", "bb"]);
+    assert_eq!(
+        heads.recv().unwrap().request(),
+        Some(("POST", "/api/v1/scopes/org/owners"))
+    );
+
+    client.revoke_scope("org", "bb").unwrap();
+    assert_eq!(
+        heads.recv().unwrap().request(),
+        Some(("DELETE", "/api/v1/scopes/org/owners/bb"))
+    );
+}
+
+#[test]
+fn test_parse_events_response() {
+    let hash = "ab".repeat(32);
+    let body = format!(
+        r#"{{"next":9,"events":[{{"seq":7,"kind":"publish","hash":"{h}","name":"f","verified":true}},{{"seq":8,"kind":"yank","hash":"{h}"}},{{"seq":9,"kind":"unknown","hash":"{h}"}}]}}"#,
+        h = hash
+    );
+    let batch = parse_events_response(&body);
+    assert_eq!(batch.next, 9);
+    assert_eq!(batch.events.len(), 2);
+    assert_eq!(batch.events[0].kind, super::events::EventKind::Publish);
+    assert_eq!(batch.events[0].name.as_deref(), Some("f"));
+    assert!(batch.events[0].verified);
+    assert_eq!(batch.events[1].seq, 8);
+    assert_eq!(batch.events[1].kind, super::events::EventKind::Yank);
+    assert_eq!(batch.events[1].name, None);
+
+    let empty = parse_events_response(r#"{"next":3,"events":[]}"#);
+    assert_eq!(
+        empty,
+        super::events::EventBatch {
+            next: 3,
+            events: Vec::new()
+        }
+    );
+}
+
+#[test]
+fn test_events_long_poll_request() {
+    let (url, heads) = serve(vec![ok_response(None, r#"{"events":[]}"#)]);
+    let client = RegistryClient::new(&url).with_cache(None);
+    let batch = client.events(41, 600).unwrap();
+    assert_eq!(batch.next, 41);
+    assert_eq!(
+        heads.recv().unwrap().request(),
+        Some(("GET", "/api/v1/events?since=41&wait=25"))
+    );
+}
+
+#[test]
+fn test_webhook_signature() {
+    let body = br#"{"seq":1,"kind":"bind","hash":"00","name":"f"}"#;
+    let signature = super::events::sign_webhook("hook-secret", body);
+    assert!(signature.starts_with("blake3="));
+    assert!(super::events::verify_webhook(
+        "hook-secret",
+        body,
+        &signature
+    ));
+    assert!(!super::events::verify_webhook(
+        "other-secret",
+        body,
+        &signature
+    ));
+    assert!(!super::events::verify_webhook(
+        "hook-secret",
+        b"{}",
+        &signature
+    ));
+    assert!(!super::events::verify_webhook(
+        "hook-secret",
+        body,
+        "sha256=00"
+    ));
+}
+
+#[test]
+fn test_parse_search_response_reads_every_result_and_cost() {
+    let body = format!(
+        r#"{{"results":[{{"name":"a","hash":"{}","cost":{{"processor":40,"hash":6}},"verified":true}},{{"name":"b","hash":"{}","tags":["x"]}},{{"name":"c","hash":"{}","cost":{{"processor":9}}}}]}}"#,
+        "01".repeat(32),
+        "02".repeat(32),
+        "03".repeat(32)
+    );
+    let results = parse_search_response(&body);
+    let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["a", "b", "c"]);
+    assert_eq!(results[0].table_rows("hash"), Some(6));
+    assert!(results[0].verified);
+    assert!(results[1].cost.is_empty());
+    assert_eq!(results[1].tags, vec!["x"]);
+    assert_eq!(results[2].max_rows(), Some(9));
+}
+
+#[test]
+fn test_search_by_cost_filters_and_orders() {
+    let body = format!(
+        r#"{{"results":[{{"name":"slow","hash":"{}","cost":{{"processor":90,"hash":12}}}},{{"name":"fast","hash":"{}","cost":{{"processor":30,"hash":6}}}},{{"name":"big","hash":"{}","cost":{{"processor":30,"hash":24}}}},{{"name":"unknown","hash":"{}"}}]}}"#,
+        "01".repeat(32),
+        "02".repeat(32),
+        "03".repeat(32),
+        "04".repeat(32)
+    );
+    let (url, heads) = serve(vec![ok_response(None, &body)]);
+    let client = RegistryClient::new(&url).with_cache(None);
+    let limits = vec![("hash".to_string(), 12), ("processor".to_string(), 100)];
+    let results = client.search_by_cost("merkle", &limits).unwrap();
+    let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["fast", "slow"]);
+    assert_eq!(
+        heads.recv().unwrap().request(),
+        Some((
+            "GET",
+            "/api/v1/search?q=merkle&max_hash=12&max_processor=100"
+        ))
+    );
+}
+
+#[test]
+fn test_definition_cost_names_tables() {
+    let cost = super::store_integration::definition_cost(
+        "fn add(a: Field, b: Field) -> Field {\n    a + b\n}",
+    );
+    assert_eq!(cost.first().map(|(t, _)| t.as_str()), Some("processor"));
+    assert!(cost.iter().any(|(_, rows)| *rows > 0));
+    assert!(super::store_integration::definition_cost("not a function").is_empty());
+}
+
+#[test]
+fn test_template_roundtrip_and_instantiate() {
+    let src = tempfile::tempdir().unwrap();
+    std::fs::write(
+        src.path().join("trident.toml"),
+        "[project]\nname = \"{{project_name}}\"\nentry = \"main.tri\"\n",
+    )
+    .unwrap();
+    std::fs::write(src.path().join("main.tri"), "program {{project_name}}\n").unwrap();
+    std::fs::create_dir_all(src.path().join("lib")).unwrap();
+    std::fs::write(src.path().join("lib/util.tri"), "// by {{author}}\n").unwrap();
+    std::fs::create_dir_all(src.path().join(".git")).unwrap();
+    std::fs::write(src.path().join(".git/config.toml"), "x").unwrap();
+    std::fs::write(src.path().join("notes.txt"), "skipped").unwrap();
+
+    let template = super::ProjectTemplate::from_dir(src.path(), "@org/app", "An app").unwrap();
+    let paths: Vec<&str> = template.files.iter().map(|(p, _)| p.as_str()).collect();
+    assert_eq!(paths, vec!["lib/util.tri", "main.tri", "trident.toml"]);
+
+    let (parsed, hash) = parse_template_response(&format_template_json(&template));
+    assert_eq!(parsed, template);
+    assert_eq!(hash, template.hash());
+
+    let out = tempfile::tempdir().unwrap();
+    let vars = [("project_name", "wallet"), ("author", "ada")];
+    let written = template.instantiate(out.path(), &vars).unwrap();
+    assert_eq!(written.len(), 3);
+    assert_eq!(
+        std::fs::read_to_string(out.path().join("main.tri")).unwrap(),
+        "program wallet\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(out.path().join("lib/util.tri")).unwrap(),
+        "// by ada\n"
+    );
+    assert!(template.instantiate(out.path(), &vars).is_err());
+}
+
+#[test]
+fn test_template_rejects_escaping_paths() {
+    let template = super::ProjectTemplate {
+        name: "evil".to_string(),
+        description: String::new(),
+        files: vec![("../outside.tri".to_string(), "x".to_string())],
+    };
+    let out = tempfile::tempdir().unwrap();
+    let err = template.instantiate(out.path(), &[]).unwrap_err();
+    assert!(err.contains("leaves the project"), "{}", err);
+}
+
+#[test]
+fn test_pull_template_checks_hash() {
+    let template = super::ProjectTemplate {
+        name: "app".to_string(),
+        description: String::new(),
+        files: vec![(
+            "main.tri".to_string(),
+            "program {{project_name}}\n".to_string(),
+        )],
+    };
+    let good = format_template_json(&template);
+    let tampered = good.replace("program", "prog");
+    let (url, heads) = serve(vec![ok_response(None, &good), ok_response(None, &tampered)]);
+    let client = RegistryClient::new(&url).with_cache(None);
+    assert_eq!(client.pull_template("app").unwrap(), template);
+    assert_eq!(
+        heads.recv().unwrap().request(),
+        Some(("GET", "/api/v1/templates/app"))
+    );
+    let err = client.pull_template("app").unwrap_err();
+    assert!(err.contains("does not match"), "{}", err);
+}
@@ -29,6 +29,8 @@ pub struct PublishedDefinition {
     pub verified: bool,
     /// Verification certificate (opaque string, if available).
     pub verification_cert: Option<String>,
+    /// Rows per execution table for one call (see `definition_cost`).
+    pub cost: Vec<(String, u64)>,
 }
 
 /// Search result entry.
@@ -40,6 +42,28 @@ pub struct SearchResult {
     pub signature: String,
     pub verified: bool,
     pub tags: Vec<String>,
+    /// Published rows per execution table; empty if none were published.
+    pub cost: Vec<(String, u64)>,
+}
+
+impl SearchResult {
+    /// Rows in `table`, if the definition's cost was published.
+    pub fn table_rows(&self, table: &str) -> Option<u64> {
+        self.cost.iter().find(|(t, _)| t == table).map(|(_, r)| *r)
+    }
+
+    /// Whether every limit `(table, max rows)` holds. A definition without
+    /// a published cost satisfies none.
+    pub fn within(&self, limits: &[(String, u64)]) -> bool {
+        limits
+            .iter()
+            .all(|(table, max)| self.table_rows(table).is_some_and(|rows| rows <= *max))
+    }
+
+    /// The tallest table's rows, for ordering results by cost.
+    pub fn max_rows(&self) -> Option<u64> {
+        self.cost.iter().map(|(_, r)| *r).max()
+    }
 }
 
 /// Result of a publish operation.
@@ -61,6 +85,10 @@ pub struct PullResult {
     pub dependencies: Vec<String>,
     pub requires: Vec<String>,
     pub ensures: Vec<String>,
+    /// Verification certificate as published, if any.
+    pub verification_cert: Option<String>,
+    /// Whether the certificate held when re-checked on pull.
+    pub verified: bool,
 }
 
 // ─── Registry Client ──────────────────────────────────────────────
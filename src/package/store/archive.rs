@@ -0,0 +1,363 @@
+//! Codebase archives: the whole store in one tar file.
+//!
+//! `export_archive` writes every definition, the name bindings and their
+//! history as a ustar archive laid out like the store directory, led by a
+//! `MANIFEST` that lists each file with its size and BLAKE3 hash.
+//! `import_archive` checks the manifest against the entries and re-hashes
+//! every definition before anything is merged, so a truncated or edited
+//! archive leaves the codebase untouched. An archive written under an
+//! older hash version is re-keyed to the current one on import. Being
+//! plain tar, an archive can also be unpacked by hand into an empty store
+//! directory.
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::hash::{ContentHash, HASH_VERSION};
+
+use super::persist::{deserialize_definition, serialize_definition, unix_timestamp};
+use super::{Codebase, Definition};
+
+/// First line of every archive manifest.
+pub const ARCHIVE_FORMAT: &str = "trident-codebase-archive 1";
+
+const MANIFEST: &str = "MANIFEST";
+const BLOCK: usize = 512;
+
+/// What an archive holds.
+pub struct ArchiveSummary {
+    pub definitions: usize,
+    pub names: usize,
+    /// Total size of the archive file in bytes.
+    pub bytes: usize,
+}
+
+/// Result of importing an archive into a codebase.
+pub struct ImportResult {
+    /// Definitions not stored before.
+    pub added: usize,
+    /// Definitions already stored under the same hash.
+    pub existing: usize,
+    /// Names bound to a different hash than before, or not bound at all.
+    pub names_bound: usize,
+}
+
+impl Codebase {
+    /// Write the whole codebase to a tar archive at `path`.
+    pub fn export_archive(&self, path: &Path) -> std::io::Result<ArchiveSummary> {
+        let mut files: Vec<(String, Vec<u8>)> = self
+            .definitions
+            .iter()
+            .map(|(hash, def)| (def_entry_path(hash), serialize_definition(def).into_bytes()))
+            .collect();
+        files.push(("names.txt".to_string(), self.names_text().into_bytes()));
+        files.push(("history.txt".to_string(), self.history_text().into_bytes()));
+
+        let created = unix_timestamp();
+        let mut manifest = format!(
+            "{}\ncreated {}\nhash_version {}\ndefinitions {}\nnames {}\n",
+            ARCHIVE_FORMAT,
+            created,
+            HASH_VERSION,
+            self.definitions.len(),
+            self.names.len()
+        );
+        for (name, data) in &files {
+            manifest.push_str(&format!(
+                "file {} {} {}\n",
+                blake3::hash(data).to_hex(),
+                data.len(),
+                name
+            ));
+        }
+
+        let mut tar = Vec::new();
+        write_tar_entry(&mut tar, MANIFEST, manifest.as_bytes(), created);
+        for (name, data) in &files {
+            write_tar_entry(&mut tar, name, data, created);
+        }
+        tar.resize(tar.len() + 2 * BLOCK, 0);
+        std::fs::write(path, &tar)?;
+
+        Ok(ArchiveSummary {
+            definitions: self.definitions.len(),
+            names: self.names.len(),
+            bytes: tar.len(),
+        })
+    }
+
+    /// Merge the archive at `path` into the codebase and save it.
+    ///
+    /// The archive is checked in full first: every file against the
+    /// manifest, every definition against its hash, every dependency and
+    /// name against the definitions. Names in the archive override the
+    /// current bindings; history entries are added once.
+    pub fn import_archive(&mut self, path: &Path) -> Result<ImportResult, String> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+        let staged = self.stage_archive(&read_tar(&bytes)?)?;
+
+        let mut result = ImportResult {
+            added: 0,
+            existing: 0,
+            names_bound: 0,
+        };
+        for (hash, def) in staged.definitions {
+            match self.definitions.entry(hash) {
+                Entry::Occupied(_) => result.existing += 1,
+                Entry::Vacant(slot) => {
+                    slot.insert(def);
+                    result.added += 1;
+                }
+            }
+        }
+        for (name, hash) in staged.names {
+            if self.names.get(&name) != Some(&hash) {
+                self.names.insert(name, hash);
+                result.names_bound += 1;
+            }
+        }
+        for (hash, entries) in staged.name_history {
+            let history = self.name_history.entry(hash).or_default();
+            for entry in entries {
+                let known = history
+                    .iter()
+                    .any(|e| e.name == entry.name && e.timestamp == entry.timestamp);
+                if !known {
+                    history.push(entry);
+                }
+            }
+        }
+
+        self.save()
+            .map_err(|e| format!("cannot save codebase: {}", e))?;
+        Ok(result)
+    }
+
+    /// The codebase held by archive `entries`, after checking it whole.
+    fn stage_archive(&self, entries: &[(String, Vec<u8>)]) -> Result<Codebase, String> {
+        let (first, data) = entries.first().ok_or("archive is empty")?;
+        if first != MANIFEST {
+            return Err(format!("archive does not start with a {}", MANIFEST));
+        }
+        let manifest = std::str::from_utf8(data).map_err(|_| "manifest is not UTF-8")?;
+        let mut lines = manifest.lines();
+        if lines.next() != Some(ARCHIVE_FORMAT) {
+            return Err(format!(
+                "not a codebase archive (expected '{}')",
+                ARCHIVE_FORMAT
+            ));
+        }
+
+        let contents: BTreeMap<&str, &[u8]> = entries[1..]
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice()))
+            .collect();
+        let mut listed: BTreeMap<&str, &[u8]> = BTreeMap::new();
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for line in lines {
+            let parts: Vec<&str> = line.splitn(4, ' ').collect();
+            match parts.as_slice() {
+                ["file", hash, size, name] => {
+                    let data = contents
+                        .get(name)
+                        .ok_or_else(|| format!("'{}' is listed but missing", name))?;
+                    if size.parse() != Ok(data.len())
+                        || *hash != blake3::hash(data).to_hex().as_str()
+                    {
+                        return Err(format!("'{}' does not match the manifest", name));
+                    }
+                    listed.insert(name, data);
+                }
+                [key @ ("hash_version" | "definitions" | "names"), count] => {
+                    let count = count
+                        .parse()
+                        .map_err(|_| format!("bad manifest line '{}'", line))?;
+                    counts.insert(key, count);
+                }
+                ["created", _] => {}
+                _ => return Err(format!("bad manifest line '{}'", line)),
+            }
+        }
+        if let Some(extra) = contents.keys().find(|name| !listed.contains_key(*name)) {
+            return Err(format!("'{}' is not listed in the manifest", extra));
+        }
+
+        let mut staged = Codebase {
+            definitions: BTreeMap::new(),
+            names: BTreeMap::new(),
+            name_history: BTreeMap::new(),
+            root: self.root.clone(),
+        };
+        let text = |name: &str| -> Result<&str, String> {
+            let data = listed.get(name).copied().unwrap_or_default();
+            std::str::from_utf8(data).map_err(|_| format!("'{}' is not UTF-8", name))
+        };
+        staged.read_names(text("names.txt")?);
+        staged.read_history(text("history.txt")?);
+        for name in listed.keys().filter(|n| n.starts_with("defs/")) {
+            let hash = def_entry_hash(name)
+                .ok_or_else(|| format!("'{}' is not named by a content hash", name))?;
+            let def = deserialize_definition(text(name)?)
+                .ok_or_else(|| format!("'{}' is not a definition", name))?;
+            staged.definitions.insert(hash, def);
+        }
+
+        if counts.get("definitions") != Some(&staged.definitions.len())
+            || counts.get("names") != Some(&staged.names.len())
+        {
+            return Err("manifest counts do not match the archive".to_string());
+        }
+        // Archives without a hash version predate it and hold version 1
+        // hashes. Their files are still checked against the manifest;
+        // the definitions are re-keyed like an opened codebase.
+        let version = counts.get("hash_version").copied().unwrap_or(1);
+        if version > HASH_VERSION as usize {
+            return Err(format!(
+                "archive uses hash version {}, newer than this compiler's {}",
+                version, HASH_VERSION
+            ));
+        }
+        if version < HASH_VERSION as usize {
+            let migration = staged.rehash_all(version as u8);
+            if let Some(hash) = migration.kept.first() {
+                return Err(format!(
+                    "definition {} cannot be re-hashed to hash version {}",
+                    hash, HASH_VERSION
+                ));
+            }
+        }
+        for (hash, def) in &staged.definitions {
+            let computed = staged.rehash(def, &BTreeMap::new())?;
+            if computed != *hash {
+                return Err(format!(
+                    "definition {} hashes to {}; the archive was altered",
+                    hash, computed
+                ));
+            }
+            check_dependencies(hash, def, &staged.definitions)?;
+        }
+        if let Some((name, hash)) = staged
+            .names
+            .iter()
+            .find(|(_, hash)| !staged.definitions.contains_key(hash))
+        {
+            return Err(format!(
+                "name '{}' points to missing definition {}",
+                name, hash
+            ));
+        }
+        Ok(staged)
+    }
+}
+
+fn check_dependencies(
+    hash: &ContentHash,
+    def: &Definition,
+    definitions: &BTreeMap<ContentHash, Definition>,
+) -> Result<(), String> {
+    match def
+        .dependencies
+        .iter()
+        .find(|d| !definitions.contains_key(d))
+    {
+        Some(dep) => Err(format!(
+            "{} depends on {} which is not in the archive",
+            hash, dep
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Archive path of a definition, as in the store: `defs/<prefix>/<hex>.def`.
+fn def_entry_path(hash: &ContentHash) -> String {
+    let hex = hash.to_hex();
+    format!("defs/{}/{}.def", &hex[..2], hex)
+}
+
+fn def_entry_hash(path: &str) -> Option<ContentHash> {
+    let file = path.rsplit('/').next()?;
+    ContentHash::from_hex(file.strip_suffix(".def")?)
+}
+
+// ─── Tar ───────────────────────────────────────────────────────────
+
+/// Append a regular file to a ustar archive. `path` fits the 100-byte
+/// name field: store paths are at most 76 bytes.
+pub(super) fn write_tar_entry(out: &mut Vec<u8>, path: &str, data: &[u8], mtime: u64) {
+    let mut header = [0u8; BLOCK];
+    header[..path.len()].copy_from_slice(path.as_bytes());
+    write_octal(&mut header[100..108], 0o644);
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_octal(&mut header[124..136], data.len() as u64);
+    write_octal(&mut header[136..148], mtime);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    let checksum = format!("{:06o}\0 ", header_checksum(&header));
+    header[148..156].copy_from_slice(checksum.as_bytes());
+
+    out.extend_from_slice(&header);
+    out.extend_from_slice(data);
+    out.resize(out.len().next_multiple_of(BLOCK), 0);
+}
+
+/// The regular files of a tar archive, in order, with their contents.
+pub(super) fn read_tar(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    while pos + BLOCK <= bytes.len() {
+        let header = &bytes[pos..pos + BLOCK];
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        if read_octal(&header[148..156]) != Some(header_checksum(header)) {
+            return Err(format!("corrupt tar header at byte {}", pos));
+        }
+        let size = read_octal(&header[124..136])
+            .ok_or_else(|| format!("corrupt tar header at byte {}", pos))?
+            as usize;
+        let start = pos + BLOCK;
+        let data = bytes
+            .get(start..start + size)
+            .ok_or("archive is truncated")?;
+        if matches!(header[156], b'0' | 0) {
+            let name = field_str(&header[..100]);
+            let prefix = field_str(&header[345..500]);
+            let path = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            entries.push((path, data.to_vec()));
+        }
+        pos = start + size.next_multiple_of(BLOCK);
+    }
+    Ok(entries)
+}
+
+/// Sum of the header bytes with the checksum field read as spaces.
+fn header_checksum(header: &[u8]) -> u64 {
+    header
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| if (148..156).contains(&i) { b' ' } else { b } as u64)
+        .sum()
+}
+
+fn write_octal(field: &mut [u8], value: u64) {
+    let text = format!("{:0width$o}\0", value, width = field.len() - 1);
+    field.copy_from_slice(text.as_bytes());
+}
+
+fn read_octal(field: &[u8]) -> Option<u64> {
+    let text = field_str(field);
+    u64::from_str_radix(text.trim(), 8).ok()
+}
+
+fn field_str(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
@@ -0,0 +1,157 @@
+//! Verification certificates for stored definitions.
+//!
+//! A certificate records that a definition's proof obligations were
+//! discharged: the hash of the constraint system the verifier built, the
+//! verifier version, the verdict, and the seed the checks ran with. The
+//! system is built from the definition's source alone, with calls to its
+//! dependencies left opaque, so anyone holding the source can rebuild it
+//! and re-run the checks. `check` does exactly that, which is what makes a
+//! certificate pulled from a registry worth trusting.
+//!
+//! Text form (one line, stored in `.def` files and published as
+//! `verification_cert`):
+//! ```text
+//! cert-v1 system=<hex> verifier=<version> verdict=safe seed=<u64>
+//! ```
+
+use crate::ast::Item;
+use crate::hash::ContentHash;
+use crate::solve::{self, format_assumption, format_constraint, format_sym_value, VerifyConfig};
+use crate::sym::{self, ConstraintSystem};
+
+const CERT_VERSION: &str = "cert-v1";
+
+/// Proof that a definition's obligations were discharged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Certificate {
+    /// Hash of the constraint system the verifier built.
+    pub system: ContentHash,
+    /// Version of the verifier that issued it.
+    pub verifier: String,
+    /// Verdict of the checks; only `safe` is ever issued.
+    pub verdict: String,
+    /// Seed of the random checks; bounded model checking derives its own
+    /// from it.
+    pub seed: u64,
+}
+
+impl Certificate {
+    /// Verify the function in `source` and certify it if it is safe.
+    pub fn issue(source: &str) -> Result<Certificate, String> {
+        let system = build_system(source)?;
+        let config = VerifyConfig::default();
+        let report = solve::verify_with(&system, &config);
+        if !report.is_safe() {
+            return Err(format!("verification failed ({:?})", report.verdict));
+        }
+        Ok(Certificate {
+            system: system_hash(&system),
+            verifier: env!("CARGO_PKG_VERSION").to_string(),
+            verdict: "safe".to_string(),
+            seed: config.seed,
+        })
+    }
+
+    /// Rebuild the constraint system of `source` and re-run the checks
+    /// with the certificate's seed.
+    pub fn check(&self, source: &str) -> Result<(), String> {
+        if self.verdict != "safe" {
+            return Err(format!("verdict is '{}'", self.verdict));
+        }
+        let system = build_system(source)?;
+        let computed = system_hash(&system);
+        if computed != self.system {
+            return Err(format!(
+                "constraint system hashes to {} but the certificate covers {}",
+                computed, self.system
+            ));
+        }
+        let config = VerifyConfig {
+            seed: self.seed,
+            ..VerifyConfig::default()
+        };
+        let report = solve::verify_with(&system, &config);
+        if !report.is_safe() {
+            return Err(format!("verification failed ({:?})", report.verdict));
+        }
+        Ok(())
+    }
+
+    /// Parse the text form.
+    pub fn parse(text: &str) -> Option<Certificate> {
+        let mut fields = text.split_whitespace();
+        if fields.next()? != CERT_VERSION {
+            return None;
+        }
+        let (mut system, mut verifier, mut verdict, mut seed) = (None, None, None, None);
+        for field in fields {
+            let (key, value) = field.split_once('=')?;
+            match key {
+                "system" => system = ContentHash::from_hex(value),
+                "verifier" => verifier = Some(value.to_string()),
+                "verdict" => verdict = Some(value.to_string()),
+                "seed" => seed = value.parse().ok(),
+                _ => {}
+            }
+        }
+        Some(Certificate {
+            system: system?,
+            verifier: verifier?,
+            verdict: verdict?,
+            seed: seed?,
+        })
+    }
+}
+
+impl std::fmt::Display for Certificate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} system={} verifier={} verdict={} seed={}",
+            CERT_VERSION,
+            self.system.to_hex(),
+            self.verifier,
+            self.verdict,
+            self.seed
+        )
+    }
+}
+
+/// The constraint system of the only function in `source`.
+fn build_system(source: &str) -> Result<ConstraintSystem, String> {
+    let wrapped = format!("module codebase\n{}\n", source);
+    let file = crate::parse_source_silent(&wrapped, "<codebase>")
+        .map_err(|errors| format!("source does not parse ({} errors)", errors.len()))?;
+    let name = file
+        .items
+        .iter()
+        .find_map(|item| match &item.node {
+            Item::Fn(func) => Some(func.name.node.clone()),
+            _ => None,
+        })
+        .ok_or_else(|| "source contains no function".to_string())?;
+    Ok(sym::analyze_function(&file, &name))
+}
+
+/// Hash of the system's constraints, assumptions and outputs, as the
+/// solver formats them.
+fn system_hash(system: &ConstraintSystem) -> ContentHash {
+    let mut text = String::new();
+    for c in &system.constraints {
+        text.push_str(&format_constraint(c));
+        text.push('\n');
+    }
+    for a in &system.assumptions {
+        text.push_str("assume ");
+        text.push_str(&format_assumption(a));
+        text.push('\n');
+    }
+    for v in &system.pub_outputs {
+        text.push_str("output ");
+        text.push_str(&format_sym_value(v));
+        text.push('\n');
+    }
+    ContentHash(crate::field::poseidon2::hash_bytes_goldilocks(
+        text.as_bytes(),
+    ))
+}
@@ -70,13 +70,17 @@ fn walk_stmt_for_calls(
             walk_expr_for_calls(&end.node, fn_hashes, self_name, deps, seen);
             walk_block_for_calls(&body.node, fn_hashes, self_name, deps, seen);
         }
+        Stmt::While { cond, body, .. } => {
+            walk_expr_for_calls(&cond.node, fn_hashes, self_name, deps, seen);
+            walk_block_for_calls(&body.node, fn_hashes, self_name, deps, seen);
+        }
         Stmt::Expr(expr) => {
             walk_expr_for_calls(&expr.node, fn_hashes, self_name, deps, seen);
         }
         Stmt::Return(Some(expr)) => {
             walk_expr_for_calls(&expr.node, fn_hashes, self_name, deps, seen);
         }
-        Stmt::Return(None) | Stmt::Asm { .. } => {}
+        Stmt::Return(None) | Stmt::Break | Stmt::Continue | Stmt::Asm { .. } => {}
         Stmt::Reveal { fields, .. } | Stmt::Seal { fields, .. } => {
             for (_, val) in fields {
                 walk_expr_for_calls(&val.node, fn_hashes, self_name, deps, seen);
@@ -127,10 +131,13 @@ fn walk_expr_for_calls(
             walk_expr_for_calls(&expr.node, fn_hashes, self_name, deps, seen);
             walk_expr_for_calls(&index.node, fn_hashes, self_name, deps, seen);
         }
-        Expr::StructInit { fields, .. } => {
+        Expr::StructInit { fields, base, .. } => {
             for (_, val) in fields {
                 walk_expr_for_calls(&val.node, fn_hashes, self_name, deps, seen);
             }
+            if let Some(base) = base {
+                walk_expr_for_calls(&base.node, fn_hashes, self_name, deps, seen);
+            }
         }
         Expr::ArrayInit(elems) | Expr::Tuple(elems) => {
             for elem in elems {
@@ -1,5 +1,6 @@
 pub(super) use crate::ast::display::format_ast_type as format_type;
 use crate::ast::{self, Expr, Stmt};
+use crate::format::format_literal;
 
 // ─── Function Source Formatter ─────────────────────────────────────
 //
@@ -161,13 +162,24 @@ fn format_stmt(stmt: &Stmt, out: &mut String, pad: &str, indent: usize) {
             out.push_str(&format_expr(&end.node));
             if let Some(b) = bound {
                 out.push_str(" bounded ");
-                out.push_str(&b.to_string());
+                out.push_str(&format_expr(&b.node));
             }
             out.push_str(" {\n");
             format_block(&body.node, out, indent + 1);
             out.push_str(pad);
             out.push_str("}\n");
         }
+        Stmt::While { cond, bound, body } => {
+            out.push_str(pad);
+            out.push_str("while ");
+            out.push_str(&format_expr(&cond.node));
+            out.push_str(" bounded ");
+            out.push_str(&format_expr(&bound.node));
+            out.push_str(" {\n");
+            format_block(&body.node, out, indent + 1);
+            out.push_str(pad);
+            out.push_str("}\n");
+        }
         Stmt::Expr(expr) => {
             out.push_str(pad);
             out.push_str(&format_expr(&expr.node));
@@ -182,6 +194,15 @@ fn format_stmt(stmt: &Stmt, out: &mut String, pad: &str, indent: usize) {
             }
             out.push('\n');
         }
+        Stmt::Break | Stmt::Continue => {
+            out.push_str(pad);
+            out.push_str(if matches!(stmt, Stmt::Break) {
+                "break"
+            } else {
+                "continue"
+            });
+            out.push('\n');
+        }
         Stmt::Reveal { event_name, fields } | Stmt::Seal { event_name, fields } => {
             let kw = if matches!(stmt, Stmt::Reveal { .. }) {
                 "reveal"
@@ -254,11 +275,8 @@ fn format_stmt(stmt: &Stmt, out: &mut String, pad: &str, indent: usize) {
             for arm in arms {
                 out.push_str(&arm_pad);
                 match &arm.pattern.node {
-                    ast::MatchPattern::Literal(ast::Literal::Integer(n)) => {
-                        out.push_str(&n.to_string());
-                    }
-                    ast::MatchPattern::Literal(ast::Literal::Bool(b)) => {
-                        out.push_str(if *b { "true" } else { "false" });
+                    ast::MatchPattern::Literal(lit) => {
+                        out.push_str(&format_literal(lit));
                     }
                     ast::MatchPattern::Wildcard => {
                         out.push('_');
@@ -277,13 +295,9 @@ fn format_stmt(stmt: &Stmt, out: &mut String, pad: &str, indent: usize) {
                                     out.push_str(": ");
                                     out.push_str(v);
                                 }
-                                ast::FieldPattern::Literal(ast::Literal::Integer(n)) => {
-                                    out.push_str(": ");
-                                    out.push_str(&n.to_string());
-                                }
-                                ast::FieldPattern::Literal(ast::Literal::Bool(b)) => {
+                                ast::FieldPattern::Literal(lit) => {
                                     out.push_str(": ");
-                                    out.push_str(if *b { "true" } else { "false" });
+                                    out.push_str(&format_literal(lit));
                                 }
                                 ast::FieldPattern::Wildcard => {
                                     out.push_str(": _");
@@ -292,6 +306,16 @@ fn format_stmt(stmt: &Stmt, out: &mut String, pad: &str, indent: usize) {
                         }
                         out.push_str(" }");
                     }
+                    ast::MatchPattern::Variant { path, bindings } => {
+                        out.push_str(&path.node.as_dotted());
+                        if !bindings.is_empty() {
+                            let names: Vec<&str> =
+                                bindings.iter().map(|b| b.node.as_str()).collect();
+                            out.push('(');
+                            out.push_str(&names.join(", "));
+                            out.push(')');
+                        }
+                    }
                 }
                 out.push_str(" => {\n");
                 format_block(&arm.body.node, out, indent + 2);
@@ -306,8 +330,7 @@ fn format_stmt(stmt: &Stmt, out: &mut String, pad: &str, indent: usize) {
 
 fn format_expr(expr: &Expr) -> String {
     match expr {
-        Expr::Literal(ast::Literal::Integer(n)) => n.to_string(),
-        Expr::Literal(ast::Literal::Bool(b)) => b.to_string(),
+        Expr::Literal(lit) => format_literal(lit),
         Expr::Var(name) => name.clone(),
         Expr::BinOp { op, lhs, rhs } => {
             let l = format_expr_prec(&lhs.node, op, true);
@@ -338,11 +361,14 @@ fn format_expr(expr: &Expr) -> String {
         Expr::Index { expr, index } => {
             format!("{}[{}]", format_expr(&expr.node), format_expr(&index.node))
         }
-        Expr::StructInit { path, fields } => {
-            let fields_str: Vec<String> = fields
+        Expr::StructInit { path, fields, base } => {
+            let mut fields_str: Vec<String> = fields
                 .iter()
                 .map(|(name, val)| format!("{}: {}", name.node, format_expr(&val.node)))
                 .collect();
+            if let Some(base) = base {
+                fields_str.push(format!("..{}", format_expr(&base.node)));
+            }
             format!("{} {{ {} }}", path.node.as_dotted(), fields_str.join(", "))
         }
         Expr::ArrayInit(elems) => {
@@ -367,12 +393,14 @@ fn format_expr_prec(expr: &Expr, parent_op: &ast::BinOp, _is_left: bool) -> Stri
 
 fn op_precedence(op: &ast::BinOp) -> u8 {
     match op {
-        ast::BinOp::Eq => 2,
-        ast::BinOp::Lt => 4,
-        ast::BinOp::Add => 6,
-        ast::BinOp::Mul | ast::BinOp::XFieldMul => 8,
-        ast::BinOp::BitAnd | ast::BinOp::BitXor => 10,
-        ast::BinOp::DivMod => 12,
+        ast::BinOp::Or => 1,
+        ast::BinOp::And => 3,
+        ast::BinOp::Eq => 5,
+        ast::BinOp::Lt => 7,
+        ast::BinOp::Add => 9,
+        ast::BinOp::Mul | ast::BinOp::XFieldMul => 11,
+        ast::BinOp::BitAnd | ast::BinOp::BitXor | ast::BinOp::BitOr => 13,
+        ast::BinOp::DivMod => 15,
     }
 }
 
@@ -0,0 +1,297 @@
+//! Codebase maintenance: garbage collection and integrity checking.
+//!
+//! The store is append-only during normal use. `gc` drops definitions
+//! that no name binding (or extra root, e.g. lockfile hashes) can reach
+//! through the dependency graph. `verify` re-reads every `.def` file from
+//! disk, re-hashes its source, and checks that dependency closures and
+//! name bindings only point at stored definitions.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::ast::Item;
+use crate::hash::{self, ContentHash};
+
+use super::persist::deserialize_definition;
+use super::{Codebase, Definition};
+
+// ─── Data Structures ───────────────────────────────────────────────
+
+/// Result of a garbage collection run.
+pub struct GcResult {
+    /// Hashes of removed definitions (sorted).
+    pub removed: Vec<ContentHash>,
+    /// Number of definitions kept.
+    pub retained: usize,
+}
+
+/// A single problem found by `Codebase::verify`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// A `.def` file could not be read, parsed, or named by a valid hash.
+    Unreadable { path: PathBuf, reason: String },
+    /// Re-hashing the stored source does not reproduce the stored hash.
+    HashMismatch {
+        stored: ContentHash,
+        computed: ContentHash,
+    },
+    /// A definition depends on a hash that is not in the codebase.
+    MissingDependency {
+        hash: ContentHash,
+        dependency: ContentHash,
+    },
+    /// A name is bound to a hash that is not in the codebase.
+    DanglingName { name: String, hash: ContentHash },
+}
+
+impl std::fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityIssue::Unreadable { path, reason } => {
+                write!(f, "unreadable definition '{}': {}", path.display(), reason)
+            }
+            IntegrityIssue::HashMismatch { stored, computed } => write!(
+                f,
+                "hash mismatch: stored as {} but source hashes to {}",
+                stored, computed
+            ),
+            IntegrityIssue::MissingDependency { hash, dependency } => write!(
+                f,
+                "{} depends on {} which is not in the codebase",
+                hash, dependency
+            ),
+            IntegrityIssue::DanglingName { name, hash } => {
+                write!(f, "name '{}' points to missing definition {}", name, hash)
+            }
+        }
+    }
+}
+
+/// Result of an integrity check.
+pub struct IntegrityReport {
+    /// Number of `.def` files examined.
+    pub checked: usize,
+    /// Problems found, in discovery order.
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    /// True when no issues were found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+// ─── Garbage Collection ────────────────────────────────────────────
+
+impl Codebase {
+    /// All hashes reachable from current name bindings and `extra_roots`
+    /// through dependency edges.
+    pub fn reachable(&self, extra_roots: &[ContentHash]) -> BTreeSet<ContentHash> {
+        let mut reachable = BTreeSet::new();
+        let mut worklist: Vec<ContentHash> = self.names.values().copied().collect();
+        worklist.extend_from_slice(extra_roots);
+
+        while let Some(hash) = worklist.pop() {
+            if !reachable.insert(hash) {
+                continue;
+            }
+            if let Some(def) = self.definitions.get(&hash) {
+                worklist.extend(def.dependencies.iter().copied());
+            }
+        }
+        reachable
+    }
+
+    /// Definitions that `gc` would remove, sorted by hash.
+    pub fn unreachable(&self, extra_roots: &[ContentHash]) -> Vec<ContentHash> {
+        let reachable = self.reachable(extra_roots);
+        self.definitions
+            .keys()
+            .filter(|h| !reachable.contains(h))
+            .copied()
+            .collect()
+    }
+
+    /// Remove unreachable definitions from memory and disk, then save.
+    ///
+    /// History entries for removed hashes are dropped with them: they
+    /// would otherwise point at definitions that no longer exist.
+    pub fn gc(&mut self, extra_roots: &[ContentHash]) -> std::io::Result<GcResult> {
+        let removed = self.unreachable(extra_roots);
+        for hash in &removed {
+            self.definitions.remove(hash);
+            self.name_history.remove(hash);
+            match std::fs::remove_file(self.def_path(hash)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.save()?;
+        Ok(GcResult {
+            removed,
+            retained: self.definitions.len(),
+        })
+    }
+
+    /// On-disk path of a definition file.
+    pub(super) fn def_path(&self, hash: &ContentHash) -> PathBuf {
+        let hex = hash.to_hex();
+        self.root
+            .join("defs")
+            .join(&hex[..2])
+            .join(format!("{}.def", hex))
+    }
+}
+
+// ─── Integrity Check ───────────────────────────────────────────────
+
+impl Codebase {
+    /// Re-hash every stored definition and validate the dependency graph.
+    ///
+    /// Reads `.def` files from disk rather than the in-memory map, because
+    /// `load` silently skips files it cannot parse.
+    pub fn verify(&self) -> std::io::Result<IntegrityReport> {
+        let mut checked = 0usize;
+        let mut issues = Vec::new();
+        let mut on_disk: BTreeMap<ContentHash, Definition> = BTreeMap::new();
+
+        for path in def_files(&self.root.join("defs"))? {
+            checked += 1;
+            let stored = match path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(ContentHash::from_hex)
+            {
+                Some(h) => h,
+                None => {
+                    issues.push(IntegrityIssue::Unreadable {
+                        path,
+                        reason: "file name is not a content hash".to_string(),
+                    });
+                    continue;
+                }
+            };
+            let def = match std::fs::read_to_string(&path) {
+                Ok(text) => match deserialize_definition(&text) {
+                    Some(def) => def,
+                    None => {
+                        issues.push(IntegrityIssue::Unreadable {
+                            path,
+                            reason: "missing source field".to_string(),
+                        });
+                        continue;
+                    }
+                },
+                Err(e) => {
+                    issues.push(IntegrityIssue::Unreadable {
+                        path,
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            match self.rehash(&def, &BTreeMap::new()) {
+                Ok(computed) if computed != stored => {
+                    issues.push(IntegrityIssue::HashMismatch { stored, computed });
+                }
+                Ok(_) => {}
+                Err(reason) => issues.push(IntegrityIssue::Unreadable { path, reason }),
+            }
+            on_disk.insert(stored, def);
+        }
+
+        for (hash, def) in &on_disk {
+            for dep in &def.dependencies {
+                if !on_disk.contains_key(dep) {
+                    issues.push(IntegrityIssue::MissingDependency {
+                        hash: *hash,
+                        dependency: *dep,
+                    });
+                }
+            }
+        }
+
+        for (name, hash) in &self.names {
+            if !on_disk.contains_key(hash) {
+                issues.push(IntegrityIssue::DanglingName {
+                    name: name.clone(),
+                    hash: *hash,
+                });
+            }
+        }
+
+        Ok(IntegrityReport { checked, issues })
+    }
+
+    /// Recompute the content hash of a stored definition from its source.
+    ///
+    /// Calls are resolved to dependency hashes through every name each
+    /// dependency has ever been bound to, mirroring how `hash_file`
+    /// resolved them when the definition was added. Dependencies listed
+    /// in `substitute` resolve to their replacement hash instead.
+    pub(super) fn rehash(
+        &self,
+        def: &Definition,
+        substitute: &BTreeMap<ContentHash, ContentHash>,
+    ) -> Result<ContentHash, String> {
+        let wrapped = format!("module codebase\n{}\n", def.source);
+        let file = crate::parse_source_silent(&wrapped, "<codebase>")
+            .map_err(|errors| format!("source does not parse ({} errors)", errors.len()))?;
+        let func = file
+            .items
+            .iter()
+            .find_map(|item| match &item.node {
+                Item::Fn(func) => Some(func),
+                _ => None,
+            })
+            .ok_or_else(|| "source contains no function".to_string())?;
+
+        let mut deps = BTreeMap::new();
+        for dep in &def.dependencies {
+            let target = substitute.get(dep).copied().unwrap_or(*dep);
+            for name in self.call_names(dep) {
+                deps.insert(name, target);
+            }
+        }
+        Ok(hash::hash_function(func, deps))
+    }
+}
+
+impl Codebase {
+    /// Every name a definition is or has been bound to.
+    pub(super) fn call_names(&self, hash: &ContentHash) -> BTreeSet<String> {
+        let mut names: BTreeSet<String> = self
+            .name_history
+            .get(hash)
+            .into_iter()
+            .flatten()
+            .map(|entry| entry.name.clone())
+            .collect();
+        names.extend(self.names_for_hash(hash).into_iter().map(str::to_string));
+        names
+    }
+}
+
+/// All `.def` files under `defs/<prefix>/`, sorted for deterministic reports.
+fn def_files(defs_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !defs_dir.is_dir() {
+        return Ok(files);
+    }
+    for prefix_entry in std::fs::read_dir(defs_dir)? {
+        let prefix_entry = prefix_entry?;
+        if !prefix_entry.file_type()?.is_dir() {
+            continue;
+        }
+        for def_entry in std::fs::read_dir(prefix_entry.path())? {
+            let path = def_entry?.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("def") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
@@ -0,0 +1,180 @@
+//! Hash-format migration: re-key a codebase written under an older
+//! `hash::HASH_VERSION`.
+//!
+//! The codebase records the format its hashes were computed with in
+//! `hash_version`. A codebase with definitions but no such file predates
+//! it and was written with version 1. An older codebase is not opened
+//! until `trident store migrate` re-hashes every definition from its
+//! source, dependencies first, and moves the definitions, name bindings,
+//! and history to the new hashes.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::hash::{ContentHash, HASH_VERSION};
+
+use super::persist::atomic_write;
+use super::{default_root, Codebase};
+
+/// Result of migrating a codebase to the current hash format.
+pub struct MigrationResult {
+    /// Format the codebase was written with.
+    pub from_version: u8,
+    /// (old, new) for every definition whose hash changed.
+    pub rehashed: Vec<(ContentHash, ContentHash)>,
+    /// Definitions kept under their old hash: the source no longer
+    /// parses, or a dependency is not stored. `verify` reports them.
+    pub kept: Vec<ContentHash>,
+}
+
+impl Codebase {
+    /// The hash format recorded for this codebase.
+    pub fn hash_version(&self) -> std::io::Result<u8> {
+        let path = self.root.join("hash_version");
+        match std::fs::read_to_string(&path) {
+            Ok(text) => text.trim().parse().map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("'{}' is not a hash version", path.display()),
+                )
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(if self.definitions.is_empty() {
+                    HASH_VERSION
+                } else {
+                    1
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Open the codebase at the default location, migrating it to the
+    /// current hash format. See `migrate_at`.
+    pub fn migrate() -> std::io::Result<(Self, Option<MigrationResult>)> {
+        Self::migrate_at(&default_root()?)
+    }
+
+    /// Open the codebase at `root` and bring it to the current hash
+    /// format, saving it and removing the definition files under the old
+    /// hashes. The migration is `None` when it was already current.
+    pub fn migrate_at(root: &Path) -> std::io::Result<(Self, Option<MigrationResult>)> {
+        let mut cb = Self::load_at(root)?;
+        let from_version = cb.hash_version()?;
+        if from_version >= HASH_VERSION {
+            cb.check_hash_version()?;
+            return Ok((cb, None));
+        }
+        let result = cb.rehash_all(from_version);
+        cb.save()?;
+        for (old, _) in &result.rehashed {
+            match std::fs::remove_file(cb.def_path(old)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok((cb, Some(result)))
+    }
+
+    /// Refuse a codebase whose hashes were computed with another format.
+    pub(super) fn check_hash_version(&self) -> std::io::Result<()> {
+        let version = self.hash_version()?;
+        let problem = if version > HASH_VERSION {
+            "newer than this compiler's"
+        } else if version < HASH_VERSION {
+            "run `trident store migrate` to re-hash it to"
+        } else {
+            return Ok(());
+        };
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "codebase at '{}' uses hash version {}; {} {}",
+                self.root.display(),
+                version,
+                problem,
+                HASH_VERSION
+            ),
+        ))
+    }
+
+    /// Write `hash_version` unless it already records the current format.
+    pub(super) fn record_hash_version(&self) -> std::io::Result<()> {
+        let path = self.root.join("hash_version");
+        let recorded = std::fs::read_to_string(&path).ok();
+        if recorded.as_deref().map(str::trim) == Some(&HASH_VERSION.to_string()) {
+            return Ok(());
+        }
+        atomic_write(&path, &format!("{}\n", HASH_VERSION))
+    }
+
+    /// Re-hash every definition, dependencies before their callers, and
+    /// re-key the codebase under the new hashes.
+    pub(super) fn rehash_all(&mut self, from_version: u8) -> MigrationResult {
+        let mut substitute: BTreeMap<ContentHash, ContentHash> = BTreeMap::new();
+        let mut kept = Vec::new();
+        let mut pending: BTreeSet<ContentHash> = self.definitions.keys().copied().collect();
+        while !pending.is_empty() {
+            let ready: Vec<ContentHash> = pending
+                .iter()
+                .filter(|hash| {
+                    self.definitions[hash]
+                        .dependencies
+                        .iter()
+                        .all(|dep| !pending.contains(dep) || dep == *hash)
+                })
+                .copied()
+                .collect();
+            // The dependency graph is acyclic; a cycle means a corrupted
+            // store, and its members keep their hashes.
+            let batch = if ready.is_empty() {
+                std::mem::take(&mut pending).into_iter().collect()
+            } else {
+                ready
+            };
+            for hash in batch {
+                pending.remove(&hash);
+                let def = &self.definitions[&hash];
+                let complete = def
+                    .dependencies
+                    .iter()
+                    .all(|dep| substitute.contains_key(dep));
+                match self.rehash(def, &substitute) {
+                    Ok(new) if complete => {
+                        substitute.insert(hash, new);
+                    }
+                    _ => {
+                        substitute.insert(hash, hash);
+                        kept.push(hash);
+                    }
+                }
+            }
+        }
+
+        let definitions = std::mem::take(&mut self.definitions);
+        for (old, mut def) in definitions {
+            for dep in def.dependencies.iter_mut() {
+                *dep = substitute.get(dep).copied().unwrap_or(*dep);
+            }
+            self.definitions.insert(substitute[&old], def);
+        }
+        for hash in self.names.values_mut() {
+            *hash = substitute.get(hash).copied().unwrap_or(*hash);
+        }
+        let history = std::mem::take(&mut self.name_history);
+        for (old, entries) in history {
+            let new = substitute.get(&old).copied().unwrap_or(old);
+            self.name_history.entry(new).or_default().extend(entries);
+        }
+
+        MigrationResult {
+            from_version,
+            rehashed: substitute
+                .into_iter()
+                .filter(|(old, new)| old != new)
+                .collect(),
+            kept,
+        }
+    }
+}
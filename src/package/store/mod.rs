@@ -12,6 +12,7 @@
 //!       <full-hex-hash>.def
 //!   names.txt
 //!   history.txt
+//!   hash_version
 //! ```
 
 use std::collections::BTreeMap;
@@ -58,6 +59,8 @@ pub struct Definition {
     pub ensures: Vec<String>,
     /// When this was first stored (Unix timestamp).
     pub first_seen: u64,
+    /// Certificate of discharged proof obligations, if verified.
+    pub certificate: Option<Certificate>,
 }
 
 /// A name binding entry in history.
@@ -86,9 +89,17 @@ pub struct CodebaseStats {
     pub total_source_bytes: usize,
 }
 
+mod archive;
+mod cert;
 mod deps;
 mod format;
+mod integrity;
+mod migrate;
 mod persist;
+mod update;
+
+pub use cert::Certificate;
+pub use migrate::MigrationResult;
 
 use deps::extract_dependencies;
 use format::{format_fn_source, format_type};
@@ -99,22 +110,35 @@ mod tests;
 
 // ─── Codebase Implementation ───────────────────────────────────────
 
+/// `codebase_dir()`, or an error when there is no home directory.
+fn default_root() -> std::io::Result<PathBuf> {
+    codebase_dir().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "cannot determine codebase directory (no $HOME)",
+        )
+    })
+}
+
 impl Codebase {
     /// Open or create a codebase at the default location.
     ///
     /// Uses `$TRIDENT_CODEBASE_DIR` if set, otherwise `~/.trident/codebase/`.
     pub fn open() -> std::io::Result<Self> {
-        let root = codebase_dir().ok_or_else(|| {
-            std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "cannot determine codebase directory (no $HOME)",
-            )
-        })?;
-        Self::open_at(&root)
+        Self::open_at(&default_root()?)
     }
 
     /// Open or create a codebase at a specific directory.
+    ///
+    /// A codebase written with another `hash::HASH_VERSION` is refused;
+    /// an older one can be brought up to date with `migrate_at`.
     pub fn open_at(root: &Path) -> std::io::Result<Self> {
+        let cb = Self::load_at(root)?;
+        cb.check_hash_version()?;
+        Ok(cb)
+    }
+
+    fn load_at(root: &Path) -> std::io::Result<Self> {
         std::fs::create_dir_all(root)?;
         std::fs::create_dir_all(root.join("defs"))?;
 
@@ -162,6 +186,7 @@ impl Codebase {
                 let deps = extract_dependencies(func, &fn_hashes);
 
                 // Build the Definition.
+                let existing = self.definitions.get(&hash);
                 let def = Definition {
                     source: format_fn_source(func),
                     module: module.clone(),
@@ -175,11 +200,8 @@ impl Codebase {
                     dependencies: deps,
                     requires: func.requires.iter().map(|s| s.node.clone()).collect(),
                     ensures: func.ensures.iter().map(|s| s.node.clone()).collect(),
-                    first_seen: self
-                        .definitions
-                        .get(&hash)
-                        .map(|d| d.first_seen)
-                        .unwrap_or(now),
+                    first_seen: existing.map(|d| d.first_seen).unwrap_or(now),
+                    certificate: existing.and_then(|d| d.certificate.clone()),
                 };
 
                 self.definitions.insert(hash, def);
@@ -204,6 +226,7 @@ impl Codebase {
     }
 
     /// Look up a definition by name.
+    #[cfg(test)]
     pub fn lookup(&self, name: &str) -> Option<&Definition> {
         let hash = self.names.get(name)?;
         self.definitions.get(hash)
@@ -363,38 +386,9 @@ impl Codebase {
             atomic_write(&def_path, &content)?;
         }
 
-        // Write names.txt
-        let names_path = self.root.join("names.txt");
-        let mut names_content = String::new();
-        let mut sorted_names: Vec<_> = self.names.iter().collect();
-        sorted_names.sort_by_key(|(n, _)| (*n).clone());
-        for (name, hash) in sorted_names {
-            names_content.push_str(name);
-            names_content.push('=');
-            names_content.push_str(&hash.to_hex());
-            names_content.push('\n');
-        }
-        atomic_write(&names_path, &names_content)?;
-
-        // Write history.txt
-        let history_path = self.root.join("history.txt");
-        let mut history_content = String::new();
-        let mut all_entries: Vec<(&ContentHash, &NameEntry)> = Vec::new();
-        for (hash, entries) in &self.name_history {
-            for entry in entries {
-                all_entries.push((hash, entry));
-            }
-        }
-        all_entries.sort_by_key(|(_, e)| e.timestamp);
-        for (hash, entry) in all_entries {
-            history_content.push_str(&entry.name);
-            history_content.push(' ');
-            history_content.push_str(&hash.to_hex());
-            history_content.push(' ');
-            history_content.push_str(&entry.timestamp.to_string());
-            history_content.push('\n');
-        }
-        atomic_write(&history_path, &history_content)?;
+        atomic_write(&self.root.join("names.txt"), &self.names_text())?;
+        atomic_write(&self.root.join("history.txt"), &self.history_text())?;
+        self.record_hash_version()?;
 
         Ok(())
     }
@@ -404,6 +398,17 @@ impl Codebase {
         self.definitions.insert(hash, def);
     }
 
+    /// Verify a stored definition and attach a certificate if its proof
+    /// obligations are discharged.
+    pub fn certify(&mut self, hash: &ContentHash) -> Result<&Certificate, String> {
+        let def = self
+            .definitions
+            .get_mut(hash)
+            .ok_or_else(|| format!("definition {} not found", hash))?;
+        let cert = Certificate::issue(&def.source)?;
+        Ok(def.certificate.insert(cert))
+    }
+
     /// Bind a name to a hash directly (used by registry pull).
     pub fn bind_name(&mut self, name: &str, hash: ContentHash) {
         self.names.insert(name.to_string(), hash);
@@ -437,6 +442,11 @@ impl Codebase {
             out.push('\n');
         }
 
+        // Certificate
+        if let Some(cert) = &def.certificate {
+            out.push_str(&format!("\n-- Verified: {}\n", cert));
+        }
+
         // Dependencies
         if !def.dependencies.is_empty() {
             out.push_str("\n-- Dependencies:\n");
@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 
 use crate::hash::ContentHash;
 
-use super::{Codebase, Definition, NameEntry};
+use super::{Certificate, Codebase, Definition, NameEntry};
 
 /// Atomically write `contents` to `path` using write-to-temp-then-rename.
 ///
@@ -62,17 +62,7 @@ impl Codebase {
             Err(e) => return Err(e),
         };
 
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
-            if let Some((name, hex)) = line.split_once('=') {
-                if let Some(hash) = ContentHash::from_hex(hex.trim()) {
-                    self.names.insert(name.trim().to_string(), hash);
-                }
-            }
-        }
+        self.read_names(&content);
         Ok(())
     }
 
@@ -119,6 +109,30 @@ impl Codebase {
             Err(e) => return Err(e),
         };
 
+        self.read_history(&content);
+        Ok(())
+    }
+
+    // ─── Persistence: Text Formats ─────────────────────────────
+
+    /// Bind the names listed in `names.txt` content (`name=hash` lines).
+    pub(super) fn read_names(&mut self, content: &str) {
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some((name, hex)) = line.split_once('=') {
+                if let Some(hash) = ContentHash::from_hex(hex.trim()) {
+                    self.names.insert(name.trim().to_string(), hash);
+                }
+            }
+        }
+    }
+
+    /// Append the entries of `history.txt` content (`name hash timestamp`
+    /// lines) to the name history.
+    pub(super) fn read_history(&mut self, content: &str) {
         for line in content.lines() {
             let line = line.trim();
             if line.is_empty() {
@@ -137,7 +151,39 @@ impl Codebase {
             let entry = NameEntry { name, timestamp };
             self.name_history.entry(hash).or_default().push(entry);
         }
-        Ok(())
+    }
+
+    /// The `names.txt` content for the current bindings, sorted by name.
+    pub(super) fn names_text(&self) -> String {
+        let mut names_content = String::new();
+        for (name, hash) in &self.names {
+            names_content.push_str(name);
+            names_content.push('=');
+            names_content.push_str(&hash.to_hex());
+            names_content.push('\n');
+        }
+        names_content
+    }
+
+    /// The `history.txt` content, oldest entry first.
+    pub(super) fn history_text(&self) -> String {
+        let mut history_content = String::new();
+        let mut all_entries: Vec<(&ContentHash, &NameEntry)> = Vec::new();
+        for (hash, entries) in &self.name_history {
+            for entry in entries {
+                all_entries.push((hash, entry));
+            }
+        }
+        all_entries.sort_by_key(|(_, e)| e.timestamp);
+        for (hash, entry) in all_entries {
+            history_content.push_str(&entry.name);
+            history_content.push(' ');
+            history_content.push_str(&hash.to_hex());
+            history_content.push(' ');
+            history_content.push_str(&entry.timestamp.to_string());
+            history_content.push('\n');
+        }
+        history_content
     }
 }
 
@@ -190,6 +236,12 @@ pub(super) fn serialize_definition(def: &Definition) -> String {
     out.push_str(&def.first_seen.to_string());
     out.push('\n');
 
+    out.push_str("certificate=");
+    if let Some(ref cert) = def.certificate {
+        out.push_str(&cert.to_string());
+    }
+    out.push('\n');
+
     out
 }
 
@@ -261,6 +313,8 @@ pub(super) fn deserialize_definition(text: &str) -> Option<Definition> {
         .and_then(|s| s.parse().ok())
         .unwrap_or(0);
 
+    let certificate = map.get("certificate").and_then(|s| Certificate::parse(s));
+
     Some(Definition {
         source,
         module,
@@ -271,6 +325,7 @@ pub(super) fn deserialize_definition(text: &str) -> Option<Definition> {
         requires,
         ensures,
         first_seen,
+        certificate,
     })
 }
 
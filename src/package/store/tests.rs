@@ -1,6 +1,6 @@
+use super::integrity::IntegrityIssue;
 use super::persist::{
-    deserialize_definition, escape_newlines, serialize_definition,
-    unescape_newlines,
+    deserialize_definition, escape_newlines, serialize_definition, unescape_newlines,
 };
 use super::*;
 use crate::hash::ContentHash;
@@ -299,6 +299,7 @@ fn test_definition_serialization_roundtrip() {
         requires: vec!["a > 0".to_string()],
         ensures: vec!["result == a + b".to_string()],
         first_seen: 1707580000,
+        certificate: None,
     };
 
     let serialized = serialize_definition(&def);
@@ -390,3 +391,398 @@ fn test_spec_annotations_stored() {
     assert_eq!(def.requires, vec!["a > 0"]);
     assert_eq!(def.ensures, vec!["result == a + 1"]);
 }
+
+#[test]
+fn test_gc_removes_only_unreachable_definitions() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(tmp.path()).unwrap();
+
+    let v1 = parse_file("program test\nfn helper(x: Field) -> Field { x + 1 }\nfn main() { pub_write(helper(pub_read())) }\n");
+    cb.add_file(&v1);
+    let old_helper = *cb.names.get("helper").unwrap();
+    let old_main = *cb.names.get("main").unwrap();
+    cb.save().unwrap();
+
+    let v2 = parse_file("program test\nfn helper(x: Field) -> Field { x + 2 }\nfn main() { pub_write(helper(pub_read())) }\n");
+    cb.add_file(&v2);
+    cb.save().unwrap();
+    assert_eq!(cb.stats().definitions, 4);
+
+    let result = cb.gc(&[]).unwrap();
+    let mut expected = vec![old_helper, old_main];
+    expected.sort();
+    assert_eq!(result.removed, expected);
+    assert_eq!(result.retained, 2);
+    assert!(!cb.def_path(&old_helper).exists());
+
+    // Reopening sees the collected state.
+    let reopened = Codebase::open_at(tmp.path()).unwrap();
+    assert_eq!(reopened.stats().definitions, 2);
+}
+
+#[test]
+fn test_gc_keeps_extra_roots_and_their_dependencies() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(tmp.path()).unwrap();
+
+    let v1 = parse_file("program test\nfn helper(x: Field) -> Field { x + 1 }\nfn main() { pub_write(helper(pub_read())) }\n");
+    cb.add_file(&v1);
+    let pinned_main = *cb.names.get("main").unwrap();
+    let pinned_helper = *cb.names.get("helper").unwrap();
+
+    let v2 = parse_file("program test\nfn helper(x: Field) -> Field { x + 2 }\nfn main() { pub_write(helper(pub_read())) }\n");
+    cb.add_file(&v2);
+
+    assert!(cb.unreachable(&[pinned_main]).is_empty());
+    let result = cb.gc(&[pinned_main]).unwrap();
+    assert!(result.removed.is_empty());
+    assert!(cb.lookup_hash(&pinned_helper).is_some());
+}
+
+#[test]
+fn test_verify_clean_codebase_with_dependencies() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(tmp.path()).unwrap();
+
+    // `main` calls `mid` which calls a helper defined later in the file.
+    let file = parse_file("program test\nfn main() { pub_write(mid(pub_read())) }\nfn mid(x: Field) -> Field { leaf(x) * 2 }\nfn leaf(x: Field) -> Field { x + 1 }\n");
+    cb.add_file(&file);
+    cb.save().unwrap();
+
+    let report = cb.verify().unwrap();
+    assert_eq!(report.checked, 3);
+    assert!(report.is_ok(), "unexpected issues: {:?}", report.issues);
+}
+
+#[test]
+fn test_verify_detects_tampered_source() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(tmp.path()).unwrap();
+
+    let file = parse_file("program test\nfn helper(x: Field) -> Field { x + 1 }\nfn main() { }\n");
+    cb.add_file(&file);
+    cb.save().unwrap();
+
+    let hash = *cb.names.get("helper").unwrap();
+    let path = cb.def_path(&hash);
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, text.replace("x + 1", "x + 9")).unwrap();
+
+    let report = cb.verify().unwrap();
+    assert!(matches!(
+        report.issues.as_slice(),
+        [IntegrityIssue::HashMismatch { stored, .. }] if *stored == hash
+    ));
+}
+
+#[test]
+fn test_verify_detects_missing_dependency_and_dangling_name() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(tmp.path()).unwrap();
+
+    let file = parse_file("program test\nfn helper(x: Field) -> Field { x + 1 }\nfn main() { pub_write(helper(pub_read())) }\n");
+    cb.add_file(&file);
+    cb.save().unwrap();
+
+    let helper = *cb.names.get("helper").unwrap();
+    std::fs::remove_file(cb.def_path(&helper)).unwrap();
+
+    let report = cb.verify().unwrap();
+    assert_eq!(report.checked, 1);
+    assert!(report.issues.iter().any(|i| matches!(
+        i,
+        IntegrityIssue::MissingDependency { dependency, .. } if *dependency == helper
+    )));
+    assert!(report.issues.iter().any(|i| matches!(
+        i,
+        IntegrityIssue::DanglingName { name, .. } if name == "helper"
+    )));
+}
+
+#[test]
+fn test_update_rehashes_dependents_like_an_edited_file() {
+    let v1 = "program test\nfn helper(x: Field) -> Field { x + 1 }\nfn mid(x: Field) -> Field { helper(x) * 2 }\nfn main() { pub_write(mid(pub_read())) }\n";
+    let v2 = "program test\nfn helper(x: Field) -> Field { x + 2 }\nfn mid(x: Field) -> Field { helper(x) * 2 }\nfn main() { pub_write(mid(pub_read())) }\n";
+    let expected = crate::hash::hash_file(&parse_file(v2));
+
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(tmp.path()).unwrap();
+    cb.add_file(&parse_file(v1));
+    let old_helper = *cb.names.get("helper").unwrap();
+    let old_main = *cb.names.get("main").unwrap();
+
+    // Store the new helper under a different name so only `update` re-points.
+    cb.add_file(&parse_file(
+        "module patch\nfn helper_v2(x: Field) -> Field { x + 2 }\n",
+    ));
+    let new_helper = *cb.names.get("helper_v2").unwrap();
+    assert_eq!(new_helper, expected["helper"]);
+
+    let result = cb.update(old_helper, new_helper).unwrap();
+    assert_eq!(result.propagated.len(), 2);
+    assert_eq!(cb.names["mid"], expected["mid"]);
+    assert_eq!(cb.names["main"], expected["main"]);
+    assert_eq!(cb.names["helper"], new_helper);
+    assert_eq!(result.frontier, vec![expected["main"]]);
+    assert_eq!(result.rebound, vec!["helper", "main", "mid"]);
+    assert_eq!(
+        cb.lookup("main").unwrap().dependencies,
+        vec![expected["mid"]]
+    );
+
+    // Originals remain until collected, and the result is self-consistent.
+    assert!(cb.lookup_hash(&old_main).is_some());
+    cb.save().unwrap();
+    let report = cb.verify().unwrap();
+    assert!(report.is_ok(), "unexpected issues: {:?}", report.issues);
+}
+
+#[test]
+fn test_update_rejects_incompatible_signature() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(tmp.path()).unwrap();
+    cb.add_file(&parse_file(
+        "program test\nfn a(x: Field) -> Field { x }\nfn b(x: U32) -> U32 { x }\nfn main() { }\n",
+    ));
+    let a = *cb.names.get("a").unwrap();
+    let b = *cb.names.get("b").unwrap();
+
+    let err = cb.update(a, b).err().unwrap();
+    assert!(err.contains("parameter types differ"), "{}", err);
+    assert_eq!(cb.names["a"], a);
+}
+
+#[test]
+fn test_certify_stores_certificate_that_survives_reload() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(tmp.path()).unwrap();
+    cb.add_file(&parse_file(
+        "module test\npub fn double(x: Field) -> Field {\n    let y: Field = x + x\n    assert_eq(y, x * 2)\n    y\n}\n",
+    ));
+    let hash = *cb.names.get("double").unwrap();
+    let cert = cb.certify(&hash).unwrap().clone();
+    assert_eq!(cert.verdict, "safe");
+    assert_eq!(Certificate::parse(&cert.to_string()), Some(cert.clone()));
+    cb.save().unwrap();
+
+    let reopened = Codebase::open_at(tmp.path()).unwrap();
+    let def = reopened.lookup("double").unwrap();
+    assert_eq!(def.certificate.as_ref(), Some(&cert));
+    assert!(cert.check(&def.source).is_ok());
+    assert!(reopened
+        .view("double")
+        .unwrap()
+        .contains("-- Verified: cert-v1"));
+}
+
+#[test]
+fn test_certificates_reject_failing_or_altered_definitions() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(tmp.path()).unwrap();
+    cb.add_file(&parse_file(
+        "module test\npub fn bad(x: Field) {\n    assert_eq(x, x + 1)\n}\npub fn good(x: Field) {\n    assert_eq(x + x, x * 2)\n}\n",
+    ));
+    let bad = *cb.names.get("bad").unwrap();
+    let good = *cb.names.get("good").unwrap();
+    assert!(cb.certify(&bad).is_err());
+    assert!(cb.lookup("bad").unwrap().certificate.is_none());
+
+    let cert = cb.certify(&good).unwrap().clone();
+    let altered = "pub fn good(x: Field) {\n    assert_eq(x + x, x * 3)\n}";
+    assert!(cert.check(altered).is_err());
+}
+
+#[test]
+fn test_archive_round_trip_into_another_codebase() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(&tmp.path().join("a")).unwrap();
+    cb.add_file(&parse_file("program test\nfn helper(x: Field) -> Field { x + 1 }\nfn main() { pub_write(helper(pub_read())) }\n"));
+    let archive = tmp.path().join("backup.tar");
+    let summary = cb.export_archive(&archive).unwrap();
+    assert_eq!((summary.definitions, summary.names), (2, 2));
+
+    let mut restored = Codebase::open_at(&tmp.path().join("b")).unwrap();
+    let result = restored.import_archive(&archive).unwrap();
+    assert_eq!(
+        (result.added, result.existing, result.names_bound),
+        (2, 0, 2)
+    );
+    assert_eq!(restored.hash_for_name("main"), cb.hash_for_name("main"));
+    assert_eq!(restored.name_history("helper").len(), 1);
+    assert!(restored.verify().unwrap().is_ok());
+
+    // Importing again changes nothing.
+    let result = restored.import_archive(&archive).unwrap();
+    assert_eq!(
+        (result.added, result.existing, result.names_bound),
+        (0, 2, 0)
+    );
+    assert_eq!(restored.name_history("helper").len(), 1);
+}
+
+#[test]
+fn test_archive_import_rejects_altered_archives() {
+    use super::archive::{read_tar, write_tar_entry};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(&tmp.path().join("a")).unwrap();
+    cb.add_file(&parse_file(
+        "program test\nfn helper(x: Field) -> Field { x + 1 }\nfn main() { }\n",
+    ));
+    let archive = tmp.path().join("backup.tar");
+    cb.export_archive(&archive).unwrap();
+    let bytes = std::fs::read(&archive).unwrap();
+    let mut target = Codebase::open_at(&tmp.path().join("b")).unwrap();
+
+    // An edited file no longer matches the manifest.
+    let text = String::from_utf8_lossy(&bytes).replace("x + 1", "x + 2");
+    let edited = tmp.path().join("edited.tar");
+    std::fs::write(&edited, text.as_bytes()).unwrap();
+    let err = target.import_archive(&edited).err().unwrap();
+    assert!(err.contains("does not match the manifest"), "{}", err);
+
+    // With the manifest rewritten to match, the definition's hash does not.
+    let entries: Vec<(String, Vec<u8>)> = read_tar(&bytes)
+        .unwrap()
+        .into_iter()
+        .map(|(name, data)| {
+            let text = String::from_utf8(data).unwrap().replace("x + 1", "x + 2");
+            (name, text.into_bytes())
+        })
+        .collect();
+    let mut manifest = String::from_utf8(entries[0].1.clone()).unwrap();
+    for (name, data) in &entries[1..] {
+        let line = manifest
+            .lines()
+            .find(|l| l.ends_with(name.as_str()))
+            .unwrap();
+        let fixed = format!(
+            "file {} {} {}",
+            blake3::hash(data).to_hex(),
+            data.len(),
+            name
+        );
+        manifest = manifest.replace(line, &fixed);
+    }
+    let mut tar = Vec::new();
+    write_tar_entry(&mut tar, "MANIFEST", manifest.as_bytes(), 0);
+    for (name, data) in &entries[1..] {
+        write_tar_entry(&mut tar, name, data, 0);
+    }
+    let forged = tmp.path().join("forged.tar");
+    std::fs::write(&forged, &tar).unwrap();
+    let err = target.import_archive(&forged).err().unwrap();
+    assert!(err.contains("the archive was altered"), "{}", err);
+
+    assert_eq!(target.stats().definitions, 0);
+}
+
+#[test]
+fn test_migrate_rehashes_codebase_from_older_hash_version() {
+    let source = "program test\nfn helper(x: Field) -> Field { x + 1 }\nfn mid(x: Field) -> Field { helper(x) * 2 }\nfn main() { pub_write(mid(pub_read())) }\n";
+    let expected = crate::hash::hash_file(&parse_file(source));
+    let tmp = tempfile::tempdir().unwrap();
+
+    // Write the codebase as version 1 left it: the same definitions
+    // under other hashes, and no `hash_version` file.
+    {
+        let mut cb = Codebase::open_at(tmp.path()).unwrap();
+        cb.add_file(&parse_file(source));
+        let old = |hash: &ContentHash| {
+            let mut bytes = hash.0;
+            bytes[31] ^= 0xff;
+            ContentHash(bytes)
+        };
+        let mut legacy = Codebase {
+            definitions: BTreeMap::new(),
+            names: cb.names.iter().map(|(n, h)| (n.clone(), old(h))).collect(),
+            name_history: std::mem::take(&mut cb.name_history)
+                .into_iter()
+                .map(|(h, e)| (old(&h), e))
+                .collect(),
+            root: tmp.path().to_path_buf(),
+        };
+        for (hash, def) in &cb.definitions {
+            let mut def = def.clone();
+            def.dependencies = def.dependencies.iter().map(old).collect();
+            legacy.definitions.insert(old(hash), def);
+        }
+        std::fs::remove_dir_all(tmp.path().join("defs")).unwrap();
+        legacy.save().unwrap();
+        std::fs::remove_file(tmp.path().join("hash_version")).unwrap();
+    }
+
+    // Opening refuses it and changes nothing on disk.
+    let err = Codebase::open_at(tmp.path()).err().unwrap();
+    assert!(err.to_string().contains("trident store migrate"), "{}", err);
+    assert!(!tmp.path().join("hash_version").exists());
+
+    let (cb, migration) = Codebase::migrate_at(tmp.path()).unwrap();
+    let migration = migration.unwrap();
+    assert_eq!(migration.from_version, 1);
+    assert_eq!(migration.rehashed.len(), 3);
+    assert!(migration.kept.is_empty());
+    for name in ["helper", "mid", "main"] {
+        assert_eq!(cb.hash_for_name(name), Some(&expected[name]), "{}", name);
+    }
+    assert_eq!(cb.name_history("helper").len(), 1);
+    assert!(cb.verify().unwrap().is_ok());
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("hash_version")).unwrap(),
+        format!("{}\n", crate::hash::HASH_VERSION)
+    );
+
+    // Reopening finds the codebase current and leaves `hash_version` be.
+    let modified = || {
+        std::fs::metadata(tmp.path().join("hash_version"))
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+    let before = modified();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    let cb = Codebase::open_at(tmp.path()).unwrap();
+    assert_eq!(cb.stats().definitions, 3);
+    cb.save().unwrap();
+    assert_eq!(modified(), before);
+    let (_, migration) = Codebase::migrate_at(tmp.path()).unwrap();
+    assert!(migration.is_none());
+}
+
+#[test]
+fn test_archive_import_checks_hash_version() {
+    use super::archive::{read_tar, write_tar_entry};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cb = Codebase::open_at(&tmp.path().join("a")).unwrap();
+    cb.add_file(&parse_file("program test\nfn helper(x: Field) -> Field { x + 1 }\nfn main() { pub_write(helper(pub_read())) }\n"));
+    let archive = tmp.path().join("backup.tar");
+    cb.export_archive(&archive).unwrap();
+    let entries = read_tar(&std::fs::read(&archive).unwrap()).unwrap();
+    let manifest = String::from_utf8(entries[0].1.clone()).unwrap();
+    let version_line = format!("hash_version {}\n", crate::hash::HASH_VERSION);
+    assert!(manifest.contains(&version_line));
+    let with_manifest = |manifest: &str| {
+        let mut tar = Vec::new();
+        write_tar_entry(&mut tar, "MANIFEST", manifest.as_bytes(), 0);
+        for (name, data) in &entries[1..] {
+            write_tar_entry(&mut tar, name, data, 0);
+        }
+        let path = tmp.path().join("rewritten.tar");
+        std::fs::write(&path, &tar).unwrap();
+        path
+    };
+
+    // An archive from a newer compiler is refused.
+    let newer = manifest.replace(&version_line, "hash_version 99\n");
+    let mut target = Codebase::open_at(&tmp.path().join("b")).unwrap();
+    let err = target.import_archive(&with_manifest(&newer)).err().unwrap();
+    assert!(err.contains("newer than this compiler"), "{}", err);
+
+    // One written before the version was recorded is re-hashed on import.
+    let older = manifest.replace(&version_line, "");
+    let result = target.import_archive(&with_manifest(&older)).unwrap();
+    assert_eq!(result.added, 2);
+    assert_eq!(target.hash_for_name("main"), cb.hash_for_name("main"));
+    assert!(target.verify().unwrap().is_ok());
+}
@@ -0,0 +1,162 @@
+//! Hash-based patches: re-point every dependent of one definition to another.
+//!
+//! Unison-style update. Replacing `old` with `new` rewrites the dependency
+//! lists of everything that (transitively) calls `old`, re-hashes each
+//! rewritten definition, and rebinds names to the replacements. Original
+//! definitions stay in the store until `gc` collects them.
+
+use std::collections::BTreeMap;
+
+use crate::hash::ContentHash;
+
+use super::persist::unix_timestamp;
+use super::{Codebase, Definition, NameEntry};
+
+/// Result of applying an update across the codebase.
+pub struct UpdateResult {
+    /// (original, replacement) for every re-hashed dependent, in the order
+    /// the propagation reached them.
+    pub propagated: Vec<(ContentHash, ContentHash)>,
+    /// Replacements that no other replacement depends on: the outermost
+    /// definitions the change reached.
+    pub frontier: Vec<ContentHash>,
+    /// Names rebound from an original hash to its replacement.
+    pub rebound: Vec<String>,
+}
+
+impl Codebase {
+    /// Replace `old` with `new` in every transitive dependent.
+    ///
+    /// Fails without modifying the codebase when either hash is unknown,
+    /// when the signatures differ, or when a dependent cannot be re-hashed.
+    pub fn update(&mut self, old: ContentHash, new: ContentHash) -> Result<UpdateResult, String> {
+        if old == new {
+            return Err("old and new hashes are the same definition".to_string());
+        }
+        let old_def = self
+            .definitions
+            .get(&old)
+            .ok_or_else(|| format!("definition {} not found", old))?;
+        let new_def = self
+            .definitions
+            .get(&new)
+            .ok_or_else(|| format!("definition {} not found", new))?;
+        check_compatible(old_def, new_def)?;
+
+        let (substitute, order) = self.propagate(old, new)?;
+
+        // Store rewritten dependents under their new hashes.
+        let now = unix_timestamp();
+        let mut propagated = Vec::new();
+        for orig in &order {
+            let repl = substitute[orig];
+            let mut def = self.definitions[orig].clone();
+            for dep in def.dependencies.iter_mut() {
+                if let Some(target) = substitute.get(dep) {
+                    *dep = *target;
+                }
+            }
+            def.first_seen = now;
+            self.definitions.insert(repl, def);
+            propagated.push((*orig, repl));
+        }
+
+        // Carry every call name over so dependents of the replacements
+        // still re-hash to the same values, and rebind current names.
+        let mut rebound = Vec::new();
+        for (orig, repl) in &substitute {
+            let known = self.call_names(repl);
+            for name in self.call_names(orig) {
+                if !known.contains(&name) {
+                    self.name_history.entry(*repl).or_default().push(NameEntry {
+                        name: name.clone(),
+                        timestamp: now,
+                    });
+                }
+                if self.names.get(&name) == Some(orig) {
+                    self.names.insert(name.clone(), *repl);
+                    rebound.push(name);
+                }
+            }
+        }
+        rebound.sort();
+
+        let frontier = order
+            .iter()
+            .filter(|orig| {
+                !order
+                    .iter()
+                    .any(|other| self.definitions[other].dependencies.contains(orig))
+            })
+            .map(|orig| substitute[orig])
+            .collect();
+
+        Ok(UpdateResult {
+            propagated,
+            frontier,
+            rebound,
+        })
+    }
+
+    /// Compute replacement hashes for all transitive dependents of `old`.
+    ///
+    /// Returns the substitution map (including `old -> new`) and the
+    /// dependents in discovery order. Iterates to a fixpoint so that a
+    /// definition reached through several paths sees every replacement.
+    fn propagate(
+        &self,
+        old: ContentHash,
+        new: ContentHash,
+    ) -> Result<(BTreeMap<ContentHash, ContentHash>, Vec<ContentHash>), String> {
+        let mut substitute = BTreeMap::from([(old, new)]);
+        let mut order = Vec::new();
+
+        // The dependency graph is acyclic, so its depth bounds the passes.
+        for _ in 0..=self.definitions.len() {
+            let mut changed = false;
+            for (hash, def) in &self.definitions {
+                if *hash == old || *hash == new {
+                    continue;
+                }
+                if !def.dependencies.iter().any(|d| substitute.contains_key(d)) {
+                    continue;
+                }
+                let repl = self
+                    .rehash(def, &substitute)
+                    .map_err(|e| format!("cannot re-hash dependent {}: {}", hash, e))?;
+                if substitute.get(hash) != Some(&repl) {
+                    if !substitute.contains_key(hash) {
+                        order.push(*hash);
+                    }
+                    substitute.insert(*hash, repl);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        Ok((substitute, order))
+    }
+}
+
+/// A replacement must accept and return the same types as the original.
+fn check_compatible(old: &Definition, new: &Definition) -> Result<(), String> {
+    let old_params: Vec<&str> = old.params.iter().map(|(_, t)| t.as_str()).collect();
+    let new_params: Vec<&str> = new.params.iter().map(|(_, t)| t.as_str()).collect();
+    if old_params != new_params {
+        return Err(format!(
+            "parameter types differ: ({}) vs ({})",
+            old_params.join(", "),
+            new_params.join(", ")
+        ));
+    }
+    if old.return_ty != new.return_ty {
+        return Err(format!(
+            "return types differ: {} vs {}",
+            old.return_ty.as_deref().unwrap_or("()"),
+            new.return_ty.as_deref().unwrap_or("()")
+        ));
+    }
+    Ok(())
+}
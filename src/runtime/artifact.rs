@@ -4,6 +4,13 @@
 //! signatures. Warriors deserialize this from a JSON file or receive it
 //! via the Rust API.
 
+use super::buildinfo::BuildInfo;
+use super::json::{
+    extract_array, extract_object, extract_string, extract_string_opt, extract_u64, json_string,
+    quoted_items, split_objects, u64_entries,
+};
+use crate::hash::ContentHash;
+
 // ─── Data Types ────────────────────────────────────────────────────
 
 /// Self-contained compilation artifact that a warrior needs to execute,
@@ -24,10 +31,22 @@ pub struct ProgramBundle {
     pub entry_point: String,
     /// Function signatures with content hashes.
     pub functions: Vec<BundleFunction>,
+    /// Error table: the code each assertion message fails with.
+    pub errors: Vec<BundleError>,
+    /// Public output layout, from the program's `pub output` declarations.
+    pub outputs: Vec<BundleOutput>,
+    /// Events the program emits, with their tags and versions.
+    pub events: Vec<BundleEvent>,
+    /// Entrypoint table: the function each selector dispatches to.
+    pub entrypoints: Vec<BundleEntrypoint>,
+    /// Tables the program embeds with `include_data`.
+    pub data: Vec<BundleData>,
     /// Cost analysis summary.
     pub cost: BundleCost,
     /// Content hash of the source AST (hex).
     pub source_hash: String,
+    /// Compiler, target, and flags that produced the bundle.
+    pub build: Option<BuildInfo>,
 }
 
 /// Function metadata within a bundle.
@@ -38,6 +57,56 @@ pub struct BundleFunction {
     pub signature: String,
 }
 
+/// An assertion message and the error code it compiles to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleError {
+    pub code: u64,
+    pub message: String,
+}
+
+/// A declared public output: its type and the field elements it takes,
+/// in write order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleOutput {
+    pub ty: String,
+    pub width: u64,
+}
+
+/// An event of the program: the tag `reveal` writes ahead of its fields
+/// and `seal` hashes with them, and the layout version behind the tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleEvent {
+    pub name: String,
+    pub tag: u64,
+    pub version: u64,
+    /// Deprecation note, when the event is `#[deprecated]`.
+    pub deprecated: Option<String>,
+    /// Field names in declaration order.
+    pub fields: Vec<String>,
+}
+
+/// An `#[entrypoint]` function and the selector that calls it, read
+/// first from public input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleEntrypoint {
+    pub selector: u64,
+    pub name: String,
+}
+
+/// A table embedded with `include_data`: the file it came from and the
+/// content hash of its values, so a rebuild can be checked against the
+/// data it used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleData {
+    /// Qualified constant name (e.g. "crypto.sbox.SBOX").
+    pub name: String,
+    /// The file, as the source names it.
+    pub source: String,
+    pub length: u64,
+    /// Content hash of the values (hex).
+    pub hash: String,
+}
+
 /// Cost analysis summary.
 #[derive(Clone, Debug)]
 pub struct BundleCost {
@@ -75,6 +144,10 @@ impl ProgramBundle {
             "  \"source_hash\": {},\n",
             json_string(&self.source_hash)
         ));
+        match &self.build {
+            Some(build) => out.push_str(&format!("  \"build\": {},\n", build.to_json("  "))),
+            None => out.push_str("  \"build\": null,\n"),
+        }
 
         // Cost
         out.push_str("  \"cost\": {\n");
@@ -110,6 +183,85 @@ impl ProgramBundle {
         }
         out.push_str("  ],\n");
 
+        // Errors
+        out.push_str("  \"errors\": [\n");
+        for (i, error) in self.errors.iter().enumerate() {
+            let comma = if i + 1 < self.errors.len() { "," } else { "" };
+            out.push_str(&format!(
+                "    {{ \"code\": {}, \"message\": {} }}{}\n",
+                error.code,
+                json_string(&error.message),
+                comma,
+            ));
+        }
+        out.push_str("  ],\n");
+
+        // Outputs
+        out.push_str("  \"outputs\": [\n");
+        for (i, output) in self.outputs.iter().enumerate() {
+            let comma = if i + 1 < self.outputs.len() { "," } else { "" };
+            out.push_str(&format!(
+                "    {{ \"type\": {}, \"width\": {} }}{}\n",
+                json_string(&output.ty),
+                output.width,
+                comma,
+            ));
+        }
+        out.push_str("  ],\n");
+
+        // Events
+        out.push_str("  \"events\": [\n");
+        for (i, event) in self.events.iter().enumerate() {
+            let comma = if i + 1 < self.events.len() { "," } else { "" };
+            let deprecated = match &event.deprecated {
+                Some(note) => json_string(note),
+                None => "null".to_string(),
+            };
+            let fields: Vec<String> = event.fields.iter().map(|f| json_string(f)).collect();
+            out.push_str(&format!(
+                "    {{ \"name\": {}, \"tag\": {}, \"version\": {}, \"deprecated\": {}, \"fields\": [{}] }}{}\n",
+                json_string(&event.name),
+                event.tag,
+                event.version,
+                deprecated,
+                fields.join(", "),
+                comma,
+            ));
+        }
+        out.push_str("  ],\n");
+
+        // Entrypoints
+        out.push_str("  \"entrypoints\": [\n");
+        for (i, entry) in self.entrypoints.iter().enumerate() {
+            let comma = if i + 1 < self.entrypoints.len() {
+                ","
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "    {{ \"selector\": {}, \"name\": {} }}{}\n",
+                entry.selector,
+                json_string(&entry.name),
+                comma,
+            ));
+        }
+        out.push_str("  ],\n");
+
+        // Embedded data
+        out.push_str("  \"data\": [\n");
+        for (i, table) in self.data.iter().enumerate() {
+            let comma = if i + 1 < self.data.len() { "," } else { "" };
+            out.push_str(&format!(
+                "    {{ \"name\": {}, \"source\": {}, \"length\": {}, \"hash\": {} }}{}\n",
+                json_string(&table.name),
+                json_string(&table.source),
+                table.length,
+                json_string(&table.hash),
+                comma,
+            ));
+        }
+        out.push_str("  ],\n");
+
         // Assembly (last field, no trailing comma)
         out.push_str(&format!(
             "  \"assembly\": {}\n",
@@ -119,6 +271,14 @@ impl ProgramBundle {
         out
     }
 
+    /// Poseidon2 digest of the assembly text — the program hash that
+    /// `trident package` records and verifiers check.
+    pub fn program_digest(&self) -> ContentHash {
+        ContentHash(crate::field::poseidon2::hash_bytes_goldilocks(
+            self.assembly.as_bytes(),
+        ))
+    }
+
     /// Deserialize from JSON (minimal parser for the bundle format).
     pub fn from_json(json: &str) -> Result<Self, String> {
         let name = extract_string(json, "name")?;
@@ -130,6 +290,94 @@ impl ProgramBundle {
         let assembly = extract_string(json, "assembly")?;
         let padded_height = extract_u64(json, "padded_height").unwrap_or(0);
         let estimated_proving_ns = extract_u64(json, "estimated_proving_ns").unwrap_or(0);
+        let (table_names, table_values) = extract_object(json, "cost")
+            .map(u64_entries)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(name, _)| name != "padded_height" && name != "estimated_proving_ns")
+            .unzip();
+        let functions = extract_array(json, "functions")
+            .map(split_objects)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|obj| {
+                Ok(BundleFunction {
+                    name: extract_string(obj, "name")?,
+                    hash: extract_string(obj, "hash")?,
+                    signature: extract_string(obj, "signature")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        // Bundles written before error tables existed have none.
+        let errors = extract_array(json, "errors")
+            .map(split_objects)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|obj| {
+                Ok(BundleError {
+                    code: extract_u64(obj, "code")?,
+                    message: extract_string(obj, "message")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        // Bundles written before output layouts existed have none.
+        let outputs = extract_array(json, "outputs")
+            .map(split_objects)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|obj| {
+                Ok(BundleOutput {
+                    ty: extract_string(obj, "type")?,
+                    width: extract_u64(obj, "width")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        // Bundles written before the event ABI existed have none.
+        let events = extract_array(json, "events")
+            .map(split_objects)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|obj| {
+                Ok(BundleEvent {
+                    name: extract_string(obj, "name")?,
+                    tag: extract_u64(obj, "tag")?,
+                    version: extract_u64(obj, "version")?,
+                    deprecated: extract_string_opt(obj, "deprecated"),
+                    fields: extract_array(obj, "fields")
+                        .map(quoted_items)
+                        .unwrap_or_default(),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        // Bundles of single-entry programs have no table.
+        let entrypoints = extract_array(json, "entrypoints")
+            .map(split_objects)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|obj| {
+                Ok(BundleEntrypoint {
+                    selector: extract_u64(obj, "selector")?,
+                    name: extract_string(obj, "name")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        // Bundles written before `include_data` existed have no tables.
+        let data = extract_array(json, "data")
+            .map(split_objects)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|obj| {
+                Ok(BundleData {
+                    name: extract_string(obj, "name")?,
+                    source: extract_string(obj, "source")?,
+                    length: extract_u64(obj, "length")?,
+                    hash: extract_string(obj, "hash")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let build = extract_object(json, "build")
+            .map(BuildInfo::from_json)
+            .transpose()?;
 
         Ok(ProgramBundle {
             name,
@@ -138,115 +386,24 @@ impl ProgramBundle {
             target_os,
             assembly,
             entry_point,
-            functions: Vec::new(), // TODO: parse functions array
+            functions,
+            errors,
+            outputs,
+            events,
+            entrypoints,
+            data,
             cost: BundleCost {
-                table_values: Vec::new(),
-                table_names: Vec::new(),
+                table_values,
+                table_names,
                 padded_height,
                 estimated_proving_ns,
             },
             source_hash,
+            build,
         })
     }
 }
 
-// ─── JSON Helpers ──────────────────────────────────────────────────
-
-/// JSON-escape a string and wrap in quotes.
-fn json_string(s: &str) -> String {
-    let mut out = String::from('"');
-    for ch in s.chars() {
-        match ch {
-            '"' => out.push_str("\\\""),
-            '\\' => out.push_str("\\\\"),
-            '\n' => out.push_str("\\n"),
-            '\r' => out.push_str("\\r"),
-            '\t' => out.push_str("\\t"),
-            c if (c as u32) < 0x20 => {
-                out.push_str(&format!("\\u{:04x}", c as u32));
-            }
-            c => out.push(c),
-        }
-    }
-    out.push('"');
-    out
-}
-
-/// Extract a string value for a key from JSON.
-fn extract_string(json: &str, key: &str) -> Result<String, String> {
-    let pattern = format!("\"{}\"", key);
-    let start = json
-        .find(&pattern)
-        .ok_or_else(|| format!("missing key '{}'", key))?;
-    let rest = &json[start + pattern.len()..];
-    // Skip `: "`
-    let quote_start = rest
-        .find('"')
-        .ok_or_else(|| format!("missing value for '{}'", key))?;
-    let value_start = quote_start + 1;
-    let value_rest = &rest[value_start..];
-    // Find closing quote (handling escapes)
-    let mut end = 0;
-    let mut escaped = false;
-    let mut value = String::new();
-    for ch in value_rest.chars() {
-        if escaped {
-            match ch {
-                '"' => value.push('"'),
-                '\\' => value.push('\\'),
-                'n' => value.push('\n'),
-                'r' => value.push('\r'),
-                't' => value.push('\t'),
-                _ => {
-                    value.push('\\');
-                    value.push(ch);
-                }
-            }
-            escaped = false;
-        } else if ch == '\\' {
-            escaped = true;
-        } else if ch == '"' {
-            break;
-        } else {
-            value.push(ch);
-        }
-        end += ch.len_utf8();
-    }
-    let _ = end; // suppress unused warning
-    Ok(value)
-}
-
-/// Extract an optional string value (returns None if key is "null").
-fn extract_string_opt(json: &str, key: &str) -> Option<String> {
-    let pattern = format!("\"{}\"", key);
-    let start = json.find(&pattern)?;
-    let rest = &json[start + pattern.len()..];
-    let trimmed = rest.trim_start().trim_start_matches(':').trim_start();
-    if trimmed.starts_with("null") {
-        return None;
-    }
-    extract_string(json, key).ok()
-}
-
-/// Extract a u64 value for a key from JSON.
-fn extract_u64(json: &str, key: &str) -> Result<u64, String> {
-    let pattern = format!("\"{}\"", key);
-    let start = json
-        .find(&pattern)
-        .ok_or_else(|| format!("missing key '{}'", key))?;
-    let rest = &json[start + pattern.len()..];
-    let colon = rest
-        .find(':')
-        .ok_or_else(|| format!("missing colon for '{}'", key))?;
-    let after_colon = rest[colon + 1..].trim_start();
-    let num_end = after_colon
-        .find(|c: char| !c.is_ascii_digit())
-        .unwrap_or(after_colon.len());
-    after_colon[..num_end]
-        .parse()
-        .map_err(|e| format!("invalid u64 for '{}': {}", key, e))
-}
-
 // ─── Tests ─────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -266,6 +423,37 @@ mod tests {
                 hash: "abc123".to_string(),
                 signature: "fn main() -> Field".to_string(),
             }],
+            errors: vec![BundleError {
+                code: 1,
+                message: "balance \"underflow\" [x]".to_string(),
+            }],
+            outputs: vec![BundleOutput {
+                ty: "Digest".to_string(),
+                width: 5,
+            }],
+            events: vec![BundleEvent {
+                name: "Transfer".to_string(),
+                tag: (1 << 32) | 1,
+                version: 2,
+                deprecated: Some("use \"Transfer3\"".to_string()),
+                fields: vec!["from".to_string(), "amount".to_string()],
+            }],
+            entrypoints: vec![
+                BundleEntrypoint {
+                    selector: 0,
+                    name: "pay".to_string(),
+                },
+                BundleEntrypoint {
+                    selector: 7,
+                    name: "mint".to_string(),
+                },
+            ],
+            data: vec![BundleData {
+                name: "sbox.SBOX".to_string(),
+                source: "tables/sbox.json".to_string(),
+                length: 256,
+                hash: "0f1e2d".to_string(),
+            }],
             cost: BundleCost {
                 table_values: vec![100, 50, 10],
                 table_names: vec![
@@ -277,6 +465,14 @@ mod tests {
                 estimated_proving_ns: 1_000_000,
             },
             source_hash: "deadbeef".to_string(),
+            build: Some(BuildInfo {
+                compiler_version: "0.1.0".to_string(),
+                compiler_rev: None,
+                target: "triton".to_string(),
+                profile: "release".to_string(),
+                cfg_flags: vec!["release".to_string()],
+                locked: Default::default(),
+            }),
         }
     }
 
@@ -299,6 +495,96 @@ mod tests {
         );
         // Assembly contains newlines — verify escape roundtrip
         assert_eq!(parsed.assembly, bundle.assembly);
+        assert_eq!(parsed.build, bundle.build);
+        assert_eq!(parsed.cost.table_names, bundle.cost.table_names);
+        assert_eq!(parsed.cost.table_values, bundle.cost.table_values);
+        assert_eq!(parsed.functions.len(), 1);
+        assert_eq!(parsed.functions[0].name, "main");
+        assert_eq!(parsed.functions[0].hash, "abc123");
+        assert_eq!(parsed.functions[0].signature, "fn main() -> Field");
+    }
+
+    #[test]
+    fn bundle_functions_with_brackets_in_signatures() {
+        let mut bundle = sample_bundle();
+        bundle.functions.push(BundleFunction {
+            name: "sum".to_string(),
+            hash: "def456".to_string(),
+            signature: "fn sum(xs: [Field; 4]) -> Field".to_string(),
+        });
+        let parsed = ProgramBundle::from_json(&bundle.to_json()).expect("parse failed");
+        assert_eq!(parsed.functions.len(), 2);
+        assert_eq!(
+            parsed.functions[1].signature,
+            "fn sum(xs: [Field; 4]) -> Field"
+        );
+    }
+
+    #[test]
+    fn bundle_error_table_roundtrips() {
+        let bundle = sample_bundle();
+        let parsed = ProgramBundle::from_json(&bundle.to_json()).expect("parse failed");
+        assert_eq!(parsed.errors, bundle.errors);
+
+        let mut bare = sample_bundle();
+        bare.errors.clear();
+        let json = bare.to_json().replace("  \"errors\": [\n  ],\n", "");
+        assert!(!json.contains("errors"));
+        let parsed = ProgramBundle::from_json(&json).expect("parse failed");
+        assert!(parsed.errors.is_empty());
+    }
+
+    #[test]
+    fn bundle_outputs_roundtrip() {
+        let bundle = sample_bundle();
+        let parsed = ProgramBundle::from_json(&bundle.to_json()).expect("parse failed");
+        assert_eq!(parsed.outputs, bundle.outputs);
+
+        let json = bundle.to_json().replace(
+            "  \"outputs\": [\n    { \"type\": \"Digest\", \"width\": 5 }\n  ],\n",
+            "",
+        );
+        assert!(!json.contains("outputs"));
+        let parsed = ProgramBundle::from_json(&json).expect("parse failed");
+        assert!(parsed.outputs.is_empty());
+    }
+
+    #[test]
+    fn bundle_events_roundtrip() {
+        let mut bundle = sample_bundle();
+        bundle.events.push(BundleEvent {
+            name: "Mint".to_string(),
+            tag: 0,
+            version: 1,
+            deprecated: None,
+            fields: Vec::new(),
+        });
+        let parsed = ProgramBundle::from_json(&bundle.to_json()).expect("parse failed");
+        assert_eq!(parsed.events, bundle.events);
+        assert_eq!(parsed.version, "0.1.0");
+    }
+
+    #[test]
+    fn bundle_entrypoints_roundtrip() {
+        let bundle = sample_bundle();
+        let parsed = ProgramBundle::from_json(&bundle.to_json()).expect("parse failed");
+        assert_eq!(parsed.entrypoints, bundle.entrypoints);
+        assert_eq!(parsed.name, "test_program");
+    }
+
+    #[test]
+    fn bundle_data_roundtrip() {
+        let bundle = sample_bundle();
+        let parsed = ProgramBundle::from_json(&bundle.to_json()).expect("parse failed");
+        assert_eq!(parsed.data, bundle.data);
+    }
+
+    #[test]
+    fn bundle_without_build_info() {
+        let mut bundle = sample_bundle();
+        bundle.build = None;
+        let parsed = ProgramBundle::from_json(&bundle.to_json()).expect("parse failed");
+        assert_eq!(parsed.build, None);
     }
 
     #[test]
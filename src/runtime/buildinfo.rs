@@ -0,0 +1,262 @@
+//! Build provenance: which compiler, target, and flags produced an artifact.
+//!
+//! Captured from `CompileOptions` at compile time and embedded in two
+//! places: the `build` object of a `ProgramBundle`, and a block of
+//! `// @trident key: value` comment lines at the top of emitted TASM.
+//! Both round-trip, so `trident inspect` can recover the metadata from
+//! either artifact.
+
+use std::collections::BTreeMap;
+
+use super::json::{
+    extract_array, extract_object, extract_string, extract_string_opt, json_string, quoted_items,
+};
+use crate::CompileOptions;
+
+/// Prefix of every structured header line in emitted assembly.
+const HEADER_PREFIX: &str = "// @trident ";
+
+/// Compiler and configuration that produced an artifact.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// Compiler crate version (e.g. "0.1.0").
+    pub compiler_version: String,
+    /// Git revision the compiler was built from, if known.
+    pub compiler_rev: Option<String>,
+    /// Target VM name.
+    pub target: String,
+    /// Compilation profile.
+    pub profile: String,
+    /// Active cfg flags (sorted).
+    pub cfg_flags: Vec<String>,
+    /// Locked dependency hashes: name -> content hash (hex).
+    pub locked: BTreeMap<String, String>,
+}
+
+impl BuildInfo {
+    /// Capture build metadata for a compilation with `options`.
+    pub fn capture(options: &CompileOptions) -> Self {
+        Self {
+            compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            compiler_rev: option_env!("TRIDENT_GIT_REV").map(str::to_string),
+            target: options.target_config.name.clone(),
+            profile: options.profile.clone(),
+            cfg_flags: options.cfg_flags.iter().cloned().collect(),
+            locked: options.locked.clone(),
+        }
+    }
+
+    /// Compiler identification, e.g. `0.1.0 (3f2a9c1d0b7e)`.
+    pub fn compiler(&self) -> String {
+        match &self.compiler_rev {
+            Some(rev) => format!("{} ({})", self.compiler_version, rev),
+            None => self.compiler_version.clone(),
+        }
+    }
+
+    // ─── TASM Header ───────────────────────────────────────────────
+
+    /// Render as comment lines to prepend to emitted assembly.
+    pub fn tasm_header(&self) -> String {
+        let mut out = String::new();
+        let mut line = |key: &str, value: &str| {
+            out.push_str(&format!("{}{}: {}\n", HEADER_PREFIX, key, value));
+        };
+        line("compiler", &self.compiler());
+        line("target", &self.target);
+        line("profile", &self.profile);
+        line("cfg", &self.cfg_flags.join(", "));
+        for (name, hash) in &self.locked {
+            line("lock", &format!("{} {}", name, hash));
+        }
+        out
+    }
+
+    /// Recover build metadata from the header of emitted assembly.
+    ///
+    /// Returns `None` when the assembly carries no header.
+    pub fn from_tasm(tasm: &str) -> Option<Self> {
+        let mut info = Self::default();
+        let mut found = false;
+        for line in tasm.lines() {
+            let Some(rest) = line.strip_prefix(HEADER_PREFIX) else {
+                break;
+            };
+            let Some((key, value)) = rest
+                .split_once(": ")
+                .or_else(|| rest.strip_suffix(':').map(|k| (k, "")))
+            else {
+                continue;
+            };
+            found = true;
+            match key {
+                "compiler" => {
+                    let (version, rev) = split_compiler(value);
+                    info.compiler_version = version;
+                    info.compiler_rev = rev;
+                }
+                "target" => info.target = value.to_string(),
+                "profile" => info.profile = value.to_string(),
+                "cfg" => info.cfg_flags = split_list(value),
+                "lock" => {
+                    if let Some((name, hash)) = value.split_once(' ') {
+                        info.locked.insert(name.to_string(), hash.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        found.then_some(info)
+    }
+
+    // ─── JSON ──────────────────────────────────────────────────────
+
+    /// Serialize as a JSON object (no trailing newline), indented by `indent`.
+    pub fn to_json(&self, indent: &str) -> String {
+        let locked: Vec<String> = self
+            .locked
+            .iter()
+            .map(|(name, hash)| format!("{}: {}", json_string(name), json_string(hash)))
+            .collect();
+        let flags: Vec<String> = self.cfg_flags.iter().map(|f| json_string(f)).collect();
+        let mut out = String::from("{\n");
+        let field = |out: &mut String, key: &str, value: String, last: bool| {
+            out.push_str(&format!(
+                "{}  \"{}\": {}{}\n",
+                indent,
+                key,
+                value,
+                if last { "" } else { "," }
+            ));
+        };
+        field(
+            &mut out,
+            "compiler_version",
+            json_string(&self.compiler_version),
+            false,
+        );
+        field(
+            &mut out,
+            "compiler_rev",
+            self.compiler_rev
+                .as_deref()
+                .map(json_string)
+                .unwrap_or_else(|| "null".to_string()),
+            false,
+        );
+        field(&mut out, "target", json_string(&self.target), false);
+        field(&mut out, "profile", json_string(&self.profile), false);
+        field(
+            &mut out,
+            "cfg_flags",
+            format!("[{}]", flags.join(", ")),
+            false,
+        );
+        field(
+            &mut out,
+            "locked",
+            format!("{{{}}}", locked.join(", ")),
+            true,
+        );
+        out.push_str(indent);
+        out.push('}');
+        out
+    }
+
+    /// Parse the object produced by `to_json`.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        Ok(Self {
+            compiler_version: extract_string(json, "compiler_version")?,
+            compiler_rev: extract_string_opt(json, "compiler_rev"),
+            target: extract_string(json, "target")?,
+            profile: extract_string(json, "profile")?,
+            cfg_flags: quoted_items(
+                extract_array(json, "cfg_flags").ok_or("missing key 'cfg_flags'")?,
+            ),
+            locked: {
+                let items =
+                    quoted_items(extract_object(json, "locked").ok_or("missing key 'locked'")?);
+                items
+                    .chunks(2)
+                    .filter(|pair| pair.len() == 2)
+                    .map(|pair| (pair[0].clone(), pair[1].clone()))
+                    .collect()
+            },
+        })
+    }
+}
+
+/// Split `0.1.0 (rev)` into version and optional revision.
+fn split_compiler(value: &str) -> (String, Option<String>) {
+    match value.split_once(" (") {
+        Some((version, rev)) => (
+            version.to_string(),
+            Some(rev.trim_end_matches(')').to_string()),
+        ),
+        None => (value.to_string(), None),
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> BuildInfo {
+        BuildInfo {
+            compiler_version: "0.1.0".to_string(),
+            compiler_rev: Some("3f2a9c1d0b7e".to_string()),
+            target: "triton".to_string(),
+            profile: "release".to_string(),
+            cfg_flags: vec!["release".to_string(), "verbose".to_string()],
+            locked: BTreeMap::from([("merkle".to_string(), "ab12".to_string())]),
+        }
+    }
+
+    #[test]
+    fn tasm_header_roundtrips() {
+        let info = sample();
+        let tasm = format!("{}    call main\n    halt\n", info.tasm_header());
+        assert!(tasm.starts_with("// @trident compiler: 0.1.0 (3f2a9c1d0b7e)\n"));
+        assert_eq!(BuildInfo::from_tasm(&tasm), Some(info));
+    }
+
+    #[test]
+    fn tasm_without_header_has_no_build_info() {
+        assert_eq!(BuildInfo::from_tasm("    call main\n    halt\n"), None);
+    }
+
+    #[test]
+    fn json_roundtrips_with_and_without_revision() {
+        let info = sample();
+        assert_eq!(BuildInfo::from_json(&info.to_json("")), Ok(info.clone()));
+
+        let mut bare = info;
+        bare.compiler_rev = None;
+        bare.cfg_flags.clear();
+        bare.locked.clear();
+        assert_eq!(BuildInfo::from_json(&bare.to_json("  ")), Ok(bare));
+    }
+
+    #[test]
+    fn capture_records_options() {
+        let mut options = CompileOptions::for_profile("release");
+        options
+            .locked
+            .insert("merkle".to_string(), "ab12".to_string());
+        let info = BuildInfo::capture(&options);
+        assert_eq!(info.compiler_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.target, "triton");
+        assert_eq!(info.profile, "release");
+        assert_eq!(info.cfg_flags, vec!["release"]);
+        assert_eq!(info.locked.get("merkle").map(String::as_str), Some("ab12"));
+    }
+}
@@ -0,0 +1,229 @@
+//! Canonical binary envelope for proofs.
+//!
+//! `ProofData.proof_bytes` is warrior-specific and self-describing only to
+//! the warrior that wrote it. The envelope wraps it with the claim, the
+//! proof-system identifier, and an optional signature in a fixed,
+//! versioned layout that any language can read without a serialization
+//! library. All integers are little-endian.
+//!
+//! ```text
+//! offset  size   field
+//! 0       4      magic "TRPF"
+//! 4       1      version (1)
+//! 5       1      flags: bit 0 = signature present, other bits zero
+//! 6       2      format id length F
+//! 8       F      format id (UTF-8)
+//!         4+8n   program_hash:  u32 count n, then n u64 elements
+//!         4+8n   public_input:  u32 count n, then n u64 elements
+//!         4+8n   public_output: u32 count n, then n u64 elements
+//!         4+L    proof: u32 length L, then L bytes
+//! if bit 0 of flags:
+//!         2+S    scheme:     u16 length S, then UTF-8
+//!         2+K    public key: u16 length K, then K bytes
+//!         2+G    signature:  u16 length G, then G bytes
+//! ```
+//!
+//! Decoding is strict: unknown versions or flag bits, invalid UTF-8,
+//! truncated fields, and trailing bytes are all rejected, so every
+//! envelope has exactly one encoding. A signature covers the encoding of
+//! the same envelope without its signature (see `signing_payload`).
+
+use super::ProofData;
+use crate::field::proof::Claim;
+
+/// First four bytes of every envelope.
+pub const ENVELOPE_MAGIC: [u8; 4] = *b"TRPF";
+
+/// Envelope layout version written by `encode`.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+const FLAG_SIGNED: u8 = 0b0000_0001;
+
+/// A signature over an envelope's signing payload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnvelopeSignature {
+    /// Signature scheme identifier (e.g. "ed25519").
+    pub scheme: String,
+    /// Signer's public key, in the scheme's native encoding.
+    pub public_key: Vec<u8>,
+    /// Signature bytes.
+    pub signature: Vec<u8>,
+}
+
+/// A proof with its claim and format, ready to store or transmit.
+#[derive(Clone, Debug)]
+pub struct ProofEnvelope {
+    pub proof: ProofData,
+    pub signature: Option<EnvelopeSignature>,
+}
+
+impl ProofEnvelope {
+    /// Wrap a proof without a signature.
+    pub fn new(proof: ProofData) -> Self {
+        Self {
+            proof,
+            signature: None,
+        }
+    }
+
+    /// True if `bytes` starts with the envelope magic.
+    pub fn is_envelope(bytes: &[u8]) -> bool {
+        bytes.starts_with(&ENVELOPE_MAGIC)
+    }
+
+    /// Canonical encoding.
+    pub fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut out = self.encode_unsigned()?;
+        if let Some(sig) = &self.signature {
+            out[5] |= FLAG_SIGNED;
+            put_short(&mut out, "scheme", sig.scheme.as_bytes())?;
+            put_short(&mut out, "public key", &sig.public_key)?;
+            put_short(&mut out, "signature", &sig.signature)?;
+        }
+        Ok(out)
+    }
+
+    /// The bytes a signer signs: the encoding with no signature attached.
+    pub fn signing_payload(&self) -> Result<Vec<u8>, String> {
+        self.encode_unsigned()
+    }
+
+    /// Decode a canonical envelope.
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut r = Reader { bytes, pos: 0 };
+        if r.take(4, "magic")? != ENVELOPE_MAGIC {
+            return Err("not a proof envelope (bad magic)".to_string());
+        }
+        let version = r.u8("version")?;
+        if version != ENVELOPE_VERSION {
+            return Err(format!("unsupported envelope version {}", version));
+        }
+        let flags = r.u8("flags")?;
+        if flags & !FLAG_SIGNED != 0 {
+            return Err(format!("unknown envelope flags {:#04x}", flags));
+        }
+        let format = r.utf8_short("format id")?;
+        let claim = Claim {
+            program_hash: r.elements("program_hash")?,
+            public_input: r.elements("public_input")?,
+            public_output: r.elements("public_output")?,
+        };
+        let len = r.u32("proof length")? as usize;
+        let proof_bytes = r.take(len, "proof")?.to_vec();
+        let signature = if flags & FLAG_SIGNED != 0 {
+            Some(EnvelopeSignature {
+                scheme: r.utf8_short("scheme")?,
+                public_key: r.short("public key")?.to_vec(),
+                signature: r.short("signature")?.to_vec(),
+            })
+        } else {
+            None
+        };
+        if r.pos != bytes.len() {
+            return Err(format!(
+                "{} trailing bytes after envelope",
+                bytes.len() - r.pos
+            ));
+        }
+        Ok(Self {
+            proof: ProofData {
+                claim,
+                proof_bytes,
+                format,
+            },
+            signature,
+        })
+    }
+
+    fn encode_unsigned(&self) -> Result<Vec<u8>, String> {
+        let proof = &self.proof;
+        let mut out = Vec::new();
+        out.extend_from_slice(&ENVELOPE_MAGIC);
+        out.push(ENVELOPE_VERSION);
+        out.push(0);
+        put_short(&mut out, "format id", proof.format.as_bytes())?;
+        put_elements(&mut out, "program_hash", &proof.claim.program_hash)?;
+        put_elements(&mut out, "public_input", &proof.claim.public_input)?;
+        put_elements(&mut out, "public_output", &proof.claim.public_output)?;
+        let len = u32::try_from(proof.proof_bytes.len())
+            .map_err(|_| "proof exceeds 4 GiB".to_string())?;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&proof.proof_bytes);
+        Ok(out)
+    }
+}
+
+// ─── Encoding Helpers ──────────────────────────────────────────────
+
+fn put_short(out: &mut Vec<u8>, what: &str, bytes: &[u8]) -> Result<(), String> {
+    let len = u16::try_from(bytes.len()).map_err(|_| format!("{} exceeds 65535 bytes", what))?;
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+fn put_elements(out: &mut Vec<u8>, what: &str, elements: &[u64]) -> Result<(), String> {
+    let count = u32::try_from(elements.len()).map_err(|_| format!("{} is too long", what))?;
+    out.extend_from_slice(&count.to_le_bytes());
+    for e in elements {
+        out.extend_from_slice(&e.to_le_bytes());
+    }
+    Ok(())
+}
+
+struct Reader<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn take(&mut self, n: usize, what: &str) -> Result<&'b [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("envelope truncated in {}", what))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self, what: &str) -> Result<u8, String> {
+        Ok(self.take(1, what)?[0])
+    }
+
+    fn u32(&mut self, what: &str) -> Result<u32, String> {
+        let b = self.take(4, what)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn short(&mut self, what: &str) -> Result<&'b [u8], String> {
+        let b = self.take(2, what)?;
+        let len = u16::from_le_bytes([b[0], b[1]]) as usize;
+        self.take(len, what)
+    }
+
+    fn utf8_short(&mut self, what: &str) -> Result<String, String> {
+        let bytes = self.short(what)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| format!("{} is not valid UTF-8", what))
+    }
+
+    fn elements(&mut self, what: &str) -> Result<Vec<u64>, String> {
+        let count = self.u32(what)? as usize;
+        // Bound the allocation by what the input can actually hold.
+        if count > (self.bytes.len() - self.pos) / 8 {
+            return Err(format!("envelope truncated in {}", what));
+        }
+        (0..count)
+            .map(|_| {
+                let b = self.take(8, what)?;
+                let mut word = [0u8; 8];
+                word.copy_from_slice(b);
+                Ok(u64::from_le_bytes(word))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,148 @@
+use super::*;
+
+fn sample() -> ProofEnvelope {
+    ProofEnvelope::new(ProofData {
+        claim: Claim {
+            program_hash: vec![1],
+            public_input: Vec::new(),
+            public_output: vec![7],
+        },
+        proof_bytes: vec![0xde, 0xad],
+        format: "stark".to_string(),
+    })
+}
+
+fn signed() -> ProofEnvelope {
+    let mut env = sample();
+    env.signature = Some(EnvelopeSignature {
+        scheme: "ed25519".to_string(),
+        public_key: vec![0x01, 0x02],
+        signature: vec![0xff],
+    });
+    env
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unhex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// Test vectors: other implementations must produce these exact bytes.
+const UNSIGNED_VECTOR: &str = concat!(
+    "54525046",         // magic "TRPF"
+    "01",               // version
+    "00",               // flags
+    "0500",             // format id length
+    "737461726b",       // "stark"
+    "01000000",         // program_hash count
+    "0100000000000000", // 1
+    "00000000",         // public_input count
+    "01000000",         // public_output count
+    "0700000000000000", // 7
+    "02000000",         // proof length
+    "dead",             // proof
+);
+
+const SIGNED_VECTOR: &str = concat!(
+    "54525046",
+    "01",
+    "01", // flags: signature present
+    "0500",
+    "737461726b",
+    "01000000",
+    "0100000000000000",
+    "00000000",
+    "01000000",
+    "0700000000000000",
+    "02000000",
+    "dead",
+    "0700",           // scheme length
+    "65643235353139", // "ed25519"
+    "0200",           // public key length
+    "0102",
+    "0100", // signature length
+    "ff",
+);
+
+#[test]
+fn encoding_matches_unsigned_test_vector() {
+    assert_eq!(hex(&sample().encode().unwrap()), UNSIGNED_VECTOR);
+}
+
+#[test]
+fn encoding_matches_signed_test_vector() {
+    assert_eq!(hex(&signed().encode().unwrap()), SIGNED_VECTOR);
+}
+
+#[test]
+fn test_vectors_decode_to_their_envelopes() {
+    let env = ProofEnvelope::decode(&unhex(UNSIGNED_VECTOR)).unwrap();
+    assert_eq!(env.proof.claim, sample().proof.claim);
+    assert_eq!(env.proof.proof_bytes, vec![0xde, 0xad]);
+    assert_eq!(env.proof.format, "stark");
+    assert_eq!(env.signature, None);
+
+    let env = ProofEnvelope::decode(&unhex(SIGNED_VECTOR)).unwrap();
+    assert_eq!(env.signature, signed().signature);
+}
+
+#[test]
+fn signing_payload_is_the_unsigned_encoding() {
+    assert_eq!(hex(&signed().signing_payload().unwrap()), UNSIGNED_VECTOR);
+}
+
+#[test]
+fn decode_rejects_non_canonical_input() {
+    let good = unhex(UNSIGNED_VECTOR);
+
+    let mut trailing = good.clone();
+    trailing.push(0);
+    assert!(ProofEnvelope::decode(&trailing)
+        .unwrap_err()
+        .contains("trailing"));
+
+    let mut bad_version = good.clone();
+    bad_version[4] = 2;
+    assert!(ProofEnvelope::decode(&bad_version)
+        .unwrap_err()
+        .contains("version"));
+
+    let mut bad_flags = good.clone();
+    bad_flags[5] = 0x80;
+    assert!(ProofEnvelope::decode(&bad_flags)
+        .unwrap_err()
+        .contains("flags"));
+
+    let mut bad_magic = good.clone();
+    bad_magic[0] = b'X';
+    assert!(!ProofEnvelope::is_envelope(&bad_magic));
+    assert!(ProofEnvelope::decode(&bad_magic).is_err());
+}
+
+#[test]
+fn decode_rejects_every_truncation() {
+    let good = unhex(SIGNED_VECTOR);
+    for len in 0..good.len() {
+        assert!(
+            ProofEnvelope::decode(&good[..len]).is_err(),
+            "prefix of length {} decoded",
+            len
+        );
+    }
+}
+
+#[test]
+fn oversized_element_count_is_rejected_without_allocating() {
+    let mut bytes = unhex(UNSIGNED_VECTOR);
+    // program_hash count at offset 13
+    bytes[13..17].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(ProofEnvelope::decode(&bytes)
+        .unwrap_err()
+        .contains("truncated"));
+}
@@ -0,0 +1,302 @@
+//! Differential fuzzing of the interpreter against another `Runner`.
+//!
+//! Each case is a small random TASM program with random inputs, derived
+//! from a single seed so any divergence can be replayed. Both runners
+//! execute it; they diverge when their outputs or cycle counts differ,
+//! or when one faults and the other halts. Error messages are not
+//! compared — only whether a run failed.
+//!
+//! Programs keep every stack access within the values they pushed (the
+//! initial stack of Triton VM holds the program digest) and avoid
+//! `hash`, the sponge, and `merkle_step`, whose permutation differs by
+//! design (see the module docs of `interp`).
+
+use super::super::artifact::{BundleCost, ProgramBundle};
+use super::super::{ExecutionResult, ProgramInput, Runner};
+use super::Interpreter;
+use crate::field::goldilocks::MODULUS;
+
+/// Values pushed above the floor at most; keeps `dup` and `swap`
+/// operands in range.
+const MAX_HEIGHT: usize = 12;
+
+/// RAM addresses used by generated memory instructions.
+const RAM_BASE: u64 = 1000;
+
+/// Helper functions appended to every generated program.
+const HELPERS: &str = "\
+__fuzz_double:
+    dup 0
+    add
+    return
+__fuzz_loop:
+    dup 0
+    push 0
+    eq
+    skiz
+    return
+    swap 1
+    addi 3
+    swap 1
+    addi -1
+    recurse
+";
+
+/// A generated program and its inputs.
+#[derive(Clone, Debug)]
+pub struct FuzzCase {
+    /// Seed the case was generated from.
+    pub seed: u64,
+    pub tasm: String,
+    pub input: ProgramInput,
+}
+
+impl FuzzCase {
+    /// The case as a bundle for a `Runner`.
+    pub fn bundle(&self) -> ProgramBundle {
+        ProgramBundle {
+            name: format!("fuzz_{}", self.seed),
+            version: "0.1.0".to_string(),
+            target_vm: "triton".to_string(),
+            target_os: None,
+            assembly: self.tasm.clone(),
+            entry_point: "main".to_string(),
+            functions: Vec::new(),
+            errors: Vec::new(),
+            outputs: Vec::new(),
+            events: Vec::new(),
+            entrypoints: Vec::new(),
+            data: Vec::new(),
+            cost: BundleCost {
+                table_values: Vec::new(),
+                table_names: Vec::new(),
+                padded_height: 0,
+                estimated_proving_ns: 0,
+            },
+            source_hash: String::new(),
+            build: None,
+        }
+    }
+}
+
+/// A case on which the two runners disagree.
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    pub case: FuzzCase,
+    /// What differs, e.g. `outputs differ`.
+    pub reason: String,
+    pub interpreter: Result<ExecutionResult, String>,
+    pub candidate: Result<ExecutionResult, String>,
+}
+
+/// Run `cases` generated cases, seeded `seed`, `seed + 1`, ..., on the
+/// interpreter and on `candidate`, returning every divergence.
+pub fn fuzz_against(candidate: &dyn Runner, seed: u64, cases: u64) -> Vec<Divergence> {
+    let interpreter = Interpreter::new();
+    let mut divergences = Vec::new();
+    for i in 0..cases {
+        let case = generate_case(seed.wrapping_add(i));
+        let bundle = case.bundle();
+        let expected = interpreter.run(&bundle, &case.input);
+        let actual = candidate.run(&bundle, &case.input);
+        if let Some(reason) = compare_runs(&expected, &actual) {
+            divergences.push(Divergence {
+                case,
+                reason,
+                interpreter: expected,
+                candidate: actual,
+            });
+        }
+    }
+    divergences
+}
+
+/// Describe how two runs of the same case differ, if they do.
+pub fn compare_runs(
+    a: &Result<ExecutionResult, String>,
+    b: &Result<ExecutionResult, String>,
+) -> Option<String> {
+    match (a, b) {
+        (Ok(a), Ok(b)) if a.output != b.output => Some("outputs differ".to_string()),
+        (Ok(a), Ok(b)) if a.cycle_count != b.cycle_count => Some(format!(
+            "cycle counts differ ({} vs {})",
+            a.cycle_count, b.cycle_count
+        )),
+        (Ok(_), Ok(_)) | (Err(_), Err(_)) => None,
+        (Ok(_), Err(_)) => Some("only the candidate fails".to_string()),
+        (Err(_), Ok(_)) => Some("only the interpreter fails".to_string()),
+    }
+}
+
+/// Generate the case for `seed`: a straight-line `main` of 5 to 40
+/// steps that writes whatever it leaves on the stack, then halts.
+pub fn generate_case(seed: u64) -> FuzzCase {
+    let mut rng = Rng::new(seed);
+    let mut input = ProgramInput::default();
+    let mut body = Vec::new();
+    let mut height = 0;
+    let steps = 5 + rng.below(36);
+    for _ in 0..steps {
+        height = emit_step(&mut rng, &mut body, &mut input, height);
+    }
+    while height > 0 {
+        let n = height.min(5);
+        body.push(format!("write_io {}", n));
+        height -= n;
+    }
+    body.push("halt".to_string());
+
+    let mut tasm = String::from("main:\n");
+    for line in body {
+        tasm.push_str("    ");
+        tasm.push_str(&line);
+        tasm.push('\n');
+    }
+    tasm.push_str(HELPERS);
+    FuzzCase { seed, tasm, input }
+}
+
+/// Append one random step valid at `height` and return the new height.
+fn emit_step(rng: &mut Rng, body: &mut Vec<String>, input: &mut ProgramInput, h: usize) -> usize {
+    let room = MAX_HEIGHT.saturating_sub(h);
+    let mut emit = |line: String| body.push(line);
+    loop {
+        match rng.below(20) {
+            0 | 1 if room > 0 => {
+                emit(format!("push {}", rng.value()));
+                return h + 1;
+            }
+            2 if room > 0 => {
+                let n = 1 + rng.below(room.min(3));
+                input.public.extend((0..n).map(|_| rng.value()));
+                emit(format!("read_io {}", n));
+                return h + n;
+            }
+            3 if room > 0 => {
+                let n = 1 + rng.below(room.min(3));
+                input.secret.extend((0..n).map(|_| rng.value()));
+                emit(format!("divine {}", n));
+                return h + n;
+            }
+            4 if h > 0 => {
+                let n = 1 + rng.below(h.min(5));
+                emit(format!("pop {}", n));
+                return h - n;
+            }
+            5 if h > 0 && room > 0 => {
+                emit(format!("dup {}", rng.below(h)));
+                return h + 1;
+            }
+            6 if h > 1 => {
+                emit(format!("swap {}", 1 + rng.below(h - 1)));
+                return h;
+            }
+            7 if h > 1 => {
+                let op = if rng.below(2) == 0 { "pick" } else { "place" };
+                emit(format!("{} {}", op, rng.below(h)));
+                return h;
+            }
+            8 if h > 1 => {
+                emit(["add", "mul", "eq"][rng.below(3)].to_string());
+                return h - 1;
+            }
+            9 if h > 0 => {
+                emit(["invert", "log_2_floor", "pop_count"][rng.below(3)].to_string());
+                return h;
+            }
+            10 if h > 0 => {
+                emit(format!("addi {}", rng.value()));
+                return h;
+            }
+            11 if h > 0 && room > 0 => {
+                emit("split".to_string());
+                return h + 1;
+            }
+            12 if h > 1 => {
+                emit(["lt", "and", "xor", "pow"][rng.below(4)].to_string());
+                return h - 1;
+            }
+            13 if h > 1 => {
+                emit("div_mod".to_string());
+                return h;
+            }
+            14 if h > 0 => {
+                let n = 1 + rng.below(h.min(5));
+                emit(format!("push {}", RAM_BASE + rng.below(8) as u64));
+                emit(format!("write_mem {}", n));
+                emit("pop 1".to_string());
+                return h - n;
+            }
+            15 if room > 0 => {
+                let n = 1 + rng.below(room.min(5));
+                emit(format!("push {}", RAM_BASE + rng.below(8) as u64));
+                emit(format!("read_mem {}", n));
+                emit("pop 1".to_string());
+                return h + n;
+            }
+            16 if h > 0 => {
+                let n = 1 + rng.below(h.min(5));
+                emit(format!("write_io {}", n));
+                return h - n;
+            }
+            17 if h > 1 => {
+                emit("skiz".to_string());
+                emit(format!("addi {}", rng.value()));
+                return h - 1;
+            }
+            18 if h > 0 => {
+                if rng.below(2) == 0 {
+                    emit("call __fuzz_double".to_string());
+                } else {
+                    emit(format!("push {}", rng.below(6)));
+                    emit("call __fuzz_loop".to_string());
+                    emit("pop 1".to_string());
+                }
+                return h;
+            }
+            19 if h > 0 && rng.below(4) == 0 => {
+                emit("assert".to_string());
+                return h - 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// xorshift64* — reproducible from the case seed.
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform-enough index in `0..n`; `n` must be nonzero.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A field element, biased towards u32 values and edge cases so the
+    /// u32 instructions both succeed and fault.
+    fn value(&mut self) -> u64 {
+        match self.below(8) {
+            0 => [0, 1, 2, MODULUS - 1, u32::MAX as u64, 1 << 32][self.below(6)],
+            1 => self.next_u64() % MODULUS,
+            2 => self.next_u64() % 16,
+            _ => self.next_u64() & u32::MAX as u64,
+        }
+    }
+}
@@ -0,0 +1,490 @@
+//! Machine state and the instruction step function.
+
+use std::collections::BTreeMap;
+
+use super::xfield::XFieldElement;
+use super::{Hint, Instr, Program};
+use crate::field::poseidon2::Sponge;
+use crate::field::{Goldilocks, PrimeField};
+use crate::runtime::ProgramInput;
+
+/// Stack elements that always exist; popping into them underflows.
+const STACK_FLOOR: usize = 16;
+
+/// Elements consumed by `hash` and `sponge_absorb`, produced by `sponge_squeeze`.
+const RATE: usize = 10;
+
+/// Elements in a digest.
+const DIGEST: usize = 5;
+
+/// What the machine does after an instruction.
+enum Flow {
+    Next,
+    Skip,
+    Jump(usize),
+    Halt,
+}
+
+pub(super) struct Machine<'a> {
+    program: &'a Program,
+    input: &'a ProgramInput,
+    errors: &'a BTreeMap<u64, String>,
+    stack: Vec<u64>,
+    ram: BTreeMap<u64, u64>,
+    /// Call frames: (return address, start of the callee).
+    calls: Vec<(usize, usize)>,
+    public_read: usize,
+    secret_read: usize,
+    digests_read: usize,
+    sponge: Option<Sponge<'static, Goldilocks>>,
+    pub(super) output: Vec<u64>,
+    print_trace: bool,
+    pub(super) trace: Vec<String>,
+    /// `error_id` of the assertion that stopped the run, if any.
+    pub(super) abort_code: Option<u64>,
+    pub(super) pc: usize,
+    pub(super) cycles: u64,
+}
+
+impl<'a> Machine<'a> {
+    pub(super) fn new(
+        program: &'a Program,
+        input: &'a ProgramInput,
+        errors: &'a BTreeMap<u64, String>,
+        print_trace: bool,
+    ) -> Self {
+        Self {
+            program,
+            input,
+            errors,
+            stack: vec![0; STACK_FLOOR],
+            ram: BTreeMap::new(),
+            calls: Vec::new(),
+            public_read: 0,
+            secret_read: 0,
+            digests_read: 0,
+            sponge: None,
+            output: Vec::new(),
+            print_trace,
+            trace: Vec::new(),
+            abort_code: None,
+            pc: 0,
+            cycles: 0,
+        }
+    }
+
+    /// Execute until `halt`. On error, `pc` is the failing instruction.
+    pub(super) fn run(&mut self, max_cycles: u64) -> Result<(), String> {
+        let program = self.program;
+        loop {
+            let instr = program
+                .instrs
+                .get(self.pc)
+                .ok_or("execution ran past the last instruction")?;
+            if self.cycles == max_cycles {
+                return Err(format!("cycle limit of {} exceeded", max_cycles));
+            }
+            self.cycles += 1;
+            if let Some(hints) = program.hints.get(&self.pc) {
+                for hint in hints {
+                    self.record_hint(hint)?;
+                }
+            }
+            match self.step(instr)? {
+                Flow::Next => self.pc += 1,
+                Flow::Skip => self.pc += 2,
+                Flow::Jump(target) => self.pc = target,
+                Flow::Halt => return Ok(()),
+            }
+        }
+    }
+
+    fn step(&mut self, instr: &Instr) -> Result<Flow, String> {
+        match instr {
+            // ── Stack ──
+            Instr::Push(v) => self.stack.push(*v),
+            Instr::Pop(n) => {
+                self.pop_n(*n)?;
+            }
+            Instr::Divine(n) => {
+                let secret = &self.input.secret;
+                if self.secret_read + n > secret.len() {
+                    return Err("secret input exhausted".to_string());
+                }
+                for &v in &secret[self.secret_read..self.secret_read + n] {
+                    self.stack.push(Goldilocks::from_u64(v).to_u64());
+                }
+                self.secret_read += n;
+            }
+            Instr::Pick(i) => {
+                let at = self.depth_index(*i);
+                let v = self.stack.remove(at);
+                self.stack.push(v);
+            }
+            Instr::Place(i) => {
+                let v = self.pop()?;
+                let at = self.stack.len() - i;
+                self.stack.insert(at, v);
+            }
+            Instr::Dup(i) => {
+                let v = self.stack[self.depth_index(*i)];
+                self.stack.push(v);
+            }
+            Instr::Swap(i) => {
+                let top = self.stack.len() - 1;
+                self.stack.swap(top, top - i);
+            }
+            Instr::Nop => {}
+
+            // ── Control flow ──
+            Instr::Skiz => {
+                if self.pop()? == 0 {
+                    return Ok(Flow::Skip);
+                }
+            }
+            Instr::Call(target) => {
+                let target = *target
+                    .as_ref()
+                    .map_err(|label| format!("call to undefined label `{}`", label))?;
+                self.calls.push((self.pc + 1, target));
+                return Ok(Flow::Jump(target));
+            }
+            Instr::Return => return self.ret(),
+            Instr::Recurse => return self.recurse(),
+            Instr::RecurseOrReturn => {
+                if self.peek(5) == self.peek(6) {
+                    return self.ret();
+                }
+                return self.recurse();
+            }
+            Instr::Assert(error_id) => {
+                let v = self.pop()?;
+                if v != 1 {
+                    return Err(format!(
+                        "{}: expected 1, found {}",
+                        self.failure("assertion", *error_id),
+                        v
+                    ));
+                }
+            }
+            Instr::AssertVector(error_id) => {
+                for i in 0..DIGEST {
+                    let (a, b) = (self.peek(i), self.peek(i + DIGEST));
+                    if a != b {
+                        return Err(format!(
+                            "{}: element {} is {}, expected {}",
+                            self.failure("vector assertion", *error_id),
+                            i,
+                            a,
+                            b
+                        ));
+                    }
+                }
+                self.pop_n(DIGEST)?;
+            }
+            Instr::Halt => return Ok(Flow::Halt),
+
+            // ── Memory ──
+            Instr::ReadMem(n) => {
+                let p = self.pop()?;
+                for i in 0..*n {
+                    let addr = field_add(p, field_neg(i as u64));
+                    let v = self.ram.get(&addr).copied().unwrap_or(0);
+                    self.stack.push(v);
+                }
+                self.stack.push(field_add(p, field_neg(*n as u64)));
+            }
+            Instr::WriteMem(n) => {
+                let p = self.pop()?;
+                for i in 0..*n {
+                    let v = self.pop()?;
+                    self.ram.insert(field_add(p, i as u64), v);
+                }
+                self.stack.push(field_add(p, *n as u64));
+            }
+
+            // ── Hashing ──
+            Instr::Hash => {
+                let block = self.pop_n(RATE)?;
+                let mut sponge = Sponge::goldilocks();
+                sponge.absorb(&block);
+                self.push_all(&sponge.squeeze(DIGEST));
+            }
+            Instr::SpongeInit => self.sponge = Some(Sponge::goldilocks()),
+            Instr::SpongeAbsorb => {
+                let block = self.pop_n(RATE)?;
+                self.sponge()?.absorb(&block);
+            }
+            Instr::SpongeAbsorbMem => {
+                let p = self.pop()?;
+                let block = self.ram_block(p, RATE);
+                self.sponge()?.absorb(&block);
+            }
+            Instr::SpongeSqueeze => {
+                let out = self.sponge()?.squeeze(RATE);
+                self.push_all(&out);
+            }
+            Instr::MerkleStep => {
+                let digests = &self.input.digests;
+                let sibling = digests
+                    .get(self.digests_read)
+                    .ok_or("digest input exhausted")?
+                    .map(Goldilocks::from_u64);
+                self.digests_read += 1;
+                self.merkle_step(&sibling)?;
+            }
+            Instr::MerkleStepMem => {
+                let p = self.pop()?;
+                let sibling = self.ram_block(p, DIGEST);
+                self.merkle_step(&sibling)?;
+                self.stack.push(field_add(p, DIGEST as u64));
+            }
+
+            // ── Base field ──
+            Instr::Add => {
+                let (b, a) = (self.pop()?, self.pop()?);
+                self.stack.push(field_add(a, b));
+            }
+            Instr::AddI(c) => {
+                let a = self.pop()?;
+                self.stack.push(field_add(a, *c));
+            }
+            Instr::Mul => {
+                let (b, a) = (self.pop()?, self.pop()?);
+                self.stack.push(Goldilocks(a).mul(Goldilocks(b)).to_u64());
+            }
+            Instr::Invert => {
+                let a = self.pop()?;
+                let inv = Goldilocks(a).inv().ok_or("inverse of zero")?;
+                self.stack.push(inv.to_u64());
+            }
+            Instr::Eq => {
+                let (b, a) = (self.pop()?, self.pop()?);
+                self.stack.push((a == b) as u64);
+            }
+
+            // ── u32 ──
+            Instr::Split => {
+                let a = self.pop()?;
+                self.stack.push(a >> 32);
+                self.stack.push(a & 0xFFFF_FFFF);
+            }
+            Instr::Lt => {
+                let (top, below) = (self.pop_u32("lt")?, self.pop_u32("lt")?);
+                self.stack.push((top < below) as u64);
+            }
+            Instr::And => {
+                let (b, a) = (self.pop_u32("and")?, self.pop_u32("and")?);
+                self.stack.push(a & b);
+            }
+            Instr::Or => {
+                let (b, a) = (self.pop_u32("or")?, self.pop_u32("or")?);
+                self.stack.push(a | b);
+            }
+            Instr::Xor => {
+                let (b, a) = (self.pop_u32("xor")?, self.pop_u32("xor")?);
+                self.stack.push(a ^ b);
+            }
+            Instr::Log2Floor => {
+                let a = self.pop_u32("log_2_floor")?;
+                if a == 0 {
+                    return Err("log_2_floor of zero".to_string());
+                }
+                self.stack.push(63 - a.leading_zeros() as u64);
+            }
+            Instr::Pow => {
+                let exp = self.pop_u32("pow")?;
+                let base = self.pop()?;
+                self.stack.push(Goldilocks(base).pow(exp).to_u64());
+            }
+            Instr::DivMod => {
+                let d = self.pop_u32("div_mod")?;
+                let n = self.pop_u32("div_mod")?;
+                if d == 0 {
+                    return Err("division by zero".to_string());
+                }
+                self.stack.push(n / d);
+                self.stack.push(n % d);
+            }
+            Instr::PopCount => {
+                let a = self.pop_u32("pop_count")?;
+                self.stack.push(a.count_ones() as u64);
+            }
+
+            // ── Extension field ──
+            Instr::XxAdd => {
+                let (b, a) = (self.pop_xfe()?, self.pop_xfe()?);
+                self.push_xfe(a.add(b));
+            }
+            Instr::XxMul => {
+                let (b, a) = (self.pop_xfe()?, self.pop_xfe()?);
+                self.push_xfe(a.mul(b));
+            }
+            Instr::XInvert => {
+                let a = self.pop_xfe()?;
+                let inv = a.inv().ok_or("extension field inverse of zero")?;
+                self.push_xfe(inv);
+            }
+            Instr::XbMul => {
+                let b = self.pop()?;
+                let a = self.pop_xfe()?;
+                self.push_xfe(a.scale(Goldilocks(b)));
+            }
+
+            // ── I/O ──
+            Instr::ReadIo(n) => {
+                let public = &self.input.public;
+                if self.public_read + n > public.len() {
+                    return Err("public input exhausted".to_string());
+                }
+                for &v in &public[self.public_read..self.public_read + n] {
+                    self.stack.push(Goldilocks::from_u64(v).to_u64());
+                }
+                self.public_read += n;
+            }
+            Instr::WriteIo(n) => {
+                for _ in 0..*n {
+                    let v = self.pop()?;
+                    self.output.push(v);
+                }
+            }
+
+            Instr::Unsupported(op) => {
+                return Err(format!("`{}` is not supported by the interpreter", op));
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn ret(&mut self) -> Result<Flow, String> {
+        let (return_to, _) = self.calls.pop().ok_or("`return` outside any call")?;
+        Ok(Flow::Jump(return_to))
+    }
+
+    fn recurse(&mut self) -> Result<Flow, String> {
+        let &(_, start) = self.calls.last().ok_or("`recurse` outside any call")?;
+        Ok(Flow::Jump(start))
+    }
+
+    /// Replace `idx d0..d4` with `idx / 2` and the parent digest. An even
+    /// index is a left child.
+    fn merkle_step(&mut self, sibling: &[Goldilocks]) -> Result<(), String> {
+        let node = self.pop_n(DIGEST)?;
+        let idx = self.pop_u32("merkle_step")?;
+        let mut sponge = Sponge::goldilocks();
+        if idx % 2 == 0 {
+            sponge.absorb(&node);
+            sponge.absorb(sibling);
+        } else {
+            sponge.absorb(sibling);
+            sponge.absorb(&node);
+        }
+        self.stack.push(idx / 2);
+        self.push_all(&sponge.squeeze(DIGEST));
+        Ok(())
+    }
+
+    /// Record `name = value` for a `hint`, the value in push order
+    /// (deepest first) so it reads like the source tuple.
+    fn record_hint(&mut self, hint: &Hint) -> Result<(), String> {
+        if hint.end > self.stack.len() {
+            return Err(format!("hint `{}` reaches below the stack", hint.name));
+        }
+        let values: Vec<String> = (hint.start..hint.end)
+            .rev()
+            .map(|i| self.peek(i).to_string())
+            .collect();
+        let line = match values.as_slice() {
+            [value] => format!("{} = {}", hint.name, value),
+            _ => format!("{} = [{}]", hint.name, values.join(", ")),
+        };
+        if self.print_trace {
+            eprintln!("trace: {}", line);
+        }
+        self.trace.push(line);
+        Ok(())
+    }
+
+    /// "assertion failed", naming the error code and its message. The
+    /// code becomes the run's abort code.
+    fn failure(&mut self, what: &str, error_id: Option<u64>) -> String {
+        self.abort_code = error_id;
+        match error_id.map(|id| (id, self.errors.get(&id))) {
+            Some((id, Some(message))) => format!("{} failed: {} (error {})", what, message, id),
+            Some((id, None)) => format!("{} failed (error {})", what, id),
+            None => format!("{} failed", what),
+        }
+    }
+
+    fn sponge(&mut self) -> Result<&mut Sponge<'static, Goldilocks>, String> {
+        self.sponge
+            .as_mut()
+            .ok_or_else(|| "sponge used before `sponge_init`".to_string())
+    }
+
+    fn pop(&mut self) -> Result<u64, String> {
+        if self.stack.len() <= STACK_FLOOR {
+            return Err("operational stack underflow".to_string());
+        }
+        Ok(self.stack.pop().unwrap_or(0))
+    }
+
+    /// Pop `n` elements, returned in push order (deepest first).
+    fn pop_n(&mut self, n: usize) -> Result<Vec<Goldilocks>, String> {
+        if self.stack.len() < STACK_FLOOR + n {
+            return Err("operational stack underflow".to_string());
+        }
+        let at = self.stack.len() - n;
+        Ok(self
+            .stack
+            .split_off(at)
+            .into_iter()
+            .map(Goldilocks)
+            .collect())
+    }
+
+    fn pop_u32(&mut self, op: &str) -> Result<u64, String> {
+        let v = self.pop()?;
+        if v > u32::MAX as u64 {
+            return Err(format!("`{}` operand {} is not a u32", op, v));
+        }
+        Ok(v)
+    }
+
+    fn pop_xfe(&mut self) -> Result<XFieldElement, String> {
+        let c = self.pop_n(3)?;
+        Ok(XFieldElement([c[0], c[1], c[2]]))
+    }
+
+    fn push_xfe(&mut self, x: XFieldElement) {
+        self.push_all(&x.0);
+    }
+
+    fn push_all(&mut self, values: &[Goldilocks]) {
+        self.stack.extend(values.iter().map(|v| v.to_u64()));
+    }
+
+    /// Index into `stack` of the element at depth `i` (st0 is depth 0).
+    /// Depths up to 15 always exist because of the stack floor.
+    fn depth_index(&self, i: usize) -> usize {
+        self.stack.len() - 1 - i
+    }
+
+    fn peek(&self, i: usize) -> u64 {
+        self.stack[self.depth_index(i)]
+    }
+
+    fn ram_block(&self, p: u64, n: usize) -> Vec<Goldilocks> {
+        (0..n as u64)
+            .map(|i| Goldilocks(self.ram.get(&field_add(p, i)).copied().unwrap_or(0)))
+            .collect()
+    }
+}
+
+fn field_add(a: u64, b: u64) -> u64 {
+    Goldilocks(a).add(Goldilocks::from_u64(b)).to_u64()
+}
+
+fn field_neg(a: u64) -> u64 {
+    Goldilocks::from_u64(a).neg().to_u64()
+}
@@ -0,0 +1,435 @@
+//! Reference interpreter for linked Triton assembly.
+//!
+//! Runs the TASM the compiler emits — labels, `call` / `return` /
+//! `recurse`, `skiz` branching, RAM, public and secret input, and the
+//! u32 and extension-field instructions — so `trident test` can execute
+//! `#[test]` functions without a warrior. Proving, and bit-exact
+//! agreement with Triton VM, stay with the warrior (e.g. Trisha).
+//!
+//! Where it knowingly differs from Triton VM:
+//! - `hash`, the sponge instructions, and `merkle_step` use Poseidon2
+//!   over Goldilocks (`field::poseidon2`) in place of Tip5. Digests are
+//!   consistent within a run but are not Triton digests.
+//! - Stack effects follow the builtin signatures in `typecheck::builtins`
+//!   and operand order follows the TIR lowering, as in
+//!   `cost::stack_verifier`: `div_mod` and `pow` take their right-hand
+//!   operand from st0.
+//! - `xx_dot_step` and `xb_dot_step` are not supported.
+//!
+//! Every executed instruction counts as one cycle. Triton `hint` lines
+//! are not instructions: the interpreter records the named stack slice
+//! as a trace line when it reaches the next instruction.
+
+pub mod fuzz;
+mod machine;
+mod xfield;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+
+use super::{ExecutionResult, ProgramBundle, ProgramInput, Runner};
+use crate::field::goldilocks::MODULUS;
+use machine::Machine;
+
+/// Cycle budget used when none is configured.
+pub const DEFAULT_MAX_CYCLES: u64 = 1 << 24;
+
+/// One decoded instruction. Call targets are resolved to instruction
+/// indices at parse time; an unknown label faults only when called.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Instr {
+    Push(u64),
+    Pop(usize),
+    Divine(usize),
+    Pick(usize),
+    Place(usize),
+    Dup(usize),
+    Swap(usize),
+    Nop,
+    Skiz,
+    Call(Result<usize, String>),
+    Return,
+    Recurse,
+    RecurseOrReturn,
+    /// `assert`, with the `error_id` a failure reports.
+    Assert(Option<u64>),
+    AssertVector(Option<u64>),
+    Halt,
+    ReadMem(usize),
+    WriteMem(usize),
+    Hash,
+    SpongeInit,
+    SpongeAbsorb,
+    SpongeAbsorbMem,
+    SpongeSqueeze,
+    Add,
+    AddI(u64),
+    Mul,
+    Invert,
+    Eq,
+    Split,
+    Lt,
+    And,
+    Or,
+    Xor,
+    Log2Floor,
+    Pow,
+    DivMod,
+    PopCount,
+    XxAdd,
+    XxMul,
+    XInvert,
+    XbMul,
+    ReadIo(usize),
+    WriteIo(usize),
+    MerkleStep,
+    MerkleStepMem,
+    Unsupported(String),
+}
+
+/// A parsed TASM program.
+#[derive(Clone, Debug)]
+pub struct Program {
+    instrs: Vec<Instr>,
+    /// 1-based source line of each instruction.
+    lines: Vec<usize>,
+    /// Label -> index of the first instruction after it.
+    labels: BTreeMap<String, usize>,
+    /// `hint` lines, by the index of the instruction they precede.
+    hints: BTreeMap<usize, Vec<Hint>>,
+}
+
+/// A Triton `hint name = stack[start..end]` annotation.
+#[derive(Clone, Debug)]
+struct Hint {
+    name: String,
+    start: usize,
+    end: usize,
+}
+
+impl Program {
+    /// Parse TASM text. Comments, blank lines, and labels are accepted
+    /// anywhere; every other line must be one instruction.
+    pub fn parse(tasm: &str) -> Result<Self, String> {
+        let mut labels = BTreeMap::new();
+        let mut hints: BTreeMap<usize, Vec<Hint>> = BTreeMap::new();
+        let mut pending: Vec<(usize, &str, Option<&str>, Option<&str>)> = Vec::new();
+        for (i, raw) in tasm.lines().enumerate() {
+            let line = raw.split("//").next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(label) = line.strip_suffix(':') {
+                if labels.insert(label.to_string(), pending.len()).is_some() {
+                    return Err(format!(
+                        "line {}: label `{}` is defined twice",
+                        i + 1,
+                        label
+                    ));
+                }
+                continue;
+            }
+            if let Some(hint) = line.strip_prefix("hint ") {
+                let hint = parse_hint(hint).map_err(|e| format!("line {}: {}", i + 1, e))?;
+                hints.entry(pending.len()).or_default().push(hint);
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let op = parts.next().unwrap_or("");
+            let mut arg = parts.next();
+            // `assert error_id 7` is the one form with a keyword operand.
+            let mut error_id = None;
+            if arg == Some("error_id") {
+                error_id = Some(parts.next().unwrap_or(""));
+                arg = None;
+            }
+            if parts.next().is_some() {
+                return Err(format!("line {}: unexpected operand in `{}`", i + 1, line));
+            }
+            pending.push((i + 1, op, arg, error_id));
+        }
+
+        let mut instrs = Vec::with_capacity(pending.len());
+        let mut lines = Vec::with_capacity(pending.len());
+        for (line, op, arg, error_id) in pending {
+            let instr =
+                decode(op, arg, error_id, &labels).map_err(|e| format!("line {}: {}", line, e))?;
+            instrs.push(instr);
+            lines.push(line);
+        }
+        Ok(Self {
+            instrs,
+            lines,
+            labels,
+            hints,
+        })
+    }
+
+    /// Number of instructions.
+    pub fn len(&self) -> usize {
+        self.instrs.len()
+    }
+
+    /// Whether the program has no instructions.
+    pub fn is_empty(&self) -> bool {
+        self.instrs.is_empty()
+    }
+
+    /// Label of the function containing instruction `pc`, if any.
+    fn enclosing_label(&self, pc: usize) -> Option<&str> {
+        self.labels
+            .iter()
+            .filter(|(_, &start)| start <= pc)
+            .max_by_key(|(_, &start)| start)
+            .map(|(label, _)| label.as_str())
+    }
+}
+
+/// Parse the body of `hint name = stack[0]` or `hint name = stack[0..5]`.
+fn parse_hint(text: &str) -> Result<Hint, String> {
+    let malformed = || format!("malformed hint `hint {}`", text);
+    let (name, slice) = text.split_once('=').ok_or_else(malformed)?;
+    // A type annotation (`hint d: Digest = ...`) only matters to debuggers.
+    let name = name.split(':').next().unwrap_or("").trim();
+    let range = slice
+        .trim()
+        .strip_prefix("stack[")
+        .and_then(|r| r.strip_suffix(']'))
+        .ok_or_else(malformed)?;
+    let (start, end) = match range.split_once("..") {
+        Some((start, end)) => (start.parse(), end.parse()),
+        None => (range.parse(), range.parse().map(|s: usize| s + 1)),
+    };
+    match (start, end) {
+        (Ok(start), Ok(end)) if !name.is_empty() && start < end => Ok(Hint {
+            name: name.to_string(),
+            start,
+            end,
+        }),
+        _ => Err(malformed()),
+    }
+}
+
+fn decode(
+    op: &str,
+    arg: Option<&str>,
+    error_id: Option<&str>,
+    labels: &BTreeMap<String, usize>,
+) -> Result<Instr, String> {
+    let error_id = match (op, error_id) {
+        (_, None) => None,
+        ("assert" | "assert_vector", Some(text)) => Some(
+            text.parse::<u64>()
+                .map_err(|_| format!("`{} error_id {}`: not an error id", op, text))?,
+        ),
+        (_, Some(_)) => return Err(format!("`{}` takes no error id", op)),
+    };
+    let index = |max: usize| -> Result<usize, String> {
+        let text = arg.ok_or_else(|| format!("`{}` needs an operand", op))?;
+        match text.parse::<usize>() {
+            Ok(n) if n <= max => Ok(n),
+            _ => Err(format!(
+                "`{} {}`: operand must be at most {}",
+                op, text, max
+            )),
+        }
+    };
+    let count = |max: usize| -> Result<usize, String> {
+        match index(max)? {
+            0 => Err(format!("`{} 0`: operand must be at least 1", op)),
+            n => Ok(n),
+        }
+    };
+    let constant = || -> Result<u64, String> {
+        let text = arg.ok_or_else(|| format!("`{}` needs an operand", op))?;
+        let value: i128 = text
+            .parse()
+            .map_err(|_| format!("`{} {}`: not an integer", op, text))?;
+        Ok(value.rem_euclid(MODULUS as i128) as u64)
+    };
+    let no_arg = |instr: Instr| -> Result<Instr, String> {
+        match arg {
+            Some(a) => Err(format!("`{}` takes no operand, found `{}`", op, a)),
+            None => Ok(instr),
+        }
+    };
+
+    match op {
+        "push" => Ok(Instr::Push(constant()?)),
+        "addi" => Ok(Instr::AddI(constant()?)),
+        "pop" => Ok(Instr::Pop(count(5)?)),
+        "divine" => Ok(Instr::Divine(count(5)?)),
+        "read_io" => Ok(Instr::ReadIo(count(5)?)),
+        "write_io" => Ok(Instr::WriteIo(count(5)?)),
+        "read_mem" => Ok(Instr::ReadMem(count(5)?)),
+        "write_mem" => Ok(Instr::WriteMem(count(5)?)),
+        "pick" => Ok(Instr::Pick(index(15)?)),
+        "place" => Ok(Instr::Place(index(15)?)),
+        "dup" => Ok(Instr::Dup(index(15)?)),
+        "swap" => Ok(Instr::Swap(count(15)?)),
+        "call" => {
+            let label = arg.ok_or("`call` needs a label")?;
+            Ok(Instr::Call(
+                labels.get(label).copied().ok_or_else(|| label.to_string()),
+            ))
+        }
+        "nop" => no_arg(Instr::Nop),
+        "skiz" => no_arg(Instr::Skiz),
+        "return" => no_arg(Instr::Return),
+        "recurse" => no_arg(Instr::Recurse),
+        "recurse_or_return" => no_arg(Instr::RecurseOrReturn),
+        "assert" => no_arg(Instr::Assert(error_id)),
+        "assert_vector" => no_arg(Instr::AssertVector(error_id)),
+        "halt" => no_arg(Instr::Halt),
+        "hash" => no_arg(Instr::Hash),
+        "sponge_init" => no_arg(Instr::SpongeInit),
+        "sponge_absorb" => no_arg(Instr::SpongeAbsorb),
+        "sponge_absorb_mem" => no_arg(Instr::SpongeAbsorbMem),
+        "sponge_squeeze" => no_arg(Instr::SpongeSqueeze),
+        "add" => no_arg(Instr::Add),
+        "mul" => no_arg(Instr::Mul),
+        "invert" => no_arg(Instr::Invert),
+        "eq" => no_arg(Instr::Eq),
+        "split" => no_arg(Instr::Split),
+        "lt" => no_arg(Instr::Lt),
+        "and" => no_arg(Instr::And),
+        "or" => no_arg(Instr::Or),
+        "xor" => no_arg(Instr::Xor),
+        "log_2_floor" => no_arg(Instr::Log2Floor),
+        "pow" => no_arg(Instr::Pow),
+        "div_mod" => no_arg(Instr::DivMod),
+        "pop_count" => no_arg(Instr::PopCount),
+        "xx_add" => no_arg(Instr::XxAdd),
+        "xx_mul" => no_arg(Instr::XxMul),
+        "x_invert" => no_arg(Instr::XInvert),
+        "xb_mul" => no_arg(Instr::XbMul),
+        "merkle_step" => no_arg(Instr::MerkleStep),
+        "merkle_step_mem" => no_arg(Instr::MerkleStepMem),
+        "xx_dot_step" | "xb_dot_step" => Ok(Instr::Unsupported(op.to_string())),
+        _ => Err(format!("unknown instruction `{}`", op)),
+    }
+}
+
+/// Why a run stopped before `halt`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Abort {
+    /// `error_id` of the failed assertion; `None` for any other fault.
+    pub code: Option<u64>,
+    /// The report, naming the failing line and function.
+    pub message: String,
+}
+
+/// In-process TASM interpreter.
+#[derive(Clone, Debug)]
+pub struct Interpreter {
+    max_cycles: u64,
+    /// Assertion messages by error code, for failure reports.
+    error_messages: BTreeMap<u64, String>,
+    /// Echo trace lines to stderr as they are recorded.
+    print_trace: bool,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self {
+            max_cycles: DEFAULT_MAX_CYCLES,
+            error_messages: BTreeMap::new(),
+            print_trace: false,
+        }
+    }
+
+    /// Abort execution after `max_cycles` instructions.
+    pub fn with_max_cycles(mut self, max_cycles: u64) -> Self {
+        self.max_cycles = max_cycles;
+        self
+    }
+
+    /// Name failed assertions by message: `code` is the `error_id` the
+    /// compiler gave `message`.
+    pub fn with_error_messages(
+        mut self,
+        messages: impl IntoIterator<Item = (u64, String)>,
+    ) -> Self {
+        self.error_messages.extend(messages);
+        self
+    }
+
+    /// Print each trace line to stderr as it is recorded, so the lines
+    /// leading up to a failure are visible.
+    pub fn with_trace_printing(mut self, print: bool) -> Self {
+        self.print_trace = print;
+        self
+    }
+
+    /// Parse and run TASM text.
+    pub fn run_tasm(&self, tasm: &str, input: &ProgramInput) -> Result<ExecutionResult, String> {
+        let program = Program::parse(tasm)?;
+        self.run_program(&program, input)
+    }
+
+    /// Run a parsed program from its first instruction until `halt`.
+    ///
+    /// Errors name the failing instruction's source line and function.
+    pub fn run_program(
+        &self,
+        program: &Program,
+        input: &ProgramInput,
+    ) -> Result<ExecutionResult, String> {
+        self.execute(program, input).map_err(|abort| abort.message)
+    }
+
+    /// Like `run_program`, but a failed run keeps the `error_id` it
+    /// stopped on — the code of a `fail` or of an assertion message.
+    pub fn execute(
+        &self,
+        program: &Program,
+        input: &ProgramInput,
+    ) -> Result<ExecutionResult, Abort> {
+        let mut machine = Machine::new(program, input, &self.error_messages, self.print_trace);
+        match machine.run(self.max_cycles) {
+            Ok(()) => Ok(ExecutionResult {
+                output: machine.output,
+                cycle_count: machine.cycles,
+                trace: machine.trace,
+            }),
+            Err(message) => {
+                let pc = machine.pc;
+                let line = program.lines.get(pc).copied().unwrap_or(0);
+                let message = match program.enclosing_label(pc) {
+                    Some(label) => format!("{} (line {}, in `{}`)", message, line, label),
+                    None => format!("{} (line {})", message, line),
+                };
+                Err(Abort {
+                    code: machine.abort_code,
+                    message,
+                })
+            }
+        }
+    }
+}
+
+impl Runner for Interpreter {
+    fn run(&self, bundle: &ProgramBundle, input: &ProgramInput) -> Result<ExecutionResult, String> {
+        if bundle.target_vm != "triton" {
+            return Err(format!(
+                "the interpreter runs Triton assembly, but `{}` targets {}",
+                bundle.name, bundle.target_vm
+            ));
+        }
+        if bundle.errors.is_empty() {
+            return self.run_tasm(&bundle.assembly, input);
+        }
+        let messages = bundle.errors.iter().map(|e| (e.code, e.message.clone()));
+        self.clone()
+            .with_error_messages(messages)
+            .run_tasm(&bundle.assembly, input)
+    }
+}
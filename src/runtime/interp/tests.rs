@@ -0,0 +1,259 @@
+use super::fuzz::{fuzz_against, generate_case};
+use super::xfield::XFieldElement;
+use super::*;
+use crate::field::{Goldilocks, PrimeField};
+
+fn run(tasm: &str) -> Result<ExecutionResult, String> {
+    Interpreter::new().run_tasm(tasm, &ProgramInput::default())
+}
+
+fn output(tasm: &str) -> Vec<u64> {
+    match run(tasm) {
+        Ok(result) => result.output,
+        Err(e) => panic!("program failed: {}", e),
+    }
+}
+
+#[test]
+fn arithmetic_wraps_modulo_goldilocks() {
+    let out = output("push -1\npush 2\nadd\nwrite_io 1\npush 3\npush 4\nmul\nwrite_io 1\nhalt\n");
+    assert_eq!(out, vec![1, 12]);
+}
+
+#[test]
+fn calls_return_and_counted_loops_recurse() {
+    // Sum 5 + 4 + 3 + 2 + 1 with the lowering's loop shape.
+    let tasm = "\
+    push 0
+    push 5
+    call sum
+    pop 1
+    write_io 1
+    halt
+sum:
+    dup 0
+    push 0
+    eq
+    skiz
+    return
+    dup 0
+    swap 2
+    add
+    swap 1
+    push -1
+    add
+    recurse
+";
+    let result = run(tasm).expect("runs");
+    assert_eq!(result.output, vec![15]);
+    assert_eq!(result.cycle_count, 6 + 5 * 11 + 5);
+}
+
+#[test]
+fn skiz_skips_the_next_instruction_on_zero() {
+    let out = output("push 7\npush 0\nskiz\npop 1\nwrite_io 1\nhalt\n");
+    assert_eq!(out, vec![7]);
+}
+
+#[test]
+fn memory_round_trips_through_write_and_read() {
+    let out = output(
+        "push 30\npush 20\npush 10\npush 100\nwrite_mem 3\npop 1\n\
+         push 102\nread_mem 3\npop 1\nwrite_io 3\nhalt\n",
+    );
+    // read_mem pushes the word at p first, so p-2 ends on top.
+    assert_eq!(out, vec![10, 20, 30]);
+}
+
+#[test]
+fn io_reads_public_and_secret_input_in_order() {
+    let input = ProgramInput {
+        public: vec![4, 5],
+        secret: vec![9],
+        digests: Vec::new(),
+    };
+    let result = Interpreter::new()
+        .run_tasm("read_io 2\ndivine 1\nwrite_io 3\nhalt\n", &input)
+        .expect("runs");
+    assert_eq!(result.output, vec![9, 5, 4]);
+    let err = Interpreter::new()
+        .run_tasm("read_io 3\nhalt\n", &input)
+        .expect_err("input exhausted");
+    assert!(err.contains("public input exhausted"), "{}", err);
+}
+
+#[test]
+fn failed_assertion_names_line_and_function() {
+    let err = run("call check\nhalt\ncheck:\n    push 2\n    assert\n    return\n")
+        .expect_err("assert fails");
+    assert!(err.contains("assertion failed"), "{}", err);
+    assert!(err.contains("line 5"), "{}", err);
+    assert!(err.contains("`check`"), "{}", err);
+}
+
+#[test]
+fn failed_assertion_reports_its_error_message() {
+    let tasm = "push 1\nassert error_id 3\npush 0\nassert error_id 3\nhalt\n";
+    let err = Interpreter::new()
+        .with_error_messages([(3, "balance underflow".to_string())])
+        .run_tasm(tasm, &ProgramInput::default())
+        .expect_err("second assert fails");
+    assert!(
+        err.contains("assertion failed: balance underflow (error 3)"),
+        "{}",
+        err
+    );
+    let err = run(tasm).expect_err("no table");
+    assert!(err.contains("assertion failed (error 3)"), "{}", err);
+    let err = Program::parse("halt error_id 2\n").expect_err("halt has no id");
+    assert!(err.contains("takes no error id"), "{}", err);
+}
+
+#[test]
+fn hints_record_trace_lines_without_cycles() {
+    let tasm =
+        "push 7\nhint x = stack[0]\npush 1\npush 2\nhint pair: Pair = stack[0..2]\npop 3\nhalt\n";
+    let result = run(tasm).expect("runs");
+    assert_eq!(result.trace, vec!["x = 7", "pair = [1, 2]"]);
+    assert_eq!(result.cycle_count, 5);
+    let err = Program::parse("hint x = stack[2..1]\nhalt\n").expect_err("empty slice");
+    assert!(err.contains("malformed hint"), "{}", err);
+}
+
+#[test]
+fn execute_reports_the_abort_code() {
+    let program = Program::parse("push 0\nassert error_id 4\nhalt\n").expect("parses");
+    let abort = Interpreter::new()
+        .execute(&program, &ProgramInput::default())
+        .expect_err("fails");
+    assert_eq!(abort.code, Some(4));
+    let program = Program::parse("pop 1\nhalt\n").expect("parses");
+    let abort = Interpreter::new()
+        .execute(&program, &ProgramInput::default())
+        .expect_err("underflow");
+    assert_eq!(abort.code, None);
+}
+
+#[test]
+fn u32_instructions_follow_lowering_operand_order() {
+    // `a < b` pushes b then a; `n /% d` pushes n then d; `pow(b, e)` pushes b then e.
+    let out = output(
+        "push 9\npush 3\nlt\nwrite_io 1\n\
+         push 17\npush 5\ndiv_mod\nwrite_io 2\n\
+         push 2\npush 10\npow\nwrite_io 1\n\
+         push 4294967301\nsplit\nwrite_io 2\nhalt\n",
+    );
+    assert_eq!(out, vec![1, 2, 3, 1024, 5, 1]);
+    let err = run("push 4294967296\npush 1\nlt\nhalt\n").expect_err("not u32");
+    assert!(err.contains("not a u32"), "{}", err);
+}
+
+#[test]
+fn stack_underflow_below_the_floor_is_an_error() {
+    let err = run("pop 1\nhalt\n").expect_err("underflow");
+    assert!(err.contains("underflow"), "{}", err);
+}
+
+#[test]
+fn cycle_limit_stops_unbounded_recursion() {
+    let err = Interpreter::new()
+        .with_max_cycles(100)
+        .run_tasm(
+            "call spin\nhalt\nspin:\n    recurse\n",
+            &ProgramInput::default(),
+        )
+        .expect_err("limit");
+    assert!(err.contains("cycle limit of 100"), "{}", err);
+}
+
+#[test]
+fn hash_is_deterministic_and_input_sensitive() {
+    let hash_of = |x: u64| {
+        output(&format!(
+            "push 0\npush 0\npush 0\npush 0\npush 0\npush 0\npush 0\npush 0\npush 0\npush {}\nhash\nwrite_io 5\nhalt\n",
+            x
+        ))
+    };
+    assert_eq!(hash_of(1), hash_of(1));
+    assert_ne!(hash_of(1), hash_of(2));
+    assert_eq!(hash_of(1).len(), 5);
+}
+
+#[test]
+fn merkle_step_puts_even_indices_on_the_left() {
+    let sibling = [11, 12, 13, 14, 15];
+    let input = ProgramInput {
+        public: Vec::new(),
+        secret: Vec::new(),
+        digests: vec![sibling, sibling],
+    };
+    let step = "push 1\npush 2\npush 3\npush 4\npush 5\nmerkle_step\nwrite_io 5\nwrite_io 1\n";
+    let tasm = format!("push 6\n{}push 7\n{}halt\n", step, step);
+    let out = Interpreter::new()
+        .run_tasm(&tasm, &input)
+        .expect("runs")
+        .output;
+    assert_eq!((out[5], out[11]), (3, 3));
+    // Index 6 is a left child, 7 a right child: different parents.
+    assert_ne!(out[..5], out[6..11]);
+}
+
+#[test]
+fn extension_field_inverse_round_trips() {
+    let x = XFieldElement([Goldilocks(3), Goldilocks(5), Goldilocks(7)]);
+    let inv = x.inv().expect("nonzero");
+    assert_eq!(
+        x.mul(inv).0,
+        [Goldilocks::ONE, Goldilocks::ZERO, Goldilocks::ZERO]
+    );
+    let out =
+        output("push 3\npush 5\npush 7\ndup 2\ndup 2\ndup 2\nx_invert\nxx_mul\nwrite_io 3\nhalt\n");
+    assert_eq!(out, vec![0, 0, 1]);
+}
+
+#[test]
+fn unknown_instructions_are_parse_errors() {
+    let err = Program::parse("frobnicate\n").expect_err("unknown");
+    assert!(
+        err.contains("line 1") && err.contains("frobnicate"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn fuzz_cases_are_reproducible_and_stay_above_the_floor() {
+    assert_eq!(generate_case(7).tasm, generate_case(7).tasm);
+    assert_ne!(generate_case(7).tasm, generate_case(8).tasm);
+    for seed in 0..300 {
+        let case = generate_case(seed);
+        if let Err(e) = Interpreter::new().run_tasm(&case.tasm, &case.input) {
+            // Faults from operand values are expected; shape faults are not.
+            for bad in ["underflow", "exhausted", "past the last", "cycle limit"] {
+                assert!(!e.contains(bad), "seed {}: {}\n{}", seed, e, case.tasm);
+            }
+        }
+    }
+}
+
+#[test]
+fn fuzzing_reports_a_runner_that_miscounts_cycles() {
+    struct OneCycleLate;
+    impl Runner for OneCycleLate {
+        fn run(
+            &self,
+            bundle: &ProgramBundle,
+            input: &ProgramInput,
+        ) -> Result<ExecutionResult, String> {
+            let mut result = Interpreter::new().run(bundle, input)?;
+            result.cycle_count += 1;
+            Ok(result)
+        }
+    }
+    assert!(fuzz_against(&Interpreter::new(), 0, 50).is_empty());
+    let divergences = fuzz_against(&OneCycleLate, 0, 50);
+    assert!(!divergences.is_empty());
+    assert!(divergences
+        .iter()
+        .all(|d| d.reason.starts_with("cycle counts differ")));
+}
@@ -0,0 +1,75 @@
+//! Cubic extension of Goldilocks: F_p[X] / (X^3 - X + 1).
+
+use crate::field::{Goldilocks, PrimeField};
+
+/// Coefficients `[c0, c1, c2]` of `c0 + c1·X + c2·X^2`, in stack push order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct XFieldElement(pub(super) [Goldilocks; 3]);
+
+impl XFieldElement {
+    const ZERO: Self = Self([Goldilocks::ZERO; 3]);
+    const ONE: Self = Self([Goldilocks::ONE, Goldilocks::ZERO, Goldilocks::ZERO]);
+
+    pub(super) fn add(self, rhs: Self) -> Self {
+        let [a0, a1, a2] = self.0;
+        let [b0, b1, b2] = rhs.0;
+        Self([a0.add(b0), a1.add(b1), a2.add(b2)])
+    }
+
+    pub(super) fn scale(self, s: Goldilocks) -> Self {
+        let [a0, a1, a2] = self.0;
+        Self([a0.mul(s), a1.mul(s), a2.mul(s)])
+    }
+
+    pub(super) fn mul(self, rhs: Self) -> Self {
+        let [a0, a1, a2] = self.0;
+        let [b0, b1, b2] = rhs.0;
+        let c0 = a0.mul(b0);
+        let c1 = a0.mul(b1).add(a1.mul(b0));
+        let c2 = a0.mul(b2).add(a1.mul(b1)).add(a2.mul(b0));
+        let c3 = a1.mul(b2).add(a2.mul(b1));
+        let c4 = a2.mul(b2);
+        // X^3 = X - 1 and X^4 = X^2 - X.
+        Self([c0.sub(c3), c1.add(c3).sub(c4), c2.add(c4)])
+    }
+
+    /// Multiplicative inverse, solving `self · y = 1` as a 3×3 linear
+    /// system over the base field. `None` for zero.
+    pub(super) fn inv(self) -> Option<Self> {
+        if self == Self::ZERO {
+            return None;
+        }
+        // Column j holds the coefficients of self · X^j.
+        let basis = [
+            Self::ONE,
+            Self([Goldilocks::ZERO, Goldilocks::ONE, Goldilocks::ZERO]),
+            Self([Goldilocks::ZERO, Goldilocks::ZERO, Goldilocks::ONE]),
+        ];
+        let cols = basis.map(|e| self.mul(e).0);
+        let mut m = [[Goldilocks::ZERO; 4]; 3];
+        for (r, row) in m.iter_mut().enumerate() {
+            for (c, col) in cols.iter().enumerate() {
+                row[c] = col[r];
+            }
+            row[3] = Self::ONE.0[r];
+        }
+        for c in 0..3 {
+            let pivot = (c..3).find(|&r| m[r][c] != Goldilocks::ZERO)?;
+            m.swap(c, pivot);
+            let inv = m[c][c].inv()?;
+            for v in m[c].iter_mut() {
+                *v = v.mul(inv);
+            }
+            for r in 0..3 {
+                if r != c && m[r][c] != Goldilocks::ZERO {
+                    let f = m[r][c];
+                    let pivot_row = m[c];
+                    for (v, p) in m[r].iter_mut().zip(pivot_row) {
+                        *v = v.sub(f.mul(p));
+                    }
+                }
+            }
+        }
+        Some(Self([m[0][3], m[1][3], m[2][3]]))
+    }
+}
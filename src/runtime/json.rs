@@ -0,0 +1,241 @@
+//! Minimal hand-rolled JSON helpers shared by the runtime artifact formats.
+//!
+//! The formats are written by this crate with a fixed layout, so the
+//! readers only need to find keys and match brackets, not parse
+//! arbitrary JSON.
+
+/// JSON-escape a string and wrap in quotes.
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                out.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Extract a string value for a key from JSON.
+pub(crate) fn extract_string(json: &str, key: &str) -> Result<String, String> {
+    let pattern = format!("\"{}\"", key);
+    let start = json
+        .find(&pattern)
+        .ok_or_else(|| format!("missing key '{}'", key))?;
+    let rest = &json[start + pattern.len()..];
+    // Skip `: "`
+    let quote_start = rest
+        .find('"')
+        .ok_or_else(|| format!("missing value for '{}'", key))?;
+    let value_start = quote_start + 1;
+    let value_rest = &rest[value_start..];
+    // Find closing quote (handling escapes)
+    let mut end = 0;
+    let mut escaped = false;
+    let mut value = String::new();
+    for ch in value_rest.chars() {
+        if escaped {
+            match ch {
+                '"' => value.push('"'),
+                '\\' => value.push('\\'),
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                _ => {
+                    value.push('\\');
+                    value.push(ch);
+                }
+            }
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == '"' {
+            break;
+        } else {
+            value.push(ch);
+        }
+        end += ch.len_utf8();
+    }
+    let _ = end; // suppress unused warning
+    Ok(value)
+}
+
+/// Extract an optional string value (returns None if key is "null").
+pub(crate) fn extract_string_opt(json: &str, key: &str) -> Option<String> {
+    let pattern = format!("\"{}\"", key);
+    let start = json.find(&pattern)?;
+    let rest = &json[start + pattern.len()..];
+    let trimmed = rest.trim_start().trim_start_matches(':').trim_start();
+    if trimmed.starts_with("null") {
+        return None;
+    }
+    extract_string(json, key).ok()
+}
+
+/// Extract a boolean value for a key; `false` if absent or not `true`.
+pub(crate) fn extract_bool(json: &str, key: &str) -> bool {
+    let pattern = format!("\"{}\"", key);
+    json.find(&pattern).is_some_and(|start| {
+        json[start + pattern.len()..]
+            .trim_start()
+            .trim_start_matches(':')
+            .trim_start()
+            .starts_with("true")
+    })
+}
+
+/// Extract a `{ ... }` object value for a key; `None` if absent or null.
+pub(crate) fn extract_object<'j>(json: &'j str, key: &str) -> Option<&'j str> {
+    extract_enclosed(json, key, '{', '}')
+}
+
+/// Extract a `[ ... ]` array value for a key; `None` if absent or null.
+pub(crate) fn extract_array<'j>(json: &'j str, key: &str) -> Option<&'j str> {
+    extract_enclosed(json, key, '[', ']')
+}
+
+/// The value following `"key":` from `open` through its matching `close`,
+/// delimiters included. Brackets inside string values are ignored.
+fn extract_enclosed<'j>(json: &'j str, key: &str, open: char, close: char) -> Option<&'j str> {
+    let pattern = format!("\"{}\"", key);
+    let start = json.find(&pattern)? + pattern.len();
+    let rest = json[start..].trim_start().strip_prefix(':')?.trim_start();
+    if !rest.starts_with(open) {
+        return None;
+    }
+    let offset = json.len() - rest.len();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in rest.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            c if c == open && !in_string => depth += 1,
+            c if c == close && !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&json[offset..offset + i + 1]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Top-level `{ ... }` objects inside an array value.
+pub(super) fn split_objects(array: &str) -> Vec<&str> {
+    let mut objects = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in array.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => {
+                if depth == 0 {
+                    start = i;
+                }
+                depth += 1;
+            }
+            '}' if !in_string && depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    objects.push(&array[start..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    objects
+}
+
+/// Parse a `[1, 2, 3]` array of unsigned integers.
+pub(crate) fn parse_u64_array(array: &str) -> Result<Vec<u64>, String> {
+    let inner = array
+        .trim()
+        .strip_prefix('[')
+        .and_then(|a| a.strip_suffix(']'))
+        .ok_or_else(|| "expected an array".to_string())?;
+    inner
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            item.parse()
+                .map_err(|_| format!("invalid integer '{}'", item))
+        })
+        .collect()
+}
+
+/// `"key": <u64>` entries of a flat object, in order. Non-numeric
+/// entries are skipped.
+pub(crate) fn u64_entries(object: &str) -> Vec<(String, u64)> {
+    let inner = object.trim().trim_start_matches('{').trim_end_matches('}');
+    inner
+        .split(',')
+        .filter_map(|entry| {
+            let (key, value) = entry.split_once(':')?;
+            let value = value.trim().parse().ok()?;
+            Some((key.trim().trim_matches('"').to_string(), value))
+        })
+        .collect()
+}
+
+/// Every quoted string in `text`, unescaped, in order.
+pub(crate) fn quoted_items(text: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '"' {
+            continue;
+        }
+        let mut item = String::new();
+        let mut escaped = false;
+        for ch in chars.by_ref() {
+            match (escaped, ch) {
+                (true, c) => {
+                    item.push(c);
+                    escaped = false;
+                }
+                (false, '\\') => escaped = true,
+                (false, '"') => break,
+                (false, c) => item.push(c),
+            }
+        }
+        items.push(item);
+    }
+    items
+}
+
+/// Extract a u64 value for a key from JSON.
+pub(crate) fn extract_u64(json: &str, key: &str) -> Result<u64, String> {
+    let pattern = format!("\"{}\"", key);
+    let start = json
+        .find(&pattern)
+        .ok_or_else(|| format!("missing key '{}'", key))?;
+    let rest = &json[start + pattern.len()..];
+    let colon = rest
+        .find(':')
+        .ok_or_else(|| format!("missing colon for '{}'", key))?;
+    let after_colon = rest[colon + 1..].trim_start();
+    let num_end = after_colon
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(after_colon.len());
+    after_colon[..num_end]
+        .parse()
+        .map_err(|e| format!("invalid u64 for '{}': {}", key, e))
+}
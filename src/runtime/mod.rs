@@ -15,9 +15,19 @@
 //! the serializable `ProgramBundle` artifact format.
 
 pub mod artifact;
+pub mod buildinfo;
+pub mod envelope;
+pub mod interp;
+pub(crate) mod json;
+mod proof;
 
+use crate::field::codec::BFieldCodec;
 use crate::field::proof::Claim;
+use crate::field::{Goldilocks, PrimeField};
 pub use artifact::ProgramBundle;
+pub use buildinfo::BuildInfo;
+pub use envelope::{EnvelopeSignature, ProofEnvelope};
+pub use interp::Interpreter;
 
 // ─── Types ─────────────────────────────────────────────────────────
 
@@ -28,6 +38,9 @@ pub struct ExecutionResult {
     pub output: Vec<u64>,
     /// Number of VM cycles consumed.
     pub cycle_count: u64,
+    /// Debug `trace` lines, in execution order. Only the interpreter
+    /// records them.
+    pub trace: Vec<String>,
 }
 
 /// Proof artifact: claim + opaque proof bytes.
@@ -56,6 +69,32 @@ pub struct ProgramInput {
     pub digests: Vec<[u64; 5]>,
 }
 
+impl ProgramInput {
+    /// Append `value` to the public input, encoded as `field::codec` lays it out.
+    pub fn push_public<T: BFieldCodec>(&mut self, value: &T) {
+        self.public
+            .extend(value.encode().iter().map(|e| e.to_u64()));
+    }
+
+    /// Append `value` to the secret input, encoded as `field::codec` lays it out.
+    pub fn push_secret<T: BFieldCodec>(&mut self, value: &T) {
+        self.secret
+            .extend(value.encode().iter().map(|e| e.to_u64()));
+    }
+}
+
+impl ExecutionResult {
+    /// Decode the whole public output as one value.
+    pub fn decode_output<T: BFieldCodec>(&self) -> Result<T, String> {
+        let elements: Vec<Goldilocks> = self
+            .output
+            .iter()
+            .map(|&v| Goldilocks::from_u64(v))
+            .collect();
+        T::decode(&elements)
+    }
+}
+
 // ─── Warrior Traits ────────────────────────────────────────────────
 
 /// Run a compiled program on a VM.
@@ -124,3 +163,14 @@ pub trait Deployer {
     /// Returns a deployment identifier (tx hash, contract address, etc.).
     fn deploy(&self, bundle: &ProgramBundle, proof: Option<&ProofData>) -> Result<String, String>;
 }
+
+/// Read the on-chain state a deployed program depends on.
+///
+/// Warriors implement this for chain-specific queries (e.g., the
+/// current mutator-set root from a Neptune node), so that
+/// `trident state pull` can write it into the next prove cycle's inputs.
+pub trait StateProvider {
+    /// Current value of the commitment `name` of the program with
+    /// `program_digest` (hex), as field elements (a digest is 5).
+    fn commitment(&self, program_digest: &str, name: &str) -> Result<Vec<u64>, String>;
+}
@@ -0,0 +1,103 @@
+//! JSON form of `ProofData`, for warriors that write proofs as text.
+//!
+//! The claim is spelled out field by field; the warrior-specific proof
+//! bytes are hex-encoded.
+
+use super::json::{extract_array, extract_object, extract_string, json_string, parse_u64_array};
+use super::ProofData;
+use crate::field::proof::Claim;
+
+impl ProofData {
+    /// Serialize to JSON.
+    pub fn to_json(&self) -> String {
+        let list = |values: &[u64]| {
+            let items: Vec<String> = values.iter().map(u64::to_string).collect();
+            format!("[{}]", items.join(", "))
+        };
+        let hex: String = self
+            .proof_bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let mut out = String::from("{\n");
+        out.push_str(&format!("  \"format\": {},\n", json_string(&self.format)));
+        out.push_str("  \"claim\": {\n");
+        out.push_str(&format!(
+            "    \"program_hash\": {},\n",
+            list(&self.claim.program_hash)
+        ));
+        out.push_str(&format!(
+            "    \"public_input\": {},\n",
+            list(&self.claim.public_input)
+        ));
+        out.push_str(&format!(
+            "    \"public_output\": {}\n",
+            list(&self.claim.public_output)
+        ));
+        out.push_str("  },\n");
+        out.push_str(&format!("  \"proof\": \"{}\"\n", hex));
+        out.push_str("}\n");
+        out
+    }
+
+    /// Deserialize from the JSON produced by `to_json`.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let claim = extract_object(json, "claim").ok_or("missing key 'claim'")?;
+        let array = |key: &str| {
+            extract_array(claim, key)
+                .ok_or_else(|| format!("missing key '{}'", key))
+                .and_then(parse_u64_array)
+        };
+        let hex = extract_string(json, "proof")?;
+        if hex.len() % 2 != 0 {
+            return Err("proof hex has odd length".to_string());
+        }
+        let proof_bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| {
+                hex.get(i..i + 2)
+                    .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                    .ok_or_else(|| "proof is not valid hex".to_string())
+            })
+            .collect::<Result<Vec<u8>, String>>()?;
+        Ok(ProofData {
+            claim: Claim {
+                program_hash: array("program_hash")?,
+                public_input: array("public_input")?,
+                public_output: array("public_output")?,
+            },
+            proof_bytes,
+            format: extract_string(json, "format")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proof_json_roundtrip() {
+        let proof = ProofData {
+            claim: Claim {
+                program_hash: vec![1, 2, 3, 4, 5],
+                public_input: vec![42],
+                public_output: Vec::new(),
+            },
+            proof_bytes: vec![0x00, 0xab, 0xff],
+            format: "stark-triton-v2".to_string(),
+        };
+        let json = proof.to_json();
+        assert!(json.contains("\"proof\": \"00abff\""));
+        let parsed = ProofData::from_json(&json).expect("parse failed");
+        assert_eq!(parsed.claim, proof.claim);
+        assert_eq!(parsed.proof_bytes, proof.proof_bytes);
+        assert_eq!(parsed.format, proof.format);
+    }
+
+    #[test]
+    fn proof_json_rejects_bad_hex() {
+        let json = "{ \"format\": \"x\", \"claim\": { \"program_hash\": [], \"public_input\": [], \"public_output\": [] }, \"proof\": \"0g\" }";
+        assert!(ProofData::from_json(json).is_err());
+    }
+}
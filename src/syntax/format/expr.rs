@@ -1,13 +1,19 @@
 pub(crate) use crate::ast::display::format_ast_type as format_type;
 use crate::ast::*;
 
+/// Format a literal as it is written in source.
+pub(crate) fn format_literal(lit: &Literal) -> String {
+    match lit {
+        Literal::Integer(n) => n.to_string(),
+        Literal::Bool(b) => b.to_string(),
+        Literal::Str(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+    }
+}
+
 /// Format an expression to a single-line string.
 pub(crate) fn format_expr(expr: &Expr) -> String {
     match expr {
-        Expr::Literal(lit) => match lit {
-            Literal::Integer(n) => n.to_string(),
-            Literal::Bool(b) => b.to_string(),
-        },
+        Expr::Literal(lit) => format_literal(lit),
         Expr::Var(name) => name.clone(),
         Expr::BinOp { op, lhs, rhs } => {
             let l = format_expr_precedence(&lhs.node, op, true);
@@ -20,6 +26,11 @@ pub(crate) fn format_expr(expr: &Expr) -> String {
             generic_args,
         } => {
             let args_str: Vec<String> = args.iter().map(|a| format_expr(&a.node)).collect();
+            if let [ty, name] = path.node.0.as_slice() {
+                if ty == "Digest" {
+                    return format!("Digest::{}({})", name, args_str.join(", "));
+                }
+            }
             if generic_args.is_empty() {
                 format!("{}({})", path.node.as_dotted(), args_str.join(", "))
             } else {
@@ -38,11 +49,14 @@ pub(crate) fn format_expr(expr: &Expr) -> String {
         Expr::Index { expr, index } => {
             format!("{}[{}]", format_expr(&expr.node), format_expr(&index.node))
         }
-        Expr::StructInit { path, fields } => {
-            let fields_str: Vec<String> = fields
+        Expr::StructInit { path, fields, base } => {
+            let mut fields_str: Vec<String> = fields
                 .iter()
                 .map(|(name, expr)| format!("{}: {}", name.node, format_expr(&expr.node)))
                 .collect();
+            if let Some(base) = base {
+                fields_str.push(format!("..{}", format_expr(&base.node)));
+            }
             format!("{} {{ {} }}", path.node.as_dotted(), fields_str.join(", "))
         }
         Expr::ArrayInit(elems) => {
@@ -4,15 +4,18 @@ use crate::span::Spanned;
 use super::expr::format_type;
 use super::{FormatCtx, INDENT, MAX_WIDTH};
 
-impl FormatCtx {
+impl FormatCtx<'_> {
     pub(super) fn emit_item(&mut self, item: &Spanned<Item>, indent: &str) {
         self.emit_leading_comments(item.span.start, indent);
         match &item.node {
             Item::Const(c) => self.emit_const(c, indent),
-            Item::Struct(s) => self.emit_struct(s, indent),
-            Item::Event(e) => self.emit_event(e, indent),
+            Item::Struct(s) => self.emit_struct(s, indent, item.span.end),
+            Item::Enum(e) => self.emit_enum(e, indent, item.span.end),
+            Item::Event(e) => self.emit_event(e, indent, item.span.end),
             Item::Fn(f) => self.emit_fn(f, indent),
         }
+        self.emit_trailing_comment(item.span.end);
+        self.output.push('\n');
     }
 
     fn emit_cfg_attr(&mut self, cfg: &Option<Spanned<String>>, indent: &str) {
@@ -37,18 +40,40 @@ impl FormatCtx {
         self.output.push_str(" = ");
         self.output
             .push_str(&super::expr::format_expr(&c.value.node));
-        self.output.push('\n');
     }
 
-    fn emit_struct(&mut self, s: &StructDef, indent: &str) {
+    /// `end` is where the item ends, just past its closing brace.
+    fn emit_struct(&mut self, s: &StructDef, indent: &str, end: u32) {
         self.emit_cfg_attr(&s.cfg, indent);
+        if s.is_authenticated {
+            self.output.push_str(indent);
+            self.output.push_str("#[authenticated]\n");
+        }
         self.output.push_str(indent);
         if s.is_pub {
             self.output.push_str("pub ");
         }
         self.output.push_str("struct ");
         self.output.push_str(&s.name.node);
-        self.output.push_str(" {\n");
+        match s.kind {
+            StructKind::Named => {}
+            StructKind::Tuple => {
+                let fields: Vec<String> = s
+                    .fields
+                    .iter()
+                    .map(|f| {
+                        let vis = if f.is_pub { "pub " } else { "" };
+                        format!("{}{}", vis, format_type(&f.ty.node))
+                    })
+                    .collect();
+                self.output.push_str(&format!("({})", fields.join(", ")));
+                return;
+            }
+            StructKind::Unit => return,
+        }
+        self.output.push_str(" {");
+        self.emit_trailing_comment(s.name.span.end);
+        self.output.push('\n');
         let inner = format!("{}{}", indent, INDENT);
         for field in &s.fields {
             self.emit_leading_comments(field.name.span.start, &inner);
@@ -59,18 +84,78 @@ impl FormatCtx {
             self.output.push_str(&field.name.node);
             self.output.push_str(": ");
             self.output.push_str(&format_type(&field.ty.node));
-            self.output.push_str(",\n");
+            if let Some(default) = &field.default {
+                self.output.push_str(" = ");
+                self.output
+                    .push_str(&super::expr::format_expr(&default.node));
+            }
+            self.output.push(',');
+            self.emit_trailing_comment(field.ty.span.end);
+            self.output.push('\n');
         }
+        self.emit_leading_comments(end.saturating_sub(1), &inner);
         self.output.push_str(indent);
-        self.output.push_str("}\n");
+        self.output.push('}');
     }
 
-    fn emit_event(&mut self, e: &EventDef, indent: &str) {
+    fn emit_enum(&mut self, e: &EnumDef, indent: &str, end: u32) {
         self.emit_cfg_attr(&e.cfg, indent);
         self.output.push_str(indent);
+        if e.is_pub {
+            self.output.push_str("pub ");
+        }
+        self.output.push_str("enum ");
+        self.output.push_str(&e.name.node);
+        self.output.push_str(" {");
+        self.emit_trailing_comment(e.name.span.end);
+        self.output.push('\n');
+        let inner = format!("{}{}", indent, INDENT);
+        for variant in &e.variants {
+            self.emit_leading_comments(variant.name.span.start, &inner);
+            self.output.push_str(&inner);
+            self.output.push_str(&variant.name.node);
+            if !variant.fields.is_empty() {
+                let fields: Vec<String> = variant
+                    .fields
+                    .iter()
+                    .map(|t| format_type(&t.node))
+                    .collect();
+                self.output.push_str(&format!("({})", fields.join(", ")));
+            }
+            self.output.push(',');
+            let last = variant.fields.last().map_or(variant.name.span, |t| t.span);
+            self.emit_trailing_comment(last.end);
+            self.output.push('\n');
+        }
+        self.emit_leading_comments(end.saturating_sub(1), &inner);
+        self.output.push_str(indent);
+        self.output.push('}');
+    }
+
+    fn emit_event(&mut self, e: &EventDef, indent: &str, end: u32) {
+        self.emit_cfg_attr(&e.cfg, indent);
+        if e.version != 1 {
+            self.output.push_str(indent);
+            self.output
+                .push_str(&format!("#[version({})]\n", e.version));
+        }
+        if let Some(note) = &e.deprecated {
+            self.output.push_str(indent);
+            if note.node.is_empty() {
+                self.output.push_str("#[deprecated]\n");
+            } else {
+                self.output.push_str(&format!(
+                    "#[deprecated(\"{}\")]\n",
+                    note.node.replace('\\', "\\\\").replace('"', "\\\"")
+                ));
+            }
+        }
+        self.output.push_str(indent);
         self.output.push_str("event ");
         self.output.push_str(&e.name.node);
-        self.output.push_str(" {\n");
+        self.output.push_str(" {");
+        self.emit_trailing_comment(e.name.span.end);
+        self.output.push('\n');
         let inner = format!("{}{}", indent, INDENT);
         for field in &e.fields {
             self.emit_leading_comments(field.name.span.start, &inner);
@@ -78,10 +163,13 @@ impl FormatCtx {
             self.output.push_str(&field.name.node);
             self.output.push_str(": ");
             self.output.push_str(&format_type(&field.ty.node));
-            self.output.push_str(",\n");
+            self.output.push(',');
+            self.emit_trailing_comment(field.ty.span.end);
+            self.output.push('\n');
         }
+        self.emit_leading_comments(end.saturating_sub(1), &inner);
         self.output.push_str(indent);
-        self.output.push_str("}\n");
+        self.output.push('}');
     }
 
     fn emit_fn(&mut self, f: &FnDef, indent: &str) {
@@ -97,6 +185,23 @@ impl FormatCtx {
             self.output.push_str("#[pure]\n");
         }
 
+        if f.is_authenticated {
+            self.output.push_str(indent);
+            self.output.push_str("#[authenticated]\n");
+        }
+
+        if f.auto_range_check {
+            self.output.push_str(indent);
+            self.output.push_str("#[auto_range_check]\n");
+        }
+
+        if let Some(attr) = &f.entrypoint {
+            self.output.push_str(indent);
+            self.output.push_str("#[");
+            self.output.push_str(&attr.node);
+            self.output.push_str("]\n");
+        }
+
         for req in &f.requires {
             self.output.push_str(indent);
             self.output.push_str("#[requires(");
@@ -109,6 +214,12 @@ impl FormatCtx {
             self.output.push_str(&ens.node);
             self.output.push_str(")]\n");
         }
+        for pred in &f.assumes {
+            self.output.push_str(indent);
+            self.output.push_str("#[assume(");
+            self.output.push_str(&super::expr::format_expr(&pred.node));
+            self.output.push_str(")]\n");
+        }
 
         self.output.push_str(indent);
 
@@ -172,16 +283,11 @@ impl FormatCtx {
             }
         }
 
-        match &f.body {
-            Some(body) => {
-                self.output.push_str(" {\n");
-                self.emit_block(&body.node, indent);
-                self.output.push_str(indent);
-                self.output.push_str("}\n");
-            }
-            None => {
-                self.output.push('\n');
-            }
+        if let Some(body) = &f.body {
+            self.output.push_str(" {");
+            self.emit_block(body, indent);
+            self.output.push_str(indent);
+            self.output.push('}');
         }
     }
 
@@ -6,16 +6,17 @@ mod stmts;
 mod tests;
 
 use crate::ast::*;
-use crate::lexer::Comment;
+use crate::span::Spanned;
+use crate::syntax::trivia::TriviaMap;
 
-pub(crate) use expr::{format_expr, format_type};
+pub(crate) use expr::{format_expr, format_literal, format_type};
 
 const MAX_WIDTH: usize = 80;
 const INDENT: &str = "    ";
 
 /// Format a parsed Trident file back to source, preserving comments.
-pub(crate) fn format_file(file: &File, comments: &[Comment]) -> String {
-    let mut ctx = FormatCtx::new(comments);
+pub(crate) fn format_file(file: &File, trivia: &TriviaMap) -> String {
+    let mut ctx = FormatCtx::new(trivia);
     ctx.emit_file(file);
     let mut out = ctx.output;
     // Ensure single trailing newline
@@ -28,81 +29,103 @@ pub(crate) fn format_file(file: &File, comments: &[Comment]) -> String {
     out
 }
 
-pub(super) struct FormatCtx {
+pub(super) struct FormatCtx<'a> {
     pub(super) output: String,
     pub(super) comments: Vec<CommentEntry>,
+    trivia: &'a TriviaMap,
 }
 
 #[derive(Clone)]
 pub(super) struct CommentEntry {
     pub(super) text: String,
     pub(super) byte_offset: u32,
+    /// Source line of the comment, for matching trailing comments.
+    pub(super) line: usize,
     pub(super) trailing: bool,
+    pub(super) blank_before: bool,
+    pub(super) blank_after: bool,
     pub(super) used: bool,
 }
 
-impl FormatCtx {
-    fn new(comments: &[Comment]) -> Self {
-        let entries = comments
-            .iter()
-            .map(|c| CommentEntry {
-                text: c.text.clone(),
-                byte_offset: c.span.start,
-                trailing: c.trailing,
-                used: false,
-            })
-            .collect();
+impl<'a> FormatCtx<'a> {
+    fn new(trivia: &'a TriviaMap) -> Self {
+        let leading = trivia.all_leading().map(|(_, t)| CommentEntry {
+            text: t.comment.text.clone(),
+            byte_offset: t.comment.span.start,
+            line: trivia.line_of(t.comment.span.start),
+            trailing: false,
+            blank_before: t.blank_before,
+            blank_after: t.blank_after,
+            used: false,
+        });
+        let trailing = trivia.all_trailing().map(|(_, c)| CommentEntry {
+            text: c.text.clone(),
+            byte_offset: c.span.start,
+            line: trivia.line_of(c.span.start),
+            trailing: true,
+            blank_before: false,
+            blank_after: false,
+            used: false,
+        });
+        let mut comments: Vec<CommentEntry> = leading.chain(trailing).collect();
+        comments.sort_by_key(|c| c.byte_offset);
         Self {
             output: String::new(),
-            comments: entries,
+            comments,
+            trivia,
         }
     }
 
-    /// Emit leading comments that appear before `span_start`.
+    /// Emit comments that appear before `span_start`, each on its own line,
+    /// keeping the blank lines that separated them from their neighbours.
+    /// A trailing comment whose line was never emitted lands here too.
     pub(super) fn emit_leading_comments(&mut self, span_start: u32, indent: &str) {
         for i in 0..self.comments.len() {
-            if self.comments[i].used || self.comments[i].trailing {
+            if self.comments[i].used || self.comments[i].byte_offset >= span_start {
                 continue;
             }
-            if self.comments[i].byte_offset < span_start {
-                let text = self.comments[i].text.clone();
-                self.comments[i].used = true;
-                self.output.push_str(indent);
-                self.output.push_str(&text);
-                self.output.push('\n');
+            self.comments[i].used = true;
+            let entry = self.comments[i].clone();
+            if entry.blank_before {
+                self.blank_line();
+            }
+            self.output.push_str(indent);
+            self.output.push_str(&entry.text);
+            self.output.push('\n');
+            if entry.blank_after {
+                self.blank_line();
             }
         }
     }
 
-    /// Emit trailing comment on the same line as the node ending at `span_end`.
+    /// Emit the trailing comment on the source line where `span_end` falls.
     pub(super) fn emit_trailing_comment(&mut self, span_end: u32) {
-        for i in 0..self.comments.len() {
-            if self.comments[i].used || !self.comments[i].trailing {
-                continue;
-            }
-            if self.comments[i].byte_offset >= span_end {
-                let text = self.comments[i].text.clone();
-                self.comments[i].used = true;
-                self.output.push(' ');
-                self.output.push_str(&text);
-                break;
-            }
+        let line = self.trivia.line_of(span_end);
+        if let Some(entry) = self
+            .comments
+            .iter_mut()
+            .find(|c| !c.used && c.trailing && c.line == line)
+        {
+            entry.used = true;
+            self.output.push(' ');
+            self.output.push_str(&entry.text);
         }
     }
 
-    /// Emit any remaining unused comments (e.g., at end of file).
-    fn emit_remaining_comments(&mut self, indent: &str) {
-        for i in 0..self.comments.len() {
-            if self.comments[i].used {
-                continue;
-            }
-            self.comments[i].used = true;
-            self.output.push_str(indent);
-            self.output.push_str(&self.comments[i].text.clone());
+    /// End the current line with an empty one, unless the output already
+    /// has one or is at the start of a file or block.
+    fn blank_line(&mut self) {
+        let out = &self.output;
+        if out.ends_with('\n') && !out.ends_with("\n\n") && !out.ends_with("{\n") {
             self.output.push('\n');
         }
     }
 
+    /// Emit any remaining unused comments (e.g., at end of file).
+    fn emit_remaining_comments(&mut self, indent: &str) {
+        self.emit_leading_comments(u32::MAX, indent);
+    }
+
     fn emit_file(&mut self, file: &File) {
         let keyword = match file.kind {
             FileKind::Program => "program",
@@ -112,6 +135,7 @@ impl FormatCtx {
         self.output.push_str(keyword);
         self.output.push(' ');
         self.output.push_str(&file.name.node);
+        self.emit_trailing_comment(file.name.span.end);
         self.output.push('\n');
 
         for u in &file.uses {
@@ -119,6 +143,7 @@ impl FormatCtx {
             self.emit_leading_comments(u.span.start, "");
             self.output.push_str("use ");
             self.output.push_str(&u.node.as_dotted());
+            self.emit_trailing_comment(u.span.end);
             self.output.push('\n');
         }
 
@@ -129,18 +154,21 @@ impl FormatCtx {
                     self.emit_leading_comments(ty.span.start, "");
                     self.output.push_str("pub input: ");
                     self.output.push_str(&format_type(&ty.node));
+                    self.emit_trailing_comment(ty.span.end);
                     self.output.push('\n');
                 }
                 Declaration::PubOutput(ty) => {
                     self.emit_leading_comments(ty.span.start, "");
                     self.output.push_str("pub output: ");
                     self.output.push_str(&format_type(&ty.node));
+                    self.emit_trailing_comment(ty.span.end);
                     self.output.push('\n');
                 }
                 Declaration::SecInput(ty) => {
                     self.emit_leading_comments(ty.span.start, "");
                     self.output.push_str("sec input: ");
                     self.output.push_str(&format_type(&ty.node));
+                    self.emit_trailing_comment(ty.span.end);
                     self.output.push('\n');
                 }
                 Declaration::SecRam(entries) => {
@@ -165,17 +193,27 @@ impl FormatCtx {
         self.emit_remaining_comments("");
     }
 
-    pub(super) fn emit_block(&mut self, block: &Block, outer_indent: &str) {
+    /// Emit a block whose opening ` {` the caller has written, up to but
+    /// not including the closing `}`: a comment after the `{`, the body,
+    /// and the comments left before the `}`.
+    pub(super) fn emit_block(&mut self, block: &Spanned<Block>, outer_indent: &str) {
+        let close = block.span.end.saturating_sub(1);
+        if self.trivia.line_of(block.span.start) != self.trivia.line_of(close) {
+            self.emit_trailing_comment(block.span.start + 1);
+        }
+        self.output.push('\n');
         let indent = format!("{}{}", outer_indent, INDENT);
-        for stmt in &block.stmts {
+        for stmt in &block.node.stmts {
             self.emit_stmt(stmt, &indent);
         }
-        if let Some(tail) = &block.tail_expr {
+        if let Some(tail) = &block.node.tail_expr {
             self.emit_leading_comments(tail.span.start, &indent);
             self.output.push_str(&indent);
             self.emit_expr_wrapped(&tail.node, &indent);
+            self.emit_trailing_comment(tail.span.end);
             self.output.push('\n');
         }
+        self.emit_leading_comments(close, &indent);
     }
 
     /// Format an expression, wrapping long function calls.
@@ -1,10 +1,10 @@
 use crate::ast::*;
 use crate::span::Spanned;
 
-use super::expr::{format_expr, format_place, format_type};
+use super::expr::{format_expr, format_literal, format_place, format_type};
 use super::{FormatCtx, INDENT, MAX_WIDTH};
 
-impl FormatCtx {
+impl FormatCtx<'_> {
     pub(super) fn emit_stmt(&mut self, stmt: &Spanned<Stmt>, indent: &str) {
         self.emit_leading_comments(stmt.span.start, indent);
         match &stmt.node {
@@ -63,32 +63,9 @@ impl FormatCtx {
                 self.emit_trailing_comment(stmt.span.end);
                 self.output.push('\n');
             }
-            Stmt::If {
-                cond,
-                then_block,
-                else_block,
-            } => {
+            Stmt::If { .. } => {
                 self.output.push_str(indent);
-                self.output.push_str("if ");
-                self.output.push_str(&format_expr(&cond.node));
-                self.output.push_str(" {\n");
-                self.emit_block(&then_block.node, indent);
-                if let Some(else_b) = else_block {
-                    if let Some(inner_if) = as_else_if(&else_b.node) {
-                        self.output.push_str(indent);
-                        self.output.push_str("} else ");
-                        self.emit_if_inline(inner_if, indent);
-                    } else {
-                        self.output.push_str(indent);
-                        self.output.push_str("} else {\n");
-                        self.emit_block(&else_b.node, indent);
-                        self.output.push_str(indent);
-                        self.output.push_str("}\n");
-                    }
-                } else {
-                    self.output.push_str(indent);
-                    self.output.push_str("}\n");
-                }
+                self.emit_if_inline(&stmt.node, indent, stmt.span.end);
             }
             Stmt::For {
                 var,
@@ -106,12 +83,21 @@ impl FormatCtx {
                 self.output.push_str(&format_expr(&end.node));
                 if let Some(b) = bound {
                     self.output.push_str(" bounded ");
-                    self.output.push_str(&b.to_string());
+                    self.output.push_str(&format_expr(&b.node));
                 }
-                self.output.push_str(" {\n");
-                self.emit_block(&body.node, indent);
+                self.output.push_str(" {");
+                self.emit_block(body, indent);
+                self.close_block(indent, stmt.span.end);
+            }
+            Stmt::While { cond, bound, body } => {
                 self.output.push_str(indent);
-                self.output.push_str("}\n");
+                self.output.push_str("while ");
+                self.output.push_str(&format_expr(&cond.node));
+                self.output.push_str(" bounded ");
+                self.output.push_str(&format_expr(&bound.node));
+                self.output.push_str(" {");
+                self.emit_block(body, indent);
+                self.close_block(indent, stmt.span.end);
             }
             Stmt::Expr(expr) => {
                 self.output.push_str(indent);
@@ -129,6 +115,16 @@ impl FormatCtx {
                 self.emit_trailing_comment(stmt.span.end);
                 self.output.push('\n');
             }
+            Stmt::Break | Stmt::Continue => {
+                self.output.push_str(indent);
+                self.output.push_str(if matches!(stmt.node, Stmt::Break) {
+                    "break"
+                } else {
+                    "continue"
+                });
+                self.emit_trailing_comment(stmt.span.end);
+                self.output.push('\n');
+            }
             Stmt::Reveal { event_name, fields } => {
                 self.emit_event_stmt("reveal", event_name, fields, indent, stmt.span.end);
             }
@@ -139,16 +135,16 @@ impl FormatCtx {
                 self.output.push_str(indent);
                 self.output.push_str("match ");
                 self.output.push_str(&format_expr(&expr.node));
-                self.output.push_str(" {\n");
+                self.output.push_str(" {");
+                self.emit_trailing_comment(expr.span.end);
+                self.output.push('\n');
                 let inner = format!("{}{}", indent, INDENT);
                 for arm in arms {
+                    self.emit_leading_comments(arm.pattern.span.start, &inner);
                     self.output.push_str(&inner);
                     match &arm.pattern.node {
-                        MatchPattern::Literal(Literal::Integer(n)) => {
-                            self.output.push_str(&n.to_string());
-                        }
-                        MatchPattern::Literal(Literal::Bool(b)) => {
-                            self.output.push_str(if *b { "true" } else { "false" });
+                        MatchPattern::Literal(lit) => {
+                            self.output.push_str(&format_literal(lit));
                         }
                         MatchPattern::Wildcard => {
                             self.output.push('_');
@@ -171,13 +167,9 @@ impl FormatCtx {
                                         self.output.push_str(": ");
                                         self.output.push_str(var_name);
                                     }
-                                    FieldPattern::Literal(Literal::Integer(n)) => {
-                                        self.output.push_str(": ");
-                                        self.output.push_str(&n.to_string());
-                                    }
-                                    FieldPattern::Literal(Literal::Bool(b)) => {
+                                    FieldPattern::Literal(lit) => {
                                         self.output.push_str(": ");
-                                        self.output.push_str(if *b { "true" } else { "false" });
+                                        self.output.push_str(&format_literal(lit));
                                     }
                                     FieldPattern::Wildcard => {
                                         self.output.push_str(": _");
@@ -186,14 +178,21 @@ impl FormatCtx {
                             }
                             self.output.push_str(" }");
                         }
+                        MatchPattern::Variant { path, bindings } => {
+                            self.output.push_str(&path.node.as_dotted());
+                            if !bindings.is_empty() {
+                                let names: Vec<&str> =
+                                    bindings.iter().map(|b| b.node.as_str()).collect();
+                                self.output.push_str(&format!("({})", names.join(", ")));
+                            }
+                        }
                     }
-                    self.output.push_str(" => {\n");
-                    self.emit_block(&arm.body.node, &inner);
-                    self.output.push_str(&inner);
-                    self.output.push_str("}\n");
+                    self.output.push_str(" => {");
+                    self.emit_block(&arm.body, &inner);
+                    self.close_block(&inner, arm.body.span.end);
                 }
-                self.output.push_str(indent);
-                self.output.push_str("}\n");
+                self.emit_leading_comments(stmt.span.end.saturating_sub(1), &inner);
+                self.close_block(indent, stmt.span.end);
             }
             Stmt::Asm {
                 body,
@@ -234,8 +233,7 @@ impl FormatCtx {
                         self.output.push('\n');
                     }
                 }
-                self.output.push_str(indent);
-                self.output.push_str("}\n");
+                self.close_block(indent, stmt.span.end);
             }
         }
     }
@@ -291,7 +289,7 @@ impl FormatCtx {
     }
 
     /// Emit an if statement without the leading indent (used for `else if` chains).
-    fn emit_if_inline(&mut self, stmt: &Stmt, indent: &str) {
+    fn emit_if_inline(&mut self, stmt: &Stmt, indent: &str, span_end: u32) {
         if let Stmt::If {
             cond,
             then_block,
@@ -300,26 +298,32 @@ impl FormatCtx {
         {
             self.output.push_str("if ");
             self.output.push_str(&format_expr(&cond.node));
-            self.output.push_str(" {\n");
-            self.emit_block(&then_block.node, indent);
+            self.output.push_str(" {");
+            self.emit_block(then_block, indent);
             if let Some(else_b) = else_block {
                 if let Some(inner_if) = as_else_if(&else_b.node) {
                     self.output.push_str(indent);
                     self.output.push_str("} else ");
-                    self.emit_if_inline(inner_if, indent);
+                    self.emit_if_inline(inner_if, indent, span_end);
                 } else {
                     self.output.push_str(indent);
-                    self.output.push_str("} else {\n");
-                    self.emit_block(&else_b.node, indent);
-                    self.output.push_str(indent);
-                    self.output.push_str("}\n");
+                    self.output.push_str("} else {");
+                    self.emit_block(else_b, indent);
+                    self.close_block(indent, span_end);
                 }
             } else {
-                self.output.push_str(indent);
-                self.output.push_str("}\n");
+                self.close_block(indent, span_end);
             }
         }
     }
+
+    /// Emit the closing `}` line of a block that ends at `span_end`.
+    pub(super) fn close_block(&mut self, indent: &str, span_end: u32) {
+        self.output.push_str(indent);
+        self.output.push('}');
+        self.emit_trailing_comment(span_end);
+        self.output.push('\n');
+    }
 }
 
 /// Check if a block is a single `if` statement (for `else if` chains).
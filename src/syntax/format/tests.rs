@@ -8,8 +8,9 @@ use super::*;
 fn fmt(source: &str) -> String {
     let (tokens, comments, lex_errors) = Lexer::new(source, 0).tokenize();
     assert!(lex_errors.is_empty(), "lex errors: {:?}", lex_errors);
+    let trivia = TriviaMap::build(source, &tokens, &comments);
     let file = Parser::new(tokens).parse_file().unwrap();
-    format_file(&file, &comments)
+    format_file(&file, &trivia)
 }
 
 // --- Basic formatting ---
@@ -59,6 +60,24 @@ fn test_event_formatting() {
     assert_eq!(fmt(src), src);
 }
 
+#[test]
+fn test_event_attributes_roundtrip() {
+    let src = "program test\n\n#[version(3)]\n#[deprecated(\"use \\\"Pay\\\"\")]\nevent Transfer {\n    from: Field,\n}\n\n#[deprecated]\nevent Mint {\n    amount: Field,\n}\n\nfn main() {\n    pub_write(pub_read())\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn test_entrypoint_attributes_roundtrip() {
+    let src = "program test\n\n#[entrypoint]\nfn pay() {\n    pub_write(pub_read())\n}\n\n#[entrypoint(7)]\nfn mint() {\n    pub_write(0)\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn test_assume_attributes_and_calls_roundtrip() {
+    let src = "program test\n\n#[assume(n < 10)]\nfn step(n: U32) {\n    assume(n == 4, \"caller checks\")\n}\n\nfn main() {\n    step(as_u32(pub_read()))\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
 // --- Statements ---
 
 #[test]
@@ -91,6 +110,12 @@ fn test_for_loop() {
     assert_eq!(fmt(src), src);
 }
 
+#[test]
+fn test_while_loop() {
+    let src = "program test\n\nfn main() {\n    let mut i: Field = 0\n    while as_u32(i) < 10 bounded 16 {\n        i = i + 1\n    }\n    pub_write(i)\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
 #[test]
 fn test_return_statement() {
     let src = "program test\n\nfn helper(x: Field) -> Field {\n    return x + 1\n}\n\nfn main() {\n    pub_write(helper(pub_read()))\n}\n";
@@ -130,6 +155,36 @@ fn test_struct_init_expr() {
     assert_eq!(fmt(src), src);
 }
 
+#[test]
+fn test_tuple_and_unit_structs() {
+    let src = "program test\n\npub struct Amount(pub Field)\n\nstruct Pair(Field, U32)\n\nstruct Marker\n\nfn main() {\n    let a: Amount = Amount(pub_read())\n    let _m: Marker = Marker\n    pub_write(a.0)\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn test_enums_and_variant_patterns() {
+    let src = "program test\n\npub enum Shape {\n    Empty, // no payload\n    Rect(Field, U32),\n}\n\nfn main() {\n    let s: Shape = Shape.Rect(pub_read(), 2)\n    match s {\n        Shape.Empty => {\n        }\n        Shape.Rect(w, _) => {\n            pub_write(w)\n        }\n    }\n}\n";
+    assert_eq!(fmt(src), src);
+    let out = fmt("program test\nenum E { A, B(Field) }\nfn main() {}\n");
+    assert!(
+        out.contains("enum E {\n    A,\n    B(Field),\n}\n"),
+        "{}",
+        out
+    );
+}
+
+#[test]
+fn test_digest_associated_functions() {
+    let src = "program test\n\nfn main() {\n    let d: Digest = Digest::from_fields(1, 2, 3, 4, 5)\n    let a: [Field; 5] = Digest::to_array(d)\n    pub_write(d[0] + a[4])\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn test_struct_defaults_and_update_syntax() {
+    let src = "program test\n\nstruct Pt {\n    x: Field,\n    y: Field = 0,\n}\n\nfn main() {\n    let p: Pt = Pt { x: 1 }\n    let q: Pt = Pt { y: 2, ..p }\n    pub_write(q.x)\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
 #[test]
 fn test_field_access() {
     let src = "program test\n\nstruct Pt {\n    x: Field,\n    y: Field,\n}\n\nfn main() {\n    let p: Pt = Pt { x: 1, y: 2 }\n    pub_write(p.x + p.y)\n}\n";
@@ -156,6 +211,24 @@ fn test_trailing_comment() {
     assert!(out.contains("// read value"), "trailing comment preserved");
 }
 
+#[test]
+fn test_trailing_comments_stay_on_their_line() {
+    let src = "program test\n\nfn main() {\n    let x: Field = pub_read()\n    let y: Field = x // copy\n    pub_write(y)\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn test_comments_around_braces() {
+    let src = "program test\n\nstruct Pt { // point\n    x: Field, // abscissa\n    // more later\n}\n\nfn main() { // entry\n    if true { // taken\n        pub_write(0)\n        // done\n    } // if\n    // end of main\n} // main\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn test_blank_lines_around_comments_kept() {
+    let src = "program test\n\n// section\n\n/// Entry.\nfn main() {\n    let x: Field = pub_read()\n\n    // then write\n    pub_write(x)\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
 // --- Idempotency ---
 
 #[test]
@@ -303,12 +376,14 @@ fn main() {
         let kind_a = match &a.node {
             Item::Fn(_) => "fn",
             Item::Struct(_) => "struct",
+            Item::Enum(_) => "enum",
             Item::Const(_) => "const",
             Item::Event(_) => "event",
         };
         let kind_b = match &b.node {
             Item::Fn(_) => "fn",
             Item::Struct(_) => "struct",
+            Item::Enum(_) => "enum",
             Item::Const(_) => "const",
             Item::Event(_) => "event",
         };
@@ -437,3 +512,34 @@ fn test_match_struct_pattern_with_literal_formatting() {
         "should format literal field pattern"
     );
 }
+
+#[test]
+fn test_authenticated_attribute_roundtrips() {
+    let src =
+        "module kernel\n\n#[authenticated]\npub fn fee(root: Digest) -> Digest {\n    root\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn test_authenticated_struct_roundtrips() {
+    let src = "module kernel\n\n#[authenticated]\npub struct Fee {\n    pub amount: Field,\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn test_auto_range_check_attribute_roundtrips() {
+    let src = "module bounds\n\n#[auto_range_check]\npub fn below(a: Field, b: Field) -> Bool {\n    a < b\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn test_assertion_message_roundtrips() {
+    let src = "program test\n\nfn main() {\n    assert(pub_read() == 1, \"say \\\"one\\\" \\\\ once\")\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn test_logical_operators() {
+    let src = "program test\n\nfn main() {\n    let a: Bool = pub_read() == 0\n    let b: Bool = a && a == false || a\n    let c: Bool = (a || b) && a | b & a\n    assert(b || c)\n}\n";
+    assert_eq!(fmt(src), src);
+}
@@ -3,7 +3,6 @@ mod dsl;
 mod tests;
 mod trident;
 
-pub use dsl::*;
 pub use trident::trident_grammar;
 
 /// Top-level grammar matching tree-sitter's grammar.json schema.
@@ -257,7 +257,10 @@ fn rules() -> Vec<(&'static str, Node)> {
                 sym("let_statement"),
                 sym("if_statement"),
                 sym("for_statement"),
+                sym("while_statement"),
                 sym("return_statement"),
+                sym("break_statement"),
+                sym("continue_statement"),
                 sym("match_statement"),
                 sym("asm_block"),
                 sym("reveal_statement"),
@@ -327,15 +330,27 @@ fn rules() -> Vec<(&'static str, Node)> {
                 field("end", sym("_expression")),
                 optional(seq(vec![
                     str_("bounded"),
-                    field("bound", sym("integer_literal")),
+                    field("bound", sym("_expression")),
                 ])),
                 field("body", sym("block")),
             ]),
         ),
+        (
+            "while_statement",
+            seq(vec![
+                str_("while"),
+                field("condition", sym("_expression")),
+                str_("bounded"),
+                field("bound", sym("_expression")),
+                field("body", sym("block")),
+            ]),
+        ),
         (
             "return_statement",
             prec_left(0, seq(vec![str_("return"), optional(sym("_expression"))])),
         ),
+        ("break_statement", str_("break")),
+        ("continue_statement", str_("continue")),
         (
             "match_statement",
             seq(vec![
@@ -438,6 +453,7 @@ fn rules() -> Vec<(&'static str, Node)> {
             choice(vec![
                 sym("integer_literal"),
                 sym("boolean_literal"),
+                sym("string_literal"),
                 sym("_path_expr"),
                 sym("binary_expression"),
                 sym("call_expression"),
@@ -450,6 +466,7 @@ fn rules() -> Vec<(&'static str, Node)> {
         ),
         ("integer_literal", pattern("[0-9]+")),
         ("boolean_literal", choice(vec![str_("true"), str_("false")])),
+        ("string_literal", pattern(r#""([^"\\\n]|\\["\\])*""#)),
         ("_path_expr", sym("module_path")),
         // Binary operators
         (
@@ -0,0 +1,96 @@
+//! String interning.
+//!
+//! An `Interner` stores each distinct string once and hands out a
+//! copyable `Symbol` for it, so tables keyed by names (labels, module
+//! paths) compare and copy integers instead of cloning strings. Interned
+//! text is held as `Arc<str>` and can be shared with other owners.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Handle to an interned string, valid for the `Interner` that made it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Dense index of the symbol, usable to index per-symbol tables.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Maps strings to symbols and back.
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    ids: BTreeMap<Arc<str>, Symbol>,
+    names: Vec<Arc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Symbol for `name`, interning it on first sight.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&sym) = self.ids.get(name) {
+            return sym;
+        }
+        let sym = Symbol(self.names.len() as u32);
+        let shared: Arc<str> = Arc::from(name);
+        self.names.push(Arc::clone(&shared));
+        self.ids.insert(shared, sym);
+        sym
+    }
+
+    /// Symbol for `name` if it has been interned.
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.ids.get(name).copied()
+    }
+
+    /// The string behind `sym`.
+    pub fn resolve(&self, sym: Symbol) -> &str {
+        &self.names[sym.index()]
+    }
+
+    /// Number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// All symbols with their strings, in interning order.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (Symbol(i as u32), &**name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_strings_share_one_symbol() {
+        let mut interner = Interner::new();
+        let a = interner.intern("std.crypto.merkle");
+        let b = interner.intern("std.io");
+        assert_eq!(interner.intern("std.crypto.merkle"), a);
+        assert_ne!(a, b);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.resolve(b), "std.io");
+        assert_eq!(interner.get("std.io"), Some(b));
+        assert_eq!(interner.get("std.hash"), None);
+    }
+
+    #[test]
+    fn symbols_are_dense_in_interning_order() {
+        let mut interner = Interner::new();
+        for name in ["c", "a", "b", "a"] {
+            interner.intern(name);
+        }
+        let order: Vec<(usize, &str)> = interner.iter().map(|(s, n)| (s.index(), n)).collect();
+        assert_eq!(order, vec![(0, "c"), (1, "a"), (2, "b")]);
+    }
+}
@@ -12,12 +12,16 @@ pub enum Lexeme {
     Mut,
     Const,
     Struct,
+    Enum,
     If,
     Else,
     For,
+    While,
     In,
     Bounded,
     Return,
+    Break,
+    Continue,
     True,
     False,
     Event,
@@ -55,12 +59,16 @@ pub enum Lexeme {
     Gt,           // >
     Amp,          // &
     Caret,        // ^
+    Pipe,         // |
+    AmpAmp,       // &&
+    PipePipe,     // ||
     SlashPercent, // /%
     Hash,         // #
     Underscore,   // _
 
     // Literals
     Integer(u64),
+    Str(String),
     Ident(String),
 
     // Inline assembly
@@ -74,6 +82,14 @@ pub enum Lexeme {
     Eof,
 }
 
+/// Words set aside for upcoming syntax.
+pub const RESERVED_KEYWORDS: &[&str] = &["impl", "trait"];
+
+/// The name the migration fix gives an identifier spelled like a reserved word.
+pub fn reserved_rename(name: &str) -> String {
+    format!("{}_", name)
+}
+
 impl Lexeme {
     /// Try to match an identifier string to a keyword or type lexeme.
     pub fn from_keyword(s: &str) -> Option<Lexeme> {
@@ -88,12 +104,16 @@ impl Lexeme {
             "mut" => Some(Lexeme::Mut),
             "const" => Some(Lexeme::Const),
             "struct" => Some(Lexeme::Struct),
+            "enum" => Some(Lexeme::Enum),
             "if" => Some(Lexeme::If),
             "else" => Some(Lexeme::Else),
             "for" => Some(Lexeme::For),
+            "while" => Some(Lexeme::While),
             "in" => Some(Lexeme::In),
             "bounded" => Some(Lexeme::Bounded),
             "return" => Some(Lexeme::Return),
+            "break" => Some(Lexeme::Break),
+            "continue" => Some(Lexeme::Continue),
             "true" => Some(Lexeme::True),
             "false" => Some(Lexeme::False),
             "event" => Some(Lexeme::Event),
@@ -110,6 +130,12 @@ impl Lexeme {
         }
     }
 
+    /// Whether `s` is reserved for a future keyword. Reserved words still
+    /// lex as identifiers, but declaring one draws a warning.
+    pub fn is_reserved(s: &str) -> bool {
+        RESERVED_KEYWORDS.contains(&s)
+    }
+
     pub fn description(&self) -> &'static str {
         match self {
             Lexeme::Program => "'program'",
@@ -122,12 +148,16 @@ impl Lexeme {
             Lexeme::Mut => "'mut'",
             Lexeme::Const => "'const'",
             Lexeme::Struct => "'struct'",
+            Lexeme::Enum => "'enum'",
             Lexeme::If => "'if'",
             Lexeme::Else => "'else'",
             Lexeme::For => "'for'",
+            Lexeme::While => "'while'",
             Lexeme::In => "'in'",
             Lexeme::Bounded => "'bounded'",
             Lexeme::Return => "'return'",
+            Lexeme::Break => "'break'",
+            Lexeme::Continue => "'continue'",
             Lexeme::True => "'true'",
             Lexeme::False => "'false'",
             Lexeme::Event => "'event'",
@@ -161,10 +191,14 @@ impl Lexeme {
             Lexeme::Gt => "'>'",
             Lexeme::Amp => "'&'",
             Lexeme::Caret => "'^'",
+            Lexeme::Pipe => "'|'",
+            Lexeme::AmpAmp => "'&&'",
+            Lexeme::PipePipe => "'||'",
             Lexeme::SlashPercent => "'/%'",
             Lexeme::Hash => "'#'",
             Lexeme::Underscore => "'_'",
             Lexeme::Integer(_) => "integer literal",
+            Lexeme::Str(_) => "string literal",
             Lexeme::Ident(_) => "identifier",
             Lexeme::AsmBlock { .. } => "asm block",
             Lexeme::Eof => "end of file",
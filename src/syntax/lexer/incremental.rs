@@ -0,0 +1,176 @@
+//! Incremental tokenization: re-lex only the lines an edit touched and
+//! reuse the previous token list for the rest of the file.
+//!
+//! Re-lexing starts at the beginning of the first edited line, or earlier
+//! when a token (an `asm` block) reaches into that line from above. It
+//! stops at the first token on a line after the edit that matches an old
+//! token once shifted by the edit's length change; from there the old
+//! tokens and comments are reused as they are, shifted.
+
+#[cfg(test)]
+use std::ops::Range;
+
+use super::{Comment, Lexer};
+use crate::lexeme::Lexeme;
+use crate::span::{Span, Spanned};
+
+/// A single text replacement, in byte offsets.
+///
+/// `start..old_end` is the replaced range in the old source and
+/// `start..new_end` the inserted text in the new one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceEdit {
+    pub start: usize,
+    pub old_end: usize,
+    pub new_end: usize,
+}
+
+impl SourceEdit {
+    /// Change in source length caused by the edit.
+    pub fn delta(&self) -> i64 {
+        self.new_end as i64 - self.old_end as i64
+    }
+}
+
+/// Tokens and comments after an incremental re-lex.
+pub struct Relexed {
+    pub tokens: Vec<Spanned<Lexeme>>,
+    pub comments: Vec<Comment>,
+    /// Byte range of the new source that was actually re-lexed.
+    #[cfg(test)]
+    pub relexed: Range<usize>,
+}
+
+impl<'src> Lexer<'src> {
+    /// Re-tokenize after `edit`, given the token and comment lists of the
+    /// source before the edit. The lexer must have been created over the
+    /// new source. Tokens and comments are identical to a full `tokenize`.
+    pub fn relex(
+        mut self,
+        old_tokens: &[Spanned<Lexeme>],
+        old_comments: &[Comment],
+        edit: SourceEdit,
+    ) -> Relexed {
+        let delta = edit.delta();
+        let start = self.relex_start(old_tokens, edit.start);
+        // Only tokens on a line after the edit may resynchronize: a token on
+        // the edited line itself can still be glued to its left neighbour.
+        let resync_from = match self.source[edit.new_end.min(self.source.len())..]
+            .iter()
+            .position(|&b| b == b'\n')
+        {
+            Some(i) => edit.new_end + i + 1,
+            None => self.source.len() + 1,
+        };
+
+        self.pos = start;
+        self.token_on_line = false;
+        let mut mid = Vec::new();
+        let mut resync = None;
+        loop {
+            let tok = self.next_token();
+            if tok.node == Lexeme::Eof {
+                mid.push(tok);
+                break;
+            }
+            if tok.span.start as usize >= resync_from {
+                if let Some(i) = find_shifted(old_tokens, &tok, delta) {
+                    resync = Some((i, tok.span.start as usize));
+                    break;
+                }
+            }
+            mid.push(tok);
+        }
+
+        let prefix = old_tokens.partition_point(|t| (t.span.start as usize) < start);
+        let mut tokens = Vec::with_capacity(old_tokens.len());
+        tokens.extend(old_tokens[..prefix].iter().cloned());
+        tokens.append(&mut mid);
+
+        let mut comments: Vec<Comment> = old_comments
+            .iter()
+            .filter(|c| (c.span.start as usize) < start)
+            .cloned()
+            .collect();
+        comments.append(&mut self.comments);
+
+        if let Some((i, new_start)) = resync {
+            tokens.extend(old_tokens[i..].iter().map(|t| shift_token(t, delta)));
+            let old_start = (new_start as i64 - delta) as usize;
+            comments.extend(
+                old_comments
+                    .iter()
+                    .filter(|c| (c.span.start as usize) >= old_start)
+                    .map(|c| shift_comment(c, delta)),
+            );
+        }
+
+        Relexed {
+            tokens,
+            comments,
+            #[cfg(test)]
+            relexed: start..resync.map_or(self.source.len(), |(_, new_start)| new_start),
+        }
+    }
+
+    /// Where re-lexing has to begin: the start of the line holding
+    /// `offset`, moved back over any old token that spans that line start.
+    fn relex_start(&self, old_tokens: &[Spanned<Lexeme>], offset: usize) -> usize {
+        let mut start = line_start(self.source, offset);
+        loop {
+            let i = old_tokens.partition_point(|t| (t.span.end as usize) <= start);
+            match old_tokens.get(i) {
+                Some(t) if (t.span.start as usize) < start => {
+                    start = line_start(self.source, t.span.start as usize);
+                }
+                _ => return start,
+            }
+        }
+    }
+}
+
+fn line_start(source: &[u8], offset: usize) -> usize {
+    let offset = offset.min(source.len());
+    source[..offset]
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1)
+}
+
+/// Index of the old token that, shifted by `delta`, is identical to `tok`.
+fn find_shifted(
+    old_tokens: &[Spanned<Lexeme>],
+    tok: &Spanned<Lexeme>,
+    delta: i64,
+) -> Option<usize> {
+    let old_start = tok.span.start as i64 - delta;
+    if old_start < 0 {
+        return None;
+    }
+    let i = old_tokens.partition_point(|t| (t.span.start as i64) < old_start);
+    let old = old_tokens.get(i)?;
+    let same = old.span.start as i64 == old_start
+        && old.span.end as i64 + delta == tok.span.end as i64
+        && old.node == tok.node;
+    same.then_some(i)
+}
+
+fn shift_span(span: Span, delta: i64) -> Span {
+    Span::new(
+        span.file_id,
+        (span.start as i64 + delta) as u32,
+        (span.end as i64 + delta) as u32,
+    )
+}
+
+fn shift_token(tok: &Spanned<Lexeme>, delta: i64) -> Spanned<Lexeme> {
+    Spanned::new(tok.node.clone(), shift_span(tok.span, delta))
+}
+
+fn shift_comment(comment: &Comment, delta: i64) -> Comment {
+    Comment {
+        text: comment.text.clone(),
+        span: shift_span(comment.span, delta),
+        trailing: comment.trailing,
+    }
+}
@@ -1,7 +1,11 @@
-use crate::diagnostic::Diagnostic;
+use crate::diagnostic::{Diagnostic, ErrorCode};
 use crate::lexeme::Lexeme;
 use crate::span::{Span, Spanned};
 
+mod incremental;
+
+pub use incremental::SourceEdit;
+
 /// A source comment preserved for the formatter.
 #[derive(Clone, Debug)]
 pub struct Comment {
@@ -10,6 +14,13 @@ pub struct Comment {
     pub trailing: bool, // true if a token appeared earlier on the same line
 }
 
+impl Comment {
+    /// A `///` doc comment (but not a `////` rule).
+    pub fn is_doc(&self) -> bool {
+        self.text.starts_with("///") && !self.text.starts_with("////")
+    }
+}
+
 pub struct Lexer<'src> {
     source: &'src [u8],
     file_id: u16,
@@ -32,35 +43,6 @@ impl<'src> Lexer<'src> {
         }
     }
 
-    /// Create a lexer that starts at the given byte offset.
-    /// Used for incremental re-lexing of dirty regions.
-    pub(crate) fn new_from_offset(source: &'src str, file_id: u16, offset: usize) -> Self {
-        Self {
-            source: source.as_bytes(),
-            file_id,
-            pos: offset,
-            diagnostics: Vec::new(),
-            comments: Vec::new(),
-            token_on_line: false,
-        }
-    }
-
-    /// Lex a single token and advance. Exposed for incremental lexing.
-    pub(crate) fn next_spanned(&mut self) -> Spanned<Lexeme> {
-        self.next_token()
-    }
-
-    /// Drain accumulated comments from the lexer.
-    pub(crate) fn take_comments(&mut self) -> Vec<Comment> {
-        std::mem::take(&mut self.comments)
-    }
-
-    /// Drain accumulated diagnostics from the lexer.
-    #[allow(dead_code)] // reserved for incremental diagnostic support
-    pub(crate) fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
-        std::mem::take(&mut self.diagnostics)
-    }
-
     pub fn tokenize(mut self) -> (Vec<Spanned<Lexeme>>, Vec<Comment>, Vec<Diagnostic>) {
         let mut tokens = Vec::new();
         loop {
@@ -97,6 +79,11 @@ impl<'src> Lexer<'src> {
                 return self.scan_number();
             }
 
+            // String literals (assertion messages)
+            if ch == b'"' {
+                return self.scan_string();
+            }
+
             // Symbols
             if let Some(tok) = self.scan_symbol(start) {
                 return tok;
@@ -218,6 +205,7 @@ impl<'src> Lexer<'src> {
                         "expected ')' after asm annotation".to_string(),
                         Span::new(self.file_id, self.pos as u32, self.pos as u32),
                     )
+                    .with_code(ErrorCode::MalformedAsm)
                     .with_help(
                         "asm annotations: `asm(+1) { ... }`, `asm(triton) { ... }`, or `asm(triton, +1) { ... }`"
                             .to_string(),
@@ -235,7 +223,7 @@ impl<'src> Lexer<'src> {
             self.diagnostics.push(Diagnostic::error(
                 "expected '{' after `asm` keyword".to_string(),
                 Span::new(self.file_id, self.pos as u32, self.pos as u32),
-            ).with_help("inline assembly syntax is `asm { instructions }` or `asm(triton) { instructions }`".to_string()));
+            ).with_code(ErrorCode::MalformedAsm).with_help("inline assembly syntax is `asm { instructions }` or `asm(triton) { instructions }`".to_string()));
             return self.make_token(
                 Lexeme::AsmBlock {
                     body: String::new(),
@@ -274,6 +262,7 @@ impl<'src> Lexer<'src> {
                     "unterminated asm block: missing closing '}'".to_string(),
                     Span::new(self.file_id, start as u32, self.pos as u32),
                 )
+                .with_code(ErrorCode::MalformedAsm)
                 .with_help(
                     "every `asm { ... }` block must have a matching closing brace".to_string(),
                 ),
@@ -329,6 +318,7 @@ impl<'src> Lexer<'src> {
                         format!("integer literal '{}' is too large", text),
                         Span::new(self.file_id, start as u32, self.pos as u32),
                     )
+                    .with_code(ErrorCode::IntegerTooLarge)
                     .with_help(format!("maximum integer value is {}", u64::MAX)),
                 );
                 self.make_token(Lexeme::Integer(0), start, self.pos)
@@ -336,6 +326,43 @@ impl<'src> Lexer<'src> {
         }
     }
 
+    /// Scan a double-quoted string on a single line. `\"` and `\\` are
+    /// the only escapes.
+    fn scan_string(&mut self) -> Spanned<Lexeme> {
+        let start = self.pos;
+        self.pos += 1; // skip opening '"'
+        let mut text = Vec::new();
+        loop {
+            match self.source.get(self.pos) {
+                Some(b'"') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(b'\\') if matches!(self.source.get(self.pos + 1), Some(b'"' | b'\\')) => {
+                    text.push(self.source[self.pos + 1]);
+                    self.pos += 2;
+                }
+                Some(b'\n') | None => {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            "unterminated string literal".to_string(),
+                            Span::new(self.file_id, start as u32, self.pos as u32),
+                        )
+                        .with_code(ErrorCode::UnterminatedString)
+                        .with_help("close the string with '\"' on the same line".to_string()),
+                    );
+                    break;
+                }
+                Some(&b) => {
+                    text.push(b);
+                    self.pos += 1;
+                }
+            }
+        }
+        let text = String::from_utf8(text).unwrap_or_default();
+        self.make_token(Lexeme::Str(text), start, self.pos)
+    }
+
     fn scan_symbol(&mut self, start: usize) -> Option<Spanned<Lexeme>> {
         let ch = self.source[self.pos];
         self.pos += 1;
@@ -353,7 +380,22 @@ impl<'src> Lexer<'src> {
             b'+' => Lexeme::Plus,
             b'<' => Lexeme::Lt,
             b'>' => Lexeme::Gt,
-            b'&' => Lexeme::Amp,
+            b'&' => {
+                if self.peek() == Some(b'&') {
+                    self.pos += 1;
+                    Lexeme::AmpAmp
+                } else {
+                    Lexeme::Amp
+                }
+            }
+            b'|' => {
+                if self.peek() == Some(b'|') {
+                    self.pos += 1;
+                    Lexeme::PipePipe
+                } else {
+                    Lexeme::Pipe
+                }
+            }
             b'^' => Lexeme::Caret,
             b'#' => Lexeme::Hash,
             b'.' => {
@@ -374,6 +416,7 @@ impl<'src> Lexer<'src> {
                             "unexpected '-'; Trident has no subtraction operator".to_string(),
                             Span::new(self.file_id, start as u32, self.pos as u32),
                         )
+                        .with_code(ErrorCode::UnexpectedCharacter)
                         .with_help("use the `sub(a, b)` function instead of `a - b`".to_string()),
                     );
                     return None;
@@ -408,6 +451,7 @@ impl<'src> Lexer<'src> {
                             "unexpected '/'; Trident has no division operator".to_string(),
                             Span::new(self.file_id, start as u32, self.pos as u32),
                         )
+                        .with_code(ErrorCode::UnexpectedCharacter)
                         .with_help(
                             "use the `/% (divmod)` operator instead: `let (quot, rem) = a /% b`"
                                 .to_string(),
@@ -422,6 +466,7 @@ impl<'src> Lexer<'src> {
                         format!("unexpected character '{}' (U+{:04X})", ch as char, ch),
                         Span::new(self.file_id, start as u32, self.pos as u32),
                     )
+                    .with_code(ErrorCode::UnexpectedCharacter)
                     .with_help(
                         "this character is not recognized as part of Trident syntax".to_string(),
                     ),
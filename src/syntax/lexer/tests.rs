@@ -1,3 +1,4 @@
+use super::incremental::Relexed;
 use super::*;
 
 fn lex(source: &str) -> Vec<Lexeme> {
@@ -8,7 +9,7 @@ fn lex(source: &str) -> Vec<Lexeme> {
 
 #[test]
 fn test_keywords() {
-    let tokens = lex("program fn let mut pub if else for in bounded return");
+    let tokens = lex("program fn let mut pub if else for while in bounded return break continue");
     assert_eq!(
         tokens,
         vec![
@@ -20,9 +21,12 @@ fn test_keywords() {
             Lexeme::If,
             Lexeme::Else,
             Lexeme::For,
+            Lexeme::While,
             Lexeme::In,
             Lexeme::Bounded,
             Lexeme::Return,
+            Lexeme::Break,
+            Lexeme::Continue,
             Lexeme::Eof,
         ]
     );
@@ -290,6 +294,16 @@ fn test_error_unexpected_character() {
     );
 }
 
+#[test]
+fn test_error_codes() {
+    use crate::diagnostic::ErrorCode;
+    let code = |source: &str| lex_with_errors(source).1[0].code;
+    assert_eq!(code("@"), Some(ErrorCode::UnexpectedCharacter));
+    assert_eq!(code("a - b"), Some(ErrorCode::UnexpectedCharacter));
+    assert_eq!(code("\"open"), Some(ErrorCode::UnterminatedString));
+    assert_eq!(code("asm { push 1"), Some(ErrorCode::MalformedAsm));
+}
+
 #[test]
 fn test_error_subtraction_operator() {
     let (_tokens, diags) = lex_with_errors("a - b");
@@ -355,3 +369,117 @@ fn test_error_unterminated_asm_block() {
         "unterminated asm error should have help text"
     );
 }
+
+#[test]
+fn test_string_literal_with_escapes() {
+    let tokens = lex(r#"assert(ok, "say \"no\" \\ twice")"#);
+    assert_eq!(tokens[4], Lexeme::Str(r#"say "no" \ twice"#.to_string()));
+    assert_eq!(tokens[5], Lexeme::RParen);
+}
+
+#[test]
+fn test_error_unterminated_string() {
+    let (_tokens, diags) = lex_with_errors("assert(ok, \"open\n)");
+    assert!(
+        diags[0].message.contains("unterminated string literal"),
+        "should report unterminated string, got: {}",
+        diags[0].message
+    );
+}
+
+// --- Incremental re-lexing ---
+
+/// Apply `old_text -> new_text` at the first occurrence of `old_text` and
+/// check that `relex` agrees with lexing the new source from scratch.
+fn check_relex(old: &str, old_text: &str, new_text: &str) -> Relexed {
+    let start = old.find(old_text).expect("edit text not in source");
+    let new = format!(
+        "{}{}{}",
+        &old[..start],
+        new_text,
+        &old[start + old_text.len()..]
+    );
+    let (old_tokens, old_comments, _) = Lexer::new(old, 0).tokenize();
+    let edit = SourceEdit {
+        start,
+        old_end: start + old_text.len(),
+        new_end: start + new_text.len(),
+    };
+    let relexed = Lexer::new(&new, 0).relex(&old_tokens, &old_comments, edit);
+
+    let (tokens, comments, _) = Lexer::new(&new, 0).tokenize();
+    let spans = |t: &[Spanned<Lexeme>]| -> Vec<(Lexeme, u32, u32)> {
+        t.iter()
+            .map(|t| (t.node.clone(), t.span.start, t.span.end))
+            .collect()
+    };
+    assert_eq!(
+        spans(&relexed.tokens),
+        spans(&tokens),
+        "tokens of {:?}",
+        new
+    );
+    let comment_keys = |c: &[Comment]| -> Vec<(String, u32, bool)> {
+        c.iter()
+            .map(|c| (c.text.clone(), c.span.start, c.trailing))
+            .collect()
+    };
+    assert_eq!(
+        comment_keys(&relexed.comments),
+        comment_keys(&comments),
+        "comments of {:?}",
+        new
+    );
+    relexed
+}
+
+#[test]
+fn test_relex_edits_within_a_line() {
+    check_relex("fn main() {}", "main", " main");
+    check_relex("fn main() { let x: Field = 42 }", "42 ", "");
+    check_relex("fn main() {}", "fn", "pub fn");
+    check_relex("fn main() {}", "}", "}\n");
+    check_relex("fn main() {\n    let ab = 1\n}", "b", "bc");
+}
+
+#[test]
+fn test_relex_multiline_edit() {
+    check_relex(
+        "fn main() {\n  let x: Field = 42\n  let y: Field = 7\n}",
+        "let x: Field = 42\n  let y: Field = 7",
+        "let z: Field = 99",
+    );
+    check_relex("fn a() {}\nfn b() {}\n", "}\nfn", "} fn");
+}
+
+#[test]
+fn test_relex_only_touches_edited_lines() {
+    let source = "fn a() {\n    let x = 1\n    let y = 2\n    let z = 3\n}\n";
+    let relexed = check_relex(source, "y = 2", "y = 20");
+    let line = source.find("    let y").unwrap();
+    // Up to the first token of the next line, which is reused.
+    assert_eq!(relexed.relexed, line..line + "    let y = 20\n    ".len());
+}
+
+#[test]
+fn test_relex_comments() {
+    let source = "// header\nfn main() {\n    let x = 1 // one\n    // two\n    let y = 2\n}\n";
+    // Editing inside a comment keeps it a comment.
+    check_relex(source, "one", "one, two");
+    check_relex(source, "header", "head }");
+    // Comments after the edit are neither lost nor duplicated.
+    check_relex(source, "x = 1", "x = 10");
+    check_relex(source, "x = 1", "x = 1\n    let w = 0");
+    // Opening a comment swallows the rest of the line.
+    check_relex(source, "let y", "// let y");
+}
+
+#[test]
+fn test_relex_inside_multiline_asm() {
+    let source = "fn f() {\n    asm(+1) {\n        push 1\n        push 2\n    }\n    return\n}\n";
+    let relexed = check_relex(source, "push 2", "push 3");
+    assert!(relexed.relexed.start <= source.find("asm").unwrap());
+    // Opening and closing a block across lines.
+    check_relex(source, "    return\n", "    asm {\n");
+    check_relex(source, "push 1\n        push 2\n    }", "push 1");
+}
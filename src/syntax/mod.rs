@@ -1,6 +1,9 @@
+pub mod flat;
 pub mod format;
 pub mod grammar;
+pub mod intern;
 pub mod lexeme;
 pub mod lexer;
 pub mod parser;
 pub mod span;
+pub mod trivia;
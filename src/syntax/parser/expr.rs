@@ -1,4 +1,5 @@
 use crate::ast::*;
+use crate::diagnostic::ErrorCode;
 use crate::lexeme::Lexeme;
 use crate::span::Spanned;
 
@@ -24,6 +25,9 @@ impl Parser {
                 Lexeme::StarDot => BinOp::XFieldMul,
                 Lexeme::Amp => BinOp::BitAnd,
                 Lexeme::Caret => BinOp::BitXor,
+                Lexeme::Pipe => BinOp::BitOr,
+                Lexeme::AmpAmp => BinOp::And,
+                Lexeme::PipePipe => BinOp::Or,
                 Lexeme::SlashPercent => BinOp::DivMod,
                 _ => break,
             };
@@ -79,6 +83,10 @@ impl Parser {
                 self.advance();
                 Spanned::new(Expr::Literal(Literal::Integer(n)), start)
             }
+            Lexeme::Str(text) => {
+                self.advance();
+                Spanned::new(Expr::Literal(Literal::Str(text)), start)
+            }
             Lexeme::True => {
                 self.advance();
                 Spanned::new(Expr::Literal(Literal::Bool(true)), start)
@@ -122,8 +130,30 @@ impl Parser {
                 let span = start.merge(self.prev_span());
                 Spanned::new(Expr::ArrayInit(elements), span)
             }
+            Lexeme::DigestTy => {
+                // `Digest::from_fields(...)` — an associated function of
+                // the builtin type, called by the path `Digest.from_fields`.
+                self.advance();
+                self.expect(&Lexeme::Colon);
+                self.expect(&Lexeme::Colon);
+                let name = self.expect_ident();
+                let path = ModulePath(vec!["Digest".to_string(), name.node]);
+                self.expect(&Lexeme::LParen);
+                let args = self.parse_call_args();
+                self.expect(&Lexeme::RParen);
+                let span = start.merge(self.prev_span());
+                Spanned::new(
+                    Expr::Call {
+                        path: Spanned::new(path, start.merge(name.span)),
+                        generic_args: Vec::new(),
+                        args,
+                    },
+                    span,
+                )
+            }
             Lexeme::Ident(_) => {
                 let path = self.parse_module_path();
+                let path_span = start.merge(self.prev_span());
 
                 // Check for generic args: name<3>(...) or name<N>(...)
                 let generic_args = self.parse_call_generic_args();
@@ -137,7 +167,7 @@ impl Parser {
                     let span = start.merge(self.prev_span());
                     Spanned::new(
                         Expr::Call {
-                            path: Spanned::new(path, start),
+                            path: Spanned::new(path, path_span),
                             generic_args,
                             args,
                         },
@@ -156,18 +186,24 @@ impl Parser {
                     if first_char.is_uppercase() && self.is_struct_init_ahead() {
                         self.advance(); // consume {
                         let fields = self.parse_struct_init_fields();
+                        let base = if self.eat(&Lexeme::DotDot) {
+                            Some(Box::new(self.parse_expr()))
+                        } else {
+                            None
+                        };
                         self.expect(&Lexeme::RBrace);
                         let span = start.merge(self.prev_span());
                         Spanned::new(
                             Expr::StructInit {
-                                path: Spanned::new(path, start),
+                                path: Spanned::new(path, path_span),
                                 fields,
+                                base,
                             },
                             span,
                         )
                     } else {
                         let name = path.0.join(".");
-                        Spanned::new(Expr::Var(name), start)
+                        Spanned::new(Expr::Var(name), path_span)
                     }
                 } else {
                     // Variable reference
@@ -178,12 +214,13 @@ impl Parser {
                         )
                     } else {
                         let name = path.0.join(".");
-                        Spanned::new(Expr::Var(name), start)
+                        Spanned::new(Expr::Var(name), path_span)
                     }
                 }
             }
             _ => {
                 self.error_with_help(
+                    ErrorCode::ExpectedExpression,
                     &format!("expected expression, found {}", self.peek().description()),
                     "expressions include literals (42, true), variables, function calls, and operators",
                 );
@@ -246,7 +283,7 @@ impl Parser {
 
     pub(super) fn parse_struct_init_fields(&mut self) -> Vec<(Spanned<String>, Spanned<Expr>)> {
         let mut fields = Vec::new();
-        while !self.at(&Lexeme::RBrace) && !self.at(&Lexeme::Eof) {
+        while !self.at(&Lexeme::RBrace) && !self.at(&Lexeme::DotDot) && !self.at(&Lexeme::Eof) {
             let name = self.expect_ident();
             if self.eat(&Lexeme::Colon) {
                 let value = self.parse_expr();
@@ -277,7 +314,7 @@ impl Parser {
                     Lexeme::Colon | Lexeme::Comma | Lexeme::RBrace
                 )
             }
-            Lexeme::RBrace => true,
+            Lexeme::RBrace | Lexeme::DotDot => true,
             _ => false,
         }
     }
@@ -1,9 +1,30 @@
 use crate::ast::*;
+use crate::diagnostic::{Diagnostic, ErrorCode};
 use crate::lexeme::Lexeme;
 use crate::span::Spanned;
 
 use super::Parser;
 
+/// Function attributes collected before an item.
+#[derive(Default)]
+struct FnAttrs {
+    intrinsic: Option<Spanned<String>>,
+    is_test: bool,
+    is_pure: bool,
+    is_authenticated: bool,
+    auto_range_check: bool,
+    entrypoint: Option<Spanned<String>>,
+    requires: Vec<Spanned<String>>,
+    ensures: Vec<Spanned<String>>,
+    assumes: Vec<Spanned<Expr>>,
+}
+
+/// Event attributes collected before an item.
+struct EventAttrs {
+    version: Option<Spanned<u64>>,
+    deprecated: Option<Spanned<String>>,
+}
+
 impl Parser {
     pub(super) fn parse_program(&mut self) -> File {
         self.expect(&Lexeme::Program);
@@ -12,6 +33,7 @@ impl Parser {
         let uses = self.parse_uses();
         let declarations = self.parse_declarations();
         let items = self.parse_items();
+        self.reject_main_with_entrypoints(&items);
 
         File {
             kind: FileKind::Program,
@@ -137,89 +159,114 @@ impl Parser {
             let start = self.current_span();
 
             // Parse attributes: #[cfg(flag)], #[intrinsic(name)], #[test],
-            // #[requires(pred)], #[ensures(pred)]
+            // #[pure], #[authenticated], #[auto_range_check], #[entrypoint],
+            // #[requires(pred)], #[ensures(pred)], #[assume(pred)],
+            // #[version(n)], #[deprecated(note)]
             let mut cfg_attr: Option<Spanned<String>> = None;
-            let mut intrinsic_attr: Option<Spanned<String>> = None;
-            let mut is_test = false;
-            let mut is_pure = false;
-            let mut requires_attrs: Vec<Spanned<String>> = Vec::new();
-            let mut ensures_attrs: Vec<Spanned<String>> = Vec::new();
+            let mut attrs = FnAttrs::default();
+            let mut event_attrs = EventAttrs {
+                version: None,
+                deprecated: None,
+            };
             while self.at(&Lexeme::Hash) {
+                if let Some(pred) = self.parse_assume_attribute() {
+                    attrs.assumes.push(pred);
+                    continue;
+                }
                 let attr = self.parse_attribute();
                 if attr.node.starts_with("cfg(") {
                     // Extract flag name from "cfg(flag)"
                     let flag = attr.node[4..attr.node.len() - 1].to_string();
                     cfg_attr = Some(Spanned::new(flag, attr.span));
                 } else if attr.node.starts_with("intrinsic(") {
-                    intrinsic_attr = Some(attr);
+                    attrs.intrinsic = Some(attr);
                 } else if attr.node.starts_with("requires(") {
                     let pred = attr.node[9..attr.node.len() - 1].to_string();
-                    requires_attrs.push(Spanned::new(pred, attr.span));
+                    attrs.requires.push(Spanned::new(pred, attr.span));
                 } else if attr.node.starts_with("ensures(") {
                     let pred = attr.node[8..attr.node.len() - 1].to_string();
-                    ensures_attrs.push(Spanned::new(pred, attr.span));
+                    attrs.ensures.push(Spanned::new(pred, attr.span));
                 } else if attr.node == "test" {
-                    is_test = true;
+                    attrs.is_test = true;
                 } else if attr.node == "pure" {
-                    is_pure = true;
+                    attrs.is_pure = true;
+                } else if attr.node == "authenticated" {
+                    attrs.is_authenticated = true;
+                } else if attr.node == "auto_range_check" {
+                    attrs.auto_range_check = true;
+                } else if attr.node == "entrypoint" || attr.node.starts_with("entrypoint(") {
+                    attrs.entrypoint = Some(attr);
+                } else if attr.node.starts_with("version(") {
+                    let value = attr.node[8..attr.node.len() - 1].parse::<u64>();
+                    match value {
+                        Ok(v) if (1..=EventDef::MAX_VERSION).contains(&v) => {
+                            event_attrs.version = Some(Spanned::new(v, attr.span));
+                        }
+                        _ => self.diagnostics.push(
+                            Diagnostic::error(
+                                format!("invalid event version `{}`", attr.node),
+                                attr.span,
+                            )
+                            .with_code(ErrorCode::UnknownAttribute)
+                            .with_help(format!(
+                                "a version is an integer from 1 to {}",
+                                EventDef::MAX_VERSION
+                            )),
+                        ),
+                    }
+                } else if attr.node == "deprecated" {
+                    event_attrs.deprecated = Some(Spanned::new(String::new(), attr.span));
+                } else if attr.node.starts_with("deprecated(") {
+                    let note = &attr.node[11..attr.node.len() - 1];
+                    // A note is a string literal, or bare words.
+                    let note = match note.strip_prefix('"').and_then(|n| n.strip_suffix('"')) {
+                        Some(quoted) => quoted.replace("\\\"", "\"").replace("\\\\", "\\"),
+                        None => note.to_string(),
+                    };
+                    event_attrs.deprecated = Some(Spanned::new(note, attr.span));
                 } else {
                     self.error_at_current(
-                        "unknown attribute; expected cfg, intrinsic, test, pure, requires, or ensures",
+                        ErrorCode::UnknownAttribute,
+                        "unknown attribute; expected cfg, intrinsic, test, pure, authenticated, auto_range_check, entrypoint, requires, ensures, assume, version, or deprecated",
                     );
                 }
             }
 
             let is_pub = self.eat(&Lexeme::Pub);
 
+            if !self.at(&Lexeme::Event) {
+                self.reject_event_only_attrs(&event_attrs);
+            }
+
             if self.at(&Lexeme::Const) {
-                self.reject_fn_only_attrs(
-                    &intrinsic_attr,
-                    is_test,
-                    is_pure,
-                    &requires_attrs,
-                    &ensures_attrs,
-                );
+                self.reject_fn_only_attrs(&attrs, false);
                 let item = self.parse_const(is_pub, cfg_attr);
                 let span = start.merge(self.prev_span());
                 items.push(Spanned::new(Item::Const(item), span));
             } else if self.at(&Lexeme::Struct) {
-                self.reject_fn_only_attrs(
-                    &intrinsic_attr,
-                    is_test,
-                    is_pure,
-                    &requires_attrs,
-                    &ensures_attrs,
-                );
-                let item = self.parse_struct(is_pub, cfg_attr);
+                self.reject_fn_only_attrs(&attrs, true);
+                let item = self.parse_struct(is_pub, cfg_attr, attrs.is_authenticated);
                 let span = start.merge(self.prev_span());
                 items.push(Spanned::new(Item::Struct(item), span));
+            } else if self.at(&Lexeme::Enum) {
+                self.reject_fn_only_attrs(&attrs, false);
+                let item = self.parse_enum(is_pub, cfg_attr);
+                let span = start.merge(self.prev_span());
+                items.push(Spanned::new(Item::Enum(item), span));
             } else if self.at(&Lexeme::Event) {
-                self.reject_fn_only_attrs(
-                    &intrinsic_attr,
-                    is_test,
-                    is_pure,
-                    &requires_attrs,
-                    &ensures_attrs,
-                );
-                let item = self.parse_event(cfg_attr);
+                self.reject_fn_only_attrs(&attrs, false);
+                let item = self.parse_event(cfg_attr, event_attrs);
                 let span = start.merge(self.prev_span());
                 items.push(Spanned::new(Item::Event(item), span));
             } else if self.at(&Lexeme::Fn) || self.at(&Lexeme::Hash) {
-                let item = self.parse_fn_with_attr(
-                    is_pub,
-                    cfg_attr,
-                    intrinsic_attr,
-                    is_test,
-                    is_pure,
-                    requires_attrs,
-                    ensures_attrs,
-                );
+                let item = self.parse_fn_with_attr(is_pub, cfg_attr, attrs);
                 let span = start.merge(self.prev_span());
                 items.push(Spanned::new(Item::Fn(item), span));
             } else {
                 self.error_with_help(
-                    "expected item (fn, struct, event, or const)",
-                    "top-level items must be function, struct, event, or const definitions",
+                    ErrorCode::ExpectedItem,
+                    "expected item (fn, struct, enum, event, or const)",
+                    "top-level items must be function, struct, enum, event, or const definitions",
                 );
                 self.advance(); // skip to recover
             }
@@ -227,25 +274,99 @@ impl Parser {
         items
     }
 
-    fn reject_fn_only_attrs(
-        &mut self,
-        intrinsic: &Option<Spanned<String>>,
-        is_test: bool,
-        is_pure: bool,
-        requires: &[Spanned<String>],
-        ensures: &[Spanned<String>],
-    ) {
-        if intrinsic.is_some() {
-            self.error_at_current("#[intrinsic] is only allowed on functions");
+    /// Report a `main` written next to `#[entrypoint]` functions: the
+    /// compiler generates `main` to dispatch to them.
+    fn reject_main_with_entrypoints(&mut self, items: &[Spanned<Item>]) {
+        let fns = || {
+            items.iter().filter_map(|item| match &item.node {
+                Item::Fn(f) => Some(f),
+                _ => None,
+            })
+        };
+        if !fns().any(|f| f.entrypoint.is_some()) {
+            return;
+        }
+        if let Some(main) = fns().find(|f| f.name.node == "main") {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    "a program with #[entrypoint] functions cannot define main".to_string(),
+                    main.name.span,
+                )
+                .with_code(ErrorCode::MainWithEntrypoints)
+                .with_help(
+                    "main is generated: it reads a selector from public input and calls the entrypoint it names"
+                        .to_string(),
+                ),
+            );
         }
-        if is_test {
-            self.error_at_current("#[test] is only allowed on functions");
+    }
+
+    /// Report function attributes attached to a non-function item.
+    /// `#[authenticated]` is also accepted on structs.
+    fn reject_fn_only_attrs(&mut self, attrs: &FnAttrs, allow_authenticated: bool) {
+        if attrs.intrinsic.is_some() {
+            self.error_at_current(
+                ErrorCode::MisplacedAttribute,
+                "#[intrinsic] is only allowed on functions",
+            );
+        }
+        if attrs.is_test {
+            self.error_at_current(
+                ErrorCode::MisplacedAttribute,
+                "#[test] is only allowed on functions",
+            );
+        }
+        if attrs.is_pure {
+            self.error_at_current(
+                ErrorCode::MisplacedAttribute,
+                "#[pure] is only allowed on functions",
+            );
+        }
+        if attrs.auto_range_check {
+            self.error_at_current(
+                ErrorCode::MisplacedAttribute,
+                "#[auto_range_check] is only allowed on functions",
+            );
         }
-        if is_pure {
-            self.error_at_current("#[pure] is only allowed on functions");
+        if attrs.entrypoint.is_some() {
+            self.error_at_current(
+                ErrorCode::MisplacedAttribute,
+                "#[entrypoint] is only allowed on functions",
+            );
         }
-        if !requires.is_empty() || !ensures.is_empty() {
-            self.error_at_current("#[requires] and #[ensures] are only allowed on functions");
+        if attrs.is_authenticated && !allow_authenticated {
+            self.error_at_current(
+                ErrorCode::MisplacedAttribute,
+                "#[authenticated] is only allowed on functions and structs",
+            );
+        }
+        if !attrs.requires.is_empty() || !attrs.ensures.is_empty() {
+            self.error_at_current(
+                ErrorCode::MisplacedAttribute,
+                "#[requires] and #[ensures] are only allowed on functions",
+            );
+        }
+        if !attrs.assumes.is_empty() {
+            self.error_at_current(
+                ErrorCode::MisplacedAttribute,
+                "#[assume] is only allowed on functions",
+            );
+        }
+    }
+
+    /// Report event attributes attached to anything but an event.
+    fn reject_event_only_attrs(&mut self, attrs: &EventAttrs) {
+        if attrs.version.is_some() {
+            self.error_at_current(
+                ErrorCode::MisplacedAttribute,
+                "#[version] is only allowed on events",
+            );
+        }
+        if attrs.deprecated.is_some() {
+            self.error_at_current(
+                ErrorCode::MisplacedAttribute,
+                "#[deprecated] is only allowed on events",
+            );
         }
     }
 
@@ -265,20 +386,65 @@ impl Parser {
         }
     }
 
-    fn parse_struct(&mut self, is_pub: bool, cfg: Option<Spanned<String>>) -> StructDef {
+    fn parse_struct(
+        &mut self,
+        is_pub: bool,
+        cfg: Option<Spanned<String>>,
+        is_authenticated: bool,
+    ) -> StructDef {
         self.expect(&Lexeme::Struct);
         let name = self.expect_ident();
-        self.expect(&Lexeme::LBrace);
         let mut fields = Vec::new();
+        if self.eat(&Lexeme::LParen) {
+            while !self.at(&Lexeme::RParen) && !self.at(&Lexeme::Eof) {
+                let field_pub = self.eat(&Lexeme::Pub);
+                let field_ty = self.parse_type();
+                fields.push(StructField {
+                    is_pub: field_pub,
+                    name: Spanned::new(fields.len().to_string(), field_ty.span),
+                    ty: field_ty,
+                    default: None,
+                });
+                if !self.eat(&Lexeme::Comma) {
+                    break;
+                }
+            }
+            self.expect(&Lexeme::RParen);
+            return StructDef {
+                is_pub,
+                cfg,
+                is_authenticated,
+                name,
+                kind: StructKind::Tuple,
+                fields,
+            };
+        }
+        if !self.at(&Lexeme::LBrace) {
+            return StructDef {
+                is_pub,
+                cfg,
+                is_authenticated,
+                name,
+                kind: StructKind::Unit,
+                fields,
+            };
+        }
+        self.expect(&Lexeme::LBrace);
         while !self.at(&Lexeme::RBrace) && !self.at(&Lexeme::Eof) {
             let field_pub = self.eat(&Lexeme::Pub);
             let field_name = self.expect_ident();
             self.expect(&Lexeme::Colon);
             let field_ty = self.parse_type();
+            let default = if self.eat(&Lexeme::Eq) {
+                Some(self.parse_expr())
+            } else {
+                None
+            };
             fields.push(StructField {
                 is_pub: field_pub,
                 name: field_name,
                 ty: field_ty,
+                default,
             });
             if !self.eat(&Lexeme::Comma) {
                 break;
@@ -288,20 +454,52 @@ impl Parser {
         StructDef {
             is_pub,
             cfg,
+            is_authenticated,
             name,
+            kind: StructKind::Named,
             fields,
         }
     }
 
+    fn parse_enum(&mut self, is_pub: bool, cfg: Option<Spanned<String>>) -> EnumDef {
+        self.expect(&Lexeme::Enum);
+        let name = self.expect_ident();
+        self.expect(&Lexeme::LBrace);
+        let mut variants = Vec::new();
+        while !self.at(&Lexeme::RBrace) && !self.at(&Lexeme::Eof) {
+            let variant_name = self.expect_ident();
+            let mut fields = Vec::new();
+            if self.eat(&Lexeme::LParen) {
+                while !self.at(&Lexeme::RParen) && !self.at(&Lexeme::Eof) {
+                    fields.push(self.parse_type());
+                    if !self.eat(&Lexeme::Comma) {
+                        break;
+                    }
+                }
+                self.expect(&Lexeme::RParen);
+            }
+            variants.push(EnumVariant {
+                name: variant_name,
+                fields,
+            });
+            if !self.eat(&Lexeme::Comma) {
+                break;
+            }
+        }
+        self.expect(&Lexeme::RBrace);
+        EnumDef {
+            is_pub,
+            cfg,
+            name,
+            variants,
+        }
+    }
+
     fn parse_fn_with_attr(
         &mut self,
         is_pub: bool,
         cfg: Option<Spanned<String>>,
-        intrinsic: Option<Spanned<String>>,
-        is_test: bool,
-        is_pure: bool,
-        requires: Vec<Spanned<String>>,
-        ensures: Vec<Spanned<String>>,
+        attrs: FnAttrs,
     ) -> FnDef {
         self.expect(&Lexeme::Fn);
         let name = self.expect_ident();
@@ -328,11 +526,15 @@ impl Parser {
         FnDef {
             is_pub,
             cfg,
-            intrinsic,
-            is_test,
-            is_pure,
-            requires,
-            ensures,
+            intrinsic: attrs.intrinsic,
+            is_test: attrs.is_test,
+            is_pure: attrs.is_pure,
+            is_authenticated: attrs.is_authenticated,
+            auto_range_check: attrs.auto_range_check,
+            entrypoint: attrs.entrypoint,
+            requires: attrs.requires,
+            ensures: attrs.ensures,
+            assumes: attrs.assumes,
             name,
             type_params,
             params,
@@ -357,6 +559,27 @@ impl Parser {
         params
     }
 
+    /// Parse `#[assume(pred)]`, whose predicate is an expression rather
+    /// than raw text. Returns `None`, consuming nothing, at any other
+    /// attribute.
+    fn parse_assume_attribute(&mut self) -> Option<Spanned<Expr>> {
+        let is_assume = matches!(
+            self.tokens.get(self.pos + 2).map(|t| &t.node),
+            Some(Lexeme::Ident(name)) if name == "assume"
+        );
+        if !is_assume {
+            return None;
+        }
+        self.expect(&Lexeme::Hash);
+        self.expect(&Lexeme::LBracket);
+        self.expect_ident();
+        self.expect(&Lexeme::LParen);
+        let pred = self.parse_expr();
+        self.expect(&Lexeme::RParen);
+        self.expect(&Lexeme::RBracket);
+        Some(pred)
+    }
+
     fn parse_attribute(&mut self) -> Spanned<String> {
         let start = self.current_span();
         self.expect(&Lexeme::Hash);
@@ -400,6 +623,7 @@ impl Parser {
         match self.peek() {
             Lexeme::Ident(s) => s.clone(),
             Lexeme::Integer(n) => n.to_string(),
+            Lexeme::Str(text) => format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"")),
             Lexeme::Plus => "+".to_string(),
             Lexeme::Star => "*".to_string(),
             Lexeme::Eq => "=".to_string(),
@@ -408,6 +632,9 @@ impl Parser {
             Lexeme::Gt => ">".to_string(),
             Lexeme::Amp => "&".to_string(),
             Lexeme::Caret => "^".to_string(),
+            Lexeme::Pipe => "|".to_string(),
+            Lexeme::AmpAmp => "&&".to_string(),
+            Lexeme::PipePipe => "||".to_string(),
             Lexeme::Dot => ".".to_string(),
             Lexeme::Comma => ",".to_string(),
             Lexeme::Colon => ":".to_string(),
@@ -433,7 +660,7 @@ impl Parser {
         params
     }
 
-    pub(super) fn parse_event(&mut self, cfg: Option<Spanned<String>>) -> EventDef {
+    fn parse_event(&mut self, cfg: Option<Spanned<String>>, attrs: EventAttrs) -> EventDef {
         self.expect(&Lexeme::Event);
         let name = self.expect_ident();
         self.expect(&Lexeme::LBrace);
@@ -451,6 +678,12 @@ impl Parser {
             }
         }
         self.expect(&Lexeme::RBrace);
-        EventDef { cfg, name, fields }
+        EventDef {
+            cfg,
+            version: attrs.version.map_or(1, |v| v.node),
+            deprecated: attrs.deprecated,
+            name,
+            fields,
+        }
     }
 }
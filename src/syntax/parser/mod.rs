@@ -7,7 +7,7 @@ mod types;
 mod tests;
 
 use crate::ast::*;
-use crate::diagnostic::Diagnostic;
+use crate::diagnostic::{Diagnostic, ErrorCode};
 use crate::lexeme::Lexeme;
 use crate::span::{Span, Spanned};
 
@@ -64,6 +64,7 @@ impl Parser {
             self.parse_module()
         } else {
             self.error_with_help(
+                ErrorCode::MissingHeader,
                 "expected 'program' or 'module' declaration at the start of file",
                 "every .tri file must begin with `program <name>` or `module <name>`",
             );
@@ -80,6 +81,7 @@ impl Parser {
         self.depth += 1;
         if self.depth > MAX_NESTING_DEPTH {
             self.error_with_help(
+                ErrorCode::NestingTooDeep,
                 "nesting depth exceeded (maximum 256 levels)",
                 "simplify your program by extracting deeply nested code into functions",
             );
@@ -137,11 +139,14 @@ impl Parser {
             self.advance();
             span
         } else {
-            self.error_at_current(&format!(
-                "expected {}, found {}",
-                token.description(),
-                self.peek().description()
-            ));
+            self.error_at_current(
+                ErrorCode::UnexpectedToken,
+                &format!(
+                    "expected {}, found {}",
+                    token.description(),
+                    self.peek().description()
+                ),
+            );
             self.current_span()
         }
     }
@@ -152,10 +157,10 @@ impl Parser {
             self.advance();
             Spanned::new(name, span)
         } else {
-            self.error_at_current(&format!(
-                "expected identifier, found {}",
-                self.peek().description()
-            ));
+            self.error_at_current(
+                ErrorCode::UnexpectedToken,
+                &format!("expected identifier, found {}", self.peek().description()),
+            );
             Spanned::new("_error_".to_string(), self.current_span())
         }
     }
@@ -170,28 +175,16 @@ impl Parser {
         }
     }
 
-    fn expect_integer(&mut self) -> u64 {
-        if let Lexeme::Integer(n) = self.peek() {
-            let n = *n;
-            self.advance();
-            n
-        } else {
-            self.error_at_current(&format!(
-                "expected integer literal, found {}",
-                self.peek().description()
-            ));
-            0
-        }
-    }
-
-    fn error_at_current(&mut self, msg: &str) {
+    fn error_at_current(&mut self, code: ErrorCode, msg: &str) {
         self.diagnostics
-            .push(Diagnostic::error(msg.to_string(), self.current_span()));
+            .push(Diagnostic::error(msg.to_string(), self.current_span()).with_code(code));
     }
 
-    fn error_with_help(&mut self, msg: &str, help: &str) {
+    fn error_with_help(&mut self, code: ErrorCode, msg: &str, help: &str) {
         self.diagnostics.push(
-            Diagnostic::error(msg.to_string(), self.current_span()).with_help(help.to_string()),
+            Diagnostic::error(msg.to_string(), self.current_span())
+                .with_code(code)
+                .with_help(help.to_string()),
         );
     }
 
@@ -201,6 +194,10 @@ impl Parser {
         while self.eat(&Lexeme::Dot) {
             if let Some(ident) = self.try_ident() {
                 parts.push(ident.node);
+            } else if let Lexeme::Integer(n) = *self.peek() {
+                // `.0` of a tuple struct
+                self.advance();
+                parts.push(n.to_string());
             } else {
                 break;
             }
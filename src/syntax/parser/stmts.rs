@@ -1,4 +1,5 @@
 use crate::ast::*;
+use crate::diagnostic::ErrorCode;
 use crate::lexeme::Lexeme;
 use crate::span::Spanned;
 
@@ -33,8 +34,16 @@ impl Parser {
                 stmts.push(self.parse_if_stmt());
             } else if self.at(&Lexeme::For) {
                 stmts.push(self.parse_for_stmt());
+            } else if self.at(&Lexeme::While) {
+                stmts.push(self.parse_while_stmt());
             } else if self.at(&Lexeme::Return) {
                 stmts.push(self.parse_return_stmt());
+            } else if self.at(&Lexeme::Break) {
+                let span = self.advance().span;
+                stmts.push(Spanned::new(Stmt::Break, span));
+            } else if self.at(&Lexeme::Continue) {
+                let span = self.advance().span;
+                stmts.push(Spanned::new(Stmt::Continue, span));
             } else if self.at(&Lexeme::Reveal) {
                 stmts.push(self.parse_reveal_stmt());
             } else if self.at(&Lexeme::Seal) {
@@ -202,7 +211,7 @@ impl Parser {
         let range_end = self.parse_expr();
 
         let bound = if self.eat(&Lexeme::Bounded) {
-            Some(self.expect_integer())
+            Some(self.parse_expr())
         } else {
             None
         };
@@ -221,6 +230,27 @@ impl Parser {
         )
     }
 
+    fn parse_while_stmt(&mut self) -> Spanned<Stmt> {
+        let start = self.current_span();
+        self.expect(&Lexeme::While);
+        let cond = self.parse_expr();
+
+        let bound = if self.eat(&Lexeme::Bounded) {
+            self.parse_expr()
+        } else {
+            self.error_with_help(
+                ErrorCode::UnexpectedToken,
+                &format!("expected 'bounded', found {}", self.peek().description()),
+                "every `while` loop needs an iteration bound: `while cond bounded 16 { ... }`",
+            );
+            Spanned::new(Expr::Literal(Literal::Integer(0)), self.current_span())
+        };
+
+        let body = self.parse_block();
+        let span = start.merge(self.prev_span());
+        Spanned::new(Stmt::While { cond, bound, body }, span)
+    }
+
     fn parse_return_stmt(&mut self) -> Spanned<Stmt> {
         let start = self.current_span();
         self.expect(&Lexeme::Return);
@@ -280,10 +310,15 @@ impl Parser {
                 && matches!(self.tokens[self.pos + 1].node, Lexeme::LBrace)
             {
                 self.parse_struct_match_pattern()
+            } else if matches!(self.peek(), Lexeme::Ident(_))
+                && matches!(self.tokens[self.pos + 1].node, Lexeme::Dot)
+            {
+                self.parse_variant_match_pattern()
             } else {
                 self.error_with_help(
-                    "expected match pattern (integer, true, false, StructName { ... }, or _)",
-                    "match arms use literal patterns like `0 =>`, `true =>`, struct patterns like `Point { x, y } =>`, or wildcard `_ =>`",
+                    ErrorCode::ExpectedPattern,
+                    "expected match pattern (integer, true, false, StructName { ... }, Enum.Variant, or _)",
+                    "match arms use literal patterns like `0 =>`, `true =>`, struct patterns like `Point { x, y } =>`, variant patterns like `Shape.Circle(r) =>`, or wildcard `_ =>`",
                 );
                 self.advance();
                 MatchPattern::Wildcard
@@ -335,6 +370,7 @@ impl Parser {
                     FieldPattern::Binding(binding.node)
                 } else {
                     self.error_with_help(
+                        ErrorCode::ExpectedPattern,
                         "expected field pattern (identifier, literal, or _)",
                         "use `field: var` to bind, `field: 0` to match, or `field: _` to ignore",
                     );
@@ -361,4 +397,36 @@ impl Parser {
         self.expect(&Lexeme::RBrace);
         MatchPattern::Struct { name, fields }
     }
+
+    /// Parse an enum variant pattern: `Shape.Empty` or `Shape.Rect(w, _)`.
+    fn parse_variant_match_pattern(&mut self) -> MatchPattern {
+        let start = self.current_span();
+        let path = self.parse_module_path();
+        let path = Spanned::new(path, start.merge(self.prev_span()));
+
+        let mut bindings = Vec::new();
+        if self.eat(&Lexeme::LParen) {
+            while !self.at(&Lexeme::RParen) && !self.at(&Lexeme::Eof) {
+                if self.at(&Lexeme::Underscore) {
+                    let span = self.current_span();
+                    self.advance();
+                    bindings.push(Spanned::new("_".to_string(), span));
+                } else if matches!(self.peek(), Lexeme::Ident(_)) {
+                    bindings.push(self.expect_ident());
+                } else {
+                    self.error_with_help(
+                        ErrorCode::ExpectedPattern,
+                        "expected variant binding (identifier or _)",
+                        "bind each payload element to a name, or ignore it with `_`",
+                    );
+                    self.advance();
+                }
+                if !self.eat(&Lexeme::Comma) {
+                    break;
+                }
+            }
+            self.expect(&Lexeme::RParen);
+        }
+        MatchPattern::Variant { path, bindings }
+    }
 }
@@ -1,9 +1,36 @@
-use crate::syntax::parser::Parser;
 use crate::ast::*;
 use crate::lexer::Lexer;
+use crate::syntax::parser::Parser;
 
 use super::parse;
 
+#[test]
+fn test_tuple_and_unit_structs() {
+    let file = parse(
+        "program test\nstruct Amount(pub Field)\nstruct Marker\nfn main() {\n    let a = Amount(1)\n    pub_write(a.0)\n}",
+    );
+    let Item::Struct(amount) = &file.items[0].node else {
+        panic!("expected struct");
+    };
+    assert_eq!(amount.kind, StructKind::Tuple);
+    assert_eq!(amount.fields.len(), 1);
+    assert_eq!(amount.fields[0].name.node, "0");
+    assert!(amount.fields[0].is_pub);
+    let Item::Struct(marker) = &file.items[1].node else {
+        panic!("expected struct");
+    };
+    assert_eq!(marker.kind, StructKind::Unit);
+    assert!(marker.fields.is_empty());
+    let Item::Fn(f) = &file.items[2].node else {
+        panic!("expected fn");
+    };
+    let call = f.body.as_ref().unwrap().node.tail_expr.as_ref().unwrap();
+    let Expr::Call { args, .. } = &call.node else {
+        panic!("expected call");
+    };
+    assert!(matches!(&args[0].node, Expr::Var(name) if name == "a.0"));
+}
+
 #[test]
 fn test_match_struct_pattern() {
     let file = parse(
@@ -73,6 +100,41 @@ fn test_match_struct_pattern_with_wildcard_field() {
     }
 }
 
+#[test]
+fn test_enum_and_variant_patterns() {
+    let file = parse(
+        "program test\npub enum Shape {\n    Empty,\n    Rect(Field, U32),\n}\nfn main() {\n    let s = Shape.Rect(1, 2)\n    match s {\n        Shape.Empty => {}\n        Shape.Rect(w, _) => { pub_write(w) }\n    }\n}",
+    );
+    let Item::Enum(shape) = &file.items[0].node else {
+        panic!("expected enum");
+    };
+    assert!(shape.is_pub);
+    assert_eq!(shape.name.node, "Shape");
+    assert_eq!(shape.variants.len(), 2);
+    assert!(shape.variants[0].fields.is_empty());
+    assert_eq!(shape.variants[1].name.node, "Rect");
+    assert_eq!(shape.variants[1].fields[1].node, Type::U32);
+
+    let Item::Fn(f) = &file.items[1].node else {
+        panic!("expected fn");
+    };
+    let block = &f.body.as_ref().unwrap().node;
+    let Stmt::Match { arms, .. } = &block.stmts[1].node else {
+        panic!("expected match statement");
+    };
+    let MatchPattern::Variant { path, bindings } = &arms[0].pattern.node else {
+        panic!("expected variant pattern");
+    };
+    assert_eq!(path.node.as_dotted(), "Shape.Empty");
+    assert!(bindings.is_empty());
+    let MatchPattern::Variant { path, bindings } = &arms[1].pattern.node else {
+        panic!("expected variant pattern");
+    };
+    assert_eq!(path.node.as_dotted(), "Shape.Rect");
+    let names: Vec<&str> = bindings.iter().map(|b| b.node.as_str()).collect();
+    assert_eq!(names, ["w", "_"]);
+}
+
 // --- #[test] attribute parsing ---
 
 #[test]
@@ -127,6 +189,79 @@ fn test_no_arg_attribute_format() {
     }
 }
 
+#[test]
+fn test_authenticated_attribute_on_fn() {
+    let file = parse(
+        "program test\n#[authenticated]\nfn leaf() -> Digest {\n    divine5()\n}\nfn main() {}",
+    );
+    if let Item::Fn(f) = &file.items[0].node {
+        assert!(f.is_authenticated);
+        assert!(!f.is_pure);
+    } else {
+        panic!("expected function");
+    }
+}
+
+#[test]
+fn test_auto_range_check_attribute_on_fn() {
+    let file = parse(
+        "program test\n#[auto_range_check]\nfn below(a: Field, b: Field) -> Bool {\n    a < b\n}\nfn main() {}",
+    );
+    if let Item::Fn(f) = &file.items[0].node {
+        assert!(f.auto_range_check);
+        assert!(!f.is_pure);
+    } else {
+        panic!("expected function");
+    }
+}
+
+#[test]
+fn test_event_version_and_deprecated_attributes() {
+    let file = parse(
+        "program test\n#[version(2)]\n#[deprecated(\"use \\\"Pay\\\"\")]\nevent Transfer { amount: Field }\nevent Pay { amount: Field }\nfn main() {}",
+    );
+    match (&file.items[0].node, &file.items[1].node) {
+        (Item::Event(old), Item::Event(new)) => {
+            assert_eq!(old.version, 2);
+            assert_eq!(old.deprecated.as_ref().unwrap().node, "use \"Pay\"");
+            assert_eq!(old.tag(0), 1 << 32);
+            assert_eq!(new.version, 1);
+            assert!(new.deprecated.is_none());
+            assert_eq!(new.tag(1), 1);
+        }
+        _ => panic!("expected events"),
+    }
+}
+
+#[test]
+fn test_entrypoint_attributes_on_fns() {
+    let file = parse("program test\n#[entrypoint]\nfn pay() {}\n#[entrypoint(7)]\nfn mint() {}");
+    match (&file.items[0].node, &file.items[1].node) {
+        (Item::Fn(pay), Item::Fn(mint)) => {
+            assert_eq!(pay.entrypoint.as_ref().unwrap().node, "entrypoint");
+            assert_eq!(mint.entrypoint.as_ref().unwrap().node, "entrypoint(7)");
+        }
+        _ => panic!("expected functions"),
+    }
+}
+
+#[test]
+fn test_assume_attribute_parses_an_expression() {
+    let file = parse(
+        "program test\n#[assume(n < 10)]\n#[assume(ok)]\nfn step(n: U32, ok: Bool) {}\nfn main() {}",
+    );
+    if let Item::Fn(f) = &file.items[0].node {
+        assert_eq!(f.assumes.len(), 2);
+        assert!(matches!(
+            f.assumes[0].node,
+            Expr::BinOp { op: BinOp::Lt, .. }
+        ));
+        assert!(matches!(&f.assumes[1].node, Expr::Var(name) if name == "ok"));
+    } else {
+        panic!("expected function");
+    }
+}
+
 // --- Error path tests ---
 
 fn parse_err(source: &str) -> Vec<crate::diagnostic::Diagnostic> {
@@ -155,6 +290,21 @@ fn test_error_missing_program_or_module() {
     );
 }
 
+#[test]
+fn test_error_codes() {
+    use crate::diagnostic::ErrorCode;
+    let code = |source: &str| parse_err(source)[0].code;
+    assert_eq!(code("fn main() {}"), Some(ErrorCode::MissingHeader));
+    assert_eq!(
+        code("program test\nfn main( {}"),
+        Some(ErrorCode::UnexpectedToken)
+    );
+    assert_eq!(
+        code("program test\n#[pure]\nstruct P { x: Field }"),
+        Some(ErrorCode::MisplacedAttribute)
+    );
+}
+
 #[test]
 fn test_error_missing_closing_brace() {
     let diags = parse_err("program test\nfn main() {");
@@ -232,6 +382,24 @@ fn test_error_expected_token_shows_found() {
     );
 }
 
+#[test]
+fn test_error_while_without_bound() {
+    let diags = parse_err("program test\nfn main() {\n    let x: Bool = true\n    while x {\n        pub_write(0)\n    }\n}");
+    assert!(
+        !diags.is_empty(),
+        "should error on a while loop without a bound"
+    );
+    assert!(
+        diags[0].message.contains("expected 'bounded'"),
+        "should ask for the bound, got: {}",
+        diags[0].message
+    );
+    assert!(
+        diags[0].help.as_deref().unwrap().contains("bounded 16"),
+        "help should show a bounded while loop"
+    );
+}
+
 #[test]
 fn test_error_expected_item() {
     let diags = parse_err("program test\n42");
@@ -347,3 +515,61 @@ fn test_parse_generic_call_size_expr() {
         other => panic!("expected Call, got {:?}", other),
     }
 }
+
+#[test]
+fn test_error_event_attributes_outside_events() {
+    let diags = parse_err("program test\n#[version(2)]\nfn main() {}");
+    assert!(
+        diags
+            .iter()
+            .any(|d| d.message.contains("#[version] is only allowed on events")),
+        "{:?}",
+        diags
+    );
+    let diags = parse_err("program test\n#[deprecated]\nstruct S { x: Field }\nfn main() {}");
+    assert!(diags.iter().any(|d| d
+        .message
+        .contains("#[deprecated] is only allowed on events")));
+}
+
+#[test]
+fn test_error_invalid_event_version() {
+    for version in ["0", "65536", "two"] {
+        let diags = parse_err(&format!(
+            "program test\n#[version({})]\nevent E {{ x: Field }}\nfn main() {{}}",
+            version
+        ));
+        assert!(
+            diags
+                .iter()
+                .any(|d| d.message.contains("invalid event version")),
+            "{}: {:?}",
+            version,
+            diags
+        );
+    }
+}
+
+#[test]
+fn test_error_entrypoint_outside_fns() {
+    let diags = parse_err("program test\n#[entrypoint]\nstruct S { x: Field }\nfn main() {}");
+    assert!(
+        diags.iter().any(|d| d
+            .message
+            .contains("#[entrypoint] is only allowed on functions")),
+        "{:?}",
+        diags
+    );
+}
+
+#[test]
+fn test_error_main_beside_entrypoints() {
+    let diags = parse_err("program test\n#[entrypoint]\nfn pay() {}\nfn main() {}");
+    assert!(
+        diags
+            .iter()
+            .any(|d| d.message.contains("cannot define main")),
+        "{:?}",
+        diags
+    );
+}
@@ -274,6 +274,55 @@ fn test_no_cfg() {
     }
 }
 
+#[test]
+fn test_while_statement() {
+    let file = parse("program test\nfn main() {\n    let mut i: U32 = 0\n    while i < 4 bounded 8 {\n        i = i + 1\n    }\n}");
+    if let Item::Fn(f) = &file.items[0].node {
+        let block = f.body.as_ref().unwrap();
+        if let Stmt::While { cond, bound, body } = &block.node.stmts[1].node {
+            assert!(matches!(cond.node, Expr::BinOp { op: BinOp::Lt, .. }));
+            assert_eq!(integer_literal(&bound.node), Some(8));
+            assert_eq!(body.node.stmts.len(), 1);
+        } else {
+            panic!("expected while statement");
+        }
+    }
+}
+
+#[test]
+fn test_bound_is_an_expression() {
+    let file = parse("program test\nfn main() {\n    for i in 0..n bounded N * 2 {\n        pub_write(i)\n    }\n}");
+    if let Item::Fn(f) = &file.items[0].node {
+        let block = f.body.as_ref().unwrap();
+        if let Stmt::For {
+            bound: Some(bound), ..
+        } = &block.node.stmts[0].node
+        {
+            assert!(matches!(bound.node, Expr::BinOp { op: BinOp::Mul, .. }));
+        } else {
+            panic!("expected bounded for statement");
+        }
+    }
+}
+
+#[test]
+fn test_break_and_continue() {
+    let file = parse("program test\nfn main() {\n    for i in 0..4 bounded 4 {\n        if i == 1 {\n            continue\n        }\n        break\n    }\n}");
+    if let Item::Fn(f) = &file.items[0].node {
+        let block = f.body.as_ref().unwrap();
+        if let Stmt::For { body, .. } = &block.node.stmts[0].node {
+            assert!(matches!(body.node.stmts[1].node, Stmt::Break));
+            if let Stmt::If { then_block, .. } = &body.node.stmts[0].node {
+                assert!(matches!(then_block.node.stmts[0].node, Stmt::Continue));
+            } else {
+                panic!("expected if statement");
+            }
+        } else {
+            panic!("expected for statement");
+        }
+    }
+}
+
 // --- match statement parsing ---
 
 #[test]
@@ -1,4 +1,5 @@
 use crate::ast::*;
+use crate::diagnostic::ErrorCode;
 use crate::lexeme::Lexeme;
 use crate::span::Spanned;
 
@@ -51,6 +52,7 @@ impl Parser {
             }
             _ => {
                 self.error_with_help(
+                    ErrorCode::ExpectedType,
                     "expected type",
                     "valid types are: Field, XField, Bool, U32, Digest, [T; N], (T, U), or a struct name",
                 );
@@ -100,6 +102,7 @@ impl Parser {
             inner
         } else {
             self.error_with_help(
+                ErrorCode::ExpectedType,
                 "expected array size (integer literal or size parameter name)",
                 "array sizes are written as `N`, `3`, `M + N`, or `N * 2`",
             );
@@ -0,0 +1,205 @@
+//! Comments attached to the tokens around them.
+//!
+//! The lexer hands comments back as a flat list beside the tokens. A
+//! `TriviaMap` attaches each one to a token: a comment that follows code on
+//! its line trails the last token before it, every other comment leads the
+//! next token. Leading comments also record whether a blank line separates
+//! them from what comes before and after, which is what the formatter needs
+//! to put them back where they were and what tells a `///` block that
+//! documents an item from one that merely sits above it.
+
+use std::collections::BTreeMap;
+
+use crate::lexeme::Lexeme;
+use crate::lexer::{Comment, Lexer};
+use crate::span::Spanned;
+
+/// A comment on its own line, leading the token after it.
+#[derive(Clone, Debug)]
+pub struct Trivia {
+    pub comment: Comment,
+    /// A blank line separates the comment from the code or comment above.
+    pub blank_before: bool,
+    /// A blank line separates the comment from the code or comment below.
+    pub blank_after: bool,
+}
+
+/// Comments of one source file, keyed by the token they belong to.
+#[derive(Clone, Debug, Default)]
+pub struct TriviaMap {
+    /// Leading comments, keyed by the start of the token they precede.
+    leading: BTreeMap<u32, Vec<Trivia>>,
+    /// Trailing comments, keyed by the end of the token they follow.
+    trailing: BTreeMap<u32, Comment>,
+    line_starts: Vec<u32>,
+}
+
+impl TriviaMap {
+    /// Attach `comments` to `tokens`, both as returned by the lexer.
+    pub fn build(source: &str, tokens: &[Spanned<Lexeme>], comments: &[Comment]) -> Self {
+        let blank_between = |from: u32, to: u32| {
+            let (from, to) = (from as usize, (to as usize).min(source.len()));
+            from < to
+                && source.as_bytes()[from..to]
+                    .iter()
+                    .filter(|&&b| b == b'\n')
+                    .count()
+                    >= 2
+        };
+        let mut map = TriviaMap {
+            line_starts: std::iter::once(0)
+                .chain(
+                    source
+                        .bytes()
+                        .enumerate()
+                        .filter(|(_, b)| *b == b'\n')
+                        .map(|(i, _)| i as u32 + 1),
+                )
+                .collect(),
+            ..Self::default()
+        };
+        for (i, c) in comments.iter().enumerate() {
+            let before = tokens.partition_point(|t| t.span.end <= c.span.start);
+            let prev_token = before.checked_sub(1).map(|p| &tokens[p]);
+            if c.trailing {
+                if let Some(t) = prev_token {
+                    map.trailing.insert(t.span.end, c.clone());
+                    continue;
+                }
+            }
+            let above = prev_token.map_or(0, |t| t.span.end);
+            let above = match i.checked_sub(1).map(|p| &comments[p]) {
+                Some(p) if p.span.end <= c.span.start => above.max(p.span.end),
+                _ => above,
+            };
+            let Some(next) = tokens[before..].iter().find(|t| t.span.start >= c.span.end) else {
+                continue;
+            };
+            let below = match comments.get(i + 1) {
+                Some(n) if n.span.start < next.span.start => n.span.start,
+                _ => next.span.start,
+            };
+            map.leading
+                .entry(next.span.start)
+                .or_default()
+                .push(Trivia {
+                    comment: c.clone(),
+                    blank_before: blank_between(above, c.span.start),
+                    blank_after: blank_between(c.span.end, below),
+                });
+        }
+        map
+    }
+
+    /// Lex `source` and attach its comments.
+    pub fn from_source(source: &str) -> Self {
+        let (tokens, comments, _) = Lexer::new(source, 0).tokenize();
+        Self::build(source, &tokens, &comments)
+    }
+
+    /// Comments on their own lines before the token starting at `token_start`.
+    pub fn leading(&self, token_start: u32) -> &[Trivia] {
+        self.leading.get(&token_start).map_or(&[], Vec::as_slice)
+    }
+
+    /// The comment after the token ending at `token_end`, on the same line.
+    #[cfg(test)]
+    pub fn trailing(&self, token_end: u32) -> Option<&Comment> {
+        self.trailing.get(&token_end)
+    }
+
+    /// All leading comments with the token start they are keyed by.
+    pub fn all_leading(&self) -> impl Iterator<Item = (u32, &Trivia)> {
+        self.leading
+            .iter()
+            .flat_map(|(&at, group)| group.iter().map(move |t| (at, t)))
+    }
+
+    /// All trailing comments with the token end they are keyed by.
+    pub fn all_trailing(&self) -> impl Iterator<Item = (u32, &Comment)> {
+        self.trailing.iter().map(|(&at, c)| (at, c))
+    }
+
+    /// Zero-based line of a byte offset.
+    pub fn line_of(&self, offset: u32) -> usize {
+        self.line_starts
+            .partition_point(|&s| s <= offset)
+            .saturating_sub(1)
+    }
+
+    /// The `///` doc comment of the item whose first token starts at
+    /// `token_start`, with the markers stripped, one line per comment line.
+    ///
+    /// Only the `///` lines directly above the item count: a blank line or
+    /// a plain `//` comment in between detaches whatever is above it.
+    pub fn doc_comment(&self, token_start: u32) -> Option<String> {
+        let leading = self.leading(token_start);
+        let mut first = leading.len();
+        while first > 0 {
+            let t = &leading[first - 1];
+            if !t.comment.is_doc() || t.blank_after {
+                break;
+            }
+            first -= 1;
+            if t.blank_before {
+                break;
+            }
+        }
+        if first == leading.len() {
+            return None;
+        }
+        let lines: Vec<&str> = leading[first..]
+            .iter()
+            .map(|t| {
+                let text = &t.comment.text[3..];
+                text.strip_prefix(' ').unwrap_or(text).trim_end()
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(trivia: &[Trivia]) -> Vec<(&str, bool, bool)> {
+        trivia
+            .iter()
+            .map(|t| (t.comment.text.as_str(), t.blank_before, t.blank_after))
+            .collect()
+    }
+
+    #[test]
+    fn comments_attach_to_neighbouring_tokens() {
+        let source =
+            "// header\n\n// about f\nfn f() { // open\n    g() // call\n    // closing\n}\n";
+        let map = TriviaMap::from_source(source);
+        let at = |needle: &str| source.find(needle).unwrap() as u32;
+
+        assert_eq!(
+            texts(map.leading(at("fn"))),
+            vec![("// header", false, true), ("// about f", true, false)]
+        );
+        assert_eq!(map.trailing(at("{") + 1).unwrap().text, "// open");
+        assert_eq!(map.trailing(at("g()") + 3).unwrap().text, "// call");
+        assert_eq!(
+            texts(map.leading(at("}"))),
+            vec![("// closing", false, false)]
+        );
+        assert_eq!(map.line_of(at("g()")), 4);
+    }
+
+    #[test]
+    fn doc_comment_stops_at_blank_lines_and_plain_comments() {
+        let source = "/// Adds one.\n///\n///   Indented.\nfn inc() {}\n\n/// Detached.\n\nfn a() {}\n/// Lost.\n// plain\nfn b() {}\n// plain\n/// Kept.\n#[cfg(x)]\nfn c() {}\n//// not doc\nfn d() {}\n";
+        let map = TriviaMap::from_source(source);
+        let doc = |needle: &str| map.doc_comment(source.find(needle).unwrap() as u32);
+
+        assert_eq!(doc("fn inc").as_deref(), Some("Adds one.\n\n  Indented."));
+        assert_eq!(doc("fn a"), None);
+        assert_eq!(doc("fn b"), None);
+        assert_eq!(doc("#[cfg").as_deref(), Some("Kept."));
+        assert_eq!(doc("fn d"), None);
+    }
+}
@@ -0,0 +1,406 @@
+//! RAM aliasing: one mutable handle per RAM region per scope.
+//!
+//! A handle is an immutable `let` bound to a statically known address
+//! (`let buf = 100`, `let slot = BASE + 2`). Addresses derived from a
+//! handle (`let hi = buf + 5`) reborrow it rather than creating a new
+//! one. Within a scope, every word of RAM may be written through one
+//! handle only; reads are shared and unrestricted. On top of that, no
+//! write may touch a `sec ram` slot — the verifier treats those words as
+//! prover-supplied input. Accesses through addresses that are not
+//! statically known are not checked.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::ast::*;
+use crate::diagnostic::{Diagnostic, ErrorCode};
+use crate::span::Span;
+
+use super::TypeChecker;
+
+/// Builtins that access RAM, by their intrinsic name.
+pub(super) const RAM_BUILTINS: [&str; 4] =
+    ["ram_read", "ram_write", "ram_read_block", "ram_write_block"];
+
+#[derive(Clone)]
+struct Handle {
+    addr: u64,
+    /// Binding that owns the region: (name, unique binding id).
+    root: (String, usize),
+}
+
+struct Write {
+    root: (String, usize),
+    start: u64,
+    end: u64,
+}
+
+#[derive(Default)]
+struct Scope {
+    /// `None` shadows an outer handle with a value of unknown address.
+    vars: BTreeMap<String, Option<Handle>>,
+    bindings: BTreeSet<usize>,
+    writes: Vec<Write>,
+}
+
+/// A `sec ram` slot: first word, width, declared type, declaration span.
+type SecSlot = (u64, u64, String, Span);
+
+struct AliasWalker<'a> {
+    constants: &'a BTreeMap<String, u64>,
+    ram_access: &'a BTreeMap<String, String>,
+    sec_ram: &'a [SecSlot],
+    digest_width: u64,
+    scopes: Vec<Scope>,
+    next_binding: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl TypeChecker {
+    /// Check RAM accesses with statically known addresses for aliasing.
+    pub(super) fn check_ram_aliasing(&mut self, file: &File) {
+        for item in &file.items {
+            if let Item::Fn(func) = &item.node {
+                if let Some(intrinsic) = &func.intrinsic {
+                    let name = intrinsic
+                        .node
+                        .trim_start_matches("intrinsic(")
+                        .trim_end_matches(')');
+                    if RAM_BUILTINS.contains(&name) {
+                        self.ram_access
+                            .insert(func.name.node.clone(), name.to_string());
+                    }
+                }
+            }
+        }
+
+        let mut sec_ram: Vec<SecSlot> = Vec::new();
+        for decl in &file.declarations {
+            if let Declaration::SecRam(entries) = decl {
+                for (addr, ty) in entries {
+                    let resolved = self.resolve_type(&ty.node);
+                    let width = resolved.width() as u64;
+                    sec_ram.push((*addr, width, resolved.display(), ty.span));
+                }
+            }
+        }
+        for (i, &(a, aw, ref aty, _)) in sec_ram.iter().enumerate() {
+            for &(b, bw, _, span) in &sec_ram[i + 1..] {
+                if a < b.saturating_add(bw) && b < a.saturating_add(aw) {
+                    self.error_with_help(
+                        ErrorCode::RamAliasing,
+                        format!(
+                            "sec ram slot at {} overlaps the slot at {} ({}, words {}..{})",
+                            b,
+                            a,
+                            aty,
+                            a,
+                            a.saturating_add(aw)
+                        ),
+                        span,
+                        "each sec ram word holds one prover-supplied value; move one slot past the other".to_string(),
+                    );
+                }
+            }
+        }
+
+        let mut walker = AliasWalker {
+            constants: &self.constants,
+            ram_access: &self.ram_access,
+            sec_ram: &sec_ram,
+            digest_width: self.target_config.digest_width as u64,
+            scopes: Vec::new(),
+            next_binding: 0,
+            diagnostics: Vec::new(),
+        };
+        for item in &file.items {
+            if let Item::Fn(func) = &item.node {
+                if let Some(body) = &func.body {
+                    walker.scopes.push(Scope::default());
+                    for param in &func.params {
+                        walker.shadow(&param.name.node);
+                    }
+                    walker.block(&body.node);
+                    walker.scopes.clear();
+                }
+            }
+        }
+        let diagnostics = walker.diagnostics;
+        self.diagnostics.extend(diagnostics);
+    }
+
+    /// Exported functions that access RAM, with the builtin they wrap.
+    pub(super) fn ram_exports(&self, file: &File) -> Vec<(String, String)> {
+        file.items
+            .iter()
+            .filter_map(|item| match &item.node {
+                Item::Fn(func) if func.is_pub => self
+                    .ram_access
+                    .get(&func.name.node)
+                    .map(|b| (func.name.node.clone(), b.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl AliasWalker<'_> {
+    fn block(&mut self, block: &Block) {
+        for stmt in &block.stmts {
+            self.stmt(&stmt.node);
+        }
+        if let Some(tail) = &block.tail_expr {
+            self.expr(&tail.node, tail.span);
+        }
+    }
+
+    fn scoped_block(&mut self, block: &Block, bound: &[&str]) {
+        self.scopes.push(Scope::default());
+        for name in bound {
+            self.shadow(name);
+        }
+        self.block(block);
+        if let Some(scope) = self.scopes.pop() {
+            // Writes through handles that outlive the scope stay visible.
+            if let Some(parent) = self.scopes.last_mut() {
+                parent.writes.extend(
+                    scope
+                        .writes
+                        .into_iter()
+                        .filter(|w| !scope.bindings.contains(&w.root.1)),
+                );
+            }
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Let {
+                mutable,
+                pattern,
+                init,
+                ..
+            } => {
+                self.expr(&init.node, init.span);
+                match pattern {
+                    Pattern::Name(name) => {
+                        let addr = self.address(&init.node);
+                        match addr {
+                            Some((addr, root)) if !*mutable => self.bind(&name.node, addr, root),
+                            _ => self.shadow(&name.node),
+                        }
+                    }
+                    Pattern::Tuple(names) => {
+                        for name in names {
+                            self.shadow(&name.node);
+                        }
+                    }
+                }
+            }
+            Stmt::Assign { value, .. } | Stmt::TupleAssign { value, .. } => {
+                self.expr(&value.node, value.span)
+            }
+            Stmt::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                self.expr(&cond.node, cond.span);
+                self.scoped_block(&then_block.node, &[]);
+                if let Some(else_block) = else_block {
+                    self.scoped_block(&else_block.node, &[]);
+                }
+            }
+            Stmt::For {
+                var,
+                start,
+                end,
+                body,
+                ..
+            } => {
+                self.expr(&start.node, start.span);
+                self.expr(&end.node, end.span);
+                self.scoped_block(&body.node, &[&var.node]);
+            }
+            Stmt::While { cond, body, .. } => {
+                self.expr(&cond.node, cond.span);
+                self.scoped_block(&body.node, &[]);
+            }
+            Stmt::Expr(expr) => self.expr(&expr.node, expr.span),
+            Stmt::Return(value) => {
+                if let Some(value) = value {
+                    self.expr(&value.node, value.span);
+                }
+            }
+            Stmt::Reveal { fields, .. } | Stmt::Seal { fields, .. } => {
+                for (_, value) in fields {
+                    self.expr(&value.node, value.span);
+                }
+            }
+            Stmt::Break | Stmt::Continue | Stmt::Asm { .. } => {}
+            Stmt::Match { expr, arms } => {
+                self.expr(&expr.node, expr.span);
+                for arm in arms {
+                    let mut bound = Vec::new();
+                    if let MatchPattern::Struct { fields, .. } = &arm.pattern.node {
+                        for field in fields {
+                            if let FieldPattern::Binding(name) = &field.pattern.node {
+                                bound.push(name.as_str());
+                            }
+                        }
+                    }
+                    self.scoped_block(&arm.body.node, &bound);
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr, span: Span) {
+        match expr {
+            Expr::Literal(_) | Expr::Var(_) => {}
+            Expr::BinOp { lhs, rhs, .. } => {
+                self.expr(&lhs.node, lhs.span);
+                self.expr(&rhs.node, rhs.span);
+            }
+            Expr::Call { path, args, .. } => {
+                for arg in args {
+                    self.expr(&arg.node, arg.span);
+                }
+                let name = path.node.as_dotted();
+                if let (Some(builtin), Some(addr)) = (self.ram_access.get(&name), args.first()) {
+                    if let Some((start, root)) = self.address(&addr.node) {
+                        self.access(builtin, start, root, span);
+                    }
+                }
+            }
+            Expr::FieldAccess { expr, .. } => self.expr(&expr.node, expr.span),
+            Expr::Index { expr, index } => {
+                self.expr(&expr.node, expr.span);
+                self.expr(&index.node, index.span);
+            }
+            Expr::StructInit { fields, base, .. } => {
+                for (_, value) in fields {
+                    self.expr(&value.node, value.span);
+                }
+                if let Some(base) = base {
+                    self.expr(&base.node, base.span);
+                }
+            }
+            Expr::ArrayInit(elems) | Expr::Tuple(elems) => {
+                for elem in elems {
+                    self.expr(&elem.node, elem.span);
+                }
+            }
+        }
+    }
+
+    fn access(&mut self, builtin: &str, start: u64, root: Option<(String, usize)>, span: Span) {
+        let width = if builtin.ends_with("_block") {
+            self.digest_width
+        } else {
+            1
+        };
+        let end = start.saturating_add(width);
+        if !builtin.starts_with("ram_write") {
+            return;
+        }
+        for (slot, slot_width, ty, _) in self.sec_ram {
+            let slot_end = slot.saturating_add(*slot_width);
+            if start < slot_end && *slot < end {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "`{}` at address {} overwrites the sec ram slot at {} ({}, words {}..{})",
+                            builtin, start, slot, ty, slot, slot_end
+                        ),
+                        span,
+                    )
+                    .with_code(ErrorCode::RamAliasing)
+                    .with_help(
+                        "sec ram words are prover-supplied input the verifier reasons about; \
+                         copy the value to an address outside the declared slots"
+                            .to_string(),
+                    ),
+                );
+                return;
+            }
+        }
+        let Some(root) = root else {
+            return;
+        };
+        let conflict = self
+            .scopes
+            .iter()
+            .flat_map(|s| &s.writes)
+            .find(|w| w.root.1 != root.1 && start < w.end && w.start < end);
+        if let Some(other) = conflict {
+            let (lo, hi) = (start.max(other.start), end.min(other.end));
+            self.diagnostics.push(
+                Diagnostic::error(
+                    format!(
+                        "RAM words {}..{} are written through both `{}` and `{}` in this scope",
+                        lo, hi, other.root.0, root.0
+                    ),
+                    span,
+                )
+                .with_code(ErrorCode::RamAliasing)
+                .with_help(format!(
+                    "a RAM region has one mutable handle per scope; derive the address from `{}` \
+                     (e.g. `{} + {}`) so both writes go through the same handle",
+                    other.root.0,
+                    other.root.0,
+                    start.saturating_sub(other.start)
+                )),
+            );
+            return;
+        }
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.writes.push(Write { root, start, end });
+        }
+    }
+
+    /// Statically known address of `expr`, with the handle it is taken through.
+    fn address(&self, expr: &Expr) -> Option<(u64, Option<(String, usize)>)> {
+        match expr {
+            Expr::Literal(Literal::Integer(n)) => Some((*n, None)),
+            Expr::Var(name) => {
+                for scope in self.scopes.iter().rev() {
+                    if let Some(var) = scope.vars.get(name) {
+                        return var.as_ref().map(|h| (h.addr, Some(h.root.clone())));
+                    }
+                }
+                self.constants.get(name).map(|v| (*v, None))
+            }
+            Expr::BinOp {
+                op: BinOp::Add,
+                lhs,
+                rhs,
+            } => {
+                let (a, ra) = self.address(&lhs.node)?;
+                let (b, rb) = self.address(&rhs.node)?;
+                if ra.is_some() && rb.is_some() {
+                    return None;
+                }
+                Some((a.checked_add(b)?, ra.or(rb)))
+            }
+            _ => None,
+        }
+    }
+
+    fn bind(&mut self, name: &str, addr: u64, root: Option<(String, usize)>) {
+        let id = self.next_binding;
+        self.next_binding += 1;
+        let root = root.unwrap_or_else(|| (name.to_string(), id));
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.bindings.insert(id);
+            scope
+                .vars
+                .insert(name.to_string(), Some(Handle { addr, root }));
+        }
+    }
+
+    fn shadow(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.vars.insert(name.to_string(), None);
+        }
+    }
+}
@@ -1,8 +1,12 @@
-//! Static analysis: recursion detection, call graph collection, used-module tracking.
+//! Static analysis: recursion detection, call graph collection, used-module tracking,
+//! Merkle authentication, and construction of guarded structs.
 
 use std::collections::{BTreeMap, BTreeSet};
 
 use crate::ast::*;
+use crate::diagnostic::ErrorCode;
+use crate::span::Span;
+use crate::types::{StructTy, Ty};
 
 use super::TypeChecker;
 
@@ -49,6 +53,7 @@ impl TypeChecker {
                         })
                         .unwrap_or(file.name.span);
                     self.error_with_help(
+                        ErrorCode::Recursion,
                         format!("recursive call cycle detected: {}", path.join(" -> ")),
                         span,
                         "stack-machine targets do not support recursion; use loops (`for`) or iterative algorithms instead".to_string(),
@@ -104,8 +109,117 @@ impl TypeChecker {
         false
     }
 
+    /// Reject literals of structs that only their defining module may
+    /// create: `#[authenticated]` values come from authenticated accessors,
+    /// sponge state from `hash.init`.
+    pub(super) fn check_struct_construction(
+        &mut self,
+        struct_name: &str,
+        sty: &StructTy,
+        span: Span,
+    ) {
+        if self.authenticated_structs.contains(struct_name) {
+            if struct_name.contains('.') {
+                self.error_with_help(
+                    ErrorCode::AuthenticatedConstruction,
+                    format!(
+                        "cannot construct #[authenticated] struct '{}' outside its module",
+                        struct_name
+                    ),
+                    span,
+                    "obtain the value from an accessor of the defining module".to_string(),
+                );
+            } else if !self.in_authenticated_fn {
+                self.error_with_help(
+                    ErrorCode::AuthenticatedConstruction,
+                    format!(
+                        "#[authenticated] struct '{}' can only be constructed in an #[authenticated] function",
+                        struct_name
+                    ),
+                    span,
+                    "mark the function #[authenticated] and authenticate the data it divines"
+                        .to_string(),
+                );
+            }
+        }
+        if self.is_sponge_state(&Ty::Struct(sty.clone())) {
+            self.error_with_help(
+                ErrorCode::AuthenticatedConstruction,
+                format!("cannot construct sponge state '{}' directly", struct_name),
+                span,
+                "start a sponge with `hash.init()`".to_string(),
+            );
+        }
+    }
+
+    /// Infer which local functions perform Merkle authentication and
+    /// report `#[authenticated]` functions that never do.
+    ///
+    /// A function authenticates when it is an intrinsic for `merkle_step`
+    /// or `merkle_step_mem`, or when its body calls a function that
+    /// authenticates. Divined data returned by an `#[authenticated]`
+    /// accessor is therefore always checked against a Merkle root.
+    pub(super) fn check_authentication(&mut self, file: &File) {
+        let mut callees: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for item in &file.items {
+            if !self.is_item_cfg_active(&item.node) {
+                continue;
+            }
+            let Item::Fn(func) = &item.node else {
+                continue;
+            };
+            if let Some(intrinsic) = &func.intrinsic {
+                let name = intrinsic
+                    .node
+                    .trim_start_matches("intrinsic(")
+                    .trim_end_matches(')');
+                if self.authenticating.contains(name) {
+                    self.authenticating.insert(func.name.node.clone());
+                }
+            }
+            if let Some(body) = &func.body {
+                let mut calls = Vec::new();
+                Self::collect_calls_block(&body.node, &mut calls);
+                callees.insert(func.name.node.clone(), calls);
+            }
+        }
+
+        // Propagate through the call graph until nothing changes.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (name, calls) in &callees {
+                if !self.authenticating.contains(name)
+                    && calls.iter().any(|c| self.authenticating.contains(c))
+                {
+                    self.authenticating.insert(name.clone());
+                    changed = true;
+                }
+            }
+        }
+
+        for item in &file.items {
+            if !self.is_item_cfg_active(&item.node) {
+                continue;
+            }
+            if let Item::Fn(func) = &item.node {
+                if func.is_authenticated && !self.authenticating.contains(&func.name.node) {
+                    self.error_with_help(
+                        ErrorCode::MissingAuthentication,
+                        format!(
+                            "#[authenticated] function '{}' never performs Merkle authentication",
+                            func.name.node
+                        ),
+                        func.name.span,
+                        "authenticate divined data against its root with `merkle_step` or a function that calls it".to_string(),
+                    );
+                }
+            }
+        }
+    }
+
     /// Collect all function call names from a block.
-    pub(super) fn collect_calls_block(block: &Block, calls: &mut Vec<String>) {
+    pub(crate) fn collect_calls_block(block: &Block, calls: &mut Vec<String>) {
         for stmt in &block.stmts {
             Self::collect_calls_stmt(&stmt.node, calls);
         }
@@ -130,10 +244,22 @@ impl TypeChecker {
                 }
             }
             Stmt::For {
-                start, end, body, ..
+                start,
+                end,
+                bound,
+                body,
+                ..
             } => {
                 Self::collect_calls_expr(&start.node, calls);
                 Self::collect_calls_expr(&end.node, calls);
+                if let Some(bound) = bound {
+                    Self::collect_calls_expr(&bound.node, calls);
+                }
+                Self::collect_calls_block(&body.node, calls);
+            }
+            Stmt::While { cond, bound, body } => {
+                Self::collect_calls_expr(&cond.node, calls);
+                Self::collect_calls_expr(&bound.node, calls);
                 Self::collect_calls_block(&body.node, calls);
             }
             Stmt::TupleAssign { value, .. } => Self::collect_calls_expr(&value.node, calls),
@@ -145,7 +271,7 @@ impl TypeChecker {
                     Self::collect_calls_expr(&val.node, calls);
                 }
             }
-            Stmt::Asm { .. } => {}
+            Stmt::Break | Stmt::Continue | Stmt::Asm { .. } => {}
             Stmt::Match { expr, arms } => {
                 Self::collect_calls_expr(&expr.node, calls);
                 for arm in arms {
@@ -213,10 +339,22 @@ impl TypeChecker {
                 }
             }
             Stmt::For {
-                start, end, body, ..
+                start,
+                end,
+                bound,
+                body,
+                ..
             } => {
                 Self::collect_used_modules_expr(&start.node, used);
                 Self::collect_used_modules_expr(&end.node, used);
+                if let Some(bound) = bound {
+                    Self::collect_used_modules_expr(&bound.node, used);
+                }
+                Self::collect_used_modules_block(&body.node, used);
+            }
+            Stmt::While { cond, bound, body } => {
+                Self::collect_used_modules_expr(&cond.node, used);
+                Self::collect_used_modules_expr(&bound.node, used);
                 Self::collect_used_modules_block(&body.node, used);
             }
             Stmt::TupleAssign { value, .. } => Self::collect_used_modules_expr(&value.node, used),
@@ -228,7 +366,7 @@ impl TypeChecker {
                     Self::collect_used_modules_expr(&val.node, used);
                 }
             }
-            Stmt::Asm { .. } => {}
+            Stmt::Break | Stmt::Continue | Stmt::Asm { .. } => {}
             Stmt::Match { expr, arms } => {
                 Self::collect_used_modules_expr(&expr.node, used);
                 for arm in arms {
@@ -270,7 +408,7 @@ impl TypeChecker {
             Expr::FieldAccess { expr: inner, .. } | Expr::Index { expr: inner, .. } => {
                 Self::collect_used_modules_expr(&inner.node, used);
             }
-            Expr::StructInit { path, fields } => {
+            Expr::StructInit { path, fields, base } => {
                 let dotted = path.node.as_dotted();
                 if let Some(dot_pos) = dotted.rfind('.') {
                     let prefix = &dotted[..dot_pos];
@@ -279,6 +417,9 @@ impl TypeChecker {
                 for (_, val) in fields {
                     Self::collect_used_modules_expr(&val.node, used);
                 }
+                if let Some(base) = base {
+                    Self::collect_used_modules_expr(&base.node, used);
+                }
             }
             Expr::Literal(_) => {}
         }
@@ -1,6 +1,7 @@
 //! Statement type checking: check_fn, check_block, check_stmt, check_event_stmt, check_place.
 
 use crate::ast::*;
+use crate::diagnostic::ErrorCode;
 use crate::span::{Span, Spanned};
 use crate::types::Ty;
 
@@ -19,6 +20,7 @@ impl TypeChecker {
         if func.is_test {
             if !func.params.is_empty() {
                 self.error(
+                    ErrorCode::TestSignature,
                     format!(
                         "#[test] function '{}' must have no parameters",
                         func.name.node
@@ -28,6 +30,7 @@ impl TypeChecker {
             }
             if func.return_ty.is_some() {
                 self.error(
+                    ErrorCode::TestSignature,
                     format!(
                         "#[test] function '{}' must not have a return type",
                         func.name.node
@@ -39,45 +42,68 @@ impl TypeChecker {
 
         let prev_pure = self.in_pure_fn;
         self.in_pure_fn = func.is_pure;
+        self.in_authenticated_fn = func.is_authenticated;
 
+        self.begin_range_checks(func);
         self.push_scope();
 
         // Bind parameters
         for param in &func.params {
             let ty = self.resolve_type(&param.ty.node);
             self.define_var(&param.name.node, ty, false);
+            self.mark_range_checkable(param.name.span);
+        }
+
+        for pred in &func.assumes {
+            let ty = self.check_expr(&pred.node, pred.span);
+            if ty != Ty::Bool {
+                self.error(
+                    ErrorCode::ConditionType,
+                    format!("#[assume] predicate must be Bool, got {}", ty.display()),
+                    pred.span,
+                );
+            }
         }
 
         let body = func.body.as_ref().expect("guarded by is_none check above");
         self.check_block(&body.node);
 
         self.pop_scope();
+        self.finish_range_checks();
         self.in_pure_fn = prev_pure;
+        self.in_authenticated_fn = false;
     }
 
     pub(super) fn check_block(&mut self, block: &Block) -> Ty {
         self.push_scope();
-        let mut terminated = false;
+        self.check_asm_alternatives(&block.stmts);
+        // The statement that ends the block early, if any.
+        let mut terminated: Option<&str> = None;
         for stmt in &block.stmts {
-            if terminated {
+            if let Some(keyword) = terminated {
                 self.error_with_help(
-                    "unreachable code after return statement".to_string(),
+                    ErrorCode::Unreachable,
+                    format!("unreachable code after {} statement", keyword),
                     stmt.span,
-                    "remove this code or move it before the return".to_string(),
+                    format!("remove this code or move it before the {}", keyword),
                 );
                 break;
             }
             self.check_stmt(&stmt.node, stmt.span);
-            if self.is_terminating_stmt(&stmt.node) {
-                terminated = true;
-            }
+            terminated = match &stmt.node {
+                Stmt::Break => Some("break"),
+                Stmt::Continue => Some("continue"),
+                s if self.is_terminating_stmt(s) => Some("return"),
+                _ => None,
+            };
         }
-        if terminated {
+        if let Some(keyword) = terminated {
             if let Some(tail) = &block.tail_expr {
                 self.error_with_help(
-                    "unreachable tail expression after return".to_string(),
+                    ErrorCode::Unreachable,
+                    format!("unreachable tail expression after {}", keyword),
                     tail.span,
-                    "remove this expression or move it before the return".to_string(),
+                    format!("remove this expression or move it before the {}", keyword),
                 );
             }
         }
@@ -90,13 +116,62 @@ impl TypeChecker {
         ty
     }
 
+    /// Side-by-side `asm` blocks are alternatives: they must cover the
+    /// target, with a block tagged for it or an untagged fallback, and
+    /// agree on their stack effect.
+    fn check_asm_alternatives(&mut self, stmts: &[Spanned<Stmt>]) {
+        let target = self.target_config.name.clone();
+        for run in asm_alternatives(stmts) {
+            let span = stmts[run.start].span.merge(stmts[run.end - 1].span);
+            let blocks: Vec<(Option<&str>, i32)> = stmts[run]
+                .iter()
+                .filter_map(|s| match &s.node {
+                    Stmt::Asm { target, effect, .. } => Some((target.as_deref(), *effect)),
+                    _ => None,
+                })
+                .collect();
+            if !blocks
+                .iter()
+                .any(|(tag, _)| tag.is_none() || *tag == Some(target.as_str()))
+            {
+                let tags: Vec<&str> = blocks.iter().filter_map(|(tag, _)| *tag).collect();
+                self.error_with_help(
+                    ErrorCode::AsmTargets,
+                    format!(
+                        "asm blocks for '{}' do not cover target '{}'",
+                        tags.join("', '"),
+                        target
+                    ),
+                    span,
+                    format!(
+                        "add an `asm({}) {{ ... }}` block beside them, or an untagged `asm {{ ... }}` fallback",
+                        target
+                    ),
+                );
+            }
+            if blocks.iter().any(|(_, effect)| *effect != blocks[0].1) {
+                self.error_with_help(
+                    ErrorCode::AsmTargets,
+                    "side-by-side asm blocks declare different stack effects".to_string(),
+                    span,
+                    "only one of them is built, so each must declare the same `(+N)` / `(-N)` effect"
+                        .to_string(),
+                );
+            }
+        }
+    }
+
     pub(super) fn is_terminating_stmt(&self, stmt: &Stmt) -> bool {
         match stmt {
             Stmt::Return(_) => true,
-            // assert(false) is an unconditional halt
+            // assert(false) and fail(code) are unconditional halts
             Stmt::Expr(expr) => {
                 if let Expr::Call { path, args, .. } = &expr.node {
                     let name = path.node.as_dotted();
+                    if name == "fail" {
+                        return true;
+                    }
+                    let (args, _) = split_assertion_message(&name, args);
                     if (name == "assert" || name == "assert.is_true") && args.len() == 1 {
                         if let Expr::Literal(Literal::Bool(false)) = &args[0].node {
                             return true;
@@ -116,16 +191,26 @@ impl TypeChecker {
     ) {
         let Some(event_fields) = self.events.get(&event_name.node).cloned() else {
             self.error(
+                ErrorCode::UndefinedType,
                 format!("undefined event '{}'", event_name.node),
                 event_name.span,
             );
             return;
         };
+        if let Some(note) = self.deprecated_events.get(&event_name.node) {
+            let message = if note.is_empty() {
+                format!("event '{}' is deprecated", event_name.node)
+            } else {
+                format!("event '{}' is deprecated: {}", event_name.node, note)
+            };
+            self.warning(ErrorCode::Deprecated, message, event_name.span);
+        }
 
         // Check all declared fields are provided
         for (def_name, _def_ty) in &event_fields {
             if !fields.iter().any(|(n, _)| n.node == *def_name) {
                 self.error(
+                    ErrorCode::MissingField,
                     format!(
                         "missing field '{}' in event '{}'",
                         def_name, event_name.node
@@ -141,6 +226,7 @@ impl TypeChecker {
                 let val_ty = self.check_expr(&val.node, val.span);
                 if val_ty != *def_ty {
                     self.error(
+                        ErrorCode::TypeMismatch,
                         format!(
                             "event field '{}': expected {} but got {}",
                             name.node,
@@ -152,6 +238,7 @@ impl TypeChecker {
                 }
             } else {
                 self.error(
+                    ErrorCode::UnknownField,
                     format!(
                         "unknown field '{}' in event '{}'",
                         name.node, event_name.node
@@ -0,0 +1,166 @@
+//! Constant tables embedded from files with `include_data`.
+//!
+//! `const SBOX: [U32; 256] = include_data("sbox.json")` reads the file,
+//! relative to the directory of the module's source, when the module is
+//! checked. A file starting with `[` is a JSON array of integers; any other
+//! file is raw bytes, one element per byte. The table must have exactly
+//! the declared length and every value must fit the element type. The
+//! values travel with the module's exports, and the program bundle records
+//! the content hash of each table, so a build names the data it embedded.
+
+use std::path::Path;
+
+use crate::ast::*;
+use crate::diagnostic::ErrorCode;
+use crate::field::goldilocks::MODULUS;
+use crate::hash::ContentHash;
+use crate::span::Spanned;
+
+use super::types::Ty;
+use super::TypeChecker;
+
+/// A constant table loaded with `include_data`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataConst {
+    pub name: String,
+    pub is_pub: bool,
+    /// The file, as the source names it.
+    pub path: String,
+    /// Element type: `Field`, `U32`, or `Bool`.
+    pub elem: Ty,
+    pub values: Vec<u64>,
+}
+
+impl DataConst {
+    /// The declared array type.
+    pub fn ty(&self) -> Ty {
+        Ty::Array(Box::new(self.elem.clone()), self.values.len() as u64)
+    }
+
+    /// Poseidon2 hash of the values, each as eight little-endian bytes,
+    /// so a reformatted file with the same values keeps its hash.
+    pub fn content_hash(&self) -> ContentHash {
+        let bytes: Vec<u8> = self.values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        ContentHash(crate::field::poseidon2::hash_bytes_goldilocks(&bytes))
+    }
+}
+
+/// The arguments of an `include_data(...)` call.
+pub(super) fn include_data_args(expr: &Expr) -> Option<&[Spanned<Expr>]> {
+    match expr {
+        Expr::Call { path, args, .. } if path.node.as_dotted() == "include_data" => Some(args),
+        _ => None,
+    }
+}
+
+/// Values of a data file: a JSON array of integers, or raw bytes.
+fn parse_data(bytes: &[u8]) -> Result<Vec<u64>, String> {
+    match std::str::from_utf8(bytes) {
+        Ok(text) if text.trim_start().starts_with('[') => {
+            crate::runtime::json::parse_u64_array(text)
+                .map_err(|e| format!("not a JSON array of integers: {}", e))
+        }
+        _ => Ok(bytes.iter().map(|&b| u64::from(b)).collect()),
+    }
+}
+
+/// Whether `value` is a valid element of type `elem`.
+fn fits(value: u64, elem: &Ty) -> bool {
+    match elem {
+        Ty::Bool => value <= 1,
+        Ty::U32 => value <= u64::from(u32::MAX),
+        _ => value < MODULUS,
+    }
+}
+
+impl TypeChecker {
+    /// Load the table of `const name: [T; N] = include_data("path")`.
+    pub(super) fn load_data_const(&mut self, cdef: &ConstDef, args: &[Spanned<Expr>]) {
+        let name = &cdef.name.node;
+        let span = cdef.value.span;
+        let path = match args {
+            [Spanned {
+                node: Expr::Literal(Literal::Str(path)),
+                ..
+            }] => path.clone(),
+            _ => {
+                self.error_with_help(
+                    ErrorCode::IncludeData,
+                    "include_data takes the path of one file".to_string(),
+                    span,
+                    "write it as a string literal: `include_data(\"table.json\")`".to_string(),
+                );
+                return;
+            }
+        };
+        let (elem, len) = match self.resolve_type(&cdef.ty.node) {
+            Ty::Array(elem, len) if matches!(*elem, Ty::Field | Ty::U32 | Ty::Bool) => (*elem, len),
+            ty => {
+                self.error(
+                    ErrorCode::IncludeData,
+                    format!(
+                        "constant '{}' holds data from a file, so it needs an array type like [Field; 256], not {}",
+                        name,
+                        ty.display()
+                    ),
+                    cdef.ty.span,
+                );
+                return;
+            }
+        };
+
+        // A table that fails to load still has its declared type, so its
+        // uses report nothing further.
+        let mut table = DataConst {
+            name: name.clone(),
+            is_pub: cdef.is_pub,
+            path: path.clone(),
+            elem: elem.clone(),
+            values: vec![0; len as usize],
+        };
+        match self.read_data(&path, &elem, len, name) {
+            Ok(values) => table.values = values,
+            Err(msg) => self.error(ErrorCode::IncludeData, msg, span),
+        }
+        self.data_consts.insert(name.clone(), table);
+    }
+
+    /// The values of a data file, checked against the declared table.
+    fn read_data(&self, path: &str, elem: &Ty, len: u64, name: &str) -> Result<Vec<u64>, String> {
+        let file = match &self.source_dir {
+            Some(dir) => dir.join(path),
+            None => Path::new(path).to_path_buf(),
+        };
+        let values = std::fs::read(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| parse_data(&bytes))
+            .map_err(|e| format!("cannot include '{}': {}", file.display(), e))?;
+        if values.len() as u64 != len {
+            return Err(format!(
+                "'{}' has {} values, but constant '{}' holds {}",
+                path,
+                values.len(),
+                name,
+                len
+            ));
+        }
+        if let Some((i, v)) = values.iter().enumerate().find(|(_, v)| !fits(**v, elem)) {
+            return Err(format!(
+                "value {} at index {} of '{}' is not a valid {}",
+                v,
+                i,
+                path,
+                elem.display()
+            ));
+        }
+        Ok(values)
+    }
+
+    /// The table a name refers to, unless a variable shadows it.
+    pub(super) fn data_const(&self, name: &str) -> Option<&DataConst> {
+        if self.lookup_var(name).is_some() {
+            return None;
+        }
+        self.data_consts.get(name)
+    }
+}
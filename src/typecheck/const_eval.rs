@@ -0,0 +1,409 @@
+//! Compile-time evaluation of `#[pure]` functions.
+//!
+//! A call of a `#[pure]` function of the module with constant arguments
+//! may stand wherever a constant is expected: a `const` value, a loop end,
+//! and, through a constant, an array size. The evaluator interprets the
+//! body over Goldilocks field elements, as the program would compute it.
+//! Every statement and expression burns fuel, so a runaway or costly body
+//! fails instead of stalling the compiler. Errors carry the calls that
+//! led to them.
+
+use std::collections::BTreeMap;
+
+use crate::ast::*;
+use crate::diagnostic::{Diagnostic, ErrorCode};
+use crate::field::{Goldilocks, PrimeField};
+use crate::span::Span;
+
+use super::TypeChecker;
+
+/// Steps a single compile-time evaluation may take.
+pub(super) const CONST_EVAL_FUEL: u64 = 100_000;
+
+/// Why evaluation of a block stopped early.
+enum Stop {
+    /// A `return`, with its value.
+    Return(u64),
+    /// A `break` out of the innermost loop.
+    Break,
+    /// A `continue` of the innermost loop.
+    Continue,
+    /// An error, with the calls in progress, outermost first.
+    Error(String, Vec<String>),
+}
+
+type Eval<T> = Result<T, Stop>;
+
+struct Evaluator<'a> {
+    functions: &'a BTreeMap<String, FnDef>,
+    constants: &'a BTreeMap<String, u64>,
+    fuel: u64,
+    /// Calls in progress, outermost first, as `f(1, 2)`.
+    calls: Vec<String>,
+    /// Local scopes of the innermost call.
+    scopes: Vec<BTreeMap<String, u64>>,
+}
+
+impl TypeChecker {
+    /// Register the module's `#[pure]` functions and evaluate its
+    /// constants, in order, so each may use the ones before it.
+    pub(super) fn evaluate_constants(&mut self, file: &File) {
+        for item in &file.items {
+            if let Item::Fn(func) = &item.node {
+                if func.is_pure
+                    && func.type_params.is_empty()
+                    && func.body.is_some()
+                    && self.is_item_cfg_active(&item.node)
+                {
+                    self.pure_fns.insert(func.name.node.clone(), func.clone());
+                }
+            }
+        }
+        for item in &file.items {
+            let Item::Const(cdef) = &item.node else {
+                continue;
+            };
+            if !self.is_item_cfg_active(&item.node) {
+                continue;
+            }
+            let value = &cdef.value;
+            if let Some(args) = super::const_data::include_data_args(&value.node) {
+                self.load_data_const(cdef, args);
+                continue;
+            }
+            match (&value.node, self.constant_value(&value.node, value.span)) {
+                (Expr::Literal(_), Some(v)) => {
+                    self.constants.insert(cdef.name.node.clone(), v);
+                }
+                // Other literals are not integer constants.
+                (Expr::Literal(_), None) => {}
+                (_, Some(v)) => {
+                    self.record_folded(value.span, v);
+                    self.constants.insert(cdef.name.node.clone(), v);
+                }
+                (_, None) => self.error_with_help(
+                    ErrorCode::NotConstant,
+                    format!("value of constant '{}' is not known at compile time", cdef.name.node),
+                    value.span,
+                    "use literals, other constants, `+`, `*`, and calls of #[pure] functions with constant arguments".to_string(),
+                ),
+            }
+        }
+    }
+
+    /// Evaluate a call of `name` with constant `args`; `None` unless
+    /// `name` is a `#[pure]` function of this module taking that many
+    /// arguments. An evaluation error is reported at `span`, and the call
+    /// evaluates to 0.
+    pub(super) fn eval_pure_call(&mut self, name: &str, args: &[u64], span: Span) -> Option<u64> {
+        let func = self.pure_fns.get(name)?;
+        if func.params.len() != args.len() {
+            return None;
+        }
+        let mut eval = Evaluator {
+            functions: &self.pure_fns,
+            constants: &self.constants,
+            fuel: CONST_EVAL_FUEL,
+            calls: Vec::new(),
+            scopes: Vec::new(),
+        };
+        match eval.call(name, args.to_vec()) {
+            Ok(value) | Err(Stop::Return(value)) => Some(value),
+            Err(Stop::Break | Stop::Continue) => None,
+            Err(Stop::Error(message, calls)) => {
+                let mut diag = Diagnostic::error(
+                    format!(
+                        "cannot evaluate `{}` at compile time: {}",
+                        calls.first().map_or(name, |c| c.as_str()),
+                        message
+                    ),
+                    span,
+                )
+                .with_code(ErrorCode::NotConstant);
+                for (i, call) in calls.iter().rev().enumerate() {
+                    diag.notes.push(if i == 0 {
+                        format!("in `{}`", call)
+                    } else {
+                        format!("called from `{}`", call)
+                    });
+                }
+                self.diagnostics.push(diag);
+                Some(0)
+            }
+        }
+    }
+}
+
+fn field(v: u64) -> Goldilocks {
+    Goldilocks::from_u64(v)
+}
+
+impl Evaluator<'_> {
+    fn fail<T>(&self, message: impl Into<String>) -> Eval<T> {
+        Err(Stop::Error(message.into(), self.calls.clone()))
+    }
+
+    fn step(&mut self) -> Eval<()> {
+        if self.fuel == 0 {
+            return self.fail(format!("ran out of fuel after {} steps", CONST_EVAL_FUEL));
+        }
+        self.fuel -= 1;
+        Ok(())
+    }
+
+    fn call(&mut self, name: &str, args: Vec<u64>) -> Eval<u64> {
+        let Some(func) = self.functions.get(name) else {
+            return self.fail(format!(
+                "'{}' is not a #[pure] function of this module",
+                name
+            ));
+        };
+        let Some(body) = &func.body else {
+            return self.fail(format!("'{}' has no body", name));
+        };
+        let shown: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        self.calls.push(format!("{}({})", name, shown.join(", ")));
+        let scope = func
+            .params
+            .iter()
+            .map(|p| p.name.node.clone())
+            .zip(args)
+            .collect();
+        let outer = std::mem::replace(&mut self.scopes, vec![scope]);
+        let value = match self.block(&body.node) {
+            Ok(value) | Err(Stop::Return(value)) => Ok(value),
+            Err(Stop::Break | Stop::Continue) => self.fail("`break` or `continue` outside a loop"),
+            Err(stop) => Err(stop),
+        };
+        self.scopes = outer;
+        self.calls.pop();
+        value
+    }
+
+    fn block(&mut self, block: &Block) -> Eval<u64> {
+        self.scopes.push(BTreeMap::new());
+        let value = self.block_inner(block);
+        self.scopes.pop();
+        value
+    }
+
+    fn block_inner(&mut self, block: &Block) -> Eval<u64> {
+        for stmt in &block.stmts {
+            self.stmt(&stmt.node)?;
+        }
+        match &block.tail_expr {
+            Some(tail) => self.expr(&tail.node),
+            None => Ok(0),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<u64> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|s| s.get(name).copied())
+            .or_else(|| self.constants.get(name).copied())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Eval<()> {
+        self.step()?;
+        match stmt {
+            Stmt::Let {
+                pattern: Pattern::Name(name),
+                init,
+                ..
+            } => {
+                let value = self.expr(&init.node)?;
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.insert(name.node.clone(), value);
+                }
+            }
+            Stmt::Assign { place, value } => {
+                let Place::Var(name) = &place.node else {
+                    return self.fail("only plain variables can be assigned at compile time");
+                };
+                let value = self.expr(&value.node)?;
+                match self.scopes.iter_mut().rev().find(|s| s.contains_key(name)) {
+                    Some(scope) => {
+                        scope.insert(name.clone(), value);
+                    }
+                    None => return self.fail(format!("undefined variable '{}'", name)),
+                }
+            }
+            Stmt::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                if self.expr(&cond.node)? != 0 {
+                    self.block(&then_block.node)?;
+                } else if let Some(else_block) = else_block {
+                    self.block(&else_block.node)?;
+                }
+            }
+            Stmt::For {
+                var,
+                start,
+                end,
+                body,
+                ..
+            } => {
+                let start = self.expr(&start.node)?;
+                let end = self.expr(&end.node)?;
+                for i in start..end {
+                    self.scopes.push(BTreeMap::from([(var.node.clone(), i)]));
+                    let value = self.block(&body.node);
+                    self.scopes.pop();
+                    match value {
+                        Ok(_) | Err(Stop::Continue) => {}
+                        Err(Stop::Break) => break,
+                        Err(stop) => return Err(stop),
+                    }
+                }
+            }
+            Stmt::While { cond, bound, body } => {
+                for _ in 0..self.expr(&bound.node)? {
+                    if self.expr(&cond.node)? == 0 {
+                        break;
+                    }
+                    match self.block(&body.node) {
+                        Ok(_) | Err(Stop::Continue) => {}
+                        Err(Stop::Break) => break,
+                        Err(stop) => return Err(stop),
+                    }
+                }
+            }
+            Stmt::Return(value) => {
+                let value = match value {
+                    Some(value) => self.expr(&value.node)?,
+                    None => 0,
+                };
+                return Err(Stop::Return(value));
+            }
+            Stmt::Break => return Err(Stop::Break),
+            Stmt::Continue => return Err(Stop::Continue),
+            Stmt::Expr(expr) => {
+                self.expr(&expr.node)?;
+            }
+            Stmt::Match { expr, arms } => {
+                let value = self.expr(&expr.node)?;
+                for arm in arms {
+                    let matches = match &arm.pattern.node {
+                        MatchPattern::Literal(Literal::Integer(n)) => *n == value,
+                        MatchPattern::Literal(Literal::Bool(b)) => (*b as u64) == value,
+                        MatchPattern::Wildcard => true,
+                        _ => return self.fail("this match pattern cannot be evaluated"),
+                    };
+                    if matches {
+                        self.block(&arm.body.node)?;
+                        break;
+                    }
+                }
+            }
+            Stmt::Let { .. } | Stmt::TupleAssign { .. } => {
+                return self.fail("tuples cannot be computed at compile time");
+            }
+            Stmt::Reveal { .. } | Stmt::Seal { .. } => {
+                return self.fail("events cannot be emitted at compile time");
+            }
+            Stmt::Asm { .. } => return self.fail("`asm` cannot run at compile time"),
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Eval<u64> {
+        self.step()?;
+        match expr {
+            Expr::Literal(Literal::Integer(n)) => Ok(field(*n).to_u64()),
+            Expr::Literal(Literal::Bool(b)) => Ok(*b as u64),
+            Expr::Var(name) => match self.lookup(name) {
+                Some(value) => Ok(value),
+                None => self.fail(format!("'{}' is not known at compile time", name)),
+            },
+            Expr::BinOp { op, lhs, rhs } => {
+                let a = self.expr(&lhs.node)?;
+                // `&&` and `||` only evaluate the right side when it decides.
+                match op {
+                    BinOp::And if a == 0 => return Ok(0),
+                    BinOp::Or if a != 0 => return Ok(1),
+                    _ => {}
+                }
+                let b = self.expr(&rhs.node)?;
+                match op {
+                    BinOp::Add => Ok(field(a).add(field(b)).to_u64()),
+                    BinOp::Mul => Ok(field(a).mul(field(b)).to_u64()),
+                    BinOp::Eq => Ok((a == b) as u64),
+                    BinOp::Lt => Ok((a < b) as u64),
+                    BinOp::BitAnd => Ok(a & b),
+                    BinOp::BitXor => Ok(a ^ b),
+                    BinOp::BitOr => Ok(a | b),
+                    BinOp::And | BinOp::Or => Ok(b),
+                    BinOp::DivMod | BinOp::XFieldMul => self.fail(format!(
+                        "`{}` cannot be computed at compile time",
+                        op.as_str()
+                    )),
+                }
+            }
+            Expr::Call { path, args, .. } => {
+                let name = path.node.as_dotted();
+                let (args, _) = split_assertion_message(&name, args);
+                let (args, _) = split_assumption_reason(&name, args);
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args {
+                    values.push(self.expr(&arg.node)?);
+                }
+                self.builtin_or_call(&name, values)
+            }
+            Expr::FieldAccess { .. }
+            | Expr::Index { .. }
+            | Expr::StructInit { .. }
+            | Expr::ArrayInit(_)
+            | Expr::Tuple(_) => {
+                self.fail("only Field, U32, and Bool values can be computed at compile time")
+            }
+            Expr::Literal(Literal::Str(_)) => self.fail("strings have no value"),
+        }
+    }
+
+    fn builtin_or_call(&mut self, name: &str, args: Vec<u64>) -> Eval<u64> {
+        let arg = |i: usize| args.get(i).copied().unwrap_or(0);
+        match name {
+            "sub" => Ok(field(arg(0)).sub(field(arg(1))).to_u64()),
+            "neg" => Ok(field(arg(0)).neg().to_u64()),
+            "field_add" => Ok(field(arg(0)).add(field(arg(1))).to_u64()),
+            "field_mul" => Ok(field(arg(0)).mul(field(arg(1))).to_u64()),
+            "field_assert_add" => match arg(0) as u128 + arg(1) as u128 {
+                sum if sum < Goldilocks::MODULUS => Ok(sum as u64),
+                _ => self.fail(format!("{} + {} overflows Field", arg(0), arg(1))),
+            },
+            "u32_wrapping_add" => Ok((arg(0) as u32).wrapping_add(arg(1) as u32) as u64),
+            "u32_assert_add" => match (arg(0) as u32).checked_add(arg(1) as u32) {
+                Some(v) => Ok(v as u64),
+                None => self.fail(format!("{} + {} overflows U32", arg(0), arg(1))),
+            },
+            "inv" | "inverse" => match field(arg(0)).inv() {
+                Some(v) => Ok(v.to_u64()),
+                None => self.fail("inverse of zero"),
+            },
+            "as_u32" if arg(0) > u32::MAX as u64 => {
+                self.fail(format!("{} does not fit in U32", arg(0)))
+            }
+            "as_u32" | "as_field" => Ok(arg(0)),
+            "log2" if arg(0) == 0 => self.fail("log2 of zero"),
+            "log2" => Ok(63 - arg(0).leading_zeros() as u64),
+            "pow" => match (arg(0) as u32).checked_pow(arg(1) as u32) {
+                Some(v) => Ok(v as u64),
+                None => self.fail(format!("{}^{} overflows U32", arg(0), arg(1))),
+            },
+            "popcount" => Ok(arg(0).count_ones() as u64),
+            "assert" | "debug_assert" if arg(0) == 0 => self.fail("assertion failed"),
+            "assert_eq" if arg(0) != arg(1) => {
+                self.fail(format!("assertion failed: {} != {}", arg(0), arg(1)))
+            }
+            "assert" | "debug_assert" | "assert_eq" | "assume" => Ok(0),
+            "fail" => self.fail(format!("fail({})", arg(0))),
+            _ if self.functions.contains_key(name) => self.call(name, args),
+            _ => self.fail(format!("'{}' cannot be evaluated at compile time", name)),
+        }
+    }
+}
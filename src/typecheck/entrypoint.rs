@@ -0,0 +1,92 @@
+//! Entrypoints: the dispatch table of a multi-operation program.
+//!
+//! A program whose functions carry `#[entrypoint]` gets a generated
+//! `main` (see `ast::desugar`) that reads a selector from public input
+//! and calls the entrypoint it names; any other selector fails. Each
+//! entrypoint reads its own arguments from public input after the
+//! selector, so it takes no parameters and returns nothing. Selectors
+//! must be distinct, so the table maps every selector to one function.
+
+use std::collections::BTreeMap;
+
+use crate::ast::desugar::{entrypoints, explicit_selector};
+use crate::ast::*;
+use crate::diagnostic::ErrorCode;
+
+use super::TypeChecker;
+
+impl TypeChecker {
+    /// Check the `#[entrypoint]` functions of `file` and their selectors.
+    pub(super) fn check_entrypoints(&mut self, file: &File) {
+        let mut owners: BTreeMap<u64, &str> = BTreeMap::new();
+        for item in &file.items {
+            if !self.is_item_cfg_active(&item.node) {
+                continue;
+            }
+            let Item::Fn(func) = &item.node else {
+                continue;
+            };
+            let Some(attr) = &func.entrypoint else {
+                continue;
+            };
+            let name = &func.name.node;
+            if file.kind != FileKind::Program {
+                self.error_with_help(
+                    ErrorCode::InvalidEntrypoint,
+                    format!("#[entrypoint] function '{}' in a module", name),
+                    attr.span,
+                    "entrypoints belong to the program that dispatches to them".to_string(),
+                );
+            }
+            if !func.params.is_empty() || func.return_ty.is_some() || !func.type_params.is_empty() {
+                self.error_with_help(
+                    ErrorCode::InvalidEntrypoint,
+                    format!(
+                        "entrypoint '{}' must take no parameters and return nothing",
+                        name
+                    ),
+                    func.name.span,
+                    "an entrypoint reads its arguments from public input, after the selector"
+                        .to_string(),
+                );
+            }
+            match explicit_selector(&attr.node) {
+                Some(Err(_)) => self.error_with_help(
+                    ErrorCode::InvalidEntrypoint,
+                    format!("invalid entrypoint selector `{}`", attr.node),
+                    attr.span,
+                    "a selector is a non-negative integer: `#[entrypoint(2)]`".to_string(),
+                ),
+                Some(Ok(selector)) => {
+                    if let Some(other) = owners.insert(selector, name) {
+                        self.error(
+                            ErrorCode::InvalidEntrypoint,
+                            format!(
+                                "entrypoints '{}' and '{}' share selector {}",
+                                other, name, selector
+                            ),
+                            attr.span,
+                        );
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// The program's dispatch table: each selector with its entrypoint.
+    pub(super) fn entrypoint_exports(&self, file: &File) -> Vec<(u64, String)> {
+        if file.kind != FileKind::Program {
+            return Vec::new();
+        }
+        entrypoints(file)
+            .into_iter()
+            .filter(|(_, f)| {
+                f.cfg
+                    .as_ref()
+                    .is_none_or(|c| self.cfg_flags.contains(&c.node))
+            })
+            .map(|(selector, f)| (selector, f.name.node.clone()))
+            .collect()
+    }
+}
@@ -0,0 +1,190 @@
+//! Enums: declarations, variant values, and variant match arms.
+//!
+//! An enum value is a tag — the variant's index in the declaration —
+//! followed by that variant's payload, padded to the widest variant.
+//! Variants are named through the enum (`Shape.Circle(r)`, `Shape.Empty`),
+//! both to build a value and as a match pattern. A match on an enum must
+//! name every variant or end in a wildcard.
+
+use std::collections::BTreeSet;
+
+use crate::ast::*;
+use crate::diagnostic::ErrorCode;
+use crate::span::{Span, Spanned};
+use crate::types::{EnumTy, Ty};
+
+use super::TypeChecker;
+
+impl TypeChecker {
+    /// Register the enum declared by `edef`.
+    pub(super) fn register_enum(&mut self, edef: &EnumDef) {
+        let mut seen = BTreeSet::new();
+        let mut variants = Vec::new();
+        for variant in &edef.variants {
+            if !seen.insert(variant.name.node.as_str()) {
+                self.error(
+                    ErrorCode::UnknownVariant,
+                    format!(
+                        "variant `{}` is declared twice in enum `{}`",
+                        variant.name.node, edef.name.node
+                    ),
+                    variant.name.span,
+                );
+                continue;
+            }
+            let fields = variant
+                .fields
+                .iter()
+                .map(|t| self.resolve_type(&t.node))
+                .collect();
+            variants.push((variant.name.node.clone(), fields));
+        }
+        let ety = EnumTy {
+            name: edef.name.node.clone(),
+            variants,
+        };
+        self.enums.insert(edef.name.node.clone(), ety);
+    }
+
+    /// The enum a dotted `Enum.Variant` path names, with the variant.
+    pub(super) fn enum_of_variant(&self, path: &str) -> Option<(EnumTy, String)> {
+        let (enum_name, variant) = path.rsplit_once('.')?;
+        let ety = self.enums.get(enum_name)?;
+        Some((ety.clone(), variant.to_string()))
+    }
+
+    /// Check a variant value: `Shape.Circle(r)` with `args`, or a bare
+    /// `Shape.Empty` with `args` of `None`.
+    pub(super) fn check_variant_value(
+        &mut self,
+        ety: &EnumTy,
+        variant: &str,
+        args: Option<(&[Spanned<Expr>], Vec<Ty>)>,
+        span: Span,
+    ) -> Ty {
+        let Some((_, fields)) = ety.variant(variant) else {
+            self.unknown_variant(ety, variant, span);
+            return Ty::Enum(ety.clone());
+        };
+        let fields = fields.to_vec();
+        let (args, arg_tys) = args.unwrap_or((&[], Vec::new()));
+        if args.len() != fields.len() {
+            let usage = if fields.is_empty() {
+                format!("`{}.{}`", ety.name, variant)
+            } else {
+                let names: Vec<String> = fields.iter().map(|t| t.display()).collect();
+                format!("`{}.{}({})`", ety.name, variant, names.join(", "))
+            };
+            self.error_with_help(
+                ErrorCode::ArgumentCount,
+                format!(
+                    "variant '{}.{}' has {} field{}, got {} argument{}",
+                    ety.name,
+                    variant,
+                    fields.len(),
+                    if fields.len() == 1 { "" } else { "s" },
+                    args.len(),
+                    if args.len() == 1 { "" } else { "s" }
+                ),
+                span,
+                format!("build it with {}", usage),
+            );
+            return Ty::Enum(ety.clone());
+        }
+        for ((arg, mut arg_ty), field_ty) in args.iter().zip(arg_tys).zip(&fields) {
+            if *field_ty == Ty::U32 {
+                arg_ty = self.coerce_to_u32(arg, arg_ty);
+            }
+            if arg_ty != *field_ty {
+                self.error(
+                    ErrorCode::TypeMismatch,
+                    format!(
+                        "'{}.{}' expects {} but got {}",
+                        ety.name,
+                        variant,
+                        field_ty.display(),
+                        arg_ty.display()
+                    ),
+                    arg.span,
+                );
+            }
+        }
+        Ty::Enum(ety.clone())
+    }
+
+    /// Check a variant pattern against the scrutinee's type and return the
+    /// types of its bindings, or `None` when the pattern does not fit.
+    pub(super) fn check_variant_pattern(
+        &mut self,
+        path: &Spanned<ModulePath>,
+        bindings: &[Spanned<String>],
+        scrutinee_ty: &Ty,
+    ) -> Option<Vec<Ty>> {
+        let name = path.node.as_dotted();
+        let Some((ety, variant)) = self.enum_of_variant(&name) else {
+            self.error(
+                ErrorCode::UndefinedType,
+                format!("unknown enum variant `{}`", name),
+                path.span,
+            );
+            return None;
+        };
+        if *scrutinee_ty != Ty::Enum(ety.clone()) {
+            self.error(
+                ErrorCode::TypeMismatch,
+                format!(
+                    "variant pattern `{}` does not match scrutinee type `{}`",
+                    name,
+                    scrutinee_ty.display()
+                ),
+                path.span,
+            );
+            return None;
+        }
+        let Some((_, fields)) = ety.variant(&variant) else {
+            self.unknown_variant(&ety, &variant, path.span);
+            return None;
+        };
+        if bindings.len() != fields.len() {
+            self.error(
+                ErrorCode::UnknownVariant,
+                format!(
+                    "variant `{}` has {} field{}, but the pattern binds {}",
+                    name,
+                    fields.len(),
+                    if fields.len() == 1 { "" } else { "s" },
+                    bindings.len()
+                ),
+                path.span,
+            );
+            return None;
+        }
+        Some(fields.to_vec())
+    }
+
+    /// The variants of `ety` no arm in `arms` names, as `Enum.Variant`.
+    pub(super) fn missing_variants(ety: &EnumTy, arms: &[MatchArm]) -> Vec<String> {
+        let covered: BTreeSet<String> = arms
+            .iter()
+            .filter_map(|arm| match &arm.pattern.node {
+                MatchPattern::Variant { path, .. } => path.node.0.last().cloned(),
+                _ => None,
+            })
+            .collect();
+        ety.variants
+            .iter()
+            .filter(|(v, _)| !covered.contains(v))
+            .map(|(v, _)| format!("{}.{}", ety.name, v))
+            .collect()
+    }
+
+    fn unknown_variant(&mut self, ety: &EnumTy, variant: &str, span: Span) {
+        let names: Vec<&str> = ety.variants.iter().map(|(v, _)| v.as_str()).collect();
+        self.error_with_help(
+            ErrorCode::UnknownVariant,
+            format!("enum `{}` has no variant `{}`", ety.name, variant),
+            span,
+            format!("the variants are: {}", names.join(", ")),
+        );
+    }
+}
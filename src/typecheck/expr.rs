@@ -3,10 +3,10 @@
 use std::collections::BTreeMap;
 
 use crate::ast::*;
-use crate::span::Span;
-use crate::types::Ty;
+use crate::diagnostic::ErrorCode;
+use crate::span::{Span, Spanned};
+use crate::types::{StructTy, Ty};
 
-use super::builtins::is_io_builtin;
 use super::{MonoInstance, TypeChecker};
 
 impl TypeChecker {
@@ -15,16 +15,32 @@ impl TypeChecker {
             Expr::Literal(lit) => match lit {
                 Literal::Integer(_) => Ty::Field,
                 Literal::Bool(_) => Ty::Bool,
+                Literal::Str(_) => {
+                    self.error_with_help(
+                        ErrorCode::MisplacedString,
+                        "string literals are only allowed as assertion messages and trace labels"
+                            .to_string(),
+                        span,
+                        "pass the message as the last argument: `assert(cond, \"message\")`"
+                            .to_string(),
+                    );
+                    Ty::Unit
+                }
             },
             Expr::Var(name) => {
                 // Direct variable lookup
                 if let Some(info) = self.lookup_var(name) {
-                    return info.ty.clone();
+                    let ty = info.ty.clone();
+                    self.mark_sponge_read(name, &ty);
+                    return ty;
                 }
                 // Known constant
                 if self.constants.contains_key(name) {
                     return Ty::Field;
                 }
+                if let Some(data) = self.data_consts.get(name) {
+                    return data.ty();
+                }
                 // Dotted name: could be nested field access (var.field.subfield)
                 // or module constant. Try resolving from the first dot outward.
                 if name.contains('.') {
@@ -32,7 +48,18 @@ impl TypeChecker {
                         return ty;
                     }
                 }
+                if let Some((ety, variant)) = self.enum_of_variant(name) {
+                    return self.check_variant_value(&ety, &variant, None, span);
+                }
+                if let Some(sty) = self.structs.get(name).cloned() {
+                    if sty.kind == StructKind::Unit {
+                        self.check_struct_construction(name, &sty, span);
+                        self.constructors.push(span);
+                        return Ty::Struct(sty);
+                    }
+                }
                 self.error_with_help(
+                    ErrorCode::UndefinedVariable,
                     format!("undefined variable '{}'", name),
                     span,
                     "check that the variable is declared with `let` before use".to_string(),
@@ -40,8 +67,18 @@ impl TypeChecker {
                 Ty::Field
             }
             Expr::BinOp { op, lhs, rhs } => {
-                let lhs_ty = self.check_expr(&lhs.node, lhs.span);
-                let rhs_ty = self.check_expr(&rhs.node, rhs.span);
+                let mut lhs_ty = self.check_expr(&lhs.node, lhs.span);
+                let mut rhs_ty = self.check_expr(&rhs.node, rhs.span);
+                if matches!(
+                    op,
+                    BinOp::Lt | BinOp::BitAnd | BinOp::BitXor | BinOp::BitOr | BinOp::DivMod
+                ) {
+                    lhs_ty = self.coerce_to_u32(lhs, lhs_ty);
+                    rhs_ty = self.coerce_to_u32(rhs, rhs_ty);
+                }
+                if *op == BinOp::Eq && matches!(lhs_ty, Ty::Digest(_)) && lhs_ty == rhs_ty {
+                    self.digest_eqs.push(span);
+                }
                 self.check_binop(*op, &lhs_ty, &rhs_ty, span)
             }
             Expr::Call {
@@ -50,16 +87,57 @@ impl TypeChecker {
                 args,
             } => {
                 let fn_name = path.node.as_dotted();
+                if fn_name == "trace" {
+                    return self.check_trace(args, span);
+                }
+                if fn_name == "include_data" {
+                    self.error_with_help(
+                        ErrorCode::IncludeData,
+                        "include_data can only give the value of a constant".to_string(),
+                        span,
+                        "declare the table as `const TABLE: [Field; N] = include_data(\"table.json\")`".to_string(),
+                    );
+                    return Ty::Field;
+                }
+                if let Some(array) = self.len_call_target(expr) {
+                    return self.check_len(&array, span);
+                }
+                let (args, message) = split_assertion_message(&fn_name, args);
+                if let Some(message) = message {
+                    self.record_assertion_message(message, span);
+                }
+                let (args, reason) = split_assumption_reason(&fn_name, args);
+                if reason.is_some_and(|r| r.trim().is_empty()) {
+                    self.error(
+                        ErrorCode::EmptyMessage,
+                        "assumption reason must not be empty".to_string(),
+                        span,
+                    );
+                }
+                if fn_name == "fail" {
+                    self.record_fail_code(args, span);
+                }
+                if let ("as_u32", [arg]) = (fn_name.rsplit('.').next().unwrap_or(""), args) {
+                    self.lint_u32_sum(arg);
+                }
                 let arg_tys: Vec<Ty> = args
                     .iter()
                     .map(|a| self.check_expr(&a.node, a.span))
                     .collect();
 
+                if let Some(sty) = self.structs.get(&fn_name).cloned() {
+                    return self.check_constructor(&fn_name, &sty, args, arg_tys, span);
+                }
+                if let Some((ety, variant)) = self.enum_of_variant(&fn_name) {
+                    return self.check_variant_value(&ety, &variant, Some((args, arg_tys)), span);
+                }
+
                 // Reject I/O builtins inside #[pure] functions.
                 if self.in_pure_fn {
                     let base = fn_name.rsplit('.').next().unwrap_or(&fn_name);
-                    if is_io_builtin(base) {
+                    if self.is_io_builtin(base) {
                         self.error(
+                            ErrorCode::ImpureOperation,
                             format!(
                                 "#[pure] function cannot call '{}' (I/O side effect)",
                                 fn_name
@@ -76,6 +154,7 @@ impl TypeChecker {
                         // Explicit: sum<3>(...)
                         if generic_args.len() != gdef.type_params.len() {
                             self.error(
+                                ErrorCode::SizeArgument,
                                 format!(
                                     "function '{}' expects {} size parameters, got {}",
                                     fn_name,
@@ -88,10 +167,19 @@ impl TypeChecker {
                         }
                         let mut sizes = Vec::new();
                         for ga in generic_args {
-                            if let Some(n) = ga.node.as_literal() {
-                                sizes.push(n);
+                            let constant = ga
+                                .node
+                                .params()
+                                .iter()
+                                .all(|p| self.constants.contains_key(*p));
+                            if constant {
+                                sizes.push(
+                                    ga.node
+                                        .eval_with_constants(&BTreeMap::new(), &self.constants),
+                                );
                             } else {
                                 self.error(
+                                    ErrorCode::SizeArgument,
                                     format!("expected concrete size, got '{}'", ga.node),
                                     ga.span,
                                 );
@@ -101,7 +189,10 @@ impl TypeChecker {
                         sizes
                     } else {
                         // Infer from argument types.
-                        self.infer_size_args(&gdef, &arg_tys, span)
+                        match self.infer_size_args(&gdef, &fn_name, args, &arg_tys, span) {
+                            Some(sizes) => sizes,
+                            None => return Ty::Field,
+                        }
                     };
 
                     // Build substitution map.
@@ -125,6 +216,7 @@ impl TypeChecker {
                     // Type-check arguments against the monomorphized signature.
                     if arg_tys.len() != params.len() {
                         self.error(
+                            ErrorCode::ArgumentCount,
                             format!(
                                 "function '{}' expects {} arguments, got {}",
                                 fn_name,
@@ -137,8 +229,10 @@ impl TypeChecker {
                         for (i, ((_, expected), actual)) in
                             params.iter().zip(arg_tys.iter()).enumerate()
                         {
+                            let actual = &self.coerce_arg(expected, &args[i], actual);
                             if expected != actual {
                                 self.error(
+                                    ErrorCode::TypeMismatch,
                                     format!(
                                         "argument {} of '{}': expected {} but got {}",
                                         i + 1,
@@ -152,12 +246,18 @@ impl TypeChecker {
                         }
                     }
 
-                    // Record this monomorphized instance.
+                    // Record this monomorphized instance; an imported
+                    // function's instances are emitted by its module.
                     let instance = MonoInstance {
                         name: fn_name.clone(),
                         size_args: size_args.clone(),
                     };
-                    if !self.mono_instances.contains(&instance) {
+                    if fn_name.contains('.') {
+                        if !self.foreign_instances.iter().any(|(i, _)| *i == instance) {
+                            self.foreign_instances
+                                .push((instance.clone(), return_ty.clone()));
+                        }
+                    } else if !self.mono_instances.contains(&instance) {
                         self.mono_instances.push(instance.clone());
                     }
                     // Record per-call-site resolution for the emitter.
@@ -168,6 +268,7 @@ impl TypeChecker {
                     // Non-generic function call — existing logic.
                     if !generic_args.is_empty() {
                         self.error(
+                            ErrorCode::SizeArgument,
                             format!(
                                 "function '{}' is not generic but called with size arguments",
                                 fn_name
@@ -177,6 +278,7 @@ impl TypeChecker {
                     }
                     if arg_tys.len() != sig.params.len() {
                         self.error(
+                            ErrorCode::ArgumentCount,
                             format!(
                                 "function '{}' expects {} arguments, got {}",
                                 fn_name,
@@ -189,8 +291,10 @@ impl TypeChecker {
                         for (i, ((_, expected), actual)) in
                             sig.params.iter().zip(arg_tys.iter()).enumerate()
                         {
+                            let actual = &self.coerce_arg(expected, &args[i], actual);
                             if expected != actual {
                                 self.error(
+                                    ErrorCode::TypeMismatch,
                                     format!(
                                         "argument {} of '{}': expected {} but got {}",
                                         i + 1,
@@ -208,7 +312,7 @@ impl TypeChecker {
                     if base_name == "as_u32" && args.len() == 1 {
                         if let Expr::Var(var_name) = &args[0].node {
                             if self.u32_proven.contains(var_name) {
-                                self.warning(
+                                self.hint(
                                     format!(
                                         "hint[H0003]: as_u32({}) is redundant — value is already proven U32",
                                         var_name
@@ -222,6 +326,7 @@ impl TypeChecker {
                     sig.return_ty
                 } else {
                     self.error_with_help(
+                        ErrorCode::UndefinedFunction,
                         format!("undefined function '{}'", fn_name),
                         span,
                         "check the function name and ensure the module is imported with `use`"
@@ -237,6 +342,7 @@ impl TypeChecker {
                         field_ty
                     } else {
                         self.error(
+                            ErrorCode::UnknownField,
                             format!("struct '{}' has no field '{}'", sty.name, field.node),
                             span,
                         );
@@ -244,6 +350,7 @@ impl TypeChecker {
                     }
                 } else {
                     self.error(
+                        ErrorCode::NotAStruct,
                         format!("field access on non-struct type {}", inner_ty.display()),
                         span,
                     );
@@ -253,10 +360,18 @@ impl TypeChecker {
             Expr::Index { expr: inner, index } => {
                 let inner_ty = self.check_expr(&inner.node, inner.span);
                 let _idx_ty = self.check_expr(&index.node, index.span);
+                if let Expr::Var(name) = &inner.node {
+                    self.check_data_index(name, index);
+                }
                 match &inner_ty {
                     Ty::Array(elem_ty, _) => *elem_ty.clone(),
+                    Ty::Digest(width) => {
+                        self.check_digest_index(index, *width);
+                        Ty::Field
+                    }
                     _ => {
                         self.error(
+                            ErrorCode::NotAnArray,
                             format!("index access on non-array type {}", inner_ty.display()),
                             span,
                         );
@@ -267,17 +382,25 @@ impl TypeChecker {
             Expr::StructInit {
                 path,
                 fields: init_fields,
+                base,
             } => {
                 let struct_name = path.node.as_dotted();
                 if let Some(sty) = self.structs.get(&struct_name).cloned() {
+                    self.check_struct_construction(&struct_name, &sty, span);
+                    let base_var = base.as_ref().and_then(|b| self.check_struct_base(b, &sty));
+                    let mut fill = Vec::new();
                     // Check all required fields are provided
                     for (def_name, def_ty, _) in &sty.fields {
                         if let Some((_name, val)) =
                             init_fields.iter().find(|(n, _)| n.node == *def_name)
                         {
-                            let val_ty = self.check_expr(&val.node, val.span);
+                            let mut val_ty = self.check_expr(&val.node, val.span);
+                            if *def_ty == Ty::U32 {
+                                val_ty = self.coerce_to_u32(val, val_ty);
+                            }
                             if val_ty != *def_ty {
                                 self.error(
+                                    ErrorCode::TypeMismatch,
                                     format!(
                                         "field '{}': expected {} but got {}",
                                         def_name,
@@ -287,17 +410,29 @@ impl TypeChecker {
                                     val.span,
                                 );
                             }
-                        } else {
+                            fill.push((def_name.clone(), None));
+                        } else if let Some(var) = &base_var {
+                            let copy = Expr::Var(format!("{}.{}", var, def_name));
+                            fill.push((def_name.clone(), Some(copy)));
+                        } else if let Some(&value) = sty.defaults.get(def_name) {
+                            let default = Expr::Literal(Literal::Integer(value));
+                            fill.push((def_name.clone(), Some(default)));
+                        } else if base.is_none() {
                             self.error(
+                                ErrorCode::MissingField,
                                 format!("missing field '{}' in struct init", def_name),
                                 span,
                             );
                         }
                     }
+                    if fill.iter().any(|(_, e)| e.is_some()) {
+                        self.struct_fills.push((span, fill));
+                    }
                     // Check for extra fields
                     for (name, _) in init_fields {
                         if !sty.fields.iter().any(|(n, _, _)| *n == name.node) {
                             self.error(
+                                ErrorCode::UnknownField,
                                 format!(
                                     "unknown field '{}' in struct '{}'",
                                     name.node, struct_name
@@ -309,6 +444,7 @@ impl TypeChecker {
                     Ty::Struct(sty)
                 } else {
                     self.error_with_help(
+                        ErrorCode::UndefinedType,
                         format!("undefined struct '{}'", struct_name),
                         span,
                         "check the struct name spelling, or import the module that defines it"
@@ -326,6 +462,7 @@ impl TypeChecker {
                         let ty = self.check_expr(&elem.node, elem.span);
                         if ty != first_ty {
                             self.error(
+                                ErrorCode::TypeMismatch,
                                 format!(
                                     "array element type mismatch: expected {} got {}",
                                     first_ty.display(),
@@ -348,6 +485,53 @@ impl TypeChecker {
         }
     }
 
+    /// `d[i]` on a digest: the index must be known at compile time and
+    /// name one of its `width` elements.
+    fn check_digest_index(&mut self, index: &Spanned<Expr>, width: u32) {
+        match self.constant_value(&index.node, index.span) {
+            None => self.error_with_help(
+                ErrorCode::NotConstant,
+                "digest index must be known at compile time".to_string(),
+                index.span,
+                "index with a literal or a constant, or destructure the digest with `let (d0, d1, d2, d3, d4) = d`".to_string(),
+            ),
+            Some(i) if i >= width as u64 => self.error(
+                ErrorCode::IndexOutOfRange,
+                format!("digest index {} out of range for a Digest of {} elements", i, width),
+                index.span,
+            ),
+            Some(i) => {
+                if !matches!(index.node, Expr::Literal(_)) {
+                    self.record_folded(index.span, i);
+                }
+            }
+        }
+    }
+
+    /// `TABLE[i]` on an `include_data` table: a known index must be in
+    /// range and is folded; any other is counted as a runtime read.
+    fn check_data_index(&mut self, name: &str, index: &Spanned<Expr>) {
+        let Some(len) = self.data_const(name).map(|d| d.values.len() as u64) else {
+            return;
+        };
+        match self.constant_value(&index.node, index.span) {
+            None => *self.data_reads.entry(name.to_string()).or_default() += 1,
+            Some(i) if i >= len => self.error(
+                ErrorCode::IndexOutOfRange,
+                format!(
+                    "index {} out of range for '{}' of {} elements",
+                    i, name, len
+                ),
+                index.span,
+            ),
+            Some(i) => {
+                if !matches!(index.node, Expr::Literal(_)) {
+                    self.record_folded(index.span, i);
+                }
+            }
+        }
+    }
+
     pub(super) fn check_binop(&mut self, op: BinOp, lhs: &Ty, rhs: &Ty, span: Span) -> Ty {
         match op {
             BinOp::Add | BinOp::Mul => {
@@ -357,6 +541,7 @@ impl TypeChecker {
                     lhs.clone()
                 } else {
                     self.error(
+                        ErrorCode::OperatorTypes,
                         format!(
                             "operator '{}' requires both operands to be Field (or both XField), got {} and {}",
                             op.as_str(), lhs.display(), rhs.display()
@@ -369,6 +554,7 @@ impl TypeChecker {
             BinOp::Eq => {
                 if lhs != rhs {
                     self.error(
+                        ErrorCode::OperatorTypes,
                         format!(
                             "operator '==' requires same types, got {} and {}",
                             lhs.display(),
@@ -382,6 +568,7 @@ impl TypeChecker {
             BinOp::Lt => {
                 if lhs != &Ty::U32 || rhs != &Ty::U32 {
                     self.error(
+                        ErrorCode::OperatorTypes,
                         format!(
                             "operator '<' requires U32 operands, got {} and {}",
                             lhs.display(),
@@ -392,11 +579,15 @@ impl TypeChecker {
                 }
                 Ty::Bool
             }
-            BinOp::BitAnd | BinOp::BitXor => {
+            BinOp::BitAnd | BinOp::BitXor | BinOp::BitOr => {
+                if lhs == &Ty::Bool && rhs == &Ty::Bool {
+                    return Ty::Bool;
+                }
                 if lhs != &Ty::U32 || rhs != &Ty::U32 {
                     self.error(
+                        ErrorCode::OperatorTypes,
                         format!(
-                            "operator '{}' requires U32 operands, got {} and {}",
+                            "operator '{}' requires U32 operands (or both Bool), got {} and {}",
                             op.as_str(),
                             lhs.display(),
                             rhs.display()
@@ -406,9 +597,26 @@ impl TypeChecker {
                 }
                 Ty::U32
             }
+            BinOp::And | BinOp::Or => {
+                if lhs != &Ty::Bool || rhs != &Ty::Bool {
+                    self.error_with_help(
+                        ErrorCode::OperatorTypes,
+                        format!(
+                            "operator '{}' requires Bool operands, got {} and {}",
+                            op.as_str(),
+                            lhs.display(),
+                            rhs.display()
+                        ),
+                        span,
+                        "compare with `==` or `<` to get a Bool".to_string(),
+                    );
+                }
+                Ty::Bool
+            }
             BinOp::DivMod => {
                 if lhs != &Ty::U32 || rhs != &Ty::U32 {
                     self.error(
+                        ErrorCode::OperatorTypes,
                         format!(
                             "operator '/%' requires U32 operands, got {} and {}",
                             lhs.display(),
@@ -422,6 +630,7 @@ impl TypeChecker {
             BinOp::XFieldMul => {
                 if !matches!(lhs, Ty::XField(_)) || rhs != &Ty::Field {
                     self.error(
+                        ErrorCode::OperatorTypes,
                         format!(
                             "operator '*.' requires XField and Field, got {} and {}",
                             lhs.display(),
@@ -435,6 +644,205 @@ impl TypeChecker {
         }
     }
 
+    /// Enter an assertion message in the module's error table.
+    /// `trace("label", value)`. The label becomes a Triton `hint` name,
+    /// so it must be a lowercase identifier; the value may be any type
+    /// that fits in the reachable stack.
+    fn check_trace(&mut self, args: &[Spanned<Expr>], span: Span) -> Ty {
+        let (rest, label) = split_trace_label("trace", args);
+        match label {
+            None => self.error_with_help(
+                ErrorCode::InvalidTrace,
+                "`trace` expects a string literal label".to_string(),
+                span,
+                "name the value first: `trace(\"balance\", balance)`".to_string(),
+            ),
+            Some(label) if !is_hint_name(label) => self.error(
+                ErrorCode::InvalidTrace,
+                format!(
+                    "trace label \"{}\" must be a lowercase identifier (a-z, 0-9, _)",
+                    label
+                ),
+                span,
+            ),
+            Some(_) => {}
+        }
+        if rest.len() != 1 {
+            self.error(
+                ErrorCode::ArgumentCount,
+                format!("function 'trace' expects 2 arguments, got {}", args.len()),
+                span,
+            );
+        }
+        for arg in rest {
+            let width = self.check_expr(&arg.node, arg.span).width();
+            if width == 0 || width > 16 {
+                self.error(
+                    ErrorCode::InvalidTrace,
+                    format!(
+                        "cannot trace a value {} elements wide (1 to 16 fit a hint)",
+                        width
+                    ),
+                    arg.span,
+                );
+            }
+        }
+        Ty::Unit
+    }
+
+    /// `fail(CODE)`: the code must be a named nonzero constant, so the
+    /// error table can name it for off-chain callers.
+    fn record_fail_code(&mut self, args: &[Spanned<Expr>], span: Span) {
+        let code = match args.first().map(|a| &a.node) {
+            Some(Expr::Var(name)) => self.constants.get(name).map(|&code| (name, code)),
+            _ => None,
+        };
+        let Some((name, code)) = code else {
+            self.error_with_help(
+                ErrorCode::InvalidFailCode,
+                "`fail` expects a named constant as its code".to_string(),
+                span,
+                "declare `const INSUFFICIENT_BALANCE: Field = 2` and call `fail(INSUFFICIENT_BALANCE)`"
+                    .to_string(),
+            );
+            return;
+        };
+        let name = name.rsplit('.').next().unwrap_or(name);
+        if code == 0 {
+            self.error(
+                ErrorCode::InvalidFailCode,
+                format!("fail code '{}' is 0, which means success", name),
+                span,
+            );
+            return;
+        }
+        match self
+            .fail_codes
+            .iter()
+            .find(|(n, c)| n == name || *c == code)
+        {
+            Some((n, c)) if n == name && *c == code => {}
+            Some((other, other_code)) => {
+                let message = format!(
+                    "fail code '{}' = {} clashes with '{}' = {}",
+                    name, code, other, other_code
+                );
+                self.error(ErrorCode::InvalidFailCode, message, span);
+            }
+            None => self.fail_codes.push((name.to_string(), code)),
+        }
+    }
+
+    fn record_assertion_message(&mut self, message: &str, span: Span) {
+        if message.trim().is_empty() {
+            self.error(
+                ErrorCode::EmptyMessage,
+                "assertion message must not be empty".to_string(),
+                span,
+            );
+        } else if !self.assertion_messages.iter().any(|m| m == message) {
+            self.assertion_messages.push(message.to_string());
+        }
+    }
+
+    /// `array.len()`: the length of a fixed-size array, known at compile
+    /// time.
+    fn check_len(&mut self, array: &str, span: Span) -> Ty {
+        match self.check_expr(&Expr::Var(array.to_string()), span) {
+            Ty::Array(_, n) => self.record_folded(span, n),
+            ty => self.error(
+                ErrorCode::NotAnArray,
+                format!("`len()` needs an array, found {}", ty.display()),
+                span,
+            ),
+        }
+        Ty::Field
+    }
+
+    /// `Amount(x)`: build a tuple struct from its fields, in order.
+    fn check_constructor(
+        &mut self,
+        name: &str,
+        sty: &StructTy,
+        args: &[Spanned<Expr>],
+        arg_tys: Vec<Ty>,
+        span: Span,
+    ) -> Ty {
+        if sty.kind != StructKind::Tuple {
+            let usage = match sty.kind {
+                StructKind::Unit => format!("`{}`", name),
+                _ => format!("`{} {{ ... }}`", name),
+            };
+            self.error_with_help(
+                ErrorCode::NotATupleStruct,
+                format!("'{}' is not a tuple struct", name),
+                span,
+                format!("build it with {}", usage),
+            );
+            return Ty::Struct(sty.clone());
+        }
+        self.check_struct_construction(name, sty, span);
+        if args.len() != sty.fields.len() {
+            self.error(
+                ErrorCode::ArgumentCount,
+                format!(
+                    "'{}' has {} field{}, got {} argument{}",
+                    name,
+                    sty.fields.len(),
+                    if sty.fields.len() == 1 { "" } else { "s" },
+                    args.len(),
+                    if args.len() == 1 { "" } else { "s" }
+                ),
+                span,
+            );
+        }
+        for ((arg, mut arg_ty), (_, field_ty, _)) in args.iter().zip(arg_tys).zip(&sty.fields) {
+            if *field_ty == Ty::U32 {
+                arg_ty = self.coerce_to_u32(arg, arg_ty);
+            }
+            if arg_ty != *field_ty {
+                self.error(
+                    ErrorCode::TypeMismatch,
+                    format!(
+                        "'{}' expects {} but got {}",
+                        name,
+                        field_ty.display(),
+                        arg_ty.display()
+                    ),
+                    arg.span,
+                );
+            }
+        }
+        self.constructors.push(span);
+        Ty::Struct(sty.clone())
+    }
+
+    /// `..base` of a struct init: a variable of the same struct, whose
+    /// name is returned so the missing fields can be copied from it.
+    fn check_struct_base(&mut self, base: &Spanned<Expr>, sty: &StructTy) -> Option<String> {
+        let ty = self.check_expr(&base.node, base.span);
+        if ty != Ty::Struct(sty.clone()) {
+            self.error(
+                ErrorCode::StructUpdateBase,
+                format!("`..` base must be a '{}', found {}", sty.name, ty.display()),
+                base.span,
+            );
+            return None;
+        }
+        match &base.node {
+            Expr::Var(name) => Some(name.clone()),
+            _ => {
+                self.error_with_help(
+                    ErrorCode::StructUpdateBase,
+                    "`..` base must be a variable".to_string(),
+                    base.span,
+                    "bind the base with `let` first, then write `..name`".to_string(),
+                );
+                None
+            }
+        }
+    }
+
     /// Resolve nested field access from a dotted name like "st.s00.lo".
     /// Tries every prefix that could be a variable, then walks the
     /// remaining dot-separated fields through struct types.
@@ -452,6 +860,7 @@ impl TypeChecker {
                             ty = field_ty;
                         } else {
                             self.error(
+                                ErrorCode::UnknownField,
                                 format!("struct '{}' has no field '{}'", sty.name, field),
                                 span,
                             );
@@ -459,6 +868,7 @@ impl TypeChecker {
                         }
                     } else {
                         self.error(
+                            ErrorCode::NotAStruct,
                             format!(
                                 "field access '.{}' on non-struct type {}",
                                 field,
@@ -475,3 +885,12 @@ impl TypeChecker {
         None
     }
 }
+
+/// Triton accepts `[a-z_][a-z0-9_]*` as a hint name.
+fn is_hint_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
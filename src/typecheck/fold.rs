@@ -0,0 +1,245 @@
+//! Compile-time values written into the AST.
+//!
+//! The type checker evaluates loop ends built from constants, every
+//! `array.len()`, and constant values computed by `#[pure]` calls, and
+//! exports the values by span; `fold_constants`
+//! replaces those expressions with integer literals before lowering and
+//! cost analysis, so a loop over `0..arr.len()` or `0..N * 2` is counted
+//! exactly without a `bounded` annotation.
+//!
+//! Struct inits that use `..base` or field defaults are completed the same
+//! way: every field is listed in declaration order, a copied field as
+//! `base.field` and a default as its value, and the base is dropped. A
+//! tuple struct call `Amount(x)` becomes `Amount { 0: x }` and a unit
+//! struct `Marker` becomes `Marker {}`, so newtypes lower to their fields.
+//! A digest comparison `a == b` becomes a call of the `Digest.eq`
+//! intrinsic, which compares all elements and is priced as such.
+
+use crate::ast::*;
+use crate::span::Spanned;
+
+use super::{ModuleExports, StructFill};
+
+/// Replace the expressions at the spans of `exports.folded` with their
+/// values, complete the struct inits of `exports.struct_fills`, and turn
+/// the tuple and unit struct constructors into struct inits and the
+/// digest comparisons into `Digest.eq` calls.
+pub fn fold_constants(file: &mut File, exports: &ModuleExports) {
+    if exports.folded.is_empty()
+        && exports.struct_fills.is_empty()
+        && exports.constructors.is_empty()
+        && exports.digest_eqs.is_empty()
+    {
+        return;
+    }
+    for item in &mut file.items {
+        match &mut item.node {
+            Item::Fn(func) => {
+                for pred in &mut func.assumes {
+                    fold_expr(pred, exports);
+                }
+                if let Some(body) = &mut func.body {
+                    fold_block(&mut body.node, exports);
+                }
+            }
+            Item::Const(cdef) => fold_expr(&mut cdef.value, exports),
+            _ => {}
+        }
+    }
+}
+
+fn fold_block(block: &mut Block, exports: &ModuleExports) {
+    for stmt in &mut block.stmts {
+        fold_stmt(&mut stmt.node, exports);
+    }
+    if let Some(tail) = &mut block.tail_expr {
+        fold_expr(tail, exports);
+    }
+}
+
+fn fold_stmt(stmt: &mut Stmt, exports: &ModuleExports) {
+    match stmt {
+        Stmt::Let { init, .. } => fold_expr(init, exports),
+        Stmt::Assign { place, value } => {
+            fold_place(&mut place.node, exports);
+            fold_expr(value, exports);
+        }
+        Stmt::TupleAssign { value, .. } | Stmt::Expr(value) => fold_expr(value, exports),
+        Stmt::If {
+            cond,
+            then_block,
+            else_block,
+        } => {
+            fold_expr(cond, exports);
+            fold_block(&mut then_block.node, exports);
+            if let Some(else_block) = else_block {
+                fold_block(&mut else_block.node, exports);
+            }
+        }
+        Stmt::For {
+            start,
+            end,
+            bound,
+            body,
+            ..
+        } => {
+            fold_expr(start, exports);
+            fold_expr(end, exports);
+            if let Some(bound) = bound {
+                fold_expr(bound, exports);
+            }
+            fold_block(&mut body.node, exports);
+        }
+        Stmt::While { cond, bound, body } => {
+            fold_expr(cond, exports);
+            fold_expr(bound, exports);
+            fold_block(&mut body.node, exports);
+        }
+        Stmt::Return(value) => {
+            if let Some(value) = value {
+                fold_expr(value, exports);
+            }
+        }
+        Stmt::Reveal { fields, .. } | Stmt::Seal { fields, .. } => {
+            for (_, value) in fields {
+                fold_expr(value, exports);
+            }
+        }
+        Stmt::Match { expr, arms } => {
+            fold_expr(expr, exports);
+            for arm in arms {
+                fold_block(&mut arm.body.node, exports);
+            }
+        }
+        Stmt::Break | Stmt::Continue | Stmt::Asm { .. } => {}
+    }
+}
+
+fn fold_place(place: &mut Place, exports: &ModuleExports) {
+    match place {
+        Place::Var(_) => {}
+        Place::FieldAccess(inner, _) => fold_place(&mut inner.node, exports),
+        Place::Index(inner, index) => {
+            fold_place(&mut inner.node, exports);
+            fold_expr(index, exports);
+        }
+    }
+}
+
+fn fold_expr(expr: &mut Spanned<Expr>, exports: &ModuleExports) {
+    if let Some(&(_, value)) = exports.folded.iter().find(|(span, _)| *span == expr.span) {
+        expr.node = Expr::Literal(Literal::Integer(value));
+        return;
+    }
+    if let Some((_, fill)) = exports
+        .struct_fills
+        .iter()
+        .find(|(span, _)| *span == expr.span)
+    {
+        fill_struct_init(expr, fill);
+    }
+    if exports.constructors.contains(&expr.span) {
+        construct_struct(expr);
+    }
+    if exports.digest_eqs.contains(&expr.span) {
+        compare_digests(expr);
+    }
+    match &mut expr.node {
+        Expr::Literal(_) | Expr::Var(_) => {}
+        Expr::BinOp { lhs, rhs, .. } => {
+            fold_expr(lhs, exports);
+            fold_expr(rhs, exports);
+        }
+        Expr::Call { args, .. } => {
+            for arg in args {
+                fold_expr(arg, exports);
+            }
+        }
+        Expr::FieldAccess { expr: inner, .. } => fold_expr(inner, exports),
+        Expr::Index { expr: inner, index } => {
+            fold_expr(inner, exports);
+            fold_expr(index, exports);
+        }
+        Expr::StructInit { fields, .. } => {
+            for (_, value) in fields {
+                fold_expr(value, exports);
+            }
+        }
+        Expr::ArrayInit(elems) | Expr::Tuple(elems) => {
+            for elem in elems {
+                fold_expr(elem, exports);
+            }
+        }
+    }
+}
+
+/// List every field of a struct init in declaration order, filling in
+/// the ones it leaves out.
+fn fill_struct_init(expr: &mut Spanned<Expr>, fill: &StructFill) {
+    let span = expr.span;
+    if let Expr::StructInit { fields, base, .. } = &mut expr.node {
+        let mut listed = std::mem::take(fields);
+        for (name, value) in fill {
+            let field = match value {
+                Some(value) => (
+                    Spanned::new(name.clone(), span),
+                    Spanned::new(value.clone(), span),
+                ),
+                None => match listed.iter().position(|(n, _)| n.node == *name) {
+                    Some(i) => listed.remove(i),
+                    None => continue,
+                },
+            };
+            fields.push(field);
+        }
+        *base = None;
+    }
+}
+
+/// `Amount(x)` to `Amount { 0: x }`, `Marker` to `Marker {}`.
+fn construct_struct(expr: &mut Spanned<Expr>) {
+    let span = expr.span;
+    let (path, fields) = match std::mem::replace(&mut expr.node, Expr::Tuple(Vec::new())) {
+        Expr::Call { path, args, .. } => {
+            let fields = args
+                .into_iter()
+                .enumerate()
+                .map(|(i, arg)| (Spanned::new(i.to_string(), arg.span), arg))
+                .collect();
+            (path, fields)
+        }
+        Expr::Var(name) => {
+            let path = ModulePath(name.split('.').map(str::to_string).collect());
+            (Spanned::new(path, span), Vec::new())
+        }
+        other => {
+            expr.node = other;
+            return;
+        }
+    };
+    expr.node = Expr::StructInit {
+        path,
+        fields,
+        base: None,
+    };
+}
+
+/// `a == b` on digests to `Digest.eq(a, b)`.
+fn compare_digests(expr: &mut Spanned<Expr>) {
+    let span = expr.span;
+    match std::mem::replace(&mut expr.node, Expr::Tuple(Vec::new())) {
+        Expr::BinOp {
+            op: BinOp::Eq,
+            lhs,
+            rhs,
+        } => {
+            let path = ModulePath(vec!["Digest".to_string(), "eq".to_string()]);
+            expr.node = Expr::Call {
+                path: Spanned::new(path, span),
+                generic_args: Vec::new(),
+                args: vec![*lhs, *rhs],
+            };
+        }
+        other => expr.node = other,
+    }
+}
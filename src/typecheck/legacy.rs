@@ -0,0 +1,108 @@
+//! Deprecation lint: imports spelled with a legacy module path.
+//!
+//! The resolver still maps old paths such as `std.hash` to their new home
+//! (`vm.crypto.hash`), so such imports keep compiling until the fallback is
+//! removed. Each one gets a warning at its `use`, with a suggestion that
+//! rewrites the import and every use of the module by its full old path;
+//! `trident fix` applies it. Uses by the short name need no change, since
+//! the last segment of a module path is kept when it moves.
+
+use crate::ast::visit::{walk, Visitor};
+use crate::ast::*;
+use crate::diagnostic::{Diagnostic, ErrorCode, Suggestion};
+use crate::span::{Span, Spanned};
+
+use super::TypeChecker;
+
+impl TypeChecker {
+    /// Warn on every import of `file` that uses a legacy module path.
+    pub(super) fn check_legacy_imports(&mut self, file: &File) {
+        for use_stmt in &file.uses {
+            let old_path = use_stmt.node.as_dotted();
+            let Some(new_path) = crate::resolve::modern_module_path(&old_path) else {
+                continue;
+            };
+            let mut uses = QualifiedUses {
+                prefix: format!("{}.", old_path),
+                new_path: &new_path,
+                edits: vec![(use_stmt.span, format!("use {}", new_path))],
+                missed: false,
+            };
+            uses.visit_file(file);
+            let mut fix = Suggestion::new(format!("import '{}'", new_path), uses.edits);
+            if uses.missed {
+                fix = fix.maybe_incorrect();
+            }
+            self.diagnostics.push(
+                Diagnostic::warning(
+                    format!("'{}' is a legacy module path", old_path),
+                    use_stmt.span,
+                )
+                .with_code(ErrorCode::LegacyPath)
+                .with_help(format!("import it as '{}'", new_path))
+                .with_note(format!(
+                    "legacy paths stop resolving under the `{}` cfg flag",
+                    crate::resolve::NO_LEGACY_PATHS_FLAG
+                ))
+                .with_suggestion(fix),
+            );
+        }
+    }
+}
+
+/// Edits rewriting the uses of a module by its full legacy path.
+/// `missed` is set for a use that cannot be rewritten by its span: one
+/// nested in a compound type, or a path written with inner whitespace.
+struct QualifiedUses<'a> {
+    /// The legacy path with a trailing dot.
+    prefix: String,
+    new_path: &'a str,
+    edits: Vec<(Span, String)>,
+    missed: bool,
+}
+
+impl QualifiedUses<'_> {
+    /// Rewrite the prefix of the path `dotted` spelled by `span`.
+    fn path(&mut self, dotted: &str, span: Span) {
+        if !dotted.starts_with(&self.prefix) {
+            return;
+        }
+        if span.end - span.start != dotted.len() as u32 {
+            self.missed = true;
+            return;
+        }
+        let old_len = self.prefix.len() as u32 - 1;
+        let prefix_span = Span::new(span.file_id, span.start, span.start + old_len);
+        self.edits.push((prefix_span, self.new_path.to_string()));
+    }
+
+    /// Flag a use of the legacy path anywhere inside `ty`.
+    fn nested(&mut self, ty: &Type) {
+        match ty {
+            Type::Named(path) if path.as_dotted().starts_with(&self.prefix) => self.missed = true,
+            Type::Array(inner, _) => self.nested(inner),
+            Type::Tuple(elems) => elems.iter().for_each(|t| self.nested(t)),
+            _ => {}
+        }
+    }
+}
+
+impl Visitor for QualifiedUses<'_> {
+    fn visit_type(&mut self, ty: &Spanned<Type>) {
+        match &ty.node {
+            Type::Named(path) => self.path(&path.as_dotted(), ty.span),
+            other => self.nested(other),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        match &expr.node {
+            Expr::Var(name) => self.path(name, expr.span),
+            Expr::Call { path, .. } | Expr::StructInit { path, .. } => {
+                self.path(&path.node.as_dotted(), path.span)
+            }
+            _ => {}
+        }
+        walk::expr(self, expr);
+    }
+}
@@ -1,19 +1,35 @@
+mod alias;
 mod analysis;
 mod block;
 mod builtins;
+mod const_data;
+mod const_eval;
+mod entrypoint;
+mod enums;
 mod expr;
+mod fold;
+mod legacy;
+mod output;
+mod range_check;
+mod reserved;
 mod resolve;
+mod scope;
 mod stmt;
 #[cfg(test)]
 mod tests;
 pub mod types;
 
+pub use const_data::DataConst;
+pub use fold::fold_constants;
+pub use range_check::{insert_range_checks, RangeCheck};
+
 use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
 
 use crate::ast::*;
-use crate::diagnostic::Diagnostic;
-use crate::span::{Span, Spanned};
-use crate::types::{StructTy, Ty};
+use crate::diagnostic::{Diagnostic, ErrorCode};
+use crate::span::Span;
+use crate::types::{EnumTy, StructTy, Ty};
 
 /// A function signature for type checking.
 #[derive(Clone, Debug)]
@@ -24,13 +40,13 @@ pub(super) struct FnSig {
 
 /// A generic (size-parameterized) function definition, stored unresolved.
 #[derive(Clone, Debug)]
-pub(super) struct GenericFnDef {
+pub struct GenericFnDef {
     /// Size parameter names, e.g. `["N"]`.
-    pub(super) type_params: Vec<String>,
+    pub type_params: Vec<String>,
     /// Parameter types as AST types (may contain `ArraySize::Param`).
-    pub(super) params: Vec<(String, Type)>,
+    pub params: Vec<(String, Type)>,
     /// Return type as AST type (may contain `ArraySize::Param`).
-    pub(super) return_ty: Option<Type>,
+    pub return_ty: Option<Type>,
 }
 
 /// A monomorphized instance of a generic function.
@@ -55,11 +71,31 @@ impl MonoInstance {
 pub(super) struct VarInfo {
     pub(super) ty: Ty,
     pub(super) mutable: bool,
+    /// Name span of the `let` that bound it, where `mut` would go; `None`
+    /// for parameters, loop variables, and destructured names.
+    pub(super) let_name: Option<Span>,
 }
 
 /// A function's exported signature: (name, params, return_type).
 pub type FnExport = (String, Vec<(String, Ty)>, Ty);
 
+/// An event as the module emits it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventExport {
+    pub name: String,
+    /// Tag written ahead of the fields by `reveal` and hashed by `seal`.
+    pub tag: u64,
+    /// `#[version]` of the event's layout.
+    pub version: u64,
+    /// `#[deprecated]` note; empty when the attribute has none.
+    pub deprecated: Option<String>,
+    /// Field names in declaration order.
+    pub fields: Vec<String>,
+}
+
+/// The fields of a completed struct init, in declaration order.
+pub type StructFill = Vec<(String, Option<Expr>)>;
+
 /// Exported signatures from a type-checked module.
 #[derive(Clone, Debug)]
 pub struct ModuleExports {
@@ -67,12 +103,89 @@ pub struct ModuleExports {
     pub functions: Vec<FnExport>,
     pub constants: Vec<(String, Ty, u64)>, // (name, ty, value)
     pub structs: Vec<StructTy>,            // exported struct types
+    pub enums: Vec<EnumTy>,                // exported enum types
     pub warnings: Vec<Diagnostic>,         // non-fatal diagnostics
     /// Unique monomorphized instances of generic functions to emit.
     pub mono_instances: Vec<MonoInstance>,
     /// Per-call-site resolution: each generic call in AST order maps to a MonoInstance.
     /// The emitter consumes these in order to know which mangled name to call.
     pub call_resolutions: Vec<MonoInstance>,
+    /// Exported generic functions, with the module's constants folded into
+    /// their sizes and its struct names qualified, so importers can
+    /// instantiate them.
+    pub generic_functions: Vec<(String, GenericFnDef)>,
+    /// Instances of other modules' generic functions this module calls,
+    /// under the name used at the call site (`merkle.verify`), with their
+    /// return types. The owning module emits them.
+    pub foreign_instances: Vec<(MonoInstance, Ty)>,
+    /// Exported functions that perform Merkle authentication.
+    pub authenticating: Vec<String>,
+    /// Exported `#[authenticated]` struct names.
+    pub authenticated_structs: Vec<String>,
+    /// Exported functions that access RAM, with the builtin they wrap.
+    pub ram_access: Vec<(String, String)>,
+    /// Exported functions that may write public output, with the lengths
+    /// in field elements of their paths; `None` when unknown.
+    pub output_writes: Vec<(String, Option<Vec<u64>>)>,
+    /// Declared `pub output` types with their widths in field elements.
+    pub public_output: Vec<(String, u64)>,
+    /// The module's events, in tag order.
+    pub events: Vec<EventExport>,
+    /// The program's entrypoints by selector, in selector order.
+    pub entrypoints: Vec<(u64, String)>,
+    /// Range checks planned by `#[auto_range_check]`, applied to the AST
+    /// with `insert_range_checks`.
+    pub range_checks: Vec<RangeCheck>,
+    /// Expressions with a compile-time value the AST does not spell as a
+    /// literal, by span, written into the AST with `fold_constants`.
+    pub folded: Vec<(Span, u64)>,
+    /// Struct inits that leave fields to `..base` or defaults, by span:
+    /// every field in declaration order, with the expression that fills it
+    /// in, or `None` for a field the init lists.
+    pub struct_fills: Vec<(Span, StructFill)>,
+    /// Calls `Amount(x)` of tuple structs and names `Marker` of unit
+    /// structs, by span, rewritten to struct inits by `fold_constants`.
+    pub constructors: Vec<Span>,
+    /// Digest comparisons `a == b`, by span, rewritten to calls of the
+    /// `Digest.eq` intrinsic by `fold_constants`.
+    pub digest_eqs: Vec<Span>,
+    /// Assertion messages, each once, in order of first use.
+    pub assertion_messages: Vec<String>,
+    /// `fail` codes by constant name, each once, in order of first use.
+    pub fail_codes: Vec<(String, u64)>,
+    /// The module's `include_data` tables, in declaration order.
+    pub data: Vec<DataConst>,
+    /// Reads of tables at indices not known at compile time, by the name
+    /// the module uses (`SBOX`, `aes.SBOX`), with their counts.
+    pub data_reads: Vec<(String, usize)>,
+}
+
+/// Build the error table of a program: `fail` codes under their constant
+/// names, then the assertion messages. Messages take the codes from 1 that
+/// no `fail` uses, in order of first use across `exports`, which come in
+/// dependency order, so a program always gets the same table. A message
+/// spelled like a `fail` constant shares its code.
+pub fn error_codes<'a>(
+    exports: impl IntoIterator<Item = &'a ModuleExports>,
+) -> BTreeMap<String, u64> {
+    let exports: Vec<&ModuleExports> = exports.into_iter().collect();
+    let mut codes = BTreeMap::new();
+    for (name, code) in exports.iter().flat_map(|e| &e.fail_codes) {
+        codes.entry(name.clone()).or_insert(*code);
+    }
+    let mut taken: BTreeSet<u64> = codes.values().copied().collect();
+    let mut next = 1;
+    for message in exports.iter().flat_map(|e| &e.assertion_messages) {
+        if codes.contains_key(message) {
+            continue;
+        }
+        while taken.contains(&next) {
+            next += 1;
+        }
+        codes.insert(message.clone(), next);
+        taken.insert(next);
+    }
+    codes
 }
 
 pub(crate) struct TypeChecker {
@@ -84,8 +197,12 @@ pub(crate) struct TypeChecker {
     pub(super) constants: BTreeMap<String, u64>,
     /// Known struct types (name or module.name -> StructTy).
     pub(super) structs: BTreeMap<String, StructTy>,
+    /// Known enum types (name or module.name -> EnumTy).
+    pub(super) enums: BTreeMap<String, EnumTy>,
     /// Known event types (name -> field list).
     pub(super) events: BTreeMap<String, Vec<(String, Ty)>>,
+    /// `#[deprecated]` events (name -> note).
+    pub(super) deprecated_events: BTreeMap<String, String>,
     /// Accumulated diagnostics.
     pub(super) diagnostics: Vec<Diagnostic>,
     /// Variables proven to be in U32 range (via as_u32, split, or U32 type).
@@ -96,12 +213,56 @@ pub(crate) struct TypeChecker {
     pub(super) mono_instances: Vec<MonoInstance>,
     /// Per-call-site resolutions in AST walk order.
     pub(super) call_resolutions: Vec<MonoInstance>,
+    /// Instances of imported generic functions, with their return types.
+    pub(super) foreign_instances: Vec<(MonoInstance, Ty)>,
     /// Active cfg flags for conditional compilation.
     pub(super) cfg_flags: BTreeSet<String>,
     /// Target VM configuration (digest width, hash rate, field limbs, etc.).
     pub(super) target_config: crate::target::TerrainConfig,
+    /// The target's intrinsics: signatures, costs, and purity.
+    pub(super) intrinsics: crate::config::intrinsics::IntrinsicTable,
     /// Whether we are currently inside a `#[pure]` function body.
     pub(super) in_pure_fn: bool,
+    /// Loops enclosing the statement being checked.
+    pub(super) loop_depth: usize,
+    /// Functions known to perform Merkle authentication (builtins,
+    /// imported, and local), by the name used at call sites.
+    pub(super) authenticating: BTreeSet<String>,
+    /// `#[authenticated]` struct types (local and imported).
+    pub(super) authenticated_structs: BTreeSet<String>,
+    /// Whether we are currently inside an `#[authenticated]` function body.
+    pub(super) in_authenticated_fn: bool,
+    /// `let`-bound sponge states not yet read: (scope depth, name, span).
+    pub(super) unused_sponges: Vec<(usize, String, Span)>,
+    /// Functions that access RAM, by call name, with the builtin they wrap.
+    pub(super) ram_access: BTreeMap<String, String>,
+    /// Functions that may write public output, by call name, with the
+    /// lengths they write.
+    pub(super) output_writes: BTreeMap<String, output::Lengths>,
+    /// Range checks planned at Field → U32 boundaries.
+    pub(super) range_checks: range_check::RangeCheckPlan,
+    /// Compile-time values of non-literal loop ends, constants, and
+    /// `len()` calls.
+    pub(super) folded: Vec<(Span, u64)>,
+    /// Struct inits completed from `..base` or defaults.
+    pub(super) struct_fills: Vec<(Span, StructFill)>,
+    /// Tuple and unit struct constructors.
+    pub(super) constructors: Vec<Span>,
+    /// Digest comparisons with `==`.
+    pub(super) digest_eqs: Vec<Span>,
+    /// Non-generic `#[pure]` functions of the module, for the constant
+    /// evaluator.
+    pub(super) pure_fns: BTreeMap<String, FnDef>,
+    /// Assertion messages seen so far, in order of first use.
+    pub(super) assertion_messages: Vec<String>,
+    /// `fail` codes seen so far, by constant name.
+    pub(super) fail_codes: Vec<(String, u64)>,
+    /// `include_data` tables (local and imported), by name.
+    pub(super) data_consts: BTreeMap<String, DataConst>,
+    /// Runtime-indexed reads of tables, by name.
+    pub(super) data_reads: BTreeMap<String, usize>,
+    /// Directory `include_data` paths are relative to.
+    pub(super) source_dir: Option<PathBuf>,
 }
 
 impl Default for TypeChecker {
@@ -121,15 +282,46 @@ impl TypeChecker {
             scopes: Vec::new(),
             constants: BTreeMap::new(),
             structs: BTreeMap::new(),
+            enums: BTreeMap::new(),
             events: BTreeMap::new(),
+            deprecated_events: BTreeMap::new(),
             diagnostics: Vec::new(),
             u32_proven: BTreeSet::new(),
             generic_fns: BTreeMap::new(),
             mono_instances: Vec::new(),
             call_resolutions: Vec::new(),
+            foreign_instances: Vec::new(),
             cfg_flags: BTreeSet::from(["debug".to_string()]),
+            intrinsics: crate::config::intrinsics::IntrinsicTable::for_target(&config),
             target_config: config,
             in_pure_fn: false,
+            loop_depth: 0,
+            authenticating: BTreeSet::from([
+                "merkle_step".to_string(),
+                "merkle_step_mem".to_string(),
+            ]),
+            authenticated_structs: BTreeSet::new(),
+            in_authenticated_fn: false,
+            unused_sponges: Vec::new(),
+            ram_access: alias::RAM_BUILTINS
+                .iter()
+                .map(|b| (b.to_string(), b.to_string()))
+                .collect(),
+            output_writes: output::WRITE_BUILTINS
+                .iter()
+                .map(|&(b, n)| (b.to_string(), Some(BTreeSet::from([n]))))
+                .collect(),
+            range_checks: range_check::RangeCheckPlan::default(),
+            folded: Vec::new(),
+            struct_fills: Vec::new(),
+            constructors: Vec::new(),
+            digest_eqs: Vec::new(),
+            pure_fns: BTreeMap::new(),
+            assertion_messages: Vec::new(),
+            fail_codes: Vec::new(),
+            data_consts: BTreeMap::new(),
+            data_reads: BTreeMap::new(),
+            source_dir: None,
         };
         tc.register_builtins();
         tc
@@ -141,22 +333,16 @@ impl TypeChecker {
         self
     }
 
-    /// Check if an item's cfg attribute is active.
-    fn is_cfg_active(&self, cfg: &Option<Spanned<String>>) -> bool {
-        match cfg {
-            None => true,
-            Some(flag) => self.cfg_flags.contains(&flag.node),
-        }
+    /// Set the directory `include_data` reads files from, the one of the
+    /// module's source.
+    pub(crate) fn with_source_dir(mut self, dir: &Path) -> Self {
+        self.source_dir = Some(dir.to_path_buf());
+        self
     }
 
     /// Check if a top-level item's cfg is active.
     fn is_item_cfg_active(&self, item: &Item) -> bool {
-        match item {
-            Item::Fn(f) => self.is_cfg_active(&f.cfg),
-            Item::Const(c) => self.is_cfg_active(&c.cfg),
-            Item::Struct(s) => self.is_cfg_active(&s.cfg),
-            Item::Event(e) => self.is_cfg_active(&e.cfg),
-        }
+        crate::ast::desugar::is_item_active(item, &self.cfg_flags)
     }
 
     /// Import exported signatures from another module.
@@ -184,6 +370,14 @@ impl TypeChecker {
                 self.functions.insert(short, sig);
             }
         }
+        for (fn_name, gdef) in &exports.generic_functions {
+            let qualified = format!("{}.{}", exports.module_name, fn_name);
+            self.generic_fns.insert(qualified, gdef.clone());
+            if has_short {
+                let short = format!("{}.{}", short_prefix, fn_name);
+                self.generic_fns.insert(short, gdef.clone());
+            }
+        }
         for (const_name, _ty, value) in &exports.constants {
             let qualified = format!("{}.{}", exports.module_name, const_name);
             self.constants.insert(qualified, *value);
@@ -192,6 +386,47 @@ impl TypeChecker {
                 self.constants.insert(short, *value);
             }
         }
+        for data in exports.data.iter().filter(|d| d.is_pub) {
+            let qualified = format!("{}.{}", exports.module_name, data.name);
+            self.data_consts.insert(qualified, data.clone());
+            if has_short {
+                let short = format!("{}.{}", short_prefix, data.name);
+                self.data_consts.insert(short, data.clone());
+            }
+        }
+        for fn_name in &exports.authenticating {
+            self.authenticating
+                .insert(format!("{}.{}", exports.module_name, fn_name));
+            if has_short {
+                self.authenticating
+                    .insert(format!("{}.{}", short_prefix, fn_name));
+            }
+        }
+        for (fn_name, builtin) in &exports.ram_access {
+            let qualified = format!("{}.{}", exports.module_name, fn_name);
+            self.ram_access.insert(qualified, builtin.clone());
+            if has_short {
+                let short = format!("{}.{}", short_prefix, fn_name);
+                self.ram_access.insert(short, builtin.clone());
+            }
+        }
+        for (fn_name, lengths) in &exports.output_writes {
+            let lengths: output::Lengths = lengths.as_ref().map(|l| l.iter().copied().collect());
+            let qualified = format!("{}.{}", exports.module_name, fn_name);
+            self.output_writes.insert(qualified, lengths.clone());
+            if has_short {
+                let short = format!("{}.{}", short_prefix, fn_name);
+                self.output_writes.insert(short, lengths);
+            }
+        }
+        for name in &exports.authenticated_structs {
+            self.authenticated_structs
+                .insert(format!("{}.{}", exports.module_name, name));
+            if has_short {
+                self.authenticated_structs
+                    .insert(format!("{}.{}", short_prefix, name));
+            }
+        }
         for sty in &exports.structs {
             let qualified = format!("{}.{}", exports.module_name, sty.name);
             self.structs.insert(qualified, sty.clone());
@@ -200,6 +435,14 @@ impl TypeChecker {
                 self.structs.insert(short, sty.clone());
             }
         }
+        for ety in &exports.enums {
+            let qualified = format!("{}.{}", exports.module_name, ety.name);
+            self.enums.insert(qualified, ety.clone());
+            if has_short {
+                let short = format!("{}.{}", short_prefix, ety.name);
+                self.enums.insert(short, ety.clone());
+            }
+        }
     }
 
     pub(crate) fn check_file(mut self, file: &File) -> Result<ModuleExports, Vec<Diagnostic>> {
@@ -209,6 +452,9 @@ impl TypeChecker {
             || file.name.node.starts_with("ext.")
             || file.name.node.contains(".ext.");
 
+        // Constants first, so array sizes and loop ends can name them
+        self.evaluate_constants(file);
+
         // First pass: register all structs, function signatures, and constants
         for item in &file.items {
             // Skip items excluded by conditional compilation
@@ -222,16 +468,24 @@ impl TypeChecker {
                         .iter()
                         .map(|f| (f.name.node.clone(), self.resolve_type(&f.ty.node), f.is_pub))
                         .collect();
+                    let defaults = self.struct_defaults(sdef, &fields);
                     let sty = StructTy {
                         name: sdef.name.node.clone(),
                         fields,
+                        defaults,
+                        kind: sdef.kind,
                     };
                     self.structs.insert(sdef.name.node.clone(), sty);
+                    if sdef.is_authenticated {
+                        self.authenticated_structs.insert(sdef.name.node.clone());
+                    }
                 }
+                Item::Enum(edef) => self.register_enum(edef),
                 Item::Fn(func) => {
                     // #[intrinsic] is only allowed in vm.*/std.*/os.*/ext.* modules
                     if func.intrinsic.is_some() && !is_std_module {
                         self.error(
+                            ErrorCode::IntrinsicOutsideStd,
                             format!(
                                 "#[intrinsic] is only allowed in vm.*/std.*/os.* modules, \
                                  not in '{}'",
@@ -268,14 +522,12 @@ impl TypeChecker {
                         self.generic_fns.insert(func.name.node.clone(), gdef);
                     }
                 }
-                Item::Const(cdef) => {
-                    if let Expr::Literal(Literal::Integer(v)) = &cdef.value.node {
-                        self.constants.insert(cdef.name.node.clone(), *v);
-                    }
-                }
+                // Registered by `evaluate_constants`
+                Item::Const(_) => {}
                 Item::Event(edef) => {
                     if edef.fields.len() > 9 {
                         self.error(
+                            ErrorCode::InvalidEvent,
                             format!(
                                 "event '{}' has {} fields, max is 9",
                                 edef.name.node,
@@ -291,6 +543,7 @@ impl TypeChecker {
                             let ty = self.resolve_type(&f.ty.node);
                             if ty != Ty::Field {
                                 self.error(
+                                    ErrorCode::InvalidEvent,
                                     format!(
                                         "event field '{}' must be Field type, got {}",
                                         f.name.node,
@@ -303,6 +556,10 @@ impl TypeChecker {
                         })
                         .collect();
                     self.events.insert(edef.name.node.clone(), fields);
+                    if let Some(note) = &edef.deprecated {
+                        self.deprecated_events
+                            .insert(edef.name.node.clone(), note.node.clone());
+                    }
                 }
             }
         }
@@ -310,6 +567,13 @@ impl TypeChecker {
         // Recursion detection: build call graph and reject cycles
         self.detect_recursion(file);
 
+        // Merkle authentication: infer which functions authenticate and
+        // reject `#[authenticated]` functions that do not
+        self.check_authentication(file);
+
+        // RAM aliasing: one mutable handle per RAM region per scope
+        self.check_ram_aliasing(file);
+
         // Second pass: type check function bodies
         for item in &file.items {
             if !self.is_item_cfg_active(&item.node) {
@@ -320,6 +584,15 @@ impl TypeChecker {
             }
         }
 
+        // Entrypoints: dispatchable signatures and distinct selectors
+        self.check_entrypoints(file);
+
+        // Public output: every path through main writes the declared shape
+        self.check_public_output(file);
+
+        // Names that upcoming keywords will take over
+        self.check_reserved_names(file);
+
         // Unused import detection: collect used module prefixes from all calls
         let mut used_prefixes: BTreeSet<String> = BTreeSet::new();
         for item in &file.items {
@@ -341,21 +614,38 @@ impl TypeChecker {
                 .unwrap_or(&module_path)
                 .to_string();
             if !used_prefixes.contains(&short) && !used_prefixes.contains(&module_path) {
-                self.warning(format!("unused import '{}'", module_path), use_stmt.span);
+                self.warning(
+                    ErrorCode::UnusedImport,
+                    format!("unused import '{}'", module_path),
+                    use_stmt.span,
+                );
             }
         }
 
+        // Imports by a path the resolver only maps for compatibility
+        self.check_legacy_imports(file);
+
         // Collect exports (pub items only)
         let module_name = file.name.node.clone();
         let mut exported_fns = Vec::new();
+        let mut exported_generics = Vec::new();
         let mut exported_consts = Vec::new();
         let mut exported_structs = Vec::new();
+        let mut exported_enums = Vec::new();
+        let mut authenticating = Vec::new();
+        let mut authenticated_structs = Vec::new();
 
         for item in &file.items {
             if !self.is_item_cfg_active(&item.node) {
                 continue;
             }
             match &item.node {
+                Item::Fn(func) if func.is_pub && !func.type_params.is_empty() => {
+                    if let Some(gdef) = self.generic_fns.get(&func.name.node) {
+                        let gdef = self.exported_generic(gdef, &module_name);
+                        exported_generics.push((func.name.node.clone(), gdef));
+                    }
+                }
                 Item::Fn(func) if func.is_pub => {
                     let params: Vec<(String, Ty)> = func
                         .params
@@ -368,17 +658,28 @@ impl TypeChecker {
                         .map(|t| self.resolve_type(&t.node))
                         .unwrap_or(Ty::Unit);
                     exported_fns.push((func.name.node.clone(), params, return_ty));
+                    if self.authenticating.contains(&func.name.node) {
+                        authenticating.push(func.name.node.clone());
+                    }
                 }
                 Item::Const(cdef) if cdef.is_pub => {
                     let ty = self.resolve_type(&cdef.ty.node);
-                    if let Expr::Literal(Literal::Integer(v)) = &cdef.value.node {
-                        exported_consts.push((cdef.name.node.clone(), ty, *v));
+                    if let Some(&v) = self.constants.get(&cdef.name.node) {
+                        exported_consts.push((cdef.name.node.clone(), ty, v));
                     }
                 }
                 Item::Struct(sdef) if sdef.is_pub => {
                     if let Some(sty) = self.structs.get(&sdef.name.node) {
                         exported_structs.push(sty.clone());
                     }
+                    if sdef.is_authenticated {
+                        authenticated_structs.push(sdef.name.node.clone());
+                    }
+                }
+                Item::Enum(edef) if edef.is_pub => {
+                    if let Some(ety) = self.enums.get(&edef.name.node) {
+                        exported_enums.push(ety.clone());
+                    }
                 }
                 _ => {}
             }
@@ -391,55 +692,175 @@ impl TypeChecker {
         if has_errors {
             Err(self.diagnostics)
         } else {
+            let ram_access = self.ram_exports(file);
+            let output_writes = self.output_exports(file);
+            let public_output = file
+                .declarations
+                .iter()
+                .filter_map(|d| match d {
+                    Declaration::PubOutput(ty) => {
+                        let ty = self.resolve_type(&ty.node);
+                        Some((ty.display(), ty.width() as u64))
+                    }
+                    _ => None,
+                })
+                .collect();
+            let events = file
+                .items
+                .iter()
+                .filter(|item| self.is_item_cfg_active(&item.node))
+                .filter_map(|item| match &item.node {
+                    Item::Event(edef) => Some(edef),
+                    _ => None,
+                })
+                .enumerate()
+                .map(|(i, edef)| EventExport {
+                    name: edef.name.node.clone(),
+                    tag: edef.tag(i as u64),
+                    version: edef.version,
+                    deprecated: edef.deprecated.as_ref().map(|n| n.node.clone()),
+                    fields: edef.fields.iter().map(|f| f.name.node.clone()).collect(),
+                })
+                .collect();
+            let entrypoints = self.entrypoint_exports(file);
+            let data = file
+                .items
+                .iter()
+                .filter_map(|item| match &item.node {
+                    Item::Const(cdef) => self.data_consts.get(&cdef.name.node).cloned(),
+                    _ => None,
+                })
+                .collect();
             Ok(ModuleExports {
                 module_name,
                 functions: exported_fns,
                 constants: exported_consts,
                 structs: exported_structs,
+                enums: exported_enums,
                 warnings: self.diagnostics,
                 mono_instances: self.mono_instances,
                 call_resolutions: self.call_resolutions,
+                generic_functions: exported_generics,
+                foreign_instances: self.foreign_instances,
+                authenticating,
+                authenticated_structs,
+                ram_access,
+                output_writes,
+                public_output,
+                events,
+                entrypoints,
+                range_checks: self.range_checks.into_checks(),
+                folded: self.folded,
+                struct_fills: self.struct_fills,
+                constructors: self.constructors,
+                digest_eqs: self.digest_eqs,
+                assertion_messages: self.assertion_messages,
+                fail_codes: self.fail_codes,
+                data,
+                data_reads: self.data_reads.into_iter().collect(),
             })
         }
     }
 
-    // --- Scope management ---
-
-    pub(super) fn push_scope(&mut self) {
-        self.scopes.push(BTreeMap::new());
-    }
-
-    pub(super) fn pop_scope(&mut self) {
-        self.scopes.pop();
+    /// A generic signature as importers see it: sizes naming the module's
+    /// constants get their values, and the module's own structs their
+    /// qualified names.
+    fn exported_generic(&self, gdef: &GenericFnDef, module: &str) -> GenericFnDef {
+        GenericFnDef {
+            type_params: gdef.type_params.clone(),
+            params: gdef
+                .params
+                .iter()
+                .map(|(name, ty)| (name.clone(), self.exported_type(ty, gdef, module)))
+                .collect(),
+            return_ty: gdef
+                .return_ty
+                .as_ref()
+                .map(|ty| self.exported_type(ty, gdef, module)),
+        }
     }
 
-    pub(super) fn define_var(&mut self, name: &str, ty: Ty, mutable: bool) {
-        if let Some(scope) = self.scopes.last_mut() {
-            scope.insert(name.to_string(), VarInfo { ty, mutable });
+    fn exported_type(&self, ty: &Type, gdef: &GenericFnDef, module: &str) -> Type {
+        match ty {
+            Type::Array(inner, size) => Type::Array(
+                Box::new(self.exported_type(inner, gdef, module)),
+                self.exported_size(size, gdef),
+            ),
+            Type::Tuple(elems) => Type::Tuple(
+                elems
+                    .iter()
+                    .map(|t| self.exported_type(t, gdef, module))
+                    .collect(),
+            ),
+            Type::Named(path) if path.0.len() == 1 && self.structs.contains_key(&path.0[0]) => {
+                let mut qualified: Vec<String> = module.split('.').map(String::from).collect();
+                qualified.push(path.0[0].clone());
+                Type::Named(ModulePath(qualified))
+            }
+            _ => ty.clone(),
         }
     }
 
-    pub(super) fn lookup_var(&self, name: &str) -> Option<&VarInfo> {
-        for scope in self.scopes.iter().rev() {
-            if let Some(info) = scope.get(name) {
-                return Some(info);
+    fn exported_size(&self, size: &ArraySize, gdef: &GenericFnDef) -> ArraySize {
+        match size {
+            ArraySize::Param(name) if !gdef.type_params.contains(name) => {
+                match self.constants.get(name) {
+                    Some(&value) => ArraySize::Literal(value),
+                    None => size.clone(),
+                }
             }
+            ArraySize::Add(a, b) => ArraySize::Add(
+                Box::new(self.exported_size(a, gdef)),
+                Box::new(self.exported_size(b, gdef)),
+            ),
+            ArraySize::Mul(a, b) => ArraySize::Mul(
+                Box::new(self.exported_size(a, gdef)),
+                Box::new(self.exported_size(b, gdef)),
+            ),
+            _ => size.clone(),
         }
-        None
     }
 
     // --- Diagnostics ---
 
-    pub(super) fn error(&mut self, msg: String, span: Span) {
-        self.diagnostics.push(Diagnostic::error(msg, span));
+    pub(super) fn error(&mut self, code: ErrorCode, msg: String, span: Span) {
+        self.diagnostics
+            .push(Diagnostic::error(msg, span).with_code(code));
+    }
+
+    pub(super) fn error_with_help(
+        &mut self,
+        code: ErrorCode,
+        msg: String,
+        span: Span,
+        help: String,
+    ) {
+        self.diagnostics
+            .push(Diagnostic::error(msg, span).with_code(code).with_help(help));
     }
 
-    pub(super) fn error_with_help(&mut self, msg: String, span: Span, help: String) {
+    pub(super) fn warning(&mut self, code: ErrorCode, msg: String, span: Span) {
         self.diagnostics
-            .push(Diagnostic::error(msg, span).with_help(help));
+            .push(Diagnostic::warning(msg, span).with_code(code));
+    }
+
+    pub(super) fn warning_with_help(
+        &mut self,
+        code: ErrorCode,
+        msg: String,
+        span: Span,
+        help: String,
+    ) {
+        self.diagnostics.push(
+            Diagnostic::warning(msg, span)
+                .with_code(code)
+                .with_help(help),
+        );
     }
 
-    pub(super) fn warning(&mut self, msg: String, span: Span) {
+    /// An optimization hint. Hints carry their `hint[Hxxxx]` code in the
+    /// message rather than an [`ErrorCode`].
+    pub(super) fn hint(&mut self, msg: String, span: Span) {
         self.diagnostics.push(Diagnostic::warning(msg, span));
     }
 }
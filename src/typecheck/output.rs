@@ -0,0 +1,430 @@
+//! Public output: `pub output` declarations checked against the writes.
+//!
+//! Every function gets the lengths, in field elements, of the public
+//! output it may write: one length per distinct path, through
+//! `pub_write*`, `reveal`, `seal`, inline `write_io`, and calls. Loops
+//! with a constant trip count repeat their body's lengths; any other loop
+//! that writes makes the length unknown. The VM writes untyped field
+//! elements, so a program's declarations fix the layout of its output:
+//! every path through `main` must write exactly the field elements the
+//! declared types occupy.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::ast::*;
+use crate::diagnostic::ErrorCode;
+use crate::span::Spanned;
+
+use super::TypeChecker;
+
+/// Distinct output lengths of the paths through some code; `None` when a
+/// loop makes them unknown. An empty set means no path gets past it.
+pub(super) type Lengths = Option<BTreeSet<u64>>;
+
+/// More distinct lengths than this are treated as unknown.
+const MAX_LENGTHS: usize = 64;
+
+/// Builtins that write public output, with the elements they write.
+pub(super) const WRITE_BUILTINS: [(&str, u64); 5] = [
+    ("pub_write", 1),
+    ("pub_write2", 2),
+    ("pub_write3", 3),
+    ("pub_write4", 4),
+    ("pub_write5", 5),
+];
+
+fn exactly(n: u64) -> Lengths {
+    Some(BTreeSet::from([n]))
+}
+
+/// Lengths of `a` followed by `b`.
+fn then(a: &Lengths, b: &Lengths) -> Lengths {
+    let (a, b) = (a.as_ref()?, b.as_ref()?);
+    let sums: BTreeSet<u64> = a
+        .iter()
+        .flat_map(|x| b.iter().map(move |y| x + y))
+        .collect();
+    (sums.len() <= MAX_LENGTHS).then_some(sums)
+}
+
+/// Lengths of either `a` or `b`.
+fn either(a: &Lengths, b: &Lengths) -> Lengths {
+    let (a, b) = (a.as_ref()?, b.as_ref()?);
+    let all: BTreeSet<u64> = a.union(b).copied().collect();
+    (all.len() <= MAX_LENGTHS).then_some(all)
+}
+
+/// Lengths of `body` repeated `times` times.
+fn repeat(body: &Lengths, times: u64) -> Lengths {
+    match body.as_ref()? {
+        b if b.len() == 1 => b.first().map(|n| BTreeSet::from([n * times])),
+        _ => {
+            // Each repetition adds a length, so this stops within
+            // MAX_LENGTHS rounds.
+            let mut acc = exactly(0);
+            for _ in 0..times {
+                acc = then(&acc, body);
+                acc.as_ref()?;
+            }
+            acc
+        }
+    }
+}
+
+/// Elements written by the `write_io` instructions of an `asm` block.
+fn asm_writes(body: &str) -> u64 {
+    body.lines()
+        .filter_map(|line| {
+            let mut words = line.split("//").next()?.split_whitespace();
+            (words.next()? == "write_io")
+                .then(|| words.next().and_then(|n| n.parse::<u64>().ok()))?
+        })
+        .sum()
+}
+
+struct OutputWalker<'a> {
+    /// Output lengths of the functions that may write, by call name.
+    writes: &'a BTreeMap<String, Lengths>,
+    constants: &'a BTreeMap<String, u64>,
+    digest_width: u64,
+    target: &'a str,
+    /// Paths still running.
+    open: Lengths,
+    /// Paths that returned.
+    returned: Lengths,
+    /// Paths that left the current loop iteration by `break` or `continue`.
+    skipped: Lengths,
+    /// Whether a path leaves the current loop by `break`.
+    broke: bool,
+}
+
+impl TypeChecker {
+    /// Compute the public output each function may write and check `main`
+    /// against the program's `pub output` declarations.
+    pub(super) fn check_public_output(&mut self, file: &File) {
+        let fns: Vec<&FnDef> = file
+            .items
+            .iter()
+            .filter(|item| self.is_item_cfg_active(&item.node))
+            .filter_map(|item| match &item.node {
+                Item::Fn(func) => Some(func),
+                _ => None,
+            })
+            .collect();
+
+        // Callees come first in no particular order; recursion is
+        // rejected, so the lengths settle within one round per function.
+        for _ in 0..=fns.len() {
+            let mut changed = false;
+            for func in &fns {
+                let lengths = match (&func.intrinsic, &func.body) {
+                    (Some(intrinsic), _) => {
+                        let name = intrinsic
+                            .node
+                            .trim_start_matches("intrinsic(")
+                            .trim_end_matches(')');
+                        self.output_writes.get(name).cloned().unwrap_or(exactly(0))
+                    }
+                    (None, Some(body)) => {
+                        let mut walker = OutputWalker {
+                            writes: &self.output_writes,
+                            constants: &self.constants,
+                            digest_width: self.target_config.digest_width as u64,
+                            target: &self.target_config.name,
+                            open: exactly(0),
+                            returned: Some(BTreeSet::new()),
+                            skipped: Some(BTreeSet::new()),
+                            broke: false,
+                        };
+                        walker.block(&body.node);
+                        either(&walker.open, &walker.returned)
+                    }
+                    (None, None) => exactly(0),
+                };
+                let name = &func.name.node;
+                if lengths == exactly(0) {
+                    changed |= self.output_writes.remove(name).is_some();
+                } else if self.output_writes.get(name) != Some(&lengths) {
+                    self.output_writes.insert(name.clone(), lengths);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        if file.kind != FileKind::Program {
+            return;
+        }
+        let declared: Vec<&Spanned<Type>> = file
+            .declarations
+            .iter()
+            .filter_map(|d| match d {
+                Declaration::PubOutput(ty) => Some(ty),
+                _ => None,
+            })
+            .collect();
+        let Some(main) = fns.iter().find(|f| f.name.node == "main") else {
+            return;
+        };
+        if declared.is_empty() {
+            return;
+        }
+        let width: u64 = declared
+            .iter()
+            .map(|ty| self.resolve_type(&ty.node).width() as u64)
+            .sum();
+        let layout: Vec<String> = declared
+            .iter()
+            .map(|ty| self.resolve_type(&ty.node).display())
+            .collect();
+        let declares = format!(
+            "`pub output` declares {} field element{} ({})",
+            width,
+            if width == 1 { "" } else { "s" },
+            layout.join(", ")
+        );
+        let lengths = self
+            .output_writes
+            .get("main")
+            .cloned()
+            .unwrap_or(exactly(0));
+        let message = match &lengths {
+            None => Some(format!(
+                "main writes public output in a loop without a constant trip count; {}",
+                declares
+            )),
+            Some(lengths) if lengths.iter().any(|&n| n != width) => {
+                let written: Vec<String> = lengths.iter().map(|n| n.to_string()).collect();
+                let written = match written.split_last() {
+                    Some((last, rest)) if !rest.is_empty() => {
+                        format!("{} or {}", rest.join(", "), last)
+                    }
+                    _ => written.join(""),
+                };
+                Some(format!(
+                    "main writes {} field element{} of public output{}, but {}",
+                    written,
+                    if written == "1" { "" } else { "s" },
+                    if lengths.len() > 1 {
+                        " depending on the path"
+                    } else {
+                        ""
+                    },
+                    declares
+                ))
+            }
+            Some(_) => None,
+        };
+        if let Some(message) = message {
+            self.error_with_help(
+                ErrorCode::OutputNotWritten,
+                message,
+                main.name.span,
+                "every path through main must write the declared output, in full".to_string(),
+            );
+        }
+    }
+
+    /// Exported functions that may write public output, with the lengths
+    /// they write.
+    pub(super) fn output_exports(&self, file: &File) -> Vec<(String, Option<Vec<u64>>)> {
+        file.items
+            .iter()
+            .filter_map(|item| match &item.node {
+                Item::Fn(func) if func.is_pub => self.output_writes.get(&func.name.node).map(|l| {
+                    (
+                        func.name.node.clone(),
+                        l.as_ref().map(|s| s.iter().copied().collect()),
+                    )
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl OutputWalker<'_> {
+    fn block(&mut self, block: &Block) {
+        let active = active_stmts(&block.stmts, self.target);
+        for (stmt, active) in block.stmts.iter().zip(active) {
+            if active {
+                self.stmt(&stmt.node);
+            }
+        }
+        if let Some(tail) = &block.tail_expr {
+            self.expr(&tail.node);
+        }
+    }
+
+    /// Walk `block` from the current paths, returning the paths that
+    /// leave it and restoring the current ones.
+    fn branch(&mut self, block: Option<&Block>) -> Lengths {
+        let start = self.open.clone();
+        if let Some(block) = block {
+            self.block(block);
+        }
+        std::mem::replace(&mut self.open, start)
+    }
+
+    /// Walk a loop body that runs `trips` times, or an unknown number of
+    /// times if `None`.
+    fn loop_body(&mut self, body: &Block, trips: Option<u64>) {
+        let mut inner = OutputWalker {
+            writes: self.writes,
+            constants: self.constants,
+            digest_width: self.digest_width,
+            target: self.target,
+            open: exactly(0),
+            returned: Some(BTreeSet::new()),
+            skipped: Some(BTreeSet::new()),
+            broke: false,
+        };
+        inner.block(body);
+        let silent = |l: &Lengths| l.as_ref().is_some_and(|s| s.iter().all(|&n| n == 0));
+        // An iteration ends at the end of the body or at a `continue`;
+        // a `break` makes the number of iterations unknown.
+        let iteration = either(&inner.open, &inner.skipped);
+        let trips = if inner.broke { None } else { trips };
+        // A return inside the loop leaves after an unknown number
+        // of iterations.
+        if inner.returned.as_ref().is_some_and(|r| !r.is_empty()) {
+            let returned = if silent(&iteration) && silent(&inner.returned) {
+                self.open.clone()
+            } else {
+                None
+            };
+            self.returned = either(&self.returned, &returned);
+        }
+        if !silent(&iteration) {
+            let body = trips.and_then(|n| repeat(&iteration, n));
+            self.open = then(&self.open, &body);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Let { init: e, .. }
+            | Stmt::Assign { value: e, .. }
+            | Stmt::TupleAssign { value: e, .. }
+            | Stmt::Expr(e) => self.expr(&e.node),
+            Stmt::Return(value) => {
+                if let Some(value) = value {
+                    self.expr(&value.node);
+                }
+                self.returned = either(&self.returned, &self.open);
+                self.open = Some(BTreeSet::new());
+            }
+            Stmt::Break | Stmt::Continue => {
+                self.broke |= matches!(stmt, Stmt::Break);
+                self.skipped = either(&self.skipped, &self.open);
+                self.open = Some(BTreeSet::new());
+            }
+            Stmt::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                self.expr(&cond.node);
+                let then_open = self.branch(Some(&then_block.node));
+                let else_open = self.branch(else_block.as_ref().map(|b| &b.node));
+                self.open = either(&then_open, &else_open);
+            }
+            Stmt::Match { expr, arms } => {
+                self.expr(&expr.node);
+                let mut open = Some(BTreeSet::new());
+                for arm in arms {
+                    let arm_open = self.branch(Some(&arm.body.node));
+                    open = either(&open, &arm_open);
+                }
+                self.open = open;
+            }
+            Stmt::For {
+                start, end, body, ..
+            } => {
+                self.expr(&start.node);
+                self.expr(&end.node);
+                let trips = match (self.constant(&start.node), self.constant(&end.node)) {
+                    (Some(s), Some(e)) => Some(e.saturating_sub(s)),
+                    _ => None,
+                };
+                self.loop_body(&body.node, trips);
+            }
+            Stmt::While { cond, body, .. } => {
+                // The condition decides how many of the `bound` iterations
+                // run, so the trip count is unknown.
+                self.expr(&cond.node);
+                self.loop_body(&body.node, None);
+            }
+            Stmt::Reveal { fields, .. } | Stmt::Seal { fields, .. } => {
+                for (_, value) in fields {
+                    self.expr(&value.node);
+                }
+                let written = if matches!(stmt, Stmt::Reveal { .. }) {
+                    // The event tag, then each field.
+                    1 + fields.len() as u64
+                } else {
+                    self.digest_width
+                };
+                self.open = then(&self.open, &exactly(written));
+            }
+            Stmt::Asm { body, .. } => {
+                self.open = then(&self.open, &exactly(asm_writes(body)));
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Call { path, args, .. } => {
+                for arg in args {
+                    self.expr(&arg.node);
+                }
+                let name = path.node.as_dotted();
+                let always_fails = name == "fail"
+                    || (name == "assert"
+                        && matches!(
+                            args.first().map(|a| &a.node),
+                            Some(Expr::Literal(Literal::Bool(false)))
+                        ));
+                if always_fails {
+                    self.open = Some(BTreeSet::new());
+                } else if let Some(lengths) = self.writes.get(&name) {
+                    self.open = then(&self.open, lengths);
+                }
+            }
+            Expr::BinOp { lhs, rhs, .. } => {
+                self.expr(&lhs.node);
+                self.expr(&rhs.node);
+            }
+            Expr::Tuple(elems) | Expr::ArrayInit(elems) => {
+                for e in elems {
+                    self.expr(&e.node);
+                }
+            }
+            Expr::FieldAccess { expr: inner, .. } => self.expr(&inner.node),
+            Expr::Index { expr: inner, index } => {
+                self.expr(&inner.node);
+                self.expr(&index.node);
+            }
+            Expr::StructInit { fields, base, .. } => {
+                for (_, value) in fields {
+                    self.expr(&value.node);
+                }
+                if let Some(base) = base {
+                    self.expr(&base.node);
+                }
+            }
+            Expr::Literal(_) | Expr::Var(_) => {}
+        }
+    }
+
+    fn constant(&self, expr: &Expr) -> Option<u64> {
+        match expr {
+            Expr::Literal(Literal::Integer(n)) => Some(*n),
+            Expr::Var(name) => self.constants.get(name).copied(),
+            _ => None,
+        }
+    }
+}
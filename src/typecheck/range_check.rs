@@ -0,0 +1,484 @@
+//! Automatic range checks at Field → U32 boundaries.
+//!
+//! In a function marked `#[auto_range_check]`, or in every function when
+//! the `auto_range_check` cfg flag is active, a Field value used where a
+//! U32 is expected is range-checked with `as_u32` instead of rejected.
+//! Every check adds u32-table rows, so placement is cost-aware:
+//!
+//! - integer literals and constants below 2^32, and variables already
+//!   passed through `as_u32` or `split`, need no check;
+//! - an immutable variable whose U32 uses would run a check more than
+//!   once (several uses, or one inside a loop) is checked once, right
+//!   after its binding, and those uses read the checked copy. At least
+//!   one use must run whenever the binding does — outside any `if`,
+//!   `match`, or loop with a runtime count — so the hoisted check is not
+//!   paid on paths that never reach a use;
+//! - anything else is checked where it is used.
+//!
+//! The type checker plans the checks while it walks a function, reports
+//! each as hint H0006, and exports them; `insert_range_checks` applies
+//! them to the AST before it is lowered.
+//!
+//! The same functions get the overflow lint: a Field sum used as a U32,
+//! or passed to `as_u32`, leaves what a carry past 2^32 does unsaid, and
+//! is pointed at `u32_assert_add`, `u32_wrapping_add`, and
+//! `u32_checked_add`.
+
+use crate::ast::*;
+use crate::diagnostic::ErrorCode;
+use crate::span::{Span, Spanned};
+use crate::types::Ty;
+
+use super::TypeChecker;
+
+/// cfg flag that turns on automatic range checks for a whole module.
+pub(super) const AUTO_RANGE_CHECK_FLAG: &str = "auto_range_check";
+
+/// A range check planned by the type checker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RangeCheck {
+    /// `as_u32` around the value at one U32 use.
+    Use { span: Span },
+    /// One `as_u32` of `name` right after its binding (a `let` name or a
+    /// parameter); the listed uses read the checked copy.
+    Binding {
+        name: String,
+        binding: Span,
+        uses: Vec<Span>,
+    },
+}
+
+/// Planning state for the function being checked.
+#[derive(Default)]
+pub(crate) struct RangeCheckPlan {
+    /// Whether the current function inserts range checks.
+    active: bool,
+    /// Enclosing loops and branches since the function entry.
+    frames: Vec<Frame>,
+    /// Every variable bound in the function, in binding order.
+    bindings: Vec<Binding>,
+    /// Indices into `bindings` of the variables in scope.
+    live: Vec<usize>,
+    /// Field values used as U32 so far.
+    uses: Vec<U32Use>,
+    /// Checks planned in the functions checked so far.
+    planned: Vec<RangeCheck>,
+}
+
+#[derive(Clone, Copy)]
+enum Frame {
+    /// Loop body run `weight` times; `certain` when it runs at least once.
+    Loop { weight: u64, certain: bool },
+    /// `if` or `match` branch.
+    Branch,
+}
+
+struct Binding {
+    name: String,
+    scope_depth: usize,
+    frame_depth: usize,
+    /// Name span of an immutable `let` or parameter; `None` when the
+    /// variable cannot be checked once at its binding.
+    site: Option<Span>,
+}
+
+struct U32Use {
+    span: Span,
+    /// The variable used, when the value is one bound in this function.
+    binding: Option<usize>,
+    /// How often the use runs per run of its variable's binding.
+    weight: u64,
+    /// Whether the use runs whenever its variable's binding does.
+    anticipated: bool,
+}
+
+impl RangeCheckPlan {
+    /// Checks planned for the whole module.
+    pub(super) fn into_checks(self) -> Vec<RangeCheck> {
+        self.planned
+    }
+}
+
+impl TypeChecker {
+    /// Start planning for a function body.
+    pub(super) fn begin_range_checks(&mut self, func: &FnDef) {
+        let planned = std::mem::take(&mut self.range_checks.planned);
+        self.range_checks = RangeCheckPlan {
+            active: func.auto_range_check || self.cfg_flags.contains(AUTO_RANGE_CHECK_FLAG),
+            planned,
+            ..Default::default()
+        };
+    }
+
+    /// Place the checks collected in the function body and report them.
+    pub(super) fn finish_range_checks(&mut self) {
+        let uses = std::mem::take(&mut self.range_checks.uses);
+        let mut by_binding: Vec<Vec<&U32Use>> = Vec::new();
+        by_binding.resize_with(self.range_checks.bindings.len(), Vec::new);
+        let mut checks = Vec::new();
+        for u in &uses {
+            match u.binding {
+                Some(b) => by_binding[b].push(u),
+                None => checks.push(RangeCheck::Use { span: u.span }),
+            }
+        }
+        for (binding, uses) in self.range_checks.bindings.iter().zip(by_binding) {
+            let total: u64 = uses.iter().fold(0, |n, u| n.saturating_add(u.weight));
+            match binding.site {
+                Some(site) if total >= 2 && uses.iter().any(|u| u.anticipated) => {
+                    checks.push(RangeCheck::Binding {
+                        name: binding.name.clone(),
+                        binding: site,
+                        uses: uses.iter().map(|u| u.span).collect(),
+                    });
+                }
+                _ => checks.extend(uses.iter().map(|u| RangeCheck::Use { span: u.span })),
+            }
+        }
+        for check in &checks {
+            match check {
+                RangeCheck::Use { span } => self.hint(
+                    "hint[H0006]: range check inserted: as_u32 at this U32 use".to_string(),
+                    *span,
+                ),
+                RangeCheck::Binding {
+                    name,
+                    binding,
+                    uses,
+                } => self.hint(
+                    format!(
+                        "hint[H0006]: range check inserted: as_u32({}) after its binding, \
+                         covering {} U32 use{}",
+                        name,
+                        uses.len(),
+                        if uses.len() == 1 { "" } else { "s" }
+                    ),
+                    *binding,
+                ),
+            }
+        }
+        self.range_checks.active = false;
+        self.range_checks.planned.extend(checks);
+    }
+
+    /// Accept a Field `expr` at a U32 position and plan its range check.
+    /// Returns the type the position sees: U32 when the value is checked
+    /// (or needs no check), `ty` unchanged otherwise.
+    pub(super) fn coerce_to_u32(&mut self, expr: &Spanned<Expr>, ty: Ty) -> Ty {
+        if !self.range_checks.active || ty != Ty::Field {
+            return ty;
+        }
+        self.lint_u32_sum(expr);
+        let fits = |v: u64| v <= u32::MAX as u64;
+        let binding = match &expr.node {
+            Expr::Literal(Literal::Integer(v)) => {
+                return if fits(*v) { Ty::U32 } else { ty };
+            }
+            Expr::Var(name) if self.lookup_var(name).is_some() => {
+                if self.u32_proven.contains(name) {
+                    return Ty::U32;
+                }
+                self.range_checks
+                    .live
+                    .iter()
+                    .rev()
+                    .copied()
+                    .find(|&b| self.range_checks.bindings[b].name == *name)
+            }
+            Expr::Var(name) if self.constants.contains_key(name) => {
+                return if fits(self.constants[name]) {
+                    Ty::U32
+                } else {
+                    ty
+                };
+            }
+            _ => None,
+        };
+        let since = binding.map_or(0, |b| self.range_checks.bindings[b].frame_depth);
+        let frames = &self.range_checks.frames[since..];
+        let weight = frames.iter().fold(1u64, |w, f| match f {
+            Frame::Loop { weight, .. } => w.saturating_mul(*weight),
+            Frame::Branch => w,
+        });
+        let anticipated = frames
+            .iter()
+            .all(|f| matches!(f, Frame::Loop { certain: true, .. }));
+        self.range_checks.uses.push(U32Use {
+            span: expr.span,
+            binding,
+            weight,
+            anticipated,
+        });
+        Ty::U32
+    }
+
+    /// Overflow lint: warn when `expr`, about to become a U32, is a Field
+    /// sum.
+    pub(super) fn lint_u32_sum(&mut self, expr: &Spanned<Expr>) {
+        if !self.range_checks.active {
+            return;
+        }
+        if let Expr::BinOp { op: BinOp::Add, .. } = &expr.node {
+            self.warning_with_help(
+                ErrorCode::U32Overflow,
+                "Field sum used as U32 may overflow 32 bits".to_string(),
+                expr.span,
+                "add the U32 values with `u32_assert_add` (fails on overflow), \
+                 `u32_wrapping_add` (wraps mod 2^32), or `u32_checked_add` \
+                 (returns `MaybeU32.None` on overflow)"
+                    .to_string(),
+            );
+        }
+    }
+
+    /// `coerce_to_u32` for an argument passed to a parameter of type
+    /// `expected`.
+    pub(super) fn coerce_arg(&mut self, expected: &Ty, arg: &Spanned<Expr>, actual: &Ty) -> Ty {
+        if *expected == Ty::U32 {
+            self.coerce_to_u32(arg, actual.clone())
+        } else {
+            actual.clone()
+        }
+    }
+
+    /// Record a variable binding in the current scope.
+    pub(super) fn bind_range_var(&mut self, name: &str) {
+        if !self.range_checks.active {
+            return;
+        }
+        let plan = &mut self.range_checks;
+        plan.live.push(plan.bindings.len());
+        plan.bindings.push(Binding {
+            name: name.to_string(),
+            scope_depth: self.scopes.len(),
+            frame_depth: plan.frames.len(),
+            site: None,
+        });
+    }
+
+    /// Allow the variable just bound at `site` to be checked once there.
+    pub(super) fn mark_range_checkable(&mut self, site: Span) {
+        if let Some(&b) = self.range_checks.live.last() {
+            self.range_checks.bindings[b].site = Some(site);
+        }
+    }
+
+    /// Forget bindings of scopes deeper than `depth`.
+    pub(super) fn drop_range_vars(&mut self, depth: usize) {
+        let bindings = &self.range_checks.bindings;
+        self.range_checks
+            .live
+            .retain(|&b| bindings[b].scope_depth <= depth);
+    }
+
+    /// Enter a loop body running `count` times, when known at compile
+    /// time (or up to `bound` times).
+    pub(super) fn enter_range_loop(&mut self, count: Option<u64>, bound: Option<u64>) {
+        let frame = match (bound, count) {
+            (None, Some(n)) => Frame::Loop {
+                weight: n,
+                certain: n > 0,
+            },
+            (Some(b), _) => Frame::Loop {
+                weight: b,
+                certain: false,
+            },
+            (None, None) => Frame::Loop {
+                weight: 2,
+                certain: false,
+            },
+        };
+        self.range_checks.frames.push(frame);
+    }
+
+    /// Enter an `if` or `match` branch.
+    pub(super) fn enter_range_branch(&mut self) {
+        self.range_checks.frames.push(Frame::Branch);
+    }
+
+    /// Leave the innermost loop or branch.
+    pub(super) fn exit_range_frame(&mut self) {
+        self.range_checks.frames.pop();
+    }
+}
+
+/// Name of the checked copy of `name`.
+fn checked_name(name: &str) -> String {
+    format!("{}__u32", name)
+}
+
+/// Apply planned range checks to a type-checked file.
+pub fn insert_range_checks(file: &mut File, checks: &[RangeCheck]) {
+    if checks.is_empty() {
+        return;
+    }
+    for item in &mut file.items {
+        let Item::Fn(func) = &mut item.node else {
+            continue;
+        };
+        let Some(body) = &mut func.body else {
+            continue;
+        };
+        for param in func.params.iter().rev() {
+            if let Some(copy) = checked_copy(&param.name, checks) {
+                body.node.stmts.insert(0, copy);
+            }
+        }
+        rewrite_block(&mut body.node, checks);
+    }
+}
+
+/// `let name__u32: U32 = as_u32(name)` when `name` is checked at its binding.
+fn checked_copy(name: &Spanned<String>, checks: &[RangeCheck]) -> Option<Spanned<Stmt>> {
+    checks.iter().find_map(|c| match c {
+        RangeCheck::Binding { binding, .. } if *binding == name.span => {
+            let span = name.span;
+            let stmt = Stmt::Let {
+                mutable: false,
+                pattern: Pattern::Name(Spanned::new(checked_name(&name.node), span)),
+                ty: Some(Spanned::new(Type::U32, span)),
+                init: as_u32(Spanned::new(Expr::Var(name.node.clone()), span)),
+            };
+            Some(Spanned::new(stmt, span))
+        }
+        _ => None,
+    })
+}
+
+fn as_u32(arg: Spanned<Expr>) -> Spanned<Expr> {
+    let span = arg.span;
+    Spanned::new(
+        Expr::Call {
+            path: Spanned::new(ModulePath::single("as_u32".to_string()), span),
+            generic_args: Vec::new(),
+            args: vec![arg],
+        },
+        span,
+    )
+}
+
+fn rewrite_block(block: &mut Block, checks: &[RangeCheck]) {
+    let stmts = std::mem::take(&mut block.stmts);
+    for mut stmt in stmts {
+        rewrite_stmt(&mut stmt.node, checks);
+        let copy = match &stmt.node {
+            Stmt::Let {
+                pattern: Pattern::Name(name),
+                ..
+            } => checked_copy(name, checks),
+            _ => None,
+        };
+        block.stmts.push(stmt);
+        block.stmts.extend(copy);
+    }
+    if let Some(tail) = &mut block.tail_expr {
+        rewrite_expr(tail, checks);
+    }
+}
+
+fn rewrite_stmt(stmt: &mut Stmt, checks: &[RangeCheck]) {
+    match stmt {
+        Stmt::Let { init, .. } => rewrite_expr(init, checks),
+        Stmt::Assign { place, value } => {
+            rewrite_place(&mut place.node, checks);
+            rewrite_expr(value, checks);
+        }
+        Stmt::TupleAssign { value, .. } | Stmt::Expr(value) => rewrite_expr(value, checks),
+        Stmt::If {
+            cond,
+            then_block,
+            else_block,
+        } => {
+            rewrite_expr(cond, checks);
+            rewrite_block(&mut then_block.node, checks);
+            if let Some(else_block) = else_block {
+                rewrite_block(&mut else_block.node, checks);
+            }
+        }
+        Stmt::For {
+            start, end, body, ..
+        } => {
+            rewrite_expr(start, checks);
+            rewrite_expr(end, checks);
+            rewrite_block(&mut body.node, checks);
+        }
+        Stmt::While { cond, body, .. } => {
+            rewrite_expr(cond, checks);
+            rewrite_block(&mut body.node, checks);
+        }
+        Stmt::Return(value) => {
+            if let Some(value) = value {
+                rewrite_expr(value, checks);
+            }
+        }
+        Stmt::Reveal { fields, .. } | Stmt::Seal { fields, .. } => {
+            for (_, value) in fields {
+                rewrite_expr(value, checks);
+            }
+        }
+        Stmt::Match { expr, arms } => {
+            rewrite_expr(expr, checks);
+            for arm in arms {
+                rewrite_block(&mut arm.body.node, checks);
+            }
+        }
+        Stmt::Break | Stmt::Continue | Stmt::Asm { .. } => {}
+    }
+}
+
+fn rewrite_place(place: &mut Place, checks: &[RangeCheck]) {
+    match place {
+        Place::Var(_) => {}
+        Place::FieldAccess(inner, _) => rewrite_place(&mut inner.node, checks),
+        Place::Index(inner, index) => {
+            rewrite_place(&mut inner.node, checks);
+            rewrite_expr(index, checks);
+        }
+    }
+}
+
+fn rewrite_expr(expr: &mut Spanned<Expr>, checks: &[RangeCheck]) {
+    match &mut expr.node {
+        Expr::Literal(_) | Expr::Var(_) => {}
+        Expr::BinOp { lhs, rhs, .. } => {
+            rewrite_expr(lhs, checks);
+            rewrite_expr(rhs, checks);
+        }
+        Expr::Call { args, .. } => {
+            for arg in args {
+                rewrite_expr(arg, checks);
+            }
+        }
+        Expr::FieldAccess { expr: inner, .. } => rewrite_expr(inner, checks),
+        Expr::Index { expr: inner, index } => {
+            rewrite_expr(inner, checks);
+            rewrite_expr(index, checks);
+        }
+        Expr::StructInit { fields, .. } => {
+            for (_, value) in fields {
+                rewrite_expr(value, checks);
+            }
+        }
+        Expr::ArrayInit(elems) | Expr::Tuple(elems) => {
+            for elem in elems {
+                rewrite_expr(elem, checks);
+            }
+        }
+    }
+    for check in checks {
+        match check {
+            RangeCheck::Use { span } if *span == expr.span => {
+                let value = std::mem::replace(expr, Spanned::new(Expr::Tuple(vec![]), *span));
+                *expr = as_u32(value);
+                return;
+            }
+            RangeCheck::Binding { name, uses, .. } if uses.contains(&expr.span) => {
+                if matches!(&expr.node, Expr::Var(v) if v == name) {
+                    expr.node = Expr::Var(checked_name(name));
+                    return;
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Forward-compatibility lint: declarations named after a reserved word.
+//!
+//! `impl` and `trait` are still plain identifiers, but a future version
+//! will make them keywords. Every place that introduces such a name gets
+//! a warning, so code can be migrated before it breaks; the LSP offers
+//! the rename as a quick fix. A parameter or local binding
+//! can only be named from inside its function, so its warning carries the
+//! rename of every mention there as a suggestion for `trident fix`.
+
+use std::collections::BTreeSet;
+
+use crate::ast::visit::{walk, Visitor};
+use crate::ast::*;
+use crate::diagnostic::{Diagnostic, ErrorCode, Suggestion};
+use crate::lexeme::{reserved_rename, Lexeme};
+use crate::span::{Span, Spanned};
+
+use super::TypeChecker;
+
+impl TypeChecker {
+    /// Warn on every declaration in `file` that uses a reserved word.
+    pub(super) fn check_reserved_names(&mut self, file: &File) {
+        let item_names: BTreeSet<&str> = file
+            .items
+            .iter()
+            .map(|item| match &item.node {
+                Item::Fn(func) => func.name.node.as_str(),
+                Item::Struct(sdef) => sdef.name.node.as_str(),
+                Item::Enum(edef) => edef.name.node.as_str(),
+                Item::Event(edef) => edef.name.node.as_str(),
+                Item::Const(cdef) => cdef.name.node.as_str(),
+            })
+            .collect();
+        for item in &file.items {
+            match &item.node {
+                Item::Fn(func) => {
+                    self.reserved_name(&func.name);
+                    for p in &func.type_params {
+                        self.reserved_name(p);
+                    }
+                    let mut locals = Vec::new();
+                    for p in &func.params {
+                        locals.push(&p.name);
+                    }
+                    if let Some(body) = &func.body {
+                        local_names(&body.node, &mut locals);
+                    }
+                    for name in locals {
+                        self.reserved_local(func, name, &item_names);
+                    }
+                }
+                Item::Struct(sdef) => {
+                    self.reserved_name(&sdef.name);
+                    for f in &sdef.fields {
+                        self.reserved_name(&f.name);
+                    }
+                }
+                Item::Enum(edef) => {
+                    self.reserved_name(&edef.name);
+                    for v in &edef.variants {
+                        self.reserved_name(&v.name);
+                    }
+                }
+                Item::Event(edef) => {
+                    self.reserved_name(&edef.name);
+                    for f in &edef.fields {
+                        self.reserved_name(&f.name);
+                    }
+                }
+                Item::Const(cdef) => self.reserved_name(&cdef.name),
+            }
+        }
+    }
+
+    /// Warn on a parameter or local binding of `func` named by a reserved
+    /// word, suggesting to rename it throughout the function.
+    fn reserved_local(&mut self, func: &FnDef, name: &Spanned<String>, items: &BTreeSet<&str>) {
+        if !Lexeme::is_reserved(&name.node) {
+            return;
+        }
+        let mut rename = LocalRename {
+            name: &name.node,
+            new_name: reserved_rename(&name.node),
+            edits: Vec::new(),
+            clash: items.contains(reserved_rename(&name.node).as_str()),
+        };
+        rename.visit_fn(func);
+        let mut fix = Suggestion::new(
+            format!("rename '{}' to '{}'", rename.name, rename.new_name),
+            rename.edits,
+        );
+        if rename.clash {
+            fix = fix.maybe_incorrect();
+        }
+        self.diagnostics
+            .push(reserved_warning(name).with_suggestion(fix));
+    }
+
+    fn reserved_name(&mut self, name: &Spanned<String>) {
+        if Lexeme::is_reserved(&name.node) {
+            self.diagnostics.push(reserved_warning(name));
+        }
+    }
+}
+
+fn reserved_warning(name: &Spanned<String>) -> Diagnostic {
+    Diagnostic::warning(
+        format!(
+            "'{}' is reserved for a future keyword and will stop being a valid name",
+            name.node
+        ),
+        name.span,
+    )
+    .with_code(ErrorCode::ReservedName)
+    .with_help(format!(
+        "rename it, e.g. to '{}'",
+        reserved_rename(&name.node)
+    ))
+}
+
+/// Names bound by `let` and `for` anywhere in `block`.
+fn local_names<'a>(block: &'a Block, out: &mut Vec<&'a Spanned<String>>) {
+    for stmt in &block.stmts {
+        match &stmt.node {
+            Stmt::Let { pattern, .. } => match pattern {
+                Pattern::Name(name) => out.push(name),
+                Pattern::Tuple(names) => out.extend(names),
+            },
+            Stmt::If {
+                then_block,
+                else_block,
+                ..
+            } => {
+                local_names(&then_block.node, out);
+                if let Some(eb) = else_block {
+                    local_names(&eb.node, out);
+                }
+            }
+            Stmt::For { var, body, .. } => {
+                out.push(var);
+                local_names(&body.node, out);
+            }
+            Stmt::While { body, .. } => local_names(&body.node, out),
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    local_names(&arm.body.node, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Edits renaming every mention of a local variable in one function.
+/// Mentions in struct field shorthand (`S { name }`) are expanded to
+/// keep the field name. `clash` is set when the new name is taken.
+struct LocalRename<'a> {
+    name: &'a str,
+    new_name: String,
+    edits: Vec<(Span, String)>,
+    clash: bool,
+}
+
+impl LocalRename<'_> {
+    /// Rename the binding spelled by `span` if it is `name`.
+    fn binding(&mut self, name: &str, span: Span) {
+        if name == self.name {
+            self.edits.push((span, self.new_name.clone()));
+        } else if name == self.new_name {
+            self.clash = true;
+        }
+    }
+
+    /// Rename the variable path (`name` or `name.field`) starting at `span`.
+    fn path(&mut self, path: &str, span: Span) {
+        let root = path.split('.').next().unwrap_or(path);
+        let root_span = Span::new(span.file_id, span.start, span.start + root.len() as u32);
+        self.binding(root, root_span);
+    }
+}
+
+impl Visitor for LocalRename<'_> {
+    fn visit_param(&mut self, param: &Param) {
+        self.binding(&param.name.node, param.name.span);
+    }
+
+    fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+        match &stmt.node {
+            Stmt::Let {
+                pattern: Pattern::Name(name),
+                ..
+            }
+            | Stmt::For { var: name, .. } => self.binding(&name.node, name.span),
+            Stmt::Let {
+                pattern: Pattern::Tuple(names),
+                ..
+            }
+            | Stmt::TupleAssign { names, .. } => {
+                for name in names {
+                    self.binding(&name.node, name.span);
+                }
+            }
+            _ => {}
+        }
+        walk::stmt(self, stmt);
+    }
+
+    fn visit_match_pattern(&mut self, pattern: &Spanned<MatchPattern>) {
+        if let MatchPattern::Variant { bindings, .. } = &pattern.node {
+            for b in bindings {
+                self.binding(&b.node, b.span);
+            }
+        }
+        if let MatchPattern::Struct { fields, .. } = &pattern.node {
+            for field in fields {
+                if let FieldPattern::Binding(name) = &field.pattern.node {
+                    if name == self.name && field.pattern.span == field.field_name.span {
+                        let expanded = format!("{}: {}", name, self.new_name);
+                        self.edits.push((field.pattern.span, expanded));
+                    } else {
+                        self.binding(name, field.pattern.span);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_place(&mut self, place: &Spanned<Place>) {
+        if let Place::Var(path) = &place.node {
+            self.path(path, place.span);
+        }
+        walk::place(self, place);
+    }
+
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        match &expr.node {
+            Expr::Var(path) => self.path(path, expr.span),
+            Expr::StructInit { fields, base, .. } => {
+                for (field, value) in fields {
+                    match &value.node {
+                        Expr::Var(v) if *v == self.name && value.span == field.span => {
+                            let expanded = format!("{}: {}", v, self.new_name);
+                            self.edits.push((value.span, expanded));
+                        }
+                        _ => self.visit_expr(value),
+                    }
+                }
+                if let Some(base) = base {
+                    self.visit_expr(base);
+                }
+            }
+            _ => walk::expr(self, expr),
+        }
+    }
+}
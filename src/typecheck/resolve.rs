@@ -3,29 +3,219 @@
 use std::collections::BTreeMap;
 
 use crate::ast::*;
-use crate::span::Span;
+use crate::diagnostic::ErrorCode;
+use crate::span::{Span, Spanned};
 use crate::types::Ty;
 
 use super::{GenericFnDef, TypeChecker};
 
 impl TypeChecker {
-    pub(super) fn is_constant_expr(&self, expr: &Expr) -> bool {
-        matches!(expr, Expr::Literal(Literal::Integer(_)))
-            || matches!(expr, Expr::Var(name) if self.constants.contains_key(name))
+    /// Value of an expression known at compile time: integer literals,
+    /// constants, `len()` of a fixed-size array, calls of `#[pure]`
+    /// functions with such arguments, and sums and products of those.
+    /// `None` when the value is only known at run time.
+    pub(super) fn constant_value(&mut self, expr: &Expr, span: Span) -> Option<u64> {
+        match expr {
+            Expr::Literal(Literal::Integer(n)) => Some(*n),
+            Expr::Var(name) => self.constants.get(name).copied(),
+            Expr::Index { expr: inner, index } => {
+                let Expr::Var(name) = &inner.node else {
+                    return None;
+                };
+                let i = self.constant_value(&index.node, index.span)?;
+                let values = &self.data_const(name)?.values;
+                values.get(usize::try_from(i).ok()?).copied()
+            }
+            Expr::BinOp { op, lhs, rhs } => {
+                let lhs = self.constant_value(&lhs.node, lhs.span)?;
+                let rhs = self.constant_value(&rhs.node, rhs.span)?;
+                match op {
+                    BinOp::Add => lhs.checked_add(rhs),
+                    BinOp::Mul => lhs.checked_mul(rhs),
+                    _ => None,
+                }
+            }
+            Expr::Call { path, args, .. } => {
+                if let Some(array) = self.len_call_target(expr) {
+                    return match self.local_ty(&array)? {
+                        Ty::Array(_, n) => Some(n),
+                        _ => None,
+                    };
+                }
+                let name = path.node.as_dotted();
+                if !self.pure_fns.contains_key(&name) {
+                    return None;
+                }
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args {
+                    values.push(self.constant_value(&arg.node, arg.span)?);
+                }
+                self.eval_pure_call(&name, &values, span)
+            }
+            _ => None,
+        }
+    }
+
+    /// The array of a `array.len()` call, when `array` names a local.
+    pub(super) fn len_call_target(&self, expr: &Expr) -> Option<String> {
+        let Expr::Call {
+            path,
+            generic_args,
+            args,
+        } = expr
+        else {
+            return None;
+        };
+        let (last, prefix) = path.node.0.split_last()?;
+        if last != "len" || prefix.is_empty() || !generic_args.is_empty() || !args.is_empty() {
+            return None;
+        }
+        self.lookup_var(&prefix[0])?;
+        Some(prefix.join("."))
+    }
+
+    /// Type of a local or one of its nested struct fields, like `st.data`.
+    fn local_ty(&self, name: &str) -> Option<Ty> {
+        let mut parts = name.split('.');
+        let mut ty = self.lookup_var(parts.next()?)?.ty.clone();
+        for field in parts {
+            let Ty::Struct(sty) = ty else {
+                return None;
+            };
+            ty = sty.field_offset(field)?.0;
+        }
+        Some(ty)
+    }
+
+    /// Record that the expression at `span` has the compile-time value
+    /// `value`, for `fold_constants` to write into the AST.
+    pub(super) fn record_folded(&mut self, span: Span, value: u64) {
+        if !self.folded.iter().any(|(s, _)| *s == span) {
+            self.folded.push((span, value));
+        }
+    }
+
+    /// Values of the fields of `sdef` declared with `= value`. A default
+    /// must be known at compile time and fit a `Field`, `U32`, or `Bool`
+    /// field.
+    pub(super) fn struct_defaults(
+        &mut self,
+        sdef: &StructDef,
+        fields: &[(String, Ty, bool)],
+    ) -> BTreeMap<String, u64> {
+        let mut defaults = BTreeMap::new();
+        for (field, (name, ty, _)) in sdef.fields.iter().zip(fields) {
+            let Some(default) = &field.default else {
+                continue;
+            };
+            let value = match (ty, &default.node) {
+                (Ty::Bool, Expr::Literal(Literal::Bool(b))) => Some(*b as u64),
+                (Ty::Bool, _) => None,
+                (Ty::Field | Ty::U32, _) => self.constant_value(&default.node, default.span),
+                _ => {
+                    self.error(
+                        ErrorCode::FieldDefault,
+                        format!(
+                            "field '{}' of type {} cannot have a default value",
+                            name,
+                            ty.display()
+                        ),
+                        default.span,
+                    );
+                    continue;
+                }
+            };
+            match value {
+                Some(v) if *ty == Ty::U32 && v > u32::MAX as u64 => self.error(
+                    ErrorCode::FieldDefault,
+                    format!(
+                        "default value {} of field '{}' does not fit in U32",
+                        v, name
+                    ),
+                    default.span,
+                ),
+                Some(v) => {
+                    defaults.insert(name.clone(), v);
+                }
+                None => self.error_with_help(
+                    ErrorCode::FieldDefault,
+                    format!(
+                        "default value of field '{}' is not known at compile time",
+                        name
+                    ),
+                    default.span,
+                    "use a literal, a constant, or a #[pure] call with constant arguments"
+                        .to_string(),
+                ),
+            }
+        }
+        defaults
     }
 
     /// Infer size arguments for a generic function from argument types.
-    /// E.g. if param is `[Field; N]` and arg type is `[Field; 5]`, infer N=5.
+    /// E.g. if param is `[Field; N]` and arg type is `[Field; 5]`, infer N=5;
+    /// `[Field; N + 1]` against `[Field; 5]` infers N=4. Returns `None`
+    /// after reporting a size no binding can produce, or two arguments
+    /// that bind a parameter to different values.
     pub(super) fn infer_size_args(
         &mut self,
         gdef: &GenericFnDef,
+        fn_name: &str,
+        args: &[Spanned<Expr>],
         arg_tys: &[Ty],
         span: Span,
-    ) -> Vec<u64> {
-        let mut subs: BTreeMap<String, u64> = BTreeMap::new();
+    ) -> Option<Vec<u64>> {
+        let mut equations: Vec<(ArraySize, u64, usize)> = Vec::new();
+        for (i, ((_, param_ty), arg_ty)) in gdef.params.iter().zip(arg_tys.iter()).enumerate() {
+            let mut sizes = Vec::new();
+            Self::unify_sizes(param_ty, arg_ty, &mut sizes);
+            equations.extend(sizes.into_iter().map(|(size, n)| (size, n, i)));
+        }
+        let is_param = |name: &&str| gdef.type_params.iter().any(|p| p == name);
 
-        for ((_, param_ty), arg_ty) in gdef.params.iter().zip(arg_tys.iter()) {
-            Self::unify_sizes(param_ty, arg_ty, &mut subs);
+        // Each pass binds the parameters that are the only unknown in some
+        // size, so `[Field; N]` and `[Field; M + N]` together bind both.
+        let mut subs: BTreeMap<String, u64> = BTreeMap::new();
+        let mut bound_by: BTreeMap<String, usize> = BTreeMap::new();
+        loop {
+            let mut progress = false;
+            for (size, n, i) in &equations {
+                let names = size.params();
+                let unknown: Vec<&str> = names
+                    .iter()
+                    .copied()
+                    .filter(|name| is_param(name) && !subs.contains_key(*name))
+                    .collect();
+                let [name] = unknown[..] else {
+                    continue;
+                };
+                match size.solve(*n, name, &subs, &self.constants) {
+                    Some(value) => {
+                        subs.insert(name.to_string(), value);
+                        bound_by.insert(name.to_string(), *i);
+                        progress = true;
+                    }
+                    None => {
+                        self.error_with_help(
+                            ErrorCode::SizeArgument,
+                            format!(
+                                "argument {} of '{}': no size '{}' makes {} equal {}",
+                                i + 1,
+                                fn_name,
+                                name,
+                                size,
+                                n
+                            ),
+                            args[*i].span,
+                            format!("pass an array of length {} for some '{}'", size, name),
+                        );
+                        return None;
+                    }
+                }
+            }
+            if !progress {
+                break;
+            }
         }
 
         let mut result = Vec::new();
@@ -34,32 +224,60 @@ impl TypeChecker {
                 result.push(val);
             } else {
                 self.error(
+                    ErrorCode::SizeArgument,
                     format!(
                         "cannot infer size parameter '{}'; provide explicit size argument",
                         param_name
                     ),
                     span,
                 );
-                result.push(0);
+                return None;
             }
         }
-        result
+
+        // Every size must agree with the bindings, not just the ones used.
+        for (size, n, i) in &equations {
+            let expected = size.eval_with_constants(&subs, &self.constants);
+            if expected == *n {
+                continue;
+            }
+            let name = size.params().into_iter().find(|name| is_param(name));
+            let source = name
+                .and_then(|name| Some((name, subs.get(name)?, bound_by.get(name)?)))
+                .map(|(name, value, from)| {
+                    format!(" ({} = {} from argument {})", name, value, from + 1)
+                })
+                .unwrap_or_default();
+            self.error(
+                ErrorCode::SizeArgument,
+                format!(
+                    "argument {} of '{}': expected length {} = {}{} but got {}",
+                    i + 1,
+                    fn_name,
+                    size,
+                    expected,
+                    source,
+                    n
+                ),
+                args[*i].span,
+            );
+            return None;
+        }
+        Some(result)
     }
 
-    /// Recursively match an AST type pattern against a concrete Ty to extract
-    /// size parameter bindings. E.g. `[Field; N]` vs `[Field; 5]` -> N=5.
-    pub(super) fn unify_sizes(pattern: &Type, concrete: &Ty, subs: &mut BTreeMap<String, u64>) {
+    /// Recursively match an AST type pattern against a concrete Ty,
+    /// collecting each array size with the length it must have. E.g.
+    /// `[[Field; N]; M + 1]` vs `[[Field; 2]; 5]` -> `M + 1 = 5`, `N = 2`.
+    pub(super) fn unify_sizes(pattern: &Type, concrete: &Ty, sizes: &mut Vec<(ArraySize, u64)>) {
         match (pattern, concrete) {
-            (Type::Array(inner_pat, ArraySize::Param(name)), Ty::Array(inner_ty, size)) => {
-                subs.insert(name.clone(), *size);
-                Self::unify_sizes(inner_pat, inner_ty, subs);
-            }
-            (Type::Array(inner_pat, _), Ty::Array(inner_ty, _)) => {
-                Self::unify_sizes(inner_pat, inner_ty, subs);
+            (Type::Array(inner_pat, size), Ty::Array(inner_ty, n)) => {
+                sizes.push((size.clone(), *n));
+                Self::unify_sizes(inner_pat, inner_ty, sizes);
             }
             (Type::Tuple(pats), Ty::Tuple(tys)) => {
                 for (p, t) in pats.iter().zip(tys.iter()) {
-                    Self::unify_sizes(p, t, subs);
+                    Self::unify_sizes(p, t, sizes);
                 }
             }
             _ => {}
@@ -79,7 +297,7 @@ impl TypeChecker {
             Type::U32 => Ty::U32,
             Type::Digest => Ty::Digest(self.target_config.digest_width),
             Type::Array(inner, n) => {
-                let size = n.eval(subs);
+                let size = n.eval_with_constants(subs, &self.constants);
                 Ty::Array(Box::new(self.resolve_type_with_subs(inner, subs)), size)
             }
             Type::Tuple(elems) => {
@@ -93,8 +311,14 @@ impl TypeChecker {
                 let name = path.as_dotted();
                 if let Some(sty) = self.structs.get(&name) {
                     Ty::Struct(sty.clone())
+                } else if let Some(ety) = self.enums.get(&name) {
+                    Ty::Enum(ety.clone())
                 } else {
-                    self.error(format!("unknown type '{}'", name), Span::dummy());
+                    self.error(
+                        ErrorCode::UndefinedType,
+                        format!("unknown type '{}'", name),
+                        Span::dummy(),
+                    );
                     Ty::Field
                 }
             }
@@ -0,0 +1,114 @@
+//! Variable scopes, and tracking of sponge state bound in them.
+//!
+//! A `vm.crypto.hash.Sponge` bound with `let` is expected to be read
+//! again — absorbed into or squeezed. A state that goes out of scope
+//! unread, or a call whose returned state is discarded, loses whatever
+//! was absorbed last, so both are reported as warnings.
+
+use std::collections::BTreeMap;
+
+use crate::diagnostic::{Diagnostic, ErrorCode};
+use crate::span::{Span, Spanned};
+use crate::types::Ty;
+
+use super::{TypeChecker, VarInfo};
+
+/// Qualified name of the typed sponge state.
+pub(super) const SPONGE_STATE: &str = "vm.crypto.hash.Sponge";
+
+impl TypeChecker {
+    pub(super) fn push_scope(&mut self) {
+        self.scopes.push(BTreeMap::new());
+    }
+
+    pub(super) fn pop_scope(&mut self) {
+        let depth = self.scopes.len();
+        let (dropped, live): (Vec<_>, Vec<_>) = std::mem::take(&mut self.unused_sponges)
+            .into_iter()
+            .partition(|(d, _, _)| *d == depth);
+        self.unused_sponges = live;
+        for (_, name, span) in dropped {
+            self.diagnostics.push(
+                Diagnostic::warning(format!("sponge state '{}' is never used", name), span)
+                    .with_code(ErrorCode::UnusedSponge)
+                    .with_help(format!(
+                        "absorb into it or squeeze it; name it `_{}` to drop it on purpose",
+                        name
+                    )),
+            );
+        }
+        self.scopes.pop();
+        self.drop_range_vars(self.scopes.len());
+    }
+
+    pub(super) fn define_var(&mut self, name: &str, ty: Ty, mutable: bool) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(
+                name.to_string(),
+                VarInfo {
+                    ty,
+                    mutable,
+                    let_name: None,
+                },
+            );
+        }
+        self.bind_range_var(name);
+    }
+
+    /// Define the variable of `let name = ...`.
+    pub(super) fn define_let(&mut self, name: &Spanned<String>, ty: Ty, mutable: bool) {
+        self.define_var(&name.node, ty, mutable);
+        if let Some(info) = self.scopes.last_mut().and_then(|s| s.get_mut(&name.node)) {
+            info.let_name = Some(name.span);
+        }
+    }
+
+    pub(super) fn lookup_var(&self, name: &str) -> Option<&VarInfo> {
+        for scope in self.scopes.iter().rev() {
+            if let Some(info) = scope.get(name) {
+                return Some(info);
+            }
+        }
+        None
+    }
+
+    // --- Sponge state ---
+
+    /// Whether `ty` is the sponge state exported by `vm.crypto.hash`.
+    /// Inside the defining module the type is not tracked.
+    pub(super) fn is_sponge_state(&self, ty: &Ty) -> bool {
+        matches!(ty, Ty::Struct(st) if self.structs.get(SPONGE_STATE) == Some(st))
+    }
+
+    /// Record a `let` binding of sponge state that must be read later.
+    pub(super) fn track_sponge_binding(&mut self, name: &str, ty: &Ty, span: Span) {
+        if !name.starts_with('_') && self.is_sponge_state(ty) {
+            self.unused_sponges
+                .push((self.scopes.len(), name.to_string(), span));
+        }
+    }
+
+    /// Mark the innermost pending sponge binding named `name` as read.
+    pub(super) fn mark_sponge_read(&mut self, name: &str, ty: &Ty) {
+        if !self.is_sponge_state(ty) {
+            return;
+        }
+        if let Some(pos) = self.unused_sponges.iter().rposition(|(_, n, _)| n == name) {
+            self.unused_sponges.remove(pos);
+        }
+    }
+
+    /// Warn when an expression statement discards a sponge state.
+    pub(super) fn check_discarded_sponge(&mut self, ty: &Ty, span: Span) {
+        if self.is_sponge_state(ty) {
+            self.diagnostics.push(
+                Diagnostic::warning("sponge state returned here is discarded".to_string(), span)
+                    .with_code(ErrorCode::UnusedSponge)
+                    .with_help(
+                        "bind the returned state and pass it to the next `absorb` or `squeeze`"
+                            .to_string(),
+                    ),
+            );
+        }
+    }
+}
@@ -1,7 +1,10 @@
 //! Statement type checking: check_fn, check_block, check_stmt, check_event_stmt, check_place.
 
+use std::collections::BTreeSet;
+
 use crate::ast::*;
-use crate::span::Span;
+use crate::diagnostic::{Diagnostic, ErrorCode, Suggestion};
+use crate::span::{Span, Spanned};
 use crate::types::Ty;
 
 use super::TypeChecker;
@@ -18,8 +21,14 @@ impl TypeChecker {
                 let init_ty = self.check_expr(&init.node, init.span);
                 let resolved_ty = if let Some(declared_ty) = ty {
                     let expected = self.resolve_type(&declared_ty.node);
+                    let init_ty = if expected == Ty::U32 {
+                        self.coerce_to_u32(init, init_ty)
+                    } else {
+                        init_ty
+                    };
                     if expected != init_ty {
                         self.error(
+                            ErrorCode::TypeMismatch,
                             format!(
                                 "type mismatch: declared {} but expression has type {}",
                                 expected.display(),
@@ -35,7 +44,11 @@ impl TypeChecker {
 
                 match pattern {
                     Pattern::Name(name) => {
-                        self.define_var(&name.node, resolved_ty.clone(), *mutable);
+                        self.define_let(name, resolved_ty.clone(), *mutable);
+                        if !*mutable {
+                            self.mark_range_checkable(name.span);
+                        }
+                        self.track_sponge_binding(&name.node, &resolved_ty, name.span);
                         // Track U32-proven variables for H0003:
                         // When as_u32(x) or split(x) is called, the INPUT x
                         // has been range-checked. Mark x as proven so a
@@ -55,6 +68,7 @@ impl TypeChecker {
                         if let Ty::Tuple(elem_tys) = &resolved_ty {
                             if names.len() != elem_tys.len() {
                                 self.error(
+                                    ErrorCode::Destructuring,
                                     format!(
                                         "tuple destructuring: expected {} elements, got {} names",
                                         elem_tys.len(),
@@ -74,6 +88,7 @@ impl TypeChecker {
                             let dw = resolved_ty.width() as usize;
                             if names.len() != dw {
                                 self.error(
+                                    ErrorCode::Destructuring,
                                     format!(
                                         "digest destructuring requires exactly {} names, got {}",
                                         dw,
@@ -89,6 +104,7 @@ impl TypeChecker {
                             }
                         } else {
                             self.error(
+                                ErrorCode::Destructuring,
                                 format!(
                                     "cannot destructure non-tuple type {}",
                                     resolved_ty.display()
@@ -102,15 +118,19 @@ impl TypeChecker {
             Stmt::Assign { place, value } => {
                 let (place_ty, is_mut) = self.check_place(&place.node, place.span);
                 if !is_mut {
-                    self.error_with_help(
+                    self.immutable_assign(
                         "cannot assign to immutable variable".to_string(),
                         place.span,
-                        "declare the variable with `let mut` to make it mutable".to_string(),
+                        place_root(&place.node),
                     );
                 }
-                let val_ty = self.check_expr(&value.node, value.span);
+                let mut val_ty = self.check_expr(&value.node, value.span);
+                if place_ty == Ty::U32 {
+                    val_ty = self.coerce_to_u32(value, val_ty);
+                }
                 if place_ty != val_ty {
                     self.error(
+                        ErrorCode::TypeMismatch,
                         format!(
                             "type mismatch in assignment: expected {} but got {}",
                             place_ty.display(),
@@ -132,6 +152,7 @@ impl TypeChecker {
                 let cond_ty = self.check_expr(&cond.node, cond.span);
                 if cond_ty != Ty::Bool && cond_ty != Ty::Field {
                     self.error(
+                        ErrorCode::ConditionType,
                         format!(
                             "if condition must be Bool or Field, got {}",
                             cond_ty.display()
@@ -139,10 +160,12 @@ impl TypeChecker {
                         cond.span,
                     );
                 }
+                self.enter_range_branch();
                 self.check_block(&then_block.node);
                 if let Some(else_blk) = else_block {
                     self.check_block(&else_blk.node);
                 }
+                self.exit_range_frame();
             }
             Stmt::For {
                 var,
@@ -154,24 +177,55 @@ impl TypeChecker {
                 let _start_ty = self.check_expr(&start.node, start.span);
                 let _end_ty = self.check_expr(&end.node, end.span);
 
-                // Check that start is a constant 0 or Field/U32
-                // end must be a constant or have bounded annotation
-                if bound.is_none() {
-                    // end must be a compile-time constant
-                    if !self.is_constant_expr(&end.node) {
+                // end must be known at compile time or have a bounded
+                // annotation; a known end is folded into a literal
+                let count = self.constant_value(&end.node, end.span);
+                match count {
+                    Some(n) if !matches!(end.node, Expr::Literal(_)) => {
+                        self.record_folded(end.span, n)
+                    }
+                    None if bound.is_none() => {
                         self.error_with_help(
+                            ErrorCode::NotConstant,
                             "loop end must be a compile-time constant, or annotated with a bound".to_string(),
                             end.span,
-                            "use a literal like `for i in 0..10 { }` or add a bound: `for i in 0..n bounded 100 { }`".to_string(),
+                            "use a constant like `for i in 0..N { }` or `for i in 0..arr.len() { }`, or add a bound: `for i in 0..n bounded 100 { }`".to_string(),
                         );
                     }
+                    _ => {}
                 }
 
+                let bound = bound.as_ref().and_then(|b| self.loop_bound(b));
                 self.push_scope();
                 if var.node != "_" {
                     self.define_var(&var.node, Ty::U32, false);
                 }
+                self.enter_range_loop(count, bound);
+                self.loop_depth += 1;
+                self.check_block(&body.node);
+                self.loop_depth -= 1;
+                self.exit_range_frame();
+                self.pop_scope();
+            }
+            Stmt::While { cond, bound, body } => {
+                let cond_ty = self.check_expr(&cond.node, cond.span);
+                if cond_ty != Ty::Bool && cond_ty != Ty::Field {
+                    self.error(
+                        ErrorCode::ConditionType,
+                        format!(
+                            "while condition must be Bool or Field, got {}",
+                            cond_ty.display()
+                        ),
+                        cond.span,
+                    );
+                }
+                let bound = self.loop_bound(bound);
+                self.push_scope();
+                self.enter_range_loop(None, bound);
+                self.loop_depth += 1;
                 self.check_block(&body.node);
+                self.loop_depth -= 1;
+                self.exit_range_frame();
                 self.pop_scope();
             }
             Stmt::TupleAssign { names, value } => {
@@ -179,6 +233,7 @@ impl TypeChecker {
                 let valid = if let Ty::Tuple(elem_tys) = &val_ty {
                     if names.len() != elem_tys.len() {
                         self.error(
+                            ErrorCode::Destructuring,
                             format!(
                                 "tuple assignment: expected {} elements, got {} names",
                                 elem_tys.len(),
@@ -192,6 +247,7 @@ impl TypeChecker {
                     let dw = val_ty.width() as usize;
                     if names.len() != dw {
                         self.error(
+                            ErrorCode::Destructuring,
                             format!(
                                 "Digest destructuring requires exactly {} names, got {}",
                                 dw,
@@ -208,17 +264,17 @@ impl TypeChecker {
                     for name in names {
                         if let Some(info) = self.lookup_var(&name.node) {
                             if !info.mutable {
-                                self.error_with_help(
+                                self.immutable_assign(
                                     format!("cannot assign to immutable variable '{}'", name.node),
                                     name.span,
-                                    "declare the variable with `let mut` to make it mutable"
-                                        .to_string(),
+                                    &name.node,
                                 );
                             }
                         }
                     }
                 } else {
                     self.error(
+                        ErrorCode::Destructuring,
                         format!(
                             "cannot tuple-assign from non-tuple type {}",
                             val_ty.display()
@@ -228,13 +284,29 @@ impl TypeChecker {
                 }
             }
             Stmt::Expr(expr) => {
-                self.check_expr(&expr.node, expr.span);
+                let ty = self.check_expr(&expr.node, expr.span);
+                self.check_discarded_sponge(&ty, expr.span);
             }
             Stmt::Return(value) => {
                 if let Some(val) = value {
                     self.check_expr(&val.node, val.span);
                 }
             }
+            Stmt::Break | Stmt::Continue => {
+                if self.loop_depth == 0 {
+                    let keyword = if matches!(stmt, Stmt::Break) {
+                        "break"
+                    } else {
+                        "continue"
+                    };
+                    self.error_with_help(
+                        ErrorCode::LoopExit,
+                        format!("`{}` outside a loop", keyword),
+                        _span,
+                        "use `return` to leave the function".to_string(),
+                    );
+                }
+            }
             Stmt::Reveal { event_name, fields } | Stmt::Seal { event_name, fields } => {
                 if self.in_pure_fn {
                     let kind = if matches!(stmt, Stmt::Reveal { .. }) {
@@ -243,36 +315,27 @@ impl TypeChecker {
                         "seal"
                     };
                     self.error(
+                        ErrorCode::ImpureOperation,
                         format!("#[pure] function cannot use '{}' (I/O side effect)", kind),
                         _span,
                     );
                 }
                 self.check_event_stmt(event_name, fields);
             }
-            Stmt::Asm { target, .. } => {
-                // Warn if asm block is tagged for a different target
-                if let Some(tag) = target {
-                    if tag != &self.target_config.name {
-                        self.warning(
-                            format!(
-                                "asm block tagged for '{}' will be skipped (current target: '{}')",
-                                tag, self.target_config.name
-                            ),
-                            _span,
-                        );
-                    }
-                }
-            }
+            // Target coverage is checked per run, in `check_block`.
+            Stmt::Asm { .. } => {}
             Stmt::Match { expr, arms } => {
                 let scrutinee_ty = self.check_expr(&expr.node, expr.span);
                 let mut has_wildcard = false;
                 let mut has_true = false;
                 let mut has_false = false;
                 let mut wildcard_seen = false;
+                let mut variants_seen = BTreeSet::new();
 
                 for arm in arms {
                     if wildcard_seen {
                         self.error_with_help(
+                            ErrorCode::Unreachable,
                             "unreachable pattern after wildcard '_'".to_string(),
                             arm.pattern.span,
                             "the wildcard `_` already matches all values; remove this arm or move it before `_`".to_string(),
@@ -283,6 +346,7 @@ impl TypeChecker {
                         MatchPattern::Literal(Literal::Integer(_)) => {
                             if scrutinee_ty != Ty::Field && scrutinee_ty != Ty::U32 {
                                 self.error(
+                                    ErrorCode::TypeMismatch,
                                     format!(
                                         "integer pattern requires Field or U32 scrutinee, got {}",
                                         scrutinee_ty.display()
@@ -294,6 +358,7 @@ impl TypeChecker {
                         MatchPattern::Literal(Literal::Bool(b)) => {
                             if scrutinee_ty != Ty::Bool {
                                 self.error(
+                                    ErrorCode::TypeMismatch,
                                     format!(
                                         "boolean pattern requires Bool scrutinee, got {}",
                                         scrutinee_ty.display()
@@ -307,6 +372,14 @@ impl TypeChecker {
                                 has_false = true;
                             }
                         }
+                        MatchPattern::Literal(Literal::Str(_)) => {
+                            self.error(
+                                ErrorCode::MisplacedString,
+                                "string literals are only allowed as assertion messages and trace labels"
+                                    .to_string(),
+                                arm.pattern.span,
+                            );
+                        }
                         MatchPattern::Wildcard => {
                             has_wildcard = true;
                             wildcard_seen = true;
@@ -317,6 +390,7 @@ impl TypeChecker {
                                 // Verify scrutinee type matches the struct
                                 if scrutinee_ty != Ty::Struct(sty.clone()) {
                                     self.error(
+                                        ErrorCode::TypeMismatch,
                                         format!(
                                             "struct pattern `{}` does not match scrutinee type `{}`",
                                             name.node,
@@ -334,6 +408,7 @@ impl TypeChecker {
                                             FieldPattern::Literal(Literal::Integer(_)) => {
                                                 if field_ty != Ty::Field && field_ty != Ty::U32 {
                                                     self.error(
+                                                        ErrorCode::TypeMismatch,
                                                         format!(
                                                             "integer pattern on field `{}` requires Field or U32, got {}",
                                                             spf.field_name.node,
@@ -346,6 +421,7 @@ impl TypeChecker {
                                             FieldPattern::Literal(Literal::Bool(_)) => {
                                                 if field_ty != Ty::Bool {
                                                     self.error(
+                                                        ErrorCode::TypeMismatch,
                                                         format!(
                                                             "boolean pattern on field `{}` requires Bool, got {}",
                                                             spf.field_name.node,
@@ -355,10 +431,19 @@ impl TypeChecker {
                                                     );
                                                 }
                                             }
+                                            FieldPattern::Literal(Literal::Str(_)) => {
+                                                self.error(
+                                                    ErrorCode::MisplacedString,
+                                                    "string literals are only allowed as assertion messages and trace labels"
+                                                        .to_string(),
+                                                    spf.pattern.span,
+                                                );
+                                            }
                                             FieldPattern::Binding(_) | FieldPattern::Wildcard => {}
                                         }
                                     } else {
                                         self.error(
+                                            ErrorCode::UnknownField,
                                             format!(
                                                 "struct `{}` has no field `{}`",
                                                 name.node, spf.field_name.node
@@ -369,14 +454,25 @@ impl TypeChecker {
                                 }
                             } else {
                                 self.error(
+                                    ErrorCode::UndefinedType,
                                     format!("unknown struct type `{}`", name.node),
                                     name.span,
                                 );
                             }
                         }
+                        MatchPattern::Variant { path, .. } => {
+                            if !variants_seen.insert(path.node.as_dotted()) {
+                                self.error(
+                                    ErrorCode::Unreachable,
+                                    format!("variant `{}` is already matched above", path.node),
+                                    arm.pattern.span,
+                                );
+                            }
+                        }
                     }
 
                     // For struct patterns, define bound variables in a scope wrapping the arm body
+                    self.enter_range_branch();
                     if let MatchPattern::Struct { name, fields } = &arm.pattern.node {
                         self.push_scope();
                         if let Some(sty) = self.structs.get(&name.node).cloned() {
@@ -392,9 +488,25 @@ impl TypeChecker {
                         }
                         self.check_block(&arm.body.node);
                         self.pop_scope();
+                    } else if let MatchPattern::Variant { path, bindings } = &arm.pattern.node {
+                        self.push_scope();
+                        let fields = self.check_variant_pattern(path, bindings, &scrutinee_ty);
+                        for (i, binding) in bindings.iter().enumerate() {
+                            if binding.node == "_" {
+                                continue;
+                            }
+                            let ty = fields
+                                .as_ref()
+                                .and_then(|f| f.get(i).cloned())
+                                .unwrap_or(Ty::Field);
+                            self.define_var(&binding.node, ty, false);
+                        }
+                        self.check_block(&arm.body.node);
+                        self.pop_scope();
                     } else {
                         self.check_block(&arm.body.node);
                     }
+                    self.exit_range_frame();
                 }
 
                 // Exhaustiveness: require wildcard unless Bool with both true+false,
@@ -402,11 +514,31 @@ impl TypeChecker {
                 let has_struct_pattern = arms
                     .iter()
                     .any(|a| matches!(a.pattern.node, MatchPattern::Struct { .. }));
+                if let Ty::Enum(ety) = &scrutinee_ty {
+                    let missing = Self::missing_variants(ety, arms);
+                    if !has_wildcard && !missing.is_empty() {
+                        let listed: Vec<String> =
+                            missing.iter().map(|v| format!("`{}`", v)).collect();
+                        self.error_with_help(
+                            ErrorCode::NonExhaustiveMatch,
+                            format!(
+                                "non-exhaustive match: variant{} {} not covered",
+                                if missing.len() == 1 { "" } else { "s" },
+                                listed.join(", ")
+                            ),
+                            expr.span,
+                            "add an arm for each missing variant, or a wildcard `_ => { ... }` arm"
+                                .to_string(),
+                        );
+                    }
+                    return;
+                }
                 let exhaustive = has_wildcard
                     || (scrutinee_ty == Ty::Bool && has_true && has_false)
                     || has_struct_pattern;
                 if !exhaustive {
                     self.error_with_help(
+                        ErrorCode::NonExhaustiveMatch,
                         "non-exhaustive match: not all possible values are covered".to_string(),
                         expr.span,
                         "add a wildcard `_ => { ... }` arm to handle all remaining values"
@@ -416,4 +548,52 @@ impl TypeChecker {
             }
         }
     }
+
+    /// The value of a `bounded` annotation, which must be known at compile
+    /// time; anything but a literal is folded into one.
+    fn loop_bound(&mut self, bound: &Spanned<Expr>) -> Option<u64> {
+        let value = self.constant_value(&bound.node, bound.span);
+        match value {
+            Some(n) if !matches!(bound.node, Expr::Literal(_)) => self.record_folded(bound.span, n),
+            Some(_) => {}
+            None => self.error_with_help(
+                ErrorCode::NotConstant,
+                "loop bound must be known at compile time".to_string(),
+                bound.span,
+                "use literals, constants, `+`, `*`, and calls of #[pure] functions with constant arguments".to_string(),
+            ),
+        }
+        value
+    }
+
+    /// Report an assignment to an immutable variable, with `name` the
+    /// variable assigned. When a `let` bound it, adding `mut` there is
+    /// offered as a fix.
+    fn immutable_assign(&mut self, msg: String, span: Span, name: &str) {
+        let mut diag = Diagnostic::error(msg, span)
+            .with_code(ErrorCode::ImmutableAssign)
+            .with_help("declare the variable with `let mut` to make it mutable".to_string());
+        let let_name = self
+            .lookup_var(name)
+            .filter(|info| !info.mutable)
+            .and_then(|info| info.let_name);
+        if let Some(at) = let_name {
+            diag = diag.with_suggestion(Suggestion::new(
+                format!("declare '{}' with `let mut`", name),
+                vec![(
+                    Span::new(at.file_id, at.start, at.start),
+                    "mut ".to_string(),
+                )],
+            ));
+        }
+        self.diagnostics.push(diag);
+    }
+}
+
+/// The variable an assignment to `place` writes into.
+fn place_root(place: &Place) -> &str {
+    match place {
+        Place::Var(name) => name.split('.').next().unwrap_or(name),
+        Place::FieldAccess(inner, _) | Place::Index(inner, _) => place_root(&inner.node),
+    }
 }
@@ -1,6 +1,6 @@
 //! TypeChecker unit tests.
 
-use super::{check, check_err};
+use super::{check, check_err, check_importing};
 #[test]
 fn test_match_integer_pattern_on_bool_error() {
     let result = check("program test\nfn main() {\n    let b: Bool = pub_read() == pub_read()\n    match b {\n        0 => { pub_write(0) }\n        _ => { pub_write(1) }\n    }\n}");
@@ -76,6 +76,83 @@ fn test_match_struct_pattern_with_literal_field() {
     );
 }
 
+// --- Enums ---
+
+const SHAPE: &str =
+    "program test\nenum Shape {\n    Empty,\n    Circle(Field),\n    Rect(Field, U32),\n}\n";
+
+#[test]
+fn test_enum_match_covering_every_variant() {
+    let result = check(&format!(
+        "{}fn area(s: Shape) -> Field {{\n    let mut a: Field = 0\n    match s {{\n        Shape.Empty => {{}}\n        Shape.Circle(r) => {{ a = r * r }}\n        Shape.Rect(w, h) => {{ a = w * as_field(h) }}\n    }}\n    a\n}}\nfn main() {{\n    pub_write(area(Shape.Circle(pub_read())))\n    pub_write(area(Shape.Empty))\n}}",
+        SHAPE
+    ));
+    assert!(result.is_ok(), "exhaustive match: {:?}", result.err());
+}
+
+#[test]
+fn test_enum_match_reports_missing_variants() {
+    let errors = check_err(&format!(
+        "{}fn main() {{\n    let s: Shape = Shape.Circle(1)\n    match s {{\n        Shape.Circle(r) => {{ pub_write(r) }}\n    }}\n}}",
+        SHAPE
+    ));
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert_eq!(
+        errors[0].message,
+        "non-exhaustive match: variants `Shape.Empty`, `Shape.Rect` not covered"
+    );
+
+    // A wildcard covers the rest.
+    let result = check(&format!(
+        "{}fn main() {{\n    let s: Shape = Shape.Circle(1)\n    match s {{\n        Shape.Circle(r) => {{ pub_write(r) }}\n        _ => {{}}\n    }}\n}}",
+        SHAPE
+    ));
+    assert!(result.is_ok(), "wildcard arm: {:?}", result.err());
+}
+
+#[test]
+fn test_enum_variant_errors() {
+    let messages = |body: &str| -> Vec<String> {
+        check_err(&format!("{}fn main() {{\n{}\n}}", SHAPE, body))
+            .into_iter()
+            .map(|d| d.message)
+            .collect()
+    };
+    assert_eq!(
+        messages("    let s: Shape = Shape.Square(1)"),
+        ["enum `Shape` has no variant `Square`"]
+    );
+    assert_eq!(
+        messages("    let s: Shape = Shape.Rect(1)"),
+        ["variant 'Shape.Rect' has 2 fields, got 1 argument"]
+    );
+    assert_eq!(
+        messages("    let s: Shape = Shape.Circle(true)"),
+        ["'Shape.Circle' expects Field but got Bool"]
+    );
+    assert_eq!(
+        messages("    let s: Shape = Shape.Empty\n    match s {\n        Shape.Rect(w) => {}\n        _ => {}\n    }"),
+        ["variant `Shape.Rect` has 2 fields, but the pattern binds 1"]
+    );
+    assert_eq!(
+        messages("    let s: Shape = Shape.Empty\n    match s {\n        Shape.Empty => {}\n        Shape.Empty => {}\n        _ => {}\n    }"),
+        ["variant `Shape.Empty` is already matched above"]
+    );
+    assert_eq!(
+        messages("    let x: Field = 1\n    match x {\n        Shape.Empty => {}\n        _ => {}\n    }"),
+        ["variant pattern `Shape.Empty` does not match scrutinee type `Field`"]
+    );
+}
+
+#[test]
+fn test_enum_imported_from_module() {
+    let result = check_importing(
+        "module geo\npub enum Dir {\n    Left,\n    Right(Field),\n}\n",
+        "program test\nuse geo\nfn main() {\n    let d: geo.Dir = geo.Dir.Right(1)\n    match d {\n        geo.Dir.Left => {}\n        geo.Dir.Right(n) => { pub_write(n) }\n    }\n}",
+    );
+    assert!(result.is_ok(), "imported enum: {:?}", result.err());
+}
+
 // --- #[test] function validation ---
 
 #[test]
@@ -248,6 +325,28 @@ fn test_error_unreachable_code_has_help() {
     );
 }
 
+#[test]
+fn test_break_and_continue_only_inside_loops() {
+    check(
+        "program test\nfn main() {\n    let n: Field = pub_read()\n    for i in 0..4 bounded 4 {\n        if n == 0 {\n            break\n        }\n        match n {\n            1 => { continue }\n            _ => {}\n        }\n        pub_write(n)\n    }\n}",
+    )
+    .expect("break and continue in a loop should type-check");
+
+    let diags =
+        check_err("program test\nfn main() {\n    if pub_read() == 0 {\n        break\n    }\n}");
+    assert_eq!(diags.len(), 1, "{:?}", diags);
+    assert_eq!(diags[0].message, "`break` outside a loop");
+
+    let diags = check_err(
+        "program test\nfn main() {\n    for i in 0..4 bounded 4 {\n        continue\n        let z: Field = 1\n    }\n}",
+    );
+    assert_eq!(diags.len(), 1, "{:?}", diags);
+    assert_eq!(
+        diags[0].message,
+        "unreachable code after continue statement"
+    );
+}
+
 #[test]
 fn test_error_undefined_variable_has_help() {
     let diags = check_err("program test\nfn main() {\n    pub_write(xyz)\n}");
@@ -278,6 +377,25 @@ fn test_error_undefined_function_has_help() {
     );
 }
 
+#[test]
+fn test_while_condition_type() {
+    let ok = check(
+        "program test\nfn main() {\n    let n: U32 = as_u32(pub_read())\n    let mut i: Field = 0\n    while as_u32(i) < n bounded 16 {\n        let next: Field = i + 1\n        i = next\n    }\n    pub_write(i)\n}",
+    );
+    assert!(ok.is_ok(), "{:?}", ok.err());
+    let diags = check_err(
+        "program test\nfn main() {\n    let i: U32 = as_u32(1)\n    while i bounded 4 {\n        pub_write(0)\n    }\n}",
+    );
+    assert_eq!(diags.len(), 1, "{:?}", diags);
+    assert!(
+        diags[0]
+            .message
+            .contains("while condition must be Bool or Field"),
+        "{}",
+        diags[0].message
+    );
+}
+
 #[test]
 fn test_error_loop_bound_has_help() {
     let diags = check_err(
@@ -296,6 +414,160 @@ fn test_error_loop_bound_has_help() {
     );
 }
 
+#[test]
+fn test_loop_end_inferred_from_constants_and_len() {
+    let exports = check(
+        "program test\nconst N: Field = 3\nfn main() {\n    let arr: [Field; 4] = [1, 2, 3, 4]\n    for i in 0..arr.len() {\n        pub_write(arr[i])\n    }\n    for _ in 0..N * 2 {\n        pub_write(0)\n    }\n    for _ in 0..N bounded 8 {\n        pub_write(1)\n    }\n}",
+    )
+    .expect("known loop ends need no bound");
+    let values: Vec<u64> = exports.folded.iter().map(|&(_, v)| v).collect();
+    assert_eq!(values, vec![4, 6, 3]);
+}
+
+#[test]
+fn test_loop_bound_is_a_constant_expression() {
+    let exports = check(
+        "program test\nconst N: Field = 8\n#[pure]\nfn double(x: Field) -> Field {\n    x + x\n}\nfn main() {\n    let n: Field = pub_read()\n    for _ in 0..n bounded N * 2 {\n        pub_write(0)\n    }\n    let mut i: Field = 0\n    while as_u32(i) < as_u32(n) bounded double(N) + 1 {\n        i = i + 1\n    }\n}",
+    )
+    .expect("constant bounds should type-check");
+    let values: Vec<u64> = exports.folded.iter().map(|&(_, v)| v).collect();
+    assert_eq!(values, vec![16, 17]);
+
+    let diags = check_err(
+        "program test\nfn main() {\n    let n: Field = pub_read()\n    for _ in 0..n bounded n {\n        pub_write(0)\n    }\n}",
+    );
+    assert_eq!(diags.len(), 1, "{:?}", diags);
+    assert!(
+        diags[0]
+            .message
+            .contains("loop bound must be known at compile time"),
+        "{}",
+        diags[0].message
+    );
+}
+
+#[test]
+fn test_newtypes_are_distinct_types() {
+    let exports = check(
+        "program test\nstruct Amount(Field)\nstruct Pair(Field, U32)\nstruct Marker\nfn main() {\n    let a: Amount = Amount(pub_read())\n    let p: Pair = Pair(a.0, as_u32(7))\n    let m: Marker = Marker\n    pub_write(p.0 + a.0)\n}",
+    )
+    .expect("tuple and unit structs should type-check");
+    assert_eq!(exports.constructors.len(), 3);
+
+    for (source, expected) in [
+        (
+            "program test\nstruct Amount(Field)\nstruct Fee(Field)\nfn main() {\n    let f: Fee = Amount(1)\n}",
+            "type mismatch",
+        ),
+        (
+            "program test\nstruct Amount(Field)\nfn main() {\n    let a: Amount = Amount(1)\n    pub_write(a)\n}",
+            "expected Field",
+        ),
+        (
+            "program test\nstruct Amount(Field)\nfn main() {\n    let a: Amount = Amount(1, 2)\n}",
+            "'Amount' has 1 field, got 2 arguments",
+        ),
+        (
+            "program test\nstruct Amount(U32)\nfn main() {\n    let a: Amount = Amount(pub_read())\n}",
+            "'Amount' expects U32 but got Field",
+        ),
+        (
+            "program test\nstruct Point { x: Field }\nfn main() {\n    let p: Point = Point(1)\n}",
+            "'Point' is not a tuple struct",
+        ),
+    ] {
+        let diags = check_err(source);
+        assert!(
+            diags.iter().any(|d| d.message.contains(expected)),
+            "{}: {:?}",
+            expected,
+            diags
+        );
+    }
+}
+
+#[test]
+fn test_struct_defaults_and_update_syntax() {
+    let exports = check(
+        "program test\nconst FEE: Field = 3\nstruct Config {\n    owner: Field,\n    fee: Field = FEE + 1,\n    limit: U32 = 10,\n    open: Bool = true,\n}\nfn main() {\n    let c: Config = Config { owner: pub_read() }\n    let d: Config = Config { limit: as_u32(20), ..c }\n    pub_write(d.fee)\n}",
+    )
+    .expect("defaults and `..base` fill every field");
+    let fills: Vec<Vec<String>> = exports
+        .struct_fills
+        .iter()
+        .map(|(_, fill)| {
+            fill.iter()
+                .map(|(name, value)| match value {
+                    Some(crate::ast::Expr::Literal(crate::ast::Literal::Integer(v))) => {
+                        format!("{}={}", name, v)
+                    }
+                    Some(crate::ast::Expr::Var(var)) => format!("{}={}", name, var),
+                    _ => name.clone(),
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        fills,
+        vec![
+            vec!["owner", "fee=4", "limit=10", "open=1"],
+            vec!["owner=c.owner", "fee=c.fee", "limit", "open=c.open"],
+        ]
+    );
+}
+
+#[test]
+fn test_error_struct_defaults_and_base() {
+    for (source, expected) in [
+        (
+            "program test\nstruct S {\n    a: Field,\n    b: Field = 1,\n}\nfn main() {\n    let s: S = S { b: 2 }\n}",
+            "missing field 'a'",
+        ),
+        (
+            "program test\nstruct S {\n    a: Field = pub_read(),\n}\nfn main() {}",
+            "default value of field 'a' is not known at compile time",
+        ),
+        (
+            "program test\nstruct S {\n    a: U32 = 4294967296,\n}\nfn main() {}",
+            "does not fit in U32",
+        ),
+        (
+            "program test\nstruct S {\n    a: Digest = 0,\n}\nfn main() {}",
+            "cannot have a default value",
+        ),
+        (
+            "program test\nstruct S {\n    a: Field,\n}\nstruct T {\n    a: Field,\n}\nfn main() {\n    let t: T = T { a: 1 }\n    let s: S = S { ..t }\n}",
+            "`..` base must be a 'S'",
+        ),
+        (
+            "program test\nstruct S {\n    a: Field,\n    b: Field,\n}\nfn make() -> S {\n    S { a: 1, b: 2 }\n}\nfn main() {\n    let s: S = S { a: 3, ..make() }\n}",
+            "`..` base must be a variable",
+        ),
+    ] {
+        let diags = check_err(source);
+        assert!(
+            diags.iter().any(|d| d.message.contains(expected)),
+            "{}: {:?}",
+            expected,
+            diags
+        );
+    }
+}
+
+#[test]
+fn test_error_len_on_non_array() {
+    let diags = check_err(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    for _ in 0..x.len() {\n        pub_write(0)\n    }\n}",
+    );
+    assert!(
+        diags
+            .iter()
+            .any(|d| d.message.contains("`len()` needs an array")),
+        "{:?}",
+        diags
+    );
+}
+
 #[test]
 fn test_error_lt_requires_u32() {
     let diags = check_err(
@@ -399,6 +671,110 @@ fn test_pure_fn_allows_assert() {
     );
 }
 
+#[test]
+fn test_pure_fn_allows_debug_builtins_without_io() {
+    let result = check(
+        "program test\n#[pure]\nfn f(x: Field, d: Digest) {\n    debug_assert(x == 0, \"zero\")\n    trace(\"x\", x)\n    trace(\"d\", d)\n}\nfn main() {}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+    let diags = check_err(
+        "program test\n#[pure]\nfn f() {\n    trace(\"input\", pub_read())\n}\nfn main() {}",
+    );
+    assert!(diags
+        .iter()
+        .any(|d| d.message.contains("#[pure]") && d.message.contains("pub_read")));
+}
+
+#[test]
+fn test_assumptions_are_type_checked() {
+    let result = check(
+        "program test\n#[assume(n == 10)]\nfn f(n: Field) {\n    assume(n == 4, \"caller checks\")\n}\nfn main() {}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+    let diags = check_err(
+        "program test\n#[assume(n)]\nfn f(n: Field) {\n    assume(n == 1, \" \")\n    assume(n)\n}\nfn main() {}",
+    );
+    let messages: Vec<&str> = diags.iter().map(|d| d.message.as_str()).collect();
+    assert!(messages.contains(&"#[assume] predicate must be Bool, got Field"));
+    assert!(messages.contains(&"assumption reason must not be empty"));
+    assert!(messages
+        .iter()
+        .any(|m| m.contains("argument 1 of 'assume'") && m.contains("Bool")));
+}
+
+#[test]
+fn test_pure_call_in_constant_contexts() {
+    let exports = check(
+        "program test\n#[pure]\nfn square(x: Field) -> Field {\n    x * x\n}\n#[pure]\nfn twice(n: Field) -> Field {\n    let mut s: Field = 0\n    for _ in 0..n bounded 16 {\n        s = s + 2\n    }\n    s\n}\npub const N: Field = square(3)\nfn main() {\n    let a: [Field; N] = [0, 0, 0, 0, 0, 0, 0, 0, 0]\n    for _ in 0..twice(4) {\n        pub_write(a[0])\n    }\n}",
+    )
+    .expect("pure calls with constant arguments are constants");
+    let values: Vec<u64> = exports.folded.iter().map(|&(_, v)| v).collect();
+    assert_eq!(values, vec![9, 8]);
+    assert_eq!(exports.constants[0].2, 9);
+}
+
+#[test]
+fn test_pure_call_error_has_call_stack() {
+    let diags = check_err(
+        "program test\n#[pure]\nfn one(x: Field) -> Field {\n    assert(x == 1)\n    x\n}\n#[pure]\nfn outer(x: Field) -> Field {\n    one(x + 1)\n}\nconst N: Field = outer(2)\nfn main() {}",
+    );
+    let diag = diags
+        .iter()
+        .find(|d| d.message.contains("cannot evaluate `outer(2)`"))
+        .expect("evaluation error");
+    assert!(
+        diag.message.contains("assertion failed"),
+        "{}",
+        diag.message
+    );
+    assert_eq!(diag.notes, vec!["in `one(3)`", "called from `outer(2)`"]);
+}
+
+#[test]
+fn test_pure_call_runs_out_of_fuel() {
+    let diags = check_err(
+        "program test\n#[pure]\nfn count(n: Field) -> Field {\n    let mut s: Field = 0\n    for _ in 0..n bounded 1000000 {\n        s = s + 1\n    }\n    s\n}\nconst N: Field = count(1000000)\nfn main() {}",
+    );
+    assert!(
+        diags.iter().any(|d| d.message.contains("ran out of fuel")),
+        "{:?}",
+        diags
+    );
+}
+
+#[test]
+fn test_error_const_value_not_constant() {
+    let diags = check_err(
+        "program test\nfn f() -> Field {\n    pub_read()\n}\nconst N: Field = f()\nfn main() {}",
+    );
+    assert!(
+        diags.iter().any(|d| d
+            .message
+            .contains("value of constant 'N' is not known at compile time")),
+        "{:?}",
+        diags
+    );
+}
+
+#[test]
+fn test_trace_requires_an_identifier_label() {
+    for (call, expected) in [
+        ("trace(1, 2)", "string literal label"),
+        ("trace(\"Balance\", 2)", "lowercase identifier"),
+        ("trace(\"x\")", "expects 2 arguments"),
+        ("trace(\"x\", pub_write(1))", "cannot trace"),
+    ] {
+        let source = format!("program test\nfn main() {{\n    {}\n}}", call);
+        let diags = check_err(&source);
+        assert!(
+            diags.iter().any(|d| d.message.contains(expected)),
+            "{}: {:?}",
+            call,
+            diags
+        );
+    }
+}
+
 #[test]
 fn test_pure_fn_allows_hash() {
     // hash is a deterministic pure computation (same inputs → same outputs)
@@ -417,3 +793,222 @@ fn test_pure_fn_rejects_sponge_init() {
         .iter()
         .any(|d| d.message.contains("#[pure]") && d.message.contains("sponge_init")));
 }
+
+#[test]
+fn test_proof_items_are_typed_by_width() {
+    let result = check("program test\nfn main() {\n    sponge_init()\n    let r: Digest = proof_item_digest()\n    let x: XField = proof_item_xfe()\n    let f: Field = proof_item()\n    let c: Digest = encode_claim(r, f, 0)\n}");
+    assert!(result.is_ok(), "{:?}", result.err());
+    let diags = check_err("program test\nfn main() {\n    let r: Digest = proof_item_xfe()\n}");
+    assert!(!diags.is_empty());
+}
+
+#[test]
+fn test_pure_fn_rejects_proof_items() {
+    let diags =
+        check_err("program test\n#[pure]\nfn f() -> Field {\n    proof_item()\n}\nfn main() {}");
+    assert!(diags
+        .iter()
+        .any(|d| d.message.contains("#[pure]") && d.message.contains("proof_item")));
+}
+
+#[test]
+fn test_authenticated_fn_accepts_transitive_merkle_step() {
+    let result = check("program test\nfn step(d: Digest) -> Digest {\n    let (d0, d1, d2, d3, d4) = d\n    let (_, parent) = merkle_step(as_u32(1), d0, d1, d2, d3, d4)\n    parent\n}\n#[authenticated]\nfn leaf(root: Digest) -> Digest {\n    let leaf: Digest = divine5()\n    assert_digest(step(leaf), root)\n    leaf\n}\nfn main() {}");
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_authenticated_fn_without_merkle_path_is_rejected() {
+    let diags = check_err(
+        "program test\n#[authenticated]\nfn leaf() -> Digest {\n    divine5()\n}\nfn main() {}",
+    );
+    assert!(diags.iter().any(|d| d
+        .message
+        .contains("'leaf' never performs Merkle authentication")));
+}
+
+#[test]
+fn test_authenticated_struct_requires_authenticated_constructor() {
+    let src = "program test\n#[authenticated]\nstruct Leaf {\n    value: Field,\n}\nfn forge() -> Leaf {\n    Leaf { value: divine() }\n}\nfn main() {}";
+    let diags = check_err(src);
+    assert!(diags.iter().any(|d| d.message.contains(
+        "#[authenticated] struct 'Leaf' can only be constructed in an #[authenticated] function"
+    )));
+}
+
+const HASH_MODULE: &str = include_str!("../../../vm/crypto/hash.tri");
+
+#[test]
+fn test_sponge_state_comes_only_from_init() {
+    let forged = "program test\nuse vm.crypto.hash\nfn main() {\n    let s: hash.Sponge = hash.Sponge { blocks: 0 }\n    let out: [Field; 10] = hash.squeeze(s)\n}";
+    let diags = check_importing(HASH_MODULE, forged).unwrap_err();
+    assert!(diags.iter().any(|d| d
+        .message
+        .contains("cannot construct sponge state 'hash.Sponge'")));
+
+    let uninit = "program test\nuse vm.crypto.hash\nfn main() {\n    let out: [Field; 10] = hash.squeeze(0)\n}";
+    assert!(check_importing(HASH_MODULE, uninit).is_err());
+}
+
+#[test]
+fn test_dropped_sponge_state_warns() {
+    let src = "program test\nuse vm.crypto.hash\nfn main() {\n    let s: hash.Sponge = hash.init()\n    hash.absorb(s, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0)\n    let last: hash.Sponge = hash.absorb(s, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0)\n    let _spare: hash.Sponge = hash.init()\n}";
+    let warnings = check_importing(HASH_MODULE, src).unwrap().warnings;
+    let messages: Vec<&str> = warnings.iter().map(|w| w.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "sponge state returned here is discarded",
+            "sponge state 'last' is never used",
+        ]
+    );
+}
+
+#[test]
+fn test_threaded_sponge_state_is_silent() {
+    let src = "program test\nuse vm.crypto.hash\nfn main() {\n    let s: hash.Sponge = hash.init()\n    let s1: hash.Sponge = hash.absorb(s, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0)\n    let out: [Field; 10] = hash.squeeze(s1)\n    pub_write(out[0] + hash.absorbed(s1))\n}";
+    let exports = check_importing(HASH_MODULE, src).unwrap();
+    assert!(exports.warnings.is_empty(), "{:?}", exports.warnings);
+}
+
+#[test]
+fn test_imported_generic_fns_record_foreign_instances() {
+    let module = "module lib\npub const WIDTH: U32 = 2\npub fn pick<N>(xs: [Field; N * WIDTH]) -> Field {\n    xs[N]\n}";
+    let src = "program test\nuse lib\nfn main() {\n    let a: [Field; 4] = [1, 2, 3, 4]\n    pub_write(lib.pick(a) + lib.pick<2>(a))\n}";
+    let exports = check_importing(module, src).expect("lib.pick infers N = 2");
+    assert!(exports.mono_instances.is_empty());
+    assert_eq!(exports.foreign_instances.len(), 1);
+    assert_eq!(exports.foreign_instances[0].0.name, "lib.pick");
+    assert_eq!(exports.foreign_instances[0].0.size_args, vec![2]);
+    assert_eq!(exports.call_resolutions.len(), 2);
+
+    let odd = "program test\nuse lib\nfn main() {\n    let a: [Field; 3] = [1, 2, 3]\n    pub_write(lib.pick(a))\n}";
+    let diags = check_importing(module, odd).unwrap_err();
+    assert_eq!(
+        diags[0].message,
+        "argument 1 of 'lib.pick': no size 'N' makes N * 2 equal 3"
+    );
+}
+
+#[test]
+fn test_digest_indexing_and_conversions() {
+    let exports = check(
+        "program test\nconst I: U32 = 2\nfn main() {\n    let d: Digest = divine5()\n    let first: Field = d[0]\n    let mid: Field = d[I + 1]\n    let arr: [Field; 5] = Digest::to_array(d)\n    let e: Digest = Digest::from_array(arr)\n    let f: Digest = Digest::from_fields(first, mid, 0, 0, 0)\n    let same: Bool = e == f\n    assert(same)\n}",
+    )
+    .expect("digest helpers should type-check");
+    assert_eq!(exports.digest_eqs.len(), 1);
+    assert_eq!(exports.folded.len(), 1, "the index `I + 1` is folded to 3");
+
+    for (source, expected) in [
+        (
+            "program test\nfn main() {\n    let d: Digest = divine5()\n    let i: U32 = as_u32(pub_read())\n    let x: Field = d[i]\n}",
+            "digest index must be known at compile time",
+        ),
+        (
+            "program test\nfn main() {\n    let d: Digest = divine5()\n    let x: Field = d[5]\n}",
+            "digest index 5 out of range",
+        ),
+        (
+            "program test\nfn main() {\n    let d: Digest = Digest::from_fields(1, 2, 3, 4)\n}",
+            "expects 5 arguments",
+        ),
+        (
+            "program test\nfn main() {\n    let a: [Field; 4] = [1, 2, 3, 4]\n    let d: Digest = Digest::from_array(a)\n}",
+            "expected [Field; 5]",
+        ),
+    ] {
+        let diags = check_err(source);
+        assert!(
+            diags.iter().any(|d| d.message.contains(expected)),
+            "{}: {:?}",
+            expected,
+            diags
+        );
+    }
+}
+
+#[test]
+fn test_logical_and_bitwise_operators() {
+    check(
+        "program test\nfn main() {\n    let a: U32 = as_u32(pub_read())\n    let b: U32 = as_u32(pub_read())\n    let lazy: Bool = a < b && b < a || a == b\n    let strict: Bool = (a < b) & (b < a) | (a == b)\n    let bits: U32 = a | b ^ a & b\n    assert(lazy == strict)\n}",
+    )
+    .expect("logical and bitwise operators should type-check");
+
+    for (source, expected) in [
+        (
+            "program test\nfn main() {\n    let a: U32 = as_u32(pub_read())\n    let x: Bool = a && true\n}",
+            "operator '&&' requires Bool operands",
+        ),
+        (
+            "program test\nfn main() {\n    let x: Bool = pub_read() == 0 | true\n}",
+            "operator '|' requires U32 operands (or both Bool)",
+        ),
+    ] {
+        let diags = check_err(source);
+        assert!(
+            diags.iter().any(|d| d.message.contains(expected)),
+            "{}: {:?}",
+            expected,
+            diags
+        );
+    }
+}
+
+#[test]
+fn test_include_data_tables() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("sq.json"), "[1, 2, 5, 10]").unwrap();
+    std::fs::write(dir.path().join("big.json"), "[1, 4294967296]").unwrap();
+    std::fs::write(dir.path().join("bytes.bin"), [7u8, 8, 9]).unwrap();
+    let check_in_dir = |source: &str| {
+        let (tokens, _, _) = crate::lexer::Lexer::new(source, 0).tokenize();
+        let file = crate::parser::Parser::new(tokens).parse_file().unwrap();
+        crate::typecheck::TypeChecker::new()
+            .with_source_dir(dir.path())
+            .check_file(&file)
+    };
+
+    let exports = check_in_dir(
+        "module t\npub const SQ: [Field; 4] = include_data(\"sq.json\")\nconst B: [U32; 3] = include_data(\"bytes.bin\")\npub fn at(i: Field) -> Field {\n    SQ[i] + SQ[3] + as_field(B[0])\n}",
+    )
+    .expect("tables should type-check");
+    assert_eq!(exports.data.len(), 2);
+    assert_eq!(exports.data[0].values, vec![1, 2, 5, 10]);
+    assert_eq!(exports.data[1].values, vec![7, 8, 9]);
+    assert_eq!(exports.data_reads, vec![("SQ".to_string(), 1)]);
+
+    for (source, expected) in [
+        (
+            "module t\nconst SQ: [Field; 5] = include_data(\"sq.json\")",
+            "'sq.json' has 4 values, but constant 'SQ' holds 5",
+        ),
+        (
+            "module t\nconst BIG: [U32; 2] = include_data(\"big.json\")",
+            "value 4294967296 at index 1 of 'big.json' is not a valid U32",
+        ),
+        (
+            "module t\nconst SQ: Field = include_data(\"sq.json\")",
+            "needs an array type",
+        ),
+        (
+            "module t\nconst SQ: [Field; 4] = include_data(\"missing.json\")",
+            "cannot include",
+        ),
+        (
+            "module t\nconst SQ: [Field; 4] = include_data(\"sq.json\")\nfn f() -> Field {\n    SQ[4]\n}",
+            "index 4 out of range for 'SQ' of 4 elements",
+        ),
+        (
+            "module t\nfn f() {\n    let x: [Field; 4] = include_data(\"sq.json\")\n}",
+            "include_data",
+        ),
+    ] {
+        let diags = check_in_dir(source).err().unwrap_or_default();
+        assert!(
+            diags.iter().any(|d| d.message.contains(expected)),
+            "{}: {:?}",
+            expected,
+            diags
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! RAM aliasing tests.
+
+use super::{check, check_err, check_importing};
+
+fn messages(source: &str) -> Vec<String> {
+    check_err(source).into_iter().map(|d| d.message).collect()
+}
+
+#[test]
+fn test_ram_two_handles_writing_one_region_rejected() {
+    let errors = messages(
+        "program test\nfn main() {\n    let a = 100\n    let b = 102\n    ram_write_block(a, divine5())\n    ram_write(b, 7)\n}",
+    );
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(
+        errors[0].contains("102..103") && errors[0].contains("`a` and `b`"),
+        "{}",
+        errors[0]
+    );
+}
+
+#[test]
+fn test_ram_derived_handle_is_a_reborrow() {
+    let result = check(
+        "program test\nfn main() {\n    let a = 100\n    let b = a + 2\n    ram_write_block(a, divine5())\n    ram_write(b, 7)\n    let c = 200\n    let x: Field = ram_read(a + 3)\n    ram_write(c, x)\n}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_ram_inner_scope_write_conflicts_with_outer_handle() {
+    let errors = messages(
+        "program test\nfn main() {\n    let a = 10\n    ram_write(a, 1)\n    if pub_read() == 0 {\n        let b = 10\n        ram_write(b, 2)\n    }\n}",
+    );
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+}
+
+#[test]
+fn test_ram_handles_of_finished_scopes_do_not_conflict() {
+    let result = check(
+        "program test\nfn main() {\n    for i in 0..2 {\n        let a = 10\n        ram_write(a, 1)\n    }\n    let b = 10\n    ram_write(b, 2)\n}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_ram_write_to_sec_ram_slot_rejected() {
+    let errors = messages(
+        "program test\nsec ram: { 17: Digest }\nfn main() {\n    let x: Field = ram_read(19)\n    ram_write(19, x + 1)\n}",
+    );
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].contains("sec ram slot at 17"), "{}", errors[0]);
+}
+
+#[test]
+fn test_ram_overlapping_sec_ram_slots_rejected() {
+    let errors = messages("program test\nsec ram: { 17: Digest, 20: Field }\nfn main() {}");
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].contains("words 17..22"), "{}", errors[0]);
+}
+
+#[test]
+fn test_ram_intrinsic_wrappers_are_checked_across_modules() {
+    let module =
+        "module vm.io.mem\n#[intrinsic(ram_write)]\npub fn write(addr: Field, val: Field)\n";
+    let result = check_importing(
+        module,
+        "program test\nuse vm.io.mem\nsec ram: { 5: Field }\nfn main() {\n    mem.write(5, 1)\n}",
+    );
+    assert!(result.is_err(), "write through mem.write should be checked");
+}
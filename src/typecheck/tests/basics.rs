@@ -1,6 +1,6 @@
 //! TypeChecker unit tests.
 
-use super::{check, check_with_flags};
+use super::{check, check_err, check_with_flags};
 
 #[test]
 fn test_valid_field_arithmetic() {
@@ -20,6 +20,24 @@ fn test_undefined_variable() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_error_codes() {
+    use crate::diagnostic::ErrorCode;
+    let code = |source: &str| check_err(source)[0].code;
+    assert_eq!(
+        code("program test\nfn main() {\n    pub_write(x)\n}"),
+        Some(ErrorCode::UndefinedVariable)
+    );
+    assert_eq!(
+        code("program test\nfn main() {\n    let a: U32 = pub_read()\n}"),
+        Some(ErrorCode::TypeMismatch)
+    );
+    assert_eq!(
+        code("program test\nfn main() {\n    let x: Field = 0\n    x = 1\n}"),
+        Some(ErrorCode::ImmutableAssign)
+    );
+}
+
 #[test]
 fn test_assert_with_eq() {
     let result = check("program test\nfn main() {\n    let a: Field = pub_read()\n    let b: Field = divine()\n    assert(a == b)\n}");
@@ -98,6 +116,25 @@ fn test_event_max_9_fields() {
     assert!(result.is_err()); // 10 fields > max 9
 }
 
+#[test]
+fn test_deprecated_event_warns_on_emit() {
+    let exports = check("program test\n#[version(2)]\n#[deprecated(\"use Pay\")]\nevent Transfer { amount: Field }\nevent Pay { amount: Field }\nfn main() {\n    reveal Transfer { amount: 1 }\n    seal Transfer { amount: 2 }\n    reveal Pay { amount: 3 }\n}").unwrap();
+    let warnings: Vec<&str> = exports
+        .warnings
+        .iter()
+        .map(|w| w.message.as_str())
+        .filter(|m| m.contains("deprecated"))
+        .collect();
+    assert_eq!(warnings, vec!["event 'Transfer' is deprecated: use Pay"; 2]);
+    let events: Vec<(&str, u64, u64)> = exports
+        .events
+        .iter()
+        .map(|e| (e.name.as_str(), e.tag, e.version))
+        .collect();
+    assert_eq!(events, vec![("Transfer", 1 << 32, 2), ("Pay", 1, 1)]);
+    assert_eq!(exports.events[0].deprecated.as_deref(), Some("use Pay"));
+}
+
 #[test]
 fn test_digest_destructuring() {
     let result = check("program test\nfn main() {\n    let d: Digest = divine5()\n    let (f0, f1, f2, f3, f4) = d\n    pub_write(f0)\n    pub_write(f4)\n}");
@@ -164,6 +201,14 @@ fn test_dead_code_after_assert_false() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_dead_code_after_assert_false_with_message() {
+    let result = check(
+        "program test\nfn foo() {\n    assert(false, \"unreachable\")\n    pub_write(1)\n}\nfn main() {\n}",
+    );
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_no_false_positive_dead_code() {
     let result = check("program test\nfn foo() -> Field {\n    let x: Field = pub_read()\n    pub_write(x)\n    x\n}\nfn main() {\n}");
@@ -184,6 +229,119 @@ fn test_unused_import_warning() {
     );
 }
 
+#[test]
+fn test_reserved_keyword_names_warn() {
+    let exports = check(
+        "program test\nstruct S {\n    impl: Field,\n}\nfn trait(x: Field) -> Field {\n    x\n}\nfn main() {\n    let x: Field = pub_read()\n    for impl in 0..2 bounded 2 {\n        pub_write(trait(x))\n    }\n    let s: S = S { impl: x }\n    pub_write(s.impl)\n}",
+    )
+    .expect("reserved words are still valid names");
+    let names: Vec<&str> = exports
+        .warnings
+        .iter()
+        .filter(|w| w.message.contains("is reserved for a future keyword"))
+        .map(|w| w.message.split('\'').nth(1).unwrap())
+        .collect();
+    // One warning per declaration, none for the uses.
+    assert_eq!(names, vec!["impl", "trait", "impl"]);
+    assert_eq!(
+        exports.warnings[0].help.as_deref(),
+        Some("rename it, e.g. to 'impl_'")
+    );
+}
+
+#[test]
+fn test_suggestions_for_mut_legacy_paths_and_reserved_locals() {
+    use crate::diagnostic::Applicability;
+
+    let source = "program test\nuse std.hash\nfn main() {\n    let x: Field = pub_read()\n    x = x + 1\n    let trait: Field = x\n    pub_write(trait)\n}";
+    let errors = check(source).expect_err("x is immutable");
+    let fixes = |prefix: &str| {
+        let d = errors
+            .iter()
+            .find(|d| d.message.starts_with(prefix))
+            .unwrap_or_else(|| panic!("no diagnostic starting with {:?}", prefix));
+        assert_eq!(d.suggestions.len(), 1);
+        assert_eq!(
+            d.suggestions[0].applicability,
+            Applicability::MachineApplicable
+        );
+        d.suggestions[0]
+            .edits
+            .iter()
+            .map(|(span, text)| {
+                (
+                    &source[span.start as usize..span.end as usize],
+                    text.as_str(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(fixes("cannot assign"), vec![("", "mut ")]);
+    assert_eq!(
+        fixes("'std.hash' is a legacy"),
+        vec![("use std.hash", "use vm.crypto.hash")]
+    );
+    assert_eq!(
+        fixes("'trait' is reserved"),
+        vec![("trait", "trait_"), ("trait", "trait_")]
+    );
+
+    // A rename onto a name already in use needs a person to look at it.
+    let errors = check("program test\nfn main() {\n    let trait_: Field = 1\n    let trait: Field = trait_\n    pub_write(trait)\n}")
+        .expect("only warnings");
+    let reserved = &errors.warnings[0];
+    assert_eq!(
+        reserved.suggestions[0].applicability,
+        Applicability::MaybeIncorrect
+    );
+}
+
+#[test]
+fn test_legacy_path_fix_rewrites_uses_by_full_path() {
+    use crate::diagnostic::Applicability;
+
+    let legacy_fix = |source: &str| {
+        let diagnostics = match check(source) {
+            Ok(exports) => exports.warnings,
+            Err(errors) => errors,
+        };
+        let d = diagnostics
+            .into_iter()
+            .find(|d| d.message.contains("legacy module path"))
+            .expect("legacy path warning");
+        assert!(d.notes[0].contains("no_legacy_paths"));
+        d.suggestions.into_iter().next().expect("suggestion")
+    };
+
+    let source = "program test\nuse std.hash\nfn main() {\n    let d: std.hash.State = std.hash.squeeze()\n    let e: Field = hash.squeeze()\n}";
+    let fix = legacy_fix(source);
+    assert_eq!(fix.applicability, Applicability::MachineApplicable);
+    let edits: Vec<_> = fix
+        .edits
+        .iter()
+        .map(|(span, text)| {
+            (
+                span.start,
+                &source[span.start as usize..span.end as usize],
+                text.as_str(),
+            )
+        })
+        .collect();
+    let at = |needle: &str| source.find(needle).unwrap() as u32;
+    assert_eq!(
+        edits,
+        vec![
+            (at("use"), "use std.hash", "use vm.crypto.hash"),
+            (at("std.hash.State"), "std.hash", "vm.crypto.hash"),
+            (at("std.hash.squeeze"), "std.hash", "vm.crypto.hash"),
+        ]
+    );
+
+    // A use inside a compound type has no span of its own to rewrite.
+    let fix = legacy_fix("program test\nuse std.hash\nfn f(xs: [std.hash.State; 2]) {\n}");
+    assert_eq!(fix.applicability, Applicability::MaybeIncorrect);
+}
+
 #[test]
 fn test_used_import_no_warning() {
     // We can't test cross-module calls in unit tests (no import_module),
@@ -244,6 +402,44 @@ fn test_asm_block_with_effect() {
     assert!(result.is_ok(), "asm with effect should type check");
 }
 
+#[test]
+fn test_asm_alternatives_per_target() {
+    let result = check(
+        "program test\nfn main() {\n    asm(miden, +1) { push.1 }\n    asm(triton, +1) { push 1 }\n    asm(+1) { push 1 }\n}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+    // An untagged block is the fallback for uncovered targets.
+    let result =
+        check("program test\nfn main() {\n    asm(miden) { dup.0 drop }\n    asm { nop }\n}");
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_error_asm_alternatives_miss_target() {
+    let errors = check_err("program test\nfn main() {\n    asm(miden) { dup.0 drop }\n}");
+    assert!(
+        errors
+            .iter()
+            .any(|d| d.message == "asm blocks for 'miden' do not cover target 'triton'"),
+        "{:?}",
+        errors
+    );
+}
+
+#[test]
+fn test_error_asm_alternatives_differ_in_effect() {
+    let errors = check_err(
+        "program test\nfn main() {\n    asm(triton, +1) { push 1 }\n    asm(miden) { dup.0 drop }\n}",
+    );
+    assert!(
+        errors
+            .iter()
+            .any(|d| d.message.contains("declare different stack effects")),
+        "{:?}",
+        errors
+    );
+}
+
 // --- Size-generic function tests ---
 
 #[test]
@@ -317,6 +513,34 @@ fn test_generic_fn_multiple_instantiations() {
     );
 }
 
+#[test]
+fn test_generic_fn_infers_through_size_expressions() {
+    let exports = check(
+        "program test\nconst DEPTH: U32 = 3\nfn path<N>(nodes: [Field; N + 1], bits: [Field; 2 * N]) -> [Field; N] {\n    [nodes[0], bits[0], nodes[N]]\n}\nfn main() {\n    let n: [Field; 4] = [1, 2, 3, 4]\n    let b: [Field; 6] = [1, 2, 3, 4, 5, 6]\n    let p: [Field; 3] = path(n, b)\n    let q: [Field; DEPTH] = path<DEPTH>(n, b)\n    pub_write(p[0] + q[0])\n}",
+    )
+    .expect("N + 1 = 4 and 2 * N = 6 agree on N = 3");
+    assert_eq!(exports.mono_instances.len(), 1);
+    assert_eq!(exports.mono_instances[0].size_args, vec![3]);
+
+    // No N makes 2 * N odd.
+    let diags = check_err(
+        "program test\nfn half<N>(xs: [Field; 2 * N]) -> Field {\n    xs[N]\n}\nfn main() {\n    let c: [Field; 5] = [1, 2, 3, 4, 5]\n    pub_write(half(c))\n}",
+    );
+    assert!(diags
+        .iter()
+        .any(|d| d.message == "argument 1 of 'half': no size 'N' makes 2 * N equal 5"));
+
+    // The second argument disagrees with the N bound by the first.
+    let diags = check_err(
+        "program test\nfn pair<N>(a: [Field; N], b: [Field; N + 1]) -> Field {\n    a[0] + b[0]\n}\nfn main() {\n    let a: [Field; 2] = [1, 2]\n    pub_write(pair(a, a))\n}",
+    );
+    assert_eq!(diags.len(), 1);
+    assert_eq!(
+        diags[0].message,
+        "argument 2 of 'pair': expected length N + 1 = 3 (N = 2 from argument 1) but got 2"
+    );
+}
+
 #[test]
 fn test_generic_fn_non_generic_with_size_args_fails() {
     // Calling a non-generic function with size args should error
@@ -426,3 +650,75 @@ fn test_match_bool_pattern_on_field_error() {
         "boolean pattern on Field scrutinee should fail"
     );
 }
+
+#[test]
+fn test_assertion_messages_collected_once_in_order() {
+    let exports = check("program test\nfn main() {\n    let x: Field = pub_read()\n    assert(x == 1, \"not one\")\n    assert_eq(x, 1, \"mismatch\")\n    assert(x == 1, \"not one\")\n    assert(x == 1)\n}")
+        .expect("assertion messages should type-check");
+    assert_eq!(exports.assertion_messages, vec!["not one", "mismatch"]);
+    let codes = crate::typecheck::error_codes([&exports]);
+    assert_eq!(codes.get("not one"), Some(&1));
+    assert_eq!(codes.get("mismatch"), Some(&2));
+}
+
+#[test]
+fn test_string_literal_outside_assertion_error() {
+    let errors =
+        check_err("program test\nfn main() {\n    pub_write(\"seven\")\n    let label = \"ok\"\n}");
+    assert_eq!(
+        errors
+            .iter()
+            .filter(|d| d.message.contains("only allowed as assertion messages"))
+            .count(),
+        2,
+        "{:?}",
+        errors
+    );
+}
+
+#[test]
+fn test_empty_assertion_message_error() {
+    let errors = check_err("program test\nfn main() {\n    assert(true, \"\")\n}");
+    assert!(errors
+        .iter()
+        .any(|d| d.message.contains("must not be empty")));
+}
+
+#[test]
+fn test_fail_codes_reserve_their_error_ids() {
+    let exports = check("program test\nconst INSUFFICIENT_BALANCE: Field = 1\nconst INVALID_SIGNATURE: Field = 3\nfn main() {\n    let x: Field = pub_read()\n    assert(x == 1, \"first\")\n    assert(x == 2, \"second\")\n    if x == 0 {\n        fail(INSUFFICIENT_BALANCE)\n    }\n    assert(x == 3, \"INVALID_SIGNATURE\")\n    fail(INVALID_SIGNATURE)\n}")
+        .expect("fail codes should type-check");
+    assert_eq!(
+        exports.fail_codes,
+        vec![
+            ("INSUFFICIENT_BALANCE".to_string(), 1),
+            ("INVALID_SIGNATURE".to_string(), 3)
+        ]
+    );
+    let codes = crate::typecheck::error_codes([&exports]);
+    assert_eq!(codes.get("first"), Some(&2));
+    assert_eq!(codes.get("second"), Some(&4));
+    assert_eq!(codes.get("INVALID_SIGNATURE"), Some(&3));
+}
+
+#[test]
+fn test_fail_code_errors() {
+    for (body, expected) in [
+        ("fail(2)", "named constant"),
+        ("fail(ZERO)", "means success"),
+        ("fail(ONE)\n    fail(UNO)", "clashes with 'ONE'"),
+        ("fail(ONE)\n    pub_write(1)", "unreachable"),
+    ] {
+        let source = format!(
+            "program test\nconst ZERO: Field = 0\nconst ONE: Field = 1\nconst UNO: Field = 1\nfn main() {{\n    {}\n}}",
+            body
+        );
+        let errors = check_err(&source);
+        assert!(
+            errors.iter().any(|d| d.message.contains(expected)),
+            "{}: {:?}",
+            body,
+            errors
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Entrypoint table tests.
+
+use super::{check, check_err};
+
+fn messages(source: &str) -> Vec<String> {
+    check_err(source).into_iter().map(|d| d.message).collect()
+}
+
+#[test]
+fn test_entrypoint_table_exported() {
+    let result = check(
+        "program test\n#[entrypoint(4)]\nfn mint() {}\n#[entrypoint]\nfn pay() {}\n#[entrypoint]\nfn burn() {}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+    assert_eq!(
+        result.unwrap().entrypoints,
+        vec![
+            (0, "pay".to_string()),
+            (1, "burn".to_string()),
+            (4, "mint".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_entrypoint_shared_selector_rejected() {
+    let errors =
+        messages("program test\n#[entrypoint(2)]\nfn pay() {}\n#[entrypoint(2)]\nfn mint() {}");
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(
+        errors[0].contains("entrypoints 'pay' and 'mint' share selector 2"),
+        "{}",
+        errors[0]
+    );
+}
+
+#[test]
+fn test_entrypoint_with_parameters_rejected() {
+    let errors =
+        messages("program test\n#[entrypoint]\nfn pay(amount: Field) -> Field {\n    amount\n}");
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(
+        errors[0].contains("must take no parameters and return nothing"),
+        "{}",
+        errors[0]
+    );
+}
+
+#[test]
+fn test_entrypoint_invalid_selector_rejected() {
+    let errors = messages("program test\n#[entrypoint(x)]\nfn pay() {}");
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(
+        errors[0].contains("invalid entrypoint selector"),
+        "{}",
+        errors[0]
+    );
+}
+
+#[test]
+fn test_entrypoint_in_module_rejected() {
+    let errors = messages("module lib\n#[entrypoint]\npub fn pay() {}");
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].contains("in a module"), "{}", errors[0]);
+}
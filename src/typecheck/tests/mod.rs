@@ -1,5 +1,9 @@
 mod advanced;
+mod alias;
 mod basics;
+mod entrypoint;
+mod output;
+mod range_check;
 
 use crate::diagnostic::Diagnostic;
 use crate::lexer::Lexer;
@@ -31,3 +35,16 @@ pub(super) fn check_with_flags(
         .with_cfg_flags(flag_set)
         .check_file(&file)
 }
+
+/// Check `source` after importing the exports of `module`.
+pub(super) fn check_importing(
+    module: &str,
+    source: &str,
+) -> Result<ModuleExports, Vec<Diagnostic>> {
+    let exports = check(module).expect("imported module should type-check");
+    let (tokens, _, _) = Lexer::new(source, 0).tokenize();
+    let file = Parser::new(tokens).parse_file().unwrap();
+    let mut tc = TypeChecker::new();
+    tc.import_module(&exports);
+    tc.check_file(&file)
+}
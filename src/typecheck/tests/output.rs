@@ -0,0 +1,113 @@
+//! Public output declaration tests.
+
+use super::{check, check_err, check_importing};
+
+fn messages(source: &str) -> Vec<String> {
+    check_err(source).into_iter().map(|d| d.message).collect()
+}
+
+#[test]
+fn test_output_matching_declaration_accepted() {
+    let result = check(
+        "program test\npub output: Digest\nfn main() {\n    let d: Digest = divine5()\n    let (a, b, c, e, f) = d\n    pub_write5(a, b, c, e, f)\n}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+    assert_eq!(
+        result.unwrap().public_output,
+        vec![("Digest".to_string(), 5)]
+    );
+}
+
+#[test]
+fn test_output_count_mismatch_rejected() {
+    let errors = messages(
+        "program test\npub output: Field\npub output: Field\nfn main() {\n    pub_write(1)\n}",
+    );
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(
+        errors[0].contains("main writes 1 field element of public output")
+            && errors[0].contains("declares 2 field elements (Field, Field)"),
+        "{}",
+        errors[0]
+    );
+}
+
+#[test]
+fn test_output_differing_paths_rejected() {
+    let errors = messages(
+        "program test\npub output: Field\nfn main() {\n    let x: Field = pub_read()\n    if x == 0 {\n        pub_write(x)\n    }\n}",
+    );
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(
+        errors[0].contains("0 or 1 field elements of public output depending on the path"),
+        "{}",
+        errors[0]
+    );
+}
+
+#[test]
+fn test_output_through_helpers_loops_and_reveal() {
+    let result = check(
+        "program test\npub output: [Field; 3]\npub output: Field\npub output: [Field; 3]\nevent Done { a: Field, b: Field }\nfn emit(x: Field) {\n    pub_write(x)\n}\nfn main() {\n    for i in 0..3 {\n        emit(1)\n    }\n    match pub_read() {\n        0 => { pub_write(0) }\n        _ => { emit(1) }\n    }\n    reveal Done { a: 1, b: 2 }\n}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_output_in_unbounded_loop_rejected() {
+    let errors = messages(
+        "program test\npub output: Field\nfn main() {\n    let n: Field = pub_read()\n    for i in 0..n bounded 4 {\n        pub_write(n)\n    }\n}",
+    );
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(
+        errors[0].contains("without a constant trip count"),
+        "{}",
+        errors[0]
+    );
+
+    // A while loop's trip count depends on its condition.
+    let errors = messages(
+        "program test\npub output: Field\nfn main() {\n    let mut go: Bool = true\n    while go bounded 1 {\n        pub_write(1)\n        go = false\n    }\n}",
+    );
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+}
+
+#[test]
+fn test_output_early_return_counts_as_a_path() {
+    let errors = messages(
+        "program test\npub output: Field\nfn main() {\n    if pub_read() == 0 {\n        return\n    }\n    pub_write(1)\n}",
+    );
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+}
+
+#[test]
+fn test_output_of_imported_function() {
+    let module =
+        "module out\npub fn pair(a: Field, b: Field) {\n    pub_write(a)\n    pub_write(b)\n}";
+    let exports = check(module).unwrap();
+    assert_eq!(
+        exports.output_writes,
+        vec![("pair".to_string(), Some(vec![2]))]
+    );
+    let result = check_importing(
+        module,
+        "program test\nuse out\npub output: (Field, Field)\nfn main() {\n    out.pair(1, 2)\n}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_output_unchecked_without_declaration() {
+    let result = check(
+        "program test\nfn main() {\n    if pub_read() == 0 {\n        pub_write(1)\n    }\n}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_output_failing_path_is_not_counted() {
+    let result = check(
+        "program test\npub output: Field\nfn main() {\n    let x: Field = pub_read()\n    if x == 0 {\n        pub_write(x)\n    } else {\n        assert(false, \"unreachable\")\n    }\n}",
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+}
@@ -0,0 +1,156 @@
+//! Automatic range check tests.
+
+use super::{check, check_err, check_with_flags};
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::typecheck::{insert_range_checks, RangeCheck, TypeChecker};
+
+fn checks(source: &str) -> Vec<RangeCheck> {
+    check(source).expect("should type-check").range_checks
+}
+
+fn hoisted(checks: &[RangeCheck]) -> Vec<(String, usize)> {
+    checks
+        .iter()
+        .filter_map(|c| match c {
+            RangeCheck::Binding { name, uses, .. } => Some((name.clone(), uses.len())),
+            RangeCheck::Use { .. } => None,
+        })
+        .collect()
+}
+
+fn at_uses(checks: &[RangeCheck]) -> usize {
+    checks
+        .iter()
+        .filter(|c| matches!(c, RangeCheck::Use { .. }))
+        .count()
+}
+
+#[test]
+fn test_field_comparison_rejected_without_auto_range_check() {
+    let errors = check_err(
+        "program test\nfn main() {\n    let a = pub_read()\n    assert(a < pub_read())\n}",
+    );
+    assert!(
+        errors.iter().any(|d| d.message.contains("requires U32")),
+        "{:?}",
+        errors
+    );
+}
+
+#[test]
+fn test_single_uses_are_checked_in_place() {
+    let checks = checks(
+        "program test\n#[auto_range_check]\nfn main() {\n    let a = pub_read()\n    assert(a < pub_read())\n}",
+    );
+    assert_eq!(at_uses(&checks), 2, "{:?}", checks);
+    assert!(hoisted(&checks).is_empty());
+}
+
+#[test]
+fn test_repeated_uses_are_checked_once_at_the_binding() {
+    let result = check(
+        "program test\n#[auto_range_check]\nfn main() {\n    let x = pub_read()\n    assert(x < 100)\n    let y: U32 = x\n    pub_write(as_field(x & y))\n}",
+    )
+    .expect("should type-check");
+    assert_eq!(
+        hoisted(&result.range_checks),
+        vec![("x".to_string(), 3)],
+        "{:?}",
+        result.range_checks
+    );
+    assert_eq!(at_uses(&result.range_checks), 0);
+    assert!(result.warnings.iter().any(|d| d
+        .message
+        .contains("hint[H0006]: range check inserted: as_u32(x)")));
+}
+
+#[test]
+fn test_use_in_a_constant_loop_is_hoisted() {
+    let checks = checks(
+        "program test\n#[auto_range_check]\nfn scan(limit: Field) {\n    for i in 0..8 {\n        if i < limit {\n            pub_write(1)\n        }\n    }\n}\nfn main() {\n    scan(pub_read())\n}",
+    );
+    assert_eq!(hoisted(&checks), vec![("limit".to_string(), 1)]);
+}
+
+#[test]
+fn test_uses_that_may_not_run_stay_in_place() {
+    let checks = checks(
+        "program test\n#[auto_range_check]\nfn main() {\n    let x = pub_read()\n    if pub_read() == 0 {\n        assert(x < 5)\n        assert(x < 6)\n    }\n    for i in 0..pub_read() bounded 4 {\n        assert(x < 7)\n    }\n}",
+    );
+    assert!(hoisted(&checks).is_empty(), "{:?}", checks);
+    assert_eq!(at_uses(&checks), 3);
+}
+
+#[test]
+fn test_mutable_variables_are_checked_at_each_use() {
+    let checks = checks(
+        "program test\n#[auto_range_check]\nfn main() {\n    let mut x = pub_read()\n    x = x + 1\n    assert(x < 3)\n    assert(x < 4)\n}",
+    );
+    assert!(hoisted(&checks).is_empty());
+    assert_eq!(at_uses(&checks), 2);
+}
+
+#[test]
+fn test_values_known_in_range_need_no_check() {
+    let checks = checks(
+        "program test\nconst LIMIT: Field = 64\n#[auto_range_check]\nfn main() {\n    let x = pub_read()\n    let small: U32 = as_u32(x)\n    assert(small < LIMIT)\n    assert(x < 10)\n}",
+    );
+    assert!(checks.is_empty(), "{:?}", checks);
+}
+
+#[test]
+fn test_literal_above_u32_range_still_rejected() {
+    let errors = check_err(
+        "program test\n#[auto_range_check]\nfn main() {\n    assert(as_u32(pub_read()) < 4294967296)\n}",
+    );
+    assert!(!errors.is_empty());
+}
+
+#[test]
+fn test_cfg_flag_enables_every_function() {
+    let result = check_with_flags(
+        "program test\nfn lt(a: Field, b: Field) -> Bool {\n    a < b\n}\nfn main() {\n    assert(lt(pub_read(), pub_read()))\n}",
+        &["debug", "auto_range_check"],
+    )
+    .expect("should type-check");
+    assert_eq!(at_uses(&result.range_checks), 2);
+}
+
+#[test]
+fn test_inserted_checks_leave_nothing_to_check() {
+    let source = "program test\n#[auto_range_check]\nfn main() {\n    let x = pub_read()\n    let y = pub_read()\n    assert(x < y)\n    assert(x < 9)\n    let (q, r) = x /% 7\n    pub_write(as_field(q ^ r))\n}";
+    let (tokens, _, _) = Lexer::new(source, 0).tokenize();
+    let mut file = Parser::new(tokens).parse_file().unwrap();
+    let exports = TypeChecker::new().check_file(&file).expect("auto mode");
+    assert_eq!(hoisted(&exports.range_checks), vec![("x".to_string(), 3)]);
+    insert_range_checks(&mut file, &exports.range_checks);
+    let rewritten = TypeChecker::new().check_file(&file);
+    assert!(rewritten.is_ok(), "{:?}", rewritten.err());
+    assert!(rewritten.unwrap().range_checks.is_empty());
+}
+
+#[test]
+fn test_overflow_lint_flags_field_sums_used_as_u32() {
+    let lint = |source: &str| -> usize {
+        check(source)
+            .expect("should type-check")
+            .warnings
+            .iter()
+            .filter(|w| w.message == "Field sum used as U32 may overflow 32 bits")
+            .count()
+    };
+    assert_eq!(
+        lint("program test\n#[auto_range_check]\nfn main() {\n    let a = pub_read()\n    let b = pub_read()\n    let s: U32 = a + b\n    let t: U32 = as_u32(a + 1)\n    pub_write(as_field(s & t))\n}"),
+        2
+    );
+    // Explicit additions, and functions without range checks, are quiet.
+    assert_eq!(
+        lint("program test\n#[auto_range_check]\nfn main() {\n    let a = pub_read()\n    let b = pub_read()\n    let s: U32 = u32_assert_add(a, b)\n    pub_write(as_field(u32_wrapping_add(s, 1)))\n}"),
+        0
+    );
+    assert_eq!(
+        lint("program test\nfn main() {\n    let t: U32 = as_u32(pub_read() + 1)\n    pub_write(as_field(t))\n}"),
+        0
+    );
+}
@@ -1,3 +1,7 @@
+use std::collections::BTreeMap;
+
+use crate::ast::StructKind;
+
 /// Semantic types used by the type checker (distinct from AST syntactic types).
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Ty {
@@ -11,6 +15,7 @@ pub enum Ty {
     Array(Box<Ty>, u64),
     Tuple(Vec<Ty>),
     Struct(StructTy),
+    Enum(EnumTy),
     Unit,
 }
 
@@ -19,6 +24,9 @@ pub enum Ty {
 pub struct StructTy {
     pub name: String,
     pub fields: Vec<(String, Ty, bool)>, // (name, type, is_pub)
+    /// Values of the fields declared with `= value`.
+    pub defaults: BTreeMap<String, u64>,
+    pub kind: StructKind,
 }
 
 impl StructTy {
@@ -44,6 +52,48 @@ impl StructTy {
     }
 }
 
+/// A resolved enum type: a tag followed by the payload of one variant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnumTy {
+    pub name: String,
+    /// Variants in declaration order; a variant's index is its tag.
+    pub variants: Vec<(String, Vec<Ty>)>,
+}
+
+impl EnumTy {
+    /// One element for the tag plus room for the widest payload.
+    pub fn width(&self) -> u32 {
+        let payload = self
+            .variants
+            .iter()
+            .map(|(_, fields)| fields.iter().map(|t| t.width()).sum::<u32>())
+            .max()
+            .unwrap_or(0);
+        1 + payload
+    }
+
+    /// The built-in result of a checked operation on `payload`:
+    /// `None` (tag 0) when it failed, `Some(value)` (tag 1) when it did
+    /// not. `MaybeU32` for a U32 payload, `MaybeField` for a Field.
+    pub fn maybe(payload: Ty) -> EnumTy {
+        EnumTy {
+            name: format!("Maybe{}", payload.display()),
+            variants: vec![
+                ("None".to_string(), Vec::new()),
+                ("Some".to_string(), vec![payload]),
+            ],
+        }
+    }
+
+    /// Tag and payload types of the variant called `name`.
+    pub fn variant(&self, name: &str) -> Option<(u64, &[Ty])> {
+        self.variants
+            .iter()
+            .position(|(v, _)| v == name)
+            .map(|i| (i as u64, self.variants[i].1.as_slice()))
+    }
+}
+
 impl Ty {
     /// Width in field elements (compile-time known for all types).
     pub fn width(&self) -> u32 {
@@ -57,6 +107,7 @@ impl Ty {
             }
             Ty::Tuple(elems) => elems.iter().map(|t| t.width()).sum(),
             Ty::Struct(s) => s.width(),
+            Ty::Enum(e) => e.width(),
             Ty::Unit => 0,
         }
     }
@@ -74,6 +125,7 @@ impl Ty {
                 format!("({})", parts.join(", "))
             }
             Ty::Struct(s) => s.name.clone(),
+            Ty::Enum(e) => e.name.clone(),
             Ty::Unit => "()".to_string(),
         }
     }
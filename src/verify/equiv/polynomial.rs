@@ -70,9 +70,10 @@ pub(crate) fn normalize_polynomial(val: &SymValue) -> Option<Vec<PolyTerm>> {
         SymValue::Inv(_)
         | SymValue::Eq(_, _)
         | SymValue::Lt(_, _)
+        | SymValue::DivMod(_, _, _)
+        | SymValue::Pow(_, _)
+        | SymValue::Log2(_)
         | SymValue::Hash(_, _)
-        | SymValue::Divine(_)
-        | SymValue::PubInput(_)
         | SymValue::Ite(_, _, _)
         | SymValue::FieldAccess(_, _) => None,
     }
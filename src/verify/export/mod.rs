@@ -0,0 +1,530 @@
+//! Constraint-system export for external analysis tools.
+//!
+//! Serializes a `ConstraintSystem` as JSON: every variable with its role,
+//! every constraint and assumption as an expression tree with the
+//! assertion it came from, and the public outputs. Optionally adds an
+//! R1CS view of the constraints that are rank-1 over the Goldilocks
+//! field: triples `(A, B, C)` of linear combinations with
+//! `<A, w> * <B, w> = <C, w>`, where `w[0] = 1` and `w[i]` is variable
+//! `i`. Range checks, nonzero obligations, comparisons and hashes need
+//! witnesses the system does not have, and are listed as unrepresentable.
+//!
+//! Uses manual JSON formatting, like `report`.
+
+use std::collections::BTreeMap;
+
+use crate::field::{Goldilocks, PrimeField};
+use crate::report::{format_json_constraint, json_escape, json_str, json_uint};
+use crate::solve::format_assumed;
+use crate::sym::{Constraint, ConstraintSystem, SymValue, SymVar, GOLDILOCKS_P};
+
+// ─── Variables ─────────────────────────────────────────────────────
+
+/// How a variable enters the constraint system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariableRole {
+    /// Read with `pub_read`: part of the public statement.
+    PublicInput,
+    /// Read with `divine`: chosen by the prover.
+    DivineInput,
+    /// Result of a call or hash the verifier does not model.
+    Opaque,
+    /// Any other value.
+    Intermediate,
+}
+
+impl VariableRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VariableRole::PublicInput => "public_input",
+            VariableRole::DivineInput => "divine_input",
+            VariableRole::Opaque => "opaque",
+            VariableRole::Intermediate => "intermediate",
+        }
+    }
+}
+
+/// A variable of the export. Indices start at 1: index 0 of the R1CS
+/// witness is the constant one.
+#[derive(Clone, Debug)]
+pub struct ExportVariable {
+    pub index: usize,
+    pub var: SymVar,
+    pub role: VariableRole,
+}
+
+/// The variables of `system`: its inputs, then every other variable its
+/// constraints, assumptions and outputs mention, in order of appearance.
+pub fn collect_variables(system: &ConstraintSystem) -> Vec<ExportVariable> {
+    let mut vars: Vec<SymVar> = Vec::new();
+    let push = |var: &SymVar, vars: &mut Vec<SymVar>| {
+        if !vars.contains(var) {
+            vars.push(var.clone());
+        }
+    };
+    for var in system.pub_inputs.iter().chain(&system.divine_inputs) {
+        push(var, &mut vars);
+    }
+    let mut mentioned = Vec::new();
+    for c in &system.constraints {
+        constraint_vars(c, &mut mentioned);
+    }
+    for a in &system.assumptions {
+        constraint_vars(&a.constraint, &mut mentioned);
+    }
+    for v in &system.pub_outputs {
+        value_vars(v, &mut mentioned);
+    }
+    for var in &mentioned {
+        push(var, &mut vars);
+    }
+    vars.into_iter()
+        .enumerate()
+        .map(|(i, var)| {
+            let role = if system.pub_inputs.contains(&var) {
+                VariableRole::PublicInput
+            } else if system.divine_inputs.contains(&var) {
+                VariableRole::DivineInput
+            } else if var.name.starts_with("__") {
+                VariableRole::Opaque
+            } else {
+                VariableRole::Intermediate
+            };
+            ExportVariable {
+                index: i + 1,
+                var,
+                role,
+            }
+        })
+        .collect()
+}
+
+fn constraint_vars(c: &Constraint, out: &mut Vec<SymVar>) {
+    match c {
+        Constraint::Equal(a, b) => {
+            value_vars(a, out);
+            value_vars(b, out);
+        }
+        Constraint::AssertTrue(v) | Constraint::RangeU32(v) | Constraint::NonZero(v) => {
+            value_vars(v, out);
+        }
+        Constraint::Conditional(cond, inner) => {
+            value_vars(cond, out);
+            constraint_vars(inner, out);
+        }
+        Constraint::DigestEqual(a, b) => {
+            for v in a.iter().chain(b) {
+                value_vars(v, out);
+            }
+        }
+    }
+}
+
+fn value_vars(v: &SymValue, out: &mut Vec<SymVar>) {
+    match v {
+        SymValue::Var(var) => out.push(var.clone()),
+        SymValue::Add(a, b)
+        | SymValue::Mul(a, b)
+        | SymValue::Sub(a, b)
+        | SymValue::Eq(a, b)
+        | SymValue::Lt(a, b)
+        | SymValue::DivMod(a, b, _)
+        | SymValue::Pow(a, b) => {
+            value_vars(a, out);
+            value_vars(b, out);
+        }
+        SymValue::Neg(a) | SymValue::Inv(a) | SymValue::Log2(a) => value_vars(a, out),
+        SymValue::FieldAccess(inner, _) => value_vars(inner, out),
+        SymValue::Ite(c, t, e) => {
+            value_vars(c, out);
+            value_vars(t, out);
+            value_vars(e, out);
+        }
+        SymValue::Hash(inputs, _) => {
+            for v in inputs {
+                value_vars(v, out);
+            }
+        }
+        SymValue::Const(_) => {}
+    }
+}
+
+// ─── R1CS ──────────────────────────────────────────────────────────
+
+/// A linear combination of witness entries: (index, coefficient) terms.
+pub type Linear = Vec<(usize, u64)>;
+
+/// One rank-1 constraint `<a, w> * <b, w> = <c, w>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1csRow {
+    /// The constraint of the system the row encodes; a digest equality
+    /// takes one row per element.
+    pub constraint: usize,
+    pub a: Linear,
+    pub b: Linear,
+    pub c: Linear,
+}
+
+/// The rank-1 constraints of a system.
+#[derive(Clone, Debug, Default)]
+pub struct R1cs {
+    pub rows: Vec<R1csRow>,
+    /// Constraints with no rank-1 form over the system's variables.
+    pub unrepresentable: Vec<usize>,
+}
+
+/// A polynomial of degree at most 2 over the witness: coefficients by
+/// monomial `w[i] * w[j]` with `i <= j`. As `w[0] = 1`, `(0, 0)` is the
+/// constant term and `(0, i)` the linear term of variable `i`.
+type Poly = BTreeMap<(usize, usize), Goldilocks>;
+
+/// Convert the constraints of `system` that are rank-1.
+pub fn to_r1cs(system: &ConstraintSystem, variables: &[ExportVariable]) -> R1cs {
+    let index: BTreeMap<String, usize> = variables
+        .iter()
+        .map(|v| (v.var.to_string(), v.index))
+        .collect();
+    let mut r1cs = R1cs::default();
+    for (i, c) in system.constraints.iter().enumerate() {
+        match constraint_rows(c, &index) {
+            Some(rows) => r1cs.rows.extend(rows.into_iter().map(|(a, b, c)| R1csRow {
+                constraint: i,
+                a,
+                b,
+                c,
+            })),
+            None => r1cs.unrepresentable.push(i),
+        }
+    }
+    r1cs
+}
+
+fn constraint_rows(
+    c: &Constraint,
+    index: &BTreeMap<String, usize>,
+) -> Option<Vec<(Linear, Linear, Linear)>> {
+    let zero = |a: &SymValue, b: &SymValue| Some(sub(poly(a, index)?, &poly(b, index)?));
+    match c {
+        Constraint::Equal(a, b) => Some(vec![rank_one(&zero(a, b)?)?]),
+        Constraint::AssertTrue(SymValue::Eq(a, b)) => Some(vec![rank_one(&zero(a, b)?)?]),
+        Constraint::AssertTrue(v) => Some(vec![rank_one(&zero(v, &SymValue::Const(1))?)?]),
+        Constraint::DigestEqual(a, b) if a.len() == b.len() => a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| rank_one(&zero(x, y)?))
+            .collect(),
+        // `cond * p = 0` for linear `cond` and `p`.
+        Constraint::Conditional(cond, inner) => {
+            let cond = poly(cond, index)?;
+            let [(a, b, c)] = <[_; 1]>::try_from(constraint_rows(inner, index)?).ok()?;
+            let linear = |p: &Poly| p.keys().all(|&(i, _)| i == 0);
+            if !linear(&cond) || b != [(0, 1)] || !c.is_empty() {
+                return None;
+            }
+            Some(vec![(terms(&cond), a, Vec::new())])
+        }
+        Constraint::DigestEqual(..) | Constraint::RangeU32(_) | Constraint::NonZero(_) => None,
+    }
+}
+
+/// Split `p = 0` into `a * b = c`: a linear `p` as `p * 1 = 0`, one
+/// quadratic term `k * x * y` as `(k * x) * y = -(rest)`.
+fn rank_one(p: &Poly) -> Option<(Linear, Linear, Linear)> {
+    let quadratic: Vec<_> = p.iter().filter(|(&(i, _), _)| i != 0).collect();
+    match quadratic[..] {
+        [] => Some((terms(p), vec![(0, 1)], Vec::new())),
+        [(&(x, y), k)] => {
+            let rest: Poly = p
+                .iter()
+                .filter(|(&(i, _), _)| i == 0)
+                .map(|(&m, c)| (m, c.neg()))
+                .collect();
+            Some((vec![(x, k.to_u64())], vec![(y, 1)], terms(&rest)))
+        }
+        _ => None,
+    }
+}
+
+/// The linear terms of `p`, by witness index.
+fn terms(p: &Poly) -> Linear {
+    p.iter().map(|(&(_, j), c)| (j, c.to_u64())).collect()
+}
+
+fn poly(v: &SymValue, index: &BTreeMap<String, usize>) -> Option<Poly> {
+    let mut p = Poly::new();
+    match v {
+        SymValue::Const(c) => {
+            p.insert((0, 0), Goldilocks::from_u64(*c));
+        }
+        SymValue::Var(var) => {
+            p.insert((0, *index.get(&var.to_string())?), Goldilocks::from_u64(1));
+        }
+        SymValue::Add(a, b) => return Some(add(poly(a, index)?, &poly(b, index)?)),
+        SymValue::Sub(a, b) => return Some(sub(poly(a, index)?, &poly(b, index)?)),
+        SymValue::Neg(a) => return Some(sub(Poly::new(), &poly(a, index)?)),
+        SymValue::Mul(a, b) => return mul(&poly(a, index)?, &poly(b, index)?),
+        SymValue::Pow(a, e) => {
+            let a = poly(a, index)?;
+            return match e.as_const()? {
+                0 => poly(&SymValue::Const(1), index),
+                1 => Some(a),
+                2 => mul(&a, &a),
+                _ => None,
+            };
+        }
+        _ => return None,
+    }
+    Some(p)
+}
+
+fn add(mut p: Poly, q: &Poly) -> Poly {
+    for (&m, &c) in q {
+        let sum = p.get(&m).map_or(c, |&d| d.add(c));
+        if sum.to_u64() == 0 {
+            p.remove(&m);
+        } else {
+            p.insert(m, sum);
+        }
+    }
+    p
+}
+
+fn sub(p: Poly, q: &Poly) -> Poly {
+    add(p, &q.iter().map(|(&m, c)| (m, c.neg())).collect())
+}
+
+/// The product of `p` and `q`, if its degree is at most 2.
+fn mul(p: &Poly, q: &Poly) -> Option<Poly> {
+    let mut product = Poly::new();
+    for (&(i, j), &c) in p {
+        for (&(k, l), &d) in q {
+            let mut vars: Vec<usize> = [i, j, k, l].into_iter().filter(|&x| x != 0).collect();
+            if vars.len() > 2 {
+                return None;
+            }
+            vars.sort_unstable();
+            let monomial = match vars[..] {
+                [] => (0, 0),
+                [x] => (0, x),
+                [x, y] => (x, y),
+                _ => unreachable!(),
+            };
+            product = add(product, &Poly::from([(monomial, c.mul(d))]));
+        }
+    }
+    Some(product)
+}
+
+// ─── JSON ──────────────────────────────────────────────────────────
+
+/// Serialize `system` for external tools, with its R1CS view if `r1cs`.
+pub fn export_json(file_name: &str, system: &ConstraintSystem, r1cs: bool) -> String {
+    let variables = collect_variables(system);
+    let mut out = String::new();
+    out.push_str("{\n");
+    out.push_str("  \"version\": 1,\n");
+    out.push_str(&json_str(2, "file", file_name));
+    out.push_str(",\n");
+    out.push_str(&format!(
+        "  \"field\": {{\"name\": \"goldilocks\", \"modulus\": \"{}\"}},\n",
+        GOLDILOCKS_P
+    ));
+
+    out.push_str("  \"variables\": [\n");
+    let items: Vec<String> = variables
+        .iter()
+        .map(|v| {
+            format!(
+                "    {{\"index\": {}, \"name\": \"{}\", \"base\": \"{}\", \"version\": {}, \"role\": \"{}\"}}",
+                v.index,
+                json_escape(&v.var.to_string()),
+                json_escape(&v.var.name),
+                v.var.version,
+                v.role.as_str()
+            )
+        })
+        .collect();
+    push_items(&mut out, &items);
+    out.push_str("  ],\n");
+
+    out.push_str("  \"constraints\": [\n");
+    let items: Vec<String> = system
+        .constraints
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let jc = format_json_constraint(c, i);
+            let origin = match system.origins.get(i).copied().flatten() {
+                Some(span) => format!("{{\"start\": {}, \"end\": {}}}", span.start, span.end),
+                None => "null".to_string(),
+            };
+            let mut item = String::from("    {\n");
+            item.push_str(&json_uint(6, "index", i));
+            item.push_str(",\n");
+            item.push_str(&json_str(6, "kind", &jc.kind));
+            item.push_str(",\n");
+            item.push_str(&json_str(6, "expression", &jc.expression));
+            item.push_str(",\n");
+            item.push_str(&format!("      \"origin\": {},\n", origin));
+            item.push_str(&format!("      \"tree\": {}\n", constraint_json(c)));
+            item.push_str("    }");
+            item
+        })
+        .collect();
+    push_items(&mut out, &items);
+    out.push_str("  ],\n");
+
+    out.push_str("  \"assumptions\": [\n");
+    let items: Vec<String> = system
+        .assumptions
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            format!(
+                "    {{\"index\": {}, \"function\": \"{}\", \"expression\": \"{}\", \"tree\": {}}}",
+                i,
+                json_escape(&a.function),
+                json_escape(&format_assumed(&a.constraint)),
+                constraint_json(&a.constraint)
+            )
+        })
+        .collect();
+    push_items(&mut out, &items);
+    out.push_str("  ],\n");
+
+    out.push_str("  \"public_outputs\": [\n");
+    let items: Vec<String> = system
+        .pub_outputs
+        .iter()
+        .map(|v| format!("    {}", value_json(v)))
+        .collect();
+    push_items(&mut out, &items);
+    out.push_str("  ]");
+
+    if r1cs {
+        let r1cs = to_r1cs(system, &variables);
+        out.push_str(",\n  \"r1cs\": {\n");
+        out.push_str("    \"rows\": [\n");
+        let items: Vec<String> = r1cs
+            .rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "      {{\"constraint\": {}, \"a\": {}, \"b\": {}, \"c\": {}}}",
+                    row.constraint,
+                    linear_json(&row.a),
+                    linear_json(&row.b),
+                    linear_json(&row.c)
+                )
+            })
+            .collect();
+        push_items(&mut out, &items);
+        out.push_str("    ],\n");
+        let unrepresentable: Vec<String> =
+            r1cs.unrepresentable.iter().map(|i| i.to_string()).collect();
+        out.push_str(&format!(
+            "    \"unrepresentable\": [{}]\n",
+            unrepresentable.join(", ")
+        ));
+        out.push_str("  }");
+    }
+    out.push_str("\n}\n");
+    out
+}
+
+/// Append `items` one per line, comma-separated.
+fn push_items(out: &mut String, items: &[String]) {
+    for (i, item) in items.iter().enumerate() {
+        out.push_str(item);
+        if i + 1 < items.len() {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+}
+
+/// A linear combination as `[[index, "coefficient"], ...]`. Field
+/// elements are strings: JSON numbers lose precision above 2^53.
+fn linear_json(terms: &Linear) -> String {
+    let terms: Vec<String> = terms
+        .iter()
+        .map(|(i, c)| format!("[{}, \"{}\"]", i, c))
+        .collect();
+    format!("[{}]", terms.join(", "))
+}
+
+fn constraint_json(c: &Constraint) -> String {
+    match c {
+        Constraint::Equal(a, b) => format!(
+            "{{\"op\": \"equal\", \"args\": [{}, {}]}}",
+            value_json(a),
+            value_json(b)
+        ),
+        Constraint::AssertTrue(v) => {
+            format!("{{\"op\": \"assert_true\", \"args\": [{}]}}", value_json(v))
+        }
+        Constraint::RangeU32(v) => {
+            format!("{{\"op\": \"range_u32\", \"args\": [{}]}}", value_json(v))
+        }
+        Constraint::NonZero(v) => {
+            format!("{{\"op\": \"non_zero\", \"args\": [{}]}}", value_json(v))
+        }
+        Constraint::Conditional(cond, inner) => format!(
+            "{{\"op\": \"implies\", \"args\": [{}, {}]}}",
+            value_json(cond),
+            constraint_json(inner)
+        ),
+        Constraint::DigestEqual(a, b) => {
+            let list = |vs: &[SymValue]| {
+                let vs: Vec<String> = vs.iter().map(value_json).collect();
+                format!("[{}]", vs.join(", "))
+            };
+            format!(
+                "{{\"op\": \"digest_equal\", \"args\": [{}, {}]}}",
+                list(a),
+                list(b)
+            )
+        }
+    }
+}
+
+/// An expression tree: `{"const": "5"}`, `{"var": "x_1"}`, or
+/// `{"op": "add", "args": [...]}`.
+fn value_json(v: &SymValue) -> String {
+    let op = |name: &str, args: &[&SymValue]| {
+        let args: Vec<String> = args.iter().map(|a| value_json(a)).collect();
+        format!("{{\"op\": \"{}\", \"args\": [{}]}}", name, args.join(", "))
+    };
+    match v {
+        SymValue::Const(c) => format!("{{\"const\": \"{}\"}}", c),
+        SymValue::Var(var) => format!("{{\"var\": \"{}\"}}", json_escape(&var.to_string())),
+        SymValue::Add(a, b) => op("add", &[a, b]),
+        SymValue::Mul(a, b) => op("mul", &[a, b]),
+        SymValue::Sub(a, b) => op("sub", &[a, b]),
+        SymValue::Neg(a) => op("neg", &[a]),
+        SymValue::Inv(a) => op("inv", &[a]),
+        SymValue::Eq(a, b) => op("eq", &[a, b]),
+        SymValue::Lt(a, b) => op("lt", &[a, b]),
+        SymValue::DivMod(a, b, 0) => op("div", &[a, b]),
+        SymValue::DivMod(a, b, _) => op("mod", &[a, b]),
+        SymValue::Pow(a, b) => op("pow", &[a, b]),
+        SymValue::Log2(a) => op("log2", &[a]),
+        SymValue::Ite(c, t, e) => op("ite", &[c, t, e]),
+        SymValue::Hash(inputs, index) => {
+            let args: Vec<String> = inputs.iter().map(value_json).collect();
+            format!(
+                "{{\"op\": \"hash\", \"index\": {}, \"args\": [{}]}}",
+                index,
+                args.join(", ")
+            )
+        }
+        SymValue::FieldAccess(inner, field) => format!(
+            "{{\"op\": \"field\", \"name\": \"{}\", \"args\": [{}]}}",
+            json_escape(field),
+            value_json(inner)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,86 @@
+use super::*;
+use crate::sym;
+
+fn analyze(source: &str) -> ConstraintSystem {
+    let file = crate::parse_source_silent(source, "test.tri").unwrap();
+    sym::analyze(&file)
+}
+
+#[test]
+fn test_variables_are_numbered_by_role() {
+    let system = analyze(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    let w: Field = divine()\n    let y: Field = x * w\n    assert_eq(y, 6)\n}\n",
+    );
+    let vars = collect_variables(&system);
+    let found: Vec<(usize, String, VariableRole)> = vars
+        .iter()
+        .map(|v| (v.index, v.var.to_string(), v.role))
+        .collect();
+    assert_eq!(found[0].0, 1);
+    assert_eq!(found[0].2, VariableRole::PublicInput);
+    assert_eq!(found[1].2, VariableRole::DivineInput);
+    assert!(found[2..]
+        .iter()
+        .all(|(_, _, role)| *role != VariableRole::PublicInput));
+}
+
+#[test]
+fn test_export_json_lists_trees_and_origins() {
+    let system = analyze(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    let w: Field = divine()\n    assert_eq(x * w, 6)\n}\n",
+    );
+    let json = export_json("test.tri", &system, false);
+    assert!(json.contains("\"file\": \"test.tri\""));
+    assert!(json.contains("\"modulus\": \"18446744069414584321\""));
+    assert!(json.contains("\"role\": \"public_input\""));
+    assert!(json.contains("\"role\": \"divine_input\""));
+    assert!(json.contains("\"kind\": \"equal\""));
+    assert!(json.contains("{\"op\": \"mul\", \"args\": [{\"var\": "));
+    assert!(json.contains("{\"const\": \"6\"}"));
+    assert!(json.contains("\"origin\": {\"start\": "));
+    assert!(!json.contains("\"r1cs\""));
+}
+
+#[test]
+fn test_r1cs_rows_for_linear_and_quadratic_constraints() {
+    let system = analyze(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    let w: Field = divine()\n    assert_eq(x + 1, 5)\n    assert_eq(x * w, 6)\n}\n",
+    );
+    let vars = collect_variables(&system);
+    let r1cs = to_r1cs(&system, &vars);
+    assert!(r1cs.unrepresentable.is_empty());
+    let minus = |c: u64| GOLDILOCKS_P - c;
+    assert_eq!(
+        r1cs.rows,
+        vec![
+            // x + 1 - 5 = 0
+            R1csRow {
+                constraint: 0,
+                a: vec![(0, minus(4)), (1, 1)],
+                b: vec![(0, 1)],
+                c: vec![],
+            },
+            // x * w = 6
+            R1csRow {
+                constraint: 1,
+                a: vec![(1, 1)],
+                b: vec![(2, 1)],
+                c: vec![(0, 6)],
+            },
+        ]
+    );
+    let json = export_json("test.tri", &system, true);
+    assert!(json.contains("\"a\": [[1, \"1\"]], \"b\": [[2, \"1\"]], \"c\": [[0, \"6\"]]"));
+    assert!(json.contains("\"unrepresentable\": []"));
+}
+
+#[test]
+fn test_r1cs_reports_unrepresentable_constraints() {
+    let system = analyze(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    let w: Field = divine()\n    assert_eq(x * x * w, 6)\n    let y: U32 = as_u32(x)\n}\n",
+    );
+    let vars = collect_variables(&system);
+    let r1cs = to_r1cs(&system, &vars);
+    assert!(r1cs.rows.is_empty());
+    assert_eq!(r1cs.unrepresentable, vec![0, 1]);
+}
@@ -1,4 +1,5 @@
 pub mod equiv;
+pub mod export;
 pub mod report;
 pub mod smt;
 pub mod solve;
@@ -4,10 +4,15 @@
 //! JSON format designed for automated tooling. Uses manual JSON formatting
 //! (no serde) following the same pattern as `cost.rs`.
 
-use crate::solve::{format_constraint, Counterexample, Verdict, VerificationReport};
+use crate::ast::File;
+use crate::cost::{create_cost_model, TableCost};
+use crate::solve::{
+    format_assumed, format_constraint, Counterexample, Verdict, VerificationReport,
+};
+use crate::span::Span;
 #[cfg(test)]
 use crate::sym::SymValue;
-use crate::sym::{Constraint, ConstraintSystem};
+use crate::sym::{Assumption, Constraint, ConstraintSystem};
 
 // ─── Data Structures ───────────────────────────────────────────────
 
@@ -18,7 +23,9 @@ pub struct JsonReport {
     pub file: String,
     pub verdict: String,
     pub summary: JsonSummary,
+    pub verification: JsonVerification,
     pub constraints: Vec<JsonConstraint>,
+    pub assumptions: Vec<JsonAssumption>,
     pub counterexamples: Vec<JsonCounterexample>,
     pub redundant_assertions: Vec<usize>,
     pub suggestions: Vec<JsonSuggestion>,
@@ -34,6 +41,19 @@ pub struct JsonSummary {
     pub static_violations: usize,
     pub random_violations: usize,
     pub bmc_violations: usize,
+    pub assumptions: usize,
+    pub truncated_loops: usize,
+}
+
+/// Budgets and seed the checks ran with, and how far they got.
+pub struct JsonVerification {
+    pub seed: u64,
+    pub samples: usize,
+    pub bmc_depth: usize,
+    pub time_budget_ms: Option<u128>,
+    pub random_rounds: usize,
+    pub bmc_rounds: usize,
+    pub budget_exhausted: bool,
 }
 
 pub struct JsonConstraint {
@@ -44,6 +64,17 @@ pub struct JsonConstraint {
     pub is_violated: bool,
 }
 
+/// A fact the verifier took on trust. Auditors review these: every
+/// result in the report holds only if they do.
+pub struct JsonAssumption {
+    pub index: usize,
+    pub expression: String,
+    pub function: String,
+    /// `"statement"` for `assume(cond)`, `"attribute"` for `#[assume]`.
+    pub kind: String,
+    pub reason: Option<String>,
+}
+
 pub struct JsonCounterexample {
     pub constraint_index: usize,
     pub constraint_desc: String,
@@ -55,12 +86,29 @@ pub struct JsonSuggestion {
     pub kind: String,
     pub message: String,
     pub constraint_index: Option<usize>,
+    /// Edit that carries the suggestion out, when there is a safe one.
+    pub fix: Option<JsonFix>,
+}
+
+/// Delete `span` of the program file: a redundant assertion call.
+pub struct JsonFix {
+    pub span: Span,
+    /// 1-based line of the call.
+    pub line: usize,
+    /// Rows the deletion saves per run of the program, by cost table.
+    pub saved: TableCost,
+}
+
+/// The program file a report covers, for suggestions that edit it.
+pub struct ReportSource<'a> {
+    pub file: &'a File,
+    pub source: &'a str,
 }
 
 // ─── JSON Helpers ──────────────────────────────────────────────────
 
 /// Escape a string for JSON output.
-fn json_escape(s: &str) -> String {
+pub(crate) fn json_escape(s: &str) -> String {
     let mut out = String::with_capacity(s.len());
     for ch in s.chars() {
         match ch {
@@ -79,13 +127,13 @@ fn json_escape(s: &str) -> String {
 }
 
 /// Write an indented JSON string value: `"key": "value"`.
-fn json_str(indent: usize, key: &str, value: &str) -> String {
+pub(crate) fn json_str(indent: usize, key: &str, value: &str) -> String {
     let pad = " ".repeat(indent);
     format!("{}\"{}\": \"{}\"", pad, key, json_escape(value))
 }
 
 /// Write an indented JSON integer value: `"key": value`.
-fn json_uint(indent: usize, key: &str, value: usize) -> String {
+pub(crate) fn json_uint(indent: usize, key: &str, value: usize) -> String {
     let pad = " ".repeat(indent);
     format!("{}\"{}\": {}", pad, key, value)
 }
@@ -111,6 +159,7 @@ pub fn format_json_constraint(c: &Constraint, index: usize) -> JsonConstraint {
         Constraint::Conditional(..) => "conditional",
         Constraint::RangeU32(..) => "range_u32",
         Constraint::DigestEqual(..) => "digest_equal",
+        Constraint::NonZero(..) => "non_zero",
     };
     JsonConstraint {
         index,
@@ -138,22 +187,41 @@ fn convert_counterexample(ce: &Counterexample, source: &str) -> JsonCounterexamp
     }
 }
 
+/// Convert an `Assumption` from the constraint system into a `JsonAssumption`.
+fn convert_assumption(a: &Assumption, index: usize) -> JsonAssumption {
+    JsonAssumption {
+        index,
+        expression: format_assumed(&a.constraint),
+        function: a.function.clone(),
+        kind: if a.from_attribute {
+            "attribute"
+        } else {
+            "statement"
+        }
+        .to_string(),
+        reason: a.reason.clone(),
+    }
+}
+
 // --- Suggestion Generation ---
 
 pub(super) mod suggestions;
-pub use suggestions::generate_suggestions;
+pub use suggestions::{generate_suggestions, redundant_assertion_fixes, AssertionFix};
 
 // ─── Report Generation ─────────────────────────────────────────────
 
-/// Create a full JSON verification report.
+/// Create a full JSON verification report. With the `program` file at
+/// hand, suggestions to drop redundant assertions carry the edit.
 pub fn generate_json_report(
     file_name: &str,
     system: &ConstraintSystem,
     report: &VerificationReport,
+    program: Option<&ReportSource>,
 ) -> String {
     let verdict_str = match report.verdict {
         Verdict::Safe => "safe",
         Verdict::StaticViolation | Verdict::RandomViolation | Verdict::BmcViolation => "unsafe",
+        Verdict::Inconclusive => "inconclusive",
     };
 
     let constraints: Vec<JsonConstraint> = system
@@ -171,7 +239,7 @@ pub fn generate_json_report(
         counterexamples.push(convert_counterexample(ce, "bmc"));
     }
 
-    let suggestions = generate_suggestions(system, report);
+    let suggestions = generate_suggestions(system, report, program);
 
     let json_report = JsonReport {
         version: 1,
@@ -187,8 +255,26 @@ pub fn generate_json_report(
             static_violations: report.static_violations.len(),
             random_violations: report.random_result.counterexamples.len(),
             bmc_violations: report.bmc_result.counterexamples.len(),
+            assumptions: system.assumptions.len(),
+            truncated_loops: system.truncated_loops.len(),
+        },
+        verification: JsonVerification {
+            seed: report.config.seed,
+            samples: report.config.samples,
+            bmc_depth: report.config.bmc_depth,
+            time_budget_ms: report.config.time_budget.map(|b| b.as_millis()),
+            random_rounds: report.random_result.rounds,
+            bmc_rounds: report.bmc_result.rounds,
+            budget_exhausted: report.random_result.budget_exhausted
+                || report.bmc_result.budget_exhausted,
         },
         constraints,
+        assumptions: system
+            .assumptions
+            .iter()
+            .enumerate()
+            .map(|(i, a)| convert_assumption(a, i))
+            .collect(),
         counterexamples,
         redundant_assertions: report.redundant_assertions.clone(),
         suggestions,
@@ -244,6 +330,33 @@ fn serialize_report(r: &JsonReport) -> String {
     ));
     out.push_str(",\n");
     out.push_str(&json_uint(4, "bmc_violations", r.summary.bmc_violations));
+    out.push_str(",\n");
+    out.push_str(&json_uint(4, "assumptions", r.summary.assumptions));
+    out.push_str(",\n");
+    out.push_str(&json_uint(4, "truncated_loops", r.summary.truncated_loops));
+    out.push('\n');
+    out.push_str("  },\n");
+
+    // verification
+    let v = &r.verification;
+    out.push_str("  \"verification\": {\n");
+    // A string, so consumers that read numbers as doubles keep every bit.
+    out.push_str(&json_str(4, "seed", &v.seed.to_string()));
+    out.push_str(",\n");
+    out.push_str(&json_uint(4, "samples", v.samples));
+    out.push_str(",\n");
+    out.push_str(&json_uint(4, "bmc_depth", v.bmc_depth));
+    out.push_str(",\n");
+    match v.time_budget_ms {
+        Some(ms) => out.push_str(&format!("    \"time_budget_ms\": {}", ms)),
+        None => out.push_str("    \"time_budget_ms\": null"),
+    }
+    out.push_str(",\n");
+    out.push_str(&json_uint(4, "random_rounds", v.random_rounds));
+    out.push_str(",\n");
+    out.push_str(&json_uint(4, "bmc_rounds", v.bmc_rounds));
+    out.push_str(",\n");
+    out.push_str(&json_bool(4, "budget_exhausted", v.budget_exhausted));
     out.push('\n');
     out.push_str("  },\n");
 
@@ -258,6 +371,17 @@ fn serialize_report(r: &JsonReport) -> String {
     }
     out.push_str("  ],\n");
 
+    // assumptions
+    out.push_str("  \"assumptions\": [\n");
+    for (i, a) in r.assumptions.iter().enumerate() {
+        out.push_str(&serialize_assumption(a));
+        if i + 1 < r.assumptions.len() {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+    out.push_str("  ],\n");
+
     // counterexamples
     out.push_str("  \"counterexamples\": [\n");
     for (i, ce) in r.counterexamples.iter().enumerate() {
@@ -311,6 +435,26 @@ fn serialize_constraint(c: &JsonConstraint) -> String {
     out
 }
 
+fn serialize_assumption(a: &JsonAssumption) -> String {
+    let mut out = String::new();
+    out.push_str("    {\n");
+    out.push_str(&json_uint(6, "index", a.index));
+    out.push_str(",\n");
+    out.push_str(&json_str(6, "expression", &a.expression));
+    out.push_str(",\n");
+    out.push_str(&json_str(6, "function", &a.function));
+    out.push_str(",\n");
+    out.push_str(&json_str(6, "kind", &a.kind));
+    out.push_str(",\n");
+    match &a.reason {
+        Some(reason) => out.push_str(&json_str(6, "reason", reason)),
+        None => out.push_str("      \"reason\": null"),
+    }
+    out.push('\n');
+    out.push_str("    }");
+    out
+}
+
 fn serialize_counterexample(ce: &JsonCounterexample) -> String {
     let mut out = String::new();
     out.push_str("    {\n");
@@ -343,8 +487,28 @@ fn serialize_suggestion(s: &JsonSuggestion) -> String {
     out.push_str(",\n");
     let pad = "      ";
     match s.constraint_index {
-        Some(idx) => out.push_str(&format!("{}\"constraint_index\": {}\n", pad, idx)),
-        None => out.push_str(&format!("{}\"constraint_index\": null\n", pad)),
+        Some(idx) => out.push_str(&format!("{}\"constraint_index\": {},\n", pad, idx)),
+        None => out.push_str(&format!("{}\"constraint_index\": null,\n", pad)),
+    }
+    match &s.fix {
+        Some(fix) => {
+            let names = create_cost_model("triton").table_names();
+            out.push_str("      \"fix\": {\n");
+            out.push_str(&json_str(8, "action", "delete"));
+            out.push_str(",\n");
+            out.push_str(&json_uint(8, "start", fix.span.start as usize));
+            out.push_str(",\n");
+            out.push_str(&json_uint(8, "end", fix.span.end as usize));
+            out.push_str(",\n");
+            out.push_str(&json_uint(8, "line", fix.line));
+            out.push_str(",\n");
+            out.push_str(&format!(
+                "        \"saved\": {}\n",
+                fix.saved.to_json_value(names)
+            ));
+            out.push_str("      }\n");
+        }
+        None => out.push_str("      \"fix\": null\n"),
     }
     out.push_str("    }");
     out
@@ -2,16 +2,142 @@
 //!
 //! Analyzes verification results and constraint systems to produce
 //! actionable fix suggestions (violated constraints, redundant assertions,
-//! unconstrained divine inputs).
+//! unconstrained divine inputs). Redundant assertions the program file
+//! can drop come with the edit and the rows it saves.
 
+use crate::ast::{integer_literal, Block, Expr, File, Item, Stmt};
+use crate::cost::{create_cost_model, may_call, CostAnalyzer, TableCost};
 use crate::solve::{format_constraint, VerificationReport};
-use crate::sym::{Constraint, ConstraintSystem, SymValue};
+use crate::span::{Span, Spanned};
+use crate::sym::{assertion_site, Constraint, ConstraintSystem, SymValue};
 
-use super::JsonSuggestion;
+use super::{JsonFix, JsonSuggestion, ReportSource};
 
+/// A redundant assertion call the program file can drop: every
+/// constraint it adds is trivial or always held when checked, and its
+/// arguments call nothing, so deleting it changes nothing else.
+#[derive(Clone, Debug)]
+pub struct AssertionFix {
+    /// The call, from the builtin's name to its closing parenthesis.
+    pub span: Span,
+    /// The constraints it adds, in the order the verifier saw them.
+    pub constraints: Vec<usize>,
+    /// Rows the deletion saves per run of the program, loops included.
+    pub saved: TableCost,
+}
+
+impl AssertionFix {
+    /// The savings in the cost model's short table names, as
+    /// `3cc, 33 u32 rows`.
+    pub fn savings(&self) -> String {
+        let names = create_cost_model("triton").table_short_names();
+        let parts: Vec<String> = names
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| self.saved.get(i) > 0)
+            .map(|(i, name)| match i {
+                0 => format!("{}{}", self.saved.get(i), name),
+                _ => format!("{} {} rows", self.saved.get(i), name),
+            })
+            .collect();
+        if parts.is_empty() {
+            "no rows".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// The redundant assertions of `file` that can be deleted, in source
+/// order. `system` must come from `file`.
+pub fn redundant_assertion_fixes(
+    file: &File,
+    system: &ConstraintSystem,
+    report: &VerificationReport,
+) -> Vec<AssertionFix> {
+    let mut calls = Vec::new();
+    for item in &file.items {
+        if let Item::Fn(func) = &item.node {
+            if let Some(body) = &func.body {
+                assertion_calls(&body.node, 1, &mut calls);
+            }
+        }
+    }
+    let mut analyzer = CostAnalyzer::default();
+    let mut fixes = Vec::new();
+    for (call, times) in calls {
+        let constraints: Vec<usize> = (0..system.origins.len())
+            .filter(|&i| system.origins[i] == Some(call.span))
+            .collect();
+        let Expr::Call { args, .. } = &call.node else {
+            continue;
+        };
+        let removable = !constraints.is_empty()
+            && constraints.iter().all(|&i| {
+                system.constraints[i].is_trivial() || report.redundant_assertions.contains(&i)
+            })
+            && !args.iter().any(|a| may_call(&a.node));
+        if removable {
+            fixes.push(AssertionFix {
+                span: call.span,
+                constraints,
+                saved: analyzer.cost_expr(&call.node).scale(times),
+            });
+        }
+    }
+    fixes
+}
+
+/// Assertion calls of `block`, which runs `times` times, in source order.
+fn assertion_calls<'f>(block: &'f Block, times: u64, out: &mut Vec<(&'f Spanned<Expr>, u64)>) {
+    for stmt in &block.stmts {
+        match &stmt.node {
+            Stmt::Expr(e) if assertion_site(e).is_some() => out.push((e, times)),
+            Stmt::For {
+                end, bound, body, ..
+            } => {
+                let runs = bound
+                    .as_ref()
+                    .and_then(|b| integer_literal(&b.node))
+                    .or(integer_literal(&end.node))
+                    .unwrap_or(1);
+                assertion_calls(&body.node, times.saturating_mul(runs), out);
+            }
+            Stmt::While { bound, body, .. } => {
+                let runs = integer_literal(&bound.node).unwrap_or(1);
+                assertion_calls(&body.node, times.saturating_mul(runs), out);
+            }
+            Stmt::If {
+                then_block,
+                else_block,
+                ..
+            } => {
+                assertion_calls(&then_block.node, times, out);
+                if let Some(eb) = else_block {
+                    assertion_calls(&eb.node, times, out);
+                }
+            }
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    assertion_calls(&arm.body.node, times, out);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(tail) = &block.tail_expr {
+        if assertion_site(tail).is_some() {
+            out.push((tail, times));
+        }
+    }
+}
+
+/// Suggestions for `report`; with the `program` file at hand, redundant
+/// assertions it can drop carry the fix.
 pub fn generate_suggestions(
     system: &ConstraintSystem,
     report: &VerificationReport,
+    program: Option<&ReportSource>,
 ) -> Vec<JsonSuggestion> {
     let mut suggestions = Vec::new();
 
@@ -24,6 +150,7 @@ pub fn generate_suggestions(
                 ce.constraint_index, ce.constraint_desc
             ),
             constraint_index: Some(ce.constraint_index),
+            fix: None,
         });
     }
     for ce in &report.bmc_result.counterexamples {
@@ -39,12 +166,42 @@ pub fn generate_suggestions(
                     ce.constraint_index, ce.constraint_desc
                 ),
                 constraint_index: Some(ce.constraint_index),
+                fix: None,
             });
         }
     }
 
-    // For each redundant assertion: "remove_redundant" suggestion
+    // For each redundant assertion: "remove_redundant" suggestion, one
+    // per call with a fix when the call can go
+    let fixes = match program {
+        Some(p) => redundant_assertion_fixes(p.file, system, report),
+        None => Vec::new(),
+    };
+    for fix in &fixes {
+        let idx = fix.constraints[0];
+        let line = program.map_or(0, |p| {
+            p.source[..fix.span.start as usize].matches('\n').count() + 1
+        });
+        suggestions.push(JsonSuggestion {
+            kind: "remove_redundant".to_string(),
+            message: format!(
+                "Assertion on line {} is redundant (always true): {}; removing it saves {}",
+                line,
+                format_constraint(&system.constraints[idx]),
+                fix.savings()
+            ),
+            constraint_index: Some(idx),
+            fix: Some(JsonFix {
+                span: fix.span,
+                line,
+                saved: fix.saved,
+            }),
+        });
+    }
     for &idx in &report.redundant_assertions {
+        if fixes.iter().any(|f| f.constraints.contains(&idx)) {
+            continue;
+        }
         let desc = if idx < system.constraints.len() {
             format_constraint(&system.constraints[idx])
         } else {
@@ -57,6 +214,7 @@ pub fn generate_suggestions(
                 idx, desc
             ),
             constraint_index: Some(idx),
+            fix: None,
         });
     }
 
@@ -73,6 +231,7 @@ pub fn generate_suggestions(
                     name
                 ),
                 constraint_index: None,
+                fix: None,
             });
         }
     }
@@ -104,7 +263,7 @@ fn collect_divine_refs_constraint(c: &Constraint, out: &mut Vec<String>) {
             collect_divine_refs_value(cond, out);
             collect_divine_refs_constraint(inner, out);
         }
-        Constraint::RangeU32(v) => {
+        Constraint::RangeU32(v) | Constraint::NonZero(v) => {
             collect_divine_refs_value(v, out);
         }
         Constraint::DigestEqual(a, b) => {
@@ -129,11 +288,13 @@ fn collect_divine_refs_value(v: &SymValue, out: &mut Vec<String>) {
         | SymValue::Mul(a, b)
         | SymValue::Sub(a, b)
         | SymValue::Eq(a, b)
-        | SymValue::Lt(a, b) => {
+        | SymValue::Lt(a, b)
+        | SymValue::DivMod(a, b, _)
+        | SymValue::Pow(a, b) => {
             collect_divine_refs_value(a, out);
             collect_divine_refs_value(b, out);
         }
-        SymValue::Neg(a) | SymValue::Inv(a) => {
+        SymValue::Neg(a) | SymValue::Inv(a) | SymValue::Log2(a) => {
             collect_divine_refs_value(a, out);
         }
         SymValue::Ite(c, t, e) => {
@@ -146,7 +307,7 @@ fn collect_divine_refs_value(v: &SymValue, out: &mut Vec<String>) {
                 collect_divine_refs_value(v, out);
             }
         }
-        SymValue::Const(_) | SymValue::Divine(_) | SymValue::PubInput(_) => {}
+        SymValue::Const(_) => {}
         SymValue::FieldAccess(inner, _) => {
             collect_divine_refs_value(inner, out);
         }
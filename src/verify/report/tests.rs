@@ -13,7 +13,7 @@ fn verify_source(source: &str) -> (ConstraintSystem, VerificationReport) {
 #[test]
 fn test_json_basic_structure() {
     let (system, report) = verify_source("program test\nfn main() {\n    assert(true)\n}\n");
-    let json = generate_json_report("test.tri", &system, &report);
+    let json = generate_json_report("test.tri", &system, &report, None);
 
     // Check basic JSON structure markers
     assert!(json.starts_with('{'));
@@ -27,12 +27,41 @@ fn test_json_basic_structure() {
     assert!(json.contains("\"suggestions\":"));
 }
 
+#[test]
+fn test_json_records_seed_and_budgets() {
+    let file = crate::parse_source(
+        "program test\nfn main() {\n    assert(true)\n}\n",
+        "test.tri",
+    )
+    .unwrap();
+    let system = sym::analyze(&file);
+    let config = solve::VerifyConfig {
+        samples: 12,
+        time_budget: Some(std::time::Duration::from_millis(250)),
+        seed: 7,
+        ..solve::VerifyConfig::default()
+    };
+    let report = solve::verify_with(&system, &config);
+    let json = generate_json_report("test.tri", &system, &report, None);
+    assert!(json.contains("\"seed\": \"7\""));
+    assert!(json.contains("\"samples\": 12"));
+    assert!(json.contains("\"bmc_depth\": 10000"));
+    assert!(json.contains("\"time_budget_ms\": 250"));
+    assert!(json.contains("\"random_rounds\": 12"));
+    assert!(json.contains("\"budget_exhausted\": false"));
+
+    let (system, report) = verify_source("program test\nfn main() {\n    assert(true)\n}\n");
+    let json = generate_json_report("test.tri", &system, &report, None);
+    assert!(json.contains("\"seed\": \"16045690984503098046\""));
+    assert!(json.contains("\"time_budget_ms\": null"));
+}
+
 #[test]
 fn test_counterexample_serialization() {
     let (system, report) = verify_source(
         "program test\nfn main() {\n    let x: Field = pub_read()\n    assert_eq(x, 0)\n}\n",
     );
-    let json = generate_json_report("test.tri", &system, &report);
+    let json = generate_json_report("test.tri", &system, &report, None);
 
     // This program asserts x == 0 which should fail for most random x
     assert!(json.contains("\"verdict\": \"unsafe\""));
@@ -46,7 +75,7 @@ fn test_safe_program_no_suggestions() {
     let (system, report) = verify_source(
         "program test\nfn main() {\n    let x: Field = pub_read()\n    assert_eq(x + 0, x)\n}\n",
     );
-    let suggestions = generate_suggestions(&system, &report);
+    let suggestions = generate_suggestions(&system, &report, None);
 
     // No fix_violation suggestions for a safe program
     let violations: Vec<_> = suggestions
@@ -64,7 +93,7 @@ fn test_unsafe_program_fix_violation() {
     let (system, report) = verify_source(
         "program test\nfn main() {\n    let x: Field = pub_read()\n    assert_eq(x, 42)\n}\n",
     );
-    let suggestions = generate_suggestions(&system, &report);
+    let suggestions = generate_suggestions(&system, &report, None);
 
     let violations: Vec<_> = suggestions
         .iter()
@@ -91,7 +120,7 @@ fn test_redundant_assertion_suggestion() {
 
     // Check the report for redundant assertions
     if !report.redundant_assertions.is_empty() {
-        let suggestions = generate_suggestions(&system, &report);
+        let suggestions = generate_suggestions(&system, &report, None);
         let redundant: Vec<_> = suggestions
             .iter()
             .filter(|s| s.kind == "remove_redundant")
@@ -152,7 +181,7 @@ fn test_divine_unconstrained_suggestion() {
     let (system, report) = verify_source(
         "program test\nfn main() {\n    let x: Field = divine()\n    assert(true)\n}\n",
     );
-    let suggestions = generate_suggestions(&system, &report);
+    let suggestions = generate_suggestions(&system, &report, None);
 
     let add_assertions: Vec<_> = suggestions
         .iter()
@@ -168,7 +197,61 @@ fn test_divine_unconstrained_suggestion() {
 #[test]
 fn test_static_violation_in_json() {
     let (system, report) = verify_source("program test\nfn main() {\n    assert(false)\n}\n");
-    let json = generate_json_report("test.tri", &system, &report);
+    let json = generate_json_report("test.tri", &system, &report, None);
     assert!(json.contains("\"verdict\": \"unsafe\""));
     assert!(json.contains("\"static_violations\": 1"));
 }
+
+#[test]
+fn test_json_lists_assumptions_apart_from_constraints() {
+    let (system, report) = verify_source(
+        "program test\n#[assume(n < 10)]\nfn step(n: U32) {\n    assert(n < 20)\n}\nfn main() {\n    let x: Field = pub_read()\n    assume(x == 0, \"kernel passes zero\")\n    step(as_u32(x))\n}\n",
+    );
+    let json = generate_json_report("test.tri", &system, &report, None);
+    assert!(json.contains("\"assumptions\": 2"));
+    assert!(json.contains("\"assumptions\": ["));
+    assert!(json.contains("\"kind\": \"statement\""));
+    assert!(json.contains("\"kind\": \"attribute\""));
+    assert!(json.contains("\"reason\": \"kernel passes zero\""));
+    assert!(json.contains("\"reason\": null"));
+}
+
+#[test]
+fn test_redundant_assertion_fixes_carry_span_and_savings() {
+    let source = "program test\nfn same(x: Field) -> Field {\n    x\n}\nfn main() {\n    let x: Field = pub_read()\n    for i in 0..4 {\n        assert_eq(x + x, x * 2)\n    }\n    assert_eq(same(x) + x, x * 2)\n}\n";
+    let file = crate::parse_source(source, "test.tri").unwrap();
+    let mut system = ConstraintSystem::new();
+    for (_, fn_system) in sym::analyze_all(&file) {
+        system.merge(fn_system);
+    }
+    let report = solve::verify(&system);
+
+    // The call in `assert_eq(same(x) + x, x * 2)` keeps it from being offered.
+    let fixes = redundant_assertion_fixes(&file, &system, &report);
+    assert_eq!(fixes.len(), 1);
+    let fix = &fixes[0];
+    assert_eq!(
+        &source[fix.span.start as usize..fix.span.end as usize],
+        "assert_eq(x + x, x * 2)"
+    );
+    assert_eq!(fix.constraints.len(), 4);
+    // Four runs of the loop body.
+    assert_eq!(fix.saved.get(0) % 4, 0);
+    assert_eq!(fix.savings(), "32cc, 32 opst rows");
+
+    let program = ReportSource {
+        file: &file,
+        source,
+    };
+    let suggestions = generate_suggestions(&system, &report, Some(&program));
+    let fixed: Vec<_> = suggestions.iter().filter(|s| s.fix.is_some()).collect();
+    assert_eq!(fixed.len(), 1);
+    assert!(fixed[0]
+        .message
+        .starts_with("Assertion on line 8 is redundant (always true)"));
+    let json = generate_json_report("test.tri", &system, &report, Some(&program));
+    assert!(json.contains("\"action\": \"delete\""));
+    assert!(json.contains("\"line\": 8"));
+    assert!(json.contains("\"saved\": {\"processor\": "));
+    assert!(json.contains("\"fix\": null"));
+}
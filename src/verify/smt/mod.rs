@@ -113,6 +113,17 @@ impl SmtEncoder {
         }
         self.emit("");
 
+        // Assumptions hold in both modes: they are trusted, not checked.
+        if !system.assumptions.is_empty() {
+            self.emit("; Assumptions (taken on trust)");
+            for (i, assumption) in system.assumptions.iter().enumerate() {
+                let smt = self.encode_constraint(&assumption.constraint);
+                self.emit(&format!("; Assumption #{}", i));
+                self.emit(&format!("(assert {})", smt));
+            }
+            self.emit("");
+        }
+
         // Encode constraints
         match self.mode {
             QueryMode::SafetyCheck => {
@@ -230,6 +241,10 @@ impl SmtEncoder {
                 let sv = self.encode_value(v);
                 format!("(bvule {} (_ bv{} 128))", sv, u32::MAX)
             }
+            Constraint::NonZero(v) => {
+                let sv = self.encode_value(v);
+                format!("(not (= {} (_ bv0 128)))", sv)
+            }
             Constraint::DigestEqual(a, b) => {
                 let mut conjuncts = Vec::new();
                 for (x, y) in a.iter().zip(b.iter()) {
@@ -303,6 +318,26 @@ impl SmtEncoder {
                 let sb = self.encode_value(b);
                 format!("(ite (bvult {} {}) (_ bv1 128) (_ bv0 128))", sa, sb)
             }
+            SymValue::DivMod(a, b, index) => {
+                // U32 operands: unsigned BV division is exact. A zero
+                // divisor is ruled out by the accompanying NonZero.
+                let sa = self.encode_value(a);
+                let sb = self.encode_value(b);
+                let op = if *index == 0 { "bvudiv" } else { "bvurem" };
+                format!("({} {} {})", op, sa, sb)
+            }
+            SymValue::Pow(a, _) | SymValue::Log2(a) => {
+                // Opaque in BV, like Inv: a fresh variable per occurrence.
+                let _sa = self.encode_value(a);
+                let prefix = if matches!(value, SymValue::Pow(..)) {
+                    "pow"
+                } else {
+                    "log2"
+                };
+                let name = format!("__{}_{}", prefix, self.declared_vars.len());
+                self.declared_vars.insert(name.clone());
+                name
+            }
             SymValue::Hash(inputs, index) => {
                 // Hash is opaque — create uninterpreted function
                 let hash_name = format!("__hash_{}_{}", inputs.len(), index);
@@ -312,22 +347,6 @@ impl SmtEncoder {
                 }
                 name
             }
-            SymValue::Divine(idx) => {
-                let name = format!("divine_{}", idx);
-                let smt_name = sanitize_smt_name(&name);
-                if !self.declared_vars.contains(&smt_name) {
-                    self.declared_vars.insert(smt_name.clone());
-                }
-                smt_name
-            }
-            SymValue::PubInput(idx) => {
-                let name = format!("pub_in_{}", idx);
-                let smt_name = sanitize_smt_name(&name);
-                if !self.declared_vars.contains(&smt_name) {
-                    self.declared_vars.insert(smt_name.clone());
-                }
-                smt_name
-            }
             SymValue::Ite(cond, then_val, else_val) => {
                 let sc = self.encode_value(cond);
                 let st = self.encode_value(then_val);
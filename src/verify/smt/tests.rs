@@ -73,3 +73,12 @@ fn test_empty_constraints() {
     );
     assert!(smt.contains("No constraints"));
 }
+
+#[test]
+fn test_assumptions_are_asserted() {
+    let smt = parse_and_encode(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    assume(x == 0)\n    assert_eq(x, 0)\n}\n",
+        QueryMode::SafetyCheck,
+    );
+    assert!(smt.contains("; Assumptions (taken on trust)"));
+}
@@ -124,6 +124,27 @@ impl<'a> Evaluator<'a> {
                 let b = self.eval(b)?;
                 Some(if a < b { 1 } else { 0 })
             }
+            SymValue::DivMod(a, b, index) => {
+                let n = self.eval(a)?;
+                let d = self.eval(b)?;
+                // Out-of-range operands and zero divisors fault in the VM
+                if d == 0 || n > u32::MAX as u64 || d > u32::MAX as u64 {
+                    return None;
+                }
+                Some(if *index == 0 { n / d } else { n % d })
+            }
+            SymValue::Pow(a, b) => {
+                let base = self.eval(a)?;
+                let exp = self.eval(b)?;
+                Some(Goldilocks::from_u64(base).pow(exp).to_u64())
+            }
+            SymValue::Log2(a) => {
+                let a = self.eval(a)?;
+                if a == 0 || a > u32::MAX as u64 {
+                    return None;
+                }
+                Some(63 - a.leading_zeros() as u64)
+            }
             SymValue::Hash(inputs, index) => {
                 // Hash is opaque — use a deterministic pseudo-hash based on inputs
                 let mut h: u64 = 0x9E3779B97F4A7C15; // golden ratio constant
@@ -137,14 +158,6 @@ impl<'a> Evaluator<'a> {
                     .wrapping_add(*index as u64);
                 Some(h % GOLDILOCKS_P)
             }
-            SymValue::Divine(idx) => {
-                let key = format!("divine_{}", idx);
-                self.assignments.get(&key).copied()
-            }
-            SymValue::PubInput(idx) => {
-                let key = format!("pub_in_{}", idx);
-                self.assignments.get(&key).copied()
-            }
             SymValue::Ite(cond, then_val, else_val) => {
                 let c = self.eval(cond)?;
                 if c != 0 {
@@ -185,6 +198,10 @@ impl<'a> Evaluator<'a> {
                 let val = self.eval(v)?;
                 Some(val <= u32::MAX as u64)
             }
+            Constraint::NonZero(v) => {
+                let val = self.eval(v)?;
+                Some(val != 0)
+            }
             Constraint::DigestEqual(a, b) => {
                 for (x, y) in a.iter().zip(b.iter()) {
                     let vx = self.eval(x)?;
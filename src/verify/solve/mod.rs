@@ -20,9 +20,10 @@
 //!    to reduce proving cost.
 
 use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
 use crate::field::goldilocks::MODULUS as GOLDILOCKS_P;
-use crate::sym::{Constraint, ConstraintSystem, SymValue};
+use crate::sym::{Assumption, Constraint, ConstraintSystem, SymValue};
 
 mod eval;
 mod solver;
@@ -79,6 +80,8 @@ pub struct SolverResult {
     pub unevaluable: Vec<usize>,
     /// Whether all evaluable constraints passed in all rounds.
     pub all_passed: bool,
+    /// Whether the time budget ran out before the planned rounds did.
+    pub budget_exhausted: bool,
 }
 
 impl SolverResult {
@@ -115,12 +118,41 @@ impl SolverResult {
             ));
         }
 
+        if self.budget_exhausted {
+            report.push_str("  Time budget exhausted: stopped early\n");
+        }
+
         report
     }
 }
 
 // ─── Combined Verification ─────────────────────────────────────────
 
+/// Budgets and seed for `verify_with`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyConfig {
+    /// Schwartz-Zippel rounds.
+    pub samples: usize,
+    /// Maximum assignments bounded model checking tests.
+    pub bmc_depth: usize,
+    /// Wall-clock budget for one `verify_with` call; `None` runs every round.
+    pub time_budget: Option<Duration>,
+    /// Seed for random testing; bounded model checking derives its own
+    /// from it. The same seed reproduces the same report.
+    pub seed: u64,
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        Self {
+            samples: 100,
+            bmc_depth: 10_000,
+            time_budget: None,
+            seed: 0xDEAD_BEEF_CAFE_BABE,
+        }
+    }
+}
+
 /// Full verification result combining static analysis, random testing, and BMC.
 #[derive(Clone, Debug)]
 pub struct VerificationReport {
@@ -134,8 +166,14 @@ pub struct VerificationReport {
     pub redundant_assertions: Vec<usize>,
     /// Hash-dependent constraints (witness-required, not testable by random sampling).
     pub witness_required: usize,
+    /// Assumptions taken on trust, as `format_assumption` shows them.
+    pub assumptions: Vec<String>,
+    /// `while` loops unrolled fewer times than their bound allows.
+    pub truncated_loops: usize,
     /// Overall verdict.
     pub verdict: Verdict,
+    /// Budgets and seed the checks ran with.
+    pub config: VerifyConfig,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -148,6 +186,9 @@ pub enum Verdict {
     RandomViolation,
     /// BMC found violations (definite for tested values).
     BmcViolation,
+    /// No violation found, but loop iterations past the unrolling limit
+    /// were not checked.
+    Inconclusive,
 }
 
 impl VerificationReport {
@@ -158,6 +199,7 @@ impl VerificationReport {
     pub fn format_report(&self) -> String {
         let mut report = String::new();
         report.push_str("═══ Verification Report ═══\n\n");
+        report.push_str(&format!("Seed: {}\n\n", self.config.seed));
 
         // Static analysis
         if self.static_violations.is_empty() {
@@ -183,6 +225,28 @@ impl VerificationReport {
         report.push_str(&self.bmc_result.format_report());
         report.push('\n');
 
+        // Assumptions
+        if !self.assumptions.is_empty() {
+            report.push_str(&format!(
+                "Assumptions: {} fact(s) taken on trust, not checked at runtime\n",
+                self.assumptions.len()
+            ));
+            for a in &self.assumptions {
+                report.push_str(&format!("  - {}\n", a));
+            }
+            report.push('\n');
+        }
+
+        // Loops cut short
+        if self.truncated_loops > 0 {
+            report.push_str(&format!(
+                "Truncated: {} while loop(s) unrolled fewer times than their bound allows\n",
+                self.truncated_loops
+            ));
+            report.push_str("  Iterations past the cut are not checked.\n");
+            report.push('\n');
+        }
+
         // Witness-required constraints
         if self.witness_required > 0 {
             report.push_str(&format!(
@@ -211,6 +275,9 @@ impl VerificationReport {
                 "UNSAFE — random testing found violations (high confidence)"
             }
             Verdict::BmcViolation => "UNSAFE — bounded model checking found violations",
+            Verdict::Inconclusive => {
+                "INCONCLUSIVE — no violations found in the unrolled iterations"
+            }
         };
         report.push_str(&format!("Verdict: {}\n", verdict_str));
 
@@ -220,6 +287,16 @@ impl VerificationReport {
 
 /// Run full verification: static + random + BMC.
 pub fn verify(system: &ConstraintSystem) -> VerificationReport {
+    verify_with(system, &VerifyConfig::default())
+}
+
+/// Run full verification within `config`'s budgets.
+///
+/// When the time budget runs out, each check keeps the rounds it
+/// finished and flags `budget_exhausted`; the verdict covers only those.
+pub fn verify_with(system: &ConstraintSystem, config: &VerifyConfig) -> VerificationReport {
+    let deadline = config.time_budget.map(|budget| Instant::now() + budget);
+
     // 1. Static analysis
     let static_violations: Vec<String> = system
         .violated_constraints()
@@ -235,10 +312,26 @@ pub fn verify(system: &ConstraintSystem) -> VerificationReport {
         .count();
 
     // 2. Random testing (Schwartz-Zippel)
-    let random_result = solve(system, &SolverConfig::default());
+    let random_result = solve(
+        system,
+        &SolverConfig {
+            rounds: config.samples,
+            seed: config.seed,
+            deadline,
+            ..SolverConfig::default()
+        },
+    );
 
     // 3. Bounded model checking
-    let bmc_result = bounded_check(system, &BmcConfig::default());
+    let bmc_result = bounded_check(
+        system,
+        &BmcConfig {
+            seed: config.seed.rotate_left(32),
+            max_rounds: config.bmc_depth,
+            deadline,
+            ..BmcConfig::default()
+        },
+    );
 
     // 4. Collect redundant assertions (from both methods)
     let mut redundant: Vec<usize> = random_result.always_satisfied.clone();
@@ -260,6 +353,8 @@ pub fn verify(system: &ConstraintSystem) -> VerificationReport {
         Verdict::RandomViolation
     } else if !bmc_result.all_passed {
         Verdict::BmcViolation
+    } else if !system.truncated_loops.is_empty() {
+        Verdict::Inconclusive
     } else {
         Verdict::Safe
     };
@@ -270,7 +365,10 @@ pub fn verify(system: &ConstraintSystem) -> VerificationReport {
         bmc_result,
         redundant_assertions: redundant,
         witness_required,
+        assumptions: system.assumptions.iter().map(format_assumption).collect(),
+        truncated_loops: system.truncated_loops.len(),
         verdict,
+        config: config.clone(),
     }
 }
 
@@ -435,6 +533,38 @@ pub fn format_constraint(c: &Constraint) -> String {
         Constraint::DigestEqual(_, _) => {
             format!("digest_eq([..], [..])")
         }
+        Constraint::NonZero(v) => {
+            format!("{} != 0", format_sym_value(v))
+        }
+    }
+}
+
+/// Format an assumption for display: its condition, where it is stated,
+/// and its reason if it has one.
+pub fn format_assumption(a: &Assumption) -> String {
+    let stated = if a.from_attribute {
+        format!("#[assume] on {}", a.function)
+    } else {
+        format!("assume in {}", a.function)
+    };
+    match &a.reason {
+        Some(reason) => format!("{} ({}): {}", format_assumed(&a.constraint), stated, reason),
+        None => format!("{} ({})", format_assumed(&a.constraint), stated),
+    }
+}
+
+/// Format an assumed constraint as the condition it takes as true.
+pub fn format_assumed(c: &Constraint) -> String {
+    match c {
+        Constraint::AssertTrue(v) => format_sym_value(v),
+        Constraint::Conditional(cond, inner) => {
+            format!(
+                "if {} then {}",
+                format_sym_value(cond),
+                format_assumed(inner)
+            )
+        }
+        other => format_constraint(other),
     }
 }
 
@@ -450,9 +580,15 @@ pub fn format_sym_value(v: &SymValue) -> String {
         SymValue::Inv(a) => format!("(1/{})", format_sym_value(a)),
         SymValue::Eq(a, b) => format!("({} == {})", format_sym_value(a), format_sym_value(b)),
         SymValue::Lt(a, b) => format!("({} < {})", format_sym_value(a), format_sym_value(b)),
+        SymValue::DivMod(a, b, 0) => {
+            format!("({} / {})", format_sym_value(a), format_sym_value(b))
+        }
+        SymValue::DivMod(a, b, _) => {
+            format!("({} % {})", format_sym_value(a), format_sym_value(b))
+        }
+        SymValue::Pow(a, b) => format!("({} ^ {})", format_sym_value(a), format_sym_value(b)),
+        SymValue::Log2(a) => format!("log2({})", format_sym_value(a)),
         SymValue::Hash(_, idx) => format!("hash[{}]", idx),
-        SymValue::Divine(idx) => format!("divine_{}", idx),
-        SymValue::PubInput(idx) => format!("pub_in_{}", idx),
         SymValue::Ite(c, t, e) => format!(
             "(if {} then {} else {})",
             format_sym_value(c),
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use super::*;
 
 // ─── Solver ────────────────────────────────────────────────────────
@@ -13,6 +15,8 @@ pub struct SolverConfig {
     pub collect_counterexamples: bool,
     /// Whether to detect redundant (always-true) constraints.
     pub detect_redundant: bool,
+    /// Stop after the round in progress when this instant passes.
+    pub deadline: Option<Instant>,
 }
 
 impl Default for SolverConfig {
@@ -22,6 +26,7 @@ impl Default for SolverConfig {
             seed: 0xDEAD_BEEF_CAFE_BABE,
             collect_counterexamples: true,
             detect_redundant: true,
+            deadline: None,
         }
     }
 }
@@ -35,12 +40,20 @@ pub fn solve(system: &ConstraintSystem, config: &SolverConfig) -> SolverResult {
     // Track which constraints have ever failed or been unevaluable
     let mut ever_failed = vec![false; num_constraints];
     let mut ever_unevaluable = vec![false; num_constraints];
+    let mut ever_checked = vec![false; num_constraints];
     let mut counterexamples: Vec<Counterexample> = Vec::new();
 
     // Collect all variable names we need to assign
     let var_names = collect_variables(system);
 
+    let mut rounds = 0;
+    let mut budget_exhausted = false;
     for _round in 0..config.rounds {
+        if _round > 0 && past(config.deadline) {
+            budget_exhausted = true;
+            break;
+        }
+        rounds += 1;
         // Generate random assignments for all variables
         let mut assignments = BTreeMap::new();
         for name in &var_names {
@@ -53,6 +66,9 @@ pub fn solve(system: &ConstraintSystem, config: &SolverConfig) -> SolverResult {
         }
 
         let evaluator = Evaluator::new(&assignments);
+        if violates_assumptions(system, &evaluator) {
+            continue;
+        }
 
         for (i, constraint) in system.constraints.iter().enumerate() {
             // Hash-dependent constraints require specific witnesses —
@@ -62,7 +78,7 @@ pub fn solve(system: &ConstraintSystem, config: &SolverConfig) -> SolverResult {
                 continue;
             }
             match evaluator.check_constraint(constraint) {
-                Some(true) => {} // Satisfied
+                Some(true) => ever_checked[i] = true,
                 Some(false) => {
                     if !ever_failed[i] {
                         ever_failed[i] = true;
@@ -86,7 +102,10 @@ pub fn solve(system: &ConstraintSystem, config: &SolverConfig) -> SolverResult {
     let always_satisfied = if config.detect_redundant {
         (0..num_constraints)
             .filter(|&i| {
-                !ever_failed[i] && !ever_unevaluable[i] && !system.constraints[i].is_trivial()
+                ever_checked[i]
+                    && !ever_failed[i]
+                    && !ever_unevaluable[i]
+                    && !system.constraints[i].is_trivial()
             })
             .collect()
     } else {
@@ -101,11 +120,12 @@ pub fn solve(system: &ConstraintSystem, config: &SolverConfig) -> SolverResult {
 
     SolverResult {
         constraints_checked: num_constraints,
-        rounds: config.rounds,
+        rounds,
         counterexamples,
         always_satisfied,
         unevaluable,
         all_passed,
+        budget_exhausted,
     }
 }
 
@@ -121,6 +141,10 @@ pub struct BmcConfig {
     pub values_per_var: usize,
     /// Seed for random sampling.
     pub seed: u64,
+    /// Maximum number of assignments to test.
+    pub max_rounds: usize,
+    /// Stop after the assignment in progress when this instant passes.
+    pub deadline: Option<Instant>,
 }
 
 impl Default for BmcConfig {
@@ -129,6 +153,8 @@ impl Default for BmcConfig {
             max_exhaustive_vars: 8,
             values_per_var: 16,
             seed: 0xCAFE_BABE_DEAD_BEEF,
+            max_rounds: 10_000,
+            deadline: None,
         }
     }
 }
@@ -144,49 +170,61 @@ pub fn bounded_check(system: &ConstraintSystem, config: &BmcConfig) -> SolverRes
 
     let mut ever_failed = vec![false; num_constraints];
     let mut ever_unevaluable = vec![false; num_constraints];
+    let mut ever_checked = vec![false; num_constraints];
     let mut counterexamples: Vec<Counterexample> = Vec::new();
     let mut total_rounds = 0;
+    let mut budget_exhausted = false;
 
     if num_vars == 0 {
         // No variables: just evaluate once with empty assignment
         let assignments = BTreeMap::new();
         let evaluator = Evaluator::new(&assignments);
         total_rounds = 1;
-        for (i, constraint) in system.constraints.iter().enumerate() {
-            if constraint.is_hash_dependent() {
-                ever_unevaluable[i] = true;
-                continue;
-            }
-            match evaluator.check_constraint(constraint) {
-                Some(true) => {}
-                Some(false) => {
-                    ever_failed[i] = true;
-                    counterexamples.push(Counterexample {
-                        constraint_index: i,
-                        constraint_desc: format_constraint(constraint),
-                        assignments: BTreeMap::new(),
-                    });
-                }
-                None => {
+        // Inputs that break an assumption are ruled out, not tested.
+        if !violates_assumptions(system, &evaluator) {
+            for (i, constraint) in system.constraints.iter().enumerate() {
+                if constraint.is_hash_dependent() {
                     ever_unevaluable[i] = true;
+                    continue;
+                }
+                match evaluator.check_constraint(constraint) {
+                    Some(true) => ever_checked[i] = true,
+                    Some(false) => {
+                        ever_failed[i] = true;
+                        counterexamples.push(Counterexample {
+                            constraint_index: i,
+                            constraint_desc: format_constraint(constraint),
+                            assignments: BTreeMap::new(),
+                        });
+                    }
+                    None => {
+                        ever_unevaluable[i] = true;
+                    }
                 }
             }
         }
     } else if num_vars <= config.max_exhaustive_vars {
         // Exhaustive grid: test interesting values for each variable
         let interesting_values = interesting_field_values(config.values_per_var);
-        let combos = generate_combinations(&var_names, &interesting_values, 10_000);
+        let combos = generate_combinations(&var_names, &interesting_values, config.max_rounds);
 
         for assignments in &combos {
+            if total_rounds > 0 && past(config.deadline) {
+                budget_exhausted = true;
+                break;
+            }
             total_rounds += 1;
             let evaluator = Evaluator::new(assignments);
+            if violates_assumptions(system, &evaluator) {
+                continue;
+            }
             for (i, constraint) in system.constraints.iter().enumerate() {
                 if constraint.is_hash_dependent() {
                     ever_unevaluable[i] = true;
                     continue;
                 }
                 match evaluator.check_constraint(constraint) {
-                    Some(true) => {}
+                    Some(true) => ever_checked[i] = true,
                     Some(false) => {
                         if !ever_failed[i] {
                             ever_failed[i] = true;
@@ -206,9 +244,13 @@ pub fn bounded_check(system: &ConstraintSystem, config: &BmcConfig) -> SolverRes
     } else {
         // Too many variables: random sampling
         let mut rng = Rng::new(config.seed);
-        let sample_count = config.values_per_var * 100;
+        let sample_count = (config.values_per_var * 100).min(config.max_rounds);
 
         for _ in 0..sample_count {
+            if total_rounds > 0 && past(config.deadline) {
+                budget_exhausted = true;
+                break;
+            }
             total_rounds += 1;
             let mut assignments = BTreeMap::new();
             for name in &var_names {
@@ -216,13 +258,16 @@ pub fn bounded_check(system: &ConstraintSystem, config: &BmcConfig) -> SolverRes
             }
 
             let evaluator = Evaluator::new(&assignments);
+            if violates_assumptions(system, &evaluator) {
+                continue;
+            }
             for (i, constraint) in system.constraints.iter().enumerate() {
                 if constraint.is_hash_dependent() {
                     ever_unevaluable[i] = true;
                     continue;
                 }
                 match evaluator.check_constraint(constraint) {
-                    Some(true) => {}
+                    Some(true) => ever_checked[i] = true,
                     Some(false) => {
                         if !ever_failed[i] {
                             ever_failed[i] = true;
@@ -242,7 +287,12 @@ pub fn bounded_check(system: &ConstraintSystem, config: &BmcConfig) -> SolverRes
     }
 
     let always_satisfied: Vec<usize> = (0..num_constraints)
-        .filter(|&i| !ever_failed[i] && !ever_unevaluable[i] && !system.constraints[i].is_trivial())
+        .filter(|&i| {
+            ever_checked[i]
+                && !ever_failed[i]
+                && !ever_unevaluable[i]
+                && !system.constraints[i].is_trivial()
+        })
         .collect();
 
     let unevaluable: Vec<usize> = (0..num_constraints)
@@ -258,5 +308,21 @@ pub fn bounded_check(system: &ConstraintSystem, config: &BmcConfig) -> SolverRes
         always_satisfied,
         unevaluable,
         all_passed,
+        budget_exhausted,
     }
 }
+
+/// Whether the assignment breaks an assumption. Such inputs are ruled
+/// out rather than tested; an assumption that cannot be evaluated is
+/// taken to hold.
+fn violates_assumptions(system: &ConstraintSystem, evaluator: &Evaluator) -> bool {
+    system
+        .assumptions
+        .iter()
+        .any(|a| evaluator.check_constraint(&a.constraint) == Some(false))
+}
+
+/// Whether `deadline` has passed.
+fn past(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|d| Instant::now() >= d)
+}
@@ -127,3 +127,91 @@ fn test_inlined_function_verification() {
     );
     assert!(report.is_safe());
 }
+
+#[test]
+fn test_verify_with_budgets_and_seed() {
+    // Nine inputs push bounded model checking onto seeded random sampling.
+    let mut source = String::from("program test\nfn main() {\n");
+    for i in 0..9 {
+        source.push_str(&format!("    let x{}: Field = pub_read()\n", i));
+    }
+    source.push_str("    assert_eq(x0 + x8, 42)\n}\n");
+    let file = crate::parse_source(&source, "test.tri").unwrap();
+    let system = sym::analyze(&file);
+
+    let config = VerifyConfig {
+        samples: 7,
+        bmc_depth: 5,
+        seed: 42,
+        ..VerifyConfig::default()
+    };
+    let report = verify_with(&system, &config);
+    assert_eq!(report.random_result.rounds, 7);
+    assert_eq!(report.bmc_result.rounds, 5);
+    assert_eq!(report.config, config);
+    assert!(report.format_report().contains("Seed: 42"));
+    assert_eq!(
+        report.format_report(),
+        verify_with(&system, &config).format_report()
+    );
+
+    let reseeded = verify_with(&system, &VerifyConfig { seed: 43, ..config });
+    assert_ne!(
+        report.bmc_result.counterexamples[0].assignments,
+        reseeded.bmc_result.counterexamples[0].assignments
+    );
+}
+
+#[test]
+fn test_verify_with_exhausted_time_budget() {
+    let file = crate::parse_source(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    assert_eq(x + 0, x)\n}\n",
+        "test.tri",
+    )
+    .unwrap();
+    let system = sym::analyze(&file);
+    let report = verify_with(
+        &system,
+        &VerifyConfig {
+            time_budget: Some(std::time::Duration::ZERO),
+            ..VerifyConfig::default()
+        },
+    );
+    assert_eq!(report.random_result.rounds, 1);
+    assert!(report.random_result.budget_exhausted);
+    assert!(report.bmc_result.budget_exhausted);
+    assert!(report.format_report().contains("Time budget exhausted"));
+}
+
+#[test]
+fn test_assumptions_restrict_the_inputs_checked() {
+    let assumed = parse_and_verify(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    assume(x == 0, \"kernel passes zero\")\n    assert_eq(x, 0)\n}\n",
+    );
+    assert!(assumed.is_safe(), "{}", assumed.format_report());
+    assert_eq!(
+        assumed.assumptions,
+        vec!["(pub_in_0 == 0) (assume in main): kernel passes zero"]
+    );
+    assert!(assumed
+        .format_report()
+        .contains("Assumptions: 1 fact(s) taken on trust"));
+
+    let unassumed = parse_and_verify(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    assert_eq(x, 0)\n}\n",
+    );
+    assert!(!unassumed.is_safe());
+}
+
+#[test]
+fn test_loops_with_symbolic_trip_counts_verify_by_closed_form() {
+    let report = parse_and_verify(
+        "program test\nfn main() {\n    let n: U32 = as_u32(pub_read())\n    let x: Field = pub_read()\n    let mut acc: Field = 0\n    let mut total: Field = 0\n    let mut p: Field = 1\n    for i in 0..n bounded 100 {\n        acc = acc + x\n        total = total + as_field(i)\n        p = p * 3 + 1\n    }\n    assert_eq(acc, x * as_field(n))\n    assert_eq(total + total + as_field(n), as_field(n) * as_field(n))\n    assert_eq(p + p + 1, pow(3, n) * 3)\n}\n",
+    );
+    assert!(report.is_safe(), "{}", report.format_report());
+
+    let report = parse_and_verify(
+        "program test\nfn main() {\n    let n: U32 = as_u32(pub_read())\n    let mut acc: Field = 0\n    for i in 0..n bounded 100 {\n        acc = acc + 2\n    }\n    assert_eq(acc, as_field(n))\n}\n",
+    );
+    assert!(!report.is_safe());
+}
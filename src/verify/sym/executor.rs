@@ -3,6 +3,14 @@ use super::*;
 /// Maximum iterations for constant-range for-loop unrolling in symbolic execution.
 const MAX_CONST_LOOP_UNROLL: u64 = 10_000;
 
+/// Maximum iterations for loops whose trip count is only known at run time.
+const MAX_DYNAMIC_LOOP_UNROLL: u64 = 64;
+
+/// Largest total size of the values a `while` loop carries before
+/// unrolling stops. Each iteration merges both outcomes of the test, so
+/// a counter's term can triple per iteration.
+const MAX_WHILE_TERM_SIZE: usize = 1 << 14;
+
 // ─── Symbolic Executor ─────────────────────────────────────────────
 
 /// Symbolic executor that walks the AST and builds a constraint system.
@@ -25,6 +33,15 @@ pub struct SymExecutor {
     pub(crate) call_depth: u32,
     /// Maximum call depth before giving up.
     pub(crate) max_call_depth: u32,
+    /// Elements of digest values, by the variable standing for the digest.
+    pub(crate) digests: BTreeMap<String, Vec<SymValue>>,
+    /// The function being executed, inlined calls included.
+    pub(crate) current_fn: String,
+    /// The assertion call being executed, recorded as the origin of the
+    /// constraints it adds.
+    pub(crate) assertion: Option<Span>,
+    /// Summaries of called functions; `None` for those inlined instead.
+    pub(crate) summaries: BTreeMap<String, Option<FnSummary>>,
 }
 
 impl SymExecutor {
@@ -39,6 +56,10 @@ impl SymExecutor {
             functions: BTreeMap::new(),
             call_depth: 0,
             max_call_depth: 64,
+            digests: BTreeMap::new(),
+            current_fn: String::new(),
+            assertion: None,
+            summaries: BTreeMap::new(),
         }
     }
 
@@ -47,6 +68,7 @@ impl SymExecutor {
         self.register_functions(file);
 
         if let Some(main_fn) = self.functions.get("main").cloned() {
+            self.current_fn = "main".to_string();
             if let Some(ref body) = main_fn.body {
                 self.execute_block(&body.node);
             }
@@ -65,6 +87,8 @@ impl SymExecutor {
                 let var = self.fresh_var(&param.name.node);
                 self.env.insert(param.name.node.clone(), SymValue::Var(var));
             }
+            self.current_fn = fn_name.to_string();
+            self.assume_attributes(&func);
             if let Some(ref body) = func.body {
                 self.execute_block(&body.node);
             }
@@ -75,10 +99,13 @@ impl SymExecutor {
 
     /// Register all non-test functions from a file for inlining.
     fn register_functions(&mut self, file: &File) {
-        for item in &file.items {
-            if let Item::Fn(func) = &item.node {
+        // As in compilation, `break` and `continue` become skip flags.
+        let mut file = file.clone();
+        crate::ast::desugar::desugar_loop_exits(&mut file);
+        for item in file.items {
+            if let Item::Fn(func) = item.node {
                 if func.body.is_some() && !func.is_test {
-                    self.functions.insert(func.name.node.clone(), func.clone());
+                    self.functions.insert(func.name.node.clone(), func);
                 }
             }
         }
@@ -117,31 +144,123 @@ impl SymExecutor {
 
     /// Add a constraint, wrapping with current path condition.
     pub(crate) fn add_constraint(&mut self, c: Constraint) {
+        let c = self.under_path_condition(c);
+        self.system.constraints.push(c);
+        self.system.origins.push(self.assertion);
+    }
+
+    /// Add an assumption, wrapping with current path condition.
+    pub(crate) fn add_assumption(
+        &mut self,
+        c: Constraint,
+        from_attribute: bool,
+        reason: Option<String>,
+    ) {
+        let constraint = self.under_path_condition(c);
+        self.system.assumptions.push(Assumption {
+            constraint,
+            function: self.current_fn.clone(),
+            from_attribute,
+            reason,
+        });
+    }
+
+    pub(crate) fn under_path_condition(&self, c: Constraint) -> Constraint {
         if self.path_condition.is_empty() {
-            self.system.constraints.push(c);
-        } else {
-            // Combine path conditions: cond1 AND cond2 AND ... => constraint
-            let mut combined = self.path_condition[0].clone();
-            for pc in &self.path_condition[1..] {
-                combined = SymValue::Mul(Box::new(combined), Box::new(pc.clone()));
-            }
-            self.system
-                .constraints
-                .push(Constraint::Conditional(combined, Box::new(c)));
+            return c;
+        }
+        // Combine path conditions: cond1 AND cond2 AND ... => constraint
+        let mut combined = self.path_condition[0].clone();
+        for pc in &self.path_condition[1..] {
+            combined = SymValue::Mul(Box::new(combined), Box::new(pc.clone()));
+        }
+        Constraint::Conditional(combined, Box::new(c))
+    }
+
+    /// Record the `#[assume]` predicates of `func`, whose parameters are
+    /// bound.
+    pub(crate) fn assume_attributes(&mut self, func: &FnDef) {
+        for pred in &func.assumes {
+            let val = self.eval_expr(&pred.node);
+            self.add_assumption(Constraint::AssertTrue(val), true, None);
         }
     }
 
     /// Execute a block of statements.
     pub(crate) fn execute_block(&mut self, block: &Block) {
         for stmt in &block.stmts {
+            let site = match &stmt.node {
+                Stmt::Expr(expr) => assertion_site(expr),
+                _ => None,
+            };
+            let outer = std::mem::replace(&mut self.assertion, site);
             self.execute_stmt(&stmt.node);
+            self.assertion = outer;
+            // Nothing after `fail` runs.
+            if is_fail(&stmt.node) {
+                return;
+            }
         }
         // Evaluate tail expression for side effects (e.g., assert calls)
         if let Some(ref tail) = block.tail_expr {
+            let outer = std::mem::replace(&mut self.assertion, assertion_site(tail));
             let _ = self.eval_expr(&tail.node);
+            self.assertion = outer;
         }
     }
 
+    /// Execute `then_block` under `cond_val` and `else_block` under its
+    /// negation, merging the two environments.
+    fn execute_branch(
+        &mut self,
+        cond_val: SymValue,
+        then_block: &Block,
+        else_block: Option<&Block>,
+    ) {
+        // Save environment
+        let saved_env = self.env.clone();
+
+        // Execute then branch
+        self.path_condition.push(cond_val.clone());
+        self.execute_block(then_block);
+        let then_env = self.env.clone();
+        self.path_condition.pop();
+
+        // Execute else branch
+        self.env = saved_env.clone();
+        if let Some(else_blk) = else_block {
+            let neg_cond = SymValue::Sub(Box::new(SymValue::Const(1)), Box::new(cond_val.clone()));
+            self.path_condition.push(neg_cond);
+            self.execute_block(else_blk);
+            self.path_condition.pop();
+        }
+        let else_env = self.env.clone();
+
+        // Merge environments: for each variable modified in either branch,
+        // create an ITE symbolic value
+        let mut merged = saved_env;
+        for (name, then_val) in &then_env {
+            let else_val = else_env.get(name).unwrap_or(then_val);
+            if then_val != else_val {
+                let ite = SymValue::Ite(
+                    Box::new(cond_val.clone()),
+                    Box::new(then_val.clone()),
+                    Box::new(else_val.clone()),
+                );
+                merged.insert(name.clone(), ite);
+            } else {
+                merged.insert(name.clone(), then_val.clone());
+            }
+        }
+        // Also merge vars that only exist in else_env
+        for (name, else_val) in &else_env {
+            if !then_env.contains_key(name) {
+                merged.insert(name.clone(), else_val.clone());
+            }
+        }
+        self.env = merged;
+    }
+
     /// Execute a single statement.
     pub(crate) fn execute_stmt(&mut self, stmt: &Stmt) {
         match stmt {
@@ -184,50 +303,47 @@ impl SymExecutor {
                 else_block,
             } => {
                 let cond_val = self.eval_expr(&cond.node);
-
-                // Save environment
-                let saved_env = self.env.clone();
-
-                // Execute then branch
-                self.path_condition.push(cond_val.clone());
-                self.execute_block(&then_block.node);
-                let then_env = self.env.clone();
-                self.path_condition.pop();
-
-                // Execute else branch
-                self.env = saved_env.clone();
-                if let Some(else_blk) = else_block {
-                    let neg_cond =
-                        SymValue::Sub(Box::new(SymValue::Const(1)), Box::new(cond_val.clone()));
-                    self.path_condition.push(neg_cond);
-                    self.execute_block(&else_blk.node);
-                    self.path_condition.pop();
-                }
-                let else_env = self.env.clone();
-
-                // Merge environments: for each variable modified in either branch,
-                // create an ITE symbolic value
-                let mut merged = saved_env;
-                for (name, then_val) in &then_env {
-                    let else_val = else_env.get(name).unwrap_or(then_val);
-                    if then_val != else_val {
-                        let ite = SymValue::Ite(
-                            Box::new(cond_val.clone()),
-                            Box::new(then_val.clone()),
-                            Box::new(else_val.clone()),
-                        );
-                        merged.insert(name.clone(), ite);
-                    } else {
-                        merged.insert(name.clone(), then_val.clone());
+                self.execute_branch(
+                    cond_val,
+                    &then_block.node,
+                    else_block.as_ref().map(|b| &b.node),
+                );
+            }
+            Stmt::While { cond, bound, body } => {
+                // An iteration runs only while the condition has held on
+                // every one before it: `looping` is that conjunction, and
+                // guards both the next test and the body.
+                let declared = self.eval_expr(&bound.node).as_const();
+                let trips = declared
+                    .unwrap_or(MAX_DYNAMIC_LOOP_UNROLL)
+                    .min(MAX_DYNAMIC_LOOP_UNROLL);
+                let mut looping = SymValue::Const(1);
+                let mut exited = false;
+                let mut ran = 0;
+                while ran < trips {
+                    let guarded = looping != SymValue::Const(1);
+                    if guarded {
+                        self.path_condition.push(looping.clone());
                     }
-                }
-                // Also merge vars that only exist in else_env
-                for (name, else_val) in &else_env {
-                    if !then_env.contains_key(name) {
-                        merged.insert(name.clone(), else_val.clone());
+                    let cond_val = self.eval_expr(&cond.node);
+                    if guarded {
+                        self.path_condition.pop();
+                    }
+                    looping = SymValue::Mul(Box::new(looping), Box::new(cond_val)).simplify();
+                    if looping.as_const() == Some(0) {
+                        exited = true;
+                        break;
                     }
+                    self.execute_branch(looping.clone(), &body.node, None);
+                    ran += 1;
+                    let carried: usize = self.env.values().map(SymValue::size).sum();
+                    if carried + looping.size() > MAX_WHILE_TERM_SIZE {
+                        break;
+                    }
+                }
+                if !exited && declared.is_none_or(|n| n > ran) {
+                    self.system.truncated_loops.push(bound.span);
                 }
-                self.env = merged;
             }
             Stmt::For {
                 var,
@@ -245,10 +361,10 @@ impl SymExecutor {
                         self.env.insert(var.node.clone(), SymValue::Const(i));
                         self.execute_block(&body.node);
                     }
-                } else {
+                } else if !self.summarize_loop(&var.node, &start_val, &end_val, &body.node) {
                     // Dynamic bound: unroll up to the declared bound
                     // Each iteration gets a path condition: i < end
-                    let bound = 64u64; // default max unroll
+                    let bound = MAX_DYNAMIC_LOOP_UNROLL;
                     if let Some(s) = start_val.as_const() {
                         for i in s..(s + bound) {
                             let iter_val = SymValue::Const(i);
@@ -269,6 +385,9 @@ impl SymExecutor {
             Stmt::Return(_) => {
                 // Return from function — handled by caller
             }
+            Stmt::Break | Stmt::Continue => {
+                // Rewritten into skip flags before execution
+            }
             Stmt::Reveal { .. } | Stmt::Seal { .. } => {
                 // Events don't produce constraints (they're output-only)
             }
@@ -290,11 +409,25 @@ impl SymExecutor {
                             Box::new(match_val.clone()),
                             Box::new(SymValue::Const(if *b { 1 } else { 0 })),
                         ),
+                        // The parser produces no string patterns.
+                        MatchPattern::Literal(Literal::Str(_)) => SymValue::Const(0),
                         MatchPattern::Wildcard => SymValue::Const(1),
                         MatchPattern::Struct { .. } => {
                             // Struct patterns are unconditional (type-checked)
                             SymValue::Const(1)
                         }
+                        MatchPattern::Variant { path, .. } => {
+                            // The variant's tag is opaque here: compare
+                            // against a fresh symbol named after it.
+                            let tag = self.fresh_var(&path.node.as_dotted());
+                            SymValue::Eq(
+                                Box::new(SymValue::FieldAccess(
+                                    Box::new(match_val.clone()),
+                                    "tag".to_string(),
+                                )),
+                                Box::new(SymValue::Var(tag)),
+                            )
+                        }
                     };
                     self.path_condition.push(cond.clone());
                     // For struct patterns, bind fields before executing body
@@ -310,6 +443,17 @@ impl SymExecutor {
                             }
                         }
                     }
+                    if let MatchPattern::Variant { path, bindings } = &arm.pattern.node {
+                        for (i, b) in bindings.iter().enumerate() {
+                            if b.node != "_" {
+                                let payload = SymValue::FieldAccess(
+                                    Box::new(match_val.clone()),
+                                    format!("{}#{}", path.node.as_dotted(), i),
+                                );
+                                self.env.insert(b.node.clone(), payload);
+                            }
+                        }
+                    }
                     self.execute_block(&arm.body.node);
                     self.path_condition.pop();
                     merged_envs.push((cond, self.env.clone()));
@@ -345,3 +489,23 @@ impl SymExecutor {
         }
     }
 }
+
+/// Whether `stmt` is a `fail(code)` call.
+/// Span of `expr` if it is a call of an assertion builtin.
+pub(crate) fn assertion_site(expr: &Spanned<Expr>) -> Option<Span> {
+    match &expr.node {
+        Expr::Call { path, .. } if ASSERTION_BUILTINS.contains(&path.node.as_dotted().as_str()) => {
+            Some(expr.span)
+        }
+        _ => None,
+    }
+}
+
+fn is_fail(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Expr(expr) => {
+            matches!(&expr.node, Expr::Call { path, .. } if path.node.as_dotted() == "fail")
+        }
+        _ => false,
+    }
+}
@@ -1,10 +1,15 @@
 use super::*;
 
+/// Elements in a digest.
+const DIGEST_WIDTH: usize = 5;
+
 impl SymExecutor {
     pub(crate) fn eval_expr(&mut self, expr: &Expr) -> SymValue {
         match expr {
             Expr::Literal(Literal::Integer(n)) => SymValue::Const(*n),
             Expr::Literal(Literal::Bool(b)) => SymValue::Const(if *b { 1 } else { 0 }),
+            // Assertion messages carry no value.
+            Expr::Literal(Literal::Str(_)) => SymValue::Const(0),
             Expr::Var(name) => {
                 self.env.get(name).cloned().unwrap_or_else(|| {
                     // Unknown variable — treat as fresh symbolic
@@ -12,6 +17,29 @@ impl SymExecutor {
                     SymValue::Var(var)
                 })
             }
+            Expr::BinOp {
+                op: op @ (BinOp::And | BinOp::Or),
+                lhs,
+                rhs,
+            } => {
+                // Branching lowering: the right side, and any assertion in
+                // it, only runs on the path where it decides the result.
+                let l = self.eval_expr(&lhs.node);
+                let taken = if *op == BinOp::And {
+                    l.clone()
+                } else {
+                    SymValue::Sub(Box::new(SymValue::Const(1)), Box::new(l.clone()))
+                };
+                self.path_condition.push(taken);
+                let r = self.eval_expr(&rhs.node);
+                self.path_condition.pop();
+                let (then_val, else_val) = if *op == BinOp::And {
+                    (r, SymValue::Const(0))
+                } else {
+                    (SymValue::Const(1), r)
+                };
+                SymValue::Ite(Box::new(l), Box::new(then_val), Box::new(else_val))
+            }
             Expr::BinOp { op, lhs, rhs } => {
                 let l = self.eval_expr(&lhs.node);
                 let r = self.eval_expr(&rhs.node);
@@ -20,8 +48,13 @@ impl SymExecutor {
                     BinOp::Mul => SymValue::Mul(Box::new(l), Box::new(r)).simplify(),
                     BinOp::Eq => SymValue::Eq(Box::new(l), Box::new(r)).simplify(),
                     BinOp::Lt => SymValue::Lt(Box::new(l), Box::new(r)),
+                    BinOp::DivMod => {
+                        // Folded on projection, so both halves stay reachable
+                        self.add_constraint(Constraint::NonZero(r.clone()));
+                        SymValue::DivMod(Box::new(l), Box::new(r), 0)
+                    }
                     _ => {
-                        // BitAnd, BitXor, DivMod, XFieldMul — leave as opaque
+                        // BitAnd, BitXor, BitOr, XFieldMul — leave as opaque
                         SymValue::Var(self.fresh_var("__binop"))
                     }
                 }
@@ -43,15 +76,26 @@ impl SymExecutor {
                 let var = self.fresh_var("__field");
                 SymValue::Var(var)
             }
-            Expr::Index { expr, .. } => {
-                let _ = self.eval_expr(&expr.node);
+            Expr::Index { expr, index } => {
+                let val = self.eval_expr(&expr.node);
+                if let Expr::Literal(Literal::Integer(i)) = index.node {
+                    if let Some(elem) = self
+                        .known_digest(&val)
+                        .and_then(|d| d.get(i as usize).cloned())
+                    {
+                        return elem;
+                    }
+                }
                 let var = self.fresh_var("__index");
                 SymValue::Var(var)
             }
-            Expr::StructInit { fields, .. } => {
+            Expr::StructInit { fields, base, .. } => {
                 for (_, val) in fields {
                     let _ = self.eval_expr(&val.node);
                 }
+                if let Some(base) = base {
+                    let _ = self.eval_expr(&base.node);
+                }
                 let var = self.fresh_var("__struct");
                 SymValue::Var(var)
             }
@@ -70,6 +114,40 @@ impl SymExecutor {
         let name = path.as_dotted();
         let func_name = path.0.last().map(|s| s.as_str()).unwrap_or("");
 
+        // Digest conversions keep the elements; `==` compares them all
+        match name.as_str() {
+            "Digest.from_fields" => {
+                let elems = args.iter().map(|a| self.eval_expr(&a.node)).collect();
+                return self.digest_value(elems);
+            }
+            "Digest.from_array" => {
+                if let Some(Expr::ArrayInit(items)) = args.first().map(|a| &a.node) {
+                    let elems = items.iter().map(|e| self.eval_expr(&e.node)).collect();
+                    return self.digest_value(elems);
+                }
+                if let Some(arg) = args.first() {
+                    return self.eval_expr(&arg.node);
+                }
+            }
+            "Digest.to_array" => {
+                if let Some(arg) = args.first() {
+                    return self.eval_expr(&arg.node);
+                }
+            }
+            "Digest.eq" if args.len() == 2 => {
+                let a = self.eval_expr(&args[0].node);
+                let b = self.eval_expr(&args[1].node);
+                let (a, b) = (self.digest_elements(&a), self.digest_elements(&b));
+                return a
+                    .into_iter()
+                    .zip(b)
+                    .map(|(x, y)| SymValue::Eq(Box::new(x), Box::new(y)).simplify())
+                    .reduce(|acc, eq| SymValue::Mul(Box::new(acc), Box::new(eq)).simplify())
+                    .unwrap_or(SymValue::Const(1));
+            }
+            _ => {}
+        }
+
         // Handle builtins
         match func_name {
             "pub_read" | "read" => return self.fresh_pub_input(),
@@ -78,11 +156,8 @@ impl SymExecutor {
                 return self.fresh_pub_input();
             }
             "pub_read5" | "read5" => {
-                for _ in 0..5 {
-                    self.fresh_pub_input();
-                }
-                let var = self.fresh_var("__digest");
-                return SymValue::Var(var);
+                let elems = (0..DIGEST_WIDTH).map(|_| self.fresh_pub_input()).collect();
+                return self.digest_value(elems);
             }
             "pub_write" | "write" => {
                 if let Some(arg) = args.first() {
@@ -100,23 +175,41 @@ impl SymExecutor {
                 return SymValue::Var(var);
             }
             "divine5" => {
-                for _ in 0..5 {
-                    self.fresh_divine();
-                }
-                let var = self.fresh_var("__divine5");
-                return SymValue::Var(var);
+                let elems = (0..DIGEST_WIDTH).map(|_| self.fresh_divine()).collect();
+                return self.digest_value(elems);
             }
             "hash" | "tip5" => {
                 let inputs: Vec<SymValue> = args.iter().map(|a| self.eval_expr(&a.node)).collect();
                 return SymValue::Hash(inputs, 0);
             }
-            "assert" => {
+            "assert" | "debug_assert" => {
                 if let Some(arg) = args.first() {
+                    // `assert(a == b)` on digests compares element-wise
+                    if let Expr::Call { path, args, .. } = &arg.node {
+                        if path.node.as_dotted() == "Digest.eq" && args.len() == 2 {
+                            self.assert_digests_equal(&args[0].node, &args[1].node);
+                            return SymValue::Const(0);
+                        }
+                    }
                     let val = self.eval_expr(&arg.node);
                     self.add_constraint(Constraint::AssertTrue(val));
                 }
                 return SymValue::Const(0);
             }
+            "assume" => {
+                let (args, reason) = split_assumption_reason("assume", args);
+                if let Some(arg) = args.first() {
+                    let val = self.eval_expr(&arg.node);
+                    let reason = reason.map(str::to_string);
+                    self.add_assumption(Constraint::AssertTrue(val), false, reason);
+                }
+                return SymValue::Const(0);
+            }
+            "fail" => {
+                // Reaching `fail` is an assertion that cannot hold.
+                self.add_constraint(Constraint::AssertTrue(SymValue::Const(0)));
+                return SymValue::Const(0);
+            }
             "assert_eq" | "eq" => {
                 if args.len() >= 2 {
                     let a = self.eval_expr(&args[0].node);
@@ -126,11 +219,8 @@ impl SymExecutor {
                 return SymValue::Const(0);
             }
             "assert_digest" | "digest" => {
-                // Digest equality: 5-element vector comparison
                 if args.len() >= 2 {
-                    let a = self.eval_expr(&args[0].node);
-                    let b = self.eval_expr(&args[1].node);
-                    self.add_constraint(Constraint::Equal(a, b));
+                    self.assert_digests_equal(&args[0].node, &args[1].node);
                 }
                 return SymValue::Const(0);
             }
@@ -157,6 +247,24 @@ impl SymExecutor {
                 }
                 return SymValue::Const(0);
             }
+            // A sum that must not overflow is the plain sum, checked.
+            // The wrapping and checked forms stay opaque.
+            "u32_assert_add" if args.len() >= 2 => {
+                let a = self.eval_expr(&args[0].node);
+                let b = self.eval_expr(&args[1].node);
+                let sum = SymValue::Add(Box::new(a), Box::new(b)).simplify();
+                self.add_constraint(Constraint::RangeU32(sum.clone()));
+                return sum;
+            }
+            "field_assert_add" if args.len() >= 2 => {
+                let a = self.eval_expr(&args[0].node);
+                let b = self.eval_expr(&args[1].node);
+                let sum = SymValue::Add(Box::new(a.clone()), Box::new(b)).simplify();
+                let wrapped = SymValue::Lt(Box::new(sum.clone()), Box::new(a));
+                let fits = SymValue::Eq(Box::new(wrapped), Box::new(SymValue::Const(0)));
+                self.add_constraint(Constraint::AssertTrue(fits.simplify()));
+                return sum;
+            }
             "neg" => {
                 if let Some(arg) = args.first() {
                     let val = self.eval_expr(&arg.node);
@@ -164,6 +272,30 @@ impl SymExecutor {
                 }
                 return SymValue::Const(0);
             }
+            "pow" => {
+                if args.len() >= 2 {
+                    let base = self.eval_expr(&args[0].node);
+                    let exp = self.eval_expr(&args[1].node);
+                    return SymValue::Pow(Box::new(base), Box::new(exp)).simplify();
+                }
+                return SymValue::Const(0);
+            }
+            "log2" => {
+                if let Some(arg) = args.first() {
+                    let val = self.eval_expr(&arg.node);
+                    self.add_constraint(Constraint::NonZero(val.clone()));
+                    return SymValue::Log2(Box::new(val)).simplify();
+                }
+                return SymValue::Const(0);
+            }
+            "inverse" => {
+                if let Some(arg) = args.first() {
+                    let val = self.eval_expr(&arg.node);
+                    self.add_constraint(Constraint::NonZero(val.clone()));
+                    return SymValue::Inv(Box::new(val));
+                }
+                return SymValue::Const(0);
+            }
             "inv" => {
                 if let Some(arg) = args.first() {
                     let val = self.eval_expr(&arg.node);
@@ -184,6 +316,9 @@ impl SymExecutor {
                 .or_else(|| self.functions.get(func_name).cloned());
 
             if let Some(func) = func {
+                if let Some(value) = self.call_summary(&func, args) {
+                    return value;
+                }
                 if let Some(ref body) = func.body {
                     self.call_depth += 1;
                     let saved_env = self.env.clone();
@@ -193,12 +328,15 @@ impl SymExecutor {
                         let val = self.eval_expr(&arg.node);
                         self.env.insert(param.name.node.clone(), val);
                     }
+                    let saved_fn = std::mem::replace(&mut self.current_fn, func.name.node.clone());
+                    self.assume_attributes(&func);
 
                     // Execute function body
                     self.execute_block(&body.node);
 
                     // Restore environment (except new constraints are kept)
                     self.env = saved_env;
+                    self.current_fn = saved_fn;
                     self.call_depth -= 1;
                 }
             }
@@ -209,12 +347,68 @@ impl SymExecutor {
         SymValue::Var(var)
     }
 
+    /// A digest made of `elems`, standing as one fresh variable.
+    pub(crate) fn digest_value(&mut self, elems: Vec<SymValue>) -> SymValue {
+        let var = self.fresh_var("__digest");
+        self.digests.insert(var.to_string(), elems);
+        SymValue::Var(var)
+    }
+
+    /// The elements of a digest whose elements are known: one built by
+    /// `digest_value`, or a hash output.
+    pub(crate) fn known_digest(&self, val: &SymValue) -> Option<Vec<SymValue>> {
+        match val {
+            SymValue::Hash(inputs, _) => Some(
+                (0..DIGEST_WIDTH)
+                    .map(|i| SymValue::Hash(inputs.clone(), i))
+                    .collect(),
+            ),
+            SymValue::Var(var) => self.digests.get(&var.to_string()).cloned(),
+            _ => None,
+        }
+    }
+
+    /// The elements of a digest. A digest whose elements are unknown,
+    /// such as a parameter, gets a fresh variable per element, once.
+    pub(crate) fn digest_elements(&mut self, val: &SymValue) -> Vec<SymValue> {
+        if let Some(elems) = self.known_digest(val) {
+            return elems;
+        }
+        let name = match val {
+            SymValue::Var(var) => var.to_string(),
+            _ => "__digest".to_string(),
+        };
+        let elems: Vec<SymValue> = (0..DIGEST_WIDTH)
+            .map(|i| SymValue::Var(self.fresh_var(&format!("{}_{}", name, i))))
+            .collect();
+        if let SymValue::Var(var) = val {
+            self.digests.insert(var.to_string(), elems.clone());
+        }
+        elems
+    }
+
+    /// Constrain two digest expressions to be equal element-wise.
+    fn assert_digests_equal(&mut self, a: &Expr, b: &Expr) {
+        let a = self.eval_expr(a);
+        let b = self.eval_expr(b);
+        let (a, b) = (self.digest_elements(&a), self.digest_elements(&b));
+        self.add_constraint(Constraint::DigestEqual(a, b));
+    }
+
     /// Project element `i` from a tuple-like symbolic value.
     pub(crate) fn project_tuple(&mut self, val: &SymValue, i: usize) -> SymValue {
+        // Destructuring a digest yields its elements
+        if let Some(elem) = self.known_digest(val).and_then(|d| d.get(i).cloned()) {
+            return elem;
+        }
         // If projecting from a hash, preserve the Hash origin with the index
         if let SymValue::Hash(inputs, _) = val {
             return SymValue::Hash(inputs.clone(), i);
         }
+        // Quotient and remainder of `/%` project to their own values
+        if let SymValue::DivMod(a, b, _) = val {
+            return SymValue::DivMod(a.clone(), b.clone(), i).simplify();
+        }
         let var = self.fresh_var(&format!("__proj_{}", i));
         SymValue::Var(var)
     }
@@ -0,0 +1,255 @@
+//! Closed forms for loops with symbolic trip counts.
+//!
+//! A loop whose bounds are not constants cannot be unrolled exactly. When
+//! its body only updates variables by affine recurrences, `x = a * x + b`
+//! with `a` and `b` the same on every iteration, or `x = x + i` in the
+//! loop variable `i`, the value after `n = end - start` iterations has a
+//! closed form, and the loop is replaced by it. Bodies that assert, read
+//! input, or write output are not summarized.
+
+use super::*;
+
+/// Prefix of the placeholders standing for loop-carried values while
+/// the body is summarized. They never escape the summary.
+const LOOP_PREFIX: &str = "__loop_";
+
+impl SymExecutor {
+    /// Bind the variables `body` updates to their values after the loop
+    /// `for var in start..end`. Returns false, with nothing changed, if
+    /// the body is not a set of affine recurrences.
+    pub(crate) fn summarize_loop(
+        &mut self,
+        var: &str,
+        start: &SymValue,
+        end: &SymValue,
+        body: &Block,
+    ) -> bool {
+        let mut assigned = Vec::new();
+        if !assigned_vars(body, &mut assigned) {
+            return false;
+        }
+        let carried: Vec<String> = assigned
+            .into_iter()
+            .filter(|name| self.env.contains_key(name))
+            .collect();
+
+        let saved_env = self.env.clone();
+        let saved_versions = self.versions.clone();
+        let saved_system = self.system.clone();
+        let saved_digests = self.digests.clone();
+        let saved_counters = (self.divine_counter, self.pub_input_counter);
+
+        let placeholder = |name: &str| {
+            SymValue::Var(SymVar {
+                name: format!("{}{}", LOOP_PREFIX, name),
+                version: 0,
+            })
+        };
+        for name in &carried {
+            self.env.insert(name.clone(), placeholder(name));
+        }
+        let index = placeholder(var);
+        self.env.insert(var.to_string(), index.clone());
+        self.execute_block(body);
+
+        let pure = self.system.constraints.len() == saved_system.constraints.len()
+            && self.system.assumptions.len() == saved_system.assumptions.len()
+            && self.system.pub_outputs.len() == saved_system.pub_outputs.len()
+            && (self.divine_counter, self.pub_input_counter) == saved_counters;
+        let updates: Option<Vec<(String, SymValue, SymValue)>> = carried
+            .iter()
+            .map(|name| {
+                let (a, b) = affine(&self.env[name], &placeholder(name))?;
+                let known = |v: &SymValue| defined_before(v, &saved_versions);
+                // `b` may be the loop variable itself when `a` is 1.
+                let b_ok = known(&b) || (b == index && a == SymValue::Const(1));
+                (known(&a) && b_ok).then(|| (name.clone(), a, b))
+            })
+            .collect();
+
+        self.env = saved_env;
+        self.versions = saved_versions;
+        self.system = saved_system;
+        self.digests = saved_digests;
+        let Some(updates) = updates.filter(|_| pure) else {
+            return false;
+        };
+
+        let n = SymValue::Sub(Box::new(end.clone()), Box::new(start.clone())).simplify();
+        let mut closed = Vec::new();
+        for (name, a, b) in updates {
+            let x = &self.env[&name];
+            let value = if b == index {
+                SymValue::Add(Box::new(x.clone()), Box::new(sum_of_range(start, &n))).simplify()
+            } else {
+                let Some(value) = closed_form(x, &a, &b, &n) else {
+                    return false;
+                };
+                value
+            };
+            closed.push((name, value));
+        }
+        for (name, value) in closed {
+            let _var = self.fresh_var(&name);
+            self.env.insert(name, value);
+        }
+        true
+    }
+}
+
+/// Names assigned anywhere in `block`, or false if the block declares
+/// a variable it also assigns, which could shadow an outer one.
+fn assigned_vars(block: &Block, out: &mut Vec<String>) -> bool {
+    let mut declared = Vec::new();
+    if !collect_assigned(block, out, &mut declared) {
+        return false;
+    }
+    !declared.iter().any(|name| out.contains(name))
+}
+
+fn collect_assigned(block: &Block, out: &mut Vec<String>, declared: &mut Vec<String>) -> bool {
+    for stmt in &block.stmts {
+        match &stmt.node {
+            Stmt::Let { pattern, .. } => match pattern {
+                Pattern::Name(name) => declared.push(name.node.clone()),
+                Pattern::Tuple(names) => declared.extend(names.iter().map(|n| n.node.clone())),
+            },
+            Stmt::Assign { place, .. } => match &place.node {
+                Place::Var(name) => {
+                    if !out.contains(name) {
+                        out.push(name.clone());
+                    }
+                }
+                _ => return false,
+            },
+            Stmt::TupleAssign { .. }
+            | Stmt::Return(_)
+            | Stmt::Break
+            | Stmt::Continue
+            | Stmt::Asm { .. } => return false,
+            Stmt::If {
+                then_block,
+                else_block,
+                ..
+            } => {
+                if !collect_assigned(&then_block.node, out, declared) {
+                    return false;
+                }
+                if let Some(else_blk) = else_block {
+                    if !collect_assigned(&else_blk.node, out, declared) {
+                        return false;
+                    }
+                }
+            }
+            Stmt::For { var, body, .. } => {
+                declared.push(var.node.clone());
+                if !collect_assigned(&body.node, out, declared) {
+                    return false;
+                }
+            }
+            Stmt::While { body, .. } => {
+                if !collect_assigned(&body.node, out, declared) {
+                    return false;
+                }
+            }
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    if !collect_assigned(&arm.body.node, out, declared) {
+                        return false;
+                    }
+                }
+            }
+            Stmt::Expr(_) | Stmt::Reveal { .. } | Stmt::Seal { .. } => {}
+        }
+    }
+    true
+}
+
+/// Split `v` as `a * x + b` for the placeholder `x`. Coefficients may
+/// still mention `x`; the caller checks they do not.
+fn affine(v: &SymValue, x: &SymValue) -> Option<(SymValue, SymValue)> {
+    let scaled = |v: &SymValue| match v {
+        _ if v == x => Some(SymValue::Const(1)),
+        SymValue::Mul(l, r) if **l == *x => Some((**r).clone()),
+        SymValue::Mul(l, r) if **r == *x => Some((**l).clone()),
+        _ => None,
+    };
+    if let Some(a) = scaled(v) {
+        return Some((a, SymValue::Const(0)));
+    }
+    match v {
+        SymValue::Add(l, r) => match (scaled(l), scaled(r)) {
+            (Some(a), _) => Some((a, (**r).clone())),
+            (_, Some(a)) => Some((a, (**l).clone())),
+            _ => None,
+        },
+        SymValue::Sub(l, r) => Some((scaled(l)?, SymValue::Neg(r.clone()))),
+        _ => None,
+    }
+}
+
+/// Whether every variable of `v` existed before the body ran: values
+/// the body created, placeholders included, differ per iteration.
+fn defined_before(v: &SymValue, versions: &BTreeMap<String, u32>) -> bool {
+    match v {
+        SymValue::Var(var) => versions.get(&var.name).is_some_and(|&n| var.version < n),
+        SymValue::Add(a, b)
+        | SymValue::Mul(a, b)
+        | SymValue::Sub(a, b)
+        | SymValue::Eq(a, b)
+        | SymValue::Lt(a, b)
+        | SymValue::DivMod(a, b, _)
+        | SymValue::Pow(a, b) => defined_before(a, versions) && defined_before(b, versions),
+        SymValue::Neg(a) | SymValue::Inv(a) | SymValue::Log2(a) => defined_before(a, versions),
+        SymValue::FieldAccess(inner, _) => defined_before(inner, versions),
+        SymValue::Ite(c, t, e) => {
+            defined_before(c, versions)
+                && defined_before(t, versions)
+                && defined_before(e, versions)
+        }
+        SymValue::Hash(inputs, _) => inputs.iter().all(|v| defined_before(v, versions)),
+        SymValue::Const(_) => true,
+    }
+}
+
+/// `start + (start + 1) + ... + (start + n - 1)`, that is
+/// `n * start + n * (n - 1) / 2`.
+fn sum_of_range(start: &SymValue, n: &SymValue) -> SymValue {
+    let half = SymValue::Const(GOLDILOCKS_P.div_ceil(2));
+    let pairs = SymValue::Mul(
+        Box::new(n.clone()),
+        Box::new(SymValue::Sub(
+            Box::new(n.clone()),
+            Box::new(SymValue::Const(1)),
+        )),
+    );
+    SymValue::Add(
+        Box::new(SymValue::Mul(Box::new(n.clone()), Box::new(start.clone()))),
+        Box::new(SymValue::Mul(Box::new(pairs), Box::new(half))),
+    )
+    .simplify()
+}
+
+/// `x` after `n` steps of `x = a * x + b`: `x + n * b` when `a` is 1,
+/// else `a^n * x + b * (a^n - 1) / (a - 1)`. The division needs `a`
+/// constant, so a symbolic `a` is only summarized with no `b`.
+fn closed_form(x: &SymValue, a: &SymValue, b: &SymValue, n: &SymValue) -> Option<SymValue> {
+    if *a == SymValue::Const(1) {
+        let step = SymValue::Mul(Box::new(n.clone()), Box::new(b.clone()));
+        return Some(SymValue::Add(Box::new(x.clone()), Box::new(step)).simplify());
+    }
+    let power = SymValue::Pow(Box::new(a.clone()), Box::new(n.clone()));
+    let scaled = SymValue::Mul(Box::new(power.clone()), Box::new(x.clone()));
+    if *b == SymValue::Const(0) {
+        return Some(scaled.simplify());
+    }
+    let inverse = Goldilocks::from_u64(a.as_const()?)
+        .sub(Goldilocks::ONE)
+        .inv()?;
+    let geometric = SymValue::Mul(
+        Box::new(SymValue::Sub(Box::new(power), Box::new(SymValue::Const(1)))),
+        Box::new(SymValue::Const(inverse.to_u64())),
+    );
+    let offset = SymValue::Mul(Box::new(b.clone()), Box::new(geometric));
+    Some(SymValue::Add(Box::new(scaled), Box::new(offset)).simplify())
+}
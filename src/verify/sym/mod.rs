@@ -9,10 +9,15 @@
 //!
 //! The symbolic engine:
 //! 1. Assigns a symbolic variable to each `let` binding
-//! 2. Tracks constraints from `assert`, `assert_eq`, `assert_digest`
+//! 2. Tracks constraints from `assert`, `assert_eq`, `assert_digest`,
+//!    and nonzero obligations for `/%` divisors and `log2`/`inverse` arguments,
+//!    and, apart from them, the assumptions of `assume` and `#[assume]`
 //! 3. Encodes `if/else` as path conditions
-//! 4. Unrolls bounded `for` loops up to their bound
-//! 5. Inlines function calls (no recursion → always terminates)
+//! 4. Unrolls bounded `for` loops up to their bound, or replaces loops
+//!    with symbolic trip counts by closed forms of their recurrences
+//! 5. Summarizes each called function once and applies the summary at
+//!    its call sites, inlining those with I/O (no recursion → always
+//!    terminates)
 //! 6. Produces a `ConstraintSystem` that can be checked by:
 //!    - The algebraic solver (polynomial identity testing)
 //!    - A bounded model checker (enumerate concrete values)
@@ -21,17 +26,21 @@
 use std::collections::BTreeMap;
 
 use crate::ast::*;
-use crate::span::Spanned;
+use crate::field::{Goldilocks, PrimeField};
+use crate::span::{Span, Spanned};
 
 /// The prime modulus for the Goldilocks field.
 pub const GOLDILOCKS_P: u64 = crate::field::goldilocks::MODULUS;
 
 mod executor;
 mod expr;
+mod loops;
+mod summary;
 #[cfg(test)]
 mod tests;
 
 pub use executor::*;
+use summary::FnSummary;
 
 // ─── Symbolic Values ───────────────────────────────────────────────
 
@@ -56,23 +65,21 @@ pub enum SymValue {
     Eq(Box<SymValue>, Box<SymValue>),
     /// Less-than test: 1 if a < b, else 0 (on canonical representatives).
     Lt(Box<SymValue>, Box<SymValue>),
+    /// U32 division `a /% b`: the quotient (index 0) or remainder (index 1).
+    DivMod(Box<SymValue>, Box<SymValue>, usize),
+    /// Exponentiation: a^b (mod p) for a U32 exponent b.
+    Pow(Box<SymValue>, Box<SymValue>),
+    /// Floor of log base 2 of a nonzero U32.
+    Log2(Box<SymValue>),
     /// Hash output: hash(inputs)[index]. Treated as opaque.
     Hash(Vec<SymValue>, usize),
-    /// A divine (nondeterministic) input. Each occurrence is unique.
-    Divine(u32),
     /// Struct field access: value.field_name.
     FieldAccess(Box<SymValue>, String),
-    /// Public input. Sequential read index.
-    PubInput(u32),
     /// If-then-else: if cond then a else b.
     Ite(Box<SymValue>, Box<SymValue>, Box<SymValue>),
 }
 
 impl SymValue {
-    pub fn is_const(&self) -> bool {
-        matches!(self, SymValue::Const(_))
-    }
-
     pub fn as_const(&self) -> Option<u64> {
         match self {
             SymValue::Const(v) => Some(*v),
@@ -80,6 +87,26 @@ impl SymValue {
         }
     }
 
+    /// Number of nodes in the expression tree.
+    pub fn size(&self) -> usize {
+        1 + match self {
+            SymValue::Const(_) | SymValue::Var(_) => 0,
+            SymValue::Neg(a)
+            | SymValue::Inv(a)
+            | SymValue::Log2(a)
+            | SymValue::FieldAccess(a, _) => a.size(),
+            SymValue::Add(a, b)
+            | SymValue::Mul(a, b)
+            | SymValue::Sub(a, b)
+            | SymValue::Eq(a, b)
+            | SymValue::Lt(a, b)
+            | SymValue::DivMod(a, b, _)
+            | SymValue::Pow(a, b) => a.size() + b.size(),
+            SymValue::Hash(inputs, _) => inputs.iter().map(SymValue::size).sum(),
+            SymValue::Ite(c, t, e) => c.size() + t.size() + e.size(),
+        }
+    }
+
     /// Check if this value contains a Hash node or opaque intrinsic output.
     ///
     /// Opaque values (hashes, intrinsic calls, tuple projections) cannot be
@@ -97,25 +124,15 @@ impl SymValue {
             | SymValue::Mul(a, b)
             | SymValue::Sub(a, b)
             | SymValue::Eq(a, b)
-            | SymValue::Lt(a, b) => a.contains_opaque() || b.contains_opaque(),
-            SymValue::Neg(a) | SymValue::Inv(a) => a.contains_opaque(),
+            | SymValue::Lt(a, b)
+            | SymValue::DivMod(a, b, _)
+            | SymValue::Pow(a, b) => a.contains_opaque() || b.contains_opaque(),
+            SymValue::Neg(a) | SymValue::Inv(a) | SymValue::Log2(a) => a.contains_opaque(),
             SymValue::Ite(c, t, e) => {
                 c.contains_opaque() || t.contains_opaque() || e.contains_opaque()
             }
             SymValue::FieldAccess(inner, _) => inner.contains_opaque(),
-            SymValue::Const(_) | SymValue::Divine(_) | SymValue::PubInput(_) => false,
-        }
-    }
-
-    /// Check if this value is an external input (pub_read or divine).
-    /// Range checks on external inputs are input preconditions, not bugs.
-    pub fn is_external_input(&self) -> bool {
-        match self {
-            SymValue::Var(var) => {
-                var.name.starts_with("pub_in_") || var.name.starts_with("divine_")
-            }
-            SymValue::PubInput(_) | SymValue::Divine(_) => true,
-            _ => false,
+            SymValue::Const(_) => false,
         }
     }
 
@@ -182,6 +199,54 @@ impl SymValue {
                     }
                 }
             }
+            SymValue::DivMod(a, b, index) => {
+                let a = a.simplify();
+                let b = b.simplify();
+                match (&a, &b) {
+                    (SymValue::Const(n), SymValue::Const(d))
+                        if *d != 0 && *n <= u32::MAX as u64 && *d <= u32::MAX as u64 =>
+                    {
+                        SymValue::Const(if *index == 0 { n / d } else { n % d })
+                    }
+                    (_, SymValue::Const(1)) if *index == 0 => a,
+                    (_, SymValue::Const(1)) => SymValue::Const(0),
+                    _ => SymValue::DivMod(Box::new(a), Box::new(b), *index),
+                }
+            }
+            SymValue::Pow(a, b) => {
+                let a = a.simplify();
+                let b = b.simplify();
+                match (&a, &b) {
+                    (_, SymValue::Const(0)) => SymValue::Const(1),
+                    (_, SymValue::Const(1)) => a,
+                    (SymValue::Const(x), SymValue::Const(e)) => {
+                        SymValue::Const(Goldilocks::from_u64(*x).pow(*e).to_u64())
+                    }
+                    _ => SymValue::Pow(Box::new(a), Box::new(b)),
+                }
+            }
+            SymValue::Log2(a) => {
+                let a = a.simplify();
+                match &a {
+                    SymValue::Const(v) if *v != 0 && *v <= u32::MAX as u64 => {
+                        SymValue::Const(63 - v.leading_zeros() as u64)
+                    }
+                    _ => SymValue::Log2(Box::new(a)),
+                }
+            }
+            SymValue::Lt(a, b) => {
+                let a = a.simplify();
+                let b = b.simplify();
+                match (&a, &b) {
+                    (SymValue::Const(x), SymValue::Const(y)) => SymValue::Const((x < y) as u64),
+                    _ => SymValue::Lt(Box::new(a), Box::new(b)),
+                }
+            }
+            SymValue::Ite(c, t, e) => match c.simplify() {
+                SymValue::Const(0) => e.simplify(),
+                SymValue::Const(_) => t.simplify(),
+                c => SymValue::Ite(Box::new(c), Box::new(t.simplify()), Box::new(e.simplify())),
+            },
             _ => self.clone(),
         }
     }
@@ -220,6 +285,8 @@ pub enum Constraint {
     RangeU32(SymValue),
     /// Digest equality: 5-element vector comparison
     DigestEqual(Vec<SymValue>, Vec<SymValue>),
+    /// a != 0 (divisor of `/%`, argument of `log2` or `inverse`) — the VM faults on zero
+    NonZero(SymValue),
 }
 
 impl Constraint {
@@ -236,6 +303,7 @@ impl Constraint {
                 }
             }
             Constraint::DigestEqual(a, b) => a == b,
+            Constraint::NonZero(v) => matches!(v, SymValue::Const(c) if c % GOLDILOCKS_P != 0),
             Constraint::Conditional(cond, inner) => {
                 matches!(cond, SymValue::Const(0)) || inner.is_trivial()
             }
@@ -248,6 +316,7 @@ impl Constraint {
             Constraint::Equal(SymValue::Const(a), SymValue::Const(b)) => a != b,
             Constraint::AssertTrue(SymValue::Const(0)) => true,
             Constraint::RangeU32(SymValue::Const(c)) => *c > u32::MAX as u64,
+            Constraint::NonZero(SymValue::Const(c)) => c % GOLDILOCKS_P == 0,
             _ => false,
         }
     }
@@ -261,7 +330,7 @@ impl Constraint {
     pub fn is_hash_dependent(&self) -> bool {
         match self {
             Constraint::Equal(a, b) => a.contains_opaque() || b.contains_opaque(),
-            Constraint::AssertTrue(v) => v.contains_opaque(),
+            Constraint::AssertTrue(v) | Constraint::NonZero(v) => v.contains_opaque(),
             Constraint::Conditional(_, inner) => inner.is_hash_dependent(),
             Constraint::DigestEqual(a, b) => {
                 a.iter().any(|v| v.contains_opaque()) || b.iter().any(|v| v.contains_opaque())
@@ -274,6 +343,20 @@ impl Constraint {
     }
 }
 
+/// A fact the verifier takes on trust, from `assume(cond)` or a
+/// function's `#[assume(pred)]`. No runtime code checks it: the solver
+/// only uses it to rule out inputs.
+#[derive(Clone, Debug)]
+pub struct Assumption {
+    pub constraint: Constraint,
+    /// The function whose body or attribute states it.
+    pub function: String,
+    /// Stated as `#[assume(pred)]` rather than `assume(cond)`.
+    pub from_attribute: bool,
+    /// Reason given as `assume(cond, "reason")`.
+    pub reason: Option<String>,
+}
+
 // ─── Constraint System ─────────────────────────────────────────────
 
 /// The complete constraint system for a program or function.
@@ -281,6 +364,11 @@ impl Constraint {
 pub struct ConstraintSystem {
     /// All constraints that must hold.
     pub constraints: Vec<Constraint>,
+    /// Span of the assertion call behind each constraint, by index;
+    /// `None` for implicit ones such as range checks.
+    pub origins: Vec<Option<Span>>,
+    /// Facts taken on trust, kept apart from the constraints.
+    pub assumptions: Vec<Assumption>,
     /// Symbolic variables introduced (name → latest version).
     pub variables: BTreeMap<String, u32>,
     /// Public inputs read (in order).
@@ -291,20 +379,40 @@ pub struct ConstraintSystem {
     pub divine_inputs: Vec<SymVar>,
     /// Number of unique symbolic variables.
     pub num_variables: u32,
+    /// Bounds of `while` loops unrolled fewer times than they allow; the
+    /// iterations past the cut add no constraints.
+    pub truncated_loops: Vec<Span>,
 }
 
 impl ConstraintSystem {
     pub fn new() -> Self {
         Self {
             constraints: Vec::new(),
+            origins: Vec::new(),
+            assumptions: Vec::new(),
             variables: BTreeMap::new(),
             pub_inputs: Vec::new(),
             pub_outputs: Vec::new(),
             divine_inputs: Vec::new(),
             num_variables: 0,
+            truncated_loops: Vec::new(),
         }
     }
 
+    /// Append `other`'s constraints, assumptions, and inputs and outputs,
+    /// as when checking several functions at once.
+    pub fn merge(&mut self, other: ConstraintSystem) {
+        self.constraints.extend(other.constraints);
+        self.origins.extend(other.origins);
+        self.assumptions.extend(other.assumptions);
+        self.num_variables += other.num_variables;
+        self.variables.extend(other.variables);
+        self.pub_inputs.extend(other.pub_inputs);
+        self.pub_outputs.extend(other.pub_outputs);
+        self.divine_inputs.extend(other.divine_inputs);
+        self.truncated_loops.extend(other.truncated_loops);
+    }
+
     /// Count of non-trivial constraints.
     pub fn active_constraints(&self) -> usize {
         self.constraints.iter().filter(|c| !c.is_trivial()).count()
@@ -320,11 +428,17 @@ impl ConstraintSystem {
 
     /// Summary for display.
     pub fn summary(&self) -> String {
+        let assumptions = if self.assumptions.is_empty() {
+            String::new()
+        } else {
+            format!(", Assumptions: {}", self.assumptions.len())
+        };
         format!(
-            "Variables: {}, Constraints: {} ({} active), Inputs: {} pub + {} divine, Outputs: {}",
+            "Variables: {}, Constraints: {} ({} active){}, Inputs: {} pub + {} divine, Outputs: {}",
             self.num_variables,
             self.constraints.len(),
             self.active_constraints(),
+            assumptions,
             self.pub_inputs.len(),
             self.divine_inputs.len(),
             self.pub_outputs.len(),
@@ -358,69 +472,3 @@ pub fn analyze_all(file: &File) -> Vec<(String, ConstraintSystem)> {
     }
     results
 }
-
-/// Verification result for a function or program.
-#[derive(Clone, Debug)]
-pub struct VerificationResult {
-    /// The function or program name.
-    pub name: String,
-    /// Total constraints.
-    pub total_constraints: usize,
-    /// Active (non-trivial) constraints.
-    pub active_constraints: usize,
-    /// Trivially violated constraints (definite bugs).
-    pub violated: Vec<String>,
-    /// Redundant (trivially satisfied) constraints.
-    pub redundant_count: usize,
-    /// Summary of the constraint system.
-    pub system_summary: String,
-}
-
-impl VerificationResult {
-    pub fn is_safe(&self) -> bool {
-        self.violated.is_empty()
-    }
-
-    pub fn format_report(&self) -> String {
-        let mut report = String::new();
-        report.push_str(&format!("Verification: {}\n", self.name));
-        report.push_str(&format!("  {}\n", self.system_summary));
-        report.push_str(&format!(
-            "  Constraints: {} total, {} active, {} redundant\n",
-            self.total_constraints, self.active_constraints, self.redundant_count,
-        ));
-        if self.violated.is_empty() {
-            report.push_str("  Status: SAFE (no trivially violated assertions)\n");
-        } else {
-            report.push_str(&format!(
-                "  Status: VIOLATED ({} assertion(s) always fail)\n",
-                self.violated.len()
-            ));
-            for v in &self.violated {
-                report.push_str(&format!("    - {}\n", v));
-            }
-        }
-        report
-    }
-}
-
-/// Verify a file: analyze constraints and check for violations.
-/// For programs, checks `main`. For modules, checks all functions.
-pub fn verify_file(file: &File) -> VerificationResult {
-    let system = analyze(file);
-    let violated: Vec<String> = system
-        .violated_constraints()
-        .iter()
-        .map(|c| format!("{:?}", c))
-        .collect();
-    let redundant_count = system.constraints.iter().filter(|c| c.is_trivial()).count();
-
-    VerificationResult {
-        name: file.name.node.clone(),
-        total_constraints: system.constraints.len(),
-        active_constraints: system.active_constraints(),
-        violated,
-        redundant_count,
-        system_summary: system.summary(),
-    }
-}
@@ -0,0 +1,252 @@
+//! Function summaries for inter-procedural symbolic execution.
+//!
+//! A called function is executed once, with placeholder parameters, into
+//! a summary: the obligations and assumptions its body adds and the value
+//! it returns, all in terms of the placeholders. Each call site then
+//! substitutes its arguments instead of executing the body again, so deep
+//! helper nesting costs one pass per function rather than one per call.
+//!
+//! A function that reads input, writes output, or builds digests from
+//! unknown values is inlined instead: those effects must happen afresh
+//! at every call.
+
+use std::collections::HashMap;
+
+use super::*;
+use crate::ast::visit::{walk, Visitor};
+
+/// A function's effect on the constraint system, over placeholder
+/// parameters.
+#[derive(Clone, Debug)]
+pub(crate) struct FnSummary {
+    params: Vec<SymVar>,
+    /// Constraints the body adds, with their assertion sites.
+    obligations: Vec<(Constraint, Option<Span>)>,
+    assumptions: Vec<Assumption>,
+    /// Loops the body cut short.
+    truncated_loops: Vec<Span>,
+    /// The returned value, if the body has a single exit.
+    result: Option<SymValue>,
+    /// Variables the body creates, renamed fresh at every call.
+    locals: Vec<SymVar>,
+}
+
+impl SymExecutor {
+    /// Apply the summary of `func` to `args`, or return `None` if the
+    /// function has to be inlined.
+    pub(crate) fn call_summary(
+        &mut self,
+        func: &FnDef,
+        args: &[Spanned<Expr>],
+    ) -> Option<SymValue> {
+        let name = &func.name.node;
+        if !self.summaries.contains_key(name) {
+            let summary = self.summarize(func);
+            self.summaries.insert(name.clone(), summary);
+        }
+        let summary = self.summaries.get(name).cloned().flatten()?;
+
+        let mut subst: HashMap<SymVar, SymValue> = HashMap::new();
+        for (param, arg) in summary.params.iter().zip(args) {
+            let val = self.eval_expr(&arg.node);
+            subst.insert(param.clone(), val);
+        }
+        for local in &summary.locals {
+            let fresh = self.fresh_var(&local.name);
+            subst.insert(local.clone(), SymValue::Var(fresh));
+        }
+        for (c, origin) in &summary.obligations {
+            let c = self.under_path_condition(substitute_constraint(c, &subst));
+            self.system.constraints.push(c);
+            self.system.origins.push(origin.or(self.assertion));
+        }
+        for a in &summary.assumptions {
+            let constraint =
+                self.under_path_condition(substitute_constraint(&a.constraint, &subst));
+            self.system.assumptions.push(Assumption {
+                constraint,
+                ..a.clone()
+            });
+        }
+        self.system
+            .truncated_loops
+            .extend(summary.truncated_loops.iter().copied());
+        Some(match &summary.result {
+            Some(result) => substitute(result, &subst),
+            None => SymValue::Var(self.fresh_var(&format!("__call_{}", name))),
+        })
+    }
+
+    /// Execute `func` once over placeholder parameters. Leaves the
+    /// executor as it was.
+    fn summarize(&mut self, func: &FnDef) -> Option<FnSummary> {
+        let body = func.body.as_ref()?;
+        if self.call_depth >= self.max_call_depth {
+            return None;
+        }
+        // Unset while in progress, so a cycle inlines instead.
+        self.summaries.insert(func.name.node.clone(), None);
+
+        let saved_system = std::mem::replace(&mut self.system, ConstraintSystem::new());
+        let saved_env = std::mem::take(&mut self.env);
+        let saved_versions = self.versions.clone();
+        let saved_paths = std::mem::take(&mut self.path_condition);
+        let saved_digests = self.digests.clone();
+        let saved_counters = (self.divine_counter, self.pub_input_counter);
+        let saved_fn = std::mem::replace(&mut self.current_fn, func.name.node.clone());
+        let saved_assertion = self.assertion.take();
+
+        let params: Vec<SymVar> = func
+            .params
+            .iter()
+            .map(|p| SymVar {
+                name: format!("__param_{}_{}", func.name.node, p.name.node),
+                version: 0,
+            })
+            .collect();
+        for (param, var) in func.params.iter().zip(&params) {
+            self.env
+                .insert(param.name.node.clone(), SymValue::Var(var.clone()));
+        }
+        self.call_depth += 1;
+        self.assume_attributes(func);
+        self.execute_block(&body.node);
+        let result = self.returned_value(&body.node);
+        self.call_depth -= 1;
+
+        let pure = self.system.pub_outputs.is_empty()
+            && self.digests.len() == saved_digests.len()
+            && (self.divine_counter, self.pub_input_counter) == saved_counters;
+        let locals = self
+            .versions
+            .iter()
+            .flat_map(|(name, &end)| {
+                let start = saved_versions.get(name).copied().unwrap_or(0);
+                (start..end).map(|version| SymVar {
+                    name: name.clone(),
+                    version,
+                })
+            })
+            .collect();
+        let system = std::mem::replace(&mut self.system, saved_system);
+        self.env = saved_env;
+        self.versions = saved_versions;
+        self.path_condition = saved_paths;
+        self.digests = saved_digests;
+        self.current_fn = saved_fn;
+        self.assertion = saved_assertion;
+        (self.divine_counter, self.pub_input_counter) = saved_counters;
+
+        pure.then(|| FnSummary {
+            params,
+            obligations: system.constraints.into_iter().zip(system.origins).collect(),
+            assumptions: system.assumptions,
+            truncated_loops: system.truncated_loops,
+            result,
+            locals,
+        })
+    }
+
+    /// The value `body` returns: its tail expression, or a final
+    /// `return`. A body returning from anywhere else has no single value.
+    fn returned_value(&mut self, body: &Block) -> Option<SymValue> {
+        let (last, rest) = match body.stmts.split_last() {
+            Some((stmt, rest)) if body.tail_expr.is_none() => (Some(stmt), rest),
+            _ => (None, &body.stmts[..]),
+        };
+        if rest.iter().any(returns) {
+            return None;
+        }
+        let expr = match (&body.tail_expr, last.map(|s| &s.node)) {
+            (Some(tail), _) => tail,
+            (None, Some(Stmt::Return(Some(expr)))) => expr,
+            _ => return None,
+        };
+        // Evaluated again only when it is a plain value: the body's
+        // execution already added the effects of any call in it.
+        match &expr.node {
+            Expr::Var(_) | Expr::Literal(_) | Expr::BinOp { .. } if !has_effects(expr) => {
+                Some(self.eval_expr(&expr.node))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether `stmt` contains a `return`.
+fn returns(stmt: &Spanned<Stmt>) -> bool {
+    struct FindReturn(bool);
+    impl Visitor for FindReturn {
+        fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+            match stmt.node {
+                Stmt::Return(_) => self.0 = true,
+                _ => walk::stmt(self, stmt),
+            }
+        }
+        // `return` is a statement; expressions cannot hold one.
+        fn visit_expr(&mut self, _expr: &Spanned<Expr>) {}
+    }
+    let mut find = FindReturn(false);
+    find.visit_stmt(stmt);
+    find.0
+}
+
+/// Whether evaluating `expr` calls anything or adds an obligation.
+fn has_effects(expr: &Spanned<Expr>) -> bool {
+    struct FindEffect(bool);
+    impl Visitor for FindEffect {
+        fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+            match expr.node {
+                Expr::Call { .. }
+                | Expr::BinOp {
+                    op: BinOp::DivMod, ..
+                } => self.0 = true,
+                _ => walk::expr(self, expr),
+            }
+        }
+    }
+    let mut find = FindEffect(false);
+    find.visit_expr(expr);
+    find.0
+}
+
+fn substitute(v: &SymValue, subst: &HashMap<SymVar, SymValue>) -> SymValue {
+    let sub = |v: &SymValue| Box::new(substitute(v, subst));
+    match v {
+        SymValue::Var(var) => subst.get(var).cloned().unwrap_or_else(|| v.clone()),
+        SymValue::Add(a, b) => SymValue::Add(sub(a), sub(b)),
+        SymValue::Mul(a, b) => SymValue::Mul(sub(a), sub(b)),
+        SymValue::Sub(a, b) => SymValue::Sub(sub(a), sub(b)),
+        SymValue::Eq(a, b) => SymValue::Eq(sub(a), sub(b)),
+        SymValue::Lt(a, b) => SymValue::Lt(sub(a), sub(b)),
+        SymValue::DivMod(a, b, index) => SymValue::DivMod(sub(a), sub(b), *index),
+        SymValue::Pow(a, b) => SymValue::Pow(sub(a), sub(b)),
+        SymValue::Neg(a) => SymValue::Neg(sub(a)),
+        SymValue::Inv(a) => SymValue::Inv(sub(a)),
+        SymValue::Log2(a) => SymValue::Log2(sub(a)),
+        SymValue::FieldAccess(inner, field) => SymValue::FieldAccess(sub(inner), field.clone()),
+        SymValue::Ite(c, t, e) => SymValue::Ite(sub(c), sub(t), sub(e)),
+        SymValue::Hash(inputs, index) => SymValue::Hash(
+            inputs.iter().map(|v| substitute(v, subst)).collect(),
+            *index,
+        ),
+        SymValue::Const(_) => v.clone(),
+    }
+    .simplify()
+}
+
+fn substitute_constraint(c: &Constraint, subst: &HashMap<SymVar, SymValue>) -> Constraint {
+    let sub = |v: &SymValue| substitute(v, subst);
+    match c {
+        Constraint::Equal(a, b) => Constraint::Equal(sub(a), sub(b)),
+        Constraint::AssertTrue(v) => Constraint::AssertTrue(sub(v)),
+        Constraint::RangeU32(v) => Constraint::RangeU32(sub(v)),
+        Constraint::NonZero(v) => Constraint::NonZero(sub(v)),
+        Constraint::Conditional(cond, inner) => {
+            Constraint::Conditional(sub(cond), Box::new(substitute_constraint(inner, subst)))
+        }
+        Constraint::DigestEqual(a, b) => {
+            Constraint::DigestEqual(a.iter().map(sub).collect(), b.iter().map(sub).collect())
+        }
+    }
+}
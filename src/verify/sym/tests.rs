@@ -97,36 +97,256 @@ fn test_range_u32_constraint() {
 }
 
 #[test]
-fn test_verify_file_safe() {
+fn test_function_inlining() {
     let file = parse_program(
-        "program test\nfn main() {\n    let x: Field = pub_read()\n    pub_write(x)\n}\n",
+        "program test\nfn helper() {\n    assert(true)\n}\nfn main() {\n    helper()\n}\n",
     );
-    let result = verify_file(&file);
-    assert!(result.is_safe());
+    let system = analyze(&file);
+    // The inlined assert(true) should produce a constraint
+    assert!(!system.constraints.is_empty());
 }
 
 #[test]
-fn test_verify_file_violated() {
-    let file = parse_program("program test\nfn main() {\n    assert(false)\n}\n");
-    let result = verify_file(&file);
-    assert!(!result.is_safe());
+fn test_if_else_symbolic() {
+    let file = parse_program(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    if x == 0 {\n        assert(true)\n    } else {\n        assert(true)\n    }\n}\n",
+    );
+    let system = analyze(&file);
+    assert!(system.violated_constraints().is_empty());
 }
 
 #[test]
-fn test_function_inlining() {
+fn test_divmod_constants_fold_both_halves() {
     let file = parse_program(
-        "program test\nfn helper() {\n    assert(true)\n}\nfn main() {\n    helper()\n}\n",
+        "program test\nfn main() {\n    let (q, r) = 17 /% 5\n    assert_eq(q, 3)\n    assert_eq(r, 2)\n}\n",
     );
     let system = analyze(&file);
-    // The inlined assert(true) should produce a constraint
-    assert!(!system.constraints.is_empty());
+    assert!(system.violated_constraints().is_empty());
 }
 
 #[test]
-fn test_if_else_symbolic() {
+fn test_divmod_obliges_nonzero_divisor() {
     let file = parse_program(
-        "program test\nfn main() {\n    let x: Field = pub_read()\n    if x == 0 {\n        assert(true)\n    } else {\n        assert(true)\n    }\n}\n",
+        "program test\nfn main() {\n    let a: U32 = as_u32(pub_read())\n    let b: U32 = as_u32(pub_read())\n    let (q, r) = a /% b\n    pub_write(as_field(q))\n}\n",
+    );
+    let system = analyze(&file);
+    assert!(system
+        .constraints
+        .iter()
+        .any(|c| matches!(c, Constraint::NonZero(_))));
+
+    let zero = parse_program("program test\nfn main() {\n    let (q, r) = 7 /% 0\n}\n");
+    assert!(!analyze(&zero).violated_constraints().is_empty());
+}
+
+#[test]
+fn test_pow_and_log2_semantics() {
+    let v = SymValue::Pow(Box::new(SymValue::Const(3)), Box::new(SymValue::Const(4))).simplify();
+    assert_eq!(v, SymValue::Const(81));
+    let v = SymValue::Log2(Box::new(SymValue::Const(1024))).simplify();
+    assert_eq!(v, SymValue::Const(10));
+    assert!(Constraint::NonZero(SymValue::Const(0)).is_violated());
+}
+
+#[test]
+fn test_inverse_obliges_nonzero_argument() {
+    let file = parse_program(
+        "program test\nfn main() {\n    let x: Field = pub_read()\n    pub_write(inverse(x))\n}\n",
+    );
+    let system = analyze(&file);
+    assert!(system
+        .constraints
+        .iter()
+        .any(|c| matches!(c, Constraint::NonZero(_))));
+
+    let zero = parse_program("program test\nfn main() {\n    pub_write(inverse(0))\n}\n");
+    assert!(!analyze(&zero).violated_constraints().is_empty());
+}
+
+#[test]
+fn test_fail_is_violated_and_terminates_its_block() {
+    let file = parse_program(
+        "program test\nconst BAD: Field = 7\nfn main() {\n    let x: Field = pub_read()\n    if x == 0 {\n        fail(BAD)\n        assert(false)\n    }\n}\n",
     );
     let system = analyze(&file);
+    assert_eq!(system.constraints.len(), 1, "{:?}", system.constraints);
+    assert!(matches!(
+        &system.constraints[0],
+        Constraint::Conditional(_, inner) if matches!(**inner, Constraint::AssertTrue(SymValue::Const(0)))
+    ));
+}
+
+#[test]
+fn test_digest_equality_compares_elements() {
+    let file = parse_program(
+        "program test\nfn main() {\n    let d: Digest = divine5()\n    let e: Digest = Digest::from_fields(d[0], d[1], d[2], d[3], d[4])\n    assert_digest(d, e)\n    assert(Digest::eq(e, d))\n}\n",
+    );
+    let system = analyze(&file);
+    let digest_eqs: Vec<_> = system
+        .constraints
+        .iter()
+        .filter(|c| matches!(c, Constraint::DigestEqual(..)))
+        .collect();
+    assert_eq!(digest_eqs.len(), 2);
+    assert!(digest_eqs.iter().all(|c| c.is_trivial()));
+}
+
+#[test]
+fn test_short_circuit_guards_right_side() {
+    let file = parse_program(
+        "program test\nfn check(x: Field) -> Bool {\n    assert_eq(x, 0)\n    true\n}\nfn main() {\n    let x: Field = pub_read()\n    let ok: Bool = x == 1 || check(x)\n    assert(ok)\n}\n",
+    );
+    let system = analyze(&file);
+    // `check` only runs when `x == 1` fails, so its assertion is guarded.
+    assert!(system.constraints.iter().any(|c| matches!(
+        c,
+        Constraint::Conditional(_, inner) if matches!(**inner, Constraint::Equal(..))
+    )));
+    assert!(!system
+        .constraints
+        .iter()
+        .any(|c| matches!(c, Constraint::Equal(..))));
+}
+
+#[test]
+fn test_assert_add_constrains_overflow() {
+    let file = parse_program(
+        "program test\nfn main() {\n    let a: U32 = as_u32(pub_read())\n    let s: U32 = u32_assert_add(a, a)\n    let f: Field = field_assert_add(pub_read(), pub_read())\n    pub_write(as_field(s) + f)\n}\n",
+    );
+    let system = analyze(&file);
+    let ranges = system
+        .constraints
+        .iter()
+        .filter(|c| matches!(c, Constraint::RangeU32(..)))
+        .count();
+    // `as_u32` and `u32_assert_add` each range-check their result.
+    assert_eq!(ranges, 2);
+    assert!(system
+        .constraints
+        .iter()
+        .any(|c| matches!(c, Constraint::AssertTrue(..))));
+}
+
+#[test]
+fn test_assumptions_are_recorded_apart_from_constraints() {
+    let file = parse_program(
+        "program test\n#[assume(n < 10)]\nfn step(n: U32) -> U32 {\n    n + 1\n}\nfn main() {\n    let x: Field = pub_read()\n    assume(x == 0, \"kernel passes zero\")\n    pub_write(as_field(step(as_u32(x))))\n}\n",
+    );
+    let system = analyze(&file);
+    assert_eq!(system.assumptions.len(), 2);
+    let statement = &system.assumptions[0];
+    assert_eq!(statement.function, "main");
+    assert!(!statement.from_attribute);
+    assert_eq!(statement.reason.as_deref(), Some("kernel passes zero"));
+    let attribute = &system.assumptions[1];
+    assert_eq!(attribute.function, "step");
+    assert!(attribute.from_attribute);
+    assert_eq!(attribute.reason, None);
+    assert!(system.summary().contains(", Assumptions: 2,"));
+}
+
+#[test]
+fn test_constraints_record_their_assertion_call() {
+    let source = "program test\nfn main() {\n    let x: U32 = as_u32(pub_read())\n    assert_eq(as_field(x), 1)\n}\n";
+    let system = analyze(&parse_program(source));
+    assert_eq!(system.origins.len(), system.constraints.len());
+    assert_eq!(system.origins[0], None);
+    let span = system.origins[1].expect("assert_eq is the origin");
+    assert_eq!(
+        &source[span.start as usize..span.end as usize],
+        "assert_eq(as_field(x), 1)"
+    );
+}
+
+#[test]
+fn test_accumulation_loops_with_symbolic_trip_counts_are_summarized() {
+    let source = "program test\nfn main() {\n    let n: U32 = as_u32(pub_read())\n    let x: Field = pub_read()\n    let mut acc: Field = 0\n    let mut total: Field = 0\n    for i in 0..n bounded 100 {\n        acc = acc + x\n        total = total + as_field(i)\n    }\n    assert_eq(acc, x * as_field(n))\n    assert_eq(total + total + as_field(n), as_field(n) * as_field(n))\n}\n";
+    let system = analyze(&parse_program(source));
+    let asserted: Vec<&Constraint> = system.constraints.iter().skip(1).collect();
+    assert_eq!(asserted.len(), 2);
+    assert!(asserted
+        .iter()
+        .all(|c| !matches!(c, Constraint::Conditional(..))));
+    let summary = format!("{:?}", system.constraints);
+    assert!(!summary.contains("__loop_"));
+}
+
+#[test]
+fn test_loops_that_assert_are_not_summarized() {
+    let source = "program test\nfn main() {\n    let n: U32 = as_u32(pub_read())\n    let mut acc: Field = 0\n    for i in 0..n bounded 4 {\n        acc = acc + 1\n        assert(acc == acc)\n    }\n}\n";
+    let system = analyze(&parse_program(source));
+    assert!(system
+        .constraints
+        .iter()
+        .any(|c| matches!(c, Constraint::Conditional(..))));
+}
+
+#[test]
+fn test_calls_apply_function_summaries() {
+    let source = "program test\nfn check(a: Field, b: Field) -> Field {\n    assert_eq(a * b, b * a)\n    a + b\n}\nfn main() {\n    let x: Field = pub_read()\n    let y: Field = pub_read()\n    let s: Field = check(x, y)\n    let t: Field = check(s, x)\n    assert_eq(t, x + y + x)\n}\n";
+    let system = analyze(&parse_program(source));
+    assert_eq!(system.constraints.len(), 3);
+    assert!(!format!("{:?}", system.constraints).contains("__param_"));
+    let span = system.origins[1].expect("the obligation keeps its site");
+    assert_eq!(
+        &source[span.start as usize..span.end as usize],
+        "assert_eq(a * b, b * a)"
+    );
+    assert!(crate::solve::verify(&system).is_safe());
+}
+
+#[test]
+fn test_functions_with_io_are_inlined_per_call() {
+    let system = analyze(&parse_program(
+        "program test\nfn next() -> Field {\n    pub_read()\n}\nfn main() {\n    let a: Field = next()\n    let b: Field = next()\n    pub_write(a + b)\n}\n",
+    ));
+    assert_eq!(system.pub_inputs.len(), 2);
+}
+
+#[test]
+fn test_summary_result_with_a_nested_call_is_not_evaluated_again() {
+    let system = analyze(&parse_program(
+        "program test\nfn scaled(a: Field) -> Field {\n    a + [inverse(a)][0]\n}\nfn main() {\n    let x: Field = pub_read()\n    pub_write(scaled(x))\n}\n",
+    ));
+    let nonzero = system
+        .constraints
+        .iter()
+        .filter(|c| matches!(c, Constraint::NonZero(_)))
+        .count();
+    assert_eq!(nonzero, 1);
+}
+
+#[test]
+fn test_while_iterations_are_guarded_by_every_earlier_condition() {
+    let source = "program test\nfn main() {\n    let x: U32 = as_u32(pub_read())\n    let mut i: U32 = 0\n    while i < x bounded 2 {\n        assert(i < 7)\n        i = i + 1\n    }\n}\n";
+    let system = analyze(&parse_program(source));
+    let guards: Vec<&SymValue> = system
+        .constraints
+        .iter()
+        .filter_map(|c| match c {
+            Constraint::Conditional(guard, _) => Some(guard),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(guards.len(), 2, "{:?}", system.constraints);
+    // The second body runs only if the first test held too.
+    assert!(!matches!(guards[0], SymValue::Mul(..)));
+    assert!(matches!(guards[1], SymValue::Mul(..)), "{:?}", guards[1]);
+    assert!(system.truncated_loops.is_empty());
+}
+
+#[test]
+fn test_while_loops_cut_at_the_unrolling_limit_are_recorded() {
+    let exits = "program test\nfn main() {\n    let mut i: U32 = 0\n    while i < 3 bounded 100 {\n        i = i + 1\n    }\n    assert(i == 3)\n}\n";
+    let system = analyze(&parse_program(exits));
+    assert!(system.truncated_loops.is_empty());
     assert!(system.violated_constraints().is_empty());
+
+    let source = "program test\nfn main() {\n    let x: U32 = as_u32(pub_read())\n    let mut i: U32 = 0\n    while i < x bounded 100 {\n        i = i + 1\n    }\n}\n";
+    let system = analyze(&parse_program(source));
+    assert_eq!(system.truncated_loops.len(), 1);
+    let span = system.truncated_loops[0];
+    assert_eq!(&source[span.start as usize..span.end as usize], "100");
+    let report = crate::verify::solve::verify(&system);
+    assert_eq!(report.verdict, crate::verify::solve::Verdict::Inconclusive);
 }
@@ -43,7 +43,7 @@ fn collect_preconditions_from_block(
                     collect_preconditions_from_block(&else_blk.node, fn_name, params, specs);
                 }
             }
-            Stmt::For { body, .. } => {
+            Stmt::For { body, .. } | Stmt::While { body, .. } => {
                 collect_preconditions_from_block(&body.node, fn_name, params, specs);
             }
             _ => {}
@@ -224,6 +224,7 @@ pub(crate) fn expr_to_string(expr: &Expr) -> String {
     match expr {
         Expr::Literal(Literal::Integer(n)) => n.to_string(),
         Expr::Literal(Literal::Bool(b)) => b.to_string(),
+        Expr::Literal(Literal::Str(s)) => format!("{:?}", s),
         Expr::Var(name) => name.clone(),
         Expr::BinOp { op, lhs, rhs } => {
             let l = expr_to_string(&lhs.node);
@@ -253,12 +254,15 @@ pub(crate) fn expr_to_string(expr: &Expr) -> String {
             let parts: Vec<String> = elems.iter().map(|e| expr_to_string(&e.node)).collect();
             format!("[{}]", parts.join(", "))
         }
-        Expr::StructInit { path, fields } => {
+        Expr::StructInit { path, fields, base } => {
             let name = path.node.as_dotted();
-            let field_strs: Vec<String> = fields
+            let mut field_strs: Vec<String> = fields
                 .iter()
                 .map(|(n, v)| format!("{}: {}", n.node, expr_to_string(&v.node)))
                 .collect();
+            if let Some(base) = base {
+                field_strs.push(format!("..{}", expr_to_string(&base.node)));
+            }
             format!("{} {{ {} }}", name, field_strs.join(", "))
         }
     }
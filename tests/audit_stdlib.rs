@@ -338,3 +338,12 @@ fn main() {
     );
     assert!(tasm.contains("__on_curve:"), "missing on_curve function");
 }
+
+// ── library #[test] functions ──
+
+#[test]
+fn std_test_functions_pass_in_the_interpreter() {
+    let report = trident::run_std_tests(&trident::CompileOptions::default())
+        .unwrap_or_else(|errs| panic!("std tests did not run: {:?}", errs));
+    assert!(report.contains("test result: ok."), "{}", report);
+}
@@ -0,0 +1,74 @@
+//! Benchmark suite (`benches/suite/`): every program and its handwritten
+//! baseline run on the reference interpreter with the expected output, and
+//! the recorded history read back as a trend.
+
+use std::path::Path;
+
+#[test]
+fn bench_suite_runs_both_sides() {
+    let runs = trident::run_bench_suite(
+        Path::new("benches/suite"),
+        &trident::CompileOptions::default(),
+    )
+    .unwrap_or_else(|errs| panic!("suite did not run: {:?}", errs));
+    assert!(!runs.is_empty());
+    for run in &runs {
+        assert!(
+            run.compiled_cycles.is_ok(),
+            "{}: {:?}",
+            run.name,
+            run.compiled_cycles
+        );
+        assert!(
+            run.baseline_cycles.is_ok(),
+            "{}: {:?}",
+            run.name,
+            run.baseline_cycles
+        );
+        assert!(run.instructions.total_baseline > 0, "{}", run.name);
+    }
+
+    let table = trident::format_bench_table(&runs);
+    assert!(table.contains("(cycles)"), "{}", table);
+    let json = trident::format_bench_json(&runs);
+    assert!(json.contains("\"cycles\": {\"tri\": "), "{}", json);
+}
+
+#[test]
+fn bench_history_records_and_renders_trend() {
+    let options = trident::CompileOptions::default();
+    let runs = trident::run_bench_suite(Path::new("benches/suite"), &options)
+        .unwrap_or_else(|errs| panic!("suite did not run: {:?}", errs));
+    let dir = tempfile::tempdir().unwrap();
+    let history = dir.path().join("history.ndjson");
+
+    let first = trident::record_bench_runs(&history, &runs, &options).unwrap();
+    assert_eq!(first.len(), runs.len());
+    assert!(first
+        .iter()
+        .all(|r| r.padded_height > 0 && r.cycles.is_some()));
+
+    // A later record where one case grew by three instructions.
+    let mut drifted = first.clone();
+    drifted[0].instructions += 3;
+    drifted[0].date = "2099-01-01T00:00:00Z".to_string();
+    trident::append_bench_history(&history, &drifted[..1]).unwrap();
+
+    let records = trident::load_bench_history(&history).unwrap();
+    assert_eq!(records.len(), runs.len() + 1);
+    assert_eq!(records[..runs.len()], first[..]);
+    assert_eq!(records[runs.len()], drifted[0]);
+
+    let trend = trident::format_bench_trend(&records, 10);
+    assert!(trend.contains("2099-01-01T00:00:00Z"), "{}", trend);
+    assert!(trend.contains("+3"), "{}", trend);
+    // With one row shown, its delta is still taken against the record before.
+    let last = trident::format_bench_trend(&records, 1);
+    assert_eq!(
+        last.matches(&first[0].date).count(),
+        runs.len() - 1,
+        "{}",
+        last
+    );
+    assert!(last.contains("+3"), "{}", last);
+}
@@ -0,0 +1,16 @@
+//! Golden-output conformance corpus (`conformance/`), run on the
+//! reference interpreter.
+
+use std::path::Path;
+
+#[test]
+fn conformance_corpus_matches_golden_outputs() {
+    let interpreter = trident::Interpreter::new();
+    let report = trident::run_conformance(
+        Path::new("conformance"),
+        &trident::CompileOptions::default(),
+        Some(&interpreter),
+    )
+    .unwrap_or_else(|errs| panic!("corpus did not run: {:?}", errs));
+    assert!(report.contains("conformance result: ok."), "{}", report);
+}
@@ -0,0 +1,65 @@
+//! Deploying a bundle file through the `trident` binary.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use trident::ProgramBundle;
+
+/// A proof file in the format `trident prove` writes.
+const PROOF_JSON: &str = "{
+  \"format\": \"stark-triton-v2\",
+  \"claim\": {
+    \"program_hash\": [1, 2, 3, 4, 5],
+    \"public_input\": [7],
+    \"public_output\": []
+  },
+  \"proof\": \"dead\"
+}
+";
+
+fn write_program(dir: &Path, body: &str) -> PathBuf {
+    let path = dir.join("coin.tri");
+    std::fs::write(&path, format!("program coin\nfn main() {{\n{}\n}}\n", body)).unwrap();
+    path
+}
+
+fn bundle(dir: &Path, body: &str) -> ProgramBundle {
+    let entry = write_program(dir, body);
+    trident::compile_to_bundle(&entry, &trident::CompileOptions::default()).unwrap()
+}
+
+#[test]
+fn dry_run_deploys_a_bundle_file_to_a_mock_node() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = bundle(dir.path(), "    pub_write(pub_read())");
+    let bundle_path = dir.path().join("coin.json");
+    std::fs::write(&bundle_path, program.to_json()).unwrap();
+    let proof_path = dir.path().join("coin.proof.json");
+    std::fs::write(&proof_path, PROOF_JSON).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_trident"))
+        .arg("deploy")
+        .arg(&bundle_path)
+        .args(["--rpc", "http://node.invalid:9799", "--dry-run", "--proof"])
+        .arg(&proof_path)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("in place of http://node.invalid:9799"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains(&program.program_digest().to_hex()),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("Proof:           stark-triton-v2"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("Deployed: tx 0x"), "{}", stderr);
+}
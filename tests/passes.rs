@@ -0,0 +1,55 @@
+//! A compiler pass written against the public API, as a crate using
+//! `trident` as a library would.
+
+use trident::api::ast::{Item, Type};
+use trident::api::{
+    check_project, check_project_with_options, CompileOptions, CompilerPass, Diagnostic,
+    PassContext,
+};
+
+/// Rejects `XField` parameters.
+struct NoXFieldParams;
+
+impl CompilerPass for NoXFieldParams {
+    fn name(&self) -> &str {
+        "no_xfield_params"
+    }
+
+    fn check(&self, module: &PassContext) -> Vec<Diagnostic> {
+        let mut found = Vec::new();
+        for item in &module.file.items {
+            let Item::Fn(func) = &item.node else {
+                continue;
+            };
+            for param in &func.params {
+                if matches!(param.ty.node, Type::XField) {
+                    found.push(Diagnostic::error(
+                        format!("`{}` takes an XField", func.name.node),
+                        param.ty.span,
+                    ));
+                }
+            }
+        }
+        found
+    }
+}
+
+#[test]
+fn pass_sees_the_module_ast() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = dir.path().join("main.tri");
+    std::fs::write(
+        &entry,
+        "program test\nfn lift(x: XField) -> Field {\n    1\n}\nfn main() {\n    pub_write(pub_read())\n}\n",
+    )
+    .unwrap();
+    let options = CompileOptions::builder()
+        .with_pass(NoXFieldParams)
+        .build()
+        .unwrap();
+
+    let errors = check_project_with_options(&entry, &options).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "`lift` takes an XField");
+    assert!(check_project(&entry).is_ok());
+}